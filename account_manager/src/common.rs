@@ -1,6 +1,31 @@
 use account_utils::read_input_from_user;
+use clap::{Arg, ArgAction, ArgMatches};
+use eth2_keystore::KdfParams;
 
 pub const WALLET_NAME_PROMPT: &str = "Enter wallet name:";
+pub const KDF_FLAG: &str = "kdf";
+
+/// The argument used to choose the KDF parameters of newly encrypted keystores and wallets.
+pub fn kdf_arg() -> Arg {
+    Arg::new(KDF_FLAG)
+        .long(KDF_FLAG)
+        .value_name("KDF_PARAMS")
+        .help(
+            "The key derivation function used to encrypt new keystores, as either \
+            scrypt:n=<N>,r=<R>,p=<P> or pbkdf2:c=<C>. Omitted parameters take their \
+            EIP-2335 defaults (scrypt:n=262144,r=8,p=1 and pbkdf2:c=262144). Stronger \
+            parameters make the keystores harder to brute-force but slower to decrypt, \
+            which delays validator client startup. Parameters weaker than the defaults \
+            are allowed but produce a warning.",
+        )
+        .action(ArgAction::Set)
+        .display_order(0)
+}
+
+/// Parses the value of `--kdf`, returning the default parameters if it is not supplied.
+pub fn parse_kdf_params(matches: &ArgMatches) -> Result<KdfParams, String> {
+    Ok(clap_utils::parse_optional(matches, KDF_FLAG)?.unwrap_or_default())
+}
 
 /// Reads in a wallet name from the user. If the `--wallet-name` flag is provided, use it. Otherwise
 /// read from an interactive prompt using tty unless the `--stdin-inputs` flag is provided.
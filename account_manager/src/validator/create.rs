@@ -1,4 +1,4 @@
-use crate::common::read_wallet_name_from_cli;
+use crate::common::{kdf_arg, parse_kdf_params, read_wallet_name_from_cli};
 use crate::{SECRETS_DIR_FLAG, WALLETS_DIR_FLAG};
 use account_utils::{
     random_password, read_password_from_user, strip_off_newlines, validator_definitions, PlainText,
@@ -114,6 +114,7 @@ pub fn cli_app() -> Command {
                 .action(ArgAction::Set)
                 .display_order(0)
         )
+        .arg(kdf_arg())
 }
 
 pub fn cli_run<E: EthSpec>(
@@ -143,6 +144,7 @@ pub fn cli_run<E: EthSpec>(
         .unwrap_or(spec.max_effective_balance);
     let count: Option<usize> = clap_utils::parse_optional(matches, COUNT_FLAG)?;
     let at_most: Option<usize> = clap_utils::parse_optional(matches, AT_MOST_FLAG)?;
+    let kdf_params = parse_kdf_params(matches)?;
 
     // The command will always fail if the wallet dir does not exist.
     if !wallet_base_dir.exists() {
@@ -225,6 +227,7 @@ pub fn cli_run<E: EthSpec>(
                 wallet_password.as_bytes(),
                 voting_password.as_bytes(),
                 withdrawal_password.as_bytes(),
+                kdf_params,
             )
             .map_err(|e| format!("Unable to create validator keys: {:?}", e))?;
 
@@ -262,17 +265,23 @@ pub fn cli_run<E: EthSpec>(
 
 /// Returns the number of validators that exist in the given `validator_dir`.
 ///
-/// This function just assumes all files and directories, excluding the validator definitions YAML
-/// and slashing protection database are validator directories, making it likely to return a higher
-/// number than accurate but never a lower one.
+/// This function just assumes all files and directories, excluding the validator definitions
+/// (including their lock file and per-validator directory) and slashing protection database are
+/// validator directories, making it likely to return a higher number than accurate but never a
+/// lower one.
 fn existing_validator_count<P: AsRef<Path>>(validator_dir: P) -> Result<usize, String> {
     fs::read_dir(validator_dir.as_ref())
         .map(|iter| {
             iter.filter_map(|e| e.ok())
                 .filter(|e| {
-                    e.file_name() != OsStr::new(validator_definitions::CONFIG_FILENAME)
-                        && e.file_name()
-                            != OsStr::new(slashing_protection::SLASHING_PROTECTION_FILENAME)
+                    ![
+                        validator_definitions::CONFIG_FILENAME,
+                        validator_definitions::LOCK_FILENAME,
+                        validator_definitions::DEFINITIONS_DIRNAME,
+                        slashing_protection::SLASHING_PROTECTION_FILENAME,
+                    ]
+                    .iter()
+                    .any(|name| e.file_name() == OsStr::new(name))
                 })
                 .count()
         })
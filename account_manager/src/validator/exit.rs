@@ -274,7 +274,7 @@ async fn get_validator_index_for_exit(
 }
 
 /// Returns the validator data by querying the beacon node client.
-async fn get_validator_data(
+pub async fn get_validator_data(
     client: &BeaconNodeHttpClient,
     validator_pubkey: &PublicKey,
 ) -> Result<ValidatorData, String> {
@@ -296,7 +296,7 @@ async fn get_validator_data(
 }
 
 /// Get genesis data by querying the beacon node client.
-async fn get_geneisis_data(client: &BeaconNodeHttpClient) -> Result<GenesisData, String> {
+pub async fn get_geneisis_data(client: &BeaconNodeHttpClient) -> Result<GenesisData, String> {
     Ok(client
         .get_beacon_genesis()
         .await
@@ -315,7 +315,7 @@ async fn is_syncing(client: &BeaconNodeHttpClient) -> Result<bool, String> {
 }
 
 /// Calculates the current epoch from the genesis time and current time.
-fn get_current_epoch<E: EthSpec>(genesis_time: u64, spec: &ChainSpec) -> Option<Epoch> {
+pub fn get_current_epoch<E: EthSpec>(genesis_time: u64, spec: &ChainSpec) -> Option<Epoch> {
     let slot_clock = SystemTimeSlotClock::new(
         spec.genesis_slot,
         Duration::from_secs(genesis_time),
@@ -3,6 +3,7 @@ pub mod exit;
 pub mod import;
 pub mod list;
 pub mod modify;
+pub mod presign_exit;
 pub mod recover;
 pub mod slashing_protection;
 
@@ -49,6 +50,7 @@ pub fn cli_app() -> Command {
         .subcommand(recover::cli_app())
         .subcommand(slashing_protection::cli_app())
         .subcommand(exit::cli_app())
+        .subcommand(presign_exit::cli_app())
 }
 
 pub fn cli_run<E: EthSpec>(matches: &ArgMatches, env: Environment<E>) -> Result<(), String> {
@@ -70,6 +72,7 @@ pub fn cli_run<E: EthSpec>(matches: &ArgMatches, env: Environment<E>) -> Result<
             slashing_protection::cli_run(matches, env, validator_base_dir)
         }
         Some((exit::CMD, matches)) => exit::cli_run(matches, env),
+        Some((presign_exit::CMD, matches)) => presign_exit::cli_run(matches, env),
         Some((unknown, _)) => Err(format!(
             "{} does not have a {} command. See --help",
             CMD, unknown
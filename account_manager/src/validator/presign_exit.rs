@@ -0,0 +1,239 @@
+use crate::validator::exit::{
+    get_current_epoch, get_geneisis_data, get_validator_data, BEACON_SERVER_FLAG,
+    DEFAULT_BEACON_NODE, KEYSTORE_FLAG, PASSWORD_FILE_FLAG, PASSWORD_PROMPT,
+};
+use account_utils::STDIN_INPUTS_FLAG;
+use bls::Keypair;
+use clap::{Arg, ArgAction, ArgMatches, Command};
+use environment::Environment;
+use eth2::{types::ValidatorStatus, BeaconNodeHttpClient, Timeouts};
+use eth2_keystore::{Keystore, PlainText};
+use eth2_network_config::Eth2NetworkConfig;
+use safe_arith::SafeArith;
+use sensitive_url::SensitiveUrl;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+use types::{ChainSpec, Epoch, EthSpec, VoluntaryExit};
+use validator_dir::{presigned_exit_path, EncryptedPresignedExit};
+
+pub const CMD: &str = "presign-exit";
+pub const EPOCH_FLAG: &str = "epoch";
+
+pub fn cli_app() -> Command {
+    Command::new(CMD)
+        .about(
+            "Signs a VoluntaryExit for a given validator keystore without publishing it. The \
+            exit is encrypted with the keystore password and stored in the same directory as \
+            the keystore, from where it can be published by the validator client.",
+        )
+        .arg(
+            Arg::new(KEYSTORE_FLAG)
+                .long(KEYSTORE_FLAG)
+                .value_name("KEYSTORE_PATH")
+                .help("The path to the EIP-2335 voting keystore for the validator")
+                .action(ArgAction::Set)
+                .required(true)
+                .display_order(0),
+        )
+        .arg(
+            Arg::new(PASSWORD_FILE_FLAG)
+                .long(PASSWORD_FILE_FLAG)
+                .value_name("PASSWORD_FILE_PATH")
+                .help("The path to the password file which unlocks the validator voting keystore")
+                .action(ArgAction::Set)
+                .display_order(0),
+        )
+        .arg(
+            Arg::new(BEACON_SERVER_FLAG)
+                .long(BEACON_SERVER_FLAG)
+                .value_name("NETWORK_ADDRESS")
+                .help("Address to a beacon node HTTP API")
+                .default_value(DEFAULT_BEACON_NODE)
+                .action(ArgAction::Set)
+                .display_order(0),
+        )
+        .arg(
+            Arg::new(EPOCH_FLAG)
+                .long(EPOCH_FLAG)
+                .value_name("EPOCH")
+                .help(
+                    "The epoch of the voluntary exit. The exit cannot be included in the beacon \
+                    chain before this epoch. Defaults to the earliest epoch at which the \
+                    validator may exit.",
+                )
+                .action(ArgAction::Set)
+                .display_order(0),
+        )
+}
+
+pub fn cli_run<E: EthSpec>(matches: &ArgMatches, env: Environment<E>) -> Result<(), String> {
+    let keystore_path: PathBuf = clap_utils::parse_required(matches, KEYSTORE_FLAG)?;
+    let password_file_path: Option<PathBuf> =
+        clap_utils::parse_optional(matches, PASSWORD_FILE_FLAG)?;
+    let epoch: Option<Epoch> = clap_utils::parse_optional(matches, EPOCH_FLAG)?;
+
+    let stdin_inputs = cfg!(windows) || matches.get_flag(STDIN_INPUTS_FLAG);
+
+    let spec = env.eth2_config().spec.clone();
+    let server_url: String = clap_utils::parse_required(matches, BEACON_SERVER_FLAG)?;
+    let client = BeaconNodeHttpClient::new(
+        SensitiveUrl::parse(&server_url)
+            .map_err(|e| format!("Failed to parse beacon http server: {:?}", e))?,
+        Timeouts::set_all(Duration::from_secs(env.eth2_config.spec.seconds_per_slot)),
+    );
+
+    let eth2_network_config = env
+        .eth2_network_config
+        .clone()
+        .expect("network should have a valid config");
+
+    env.runtime().block_on(presign_voluntary_exit::<E>(
+        &keystore_path,
+        password_file_path.as_deref(),
+        epoch,
+        &client,
+        &spec,
+        stdin_inputs,
+        &eth2_network_config,
+    ))
+}
+
+/// Signs a voluntary exit for the validator in `keystore_path` and stores it, encrypted with the
+/// keystore password, alongside the keystore.
+async fn presign_voluntary_exit<E: EthSpec>(
+    keystore_path: &Path,
+    password_file_path: Option<&Path>,
+    epoch: Option<Epoch>,
+    client: &BeaconNodeHttpClient,
+    spec: &ChainSpec,
+    stdin_inputs: bool,
+    eth2_network_config: &Eth2NetworkConfig,
+) -> Result<(), String> {
+    let output_path = presigned_exit_path(keystore_path);
+    if output_path.exists() {
+        return Err(format!(
+            "A presigned exit already exists at {:?}. Remove it to sign a new one.",
+            output_path
+        ));
+    }
+
+    let genesis_data = get_geneisis_data(client).await?;
+    let testnet_genesis_root = eth2_network_config
+        .genesis_validators_root::<E>()?
+        .ok_or("Genesis state is unknown")?;
+
+    // Verify that the beacon node and validator being exited are on the same network.
+    if genesis_data.genesis_validators_root != testnet_genesis_root {
+        return Err(
+            "Invalid genesis state. Please ensure that your beacon node is on the same network \
+                 as the validator you are signing an exit for"
+                .to_string(),
+        );
+    }
+
+    let (keypair, password) =
+        load_voting_keypair_and_password(keystore_path, password_file_path, stdin_inputs)?;
+
+    let current_epoch = get_current_epoch::<E>(genesis_data.genesis_time, spec)
+        .ok_or("Failed to get current epoch. Please check your system time")?;
+    let validator_data = get_validator_data(client, &keypair.pk).await?;
+
+    // The validator may have been deposited but not yet activated, in which case the activation
+    // epoch is not yet known.
+    match validator_data.status {
+        ValidatorStatus::PendingQueued | ValidatorStatus::ActiveOngoing => {}
+        status => {
+            return Err(format!(
+                "Validator {:?} is not eligible for a voluntary exit. Validator status: {:?}",
+                keypair.pk, status
+            ))
+        }
+    }
+    let eligible_epoch = validator_data
+        .validator
+        .activation_epoch
+        .safe_add(spec.shard_committee_period)
+        .map_err(|_| {
+            format!(
+                "The activation epoch of validator {:?} is not yet known. Please try again once \
+                the validator has been activated.",
+                keypair.pk
+            )
+        })?;
+    let epoch = match epoch {
+        Some(epoch) if epoch < eligible_epoch => {
+            return Err(format!(
+                "Validator {:?} is not eligible for exit at epoch {}. It will become eligible \
+                on epoch {}",
+                keypair.pk, epoch, eligible_epoch
+            ))
+        }
+        Some(epoch) => epoch,
+        None => std::cmp::max(current_epoch, eligible_epoch),
+    };
+
+    let voluntary_exit = VoluntaryExit {
+        epoch,
+        validator_index: validator_data.index,
+    };
+    let signed_voluntary_exit =
+        voluntary_exit.sign(&keypair.sk, genesis_data.genesis_validators_root, spec);
+
+    EncryptedPresignedExit::encrypt(
+        &signed_voluntary_exit,
+        keypair.pk.compress(),
+        password.as_bytes(),
+    )
+    .and_then(|encrypted| encrypted.save(&output_path))
+    .map_err(|e| format!("Failed to store presigned exit: {:?}", e))?;
+
+    eprintln!(
+        "Stored a presigned voluntary exit for validator {} at epoch {} in {:?}",
+        keypair.pk, epoch, output_path
+    );
+    eprintln!(
+        "The exit is encrypted with the keystore password. It can be published using the \
+        validator client's HTTP API."
+    );
+
+    Ok(())
+}
+
+/// Load the voting keypair by loading and decrypting the keystore, returning the password which
+/// was used to decrypt it.
+///
+/// If the `password_file_path` is Some, unlock keystore using password in given file
+/// otherwise, prompts user for a password to unlock the keystore.
+fn load_voting_keypair_and_password(
+    voting_keystore_path: &Path,
+    password_file_path: Option<&Path>,
+    stdin_inputs: bool,
+) -> Result<(Keypair, PlainText), String> {
+    let keystore = Keystore::from_json_file(voting_keystore_path).map_err(|e| {
+        format!(
+            "Unable to read keystore JSON {:?}: {:?}",
+            voting_keystore_path, e
+        )
+    })?;
+
+    let password: PlainText = if let Some(password_file) = password_file_path {
+        account_utils::read_password(password_file)
+            .map_err(|e| format!("Unable to read password file {:?}: {:?}", password_file, e))?
+    } else {
+        eprintln!();
+        eprintln!(
+            "{} for validator in {:?}: ",
+            PASSWORD_PROMPT, voting_keystore_path
+        );
+        account_utils::read_password_from_user(stdin_inputs)?
+            .as_ref()
+            .to_vec()
+            .into()
+    };
+
+    match keystore.decrypt_keypair(password.as_bytes()) {
+        Ok(keypair) => Ok((keypair, password)),
+        Err(eth2_keystore::Error::InvalidPassword) => Err("Invalid password".to_string()),
+        Err(e) => Err(format!("Error while decrypting keypair: {:?}", e)),
+    }
+}
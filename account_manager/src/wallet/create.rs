@@ -1,4 +1,4 @@
-use crate::common::read_wallet_name_from_cli;
+use crate::common::{kdf_arg, parse_kdf_params, read_wallet_name_from_cli};
 use crate::WALLETS_DIR_FLAG;
 use account_utils::{
     is_password_sufficiently_complex, random_password, read_password_from_user, strip_off_newlines,
@@ -109,6 +109,7 @@ pub fn cli_app() -> Command {
                 .default_value("24")
                 .display_order(0)
         )
+        .arg(kdf_arg())
 }
 
 pub fn cli_run(matches: &ArgMatches, wallet_base_dir: PathBuf) -> Result<(), String> {
@@ -163,6 +164,7 @@ pub fn create_wallet_from_mnemonic(
     let wallet_password_path: Option<PathBuf> = clap_utils::parse_optional(matches, PASSWORD_FLAG)?;
     let type_field: String = clap_utils::parse_required(matches, TYPE_FLAG)?;
     let stdin_inputs = cfg!(windows) || matches.get_flag(STDIN_INPUTS_FLAG);
+    let kdf_params = parse_kdf_params(matches)?;
     let wallet_type = match type_field.as_ref() {
         HD_TYPE => WalletType::Hd,
         unknown => return Err(format!("--{} {} is not supported", TYPE_FLAG, unknown)),
@@ -200,6 +202,7 @@ pub fn create_wallet_from_mnemonic(
             wallet_type,
             mnemonic,
             wallet_password.as_bytes(),
+            kdf_params,
         )
         .map_err(|e| format!("Unable to create wallet: {:?}", e))?;
     Ok(wallet)
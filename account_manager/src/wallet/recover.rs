@@ -1,3 +1,4 @@
+use crate::common::kdf_arg;
 use crate::wallet::create::create_wallet_from_mnemonic;
 use crate::wallet::create::{HD_TYPE, NAME_FLAG, PASSWORD_FLAG, TYPE_FLAG};
 use account_utils::{read_mnemonic_from_cli, STDIN_INPUTS_FLAG};
@@ -56,6 +57,7 @@ pub fn cli_app() -> Command {
                 .default_value(HD_TYPE)
                 .display_order(0),
         )
+        .arg(kdf_arg())
 }
 
 pub fn cli_run(matches: &ArgMatches, wallet_base_dir: PathBuf) -> Result<(), String> {
@@ -0,0 +1,77 @@
+//! A cache of aggregate attestation signatures which are known to be valid.
+//!
+//! The aggregators of a committee commonly broadcast identical aggregates, and these are usually
+//! verified together before any of them has been observed. The aggregate attestation signature is
+//! by far the most expensive of the three signatures on a `SignedAggregateAndProof`, so it is only
+//! verified once for each distinct attestation. The selection proof and aggregator signature are
+//! specific to each aggregator and are always verified.
+use crate::metrics;
+use lru::LruCache;
+use parking_lot::Mutex;
+use std::num::NonZeroUsize;
+use tree_hash::TreeHash;
+use types::non_zero_usize::new_non_zero_usize;
+use types::{AttestationRef, EthSpec, Hash256};
+
+/// The number of verified attestation signatures to retain.
+const CACHE_SIZE: NonZeroUsize = new_non_zero_usize(1024);
+
+pub struct AggregateSignatureCache {
+    verified: Mutex<LruCache<Hash256, ()>>,
+}
+
+impl Default for AggregateSignatureCache {
+    fn default() -> Self {
+        Self {
+            verified: Mutex::new(LruCache::new(CACHE_SIZE)),
+        }
+    }
+}
+
+impl AggregateSignatureCache {
+    /// Return the key identifying the signature set of `attestation`.
+    ///
+    /// The key commits to the attestation data, the participation bits and the signature, which
+    /// together determine the signature set.
+    pub fn key<E: EthSpec>(attestation: AttestationRef<E>) -> Hash256 {
+        attestation.tree_hash_root()
+    }
+
+    /// Return `true` if the signature set identified by `key` is known to be valid.
+    pub fn is_verified(&self, key: &Hash256) -> bool {
+        let hit = self.verified.lock().get(key).is_some();
+        if hit {
+            metrics::inc_counter(&metrics::AGGREGATE_SIGNATURE_CACHE_HITS);
+        } else {
+            metrics::inc_counter(&metrics::AGGREGATE_SIGNATURE_CACHE_MISSES);
+        }
+        hit
+    }
+
+    /// Record that the signature set identified by `key` is valid.
+    pub fn insert_verified(&self, key: Hash256) {
+        self.verified.lock().put(key, ());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use types::FixedBytesExtended;
+
+    #[test]
+    fn insert_and_evict() {
+        let cache = AggregateSignatureCache::default();
+        let key = |i: u64| Hash256::from_low_u64_be(i);
+
+        assert!(!cache.is_verified(&key(0)));
+        cache.insert_verified(key(0));
+        assert!(cache.is_verified(&key(0)));
+
+        for i in 1..=CACHE_SIZE.get() as u64 {
+            cache.insert_verified(key(i));
+        }
+        assert!(!cache.is_verified(&key(0)));
+        assert!(cache.is_verified(&key(CACHE_SIZE.get() as u64)));
+    }
+}
@@ -0,0 +1,195 @@
+//! A cache which coalesces concurrent requests for identical attestation data.
+//!
+//! Validator clients with many keys request attestation data for the same slot and committee at
+//! almost the same time. The first request for a given key computes the data whilst the others
+//! wait for it, so that the head state is only read once.
+use crate::metrics;
+use parking_lot::Mutex;
+use std::collections::HashMap;
+use std::sync::{Arc, OnceLock};
+use types::{AttestationData, CommitteeIndex, Hash256, Slot};
+
+/// Identifies a distinct attestation data computation.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct AttestationDataCacheKey {
+    pub slot: Slot,
+    pub committee_index: CommitteeIndex,
+    /// The head block root at the time of the request, so that a new head is never attested to
+    /// with stale data.
+    pub head_block_root: Hash256,
+}
+
+/// A cell which is initialised once by the first request for a key. A `None` value indicates that
+/// the computation failed.
+type Entry = Arc<OnceLock<Option<AttestationData>>>;
+
+#[derive(Default)]
+pub struct AttestationDataCache {
+    entries: Mutex<HashMap<AttestationDataCacheKey, Entry>>,
+}
+
+impl AttestationDataCache {
+    /// Return the attestation data for `key`, computing it with `compute` if no other request has
+    /// done so already.
+    ///
+    /// If another request is currently computing the data for `key`, wait for it to complete. If
+    /// that computation failed, `compute` is run for this request instead.
+    pub fn get_or_compute<E, F>(
+        &self,
+        key: AttestationDataCacheKey,
+        compute: F,
+    ) -> Result<AttestationData, E>
+    where
+        F: Fn() -> Result<AttestationData, E>,
+    {
+        let entry = {
+            let mut entries = self.entries.lock();
+            // Requests are permitted up to one slot in advance of the clock, so retain the entries
+            // for the prior slot and prune anything older.
+            entries.retain(|existing, _| existing.slot + 1 >= key.slot);
+            entries.entry(key).or_default().clone()
+        };
+
+        let mut result = None;
+        let data = entry.get_or_init(|| match compute() {
+            Ok(data) => {
+                result = Some(Ok(data.clone()));
+                Some(data)
+            }
+            Err(e) => {
+                result = Some(Err(e));
+                None
+            }
+        });
+
+        match (result, data) {
+            // This request performed the computation.
+            (Some(result), _) => {
+                metrics::inc_counter(&metrics::ATTESTATION_DATA_CACHE_MISSES);
+                if result.is_err() {
+                    self.remove(&key, &entry);
+                }
+                result
+            }
+            // Another request performed the computation.
+            (None, Some(data)) => {
+                metrics::inc_counter(&metrics::ATTESTATION_DATA_CACHE_COALESCED_REQUESTS);
+                Ok(data.clone())
+            }
+            // Another request failed to perform the computation, try again without the cache.
+            (None, None) => {
+                self.remove(&key, &entry);
+                compute()
+            }
+        }
+    }
+
+    /// Remove `entry` if it is still the entry for `key`.
+    fn remove(&self, key: &AttestationDataCacheKey, entry: &Entry) {
+        let mut entries = self.entries.lock();
+        if entries
+            .get(key)
+            .is_some_and(|existing| Arc::ptr_eq(existing, entry))
+        {
+            entries.remove(key);
+        }
+    }
+
+    /// Return the number of cached entries.
+    pub fn len(&self) -> usize {
+        self.entries.lock().len()
+    }
+
+    /// Return `true` if there are no cached entries.
+    pub fn is_empty(&self) -> bool {
+        self.entries.lock().is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Barrier;
+    use types::FixedBytesExtended;
+
+    fn key(slot: u64) -> AttestationDataCacheKey {
+        AttestationDataCacheKey {
+            slot: Slot::new(slot),
+            committee_index: 0,
+            head_block_root: Hash256::from_low_u64_be(1),
+        }
+    }
+
+    fn data(slot: u64) -> AttestationData {
+        AttestationData {
+            slot: Slot::new(slot),
+            ..AttestationData::default()
+        }
+    }
+
+    #[test]
+    fn concurrent_requests_are_coalesced() {
+        let cache = AttestationDataCache::default();
+        let computations = AtomicUsize::new(0);
+        let barrier = Barrier::new(8);
+
+        std::thread::scope(|scope| {
+            for _ in 0..8 {
+                scope.spawn(|| {
+                    barrier.wait();
+                    let result = cache.get_or_compute(key(1), || {
+                        computations.fetch_add(1, Ordering::SeqCst);
+                        std::thread::sleep(std::time::Duration::from_millis(50));
+                        Ok::<_, ()>(data(1))
+                    });
+                    assert_eq!(result, Ok(data(1)));
+                });
+            }
+        });
+
+        assert_eq!(computations.load(Ordering::SeqCst), 1);
+    }
+
+    #[test]
+    fn failures_are_not_cached() {
+        let cache = AttestationDataCache::default();
+
+        assert_eq!(
+            cache.get_or_compute(key(1), || Err("failed")),
+            Err("failed")
+        );
+        assert!(cache.is_empty());
+        assert_eq!(
+            cache.get_or_compute(key(1), || Ok::<_, &str>(data(1))),
+            Ok(data(1))
+        );
+    }
+
+    #[test]
+    fn prior_slots_are_pruned() {
+        let cache = AttestationDataCache::default();
+
+        cache
+            .get_or_compute(key(1), || Ok::<_, ()>(data(1)))
+            .unwrap();
+        cache
+            .get_or_compute(key(2), || Ok::<_, ()>(data(2)))
+            .unwrap();
+        assert_eq!(cache.len(), 2);
+        cache
+            .get_or_compute(key(3), || Ok::<_, ()>(data(3)))
+            .unwrap();
+        assert_eq!(cache.len(), 2);
+
+        // A different head block is a distinct computation.
+        let new_head_key = AttestationDataCacheKey {
+            head_block_root: Hash256::from_low_u64_be(2),
+            ..key(3)
+        };
+        cache
+            .get_or_compute(new_head_key, || Ok::<_, ()>(data(3)))
+            .unwrap();
+        assert_eq!(cache.len(), 3);
+    }
+}
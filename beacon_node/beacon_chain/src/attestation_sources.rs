@@ -0,0 +1,106 @@
+//! Tracks which peer first delivered an attestation from each validator.
+//!
+//! This allows the attestation rewards of a block to be attributed to the peers which supplied
+//! the attestations it includes. Peers are identified by the string form of their peer ID, since
+//! this crate has no knowledge of the networking types.
+use std::collections::HashMap;
+use std::sync::Arc;
+use types::Epoch;
+
+/// The number of target epochs for which attestation sources are retained.
+///
+/// Attestations can be included up to the end of the epoch following their target epoch, so a
+/// small margin on top of two epochs is sufficient.
+pub const ATTESTATION_SOURCE_RETENTION_EPOCHS: u64 = 4;
+
+#[derive(Default)]
+pub struct AttestationSources {
+    /// Map from target epoch to a map from validator index to the first peer to deliver an
+    /// attestation from that validator.
+    epochs: HashMap<Epoch, HashMap<u64, Arc<str>>>,
+    /// The highest target epoch observed so far.
+    latest_epoch: Epoch,
+}
+
+impl AttestationSources {
+    /// Record `peer` as the source of an attestation from each of the `attesting_indices`, unless
+    /// an attestation from that validator has already been received for `target_epoch`.
+    pub fn observe(
+        &mut self,
+        target_epoch: Epoch,
+        attesting_indices: impl IntoIterator<Item = u64>,
+        peer: &str,
+    ) {
+        if target_epoch + ATTESTATION_SOURCE_RETENTION_EPOCHS <= self.latest_epoch {
+            return;
+        }
+
+        if target_epoch > self.latest_epoch {
+            self.latest_epoch = target_epoch;
+            self.prune();
+        }
+
+        let sources = self.epochs.entry(target_epoch).or_default();
+        let mut peer_id: Option<Arc<str>> = None;
+        for validator_index in attesting_indices {
+            sources
+                .entry(validator_index)
+                .or_insert_with(|| peer_id.get_or_insert_with(|| Arc::from(peer)).clone());
+        }
+    }
+
+    /// Return the peer which first delivered an attestation from `validator_index` for
+    /// `target_epoch`, if any.
+    pub fn source(&self, target_epoch: Epoch, validator_index: u64) -> Option<&str> {
+        self.epochs
+            .get(&target_epoch)
+            .and_then(|sources| sources.get(&validator_index))
+            .map(AsRef::as_ref)
+    }
+
+    /// Remove all epochs which have fallen outside of the retention window.
+    fn prune(&mut self) {
+        let latest_epoch = self.latest_epoch;
+        self.epochs
+            .retain(|epoch, _| *epoch + ATTESTATION_SOURCE_RETENTION_EPOCHS > latest_epoch);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn first_peer_is_retained() {
+        let mut sources = AttestationSources::default();
+        let epoch = Epoch::new(1);
+
+        sources.observe(epoch, [0, 1], "peer_a");
+        sources.observe(epoch, [1, 2], "peer_b");
+
+        assert_eq!(sources.source(epoch, 0), Some("peer_a"));
+        assert_eq!(sources.source(epoch, 1), Some("peer_a"));
+        assert_eq!(sources.source(epoch, 2), Some("peer_b"));
+        assert_eq!(sources.source(epoch, 3), None);
+        assert_eq!(sources.source(epoch + 1, 0), None);
+    }
+
+    #[test]
+    fn old_epochs_are_pruned() {
+        let mut sources = AttestationSources::default();
+        let first_epoch = Epoch::new(1);
+        let last_epoch = first_epoch + ATTESTATION_SOURCE_RETENTION_EPOCHS;
+
+        sources.observe(first_epoch, [0], "peer_a");
+        sources.observe(last_epoch - 1, [0], "peer_a");
+        assert_eq!(sources.source(first_epoch, 0), Some("peer_a"));
+
+        sources.observe(last_epoch, [0], "peer_a");
+        assert_eq!(sources.source(first_epoch, 0), None);
+        assert_eq!(sources.source(last_epoch - 1, 0), Some("peer_a"));
+
+        // Attestations from outside the retention window are ignored.
+        sources.observe(first_epoch, [0], "peer_b");
+        assert_eq!(sources.source(first_epoch, 0), None);
+    }
+}
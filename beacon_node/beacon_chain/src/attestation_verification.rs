@@ -35,6 +35,7 @@
 mod batch;
 
 use crate::{
+    aggregate_signature_cache::AggregateSignatureCache,
     metrics,
     observed_aggregates::{ObserveOutcome, ObservedAttestationKey},
     observed_attesters::Error as ObservedAttestersError,
@@ -434,8 +435,16 @@ fn process_slash_info<T: BeaconChainTypes>(
             }
         }
 
-        // Supply to slasher.
-        slasher.accept_attestation(indexed_attestation);
+        // Supply to slasher. An unaggregated attestation from a validator which has already been
+        // observed in this epoch is almost certainly a double vote, so is flagged for immediate
+        // processing.
+        if matches!(err, Error::PriorAttestationKnown { .. })
+            && indexed_attestation.attesting_indices_len() == 1
+        {
+            slasher.accept_conflicting_attestation(indexed_attestation);
+        } else {
+            slasher.accept_attestation(indexed_attestation);
+        }
 
         err
     } else {
@@ -1257,6 +1266,9 @@ pub fn verify_attestation_target_root<E: EthSpec>(
 /// - `signed_aggregate.message.selection_proof`
 /// - `signed_aggregate.message.aggregate.signature`
 ///
+/// The last is skipped if it is present in the `AggregateSignatureCache`, and added to it once
+/// verified.
+///
 /// # Returns
 ///
 /// - `Ok(true)`: if all signatures are valid.
@@ -1278,7 +1290,12 @@ pub fn verify_signed_aggregate_signatures<T: BeaconChainTypes>(
         .spec
         .fork_at_epoch(indexed_attestation.data().target.epoch);
 
-    let signature_sets = vec![
+    let signature_cache_key = AggregateSignatureCache::key(signed_aggregate.message().aggregate());
+    let attestation_signature_verified = chain
+        .aggregate_signature_cache
+        .is_verified(&signature_cache_key);
+
+    let mut signature_sets = vec![
         signed_aggregate_selection_proof_signature_set(
             |validator_index| pubkey_cache.get(validator_index).map(Cow::Borrowed),
             signed_aggregate,
@@ -1295,18 +1312,28 @@ pub fn verify_signed_aggregate_signatures<T: BeaconChainTypes>(
             &chain.spec,
         )
         .map_err(BeaconChainError::SignatureSetError)?,
-        indexed_attestation_signature_set_from_pubkeys(
-            |validator_index| pubkey_cache.get(validator_index).map(Cow::Borrowed),
-            indexed_attestation.signature(),
-            indexed_attestation,
-            &fork,
-            chain.genesis_validators_root,
-            &chain.spec,
-        )
-        .map_err(BeaconChainError::SignatureSetError)?,
     ];
+    if !attestation_signature_verified {
+        signature_sets.push(
+            indexed_attestation_signature_set_from_pubkeys(
+                |validator_index| pubkey_cache.get(validator_index).map(Cow::Borrowed),
+                indexed_attestation.signature(),
+                indexed_attestation,
+                &fork,
+                chain.genesis_validators_root,
+                &chain.spec,
+            )
+            .map_err(BeaconChainError::SignatureSetError)?,
+        );
+    }
 
-    Ok(verify_signature_sets(signature_sets.iter()))
+    let is_valid = verify_signature_sets(signature_sets.iter());
+    if is_valid && !attestation_signature_verified {
+        chain
+            .aggregate_signature_cache
+            .insert_verified(signature_cache_key);
+    }
+    Ok(is_valid)
 }
 
 /// Verify that the `attestation` committee index is properly set for the attestation's fork.
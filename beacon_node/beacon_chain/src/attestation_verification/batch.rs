@@ -13,6 +13,7 @@ use super::{
     CheckAttestationSignature, Error, IndexedAggregatedAttestation, IndexedUnaggregatedAttestation,
     VerifiedAggregatedAttestation, VerifiedUnaggregatedAttestation,
 };
+use crate::aggregate_signature_cache::AggregateSignatureCache;
 use crate::{metrics, BeaconChain, BeaconChainError, BeaconChainTypes};
 use bls::verify_signature_sets;
 use state_processing::signature_sets::{
@@ -20,6 +21,7 @@ use state_processing::signature_sets::{
     signed_aggregate_signature_set,
 };
 use std::borrow::Cow;
+use std::collections::HashSet;
 use types::*;
 
 /// Verify aggregated attestations using batch BLS signature verification.
@@ -60,6 +62,9 @@ where
         let pubkey_cache = chain.validator_pubkey_cache.read();
 
         let mut signature_sets = Vec::with_capacity(num_indexed * 3);
+        // The aggregate attestation signatures which will be known to be valid if the batch
+        // verifies. Identical attestations only need to be included in the batch once.
+        let mut signature_cache_keys = HashSet::with_capacity(num_indexed);
         // Iterate, flattening to get only the `Ok` values.
         for indexed in indexing_results.iter().flatten() {
             let signed_aggregate = &indexed.signed_aggregate;
@@ -88,6 +93,20 @@ where
                 )
                 .map_err(BeaconChainError::SignatureSetError)?,
             );
+
+            let signature_cache_key =
+                AggregateSignatureCache::key(signed_aggregate.message().aggregate());
+            if signature_cache_keys.contains(&signature_cache_key) {
+                metrics::inc_counter(&metrics::AGGREGATE_SIGNATURE_CACHE_HITS);
+                continue;
+            }
+            if chain
+                .aggregate_signature_cache
+                .is_verified(&signature_cache_key)
+            {
+                continue;
+            }
+            signature_cache_keys.insert(signature_cache_key);
             signature_sets.push(
                 indexed_attestation_signature_set_from_pubkeys(
                     |validator_index| pubkey_cache.get(validator_index).map(Cow::Borrowed),
@@ -107,6 +126,12 @@ where
             metrics::start_timer(&metrics::ATTESTATION_PROCESSING_BATCH_AGG_SIGNATURE_TIMES);
 
         if verify_signature_sets(signature_sets.iter()) {
+            for signature_cache_key in signature_cache_keys {
+                chain
+                    .aggregate_signature_cache
+                    .insert_verified(signature_cache_key);
+            }
+
             // Since all the signatures verified in a batch, there's no reason for them to be
             // checked again later.
             check_signatures = CheckAttestationSignature::No
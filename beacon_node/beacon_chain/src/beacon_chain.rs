@@ -1,3 +1,6 @@
+use crate::aggregate_signature_cache::AggregateSignatureCache;
+use crate::attestation_data_cache::{AttestationDataCache, AttestationDataCacheKey};
+use crate::attestation_sources::AttestationSources;
 use crate::attestation_verification::{
     batch_verify_aggregated_attestations, batch_verify_unaggregated_attestations,
     Error as AttestationError, VerifiedAggregatedAttestation, VerifiedAttestation,
@@ -7,19 +10,23 @@ use crate::attester_cache::{AttesterCache, AttesterCacheKey};
 use crate::beacon_block_streamer::{BeaconBlockStreamer, CheckCaches};
 use crate::beacon_proposer_cache::compute_proposer_duties_from_head;
 use crate::beacon_proposer_cache::BeaconProposerCache;
+use crate::beacon_snapshot::PreProcessingSnapshot;
+use crate::blob_inclusion_proof_cache::BlobInclusionProofCache;
 use crate::blob_verification::{GossipBlobError, GossipVerifiedBlob};
 use crate::block_times_cache::BlockTimesCache;
+use crate::block_timing_history::BlockTimingHistory;
 use crate::block_verification::POS_PANDA_BANNER;
 use crate::block_verification::{
     check_block_is_finalized_checkpoint_or_descendant, check_block_relevancy,
-    signature_verify_chain_segment, verify_header_signature, BlockError, ExecutionPendingBlock,
-    GossipVerifiedBlock, IntoExecutionPendingBlock,
+    load_chain_segment_pre_state, signature_verify_chain_segment_batch, verify_header_signature,
+    BlockError, ExecutionPendingBlock, GossipVerifiedBlock, IntoExecutionPendingBlock,
+    SignatureVerifiedBlock,
 };
 use crate::block_verification_types::{
     AsBlock, AvailableExecutedBlock, BlockImportData, ExecutedBlock, RpcBlock,
 };
 pub use crate::canonical_head::CanonicalHead;
-use crate::chain_config::ChainConfig;
+use crate::chain_config::{BlobFetchStrategy, ChainConfig};
 use crate::data_availability_checker::{
     Availability, AvailabilityCheckError, AvailableBlock, DataAvailabilityChecker,
     DataColumnReconstructionResult,
@@ -33,6 +40,7 @@ use crate::events::ServerSentEventHandler;
 use crate::execution_payload::{get_execution_payload, NotifyExecutionLayer, PreparePayloadHandle};
 use crate::fork_choice_signal::{ForkChoiceSignalRx, ForkChoiceSignalTx, ForkChoiceWaitResult};
 use crate::graffiti_calculator::GraffitiCalculator;
+use crate::head_history::HeadHistory;
 use crate::head_tracker::{HeadTracker, HeadTrackerReader, SszHeadTracker};
 use crate::light_client_finality_update_verification::{
     Error as LightClientFinalityUpdateError, VerifiedLightClientFinalityUpdate,
@@ -40,7 +48,9 @@ use crate::light_client_finality_update_verification::{
 use crate::light_client_optimistic_update_verification::{
     Error as LightClientOptimisticUpdateError, VerifiedLightClientOptimisticUpdate,
 };
-use crate::light_client_server_cache::LightClientServerCache;
+use crate::light_client_server_cache::{
+    LightClientServerCache, LIGHT_CLIENT_STORE_SNAPSHOT_PERIODS,
+};
 use crate::migrate::BackgroundMigrator;
 use crate::naive_aggregation_pool::{
     AggregatedAttestationMap, Error as NaiveAggregationError, NaiveAggregationPool,
@@ -59,7 +69,10 @@ use crate::observed_slashable::ObservedSlashable;
 use crate::persisted_beacon_chain::{PersistedBeaconChain, DUMMY_CANONICAL_HEAD_BLOCK_ROOT};
 use crate::persisted_fork_choice::PersistedForkChoice;
 use crate::pre_finalization_cache::PreFinalizationBlockCache;
+use crate::publish_safety::{PublishSafety, PublishSafetyViolation};
 use crate::shuffling_cache::{BlockShufflingIds, ShufflingCache};
+use crate::state_request_coalescer::StateRequestCoalescer;
+use crate::storage_watchdog::StorageWatchdog;
 use crate::sync_committee_verification::{
     Error as SyncCommitteeError, VerifiedSyncCommitteeMessage, VerifiedSyncContribution,
 };
@@ -72,10 +85,15 @@ use crate::{
     kzg_utils, metrics, AvailabilityPendingExecutedBlock, BeaconChainError, BeaconForkChoiceStore,
     BeaconSnapshot, CachedHead,
 };
-use eth2::types::{EventKind, SseBlobSidecar, SseBlock, SseExtendedPayloadAttributes};
+use eth2::lighthouse::{
+    BlockTimingRecord, BlockValueRecord, LightClientStoreImport, LightClientStoreSnapshot,
+};
+use eth2::types::{
+    EventKind, ParentBlockHashSource, SseBlobSidecar, SseBlock, SseExtendedPayloadAttributes,
+};
 use execution_layer::{
-    BlockProposalContents, BlockProposalContentsType, BuilderParams, ChainHealth, ExecutionLayer,
-    FailedCondition, PayloadAttributes, PayloadStatus,
+    block_value_history::BLOCK_VALUE_RETENTION, BlockProposalContents, BlockProposalContentsType,
+    BuilderParams, ChainHealth, ExecutionLayer, FailedCondition, PayloadAttributes, PayloadStatus,
 };
 use fork_choice::{
     AttestationFromBlock, ExecutionStatus, ForkChoice, ForkchoiceUpdateParameters,
@@ -111,6 +129,8 @@ use std::borrow::Cow;
 use std::cmp::Ordering;
 use std::collections::HashMap;
 use std::collections::HashSet;
+use std::collections::VecDeque;
+use std::future::Future;
 use std::io::prelude::*;
 use std::marker::PhantomData;
 use std::sync::Arc;
@@ -470,16 +490,38 @@ pub struct BeaconChain<T: BeaconChainTypes> {
     pub(crate) attester_cache: Arc<AttesterCache>,
     /// A cache used when producing attestations whilst the head block is still being imported.
     pub early_attester_cache: EarlyAttesterCache<T::EthSpec>,
+    /// Coalesces concurrent requests for the same attestation data.
+    pub attestation_data_cache: AttestationDataCache,
     /// Cache gossip verified blocks to serve over ReqResp before they are imported
     pub reqresp_pre_import_cache: Arc<RwLock<ReqRespPreImportCache<T::EthSpec>>>,
     /// A cache used to keep track of various block timings.
     pub block_times_cache: Arc<RwLock<BlockTimesCache>>,
+    /// A rolling history of when and how imported blocks were first seen.
+    pub block_timing_history: RwLock<BlockTimingHistory>,
+    /// A rolling history of changes to the canonical head, used to summarise re-orgs.
+    pub head_history: RwLock<HeadHistory>,
     /// A cache used to track pre-finalization block roots for quick rejection.
     pub pre_finalization_block_cache: PreFinalizationBlockCache,
+    /// A cache of aggregate attestation signatures which are known to be valid.
+    pub aggregate_signature_cache: AggregateSignatureCache,
+    /// A cache of the blob sidecar inclusion proofs of locally produced blocks.
+    pub blob_inclusion_proof_cache: BlobInclusionProofCache<T::EthSpec>,
     /// A cache used to produce light_client server messages
     pub light_client_server_cache: LightClientServerCache<T>,
+    /// Coalesces concurrent requests to load the same state via `Self::get_state_coalesced`.
+    pub state_request_coalescer: StateRequestCoalescer<T::EthSpec>,
     /// Sender to signal the light_client server to produce new updates
     pub light_client_server_tx: Option<Sender<LightClientProducerEvent<T::EthSpec>>>,
+    /// Records the peer which first delivered an attestation from each validator.
+    ///
+    /// Only populated when `ChainConfig::track_attestation_sources` is set.
+    pub attestation_sources: Mutex<AttestationSources>,
+    /// Recent attestations from validators connected to the HTTP API.
+    ///
+    /// Only populated when `ChainConfig::publish_safety_checks` is set.
+    pub publish_safety: RwLock<PublishSafety>,
+    /// The disk space pressure reported by the storage watchdog.
+    pub storage_watchdog: StorageWatchdog,
     /// Sender given to tasks, so that if they encounter a state in which execution cannot
     /// continue they can request that everything shuts down.
     pub shutdown_sender: Sender<ShutdownReason>,
@@ -737,6 +779,12 @@ impl<T: BeaconChainTypes> BeaconChain<T> {
             .map(|slot| slot.epoch(T::EthSpec::slots_per_epoch()))
     }
 
+    /// Returns the name of the fork at `slot`, for use as the `fork` label of metrics about
+    /// objects from that slot.
+    pub fn fork_label_at_slot(&self, slot: Slot) -> &'static str {
+        self.spec.fork_name_at_slot::<T::EthSpec>(slot).as_str()
+    }
+
     /// Iterates across all `(block_root, slot)` pairs from `start_slot`
     /// to the head of the chain (inclusive).
     ///
@@ -1300,6 +1348,19 @@ impl<T: BeaconChainTypes> BeaconChain<T> {
         Ok(self.store.get_state(state_root, slot)?)
     }
 
+    /// As for `Self::get_state`, but if the same state is already being loaded by a concurrent
+    /// call to this function, wait for and return its result instead of loading it again.
+    ///
+    /// This is useful for serving expensive requests for historical states from the HTTP API.
+    pub fn get_state_coalesced(
+        &self,
+        state_root: &Hash256,
+        slot: Option<Slot>,
+    ) -> Result<Option<BeaconState<T::EthSpec>>, Error> {
+        self.state_request_coalescer
+            .get_or_load(*state_root, || self.get_state(state_root, slot))
+    }
+
     /// Return the sync committee at `slot + 1` from the canonical chain.
     ///
     /// This is useful when dealing with sync committee messages, because messages are signed
@@ -1401,6 +1462,29 @@ impl<T: BeaconChainTypes> BeaconChain<T> {
         )
     }
 
+    /// Returns a snapshot of the light client server's cached updates, including the best updates
+    /// of the most recent `LIGHT_CLIENT_STORE_SNAPSHOT_PERIODS` sync committee periods.
+    pub fn get_light_client_store_snapshot(
+        &self,
+    ) -> Result<LightClientStoreSnapshot<T::EthSpec>, Error> {
+        let current_period = self.epoch()?.sync_committee_period(&self.spec)?;
+        self.light_client_server_cache.get_snapshot(
+            &self.store,
+            current_period,
+            LIGHT_CLIENT_STORE_SNAPSHOT_PERIODS,
+            &self.spec,
+        )
+    }
+
+    /// Warms the light client server's cache with a snapshot from `get_light_client_store_snapshot`.
+    pub fn import_light_client_store_snapshot(
+        &self,
+        snapshot: LightClientStoreSnapshot<T::EthSpec>,
+    ) -> Result<LightClientStoreImport, Error> {
+        self.light_client_server_cache
+            .import_snapshot(self, snapshot)
+    }
+
     /// Returns the current heads of the `BeaconChain`. For the canonical head, see `Self::head`.
     ///
     /// Returns `(block_root, block_slot)`.
@@ -1783,6 +1867,35 @@ impl<T: BeaconChainTypes> BeaconChain<T> {
         }
     }
 
+    /// Produce the `AttestationData` for the given `request_slot` and `request_index`.
+    ///
+    /// Concurrent requests for the same slot, committee and head block are served from a single
+    /// call to `Self::produce_unaggregated_attestation`.
+    pub fn produce_attestation_data(
+        &self,
+        request_slot: Slot,
+        request_index: CommitteeIndex,
+    ) -> Result<AttestationData, Error> {
+        // The early attester cache is cheap to query and may contain a block which is not yet the
+        // head, so it is checked before the coalescing cache.
+        if let Ok(Some(attestation)) =
+            self.early_attester_cache
+                .try_attest(request_slot, request_index, &self.spec)
+        {
+            return Ok(attestation.data().clone());
+        }
+
+        let key = AttestationDataCacheKey {
+            slot: request_slot,
+            committee_index: request_index,
+            head_block_root: self.canonical_head.cached_head().head_block_root(),
+        };
+        self.attestation_data_cache.get_or_compute(key, || {
+            self.produce_unaggregated_attestation(request_slot, request_index)
+                .map(|attestation| attestation.data().clone())
+        })
+    }
+
     /// Produce an unaggregated `Attestation` that is valid for the given `slot` and `index`.
     ///
     /// The produced `Attestation` will not be valid until it has been signed by exactly one
@@ -2025,7 +2138,11 @@ impl<T: BeaconChainTypes> BeaconChain<T> {
         unaggregated_attestation: &'a Attestation<T::EthSpec>,
         subnet_id: Option<SubnetId>,
     ) -> Result<VerifiedUnaggregatedAttestation<'a, T>, AttestationError> {
-        metrics::inc_counter(&metrics::UNAGGREGATED_ATTESTATION_PROCESSING_REQUESTS);
+        let fork_label = self.fork_label_at_slot(unaggregated_attestation.data().slot);
+        metrics::inc_counter_vec(
+            &metrics::UNAGGREGATED_ATTESTATION_PROCESSING_REQUESTS,
+            &[fork_label],
+        );
         let _timer =
             metrics::start_timer(&metrics::UNAGGREGATED_ATTESTATION_GOSSIP_VERIFICATION_TIMES);
 
@@ -2039,7 +2156,10 @@ impl<T: BeaconChainTypes> BeaconChain<T> {
                         )));
                     }
                 }
-                metrics::inc_counter(&metrics::UNAGGREGATED_ATTESTATION_PROCESSING_SUCCESSES);
+                metrics::inc_counter_vec(
+                    &metrics::UNAGGREGATED_ATTESTATION_PROCESSING_SUCCESSES,
+                    &[fork_label],
+                );
             },
         )
     }
@@ -2063,7 +2183,12 @@ impl<T: BeaconChainTypes> BeaconChain<T> {
         &self,
         signed_aggregate: &'a SignedAggregateAndProof<T::EthSpec>,
     ) -> Result<VerifiedAggregatedAttestation<'a, T>, AttestationError> {
-        metrics::inc_counter(&metrics::AGGREGATED_ATTESTATION_PROCESSING_REQUESTS);
+        let fork_label =
+            self.fork_label_at_slot(signed_aggregate.message().aggregate().data().slot);
+        metrics::inc_counter_vec(
+            &metrics::AGGREGATED_ATTESTATION_PROCESSING_REQUESTS,
+            &[fork_label],
+        );
         let _timer =
             metrics::start_timer(&metrics::AGGREGATED_ATTESTATION_GOSSIP_VERIFICATION_TIMES);
 
@@ -2076,7 +2201,10 @@ impl<T: BeaconChainTypes> BeaconChain<T> {
                     )));
                 }
             }
-            metrics::inc_counter(&metrics::AGGREGATED_ATTESTATION_PROCESSING_SUCCESSES);
+            metrics::inc_counter_vec(
+                &metrics::AGGREGATED_ATTESTATION_PROCESSING_SUCCESSES,
+                &[fork_label],
+            );
         })
     }
 
@@ -2192,6 +2320,73 @@ impl<T: BeaconChainTypes> BeaconChain<T> {
             .map_err(Into::into)
     }
 
+    /// Record `peer_id` as the source of a verified gossip attestation, if attestation source
+    /// tracking is enabled.
+    ///
+    /// Only the first peer to deliver an attestation from each validator is retained.
+    pub fn observe_attestation_source(
+        &self,
+        verified: &impl VerifiedAttestation<T>,
+        peer_id: impl std::fmt::Display,
+    ) {
+        if !self.config.track_attestation_sources {
+            return;
+        }
+
+        let indexed_attestation = verified.indexed_attestation();
+        self.attestation_sources.lock().observe(
+            indexed_attestation.data().target.epoch,
+            indexed_attestation.attesting_indices_iter().copied(),
+            &peer_id.to_string(),
+        );
+    }
+
+    /// Check that publishing a verified attestation submitted via the HTTP API would not make any
+    /// of its attesters slashable, if publish safety checks are enabled.
+    ///
+    /// If the attestation is safe to publish it is recorded and its attesters are tracked, so that
+    /// a conflicting attestation submitted concurrently is refused.
+    pub fn check_and_observe_attestation_publish_safety(
+        &self,
+        verified: &impl VerifiedAttestation<T>,
+    ) -> Result<(), PublishSafetyViolation> {
+        if !self.config.publish_safety_checks {
+            return Ok(());
+        }
+
+        let indexed_attestation = verified.indexed_attestation();
+        let data = indexed_attestation.data();
+        let attesting_indices = indexed_attestation
+            .attesting_indices_iter()
+            .copied()
+            .collect::<Vec<_>>();
+        self.publish_safety.write().check_and_observe(
+            data,
+            data.tree_hash_root(),
+            &attesting_indices,
+        )
+    }
+
+    /// Record a verified gossip attestation for the purpose of publish safety checks, if they are
+    /// enabled.
+    ///
+    /// The attestation is only recorded for validators which are already tracked because they have
+    /// submitted attestations via the HTTP API.
+    pub fn observe_attestation_for_publish_safety(&self, verified: &impl VerifiedAttestation<T>) {
+        if !self.config.publish_safety_checks {
+            return;
+        }
+
+        let indexed_attestation = verified.indexed_attestation();
+        let data = indexed_attestation.data();
+        self.publish_safety.write().observe(
+            data,
+            data.tree_hash_root(),
+            indexed_attestation.attesting_indices_iter().copied(),
+            false,
+        );
+    }
+
     /// Accepts an `VerifiedUnaggregatedAttestation` and attempts to apply it to the "naive
     /// aggregation pool".
     ///
@@ -2825,15 +3020,17 @@ impl<T: BeaconChainTypes> BeaconChain<T> {
             let mut blocks = filtered_chain_segment.split_off(last_index);
             std::mem::swap(&mut blocks, &mut filtered_chain_segment);
 
+            // Load the state used to verify the signatures of all blocks in this epoch.
             let chain = self.clone();
-            let signature_verification_future = self.spawn_blocking_handle(
-                move || signature_verify_chain_segment(blocks, &chain),
-                "signature_verify_chain_segment",
+            let pre_state_future = self.spawn_blocking_handle(
+                move || {
+                    load_chain_segment_pre_state(&mut blocks, &chain)
+                        .map(|(parent, state)| (blocks, parent, Arc::new(state)))
+                },
+                "load_chain_segment_pre_state",
             );
-
-            // Verify the signature of the blocks, returning early if the signature is invalid.
-            let signature_verified_blocks = match signature_verification_future.await {
-                Ok(Ok(blocks)) => blocks,
+            let (mut blocks, parent, state) = match pre_state_future.await {
+                Ok(Ok(pre_state)) => pre_state,
                 Ok(Err(error)) => {
                     return ChainSegmentResult::Failed {
                         imported_blocks,
@@ -2848,49 +3045,60 @@ impl<T: BeaconChainTypes> BeaconChain<T> {
                 }
             };
 
-            // Import the blocks into the chain.
-            for signature_verified_block in signature_verified_blocks {
-                let block_slot = signature_verified_block.slot();
-                match self
-                    .process_block(
-                        signature_verified_block.block_root(),
-                        signature_verified_block,
-                        notify_execution_layer,
-                        BlockImportSource::RangeSync,
-                        || Ok(()),
-                    )
-                    .await
-                {
-                    Ok(status) => {
-                        match status {
-                            AvailabilityProcessingStatus::Imported(block_root) => {
-                                // The block was imported successfully.
-                                imported_blocks.push((block_root, block_slot));
-                            }
-                            AvailabilityProcessingStatus::MissingComponents(slot, block_root) => {
-                                warn!(self.log, "Blobs missing in response to range request";
-                                    "block_root" => ?block_root, "slot" => slot);
-                                return ChainSegmentResult::Failed {
-                                    imported_blocks,
-                                    error: BlockError::AvailabilityCheck(
-                                        AvailabilityCheckError::MissingBlobs,
-                                    ),
-                                };
-                            }
-                        }
-                    }
-                    Err(BlockError::DuplicateFullyImported(block_root)) => {
-                        debug!(self.log,
-                            "Ignoring already known blocks while processing chain segment";
-                            "block_root" => ?block_root);
-                        continue;
-                    }
+            // Split the blocks into batches of at most `max_chain_segment_length`.
+            let batch_size = self.config.max_chain_segment_length.max(1);
+            let mut batches = VecDeque::with_capacity(blocks.len().div_ceil(batch_size));
+            while !blocks.is_empty() {
+                let remaining = blocks.split_off(std::cmp::min(batch_size, blocks.len()));
+                batches.push_back(std::mem::replace(&mut blocks, remaining));
+            }
+
+            // Only the first batch is verified with the pre-state of the first block. Blocks in
+            // later batches load their parent once the previous batch has been imported.
+            let pipeline = self.config.pipeline_chain_segment_verification;
+            let mut parent = Some(parent);
+            let mut next_batch = batches.pop_front().map(|batch| {
+                self.spawn_signature_verify_chain_segment_batch(batch, parent.take(), state.clone())
+            });
+
+            while let Some(signature_verification_future) = next_batch.take() {
+                // Verify the signature of the blocks, returning early if the signature is invalid.
+                let signature_verified_blocks = match signature_verification_future.await {
+                    Ok(blocks) => blocks,
                     Err(error) => {
                         return ChainSegmentResult::Failed {
                             imported_blocks,
                             error,
                         };
                     }
+                };
+
+                // All batches share the same state, so the next batch can be verified whilst
+                // this one is imported.
+                if pipeline {
+                    next_batch = batches.pop_front().map(|batch| {
+                        self.spawn_signature_verify_chain_segment_batch(batch, None, state.clone())
+                    });
+                }
+
+                if let Err(error) = self
+                    .import_chain_segment_batch(
+                        signature_verified_blocks,
+                        notify_execution_layer,
+                        &mut imported_blocks,
+                    )
+                    .await
+                {
+                    return ChainSegmentResult::Failed {
+                        imported_blocks,
+                        error,
+                    };
+                }
+
+                if !pipeline {
+                    next_batch = batches.pop_front().map(|batch| {
+                        self.spawn_signature_verify_chain_segment_batch(batch, None, state.clone())
+                    });
                 }
             }
         }
@@ -2898,6 +3106,77 @@ impl<T: BeaconChainTypes> BeaconChain<T> {
         ChainSegmentResult::Successful { imported_blocks }
     }
 
+    /// Spawns a blocking task which verifies the signatures of a `batch` of blocks from a chain
+    /// segment. Verification begins immediately, the returned future only awaits the result.
+    fn spawn_signature_verify_chain_segment_batch(
+        self: &Arc<Self>,
+        batch: Vec<HashBlockTuple<T::EthSpec>>,
+        parent: Option<PreProcessingSnapshot<T::EthSpec>>,
+        state: Arc<BeaconState<T::EthSpec>>,
+    ) -> impl Future<Output = Result<Vec<SignatureVerifiedBlock<T>>, BlockError>> {
+        let chain = self.clone();
+        let handle = self.task_executor.spawn_blocking_handle(
+            move || signature_verify_chain_segment_batch(batch, parent, &state, &chain),
+            "signature_verify_chain_segment",
+        );
+
+        async move {
+            handle
+                .ok_or(Error::RuntimeShutdown)
+                .map_err(BlockError::BeaconChainError)?
+                .await
+                .map_err(|e| BlockError::BeaconChainError(Error::TokioJoin(e)))?
+        }
+    }
+
+    /// Imports a batch of signature-verified blocks from a chain segment, adding the imported
+    /// blocks to `imported_blocks`.
+    async fn import_chain_segment_batch(
+        self: &Arc<Self>,
+        signature_verified_blocks: Vec<SignatureVerifiedBlock<T>>,
+        notify_execution_layer: NotifyExecutionLayer,
+        imported_blocks: &mut Vec<(Hash256, Slot)>,
+    ) -> Result<(), BlockError> {
+        for signature_verified_block in signature_verified_blocks {
+            let block_slot = signature_verified_block.slot();
+            match self
+                .process_block(
+                    signature_verified_block.block_root(),
+                    signature_verified_block,
+                    notify_execution_layer,
+                    BlockImportSource::RangeSync,
+                    || Ok(()),
+                )
+                .await
+            {
+                Ok(status) => {
+                    match status {
+                        AvailabilityProcessingStatus::Imported(block_root) => {
+                            // The block was imported successfully.
+                            imported_blocks.push((block_root, block_slot));
+                        }
+                        AvailabilityProcessingStatus::MissingComponents(slot, block_root) => {
+                            warn!(self.log, "Blobs missing in response to range request";
+                                "block_root" => ?block_root, "slot" => slot);
+                            return Err(BlockError::AvailabilityCheck(
+                                AvailabilityCheckError::MissingBlobs,
+                            ));
+                        }
+                    }
+                }
+                Err(BlockError::DuplicateFullyImported(block_root)) => {
+                    debug!(self.log,
+                        "Ignoring already known blocks while processing chain segment";
+                        "block_root" => ?block_root);
+                    continue;
+                }
+                Err(error) => return Err(error),
+            }
+        }
+
+        Ok(())
+    }
+
     /// Updates fork-choice node into a permanent `available` state so it can become a viable head.
     /// Only completed sampling results are received. Blocks are unavailable by default and should
     /// be pruned on finalization, on a timeout or by a max count.
@@ -2990,7 +3269,9 @@ impl<T: BeaconChainTypes> BeaconChain<T> {
 
         self.emit_sse_blob_sidecar_events(&block_root, std::iter::once(blob.as_blob()));
 
+        let slot = blob.slot();
         let r = self.check_gossip_blob_availability_and_import(blob).await;
+        self.record_blob_acquisition(BlobFetchStrategy::Gossip, slot, 1, &r);
         self.remove_notified(&block_root, r)
     }
 
@@ -3070,9 +3351,11 @@ impl<T: BeaconChainTypes> BeaconChain<T> {
 
         self.emit_sse_blob_sidecar_events(&block_root, blobs.iter().flatten().map(Arc::as_ref));
 
+        let blob_count = blobs.iter().flatten().count();
         let r = self
             .check_rpc_blob_availability_and_import(slot, block_root, blobs)
             .await;
+        self.record_blob_acquisition(BlobFetchStrategy::RpcByRoot, slot, blob_count, &r);
         self.remove_notified(&block_root, r)
     }
 
@@ -3100,12 +3383,47 @@ impl<T: BeaconChainTypes> BeaconChain<T> {
 
         self.emit_sse_blob_sidecar_events(&block_root, blobs.iter().flatten().map(Arc::as_ref));
 
+        let blob_count = blobs.iter().flatten().count();
         let r = self
             .check_engine_blob_availability_and_import(slot, block_root, blobs, data_column_recv)
             .await;
+        self.record_blob_acquisition(BlobFetchStrategy::EngineGetBlobs, slot, blob_count, &r);
         self.remove_notified(&block_root, r)
     }
 
+    /// Record the number of blobs received by `strategy`, how late in the slot they arrived and
+    /// whether they completed the block's data.
+    fn record_blob_acquisition(
+        &self,
+        strategy: BlobFetchStrategy,
+        slot: Slot,
+        blob_count: usize,
+        r: &Result<AvailabilityProcessingStatus, BlockError>,
+    ) {
+        if blob_count == 0 {
+            return;
+        }
+        let label = &[strategy.as_ref()];
+        metrics::inc_counter_vec_by(
+            &metrics::BLOB_ACQUISITION_BLOBS_TOTAL,
+            label,
+            blob_count as u64,
+        );
+        if let (Some(now), Some(slot_start)) = (
+            self.slot_clock.now_duration(),
+            self.slot_clock.start_of(slot),
+        ) {
+            metrics::observe_timer_vec(
+                &metrics::BLOB_ACQUISITION_SLOT_DELAY,
+                label,
+                now.saturating_sub(slot_start),
+            );
+        }
+        if matches!(r, Ok(AvailabilityProcessingStatus::Imported(_))) {
+            metrics::inc_counter_vec(&metrics::BLOB_ACQUISITION_COMPLETED_TOTAL, label);
+        }
+    }
+
     fn emit_sse_blob_sidecar_events<'a, I>(self: &Arc<Self>, block_root: &Hash256, blobs_iter: I)
     where
         I: Iterator<Item = &'a BlobSidecar<T::EthSpec>>,
@@ -3297,11 +3615,14 @@ impl<T: BeaconChainTypes> BeaconChain<T> {
         // Start the Prometheus timer.
         let _full_timer = metrics::start_timer(&metrics::BLOCK_PROCESSING_TIMES);
 
-        // Increment the Prometheus counter for block processing requests.
-        metrics::inc_counter(&metrics::BLOCK_PROCESSING_REQUESTS);
-
         let block_slot = unverified_block.block().slot();
 
+        // Increment the Prometheus counter for block processing requests.
+        metrics::inc_counter_vec(
+            &metrics::BLOCK_PROCESSING_REQUESTS,
+            &[self.fork_label_at_slot(block_slot)],
+        );
+
         // Set observed time if not already set. Usually this should be set by gossip or RPC,
         // but just in case we set it again here (useful for tests).
         if let Some(seen_timestamp) = self.slot_clock.now_duration() {
@@ -3309,6 +3630,7 @@ impl<T: BeaconChainTypes> BeaconChain<T> {
                 block_root,
                 block_slot,
                 seen_timestamp,
+                block_source,
                 None,
                 None,
             );
@@ -3363,7 +3685,10 @@ impl<T: BeaconChainTypes> BeaconChain<T> {
                 );
 
                 // Increment the Prometheus counter for block processing successes.
-                metrics::inc_counter(&metrics::BLOCK_PROCESSING_SUCCESSES);
+                metrics::inc_counter_vec(
+                    &metrics::BLOCK_PROCESSING_SUCCESSES,
+                    &[self.fork_label_at_slot(block_slot)],
+                );
 
                 Ok(status)
             }
@@ -3992,10 +4317,7 @@ impl<T: BeaconChainTypes> BeaconChain<T> {
         let parent_root = block.parent_root();
         let slot = block.slot();
 
-        let current_eth1_finalization_data = Eth1FinalizationData {
-            eth1_data: state.eth1_data().clone(),
-            eth1_deposit_index: state.eth1_deposit_index(),
-        };
+        let current_eth1_finalization_data = Eth1FinalizationData::from_state(&state);
         let current_finalized_checkpoint = state.finalized_checkpoint();
 
         // compute state proofs for light client updates before inserting the state into the
@@ -4017,7 +4339,10 @@ impl<T: BeaconChainTypes> BeaconChain<T> {
 
         metrics::stop_timer(db_write_timer);
 
-        metrics::inc_counter(&metrics::BLOCK_PROCESSING_SUCCESSES);
+        metrics::inc_counter_vec(
+            &metrics::BLOCK_PROCESSING_SUCCESSES,
+            &[self.fork_label_at_slot(slot)],
+        );
 
         // Update the deposit contract cache.
         self.import_block_update_deposit_contract_finalization(
@@ -4361,11 +4686,33 @@ impl<T: BeaconChainTypes> BeaconChain<T> {
         // the cache during sync.
         if block_delay_total < self.slot_clock.slot_duration() * 64 {
             // Store the timestamp of the block being imported into the cache.
-            self.block_times_cache.write().set_time_imported(
-                block_root,
-                current_slot,
-                block_time_imported,
-            );
+            let mut block_times_cache = self.block_times_cache.write();
+            block_times_cache.set_time_imported(block_root, current_slot, block_time_imported);
+
+            let observed_delay = self
+                .slot_clock
+                .start_of(block.slot())
+                .and_then(|slot_start| {
+                    block_times_cache
+                        .get_block_delays(block_root, slot_start)
+                        .observed
+                });
+            let peer_info = block_times_cache.get_peer_info(block_root);
+            let source = block_times_cache.get_source(block_root);
+            drop(block_times_cache);
+
+            self.block_timing_history
+                .write()
+                .record::<T::EthSpec>(BlockTimingRecord {
+                    slot: block.slot(),
+                    block_root,
+                    proposer_index: block.proposer_index(),
+                    source,
+                    peer_id: peer_info.id,
+                    peer_client: peer_info.client,
+                    observed_delay_ms: observed_delay.map(|delay| delay.as_millis() as u64),
+                    payload_source: None,
+                });
         }
 
         if let Some(event_handler) = self.event_handler.as_ref() {
@@ -4591,6 +4938,91 @@ impl<T: BeaconChainTypes> BeaconChain<T> {
         .await
     }
 
+    /// Produce a block at `slot` on top of the block with `parent_root`, irrespective of the
+    /// current head.
+    ///
+    /// This is intended for testing (e.g. of re-org handling) and should not be used to produce
+    /// blocks for regular proposals, since the block is likely to be orphaned if `parent_root` is
+    /// not the head.
+    #[allow(clippy::too_many_arguments)]
+    pub async fn produce_block_on_parent(
+        self: &Arc<Self>,
+        parent_root: Hash256,
+        randao_reveal: Signature,
+        slot: Slot,
+        validator_graffiti: Option<Graffiti>,
+        verification: ProduceBlockVerification,
+        builder_boost_factor: Option<u64>,
+        block_production_version: BlockProductionVersion,
+    ) -> Result<BeaconBlockResponseWrapper<T::EthSpec>, BlockProductionError> {
+        // Load the parent state from disk.
+        let chain = self.clone();
+        let (state, state_root_opt) = self
+            .task_executor
+            .spawn_blocking_handle(
+                move || chain.load_parent_state_for_block_production(parent_root, slot),
+                "load_parent_state_for_block_production",
+            )
+            .ok_or(BlockProductionError::ShuttingDown)?
+            .await
+            .map_err(BlockProductionError::TokioJoin)??;
+
+        self.produce_block_on_state(
+            state,
+            state_root_opt,
+            slot,
+            randao_reveal,
+            validator_graffiti,
+            verification,
+            builder_boost_factor,
+            block_production_version,
+        )
+        .await
+    }
+
+    /// Load the post-state of the block with `parent_root` from the database, advanced as close to
+    /// `slot` as is cheaply possible. This is a long-running process that should not be performed
+    /// in an `async` context.
+    fn load_parent_state_for_block_production(
+        &self,
+        parent_root: Hash256,
+        slot: Slot,
+    ) -> Result<(BeaconState<T::EthSpec>, Option<Hash256>), BlockProductionError> {
+        let parent_block = self
+            .get_blinded_block(&parent_root)
+            .map_err(BlockProductionError::BeaconChain)?
+            .ok_or(BlockProductionError::UnknownParentBlock(parent_root))?;
+
+        if parent_block.slot() >= slot {
+            return Err(BlockProductionError::ParentSlotTooHigh {
+                produce_at_slot: slot,
+                parent_slot: parent_block.slot(),
+            });
+        }
+
+        // Blocks prior to the finalized checkpoint can never become canonical, and their states
+        // may have been pruned from the hot database.
+        let finalized_checkpoint = self.canonical_head.cached_head().finalized_checkpoint();
+        let finalized_slot = finalized_checkpoint
+            .epoch
+            .start_slot(T::EthSpec::slots_per_epoch());
+        if parent_block.slot() < finalized_slot && parent_root != finalized_checkpoint.root {
+            return Err(BlockProductionError::ParentPriorToFinalization {
+                parent_root,
+                parent_slot: parent_block.slot(),
+                finalized_slot,
+            });
+        }
+
+        let (state_root, state) = self
+            .store
+            .get_advanced_hot_state(parent_root, slot, parent_block.state_root())
+            .map_err(BlockProductionError::FailedToLoadState)?
+            .ok_or(BlockProductionError::UnableToProduceAtSlot(slot))?;
+
+        Ok((state, Some(state_root)))
+    }
+
     /// Load a beacon state from the database for block production. This is a long-running process
     /// that should not be performed in an `async` context.
     fn load_state_for_block_production(
@@ -5508,6 +5940,66 @@ impl<T: BeaconChainTypes> BeaconChain<T> {
         })
     }
 
+    /// Returns the payloads considered when this node produced a block at `slot`, if it did so
+    /// within the last day.
+    pub fn block_value_record(&self, slot: Slot) -> Result<Option<BlockValueRecord>, Error> {
+        if let Some(record) = self
+            .execution_layer
+            .as_ref()
+            .and_then(|execution_layer| execution_layer.block_value_record(slot))
+        {
+            return Ok(Some(record));
+        }
+
+        // Records from before a restart are only held in the database.
+        let oldest_retained = self
+            .slot()?
+            .saturating_sub(self.block_value_retention_slots());
+        if slot < oldest_retained {
+            return Ok(None);
+        }
+        self.store
+            .get_block_value_record_bytes(slot)?
+            .map(|bytes| {
+                serde_json::from_slice(&bytes).map_err(|e| {
+                    Error::DBInconsistent(format!("invalid block value record: {:?}", e))
+                })
+            })
+            .transpose()
+    }
+
+    /// Persist the payloads considered when producing a block at `slot` so that they survive a
+    /// restart.
+    fn persist_block_value_record(&self, slot: Slot) {
+        let Some(record) = self
+            .execution_layer
+            .as_ref()
+            .and_then(|execution_layer| execution_layer.block_value_record(slot))
+        else {
+            return;
+        };
+        let oldest_retained = slot.saturating_sub(self.block_value_retention_slots());
+        let result = serde_json::to_vec(&record)
+            .map_err(|e| format!("{:?}", e))
+            .and_then(|bytes| {
+                self.store
+                    .put_block_value_record_bytes(slot, &bytes, oldest_retained)
+                    .map_err(|e| format!("{:?}", e))
+            });
+        if let Err(e) = result {
+            warn!(
+                self.log,
+                "Unable to persist block value record";
+                "slot" => slot,
+                "error" => e,
+            );
+        }
+    }
+
+    fn block_value_retention_slots(&self) -> u64 {
+        BLOCK_VALUE_RETENTION.as_secs() / self.spec.seconds_per_slot.max(1)
+    }
+
     fn complete_partial_beacon_block<Payload: AbstractExecPayload<T::EthSpec>>(
         &self,
         partial_beacon_block: PartialBeaconBlock<T::EthSpec>,
@@ -5535,6 +6027,10 @@ impl<T: BeaconChainTypes> BeaconChain<T> {
             bls_to_execution_changes,
         } = partial_beacon_block;
 
+        if block_contents.is_some() {
+            self.persist_block_value_record(slot);
+        }
+
         let (attester_slashings_base, attester_slashings_electra) =
             attester_slashings.into_iter().fold(
                 (Vec::new(), Vec::new()),
@@ -5842,6 +6338,23 @@ impl<T: BeaconChainTypes> BeaconChain<T> {
 
         drop(blobs_verification_timer);
 
+        // Compute the blob sidecar inclusion proofs now, so that the sidecars can be published
+        // without delay once the block has been signed.
+        if blob_items.is_some() {
+            let block_root = block.tree_hash_root();
+            if let Err(e) = self
+                .blob_inclusion_proof_cache
+                .compute_and_insert(block_root, block.to_ref())
+            {
+                warn!(
+                    self.log,
+                    "Failed to compute blob inclusion proofs";
+                    "error" => ?e,
+                    "slot" => block.slot(),
+                );
+            }
+        }
+
         metrics::inc_counter(&metrics::BLOCK_PRODUCTION_SUCCESSES);
 
         trace!(
@@ -6030,18 +6543,21 @@ impl<T: BeaconChainTypes> BeaconChain<T> {
                     let canonical_fcu_params = cached_head.forkchoice_update_parameters();
                     let fcu_params =
                         chain.overridden_forkchoice_update_params(canonical_fcu_params)?;
+                    let re_org_active = fcu_params.head_root != canonical_fcu_params.head_root;
                     let pre_payload_attributes = chain.get_pre_payload_attributes(
                         prepare_slot,
                         fcu_params.head_root,
                         &cached_head,
                     )?;
-                    Ok::<_, Error>(Some((fcu_params, pre_payload_attributes)))
+                    Ok::<_, Error>(Some((fcu_params, pre_payload_attributes, re_org_active)))
                 },
                 "prepare_beacon_proposer_head_read",
             )
             .await??;
 
-        let Some((forkchoice_update_params, Some(pre_payload_attributes))) = maybe_prep_data else {
+        let Some((forkchoice_update_params, Some(pre_payload_attributes), re_org_active)) =
+            maybe_prep_data
+        else {
             // Appropriate log messages have already been logged above and in
             // `get_pre_payload_attributes`.
             return Ok(None);
@@ -6123,6 +6639,19 @@ impl<T: BeaconChainTypes> BeaconChain<T> {
         // Push a server-sent event (probably to a block builder or relay).
         if let Some(event_handler) = &self.event_handler {
             if event_handler.has_payload_attributes_subscribers() {
+                let fee_recipient_source = execution_layer
+                    .suggested_fee_recipient_source(
+                        proposer,
+                        payload_attributes.suggested_fee_recipient(),
+                    )
+                    .await;
+                let parent_block_hash_source = if forkchoice_update_params.head_hash.is_none() {
+                    ParentBlockHashSource::PreMerge
+                } else if re_org_active {
+                    ParentBlockHashSource::ReOrgParent
+                } else {
+                    ParentBlockHashSource::Head
+                };
                 event_handler.register(EventKind::PayloadAttributes(ForkVersionedResponse {
                     data: SseExtendedPayloadAttributes {
                         proposal_slot: prepare_slot,
@@ -6131,6 +6660,9 @@ impl<T: BeaconChainTypes> BeaconChain<T> {
                         parent_block_number: pre_payload_attributes.parent_block_number,
                         parent_block_hash: forkchoice_update_params.head_hash.unwrap_or_default(),
                         payload_attributes: payload_attributes.into(),
+                        fee_recipient_source: Some(fee_recipient_source),
+                        parent_block_hash_source: Some(parent_block_hash_source),
+                        re_org_active: Some(re_org_active),
                     },
                     metadata: Default::default(),
                     version: Some(self.spec.fork_name_at_slot::<T::EthSpec>(prepare_slot)),
@@ -6593,6 +7125,12 @@ impl<T: BeaconChainTypes> BeaconChain<T> {
             // Run fork choice and signal to any waiting task that it has completed.
             self.recompute_head_at_current_slot().await;
 
+            // Compare the re-org decisions of a proposer at this slot under alternative
+            // parameters, now that fork choice has been updated for the slot.
+            if self.config.re_org_analysis.is_some() {
+                self.analyze_proposer_re_org(slot);
+            }
+
             // Send the notification regardless of fork choice success, this is a "best effort"
             // notification and we don't want block production to hit the timeout in case of error.
             // Use a blocking task to avoid blocking the core executor whilst waiting for locks
@@ -1,8 +1,9 @@
 //! Defines the `BeaconForkChoiceStore` which provides the persistent storage for the `ForkChoice`
 //! struct.
 //!
-//! Additionally, the `BalancesCache` struct is defined; a cache designed to avoid database
-//! reads when fork choice requires the validator balances of the justified state.
+//! Additionally, the `BalancesCache` and `SharedValidatorsCache` structs are defined; caches
+//! designed to avoid database reads when fork choice requires the validator balances of the
+//! justified state.
 
 use crate::{metrics, BeaconSnapshot};
 use derivative::Derivative;
@@ -17,7 +18,7 @@ use store::{Error as StoreError, HotColdDB, ItemStore};
 use superstruct::superstruct;
 use types::{
     AbstractExecPayload, BeaconBlockRef, BeaconState, BeaconStateError, Checkpoint, Epoch, EthSpec,
-    FixedBytesExtended, Hash256, Slot,
+    FixedBytesExtended, Hash256, Slot, Validators,
 };
 
 #[derive(Debug)]
@@ -45,6 +46,26 @@ impl From<ArithError> for Error {
 /// The number of validator balance sets that are cached within `BalancesCache`.
 const MAX_BALANCE_CACHE_SIZE: usize = 4;
 
+/// The number of validator registries that are cached within `SharedValidatorsCache`.
+const MAX_SHARED_VALIDATORS_CACHE_SIZE: usize = 8;
+
+/// Return the root of the block at the first slot of `state.current_epoch`, along with the epoch.
+fn epoch_boundary_block_root<E: EthSpec>(
+    block_root: Hash256,
+    state: &BeaconState<E>,
+) -> Result<(Hash256, Epoch), Error> {
+    let epoch = state.current_epoch();
+    let epoch_boundary_slot = epoch.start_slot(E::slots_per_epoch());
+    let epoch_boundary_root = if epoch_boundary_slot == state.slot() {
+        block_root
+    } else {
+        // This call remains sensible as long as `state.block_roots` is larger than a single
+        // epoch.
+        *state.get_block_root(epoch_boundary_slot)?
+    };
+    Ok((epoch_boundary_root, epoch))
+}
+
 #[superstruct(
     variants(V8),
     variant_attributes(derive(PartialEq, Clone, Debug, Encode, Decode)),
@@ -78,15 +99,7 @@ impl BalancesCache {
         block_root: Hash256,
         state: &BeaconState<E>,
     ) -> Result<(), Error> {
-        let epoch = state.current_epoch();
-        let epoch_boundary_slot = epoch.start_slot(E::slots_per_epoch());
-        let epoch_boundary_root = if epoch_boundary_slot == state.slot() {
-            block_root
-        } else {
-            // This call remains sensible as long as `state.block_roots` is larger than a single
-            // epoch.
-            *state.get_block_root(epoch_boundary_slot)?
-        };
+        let (epoch_boundary_root, epoch) = epoch_boundary_block_root(block_root, state)?;
 
         // Check if there already exists a cache entry for the epoch boundary block of the current
         // epoch. We rely on the invariant that effective balances do not change for the duration
@@ -124,6 +137,59 @@ impl BalancesCache {
     }
 }
 
+/// A cache of the validator registries of recent epoch boundary states, taken from the states of
+/// newly imported blocks.
+///
+/// Registries are persistent lists which share their trees with the head state, so many more of
+/// them can be retained than the eagerly computed balances of the `BalancesCache`. When the
+/// justified checkpoint changes to one that is missing from the `BalancesCache`, its balances can
+/// be computed from a shared registry rather than by reading the justified state from disk whilst
+/// fork choice is locked.
+///
+/// This cache is not persisted.
+#[derive(Debug, Clone)]
+pub struct SharedValidatorsCache<E: EthSpec> {
+    items: Vec<(Hash256, Epoch, Validators<E>)>,
+}
+
+impl<E: EthSpec> Default for SharedValidatorsCache<E> {
+    fn default() -> Self {
+        Self { items: vec![] }
+    }
+}
+
+impl<E: EthSpec> SharedValidatorsCache<E> {
+    /// Retain a handle to the validator registry of `state`, if there isn't one already for the
+    /// block at the first slot of `state.current_epoch`.
+    pub fn process_state(
+        &mut self,
+        block_root: Hash256,
+        state: &BeaconState<E>,
+    ) -> Result<(), Error> {
+        let (epoch_boundary_root, epoch) = epoch_boundary_block_root(block_root, state)?;
+
+        // Effective balances and activity don't change within an epoch, so any state in the
+        // epoch provides the same justified balances.
+        if self.get(epoch_boundary_root, epoch).is_none() {
+            if self.items.len() == MAX_SHARED_VALIDATORS_CACHE_SIZE {
+                self.items.remove(0);
+            }
+            self.items
+                .push((epoch_boundary_root, epoch, state.validators().clone()));
+        }
+
+        Ok(())
+    }
+
+    /// Get the validator registry for the given `block_root`, if any.
+    pub fn get(&self, block_root: Hash256, epoch: Epoch) -> Option<&Validators<E>> {
+        self.items
+            .iter()
+            .find(|(item_root, item_epoch, _)| *item_root == block_root && *item_epoch == epoch)
+            .map(|(_, _, validators)| validators)
+    }
+}
+
 /// Implements `fork_choice::ForkChoiceStore` in order to provide a persistent backing to the
 /// `fork_choice::ForkChoice` struct.
 #[derive(Debug, Derivative)]
@@ -132,6 +198,8 @@ pub struct BeaconForkChoiceStore<E: EthSpec, Hot: ItemStore<E>, Cold: ItemStore<
     #[derivative(PartialEq = "ignore")]
     store: Arc<HotColdDB<E, Hot, Cold>>,
     balances_cache: BalancesCache,
+    #[derivative(PartialEq = "ignore")]
+    shared_validators_cache: SharedValidatorsCache<E>,
     time: Slot,
     finalized_checkpoint: Checkpoint,
     justified_checkpoint: Checkpoint,
@@ -181,6 +249,7 @@ where
         Ok(Self {
             store,
             balances_cache: <_>::default(),
+            shared_validators_cache: <_>::default(),
             time: anchor_state.slot(),
             justified_checkpoint,
             justified_balances,
@@ -219,6 +288,7 @@ where
         Ok(Self {
             store,
             balances_cache: persisted.balances_cache,
+            shared_validators_cache: <_>::default(),
             time: persisted.time,
             finalized_checkpoint: persisted.finalized_checkpoint,
             justified_checkpoint: persisted.justified_checkpoint,
@@ -254,7 +324,9 @@ where
         block_root: Hash256,
         state: &BeaconState<E>,
     ) -> Result<(), Self::Error> {
-        self.balances_cache.process_state(block_root, state)
+        self.balances_cache.process_state(block_root, state)?;
+        self.shared_validators_cache
+            .process_state(block_root, state)
     }
 
     fn justified_checkpoint(&self) -> &Checkpoint {
@@ -295,6 +367,15 @@ where
             // NOTE: could avoid this re-calculation by introducing a `PersistedCacheItem`.
             metrics::inc_counter(&metrics::BALANCES_CACHE_HITS);
             self.justified_balances = JustifiedBalances::from_effective_balances(balances)?;
+        } else if let Some(validators) = self.shared_validators_cache.get(
+            self.justified_checkpoint.root,
+            self.justified_checkpoint.epoch,
+        ) {
+            metrics::inc_counter(&metrics::BALANCES_CACHE_SHARED_VALIDATORS_HITS);
+            self.justified_balances = JustifiedBalances::from_validators::<E>(
+                validators,
+                self.justified_checkpoint.epoch,
+            )?;
         } else {
             metrics::inc_counter(&metrics::BALANCES_CACHE_MISSES);
             let justified_block = self
@@ -361,3 +442,60 @@ pub struct PersistedForkChoiceStore {
     pub proposer_boost_root: Hash256,
     pub equivocating_indices: BTreeSet<u64>,
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use types::{ChainSpec, Eth1Data, MinimalEthSpec, Validator};
+
+    type E = MinimalEthSpec;
+
+    fn state_at_epoch(epoch: u64, num_validators: u64) -> BeaconState<E> {
+        let spec = ChainSpec::minimal();
+        let mut state = BeaconState::new(0, Eth1Data::default(), &spec);
+        *state.slot_mut() = Epoch::new(epoch).start_slot(E::slots_per_epoch());
+        for _ in 0..num_validators {
+            state
+                .validators_mut()
+                .push(Validator {
+                    effective_balance: spec.max_effective_balance,
+                    activation_epoch: Epoch::new(0),
+                    exit_epoch: spec.far_future_epoch,
+                    ..Validator::default()
+                })
+                .unwrap();
+        }
+        state
+    }
+
+    #[test]
+    fn shared_validators_cache_lookup_and_eviction() {
+        let mut cache = SharedValidatorsCache::<E>::default();
+        let num_items = MAX_SHARED_VALIDATORS_CACHE_SIZE as u64 + 1;
+        for epoch in 0..num_items {
+            let state = state_at_epoch(epoch, epoch + 1);
+            cache
+                .process_state(Hash256::from_low_u64_be(epoch), &state)
+                .unwrap();
+        }
+
+        // The oldest registry has been evicted.
+        assert!(cache
+            .get(Hash256::from_low_u64_be(0), Epoch::new(0))
+            .is_none());
+        // The epoch must match as well as the block root.
+        assert!(cache
+            .get(Hash256::from_low_u64_be(1), Epoch::new(2))
+            .is_none());
+
+        let epoch = Epoch::new(num_items - 1);
+        let validators = cache
+            .get(Hash256::from_low_u64_be(num_items - 1), epoch)
+            .unwrap();
+        let state = state_at_epoch(epoch.as_u64(), num_items);
+        assert_eq!(
+            JustifiedBalances::from_validators::<E>(validators, epoch).unwrap(),
+            JustifiedBalances::from_justified_state(&state).unwrap()
+        );
+    }
+}
@@ -0,0 +1,58 @@
+//! A cache of the blob sidecar inclusion proofs of locally produced blocks.
+//!
+//! The inclusion proofs only depend on the block body, so they are computed when the block is
+//! produced rather than when the signed block is published. This allows the blob sidecars to be
+//! built and gossiped as soon as the signed block is returned by the validator client.
+use crate::metrics;
+use lru::LruCache;
+use parking_lot::Mutex;
+use std::num::NonZeroUsize;
+use std::sync::Arc;
+use types::non_zero_usize::new_non_zero_usize;
+use types::{AbstractExecPayload, BeaconBlockRef, BeaconStateError, EthSpec, FixedVector, Hash256};
+
+/// The number of produced blocks for which inclusion proofs are retained.
+///
+/// Blocks may be produced several times for the same slot (e.g. with and without a builder), so
+/// this is larger than one.
+const CACHE_SIZE: NonZeroUsize = new_non_zero_usize(8);
+
+pub type InclusionProofs<E> =
+    Arc<Vec<FixedVector<Hash256, <E as EthSpec>::KzgCommitmentInclusionProofDepth>>>;
+
+pub struct BlobInclusionProofCache<E: EthSpec> {
+    proofs: Mutex<LruCache<Hash256, InclusionProofs<E>>>,
+}
+
+impl<E: EthSpec> Default for BlobInclusionProofCache<E> {
+    fn default() -> Self {
+        Self {
+            proofs: Mutex::new(LruCache::new(CACHE_SIZE)),
+        }
+    }
+}
+
+impl<E: EthSpec> BlobInclusionProofCache<E> {
+    /// Compute the inclusion proofs of all blobs in `block` and store them against `block_root`.
+    pub fn compute_and_insert<Payload: AbstractExecPayload<E>>(
+        &self,
+        block_root: Hash256,
+        block: BeaconBlockRef<E, Payload>,
+    ) -> Result<(), BeaconStateError> {
+        let _timer = metrics::start_timer(&metrics::BLOB_SIDECAR_INCLUSION_PROOF_PRECOMPUTATION);
+        let proofs = block.body().kzg_commitment_merkle_proofs()?;
+        self.proofs.lock().put(block_root, Arc::new(proofs));
+        Ok(())
+    }
+
+    /// Return the inclusion proofs of the block with `block_root`, if it was produced locally.
+    pub fn get(&self, block_root: &Hash256) -> Option<InclusionProofs<E>> {
+        let proofs = self.proofs.lock().get(block_root).cloned();
+        if proofs.is_some() {
+            metrics::inc_counter(&metrics::BLOB_SIDECAR_INCLUSION_PROOF_CACHE_HITS);
+        } else {
+            metrics::inc_counter(&metrics::BLOB_SIDECAR_INCLUSION_PROOF_CACHE_MISSES);
+        }
+        proofs
+    }
+}
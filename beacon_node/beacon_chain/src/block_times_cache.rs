@@ -9,15 +9,20 @@
 //! - We were too slow to import it.
 //! - We were too slow to set it as head.
 
+use eth2::lighthouse::BlockTimingSource;
 use eth2::types::{Hash256, Slot};
 use std::collections::HashMap;
 use std::time::Duration;
+use types::BlockImportSource;
 
 type BlockRoot = Hash256;
 
 #[derive(Clone, Default)]
 pub struct Timestamps {
     pub observed: Option<Duration>,
+    /// The time the block was first received via gossip, which unlike `observed` excludes blocks
+    /// received via RPC or produced locally.
+    pub gossip_observed: Option<Duration>,
     pub all_blobs_observed: Option<Duration>,
     pub consensus_verified: Option<Duration>,
     pub started_execution: Option<Duration>,
@@ -109,6 +114,10 @@ pub struct BlockTimesCacheValue {
     pub slot: Slot,
     pub timestamps: Timestamps,
     pub peer_info: BlockPeerInfo,
+    /// How the block was received when it was first observed.
+    pub source: Option<BlockImportSource>,
+    /// Whether the block's payload was revealed by a builder relay.
+    pub revealed_by_builder_relay: bool,
 }
 
 impl BlockTimesCacheValue {
@@ -117,6 +126,8 @@ impl BlockTimesCacheValue {
             slot,
             timestamps: Default::default(),
             peer_info: Default::default(),
+            source: None,
+            revealed_by_builder_relay: false,
         }
     }
 }
@@ -135,6 +146,7 @@ impl BlockTimesCache {
         block_root: BlockRoot,
         slot: Slot,
         timestamp: Duration,
+        source: BlockImportSource,
         peer_id: Option<String>,
         peer_client: Option<String>,
     ) {
@@ -149,6 +161,7 @@ impl BlockTimesCache {
             _ => {
                 // No existing timestamp, or new timestamp is earlier.
                 block_times.timestamps.observed = Some(timestamp);
+                block_times.source = Some(source);
                 block_times.peer_info = BlockPeerInfo {
                     id: peer_id,
                     client: peer_client,
@@ -157,6 +170,30 @@ impl BlockTimesCache {
         }
     }
 
+    /// Record that the payload of the blinded block `block_root` was revealed by a builder relay.
+    pub fn set_revealed_by_builder_relay(&mut self, block_root: BlockRoot, slot: Slot) {
+        self.cache
+            .entry(block_root)
+            .or_insert_with(|| BlockTimesCacheValue::new(slot))
+            .revealed_by_builder_relay = true;
+    }
+
+    /// Set the gossip observation time for `block_root` to `timestamp` if `timestamp` is less than
+    /// any previous timestamp at which this block was received via gossip.
+    pub fn set_time_gossip_observed(
+        &mut self,
+        block_root: BlockRoot,
+        slot: Slot,
+        timestamp: Duration,
+    ) {
+        self.set_time_if_less(
+            block_root,
+            slot,
+            |timestamps| &mut timestamps.gossip_observed,
+            timestamp,
+        )
+    }
+
     pub fn set_time_blob_observed(
         &mut self,
         block_root: BlockRoot,
@@ -276,6 +313,18 @@ impl BlockTimesCache {
         }
     }
 
+    /// How the block was received when it was first observed, distinguishing blinded blocks
+    /// published to the HTTP API whose payload was revealed by a builder relay.
+    pub fn get_source(&self, block_root: BlockRoot) -> Option<BlockTimingSource> {
+        let block_info = self.cache.get(&block_root)?;
+        block_info.source.map(|source| match source {
+            BlockImportSource::HttpApi if block_info.revealed_by_builder_relay => {
+                BlockTimingSource::BuilderRelay
+            }
+            source => source.into(),
+        })
+    }
+
     pub fn get_peer_info(&self, block_root: BlockRoot) -> BlockPeerInfo {
         if let Some(block_info) = self.cache.get(&block_root) {
             block_info.peer_info.clone()
@@ -319,13 +368,24 @@ mod test {
             client: Some("prysm".to_string()),
         };
 
-        cache.set_time_observed(block_root, slot, ts1, None, None);
+        cache.set_time_observed(
+            block_root,
+            slot,
+            ts1,
+            BlockImportSource::HttpApi,
+            None,
+            None,
+        );
 
         assert_eq!(
             cache.get_block_delays(block_root, slot_start_time).observed,
             Some(ts1)
         );
         assert_eq!(cache.get_peer_info(block_root), BlockPeerInfo::default());
+        assert_eq!(
+            cache.get_source(block_root),
+            Some(BlockTimingSource::HttpApi)
+        );
 
         // Second observation with higher timestamp should not override anything, even though it has
         // superior peer info.
@@ -333,6 +393,7 @@ mod test {
             block_root,
             slot,
             ts2,
+            BlockImportSource::Gossip,
             peer_info2.id.clone(),
             peer_info2.client.clone(),
         );
@@ -348,6 +409,7 @@ mod test {
             block_root,
             slot,
             ts3,
+            BlockImportSource::Gossip,
             peer_info3.id.clone(),
             peer_info3.client.clone(),
         );
@@ -357,5 +419,53 @@ mod test {
             Some(ts3)
         );
         assert_eq!(cache.get_peer_info(block_root), peer_info3);
+        assert_eq!(
+            cache.get_source(block_root),
+            Some(BlockTimingSource::Gossip)
+        );
+    }
+
+    #[test]
+    fn builder_relay_source() {
+        let mut cache = BlockTimesCache::default();
+        let slot = Slot::new(100);
+        let local_root = Hash256::repeat_byte(1);
+        let builder_root = Hash256::repeat_byte(2);
+
+        for block_root in [local_root, builder_root] {
+            cache.set_time_observed(
+                block_root,
+                slot,
+                Duration::from_secs(1),
+                BlockImportSource::HttpApi,
+                None,
+                None,
+            );
+        }
+        cache.set_revealed_by_builder_relay(builder_root, slot);
+
+        assert_eq!(
+            cache.get_source(local_root),
+            Some(BlockTimingSource::HttpApi)
+        );
+        assert_eq!(
+            cache.get_source(builder_root),
+            Some(BlockTimingSource::BuilderRelay)
+        );
+
+        // A block which reached us over gossip before the relay returned it was first seen via
+        // gossip.
+        cache.set_time_observed(
+            builder_root,
+            slot,
+            Duration::from_secs(0),
+            BlockImportSource::Gossip,
+            None,
+            None,
+        );
+        assert_eq!(
+            cache.get_source(builder_root),
+            Some(BlockTimingSource::Gossip)
+        );
     }
 }
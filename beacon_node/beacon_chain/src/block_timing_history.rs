@@ -0,0 +1,174 @@
+//! A rolling history of when and how imported blocks were first seen, so that operators can
+//! compare the propagation of blocks from different proposers, for example to audit whether their
+//! own builder blocks arrive later than locally built ones.
+use crate::{BeaconChain, BeaconChainError, BeaconChainTypes, WhenSlotSkipped};
+use eth2::lighthouse::{BlockTimingRecord, BlockTimingSource, ProposerBlockTimings};
+use std::collections::{BTreeMap, VecDeque};
+use types::EthSpec;
+
+/// The number of epochs of block timings which are retained, roughly one day on mainnet.
+pub const BLOCK_TIMING_HISTORY_EPOCHS: u64 = 225;
+
+/// The timings of the blocks imported in the last `BLOCK_TIMING_HISTORY_EPOCHS` epochs, in the
+/// order they were imported.
+#[derive(Debug, Default)]
+pub struct BlockTimingHistory {
+    records: VecDeque<BlockTimingRecord>,
+}
+
+impl BlockTimingHistory {
+    /// Record the timing of an imported block, pruning records which are older than
+    /// `BLOCK_TIMING_HISTORY_EPOCHS`.
+    pub fn record<E: EthSpec>(&mut self, record: BlockTimingRecord) {
+        let oldest_slot = record
+            .slot
+            .saturating_sub(BLOCK_TIMING_HISTORY_EPOCHS * E::slots_per_epoch());
+        while self
+            .records
+            .front()
+            .is_some_and(|oldest| oldest.slot < oldest_slot)
+        {
+            self.records.pop_front();
+        }
+        self.records.push_back(record);
+    }
+
+    /// Group the records for which `is_canonical` returns `true` by proposer, in ascending order
+    /// of proposer index.
+    pub fn summarize<F>(
+        &self,
+        proposer_index: Option<u64>,
+        mut is_canonical: F,
+    ) -> Result<Vec<ProposerBlockTimings>, BeaconChainError>
+    where
+        F: FnMut(&BlockTimingRecord) -> Result<bool, BeaconChainError>,
+    {
+        let mut proposers = BTreeMap::<u64, Vec<BlockTimingRecord>>::new();
+        for record in &self.records {
+            if proposer_index.is_some_and(|index| index != record.proposer_index)
+                || !is_canonical(record)?
+            {
+                continue;
+            }
+            proposers
+                .entry(record.proposer_index)
+                .or_default()
+                .push(record.clone());
+        }
+
+        Ok(proposers
+            .into_iter()
+            .map(|(proposer_index, mut blocks)| {
+                blocks.sort_by_key(|block| block.slot);
+                let delays = blocks
+                    .iter()
+                    .filter_map(|block| block.observed_delay_ms)
+                    .collect::<Vec<_>>();
+                ProposerBlockTimings {
+                    proposer_index,
+                    mean_observed_delay_ms: (!delays.is_empty())
+                        .then(|| delays.iter().sum::<u64>() / delays.len() as u64),
+                    max_observed_delay_ms: delays.iter().max().copied(),
+                    blocks,
+                }
+            })
+            .collect())
+    }
+}
+
+impl<T: BeaconChainTypes> BeaconChain<T> {
+    /// Report the timings of the canonical blocks imported in the last
+    /// `BLOCK_TIMING_HISTORY_EPOCHS` epochs, grouped by proposer.
+    ///
+    /// Blocks which were published to this node's HTTP API and produced by this node are annotated
+    /// with the source of their payload.
+    pub fn block_timings_report(
+        &self,
+        proposer_index: Option<u64>,
+    ) -> Result<Vec<ProposerBlockTimings>, BeaconChainError> {
+        let mut report = self
+            .block_timing_history
+            .read()
+            .summarize(proposer_index, |record| {
+                Ok(self.block_root_at_slot(record.slot, WhenSlotSkipped::None)?
+                    == Some(record.block_root))
+            })?;
+
+        if let Some(execution_layer) = &self.execution_layer {
+            for block in report
+                .iter_mut()
+                .flat_map(|proposer| &mut proposer.blocks)
+                .filter(|block| {
+                    matches!(
+                        block.source,
+                        Some(BlockTimingSource::HttpApi | BlockTimingSource::BuilderRelay)
+                    )
+                })
+            {
+                block.payload_source = execution_layer
+                    .block_value_record(block.slot)
+                    .map(|produced| produced.choice);
+            }
+        }
+        Ok(report)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use types::{Hash256, MainnetEthSpec, Slot};
+
+    type E = MainnetEthSpec;
+
+    fn record(slot: u64, proposer_index: u64, observed_delay_ms: u64) -> BlockTimingRecord {
+        BlockTimingRecord {
+            slot: Slot::new(slot),
+            block_root: Hash256::repeat_byte(slot as u8),
+            proposer_index,
+            source: Some(BlockTimingSource::Gossip),
+            peer_id: None,
+            peer_client: None,
+            observed_delay_ms: Some(observed_delay_ms),
+            payload_source: None,
+        }
+    }
+
+    #[test]
+    fn summarize_by_proposer() {
+        let mut history = BlockTimingHistory::default();
+        history.record::<E>(record(1, 7, 1000));
+        history.record::<E>(record(2, 3, 500));
+        history.record::<E>(record(3, 7, 3000));
+        // A block which was re-orged out.
+        history.record::<E>(record(4, 7, 100));
+
+        let report = history
+            .summarize(None, |record| Ok(record.slot != 4))
+            .unwrap();
+        assert_eq!(report.len(), 2);
+        assert_eq!(report[0].proposer_index, 3);
+        assert_eq!(report[0].mean_observed_delay_ms, Some(500));
+        assert_eq!(report[1].proposer_index, 7);
+        assert_eq!(report[1].blocks.len(), 2);
+        assert_eq!(report[1].mean_observed_delay_ms, Some(2000));
+        assert_eq!(report[1].max_observed_delay_ms, Some(3000));
+
+        let report = history.summarize(Some(3), |_| Ok(true)).unwrap();
+        assert_eq!(report.len(), 1);
+        assert_eq!(report[0].proposer_index, 3);
+    }
+
+    #[test]
+    fn prune_old_records() {
+        let mut history = BlockTimingHistory::default();
+        history.record::<E>(record(1, 0, 0));
+        history.record::<E>(record(2, 0, 0));
+
+        let retained_slots = BLOCK_TIMING_HISTORY_EPOCHS * E::slots_per_epoch();
+        history.record::<E>(record(2 + retained_slots, 0, 0));
+        let report = history.summarize(None, |_| Ok(true)).unwrap();
+        assert_eq!(report[0].blocks.len(), 2);
+        assert_eq!(report[0].blocks[0].slot, Slot::new(2));
+    }
+}
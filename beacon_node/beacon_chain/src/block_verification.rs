@@ -586,22 +586,24 @@ pub(crate) fn process_block_slash_info<T: BeaconChainTypes, TErr: BlockBlobError
     }
 }
 
-/// Verify all signatures (except deposit signatures) on all blocks in the `chain_segment`. If all
-/// signatures are valid, the `chain_segment` is mapped to a `Vec<SignatureVerifiedBlock>` that can
-/// later be transformed into a `ExecutionPendingBlock` without re-checking the signatures. If any
-/// signature in the block is invalid, an `Err` is returned (it is not possible to known _which_
-/// signature was invalid).
+/// Loads the parent of the first block of `chain_segment` and a state from which the committees
+/// of every block in the segment can be computed.
+///
+/// All blocks in `chain_segment` must be from the same epoch, so that the returned state can be
+/// used to verify the segment in several batches with `signature_verify_chain_segment_batch`.
 ///
 /// ## Errors
 ///
 /// The given `chain_segment` must contain only blocks from the same epoch, otherwise an error
 /// will be returned.
-pub fn signature_verify_chain_segment<T: BeaconChainTypes>(
-    mut chain_segment: Vec<(Hash256, RpcBlock<T::EthSpec>)>,
+pub fn load_chain_segment_pre_state<T: BeaconChainTypes>(
+    chain_segment: &mut Vec<(Hash256, RpcBlock<T::EthSpec>)>,
     chain: &BeaconChain<T>,
-) -> Result<Vec<SignatureVerifiedBlock<T>>, BlockError> {
+) -> Result<(PreProcessingSnapshot<T::EthSpec>, BeaconState<T::EthSpec>), BlockError> {
     if chain_segment.is_empty() {
-        return Ok(vec![]);
+        return Err(BlockError::BeaconChainError(
+            BeaconChainError::EmptyChainSegment,
+        ));
     }
 
     let (first_root, first_block) = chain_segment.remove(0);
@@ -619,7 +621,30 @@ pub fn signature_verify_chain_segment<T: BeaconChainTypes>(
         parent.beacon_state_root,
         highest_slot,
         &chain.spec,
-    )?;
+    )?
+    .into_owned();
+
+    Ok((parent, state))
+}
+
+/// Verify all signatures (except deposit signatures) on all blocks in the `chain_segment`. If all
+/// signatures are valid, the `chain_segment` is mapped to a `Vec<SignatureVerifiedBlock>` that can
+/// later be transformed into a `ExecutionPendingBlock` without re-checking the signatures. If any
+/// signature in the block is invalid, an `Err` is returned (it is not possible to known _which_
+/// signature was invalid).
+///
+/// The `state` must be obtained from `load_chain_segment_pre_state` for a segment containing
+/// `chain_segment`. The `parent` should only be provided for the batch which contains the first
+/// block of that segment, the blocks of later batches load their parent when they are imported.
+pub fn signature_verify_chain_segment_batch<T: BeaconChainTypes>(
+    chain_segment: Vec<(Hash256, RpcBlock<T::EthSpec>)>,
+    parent: Option<PreProcessingSnapshot<T::EthSpec>>,
+    state: &BeaconState<T::EthSpec>,
+    chain: &BeaconChain<T>,
+) -> Result<Vec<SignatureVerifiedBlock<T>>, BlockError> {
+    if chain_segment.is_empty() {
+        return Ok(vec![]);
+    }
 
     // unzip chain segment and verify kzg in bulk
     let (roots, blocks): (Vec<_>, Vec<_>) = chain_segment.into_iter().unzip();
@@ -644,7 +669,7 @@ pub fn signature_verify_chain_segment<T: BeaconChainTypes>(
 
     // verify signatures
     let pubkey_cache = get_validator_pubkey_cache(chain)?;
-    let mut signature_verifier = get_signature_verifier(&state, &pubkey_cache, &chain.spec);
+    let mut signature_verifier = get_signature_verifier(state, &pubkey_cache, &chain.spec);
     for svb in &mut signature_verified_blocks {
         signature_verifier
             .include_all_signatures(svb.block.as_block(), &mut svb.consensus_context)?;
@@ -657,7 +682,7 @@ pub fn signature_verify_chain_segment<T: BeaconChainTypes>(
     drop(pubkey_cache);
 
     if let Some(signature_verified_block) = signature_verified_blocks.first_mut() {
-        signature_verified_block.parent = Some(parent);
+        signature_verified_block.parent = parent;
     }
 
     Ok(signature_verified_blocks)
@@ -1441,10 +1466,7 @@ impl<T: BeaconChainTypes> ExecutionPendingBlock<T> {
             .into());
         }
 
-        let parent_eth1_finalization_data = Eth1FinalizationData {
-            eth1_data: state.eth1_data().clone(),
-            eth1_deposit_index: state.eth1_deposit_index(),
-        };
+        let parent_eth1_finalization_data = Eth1FinalizationData::from_state(&state);
 
         // Transition the parent state to the block slot.
         //
@@ -378,6 +378,7 @@ impl<E: EthSpec> BlockImportData<E> {
             parent_eth1_finalization_data: Eth1FinalizationData {
                 eth1_data: <_>::default(),
                 eth1_deposit_index: 0,
+                deposit_requests_start_index: u64::MAX,
             },
             confirmed_state_roots: vec![],
             consensus_context: ConsensusContext::new(Slot::new(0)),
@@ -30,7 +30,7 @@ use operation_pool::{OperationPool, PersistedOperationPool};
 use parking_lot::{Mutex, RwLock};
 use proto_array::{DisallowedReOrgOffsets, ReOrgThreshold};
 use slasher::Slasher;
-use slog::{crit, debug, error, info, o, Logger};
+use slog::{crit, debug, error, info, o, warn, Logger};
 use slot_clock::{SlotClock, TestingSlotClock};
 use state_processing::{per_slot_processing, AllCaches};
 use std::marker::PhantomData;
@@ -723,6 +723,16 @@ where
         let genesis_state_root = self
             .genesis_state_root
             .ok_or("Cannot build without a genesis state root")?;
+        if let Err(e) = state_processing::set_epoch_processing_threads(
+            self.chain_config.epoch_processing_threads,
+        ) {
+            warn!(log, "Unable to configure epoch processing threads"; "error" => e);
+        }
+        if let Err(e) = crate::kzg_utils::set_kzg_verification_threads(
+            self.chain_config.kzg_verification_threads,
+        ) {
+            warn!(log, "Unable to configure KZG verification threads"; "error" => e);
+        }
         let validator_monitor_config = self.validator_monitor_config.unwrap_or_default();
         let head_tracker = Arc::new(self.head_tracker.unwrap_or_default());
         let beacon_proposer_cache: Arc<Mutex<BeaconProposerCache>> = <_>::default();
@@ -961,13 +971,22 @@ where
             eth1_finalization_cache: RwLock::new(Eth1FinalizationCache::new(log.clone())),
             beacon_proposer_cache,
             block_times_cache: <_>::default(),
+            block_timing_history: <_>::default(),
+            head_history: <_>::default(),
             pre_finalization_block_cache: <_>::default(),
+            aggregate_signature_cache: <_>::default(),
+            blob_inclusion_proof_cache: <_>::default(),
             validator_pubkey_cache: RwLock::new(validator_pubkey_cache),
             attester_cache: <_>::default(),
             early_attester_cache: <_>::default(),
+            attestation_data_cache: <_>::default(),
             reqresp_pre_import_cache: <_>::default(),
             light_client_server_cache: LightClientServerCache::new(),
+            state_request_coalescer: <_>::default(),
             light_client_server_tx: self.light_client_server_tx,
+            attestation_sources: <_>::default(),
+            storage_watchdog: <_>::default(),
+            publish_safety: <_>::default(),
             shutdown_sender: self
                 .shutdown_sender
                 .ok_or("Cannot build without a shutdown sender.")?,
@@ -1036,6 +1055,39 @@ where
             "head_slot" => format!("{}", head.beacon_block.slot()),
         );
 
+        // Warm the light client server cache from a snapshot taken before a restart. The cache
+        // fills as blocks are imported, so a snapshot which cannot be loaded is not fatal.
+        if let Some(path) = beacon_chain.config.light_client_store_snapshot.as_ref() {
+            let import = std::fs::read(path)
+                .map_err(|e| format!("unable to read file: {:?}", e))
+                .and_then(|bytes| {
+                    serde_json::from_slice(&bytes)
+                        .map_err(|e| format!("unable to parse snapshot: {:?}", e))
+                })
+                .and_then(|snapshot| {
+                    beacon_chain
+                        .import_light_client_store_snapshot(snapshot)
+                        .map_err(|e| format!("{:?}", e))
+                });
+            match import {
+                Ok(import) => info!(
+                    log,
+                    "Imported light client store snapshot";
+                    "path" => %path.display(),
+                    "best_updates" => import.best_updates_imported,
+                    "finality_update" => import.finality_update_imported,
+                    "optimistic_update" => import.optimistic_update_imported,
+                    "rejected" => import.rejected,
+                ),
+                Err(e) => warn!(
+                    log,
+                    "Unable to import light client store snapshot";
+                    "path" => %path.display(),
+                    "error" => e,
+                ),
+            }
+        }
+
         // Check for states to reconstruct (in the background).
         if beacon_chain.config.reconstruct_historic_states
             && beacon_chain.store.get_oldest_block_slot() == 0
@@ -1057,6 +1109,19 @@ where
             );
         }
 
+        // Summarise epochs which were finalized before epoch summaries were written, in the
+        // background.
+        let store = beacon_chain.store.clone();
+        let summary_log = log.clone();
+        beacon_chain.task_executor.spawn_blocking(
+            move || {
+                if let Err(e) = store.backfill_epoch_summaries() {
+                    error!(summary_log, "Error backfilling epoch summaries"; "error" => ?e);
+                }
+            },
+            "backfill_epoch_summaries",
+        );
+
         // Prune blobs older than the blob data availability boundary in the background.
         if let Some(data_availability_boundary) = beacon_chain.data_availability_boundary() {
             beacon_chain
@@ -37,6 +37,7 @@ use crate::{
     beacon_chain::{BeaconForkChoice, BeaconStore, OverrideForkchoiceUpdate, FORK_CHOICE_DB_KEY},
     block_times_cache::BlockTimesCache,
     events::ServerSentEventHandler,
+    head_history::HeadChange,
     metrics,
     validator_monitor::{get_slot_delay_ms, timestamp_now},
     BeaconChain, BeaconChainError as Error, BeaconChainTypes, BeaconSnapshot,
@@ -794,6 +795,15 @@ impl<T: BeaconChainTypes> BeaconChain<T> {
             &self.log,
         );
 
+        self.head_history.write().record::<T::EthSpec>(HeadChange {
+            block_root: new_snapshot.beacon_block_root,
+            slot: new_snapshot.beacon_block.slot(),
+            parent_root: new_snapshot.beacon_block.parent_root(),
+            previous_block_root: old_snapshot.beacon_block_root,
+            previous_slot: old_snapshot.beacon_block.slot(),
+            reorg_depth: reorg_distance.map(|depth| depth.as_u64()),
+        });
+
         // Determine if the new head is in a later epoch to the previous head.
         let is_epoch_transition = old_snapshot
             .beacon_block
@@ -847,6 +857,21 @@ impl<T: BeaconChainTypes> BeaconChain<T> {
             &self.log,
         );
 
+        // Track the exits, BLS to execution changes and slashings omitted by the new head.
+        let proposer_index = new_snapshot.beacon_block.message().proposer_index();
+        let is_local = self
+            .validator_monitor
+            .read()
+            .get_monitored_validator(proposer_index)
+            .is_some();
+        self.op_pool.observe_block_for_censorship(
+            new_snapshot.beacon_block_root,
+            &new_snapshot.beacon_block,
+            &new_snapshot.beacon_state,
+            is_local,
+            &self.spec,
+        );
+
         if is_epoch_transition || reorg_distance.is_some() {
             self.persist_head_and_fork_choice()?;
             self.op_pool.prune_attestations(self.epoch()?);
@@ -903,6 +928,46 @@ impl<T: BeaconChainTypes> BeaconChain<T> {
         Ok(())
     }
 
+    /// Manually trigger a migration of finalized states from the hot database to the freezer.
+    ///
+    /// This is intended for operators recovering from a stuck or failed migration and bypasses
+    /// the `epochs_per_migration` limit. The migration itself runs on the background migrator.
+    ///
+    /// Returns `false` if the split point is already at the finalized checkpoint.
+    pub fn manually_migrate_database(&self) -> Result<bool, Error> {
+        let cached_head = self.canonical_head.cached_head();
+        let finalized_checkpoint = cached_head.finalized_checkpoint();
+        let finalized_slot = finalized_checkpoint
+            .epoch
+            .start_slot(T::EthSpec::slots_per_epoch());
+
+        if finalized_slot <= self.store.get_split_slot() {
+            return Ok(false);
+        }
+
+        let finalized_state_root = process_results(
+            StateRootsIterator::new(&self.store, &cached_head.snapshot.beacon_state),
+            |mut iter| {
+                iter.find_map(|(state_root, slot)| {
+                    if slot == finalized_slot {
+                        Some(state_root)
+                    } else {
+                        None
+                    }
+                })
+            },
+        )?
+        .ok_or(Error::MissingFinalizedStateRoot(finalized_slot))?;
+
+        self.store_migrator.process_manual_finalization(
+            finalized_state_root.into(),
+            finalized_checkpoint,
+            self.head_tracker.clone(),
+        )?;
+
+        Ok(true)
+    }
+
     /// Perform updates to caches and other components after the finalized checkpoint has been
     /// changed.
     ///
@@ -1214,6 +1279,10 @@ fn detect_reorg<E: EthSpec>(
             &metrics::FORK_CHOICE_REORG_DISTANCE,
             reorg_distance.as_u64() as i64,
         );
+        metrics::observe(
+            &metrics::FORK_CHOICE_REORG_DEPTH,
+            reorg_distance.as_u64() as f64,
+        );
         info!(
             log,
             "Beacon chain re-org";
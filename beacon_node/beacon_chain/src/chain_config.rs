@@ -1,6 +1,9 @@
-pub use proto_array::{DisallowedReOrgOffsets, ReOrgThreshold};
+pub use crate::storage_watchdog::StorageThresholds;
+pub use proto_array::{DisallowedReOrgOffsets, ReOrgSimulationParams, ReOrgThreshold};
 use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
 use std::time::Duration;
+use strum::{AsRefStr, EnumString};
 use types::{Checkpoint, Epoch};
 
 pub const DEFAULT_RE_ORG_HEAD_THRESHOLD: ReOrgThreshold = ReOrgThreshold(20);
@@ -8,6 +11,8 @@ pub const DEFAULT_RE_ORG_PARENT_THRESHOLD: ReOrgThreshold = ReOrgThreshold(160);
 pub const DEFAULT_RE_ORG_MAX_EPOCHS_SINCE_FINALIZATION: Epoch = Epoch::new(2);
 /// Default to 1/12th of the slot, which is 1 second on mainnet.
 pub const DEFAULT_RE_ORG_CUTOFF_DENOMINATOR: u32 = 12;
+/// Default maximum number of blocks orphaned by re-orgs simulated for analysis.
+pub const DEFAULT_RE_ORG_ANALYSIS_MAX_DEPTH: u64 = 2;
 pub const DEFAULT_FORK_CHOICE_BEFORE_PROPOSAL_TIMEOUT: u64 = 250;
 
 /// Default fraction of a slot lookahead for payload preparation (12/3 = 4 seconds on mainnet).
@@ -16,6 +21,37 @@ pub const DEFAULT_PREPARE_PAYLOAD_LOOKAHEAD_FACTOR: u32 = 3;
 /// Fraction of a slot lookahead for fork choice in the state advance timer (500ms on mainnet).
 pub const FORK_CHOICE_LOOKAHEAD_FACTOR: u32 = 24;
 
+/// Default time to wait for blobs on gossip before fetching them from the EL, when gossip is
+/// preferred.
+pub const DEFAULT_BLOB_GOSSIP_WAIT: Duration = Duration::from_millis(500);
+
+/// Default number of threads used to verify batches of blobs and data columns.
+pub const DEFAULT_KZG_VERIFICATION_THREADS: usize = 4;
+
+/// Default maximum number of blocks from a chain segment which are signature-verified together.
+/// A quarter of an epoch on mainnet, so that verification and import overlap within each epoch.
+pub const DEFAULT_MAX_CHAIN_SEGMENT_LENGTH: usize = 8;
+
+/// A way of acquiring the blobs of a block.
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Deserialize, Serialize, EnumString, AsRefStr)]
+#[serde(rename_all = "snake_case")]
+#[strum(serialize_all = "snake_case")]
+pub enum BlobFetchStrategy {
+    /// Wait for the blobs to arrive on gossip.
+    Gossip,
+    /// Fetch the blobs from the EL's blob pool with `engine_getBlobs`.
+    EngineGetBlobs,
+    /// Request the blobs from peers with `BlobsByRoot`.
+    RpcByRoot,
+}
+
+impl BlobFetchStrategy {
+    /// The default order, which fetches blobs from the EL as soon as the block is seen.
+    pub fn default_order() -> Vec<Self> {
+        vec![Self::EngineGetBlobs, Self::Gossip, Self::RpcByRoot]
+    }
+}
+
 #[derive(Debug, PartialEq, Eq, Clone, Deserialize, Serialize)]
 pub struct ChainConfig {
     /// Maximum number of slots to skip when importing an attestation.
@@ -43,6 +79,11 @@ pub struct ChainConfig {
     /// By default this list is empty, but it can be useful for reacting to network conditions, e.g.
     /// slow gossip of re-org blocks at slot 1 in the epoch.
     pub re_org_disallowed_offsets: DisallowedReOrgOffsets,
+    /// Alternative proposer re-org parameters to simulate each slot for analysis.
+    ///
+    /// Decisions under these parameters are compared with those under the configured parameters
+    /// and divergences are logged. They are never acted on.
+    pub re_org_analysis: Option<ReOrgSimulationParams>,
     /// Number of milliseconds to wait for fork choice before proposing a block.
     ///
     /// If set to 0 then block proposal will not wait for fork choice at all.
@@ -84,6 +125,12 @@ pub struct ChainConfig {
     pub epochs_per_migration: u64,
     /// When set to true Light client server computes and caches state proofs for serving updates
     pub enable_light_client_server: bool,
+    /// A `/lighthouse/light_client/store` snapshot to load into the light client server's cache
+    /// on startup.
+    pub light_client_store_snapshot: Option<PathBuf>,
+    /// Record the peer which first delivered each gossip attestation, so that block rewards can
+    /// be attributed to the peers which supplied them.
+    pub track_attestation_sources: bool,
     /// The number of data columns to withhold / exclude from publishing when proposing a block.
     pub malicious_withhold_count: usize,
     /// Enable peer sampling on blocks.
@@ -94,6 +141,32 @@ pub struct ChainConfig {
     /// The delay in milliseconds applied by the node between sending each blob or data column batch.
     /// This doesn't apply if the node is the block proposer.
     pub blob_publication_batch_interval: Duration,
+    /// The clock skew above which a warning is logged.
+    pub clock_skew_threshold: Duration,
+    /// An NTP server (`host:port`) to check the system clock against, if any.
+    pub ntp_server: Option<String>,
+    /// Free disk space thresholds for the storage watchdog, which is disabled if `None`.
+    pub storage_watchdog: Option<StorageThresholds>,
+    /// The number of threads used for the per-validator computations in epoch processing.
+    pub epoch_processing_threads: usize,
+    /// The number of threads used to verify the KZG proofs of batches of blobs and data columns.
+    pub kzg_verification_threads: usize,
+    /// Refuse to publish attestations and blocks from the HTTP API which are slashable with
+    /// respect to recently observed messages.
+    pub publish_safety_checks: bool,
+    /// The order in which blob acquisition strategies are preferred. Fetching blobs from the EL is
+    /// disabled if `EngineGetBlobs` is absent.
+    pub blob_fetch_order: Vec<BlobFetchStrategy>,
+    /// How long to wait for blobs on gossip before fetching them from the EL, if gossip is
+    /// preferred over the EL.
+    pub blob_gossip_wait: Duration,
+    /// The maximum number of blocks from a chain segment which are signature-verified together.
+    ///
+    /// Blocks from the same epoch are split into batches of at most this many blocks, so that the
+    /// next batch can be signature-verified whilst the current batch is imported.
+    pub max_chain_segment_length: usize,
+    /// Signature-verify the next batch of a chain segment while the current batch is imported.
+    pub pipeline_chain_segment_verification: bool,
 }
 
 impl Default for ChainConfig {
@@ -108,6 +181,7 @@ impl Default for ChainConfig {
             re_org_max_epochs_since_finalization: DEFAULT_RE_ORG_MAX_EPOCHS_SINCE_FINALIZATION,
             re_org_cutoff_millis: None,
             re_org_disallowed_offsets: DisallowedReOrgOffsets::default(),
+            re_org_analysis: None,
             fork_choice_before_proposal_timeout_ms: DEFAULT_FORK_CHOICE_BEFORE_PROPOSAL_TIMEOUT,
             // Builder fallback configs that are set in `clap` will override these.
             builder_fallback_skips: 3,
@@ -125,10 +199,22 @@ impl Default for ChainConfig {
             always_prepare_payload: false,
             epochs_per_migration: crate::migrate::DEFAULT_EPOCHS_PER_MIGRATION,
             enable_light_client_server: false,
+            light_client_store_snapshot: None,
+            track_attestation_sources: false,
             malicious_withhold_count: 0,
             enable_sampling: false,
             blob_publication_batches: 4,
             blob_publication_batch_interval: Duration::from_millis(300),
+            clock_skew_threshold: crate::clock_sanity_service::DEFAULT_CLOCK_SKEW_THRESHOLD,
+            ntp_server: None,
+            storage_watchdog: None,
+            epoch_processing_threads: 1,
+            kzg_verification_threads: DEFAULT_KZG_VERIFICATION_THREADS,
+            publish_safety_checks: false,
+            blob_fetch_order: BlobFetchStrategy::default_order(),
+            blob_gossip_wait: DEFAULT_BLOB_GOSSIP_WAIT,
+            max_chain_segment_length: DEFAULT_MAX_CHAIN_SEGMENT_LENGTH,
+            pipeline_chain_segment_verification: true,
         }
     }
 }
@@ -142,4 +228,26 @@ impl ChainConfig {
                 Duration::from_secs(seconds_per_slot) / DEFAULT_RE_ORG_CUTOFF_DENOMINATOR
             })
     }
+
+    /// Whether blob acquisition `strategy` is preferred over `other`. Absent strategies are
+    /// preferred least.
+    pub fn prefers_blob_fetch_strategy(
+        &self,
+        strategy: BlobFetchStrategy,
+        other: BlobFetchStrategy,
+    ) -> bool {
+        let position = |s| {
+            self.blob_fetch_order
+                .iter()
+                .position(|order| *order == s)
+                .unwrap_or(usize::MAX)
+        };
+        position(strategy) < position(other)
+    }
+
+    /// Whether fetching blobs from the EL is enabled.
+    pub fn engine_blobs_enabled(&self) -> bool {
+        self.blob_fetch_order
+            .contains(&BlobFetchStrategy::EngineGetBlobs)
+    }
 }
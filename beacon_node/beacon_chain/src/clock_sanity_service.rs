@@ -0,0 +1,414 @@
+//! Provides a service which periodically checks that the local clock agrees with the network.
+//!
+//! A skewed clock causes attestations and blocks to be produced at the wrong time, which usually
+//! presents as missed attestations without any obvious cause. Two sources are used to detect skew:
+//!
+//! 1. Gossip blocks: an honest block cannot arrive before the start of its slot, so a block which
+//!    arrives early according to the local clock indicates that the local clock is behind. A local
+//!    clock which is ahead cannot be distinguished from late blocks, so this is a lower bound. A
+//!    low percentile of the arrival times is used rather than the earliest, so that a few blocks
+//!    from proposers with fast clocks don't cause false alarms.
+//! 2. NTP: if an NTP server is configured, it is queried directly for the clock offset.
+use crate::block_times_cache::BlockTimesCache;
+use crate::{metrics, BeaconChain, BeaconChainTypes};
+use slog::{debug, error, warn};
+use slot_clock::SlotClock;
+use std::sync::Arc;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use task_executor::TaskExecutor;
+use tokio::net::{lookup_host, UdpSocket};
+use tokio::time::{sleep, timeout};
+use types::{EthSpec, Slot};
+
+/// The default skew above which a warning is logged.
+///
+/// This is half of the `MAXIMUM_GOSSIP_CLOCK_DISPARITY`, beyond which peers start to reject our
+/// messages (or we start to reject theirs).
+pub const DEFAULT_CLOCK_SKEW_THRESHOLD: Duration = Duration::from_millis(250);
+
+/// The minimum number of recent gossip blocks required to estimate the clock skew.
+const MIN_GOSSIP_SAMPLES: usize = 8;
+/// The percentile of gossip block arrival times used as the clock skew estimate.
+///
+/// With `MIN_GOSSIP_SAMPLES` blocks, the two earliest arrivals are disregarded.
+const GOSSIP_SKEW_PERCENTILE: usize = 25;
+
+/// The maximum time to wait for a response from the NTP server.
+const NTP_TIMEOUT: Duration = Duration::from_secs(5);
+/// The length of an NTP packet without extension fields.
+const NTP_PACKET_LEN: usize = 48;
+/// The number of seconds between the NTP epoch (1900) and the Unix epoch (1970).
+const NTP_UNIX_EPOCH_OFFSET_SECS: u64 = 2_208_988_800;
+
+/// Spawns the service described in the module-level documentation.
+pub fn start_clock_sanity_service<T: BeaconChainTypes>(
+    executor: TaskExecutor,
+    chain: Arc<BeaconChain<T>>,
+) {
+    executor.spawn(
+        async move { clock_sanity_service(chain).await },
+        "clock_sanity_service",
+    );
+}
+
+/// Loop indefinitely, checking the clock skew once per epoch.
+async fn clock_sanity_service<T: BeaconChainTypes>(chain: Arc<BeaconChain<T>>) {
+    let slots_per_epoch = T::EthSpec::slots_per_epoch();
+    let slot_duration = chain.slot_clock.slot_duration();
+    let threshold = chain.config.clock_skew_threshold;
+    let threshold_millis = threshold.as_millis() as i64;
+
+    loop {
+        let Some(duration_to_next_epoch) = chain.slot_clock.duration_to_next_epoch(slots_per_epoch)
+        else {
+            error!(chain.log, "Failed to read slot clock");
+            // If we can't read the slot clock, just wait another slot.
+            sleep(slot_duration).await;
+            continue;
+        };
+        // Run halfway through the first slot of the epoch, after its block has had time to arrive.
+        sleep(duration_to_next_epoch + slot_duration / 2).await;
+
+        if let Ok(current_slot) = chain.slot() {
+            let earliest_slot = current_slot.saturating_sub(slots_per_epoch);
+            let estimate = gossip_skew_estimate(
+                &chain.block_times_cache.read(),
+                &chain.slot_clock,
+                earliest_slot,
+            );
+            if let Some(estimate_millis) = estimate {
+                metrics::set_gauge(&metrics::CLOCK_SKEW_GOSSIP_ESTIMATE, estimate_millis);
+                if clock_is_behind(estimate_millis, threshold) {
+                    warn!(
+                        chain.log,
+                        "System clock appears to be behind";
+                        "info" => "recent blocks arrived before the start of their slot, \
+                                   check the system time is synchronised",
+                        "estimated_skew_ms" => -estimate_millis,
+                    );
+                }
+            }
+        }
+
+        if let Some(server) = &chain.config.ntp_server {
+            match query_ntp_offset(server).await {
+                Ok(offset_millis) => {
+                    metrics::set_gauge(&metrics::CLOCK_SKEW_NTP_OFFSET, offset_millis);
+                    if offset_millis.abs() > threshold_millis {
+                        warn!(
+                            chain.log,
+                            "System clock differs from NTP server";
+                            "info" => "check the system time is synchronised",
+                            "ntp_server" => server,
+                            "offset_ms" => offset_millis,
+                        );
+                    } else {
+                        debug!(
+                            chain.log,
+                            "Checked system clock against NTP server";
+                            "ntp_server" => server,
+                            "offset_ms" => offset_millis,
+                        );
+                    }
+                }
+                Err(e) => {
+                    metrics::inc_counter(&metrics::CLOCK_SKEW_NTP_ERRORS);
+                    warn!(
+                        chain.log,
+                        "Unable to query NTP server";
+                        "ntp_server" => server,
+                        "error" => e,
+                    );
+                }
+            }
+        }
+    }
+}
+
+/// Returns the `GOSSIP_SKEW_PERCENTILE` arrival time of gossip blocks from `earliest_slot` onwards
+/// relative to the start of their slots, in milliseconds.
+///
+/// Blocks received via RPC or produced locally are ignored, since their arrival time is unrelated
+/// to the start of their slot. A negative value indicates that the local clock is behind by at
+/// least that amount.
+fn gossip_skew_estimate<S: SlotClock>(
+    block_times_cache: &BlockTimesCache,
+    slot_clock: &S,
+    earliest_slot: Slot,
+) -> Option<i64> {
+    let mut delays = block_times_cache
+        .cache
+        .values()
+        .filter(|block_times| block_times.slot >= earliest_slot)
+        .filter_map(|block_times| {
+            let observed = block_times.timestamps.gossip_observed?;
+            let slot_start = slot_clock.start_of(block_times.slot)?;
+            Some(observed.as_millis() as i64 - slot_start.as_millis() as i64)
+        })
+        .collect::<Vec<_>>();
+
+    if delays.len() < MIN_GOSSIP_SAMPLES {
+        return None;
+    }
+    let index = delays.len() * GOSSIP_SKEW_PERCENTILE / 100;
+    let (_, estimate, _) = delays.select_nth_unstable(index);
+    Some(*estimate)
+}
+
+/// Returns `true` if the gossip skew estimate shows the local clock to be behind by more than
+/// `threshold`.
+fn clock_is_behind(estimate_millis: i64, threshold: Duration) -> bool {
+    estimate_millis < -(threshold.as_millis() as i64)
+}
+
+/// Query the NTP `server` (e.g. `pool.ntp.org:123`) and return the offset of its clock from the
+/// local clock in milliseconds.
+///
+/// A positive value indicates that the local clock is behind.
+async fn query_ntp_offset(server: &str) -> Result<i64, String> {
+    let address = lookup_host(server)
+        .await
+        .map_err(|e| format!("Unable to resolve {}: {}", server, e))?
+        .next()
+        .ok_or_else(|| format!("No addresses found for {}", server))?;
+    let bind_address = if address.is_ipv4() {
+        "0.0.0.0:0"
+    } else {
+        "[::]:0"
+    };
+    let socket = UdpSocket::bind(bind_address)
+        .await
+        .map_err(|e| format!("Unable to bind socket: {}", e))?;
+    socket
+        .connect(address)
+        .await
+        .map_err(|e| format!("Unable to connect: {}", e))?;
+
+    // Leap indicator 0, version 4, client mode. The transmit timestamp is echoed back by the
+    // server, which allows the response to be matched with the request.
+    let mut request = [0; NTP_PACKET_LEN];
+    request[0] = 0x23;
+    let originate = now_nanos()?;
+    request[40..48].copy_from_slice(&to_ntp_timestamp(originate));
+
+    let mut response = [0; NTP_PACKET_LEN];
+    let received_len = timeout(NTP_TIMEOUT, async {
+        socket.send(&request).await?;
+        socket.recv(&mut response).await
+    })
+    .await
+    .map_err(|_| "Timed out".to_string())?
+    .map_err(|e| format!("Socket error: {}", e))?;
+    let destination = now_nanos()?;
+
+    if received_len < NTP_PACKET_LEN {
+        return Err(format!("Short response of {} bytes", received_len));
+    }
+    if response[0] & 0b111 != 4 {
+        return Err("Response is not from a server".to_string());
+    }
+    if response[1] == 0 {
+        return Err("Server sent a kiss-of-death response".to_string());
+    }
+    if response[24..32] != request[40..48] {
+        return Err("Response does not match request".to_string());
+    }
+
+    let receive = from_ntp_timestamp(&response[32..40]);
+    let transmit = from_ntp_timestamp(&response[40..48]);
+    Ok((ntp_offset_nanos(originate, receive, transmit, destination) / 1_000_000) as i64)
+}
+
+/// Compute the clock offset from the four timestamps of an NTP exchange, as per RFC 5905.
+fn ntp_offset_nanos(originate: i128, receive: i128, transmit: i128, destination: i128) -> i128 {
+    ((receive - originate) + (transmit - destination)) / 2
+}
+
+/// The nanoseconds since the Unix epoch according to the local clock.
+fn now_nanos() -> Result<i128, String> {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|duration| duration.as_nanos() as i128)
+        .map_err(|e| format!("System time is before the Unix epoch: {}", e))
+}
+
+/// Convert nanoseconds since the Unix epoch into an NTP timestamp.
+fn to_ntp_timestamp(unix_nanos: i128) -> [u8; 8] {
+    let seconds = (unix_nanos / 1_000_000_000) as u64 + NTP_UNIX_EPOCH_OFFSET_SECS;
+    let fraction = ((unix_nanos % 1_000_000_000) << 32) / 1_000_000_000;
+
+    let mut timestamp = [0; 8];
+    timestamp[0..4].copy_from_slice(&(seconds as u32).to_be_bytes());
+    timestamp[4..8].copy_from_slice(&(fraction as u32).to_be_bytes());
+    timestamp
+}
+
+/// Convert an NTP timestamp into nanoseconds since the Unix epoch.
+fn from_ntp_timestamp(timestamp: &[u8]) -> i128 {
+    let mut seconds =
+        u32::from_be_bytes([timestamp[0], timestamp[1], timestamp[2], timestamp[3]]) as u64;
+    let fraction = u32::from_be_bytes([timestamp[4], timestamp[5], timestamp[6], timestamp[7]]);
+
+    // Timestamps before the Unix epoch must belong to the next NTP era, which starts in 2036.
+    if seconds < NTP_UNIX_EPOCH_OFFSET_SECS {
+        seconds += 1 << 32;
+    }
+
+    (seconds - NTP_UNIX_EPOCH_OFFSET_SECS) as i128 * 1_000_000_000
+        + ((fraction as i128 * 1_000_000_000) >> 32)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use slot_clock::TestingSlotClock;
+    use types::{BlockImportSource, Hash256};
+
+    const SLOT_DURATION: Duration = Duration::from_secs(12);
+
+    fn slot_clock() -> TestingSlotClock {
+        TestingSlotClock::new(Slot::new(0), Duration::from_secs(1_000), SLOT_DURATION)
+    }
+
+    /// Record a gossip block at `slot` which arrived `delay_millis` after the start of its slot.
+    fn observe_gossip_block(
+        cache: &mut BlockTimesCache,
+        slot_clock: &TestingSlotClock,
+        slot: u64,
+        delay_millis: i64,
+    ) {
+        let slot = Slot::new(slot);
+        let slot_start = slot_clock.start_of(slot).unwrap().as_millis() as i64;
+        let timestamp = Duration::from_millis((slot_start + delay_millis) as u64);
+        let block_root = Hash256::from_low_u64_be(slot.as_u64());
+        cache.set_time_observed(
+            block_root,
+            slot,
+            timestamp,
+            BlockImportSource::Gossip,
+            None,
+            None,
+        );
+        cache.set_time_gossip_observed(block_root, slot, timestamp);
+    }
+
+    #[test]
+    fn gossip_skew_estimate_requires_samples() {
+        let slot_clock = slot_clock();
+        let mut cache = BlockTimesCache::default();
+        for slot in 1..MIN_GOSSIP_SAMPLES as u64 {
+            observe_gossip_block(&mut cache, &slot_clock, slot, -1_000);
+        }
+        assert_eq!(
+            gossip_skew_estimate(&cache, &slot_clock, Slot::new(0)),
+            None
+        );
+
+        observe_gossip_block(&mut cache, &slot_clock, MIN_GOSSIP_SAMPLES as u64, 500);
+        assert_eq!(
+            gossip_skew_estimate(&cache, &slot_clock, Slot::new(0)),
+            Some(-1_000)
+        );
+        // Blocks prior to `earliest_slot` are ignored.
+        assert_eq!(
+            gossip_skew_estimate(&cache, &slot_clock, Slot::new(2)),
+            None
+        );
+    }
+
+    #[test]
+    fn gossip_skew_estimate_warning_threshold() {
+        let threshold = DEFAULT_CLOCK_SKEW_THRESHOLD;
+        let slot_clock = slot_clock();
+
+        let delays = [-250, -240, -200, 300, 500, 800, 1_000, 2_000];
+
+        // Blocks arriving after the start of their slot, or marginally before it, are normal.
+        let mut cache = BlockTimesCache::default();
+        for (slot, delay_millis) in (1..).zip(delays) {
+            observe_gossip_block(&mut cache, &slot_clock, slot, delay_millis);
+        }
+        let estimate = gossip_skew_estimate(&cache, &slot_clock, Slot::new(0)).unwrap();
+        assert_eq!(estimate, -200);
+        assert!(!clock_is_behind(estimate, threshold));
+
+        // Blocks consistently arriving more than the threshold before the start of their slot
+        // indicate that the local clock is behind.
+        let mut cache = BlockTimesCache::default();
+        for (slot, delay_millis) in (1..).zip(delays) {
+            observe_gossip_block(&mut cache, &slot_clock, slot, delay_millis - 500);
+        }
+        let estimate = gossip_skew_estimate(&cache, &slot_clock, Slot::new(0)).unwrap();
+        assert_eq!(estimate, -700);
+        assert!(clock_is_behind(estimate, threshold));
+    }
+
+    #[test]
+    fn gossip_skew_estimate_ignores_outliers() {
+        let slot_clock = slot_clock();
+
+        // A couple of blocks from proposers whose clocks are ahead arrive well before the start
+        // of their slot, but the rest arrive on time.
+        let mut cache = BlockTimesCache::default();
+        let delays = [-5_000, -2_000, 300, 500, 800, 1_000, 1_500, 2_000];
+        for (slot, delay_millis) in (1..).zip(delays) {
+            observe_gossip_block(&mut cache, &slot_clock, slot, delay_millis);
+        }
+        let estimate = gossip_skew_estimate(&cache, &slot_clock, Slot::new(0)).unwrap();
+        assert_eq!(estimate, 300);
+        assert!(!clock_is_behind(estimate, DEFAULT_CLOCK_SKEW_THRESHOLD));
+    }
+
+    #[test]
+    fn gossip_skew_estimate_ignores_rpc_blocks() {
+        let slot_clock = slot_clock();
+        let mut cache = BlockTimesCache::default();
+        for slot in 1..=MIN_GOSSIP_SAMPLES as u64 {
+            observe_gossip_block(&mut cache, &slot_clock, slot, 1_000);
+        }
+
+        // A block fetched via RPC or imported locally can be "observed" at any time, including
+        // before the start of its slot if the slot is in the future.
+        let slot = Slot::new(MIN_GOSSIP_SAMPLES as u64 + 1);
+        let early = slot_clock.start_of(slot).unwrap() - Duration::from_secs(5);
+        for (i, source) in [BlockImportSource::Lookup, BlockImportSource::RangeSync]
+            .into_iter()
+            .enumerate()
+        {
+            cache.set_time_observed(
+                Hash256::repeat_byte(0xff - i as u8),
+                slot,
+                early,
+                source,
+                None,
+                None,
+            );
+        }
+
+        let estimate = gossip_skew_estimate(&cache, &slot_clock, Slot::new(0)).unwrap();
+        assert_eq!(estimate, 1_000);
+        assert!(!clock_is_behind(estimate, DEFAULT_CLOCK_SKEW_THRESHOLD));
+    }
+
+    #[test]
+    fn ntp_timestamp_round_trip() {
+        let unix_nanos = 1_760_000_000_123_456_789;
+        let round_trip = from_ntp_timestamp(&to_ntp_timestamp(unix_nanos));
+        // The fraction has a resolution of ~0.23ns, so allow for rounding.
+        assert!((round_trip - unix_nanos).abs() <= 1);
+    }
+
+    #[test]
+    fn ntp_offset() {
+        let second = 1_000_000_000;
+        // The server clock is 2s ahead and the network delay is 100ms in each direction.
+        let originate = 100 * second;
+        let receive = originate + second / 10 + 2 * second;
+        let transmit = receive;
+        let destination = originate + second / 5;
+        assert_eq!(
+            ntp_offset_nanos(originate, receive, transmit, destination),
+            2 * second
+        );
+    }
+}
@@ -787,6 +787,7 @@ mod test {
         let parent_eth1_finalization_data = Eth1FinalizationData {
             eth1_data: parent_block.message().body().eth1_data().clone(),
             eth1_deposit_index: 0,
+            deposit_requests_start_index: u64::MAX,
         };
 
         let (signed_beacon_block_hash, (block, maybe_blobs), state) = harness
@@ -1216,6 +1217,7 @@ mod pending_components_tests {
                 parent_eth1_finalization_data: Eth1FinalizationData {
                     eth1_data: Default::default(),
                     eth1_deposit_index: 0,
+                    deposit_requests_start_index: u64::MAX,
                 },
                 confirmed_state_roots: vec![],
                 consensus_context: ConsensusContext::new(Slot::new(0)),
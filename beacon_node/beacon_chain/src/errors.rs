@@ -221,11 +221,13 @@ pub enum BeaconChainError {
     AvailabilityCheckError(AvailabilityCheckError),
     LightClientUpdateError(LightClientUpdateError),
     LightClientBootstrapError(String),
+    LightClientStoreImportError(String),
     UnsupportedFork,
     MilhouseError(MilhouseError),
     EmptyRpcCustodyColumns,
     AttestationError(AttestationError),
     AttestationCommitteeIndexNotSet,
+    EmptyChainSegment,
 }
 
 easy_from_to!(SlotProcessingError, BeaconChainError);
@@ -297,6 +299,16 @@ pub enum BlockProductionError {
     KzgError(kzg::Error),
     FailedToBuildBlobSidecars(String),
     MissingExecutionRequests,
+    UnknownParentBlock(Hash256),
+    ParentSlotTooHigh {
+        produce_at_slot: Slot,
+        parent_slot: Slot,
+    },
+    ParentPriorToFinalization {
+        parent_root: Hash256,
+        parent_slot: Slot,
+        finalized_slot: Slot,
+    },
 }
 
 easy_from_to!(BlockProcessingError, BlockProductionError);
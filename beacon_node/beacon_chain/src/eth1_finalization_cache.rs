@@ -2,7 +2,7 @@ use slog::{debug, Logger};
 use ssz_derive::{Decode, Encode};
 use std::cmp;
 use std::collections::BTreeMap;
-use types::{Checkpoint, Epoch, Eth1Data, Hash256 as Root};
+use types::{BeaconState, Checkpoint, Epoch, Eth1Data, EthSpec, Hash256 as Root};
 
 /// The default size of the cache.
 /// The beacon chain only looks at the last 4 epochs for finalization.
@@ -15,13 +15,32 @@ pub const DEFAULT_ETH1_CACHE_SIZE: usize = 5;
 pub struct Eth1FinalizationData {
     pub eth1_data: Eth1Data,
     pub eth1_deposit_index: u64,
+    /// The index of the first deposit processed via execution requests (EIP-6110), or `u64::MAX`
+    /// if unset.
+    pub deposit_requests_start_index: u64,
 }
 
 impl Eth1FinalizationData {
+    pub fn from_state<E: EthSpec>(state: &BeaconState<E>) -> Self {
+        Self {
+            eth1_data: state.eth1_data().clone(),
+            eth1_deposit_index: state.eth1_deposit_index(),
+            deposit_requests_start_index: state.deposit_requests_start_index().unwrap_or(u64::MAX),
+        }
+    }
+
     /// Ensures the deposit finalization conditions have been met. See:
     /// https://eips.ethereum.org/EIPS/eip-4881#deposit-finalization-conditions
+    ///
+    /// Once deposits are processed via execution requests (EIP-6110), deposits from the deposit
+    /// contract beyond `deposit_requests_start_index` will never be imported, so the deposits are
+    /// fully imported once `eth1_deposit_index` reaches it.
     fn fully_imported(&self) -> bool {
-        self.eth1_deposit_index >= self.eth1_data.deposit_count
+        self.eth1_deposit_index
+            >= cmp::min(
+                self.eth1_data.deposit_count,
+                self.deposit_requests_start_index,
+            )
     }
 }
 
@@ -148,7 +167,16 @@ impl Eth1FinalizationCache {
 
     pub fn finalize(&mut self, checkpoint: &Checkpoint) -> Option<Eth1Data> {
         if let Some(eth1_finalized_data) = self.by_checkpoint.get(checkpoint) {
-            let finalized_deposit_index = eth1_finalized_data.eth1_deposit_index;
+            // Every pending deposit up to `eth1_data.deposit_count` can be dropped once the
+            // deposits are fully imported, even if some were superseded by execution requests.
+            let finalized_deposit_index = if eth1_finalized_data.fully_imported() {
+                cmp::max(
+                    eth1_finalized_data.eth1_deposit_index,
+                    eth1_finalized_data.eth1_data.deposit_count,
+                )
+            } else {
+                eth1_finalized_data.eth1_deposit_index
+            };
             let mut result = None;
             while let Some(pending_count) = self.pending_eth1.keys().next().cloned() {
                 if finalized_deposit_index >= pending_count {
@@ -254,6 +282,7 @@ pub mod tests {
                 Eth1FinalizationData {
                     eth1_data: eth1data.clone(),
                     eth1_deposit_index: deposits_imported,
+                    deposit_requests_start_index: u64::MAX,
                 },
             );
 
@@ -312,6 +341,7 @@ pub mod tests {
                 Eth1FinalizationData {
                     eth1_data: eth1data.clone(),
                     eth1_deposit_index: deposits_imported,
+                    deposit_requests_start_index: u64::MAX,
                 },
             );
 
@@ -347,6 +377,64 @@ pub mod tests {
         }
     }
 
+    #[test]
+    fn deposit_requests_started() {
+        let epochs = 16;
+        let deposit_requests_start_index = 1024;
+        let deposit_requests_epoch = 8;
+        let deposits_per_epoch = 16;
+
+        let checkpoints = random_checkpoints(epochs as usize);
+        let mut eth1_data_by_epoch = vec![];
+        let mut eth1cache = eth1cache();
+
+        for epoch in 0..epochs {
+            // Deposits continue to be made to the deposit contract, but once deposit requests
+            // have started the `eth1_deposit_index` is capped at the start index.
+            let eth1data = random_eth1_data(
+                deposit_requests_start_index - deposit_requests_epoch * deposits_per_epoch
+                    + epoch * deposits_per_epoch,
+            );
+            eth1_data_by_epoch.push(eth1data.clone());
+            let checkpoint = checkpoints
+                .get(epoch as usize)
+                .expect("should get checkpoint");
+            eth1cache.insert(
+                *checkpoint,
+                Eth1FinalizationData {
+                    eth1_data: eth1data,
+                    eth1_deposit_index: if epoch < deposit_requests_epoch {
+                        epoch * deposits_per_epoch
+                    } else {
+                        deposit_requests_start_index
+                    },
+                    deposit_requests_start_index,
+                },
+            );
+
+            if epoch >= 4 {
+                let finalized_epoch = epoch - 4;
+                let finalized_checkpoint = checkpoints
+                    .get(finalized_epoch as usize)
+                    .expect("should get finalized checkpoint");
+                let finalized = eth1cache.finalize(finalized_checkpoint);
+                if finalized_epoch >= deposit_requests_epoch {
+                    assert_eq!(
+                        finalized,
+                        eth1_data_by_epoch.get(finalized_epoch as usize).cloned(),
+                        "Deposits up to the start index are imported so cache should finalize"
+                    );
+                    assert!(
+                        eth1cache.pending_eth1().is_empty(),
+                        "Pending cache should be empty once deposit requests have started"
+                    );
+                } else {
+                    assert_eq!(finalized, None, "Deposits are not yet imported");
+                }
+            }
+        }
+    }
+
     #[test]
     fn fork_at_epoch_boundary() {
         let epochs = 12;
@@ -372,6 +460,7 @@ pub mod tests {
                 Eth1FinalizationData {
                     eth1_data: eth1data.clone(),
                     eth1_deposit_index: deposits_imported,
+                    deposit_requests_start_index: u64::MAX,
                 },
             );
             // lets put a fork at every third epoch
@@ -382,6 +471,7 @@ pub mod tests {
                     Eth1FinalizationData {
                         eth1_data: eth1data.clone(),
                         eth1_deposit_index: deposits_imported,
+                        deposit_requests_start_index: u64::MAX,
                     },
                 );
                 forks.insert(epoch as usize, fork);
@@ -455,6 +545,7 @@ pub mod tests {
                     Eth1FinalizationData {
                         eth1_data: period_eth1_data.clone(),
                         eth1_deposit_index: deposits_imported,
+                        deposit_requests_start_index: u64::MAX,
                     },
                 );
                 epoch_data.insert(epoch, (checkpoint, deposits_imported));
@@ -0,0 +1,154 @@
+//! A rolling history of changes to the canonical head, so that re-orgs can be summarised without
+//! scraping logs.
+use crate::{BeaconChain, BeaconChainError, BeaconChainTypes};
+use eth2::lighthouse::{ReorgDepthCount, ReorgInfo, ReorgSummary};
+use std::collections::{BTreeMap, VecDeque};
+use types::{Epoch, EthSpec, Hash256, Slot};
+
+/// The number of epochs of head changes which are retained.
+pub const HEAD_HISTORY_EPOCHS: u64 = 64;
+
+/// A single change of the canonical head.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct HeadChange {
+    pub block_root: Hash256,
+    pub slot: Slot,
+    pub parent_root: Hash256,
+    pub previous_block_root: Hash256,
+    pub previous_slot: Slot,
+    /// The re-org depth, or `None` if the new head descends from the previous head.
+    pub reorg_depth: Option<u64>,
+}
+
+/// The head changes of the last `HEAD_HISTORY_EPOCHS` epochs, in the order they were observed.
+#[derive(Debug, Default)]
+pub struct HeadHistory {
+    changes: VecDeque<HeadChange>,
+}
+
+impl HeadHistory {
+    /// Record a change of the head, pruning changes which are older than `HEAD_HISTORY_EPOCHS`.
+    pub fn record<E: EthSpec>(&mut self, change: HeadChange) {
+        let oldest_slot = change
+            .slot
+            .saturating_sub(HEAD_HISTORY_EPOCHS * E::slots_per_epoch());
+        while self
+            .changes
+            .front()
+            .is_some_and(|oldest| oldest.slot < oldest_slot)
+        {
+            self.changes.pop_front();
+        }
+        self.changes.push_back(change);
+    }
+
+    /// Summarise the head changes of the `epochs` epochs up to and including `current_epoch`.
+    pub fn summarize<E: EthSpec>(&self, current_epoch: Epoch, epochs: u64) -> ReorgSummary {
+        let start_slot = current_epoch
+            .saturating_sub(epochs.saturating_sub(1))
+            .start_slot(E::slots_per_epoch());
+
+        let mut head_changes = 0;
+        let mut depth_counts = BTreeMap::<u64, u64>::new();
+        let mut reorgs = vec![];
+        for change in self
+            .changes
+            .iter()
+            .filter(|change| change.slot >= start_slot)
+        {
+            head_changes += 1;
+            if let Some(depth) = change.reorg_depth {
+                *depth_counts.entry(depth).or_default() += 1;
+                reorgs.push(ReorgInfo {
+                    depth,
+                    old_head_block: change.previous_block_root,
+                    old_head_slot: change.previous_slot,
+                    new_head_block: change.block_root,
+                    new_head_slot: change.slot,
+                    new_head_parent: change.parent_root,
+                });
+            }
+        }
+
+        ReorgSummary {
+            start_slot,
+            head_changes,
+            reorg_count: reorgs.len() as u64,
+            depth_distribution: depth_counts
+                .into_iter()
+                .map(|(depth, count)| ReorgDepthCount { depth, count })
+                .collect(),
+            reorgs,
+        }
+    }
+}
+
+impl<T: BeaconChainTypes> BeaconChain<T> {
+    /// Summarise the re-orgs of the last `epochs` epochs, including the current epoch.
+    pub fn reorg_summary(&self, epochs: u64) -> Result<ReorgSummary, BeaconChainError> {
+        let current_epoch = self.epoch()?;
+        Ok(self
+            .head_history
+            .read()
+            .summarize::<T::EthSpec>(current_epoch, epochs))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use types::MainnetEthSpec;
+
+    type E = MainnetEthSpec;
+
+    fn change(slot: u64, reorg_depth: Option<u64>) -> HeadChange {
+        HeadChange {
+            block_root: Hash256::repeat_byte(slot as u8),
+            slot: Slot::new(slot),
+            parent_root: Hash256::repeat_byte(slot as u8 - 1),
+            previous_block_root: Hash256::repeat_byte(slot as u8 - 1),
+            previous_slot: Slot::new(slot - 1),
+            reorg_depth,
+        }
+    }
+
+    #[test]
+    fn summarize_reorgs() {
+        let mut history = HeadHistory::default();
+        history.record::<E>(change(1, None));
+        history.record::<E>(change(33, Some(1)));
+        history.record::<E>(change(34, None));
+        history.record::<E>(change(40, Some(2)));
+        history.record::<E>(change(41, Some(1)));
+
+        let summary = history.summarize::<E>(Epoch::new(1), 2);
+        assert_eq!(summary.start_slot, Slot::new(0));
+        assert_eq!(summary.head_changes, 5);
+        assert_eq!(summary.reorg_count, 3);
+        assert_eq!(
+            summary.depth_distribution,
+            vec![
+                ReorgDepthCount { depth: 1, count: 2 },
+                ReorgDepthCount { depth: 2, count: 1 },
+            ]
+        );
+        assert_eq!(summary.reorgs[1].new_head_slot, Slot::new(40));
+        assert_eq!(summary.reorgs[1].depth, 2);
+
+        let summary = history.summarize::<E>(Epoch::new(1), 1);
+        assert_eq!(summary.start_slot, Slot::new(32));
+        assert_eq!(summary.head_changes, 4);
+        assert_eq!(summary.reorg_count, 3);
+    }
+
+    #[test]
+    fn prune_old_changes() {
+        let mut history = HeadHistory::default();
+        history.record::<E>(change(1, Some(1)));
+        history.record::<E>(change(HEAD_HISTORY_EPOCHS * 32 + 2, None));
+
+        let summary = history.summarize::<E>(Epoch::new(HEAD_HISTORY_EPOCHS), u64::MAX);
+        assert_eq!(summary.head_changes, 1);
+        assert_eq!(summary.reorg_count, 0);
+    }
+}
@@ -2,8 +2,9 @@ use kzg::{
     Blob as KzgBlob, Bytes48, CellRef as KzgCellRef, CellsAndKzgProofs, Error as KzgError, Kzg,
 };
 use rayon::prelude::*;
+use rayon::{ThreadPool, ThreadPoolBuilder};
 use ssz_types::FixedVector;
-use std::sync::Arc;
+use std::sync::{Arc, OnceLock};
 use types::beacon_block_body::KzgCommitments;
 use types::data_column_sidecar::{Cell, DataColumn, DataColumnSidecarError};
 use types::{
@@ -11,6 +12,78 @@ use types::{
     KzgCommitment, KzgProof, KzgProofs, SignedBeaconBlock, SignedBeaconBlockHeader,
 };
 
+/// The minimum number of blobs verified together by each thread.
+///
+/// Batch verification amortises the cost of the final pairing check, so very small chunks are
+/// slower than verifying the batch on a single thread.
+const MIN_BLOBS_PER_THREAD: usize = 2;
+
+/// The minimum number of cells verified together by each thread.
+const MIN_CELLS_PER_THREAD: usize = 16;
+
+static KZG_VERIFICATION_POOL: OnceLock<ThreadPool> = OnceLock::new();
+
+/// Use `threads` threads to verify batches of blobs and data columns.
+///
+/// Values of 0 or 1 disable parallelism. This can only be set once per process, although setting
+/// the same value again is permitted.
+pub fn set_kzg_verification_threads(threads: usize) -> Result<(), String> {
+    if threads <= 1 {
+        return Ok(());
+    }
+    if let Some(pool) = KZG_VERIFICATION_POOL.get() {
+        return if pool.current_num_threads() == threads {
+            Ok(())
+        } else {
+            Err("KZG verification threads have already been set".to_string())
+        };
+    }
+    let pool = ThreadPoolBuilder::new()
+        .num_threads(threads)
+        .thread_name(|i| format!("kzg_verification_{i}"))
+        .build()
+        .map_err(|e| format!("Unable to build KZG verification thread pool: {e:?}"))?;
+    // Another thread may have set the pool in the meantime, which is harmless.
+    let _ = KZG_VERIFICATION_POOL.set(pool);
+    Ok(())
+}
+
+/// Returns the number of threads used to verify batches of blobs and data columns.
+pub fn kzg_verification_threads() -> usize {
+    KZG_VERIFICATION_POOL
+        .get()
+        .map_or(1, ThreadPool::current_num_threads)
+}
+
+/// Returns the size of the chunks that a batch of `len` items should be split into, such that each
+/// verification thread receives at most one chunk of at least `min_per_thread` items.
+fn verification_chunk_size(len: usize, min_per_thread: usize) -> usize {
+    len.div_ceil(kzg_verification_threads())
+        .max(min_per_thread)
+        .max(1)
+}
+
+/// Verify the items `0..len` by calling `verify` on consecutive chunks of indices.
+///
+/// The chunks are verified in parallel if `set_kzg_verification_threads` has been called,
+/// otherwise the whole batch is verified at once on the calling thread.
+fn verify_in_chunks<F>(len: usize, min_per_thread: usize, verify: F) -> Result<(), KzgError>
+where
+    F: Fn(std::ops::Range<usize>) -> Result<(), KzgError> + Sync,
+{
+    match KZG_VERIFICATION_POOL.get() {
+        Some(pool) if len > min_per_thread => {
+            let chunk_size = verification_chunk_size(len, min_per_thread);
+            let chunks = (0..len)
+                .step_by(chunk_size)
+                .map(|start| start..start.saturating_add(chunk_size).min(len))
+                .collect::<Vec<_>>();
+            pool.install(|| chunks.into_par_iter().try_for_each(&verify))
+        }
+        _ => verify(0..len),
+    }
+}
+
 /// Converts a blob ssz List object to an array to be used with the kzg
 /// crypto library.
 fn ssz_blob_to_crypto_blob<E: EthSpec>(blob: &Blob<E>) -> Result<KzgBlob, KzgError> {
@@ -83,7 +156,23 @@ where
         })
         .collect::<Vec<_>>();
 
-    kzg.verify_cell_proof_batch(&cells, &proofs, column_indices, &commitments)
+    if proofs.len() != cells.len() || commitments.len() != cells.len() {
+        return Err(KzgError::InconsistentArrayLength(format!(
+            "{} cells, {} proofs and {} commitments",
+            cells.len(),
+            proofs.len(),
+            commitments.len()
+        )));
+    }
+
+    verify_in_chunks(cells.len(), MIN_CELLS_PER_THREAD, |chunk| {
+        kzg.verify_cell_proof_batch(
+            &cells[chunk.clone()],
+            &proofs[chunk.clone()],
+            column_indices[chunk.clone()].to_vec(),
+            &commitments[chunk],
+        )
+    })
 }
 
 /// Validate a batch of blob-commitment-proof triplets from multiple `BlobSidecars`.
@@ -99,7 +188,22 @@ pub fn validate_blobs<E: EthSpec>(
         .map(|blob| ssz_blob_to_crypto_blob::<E>(blob))
         .collect::<Result<Vec<_>, KzgError>>()?;
 
-    kzg.verify_blob_kzg_proof_batch(&blobs, expected_kzg_commitments, kzg_proofs)
+    if expected_kzg_commitments.len() != blobs.len() || kzg_proofs.len() != blobs.len() {
+        return Err(KzgError::InconsistentArrayLength(format!(
+            "{} blobs, {} commitments and {} proofs",
+            blobs.len(),
+            expected_kzg_commitments.len(),
+            kzg_proofs.len()
+        )));
+    }
+
+    verify_in_chunks(blobs.len(), MIN_BLOBS_PER_THREAD, |chunk| {
+        kzg.verify_blob_kzg_proof_batch(
+            &blobs[chunk.clone()],
+            &expected_kzg_commitments[chunk.clone()],
+            &kzg_proofs[chunk],
+        )
+    })
 }
 
 /// Compute the kzg proof given an ssz blob and its kzg commitment.
@@ -289,9 +393,13 @@ pub fn reconstruct_data_columns<E: EthSpec>(
 
 #[cfg(test)]
 mod test {
-    use crate::kzg_utils::{blobs_to_data_column_sidecars, reconstruct_data_columns};
+    use crate::kzg_utils::{
+        blob_to_kzg_commitment, blobs_to_data_column_sidecars, reconstruct_data_columns,
+        set_kzg_verification_threads, validate_data_columns,
+    };
     use bls::Signature;
     use kzg::{trusted_setup::get_trusted_setup, Kzg, KzgCommitment, TrustedSetup};
+    use std::sync::Arc;
     use types::{
         beacon_block_body::KzgCommitments, BeaconBlock, BeaconBlockDeneb, Blob, BlobsList,
         ChainSpec, EmptyBlock, EthSpec, MainnetEthSpec, SignedBeaconBlock,
@@ -308,6 +416,7 @@ mod test {
         test_build_data_columns_empty(&kzg, &spec);
         test_build_data_columns(&kzg, &spec);
         test_reconstruct_data_columns(&kzg, &spec);
+        test_validate_data_columns_in_parallel(&kzg, &spec);
     }
 
     #[track_caller]
@@ -379,11 +488,32 @@ mod test {
         }
     }
 
-    fn get_kzg() -> Kzg {
+    #[track_caller]
+    fn test_validate_data_columns_in_parallel(kzg: &Kzg, spec: &ChainSpec) {
+        set_kzg_verification_threads(4).unwrap();
+
+        let num_of_blobs = 6;
+        let (signed_block, blobs) = create_test_block_and_valid_blobs::<E>(num_of_blobs, kzg, spec);
+        let blob_refs = blobs.iter().collect::<Vec<_>>();
+        let mut column_sidecars =
+            blobs_to_data_column_sidecars(&blob_refs, &signed_block, kzg, spec).unwrap();
+
+        validate_data_columns(kzg, column_sidecars.iter()).unwrap();
+
+        // Swapping a proof between columns must be detected regardless of which chunk it is in.
+        let last = column_sidecars.len() - 1;
+        let mut invalid_column = (*column_sidecars[last]).clone();
+        invalid_column.kzg_proofs[0] = column_sidecars[0].kzg_proofs[0];
+        column_sidecars[last] = Arc::new(invalid_column);
+
+        assert!(validate_data_columns(kzg, column_sidecars.iter()).is_err());
+    }
+
+    fn get_kzg() -> Arc<Kzg> {
         let trusted_setup: TrustedSetup = serde_json::from_reader(get_trusted_setup().as_slice())
             .map_err(|e| format!("Unable to read trusted setup file: {}", e))
             .expect("should have trusted setup");
-        Kzg::new_from_trusted_setup_das_enabled(trusted_setup).expect("should create kzg")
+        Kzg::shared(trusted_setup, true).expect("should create kzg")
     }
 
     fn create_test_block_and_blobs<E: EthSpec>(
@@ -406,4 +536,35 @@ mod test {
 
         (signed_block, blobs)
     }
+
+    /// Create blobs with non-zero, distinct contents and a block committing to them.
+    fn create_test_block_and_valid_blobs<E: EthSpec>(
+        num_of_blobs: usize,
+        kzg: &Kzg,
+        spec: &ChainSpec,
+    ) -> (SignedBeaconBlock<E>, BlobsList<E>) {
+        let blobs = (0..num_of_blobs)
+            .map(|i| {
+                let mut blob = Blob::<E>::default();
+                // Set the least significant byte of some field elements, keeping each one below
+                // the field modulus.
+                for (j, field_element) in blob.chunks_mut(32).take(64).enumerate() {
+                    field_element[31] = (i + j + 1) as u8;
+                }
+                blob
+            })
+            .collect::<Vec<_>>();
+        let commitments = blobs
+            .iter()
+            .map(|blob| blob_to_kzg_commitment::<E>(kzg, blob).unwrap())
+            .collect::<Vec<_>>();
+
+        let mut block = BeaconBlock::Deneb(BeaconBlockDeneb::empty(spec));
+        let mut body = block.body_mut();
+        *body.blob_kzg_commitments_mut().unwrap() = KzgCommitments::<E>::new(commitments).unwrap();
+
+        let signed_block = SignedBeaconBlock::from_block(block, Signature::empty());
+
+        (signed_block, blobs.into())
+    }
 }
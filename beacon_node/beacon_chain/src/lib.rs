@@ -1,5 +1,8 @@
+pub mod aggregate_signature_cache;
+pub mod attestation_data_cache;
 pub mod attestation_rewards;
 pub mod attestation_simulator;
+pub mod attestation_sources;
 pub mod attestation_verification;
 mod attester_cache;
 pub mod beacon_block_reward;
@@ -9,15 +12,18 @@ mod beacon_fork_choice_store;
 pub mod beacon_proposer_cache;
 mod beacon_snapshot;
 pub mod bellatrix_readiness;
+pub mod blob_inclusion_proof_cache;
 pub mod blob_verification;
 pub mod block_reward;
 mod block_times_cache;
+pub mod block_timing_history;
 mod block_verification;
 pub mod block_verification_types;
 pub mod builder;
 pub mod canonical_head;
 pub mod capella_readiness;
 pub mod chain_config;
+pub mod clock_sanity_service;
 pub mod data_availability_checker;
 pub mod data_column_verification;
 pub mod deneb_readiness;
@@ -32,6 +38,7 @@ pub mod fetch_blobs;
 pub mod fork_choice_signal;
 pub mod fork_revert;
 pub mod graffiti_calculator;
+pub mod head_history;
 mod head_tracker;
 pub mod historical_blocks;
 pub mod kzg_utils;
@@ -52,9 +59,13 @@ mod persisted_beacon_chain;
 mod persisted_fork_choice;
 mod pre_finalization_cache;
 pub mod proposer_prep_service;
+pub mod publish_safety;
+mod re_org_analysis;
 pub mod schema_change;
 pub mod shuffling_cache;
 pub mod state_advance_timer;
+pub mod state_request_coalescer;
+pub mod storage_watchdog;
 pub mod sync_committee_rewards;
 pub mod sync_committee_verification;
 pub mod test_utils;
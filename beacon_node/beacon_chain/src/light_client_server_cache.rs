@@ -1,5 +1,6 @@
 use crate::errors::BeaconChainError;
-use crate::{metrics, BeaconChainTypes, BeaconStore};
+use crate::{metrics, BeaconChain, BeaconChainTypes, BeaconStore, WhenSlotSkipped};
+use eth2::lighthouse::{LightClientStoreImport, LightClientStoreSnapshot};
 use parking_lot::{Mutex, RwLock};
 use safe_arith::SafeArith;
 use slog::{debug, Logger};
@@ -9,11 +10,18 @@ use std::sync::Arc;
 use store::DBColumn;
 use store::KeyValueStore;
 use tree_hash::TreeHash;
+use types::light_client_update::{
+    FINALIZED_ROOT_INDEX, FINALIZED_ROOT_INDEX_ELECTRA, FINALIZED_ROOT_PROOF_LEN,
+    FINALIZED_ROOT_PROOF_LEN_ELECTRA, NEXT_SYNC_COMMITTEE_INDEX, NEXT_SYNC_COMMITTEE_INDEX_ELECTRA,
+    NEXT_SYNC_COMMITTEE_PROOF_LEN, NEXT_SYNC_COMMITTEE_PROOF_LEN_ELECTRA,
+};
 use types::non_zero_usize::new_non_zero_usize;
 use types::{
-    BeaconBlockRef, BeaconState, ChainSpec, Checkpoint, EthSpec, ForkName, Hash256,
-    LightClientBootstrap, LightClientFinalityUpdate, LightClientOptimisticUpdate,
-    LightClientUpdate, MerkleProof, Slot, SyncAggregate, SyncCommittee,
+    BeaconBlockHeader, BeaconBlockRef, BeaconState, ChainSpec, Checkpoint, EthSpec, ForkName,
+    ForkVersionedResponse, Hash256, LightClientBootstrap, LightClientFinalityUpdate,
+    LightClientHeader, LightClientHeaderAltair, LightClientHeaderCapella, LightClientHeaderDeneb,
+    LightClientHeaderElectra, LightClientOptimisticUpdate, LightClientUpdate, MerkleProof,
+    SignedBlindedBeaconBlock, Slot, SyncAggregate, SyncCommittee,
 };
 
 /// A prev block cache miss requires to re-generate the state of the post-parent block. Items in the
@@ -21,6 +29,10 @@ use types::{
 /// represents unlikely re-orgs, while keeping the cache very small.
 const PREV_BLOCK_CACHE_SIZE: NonZeroUsize = new_non_zero_usize(32);
 
+/// The number of sync committee periods whose best updates are included in a snapshot. This is the
+/// maximum number of updates a light client may request at once.
+pub const LIGHT_CLIENT_STORE_SNAPSHOT_PERIODS: u64 = 128;
+
 /// This cache computes light client messages ahead of time, required to satisfy p2p and API
 /// requests. These messages include proofs on historical states, so on-demand computation is
 /// expensive.
@@ -399,6 +411,416 @@ impl<T: BeaconChainTypes> LightClientServerCache<T> {
 
         Ok(Some((light_client_bootstrap, fork_name)))
     }
+
+    /// Returns the latest finality and optimistic updates, along with the best update of each of
+    /// the `periods` sync committee periods up to and including `current_period`.
+    pub fn get_snapshot(
+        &self,
+        store: &BeaconStore<T>,
+        current_period: u64,
+        periods: u64,
+        chain_spec: &ChainSpec,
+    ) -> Result<LightClientStoreSnapshot<T::EthSpec>, BeaconChainError> {
+        let start_period = current_period.saturating_sub(periods.saturating_sub(1));
+        let best_updates = self
+            .get_light_client_updates(store, start_period, periods, chain_spec)?
+            .into_iter()
+            .map(|update| fork_versioned(*update.signature_slot(), update, chain_spec))
+            .collect();
+
+        Ok(LightClientStoreSnapshot {
+            latest_finality_update: self
+                .get_latest_finality_update()
+                .map(|update| fork_versioned(*update.signature_slot(), update, chain_spec)),
+            latest_optimistic_update: self
+                .get_latest_optimistic_update()
+                .map(|update| fork_versioned(*update.signature_slot(), update, chain_spec)),
+            best_updates,
+        })
+    }
+
+    /// Imports a snapshot produced by `Self::get_snapshot`, keeping any cached or persisted data
+    /// which is more recent or better than that of the snapshot.
+    ///
+    /// Each message of the snapshot is verified against the local chain before it is cached or
+    /// persisted, see `verify_against_chain`. Messages which cannot be verified, e.g. because they
+    /// refer to blocks this node has not imported, are ignored.
+    pub fn import_snapshot(
+        &self,
+        chain: &BeaconChain<T>,
+        snapshot: LightClientStoreSnapshot<T::EthSpec>,
+    ) -> Result<LightClientStoreImport, BeaconChainError> {
+        let store = &chain.store;
+        let chain_spec = &chain.spec;
+        let mut import = LightClientStoreImport::default();
+
+        for versioned in snapshot.best_updates {
+            let signature_slot = *versioned.data.signature_slot();
+            let update = check_fork::<T::EthSpec, _>(versioned, signature_slot, chain_spec)?;
+            if let Err(reason) = verify_against_chain(chain, MessageToVerify::from_update(&update))?
+            {
+                debug!(
+                    chain.log,
+                    "Ignoring unverified light client update";
+                    "signature_slot" => signature_slot,
+                    "reason" => reason,
+                );
+                import.rejected += 1;
+                continue;
+            }
+            let sync_period = signature_slot
+                .epoch(T::EthSpec::slots_per_epoch())
+                .sync_committee_period(chain_spec)?;
+
+            let should_persist_light_client_update =
+                match self.get_light_client_update(store, sync_period, chain_spec)? {
+                    Some(prev_light_client_update) => prev_light_client_update
+                        .is_better_light_client_update(&update, chain_spec)?,
+                    None => true,
+                };
+
+            if should_persist_light_client_update {
+                store.store_light_client_update(sync_period, &update)?;
+                *self.latest_light_client_update.write() = Some(update);
+                import.best_updates_imported += 1;
+            }
+        }
+
+        if let Some(versioned) = snapshot.latest_finality_update {
+            let signature_slot = *versioned.data.signature_slot();
+            let update = check_fork::<T::EthSpec, _>(versioned, signature_slot, chain_spec)?;
+            let verification =
+                verify_against_chain(chain, MessageToVerify::from_finality_update(&update))?;
+            let mut latest_finality_update = self.latest_finality_update.write();
+            let is_latest = latest_finality_update.as_ref().map_or(true, |latest| {
+                latest.is_latest(update.get_attested_header_slot(), signature_slot)
+            });
+            if let Err(reason) = verification {
+                debug!(
+                    chain.log,
+                    "Ignoring unverified light client finality update";
+                    "signature_slot" => signature_slot,
+                    "reason" => reason,
+                );
+                import.rejected += 1;
+            } else if is_latest {
+                *latest_finality_update = Some(update);
+                import.finality_update_imported = true;
+            }
+        }
+
+        if let Some(versioned) = snapshot.latest_optimistic_update {
+            let signature_slot = *versioned.data.signature_slot();
+            let update = check_fork::<T::EthSpec, _>(versioned, signature_slot, chain_spec)?;
+            let verification =
+                verify_against_chain(chain, MessageToVerify::from_optimistic_update(&update))?;
+            let mut latest_optimistic_update = self.latest_optimistic_update.write();
+            let is_latest = latest_optimistic_update.as_ref().map_or(true, |latest| {
+                latest.is_latest(update.get_slot(), signature_slot)
+            });
+            if let Err(reason) = verification {
+                debug!(
+                    chain.log,
+                    "Ignoring unverified light client optimistic update";
+                    "signature_slot" => signature_slot,
+                    "reason" => reason,
+                );
+                import.rejected += 1;
+            } else if is_latest {
+                *latest_optimistic_update = Some(update);
+                import.optimistic_update_imported = true;
+            }
+        }
+
+        Ok(import)
+    }
+}
+
+/// The parts of a light client message which are checked against the local chain on import.
+struct MessageToVerify<E: EthSpec> {
+    attested_header: LightClientHeader<E>,
+    /// The finalized header and the branch proving it against the attested state, if the
+    /// message proves finality.
+    finalized_header: Option<(LightClientHeader<E>, Vec<Hash256>)>,
+    /// The root of the next sync committee and the branch proving it against the attested state,
+    /// if the message carries the next sync committee.
+    next_sync_committee: Option<(Hash256, Vec<Hash256>)>,
+    sync_aggregate: SyncAggregate<E>,
+    signature_slot: Slot,
+}
+
+impl<E: EthSpec> MessageToVerify<E> {
+    fn from_update(update: &LightClientUpdate<E>) -> Self {
+        let (attested_header, finalized_header, finality_branch, next_sync_committee_branch) =
+            match update {
+                LightClientUpdate::Altair(update) => (
+                    LightClientHeader::Altair(update.attested_header.clone()),
+                    LightClientHeader::Altair(update.finalized_header.clone()),
+                    update.finality_branch.to_vec(),
+                    update.next_sync_committee_branch.to_vec(),
+                ),
+                LightClientUpdate::Capella(update) => (
+                    LightClientHeader::Capella(update.attested_header.clone()),
+                    LightClientHeader::Capella(update.finalized_header.clone()),
+                    update.finality_branch.to_vec(),
+                    update.next_sync_committee_branch.to_vec(),
+                ),
+                LightClientUpdate::Deneb(update) => (
+                    LightClientHeader::Deneb(update.attested_header.clone()),
+                    LightClientHeader::Deneb(update.finalized_header.clone()),
+                    update.finality_branch.to_vec(),
+                    update.next_sync_committee_branch.to_vec(),
+                ),
+                LightClientUpdate::Electra(update) => (
+                    LightClientHeader::Electra(update.attested_header.clone()),
+                    LightClientHeader::Electra(update.finalized_header.clone()),
+                    update.finality_branch.to_vec(),
+                    update.next_sync_committee_branch.to_vec(),
+                ),
+            };
+        Self {
+            attested_header,
+            finalized_header: Some((finalized_header, finality_branch)),
+            next_sync_committee: Some((
+                update.next_sync_committee().tree_hash_root(),
+                next_sync_committee_branch,
+            )),
+            sync_aggregate: update.sync_aggregate().clone(),
+            signature_slot: *update.signature_slot(),
+        }
+    }
+
+    fn from_finality_update(update: &LightClientFinalityUpdate<E>) -> Self {
+        let (attested_header, finalized_header, finality_branch) = match update {
+            LightClientFinalityUpdate::Altair(update) => (
+                LightClientHeader::Altair(update.attested_header.clone()),
+                LightClientHeader::Altair(update.finalized_header.clone()),
+                update.finality_branch.to_vec(),
+            ),
+            LightClientFinalityUpdate::Capella(update) => (
+                LightClientHeader::Capella(update.attested_header.clone()),
+                LightClientHeader::Capella(update.finalized_header.clone()),
+                update.finality_branch.to_vec(),
+            ),
+            LightClientFinalityUpdate::Deneb(update) => (
+                LightClientHeader::Deneb(update.attested_header.clone()),
+                LightClientHeader::Deneb(update.finalized_header.clone()),
+                update.finality_branch.to_vec(),
+            ),
+            LightClientFinalityUpdate::Electra(update) => (
+                LightClientHeader::Electra(update.attested_header.clone()),
+                LightClientHeader::Electra(update.finalized_header.clone()),
+                update.finality_branch.to_vec(),
+            ),
+        };
+        Self {
+            attested_header,
+            finalized_header: Some((finalized_header, finality_branch)),
+            next_sync_committee: None,
+            sync_aggregate: update.sync_aggregate().clone(),
+            signature_slot: *update.signature_slot(),
+        }
+    }
+
+    fn from_optimistic_update(update: &LightClientOptimisticUpdate<E>) -> Self {
+        let attested_header = match update {
+            LightClientOptimisticUpdate::Altair(update) => {
+                LightClientHeader::Altair(update.attested_header.clone())
+            }
+            LightClientOptimisticUpdate::Capella(update) => {
+                LightClientHeader::Capella(update.attested_header.clone())
+            }
+            LightClientOptimisticUpdate::Deneb(update) => {
+                LightClientHeader::Deneb(update.attested_header.clone())
+            }
+            LightClientOptimisticUpdate::Electra(update) => {
+                LightClientHeader::Electra(update.attested_header.clone())
+            }
+        };
+        Self {
+            attested_header,
+            finalized_header: None,
+            next_sync_committee: None,
+            sync_aggregate: update.sync_aggregate().clone(),
+            signature_slot: *update.signature_slot(),
+        }
+    }
+}
+
+/// Checks a light client message from a snapshot against the local chain.
+///
+/// - The attested header must be that of a block known to this node.
+/// - The sync aggregate must be the one included in the canonical block at `signature_slot`,
+///   which must be a child of the attested block. The aggregate's signature was verified against
+///   the local sync committee and genesis validators root when that block was imported.
+/// - The finalized header, if any, must be that of a known block, and both it and the next sync
+///   committee must be proven by their branches against the attested state root.
+///
+/// Returns `Ok(Err(reason))` if the message does not match the local chain.
+fn verify_against_chain<T: BeaconChainTypes>(
+    chain: &BeaconChain<T>,
+    message: MessageToVerify<T::EthSpec>,
+) -> Result<Result<(), String>, BeaconChainError> {
+    let attested_root = message.attested_header.beacon().canonical_root();
+    let Some(attested_block) = chain.store.get_blinded_block(&attested_root)? else {
+        return Ok(Err(format!("attested block {attested_root:?} is unknown")));
+    };
+    if !header_matches_block(&message.attested_header, &attested_block)? {
+        return Ok(Err(format!(
+            "attested header does not match block {attested_root:?}"
+        )));
+    }
+
+    let Some(signature_block_root) =
+        chain.block_root_at_slot(message.signature_slot, WhenSlotSkipped::None)?
+    else {
+        return Ok(Err(format!(
+            "no canonical block at signature slot {}",
+            message.signature_slot
+        )));
+    };
+    let signature_block = chain
+        .store
+        .get_blinded_block(&signature_block_root)?
+        .ok_or(BeaconChainError::MissingBeaconBlock(signature_block_root))?;
+    if signature_block.parent_root() != attested_root {
+        return Ok(Err(format!(
+            "block at signature slot {} is not a child of the attested block",
+            message.signature_slot
+        )));
+    }
+    if signature_block.message().body().sync_aggregate().ok() != Some(&message.sync_aggregate) {
+        return Ok(Err(format!(
+            "sync aggregate does not match block at signature slot {}",
+            message.signature_slot
+        )));
+    }
+
+    let attested_state_root = message.attested_header.beacon().state_root;
+    let is_electra = matches!(message.attested_header, LightClientHeader::Electra(_));
+
+    // A message without finality carries an empty finalized header and branch.
+    if let Some((finalized_header, finality_branch)) = message
+        .finalized_header
+        .filter(|(header, _)| *header.beacon() != BeaconBlockHeader::empty())
+    {
+        let finalized_root = finalized_header.beacon().canonical_root();
+        let Some(finalized_block) = chain.store.get_blinded_block(&finalized_root)? else {
+            return Ok(Err(format!(
+                "finalized block {finalized_root:?} is unknown"
+            )));
+        };
+        if !header_matches_block(&finalized_header, &finalized_block)? {
+            return Ok(Err(format!(
+                "finalized header does not match block {finalized_root:?}"
+            )));
+        }
+        let (depth, index) = if is_electra {
+            (
+                FINALIZED_ROOT_PROOF_LEN_ELECTRA,
+                FINALIZED_ROOT_INDEX_ELECTRA,
+            )
+        } else {
+            (FINALIZED_ROOT_PROOF_LEN, FINALIZED_ROOT_INDEX)
+        };
+        if !verify_branch(
+            finalized_root,
+            &finality_branch,
+            depth,
+            index,
+            attested_state_root,
+        ) {
+            return Ok(Err("invalid finality branch".to_string()));
+        }
+    }
+
+    if let Some((next_sync_committee_root, next_sync_committee_branch)) = message
+        .next_sync_committee
+        .filter(|(_, branch)| branch.iter().any(|node| !node.is_zero()))
+    {
+        let (depth, index) = if is_electra {
+            (
+                NEXT_SYNC_COMMITTEE_PROOF_LEN_ELECTRA,
+                NEXT_SYNC_COMMITTEE_INDEX_ELECTRA,
+            )
+        } else {
+            (NEXT_SYNC_COMMITTEE_PROOF_LEN, NEXT_SYNC_COMMITTEE_INDEX)
+        };
+        if !verify_branch(
+            next_sync_committee_root,
+            &next_sync_committee_branch,
+            depth,
+            index,
+            attested_state_root,
+        ) {
+            return Ok(Err("invalid next sync committee branch".to_string()));
+        }
+    }
+
+    Ok(Ok(()))
+}
+
+/// Checks that `header` is the light client header of `block`, built for the fork of `header`.
+fn header_matches_block<E: EthSpec>(
+    header: &LightClientHeader<E>,
+    block: &SignedBlindedBeaconBlock<E>,
+) -> Result<bool, BeaconChainError> {
+    let expected = match header {
+        LightClientHeader::Altair(_) => LightClientHeader::Altair(
+            LightClientHeaderAltair::block_to_light_client_header(block)?,
+        ),
+        LightClientHeader::Capella(_) => LightClientHeader::Capella(
+            LightClientHeaderCapella::block_to_light_client_header(block)?,
+        ),
+        LightClientHeader::Deneb(_) => {
+            LightClientHeader::Deneb(LightClientHeaderDeneb::block_to_light_client_header(block)?)
+        }
+        LightClientHeader::Electra(_) => LightClientHeader::Electra(
+            LightClientHeaderElectra::block_to_light_client_header(block)?,
+        ),
+    };
+    Ok(expected == *header)
+}
+
+/// Verifies a merkle `branch` for the leaf at generalized index `gindex`.
+fn verify_branch(
+    leaf: Hash256,
+    branch: &[Hash256],
+    depth: usize,
+    gindex: usize,
+    root: Hash256,
+) -> bool {
+    merkle_proof::verify_merkle_proof(leaf, branch, depth, gindex % (1 << depth), root)
+}
+
+/// Tags a light client message with the fork of its `signature_slot`.
+fn fork_versioned<E: EthSpec, D>(
+    signature_slot: Slot,
+    data: D,
+    chain_spec: &ChainSpec,
+) -> ForkVersionedResponse<D> {
+    ForkVersionedResponse {
+        version: Some(chain_spec.fork_name_at_slot::<E>(signature_slot)),
+        metadata: Default::default(),
+        data,
+    }
+}
+
+/// Unwraps a light client message from a snapshot, checking that it is tagged with the fork of its
+/// `signature_slot` on this chain.
+fn check_fork<E: EthSpec, D>(
+    versioned: ForkVersionedResponse<D>,
+    signature_slot: Slot,
+    chain_spec: &ChainSpec,
+) -> Result<D, BeaconChainError> {
+    let fork_name = chain_spec.fork_name_at_slot::<E>(signature_slot);
+    if versioned.version != Some(fork_name) || !fork_name.altair_enabled() {
+        return Err(BeaconChainError::LightClientStoreImportError(format!(
+            "update at signature slot {signature_slot} has version {:?}, expected {fork_name}",
+            versioned.version
+        )));
+    }
+    Ok(versioned.data)
 }
 
 impl<T: BeaconChainTypes> Default for LightClientServerCache<T> {
@@ -24,16 +24,18 @@ pub const VALIDATOR_MONITOR_ATTESTATION_SIMULATOR_SOURCE_ATTESTER_MISS_TOTAL: &s
 /*
  * Block Processing
  */
-pub static BLOCK_PROCESSING_REQUESTS: LazyLock<Result<IntCounter>> = LazyLock::new(|| {
-    try_create_int_counter(
+pub static BLOCK_PROCESSING_REQUESTS: LazyLock<Result<IntCounterVec>> = LazyLock::new(|| {
+    try_create_int_counter_vec(
         "beacon_block_processing_requests_total",
         "Count of blocks submitted for processing",
+        &["fork"],
     )
 });
-pub static BLOCK_PROCESSING_SUCCESSES: LazyLock<Result<IntCounter>> = LazyLock::new(|| {
-    try_create_int_counter(
+pub static BLOCK_PROCESSING_SUCCESSES: LazyLock<Result<IntCounterVec>> = LazyLock::new(|| {
+    try_create_int_counter_vec(
         "beacon_block_processing_successes_total",
         "Count of blocks processed without error",
+        &["fork"],
     )
 });
 // Keeping the existing "snapshot_cache" metric name as it would break existing dashboards
@@ -186,6 +188,25 @@ pub static BLOCK_PRODUCTION_GET_PROPOSER_HEAD_TIMES: LazyLock<Result<Histogram>>
             exponential_buckets(1e-3, 2.0, 8),
         )
     });
+pub static RE_ORG_ANALYSIS_EVALUATIONS_TOTAL: LazyLock<Result<IntCounter>> = LazyLock::new(|| {
+    try_create_int_counter(
+        "beacon_re_org_analysis_evaluations_total",
+        "Count of slots at which proposer re-orgs were simulated under alternative parameters",
+    )
+});
+pub static RE_ORG_ANALYSIS_RE_ORGS_TOTAL: LazyLock<Result<IntCounter>> = LazyLock::new(|| {
+    try_create_int_counter(
+        "beacon_re_org_analysis_re_orgs_total",
+        "Count of simulated proposer re-orgs under alternative parameters",
+    )
+});
+pub static RE_ORG_ANALYSIS_DIVERGENCES_TOTAL: LazyLock<Result<IntCounter>> = LazyLock::new(|| {
+    try_create_int_counter(
+        "beacon_re_org_analysis_divergences_total",
+        "Count of slots at which simulated re-org decisions under alternative and configured \
+            parameters differed",
+    )
+});
 pub static BLOCK_PRODUCTION_STATE_LOAD_TIMES: LazyLock<Result<Histogram>> = LazyLock::new(|| {
     try_create_histogram(
         "beacon_block_production_state_load_seconds",
@@ -248,18 +269,20 @@ pub static BLOCK_SIZE: LazyLock<Result<Histogram>> = LazyLock::new(|| {
 /*
  * Unaggregated Attestation Verification
  */
-pub static UNAGGREGATED_ATTESTATION_PROCESSING_REQUESTS: LazyLock<Result<IntCounter>> =
+pub static UNAGGREGATED_ATTESTATION_PROCESSING_REQUESTS: LazyLock<Result<IntCounterVec>> =
     LazyLock::new(|| {
-        try_create_int_counter(
+        try_create_int_counter_vec(
             "beacon_unaggregated_attestation_processing_requests_total",
             "Count of all unaggregated attestations submitted for processing",
+            &["fork"],
         )
     });
-pub static UNAGGREGATED_ATTESTATION_PROCESSING_SUCCESSES: LazyLock<Result<IntCounter>> =
+pub static UNAGGREGATED_ATTESTATION_PROCESSING_SUCCESSES: LazyLock<Result<IntCounterVec>> =
     LazyLock::new(|| {
-        try_create_int_counter(
+        try_create_int_counter_vec(
             "beacon_unaggregated_attestation_processing_successes_total",
             "Number of unaggregated attestations verified for gossip",
+            &["fork"],
         )
     });
 pub static UNAGGREGATED_ATTESTATION_GOSSIP_VERIFICATION_TIMES: LazyLock<Result<Histogram>> =
@@ -273,18 +296,20 @@ pub static UNAGGREGATED_ATTESTATION_GOSSIP_VERIFICATION_TIMES: LazyLock<Result<H
 /*
  * Aggregated Attestation Verification
  */
-pub static AGGREGATED_ATTESTATION_PROCESSING_REQUESTS: LazyLock<Result<IntCounter>> =
+pub static AGGREGATED_ATTESTATION_PROCESSING_REQUESTS: LazyLock<Result<IntCounterVec>> =
     LazyLock::new(|| {
-        try_create_int_counter(
+        try_create_int_counter_vec(
             "beacon_aggregated_attestation_processing_requests_total",
             "Count of all aggregated attestations submitted for processing",
+            &["fork"],
         )
     });
-pub static AGGREGATED_ATTESTATION_PROCESSING_SUCCESSES: LazyLock<Result<IntCounter>> =
+pub static AGGREGATED_ATTESTATION_PROCESSING_SUCCESSES: LazyLock<Result<IntCounterVec>> =
     LazyLock::new(|| {
-        try_create_int_counter(
+        try_create_int_counter_vec(
             "beacon_aggregated_attestation_processing_successes_total",
             "Number of aggregated attestations verified for gossip",
+            &["fork"],
         )
     });
 pub static AGGREGATED_ATTESTATION_GOSSIP_VERIFICATION_TIMES: LazyLock<Result<Histogram>> =
@@ -508,6 +533,19 @@ pub static ATTESTATION_PRODUCTION_CACHE_PRIME_SECONDS: LazyLock<Result<Histogram
             "Time spent loading a new state from the disk due to a cache miss",
         )
     });
+pub static ATTESTATION_DATA_CACHE_MISSES: LazyLock<Result<IntCounter>> = LazyLock::new(|| {
+    try_create_int_counter(
+        "beacon_attestation_data_cache_misses_total",
+        "Count of attestation data requests which were computed from the head",
+    )
+});
+pub static ATTESTATION_DATA_CACHE_COALESCED_REQUESTS: LazyLock<Result<IntCounter>> =
+    LazyLock::new(|| {
+        try_create_int_counter(
+            "beacon_attestation_data_cache_coalesced_requests_total",
+            "Count of attestation data requests served by a concurrent or prior computation",
+        )
+    });
 
 /*
  * Fork Choice
@@ -542,6 +580,13 @@ pub static FORK_CHOICE_REORG_DISTANCE: LazyLock<Result<IntGauge>> = LazyLock::ne
         "The distance of each re-org of the fork choice algorithm",
     )
 });
+pub static FORK_CHOICE_REORG_DEPTH: LazyLock<Result<Histogram>> = LazyLock::new(|| {
+    try_create_histogram_with_buckets(
+        "beacon_fork_choice_reorg_depth",
+        "The distribution of the depths of re-orgs of the fork choice algorithm",
+        Ok(vec![1.0, 2.0, 3.0, 4.0, 8.0, 16.0, 32.0, 64.0]),
+    )
+});
 pub static FORK_CHOICE_REORG_COUNT_INTEROP: LazyLock<Result<IntCounter>> = LazyLock::new(|| {
     try_create_int_counter(
         "beacon_reorgs_total",
@@ -603,6 +648,13 @@ pub static BALANCES_CACHE_HITS: LazyLock<Result<IntCounter>> = LazyLock::new(||
         "Count of times balances cache fulfils request",
     )
 });
+pub static BALANCES_CACHE_SHARED_VALIDATORS_HITS: LazyLock<Result<IntCounter>> =
+    LazyLock::new(|| {
+        try_create_int_counter(
+            "beacon_balances_cache_shared_validators_hits_total",
+            "Count of times the justified balances are computed from a cached validator registry",
+        )
+    });
 pub static BALANCES_CACHE_MISSES: LazyLock<Result<IntCounter>> = LazyLock::new(|| {
     try_create_int_counter(
         "beacon_balances_cache_misses_total",
@@ -648,6 +700,48 @@ pub static DEFAULT_ETH1_VOTES: LazyLock<Result<IntCounter>> = LazyLock::new(|| {
     )
 });
 
+/*
+ * Clock Sanity
+ */
+pub static CLOCK_SKEW_GOSSIP_ESTIMATE: LazyLock<Result<IntGauge>> = LazyLock::new(|| {
+    try_create_int_gauge(
+        "beacon_clock_skew_gossip_estimate_milliseconds",
+        "Earliest arrival of a recent gossip block relative to the start of its slot. Negative \
+        values indicate that the local clock is behind",
+    )
+});
+pub static CLOCK_SKEW_NTP_OFFSET: LazyLock<Result<IntGauge>> = LazyLock::new(|| {
+    try_create_int_gauge(
+        "beacon_clock_skew_ntp_offset_milliseconds",
+        "Offset of the NTP server clock from the local clock. Positive values indicate that the \
+        local clock is behind",
+    )
+});
+pub static CLOCK_SKEW_NTP_ERRORS: LazyLock<Result<IntCounter>> = LazyLock::new(|| {
+    try_create_int_counter(
+        "beacon_clock_skew_ntp_errors_total",
+        "Count of failed NTP queries",
+    )
+});
+
+/*
+ * Storage Watchdog
+ */
+pub static STORAGE_PRESSURE_LEVEL: LazyLock<Result<IntGauge>> = LazyLock::new(|| {
+    try_create_int_gauge(
+        "beacon_storage_pressure_level",
+        "Current disk space pressure: 0 normal, 1 warning, 2 prune blobs, 3 pause backfill, 4 \
+        read only",
+    )
+});
+pub static STORAGE_PRESSURE_TRANSITIONS: LazyLock<Result<IntCounterVec>> = LazyLock::new(|| {
+    try_create_int_counter_vec(
+        "beacon_storage_pressure_transitions_total",
+        "Count of transitions into each disk space pressure level",
+        &["level"],
+    )
+});
+
 /*
  * Chain Head
  */
@@ -1654,6 +1748,29 @@ pub static BLOB_SIDECAR_INCLUSION_PROOF_COMPUTATION: LazyLock<Result<Histogram>>
             "Time taken to compute blob sidecar inclusion proof",
         )
     });
+pub static BLOB_SIDECAR_INCLUSION_PROOF_PRECOMPUTATION: LazyLock<Result<Histogram>> = LazyLock::new(
+    || {
+        try_create_histogram(
+            "blob_sidecar_inclusion_proof_precomputation_seconds",
+            "Time taken to compute the inclusion proofs of all blob sidecars during block production",
+        )
+    },
+);
+pub static BLOB_SIDECAR_INCLUSION_PROOF_CACHE_HITS: LazyLock<Result<IntCounter>> =
+    LazyLock::new(|| {
+        try_create_int_counter(
+            "blob_sidecar_inclusion_proof_cache_hits_total",
+            "Count of published blocks whose blob inclusion proofs were computed during production",
+        )
+    });
+pub static BLOB_SIDECAR_INCLUSION_PROOF_CACHE_MISSES: LazyLock<Result<IntCounter>> = LazyLock::new(
+    || {
+        try_create_int_counter(
+            "blob_sidecar_inclusion_proof_cache_misses_total",
+            "Count of published blocks whose blob inclusion proofs had to be computed on publication",
+        )
+    },
+);
 pub static DATA_COLUMN_SIDECAR_COMPUTATION: LazyLock<Result<HistogramVec>> = LazyLock::new(|| {
     try_create_histogram_vec_with_buckets(
         "data_column_sidecar_computation_seconds",
@@ -1726,6 +1843,48 @@ pub static BLOBS_FROM_EL_RECEIVED_TOTAL: LazyLock<Result<IntCounter>> = LazyLock
     )
 });
 
+pub static BLOB_ACQUISITION_BLOBS_TOTAL: LazyLock<Result<IntCounterVec>> = LazyLock::new(|| {
+    try_create_int_counter_vec(
+        "beacon_blob_acquisition_blobs_total",
+        "Number of blobs received by each blob acquisition strategy",
+        &["strategy"],
+    )
+});
+
+pub static BLOB_ACQUISITION_SLOT_DELAY: LazyLock<Result<HistogramVec>> = LazyLock::new(|| {
+    try_create_histogram_vec_with_buckets(
+        "beacon_blob_acquisition_slot_delay_seconds",
+        "Delay from the start of the slot until blobs for that slot were received, per strategy",
+        Ok(vec![0.25, 0.5, 1.0, 1.5, 2.0, 3.0, 4.0, 6.0, 8.0, 12.0]),
+        &["strategy"],
+    )
+});
+
+pub static BLOB_ACQUISITION_COMPLETED_TOTAL: LazyLock<Result<IntCounterVec>> =
+    LazyLock::new(|| {
+        try_create_int_counter_vec(
+            "beacon_blob_acquisition_completed_total",
+            "Number of blocks whose data became available after blobs from each strategy",
+            &["strategy"],
+        )
+    });
+
+/*
+ * State request coalescing
+ */
+pub static STATE_REQUESTS_WAITING: LazyLock<Result<IntGauge>> = LazyLock::new(|| {
+    try_create_int_gauge(
+        "beacon_state_requests_waiting",
+        "Number of state requests waiting for an identical in-flight request to load the state",
+    )
+});
+pub static STATE_REQUESTS_COALESCED_TOTAL: LazyLock<Result<IntCounter>> = LazyLock::new(|| {
+    try_create_int_counter(
+        "beacon_state_requests_coalesced_total",
+        "Number of state requests served by the result of an identical in-flight request",
+    )
+});
+
 /*
  * Light server message verification
  */
@@ -1761,6 +1920,18 @@ pub static AGGREGATED_ATTESTATION_SUBSETS: LazyLock<Result<IntCounter>> = LazyLo
         "Count of new aggregated attestations that are subsets of already known aggregates",
     )
 });
+pub static AGGREGATE_SIGNATURE_CACHE_HITS: LazyLock<Result<IntCounter>> = LazyLock::new(|| {
+    try_create_int_counter(
+        "beacon_aggregate_signature_cache_hits_total",
+        "Count of aggregate attestation signatures which were already known to be valid",
+    )
+});
+pub static AGGREGATE_SIGNATURE_CACHE_MISSES: LazyLock<Result<IntCounter>> = LazyLock::new(|| {
+    try_create_int_counter(
+        "beacon_aggregate_signature_cache_misses_total",
+        "Count of aggregate attestation signatures which had to be verified",
+    )
+});
 /*
  * Attestation simulator metrics
  */
@@ -6,6 +6,7 @@ use parking_lot::Mutex;
 use slog::{debug, error, info, warn, Logger};
 use std::collections::{HashMap, HashSet};
 use std::mem;
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::{mpsc, Arc};
 use std::thread;
 use std::time::{Duration, SystemTime, UNIX_EPOCH};
@@ -44,6 +45,8 @@ pub struct BackgroundMigrator<E: EthSpec, Hot: ItemStore<E>, Cold: ItemStore<E>>
     tx_thread: Option<Mutex<(mpsc::Sender<Notification>, thread::JoinHandle<()>)>>,
     /// Genesis block root, for persisting the `PersistedBeaconChain`.
     genesis_block_root: Hash256,
+    /// Set to stop historic state reconstruction, e.g. while disk space is low.
+    reconstruction_paused: Arc<AtomicBool>,
     log: Logger,
 }
 
@@ -123,7 +126,27 @@ pub enum PruningError {
 pub enum Notification {
     Finalization(FinalizationNotification),
     Reconstruction,
-    PruneBlobs(Epoch),
+    PruneBlobs(PruneBlobsNotification),
+}
+
+#[derive(Clone, Copy)]
+pub struct PruneBlobsNotification {
+    data_availability_boundary: Epoch,
+    /// Prune regardless of the `prune_blobs` and `epochs_per_blob_prune` config.
+    force: bool,
+}
+
+impl PruneBlobsNotification {
+    /// Combine two notifications into one which prunes at least as much as both.
+    fn merge(self, other: Self) -> Self {
+        Self {
+            data_availability_boundary: std::cmp::max(
+                self.data_availability_boundary,
+                other.data_availability_boundary,
+            ),
+            force: self.force || other.force,
+        }
+    }
 }
 
 pub struct FinalizationNotification {
@@ -132,6 +155,8 @@ pub struct FinalizationNotification {
     head_tracker: Arc<HeadTracker>,
     prev_migration: Arc<Mutex<PrevMigration>>,
     genesis_block_root: Hash256,
+    /// Set when the migration was requested manually, bypassing `epochs_per_migration`.
+    manual: bool,
 }
 
 impl<E: EthSpec, Hot: ItemStore<E>, Cold: ItemStore<E>> BackgroundMigrator<E, Hot, Cold> {
@@ -147,16 +172,22 @@ impl<E: EthSpec, Hot: ItemStore<E>, Cold: ItemStore<E>> BackgroundMigrator<E, Ho
             epoch: db.get_split_slot().epoch(E::slots_per_epoch()),
             epochs_per_migration: config.epochs_per_migration,
         }));
+        let reconstruction_paused = Arc::new(AtomicBool::new(false));
         let tx_thread = if config.blocking {
             None
         } else {
-            Some(Mutex::new(Self::spawn_thread(db.clone(), log.clone())))
+            Some(Mutex::new(Self::spawn_thread(
+                db.clone(),
+                reconstruction_paused.clone(),
+                log.clone(),
+            )))
         };
         Self {
             db,
             tx_thread,
             prev_migration,
             genesis_block_root,
+            reconstruction_paused,
             log,
         }
     }
@@ -178,6 +209,7 @@ impl<E: EthSpec, Hot: ItemStore<E>, Cold: ItemStore<E>> BackgroundMigrator<E, Ho
             head_tracker,
             prev_migration: self.prev_migration.clone(),
             genesis_block_root: self.genesis_block_root,
+            manual: false,
         };
 
         // Send to background thread if configured, otherwise run in foreground.
@@ -190,7 +222,39 @@ impl<E: EthSpec, Hot: ItemStore<E>, Cold: ItemStore<E>> BackgroundMigrator<E, Ho
         Ok(())
     }
 
+    /// Process a manually requested migration to `finalized_checkpoint`.
+    ///
+    /// This is identical to `process_finalization` except that the `epochs_per_migration` limit
+    /// is ignored, allowing operators to advance the split point after a failed migration.
+    pub fn process_manual_finalization(
+        &self,
+        finalized_state_root: BeaconStateHash,
+        finalized_checkpoint: Checkpoint,
+        head_tracker: Arc<HeadTracker>,
+    ) -> Result<(), BeaconChainError> {
+        let notif = FinalizationNotification {
+            finalized_state_root,
+            finalized_checkpoint,
+            head_tracker,
+            prev_migration: self.prev_migration.clone(),
+            genesis_block_root: self.genesis_block_root,
+            manual: true,
+        };
+
+        if let Some(Notification::Finalization(notif)) =
+            self.send_background_notification(Notification::Finalization(notif))
+        {
+            Self::run_migration(self.db.clone(), notif, &self.log);
+        }
+
+        Ok(())
+    }
+
     pub fn process_reconstruction(&self) {
+        if self.reconstruction_paused.load(Ordering::Relaxed) {
+            debug!(self.log, "Historic state reconstruction is paused");
+            return;
+        }
         if let Some(Notification::Reconstruction) =
             self.send_background_notification(Notification::Reconstruction)
         {
@@ -200,11 +264,36 @@ impl<E: EthSpec, Hot: ItemStore<E>, Cold: ItemStore<E>> BackgroundMigrator<E, Ho
         }
     }
 
+    /// Pause or resume historic state reconstruction.
+    ///
+    /// Pausing takes effect after the batch currently being reconstructed. Reconstruction must be
+    /// restarted with `process_reconstruction` after it is resumed.
+    pub fn set_reconstruction_paused(&self, paused: bool) {
+        self.reconstruction_paused.store(paused, Ordering::Relaxed);
+    }
+
     pub fn process_prune_blobs(&self, data_availability_boundary: Epoch) {
-        if let Some(Notification::PruneBlobs(data_availability_boundary)) =
-            self.send_background_notification(Notification::PruneBlobs(data_availability_boundary))
+        self.prune_blobs(PruneBlobsNotification {
+            data_availability_boundary,
+            force: false,
+        })
+    }
+
+    /// Prune blobs older than the data availability boundary, even if blob pruning is disabled.
+    ///
+    /// This is used to free disk space when it is running low.
+    pub fn process_force_prune_blobs(&self, data_availability_boundary: Epoch) {
+        self.prune_blobs(PruneBlobsNotification {
+            data_availability_boundary,
+            force: true,
+        })
+    }
+
+    fn prune_blobs(&self, notif: PruneBlobsNotification) {
+        if let Some(Notification::PruneBlobs(notif)) =
+            self.send_background_notification(Notification::PruneBlobs(notif))
         {
-            Self::run_prune_blobs(self.db.clone(), data_availability_boundary, &self.log);
+            Self::run_prune_blobs(self.db.clone(), notif, &self.log);
         }
     }
 
@@ -241,10 +330,10 @@ impl<E: EthSpec, Hot: ItemStore<E>, Cold: ItemStore<E>> BackgroundMigrator<E, Ho
 
     pub fn run_prune_blobs(
         db: Arc<HotColdDB<E, Hot, Cold>>,
-        data_availability_boundary: Epoch,
+        notif: PruneBlobsNotification,
         log: &Logger,
     ) {
-        if let Err(e) = db.try_prune_blobs(false, data_availability_boundary) {
+        if let Err(e) = db.try_prune_blobs(notif.force, notif.data_availability_boundary) {
             error!(
                 log,
                 "Blob pruning failed";
@@ -264,7 +353,11 @@ impl<E: EthSpec, Hot: ItemStore<E>, Cold: ItemStore<E>> BackgroundMigrator<E, Ho
 
             // Restart the background thread if it has crashed.
             if let Err(tx_err) = tx.send(notif) {
-                let (new_tx, new_thread) = Self::spawn_thread(self.db.clone(), self.log.clone());
+                let (new_tx, new_thread) = Self::spawn_thread(
+                    self.db.clone(),
+                    self.reconstruction_paused.clone(),
+                    self.log.clone(),
+                );
 
                 *tx = new_tx;
                 let old_thread = mem::replace(thread, new_thread);
@@ -298,7 +391,7 @@ impl<E: EthSpec, Hot: ItemStore<E>, Cold: ItemStore<E>> BackgroundMigrator<E, Ho
         // Do not run too frequently.
         let epoch = notif.finalized_checkpoint.epoch;
         let mut prev_migration = notif.prev_migration.lock();
-        if epoch < prev_migration.epoch + prev_migration.epochs_per_migration {
+        if !notif.manual && epoch < prev_migration.epoch + prev_migration.epochs_per_migration {
             debug!(
                 log,
                 "Database consolidation deferred";
@@ -315,7 +408,16 @@ impl<E: EthSpec, Hot: ItemStore<E>, Cold: ItemStore<E>> BackgroundMigrator<E, Ho
         prev_migration.epoch = epoch;
         drop(prev_migration);
 
-        debug!(log, "Database consolidation started");
+        if notif.manual {
+            info!(
+                log,
+                "Manual database migration started";
+                "finalized_epoch" => epoch,
+                "split_slot" => db.get_split_slot(),
+            );
+        } else {
+            debug!(log, "Database consolidation started");
+        }
 
         let finalized_state_root = notif.finalized_state_root;
         let finalized_block_root = notif.finalized_checkpoint.root;
@@ -414,6 +516,7 @@ impl<E: EthSpec, Hot: ItemStore<E>, Cold: ItemStore<E>> BackgroundMigrator<E, Ho
     /// Return a channel handle for sending requests to the thread.
     fn spawn_thread(
         db: Arc<HotColdDB<E, Hot, Cold>>,
+        reconstruction_paused: Arc<AtomicBool>,
         log: Logger,
     ) -> (mpsc::Sender<Notification>, thread::JoinHandle<()>) {
         let (tx, rx) = mpsc::channel();
@@ -426,7 +529,7 @@ impl<E: EthSpec, Hot: ItemStore<E>, Cold: ItemStore<E>> BackgroundMigrator<E, Ho
                 match notif {
                     Notification::Reconstruction => reconstruction_notif = Some(notif),
                     Notification::Finalization(fin) => finalization_notif = Some(fin),
-                    Notification::PruneBlobs(dab) => prune_blobs_notif = Some(dab),
+                    Notification::PruneBlobs(prune) => prune_blobs_notif = Some(prune),
                 }
                 // Read the rest of the messages in the channel, taking the best of each type.
                 for notif in rx.try_iter() {
@@ -438,13 +541,19 @@ impl<E: EthSpec, Hot: ItemStore<E>, Cold: ItemStore<E>> BackgroundMigrator<E, Ho
                                     > current.finalized_checkpoint.epoch
                                 {
                                     *current = fin;
+                                } else if fin.manual
+                                    && fin.finalized_checkpoint == current.finalized_checkpoint
+                                {
+                                    current.manual = true;
                                 }
                             } else {
                                 finalization_notif = Some(fin);
                             }
                         }
-                        Notification::PruneBlobs(dab) => {
-                            prune_blobs_notif = std::cmp::max(prune_blobs_notif, Some(dab));
+                        Notification::PruneBlobs(prune) => {
+                            prune_blobs_notif = Some(
+                                prune_blobs_notif.map_or(prune, |current| current.merge(prune)),
+                            );
                         }
                     }
                 }
@@ -454,10 +563,12 @@ impl<E: EthSpec, Hot: ItemStore<E>, Cold: ItemStore<E>> BackgroundMigrator<E, Ho
                 if let Some(fin) = finalization_notif {
                     Self::run_migration(db.clone(), fin, &log);
                 }
-                if let Some(dab) = prune_blobs_notif {
-                    Self::run_prune_blobs(db.clone(), dab, &log);
+                if let Some(prune) = prune_blobs_notif {
+                    Self::run_prune_blobs(db.clone(), prune, &log);
                 }
-                if reconstruction_notif.is_some() {
+                // A paused reconstruction is not requeued, and must be restarted when resumed.
+                if reconstruction_notif.is_some() && !reconstruction_paused.load(Ordering::Relaxed)
+                {
                     Self::run_reconstruction(db.clone(), Some(inner_tx.clone()), &log);
                 }
             }
@@ -0,0 +1,263 @@
+//! A last line of defence against publishing slashable attestations on behalf of validator
+//! clients connected to the HTTP API.
+//!
+//! Gossip verification already refuses a second attestation from a validator for the same target
+//! epoch, but it cannot detect surround votes. This is most likely to occur in misconfigured
+//! redundant setups, where two validator clients (possibly with independent slashing protection
+//! databases) run the same keys.
+//!
+//! The attestations of each validator which has submitted an attestation via the HTTP API are
+//! tracked, including those subsequently observed on gossip, and any new attestation from the API
+//! which would be slashable with respect to them is refused.
+use std::collections::{BTreeMap, HashMap};
+use types::{AttestationData, Epoch, Hash256};
+
+/// The number of target epochs for which attestations are retained.
+///
+/// Surround votes spanning a longer period than this will not be detected.
+pub const PUBLISH_SAFETY_RETENTION_EPOCHS: u64 = 256;
+
+/// An attestation which would make a validator slashable if published.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum PublishSafetyViolation {
+    /// The validator has already attested to a different `AttestationData` in the same target
+    /// epoch.
+    DoubleVote {
+        validator_index: u64,
+        target_epoch: Epoch,
+    },
+    /// The attestation surrounds, or is surrounded by, an existing attestation from the validator.
+    SurroundVote {
+        validator_index: u64,
+        existing_source_epoch: Epoch,
+        existing_target_epoch: Epoch,
+    },
+}
+
+#[derive(Debug, Clone, Copy)]
+struct ObservedAttestation {
+    source_epoch: Epoch,
+    data_root: Hash256,
+}
+
+#[derive(Default)]
+pub struct PublishSafety {
+    /// Map from validator index to the attestations observed from that validator, keyed by target
+    /// epoch.
+    validators: HashMap<u64, BTreeMap<Epoch, ObservedAttestation>>,
+    /// The highest target epoch observed so far.
+    latest_epoch: Epoch,
+}
+
+impl PublishSafety {
+    /// Check that none of the `attesting_indices` would be slashable if an attestation with `data`
+    /// were published.
+    pub fn check(
+        &self,
+        data: &AttestationData,
+        data_root: Hash256,
+        attesting_indices: impl IntoIterator<Item = u64>,
+    ) -> Result<(), PublishSafetyViolation> {
+        let source_epoch = data.source.epoch;
+        let target_epoch = data.target.epoch;
+
+        for validator_index in attesting_indices {
+            let Some(attestations) = self.validators.get(&validator_index) else {
+                continue;
+            };
+            for (&existing_target_epoch, existing) in attestations {
+                if existing.data_root == data_root {
+                    continue;
+                }
+                if existing_target_epoch == target_epoch {
+                    return Err(PublishSafetyViolation::DoubleVote {
+                        validator_index,
+                        target_epoch,
+                    });
+                }
+                let surrounds =
+                    source_epoch < existing.source_epoch && existing_target_epoch < target_epoch;
+                let surrounded =
+                    existing.source_epoch < source_epoch && target_epoch < existing_target_epoch;
+                if surrounds || surrounded {
+                    return Err(PublishSafetyViolation::SurroundVote {
+                        validator_index,
+                        existing_source_epoch: existing.source_epoch,
+                        existing_target_epoch,
+                    });
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Check that none of the `attesting_indices` would be slashable if an attestation with `data`
+    /// were published and, if so, record it and start tracking the attesters.
+    ///
+    /// Checking and recording under the same lock ensures that two conflicting attestations
+    /// submitted concurrently cannot both pass the check.
+    pub fn check_and_observe(
+        &mut self,
+        data: &AttestationData,
+        data_root: Hash256,
+        attesting_indices: &[u64],
+    ) -> Result<(), PublishSafetyViolation> {
+        self.check(data, data_root, attesting_indices.iter().copied())?;
+        self.observe(data, data_root, attesting_indices.iter().copied(), true);
+        Ok(())
+    }
+
+    /// Record an attestation with `data` from each of the `attesting_indices`.
+    ///
+    /// If `track` is `false`, the attestation is only recorded for validators which are already
+    /// being tracked. The first attestation observed from a validator for each target epoch is
+    /// retained.
+    pub fn observe(
+        &mut self,
+        data: &AttestationData,
+        data_root: Hash256,
+        attesting_indices: impl IntoIterator<Item = u64>,
+        track: bool,
+    ) {
+        let target_epoch = data.target.epoch;
+        if target_epoch + PUBLISH_SAFETY_RETENTION_EPOCHS <= self.latest_epoch {
+            return;
+        }
+
+        if target_epoch > self.latest_epoch {
+            self.latest_epoch = target_epoch;
+            self.prune();
+        }
+
+        let observed = ObservedAttestation {
+            source_epoch: data.source.epoch,
+            data_root,
+        };
+        for validator_index in attesting_indices {
+            let attestations = if track {
+                self.validators.entry(validator_index).or_default()
+            } else if let Some(attestations) = self.validators.get_mut(&validator_index) {
+                attestations
+            } else {
+                continue;
+            };
+            attestations.entry(target_epoch).or_insert(observed);
+        }
+    }
+
+    /// Remove all attestations which have fallen outside of the retention window.
+    fn prune(&mut self) {
+        let earliest_epoch =
+            (self.latest_epoch + 1).saturating_sub(PUBLISH_SAFETY_RETENTION_EPOCHS);
+        for attestations in self.validators.values_mut() {
+            *attestations = attestations.split_off(&earliest_epoch);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use types::{Checkpoint, FixedBytesExtended};
+
+    fn attestation_data(
+        source_epoch: u64,
+        target_epoch: u64,
+        root: u64,
+    ) -> (AttestationData, Hash256) {
+        let data = AttestationData {
+            source: Checkpoint {
+                epoch: Epoch::new(source_epoch),
+                root: Hash256::zero(),
+            },
+            target: Checkpoint {
+                epoch: Epoch::new(target_epoch),
+                root: Hash256::from_low_u64_be(root),
+            },
+            ..AttestationData::default()
+        };
+        (data, Hash256::from_low_u64_be(root))
+    }
+
+    #[test]
+    fn slashable_attestations_are_refused() {
+        let mut safety = PublishSafety::default();
+        let (data, root) = attestation_data(2, 3, 1);
+        safety.observe(&data, root, [0], true);
+
+        // The same attestation may be published again.
+        assert_eq!(safety.check(&data, root, [0]), Ok(()));
+
+        let (double, double_root) = attestation_data(2, 3, 2);
+        assert_eq!(
+            safety.check(&double, double_root, [0]),
+            Err(PublishSafetyViolation::DoubleVote {
+                validator_index: 0,
+                target_epoch: Epoch::new(3),
+            })
+        );
+
+        let (surrounding, surrounding_root) = attestation_data(1, 4, 3);
+        assert!(matches!(
+            safety.check(&surrounding, surrounding_root, [0]),
+            Err(PublishSafetyViolation::SurroundVote { .. })
+        ));
+
+        // Other validators are unaffected.
+        assert_eq!(safety.check(&double, double_root, [1]), Ok(()));
+        assert_eq!(safety.check(&surrounding, surrounding_root, [1]), Ok(()));
+
+        // Consecutive attestations are not slashable.
+        let (next, next_root) = attestation_data(3, 4, 4);
+        assert_eq!(safety.check(&next, next_root, [0]), Ok(()));
+    }
+
+    #[test]
+    fn check_and_observe_refuses_the_second_of_two_conflicting_attestations() {
+        let mut safety = PublishSafety::default();
+        let (data, root) = attestation_data(2, 3, 1);
+        let (double, double_root) = attestation_data(2, 3, 2);
+
+        assert_eq!(safety.check_and_observe(&data, root, &[0]), Ok(()));
+        assert_eq!(
+            safety.check_and_observe(&double, double_root, &[0]),
+            Err(PublishSafetyViolation::DoubleVote {
+                validator_index: 0,
+                target_epoch: Epoch::new(3),
+            })
+        );
+        // The refused attestation is not recorded, so the first may still be re-published.
+        assert_eq!(safety.check_and_observe(&data, root, &[0]), Ok(()));
+    }
+
+    #[test]
+    fn only_tracked_validators_are_observed() {
+        let mut safety = PublishSafety::default();
+        let (data, root) = attestation_data(2, 3, 1);
+        safety.observe(&data, root, [0, 1], false);
+        safety.observe(&data, root, [1], true);
+
+        let (double, double_root) = attestation_data(2, 3, 2);
+        assert_eq!(safety.check(&double, double_root, [0]), Ok(()));
+        assert!(safety.check(&double, double_root, [1]).is_err());
+
+        // Once tracked, attestations observed on gossip are also checked.
+        let (gossip, gossip_root) = attestation_data(3, 4, 3);
+        safety.observe(&gossip, gossip_root, [1], false);
+        let (double, double_root) = attestation_data(3, 4, 4);
+        assert!(safety.check(&double, double_root, [1]).is_err());
+    }
+
+    #[test]
+    fn old_epochs_are_pruned() {
+        let mut safety = PublishSafety::default();
+        let (first, first_root) = attestation_data(0, 1, 1);
+        safety.observe(&first, first_root, [0], true);
+
+        let (last, last_root) = attestation_data(0, 1 + PUBLISH_SAFETY_RETENTION_EPOCHS, 2);
+        safety.observe(&last, last_root, [0], true);
+
+        let (double, double_root) = attestation_data(0, 1, 3);
+        assert_eq!(safety.check(&double, double_root, [0]), Ok(()));
+    }
+}
@@ -0,0 +1,101 @@
+//! Simulates proposer re-org decisions under alternative parameters without acting on them, so
+//! that parameter changes can be evaluated against live network conditions.
+use crate::{metrics, BeaconChain, BeaconChainTypes};
+use proto_array::{DoNotReOrg, ProposerHeadError, ReOrgSimulationParams, SimulatedReOrg};
+use slog::{debug, info};
+use types::Slot;
+
+/// Describe a simulated re-org decision for logging.
+fn describe_decision(decision: &Result<SimulatedReOrg, DoNotReOrg>) -> String {
+    match decision {
+        Ok(re_org) => format!(
+            "re-org {} block(s) onto {:?}",
+            re_org.depth, re_org.parent_root
+        ),
+        Err(reason) => format!("no re-org: {}", reason),
+    }
+}
+
+impl<T: BeaconChainTypes> BeaconChain<T> {
+    /// The parameters which a proposer at the current slot would use, as `ReOrgSimulationParams`.
+    ///
+    /// Returns `None` if proposer re-orgs are disabled.
+    fn configured_re_org_simulation_params(&self) -> Option<ReOrgSimulationParams> {
+        Some(ReOrgSimulationParams {
+            re_org_head_threshold: self.config.re_org_head_threshold?,
+            re_org_parent_threshold: self.config.re_org_parent_threshold?,
+            proposer_score_boost: self.spec.proposer_score_boost?,
+            max_re_org_depth: 1,
+        })
+    }
+
+    /// Compare the re-org decision of a proposer at `slot` under the configured parameters with
+    /// the decision under the alternative parameters in `ChainConfig::re_org_analysis`, logging
+    /// any divergence.
+    ///
+    /// Both decisions are simulated, so that every slot is analysed regardless of whether this
+    /// node is proposing. The lateness of the head block and the proposal time are not considered
+    /// since they are common to both sets of parameters.
+    pub fn analyze_proposer_re_org(&self, slot: Slot) {
+        let Some(alternative_params) = self.config.re_org_analysis else {
+            return;
+        };
+        let configured_params = self.configured_re_org_simulation_params();
+        let head_block_root = self.canonical_head.cached_head().head_block_root();
+
+        let fork_choice = self.canonical_head.fork_choice_read_lock();
+        let simulate = |params: &ReOrgSimulationParams| match fork_choice.simulate_proposer_head(
+            head_block_root,
+            params,
+            &self.config.re_org_disallowed_offsets,
+            self.config.re_org_max_epochs_since_finalization,
+        ) {
+            Ok(re_org) => Ok(Ok(re_org)),
+            Err(ProposerHeadError::DoNotReOrg(reason)) => Ok(Err(reason)),
+            Err(ProposerHeadError::Error(e)) => Err(e),
+        };
+        let configured = configured_params
+            .as_ref()
+            .map_or(Ok(Err(DoNotReOrg::ReOrgsDisabled)), simulate);
+        let alternative = simulate(&alternative_params);
+        drop(fork_choice);
+
+        let (configured, alternative) = match (configured, alternative) {
+            (Ok(configured), Ok(alternative)) => (configured, alternative),
+            (Err(e), _) | (_, Err(e)) => {
+                debug!(
+                    self.log,
+                    "Unable to simulate proposer re-org";
+                    "error" => ?e,
+                    "slot" => slot,
+                );
+                return;
+            }
+        };
+
+        metrics::inc_counter(&metrics::RE_ORG_ANALYSIS_EVALUATIONS_TOTAL);
+        if alternative.is_ok() {
+            metrics::inc_counter(&metrics::RE_ORG_ANALYSIS_RE_ORGS_TOTAL);
+        }
+
+        if configured.as_ref().ok() != alternative.as_ref().ok() {
+            metrics::inc_counter(&metrics::RE_ORG_ANALYSIS_DIVERGENCES_TOTAL);
+            info!(
+                self.log,
+                "Re-org analysis diverged from configuration";
+                "alternative" => describe_decision(&alternative),
+                "configured" => describe_decision(&configured),
+                "head" => ?head_block_root,
+                "slot" => slot,
+            );
+        } else {
+            debug!(
+                self.log,
+                "Re-org analysis matched configuration";
+                "decision" => describe_decision(&configured),
+                "head" => ?head_block_root,
+                "slot" => slot,
+            );
+        }
+    }
+}
@@ -0,0 +1,31 @@
+use crate::beacon_chain::BeaconChainTypes;
+use slog::{info, Logger};
+use std::sync::Arc;
+use store::{Error, HotColdDB, KeyValueStoreOp};
+
+/// Schema v23 allows epoch boundary states in the hot database to be stored as diffs.
+///
+/// Full states remain valid, so no changes are required to upgrade. States are converted to diffs
+/// when the database is opened with hot tree-states enabled.
+pub fn upgrade_to_v23<T: BeaconChainTypes>(
+    _db: Arc<HotColdDB<T::EthSpec, T::HotStore, T::ColdStore>>,
+    log: Logger,
+) -> Result<Vec<KeyValueStoreOp>, Error> {
+    info!(log, "Upgrading from v22 to v23");
+    Ok(vec![])
+}
+
+/// Prior versions can only read full states, so any hot state diffs are converted to full states.
+///
+/// The conversion is too large to write in a single batch, so each state is converted atomically
+/// and the schema version is only written once every state has been converted. If the downgrade is
+/// interrupted the database remains at v23, with a mix of full states and diffs which v23 can read,
+/// and running the downgrade again converts the remaining diffs.
+pub fn downgrade_from_v23<T: BeaconChainTypes>(
+    db: Arc<HotColdDB<T::EthSpec, T::HotStore, T::ColdStore>>,
+    log: Logger,
+) -> Result<Vec<KeyValueStoreOp>, Error> {
+    info!(log, "Downgrading from v23 to v22");
+    db.convert_hot_state_storage(false)?;
+    Ok(vec![])
+}
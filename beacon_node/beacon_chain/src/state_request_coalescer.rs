@@ -0,0 +1,171 @@
+//! Coalesces concurrent requests to load the same state.
+//!
+//! Loading a state which is not cached can require replaying many blocks. When several requests
+//! for the same state arrive at once (e.g. from a block explorer), only the first performs the
+//! load and the others wait for and share its result.
+use crate::{metrics, BeaconChainError};
+use parking_lot::{Condvar, Mutex};
+use std::collections::hash_map::Entry;
+use std::collections::HashMap;
+use std::sync::Arc;
+use types::{BeaconState, EthSpec, Hash256};
+
+enum LoadStatus<E: EthSpec> {
+    Pending,
+    Loaded(Option<BeaconState<E>>),
+    /// The load failed. Waiting requests perform their own load so that they receive the error.
+    Failed,
+}
+
+struct InFlightLoad<E: EthSpec> {
+    status: Mutex<LoadStatus<E>>,
+    condvar: Condvar,
+}
+
+/// Tracks the states which are currently being loaded.
+pub struct StateRequestCoalescer<E: EthSpec> {
+    in_flight: Mutex<HashMap<Hash256, Arc<InFlightLoad<E>>>>,
+}
+
+impl<E: EthSpec> Default for StateRequestCoalescer<E> {
+    fn default() -> Self {
+        Self {
+            in_flight: Mutex::new(HashMap::new()),
+        }
+    }
+}
+
+impl<E: EthSpec> StateRequestCoalescer<E> {
+    /// Return the state with `state_root`, using `load` to load it unless another request for the
+    /// same state is already in flight, in which case its result is shared.
+    pub fn get_or_load<F>(
+        &self,
+        state_root: Hash256,
+        load: F,
+    ) -> Result<Option<BeaconState<E>>, BeaconChainError>
+    where
+        F: FnOnce() -> Result<Option<BeaconState<E>>, BeaconChainError>,
+    {
+        let (in_flight, is_leader) = match self.in_flight.lock().entry(state_root) {
+            Entry::Occupied(entry) => (entry.get().clone(), false),
+            Entry::Vacant(entry) => {
+                let in_flight = Arc::new(InFlightLoad {
+                    status: Mutex::new(LoadStatus::Pending),
+                    condvar: Condvar::new(),
+                });
+                (entry.insert(in_flight).clone(), true)
+            }
+        };
+
+        if is_leader {
+            // The guard ensures waiting requests are woken even if `load` panics.
+            let guard = LeaderGuard {
+                coalescer: self,
+                state_root,
+                in_flight,
+            };
+            let result = load();
+            *guard.in_flight.status.lock() = match &result {
+                Ok(state) => LoadStatus::Loaded(state.clone()),
+                Err(_) => LoadStatus::Failed,
+            };
+            drop(guard);
+            return result;
+        }
+
+        metrics::inc_gauge(&metrics::STATE_REQUESTS_WAITING);
+        let mut status = in_flight.status.lock();
+        while let LoadStatus::Pending = *status {
+            in_flight.condvar.wait(&mut status);
+        }
+        metrics::dec_gauge(&metrics::STATE_REQUESTS_WAITING);
+
+        match &*status {
+            LoadStatus::Loaded(state) => {
+                metrics::inc_counter(&metrics::STATE_REQUESTS_COALESCED_TOTAL);
+                Ok(state.clone())
+            }
+            LoadStatus::Pending | LoadStatus::Failed => {
+                drop(status);
+                load()
+            }
+        }
+    }
+
+    /// The number of states currently being loaded.
+    pub fn in_flight_count(&self) -> usize {
+        self.in_flight.lock().len()
+    }
+}
+
+/// Completes an in-flight load when dropped, waking all requests waiting on it.
+struct LeaderGuard<'a, E: EthSpec> {
+    coalescer: &'a StateRequestCoalescer<E>,
+    state_root: Hash256,
+    in_flight: Arc<InFlightLoad<E>>,
+}
+
+impl<E: EthSpec> Drop for LeaderGuard<'_, E> {
+    fn drop(&mut self) {
+        self.coalescer.in_flight.lock().remove(&self.state_root);
+        let mut status = self.in_flight.status.lock();
+        if let LoadStatus::Pending = *status {
+            *status = LoadStatus::Failed;
+        }
+        drop(status);
+        self.in_flight.condvar.notify_all();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Barrier;
+    use std::thread;
+    use std::time::Duration;
+    use types::MinimalEthSpec;
+
+    type E = MinimalEthSpec;
+
+    #[test]
+    fn concurrent_requests_share_one_load() {
+        let coalescer = Arc::new(StateRequestCoalescer::<E>::default());
+        let loads = Arc::new(AtomicUsize::new(0));
+        let barrier = Arc::new(Barrier::new(4));
+
+        let handles = (0..4)
+            .map(|_| {
+                let coalescer = coalescer.clone();
+                let loads = loads.clone();
+                let barrier = barrier.clone();
+                thread::spawn(move || {
+                    barrier.wait();
+                    coalescer.get_or_load(Hash256::zero(), || {
+                        loads.fetch_add(1, Ordering::SeqCst);
+                        thread::sleep(Duration::from_millis(200));
+                        Ok(None)
+                    })
+                })
+            })
+            .collect::<Vec<_>>();
+
+        for handle in handles {
+            assert!(handle.join().unwrap().unwrap().is_none());
+        }
+        assert_eq!(loads.load(Ordering::SeqCst), 1);
+        assert_eq!(coalescer.in_flight_count(), 0);
+    }
+
+    #[test]
+    fn failed_load_is_retried_by_waiters() {
+        let coalescer = StateRequestCoalescer::<E>::default();
+        let result =
+            coalescer.get_or_load(Hash256::zero(), || Err(BeaconChainError::UnableToReadSlot));
+        assert!(result.is_err());
+        assert_eq!(coalescer.in_flight_count(), 0);
+
+        let result = coalescer.get_or_load(Hash256::zero(), || Ok(None));
+        assert!(matches!(result, Ok(None)));
+    }
+}
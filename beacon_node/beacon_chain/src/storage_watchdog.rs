@@ -0,0 +1,271 @@
+//! Tracks how close the disk holding the database is to being full.
+//!
+//! As free space falls past each threshold the node escalates through increasingly disruptive
+//! measures to preserve the space it needs to keep following the chain:
+//!
+//! 1. `Warning`: log a warning.
+//! 2. `PruneBlobs`: prune blobs outside the data availability window, even if blob pruning is
+//!    disabled.
+//! 3. `PauseBackfill`: pause backfill sync.
+//! 4. `ReadOnly`: refuse non-essential writes, such as historic state reconstruction.
+//!
+//! Free space is measured by the client, which reports it via
+//! `BeaconChain::update_storage_pressure`.
+use crate::{metrics, BeaconChain, BeaconChainTypes};
+use serde::{Deserialize, Serialize};
+use slog::{info, warn};
+use std::str::FromStr;
+use std::sync::atomic::{AtomicU8, Ordering};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum StoragePressure {
+    Normal,
+    Warning,
+    PruneBlobs,
+    PauseBackfill,
+    ReadOnly,
+}
+
+impl StoragePressure {
+    const ALL: [Self; 5] = [
+        Self::Normal,
+        Self::Warning,
+        Self::PruneBlobs,
+        Self::PauseBackfill,
+        Self::ReadOnly,
+    ];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Normal => "normal",
+            Self::Warning => "warning",
+            Self::PruneBlobs => "prune_blobs",
+            Self::PauseBackfill => "pause_backfill",
+            Self::ReadOnly => "read_only",
+        }
+    }
+}
+
+impl std::fmt::Display for StoragePressure {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "{}", self.as_str())
+    }
+}
+
+/// The percentages of free disk space below which each level of `StoragePressure` applies.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct StorageThresholds {
+    pub warning: u8,
+    pub prune_blobs: u8,
+    pub pause_backfill: u8,
+    pub read_only: u8,
+}
+
+impl Default for StorageThresholds {
+    fn default() -> Self {
+        Self {
+            warning: 10,
+            prune_blobs: 5,
+            pause_backfill: 3,
+            read_only: 1,
+        }
+    }
+}
+
+impl StorageThresholds {
+    /// Returns the pressure applicable when `disk_bytes_free` of `disk_bytes_total` are free.
+    pub fn pressure(&self, disk_bytes_free: u64, disk_bytes_total: u64) -> StoragePressure {
+        if disk_bytes_total == 0 {
+            return StoragePressure::Normal;
+        }
+        let is_below = |percent: u8| {
+            (disk_bytes_free as u128) * 100 < (percent as u128) * (disk_bytes_total as u128)
+        };
+
+        if is_below(self.read_only) {
+            StoragePressure::ReadOnly
+        } else if is_below(self.pause_backfill) {
+            StoragePressure::PauseBackfill
+        } else if is_below(self.prune_blobs) {
+            StoragePressure::PruneBlobs
+        } else if is_below(self.warning) {
+            StoragePressure::Warning
+        } else {
+            StoragePressure::Normal
+        }
+    }
+}
+
+impl FromStr for StorageThresholds {
+    type Err = String;
+
+    /// Parse a comma-separated list of four percentages, e.g. `10,5,3,1`.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let percentages = s
+            .split(',')
+            .map(|percent| {
+                percent
+                    .trim()
+                    .parse::<u8>()
+                    .ok()
+                    .filter(|percent| *percent <= 100)
+                    .ok_or_else(|| format!("Invalid percentage: {}", percent))
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let [warning, prune_blobs, pause_backfill, read_only] = percentages[..] else {
+            return Err(format!(
+                "Expected 4 comma-separated percentages, got {}",
+                percentages.len()
+            ));
+        };
+        if !(warning >= prune_blobs && prune_blobs >= pause_backfill && pause_backfill >= read_only)
+        {
+            return Err("Thresholds must be in decreasing order".to_string());
+        }
+
+        Ok(Self {
+            warning,
+            prune_blobs,
+            pause_backfill,
+            read_only,
+        })
+    }
+}
+
+/// The current `StoragePressure`, shared with the components which respond to it.
+#[derive(Default)]
+pub struct StorageWatchdog {
+    pressure: AtomicU8,
+}
+
+impl StorageWatchdog {
+    pub fn pressure(&self) -> StoragePressure {
+        StoragePressure::ALL
+            .get(self.pressure.load(Ordering::Relaxed) as usize)
+            .copied()
+            .unwrap_or(StoragePressure::Normal)
+    }
+
+    /// Set the current pressure, returning the previous pressure.
+    pub(crate) fn set_pressure(&self, pressure: StoragePressure) -> StoragePressure {
+        let previous = self.pressure.swap(pressure as u8, Ordering::Relaxed);
+        StoragePressure::ALL
+            .get(previous as usize)
+            .copied()
+            .unwrap_or(StoragePressure::Normal)
+    }
+
+    /// Returns `true` if backfill sync should be paused.
+    pub fn backfill_paused(&self) -> bool {
+        self.pressure() >= StoragePressure::PauseBackfill
+    }
+
+    /// Returns `true` if non-essential writes should be refused.
+    pub fn is_read_only(&self) -> bool {
+        self.pressure() >= StoragePressure::ReadOnly
+    }
+}
+
+impl<T: BeaconChainTypes> BeaconChain<T> {
+    /// Update the storage pressure given the free space on the disk holding the database, and
+    /// apply the measures for the new pressure.
+    ///
+    /// Does nothing unless `ChainConfig::storage_watchdog` is set.
+    pub fn update_storage_pressure(&self, disk_bytes_free: u64, disk_bytes_total: u64) {
+        let Some(thresholds) = self.config.storage_watchdog else {
+            return;
+        };
+        let pressure = thresholds.pressure(disk_bytes_free, disk_bytes_total);
+        let previous = self.storage_watchdog.set_pressure(pressure);
+        metrics::set_gauge(&metrics::STORAGE_PRESSURE_LEVEL, pressure as i64);
+
+        if pressure > previous {
+            metrics::inc_counter_vec(&metrics::STORAGE_PRESSURE_TRANSITIONS, &[pressure.as_str()]);
+            warn!(
+                self.log,
+                "Disk space is low";
+                "info" => "free up disk space or increase the size of the disk",
+                "pressure" => %pressure,
+                "disk_bytes_free" => disk_bytes_free,
+                "disk_bytes_total" => disk_bytes_total,
+            );
+        } else if pressure < previous {
+            metrics::inc_counter_vec(&metrics::STORAGE_PRESSURE_TRANSITIONS, &[pressure.as_str()]);
+            info!(
+                self.log,
+                "Disk space pressure reduced";
+                "pressure" => %pressure,
+                "disk_bytes_free" => disk_bytes_free,
+                "disk_bytes_total" => disk_bytes_total,
+            );
+        }
+
+        // Keep pruning while under pressure, since new blobs continue to arrive.
+        if pressure >= StoragePressure::PruneBlobs {
+            if let Some(data_availability_boundary) = self.data_availability_boundary() {
+                self.store_migrator
+                    .process_force_prune_blobs(data_availability_boundary);
+            }
+        }
+
+        // Backfill sync polls `StorageWatchdog::backfill_paused` itself.
+
+        let read_only = pressure >= StoragePressure::ReadOnly;
+        if read_only != (previous >= StoragePressure::ReadOnly) {
+            self.store_migrator.set_reconstruction_paused(read_only);
+            if !read_only
+                && self.config.reconstruct_historic_states
+                && self.store.get_oldest_block_slot() == 0
+            {
+                self.store_migrator.process_reconstruction();
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn pressure_levels() {
+        let thresholds = StorageThresholds::default();
+        assert_eq!(thresholds.pressure(50, 100), StoragePressure::Normal);
+        assert_eq!(thresholds.pressure(10, 100), StoragePressure::Normal);
+        assert_eq!(thresholds.pressure(9, 100), StoragePressure::Warning);
+        assert_eq!(thresholds.pressure(4, 100), StoragePressure::PruneBlobs);
+        assert_eq!(thresholds.pressure(2, 100), StoragePressure::PauseBackfill);
+        assert_eq!(thresholds.pressure(0, 100), StoragePressure::ReadOnly);
+        assert_eq!(thresholds.pressure(0, 0), StoragePressure::Normal);
+    }
+
+    #[test]
+    fn parse_thresholds() {
+        assert_eq!(
+            "20, 10,5,2".parse::<StorageThresholds>(),
+            Ok(StorageThresholds {
+                warning: 20,
+                prune_blobs: 10,
+                pause_backfill: 5,
+                read_only: 2,
+            })
+        );
+        assert!("10,5,3".parse::<StorageThresholds>().is_err());
+        assert!("1,5,3,1".parse::<StorageThresholds>().is_err());
+        assert!("101,5,3,1".parse::<StorageThresholds>().is_err());
+    }
+
+    #[test]
+    fn watchdog_round_trip() {
+        let watchdog = StorageWatchdog::default();
+        assert_eq!(watchdog.pressure(), StoragePressure::Normal);
+        for pressure in StoragePressure::ALL {
+            watchdog.set_pressure(pressure);
+            assert_eq!(watchdog.pressure(), pressure);
+        }
+        assert!(watchdog.backfill_paused());
+        assert!(watchdog.is_read_only());
+    }
+}
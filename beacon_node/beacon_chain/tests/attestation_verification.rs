@@ -1,5 +1,6 @@
 #![cfg(not(debug_assertions))]
 
+use beacon_chain::aggregate_signature_cache::AggregateSignatureCache;
 use beacon_chain::attestation_verification::{
     batch_verify_aggregated_attestations, batch_verify_unaggregated_attestations, Error,
 };
@@ -1433,6 +1434,98 @@ async fn attestation_to_finalized_block() {
         .contains(earlier_block_root));
 }
 
+/// Aggregates of an identical attestation from several aggregators should have the attestation
+/// signature verified once, without accepting a different signature over the same attestation.
+#[tokio::test]
+async fn batch_verify_identical_aggregates() {
+    let harness = get_harness(VALIDATOR_COUNT);
+
+    harness
+        .extend_chain(
+            MainnetEthSpec::slots_per_epoch() as usize * 3 - 1,
+            BlockStrategy::OnCanonicalHead,
+            AttestationStrategy::AllValidators,
+        )
+        .await;
+    harness.advance_slot();
+
+    let (valid_attestation, _, _, _, _) = get_valid_unaggregated_attestation(&harness.chain);
+    let mut invalid_attestation = valid_attestation.clone();
+    *invalid_attestation.signature_mut() = AggregateSignature::infinity();
+
+    // Sign the aggregates with three distinct aggregators from the same committee.
+    let head = harness.chain.head_snapshot();
+    let state = &head.beacon_state;
+    let committee = state
+        .get_beacon_committee(
+            valid_attestation.data().slot,
+            valid_attestation
+                .committee_index()
+                .expect("should get committee index"),
+        )
+        .expect("should get committee");
+    let signed_aggregates = committee
+        .committee
+        .iter()
+        .filter_map(|&val_index| {
+            let aggregator_sk = generate_deterministic_keypair(val_index).sk;
+            let proof = SelectionProof::new::<E>(
+                valid_attestation.data().slot,
+                &aggregator_sk,
+                &state.fork(),
+                harness.chain.genesis_validators_root,
+                &harness.chain.spec,
+            );
+            proof
+                .is_aggregator(committee.committee.len(), &harness.chain.spec)
+                .unwrap()
+                .then_some((val_index, aggregator_sk))
+        })
+        .take(3)
+        .enumerate()
+        .map(|(i, (aggregator_index, aggregator_sk))| {
+            let attestation = if i < 2 {
+                &valid_attestation
+            } else {
+                &invalid_attestation
+            };
+            SignedAggregateAndProof::from_aggregate(
+                aggregator_index as u64,
+                attestation.to_ref(),
+                None,
+                &aggregator_sk,
+                &state.fork(),
+                harness.chain.genesis_validators_root,
+                &harness.chain.spec,
+            )
+        })
+        .collect::<Vec<_>>();
+    assert_eq!(
+        signed_aggregates.len(),
+        3,
+        "the test requires 3 aggregators"
+    );
+
+    let results =
+        batch_verify_aggregated_attestations(signed_aggregates.iter(), &harness.chain).unwrap();
+
+    assert!(results[0].is_ok());
+    assert!(matches!(
+        results[1],
+        Err(AttnError::AttestationSupersetKnown(_))
+    ));
+    assert!(matches!(results[2], Err(AttnError::InvalidSignature)));
+
+    assert!(harness
+        .chain
+        .aggregate_signature_cache
+        .is_verified(&AggregateSignatureCache::key(valid_attestation.to_ref())));
+    assert!(!harness
+        .chain
+        .aggregate_signature_cache
+        .is_verified(&AggregateSignatureCache::key(invalid_attestation.to_ref())));
+}
+
 #[tokio::test]
 async fn verify_aggregate_for_gossip_doppelganger_detection() {
     let harness = get_harness(VALIDATOR_COUNT);
@@ -117,12 +117,22 @@ async fn get_chain_segment_with_blob_sidecars(
 }
 
 fn get_harness(validator_count: usize) -> BeaconChainHarness<EphemeralHarnessType<E>> {
-    let harness = BeaconChainHarness::builder(MainnetEthSpec)
-        .default_spec()
-        .chain_config(ChainConfig {
+    get_harness_with_config(
+        validator_count,
+        ChainConfig {
             reconstruct_historic_states: true,
             ..ChainConfig::default()
-        })
+        },
+    )
+}
+
+fn get_harness_with_config(
+    validator_count: usize,
+    chain_config: ChainConfig,
+) -> BeaconChainHarness<EphemeralHarnessType<E>> {
+    let harness = BeaconChainHarness::builder(MainnetEthSpec)
+        .default_spec()
+        .chain_config(chain_config)
         .keypairs(KEYPAIRS[0..validator_count].to_vec())
         .fresh_ephemeral_store()
         .mock_execution_layer()
@@ -293,6 +303,50 @@ async fn chain_segment_varying_chunk_size() {
     }
 }
 
+#[tokio::test]
+async fn chain_segment_max_length() {
+    for pipeline in [true, false] {
+        for max_length in [1, 5, 32] {
+            let harness = get_harness_with_config(
+                VALIDATOR_COUNT,
+                ChainConfig {
+                    reconstruct_historic_states: true,
+                    max_chain_segment_length: max_length,
+                    pipeline_chain_segment_verification: pipeline,
+                    ..ChainConfig::default()
+                },
+            );
+            let (chain_segment, chain_segment_blobs) = get_chain_segment().await;
+            let blocks = chain_segment_blocks(&chain_segment, &chain_segment_blobs);
+
+            harness
+                .chain
+                .slot_clock
+                .set_slot(blocks.last().unwrap().slot().as_u64());
+
+            harness
+                .chain
+                .process_chain_segment(blocks.clone(), NotifyExecutionLayer::Yes)
+                .await
+                .into_block_error()
+                .unwrap_or_else(|_| {
+                    panic!(
+                        "should import chain segment with max length {} and pipeline {}",
+                        max_length, pipeline
+                    )
+                });
+
+            harness.chain.recompute_head_at_current_slot().await;
+
+            assert_eq!(
+                harness.head_block_root(),
+                blocks.last().unwrap().canonical_root(),
+                "harness should have last block as head"
+            );
+        }
+    }
+}
+
 #[tokio::test]
 async fn chain_segment_non_linear_parent_roots() {
     let harness = get_harness(VALIDATOR_COUNT);
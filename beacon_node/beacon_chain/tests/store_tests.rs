@@ -19,16 +19,22 @@ use logging::test_logger;
 use maplit::hashset;
 use rand::Rng;
 use slot_clock::{SlotClock, TestingSlotClock};
+use ssz::Encode;
 use state_processing::{state_advance::complete_state_advance, BlockReplayer};
 use std::collections::HashMap;
 use std::collections::HashSet;
 use std::convert::TryInto;
 use std::sync::{Arc, LazyLock};
 use std::time::Duration;
-use store::metadata::{SchemaVersion, CURRENT_SCHEMA_VERSION, STATE_UPPER_LIMIT_NO_RETAIN};
+use store::metadata::{
+    SchemaVersion, CURRENT_SCHEMA_VERSION, SCHEMA_VERSION_KEY, STATE_UPPER_LIMIT_NO_RETAIN,
+};
 use store::{
+    config::FsyncPolicy,
+    hdiff::HierarchyConfig,
+    integrity::Corruption,
     iter::{BlockRootsIterator, StateRootsIterator},
-    BlobInfo, DBColumn, HotColdDB, LevelDB, StoreConfig,
+    BlobInfo, DBColumn, HotColdDB, ItemStore, KeyValueStore, LevelDB, StateLoadCost, StoreConfig,
 };
 use tempfile::{tempdir, TempDir};
 use tokio::time::sleep;
@@ -437,6 +443,261 @@ async fn split_slot_restore() {
     assert_eq!(store.get_split_slot(), split_slot);
 }
 
+// Check that the block root filter is rebuilt from the blocks on disk when the DB is reopened.
+#[tokio::test]
+async fn block_root_filter_restore() {
+    let db_path = tempdir().unwrap();
+    let config = StoreConfig {
+        block_root_filter: true,
+        // Small enough that the filter has to grow.
+        block_root_filter_capacity: 4,
+        ..StoreConfig::default()
+    };
+
+    let head_block_root = {
+        let store = get_store_generic(&db_path, config.clone(), test_spec::<E>());
+        let harness = get_harness(store.clone(), LOW_VALIDATOR_COUNT);
+
+        harness
+            .extend_chain(
+                E::slots_per_epoch() as usize,
+                BlockStrategy::OnCanonicalHead,
+                AttestationStrategy::AllValidators,
+            )
+            .await;
+
+        harness.head_block_root()
+    };
+
+    // Re-open the store and wait for the filter to be populated in the background.
+    let store = get_store_generic(&db_path, config, test_spec::<E>());
+    let filter = store.block_root_filter().expect("filter is enabled");
+    for _ in 0..100 {
+        if filter.is_ready() {
+            break;
+        }
+        tokio::time::sleep(Duration::from_millis(50)).await;
+    }
+    assert!(filter.is_ready());
+    assert!(filter.num_layers() > 1);
+    assert!(filter.may_contain(&head_block_root));
+    assert!(store.block_exists(&head_block_root).unwrap());
+
+    let unknown_block_root = Hash256::repeat_byte(0xaa);
+    assert!(!store.block_exists(&unknown_block_root).unwrap());
+}
+
+// Check that blocks and states written via the hot DB's write-ahead log survive a restart.
+#[tokio::test]
+async fn write_batching_restore() {
+    let db_path = tempdir().unwrap();
+    let config = StoreConfig {
+        hot_write_batching: true,
+        hot_fsync_policy: FsyncPolicy::Periodic(Duration::from_secs(60)),
+        ..StoreConfig::default()
+    };
+
+    let (head_block_root, head_state_root, split_slot) = {
+        let store = get_store_generic(&db_path, config.clone(), test_spec::<E>());
+        let harness = get_harness(store.clone(), LOW_VALIDATOR_COUNT);
+
+        let num_blocks = 4 * E::slots_per_epoch();
+        harness
+            .extend_chain(
+                num_blocks as usize,
+                BlockStrategy::OnCanonicalHead,
+                AttestationStrategy::AllValidators,
+            )
+            .await;
+
+        let head = harness.chain.head_snapshot();
+        (
+            head.beacon_block_root,
+            head.beacon_state_root(),
+            store.get_split_slot(),
+        )
+    };
+    assert_ne!(split_slot, Slot::new(0));
+    // A clean shutdown applies all writes and truncates the log.
+    let wal_path = db_path.path().join("chain_db.wal");
+    assert_eq!(std::fs::metadata(&wal_path).unwrap().len(), 0);
+
+    // Re-open the store without write batching.
+    let store = get_store(&db_path);
+    assert!(!wal_path.exists());
+    assert_eq!(store.get_split_slot(), split_slot);
+    assert!(store.block_exists(&head_block_root).unwrap());
+    assert!(store
+        .load_hot_state_summary(&head_state_root)
+        .unwrap()
+        .is_some());
+}
+
+fn num_hot_state_diffs(store: &HotColdDB<E, LevelDB<E>, LevelDB<E>>) -> usize {
+    store
+        .hot_db
+        .iter_column_keys::<Hash256>(DBColumn::BeaconStateHotDiff)
+        .count()
+}
+
+// Return the slots and roots of the epoch boundary states between the split and the head.
+fn hot_epoch_boundary_states(harness: &TestHarness) -> Vec<(Slot, Hash256)> {
+    let split_slot = harness.chain.store.get_split_slot();
+    let head = harness.chain.head_snapshot();
+    (split_slot.as_u64()..head.beacon_state.slot().as_u64())
+        .step_by(E::slots_per_epoch() as usize)
+        .map(|slot| {
+            let slot = Slot::new(slot);
+            (slot, *head.beacon_state.get_state_root(slot).unwrap())
+        })
+        .collect()
+}
+
+fn check_hot_states(store: &HotColdDB<E, LevelDB<E>, LevelDB<E>>, states: &[(Slot, Hash256)]) {
+    for (slot, state_root) in states {
+        let (mut state, _) = store
+            .load_hot_state(state_root)
+            .unwrap()
+            .unwrap_or_else(|| panic!("state at slot {slot} is missing"));
+        assert_eq!(state.slot(), *slot);
+        assert_eq!(state.update_tree_hash_cache().unwrap(), *state_root);
+    }
+}
+
+// Check that hot states are stored as diffs during non-finality, remain loadable when finality
+// resumes, and are converted back to full states when hot tree-states is disabled.
+#[tokio::test]
+async fn hot_tree_states_non_finality() {
+    let db_path = tempdir().unwrap();
+    let config = StoreConfig {
+        hot_tree_states: true,
+        ..StoreConfig::default()
+    };
+
+    let hot_states = {
+        let store = get_store_generic(&db_path, config.clone(), test_spec::<E>());
+        let harness = get_harness(store.clone(), LOW_VALIDATOR_COUNT);
+
+        harness
+            .extend_chain(
+                4 * E::slots_per_epoch() as usize,
+                BlockStrategy::OnCanonicalHead,
+                AttestationStrategy::AllValidators,
+            )
+            .await;
+        let finalized_split_slot = store.get_split_slot();
+        assert_ne!(finalized_split_slot, Slot::new(0));
+
+        // Stop attesting so that finality stalls.
+        harness
+            .extend_chain(
+                5 * E::slots_per_epoch() as usize,
+                BlockStrategy::OnCanonicalHead,
+                AttestationStrategy::SomeValidators(vec![]),
+            )
+            .await;
+        assert_eq!(store.get_split_slot(), finalized_split_slot);
+        assert!(num_hot_state_diffs(&store) > 0);
+        check_hot_states(&store, &hot_epoch_boundary_states(&harness));
+
+        // Finality resumes, pruning the base states of some diffs.
+        harness
+            .extend_chain(
+                4 * E::slots_per_epoch() as usize,
+                BlockStrategy::OnCanonicalHead,
+                AttestationStrategy::AllValidators,
+            )
+            .await;
+        assert!(store.get_split_slot() > finalized_split_slot);
+        check_hot_states(&store, &hot_epoch_boundary_states(&harness));
+
+        // Stall finality again so that there are diffs to convert.
+        harness
+            .extend_chain(
+                3 * E::slots_per_epoch() as usize,
+                BlockStrategy::OnCanonicalHead,
+                AttestationStrategy::SomeValidators(vec![]),
+            )
+            .await;
+        assert!(num_hot_state_diffs(&store) > 0);
+        hot_epoch_boundary_states(&harness)
+    };
+
+    // Re-open the store with hot tree-states disabled.
+    let store = get_store(&db_path);
+    assert_eq!(num_hot_state_diffs(&store), 0);
+    check_hot_states(&store, &hot_states);
+}
+
+// Check that downgrading to v22 converts hot state diffs to full states, and that a downgrade which
+// is interrupted part way through is completed when it is run again after re-opening the database.
+#[tokio::test]
+async fn hot_tree_states_schema_downgrade() {
+    let db_path = tempdir().unwrap();
+    let config = StoreConfig {
+        hot_tree_states: true,
+        ..StoreConfig::default()
+    };
+
+    let (hot_states, genesis_state_root) = {
+        let store = get_store_generic(&db_path, config.clone(), test_spec::<E>());
+        let harness = get_harness(store.clone(), LOW_VALIDATOR_COUNT);
+
+        harness
+            .extend_chain(
+                4 * E::slots_per_epoch() as usize,
+                BlockStrategy::OnCanonicalHead,
+                AttestationStrategy::AllValidators,
+            )
+            .await;
+        harness
+            .extend_chain(
+                3 * E::slots_per_epoch() as usize,
+                BlockStrategy::OnCanonicalHead,
+                AttestationStrategy::SomeValidators(vec![]),
+            )
+            .await;
+        let num_diffs = num_hot_state_diffs(&store);
+        assert!(num_diffs > 1);
+
+        // Interrupt the downgrade after converting a single state.
+        assert!(!store.convert_hot_state_storage_up_to(false, 1).unwrap());
+        assert_eq!(num_hot_state_diffs(&store), num_diffs - 1);
+        assert_eq!(
+            store
+                .hot_db
+                .get::<SchemaVersion>(&SCHEMA_VERSION_KEY)
+                .unwrap(),
+            Some(CURRENT_SCHEMA_VERSION)
+        );
+
+        let hot_states = hot_epoch_boundary_states(&harness);
+        check_hot_states(&store, &hot_states);
+        (hot_states, harness.chain.genesis_state_root)
+    };
+
+    // Re-open the database, which remains readable at v23, and run the downgrade again.
+    let store = get_store_generic(&db_path, config, test_spec::<E>());
+    check_hot_states(&store, &hot_states);
+    migrate_schema::<DiskHarnessType<E>>(
+        store.clone(),
+        Some(genesis_state_root),
+        CURRENT_SCHEMA_VERSION,
+        SchemaVersion(22),
+        store.logger().clone(),
+    )
+    .expect("schema downgrade from v23 should work");
+    assert_eq!(num_hot_state_diffs(&store), 0);
+    assert_eq!(
+        store
+            .hot_db
+            .get::<SchemaVersion>(&SCHEMA_VERSION_KEY)
+            .unwrap(),
+        Some(SchemaVersion(22))
+    );
+    check_hot_states(&store, &hot_states);
+}
+
 // Check attestation processing and `load_epoch_boundary_state` in the presence of a split DB.
 // This is a bit of a monster test in that it tests lots of different things, but until they're
 // tested elsewhere, this is as good a place as any.
@@ -3096,6 +3357,87 @@ async fn deneb_prune_blobs_happy_case() {
     check_blob_existence(&harness, oldest_blob_slot, harness.head_slot(), true);
 }
 
+/// Check that blob repair removes invalid blob lists and stops claiming blobs which are missing.
+#[tokio::test]
+async fn repair_blobs_db() {
+    let db_path = tempdir().unwrap();
+    let store = get_store(&db_path);
+
+    let Some(deneb_fork_epoch) = store.get_chain_spec().deneb_fork_epoch else {
+        // No-op prior to Deneb.
+        return;
+    };
+    if store
+        .get_chain_spec()
+        .is_peer_das_enabled_for_epoch(deneb_fork_epoch)
+    {
+        // Blobs are replaced by data columns.
+        return;
+    }
+    let deneb_fork_slot = deneb_fork_epoch.start_slot(E::slots_per_epoch());
+
+    let num_blocks_produced = E::slots_per_epoch() * 8;
+    let harness = get_harness(store.clone(), LOW_VALIDATOR_COUNT);
+
+    harness
+        .extend_chain(
+            num_blocks_produced as usize,
+            BlockStrategy::OnCanonicalHead,
+            AttestationStrategy::AllValidators,
+        )
+        .await;
+
+    let report = store.repair_blobs_db(false).unwrap();
+    assert!(!report.is_repaired(), "{report:?}");
+    assert_eq!(report.oldest_blob_slot, Some(deneb_fork_slot));
+
+    // Move the blobs of the last finalized block which has blobs under another root.
+    let split_slot = store.get_split_slot();
+    let (block_root, slot) = harness
+        .chain
+        .forwards_iter_block_roots_until(deneb_fork_slot, split_slot - 1)
+        .unwrap()
+        .map(Result::unwrap)
+        .filter(|(block_root, _)| store.blobs_exist(block_root).unwrap())
+        .last()
+        .unwrap();
+    let other_root = Hash256::repeat_byte(0xaa);
+    let blob_bytes = store
+        .blobs_db
+        .get_bytes(DBColumn::BeaconBlob.into(), block_root.as_slice())
+        .unwrap()
+        .unwrap();
+    store
+        .blobs_db
+        .put_bytes(
+            DBColumn::BeaconBlob.into(),
+            other_root.as_slice(),
+            &blob_bytes,
+        )
+        .unwrap();
+    store
+        .blobs_db
+        .key_delete(DBColumn::BeaconBlob.into(), block_root.as_slice())
+        .unwrap();
+
+    // A dry run reports the repair without making it.
+    let report = store.repair_blobs_db(true).unwrap();
+    assert_eq!(report.invalid_removed, 1);
+    assert_eq!(report.orphans_removed, 0);
+    assert_eq!(report.oldest_blob_slot, Some(slot + 1));
+    assert!(store.blobs_exist(&other_root).unwrap());
+    assert_eq!(
+        store.get_blob_info().oldest_blob_slot,
+        Some(deneb_fork_slot)
+    );
+
+    let repaired = store.repair_blobs_db(false).unwrap();
+    assert_eq!(repaired, report);
+    assert!(!store.blobs_exist(&other_root).unwrap());
+    assert_eq!(store.get_blob_info().oldest_blob_slot, Some(slot + 1));
+    check_blob_existence(&harness, slot + 1, harness.head_slot(), true);
+}
+
 /// Check that blob pruning does not prune without finalization.
 #[tokio::test]
 async fn deneb_prune_blobs_no_finalization() {
@@ -3415,6 +3757,254 @@ async fn prune_historic_states() {
     check_split_slot(&harness, store);
 }
 
+#[tokio::test]
+async fn cold_state_ssz_matches_state() {
+    let num_blocks_produced = E::slots_per_epoch() * 6;
+    let db_path = tempdir().unwrap();
+    let config = StoreConfig {
+        hierarchy_config: HierarchyConfig {
+            exponents: vec![1, 3, 5],
+        },
+        ..StoreConfig::default()
+    };
+    let store = get_store_generic(&db_path, config, test_spec::<E>());
+    let harness = get_harness(store.clone(), LOW_VALIDATOR_COUNT);
+
+    harness
+        .extend_chain(
+            num_blocks_produced as usize,
+            BlockStrategy::OnCanonicalHead,
+            AttestationStrategy::AllValidators,
+        )
+        .await;
+
+    let split_slot = store.get_split_slot();
+    assert!(split_slot > 32);
+
+    for slot in (0..split_slot.as_u64()).map(Slot::new) {
+        // Load the SSZ first so that it isn't served from the historic state cache.
+        let ssz = store.load_cold_state_ssz_by_slot(slot).unwrap();
+        let state = store.load_cold_state_by_slot(slot).unwrap();
+
+        // Slots which are multiples of 2 are stored as snapshots or diffs, the rest are replayed.
+        if slot.as_u64() % 2 == 0 {
+            assert_eq!(ssz, Some(state.as_ssz_bytes()), "slot {slot}");
+        } else {
+            assert_eq!(ssz, None, "slot {slot}");
+        }
+    }
+}
+
+#[tokio::test]
+async fn estimate_cold_state_load_cost() {
+    let num_blocks_produced = E::slots_per_epoch() * 6;
+    let db_path = tempdir().unwrap();
+    let config = StoreConfig {
+        hierarchy_config: HierarchyConfig {
+            exponents: vec![1, 3, 5],
+        },
+        ..StoreConfig::default()
+    };
+    let store = get_store_generic(&db_path, config, test_spec::<E>());
+    let harness = get_harness(store.clone(), LOW_VALIDATOR_COUNT);
+
+    harness
+        .extend_chain(
+            num_blocks_produced as usize,
+            BlockStrategy::OnCanonicalHead,
+            AttestationStrategy::AllValidators,
+        )
+        .await;
+
+    let split_slot = store.get_split_slot();
+    assert!(split_slot > 32);
+
+    // With diff layers every 2, 8 and 32 slots, at most 1 block is replayed on top of a state
+    // which is built from at most 2 diffs.
+    for slot in (0..split_slot.as_u64()).map(Slot::new) {
+        let cost = store.estimate_cold_state_load_cost(slot).unwrap();
+        assert!(cost.blocks_to_replay <= 1, "slot {slot}: {cost:?}");
+        assert!(cost.diffs_to_apply <= 2, "slot {slot}: {cost:?}");
+    }
+
+    // Once a state is cached, it and its descendants are cheap to load.
+    store.load_cold_state_by_slot(Slot::new(4)).unwrap();
+    assert_eq!(
+        store.estimate_cold_state_load_cost(Slot::new(4)).unwrap(),
+        StateLoadCost::default()
+    );
+    assert_eq!(
+        store.estimate_cold_state_load_cost(Slot::new(5)).unwrap(),
+        StateLoadCost {
+            blocks_to_replay: 1,
+            diffs_to_apply: 0,
+        }
+    );
+}
+
+#[tokio::test]
+async fn verify_freezer_integrity() {
+    let num_blocks_produced = E::slots_per_epoch() * 6;
+    let db_path = tempdir().unwrap();
+    let config = StoreConfig {
+        hierarchy_config: HierarchyConfig {
+            exponents: vec![1, 3, 5],
+        },
+        ..StoreConfig::default()
+    };
+    let store = get_store_generic(&db_path, config, test_spec::<E>());
+    let harness = get_harness(store.clone(), LOW_VALIDATOR_COUNT);
+
+    harness
+        .extend_chain(
+            num_blocks_produced as usize,
+            BlockStrategy::OnCanonicalHead,
+            AttestationStrategy::AllValidators,
+        )
+        .await;
+
+    let split_slot = store.get_split_slot();
+    assert!(split_slot > 32);
+
+    let report = store
+        .verify_freezer_integrity(Slot::new(0), split_slot, true)
+        .unwrap();
+    assert_eq!(report.corruptions, vec![]);
+    assert_eq!(report.blocks_checked, split_slot.as_u64());
+    // States at even slots are stored as snapshots or diffs.
+    assert_eq!(report.states_checked, split_slot.as_u64() / 2);
+
+    // Overwrite the block at slot 1 with the block at slot 2.
+    let block_root_at = |slot| {
+        harness
+            .chain
+            .block_root_at_slot(Slot::new(slot), WhenSlotSkipped::None)
+            .unwrap()
+            .unwrap()
+    };
+    let block_root = block_root_at(1);
+    let other_block_bytes = store
+        .hot_db
+        .get_bytes(DBColumn::BeaconBlock.into(), block_root_at(2).as_slice())
+        .unwrap()
+        .unwrap();
+    store
+        .hot_db
+        .put_bytes(
+            DBColumn::BeaconBlock.into(),
+            block_root.as_slice(),
+            &other_block_bytes,
+        )
+        .unwrap();
+
+    let report = store
+        .verify_freezer_integrity(Slot::new(0), split_slot, false)
+        .unwrap();
+    assert_eq!(report.states_checked, 0);
+    assert_eq!(report.corruptions.len(), 1);
+    assert!(matches!(
+        report.corruptions[0],
+        Corruption::InvalidBlock { slot, block_root: root, .. }
+            if slot == 1 && root == block_root
+    ));
+}
+
+#[tokio::test]
+async fn epoch_summaries_written_at_finalization() {
+    let num_blocks_produced = E::slots_per_epoch() * 6;
+    let db_path = tempdir().unwrap();
+    let store = get_store(&db_path);
+    let harness = get_harness(store.clone(), LOW_VALIDATOR_COUNT);
+
+    harness
+        .extend_chain(
+            num_blocks_produced as usize,
+            BlockStrategy::OnCanonicalHead,
+            AttestationStrategy::AllValidators,
+        )
+        .await;
+
+    let split_epoch = store.get_split_slot().epoch(E::slots_per_epoch());
+    assert!(split_epoch > 2);
+
+    // An epoch is summarised once the last state of the following epoch has been frozen.
+    for epoch in (0..split_epoch.as_u64() - 1).map(Epoch::new) {
+        let summary = store.get_epoch_summary(epoch).unwrap().unwrap();
+        assert_eq!(summary.epoch, epoch);
+        assert_eq!(summary.blocks.len() as u64, E::slots_per_epoch());
+        for block in &summary.blocks {
+            let block_root = harness
+                .chain
+                .block_root_at_slot(block.slot, WhenSlotSkipped::None)
+                .unwrap()
+                .unwrap();
+            assert_eq!(block.block_root, block_root);
+            let stored_block = store.get_blinded_block(&block_root).unwrap().unwrap();
+            assert_eq!(
+                block.proposer_index,
+                stored_block.message().proposer_index()
+            );
+        }
+
+        // Attestations are only produced from slot 1, so only later epochs have full
+        // participation.
+        if let Some(participation) = summary.participation.filter(|_| epoch > 0) {
+            assert_eq!(participation.active_validators, LOW_VALIDATOR_COUNT as u64);
+            assert_eq!(participation.timely_target, LOW_VALIDATOR_COUNT as u64);
+        }
+    }
+    assert_eq!(store.get_epoch_summary(split_epoch - 1).unwrap(), None);
+}
+
+#[tokio::test]
+async fn epoch_summaries_backfilled_for_epochs_finalized_earlier() {
+    let num_blocks_produced = E::slots_per_epoch() * 6;
+    let db_path = tempdir().unwrap();
+    let store = get_store(&db_path);
+    let harness = get_harness(store.clone(), LOW_VALIDATOR_COUNT);
+
+    harness
+        .extend_chain(
+            num_blocks_produced as usize,
+            BlockStrategy::OnCanonicalHead,
+            AttestationStrategy::AllValidators,
+        )
+        .await;
+
+    let split_epoch = store.get_split_slot().epoch(E::slots_per_epoch());
+    let summarised_epochs = (0..split_epoch.as_u64() - 1)
+        .map(Epoch::new)
+        .collect::<Vec<_>>();
+    let summaries = summarised_epochs
+        .iter()
+        .map(|epoch| store.get_epoch_summary(*epoch).unwrap().unwrap())
+        .collect::<Vec<_>>();
+
+    // Simulate epochs which were finalized before summaries were written.
+    for epoch in &summarised_epochs {
+        store
+            .cold_db
+            .key_delete(
+                DBColumn::BeaconEpochSummary.into(),
+                &epoch.as_u64().to_be_bytes(),
+            )
+            .unwrap();
+        assert_eq!(store.get_epoch_summary(*epoch).unwrap(), None);
+    }
+
+    // The backfilled summaries match those written at finalization.
+    assert_eq!(
+        store.backfill_epoch_summaries().unwrap(),
+        summarised_epochs.len()
+    );
+    for (epoch, summary) in summarised_epochs.iter().zip(summaries) {
+        assert_eq!(store.get_epoch_summary(*epoch).unwrap(), Some(summary));
+    }
+
+    // Summaries which already exist are not recomputed.
+    assert_eq!(store.backfill_epoch_summaries().unwrap(), 0);
+}
+
 /// Checks that two chains are the same, for the purpose of these tests.
 ///
 /// Several fields that are hard/impossible to check are ignored (e.g., the store).
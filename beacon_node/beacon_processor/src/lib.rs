@@ -45,19 +45,19 @@ use futures::stream::{Stream, StreamExt};
 use futures::task::Poll;
 use lighthouse_network::{MessageId, NetworkGlobals, PeerId};
 use logging::TimeLatch;
-use parking_lot::Mutex;
+use parking_lot::{Mutex, RwLock};
 use serde::{Deserialize, Serialize};
 use slog::{crit, debug, error, trace, warn, Logger};
 use slot_clock::SlotClock;
 use std::cmp;
-use std::collections::{HashSet, VecDeque};
+use std::collections::{BTreeMap, HashSet, VecDeque};
 use std::fmt;
 use std::future::Future;
 use std::pin::Pin;
 use std::sync::Arc;
 use std::task::Context;
 use std::time::Duration;
-use strum::IntoStaticStr;
+use strum::{EnumString, IntoStaticStr};
 use task_executor::TaskExecutor;
 use tokio::sync::mpsc;
 use tokio::sync::mpsc::error::TrySendError;
@@ -69,7 +69,7 @@ use work_reprocessing_queue::{
     spawn_reprocess_scheduler, QueuedAggregate, QueuedLightClientUpdate, QueuedRpcBlock,
     QueuedUnaggregate, ReadyWork,
 };
-use work_reprocessing_queue::{IgnoredRpcBlock, QueuedSamplingRequest};
+use work_reprocessing_queue::{IgnoredRpcBlock, QueuedSamplingRequest, QueuedUnknownParentBlock};
 
 mod metrics;
 pub mod work_reprocessing_queue;
@@ -208,6 +208,74 @@ impl BeaconProcessorQueueLengths {
             api_request_p1_queue: 1024,
         })
     }
+
+    /// Returns a mutable reference to the capacity of the queue which holds `work_type`, or `None`
+    /// if that work type is never queued.
+    fn queue_length_mut(&mut self, work_type: WorkType) -> Option<&mut usize> {
+        let queue_length = match work_type {
+            WorkType::GossipAttestation => &mut self.attestation_queue,
+            WorkType::UnknownBlockAttestation => &mut self.unknown_block_attestation_queue,
+            WorkType::GossipAggregate => &mut self.aggregate_queue,
+            WorkType::UnknownBlockAggregate => &mut self.unknown_block_aggregate_queue,
+            WorkType::UnknownLightClientOptimisticUpdate => {
+                &mut self.unknown_light_client_update_queue
+            }
+            WorkType::UnknownBlockSamplingRequest => &mut self.unknown_block_sampling_request_queue,
+            WorkType::GossipBlock => &mut self.gossip_block_queue,
+            WorkType::GossipBlobSidecar => &mut self.gossip_blob_queue,
+            WorkType::GossipDataColumnSidecar => &mut self.gossip_data_column_queue,
+            WorkType::DelayedImportBlock => &mut self.delayed_block_queue,
+            WorkType::GossipVoluntaryExit => &mut self.gossip_voluntary_exit_queue,
+            WorkType::GossipProposerSlashing => &mut self.gossip_proposer_slashing_queue,
+            WorkType::GossipAttesterSlashing => &mut self.gossip_attester_slashing_queue,
+            WorkType::GossipSyncSignature => &mut self.sync_message_queue,
+            WorkType::GossipSyncContribution => &mut self.sync_contribution_queue,
+            WorkType::GossipLightClientFinalityUpdate => &mut self.finality_update_queue,
+            WorkType::GossipLightClientOptimisticUpdate => &mut self.optimistic_update_queue,
+            WorkType::RpcBlock | WorkType::IgnoredRpcBlock => &mut self.rpc_block_queue,
+            WorkType::RpcBlobs => &mut self.rpc_blob_queue,
+            WorkType::RpcCustodyColumn => &mut self.rpc_custody_column_queue,
+            WorkType::RpcVerifyDataColumn => &mut self.rpc_verify_data_column_queue,
+            WorkType::SamplingResult => &mut self.sampling_result_queue,
+            WorkType::ChainSegment => &mut self.chain_segment_queue,
+            WorkType::ChainSegmentBackfill => &mut self.backfill_chain_segment,
+            WorkType::Status => &mut self.status_queue,
+            WorkType::BlocksByRangeRequest => &mut self.bbrange_queue,
+            WorkType::BlocksByRootsRequest => &mut self.bbroots_queue,
+            WorkType::BlobsByRangeRequest => &mut self.blbrange_queue,
+            WorkType::BlobsByRootsRequest => &mut self.blbroots_queue,
+            WorkType::DataColumnsByRootsRequest => &mut self.dcbroots_queue,
+            WorkType::DataColumnsByRangeRequest => &mut self.dcbrange_queue,
+            WorkType::GossipBlsToExecutionChange => &mut self.gossip_bls_to_execution_change_queue,
+            WorkType::LightClientBootstrapRequest => &mut self.lc_bootstrap_queue,
+            WorkType::LightClientOptimisticUpdateRequest => &mut self.lc_optimistic_update_queue,
+            WorkType::LightClientFinalityUpdateRequest => &mut self.lc_finality_update_queue,
+            WorkType::LightClientUpdatesByRangeRequest => &mut self.lc_update_range_queue,
+            WorkType::ApiRequestP0 => &mut self.api_request_p0_queue,
+            WorkType::ApiRequestP1 => &mut self.api_request_p1_queue,
+            // Batches are formed from the attestation and aggregate queues, they have no queue of
+            // their own.
+            WorkType::GossipAttestationBatch | WorkType::GossipAggregateBatch => return None,
+        };
+        Some(queue_length)
+    }
+
+    /// Replace the capacity of the queues listed in `overrides`.
+    pub fn apply_overrides(&mut self, overrides: &[(WorkType, usize)]) -> Result<(), String> {
+        for &(work_type, length) in overrides {
+            if length == 0 {
+                return Err(format!(
+                    "Queue length for {} must be greater than zero",
+                    <&'static str>::from(work_type)
+                ));
+            }
+            let queue_length = self.queue_length_mut(work_type).ok_or_else(|| {
+                format!("{} does not have a queue", <&'static str>::from(work_type))
+            })?;
+            *queue_length = length;
+        }
+        Ok(())
+    }
 }
 
 /// The name of the manager tokio task.
@@ -242,6 +310,11 @@ pub struct BeaconProcessorConfig {
     pub max_gossip_attestation_batch_size: usize,
     pub max_gossip_aggregate_batch_size: usize,
     pub enable_backfill_rate_limiting: bool,
+    /// Capacities which replace the defaults computed by `BeaconProcessorQueueLengths`.
+    pub queue_length_overrides: Vec<(WorkType, usize)>,
+    /// Work types which are checked, in order, before the default priority ordering whenever a
+    /// worker becomes free.
+    pub prioritized_work_types: Vec<WorkType>,
 }
 
 impl Default for BeaconProcessorConfig {
@@ -253,6 +326,8 @@ impl Default for BeaconProcessorConfig {
             max_gossip_attestation_batch_size: DEFAULT_MAX_GOSSIP_ATTESTATION_BATCH_SIZE,
             max_gossip_aggregate_batch_size: DEFAULT_MAX_GOSSIP_AGGREGATE_BATCH_SIZE,
             enable_backfill_rate_limiting: true,
+            queue_length_overrides: vec![],
+            prioritized_work_types: vec![],
         }
     }
 }
@@ -287,10 +362,42 @@ impl<E: EthSpec> Default for BeaconProcessorChannels<E> {
     }
 }
 
+/// A point-in-time summary of a single work queue.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub struct QueueStats {
+    /// The number of items currently in the queue.
+    pub length: usize,
+    /// The maximum number of items the queue will hold.
+    pub capacity: usize,
+    /// The number of items dropped because the queue was full, since startup.
+    pub dropped: u64,
+}
+
+/// Shares the state of the `BeaconProcessor` work queues with other components (e.g., the HTTP
+/// API).
+///
+/// Only queues which have been modified since startup are present.
+#[derive(Clone, Default)]
+pub struct BeaconProcessorQueueStats {
+    inner: Arc<RwLock<BTreeMap<&'static str, QueueStats>>>,
+}
+
+impl BeaconProcessorQueueStats {
+    /// Returns a copy of the stats for each queue, keyed by work type.
+    pub fn snapshot(&self) -> BTreeMap<&'static str, QueueStats> {
+        self.inner.read().clone()
+    }
+
+    fn update(&self, work_type: WorkType, stats: QueueStats) {
+        self.inner.write().insert(work_type.into(), stats);
+    }
+}
+
 /// A simple first-in-first-out queue with a maximum length.
 struct FifoQueue<T> {
     queue: VecDeque<T>,
     max_length: usize,
+    dropped: u64,
 }
 
 impl<T> FifoQueue<T> {
@@ -299,6 +406,7 @@ impl<T> FifoQueue<T> {
         Self {
             queue: VecDeque::default(),
             max_length,
+            dropped: 0,
         }
     }
 
@@ -313,7 +421,8 @@ impl<T> FifoQueue<T> {
                 "msg" => "the system has insufficient resources for load",
                 "queue_len" => self.max_length,
                 "queue" => item_desc,
-            )
+            );
+            self.dropped = self.dropped.saturating_add(1);
         } else {
             self.queue.push_back(item);
         }
@@ -324,9 +433,13 @@ impl<T> FifoQueue<T> {
         self.queue.pop_front()
     }
 
-    /// Returns the current length of the queue.
-    pub fn len(&self) -> usize {
-        self.queue.len()
+    /// Returns a summary of the queue.
+    pub fn stats(&self) -> QueueStats {
+        QueueStats {
+            length: self.queue.len(),
+            capacity: self.max_length,
+            dropped: self.dropped,
+        }
     }
 }
 
@@ -334,6 +447,7 @@ impl<T> FifoQueue<T> {
 struct LifoQueue<T> {
     queue: VecDeque<T>,
     max_length: usize,
+    dropped: u64,
 }
 
 impl<T> LifoQueue<T> {
@@ -342,6 +456,7 @@ impl<T> LifoQueue<T> {
         Self {
             queue: VecDeque::default(),
             max_length,
+            dropped: 0,
         }
     }
 
@@ -351,6 +466,7 @@ impl<T> LifoQueue<T> {
     pub fn push(&mut self, item: T) {
         if self.queue.len() == self.max_length {
             self.queue.pop_back();
+            self.dropped = self.dropped.saturating_add(1);
         }
         self.queue.push_front(item);
     }
@@ -369,6 +485,15 @@ impl<T> LifoQueue<T> {
     pub fn len(&self) -> usize {
         self.queue.len()
     }
+
+    /// Returns a summary of the queue.
+    pub fn stats(&self) -> QueueStats {
+        QueueStats {
+            length: self.queue.len(),
+            capacity: self.max_length,
+            dropped: self.dropped,
+        }
+    }
 }
 
 /// A handle that sends a message on the provided channel to a receiver when it gets dropped.
@@ -494,6 +619,10 @@ impl<E: EthSpec> From<ReadyWork> for WorkEvent<E> {
                 drop_during_sync: true,
                 work: Work::UnknownBlockSamplingRequest { process_fn },
             },
+            ReadyWork::UnknownParentBlock(QueuedUnknownParentBlock { process_fn, .. }) => Self {
+                drop_during_sync: false,
+                work: Work::GossipBlock(process_fn),
+            },
             ReadyWork::BackfillSync(QueuedBackfillBatch(process_fn)) => Self {
                 drop_during_sync: false,
                 work: Work::ChainSegmentBackfill(process_fn),
@@ -635,8 +764,9 @@ impl<E: EthSpec> fmt::Debug for Work<E> {
     }
 }
 
-#[derive(IntoStaticStr, PartialEq, Eq, Debug)]
+#[derive(IntoStaticStr, EnumString, PartialEq, Eq, Debug, Clone, Copy, Serialize, Deserialize)]
 #[strum(serialize_all = "snake_case")]
+#[serde(rename_all = "snake_case")]
 pub enum WorkType {
     GossipAttestation,
     UnknownBlockAttestation,
@@ -814,6 +944,7 @@ pub struct BeaconProcessor<E: EthSpec> {
     pub executor: TaskExecutor,
     pub current_workers: usize,
     pub config: BeaconProcessorConfig,
+    pub queue_stats: BeaconProcessorQueueStats,
     pub log: Logger,
 }
 
@@ -838,8 +969,10 @@ impl<E: EthSpec> BeaconProcessor<E> {
         work_journal_tx: Option<mpsc::Sender<&'static str>>,
         slot_clock: S,
         maximum_gossip_clock_disparity: Duration,
-        queue_lengths: BeaconProcessorQueueLengths,
+        mut queue_lengths: BeaconProcessorQueueLengths,
     ) -> Result<(), String> {
+        queue_lengths.apply_overrides(&self.config.queue_length_overrides)?;
+
         // Used by workers to communicate that they are finished a task.
         let (idle_tx, idle_rx) = mpsc::channel::<()>(MAX_IDLE_QUEUE_LEN);
 
@@ -1031,11 +1164,96 @@ impl<E: EthSpec> BeaconProcessor<E> {
                     // We don't check the `work.drop_during_sync` here. We assume that if it made
                     // it into the queue at any point then we should process it.
                     None if can_spawn => {
+                        // Check any user-configured priorities before the default ordering.
+                        let prioritized_work =
+                            self.config
+                                .prioritized_work_types
+                                .iter()
+                                .find_map(|work_type| match work_type {
+                                    WorkType::GossipAttestation => attestation_queue.pop(),
+                                    WorkType::UnknownBlockAttestation => {
+                                        unknown_block_attestation_queue.pop()
+                                    }
+                                    WorkType::GossipAggregate => aggregate_queue.pop(),
+                                    WorkType::UnknownBlockAggregate => {
+                                        unknown_block_aggregate_queue.pop()
+                                    }
+                                    WorkType::UnknownLightClientOptimisticUpdate => {
+                                        unknown_light_client_update_queue.pop()
+                                    }
+                                    WorkType::UnknownBlockSamplingRequest => {
+                                        unknown_block_sampling_request_queue.pop()
+                                    }
+                                    WorkType::GossipBlock => gossip_block_queue.pop(),
+                                    WorkType::GossipBlobSidecar => gossip_blob_queue.pop(),
+                                    WorkType::GossipDataColumnSidecar => {
+                                        gossip_data_column_queue.pop()
+                                    }
+                                    WorkType::DelayedImportBlock => delayed_block_queue.pop(),
+                                    WorkType::GossipVoluntaryExit => {
+                                        gossip_voluntary_exit_queue.pop()
+                                    }
+                                    WorkType::GossipProposerSlashing => {
+                                        gossip_proposer_slashing_queue.pop()
+                                    }
+                                    WorkType::GossipAttesterSlashing => {
+                                        gossip_attester_slashing_queue.pop()
+                                    }
+                                    WorkType::GossipSyncSignature => sync_message_queue.pop(),
+                                    WorkType::GossipSyncContribution => {
+                                        sync_contribution_queue.pop()
+                                    }
+                                    WorkType::GossipLightClientFinalityUpdate => {
+                                        finality_update_queue.pop()
+                                    }
+                                    WorkType::GossipLightClientOptimisticUpdate => {
+                                        optimistic_update_queue.pop()
+                                    }
+                                    WorkType::RpcBlock | WorkType::IgnoredRpcBlock => {
+                                        rpc_block_queue.pop()
+                                    }
+                                    WorkType::RpcBlobs => rpc_blob_queue.pop(),
+                                    WorkType::RpcCustodyColumn => rpc_custody_column_queue.pop(),
+                                    WorkType::RpcVerifyDataColumn => {
+                                        rpc_verify_data_column_queue.pop()
+                                    }
+                                    WorkType::SamplingResult => sampling_result_queue.pop(),
+                                    WorkType::ChainSegment => chain_segment_queue.pop(),
+                                    WorkType::ChainSegmentBackfill => backfill_chain_segment.pop(),
+                                    WorkType::Status => status_queue.pop(),
+                                    WorkType::BlocksByRangeRequest => bbrange_queue.pop(),
+                                    WorkType::BlocksByRootsRequest => bbroots_queue.pop(),
+                                    WorkType::BlobsByRangeRequest => blbrange_queue.pop(),
+                                    WorkType::BlobsByRootsRequest => blbroots_queue.pop(),
+                                    WorkType::DataColumnsByRootsRequest => dcbroots_queue.pop(),
+                                    WorkType::DataColumnsByRangeRequest => dcbrange_queue.pop(),
+                                    WorkType::GossipBlsToExecutionChange => {
+                                        gossip_bls_to_execution_change_queue.pop()
+                                    }
+                                    WorkType::LightClientBootstrapRequest => {
+                                        lc_bootstrap_queue.pop()
+                                    }
+                                    WorkType::LightClientOptimisticUpdateRequest => {
+                                        lc_optimistic_update_queue.pop()
+                                    }
+                                    WorkType::LightClientFinalityUpdateRequest => {
+                                        lc_finality_update_queue.pop()
+                                    }
+                                    WorkType::LightClientUpdatesByRangeRequest => {
+                                        lc_update_range_queue.pop()
+                                    }
+                                    WorkType::ApiRequestP0 => api_request_p0_queue.pop(),
+                                    WorkType::ApiRequestP1 => api_request_p1_queue.pop(),
+                                    // Batches are only formed by the default ordering below.
+                                    WorkType::GossipAttestationBatch
+                                    | WorkType::GossipAggregateBatch => None,
+                                });
+
+                        let work_event: Option<Work<E>> = if let Some(item) = prioritized_work {
+                            Some(item)
                         // Check for chain segments first, they're the most efficient way to get
                         // blocks into the system.
-                        let work_event: Option<Work<E>> = if let Some(item) =
-                            chain_segment_queue.pop()
-                        {
+                        } else if let Some(item) = chain_segment_queue.pop() {
                             Some(item)
                         // Check sync blocks before gossip blocks, since we've already explicitly
                         // requested these blocks.
@@ -1429,65 +1647,68 @@ impl<E: EthSpec> BeaconProcessor<E> {
                 );
 
                 if let Some(modified_queue_id) = modified_queue_id {
-                    let queue_len = match modified_queue_id {
-                        WorkType::GossipAttestation => aggregate_queue.len(),
-                        WorkType::UnknownBlockAttestation => unknown_block_attestation_queue.len(),
-                        WorkType::GossipAttestationBatch => 0, // No queue
-                        WorkType::GossipAggregate => aggregate_queue.len(),
-                        WorkType::UnknownBlockAggregate => unknown_block_aggregate_queue.len(),
+                    let queue_stats = match modified_queue_id {
+                        WorkType::GossipAttestation => attestation_queue.stats(),
+                        WorkType::UnknownBlockAttestation => {
+                            unknown_block_attestation_queue.stats()
+                        }
+                        WorkType::GossipAttestationBatch => QueueStats::default(), // No queue
+                        WorkType::GossipAggregate => aggregate_queue.stats(),
+                        WorkType::UnknownBlockAggregate => unknown_block_aggregate_queue.stats(),
                         WorkType::UnknownLightClientOptimisticUpdate => {
-                            unknown_light_client_update_queue.len()
+                            unknown_light_client_update_queue.stats()
                         }
                         WorkType::UnknownBlockSamplingRequest => {
-                            unknown_block_sampling_request_queue.len()
+                            unknown_block_sampling_request_queue.stats()
                         }
-                        WorkType::GossipAggregateBatch => 0, // No queue
-                        WorkType::GossipBlock => gossip_block_queue.len(),
-                        WorkType::GossipBlobSidecar => gossip_blob_queue.len(),
-                        WorkType::GossipDataColumnSidecar => gossip_data_column_queue.len(),
-                        WorkType::DelayedImportBlock => delayed_block_queue.len(),
-                        WorkType::GossipVoluntaryExit => gossip_voluntary_exit_queue.len(),
-                        WorkType::GossipProposerSlashing => gossip_proposer_slashing_queue.len(),
-                        WorkType::GossipAttesterSlashing => gossip_attester_slashing_queue.len(),
-                        WorkType::GossipSyncSignature => sync_message_queue.len(),
-                        WorkType::GossipSyncContribution => sync_contribution_queue.len(),
-                        WorkType::GossipLightClientFinalityUpdate => finality_update_queue.len(),
+                        WorkType::GossipAggregateBatch => QueueStats::default(), // No queue
+                        WorkType::GossipBlock => gossip_block_queue.stats(),
+                        WorkType::GossipBlobSidecar => gossip_blob_queue.stats(),
+                        WorkType::GossipDataColumnSidecar => gossip_data_column_queue.stats(),
+                        WorkType::DelayedImportBlock => delayed_block_queue.stats(),
+                        WorkType::GossipVoluntaryExit => gossip_voluntary_exit_queue.stats(),
+                        WorkType::GossipProposerSlashing => gossip_proposer_slashing_queue.stats(),
+                        WorkType::GossipAttesterSlashing => gossip_attester_slashing_queue.stats(),
+                        WorkType::GossipSyncSignature => sync_message_queue.stats(),
+                        WorkType::GossipSyncContribution => sync_contribution_queue.stats(),
+                        WorkType::GossipLightClientFinalityUpdate => finality_update_queue.stats(),
                         WorkType::GossipLightClientOptimisticUpdate => {
-                            optimistic_update_queue.len()
+                            optimistic_update_queue.stats()
                         }
-                        WorkType::RpcBlock => rpc_block_queue.len(),
-                        WorkType::RpcBlobs | WorkType::IgnoredRpcBlock => rpc_blob_queue.len(),
-                        WorkType::RpcCustodyColumn => rpc_custody_column_queue.len(),
-                        WorkType::RpcVerifyDataColumn => rpc_verify_data_column_queue.len(),
-                        WorkType::SamplingResult => sampling_result_queue.len(),
-                        WorkType::ChainSegment => chain_segment_queue.len(),
-                        WorkType::ChainSegmentBackfill => backfill_chain_segment.len(),
-                        WorkType::Status => status_queue.len(),
-                        WorkType::BlocksByRangeRequest => blbrange_queue.len(),
-                        WorkType::BlocksByRootsRequest => blbroots_queue.len(),
-                        WorkType::BlobsByRangeRequest => bbrange_queue.len(),
-                        WorkType::BlobsByRootsRequest => bbroots_queue.len(),
-                        WorkType::DataColumnsByRootsRequest => dcbroots_queue.len(),
-                        WorkType::DataColumnsByRangeRequest => dcbrange_queue.len(),
+                        WorkType::RpcBlock | WorkType::IgnoredRpcBlock => rpc_block_queue.stats(),
+                        WorkType::RpcBlobs => rpc_blob_queue.stats(),
+                        WorkType::RpcCustodyColumn => rpc_custody_column_queue.stats(),
+                        WorkType::RpcVerifyDataColumn => rpc_verify_data_column_queue.stats(),
+                        WorkType::SamplingResult => sampling_result_queue.stats(),
+                        WorkType::ChainSegment => chain_segment_queue.stats(),
+                        WorkType::ChainSegmentBackfill => backfill_chain_segment.stats(),
+                        WorkType::Status => status_queue.stats(),
+                        WorkType::BlocksByRangeRequest => bbrange_queue.stats(),
+                        WorkType::BlocksByRootsRequest => bbroots_queue.stats(),
+                        WorkType::BlobsByRangeRequest => blbrange_queue.stats(),
+                        WorkType::BlobsByRootsRequest => blbroots_queue.stats(),
+                        WorkType::DataColumnsByRootsRequest => dcbroots_queue.stats(),
+                        WorkType::DataColumnsByRangeRequest => dcbrange_queue.stats(),
                         WorkType::GossipBlsToExecutionChange => {
-                            gossip_bls_to_execution_change_queue.len()
+                            gossip_bls_to_execution_change_queue.stats()
                         }
-                        WorkType::LightClientBootstrapRequest => lc_bootstrap_queue.len(),
+                        WorkType::LightClientBootstrapRequest => lc_bootstrap_queue.stats(),
                         WorkType::LightClientOptimisticUpdateRequest => {
-                            lc_optimistic_update_queue.len()
+                            lc_optimistic_update_queue.stats()
                         }
                         WorkType::LightClientFinalityUpdateRequest => {
-                            lc_finality_update_queue.len()
+                            lc_finality_update_queue.stats()
                         }
-                        WorkType::LightClientUpdatesByRangeRequest => lc_update_range_queue.len(),
-                        WorkType::ApiRequestP0 => api_request_p0_queue.len(),
-                        WorkType::ApiRequestP1 => api_request_p1_queue.len(),
+                        WorkType::LightClientUpdatesByRangeRequest => lc_update_range_queue.stats(),
+                        WorkType::ApiRequestP0 => api_request_p0_queue.stats(),
+                        WorkType::ApiRequestP1 => api_request_p1_queue.stats(),
                     };
                     metrics::observe_vec(
                         &metrics::BEACON_PROCESSOR_QUEUE_LENGTH,
                         &[modified_queue_id.into()],
-                        queue_len as f64,
+                        queue_stats.length as f64,
                     );
+                    self.queue_stats.update(modified_queue_id, queue_stats);
                 }
 
                 if aggregate_queue.is_full() && aggregate_debounce.elapsed() {
@@ -1725,4 +1946,55 @@ mod tests {
         assert_eq!(queue_lengths.attestation_queue, MIN_QUEUE_LEN);
         assert_eq!(queue_lengths.unknown_block_attestation_queue, MIN_QUEUE_LEN);
     }
+
+    #[test]
+    fn queue_length_overrides() {
+        let spec = ForkName::latest().make_genesis_spec(ChainSpec::mainnet());
+        let state = BeaconState::<MainnetEthSpec>::new(0, Eth1Data::default(), &spec);
+        let mut queue_lengths = BeaconProcessorQueueLengths::from_state(&state, &spec).unwrap();
+
+        queue_lengths
+            .apply_overrides(&[(WorkType::Status, 7), (WorkType::ChainSegmentBackfill, 3)])
+            .unwrap();
+        assert_eq!(queue_lengths.status_queue, 7);
+        assert_eq!(queue_lengths.backfill_chain_segment, 3);
+
+        assert!(queue_lengths
+            .apply_overrides(&[(WorkType::GossipAttestationBatch, 7)])
+            .is_err());
+        assert!(queue_lengths
+            .apply_overrides(&[(WorkType::Status, 0)])
+            .is_err());
+    }
+
+    #[test]
+    fn queue_stats_count_drops() {
+        let log = logging::test_logger();
+
+        let mut fifo = FifoQueue::new(2);
+        for i in 0..5 {
+            fifo.push(i, "test", &log);
+        }
+        assert_eq!(
+            fifo.stats(),
+            QueueStats {
+                length: 2,
+                capacity: 2,
+                dropped: 3,
+            }
+        );
+
+        let mut lifo = LifoQueue::new(2);
+        for i in 0..3 {
+            lifo.push(i);
+        }
+        assert_eq!(
+            lifo.stats(),
+            QueueStats {
+                length: 2,
+                capacity: 2,
+                dropped: 1,
+            }
+        );
+    }
 }
@@ -128,6 +128,34 @@ pub static BEACON_PROCESSOR_REPROCESSING_QUEUE_MATCHED_OPTIMISTIC_UPDATES: LazyL
     )
 });
 
+/*
+ * Unknown parent block reprocessing queue metrics.
+ */
+pub static BEACON_PROCESSOR_REPROCESSING_QUEUE_MATCHED_UNKNOWN_PARENT_BLOCKS: LazyLock<
+    Result<IntCounter>,
+> = LazyLock::new(|| {
+    try_create_int_counter(
+        "beacon_processor_reprocessing_queue_matched_unknown_parent_blocks",
+        "Number of queued blocks with an unknown parent where the parent has been imported.",
+    )
+});
+pub static BEACON_PROCESSOR_REPROCESSING_QUEUE_EXPIRED_UNKNOWN_PARENT_BLOCKS: LazyLock<
+    Result<IntCounter>,
+> = LazyLock::new(|| {
+    try_create_int_counter(
+        "beacon_processor_reprocessing_queue_expired_unknown_parent_blocks",
+        "Number of queued blocks with an unknown parent which have expired before the parent was imported.",
+    )
+});
+pub static BEACON_PROCESSOR_REPROCESSING_QUEUE_DROPPED_UNKNOWN_PARENT_BLOCKS: LazyLock<
+    Result<IntCounter>,
+> = LazyLock::new(|| {
+    try_create_int_counter(
+        "beacon_processor_reprocessing_queue_dropped_unknown_parent_blocks",
+        "Number of blocks with an unknown parent which were not queued because the queue was full.",
+    )
+});
+
 /// Errors and Debugging Stats
 pub static BEACON_PROCESSOR_SEND_ERROR_PER_WORK_TYPE: LazyLock<Result<IntCounterVec>> =
     LazyLock::new(|| {
@@ -10,6 +10,9 @@
 //!
 //! Aggregated and unaggregated attestations that failed verification due to referencing an unknown
 //! block will be re-queued until their block is imported, or until they expire.
+//!
+//! Gossip blocks whose parent is unknown are held, keyed by their parent root, until the parent is
+//! imported (at which point they are sent back out for gossip processing) or until they expire.
 use crate::metrics;
 use crate::{AsyncFn, BlockingFn, Work, WorkEvent};
 use fnv::FnvHashMap;
@@ -36,6 +39,7 @@ const GOSSIP_BLOCKS: &str = "gossip_blocks";
 const RPC_BLOCKS: &str = "rpc_blocks";
 const ATTESTATIONS: &str = "attestations";
 const LIGHT_CLIENT_UPDATES: &str = "lc_updates";
+const UNKNOWN_PARENT_BLOCKS: &str = "unknown_parent_blocks";
 
 /// Queue blocks for re-processing with an `ADDITIONAL_QUEUED_BLOCK_DELAY` after the slot starts.
 /// This is to account for any slight drift in the system clock.
@@ -53,6 +57,10 @@ pub const QUEUED_RPC_BLOCK_DELAY: Duration = Duration::from_secs(4);
 /// For how long to queue sampling requests for reprocessing.
 pub const QUEUED_SAMPLING_REQUESTS_DELAY: Duration = Duration::from_secs(12);
 
+/// For how long to queue gossip blocks with an unknown parent whilst waiting for the parent to be
+/// imported.
+pub const QUEUED_UNKNOWN_PARENT_BLOCK_DELAY: Duration = Duration::from_secs(12);
+
 /// Set an arbitrary upper-bound on the number of queued blocks to avoid DoS attacks. The fact that
 /// we signature-verify blocks before putting them in the queue *should* protect against this, but
 /// it's nice to have extra protection.
@@ -68,6 +76,10 @@ const MAXIMUM_QUEUED_LIGHT_CLIENT_UPDATES: usize = 128;
 /// TODO(das): choose a sensible value
 const MAXIMUM_QUEUED_SAMPLING_REQUESTS: usize = 16_384;
 
+/// How many gossip blocks with an unknown parent we keep before new ones get dropped. These blocks
+/// have not been signature-verified, so the bound is kept small.
+const MAXIMUM_QUEUED_UNKNOWN_PARENT_BLOCKS: usize = 64;
+
 // Process backfill batch 50%, 60%, 80% through each slot.
 //
 // Note: use caution to set these fractions in a way that won't cause panic-y
@@ -106,6 +118,8 @@ pub enum ReprocessQueueMessage {
     UnknownLightClientOptimisticUpdate(QueuedLightClientUpdate),
     /// A sampling request that references an unknown block.
     UnknownBlockSamplingRequest(QueuedSamplingRequest),
+    /// A gossip block whose parent is not yet known.
+    UnknownParentBlock(QueuedUnknownParentBlock),
     /// A new backfill batch that needs to be scheduled for processing.
     BackfillSync(QueuedBackfillBatch),
 }
@@ -119,6 +133,7 @@ pub enum ReadyWork {
     Aggregate(QueuedAggregate),
     LightClientUpdate(QueuedLightClientUpdate),
     SamplingRequest(QueuedSamplingRequest),
+    UnknownParentBlock(QueuedUnknownParentBlock),
     BackfillSync(QueuedBackfillBatch),
 }
 
@@ -156,6 +171,15 @@ pub struct QueuedGossipBlock {
     pub process_fn: AsyncFn,
 }
 
+/// A gossip block for which the parent block was not known while processing, queued until the
+/// parent is imported.
+pub struct QueuedUnknownParentBlock {
+    pub parent_root: Hash256,
+    pub beacon_block_root: Hash256,
+    /// Processes the block as a gossip block.
+    pub process_fn: AsyncFn,
+}
+
 /// A block that arrived for processing when the same block was being imported over gossip.
 /// It is queued for later import.
 pub struct QueuedRpcBlock {
@@ -209,6 +233,8 @@ enum InboundEvent {
     ReadyAttestation(QueuedAttestationId),
     /// A light client update that is ready for re-processing.
     ReadyLightClientUpdate(QueuedLightClientUpdateId),
+    /// A block with an unknown parent that expired before the parent was imported.
+    ExpiredUnknownParentBlock(Hash256),
     /// A backfill batch that was queued is ready for processing.
     ReadyBackfillSync(QueuedBackfillBatch),
     /// A message sent to the `ReprocessQueue`
@@ -233,6 +259,8 @@ struct ReprocessQueue<S> {
     lc_updates_delay_queue: DelayQueue<QueuedLightClientUpdateId>,
     /// Queue to manage scheduled sampling requests
     sampling_requests_delay_queue: DelayQueue<QueuedSamplingRequestId>,
+    /// Queue to manage the expiry of blocks with an unknown parent.
+    unknown_parent_blocks_delay_queue: DelayQueue<Hash256>,
 
     /* Queued items */
     /// Queued blocks.
@@ -251,6 +279,10 @@ struct ReprocessQueue<S> {
     queued_sampling_requests: FnvHashMap<usize, (QueuedSamplingRequest, DelayKey)>,
     /// Sampling requests per block root.
     awaiting_sampling_requests_per_block_root: HashMap<Hash256, Vec<QueuedSamplingRequestId>>,
+    /// Queued blocks with an unknown parent, by block root.
+    queued_unknown_parent_blocks: HashMap<Hash256, (QueuedUnknownParentBlock, DelayKey)>,
+    /// Roots of the queued blocks with an unknown parent, per parent root.
+    awaiting_blocks_per_parent_root: HashMap<Hash256, Vec<Hash256>>,
     /// Queued backfill batches
     queued_backfill_batches: Vec<QueuedBackfillBatch>,
 
@@ -264,6 +296,7 @@ struct ReprocessQueue<S> {
     attestation_delay_debounce: TimeLatch,
     lc_update_delay_debounce: TimeLatch,
     sampling_request_delay_debounce: TimeLatch,
+    unknown_parent_block_debounce: TimeLatch,
     next_backfill_batch_event: Option<Pin<Box<tokio::time::Sleep>>>,
     slot_clock: Arc<S>,
 }
@@ -342,6 +375,17 @@ impl<S: SlotClock> Stream for ReprocessQueue<S> {
             Poll::Ready(None) | Poll::Pending => (),
         }
 
+        match self.unknown_parent_blocks_delay_queue.poll_expired(cx) {
+            Poll::Ready(Some(block_root)) => {
+                return Poll::Ready(Some(InboundEvent::ExpiredUnknownParentBlock(
+                    block_root.into_inner(),
+                )));
+            }
+            // `Poll::Ready(None)` means that there are no more entries in the delay queue and we
+            // will continue to get this result until something else is added into the queue.
+            Poll::Ready(None) | Poll::Pending => (),
+        }
+
         if let Some(next_backfill_batch_event) = self.next_backfill_batch_event.as_mut() {
             match next_backfill_batch_event.as_mut().poll(cx) {
                 Poll::Ready(_) => {
@@ -414,6 +458,7 @@ impl<S: SlotClock> ReprocessQueue<S> {
             attestations_delay_queue: DelayQueue::new(),
             lc_updates_delay_queue: DelayQueue::new(),
             sampling_requests_delay_queue: <_>::default(),
+            unknown_parent_blocks_delay_queue: DelayQueue::new(),
             queued_gossip_block_roots: HashSet::new(),
             queued_lc_updates: FnvHashMap::default(),
             queued_aggregates: FnvHashMap::default(),
@@ -422,6 +467,8 @@ impl<S: SlotClock> ReprocessQueue<S> {
             awaiting_attestations_per_root: HashMap::new(),
             awaiting_lc_updates_per_parent_root: HashMap::new(),
             awaiting_sampling_requests_per_block_root: <_>::default(),
+            queued_unknown_parent_blocks: HashMap::new(),
+            awaiting_blocks_per_parent_root: HashMap::new(),
             queued_backfill_batches: Vec::new(),
             next_attestation: 0,
             next_lc_update: 0,
@@ -431,6 +478,7 @@ impl<S: SlotClock> ReprocessQueue<S> {
             attestation_delay_debounce: TimeLatch::default(),
             lc_update_delay_debounce: TimeLatch::default(),
             sampling_request_delay_debounce: <_>::default(),
+            unknown_parent_block_debounce: TimeLatch::default(),
             next_backfill_batch_event: None,
             slot_clock,
         }
@@ -683,6 +731,43 @@ impl<S: SlotClock> ReprocessQueue<S> {
                 self.queued_sampling_requests
                     .insert(id, (queued_sampling_request, delay_key));
             }
+            InboundEvent::Msg(UnknownParentBlock(queued_block)) => {
+                let block_root = queued_block.beacon_block_root;
+
+                // Don't add the same block to the queue twice. This prevents DoS attacks.
+                if self.queued_unknown_parent_blocks.contains_key(&block_root) {
+                    return;
+                }
+
+                if self.queued_unknown_parent_blocks.len() >= MAXIMUM_QUEUED_UNKNOWN_PARENT_BLOCKS {
+                    if self.unknown_parent_block_debounce.elapsed() {
+                        warn!(
+                            log,
+                            "Unknown parent blocks queue is full";
+                            "queue_size" => MAXIMUM_QUEUED_UNKNOWN_PARENT_BLOCKS,
+                        );
+                    }
+                    metrics::inc_counter(
+                        &metrics::BEACON_PROCESSOR_REPROCESSING_QUEUE_DROPPED_UNKNOWN_PARENT_BLOCKS,
+                    );
+                    // Drop the block, it will still be retrieved by a parent lookup.
+                    return;
+                }
+
+                // Register the delay.
+                let delay_key = self
+                    .unknown_parent_blocks_delay_queue
+                    .insert(block_root, QUEUED_UNKNOWN_PARENT_BLOCK_DELAY);
+
+                // Register this block for its parent root.
+                self.awaiting_blocks_per_parent_root
+                    .entry(queued_block.parent_root)
+                    .or_default()
+                    .push(block_root);
+
+                self.queued_unknown_parent_blocks
+                    .insert(block_root, (queued_block, delay_key));
+            }
             InboundEvent::Msg(BlockImported {
                 block_root,
                 parent_root,
@@ -787,6 +872,53 @@ impl<S: SlotClock> ReprocessQueue<S> {
                         );
                     }
                 }
+                // Unqueue the blocks which are children of this root, if any.
+                if let Some(child_roots) = self.awaiting_blocks_per_parent_root.remove(&block_root)
+                {
+                    let mut sent_count = 0;
+                    let mut failed_to_send_count = 0;
+
+                    for child_root in child_roots {
+                        if let Some((queued_block, delay_key)) =
+                            self.queued_unknown_parent_blocks.remove(&child_root)
+                        {
+                            metrics::inc_counter(
+                                &metrics::BEACON_PROCESSOR_REPROCESSING_QUEUE_MATCHED_UNKNOWN_PARENT_BLOCKS,
+                            );
+
+                            // Remove the delay.
+                            self.unknown_parent_blocks_delay_queue.remove(&delay_key);
+
+                            // Send the work.
+                            let work = ReadyWork::UnknownParentBlock(queued_block);
+
+                            if self.ready_work_tx.try_send(work).is_err() {
+                                failed_to_send_count += 1;
+                            } else {
+                                sent_count += 1;
+                            }
+                        } else {
+                            // This should never happen.
+                            error!(
+                                log,
+                                "Unknown queued block for parent root";
+                                "parent_root" => ?block_root,
+                                "block_root" => ?child_root,
+                            );
+                        }
+                    }
+
+                    if failed_to_send_count > 0 {
+                        error!(
+                            log,
+                            "Ignored queued child block(s) for block";
+                            "hint" => "system may be overloaded",
+                            "block_root" => ?block_root,
+                            "failed_count" => failed_to_send_count,
+                            "sent_count" => sent_count,
+                        );
+                    }
+                }
             }
             InboundEvent::Msg(NewLightClientOptimisticUpdate { parent_root }) => {
                 // Unqueue the light client optimistic updates we have for this root, if any.
@@ -947,6 +1079,36 @@ impl<S: SlotClock> ReprocessQueue<S> {
                     }
                 }
             }
+            InboundEvent::ExpiredUnknownParentBlock(block_root) => {
+                metrics::inc_counter(
+                    &metrics::BEACON_PROCESSOR_REPROCESSING_QUEUE_EXPIRED_UNKNOWN_PARENT_BLOCKS,
+                );
+
+                // The parent lookup is still responsible for importing the block, so the queued
+                // block is simply dropped.
+                if let Some((queued_block, _delay_key)) =
+                    self.queued_unknown_parent_blocks.remove(&block_root)
+                {
+                    let parent_root = queued_block.parent_root;
+                    if let Some(child_roots) =
+                        self.awaiting_blocks_per_parent_root.get_mut(&parent_root)
+                    {
+                        if let Some(index) = child_roots.iter().position(|&root| root == block_root)
+                        {
+                            child_roots.swap_remove(index);
+                        }
+                        if child_roots.is_empty() {
+                            self.awaiting_blocks_per_parent_root.remove(&parent_root);
+                        }
+                    }
+                    trace!(
+                        log,
+                        "Unknown parent block expired";
+                        "block_root" => ?block_root,
+                        "parent_root" => ?parent_root,
+                    );
+                }
+            }
             InboundEvent::ReadyBackfillSync(queued_backfill_batch) => {
                 let millis_from_slot_start = self
                     .slot_clock
@@ -1012,6 +1174,11 @@ impl<S: SlotClock> ReprocessQueue<S> {
             &[LIGHT_CLIENT_UPDATES],
             self.lc_updates_delay_queue.len() as i64,
         );
+        metrics::set_gauge_vec(
+            &metrics::BEACON_PROCESSOR_REPROCESSING_QUEUE_TOTAL,
+            &[UNKNOWN_PARENT_BLOCKS],
+            self.queued_unknown_parent_blocks.len() as i64,
+        );
     }
 
     fn recompute_next_backfill_batch_event(&mut self) {
@@ -1164,6 +1331,115 @@ mod tests {
         ));
     }
 
+    #[tokio::test]
+    async fn unknown_parent_block_released_on_parent_import() {
+        let runtime = TestRuntime::default();
+        let log = test_logger();
+        let (work_reprocessing_tx, work_reprocessing_rx) = mpsc::channel(4);
+        let (ready_work_tx, mut ready_work_rx) = mpsc::channel(4);
+        let slot_clock = Arc::new(testing_slot_clock(12));
+
+        spawn_reprocess_scheduler(
+            ready_work_tx,
+            work_reprocessing_rx,
+            &runtime.task_executor,
+            slot_clock.clone(),
+            log,
+            Duration::from_millis(500),
+        )
+        .unwrap();
+
+        tokio::time::pause();
+
+        let parent_root = Hash256::repeat_byte(1);
+        let block_root = Hash256::repeat_byte(2);
+        work_reprocessing_tx
+            .try_send(ReprocessQueueMessage::UnknownParentBlock(
+                QueuedUnknownParentBlock {
+                    parent_root,
+                    beacon_block_root: block_root,
+                    process_fn: Box::pin(async {}),
+                },
+            ))
+            .unwrap();
+        tokio::task::yield_now().await;
+        assert!(ready_work_rx.try_recv().is_err());
+
+        // Importing an unrelated block does not release the queued block.
+        work_reprocessing_tx
+            .try_send(ReprocessQueueMessage::BlockImported {
+                block_root: Hash256::repeat_byte(3),
+                parent_root: Hash256::zero(),
+            })
+            .unwrap();
+        tokio::task::yield_now().await;
+        assert!(ready_work_rx.try_recv().is_err());
+
+        work_reprocessing_tx
+            .try_send(ReprocessQueueMessage::BlockImported {
+                block_root: parent_root,
+                parent_root: Hash256::zero(),
+            })
+            .unwrap();
+        tokio::task::yield_now().await;
+        assert!(matches!(
+            ready_work_rx.try_recv(),
+            Ok(ReadyWork::UnknownParentBlock(QueuedUnknownParentBlock {
+                beacon_block_root,
+                ..
+            })) if beacon_block_root == block_root
+        ));
+    }
+
+    #[tokio::test]
+    async fn unknown_parent_block_expires() {
+        let runtime = TestRuntime::default();
+        let log = test_logger();
+        let (work_reprocessing_tx, work_reprocessing_rx) = mpsc::channel(4);
+        let (ready_work_tx, mut ready_work_rx) = mpsc::channel(4);
+        let slot_clock = Arc::new(testing_slot_clock(12));
+
+        spawn_reprocess_scheduler(
+            ready_work_tx,
+            work_reprocessing_rx,
+            &runtime.task_executor,
+            slot_clock.clone(),
+            log,
+            Duration::from_millis(500),
+        )
+        .unwrap();
+
+        tokio::time::pause();
+
+        let parent_root = Hash256::repeat_byte(1);
+        work_reprocessing_tx
+            .try_send(ReprocessQueueMessage::UnknownParentBlock(
+                QueuedUnknownParentBlock {
+                    parent_root,
+                    beacon_block_root: Hash256::repeat_byte(2),
+                    process_fn: Box::pin(async {}),
+                },
+            ))
+            .unwrap();
+        tokio::task::yield_now().await;
+
+        advance_time(
+            &slot_clock,
+            QUEUED_UNKNOWN_PARENT_BLOCK_DELAY + Duration::from_millis(1),
+        )
+        .await;
+
+        // The expired block is dropped rather than released when the parent is imported.
+        work_reprocessing_tx
+            .try_send(ReprocessQueueMessage::BlockImported {
+                block_root: parent_root,
+                parent_root: Hash256::zero(),
+            })
+            .unwrap();
+        tokio::task::yield_now().await;
+        assert!(ready_work_rx.try_recv().is_err());
+    }
+
     /// Advances slot clock and test clock time by the same duration.
     async fn advance_time(slot_clock: &ManualSlotClock, duration: Duration) {
         slot_clock.advance_time(duration);
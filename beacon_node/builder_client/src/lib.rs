@@ -27,6 +27,7 @@ pub struct Timeouts {
     post_validators: Duration,
     post_blinded_blocks: Duration,
     get_builder_status: Duration,
+    get_relay_data: Duration,
 }
 
 impl Timeouts {
@@ -39,6 +40,7 @@ impl Timeouts {
             post_validators: Duration::from_millis(DEFAULT_TIMEOUT_MILLIS),
             post_blinded_blocks: Duration::from_millis(DEFAULT_TIMEOUT_MILLIS),
             get_builder_status: Duration::from_millis(DEFAULT_TIMEOUT_MILLIS),
+            get_relay_data: Duration::from_millis(DEFAULT_TIMEOUT_MILLIS),
         }
     }
 }
@@ -225,4 +227,41 @@ impl BuilderHttpClient {
         self.get_with_timeout(path, self.timeouts.get_builder_status)
             .await
     }
+    /// `GET /relay/v1/data/validator_registration`
+    ///
+    /// Returns the latest registration the relay holds for `pubkey`, or `None` if the validator is
+    /// not registered with the relay. Relays signal an unknown validator with either a 404 or a
+    /// 400, so both are treated as "not registered".
+    pub async fn get_relay_validator_registration(
+        &self,
+        pubkey: &PublicKeyBytes,
+    ) -> Result<Option<SignedValidatorRegistrationData>, Error> {
+        let mut path = self.server.full.clone();
+
+        path.path_segments_mut()
+            .map_err(|()| Error::InvalidUrl(self.server.clone()))?
+            .push("relay")
+            .push("v1")
+            .push("data")
+            .push("validator_registration");
+
+        path.query_pairs_mut()
+            .append_pair("pubkey", pubkey.as_hex_string().as_str());
+
+        let resp = self
+            .get_with_timeout(path, self.timeouts.get_relay_data)
+            .await;
+
+        match resp {
+            Err(e)
+                if matches!(
+                    e.status(),
+                    Some(StatusCode::NOT_FOUND) | Some(StatusCode::BAD_REQUEST)
+                ) =>
+            {
+                Ok(None)
+            }
+            resp => resp.map(Some),
+        }
+    }
 }
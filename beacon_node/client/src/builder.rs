@@ -3,8 +3,10 @@ use crate::compute_light_client_updates::{
 };
 use crate::config::{ClientGenesis, Config as ClientConfig};
 use crate::notifier::spawn_notifier;
+use crate::storage_watchdog::spawn_storage_watchdog;
 use crate::Client;
 use beacon_chain::attestation_simulator::start_attestation_simulator_service;
+use beacon_chain::clock_sanity_service::start_clock_sanity_service;
 use beacon_chain::data_availability_checker::start_availability_cache_maintenance_service;
 use beacon_chain::graffiti_calculator::start_engine_version_cache_refresh_service;
 use beacon_chain::otb_verification_service::start_otb_verification_service;
@@ -19,7 +21,7 @@ use beacon_chain::{
     BeaconChain, BeaconChainTypes, Eth1ChainBackend, MigratorConfig, ServerSentEventHandler,
 };
 use beacon_chain::{Kzg, LightClientProducerEvent};
-use beacon_processor::{BeaconProcessor, BeaconProcessorChannels};
+use beacon_processor::{BeaconProcessor, BeaconProcessorChannels, BeaconProcessorQueueStats};
 use beacon_processor::{BeaconProcessorConfig, BeaconProcessorQueueLengths};
 use environment::RuntimeContext;
 use eth1::{Config as Eth1Config, Service as Eth1Service};
@@ -197,15 +199,11 @@ where
 
         let kzg_err_msg = |e| format!("Failed to load trusted setup: {:?}", e);
         let trusted_setup = config.trusted_setup.clone();
-        let kzg = if spec.is_peer_das_scheduled() {
-            Kzg::new_from_trusted_setup_das_enabled(trusted_setup).map_err(kzg_err_msg)?
-        } else if spec.deneb_fork_epoch.is_some() {
-            Kzg::new_from_trusted_setup(trusted_setup).map_err(kzg_err_msg)?
-        } else {
-            Kzg::new_from_trusted_setup_no_precomp(trusted_setup).map_err(kzg_err_msg)?
-        };
+        // Precomputation is only worthwhile once blobs are in use.
+        let use_precomp = spec.is_peer_das_scheduled() || spec.deneb_fork_epoch.is_some();
+        let kzg = Kzg::shared(trusted_setup, use_precomp).map_err(kzg_err_msg)?;
 
-        let builder = BeaconChainBuilder::new(eth_spec_instance, Arc::new(kzg))
+        let builder = BeaconChainBuilder::new(eth_spec_instance, kzg)
             .logger(context.log().clone())
             .store(store)
             .task_executor(context.executor.clone())
@@ -574,6 +572,7 @@ where
                         network_globals: None,
                         beacon_processor_send: None,
                         beacon_processor_reprocess_send: None,
+                        beacon_processor_queue_stats: None,
                         eth1_service: Some(genesis_service.eth1_service.clone()),
                         log: context.log().clone(),
                         sse_logging_components: runtime_context.sse_logging_components.clone(),
@@ -798,6 +797,7 @@ where
             .beacon_processor_config
             .take()
             .ok_or("build requires a beacon_processor_config")?;
+        let beacon_processor_queue_stats = BeaconProcessorQueueStats::default();
         let log = runtime_context.log().clone();
 
         let http_api_listen_addr = if self.http_api_config.enabled {
@@ -811,6 +811,7 @@ where
                 beacon_processor_reprocess_send: Some(
                     beacon_processor_channels.work_reprocessing_tx.clone(),
                 ),
+                beacon_processor_queue_stats: Some(beacon_processor_queue_stats.clone()),
                 sse_logging_components: runtime_context.sse_logging_components.clone(),
                 log: log.clone(),
             });
@@ -874,6 +875,7 @@ where
                     executor: beacon_processor_context.executor.clone(),
                     current_workers: 0,
                     config: beacon_processor_config,
+                    queue_stats: beacon_processor_queue_stats,
                     log: beacon_processor_context.log().clone(),
                 }
                 .spawn_manager(
@@ -983,6 +985,17 @@ where
                 beacon_chain.task_executor.clone(),
                 beacon_chain.clone(),
             );
+            start_clock_sanity_service(runtime_context.executor.clone(), beacon_chain.clone());
+
+            if beacon_chain.config.storage_watchdog.is_some() {
+                if let Some(db_path) = self.db_path.clone() {
+                    spawn_storage_watchdog(
+                        runtime_context.executor.clone(),
+                        beacon_chain.clone(),
+                        db_path,
+                    );
+                }
+            }
         }
 
         Ok(Client {
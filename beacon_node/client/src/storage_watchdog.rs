@@ -0,0 +1,35 @@
+use beacon_chain::{BeaconChain, BeaconChainTypes};
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::time::Duration;
+use sysinfo::{System, SystemExt};
+use task_executor::TaskExecutor;
+use tokio::time::interval;
+
+/// How often to check the free space on the disk holding the database.
+const POLL_INTERVAL: Duration = Duration::from_secs(60);
+
+/// Spawns a service which periodically reports the free space on the disk holding `db_path` to
+/// the beacon chain's storage watchdog.
+pub fn spawn_storage_watchdog<T: BeaconChainTypes>(
+    executor: TaskExecutor,
+    beacon_chain: Arc<BeaconChain<T>>,
+    db_path: PathBuf,
+) {
+    executor.spawn(
+        async move {
+            let mut sysinfo = System::new();
+            sysinfo.refresh_disks_list();
+
+            let mut interval = interval(POLL_INTERVAL);
+            loop {
+                interval.tick().await;
+                sysinfo.refresh_disks();
+                let (disk_bytes_total, disk_bytes_free) =
+                    system_health::observe_disk_space(&sysinfo, &db_path);
+                beacon_chain.update_storage_pressure(disk_bytes_free, disk_bytes_total);
+            }
+        },
+        "storage_watchdog",
+    );
+}
@@ -206,6 +206,11 @@ impl DepositCache {
         self.finalized_deposit_count != 0 && self.logs.is_empty()
     }
 
+    /// Returns the number of deposits which have not been finalized, and are held in memory.
+    pub fn pending_len(&self) -> usize {
+        self.logs.len()
+    }
+
     /// Returns the block number for the most recent deposit in the cache.
     pub fn latest_block_number(&self) -> u64 {
         self.logs
@@ -269,6 +274,11 @@ impl DepositCache {
             self.logs.drain(0..drop);
             self.leaves.drain(0..drop);
             self.deposit_roots.drain(0..drop);
+            // Release the memory held by the finalized deposits, which may be substantial on a
+            // long-running node.
+            self.logs.shrink_to_fit();
+            self.leaves.shrink_to_fit();
+            self.deposit_roots.shrink_to_fit();
             self.finalized_deposit_count = deposits_to_finalize;
             self.finalized_block_height = finalized_log.block_number;
 
@@ -414,6 +424,20 @@ impl DepositCache {
         }
     }
 
+    /// Returns the `Deposit` with the given `index`, with a proof of inclusion in the deposit
+    /// tree containing every deposit in the cache, along with the root of that tree.
+    ///
+    /// ## Errors
+    ///
+    /// - If the deposit has been finalized.
+    /// - If the deposit is not in the cache.
+    pub fn get_deposit_proof(&self, index: u64) -> Result<(Hash256, Deposit), Error> {
+        let deposit_count = self.len() as u64;
+        let (deposit_root, mut deposits) = self.get_deposits(index, index + 1, deposit_count)?;
+        let deposit = deposits.pop().ok_or(Error::PleaseNotifyTheDevs)?;
+        Ok((deposit_root, deposit))
+    }
+
     /// Returns the number of deposits with valid signatures that have been observed up to and
     /// including the block at `block_number`.
     ///
@@ -656,6 +680,34 @@ pub mod tests {
         assert!(tree.get_deposits(8, 9, 11).is_ok());
     }
 
+    #[test]
+    fn get_deposit_proof() {
+        let n = 16;
+        let mut tree = get_cache_with_deposits(n);
+
+        let block7 = fake_eth1_block(&tree, 7).expect("should create fake eth1 block");
+        tree.finalize(block7).expect("should finalize");
+        assert_eq!(tree.pending_len(), 8);
+
+        // Finalized and unknown deposits have no proof.
+        assert!(tree.get_deposit_proof(7).is_err());
+        assert!(tree.get_deposit_proof(n).is_err());
+
+        for index in 8..n {
+            let (root, deposit) = tree
+                .get_deposit_proof(index)
+                .expect("should get proof for pending deposit");
+            assert_eq!(Some(&root), tree.get_root(n as usize));
+            assert!(merkle_proof::verify_merkle_proof(
+                deposit.data.tree_hash_root(),
+                &deposit.proof,
+                DEPOSIT_TREE_DEPTH + 1,
+                index as usize,
+                root,
+            ));
+        }
+    }
+
     // returns an eth1 block that can be used to finalize the cache at `deposit_index`
     // this will ensure the `deposit_root` on the `Eth1Block` is correct
     fn fake_eth1_block(deposit_cache: &DepositCache, deposit_index: usize) -> Option<Eth1Block> {
@@ -22,6 +22,12 @@ pub static DEPOSIT_CACHE_LEN: LazyLock<Result<IntGauge>> = LazyLock::new(|| {
         "Number of deposits in the eth1 cache",
     )
 });
+pub static DEPOSIT_CACHE_PENDING_LEN: LazyLock<Result<IntGauge>> = LazyLock::new(|| {
+    try_create_int_gauge(
+        "eth1_deposit_cache_pending_len",
+        "Number of unfinalized deposits held in memory by the eth1 cache",
+    )
+});
 pub static HIGHEST_PROCESSED_DEPOSIT_BLOCK: LazyLock<Result<IntGauge>> = LazyLock::new(|| {
     try_create_int_gauge(
         "eth1_highest_processed_deposit_block",
@@ -820,12 +820,16 @@ impl Service {
                     eth1_data.block_hash
                 ))
             })?;
-        self.inner
-            .deposit_cache
-            .write()
+        let mut deposit_cache = self.inner.deposit_cache.write();
+        deposit_cache
             .cache
             .finalize(eth1_block)
-            .map_err(|e| Error::FailedToFinalizeDeposit(format!("{:?}", e)))
+            .map_err(|e| Error::FailedToFinalizeDeposit(format!("{:?}", e)))?;
+        metrics::set_gauge(
+            &metrics::DEPOSIT_CACHE_PENDING_LEN,
+            deposit_cache.cache.pending_len() as i64,
+        );
+        Ok(())
     }
 
     pub fn get_deposit_snapshot(&self) -> Option<DepositTreeSnapshot> {
@@ -955,6 +959,10 @@ impl Service {
             cache.last_processed_block = Some(block_range.end.saturating_sub(1));
 
             metrics::set_gauge(&metrics::DEPOSIT_CACHE_LEN, cache.cache.len() as i64);
+            metrics::set_gauge(
+                &metrics::DEPOSIT_CACHE_PENDING_LEN,
+                cache.cache.pending_len() as i64,
+            );
             metrics::set_gauge(
                 &metrics::HIGHEST_PROCESSED_DEPOSIT_BLOCK,
                 cache.last_processed_block.unwrap_or(0) as i64,
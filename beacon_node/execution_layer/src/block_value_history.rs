@@ -0,0 +1,73 @@
+//! A record of the payloads considered for recently produced blocks, so that operators can audit
+//! whether using a builder is profitable.
+use eth2::lighthouse::BlockValueRecord;
+use parking_lot::Mutex;
+use std::collections::BTreeMap;
+use std::time::Duration;
+use types::Slot;
+
+/// How long records are retained.
+pub const BLOCK_VALUE_RETENTION: Duration = Duration::from_secs(24 * 60 * 60);
+
+/// The most recent `BlockValueRecord` for each slot within the retention period.
+#[derive(Default)]
+pub struct BlockValueHistory {
+    records: Mutex<BTreeMap<Slot, BlockValueRecord>>,
+}
+
+impl BlockValueHistory {
+    /// Store `record`, replacing any previous record for the same slot, and prune records more
+    /// than `retention_slots` older than it.
+    pub fn insert(&self, record: BlockValueRecord, retention_slots: u64) {
+        let mut records = self.records.lock();
+        let oldest_retained = record.slot.saturating_sub(retention_slots);
+        records.insert(record.slot, record);
+        *records = records.split_off(&oldest_retained);
+    }
+
+    pub fn get(&self, slot: Slot) -> Option<BlockValueRecord> {
+        self.records.lock().get(&slot).cloned()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use eth2::lighthouse::{PayloadChoiceReason, PayloadSource};
+    use types::ExecutionBlockHash;
+
+    fn record(slot: u64) -> BlockValueRecord {
+        BlockValueRecord {
+            slot: Slot::new(slot),
+            parent_hash: ExecutionBlockHash::zero(),
+            local_payload: None,
+            builder_bid: None,
+            choice: PayloadSource::Local,
+            reason: PayloadChoiceReason::NoBuilder,
+        }
+    }
+
+    #[test]
+    fn prunes_records_outside_retention() {
+        let history = BlockValueHistory::default();
+        history.insert(record(1), 10);
+        history.insert(record(5), 10);
+        assert!(history.get(Slot::new(1)).is_some());
+
+        history.insert(record(12), 10);
+        assert!(history.get(Slot::new(1)).is_none());
+        assert!(history.get(Slot::new(5)).is_some());
+        assert!(history.get(Slot::new(12)).is_some());
+    }
+
+    #[test]
+    fn replaces_record_for_same_slot() {
+        let history = BlockValueHistory::default();
+        history.insert(record(3), 10);
+        let mut builder_record = record(3);
+        builder_record.choice = PayloadSource::Builder;
+        builder_record.reason = PayloadChoiceReason::BuilderMoreProfitable;
+        history.insert(builder_record.clone(), 10);
+        assert_eq!(history.get(Slot::new(3)), Some(builder_record));
+    }
+}
@@ -363,6 +363,16 @@ pub enum GetPayloadResponseType<E: EthSpec> {
     Blinded(GetPayloadResponse<E>),
 }
 
+impl<E: EthSpec> GetPayloadResponseType<E> {
+    pub fn payload_response(&self) -> &GetPayloadResponse<E> {
+        match self {
+            GetPayloadResponseType::Full(response) | GetPayloadResponseType::Blinded(response) => {
+                response
+            }
+        }
+    }
+}
+
 impl<E: EthSpec> GetPayloadResponse<E> {
     pub fn execution_payload_ref(&self) -> ExecutionPayloadRef<E> {
         self.to_ref().into()
@@ -1307,7 +1307,7 @@ impl HttpJsonRpc {
 mod test {
     use super::auth::JwtKey;
     use super::*;
-    use crate::test_utils::{MockServer, DEFAULT_JWT_SECRET};
+    use crate::test_utils::{MockExecutionFaults, MockServer, DEFAULT_JWT_SECRET};
     use std::future::Future;
     use std::str::FromStr;
     use std::sync::Arc;
@@ -2090,4 +2090,28 @@ mod test {
             )
             .await;
     }
+
+    #[tokio::test]
+    async fn mock_server_injected_faults() {
+        let tester = Tester::new(true);
+
+        tester.server.set_faults(MockExecutionFaults {
+            response_delay: Duration::from_millis(100),
+            error_probability: 1.0,
+            methods: vec![ETH_SYNCING.to_string()],
+        });
+        let start = Instant::now();
+        assert!(tester.rpc_client.upcheck().await.is_err());
+        assert!(start.elapsed() >= Duration::from_millis(100));
+
+        // Methods which are not listed are unaffected.
+        tester.server.set_faults(MockExecutionFaults {
+            response_delay: Duration::from_secs(60),
+            error_probability: 1.0,
+            methods: vec![ENGINE_GET_PAYLOAD_V3.to_string()],
+        });
+        let start = Instant::now();
+        tester.rpc_client.upcheck().await.unwrap();
+        assert!(start.elapsed() < Duration::from_secs(60));
+    }
 }
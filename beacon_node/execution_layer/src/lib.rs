@@ -9,6 +9,7 @@ use crate::payload_cache::PayloadCache;
 use arc_swap::ArcSwapOption;
 use auth::{strip_prefix, Auth, JwtKey};
 pub use block_hash::calculate_execution_block_hash;
+use block_value_history::{BlockValueHistory, BLOCK_VALUE_RETENTION};
 use builder_client::BuilderHttpClient;
 pub use engine_api::EngineCapabilities;
 use engine_api::Error as ApiError;
@@ -16,8 +17,13 @@ pub use engine_api::*;
 pub use engine_api::{http, http::deposit_methods, http::HttpJsonRpc};
 use engines::{Engine, EngineError};
 pub use engines::{EngineState, ForkchoiceState};
+use eth2::lighthouse::{
+    BlockValueRecord, BuilderBidRecord, LocalPayloadRecord, PayloadChoiceReason, PayloadSource,
+};
 use eth2::types::FullPayloadContents;
-use eth2::types::{builder_bid::SignedBuilderBid, BlobsBundle, ForkVersionedResponse};
+use eth2::types::{
+    builder_bid::SignedBuilderBid, BlobsBundle, FeeRecipientSource, ForkVersionedResponse,
+};
 use ethers_core::types::Transaction as EthersTransaction;
 use fixed_bytes::UintExtended;
 use fork_choice::ForkchoiceUpdateParameters;
@@ -59,6 +65,7 @@ use types::{
 };
 
 mod block_hash;
+pub mod block_value_history;
 mod engine_api;
 pub mod engines;
 mod keccak;
@@ -323,6 +330,9 @@ impl<E: EthSpec, Payload: AbstractExecPayload<E>> BlockProposalContents<E, Paylo
 pub struct ProposerPreparationDataEntry {
     update_epoch: Epoch,
     preparation_data: ProposerPreparationData,
+    /// `true` if the fee recipient was taken from a validator registration for the builder,
+    /// overriding the one provided via `prepare_beacon_proposer`.
+    from_builder_registration: bool,
 }
 
 #[derive(Hash, PartialEq, Eq)]
@@ -371,6 +381,7 @@ struct Inner<E: EthSpec> {
     proposers: RwLock<HashMap<ProposerKey, Proposer>>,
     executor: TaskExecutor,
     payload_cache: PayloadCache<E>,
+    block_value_history: BlockValueHistory,
     log: Logger,
     /// Track whether the last `newPayload` call errored.
     ///
@@ -479,6 +490,7 @@ impl<E: EthSpec> ExecutionLayer<E> {
             execution_blocks: Mutex::new(LruCache::new(EXECUTION_BLOCKS_LRU_CACHE_SIZE)),
             executor,
             payload_cache: PayloadCache::default(),
+            block_value_history: BlockValueHistory::default(),
             log,
             last_new_payload_errored: RwLock::new(false),
         };
@@ -554,6 +566,19 @@ impl<E: EthSpec> ExecutionLayer<E> {
         self.inner.payload_cache.get(root)
     }
 
+    /// Returns the payloads considered when producing a block at `slot`, if a block was produced
+    /// at that slot within the last day.
+    pub fn block_value_record(&self, slot: Slot) -> Option<BlockValueRecord> {
+        self.inner.block_value_history.get(slot)
+    }
+
+    fn record_block_value(&self, record: BlockValueRecord, spec: &ChainSpec) {
+        let retention_slots = BLOCK_VALUE_RETENTION.as_secs() / spec.seconds_per_slot.max(1);
+        self.inner
+            .block_value_history
+            .insert(record, retention_slots);
+    }
+
     pub fn executor(&self) -> &TaskExecutor {
         &self.inner.executor
     }
@@ -715,12 +740,34 @@ impl<E: EthSpec> ExecutionLayer<E> {
         &self,
         update_epoch: Epoch,
         preparation_data: &[ProposerPreparationData],
+    ) {
+        self.insert_proposer_preparation(update_epoch, preparation_data, false)
+            .await
+    }
+
+    /// Updates the proposer preparation data using the fee recipients of validator registrations
+    /// sent to the builder, which override those provided via `prepare_beacon_proposer`.
+    pub async fn update_proposer_preparation_from_registrations(
+        &self,
+        update_epoch: Epoch,
+        preparation_data: &[ProposerPreparationData],
+    ) {
+        self.insert_proposer_preparation(update_epoch, preparation_data, true)
+            .await
+    }
+
+    async fn insert_proposer_preparation(
+        &self,
+        update_epoch: Epoch,
+        preparation_data: &[ProposerPreparationData],
+        from_builder_registration: bool,
     ) {
         let mut proposer_preparation_data = self.proposer_preparation_data().await;
         for preparation_entry in preparation_data {
             let new = ProposerPreparationDataEntry {
                 update_epoch,
                 preparation_data: preparation_entry.clone(),
+                from_builder_registration,
             };
 
             let existing =
@@ -783,6 +830,40 @@ impl<E: EthSpec> ExecutionLayer<E> {
             .contains_key(&proposer_index)
     }
 
+    /// Returns the source that `fee_recipient` would have been obtained from by
+    /// `Self::get_suggested_fee_recipient` for the given `proposer_index`.
+    pub async fn suggested_fee_recipient_source(
+        &self,
+        proposer_index: u64,
+        fee_recipient: Address,
+    ) -> FeeRecipientSource {
+        let prepared_fee_recipient = self
+            .proposer_preparation_data()
+            .await
+            .get(&proposer_index)
+            .map(|entry| {
+                (
+                    entry.preparation_data.fee_recipient,
+                    entry.from_builder_registration,
+                )
+            });
+        match (prepared_fee_recipient, self.inner.suggested_fee_recipient) {
+            (Some((address, true)), _) if address == fee_recipient => {
+                FeeRecipientSource::BuilderOverride
+            }
+            (Some((address, false)), _) if address == fee_recipient => {
+                FeeRecipientSource::ProposerPreparation
+            }
+            (None, Some(address)) if address == fee_recipient => FeeRecipientSource::Default,
+            (None, None)
+                if fee_recipient == Address::from_slice(&DEFAULT_SUGGESTED_FEE_RECIPIENT) =>
+            {
+                FeeRecipientSource::Placeholder
+            }
+            _ => FeeRecipientSource::Unknown,
+        }
+    }
+
     /// Returns the fee-recipient address that should be used to build a block
     pub async fn get_suggested_fee_recipient(&self, proposer_index: u64) -> Address {
         if let Some(preparation_data_entry) =
@@ -995,16 +1076,39 @@ impl<E: EthSpec> ExecutionLayer<E> {
         builder_boost_factor: Option<u64>,
         spec: &ChainSpec,
     ) -> Result<ProvenancedPayload<BlockProposalContentsType<E>>, Error> {
+        let record = |local_payload, builder_bid, choice, reason| {
+            self.record_block_value(
+                BlockValueRecord {
+                    slot: builder_params.slot,
+                    parent_hash,
+                    local_payload,
+                    builder_bid,
+                    choice,
+                    reason,
+                },
+                spec,
+            )
+        };
+
         let Some(builder) = self.builder() else {
             // no builder.. return local payload
-            return self
+            let local_result = self
                 .get_full_payload_caching(
                     parent_hash,
                     payload_attributes,
                     forkchoice_update_params,
                     current_fork,
                 )
-                .await
+                .await;
+            if let Ok(local) = &local_result {
+                record(
+                    Some(local_payload_record(local.payload_response())),
+                    None,
+                    PayloadSource::Local,
+                    PayloadChoiceReason::NoBuilder,
+                );
+            }
+            return local_result
                 .and_then(GetPayloadResponseType::try_into)
                 .map(ProvenancedPayload::Local);
         };
@@ -1033,14 +1137,23 @@ impl<E: EthSpec> ExecutionLayer<E> {
                     "got healthy but also not healthy.. this shouldn't happen!"
                 ),
             }
-            return self
+            let local_result = self
                 .get_full_payload_caching(
                     parent_hash,
                     payload_attributes,
                     forkchoice_update_params,
                     current_fork,
                 )
-                .await
+                .await;
+            if let Ok(local) = &local_result {
+                record(
+                    Some(local_payload_record(local.payload_response())),
+                    None,
+                    PayloadSource::Local,
+                    PayloadChoiceReason::ChainUnhealthy,
+                );
+            }
+            return local_result
                 .and_then(GetPayloadResponseType::try_into)
                 .map(ProvenancedPayload::Local);
         }
@@ -1066,6 +1179,12 @@ impl<E: EthSpec> ExecutionLayer<E> {
                     "local_block_hash" => ?local.block_hash(),
                     "parent_hash" => ?parent_hash,
                 );
+                record(
+                    Some(local_payload_record(&local)),
+                    None,
+                    PayloadSource::Local,
+                    PayloadChoiceReason::BuilderError,
+                );
                 Ok(ProvenancedPayload::Local(BlockProposalContentsType::Full(
                     local.try_into()?,
                 )))
@@ -1078,6 +1197,12 @@ impl<E: EthSpec> ExecutionLayer<E> {
                     "local_block_hash" => ?local.block_hash(),
                     "parent_hash" => ?parent_hash,
                 );
+                record(
+                    Some(local_payload_record(&local)),
+                    None,
+                    PayloadSource::Local,
+                    PayloadChoiceReason::NoBuilderBid,
+                );
                 Ok(ProvenancedPayload::Local(BlockProposalContentsType::Full(
                     local.try_into()?,
                 )))
@@ -1091,6 +1216,12 @@ impl<E: EthSpec> ExecutionLayer<E> {
                     "local_error" => ?local_error,
                     "parent_hash" => ?parent_hash,
                 );
+                record(
+                    None,
+                    None,
+                    PayloadSource::None,
+                    PayloadChoiceReason::LocalError,
+                );
 
                 Err(Error::CannotProduceHeader)
             }
@@ -1103,6 +1234,12 @@ impl<E: EthSpec> ExecutionLayer<E> {
                     "local_error" => ?local_error,
                     "parent_hash" => ?parent_hash,
                 );
+                record(
+                    None,
+                    None,
+                    PayloadSource::None,
+                    PayloadChoiceReason::LocalError,
+                );
 
                 Err(Error::CannotProduceHeader)
             }
@@ -1117,6 +1254,25 @@ impl<E: EthSpec> ExecutionLayer<E> {
                     "parent_hash" => ?parent_hash,
                 );
 
+                let relay_value = *relay.data.message.value();
+
+                let boosted_relay_value = match builder_boost_factor {
+                    Some(builder_boost_factor) => (relay_value / Uint256::from(100))
+                        .saturating_mul(Uint256::from(builder_boost_factor)),
+                    None => relay_value,
+                };
+
+                let local_value = *local.block_value();
+
+                let local_record = Some(local_payload_record(&local));
+                let mut bid_record = BuilderBidRecord {
+                    block_hash: header.block_hash(),
+                    value: relay_value,
+                    boosted_value: boosted_relay_value,
+                    builder_boost_factor,
+                    rejection_reason: None,
+                };
+
                 // check relay payload validity
                 if let Err(reason) = verify_builder_bid(
                     &relay,
@@ -1139,21 +1295,18 @@ impl<E: EthSpec> ExecutionLayer<E> {
                         "relay_block_hash" => ?header.block_hash(),
                         "parent_hash" => ?parent_hash,
                     );
+                    bid_record.rejection_reason = Some(reason.to_string());
+                    record(
+                        local_record,
+                        Some(bid_record),
+                        PayloadSource::Local,
+                        PayloadChoiceReason::InvalidBuilderBid,
+                    );
                     return Ok(ProvenancedPayload::Local(BlockProposalContentsType::Full(
                         local.try_into()?,
                     )));
                 }
 
-                let relay_value = *relay.data.message.value();
-
-                let boosted_relay_value = match builder_boost_factor {
-                    Some(builder_boost_factor) => (relay_value / Uint256::from(100))
-                        .saturating_mul(Uint256::from(builder_boost_factor)),
-                    None => relay_value,
-                };
-
-                let local_value = *local.block_value();
-
                 if local_value >= boosted_relay_value {
                     info!(
                         self.log(),
@@ -1163,6 +1316,12 @@ impl<E: EthSpec> ExecutionLayer<E> {
                         "boosted_relay_value" => %boosted_relay_value,
                         "builder_boost_factor" => ?builder_boost_factor,
                     );
+                    record(
+                        local_record,
+                        Some(bid_record),
+                        PayloadSource::Local,
+                        PayloadChoiceReason::LocalMoreProfitable,
+                    );
                     return Ok(ProvenancedPayload::Local(BlockProposalContentsType::Full(
                         local.try_into()?,
                     )));
@@ -1175,6 +1334,12 @@ impl<E: EthSpec> ExecutionLayer<E> {
                         "local_block_value" => %local_value,
                         "relay_value" => %relay_value
                     );
+                    record(
+                        local_record,
+                        Some(bid_record),
+                        PayloadSource::Local,
+                        PayloadChoiceReason::EngineOverride,
+                    );
                     return Ok(ProvenancedPayload::Local(BlockProposalContentsType::Full(
                         local.try_into()?,
                     )));
@@ -1188,6 +1353,12 @@ impl<E: EthSpec> ExecutionLayer<E> {
                     "boosted_relay_value" => %boosted_relay_value,
                     "builder_boost_factor" => ?builder_boost_factor
                 );
+                record(
+                    local_record,
+                    Some(bid_record),
+                    PayloadSource::Builder,
+                    PayloadChoiceReason::BuilderMoreProfitable,
+                );
 
                 Ok(ProvenancedPayload::try_from(relay.data.message)?)
             }
@@ -1202,6 +1373,15 @@ impl<E: EthSpec> ExecutionLayer<E> {
                     "parent_hash" => ?parent_hash,
                 );
 
+                let relay_value = *relay.data.message.value();
+                let mut bid_record = BuilderBidRecord {
+                    block_hash: header.block_hash(),
+                    value: relay_value,
+                    boosted_value: relay_value,
+                    builder_boost_factor,
+                    rejection_reason: None,
+                };
+
                 match verify_builder_bid(
                     &relay,
                     parent_hash,
@@ -1210,8 +1390,23 @@ impl<E: EthSpec> ExecutionLayer<E> {
                     current_fork,
                     spec,
                 ) {
-                    Ok(()) => Ok(ProvenancedPayload::try_from(relay.data.message)?),
+                    Ok(()) => {
+                        record(
+                            None,
+                            Some(bid_record),
+                            PayloadSource::Builder,
+                            PayloadChoiceReason::LocalError,
+                        );
+                        Ok(ProvenancedPayload::try_from(relay.data.message)?)
+                    }
                     Err(reason) => {
+                        bid_record.rejection_reason = Some(reason.to_string());
+                        record(
+                            None,
+                            Some(bid_record),
+                            PayloadSource::None,
+                            PayloadChoiceReason::InvalidBuilderBid,
+                        );
                         metrics::inc_counter_vec(
                             &metrics::EXECUTION_LAYER_GET_PAYLOAD_BUILDER_REJECTIONS,
                             &[reason.as_ref().as_ref()],
@@ -2092,6 +2287,13 @@ fn verify_builder_bid<E: EthSpec>(
 }
 
 /// A helper function to record the time it takes to execute a future.
+fn local_payload_record<E: EthSpec>(local: &GetPayloadResponse<E>) -> LocalPayloadRecord {
+    LocalPayloadRecord {
+        block_hash: local.block_hash(),
+        value: *local.block_value(),
+    }
+}
+
 async fn timed_future<F: Future<Output = T>, T>(metric: &str, future: F) -> (T, Duration) {
     let start = Instant::now();
     let result = future.await;
@@ -2138,6 +2340,51 @@ mod test {
             .await;
     }
 
+    #[tokio::test]
+    async fn suggested_fee_recipient_sources() {
+        let runtime = TestRuntime::default();
+        let mock = MockExecutionLayer::default_params(runtime.task_executor.clone());
+        let el = &mock.el;
+        let proposer_index = 1;
+        let prepared = Address::repeat_byte(1);
+        let registered = Address::repeat_byte(2);
+        let preparation_data = |fee_recipient| {
+            vec![ProposerPreparationData {
+                validator_index: proposer_index,
+                fee_recipient,
+            }]
+        };
+
+        el.update_proposer_preparation(Epoch::new(0), &preparation_data(prepared))
+            .await;
+        assert_eq!(
+            el.suggested_fee_recipient_source(proposer_index, prepared)
+                .await,
+            FeeRecipientSource::ProposerPreparation
+        );
+
+        // A validator registration for the builder overrides the prepared fee recipient.
+        el.update_proposer_preparation_from_registrations(
+            Epoch::new(0),
+            &preparation_data(registered),
+        )
+        .await;
+        assert_eq!(
+            el.get_suggested_fee_recipient(proposer_index).await,
+            registered
+        );
+        assert_eq!(
+            el.suggested_fee_recipient_source(proposer_index, registered)
+                .await,
+            FeeRecipientSource::BuilderOverride
+        );
+        assert_eq!(
+            el.suggested_fee_recipient_source(proposer_index, prepared)
+                .await,
+            FeeRecipientSource::Unknown
+        );
+    }
+
     #[tokio::test]
     async fn test_forked_terminal_block() {
         let runtime = TestRuntime::default();
@@ -942,11 +942,10 @@ mod test {
             .map_err(|e| format!("Invalid blobs bundle: {e:?}"))
     }
 
-    fn load_kzg() -> Result<Kzg, String> {
+    fn load_kzg() -> Result<Arc<Kzg>, String> {
         let trusted_setup: TrustedSetup =
             serde_json::from_reader(get_trusted_setup().as_slice())
                 .map_err(|e| format!("Unable to read trusted setup file: {e:?}"))?;
-        Kzg::new_from_trusted_setup(trusted_setup)
-            .map_err(|e| format!("Failed to load trusted setup: {e:?}"))
+        Kzg::shared(trusted_setup, true).map_err(|e| format!("Failed to load trusted setup: {e:?}"))
     }
 }
@@ -7,7 +7,7 @@ use crate::engine_api::{
 use crate::json_structures::JsonClientVersionV1;
 use bytes::Bytes;
 use execution_block_generator::PoWBlock;
-use handle_rpc::handle_rpc;
+use handle_rpc::{handle_rpc, GENERIC_ERROR_CODE};
 use kzg::Kzg;
 use logging::test_logger;
 use parking_lot::{Mutex, RwLock, RwLockWriteGuard};
@@ -20,6 +20,7 @@ use std::future::Future;
 use std::marker::PhantomData;
 use std::net::{Ipv4Addr, SocketAddr, SocketAddrV4};
 use std::sync::{Arc, LazyLock};
+use std::time::Duration;
 use tokio::{runtime, sync::oneshot};
 use types::{EthSpec, ExecutionBlockHash, Uint256};
 use warp::{http::StatusCode, Filter, Rejection};
@@ -83,6 +84,30 @@ pub struct MockExecutionConfig {
     pub prague_time: Option<u64>,
 }
 
+/// Latency and failures injected into the responses of the mock execution layer.
+///
+/// Used to reproduce the behaviour of the beacon node and validator client when the execution
+/// layer is slow or unreliable.
+#[derive(Debug, Clone, Default)]
+pub struct MockExecutionFaults {
+    /// Delay applied before handling each affected request.
+    pub response_delay: Duration,
+    /// Probability in `[0, 1]` that an affected request fails with an error.
+    pub error_probability: f64,
+    /// The JSON-RPC methods affected by the faults. All methods are affected if empty.
+    pub methods: Vec<String>,
+}
+
+impl MockExecutionFaults {
+    fn affects(&self, method: &str) -> bool {
+        self.methods.is_empty() || self.methods.iter().any(|m| m == method)
+    }
+
+    fn should_fail(&self) -> bool {
+        self.error_probability > 0.0 && rand::random::<f64>() < self.error_probability
+    }
+}
+
 impl Default for MockExecutionConfig {
     fn default() -> Self {
         Self {
@@ -163,6 +188,10 @@ impl<E: EthSpec> MockServer<E> {
             fcu_payload_statuses: <_>::default(),
             syncing_response: Arc::new(Mutex::new(Ok(false))),
             engine_capabilities: Arc::new(RwLock::new(DEFAULT_ENGINE_CAPABILITIES)),
+            faults: <_>::default(),
+            payload_value: Arc::new(RwLock::new(Uint256::from(
+                DEFAULT_MOCK_EL_PAYLOAD_VALUE_WEI,
+            ))),
             _phantom: PhantomData,
         });
 
@@ -197,6 +226,15 @@ impl<E: EthSpec> MockServer<E> {
         *self.ctx.engine_capabilities.write() = engine_capabilities;
     }
 
+    pub fn set_faults(&self, faults: MockExecutionFaults) {
+        *self.ctx.faults.write() = faults;
+    }
+
+    /// Sets the block value returned with locally built payloads.
+    pub fn set_payload_value(&self, payload_value: Uint256) {
+        *self.ctx.payload_value.write() = payload_value;
+    }
+
     #[allow(clippy::too_many_arguments)]
     pub fn new(
         handle: &runtime::Handle,
@@ -539,6 +577,8 @@ pub struct Context<E: EthSpec> {
     pub syncing_response: Arc<Mutex<Result<bool, String>>>,
 
     pub engine_capabilities: Arc<RwLock<EngineCapabilities>>,
+    pub faults: Arc<RwLock<MockExecutionFaults>>,
+    pub payload_value: Arc<RwLock<Uint256>>,
     pub _phantom: PhantomData<E>,
 }
 
@@ -681,8 +721,28 @@ pub fn serve<E: EthSpec>(
                 }
             };
 
+            let faults = ctx.faults.read().clone();
+            let method = body.get("method").and_then(serde_json::Value::as_str);
+            let injected_fault = if method.map_or(false, |method| faults.affects(method)) {
+                if !faults.response_delay.is_zero() {
+                    tokio::time::sleep(faults.response_delay).await;
+                }
+                faults.should_fail()
+            } else {
+                false
+            };
+
             let response = if let Some(preloaded_response) = preloaded_response {
                 preloaded_response
+            } else if injected_fault {
+                json!({
+                    "id": id,
+                    "jsonrpc": JSONRPC_VERSION,
+                    "error": {
+                        "code": GENERIC_ERROR_CODE,
+                        "message": "injected fault"
+                    }
+                })
             } else {
                 match handle_rpc(body, ctx).await {
                     Ok(result) => json!({
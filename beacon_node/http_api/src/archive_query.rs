@@ -0,0 +1,171 @@
+//! Admission control for queries which load states from the freezer database.
+//!
+//! Loading a finalized state may require applying many hierarchical diffs and replaying many
+//! blocks. The cost of each such query is estimated before it runs, and it is only admitted
+//! whilst the total estimated cost of running queries is within the configured budget. This
+//! prevents a node which is also validating from being starved by historical queries.
+//!
+//! Queries which don't fit are rejected immediately rather than queued, as admission runs on
+//! beacon processor workers which must not block waiting for other queries.
+use crate::metrics;
+use crate::StateId;
+use beacon_chain::{BeaconChain, BeaconChainError, BeaconChainTypes};
+use eth2::types::StateId as CoreStateId;
+use parking_lot::Mutex;
+use std::time::Duration;
+use store::StateLoadCost;
+use types::Slot;
+use warp_utils::reject::{archive_query_rejected, beacon_chain_error, ArchiveQueryRejected};
+
+/// The cost of applying a single diff, in units of replayed blocks.
+pub const DIFF_COST: u64 = 8;
+
+/// The time after which a query rejected because the budget is in use may be retried.
+pub const BUSY_RETRY_AFTER: Duration = Duration::from_secs(2);
+
+/// Return the cost of `load_cost`, in units of replayed blocks.
+pub fn estimated_cost(load_cost: &StateLoadCost) -> u64 {
+    load_cost
+        .blocks_to_replay
+        .saturating_add(load_cost.diffs_to_apply.saturating_mul(DIFF_COST))
+}
+
+/// Shares a budget between concurrent queries for finalized states.
+#[derive(Debug)]
+pub struct ArchiveQueryLimiter {
+    budget: Option<u64>,
+    in_flight: Mutex<u64>,
+}
+
+/// The share of the budget held by an admitted query, which is released on drop.
+#[must_use]
+pub struct ArchiveQueryPermit<'a> {
+    limiter: &'a ArchiveQueryLimiter,
+    cost: u64,
+}
+
+impl Drop for ArchiveQueryPermit<'_> {
+    fn drop(&mut self) {
+        if self.cost > 0 {
+            *self.limiter.in_flight.lock() -= self.cost;
+        }
+    }
+}
+
+impl ArchiveQueryLimiter {
+    /// Create a limiter which admits queries whose total estimated cost is at most `budget`, or
+    /// which admits all queries if `None`.
+    pub fn new(budget: Option<u64>) -> Self {
+        Self {
+            budget,
+            in_flight: Mutex::new(0),
+        }
+    }
+
+    /// Admit a query which loads the state identified by `state_id`.
+    pub fn admit_state_id<T: BeaconChainTypes>(
+        &self,
+        chain: &BeaconChain<T>,
+        state_id: &StateId,
+    ) -> Result<ArchiveQueryPermit<'_>, warp::Rejection> {
+        if self.budget.is_none() {
+            return Ok(self.free_permit());
+        }
+        let slot = match &state_id.0 {
+            CoreStateId::Slot(slot) => Some(*slot),
+            CoreStateId::Genesis => Some(chain.spec.genesis_slot),
+            CoreStateId::Root(root) => chain
+                .store
+                .load_cold_state_slot(root)
+                .map_err(BeaconChainError::DBError)
+                .map_err(beacon_chain_error)?,
+            CoreStateId::Head | CoreStateId::Finalized | CoreStateId::Justified => None,
+        };
+        match slot {
+            Some(slot) => self.admit(chain, slot, 0),
+            None => Ok(self.free_permit()),
+        }
+    }
+
+    /// Admit a query which loads the state at `slot` and then replays `extra_blocks` blocks on
+    /// top of it.
+    ///
+    /// Queries for unfinalized states are cheap and are always admitted. Other queries are
+    /// rejected if their estimated cost doesn't fit within the budget left by running queries,
+    /// with a hint to retry after `BUSY_RETRY_AFTER` if it would fit within the entire budget.
+    pub fn admit<T: BeaconChainTypes>(
+        &self,
+        chain: &BeaconChain<T>,
+        slot: Slot,
+        extra_blocks: u64,
+    ) -> Result<ArchiveQueryPermit<'_>, warp::Rejection> {
+        let Some(budget) = self.budget else {
+            return Ok(self.free_permit());
+        };
+        if slot >= chain.store.get_split_slot() {
+            return Ok(self.free_permit());
+        }
+
+        let mut load_cost = chain
+            .store
+            .estimate_cold_state_load_cost(slot)
+            .map_err(BeaconChainError::DBError)
+            .map_err(beacon_chain_error)?;
+        load_cost.blocks_to_replay = load_cost.blocks_to_replay.saturating_add(extra_blocks);
+        let cost = estimated_cost(&load_cost);
+        metrics::observe(&metrics::HTTP_API_ARCHIVE_QUERY_COST, cost as f64);
+
+        let reject = |outcome: &str, reason: String, hint: &str, retry_after: Option<Duration>| {
+            metrics::inc_counter_vec(&metrics::HTTP_API_ARCHIVE_QUERIES_TOTAL, &[outcome]);
+            archive_query_rejected(ArchiveQueryRejected {
+                reason,
+                blocks_to_replay: load_cost.blocks_to_replay,
+                diffs_to_apply: load_cost.diffs_to_apply,
+                estimated_cost: cost,
+                budget,
+                hint: hint.to_string(),
+                retry_after,
+            })
+        };
+
+        if cost > budget {
+            return Err(reject(
+                "over_budget",
+                format!(
+                    "estimated cost {} of loading the state at slot {} exceeds the budget {}",
+                    cost, slot, budget
+                ),
+                "query a slot at which a snapshot or diff is stored, or ask the operator to \
+                 raise --http-archive-query-budget",
+                None,
+            ));
+        }
+
+        let mut in_flight = self.in_flight.lock();
+        if in_flight.saturating_add(cost) > budget {
+            return Err(reject(
+                "busy",
+                format!(
+                    "the budget {} is in use by other queries with estimated cost {}",
+                    budget, *in_flight
+                ),
+                "retry later",
+                Some(BUSY_RETRY_AFTER),
+            ));
+        }
+        *in_flight += cost;
+        metrics::inc_counter_vec(&metrics::HTTP_API_ARCHIVE_QUERIES_TOTAL, &["admitted"]);
+
+        Ok(ArchiveQueryPermit {
+            limiter: self,
+            cost,
+        })
+    }
+
+    fn free_permit(&self) -> ArchiveQueryPermit<'_> {
+        ArchiveQueryPermit {
+            limiter: self,
+            cost: 0,
+        }
+    }
+}
@@ -0,0 +1,203 @@
+//! Streaming export of the attestations included on chain in a range of slots.
+//!
+//! Records are computed block by block on a blocking thread and sent to the client as they are
+//! produced, so the response for a long range is never held in memory. The committees required to
+//! resolve attesting indices are built from a single state per epoch, rather than by replaying
+//! every block in the range.
+use crate::archive_query::ArchiveQueryLimiter;
+use beacon_chain::{BeaconChain, BeaconChainTypes, StateSkipConfig};
+use bytes::Bytes;
+use eth2::lighthouse::{AttestationExportQuery, ExportedAttestation};
+use eth2::types::Accept;
+use eth2::SSZ_CONTENT_TYPE_HEADER;
+use ssz::Encode;
+use state_processing::common::get_attesting_indices_from_state;
+use std::sync::Arc;
+use tokio::sync::mpsc;
+use tokio_stream::wrappers::ReceiverStream;
+use types::{BeaconState, Epoch, EthSpec, RelativeEpoch, Slot};
+use warp::http::{header::CONTENT_TYPE, Response};
+use warp::hyper::Body;
+use warp_utils::reject::{custom_bad_request, custom_server_error};
+
+/// The maximum number of slots which may be exported by a single request.
+pub const MAX_EXPORT_SLOTS: u64 = 8192;
+
+/// The content type of an export encoded as newline-delimited JSON.
+pub const NDJSON_CONTENT_TYPE: &str = "application/x-ndjson";
+
+/// The number of encoded blocks which may be buffered ahead of a slow client.
+const CHANNEL_CAPACITY: usize = 4;
+
+type Chunk = Result<Bytes, String>;
+
+/// Stream the attestations included in blocks from `start_slot` to `end_slot` inclusive, as
+/// newline-delimited JSON or, if SSZ is accepted, as length-prefixed SSZ records.
+pub fn export_attestations<T: BeaconChainTypes>(
+    query: AttestationExportQuery,
+    accept: Option<Accept>,
+    chain: Arc<BeaconChain<T>>,
+    archive_query: Arc<ArchiveQueryLimiter>,
+) -> Result<Response<Body>, warp::Rejection> {
+    let AttestationExportQuery {
+        start_slot,
+        end_slot,
+    } = query;
+    if start_slot > end_slot {
+        return Err(custom_bad_request(format!(
+            "start_slot {} is after end_slot {}",
+            start_slot, end_slot
+        )));
+    }
+    let slots = end_slot.as_u64() - start_slot.as_u64() + 1;
+    if slots > MAX_EXPORT_SLOTS {
+        return Err(custom_bad_request(format!(
+            "range of {} slots exceeds the limit of {}",
+            slots, MAX_EXPORT_SLOTS
+        )));
+    }
+    let head_slot = chain.canonical_head.cached_head().head_slot();
+    if end_slot > head_slot {
+        return Err(custom_bad_request(format!(
+            "end_slot {} is after the head slot {}",
+            end_slot, head_slot
+        )));
+    }
+    let oldest_block_slot = chain.store.get_oldest_block_slot();
+    if start_slot < oldest_block_slot {
+        return Err(custom_bad_request(format!(
+            "start_slot {} is prior to the oldest block stored at slot {}",
+            start_slot, oldest_block_slot
+        )));
+    }
+
+    let ssz = matches!(accept, Some(Accept::Ssz));
+    let (tx, rx) = mpsc::channel(CHANNEL_CAPACITY);
+    chain.task_executor.clone().spawn_blocking(
+        move || {
+            if let Err(e) =
+                send_attestations(&chain, &archive_query, start_slot, end_slot, ssz, &tx)
+            {
+                // The response has already started, so the error can only be signalled by
+                // aborting it.
+                let _ = tx.blocking_send(Err(e));
+            }
+        },
+        "http_attestation_export",
+    );
+
+    Response::builder()
+        .status(200)
+        .header(
+            CONTENT_TYPE,
+            if ssz {
+                SSZ_CONTENT_TYPE_HEADER
+            } else {
+                NDJSON_CONTENT_TYPE
+            },
+        )
+        .body(Body::wrap_stream(ReceiverStream::new(rx)))
+        .map_err(|e| custom_server_error(format!("failed to create response: {}", e)))
+}
+
+/// Encode and send the attestations of each block in the range, stopping early if the client
+/// disconnects.
+fn send_attestations<T: BeaconChainTypes>(
+    chain: &BeaconChain<T>,
+    archive_query: &ArchiveQueryLimiter,
+    start_slot: Slot,
+    end_slot: Slot,
+    ssz: bool,
+    tx: &mpsc::Sender<Chunk>,
+) -> Result<(), String> {
+    let block_roots = chain
+        .forwards_iter_block_roots_until(start_slot, end_slot)
+        .map_err(|e| format!("unable to iterate block roots: {:?}", e))?;
+
+    let mut previous_block_root = None;
+    let mut committee_state: Option<BeaconState<T::EthSpec>> = None;
+    for result in block_roots {
+        let (block_root, slot) =
+            result.map_err(|e| format!("unable to iterate block roots: {:?}", e))?;
+        // Skipped slots repeat the root of the previous block.
+        if previous_block_root == Some(block_root) {
+            continue;
+        }
+        previous_block_root = Some(block_root);
+
+        let block = chain
+            .get_blinded_block(&block_root)
+            .map_err(|e| format!("unable to load block {:?}: {:?}", block_root, e))?
+            .ok_or_else(|| format!("missing block {:?}", block_root))?;
+        // The first slot of the range may be skipped, in which case its root is of an earlier
+        // block.
+        if block.slot() != slot {
+            continue;
+        }
+
+        let epoch = slot.epoch(T::EthSpec::slots_per_epoch());
+        if committee_state
+            .as_ref()
+            .map_or(true, |state| state.current_epoch() != epoch)
+        {
+            committee_state = Some(load_committee_state(chain, archive_query, epoch)?);
+        }
+        let state = committee_state
+            .as_ref()
+            .ok_or("committee state was not loaded")?;
+
+        let mut chunk = vec![];
+        for (attestation_index, attestation) in block.message().body().attestations().enumerate() {
+            let attesting_indices = get_attesting_indices_from_state(state, attestation)
+                .map_err(|e| format!("unable to compute attesting indices: {:?}", e))?;
+            let data = attestation.data().clone();
+            let record = ExportedAttestation {
+                block_slot: slot,
+                block_root,
+                proposer_index: block.message().proposer_index(),
+                attestation_index: attestation_index as u64,
+                inclusion_distance: slot.saturating_sub(data.slot).as_u64(),
+                data,
+                attesting_indices,
+            };
+            if ssz {
+                let bytes = record.as_ssz_bytes();
+                chunk.extend_from_slice(&(bytes.len() as u32).to_le_bytes());
+                chunk.extend_from_slice(&bytes);
+            } else {
+                serde_json::to_writer(&mut chunk, &record)
+                    .map_err(|e| format!("unable to encode attestation: {:?}", e))?;
+                chunk.push(b'\n');
+            }
+        }
+
+        if !chunk.is_empty() && tx.blocking_send(Ok(chunk.into())).is_err() {
+            // The client has disconnected.
+            return Ok(());
+        }
+    }
+    Ok(())
+}
+
+/// Load the state at the start of `epoch`, with the committees of `epoch` and the previous epoch.
+///
+/// These are the only epochs whose attestations may be included in a block in `epoch`.
+fn load_committee_state<T: BeaconChainTypes>(
+    chain: &BeaconChain<T>,
+    archive_query: &ArchiveQueryLimiter,
+    epoch: Epoch,
+) -> Result<BeaconState<T::EthSpec>, String> {
+    let slot = epoch.start_slot(T::EthSpec::slots_per_epoch());
+    let _permit = archive_query
+        .admit(chain, slot, 0)
+        .map_err(|e| format!("state query rejected: {:?}", e))?;
+    let mut state = chain
+        .state_at_slot(slot, StateSkipConfig::WithoutStateRoots)
+        .map_err(|e| format!("unable to load state at slot {}: {:?}", slot, e))?;
+    for relative_epoch in [RelativeEpoch::Previous, RelativeEpoch::Current] {
+        state
+            .build_committee_cache(relative_epoch, &chain.spec)
+            .map_err(|e| format!("unable to build committee cache: {:?}", e))?;
+    }
+    Ok(state)
+}
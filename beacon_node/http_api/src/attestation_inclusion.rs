@@ -0,0 +1,68 @@
+use crate::archive_query::ArchiveQueryLimiter;
+use crate::sync_committee_rewards::get_state_before_applying_block;
+use crate::BlockId;
+use beacon_chain::{BeaconChain, BeaconChainTypes};
+use eth2::lighthouse::{AttestationInclusion, AttestationInclusionRecord};
+use state_processing::common::get_attesting_indices_from_state;
+use std::sync::Arc;
+use types::RelativeEpoch;
+use warp_utils::reject::{beacon_state_error, custom_bad_request};
+
+/// Find the attestations in the block identified by `block_id` which include a vote from
+/// `validator_index`.
+///
+/// The committees are computed from the parent state advanced to the block's slot, which is the
+/// state the block's attestations were processed against.
+pub fn attestation_inclusion<T: BeaconChainTypes>(
+    chain: Arc<BeaconChain<T>>,
+    block_id: BlockId,
+    validator_index: u64,
+    archive_query: &ArchiveQueryLimiter,
+) -> Result<AttestationInclusion, warp::Rejection> {
+    let (block, _, _) = block_id.blinded_block(&chain)?;
+    let block_root = block.canonical_root();
+
+    let mut state = get_state_before_applying_block(chain.clone(), &block, archive_query)?;
+    if state.validators().get(validator_index as usize).is_none() {
+        return Err(custom_bad_request(format!(
+            "unknown validator index {validator_index}"
+        )));
+    }
+    for relative_epoch in [RelativeEpoch::Previous, RelativeEpoch::Current] {
+        state
+            .build_committee_cache(relative_epoch, &chain.spec)
+            .map_err(beacon_state_error)?;
+    }
+
+    let mut inclusions = vec![];
+    for (attestation_index, attestation) in block.message().body().attestations().enumerate() {
+        let attesting_indices =
+            get_attesting_indices_from_state(&state, attestation).map_err(beacon_state_error)?;
+        if !attesting_indices.contains(&validator_index) {
+            continue;
+        }
+
+        let slot = attestation.data().slot;
+        let committee_index = state
+            .get_beacon_committees_at_slot(slot)
+            .map_err(beacon_state_error)?
+            .iter()
+            .find(|committee| committee.committee.contains(&(validator_index as usize)))
+            .map_or(attestation.data().index, |committee| committee.index);
+
+        inclusions.push(AttestationInclusionRecord {
+            attestation_index: attestation_index as u64,
+            slot,
+            committee_index,
+            inclusion_distance: block.slot().saturating_sub(slot).as_u64(),
+        });
+    }
+
+    Ok(AttestationInclusion {
+        block_root,
+        block_slot: block.slot(),
+        validator_index,
+        included: !inclusions.is_empty(),
+        inclusions,
+    })
+}
@@ -299,17 +299,34 @@ impl BlockId {
             BlobSidecarList::default()
         };
 
-        let blob_sidecar_list_filtered = match indices.indices {
-            Some(vec) => {
+        let blob_sidecar_list_filtered =
+            if indices.indices.is_none() && indices.versioned_hashes.is_none() {
+                blob_sidecar_list
+            } else {
+                // Blobs must match every filter which is supplied. Versioned hashes are computed
+                // from the block's commitments rather than from each sidecar.
+                let requested_indices = blob_kzg_commitments
+                    .iter()
+                    .enumerate()
+                    .map(|(index, commitment)| (index as u64, commitment))
+                    .filter(|(index, commitment)| {
+                        indices
+                            .indices
+                            .as_ref()
+                            .map_or(true, |indices| indices.contains(index))
+                            && indices.versioned_hashes.as_ref().map_or(true, |hashes| {
+                                hashes.contains(&commitment.calculate_versioned_hash())
+                            })
+                    })
+                    .map(|(index, _)| index)
+                    .collect::<Vec<_>>();
                 let list = blob_sidecar_list
                     .into_iter()
-                    .filter(|blob_sidecar| vec.contains(&blob_sidecar.index))
+                    .filter(|blob_sidecar| requested_indices.contains(&blob_sidecar.index))
                     .collect();
                 BlobSidecarList::new(list)
                     .map_err(|e| warp_utils::reject::custom_server_error(format!("{:?}", e)))?
-            }
-            None => blob_sidecar_list,
-        };
+            };
         Ok((
             block,
             blob_sidecar_list_filtered,
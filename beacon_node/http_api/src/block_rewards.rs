@@ -1,13 +1,18 @@
+use crate::archive_query::ArchiveQueryLimiter;
 use beacon_chain::{BeaconChain, BeaconChainError, BeaconChainTypes, WhenSlotSkipped};
-use eth2::lighthouse::{BlockReward, BlockRewardsQuery};
+use eth2::lighthouse::{BlockReward, BlockRewardSources, BlockRewardsQuery, PeerRewardAttribution};
 use lru::LruCache;
 use slog::{debug, warn, Logger};
 use state_processing::BlockReplayer;
+use std::collections::BTreeMap;
 use std::num::NonZeroUsize;
 use std::sync::Arc;
 use types::beacon_block::BlindedBeaconBlock;
 use types::non_zero_usize::new_non_zero_usize;
-use warp_utils::reject::{beacon_chain_error, beacon_state_error, custom_bad_request};
+use types::Slot;
+use warp_utils::reject::{
+    beacon_chain_error, beacon_state_error, custom_bad_request, custom_not_found,
+};
 
 const STATE_CACHE_SIZE: NonZeroUsize = new_non_zero_usize(2);
 
@@ -16,6 +21,7 @@ pub fn get_block_rewards<T: BeaconChainTypes>(
     query: BlockRewardsQuery,
     chain: Arc<BeaconChain<T>>,
     log: Logger,
+    archive_query: &ArchiveQueryLimiter,
 ) -> Result<Vec<BlockReward>, warp::Rejection> {
     let start_slot = query.start_slot;
     let end_slot = query.end_slot;
@@ -38,6 +44,8 @@ pub fn get_block_rewards<T: BeaconChainTypes>(
         .load_blocks_to_replay(start_slot, end_slot, end_block_root)
         .map_err(|e| beacon_chain_error(e.into()))?;
 
+    let _permit = archive_query.admit(&chain, prior_slot, blocks.len() as u64)?;
+
     let state_root = chain
         .state_root_at_slot(prior_slot)
         .map_err(beacon_chain_error)?
@@ -182,3 +190,66 @@ pub fn compute_block_rewards<T: BeaconChainTypes>(
 
     Ok(block_rewards)
 }
+
+/// Attribute the attestation rewards of the canonical block at `slot` to the peers which first
+/// delivered the attestations it includes.
+pub fn get_block_reward_sources<T: BeaconChainTypes>(
+    slot: Slot,
+    chain: Arc<BeaconChain<T>>,
+    log: Logger,
+) -> Result<BlockRewardSources, warp::Rejection> {
+    if !chain.config.track_attestation_sources {
+        return Err(custom_bad_request(
+            "attestation source tracking is disabled, enable it with \
+             --track-attestation-sources"
+                .to_string(),
+        ));
+    }
+
+    let block_root = chain
+        .block_root_at_slot(slot, WhenSlotSkipped::None)
+        .map_err(beacon_chain_error)?
+        .ok_or_else(|| custom_not_found(format!("no canonical block at slot {}", slot)))?;
+    let block = chain
+        .get_blinded_block(&block_root)
+        .map_err(beacon_chain_error)?
+        .ok_or_else(|| custom_not_found(format!("block not found: {:?}", block_root)))?;
+
+    let target_epochs = block
+        .message()
+        .body()
+        .attestations()
+        .map(|attestation| attestation.data().target.epoch)
+        .collect::<Vec<_>>();
+
+    let block_reward = compute_block_rewards(vec![block.message().clone()], chain.clone(), log)?
+        .pop()
+        .ok_or_else(|| custom_bad_request("unable to compute block reward".to_string()))?;
+
+    let mut peers = BTreeMap::<String, PeerRewardAttribution>::new();
+    let mut unattributed = PeerRewardAttribution::default();
+
+    let sources = chain.attestation_sources.lock();
+    for (target_epoch, rewards) in target_epochs
+        .into_iter()
+        .zip(&block_reward.attestation_rewards.per_attestation_rewards)
+    {
+        for (&validator_index, &reward) in rewards {
+            let attribution = match sources.source(target_epoch, validator_index) {
+                Some(peer_id) => peers.entry(peer_id.to_string()).or_default(),
+                None => &mut unattributed,
+            };
+            attribution.reward += reward;
+            attribution.attesters += 1;
+        }
+    }
+    drop(sources);
+
+    Ok(BlockRewardSources {
+        block_root,
+        meta: block_reward.meta,
+        attestation_rewards_total: block_reward.attestation_rewards.total,
+        peers,
+        unattributed,
+    })
+}
@@ -0,0 +1,87 @@
+use beacon_chain::{BeaconChain, BeaconChainTypes, WhenSlotSkipped};
+use eth2::lighthouse::BlocksBatchRequest;
+use futures::StreamExt;
+use std::sync::Arc;
+use types::{Hash256, SignedBeaconBlock, Slot};
+use warp_utils::reject::{beacon_chain_error, custom_bad_request, custom_server_error};
+
+/// The maximum number of block roots or slots which may be requested at once.
+pub const MAX_BLOCKS_PER_BATCH: u64 = 128;
+
+/// Load the blocks selected by `request`, in order, omitting unknown roots and skipped slots.
+///
+/// Blocks are loaded with the `BeaconBlockStreamer`, which reads from the database on blocking
+/// threads and fetches the payloads of blinded blocks from the execution layer in bulk.
+pub async fn get_blocks_batch<T: BeaconChainTypes>(
+    request: BlocksBatchRequest,
+    chain: Arc<BeaconChain<T>>,
+) -> Result<Vec<Arc<SignedBeaconBlock<T::EthSpec>>>, warp::Rejection> {
+    let block_roots = match request {
+        BlocksBatchRequest::Roots { block_roots } => {
+            check_batch_size(block_roots.len() as u64)?;
+            block_roots
+        }
+        BlocksBatchRequest::Range { start_slot, count } => {
+            check_batch_size(count)?;
+            let chain_clone = chain.clone();
+            chain
+                .task_executor
+                .spawn_blocking_handle(
+                    move || canonical_block_roots(&chain_clone, start_slot, count),
+                    "blocks_batch_block_roots",
+                )
+                .ok_or_else(|| custom_server_error("shutting down".to_string()))?
+                .await
+                .map_err(|e| custom_server_error(format!("block roots task failed: {e:?}")))??
+        }
+    };
+
+    let num_roots = block_roots.len();
+    let mut block_stream = chain.get_blocks(block_roots).map_err(beacon_chain_error)?;
+    let mut blocks = Vec::with_capacity(num_roots);
+    while let Some((block_root, result)) = block_stream.next().await {
+        match result.as_ref() {
+            Ok(Some(block)) => blocks.push(block.clone()),
+            Ok(None) => {}
+            Err(e) => {
+                return Err(custom_server_error(format!(
+                    "failed to load block {block_root:?}: {e:?}"
+                )))
+            }
+        }
+    }
+    Ok(blocks)
+}
+
+/// Return the roots of the canonical blocks in `count` slots from `start_slot`.
+fn canonical_block_roots<T: BeaconChainTypes>(
+    chain: &BeaconChain<T>,
+    start_slot: Slot,
+    count: u64,
+) -> Result<Vec<Hash256>, warp::Rejection> {
+    // Slots after the head can't contain canonical blocks.
+    let end_slot = std::cmp::min(
+        start_slot.as_u64().saturating_add(count),
+        chain.canonical_head.cached_head().head_slot().as_u64() + 1,
+    );
+    let mut block_roots = vec![];
+    for slot in (start_slot.as_u64()..end_slot).map(Slot::new) {
+        if let Some(block_root) = chain
+            .block_root_at_slot(slot, WhenSlotSkipped::None)
+            .map_err(beacon_chain_error)?
+        {
+            block_roots.push(block_root);
+        }
+    }
+    Ok(block_roots)
+}
+
+fn check_batch_size(size: u64) -> Result<(), warp::Rejection> {
+    if size > MAX_BLOCKS_PER_BATCH {
+        return Err(custom_bad_request(format!(
+            "batch of {} blocks exceeds the limit of {}",
+            size, MAX_BLOCKS_PER_BATCH
+        )));
+    }
+    Ok(())
+}
@@ -2,6 +2,7 @@ use beacon_chain::store::metadata::CURRENT_SCHEMA_VERSION;
 use beacon_chain::{BeaconChain, BeaconChainTypes};
 use eth2::lighthouse::DatabaseInfo;
 use std::sync::Arc;
+use types::EthSpec;
 
 pub fn info<T: BeaconChainTypes>(
     chain: Arc<BeaconChain<T>>,
@@ -11,6 +12,13 @@ pub fn info<T: BeaconChainTypes>(
     let config = store.get_config().clone();
     let anchor = store.get_anchor_info();
     let blob_info = store.get_blob_info();
+    let finalized_slot = chain
+        .canonical_head
+        .cached_head()
+        .finalized_checkpoint()
+        .epoch
+        .start_slot(T::EthSpec::slots_per_epoch());
+    let migration_lag = finalized_slot.saturating_sub(split.slot).as_u64();
 
     Ok(DatabaseInfo {
         schema_version: CURRENT_SCHEMA_VERSION.as_u64(),
@@ -18,5 +26,23 @@ pub fn info<T: BeaconChainTypes>(
         split,
         anchor,
         blob_info,
+        finalized_slot,
+        migration_lag,
     })
 }
+
+pub fn migrate<T: BeaconChainTypes>(
+    chain: Arc<BeaconChain<T>>,
+) -> Result<&'static str, warp::Rejection> {
+    let scheduled = chain
+        .manually_migrate_database()
+        .map_err(warp_utils::reject::beacon_chain_error)?;
+
+    if scheduled {
+        Ok("success")
+    } else {
+        Err(warp_utils::reject::custom_bad_request(
+            "split point is already at the finalized checkpoint".to_string(),
+        ))
+    }
+}
@@ -0,0 +1,235 @@
+//! Determines the reasons that the beacon node is degraded, for the `eth/v1/node/health` and
+//! `lighthouse/health` endpoints.
+//!
+//! These allow orchestration tooling to distinguish between e.g. a node which is syncing and a
+//! node whose execution layer has gone offline, rather than relying on the status code alone.
+use beacon_chain::storage_watchdog::StoragePressure;
+use beacon_chain::{BeaconChain, BeaconChainTypes};
+use eth2::lighthouse::{Degradation, DegradationReason, HealthReport, HealthSeverity};
+use lighthouse_network::NetworkGlobals;
+use parking_lot::{Mutex, RwLock};
+use slot_clock::SlotClock;
+use std::path::PathBuf;
+use std::time::{Duration, Instant};
+use sysinfo::{System, SystemExt};
+use types::EthSpec;
+use warp::http::StatusCode;
+use warp_utils::reject::beacon_chain_error;
+
+/// The head is considered stale once it is more than this many slots behind the current slot.
+const STALE_HEAD_SLOTS: u64 = 2;
+/// The peer count is considered low once it falls below the target peer count divided by this.
+const LOW_PEER_COUNT_DIVISOR: usize = 4;
+/// The disk is considered nearly full once the percentage of free space falls below this.
+const DISK_FREE_WARNING_PERCENT: u64 = 10;
+/// The node is considered unable to function once the percentage of free space falls below this.
+const DISK_FREE_CRITICAL_PERCENT: u64 = 2;
+/// How long the disk statistics used by the health endpoints are cached for.
+const DISK_SPACE_REFRESH_INTERVAL: Duration = Duration::from_secs(30);
+
+/// The space on the disk holding the data directory, refreshed at most once per
+/// `DISK_SPACE_REFRESH_INTERVAL`.
+///
+/// Health endpoints are probed frequently by orchestration tooling, so they must not query the
+/// file system on every request.
+pub struct DiskSpaceCache {
+    data_dir: PathBuf,
+    sysinfo: Mutex<System>,
+    cached: RwLock<Option<CachedDiskSpace>>,
+}
+
+#[derive(Clone, Copy)]
+struct CachedDiskSpace {
+    observed_at: Instant,
+    disk_bytes_total: u64,
+    disk_bytes_free: u64,
+}
+
+impl DiskSpaceCache {
+    pub fn new(data_dir: PathBuf) -> Self {
+        let mut sysinfo = System::new();
+        sysinfo.refresh_disks_list();
+        Self {
+            data_dir,
+            sysinfo: Mutex::new(sysinfo),
+            cached: RwLock::new(None),
+        }
+    }
+
+    /// Returns the total and available bytes of the disk holding the data directory.
+    ///
+    /// If the cached values are stale they are refreshed, unless another request is already
+    /// refreshing them, in which case the stale values are returned.
+    pub fn disk_space(&self) -> (u64, u64) {
+        let cached = *self.cached.read();
+        let is_stale = cached.map_or(true, |cached| {
+            cached.observed_at.elapsed() >= DISK_SPACE_REFRESH_INTERVAL
+        });
+
+        if is_stale {
+            if let Some(mut sysinfo) = self.sysinfo.try_lock() {
+                sysinfo.refresh_disks();
+                let (disk_bytes_total, disk_bytes_free) =
+                    system_health::observe_disk_space(&sysinfo, &self.data_dir);
+                *self.cached.write() = Some(CachedDiskSpace {
+                    observed_at: Instant::now(),
+                    disk_bytes_total,
+                    disk_bytes_free,
+                });
+                return (disk_bytes_total, disk_bytes_free);
+            }
+        }
+
+        cached.map_or((0, 0), |cached| {
+            (cached.disk_bytes_total, cached.disk_bytes_free)
+        })
+    }
+}
+
+/// Determine all the reasons that the node is currently degraded.
+///
+/// The `el_offline` status is supplied by the caller, since determining it requires an async
+/// call to the execution layer.
+pub fn observe_health_report<T: BeaconChainTypes>(
+    chain: &BeaconChain<T>,
+    network_globals: &NetworkGlobals<T::EthSpec>,
+    disk_space: &DiskSpaceCache,
+    el_offline: bool,
+) -> Result<HealthReport, warp::Rejection> {
+    let mut reasons = vec![];
+    let mut degraded = |reason, severity| reasons.push(Degradation { reason, severity });
+
+    if el_offline {
+        degraded(
+            DegradationReason::ExecutionLayerOffline,
+            HealthSeverity::Critical,
+        );
+    }
+
+    if !network_globals.sync_state.read().is_synced() {
+        degraded(DegradationReason::Syncing, HealthSeverity::Warning);
+    }
+
+    if chain
+        .is_optimistic_or_invalid_head()
+        .map_err(beacon_chain_error)?
+    {
+        degraded(DegradationReason::OptimisticHead, HealthSeverity::Warning);
+    }
+
+    let connected_peers = network_globals.connected_peers();
+    let target_peers = network_globals.config.target_peers;
+    if connected_peers == 0 {
+        degraded(
+            DegradationReason::LowPeerCount {
+                connected_peers,
+                target_peers,
+            },
+            HealthSeverity::Critical,
+        );
+    } else if connected_peers < target_peers / LOW_PEER_COUNT_DIVISOR {
+        degraded(
+            DegradationReason::LowPeerCount {
+                connected_peers,
+                target_peers,
+            },
+            HealthSeverity::Warning,
+        );
+    }
+
+    let cached_head = chain.canonical_head.cached_head();
+    let head_slot = cached_head.head_slot();
+    if let Ok(current_slot) = chain.slot() {
+        let slots_behind = current_slot.saturating_sub(head_slot).as_u64();
+        if slots_behind > STALE_HEAD_SLOTS {
+            let severity = if slots_behind >= T::EthSpec::slots_per_epoch() {
+                HealthSeverity::Critical
+            } else {
+                HealthSeverity::Warning
+            };
+            degraded(
+                DegradationReason::StaleHead {
+                    head_slot,
+                    current_slot,
+                    slots_behind,
+                },
+                severity,
+            );
+        }
+    }
+
+    let (disk_bytes_total, disk_bytes_free) = disk_space.disk_space();
+    if disk_bytes_total > 0 {
+        let free_percent = disk_bytes_free.saturating_mul(100) / disk_bytes_total;
+        let severity = if free_percent < DISK_FREE_CRITICAL_PERCENT {
+            Some(HealthSeverity::Critical)
+        } else if free_percent < DISK_FREE_WARNING_PERCENT {
+            Some(HealthSeverity::Warning)
+        } else {
+            None
+        };
+        if let Some(severity) = severity {
+            degraded(
+                DegradationReason::DiskNearlyFull {
+                    disk_bytes_free,
+                    disk_bytes_total,
+                },
+                severity,
+            );
+        }
+    }
+
+    let storage_pressure = chain.storage_watchdog.pressure();
+    if storage_pressure > StoragePressure::Normal {
+        let severity = if storage_pressure >= StoragePressure::ReadOnly {
+            HealthSeverity::Critical
+        } else {
+            HealthSeverity::Warning
+        };
+        degraded(
+            DegradationReason::StoragePressure {
+                pressure: storage_pressure.to_string(),
+            },
+            severity,
+        );
+    }
+
+    // A block can only arrive before the start of its slot if our clock is behind that of the
+    // proposer. A clock which is ahead cannot be distinguished from a block which arrived late.
+    let observed = chain
+        .block_times_cache
+        .read()
+        .cache
+        .get(&cached_head.head_block_root())
+        .and_then(|times| times.timestamps.gossip_observed);
+    if let (Some(observed), Some(slot_start)) = (observed, chain.slot_clock.start_of(head_slot)) {
+        if let Some(skew) = slot_start.checked_sub(observed) {
+            if skew > chain.config.clock_skew_threshold {
+                degraded(
+                    DegradationReason::ClockSkew {
+                        skew_millis: skew.as_millis() as u64,
+                    },
+                    HealthSeverity::Warning,
+                );
+            }
+        }
+    }
+
+    Ok(HealthReport::new(reasons))
+}
+
+/// The status code for the `eth/v1/node/health` endpoint, as defined by the beacon API.
+pub fn status_code(report: &HealthReport) -> StatusCode {
+    if report.has_reason(|reason| matches!(reason, DegradationReason::ExecutionLayerOffline)) {
+        StatusCode::SERVICE_UNAVAILABLE
+    } else if report.has_reason(|reason| {
+        matches!(
+            reason,
+            DegradationReason::Syncing | DegradationReason::OptimisticHead
+        )
+    }) {
+        StatusCode::PARTIAL_CONTENT
+    } else {
+        StatusCode::OK
+    }
+}
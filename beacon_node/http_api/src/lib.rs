@@ -5,20 +5,29 @@
 //! There are also some additional, non-standard endpoints behind the `/lighthouse/` path which are
 //! used for development.
 
+mod archive_query;
+mod attestation_export;
+mod attestation_inclusion;
 mod attestation_performance;
 mod attester_duties;
 mod block_id;
 mod block_packing_efficiency;
 mod block_rewards;
+mod blocks_batch;
 mod build_block_contents;
 mod builder_states;
 mod database;
+mod health;
 mod light_client;
 mod metrics;
+mod op_pool;
+mod pending_queues;
 mod produce_block;
 mod proposer_duties;
 mod publish_attestations;
 mod publish_blocks;
+mod publish_sidecars;
+mod ssz_bodies;
 mod standard_block_rewards;
 mod state_id;
 mod sync_committee_rewards;
@@ -30,16 +39,23 @@ mod validator;
 mod validator_inclusion;
 mod validators;
 mod version;
+mod withdrawal_projection;
 
+use crate::archive_query::ArchiveQueryLimiter;
 use crate::light_client::{get_light_client_bootstrap, get_light_client_updates};
-use crate::produce_block::{produce_blinded_block_v2, produce_block_v2, produce_block_v3};
+use crate::produce_block::{
+    produce_blinded_block_v2, produce_block_v2, produce_block_v3, produce_block_v3_on_parent,
+};
 use crate::version::fork_versioned_response;
 use beacon_chain::{
-    attestation_verification::VerifiedAttestation, observed_operations::ObservationOutcome,
-    validator_monitor::timestamp_now, AttestationError as AttnError, BeaconChain, BeaconChainError,
-    BeaconChainTypes, WhenSlotSkipped,
+    attestation_verification::VerifiedAttestation, head_history::HEAD_HISTORY_EPOCHS,
+    observed_operations::ObservationOutcome, validator_monitor::timestamp_now,
+    AttestationError as AttnError, BeaconChain, BeaconChainError, BeaconChainTypes,
+    WhenSlotSkipped,
+};
+use beacon_processor::{
+    work_reprocessing_queue::ReprocessQueueMessage, BeaconProcessorQueueStats, BeaconProcessorSend,
 };
-use beacon_processor::{work_reprocessing_queue::ReprocessQueueMessage, BeaconProcessorSend};
 pub use block_id::BlockId;
 use builder_states::get_next_withdrawals;
 use bytes::Bytes;
@@ -50,7 +66,9 @@ use eth2::types::{
     ValidatorStatus, ValidatorsRequestBody,
 };
 use eth2::{CONSENSUS_VERSION_HEADER, CONTENT_TYPE_HEADER, SSZ_CONTENT_TYPE_HEADER};
-use lighthouse_network::{types::SyncState, EnrExt, NetworkGlobals, PeerId, PubsubMessage};
+use lighthouse_network::{
+    types::SyncState, EnrExt, Eth2Enr, NetworkGlobals, PeerId, PubsubMessage,
+};
 use lighthouse_version::version_with_platform;
 use logging::SSELoggingComponents;
 use network::{NetworkMessage, NetworkSenders, ValidatorSubscriptionMessage};
@@ -60,6 +78,7 @@ pub use publish_blocks::{
     publish_blinded_block, publish_block, reconstruct_block, ProvenancedBlock,
 };
 use serde::{Deserialize, Serialize};
+use serde_utils::quoted_u64::Quoted;
 use slog::{crit, debug, error, info, warn, Logger};
 use slot_clock::SlotClock;
 use ssz::Encode;
@@ -82,11 +101,12 @@ use tokio_stream::{
 };
 use types::{
     fork_versioned_response::EmptyMetadata, Attestation, AttestationData, AttestationShufflingId,
-    AttesterSlashing, BeaconStateError, CommitteeCache, ConfigAndPreset, Epoch, EthSpec, ForkName,
-    ForkVersionedResponse, Hash256, ProposerPreparationData, ProposerSlashing, RelativeEpoch,
+    AttesterSlashing, BeaconStateError, BlobSidecar, CommitteeCache, ConfigAndPreset,
+    DataColumnSidecar, Epoch, EthSpec, ForkName, ForkVersionedResponse, Hash256,
+    ProposerPreparationData, ProposerSlashing, PublicKeyBytes, RelativeEpoch,
     SignedAggregateAndProof, SignedBlindedBeaconBlock, SignedBlsToExecutionChange,
     SignedContributionAndProof, SignedValidatorRegistrationData, SignedVoluntaryExit, Slot,
-    SyncCommitteeMessage, SyncContributionData,
+    SubnetId, SyncCommitteeMessage, SyncContributionData,
 };
 use validator::pubkey_to_validator_index;
 use version::{
@@ -94,12 +114,16 @@ use version::{
     execution_optimistic_finalized_fork_versioned_response, inconsistent_fork_rejection,
     unsupported_version_rejection, V1, V2, V3,
 };
+use warp::filters::BoxedFilter;
 use warp::http::StatusCode;
 use warp::hyper::Body;
 use warp::sse::Event;
 use warp::Reply;
 use warp::{http::Response, Filter, Rejection};
-use warp_utils::{query::multi_key_query, reject::convert_rejection, uor::UnifyingOrFilter};
+use warp_utils::{
+    metrics_access::constant_time_eq, query::multi_key_query, reject::convert_rejection,
+    uor::UnifyingOrFilter,
+};
 
 const API_PREFIX: &str = "eth";
 
@@ -133,11 +157,36 @@ pub struct Context<T: BeaconChainTypes> {
     pub network_globals: Option<Arc<NetworkGlobals<T::EthSpec>>>,
     pub beacon_processor_send: Option<BeaconProcessorSend<T::EthSpec>>,
     pub beacon_processor_reprocess_send: Option<Sender<ReprocessQueueMessage>>,
+    pub beacon_processor_queue_stats: Option<BeaconProcessorQueueStats>,
     pub eth1_service: Option<eth1::Service>,
     pub sse_logging_components: Option<SSELoggingComponents>,
     pub log: Logger,
 }
 
+/// Configuration for a separate listener which serves the `/lighthouse` routes.
+///
+/// When present, the `/lighthouse` routes are removed from the main listener.
+#[derive(PartialEq, Debug, Clone, Serialize, Deserialize)]
+pub struct AdminConfig {
+    pub listen_addr: IpAddr,
+    pub listen_port: u16,
+    pub allow_origin: Option<String>,
+    /// A file containing a token which must be provided as a bearer token in the `Authorization`
+    /// header of every request to the admin listener.
+    pub api_token_path: Option<PathBuf>,
+}
+
+impl Default for AdminConfig {
+    fn default() -> Self {
+        Self {
+            listen_addr: IpAddr::V4(Ipv4Addr::new(127, 0, 0, 1)),
+            listen_port: 5053,
+            allow_origin: None,
+            api_token_path: None,
+        }
+    }
+}
+
 /// Configuration for the HTTP server.
 #[derive(PartialEq, Debug, Clone, Serialize, Deserialize)]
 pub struct Config {
@@ -152,7 +201,13 @@ pub struct Config {
     #[serde(with = "eth2::types::serde_status_code")]
     pub duplicate_block_status_code: StatusCode,
     pub enable_light_client_server: bool,
+    pub enable_unsafe_block_production: bool,
     pub target_peers: usize,
+    /// The maximum total estimated cost of concurrent queries for finalized states, in units of
+    /// replayed blocks, or `None` for no limit.
+    pub archive_query_budget: Option<u64>,
+    /// Serve the `/lighthouse` routes on a separate listener.
+    pub admin: Option<AdminConfig>,
 }
 
 impl Default for Config {
@@ -168,7 +223,10 @@ impl Default for Config {
             enable_beacon_processor: true,
             duplicate_block_status_code: StatusCode::ACCEPTED,
             enable_light_client_server: false,
+            enable_unsafe_block_production: false,
             target_peers: 100,
+            archive_query_budget: None,
+            admin: None,
         }
     }
 }
@@ -507,6 +565,10 @@ pub fn serve<T: BeaconChainTypes>(
         system_info.refresh_cpu();
     } // end lock
 
+    // The health endpoints only require disk statistics, which are cached between probes.
+    let health_disk_space = Arc::new(health::DiskSpaceCache::new(ctx.config.data_dir.clone()));
+    let health_disk_space_filter = warp::any().map(move || health_disk_space.clone());
+
     let system_info_filter =
         warp::any()
             .map(move || system_info.clone())
@@ -540,6 +602,23 @@ pub fn serve<T: BeaconChainTypes>(
         .filter(|_| config.enable_beacon_processor);
     let reprocess_send_filter = warp::any().map(move || beacon_processor_reprocess_send.clone());
 
+    // Create a `warp` filter that provides access to the beacon processor queue stats.
+    let beacon_processor_queue_stats = ctx.beacon_processor_queue_stats.clone();
+    let beacon_processor_queue_stats_filter = warp::any()
+        .map(move || beacon_processor_queue_stats.clone())
+        .and_then(|queue_stats| async move {
+            match queue_stats {
+                Some(queue_stats) => Ok(queue_stats),
+                None => Err(warp_utils::reject::custom_not_found(
+                    "The beacon processor has not yet started.".to_string(),
+                )),
+            }
+        });
+
+    // Create a `warp` filter that provides access to admission control for archive queries.
+    let archive_query_limiter = Arc::new(ArchiveQueryLimiter::new(config.archive_query_budget));
+    let archive_query_filter = warp::any().map(move || archive_query_limiter.clone());
+
     let duplicate_block_status_code = ctx.config.duplicate_block_status_code;
 
     /*
@@ -1836,14 +1915,15 @@ pub fn serve<T: BeaconChainTypes>(
         .clone()
         .and(warp::path("attestations"))
         .and(warp::path::end())
-        .and(warp_utils::json::json())
+        .and(warp_utils::json::json_or_ssz(
+            ssz_bodies::decode_attestations::<T::EthSpec>,
+        ))
         .and(network_tx_filter.clone())
         .and(reprocess_send_filter)
         .and(log_filter.clone())
         .then(
             // V1 and V2 are identical except V2 has a consensus version header in the request.
-            // We only require this header for SSZ deserialization, which isn't supported for
-            // this endpoint presently.
+            // We only require this header for SSZ deserialization of Electra attestations.
             |_endpoint_version: EndpointVersion,
              task_spawner: TaskSpawner<T::EthSpec>,
              chain: Arc<BeaconChain<T>>,
@@ -2316,13 +2396,19 @@ pub fn serve<T: BeaconChainTypes>(
         .and(warp::path("blocks"))
         .and(block_id_or_err)
         .and(warp::path::end())
+        .and(archive_query_filter.clone())
         .then(
             |task_spawner: TaskSpawner<T::EthSpec>,
              chain: Arc<BeaconChain<T>>,
-             block_id: BlockId| {
+             block_id: BlockId,
+             archive_query: Arc<ArchiveQueryLimiter>| {
                 task_spawner.blocking_json_task(Priority::P1, move || {
                     let (rewards, execution_optimistic, finalized) =
-                        standard_block_rewards::compute_beacon_block_rewards(chain, block_id)?;
+                        standard_block_rewards::compute_beacon_block_rewards(
+                            chain,
+                            block_id,
+                            &archive_query,
+                        )?;
                     Ok(api_types::GenericResponse::from(rewards)).map(|resp| {
                         resp.add_execution_optimistic_finalized(execution_optimistic, finalized)
                     })
@@ -2546,12 +2632,19 @@ pub fn serve<T: BeaconChainTypes>(
         .and(warp::path::param::<Epoch>())
         .and(warp::path::end())
         .and(warp_utils::json::json())
+        .and(archive_query_filter.clone())
         .then(
             |task_spawner: TaskSpawner<T::EthSpec>,
              chain: Arc<BeaconChain<T>>,
              epoch: Epoch,
-             validators: Vec<ValidatorId>| {
+             validators: Vec<ValidatorId>,
+             archive_query: Arc<ArchiveQueryLimiter>| {
                 task_spawner.blocking_json_task(Priority::P1, move || {
+                    let _permit = archive_query.admit(
+                        &chain,
+                        (epoch + 1).end_slot(T::EthSpec::slots_per_epoch()),
+                        0,
+                    )?;
                     let attestation_rewards = chain
                         .compute_attestation_rewards(epoch, validators)
                         .map_err(|e| match e {
@@ -2597,16 +2690,22 @@ pub fn serve<T: BeaconChainTypes>(
         .and(warp::path::end())
         .and(warp_utils::json::json())
         .and(log_filter.clone())
+        .and(archive_query_filter.clone())
         .then(
             |task_spawner: TaskSpawner<T::EthSpec>,
              chain: Arc<BeaconChain<T>>,
              block_id: BlockId,
              validators: Vec<ValidatorId>,
-             log: Logger| {
+             log: Logger,
+             archive_query: Arc<ArchiveQueryLimiter>| {
                 task_spawner.blocking_json_task(Priority::P1, move || {
                     let (rewards, execution_optimistic, finalized) =
                         sync_committee_rewards::compute_sync_committee_rewards(
-                            chain, block_id, validators, log,
+                            chain,
+                            block_id,
+                            validators,
+                            log,
+                            &archive_query,
                         )?;
 
                     Ok(api_types::GenericResponse::from(rewards)).map(|resp| {
@@ -2694,66 +2793,83 @@ pub fn serve<T: BeaconChainTypes>(
         .and(task_spawner_filter.clone())
         .and(chain_filter.clone())
         .and(log_filter.clone())
+        .and(archive_query_filter.clone())
         .then(
             |endpoint_version: EndpointVersion,
              state_id: StateId,
              accept_header: Option<api_types::Accept>,
              task_spawner: TaskSpawner<T::EthSpec>,
              chain: Arc<BeaconChain<T>>,
-             log: Logger| {
-                task_spawner.blocking_response_task(Priority::P1, move || match accept_header {
-                    Some(api_types::Accept::Ssz) => {
-                        // We can ignore the optimistic status for the "fork" since it's a
-                        // specification constant that doesn't change across competing heads of the
-                        // beacon chain.
-                        let t = std::time::Instant::now();
-                        let (state, _execution_optimistic, _finalized) = state_id.state(&chain)?;
-                        let fork_name = state
-                            .fork_name(&chain.spec)
-                            .map_err(inconsistent_fork_rejection)?;
-                        let timer = metrics::start_timer(&metrics::HTTP_API_STATE_SSZ_ENCODE_TIMES);
-                        let response_bytes = state.as_ssz_bytes();
-                        drop(timer);
-                        debug!(
-                            log,
-                            "HTTP state load";
-                            "total_time_ms" => t.elapsed().as_millis(),
-                            "target_slot" => state.slot()
-                        );
+             log: Logger,
+             archive_query: Arc<ArchiveQueryLimiter>| {
+                task_spawner.blocking_response_task(Priority::P1, move || {
+                    let _permit = archive_query.admit_state_id(&chain, &state_id)?;
+                    match accept_header {
+                        Some(api_types::Accept::Ssz) => {
+                            // We can ignore the optimistic status for the "fork" since it's a
+                            // specification constant that doesn't change across competing heads of the
+                            // beacon chain.
+                            let t = std::time::Instant::now();
+                            // Finalized states stored as snapshots or diffs can be encoded without
+                            // first being loaded as a `BeaconState`.
+                            let (response_bytes, fork_name, slot) = if let Some((bytes, slot)) =
+                                state_id.cold_state_ssz(&chain)?
+                            {
+                                let fork_name = chain.spec.fork_name_at_slot::<T::EthSpec>(slot);
+                                (bytes, fork_name, slot)
+                            } else {
+                                let (state, _execution_optimistic, _finalized) =
+                                    state_id.state(&chain)?;
+                                let fork_name = state
+                                    .fork_name(&chain.spec)
+                                    .map_err(inconsistent_fork_rejection)?;
+                                let timer =
+                                    metrics::start_timer(&metrics::HTTP_API_STATE_SSZ_ENCODE_TIMES);
+                                let response_bytes = state.as_ssz_bytes();
+                                drop(timer);
+                                (response_bytes, fork_name, state.slot())
+                            };
+                            debug!(
+                                log,
+                                "HTTP state load";
+                                "total_time_ms" => t.elapsed().as_millis(),
+                                "target_slot" => slot
+                            );
 
-                        Response::builder()
-                            .status(200)
-                            .body(response_bytes.into())
-                            .map(|res: Response<Body>| add_ssz_content_type_header(res))
-                            .map(|resp: warp::reply::Response| {
-                                add_consensus_version_header(resp, fork_name)
-                            })
-                            .map_err(|e| {
-                                warp_utils::reject::custom_server_error(format!(
-                                    "failed to create response: {}",
-                                    e
+                            Response::builder()
+                                .status(200)
+                                .body(response_bytes.into())
+                                .map(|res: Response<Body>| add_ssz_content_type_header(res))
+                                .map(|resp: warp::reply::Response| {
+                                    add_consensus_version_header(resp, fork_name)
+                                })
+                                .map_err(|e| {
+                                    warp_utils::reject::custom_server_error(format!(
+                                        "failed to create response: {}",
+                                        e
+                                    ))
+                                })
+                        }
+                        _ => state_id.map_state_and_execution_optimistic_and_finalized(
+                            &chain,
+                            |state, execution_optimistic, finalized| {
+                                let fork_name = state
+                                    .fork_name(&chain.spec)
+                                    .map_err(inconsistent_fork_rejection)?;
+                                let res = execution_optimistic_finalized_fork_versioned_response(
+                                    endpoint_version,
+                                    fork_name,
+                                    execution_optimistic,
+                                    finalized,
+                                    &state,
+                                )?;
+                                Ok(add_consensus_version_header(
+                                    warp::reply::json(&res).into_response(),
+                                    fork_name,
                                 ))
-                            })
+                            },
+                        ),
                     }
-                    _ => state_id.map_state_and_execution_optimistic_and_finalized(
-                        &chain,
-                        |state, execution_optimistic, finalized| {
-                            let fork_name = state
-                                .fork_name(&chain.spec)
-                                .map_err(inconsistent_fork_rejection)?;
-                            let res = execution_optimistic_finalized_fork_versioned_response(
-                                endpoint_version,
-                                fork_name,
-                                execution_optimistic,
-                                finalized,
-                                &state,
-                            )?;
-                            Ok(add_consensus_version_header(
-                                warp::reply::json(&res).into_response(),
-                                fork_name,
-                            ))
-                        },
-                    ),
                 })
             },
         );
@@ -2864,33 +2980,9 @@ pub fn serve<T: BeaconChainTypes>(
             |task_spawner: TaskSpawner<T::EthSpec>,
              network_globals: Arc<NetworkGlobals<T::EthSpec>>| {
                 task_spawner.blocking_json_task(Priority::P1, move || {
-                    let enr = network_globals.local_enr();
-                    let p2p_addresses = enr.multiaddr_p2p_tcp();
-                    let discovery_addresses = enr.multiaddr_p2p_udp();
-                    let meta_data = network_globals.local_metadata.read();
-                    Ok(api_types::GenericResponse::from(api_types::IdentityData {
-                        peer_id: network_globals.local_peer_id().to_base58(),
-                        enr,
-                        p2p_addresses,
-                        discovery_addresses,
-                        metadata: api_types::MetaData {
-                            seq_number: *meta_data.seq_number(),
-                            attnets: format!(
-                                "0x{}",
-                                hex::encode(meta_data.attnets().clone().into_bytes()),
-                            ),
-                            syncnets: format!(
-                                "0x{}",
-                                hex::encode(
-                                    meta_data
-                                        .syncnets()
-                                        .cloned()
-                                        .unwrap_or_default()
-                                        .into_bytes()
-                                )
-                            ),
-                        },
-                    }))
+                    Ok(api_types::GenericResponse::from(node_identity(
+                        &network_globals,
+                    )))
                 })
             },
         );
@@ -2977,10 +3069,12 @@ pub fn serve<T: BeaconChainTypes>(
         .and(task_spawner_filter.clone())
         .and(network_globals.clone())
         .and(chain_filter.clone())
+        .and(health_disk_space_filter.clone())
         .then(
             |task_spawner: TaskSpawner<T::EthSpec>,
              network_globals: Arc<NetworkGlobals<T::EthSpec>>,
-             chain: Arc<BeaconChain<T>>| {
+             chain: Arc<BeaconChain<T>>,
+             disk_space: Arc<health::DiskSpaceCache>| {
                 async move {
                     let el_offline = if let Some(el) = &chain.execution_layer {
                         el.is_offline_or_erroring().await
@@ -2990,27 +3084,18 @@ pub fn serve<T: BeaconChainTypes>(
 
                     task_spawner
                         .blocking_response_task(Priority::P0, move || {
-                            let is_optimistic = chain
-                                .is_optimistic_or_invalid_head()
-                                .map_err(warp_utils::reject::beacon_chain_error)?;
-
-                            let is_syncing = !network_globals.sync_state.read().is_synced();
+                            let report = health::observe_health_report(
+                                &chain,
+                                &network_globals,
+                                &disk_space,
+                                el_offline,
+                            )?;
+                            let status = health::status_code(&report);
 
-                            if el_offline {
-                                Err(warp_utils::reject::not_synced(
-                                    "execution layer is offline".to_string(),
-                                ))
-                            } else if is_syncing || is_optimistic {
-                                Ok(warp::reply::with_status(
-                                    warp::reply(),
-                                    warp::http::StatusCode::PARTIAL_CONTENT,
-                                ))
-                            } else {
-                                Ok(warp::reply::with_status(
-                                    warp::reply(),
-                                    warp::http::StatusCode::OK,
-                                ))
-                            }
+                            Ok(warp::reply::with_status(
+                                warp::reply::json(&api_types::GenericResponse::from(report)),
+                                status,
+                            ))
                         })
                         .await
                 }
@@ -3315,8 +3400,7 @@ pub fn serve<T: BeaconChainTypes>(
                     }
 
                     chain
-                        .produce_unaggregated_attestation(query.slot, query.committee_index)
-                        .map(|attestation| attestation.data().clone())
+                        .produce_attestation_data(query.slot, query.committee_index)
                         .map(api_types::GenericResponse::from)
                         .map_err(warp_utils::reject::beacon_chain_error)
                 })
@@ -3388,7 +3472,9 @@ pub fn serve<T: BeaconChainTypes>(
         }))
         .and(warp::path::end())
         .and(not_while_syncing_filter.clone())
-        .and(warp_utils::json::json())
+        .and(warp_utils::json::json_or_ssz(
+            ssz_bodies::decode_validator_indices,
+        ))
         .and(task_spawner_filter.clone())
         .and(chain_filter.clone())
         .then(
@@ -3416,7 +3502,9 @@ pub fn serve<T: BeaconChainTypes>(
         }))
         .and(warp::path::end())
         .and(not_while_syncing_filter.clone())
-        .and(warp_utils::json::json())
+        .and(warp_utils::json::json_or_ssz(
+            ssz_bodies::decode_validator_indices,
+        ))
         .and(task_spawner_filter.clone())
         .and(chain_filter.clone())
         .then(
@@ -3474,13 +3562,14 @@ pub fn serve<T: BeaconChainTypes>(
         .and(not_while_syncing_filter.clone())
         .and(task_spawner_filter.clone())
         .and(chain_filter.clone())
-        .and(warp_utils::json::json())
+        .and(warp_utils::json::json_or_ssz(
+            ssz_bodies::decode_aggregates::<T::EthSpec>,
+        ))
         .and(network_tx_filter.clone())
         .and(log_filter.clone())
         .then(
             // V1 and V2 are identical except V2 has a consensus version header in the request.
-            // We only require this header for SSZ deserialization, which isn't supported for
-            // this endpoint presently.
+            // We only require this header for SSZ deserialization of Electra aggregates.
             |_endpoint_version: EndpointVersion,
             not_synced_filter: Result<(), Rejection>,
              task_spawner: TaskSpawner<T::EthSpec>,
@@ -3594,7 +3683,7 @@ pub fn serve<T: BeaconChainTypes>(
         .and(task_spawner_filter.clone())
         .and(chain_filter.clone())
         .and(warp_utils::json::json())
-        .and(network_tx_filter)
+        .and(network_tx_filter.clone())
         .and(log_filter.clone())
         .then(
             |not_synced_filter: Result<(), Rejection>,
@@ -3807,7 +3896,10 @@ pub fn serve<T: BeaconChainTypes>(
 
                         // Update the prepare beacon proposer cache based on this request.
                         execution_layer
-                            .update_proposer_preparation(current_epoch, &preparation_data)
+                            .update_proposer_preparation_from_registrations(
+                                current_epoch,
+                                &preparation_data,
+                            )
                             .await;
 
                         // Call prepare beacon proposer blocking with the latest update in order to make
@@ -4034,18 +4126,139 @@ pub fn serve<T: BeaconChainTypes>(
             },
         );
 
+    // POST lighthouse/beacon/blocks/batch
+    let post_lighthouse_beacon_blocks_batch = warp::path("lighthouse")
+        .and(warp::path("beacon"))
+        .and(warp::path("blocks"))
+        .and(warp::path("batch"))
+        .and(warp::path::end())
+        .and(warp_utils::json::json())
+        .and(warp::header::optional::<api_types::Accept>("accept"))
+        .and(task_spawner_filter.clone())
+        .and(chain_filter.clone())
+        .then(
+            |request: eth2::lighthouse::BlocksBatchRequest,
+             accept_header: Option<api_types::Accept>,
+             task_spawner: TaskSpawner<T::EthSpec>,
+             chain: Arc<BeaconChain<T>>| {
+                task_spawner.spawn_async_with_rejection(Priority::P1, async move {
+                    let blocks = blocks_batch::get_blocks_batch(request, chain.clone()).await?;
+
+                    match accept_header {
+                        Some(api_types::Accept::Ssz) => Response::builder()
+                            .status(200)
+                            .body(blocks.as_ssz_bytes().into())
+                            .map(|res: Response<Body>| add_ssz_content_type_header(res))
+                            .map_err(|e| {
+                                warp_utils::reject::custom_server_error(format!(
+                                    "failed to create response: {}",
+                                    e
+                                ))
+                            }),
+                        _ => {
+                            let blocks = blocks
+                                .into_iter()
+                                .map(|block| {
+                                    let fork_name = block
+                                        .fork_name(&chain.spec)
+                                        .map_err(inconsistent_fork_rejection)?;
+                                    Ok(ForkVersionedResponse {
+                                        version: Some(fork_name),
+                                        metadata: EmptyMetadata {},
+                                        data: block,
+                                    })
+                                })
+                                .collect::<Result<Vec<_>, warp::Rejection>>()?;
+                            Ok(warp::reply::json(&api_types::GenericResponse::from(blocks))
+                                .into_response())
+                        }
+                    }
+                })
+            },
+        );
+
+    // POST lighthouse/data_columns
+    let post_lighthouse_data_columns = warp::path("lighthouse")
+        .and(warp::path("data_columns"))
+        .and(warp::path::end())
+        .and(warp_utils::json::json())
+        .and(task_spawner_filter.clone())
+        .and(chain_filter.clone())
+        .and(network_tx_filter.clone())
+        .and(log_filter.clone())
+        .then(
+            |data_columns: Vec<Arc<DataColumnSidecar<T::EthSpec>>>,
+             task_spawner: TaskSpawner<T::EthSpec>,
+             chain: Arc<BeaconChain<T>>,
+             network_tx: UnboundedSender<NetworkMessage<T::EthSpec>>,
+             log: Logger| {
+                task_spawner.spawn_async_with_rejection(Priority::P0, async move {
+                    publish_sidecars::publish_data_columns(data_columns, chain, network_tx, log)
+                        .await
+                        .map(|()| warp::reply().into_response())
+                })
+            },
+        );
+
+    // POST lighthouse/blobs
+    let post_lighthouse_blobs = warp::path("lighthouse")
+        .and(warp::path("blobs"))
+        .and(warp::path::end())
+        .and(warp_utils::json::json())
+        .and(task_spawner_filter.clone())
+        .and(chain_filter.clone())
+        .and(network_tx_filter.clone())
+        .and(log_filter.clone())
+        .then(
+            |blobs: Vec<Arc<BlobSidecar<T::EthSpec>>>,
+             task_spawner: TaskSpawner<T::EthSpec>,
+             chain: Arc<BeaconChain<T>>,
+             network_tx: UnboundedSender<NetworkMessage<T::EthSpec>>,
+             log: Logger| {
+                task_spawner.spawn_async_with_rejection(Priority::P0, async move {
+                    publish_sidecars::publish_blobs(blobs, chain, network_tx, log)
+                        .await
+                        .map(|()| warp::reply().into_response())
+                })
+            },
+        );
+
     // GET lighthouse/health
     let get_lighthouse_health = warp::path("lighthouse")
         .and(warp::path("health"))
         .and(warp::path::end())
         .and(task_spawner_filter.clone())
-        .then(|task_spawner: TaskSpawner<T::EthSpec>| {
-            task_spawner.blocking_json_task(Priority::P0, move || {
-                eth2::lighthouse::Health::observe()
-                    .map(api_types::GenericResponse::from)
-                    .map_err(warp_utils::reject::custom_bad_request)
-            })
-        });
+        .and(network_globals.clone())
+        .and(chain_filter.clone())
+        .and(health_disk_space_filter)
+        .then(
+            |task_spawner: TaskSpawner<T::EthSpec>,
+             network_globals: Arc<NetworkGlobals<T::EthSpec>>,
+             chain: Arc<BeaconChain<T>>,
+             disk_space: Arc<health::DiskSpaceCache>| {
+                async move {
+                    let el_offline = if let Some(el) = &chain.execution_layer {
+                        el.is_offline_or_erroring().await
+                    } else {
+                        true
+                    };
+
+                    task_spawner
+                        .blocking_json_task(Priority::P0, move || {
+                            let mut health = eth2::lighthouse::Health::observe()
+                                .map_err(warp_utils::reject::custom_bad_request)?;
+                            health.degradation = Some(health::observe_health_report(
+                                &chain,
+                                &network_globals,
+                                &disk_space,
+                                el_offline,
+                            )?);
+                            Ok(api_types::GenericResponse::from(health))
+                        })
+                        .await
+                }
+            },
+        );
 
     // GET lighthouse/ui/health
     let get_lighthouse_ui_health = warp::path("lighthouse")
@@ -4099,7 +4312,7 @@ pub fn serve<T: BeaconChainTypes>(
         .and(task_spawner_filter.clone())
         .and(chain_filter.clone())
         .then(
-            |request_data: ui::ValidatorMetricsRequestData,
+            |request_data: eth2::lighthouse::ValidatorMetricsRequestData,
              task_spawner: TaskSpawner<T::EthSpec>,
              chain: Arc<BeaconChain<T>>| {
                 task_spawner.blocking_json_task(Priority::P1, move || {
@@ -4118,7 +4331,7 @@ pub fn serve<T: BeaconChainTypes>(
         .and(task_spawner_filter.clone())
         .and(chain_filter.clone())
         .then(
-            |request_data: ui::ValidatorInfoRequestData,
+            |request_data: eth2::lighthouse::ValidatorInfoRequestData,
              task_spawner: TaskSpawner<T::EthSpec>,
              chain: Arc<BeaconChain<T>>| {
                 task_spawner.blocking_json_task(Priority::P1, move || {
@@ -4185,7 +4398,8 @@ pub fn serve<T: BeaconChainTypes>(
         .and(warp::path("connected"))
         .and(warp::path::end())
         .and(task_spawner_filter.clone())
-        .and(network_globals)
+        .and(network_globals.clone())
+        .clone()
         .then(
             |task_spawner: TaskSpawner<T::EthSpec>,
              network_globals: Arc<NetworkGlobals<T::EthSpec>>| {
@@ -4203,58 +4417,464 @@ pub fn serve<T: BeaconChainTypes>(
             },
         );
 
-    // GET lighthouse/proto_array
-    let get_lighthouse_proto_array = warp::path("lighthouse")
-        .and(warp::path("proto_array"))
+    // GET lighthouse/peers/decisions
+    let get_lighthouse_peers_decisions = warp::path("lighthouse")
+        .and(warp::path("peers"))
+        .and(warp::path("decisions"))
         .and(warp::path::end())
         .and(task_spawner_filter.clone())
-        .and(chain_filter.clone())
+        .and(network_globals.clone())
         .then(
-            |task_spawner: TaskSpawner<T::EthSpec>, chain: Arc<BeaconChain<T>>| {
-                task_spawner.blocking_response_task(Priority::P1, move || {
-                    Ok::<_, warp::Rejection>(warp::reply::json(
-                        &api_types::GenericResponseRef::from(
-                            chain
-                                .canonical_head
-                                .fork_choice_read_lock()
-                                .proto_array()
-                                .core_proto_array(),
-                        ),
+            |task_spawner: TaskSpawner<T::EthSpec>,
+             network_globals: Arc<NetworkGlobals<T::EthSpec>>| {
+                task_spawner.blocking_json_task(Priority::P1, move || {
+                    Ok(api_types::GenericResponse::from(
+                        network_globals.peers.read().peer_decisions(),
                     ))
                 })
             },
         );
 
-    // GET lighthouse/validator_inclusion/{epoch}/{validator_id}
-    let get_lighthouse_validator_inclusion_global = warp::path("lighthouse")
-        .and(warp::path("validator_inclusion"))
-        .and(warp::path::param::<Epoch>())
-        .and(warp::path::param::<ValidatorId>())
+    // POST lighthouse/peers/{peer_id}/protect
+    let post_lighthouse_peers_protect = warp::path("lighthouse")
+        .and(warp::path("peers"))
+        .and(warp::path::param::<String>())
+        .and(warp::path("protect"))
         .and(warp::path::end())
         .and(task_spawner_filter.clone())
-        .and(chain_filter.clone())
+        .and(network_tx_filter.clone())
         .then(
-            |epoch: Epoch,
-             validator_id: ValidatorId,
+            |requested_peer_id: String,
              task_spawner: TaskSpawner<T::EthSpec>,
-             chain: Arc<BeaconChain<T>>| {
-                task_spawner.blocking_json_task(Priority::P1, move || {
-                    validator_inclusion::validator_inclusion_data(epoch, &validator_id, &chain)
-                        .map(api_types::GenericResponse::from)
+             network_tx: UnboundedSender<NetworkMessage<T::EthSpec>>| {
+                task_spawner.blocking_json_task(Priority::P0, move || {
+                    let peer_id = requested_peer_id.parse::<PeerId>().map_err(|e| {
+                        warp_utils::reject::custom_bad_request(format!("invalid peer id: {}", e))
+                    })?;
+                    publish_network_message(&network_tx, NetworkMessage::ProtectPeer { peer_id })
                 })
             },
         );
 
-    // GET lighthouse/validator_inclusion/{epoch}/global
-    let get_lighthouse_validator_inclusion = warp::path("lighthouse")
-        .and(warp::path("validator_inclusion"))
-        .and(warp::path::param::<Epoch>())
-        .and(warp::path("global"))
+    // GET lighthouse/network/fork_readiness
+    let get_lighthouse_network_fork_readiness = warp::path("lighthouse")
+        .and(warp::path("network"))
+        .and(warp::path("fork_readiness"))
         .and(warp::path::end())
         .and(task_spawner_filter.clone())
-        .and(chain_filter.clone())
+        .and(network_globals.clone())
         .then(
-            |epoch: Epoch, task_spawner: TaskSpawner<T::EthSpec>, chain: Arc<BeaconChain<T>>| {
+            |task_spawner: TaskSpawner<T::EthSpec>,
+             network_globals: Arc<NetworkGlobals<T::EthSpec>>| {
+                task_spawner.blocking_json_task(Priority::P1, move || {
+                    let enr_fork_id = network_globals.local_enr().eth2().map_err(|e| {
+                        warp_utils::reject::custom_server_error(format!(
+                            "unable to read local ENR fork id: {}",
+                            e
+                        ))
+                    })?;
+                    Ok(api_types::GenericResponse::from(
+                        network_globals.peers.read().fork_readiness(&enr_fork_id),
+                    ))
+                })
+            },
+        );
+
+    // GET lighthouse/network/reachability
+    let get_lighthouse_network_reachability = warp::path("lighthouse")
+        .and(warp::path("network"))
+        .and(warp::path("reachability"))
+        .and(warp::path::end())
+        .and(task_spawner_filter.clone())
+        .and(network_globals.clone())
+        .then(
+            |task_spawner: TaskSpawner<T::EthSpec>,
+             network_globals: Arc<NetworkGlobals<T::EthSpec>>| {
+                task_spawner.blocking_json_task(Priority::P1, move || {
+                    Ok(api_types::GenericResponse::from(
+                        network_globals.reachability(),
+                    ))
+                })
+            },
+        );
+
+    // GET lighthouse/network/client_diversity
+    let get_lighthouse_network_client_diversity = warp::path("lighthouse")
+        .and(warp::path("network"))
+        .and(warp::path("client_diversity"))
+        .and(warp::path::end())
+        .and(task_spawner_filter.clone())
+        .and(network_globals.clone())
+        .then(
+            |task_spawner: TaskSpawner<T::EthSpec>,
+             network_globals: Arc<NetworkGlobals<T::EthSpec>>| {
+                task_spawner.blocking_json_task(Priority::P1, move || {
+                    Ok(api_types::GenericResponse::from(
+                        network_globals.peers.read().client_diversity(),
+                    ))
+                })
+            },
+        );
+
+    // GET lighthouse/identity
+    let get_lighthouse_identity = warp::path("lighthouse")
+        .and(warp::path("identity"))
+        .and(warp::path::end())
+        .and(task_spawner_filter.clone())
+        .and(network_globals.clone())
+        .and(chain_filter.clone())
+        .then(
+            |task_spawner: TaskSpawner<T::EthSpec>,
+             network_globals: Arc<NetworkGlobals<T::EthSpec>>,
+             chain: Arc<BeaconChain<T>>| {
+                task_spawner.blocking_json_task(Priority::P1, move || {
+                    let enr = network_globals.local_enr();
+                    let custody_group_count = enr
+                        .custody_subnet_count::<T::EthSpec>(&chain.spec)
+                        .ok()
+                        .map(|value| Quoted { value });
+                    Ok(api_types::GenericResponse::from(
+                        eth2::lighthouse::LighthouseIdentityData {
+                            identity: node_identity(&network_globals),
+                            quic_addresses: enr.multiaddr_p2p_quic(),
+                            custody_group_count,
+                            fork_digest: chain.enr_fork_id().fork_digest,
+                        },
+                    ))
+                })
+            },
+        );
+
+    // GET lighthouse/network/subnets
+    let get_lighthouse_network_subnets = warp::path("lighthouse")
+        .and(warp::path("network"))
+        .and(warp::path("subnets"))
+        .and(warp::path::end())
+        .and(task_spawner_filter.clone())
+        .and(network_globals.clone())
+        .then(
+            |task_spawner: TaskSpawner<T::EthSpec>,
+             network_globals: Arc<NetworkGlobals<T::EthSpec>>| {
+                task_spawner.blocking_json_task(Priority::P1, move || {
+                    let node_id = network_globals.local_enr().node_id().raw();
+                    let config = &network_globals.config;
+                    let source = if config.subscribe_all_subnets {
+                        eth2::lighthouse::LongLivedSubnetsSource::All
+                    } else if config.long_lived_subnets_override.is_some() {
+                        eth2::lighthouse::LongLivedSubnetsSource::Override
+                    } else {
+                        eth2::lighthouse::LongLivedSubnetsSource::Computed
+                    };
+                    Ok(api_types::GenericResponse::from(
+                        eth2::lighthouse::LongLivedSubnets {
+                            node_id: Hash256::from(node_id),
+                            computed_subnets: SubnetId::compute_attestation_subnets(
+                                node_id,
+                                &network_globals.spec,
+                            )
+                            .collect(),
+                            subscribed_subnets: config
+                                .long_lived_attestation_subnets(node_id, &network_globals.spec),
+                            source,
+                        },
+                    ))
+                })
+            },
+        );
+
+    // GET lighthouse/proto_array
+    let get_lighthouse_proto_array = warp::path("lighthouse")
+        .and(warp::path("proto_array"))
+        .and(warp::path::end())
+        .and(task_spawner_filter.clone())
+        .and(chain_filter.clone())
+        .then(
+            |task_spawner: TaskSpawner<T::EthSpec>, chain: Arc<BeaconChain<T>>| {
+                task_spawner.blocking_response_task(Priority::P1, move || {
+                    Ok::<_, warp::Rejection>(warp::reply::json(
+                        &api_types::GenericResponseRef::from(
+                            chain
+                                .canonical_head
+                                .fork_choice_read_lock()
+                                .proto_array()
+                                .core_proto_array(),
+                        ),
+                    ))
+                })
+            },
+        );
+
+    // GET lighthouse/op_pool
+    let get_lighthouse_op_pool = warp::path("lighthouse")
+        .and(warp::path("op_pool"))
+        .and(warp::path::end())
+        .and(task_spawner_filter.clone())
+        .and(chain_filter.clone())
+        .then(
+            |task_spawner: TaskSpawner<T::EthSpec>, chain: Arc<BeaconChain<T>>| {
+                task_spawner.blocking_json_task(Priority::P1, move || {
+                    Ok(api_types::GenericResponse::from(op_pool::info(chain)))
+                })
+            },
+        );
+
+    // GET lighthouse/op_pool/censorship
+    let get_lighthouse_op_pool_censorship = warp::path("lighthouse")
+        .and(warp::path("op_pool"))
+        .and(warp::path("censorship"))
+        .and(warp::path::end())
+        .and(task_spawner_filter.clone())
+        .and(chain_filter.clone())
+        .then(
+            |task_spawner: TaskSpawner<T::EthSpec>, chain: Arc<BeaconChain<T>>| {
+                task_spawner.blocking_json_task(Priority::P1, move || {
+                    op_pool::censorship_report(chain).map(api_types::GenericResponse::from)
+                })
+            },
+        );
+
+    // GET lighthouse/fork_choice/reorgs?epochs
+    let get_lighthouse_fork_choice_reorgs = warp::path("lighthouse")
+        .and(warp::path("fork_choice"))
+        .and(warp::path("reorgs"))
+        .and(warp::query::<eth2::lighthouse::ReorgsQuery>())
+        .and(warp::path::end())
+        .and(task_spawner_filter.clone())
+        .and(chain_filter.clone())
+        .then(
+            |query: eth2::lighthouse::ReorgsQuery,
+             task_spawner: TaskSpawner<T::EthSpec>,
+             chain: Arc<BeaconChain<T>>| {
+                task_spawner.blocking_json_task(Priority::P1, move || {
+                    let epochs = query.epochs.unwrap_or(HEAD_HISTORY_EPOCHS);
+                    if epochs == 0 || epochs > HEAD_HISTORY_EPOCHS {
+                        return Err(warp_utils::reject::custom_bad_request(format!(
+                            "epochs must be between 1 and {}",
+                            HEAD_HISTORY_EPOCHS
+                        )));
+                    }
+                    chain
+                        .reorg_summary(epochs)
+                        .map(api_types::GenericResponse::from)
+                        .map_err(warp_utils::reject::beacon_chain_error)
+                })
+            },
+        );
+
+    // GET lighthouse/block_value/{slot}
+    let get_lighthouse_block_value = warp::path("lighthouse")
+        .and(warp::path("block_value"))
+        .and(warp::path::param::<Slot>().or_else(|_| async {
+            Err(warp_utils::reject::custom_bad_request(
+                "Invalid slot".to_string(),
+            ))
+        }))
+        .and(warp::path::end())
+        .and(task_spawner_filter.clone())
+        .and(chain_filter.clone())
+        .then(
+            |slot: Slot, task_spawner: TaskSpawner<T::EthSpec>, chain: Arc<BeaconChain<T>>| {
+                task_spawner.blocking_json_task(Priority::P1, move || {
+                    chain
+                        .block_value_record(slot)
+                        .map_err(warp_utils::reject::beacon_chain_error)?
+                        .map(api_types::GenericResponse::from)
+                        .ok_or_else(|| {
+                            warp_utils::reject::custom_not_found(format!(
+                                "no block produced locally at slot {} in the last day",
+                                slot
+                            ))
+                        })
+                })
+            },
+        );
+
+    // GET lighthouse/validator/blocks/{slot}
+    let get_lighthouse_validator_blocks = warp::path("lighthouse")
+        .and(warp::path("validator"))
+        .and(warp::path("blocks"))
+        .and(warp::path::param::<Slot>().or_else(|_| async {
+            Err(warp_utils::reject::custom_bad_request(
+                "Invalid slot".to_string(),
+            ))
+        }))
+        .and(warp::path::end())
+        .and(warp::header::optional::<api_types::Accept>("accept"))
+        .and(warp::query::<eth2::lighthouse::ProduceBlockOnParentQuery>())
+        .and(warp::query::<api_types::ValidatorBlocksQuery>())
+        .and(task_spawner_filter.clone())
+        .and(chain_filter.clone())
+        .and(log_filter.clone())
+        .then(
+            |slot: Slot,
+             accept_header: Option<api_types::Accept>,
+             parent_query: eth2::lighthouse::ProduceBlockOnParentQuery,
+             query: api_types::ValidatorBlocksQuery,
+             task_spawner: TaskSpawner<T::EthSpec>,
+             chain: Arc<BeaconChain<T>>,
+             log: Logger| {
+                task_spawner.spawn_async_with_rejection(Priority::P1, async move {
+                    warn!(
+                        log,
+                        "Producing block on arbitrary parent";
+                        "slot" => slot,
+                        "parent_root" => ?parent_query.parent_root,
+                        "info" => "this endpoint is intended for testing only",
+                    );
+                    produce_block_v3_on_parent(
+                        accept_header,
+                        chain,
+                        slot,
+                        Some(parent_query.parent_root),
+                        query,
+                    )
+                    .await
+                })
+            },
+        );
+
+    // GET lighthouse/beacon_processor
+    let get_lighthouse_beacon_processor = warp::path("lighthouse")
+        .and(warp::path("beacon_processor"))
+        .and(warp::path::end())
+        .and(task_spawner_filter.clone())
+        .and(beacon_processor_queue_stats_filter)
+        .then(
+            |task_spawner: TaskSpawner<T::EthSpec>, queue_stats: BeaconProcessorQueueStats| {
+                task_spawner.blocking_json_task(Priority::P0, move || {
+                    let queues = queue_stats
+                        .snapshot()
+                        .into_iter()
+                        .map(|(work_type, stats)| {
+                            (
+                                work_type.to_string(),
+                                eth2::lighthouse::BeaconProcessorQueueInfo {
+                                    length: stats.length,
+                                    capacity: stats.capacity,
+                                    dropped: stats.dropped,
+                                },
+                            )
+                        })
+                        .collect::<std::collections::BTreeMap<_, _>>();
+                    Ok(api_types::GenericResponse::from(queues))
+                })
+            },
+        );
+
+    // GET lighthouse/validator_inclusion/{epoch}/{validator_id}
+    let get_lighthouse_validator_inclusion_global = warp::path("lighthouse")
+        .and(warp::path("validator_inclusion"))
+        .and(warp::path::param::<Epoch>())
+        .and(warp::path::param::<ValidatorId>())
+        .and(warp::path::end())
+        .and(task_spawner_filter.clone())
+        .and(chain_filter.clone())
+        .then(
+            |epoch: Epoch,
+             validator_id: ValidatorId,
+             task_spawner: TaskSpawner<T::EthSpec>,
+             chain: Arc<BeaconChain<T>>| {
+                task_spawner.blocking_json_task(Priority::P1, move || {
+                    validator_inclusion::validator_inclusion_data(epoch, &validator_id, &chain)
+                        .map(api_types::GenericResponse::from)
+                })
+            },
+        );
+
+    let lighthouse_states_path = warp::path("lighthouse")
+        .and(warp::path("states"))
+        .and(warp::path::param::<StateId>().or_else(|_| async {
+            Err(warp_utils::reject::custom_bad_request(
+                "Invalid state ID".to_string(),
+            ))
+        }))
+        .and(task_spawner_filter.clone())
+        .and(chain_filter.clone());
+
+    // GET lighthouse/states/{state_id}/pending_deposits
+    let get_lighthouse_pending_deposits = lighthouse_states_path
+        .clone()
+        .and(warp::path("pending_deposits"))
+        .and(warp::path::end())
+        .and(warp::query::<eth2::lighthouse::PendingQueueQuery>())
+        .then(
+            |state_id: StateId,
+             task_spawner: TaskSpawner<T::EthSpec>,
+             chain: Arc<BeaconChain<T>>,
+             query: eth2::lighthouse::PendingQueueQuery| {
+                task_spawner.blocking_json_task(Priority::P1, move || {
+                    pending_queues::pending_deposits(state_id, query, &chain)
+                })
+            },
+        );
+
+    // GET lighthouse/states/{state_id}/pending_partial_withdrawals
+    let get_lighthouse_pending_partial_withdrawals = lighthouse_states_path
+        .clone()
+        .and(warp::path("pending_partial_withdrawals"))
+        .and(warp::path::end())
+        .and(warp::query::<eth2::lighthouse::PendingQueueQuery>())
+        .then(
+            |state_id: StateId,
+             task_spawner: TaskSpawner<T::EthSpec>,
+             chain: Arc<BeaconChain<T>>,
+             query: eth2::lighthouse::PendingQueueQuery| {
+                task_spawner.blocking_json_task(Priority::P1, move || {
+                    pending_queues::pending_partial_withdrawals(state_id, query, &chain)
+                })
+            },
+        );
+
+    // GET lighthouse/states/{state_id}/pending_consolidations
+    let get_lighthouse_pending_consolidations = lighthouse_states_path
+        .clone()
+        .and(warp::path("pending_consolidations"))
+        .and(warp::path::end())
+        .and(warp::query::<eth2::lighthouse::PendingQueueQuery>())
+        .then(
+            |state_id: StateId,
+             task_spawner: TaskSpawner<T::EthSpec>,
+             chain: Arc<BeaconChain<T>>,
+             query: eth2::lighthouse::PendingQueueQuery| {
+                task_spawner.blocking_json_task(Priority::P1, move || {
+                    pending_queues::pending_consolidations(state_id, query, &chain)
+                })
+            },
+        );
+
+    // GET lighthouse/validators/{pubkey}/withdrawal_projection
+    let get_lighthouse_validator_withdrawal_projection = warp::path("lighthouse")
+        .and(warp::path("validators"))
+        .and(warp::path::param::<PublicKeyBytes>().or_else(|_| async {
+            Err(warp_utils::reject::custom_bad_request(
+                "Invalid validator pubkey".to_string(),
+            ))
+        }))
+        .and(warp::path("withdrawal_projection"))
+        .and(warp::path::end())
+        .and(task_spawner_filter.clone())
+        .and(chain_filter.clone())
+        .then(
+            |pubkey: PublicKeyBytes,
+             task_spawner: TaskSpawner<T::EthSpec>,
+             chain: Arc<BeaconChain<T>>| {
+                task_spawner.blocking_json_task(Priority::P1, move || {
+                    withdrawal_projection::withdrawal_projection(&pubkey, &chain)
+                        .map(api_types::GenericResponse::from)
+                })
+            },
+        );
+
+    // GET lighthouse/validator_inclusion/{epoch}/global
+    let get_lighthouse_validator_inclusion = warp::path("lighthouse")
+        .and(warp::path("validator_inclusion"))
+        .and(warp::path::param::<Epoch>())
+        .and(warp::path("global"))
+        .and(warp::path::end())
+        .and(task_spawner_filter.clone())
+        .and(chain_filter.clone())
+        .then(
+            |epoch: Epoch, task_spawner: TaskSpawner<T::EthSpec>, chain: Arc<BeaconChain<T>>| {
                 task_spawner.blocking_json_task(Priority::P1, move || {
                     validator_inclusion::global_validator_inclusion_data(epoch, &chain)
                         .map(api_types::GenericResponse::from)
@@ -4262,6 +4882,98 @@ pub fn serve<T: BeaconChainTypes>(
             },
         );
 
+    // GET lighthouse/export/attestations
+    let get_lighthouse_export_attestations = warp::path("lighthouse")
+        .and(warp::path("export"))
+        .and(warp::path("attestations"))
+        .and(warp::path::end())
+        .and(warp::query::<eth2::lighthouse::AttestationExportQuery>())
+        .and(warp::header::optional::<api_types::Accept>("accept"))
+        .and(task_spawner_filter.clone())
+        .and(chain_filter.clone())
+        .and(archive_query_filter.clone())
+        .then(
+            |query: eth2::lighthouse::AttestationExportQuery,
+             accept_header: Option<api_types::Accept>,
+             task_spawner: TaskSpawner<T::EthSpec>,
+             chain: Arc<BeaconChain<T>>,
+             archive_query: Arc<ArchiveQueryLimiter>| {
+                task_spawner.blocking_response_task(Priority::P1, move || {
+                    attestation_export::export_attestations(
+                        query,
+                        accept_header,
+                        chain,
+                        archive_query,
+                    )
+                })
+            },
+        );
+
+    // GET lighthouse/blocks/{block_id}/attestation_inclusion/{validator_index}
+    let get_lighthouse_attestation_inclusion = warp::path("lighthouse")
+        .and(warp::path("blocks"))
+        .and(block_id_or_err)
+        .and(warp::path("attestation_inclusion"))
+        .and(warp::path::param::<u64>().or_else(|_| async {
+            Err(warp_utils::reject::custom_bad_request(
+                "Invalid validator index".to_string(),
+            ))
+        }))
+        .and(warp::path::end())
+        .and(task_spawner_filter.clone())
+        .and(chain_filter.clone())
+        .and(archive_query_filter.clone())
+        .then(
+            |block_id: BlockId,
+             validator_index: u64,
+             task_spawner: TaskSpawner<T::EthSpec>,
+             chain: Arc<BeaconChain<T>>,
+             archive_query: Arc<ArchiveQueryLimiter>| {
+                task_spawner.blocking_json_task(Priority::P1, move || {
+                    attestation_inclusion::attestation_inclusion(
+                        chain,
+                        block_id,
+                        validator_index,
+                        &archive_query,
+                    )
+                    .map(api_types::GenericResponse::from)
+                })
+            },
+        );
+
+    // GET lighthouse/epochs/{epoch}/summary
+    let get_lighthouse_epoch_summary = warp::path("lighthouse")
+        .and(warp::path("epochs"))
+        .and(warp::path::param::<Epoch>().or_else(|_| async {
+            Err(warp_utils::reject::custom_bad_request(
+                "Invalid epoch".to_string(),
+            ))
+        }))
+        .and(warp::path("summary"))
+        .and(warp::path::end())
+        .and(task_spawner_filter.clone())
+        .and(chain_filter.clone())
+        .then(
+            |epoch: Epoch, task_spawner: TaskSpawner<T::EthSpec>, chain: Arc<BeaconChain<T>>| {
+                task_spawner.blocking_json_task(Priority::P1, move || {
+                    chain
+                        .store
+                        .get_epoch_summary(epoch)
+                        .map_err(|e| {
+                            warp_utils::reject::custom_server_error(format!(
+                                "failed to load epoch summary: {e:?}"
+                            ))
+                        })?
+                        .map(api_types::GenericResponse::from)
+                        .ok_or_else(|| {
+                            warp_utils::reject::custom_not_found(format!(
+                                "no summary for epoch {epoch}, it may not be finalized"
+                            ))
+                        })
+                })
+            },
+        );
+
     // GET lighthouse/eth1/syncing
     let get_lighthouse_eth1_syncing = warp::path("lighthouse")
         .and(warp::path("eth1"))
@@ -4323,7 +5035,7 @@ pub fn serve<T: BeaconChainTypes>(
         .and(warp::path("deposit_cache"))
         .and(warp::path::end())
         .and(task_spawner_filter.clone())
-        .and(eth1_service_filter)
+        .and(eth1_service_filter.clone())
         .then(
             |task_spawner: TaskSpawner<T::EthSpec>, eth1_service: eth1::Service| {
                 task_spawner.blocking_json_task(Priority::P1, move || {
@@ -4340,6 +5052,54 @@ pub fn serve<T: BeaconChainTypes>(
             },
         );
 
+    // GET lighthouse/eth1/deposit_proof/{deposit_index}
+    let get_lighthouse_eth1_deposit_proof = warp::path("lighthouse")
+        .and(warp::path("eth1"))
+        .and(warp::path("deposit_proof"))
+        .and(warp::path::param::<u64>())
+        .and(warp::path::end())
+        .and(task_spawner_filter.clone())
+        .and(eth1_service_filter)
+        .then(
+            |deposit_index: u64,
+             task_spawner: TaskSpawner<T::EthSpec>,
+             eth1_service: eth1::Service| {
+                task_spawner.blocking_json_task(Priority::P1, move || {
+                    let deposits = eth1_service.deposits().read();
+                    let cache = &deposits.cache;
+                    if deposit_index < cache.finalized_deposit_count() {
+                        return Err(warp_utils::reject::custom_not_found(format!(
+                            "deposit {} is finalized, proofs are only available for the {} \
+                             pending deposits from index {}",
+                            deposit_index,
+                            cache.pending_len(),
+                            cache.finalized_deposit_count(),
+                        )));
+                    }
+                    if deposit_index >= cache.len() as u64 {
+                        return Err(warp_utils::reject::custom_not_found(format!(
+                            "deposit {} is unknown",
+                            deposit_index
+                        )));
+                    }
+                    let (deposit_root, deposit) =
+                        cache.get_deposit_proof(deposit_index).map_err(|e| {
+                            warp_utils::reject::custom_server_error(format!(
+                                "unable to generate deposit proof: {:?}",
+                                e
+                            ))
+                        })?;
+                    Ok(api_types::GenericResponse::from(
+                        eth2::lighthouse::DepositProof {
+                            deposit_root,
+                            deposit_count: cache.len() as u64,
+                            deposit,
+                        },
+                    ))
+                })
+            },
+        );
+
     // GET lighthouse/staking
     let get_lighthouse_staking = warp::path("lighthouse")
         .and(warp::path("staking"))
@@ -4389,12 +5149,29 @@ pub fn serve<T: BeaconChainTypes>(
              chain: Arc<BeaconChain<T>>| {
                 task_spawner.blocking_json_task(Priority::P1, move || {
                     not_synced_filter?;
+                    if chain.storage_watchdog.is_read_only() {
+                        return Err(warp_utils::reject::custom_server_error(
+                            "disk space is too low to reconstruct states".to_string(),
+                        ));
+                    }
                     chain.store_migrator.process_reconstruction();
                     Ok("success")
                 })
             },
         );
 
+    // POST lighthouse/database/migrate
+    let post_lighthouse_database_migrate = database_path
+        .and(warp::path("migrate"))
+        .and(warp::path::end())
+        .and(task_spawner_filter.clone())
+        .and(chain_filter.clone())
+        .then(
+            |task_spawner: TaskSpawner<T::EthSpec>, chain: Arc<BeaconChain<T>>| {
+                task_spawner.blocking_json_task(Priority::P1, move || database::migrate(chain))
+            },
+        );
+
     // GET lighthouse/analysis/block_rewards
     let get_lighthouse_block_rewards = warp::path("lighthouse")
         .and(warp::path("analysis"))
@@ -4404,11 +5181,18 @@ pub fn serve<T: BeaconChainTypes>(
         .and(task_spawner_filter.clone())
         .and(chain_filter.clone())
         .and(log_filter.clone())
-        .then(|query, task_spawner: TaskSpawner<T::EthSpec>, chain, log| {
-            task_spawner.blocking_json_task(Priority::P1, move || {
-                block_rewards::get_block_rewards(query, chain, log)
-            })
-        });
+        .and(archive_query_filter.clone())
+        .then(
+            |query,
+             task_spawner: TaskSpawner<T::EthSpec>,
+             chain,
+             log,
+             archive_query: Arc<ArchiveQueryLimiter>| {
+                task_spawner.blocking_json_task(Priority::P1, move || {
+                    block_rewards::get_block_rewards(query, chain, log, &archive_query)
+                })
+            },
+        );
 
     // POST lighthouse/analysis/block_rewards
     let post_lighthouse_block_rewards = warp::path("lighthouse")
@@ -4427,6 +5211,25 @@ pub fn serve<T: BeaconChainTypes>(
             },
         );
 
+    // GET lighthouse/analysis/block_reward_sources/{slot}
+    let get_lighthouse_block_reward_sources = warp::path("lighthouse")
+        .and(warp::path("analysis"))
+        .and(warp::path("block_reward_sources"))
+        .and(warp::path::param::<Slot>().or_else(|_| async {
+            Err(warp_utils::reject::custom_bad_request(
+                "Invalid slot".to_string(),
+            ))
+        }))
+        .and(warp::path::end())
+        .and(task_spawner_filter.clone())
+        .and(chain_filter.clone())
+        .and(log_filter.clone())
+        .then(|slot, task_spawner: TaskSpawner<T::EthSpec>, chain, log| {
+            task_spawner.blocking_json_task(Priority::P1, move || {
+                block_rewards::get_block_reward_sources(slot, chain, log)
+            })
+        });
+
     // GET lighthouse/analysis/attestation_performance/{index}
     let get_lighthouse_attestation_performance = warp::path("lighthouse")
         .and(warp::path("analysis"))
@@ -4460,6 +5263,43 @@ pub fn serve<T: BeaconChainTypes>(
             },
         );
 
+    // GET lighthouse/analysis/block_timings
+    let get_lighthouse_block_timings = warp::path("lighthouse")
+        .and(warp::path("analysis"))
+        .and(warp::path("block_timings"))
+        .and(warp::query::<eth2::lighthouse::BlockTimingsQuery>())
+        .and(warp::path::end())
+        .and(task_spawner_filter.clone())
+        .and(chain_filter.clone())
+        .then(
+            |query: eth2::lighthouse::BlockTimingsQuery,
+             task_spawner: TaskSpawner<T::EthSpec>,
+             chain: Arc<BeaconChain<T>>| {
+                task_spawner.blocking_json_task(Priority::P1, move || {
+                    chain
+                        .block_timings_report(query.proposer_index)
+                        .map_err(warp_utils::reject::beacon_chain_error)
+                })
+            },
+        );
+
+    // GET lighthouse/light_client/store
+    let get_lighthouse_light_client_store = warp::path("lighthouse")
+        .and(warp::path("light_client"))
+        .and(warp::path("store"))
+        .and(warp::path::end())
+        .and(task_spawner_filter.clone())
+        .and(chain_filter.clone())
+        .then(
+            |task_spawner: TaskSpawner<T::EthSpec>, chain: Arc<BeaconChain<T>>| {
+                task_spawner.blocking_json_task(Priority::P1, move || {
+                    chain
+                        .get_light_client_store_snapshot()
+                        .map_err(warp_utils::reject::beacon_chain_error)
+                })
+            },
+        );
+
     // GET lighthouse/merge_readiness
     let get_lighthouse_merge_readiness = warp::path("lighthouse")
         .and(warp::path("merge_readiness"))
@@ -4683,15 +5523,39 @@ pub fn serve<T: BeaconChainTypes>(
                 .uor(get_lighthouse_nat)
                 .uor(get_lighthouse_peers)
                 .uor(get_lighthouse_peers_connected)
+                .uor(get_lighthouse_peers_decisions)
+                .uor(get_lighthouse_network_client_diversity)
+                .uor(get_lighthouse_network_fork_readiness)
+                .uor(get_lighthouse_network_reachability)
+                .uor(get_lighthouse_network_subnets)
+                .uor(get_lighthouse_identity)
+                .uor(get_lighthouse_block_value)
                 .uor(get_lighthouse_proto_array)
+                .uor(get_lighthouse_op_pool)
+                .uor(get_lighthouse_op_pool_censorship)
+                .uor(get_lighthouse_fork_choice_reorgs)
+                .uor(get_lighthouse_beacon_processor)
+                .uor(
+                    enable(ctx.config.enable_unsafe_block_production)
+                        .and(get_lighthouse_validator_blocks),
+                )
                 .uor(get_lighthouse_validator_inclusion_global)
+                .uor(get_lighthouse_validator_withdrawal_projection)
+                .uor(get_lighthouse_pending_deposits)
+                .uor(get_lighthouse_pending_partial_withdrawals)
+                .uor(get_lighthouse_pending_consolidations)
                 .uor(get_lighthouse_validator_inclusion)
+                .uor(get_lighthouse_attestation_inclusion)
+                .uor(get_lighthouse_export_attestations)
+                .uor(get_lighthouse_epoch_summary)
                 .uor(get_lighthouse_eth1_syncing)
                 .uor(get_lighthouse_eth1_block_cache)
                 .uor(get_lighthouse_eth1_deposit_cache)
+                .uor(get_lighthouse_eth1_deposit_proof)
                 .uor(get_lighthouse_staking)
                 .uor(get_lighthouse_database_info)
                 .uor(get_lighthouse_block_rewards)
+                .uor(get_lighthouse_block_reward_sources)
                 .uor(get_lighthouse_attestation_performance)
                 .uor(
                     enable(ctx.config.enable_light_client_server)
@@ -4709,7 +5573,12 @@ pub fn serve<T: BeaconChainTypes>(
                     enable(ctx.config.enable_light_client_server)
                         .and(get_beacon_light_client_updates),
                 )
+                .uor(
+                    enable(ctx.config.enable_light_client_server)
+                        .and(get_lighthouse_light_client_store),
+                )
                 .uor(get_lighthouse_block_packing_efficiency)
+                .uor(get_lighthouse_block_timings)
                 .uor(get_lighthouse_merge_readiness)
                 .uor(get_events)
                 .uor(get_expected_withdrawals)
@@ -4751,52 +5620,231 @@ pub fn serve<T: BeaconChainTypes>(
                     .uor(post_validator_register_validator)
                     .uor(post_validator_liveness_epoch)
                     .uor(post_lighthouse_liveness)
+                    .uor(post_lighthouse_beacon_blocks_batch)
+                    .uor(post_lighthouse_data_columns)
+                    .uor(post_lighthouse_blobs)
                     .uor(post_lighthouse_database_reconstruct)
+                    .uor(post_lighthouse_database_migrate)
                     .uor(post_lighthouse_block_rewards)
                     .uor(post_lighthouse_ui_validator_metrics)
                     .uor(post_lighthouse_ui_validator_info)
+                    .uor(post_lighthouse_peers_protect)
                     .recover(warp_utils::reject::handle_rejection),
             ),
         )
+        .boxed();
+
+    let Some(admin_config) = config.admin.as_ref() else {
+        let routes = finalize_routes(routes, cors_builder, log.clone());
+        let http_socket = SocketAddr::new(config.listen_addr, config.listen_port);
+        let http_server = bind_server(
+            routes,
+            http_socket,
+            config.tls_config.as_ref(),
+            shutdown,
+            &log,
+        )?;
+
+        info!(
+            log,
+            "HTTP API started";
+            "listen_address" => %http_server.0,
+        );
+
+        return Ok(http_server);
+    };
+
+    // The admin listener has its own CORS origins and allows the `Authorization` header.
+    let admin_cors_builder = {
+        let builder = warp::cors()
+            .allow_methods(vec!["GET", "POST"])
+            .allow_headers(vec!["Content-Type", "Authorization"]);
+
+        warp_utils::cors::set_builder_origins(
+            builder,
+            admin_config.allow_origin.as_deref(),
+            (admin_config.listen_addr, admin_config.listen_port),
+        )?
+    };
+
+    let admin_api_token = admin_config
+        .api_token_path
+        .as_ref()
+        .map(|path| {
+            std::fs::read_to_string(path)
+                .map(|token| token.trim().to_string())
+                .map_err(|e| Error::Other(format!("Unable to read admin API token: {e:?}")))
+        })
+        .transpose()?;
+    if admin_api_token.as_ref().is_some_and(String::is_empty) {
+        return Err(Error::Other("The admin API token is empty".to_string()));
+    }
+
+    let public_routes = finalize_routes(
+        admin_route_filter(false).and(routes.clone()),
+        cors_builder,
+        log.clone(),
+    );
+    let admin_routes = match admin_api_token {
+        Some(token) => admin_route_filter(true)
+            .and(bearer_token_filter(token))
+            .and(routes)
+            .boxed(),
+        None => admin_route_filter(true).and(routes).boxed(),
+    };
+    let admin_routes = finalize_routes(admin_routes, admin_cors_builder, log.clone());
+
+    // Both listeners are stopped by the same shutdown signal.
+    let shutdown = futures::FutureExt::shared(Box::pin(shutdown));
+    let http_socket = SocketAddr::new(config.listen_addr, config.listen_port);
+    let (listen_addr, public_server) = bind_server(
+        public_routes,
+        http_socket,
+        config.tls_config.as_ref(),
+        shutdown.clone(),
+        &log,
+    )?;
+    let admin_socket = SocketAddr::new(admin_config.listen_addr, admin_config.listen_port);
+    let (admin_listen_addr, admin_server) = bind_server(
+        admin_routes,
+        admin_socket,
+        config.tls_config.as_ref(),
+        shutdown,
+        &log,
+    )?;
+
+    info!(
+        log,
+        "HTTP API started";
+        "listen_address" => %listen_addr,
+        "admin_listen_address" => %admin_listen_addr,
+    );
+
+    let server = async move {
+        futures::future::join(public_server, admin_server).await;
+    };
+    Ok((listen_addr, Box::pin(server)))
+}
+
+/// Add the logging, metrics, `Server` header and CORS layers shared by all listeners.
+fn finalize_routes<F, R>(
+    routes: F,
+    cors_builder: warp::filters::cors::Builder,
+    log: Logger,
+) -> BoxedFilter<(impl Reply,)>
+where
+    F: Filter<Extract = (R,), Error = Rejection> + Clone + Send + Sync + 'static,
+    R: Reply + Send + 'static,
+{
+    routes
         .recover(warp_utils::reject::handle_rejection)
-        .with(slog_logging(log.clone()))
+        .with(slog_logging(log))
         .with(prometheus_metrics())
         // Add a `Server` header.
         .map(|reply| warp::reply::with_header(reply, "Server", &version_with_platform()))
         .with(cors_builder.build())
-        .boxed();
+        .boxed()
+}
 
-    let http_socket: SocketAddr = SocketAddr::new(config.listen_addr, config.listen_port);
-    let http_server: HttpServer = match config.tls_config {
+/// Serve `routes` on `socket`, over TLS if `tls_config` is set.
+fn bind_server<R: Reply + 'static>(
+    routes: BoxedFilter<(R,)>,
+    socket: SocketAddr,
+    tls_config: Option<&TlsConfig>,
+    shutdown: impl Future<Output = ()> + Send + 'static,
+    log: &Logger,
+) -> Result<HttpServer, Error> {
+    match tls_config {
         Some(tls_config) => {
             let (socket, server) = warp::serve(routes)
                 .tls()
-                .cert_path(tls_config.cert)
-                .key_path(tls_config.key)
-                .try_bind_with_graceful_shutdown(http_socket, async {
-                    shutdown.await;
-                })?;
+                .cert_path(&tls_config.cert)
+                .key_path(&tls_config.key)
+                .try_bind_with_graceful_shutdown(socket, shutdown)?;
 
-            info!(log, "HTTP API is being served over TLS";);
+            info!(log, "HTTP API is being served over TLS"; "listen_address" => %socket);
 
-            (socket, Box::pin(server))
+            Ok((socket, Box::pin(server)))
         }
         None => {
             let (socket, server) =
-                warp::serve(routes).try_bind_with_graceful_shutdown(http_socket, async {
-                    shutdown.await;
-                })?;
-            (socket, Box::pin(server))
+                warp::serve(routes).try_bind_with_graceful_shutdown(socket, shutdown)?;
+            Ok((socket, Box::pin(server)))
         }
-    };
+    }
+}
 
-    info!(
-        log,
-        "HTTP API started";
-        "listen_address" => %http_server.0,
-    );
+/// Only pass requests for the `/lighthouse` routes if `admin` is set, or only requests for the
+/// other routes if not.
+fn admin_route_filter(admin: bool) -> impl Filter<Extract = (), Error = Rejection> + Clone {
+    warp::path::peek()
+        .and_then(move |path: warp::path::Peek| {
+            let is_admin_route = path.segments().next() == Some("lighthouse");
+            async move {
+                if is_admin_route == admin {
+                    Ok(())
+                } else {
+                    Err(warp::reject::not_found())
+                }
+            }
+        })
+        .untuple_one()
+}
 
-    Ok(http_server)
+/// Require `token` as a bearer token in the `Authorization` header.
+fn bearer_token_filter(token: String) -> impl Filter<Extract = (), Error = Rejection> + Clone {
+    let expected = format!("Bearer {token}");
+    warp::header::<String>("Authorization")
+        .and_then(move |header: String| {
+            let is_valid = constant_time_eq(header.as_bytes(), expected.as_bytes());
+            async move {
+                if is_valid {
+                    Ok(())
+                } else {
+                    Err(warp_utils::reject::invalid_auth(header))
+                }
+            }
+        })
+        .untuple_one()
+}
+
+/// Build the `/eth/v1/node/identity` response from the node's ENR and metadata.
+///
+/// The `p2p_addresses` include both the TCP and the QUIC multiaddrs, since RPC requests are served
+/// on either transport.
+fn node_identity<E: EthSpec>(network_globals: &NetworkGlobals<E>) -> api_types::IdentityData {
+    let enr = network_globals.local_enr();
+    let mut p2p_addresses = enr.multiaddr_p2p_tcp();
+    p2p_addresses.extend(enr.multiaddr_p2p_quic());
+    let discovery_addresses = enr.multiaddr_p2p_udp();
+    let meta_data = network_globals.local_metadata.read();
+    api_types::IdentityData {
+        peer_id: network_globals.local_peer_id().to_base58(),
+        enr,
+        p2p_addresses,
+        discovery_addresses,
+        metadata: api_types::MetaData {
+            seq_number: *meta_data.seq_number(),
+            attnets: format!(
+                "0x{}",
+                hex::encode(meta_data.attnets().clone().into_bytes()),
+            ),
+            syncnets: format!(
+                "0x{}",
+                hex::encode(
+                    meta_data
+                        .syncnets()
+                        .cloned()
+                        .unwrap_or_default()
+                        .into_bytes()
+                )
+            ),
+            custody_group_count: meta_data
+                .custody_subnet_count()
+                .ok()
+                .map(|&value| Quoted { value }),
+        },
+    }
 }
 
 /// Publish a message to the libp2p pubsub network.
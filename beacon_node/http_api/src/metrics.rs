@@ -51,3 +51,17 @@ pub static HTTP_API_STATE_ROOT_TIMES: LazyLock<Result<Histogram>> = LazyLock::ne
         "Time to load a state root for a request",
     )
 });
+pub static HTTP_API_ARCHIVE_QUERIES_TOTAL: LazyLock<Result<IntCounterVec>> = LazyLock::new(|| {
+    try_create_int_counter_vec(
+        "http_api_archive_queries_total",
+        "Count of queries for finalized states by admission outcome",
+        &["outcome"],
+    )
+});
+pub static HTTP_API_ARCHIVE_QUERY_COST: LazyLock<Result<Histogram>> = LazyLock::new(|| {
+    try_create_histogram_with_buckets(
+        "http_api_archive_query_cost",
+        "Estimated cost of queries for finalized states, in units of replayed blocks",
+        Ok(vec![0.0, 1.0, 8.0, 32.0, 128.0, 512.0, 2048.0, 8192.0]),
+    )
+});
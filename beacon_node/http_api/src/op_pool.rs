@@ -0,0 +1,89 @@
+use beacon_chain::{BeaconChain, BeaconChainTypes};
+use eth2::lighthouse::{
+    CensorshipReport, OperationPoolCommitteeInfo, OperationPoolInfo, PendingOperationInclusion,
+    PendingOperationKind,
+};
+use operation_pool::{PendingOperation, CENSORSHIP_OMITTED_BLOCKS_THRESHOLD};
+use std::sync::Arc;
+use warp_utils::reject::beacon_chain_error;
+
+/// Summarise the contents of the operation pool.
+pub fn info<T: BeaconChainTypes>(chain: Arc<BeaconChain<T>>) -> OperationPoolInfo {
+    let op_pool = &chain.op_pool;
+
+    let attestations = op_pool
+        .attestation_committee_stats()
+        .into_iter()
+        .map(
+            |((slot, committee_index), stats)| OperationPoolCommitteeInfo {
+                slot,
+                committee_index,
+                num_unaggregated: stats.num_unaggregated,
+                num_aggregated: stats.num_aggregated,
+                max_attesters: stats.max_attesters,
+            },
+        )
+        .collect::<Vec<_>>();
+
+    let num_unaggregated_attestations = attestations.iter().map(|c| c.num_unaggregated).sum();
+    let num_aggregated_attestations = attestations.iter().map(|c| c.num_aggregated).sum();
+
+    OperationPoolInfo {
+        num_attestations: op_pool.num_attestations(),
+        num_unaggregated_attestations,
+        num_aggregated_attestations,
+        num_sync_contributions: op_pool.num_sync_contributions(),
+        num_attester_slashings: op_pool.num_attester_slashings(),
+        num_proposer_slashings: op_pool.num_proposer_slashings(),
+        num_voluntary_exits: op_pool.num_voluntary_exits(),
+        num_bls_to_execution_changes: op_pool.num_bls_to_execution_changes(),
+        attestations,
+    }
+}
+
+/// Report how long the valid exits, BLS to execution changes and slashings in the operation pool
+/// have been awaiting inclusion.
+pub fn censorship_report<T: BeaconChainTypes>(
+    chain: Arc<BeaconChain<T>>,
+) -> Result<CensorshipReport, warp::Rejection> {
+    let current_slot = chain.slot().map_err(beacon_chain_error)?;
+
+    let pending = chain
+        .op_pool
+        .pending_operation_records()
+        .into_iter()
+        .map(|(operation, record)| {
+            let (kind, validator_index, root) = match operation {
+                PendingOperation::ProposerSlashing(index) => {
+                    (PendingOperationKind::ProposerSlashing, Some(index), None)
+                }
+                PendingOperation::AttesterSlashing(root) => {
+                    (PendingOperationKind::AttesterSlashing, None, Some(root))
+                }
+                PendingOperation::VoluntaryExit(index) => {
+                    (PendingOperationKind::VoluntaryExit, Some(index), None)
+                }
+                PendingOperation::BlsToExecutionChange(index) => (
+                    PendingOperationKind::BlsToExecutionChange,
+                    Some(index),
+                    None,
+                ),
+            };
+            PendingOperationInclusion {
+                kind,
+                validator_index,
+                root,
+                first_seen_slot: record.first_seen_slot,
+                blocks_omitted: record.blocks_omitted,
+                local_blocks_omitted: record.local_blocks_omitted,
+                suspected_censorship: record.is_suspected_censorship(),
+            }
+        })
+        .collect();
+
+    Ok(CensorshipReport {
+        current_slot,
+        omitted_blocks_threshold: CENSORSHIP_OMITTED_BLOCKS_THRESHOLD,
+        pending,
+    })
+}
@@ -0,0 +1,124 @@
+use crate::state_id::StateId;
+use beacon_chain::{BeaconChain, BeaconChainTypes};
+use eth2::lighthouse::{PendingQueueItem, PendingQueuePage, PendingQueueQuery};
+use eth2::types::ExecutionOptimisticFinalizedResponse;
+use serde::{de::DeserializeOwned, Serialize};
+use std::ops::Range;
+use types::{
+    BeaconState, BeaconStateError, PendingBalanceDeposit, PendingConsolidation,
+    PendingPartialWithdrawal,
+};
+use warp_utils::reject::custom_bad_request;
+
+/// The maximum number of queue items which may be requested at once.
+pub const MAX_PENDING_QUEUE_PAGE_SIZE: u64 = 1024;
+
+type PendingQueueResponse<T> = ExecutionOptimisticFinalizedResponse<PendingQueuePage<T>>;
+
+pub fn pending_deposits<T: BeaconChainTypes>(
+    state_id: StateId,
+    query: PendingQueueQuery,
+    chain: &BeaconChain<T>,
+) -> Result<PendingQueueResponse<PendingBalanceDeposit>, warp::Rejection> {
+    pending_queue(state_id, query, chain, |state, range| {
+        let queue = state.pending_balance_deposits()?;
+        Ok(PendingQueuePage {
+            queue_length: queue.len() as u64,
+            queue_amount_gwei: Some(queue.iter().map(|deposit| deposit.amount).sum()),
+            items: page(queue.iter(), range),
+        })
+    })
+}
+
+pub fn pending_partial_withdrawals<T: BeaconChainTypes>(
+    state_id: StateId,
+    query: PendingQueueQuery,
+    chain: &BeaconChain<T>,
+) -> Result<PendingQueueResponse<PendingPartialWithdrawal>, warp::Rejection> {
+    pending_queue(state_id, query, chain, |state, range| {
+        let queue = state.pending_partial_withdrawals()?;
+        Ok(PendingQueuePage {
+            queue_length: queue.len() as u64,
+            queue_amount_gwei: Some(queue.iter().map(|withdrawal| withdrawal.amount).sum()),
+            items: page(queue.iter(), range),
+        })
+    })
+}
+
+pub fn pending_consolidations<T: BeaconChainTypes>(
+    state_id: StateId,
+    query: PendingQueueQuery,
+    chain: &BeaconChain<T>,
+) -> Result<PendingQueueResponse<PendingConsolidation>, warp::Rejection> {
+    pending_queue(state_id, query, chain, |state, range| {
+        let queue = state.pending_consolidations()?;
+        // Consolidations move the source validator's whole balance, so they have no amount of
+        // their own.
+        Ok(PendingQueuePage {
+            queue_length: queue.len() as u64,
+            queue_amount_gwei: None,
+            items: page(queue.iter(), range),
+        })
+    })
+}
+
+/// Load the state for `state_id` and read the page of one of its queues with `get_page`.
+///
+/// `get_page` is passed the range of queue positions selected by `query`.
+fn pending_queue<T, U, F>(
+    state_id: StateId,
+    query: PendingQueueQuery,
+    chain: &BeaconChain<T>,
+    get_page: F,
+) -> Result<PendingQueueResponse<U>, warp::Rejection>
+where
+    T: BeaconChainTypes,
+    U: Serialize + DeserializeOwned,
+    F: Fn(&BeaconState<T::EthSpec>, Range<usize>) -> Result<PendingQueuePage<U>, BeaconStateError>,
+{
+    let offset = query.offset.unwrap_or(0);
+    let limit = query.limit.unwrap_or(MAX_PENDING_QUEUE_PAGE_SIZE);
+    if limit > MAX_PENDING_QUEUE_PAGE_SIZE {
+        return Err(custom_bad_request(format!(
+            "limit of {} exceeds the maximum of {}",
+            limit, MAX_PENDING_QUEUE_PAGE_SIZE
+        )));
+    }
+    let range = offset as usize..offset.saturating_add(limit) as usize;
+
+    let (data, execution_optimistic, finalized) = state_id
+        .map_state_and_execution_optimistic_and_finalized(
+            chain,
+            |state, execution_optimistic, finalized| {
+                // The queues only exist from Electra onwards.
+                let data = get_page(state, range.clone()).map_err(|_| {
+                    custom_bad_request(format!(
+                        "pending queues are not available at slot {}",
+                        state.slot()
+                    ))
+                })?;
+                Ok((data, execution_optimistic, finalized))
+            },
+        )?;
+
+    Ok(ExecutionOptimisticFinalizedResponse {
+        data,
+        execution_optimistic: Some(execution_optimistic),
+        finalized: Some(finalized),
+    })
+}
+
+fn page<'a, U: Clone + 'a>(
+    queue: impl Iterator<Item = &'a U>,
+    range: Range<usize>,
+) -> Vec<PendingQueueItem<U>> {
+    queue
+        .enumerate()
+        .skip(range.start)
+        .take(range.len())
+        .map(|(position, item)| PendingQueueItem {
+            position: position as u64,
+            item: item.clone(),
+        })
+        .collect()
+}
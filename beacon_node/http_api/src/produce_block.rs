@@ -47,6 +47,19 @@ pub async fn produce_block_v3<T: BeaconChainTypes>(
     chain: Arc<BeaconChain<T>>,
     slot: Slot,
     query: api_types::ValidatorBlocksQuery,
+) -> Result<Response<Body>, warp::Rejection> {
+    produce_block_v3_on_parent(accept_header, chain, slot, None, query).await
+}
+
+/// Produce a block in the manner of `produce_block_v3`.
+///
+/// If `parent_root` is provided the block is built upon that parent rather than the head.
+pub async fn produce_block_v3_on_parent<T: BeaconChainTypes>(
+    accept_header: Option<api_types::Accept>,
+    chain: Arc<BeaconChain<T>>,
+    slot: Slot,
+    parent_root: Option<Hash256>,
+    query: api_types::ValidatorBlocksQuery,
 ) -> Result<Response<Body>, warp::Rejection> {
     let randao_reveal = query.randao_reveal.decompress().map_err(|e| {
         warp_utils::reject::custom_bad_request(format!(
@@ -62,19 +75,33 @@ pub async fn produce_block_v3<T: BeaconChainTypes>(
         query.builder_boost_factor
     };
 
-    let block_response_type = chain
-        .produce_block_with_verification(
-            randao_reveal,
-            slot,
-            query.graffiti,
-            randao_verification,
-            builder_boost_factor,
-            BlockProductionVersion::V3,
-        )
-        .await
-        .map_err(|e| {
-            warp_utils::reject::custom_bad_request(format!("failed to fetch a block: {:?}", e))
-        })?;
+    let block_response_type = if let Some(parent_root) = parent_root {
+        chain
+            .produce_block_on_parent(
+                parent_root,
+                randao_reveal,
+                slot,
+                query.graffiti,
+                randao_verification,
+                builder_boost_factor,
+                BlockProductionVersion::V3,
+            )
+            .await
+    } else {
+        chain
+            .produce_block_with_verification(
+                randao_reveal,
+                slot,
+                query.graffiti,
+                randao_verification,
+                builder_boost_factor,
+                BlockProductionVersion::V3,
+            )
+            .await
+    }
+    .map_err(|e| {
+        warp_utils::reject::custom_bad_request(format!("failed to fetch a block: {:?}", e))
+    })?;
 
     build_response_v3(chain, block_response_type, accept_header)
 }
@@ -36,8 +36,8 @@
 //! attestations and there's no immediate cause for concern.
 use crate::task_spawner::{Priority, TaskSpawner};
 use beacon_chain::{
-    validator_monitor::timestamp_now, AttestationError, BeaconChain, BeaconChainError,
-    BeaconChainTypes,
+    publish_safety::PublishSafetyViolation, validator_monitor::timestamp_now, AttestationError,
+    BeaconChain, BeaconChainError, BeaconChainTypes,
 };
 use beacon_processor::work_reprocessing_queue::{QueuedUnaggregate, ReprocessQueueMessage};
 use eth2::types::Failure;
@@ -56,6 +56,7 @@ use types::Attestation;
 #[derive(Debug)]
 enum Error {
     Validation(AttestationError),
+    PublishSafety(#[allow(dead_code)] PublishSafetyViolation),
     Publication,
     ForkChoice(#[allow(dead_code)] BeaconChainError),
     AggregationPool(#[allow(dead_code)] AttestationError),
@@ -82,6 +83,17 @@ fn verify_and_publish_attestation<T: BeaconChainTypes>(
         .verify_unaggregated_attestation_for_gossip(attestation, None)
         .map_err(Error::Validation)?;
 
+    // Refuse to publish attestations which are slashable with respect to those recently observed.
+    if let Err(e) = chain.check_and_observe_attestation_publish_safety(&attestation) {
+        warn!(
+            log,
+            "Not publishing slashable attestation";
+            "error" => ?e,
+            "slot" => attestation.attestation().data().slot,
+        );
+        return Err(Error::PublishSafety(e));
+    }
+
     // Publish.
     network_tx
         .send(NetworkMessage::Publish {
@@ -26,6 +26,7 @@ use std::sync::Arc;
 use std::time::Duration;
 use tokio::sync::mpsc::UnboundedSender;
 use tree_hash::TreeHash;
+use types::blob_sidecar::BlobSidecarError;
 use types::{
     AbstractExecPayload, BeaconBlockRef, BlobSidecar, BlobsList, BlockImportSource,
     DataColumnSubnetId, EthSpec, ExecPayload, ExecutionBlockHash, ForkName, FullPayload,
@@ -133,8 +134,14 @@ pub async fn publish_block<T: BeaconChainTypes, B: IntoGossipVerifiedBlock<T>>(
     let slot = block.message().slot();
     let sender_clone = network_tx.clone();
 
-    let build_sidecar_task_handle =
-        spawn_build_data_sidecar_task(chain.clone(), block.clone(), unverified_blobs, log.clone())?;
+    let build_sidecar_task_handle = spawn_build_data_sidecar_task(
+        chain.clone(),
+        block_root,
+        block.clone(),
+        unverified_blobs,
+        network_tx.clone(),
+        log.clone(),
+    )?;
 
     // Gossip verify the block and blobs/data columns separately.
     let gossip_verified_block_result = unverified_block.into_gossip_verified_block(&chain);
@@ -162,12 +169,17 @@ pub async fn publish_block<T: BeaconChainTypes, B: IntoGossipVerifiedBlock<T>>(
         if should_publish_block {
             match validation_level {
                 BroadcastValidation::Gossip => (),
-                BroadcastValidation::Consensus => publish_block_p2p(
-                    block_to_publish.clone(),
-                    sender_clone.clone(),
-                    log.clone(),
-                    seen_timestamp,
-                )?,
+                BroadcastValidation::Consensus => {
+                    if chain.config.publish_safety_checks {
+                        check_slashable(&chain, block_root, &block_to_publish, &log)?;
+                    }
+                    publish_block_p2p(
+                        block_to_publish.clone(),
+                        sender_clone.clone(),
+                        log.clone(),
+                        seen_timestamp,
+                    )?;
+                }
                 BroadcastValidation::ConsensusAndEquivocation => {
                     check_slashable(&chain, block_root, &block_to_publish, &log)?;
                     publish_block_p2p(
@@ -185,12 +197,10 @@ pub async fn publish_block<T: BeaconChainTypes, B: IntoGossipVerifiedBlock<T>>(
     };
 
     // Wait for blobs/columns to get gossip verified before proceeding further as we need them for import.
+    // Blobs are published by the task as soon as they are verified.
     let (gossip_verified_blobs, gossip_verified_columns) = build_sidecar_task_handle.await?;
 
     for blob in gossip_verified_blobs.into_iter().flatten() {
-        publish_blob_sidecars(network_tx, &blob).map_err(|_| {
-            warp_utils::reject::custom_server_error("unable to publish blob sidecars".into())
-        })?;
         if let Err(e) = Box::pin(chain.process_gossip_blob(blob)).await {
             let msg = format!("Invalid blob: {e}");
             return if let BroadcastValidation::Gossip = validation_level {
@@ -332,12 +342,14 @@ type BuildDataSidecarTaskResult<T> = Result<
 
 /// Convert blobs to either:
 ///
-/// 1. Blob sidecars if prior to peer DAS, or
+/// 1. Blob sidecars if prior to peer DAS, which are published as soon as they are verified, or
 /// 2. Data column sidecars if post peer DAS.
 fn spawn_build_data_sidecar_task<T: BeaconChainTypes>(
     chain: Arc<BeaconChain<T>>,
+    block_root: Option<Hash256>,
     block: Arc<SignedBeaconBlock<T::EthSpec, FullPayload<T::EthSpec>>>,
     proofs_and_blobs: UnverifiedBlobs<T>,
+    network_tx: UnboundedSender<NetworkMessage<T::EthSpec>>,
     log: Logger,
 ) -> Result<impl Future<Output = BuildDataSidecarTaskResult<T>>, Rejection> {
     chain
@@ -352,8 +364,16 @@ fn spawn_build_data_sidecar_task<T: BeaconChainTypes>(
                 let peer_das_enabled = chain.spec.is_peer_das_enabled_for_epoch(block.epoch());
                 if !peer_das_enabled {
                     // Pre-PeerDAS: construct blob sidecars for the network.
-                    let gossip_verified_blobs =
-                        build_gossip_verified_blobs(&chain, &block, blobs, kzg_proofs, &log)?;
+                    let block_root = block_root.unwrap_or_else(|| block.canonical_root());
+                    let gossip_verified_blobs = build_and_publish_gossip_verified_blobs(
+                        &chain,
+                        block_root,
+                        &block,
+                        blobs,
+                        kzg_proofs,
+                        &network_tx,
+                        &log,
+                    )?;
                     Ok((gossip_verified_blobs, vec![]))
                 } else {
                     // Post PeerDAS: construct data columns.
@@ -433,41 +453,80 @@ fn build_gossip_verified_data_columns<T: BeaconChainTypes>(
     Ok(gossip_verified_data_columns)
 }
 
-fn build_gossip_verified_blobs<T: BeaconChainTypes>(
+/// Build and gossip verify the blob sidecars of `block`, publishing each one as soon as it has
+/// been verified.
+///
+/// The inclusion proofs are taken from the cache populated during block production if the block
+/// was produced by this node, and are otherwise computed in parallel up front.
+fn build_and_publish_gossip_verified_blobs<T: BeaconChainTypes>(
     chain: &BeaconChain<T>,
+    block_root: Hash256,
     block: &SignedBeaconBlock<T::EthSpec, FullPayload<T::EthSpec>>,
     blobs: BlobsList<T::EthSpec>,
     kzg_proofs: KzgProofs<T::EthSpec>,
+    network_tx: &UnboundedSender<NetworkMessage<T::EthSpec>>,
     log: &Logger,
 ) -> Result<Vec<Option<GossipVerifiedBlob<T>>>, Rejection> {
     let slot = block.slot();
+    let invalid_blob = |e: &dyn std::fmt::Debug, blob_index: Option<usize>| {
+        error!(
+            log,
+            "Invalid blob - not publishing block";
+            "error" => ?e,
+            "blob_index" => ?blob_index,
+            "slot" => slot,
+        );
+        warp_utils::reject::custom_bad_request(format!("{e:?}"))
+    };
+
+    let inclusion_proofs = match chain.blob_inclusion_proof_cache.get(&block_root) {
+        Some(inclusion_proofs) => inclusion_proofs,
+        None => {
+            let _timer = metrics::start_timer(
+                &beacon_chain::metrics::BLOB_SIDECAR_INCLUSION_PROOF_COMPUTATION,
+            );
+            block
+                .message()
+                .body()
+                .kzg_commitment_merkle_proofs()
+                .map(Arc::new)
+                .map_err(|e| invalid_blob(&e, None))?
+        }
+    };
+    let signed_block_header = block.signed_block_header();
+
     let gossip_verified_blobs = kzg_proofs
         .into_iter()
         .zip(blobs)
         .enumerate()
         .map(|(i, (proof, unverified_blob))| {
-            let timer = metrics::start_timer(
-                &beacon_chain::metrics::BLOB_SIDECAR_INCLUSION_PROOF_COMPUTATION,
-            );
-            let blob_sidecar = BlobSidecar::new(i, unverified_blob, block, proof)
-                .map(Arc::new)
-                .map_err(|e| {
-                    error!(
-                        log,
-                        "Invalid blob - not publishing block";
-                        "error" => ?e,
-                        "blob_index" => i,
-                        "slot" => slot,
-                    );
-                    warp_utils::reject::custom_bad_request(format!("{e:?}"))
-                })?;
-            drop(timer);
+            let inclusion_proof = inclusion_proofs
+                .get(i)
+                .cloned()
+                .ok_or_else(|| invalid_blob(&BlobSidecarError::MissingKzgCommitment, Some(i)))?;
+            let blob_sidecar = BlobSidecar::new_with_inclusion_proof(
+                i,
+                unverified_blob,
+                block,
+                signed_block_header.clone(),
+                inclusion_proof,
+                proof,
+            )
+            .map(Arc::new)
+            .map_err(|e| invalid_blob(&e, Some(i)))?;
 
             let gossip_verified_blob =
                 GossipVerifiedBlob::new(blob_sidecar.clone(), blob_sidecar.index, chain);
 
             match gossip_verified_blob {
-                Ok(blob) => Ok(Some(blob)),
+                Ok(blob) => {
+                    publish_blob_sidecars(network_tx, &blob).map_err(|_| {
+                        warp_utils::reject::custom_server_error(
+                            "unable to publish blob sidecars".into(),
+                        )
+                    })?;
+                    Ok(Some(blob))
+                }
                 Err(GossipBlobError::RepeatBlob { proposer, .. }) => {
                     // Log the error but do not abort publication, we may need to publish the block
                     // or some of the other blobs if the block & blobs are only partially published
@@ -639,6 +698,12 @@ pub async fn publish_blinded_block<T: BeaconChainTypes>(
     let block_root = blinded_block.canonical_root();
     let full_block =
         reconstruct_block(chain.clone(), block_root, blinded_block, log.clone()).await?;
+    if let ProvenancedBlock::Builder(block, _, _) = &full_block {
+        chain
+            .block_times_cache
+            .write()
+            .set_revealed_by_builder_relay(block_root, block.slot());
+    }
     publish_block::<T, _>(
         Some(block_root),
         full_block,
@@ -0,0 +1,169 @@
+//! Import blob and data column sidecars supplied by trusted local tooling and publish them to the
+//! network.
+//!
+//! These endpoints exist for recovery setups where sidecars are obtained out-of-band (e.g.
+//! reconstructed on another machine) and need to be fed into the data availability checker. The
+//! sidecars are subject to full gossip verification, so they cannot be used to bypass any checks.
+use beacon_chain::blob_verification::{GossipBlobError, GossipVerifiedBlob};
+use beacon_chain::data_column_verification::{GossipDataColumnError, GossipVerifiedDataColumn};
+use beacon_chain::{BeaconChain, BeaconChainTypes, BlockError};
+use eth2::types::Failure;
+use lighthouse_network::PubsubMessage;
+use network::NetworkMessage;
+use slog::{debug, warn, Logger};
+use std::collections::BTreeMap;
+use std::sync::Arc;
+use tokio::sync::mpsc::UnboundedSender;
+use types::{BlobSidecar, DataColumnSidecar, DataColumnSubnetId, Hash256};
+use warp::Rejection;
+
+/// Verify, publish and import a list of data column sidecars.
+///
+/// Columns which are already known are skipped silently. An indexed error is returned for any
+/// columns which fail verification or import.
+pub async fn publish_data_columns<T: BeaconChainTypes>(
+    data_columns: Vec<Arc<DataColumnSidecar<T::EthSpec>>>,
+    chain: Arc<BeaconChain<T>>,
+    network_tx: UnboundedSender<NetworkMessage<T::EthSpec>>,
+    log: Logger,
+) -> Result<(), Rejection> {
+    let mut failures = vec![];
+    let mut verified_by_block =
+        BTreeMap::<Hash256, Vec<(usize, GossipVerifiedDataColumn<T>)>>::new();
+
+    for (index, data_column) in data_columns.into_iter().enumerate() {
+        let subnet = DataColumnSubnetId::from_column_index::<T::EthSpec>(
+            data_column.index as usize,
+            &chain.spec,
+        );
+        match GossipVerifiedDataColumn::<T>::new(data_column, subnet.into(), &chain) {
+            Ok(verified) => verified_by_block
+                .entry(verified.block_root())
+                .or_default()
+                .push((index, verified)),
+            Err(GossipDataColumnError::PriorKnown { slot, index, .. }) => {
+                debug!(
+                    log,
+                    "Ignoring known data column";
+                    "slot" => slot,
+                    "column_index" => index,
+                );
+            }
+            Err(e) => failures.push(Failure::new(index, format!("Verification: {e:?}"))),
+        }
+    }
+
+    for (block_root, columns) in verified_by_block {
+        let (indices, columns): (Vec<_>, Vec<_>) = columns.into_iter().unzip();
+
+        let messages = columns
+            .iter()
+            .map(|column| {
+                let subnet = DataColumnSubnetId::from_column_index::<T::EthSpec>(
+                    column.index() as usize,
+                    &chain.spec,
+                );
+                PubsubMessage::DataColumnSidecar(Box::new((subnet, column.clone_data_column())))
+            })
+            .collect::<Vec<_>>();
+        crate::publish_pubsub_messages(&network_tx, messages)?;
+
+        match chain.process_gossip_data_columns(columns, || Ok(())).await {
+            Ok(status) => debug!(
+                log,
+                "Imported data columns from API";
+                "block_root" => ?block_root,
+                "status" => ?status,
+            ),
+            Err(BlockError::DuplicateFullyImported(_)) => debug!(
+                log,
+                "Data columns for imported block";
+                "block_root" => ?block_root,
+            ),
+            Err(e) => {
+                warn!(
+                    log,
+                    "Unable to import data columns from API";
+                    "block_root" => ?block_root,
+                    "error" => ?e,
+                );
+                failures.extend(
+                    indices
+                        .into_iter()
+                        .map(|index| Failure::new(index, format!("Import: {e:?}"))),
+                );
+            }
+        }
+    }
+
+    if failures.is_empty() {
+        Ok(())
+    } else {
+        Err(warp_utils::reject::indexed_bad_request(
+            "error processing data columns".to_string(),
+            failures,
+        ))
+    }
+}
+
+/// Verify, publish and import a list of blob sidecars.
+///
+/// Blobs which are already known are skipped silently. An indexed error is returned for any
+/// blobs which fail verification or import.
+pub async fn publish_blobs<T: BeaconChainTypes>(
+    blobs: Vec<Arc<BlobSidecar<T::EthSpec>>>,
+    chain: Arc<BeaconChain<T>>,
+    network_tx: UnboundedSender<NetworkMessage<T::EthSpec>>,
+    log: Logger,
+) -> Result<(), Rejection> {
+    let mut failures = vec![];
+
+    for (index, blob) in blobs.into_iter().enumerate() {
+        let blob_index = blob.index;
+        let verified = match GossipVerifiedBlob::<T>::new(blob, blob_index, &chain) {
+            Ok(verified) => verified,
+            Err(GossipBlobError::RepeatBlob { slot, index, .. }) => {
+                debug!(
+                    log,
+                    "Ignoring known blob";
+                    "slot" => slot,
+                    "blob_index" => index,
+                );
+                continue;
+            }
+            Err(e) => {
+                failures.push(Failure::new(index, format!("Verification: {e}")));
+                continue;
+            }
+        };
+
+        crate::publish_pubsub_message(
+            &network_tx,
+            PubsubMessage::BlobSidecar(Box::new((verified.index(), verified.clone_blob()))),
+        )?;
+
+        let block_root = verified.block_root();
+        match chain.process_gossip_blob(verified).await {
+            Ok(_) | Err(BlockError::DuplicateFullyImported(_)) => {}
+            Err(e) => {
+                warn!(
+                    log,
+                    "Unable to import blob from API";
+                    "block_root" => ?block_root,
+                    "blob_index" => blob_index,
+                    "error" => ?e,
+                );
+                failures.push(Failure::new(index, format!("Import: {e:?}")));
+            }
+        }
+    }
+
+    if failures.is_empty() {
+        Ok(())
+    } else {
+        Err(warp_utils::reject::indexed_bad_request(
+            "error processing blobs".to_string(),
+            failures,
+        ))
+    }
+}
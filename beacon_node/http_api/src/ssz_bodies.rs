@@ -0,0 +1,62 @@
+//! Decoders for request bodies which may be submitted as SSZ as well as JSON.
+//!
+//! These are used with `warp_utils::json::json_or_ssz` for endpoints which receive large volumes
+//! of requests from validator clients.
+use eth2::types::ValidatorIndexData;
+use ssz::{Decode, DecodeError};
+use types::{
+    Attestation, AttestationBase, AttestationElectra, EthSpec, ForkName, SignedAggregateAndProof,
+    SignedAggregateAndProofBase, SignedAggregateAndProofElectra,
+};
+
+/// Returns `true` if the body should be decoded with the Electra container types.
+///
+/// Requests without a consensus version header (i.e., v1 endpoints) always use the Phase 0 types.
+fn use_electra_types(fork_name: Option<ForkName>) -> bool {
+    fork_name.map_or(false, ForkName::electra_enabled)
+}
+
+pub fn decode_attestations<E: EthSpec>(
+    bytes: &[u8],
+    fork_name: Option<ForkName>,
+) -> Result<Vec<Attestation<E>>, DecodeError> {
+    if use_electra_types(fork_name) {
+        Ok(Vec::<AttestationElectra<E>>::from_ssz_bytes(bytes)?
+            .into_iter()
+            .map(Attestation::Electra)
+            .collect())
+    } else {
+        Ok(Vec::<AttestationBase<E>>::from_ssz_bytes(bytes)?
+            .into_iter()
+            .map(Attestation::Base)
+            .collect())
+    }
+}
+
+pub fn decode_aggregates<E: EthSpec>(
+    bytes: &[u8],
+    fork_name: Option<ForkName>,
+) -> Result<Vec<SignedAggregateAndProof<E>>, DecodeError> {
+    if use_electra_types(fork_name) {
+        Ok(
+            Vec::<SignedAggregateAndProofElectra<E>>::from_ssz_bytes(bytes)?
+                .into_iter()
+                .map(SignedAggregateAndProof::Electra)
+                .collect(),
+        )
+    } else {
+        Ok(
+            Vec::<SignedAggregateAndProofBase<E>>::from_ssz_bytes(bytes)?
+                .into_iter()
+                .map(SignedAggregateAndProof::Base)
+                .collect(),
+        )
+    }
+}
+
+pub fn decode_validator_indices(
+    bytes: &[u8],
+    _fork_name: Option<ForkName>,
+) -> Result<ValidatorIndexData, DecodeError> {
+    Vec::<u64>::from_ssz_bytes(bytes).map(ValidatorIndexData)
+}
@@ -1,3 +1,4 @@
+use crate::archive_query::ArchiveQueryLimiter;
 use crate::sync_committee_rewards::get_state_before_applying_block;
 use crate::BlockId;
 use crate::ExecutionOptimistic;
@@ -10,12 +11,13 @@ use warp_utils::reject::beacon_chain_error;
 pub fn compute_beacon_block_rewards<T: BeaconChainTypes>(
     chain: Arc<BeaconChain<T>>,
     block_id: BlockId,
+    archive_query: &ArchiveQueryLimiter,
 ) -> Result<(StandardBlockReward, ExecutionOptimistic, bool), warp::Rejection> {
     let (block, execution_optimistic, finalized) = block_id.blinded_block(&chain)?;
 
     let block_ref = block.message();
 
-    let mut state = get_state_before_applying_block(chain.clone(), &block)?;
+    let mut state = get_state_before_applying_block(chain.clone(), &block, archive_query)?;
 
     let rewards = chain
         .compute_beacon_block_reward(block_ref, &mut state)
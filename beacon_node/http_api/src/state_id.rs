@@ -189,8 +189,10 @@ impl StateId {
             _ => (self.root(chain)?, None),
         };
 
+        // Concurrent requests for the same state share a single load, as loading a historical
+        // state may require replaying many blocks.
         let state = chain
-            .get_state(&state_root, slot_opt)
+            .get_state_coalesced(&state_root, slot_opt)
             .map_err(warp_utils::reject::beacon_chain_error)
             .and_then(|opt| {
                 opt.ok_or_else(|| {
@@ -204,6 +206,35 @@ impl StateId {
         Ok((state, execution_optimistic, finalized))
     }
 
+    /// Return the SSZ bytes and slot of the finalized state identified by `self`, if it can be
+    /// loaded directly from the freezer database's diff hierarchy.
+    ///
+    /// Return `None` if the state must be loaded via `Self::state` instead.
+    pub fn cold_state_ssz<T: BeaconChainTypes>(
+        &self,
+        chain: &BeaconChain<T>,
+    ) -> Result<Option<(Vec<u8>, Slot)>, warp::Rejection> {
+        if let CoreStateId::Head = self.0 {
+            return Ok(None);
+        }
+        let (state_root, _, _) = self.root(chain)?;
+        let Some(slot) = chain
+            .store
+            .load_cold_state_slot(&state_root)
+            .map_err(BeaconChainError::DBError)
+            .map_err(warp_utils::reject::beacon_chain_error)?
+        else {
+            return Ok(None);
+        };
+
+        let bytes = chain
+            .store
+            .load_cold_state_ssz_by_slot(slot)
+            .map_err(BeaconChainError::DBError)
+            .map_err(warp_utils::reject::beacon_chain_error)?;
+        Ok(bytes.map(|bytes| (bytes, slot)))
+    }
+
     /// Map a function across the `BeaconState` identified by `self`.
     ///
     /// The optimistic and finalization status of the requested state is also provided to the `func`
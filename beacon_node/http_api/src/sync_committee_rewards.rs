@@ -1,3 +1,4 @@
+use crate::archive_query::ArchiveQueryLimiter;
 use crate::{BlockId, ExecutionOptimistic};
 use beacon_chain::{BeaconChain, BeaconChainError, BeaconChainTypes};
 use eth2::lighthouse::SyncCommitteeReward;
@@ -13,10 +14,11 @@ pub fn compute_sync_committee_rewards<T: BeaconChainTypes>(
     block_id: BlockId,
     validators: Vec<ValidatorId>,
     log: Logger,
+    archive_query: &ArchiveQueryLimiter,
 ) -> Result<(Option<Vec<SyncCommitteeReward>>, ExecutionOptimistic, bool), warp::Rejection> {
     let (block, execution_optimistic, finalized) = block_id.blinded_block(&chain)?;
 
-    let mut state = get_state_before_applying_block(chain.clone(), &block)?;
+    let mut state = get_state_before_applying_block(chain.clone(), &block, archive_query)?;
 
     let reward_payload = chain
         .compute_sync_committee_rewards(block.message(), &mut state)
@@ -50,6 +52,7 @@ pub fn compute_sync_committee_rewards<T: BeaconChainTypes>(
 pub fn get_state_before_applying_block<T: BeaconChainTypes>(
     chain: Arc<BeaconChain<T>>,
     block: &SignedBlindedBeaconBlock<T::EthSpec>,
+    archive_query: &ArchiveQueryLimiter,
 ) -> Result<BeaconState<T::EthSpec>, warp::reject::Rejection> {
     let parent_block: SignedBlindedBeaconBlock<T::EthSpec> = chain
         .get_blinded_block(&block.parent_root())
@@ -58,6 +61,7 @@ pub fn get_state_before_applying_block<T: BeaconChainTypes>(
         })
         .map_err(|e| custom_not_found(format!("Parent block is not available! {:?}", e)))?;
 
+    let _permit = archive_query.admit(&chain, parent_block.slot(), 0)?;
     let parent_state = chain
         .get_state(&parent_block.state_root(), Some(parent_block.slot()))
         .and_then(|maybe_state| {
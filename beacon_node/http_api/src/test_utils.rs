@@ -5,6 +5,7 @@ use beacon_chain::{
 };
 use beacon_processor::{
     BeaconProcessor, BeaconProcessorChannels, BeaconProcessorConfig, BeaconProcessorQueueLengths,
+    BeaconProcessorQueueStats,
 };
 use directory::DEFAULT_ROOT_DIR;
 use eth2::{BeaconNodeHttpClient, Timeouts};
@@ -209,11 +210,13 @@ pub async fn create_api_server_with_config<T: BeaconChainTypes>(
 
     let beacon_processor_send = beacon_processor_tx;
     let reprocess_send = work_reprocessing_tx.clone();
+    let queue_stats = BeaconProcessorQueueStats::default();
     BeaconProcessor {
         network_globals: network_globals.clone(),
         executor: test_runtime.task_executor.clone(),
         current_workers: 0,
         config: beacon_processor_config,
+        queue_stats: queue_stats.clone(),
         log: log.clone(),
     }
     .spawn_manager(
@@ -239,6 +242,7 @@ pub async fn create_api_server_with_config<T: BeaconChainTypes>(
             listen_port: port,
             data_dir: std::path::PathBuf::from(DEFAULT_ROOT_DIR),
             enable_light_client_server: true,
+            enable_unsafe_block_production: true,
             ..http_config
         },
         chain: Some(chain),
@@ -246,6 +250,7 @@ pub async fn create_api_server_with_config<T: BeaconChainTypes>(
         network_globals: Some(network_globals),
         beacon_processor_send: Some(beacon_processor_send),
         beacon_processor_reprocess_send: Some(reprocess_send),
+        beacon_processor_queue_stats: Some(queue_stats),
         eth1_service: Some(eth1_service),
         sse_logging_components: None,
         log,
@@ -1,25 +1,15 @@
 use beacon_chain::{
     validator_monitor::HISTORIC_EPOCHS, BeaconChain, BeaconChainError, BeaconChainTypes,
 };
+use eth2::lighthouse::{
+    ValidatorCountResponse, ValidatorInfo, ValidatorInfoRequestData, ValidatorInfoResponse,
+    ValidatorInfoValues, ValidatorMetrics, ValidatorMetricsRequestData, ValidatorMetricsResponse,
+};
 use eth2::types::{Epoch, ValidatorStatus};
-use serde::{Deserialize, Serialize};
 use std::collections::{HashMap, HashSet};
 use std::sync::Arc;
 use warp_utils::reject::beacon_chain_error;
 
-#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
-pub struct ValidatorCountResponse {
-    pub active_ongoing: u64,
-    pub active_exiting: u64,
-    pub active_slashed: u64,
-    pub pending_initialized: u64,
-    pub pending_queued: u64,
-    pub withdrawal_possible: u64,
-    pub withdrawal_done: u64,
-    pub exited_unslashed: u64,
-    pub exited_slashed: u64,
-}
-
 pub fn get_validator_count<T: BeaconChainTypes>(
     chain: Arc<BeaconChain<T>>,
 ) -> Result<ValidatorCountResponse, warp::Rejection> {
@@ -73,30 +63,6 @@ pub fn get_validator_count<T: BeaconChainTypes>(
     })
 }
 
-#[derive(PartialEq, Serialize, Deserialize)]
-pub struct ValidatorInfoRequestData {
-    #[serde(with = "serde_utils::quoted_u64_vec")]
-    indices: Vec<u64>,
-}
-
-#[derive(PartialEq, Serialize, Deserialize)]
-pub struct ValidatorInfoValues {
-    #[serde(with = "serde_utils::quoted_u64")]
-    epoch: u64,
-    #[serde(with = "serde_utils::quoted_u64")]
-    total_balance: u64,
-}
-
-#[derive(PartialEq, Serialize, Deserialize)]
-pub struct ValidatorInfo {
-    info: Vec<ValidatorInfoValues>,
-}
-
-#[derive(PartialEq, Serialize, Deserialize)]
-pub struct ValidatorInfoResponse {
-    validators: HashMap<String, ValidatorInfo>,
-}
-
 pub fn get_validator_info<T: BeaconChainTypes>(
     request_data: ValidatorInfoRequestData,
     chain: Arc<BeaconChain<T>>,
@@ -149,30 +115,6 @@ pub fn get_validator_info<T: BeaconChainTypes>(
     Ok(ValidatorInfoResponse { validators })
 }
 
-#[derive(PartialEq, Serialize, Deserialize)]
-pub struct ValidatorMetricsRequestData {
-    indices: Vec<u64>,
-}
-
-#[derive(PartialEq, Serialize, Deserialize)]
-pub struct ValidatorMetrics {
-    attestation_hits: u64,
-    attestation_misses: u64,
-    attestation_hit_percentage: f64,
-    attestation_head_hits: u64,
-    attestation_head_misses: u64,
-    attestation_head_hit_percentage: f64,
-    attestation_target_hits: u64,
-    attestation_target_misses: u64,
-    attestation_target_hit_percentage: f64,
-    latest_attestation_inclusion_distance: u64,
-}
-
-#[derive(PartialEq, Serialize, Deserialize)]
-pub struct ValidatorMetricsResponse {
-    validators: HashMap<String, ValidatorMetrics>,
-}
-
 pub fn post_validator_monitor_metrics<T: BeaconChainTypes>(
     request_data: ValidatorMetricsRequestData,
     chain: Arc<BeaconChain<T>>,
@@ -0,0 +1,123 @@
+use beacon_chain::{BeaconChain, BeaconChainTypes};
+use eth2::lighthouse::WithdrawalProjection;
+use slot_clock::SlotClock;
+use types::{EthSpec, PublicKeyBytes};
+use warp_utils::reject::{
+    beacon_chain_error, beacon_state_error, custom_bad_request, custom_not_found,
+};
+
+/// Project the next withdrawal for the validator with `pubkey` from the head state.
+///
+/// The projection replays the withdrawal sweep forwards from the head, assuming that a block is
+/// produced at every slot and that no balances change in the meantime. Pending partial
+/// withdrawals are not accounted for, so the estimate may be early for post-Electra states
+/// which have a backlog of them.
+pub fn withdrawal_projection<T: BeaconChainTypes>(
+    pubkey: &PublicKeyBytes,
+    chain: &BeaconChain<T>,
+) -> Result<WithdrawalProjection, warp::Rejection> {
+    let head = chain.head_snapshot();
+    let state = &head.beacon_state;
+    let spec = &chain.spec;
+
+    let next_validator_index = state.next_withdrawal_validator_index().map_err(|_| {
+        custom_bad_request("withdrawals are not enabled at the head state".to_string())
+    })?;
+    // The chain's pubkey cache may contain validators which are not yet in the head state.
+    let (validator_index, validator) = chain
+        .validator_index(pubkey)
+        .map_err(beacon_chain_error)?
+        .and_then(|index| Some((index, state.validators().get(index)?)))
+        .ok_or_else(|| custom_not_found(format!("unknown validator: {}", pubkey)))?;
+    let balance = state
+        .get_balance(validator_index)
+        .map_err(beacon_state_error)?;
+
+    let epoch = state.current_epoch();
+    let fork_name = state.fork_name_unchecked();
+    let is_withdrawable =
+        |index: usize| match (state.validators().get(index), state.balances().get(index)) {
+            (Some(validator), Some(&balance)) => {
+                validator.is_fully_withdrawable_at(balance, epoch, spec, fork_name)
+                    || validator.is_partially_withdrawable_validator(balance, spec, fork_name)
+            }
+            _ => false,
+        };
+
+    let num_validators = state.validators().len() as u64;
+    let validator_index = validator_index as u64;
+    let sweep_position =
+        (validator_index + num_validators - next_validator_index % num_validators) % num_validators;
+
+    let is_fully_withdrawable = validator.is_fully_withdrawable_at(balance, epoch, spec, fork_name);
+    let is_partially_withdrawable = !is_fully_withdrawable
+        && validator.is_partially_withdrawable_validator(balance, spec, fork_name);
+    let expected_amount_gwei = if is_fully_withdrawable {
+        balance
+    } else if is_partially_withdrawable {
+        balance.saturating_sub(validator.get_max_effective_balance(spec, fork_name))
+    } else {
+        0
+    };
+
+    let estimated_slot = (is_fully_withdrawable || is_partially_withdrawable).then(|| {
+        let payloads = payloads_until_withdrawal(
+            next_validator_index,
+            num_validators,
+            validator_index,
+            is_withdrawable,
+            T::EthSpec::max_withdrawals_per_payload() as u64,
+            spec.max_validators_per_withdrawals_sweep,
+        );
+        state.slot() + 1 + payloads
+    });
+    let estimated_timestamp = estimated_slot
+        .and_then(|slot| chain.slot_clock.start_of(slot))
+        .map(|time| time.as_secs());
+
+    Ok(WithdrawalProjection {
+        validator_index,
+        head_slot: state.slot(),
+        sweep_position,
+        is_fully_withdrawable,
+        is_partially_withdrawable,
+        expected_amount_gwei,
+        estimated_slot,
+        estimated_timestamp,
+    })
+}
+
+/// Return the number of execution payloads which will be processed before the payload which
+/// withdraws from `target_index`, following the sweep rules of `process_withdrawals`.
+///
+/// The validator at `target_index` must be withdrawable, otherwise the sweep never stops for it.
+fn payloads_until_withdrawal(
+    mut next_validator_index: u64,
+    num_validators: u64,
+    target_index: u64,
+    is_withdrawable: impl Fn(usize) -> bool,
+    max_withdrawals_per_payload: u64,
+    max_validators_per_sweep: u64,
+) -> u64 {
+    let bound = std::cmp::min(num_validators, max_validators_per_sweep);
+    let mut payloads = 0;
+    loop {
+        let mut num_withdrawals = 0;
+        let mut following_index = next_validator_index.saturating_add(max_validators_per_sweep);
+        for offset in 0..bound {
+            let index = (next_validator_index + offset) % num_validators;
+            if index == target_index {
+                return payloads;
+            }
+            if is_withdrawable(index as usize) {
+                num_withdrawals += 1;
+                if num_withdrawals == max_withdrawals_per_payload {
+                    following_index = index + 1;
+                    break;
+                }
+            }
+        }
+        next_validator_index = following_index % num_validators;
+        payloads += 1;
+    }
+}
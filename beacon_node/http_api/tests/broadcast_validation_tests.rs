@@ -1799,6 +1799,72 @@ pub async fn duplicate_block_status_code() {
     assert_eq!(err.status().unwrap(), duplicate_block_status_code);
 }
 
+/// This test checks that the blob inclusion proofs of a locally produced block are computed during
+/// production, and are used to build the blob sidecars when the block is published.
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+pub async fn blob_inclusion_proofs_precomputed_on_production() {
+    let validation_level: Option<BroadcastValidation> = Some(BroadcastValidation::Gossip);
+
+    let validator_count = 64;
+    let num_initial: u64 = 31;
+    let spec = ForkName::latest().make_genesis_spec(E::default_spec());
+    let tester = InteractiveTester::<E>::new(Some(spec), validator_count).await;
+
+    // Create some chain depth.
+    tester.harness.advance_slot();
+    tester
+        .harness
+        .extend_chain(
+            num_initial as usize,
+            BlockStrategy::OnCanonicalHead,
+            AttestationStrategy::AllValidators,
+        )
+        .await;
+    tester.harness.advance_slot();
+
+    let slot_b = Slot::new(num_initial) + 1;
+    let state_a = tester.harness.get_current_state();
+    let ((block, blobs), _) = tester.harness.make_block(state_a, slot_b).await;
+    let blobs = blobs.expect("should have some blobs");
+    assert_ne!(blobs.0.len(), 0);
+
+    // The proofs are cached against the root of the unsigned block, which is the same as the
+    // root of the signed block.
+    let block_root = block.canonical_root();
+    let inclusion_proofs = tester
+        .harness
+        .chain
+        .blob_inclusion_proof_cache
+        .get(&block_root)
+        .expect("inclusion proofs should be cached");
+    assert_eq!(inclusion_proofs.len(), blobs.0.len());
+    for (index, inclusion_proof) in inclusion_proofs.iter().enumerate() {
+        assert_eq!(
+            *inclusion_proof,
+            block
+                .message()
+                .body()
+                .kzg_commitment_merkle_proof(index)
+                .unwrap()
+        );
+    }
+
+    let response: Result<(), eth2::Error> = tester
+        .client
+        .post_beacon_blocks_v2(
+            &PublishBlockRequest::new(block.clone(), Some(blobs)),
+            validation_level,
+        )
+        .await;
+
+    // The sidecars built from the cached proofs should be valid, so the block is imported.
+    response.unwrap();
+    assert!(tester
+        .harness
+        .chain
+        .block_is_known_to_fork_choice(&block_root));
+}
+
 fn assert_server_message_error(error_response: eth2::Error, expected_message: String) {
     let eth2::Error::ServerMessage(err) = error_response else {
         panic!("Not a eth2::Error::ServerMessage");
@@ -5,11 +5,22 @@ use beacon_chain::{
     ChainConfig,
 };
 use beacon_processor::work_reprocessing_queue::ReprocessQueueMessage;
+use eth2::reqwest::{
+    self,
+    header::{HeaderMap, HeaderValue, AUTHORIZATION},
+    StatusCode,
+};
 use eth2::types::ProduceBlockV3Response;
-use eth2::types::{DepositContractData, StateId};
+use eth2::types::{
+    DepositContractData, EventKind, FeeRecipientSource, IndexedErrorMessage, ParentBlockHashSource,
+    StateId,
+};
+use eth2::{BeaconNodeHttpClient, Timeouts};
 use execution_layer::{ForkchoiceState, PayloadAttributes};
 use http_api::test_utils::InteractiveTester;
+use http_api::{AdminConfig, Config};
 use parking_lot::Mutex;
+use sensitive_url::SensitiveUrl;
 use slot_clock::SlotClock;
 use state_processing::{
     per_block_processing::get_expected_withdrawals, state_advance::complete_state_advance,
@@ -18,8 +29,10 @@ use std::collections::HashMap;
 use std::sync::Arc;
 use std::time::Duration;
 use types::{
-    Address, Epoch, EthSpec, ExecPayload, ExecutionBlockHash, FixedBytesExtended, ForkName,
-    Hash256, MainnetEthSpec, MinimalEthSpec, ProposerPreparationData, Slot, Uint256,
+    Address, AggregateSignature, Attestation, AttestationDuty, BlockImportSource, Domain, Epoch,
+    EthSpec, ExecPayload, ExecutionBlockHash, FixedBytesExtended, ForkName, Hash256,
+    MainnetEthSpec, MinimalEthSpec, ProposerPreparationData, RelativeEpoch, SignedRoot, Slot,
+    Uint256,
 };
 
 type E = MainnetEthSpec;
@@ -566,6 +579,7 @@ pub async fn proposer_boost_re_org_test(
         block_b_root,
         slot_b,
         obs_time,
+        BlockImportSource::Gossip,
         None,
         None,
     );
@@ -582,6 +596,13 @@ pub async fn proposer_boost_re_org_test(
     );
     harness.process_attestations(block_b_head_votes);
 
+    let mut payload_attributes_events = harness
+        .chain
+        .event_handler
+        .as_ref()
+        .unwrap()
+        .subscribe_payload_attributes();
+
     let payload_lookahead = harness.chain.config.prepare_payload_lookahead;
     let fork_choice_lookahead = Duration::from_millis(500);
     while harness.get_current_slot() != slot_c {
@@ -659,6 +680,19 @@ pub async fn proposer_boost_re_org_test(
 
     assert_eq!(harness.head_block_root(), block_root_c);
 
+    // A re-org should be recorded in the head history.
+    let reorgs = harness.chain.reorg_summary(1).unwrap().reorgs;
+    if should_re_org {
+        let reorg = reorgs.last().expect("re-org should be recorded");
+        assert_eq!(reorg.old_head_block, block_b_root);
+        assert_eq!(reorg.new_head_block, block_root_c);
+        assert_eq!(reorg.new_head_parent, Hash256::from(block_a_root));
+    } else {
+        assert!(reorgs
+            .iter()
+            .all(|reorg| reorg.new_head_block != block_root_c));
+    }
+
     // Check the fork choice updates that were sent.
     let forkchoice_updates = forkchoice_updates.lock();
     let block_a_exec_hash = block_a
@@ -722,6 +756,40 @@ pub async fn proposer_boost_re_org_test(
         assert_eq!(parent_beacon_block_root, block_c.parent_root());
     }
 
+    // Check the sources reported in the `payload_attributes` events for block C.
+    let mut re_org_events = 0;
+    while let Ok(event) = payload_attributes_events.try_recv() {
+        let EventKind::PayloadAttributes(event) = event else {
+            panic!("unexpected event: {event:?}");
+        };
+        let event = event.data;
+        if event.proposal_slot != slot_c {
+            continue;
+        }
+        assert_eq!(
+            event.fee_recipient_source,
+            Some(FeeRecipientSource::ProposerPreparation)
+        );
+        if event.re_org_active == Some(true) {
+            re_org_events += 1;
+            assert_eq!(
+                event.parent_block_hash_source,
+                Some(ParentBlockHashSource::ReOrgParent)
+            );
+            assert_eq!(event.parent_block_root, Hash256::from(block_a_root));
+            assert_eq!(event.parent_block_hash, block_a_exec_hash);
+        } else {
+            assert_eq!(event.re_org_active, Some(false));
+            assert_eq!(
+                event.parent_block_hash_source,
+                Some(ParentBlockHashSource::Head)
+            );
+        }
+    }
+    if should_re_org {
+        assert!(re_org_events > 0);
+    }
+
     let lookahead = slot_clock
         .start_of(slot_c)
         .unwrap()
@@ -928,3 +996,212 @@ async fn queue_attestations_from_http() {
 
     attestation_future.await.unwrap();
 }
+
+// Test that an attestation submitted via the API which surrounds one previously submitted for the
+// same validator is refused when publish safety checks are enabled.
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn publish_safety_refuses_surround_vote() {
+    type E = MinimalEthSpec;
+
+    let validator_count = 64;
+    let spec = ForkName::latest().make_genesis_spec(E::default_spec());
+
+    let tester = InteractiveTester::<E>::new_with_initializer_and_mutator(
+        Some(spec.clone()),
+        validator_count,
+        Some(Box::new(move |builder| {
+            builder
+                .deterministic_keypairs(validator_count)
+                .fresh_ephemeral_store()
+                .chain_config(ChainConfig {
+                    publish_safety_checks: true,
+                    ..ChainConfig::default()
+                })
+        })),
+        None,
+        Default::default(),
+    )
+    .await;
+    let harness = &tester.harness;
+    let client = &tester.client;
+
+    // The harness attests with every validator except the one under test, whose attestations
+    // would otherwise already be known when submitted via the API.
+    let validator_index = 0;
+    let other_validators = AttestationStrategy::SomeValidators((1..validator_count).collect());
+
+    // Justify some epochs, so that there is room to surround an attestation.
+    harness.advance_slot();
+    harness
+        .extend_chain(
+            4 * E::slots_per_epoch() as usize,
+            BlockStrategy::OnCanonicalHead,
+            other_validators.clone(),
+        )
+        .await;
+
+    // Make a signed attestation at the head from the validator at `duty`, optionally with a
+    // modified source epoch.
+    let make_attestation = |duty: AttestationDuty,
+                            validator_index: usize,
+                            source_epoch: Option<Epoch>| {
+        let (state, state_root) = harness.get_current_state_and_root();
+        let mut attestation = harness
+            .produce_unaggregated_attestation_for_block(
+                duty.slot,
+                duty.index,
+                harness.head_block_root(),
+                std::borrow::Cow::Borrowed(&state),
+                state_root,
+            )
+            .unwrap();
+        match &mut attestation {
+            Attestation::Base(att) => att.aggregation_bits.set(duty.committee_position, true),
+            Attestation::Electra(att) => att.aggregation_bits.set(duty.committee_position, true),
+        }
+        .unwrap();
+        if let Some(source_epoch) = source_epoch {
+            attestation.data_mut().source.epoch = source_epoch;
+        }
+
+        let target_epoch = attestation.data().target.epoch;
+        let domain = spec.get_domain(
+            target_epoch,
+            Domain::BeaconAttester,
+            &spec.fork_at_epoch(target_epoch),
+            state.genesis_validators_root(),
+        );
+        let mut signature = AggregateSignature::infinity();
+        signature.add_assign(
+            &harness.validator_keypairs[validator_index]
+                .sk
+                .sign(attestation.data().signing_root(domain)),
+        );
+        *attestation.signature_mut() = signature;
+        attestation
+    };
+
+    // Attest with the first validator in the first committee of the current slot.
+    let mut state = harness.get_current_state();
+    state
+        .build_committee_cache(RelativeEpoch::Current, &spec)
+        .unwrap();
+    state
+        .build_committee_cache(RelativeEpoch::Next, &spec)
+        .unwrap();
+    let validator_index = state
+        .get_beacon_committee(harness.get_current_slot(), 0)
+        .unwrap()
+        .committee[0];
+    let first_duty = state
+        .get_attestation_duties(validator_index, RelativeEpoch::Current)
+        .unwrap()
+        .unwrap();
+    assert_eq!(first_duty.slot, harness.get_current_slot());
+    let first = make_attestation(first_duty, validator_index, None);
+    let first_source_epoch = first.data().source.epoch;
+    assert!(first_source_epoch > 0);
+
+    let fork_name = spec.fork_name_at_slot::<E>(first_duty.slot);
+    client
+        .post_beacon_pool_attestations_v2(&[first], fork_name)
+        .await
+        .unwrap();
+
+    // Advance to the validator's slot in the next epoch and attest with an earlier source, which
+    // surrounds the first attestation.
+    harness
+        .extend_chain(
+            (second_duty.slot - first_duty.slot).as_usize(),
+            BlockStrategy::OnCanonicalHead,
+            other_validators,
+        )
+        .await;
+    assert_eq!(harness.get_current_slot(), second_duty.slot);
+    let surrounding = make_attestation(second_duty, validator_index, Some(first_source_epoch - 1));
+
+    let fork_name = spec.fork_name_at_slot::<E>(second_duty.slot);
+    let error = client
+        .post_beacon_pool_attestations_v2(&[surrounding], fork_name)
+        .await
+        .unwrap_err();
+    let eth2::Error::ServerIndexedMessage(IndexedErrorMessage { code, failures, .. }) = error
+    else {
+        panic!("wrong error, expected ServerIndexedMessage, got: {error:?}")
+    };
+    assert_eq!(code, 400);
+    assert_eq!(failures.len(), 1);
+    assert!(
+        failures[0].message.contains("SurroundVote"),
+        "unexpected failure: {}",
+        failures[0].message
+    );
+}
+
+// Test that the `/lighthouse` routes are only served by the admin listener when it is enabled,
+// and that the admin listener requires its token.
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn admin_listener() {
+    let token_dir = tempfile::tempdir().unwrap();
+    let token_path = token_dir.path().join("api-token.txt");
+    std::fs::write(&token_path, "secret\n").unwrap();
+
+    let admin_port = unused_port::unused_tcp4_port().unwrap();
+    let tester = InteractiveTester::<E>::new_with_initializer_and_mutator(
+        None,
+        32,
+        None,
+        None,
+        Config {
+            admin: Some(AdminConfig {
+                listen_port: admin_port,
+                api_token_path: Some(token_path),
+                ..AdminConfig::default()
+            }),
+            ..Config::default()
+        },
+    )
+    .await;
+
+    // The public listener serves the standard routes but not the `/lighthouse` routes.
+    tester.client.get_node_version().await.unwrap();
+    let err = tester.client.get_lighthouse_syncing().await.unwrap_err();
+    assert_eq!(err.status(), Some(StatusCode::NOT_FOUND));
+
+    let admin_url = SensitiveUrl::parse(&format!("http://127.0.0.1:{admin_port}")).unwrap();
+    let admin_client = |token: Option<&str>| {
+        let mut headers = HeaderMap::new();
+        if let Some(token) = token {
+            headers.insert(
+                AUTHORIZATION,
+                HeaderValue::from_str(&format!("Bearer {token}")).unwrap(),
+            );
+        }
+        let client = reqwest::Client::builder()
+            .default_headers(headers)
+            .build()
+            .unwrap();
+        BeaconNodeHttpClient::from_components(
+            admin_url.clone(),
+            client,
+            Timeouts::set_all(Duration::from_secs(1)),
+        )
+    };
+
+    let err = admin_client(None)
+        .get_lighthouse_syncing()
+        .await
+        .unwrap_err();
+    assert_eq!(err.status(), Some(StatusCode::UNAUTHORIZED));
+    let err = admin_client(Some("wrong"))
+        .get_lighthouse_syncing()
+        .await
+        .unwrap_err();
+    assert_eq!(err.status(), Some(StatusCode::FORBIDDEN));
+
+    // The admin listener serves the `/lighthouse` routes but not the standard routes.
+    let admin = admin_client(Some("secret"));
+    admin.get_lighthouse_syncing().await.unwrap();
+    let err = admin.get_node_version().await.unwrap_err();
+    assert_eq!(err.status(), Some(StatusCode::NOT_FOUND));
+}
@@ -3,5 +3,7 @@
 pub mod broadcast_validation_tests;
 pub mod fork_tests;
 pub mod interactive_tests;
+#[cfg(feature = "schema-snapshots")]
+pub mod schema_snapshot_tests;
 pub mod status_tests;
 pub mod tests;
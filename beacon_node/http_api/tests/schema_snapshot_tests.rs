@@ -0,0 +1,321 @@
+//! Snapshot tests for the JSON schemas of beacon API responses.
+//!
+//! Each endpoint is queried against a fixed test chain and the *shape* of its JSON response (field
+//! names and value types, ignoring the values themselves) is compared against a snapshot stored in
+//! `tests/schema_snapshots`. This catches accidental field renames that would break downstream
+//! consumers of the API.
+//!
+//! These tests are not run by default. Run them with:
+//!
+//! ```bash
+//! cargo test --release -p http_api --features schema-snapshots schema_snapshots
+//! ```
+//!
+//! Snapshots are recorded from real responses. A missing snapshot is a failure, so that new
+//! endpoints can't silently go untested. After adding an endpoint or intentionally changing a
+//! response, (re-)record all snapshots and commit the result:
+//!
+//! ```bash
+//! UPDATE_SCHEMA_SNAPSHOTS=1 cargo test --release -p http_api --features schema-snapshots schema_snapshots
+//! ```
+//!
+//! The test chain is deterministic, so responses are compared strictly: objects must have the same
+//! fields, `null` only matches `null` and every element of an array must match the shape of an
+//! element in the snapshot. Two exceptions may only appear in a snapshot: an empty array matches
+//! any array, and the string `"any"` (which is never recorded, but may be written by hand) matches
+//! any value.
+//!
+//! Every beacon API endpoint served by Lighthouse is snapshotted, except for:
+//!
+//! - `POST` endpoints which submit objects to the node (blocks, blinded blocks, pool operations,
+//!   aggregates, contributions, subscriptions, proposer preparations and validator registrations),
+//!   since they have no response body.
+//! - `GET eth/v1/events`, which is an event stream rather than a JSON response.
+//! - `GET eth/v2/debug/beacon/states/{state_id}`, since its data is the `BeaconState`, whose JSON
+//!   encoding is pinned by the consensus spec tests.
+//! - `GET eth/v{2,3}/validator/blocks/{slot}` and `GET eth/v1/validator/blinded_blocks/{slot}`,
+//!   since their data is a `BeaconBlock`, which is covered by `eth/v2/beacon/blocks/{block_id}`
+//!   and `eth/v1/beacon/blinded_blocks/{block_id}`.
+//! - `GET eth/v{1,2}/validator/aggregate_attestation`, since its data is an `Attestation`, which is
+//!   covered by `eth/v2/beacon/pool/attestations`.
+//! - `GET eth/v1/validator/sync_committee_contribution`, since it requires a contribution for the
+//!   exact head block to be pooled, which the harness does not guarantee at the time of the query.
+//! - `GET eth/v1/node/peers/{peer_id}`, since the test node has no peers.
+//! - `GET eth/v1/beacon/deposit_snapshot`, since the test node has no eth1 service.
+//! - `GET eth/v1/beacon/light_client/*`, since the light client server is disabled.
+//!
+//! Endpoints served at several versions with the same response are snapshotted at the latest
+//! version only.
+use beacon_chain::test_utils::{
+    AttestationStrategy, BlockStrategy, LightClientStrategy, SyncCommitteeStrategy,
+};
+use eth2::reqwest::Client;
+use http_api::test_utils::InteractiveTester;
+use serde_json::{json, Value};
+use std::fs;
+use std::path::PathBuf;
+use types::{Epoch, EthSpec, ForkName, MinimalEthSpec};
+
+type E = MinimalEthSpec;
+
+const VALIDATOR_COUNT: usize = 32;
+const NUM_EPOCHS: u64 = 3;
+
+/// Set this environment variable to overwrite existing snapshots.
+const UPDATE_ENV_VAR: &str = "UPDATE_SCHEMA_SNAPSHOTS";
+
+struct Endpoint {
+    /// Path relative to the server root, including any query string.
+    path: String,
+    /// JSON body to `POST`, or `None` for a `GET` request.
+    body: Option<Value>,
+}
+
+impl Endpoint {
+    fn get(path: impl Into<String>) -> Self {
+        Self {
+            path: path.into(),
+            body: None,
+        }
+    }
+
+    fn post(path: impl Into<String>, body: Value) -> Self {
+        Self {
+            path: path.into(),
+            body: Some(body),
+        }
+    }
+
+    /// The file name of the snapshot for this endpoint, e.g. `get_eth_v1_beacon_genesis.json`.
+    fn snapshot_file_name(&self) -> String {
+        let method = if self.body.is_some() { "post" } else { "get" };
+        let path = self
+            .path
+            .chars()
+            .map(|c| if c.is_ascii_alphanumeric() { c } else { '_' })
+            .collect::<String>();
+        format!("{method}_{path}.json")
+    }
+}
+
+/// The endpoints to snapshot. Values which depend on the chain (e.g. epochs) are derived from the
+/// fixed test chain, so they are stable between runs.
+fn endpoints(epoch: Epoch) -> Vec<Endpoint> {
+    let slot = epoch.start_slot(E::slots_per_epoch());
+    let indices = json!(["0", "1"]);
+    let ids = json!({ "ids": indices.clone() });
+
+    vec![
+        // Beacon.
+        Endpoint::get("eth/v1/beacon/genesis"),
+        Endpoint::get("eth/v1/beacon/states/head/root"),
+        Endpoint::get("eth/v1/beacon/states/head/fork"),
+        Endpoint::get("eth/v1/beacon/states/head/finality_checkpoints"),
+        Endpoint::get("eth/v1/beacon/states/head/validators?id=0,1"),
+        Endpoint::post("eth/v1/beacon/states/head/validators", ids),
+        Endpoint::get("eth/v1/beacon/states/head/validators/0"),
+        Endpoint::get("eth/v1/beacon/states/head/validator_balances?id=0,1"),
+        Endpoint::post(
+            "eth/v1/beacon/states/head/validator_balances",
+            indices.clone(),
+        ),
+        Endpoint::get("eth/v1/beacon/states/head/committees"),
+        Endpoint::get("eth/v1/beacon/states/head/sync_committees"),
+        Endpoint::get("eth/v1/beacon/states/head/randao"),
+        Endpoint::get("eth/v1/beacon/headers"),
+        Endpoint::get("eth/v1/beacon/headers/head"),
+        Endpoint::get("eth/v2/beacon/blocks/head"),
+        Endpoint::get("eth/v1/beacon/blocks/head/root"),
+        Endpoint::get("eth/v2/beacon/blocks/head/attestations"),
+        Endpoint::get("eth/v1/beacon/blinded_blocks/head"),
+        Endpoint::get("eth/v1/beacon/blob_sidecars/head"),
+        Endpoint::get("eth/v2/beacon/pool/attestations"),
+        Endpoint::get("eth/v2/beacon/pool/attester_slashings"),
+        Endpoint::get("eth/v1/beacon/pool/proposer_slashings"),
+        Endpoint::get("eth/v1/beacon/pool/voluntary_exits"),
+        Endpoint::get("eth/v1/beacon/pool/bls_to_execution_changes"),
+        Endpoint::get("eth/v1/beacon/rewards/blocks/head"),
+        Endpoint::post("eth/v1/beacon/rewards/sync_committee/head", json!([])),
+        Endpoint::post(
+            format!("eth/v1/beacon/rewards/attestations/{}", epoch - 2),
+            json!([]),
+        ),
+        // Builder.
+        Endpoint::get("eth/v1/builder/states/head/expected_withdrawals"),
+        // Config.
+        Endpoint::get("eth/v1/config/spec"),
+        Endpoint::get("eth/v1/config/fork_schedule"),
+        Endpoint::get("eth/v1/config/deposit_contract"),
+        // Node.
+        Endpoint::get("eth/v1/node/identity"),
+        Endpoint::get("eth/v1/node/peers"),
+        Endpoint::get("eth/v1/node/peer_count"),
+        Endpoint::get("eth/v1/node/version"),
+        Endpoint::get("eth/v1/node/syncing"),
+        Endpoint::get("eth/v1/node/health"),
+        // Debug.
+        Endpoint::get("eth/v2/debug/beacon/heads"),
+        Endpoint::get("eth/v1/debug/fork_choice"),
+        // Validator.
+        Endpoint::get(format!("eth/v1/validator/duties/proposer/{epoch}")),
+        Endpoint::post(
+            format!("eth/v1/validator/duties/attester/{epoch}"),
+            indices.clone(),
+        ),
+        Endpoint::post(
+            format!("eth/v1/validator/duties/sync/{epoch}"),
+            indices.clone(),
+        ),
+        Endpoint::get(format!(
+            "eth/v1/validator/attestation_data?slot={slot}&committee_index=0"
+        )),
+        Endpoint::post(format!("eth/v1/validator/liveness/{epoch}"), indices),
+    ]
+}
+
+/// A schema which matches any value, for use in hand-edited snapshots.
+const WILDCARD: &str = "any";
+
+/// Reduce a JSON value to its schema: objects retain their keys, arrays retain the distinct
+/// schemas of their elements and all other values are replaced by the name of their type.
+fn schema(value: &Value) -> Value {
+    match value {
+        Value::Null => json!("null"),
+        Value::Bool(_) => json!("bool"),
+        Value::Number(_) => json!("number"),
+        Value::String(_) => json!("string"),
+        Value::Array(values) => {
+            let mut schemas = vec![];
+            for schema in values.iter().map(schema) {
+                if !schemas.contains(&schema) {
+                    schemas.push(schema);
+                }
+            }
+            Value::Array(schemas)
+        }
+        Value::Object(map) => Value::Object(
+            map.iter()
+                .map(|(key, value)| (key.clone(), schema(value)))
+                .collect(),
+        ),
+    }
+}
+
+/// Returns `true` if the `actual` schema matches the `expected` schema from a snapshot.
+///
+/// Wildcards are only recognised in `expected`: `WILDCARD` matches any value and an empty array
+/// matches any array.
+fn schema_matches(expected: &Value, actual: &Value) -> bool {
+    match (expected, actual) {
+        (Value::String(name), _) if name == WILDCARD => true,
+        (Value::Array(expected), Value::Array(actual)) => {
+            expected.is_empty()
+                || (!actual.is_empty()
+                    && actual.iter().all(|actual| {
+                        expected
+                            .iter()
+                            .any(|expected| schema_matches(expected, actual))
+                    }))
+        }
+        (Value::Object(expected), Value::Object(actual)) => {
+            expected.len() == actual.len()
+                && expected.iter().all(|(key, expected)| {
+                    actual
+                        .get(key)
+                        .map_or(false, |actual| schema_matches(expected, actual))
+                })
+        }
+        (expected, actual) => expected == actual,
+    }
+}
+
+fn snapshot_dir() -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+        .join("tests")
+        .join("schema_snapshots")
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn schema_snapshots() {
+    let spec = ForkName::latest().make_genesis_spec(E::default_spec());
+    let tester = InteractiveTester::<E>::new(Some(spec), VALIDATOR_COUNT).await;
+    let harness = &tester.harness;
+
+    harness.advance_slot();
+    harness
+        .extend_chain_with_sync(
+            (NUM_EPOCHS * E::slots_per_epoch()) as usize,
+            BlockStrategy::OnCanonicalHead,
+            AttestationStrategy::AllValidators,
+            SyncCommitteeStrategy::AllValidators,
+            LightClientStrategy::Disabled,
+        )
+        .await;
+
+    let epoch = harness.chain.epoch().unwrap();
+    let base_url = tester.client.as_ref().to_string();
+    let http_client = Client::new();
+    let update = std::env::var(UPDATE_ENV_VAR).is_ok();
+    let dir = snapshot_dir();
+    if update {
+        fs::create_dir_all(&dir).unwrap();
+    }
+
+    let mut failures = vec![];
+
+    for endpoint in endpoints(epoch) {
+        let url = format!("{base_url}{}", endpoint.path);
+        let request = match &endpoint.body {
+            Some(body) => http_client.post(&url).json(body),
+            None => http_client.get(&url),
+        };
+
+        let response = request.send().await.unwrap();
+        let status = response.status();
+        if !status.is_success() {
+            failures.push(format!(
+                "{}: request failed with status {status}",
+                endpoint.path
+            ));
+            continue;
+        }
+        let actual = schema(&response.json::<Value>().await.unwrap());
+
+        let snapshot_path = dir.join(endpoint.snapshot_file_name());
+        if update {
+            fs::write(
+                &snapshot_path,
+                serde_json::to_string_pretty(&actual).unwrap() + "\n",
+            )
+            .unwrap();
+            continue;
+        }
+        if !snapshot_path.exists() {
+            failures.push(format!(
+                "{}: no snapshot at {}, record it by setting {UPDATE_ENV_VAR}=1",
+                endpoint.path,
+                snapshot_path.display()
+            ));
+            continue;
+        }
+
+        let expected: Value =
+            serde_json::from_str(&fs::read_to_string(&snapshot_path).unwrap()).unwrap();
+        if !schema_matches(&expected, &actual) {
+            failures.push(format!(
+                "{}: schema does not match {}\nexpected: {}\nactual: {}",
+                endpoint.path,
+                snapshot_path.display(),
+                serde_json::to_string_pretty(&expected).unwrap(),
+                serde_json::to_string_pretty(&actual).unwrap(),
+            ));
+        }
+    }
+
+    assert!(
+        failures.is_empty(),
+        "{} endpoint(s) failed, set {UPDATE_ENV_VAR}=1 if the changes are intentional:\n\n{}",
+        failures.len(),
+        failures.join("\n\n")
+    );
+}
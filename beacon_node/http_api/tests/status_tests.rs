@@ -3,6 +3,7 @@ use beacon_chain::{
     test_utils::{AttestationStrategy, BlockStrategy, LightClientStrategy, SyncCommitteeStrategy},
     BlockError,
 };
+use eth2::lighthouse::{DegradationReason, HealthSeverity};
 use eth2::StatusCode;
 use execution_layer::{PayloadStatusV1, PayloadStatusV1Status};
 use http_api::test_utils::InteractiveTester;
@@ -227,3 +228,74 @@ async fn node_health_el_online_and_not_synced() {
         }
     }
 }
+
+/// Check that the `node health` endpoint reports the reasons that the EL is unhealthy.
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn node_health_report_el_offline() {
+    let num_blocks = E::slots_per_epoch() / 2;
+    let num_validators = E::slots_per_epoch();
+    let tester = post_merge_tester(num_blocks, num_validators).await;
+    let harness = &tester.harness;
+    let mock_el = harness.mock_execution_layer.as_ref().unwrap();
+
+    // EL offline
+    mock_el.server.set_syncing_response(Err("offline".into()));
+    mock_el.el.upcheck().await;
+
+    let (status, report) = tester.client.get_node_health_report().await.unwrap();
+    assert_eq!(status, StatusCode::SERVICE_UNAVAILABLE);
+    assert!(report
+        .data
+        .reasons
+        .iter()
+        .any(
+            |degradation| degradation.reason == DegradationReason::ExecutionLayerOffline
+                && degradation.severity == HealthSeverity::Critical
+        ));
+
+    // The same reasons are included in the Lighthouse health endpoint, which is only supported
+    // on Linux.
+    #[cfg(target_os = "linux")]
+    {
+        let health = tester.client.get_lighthouse_health().await.unwrap();
+        let degradation = health.data.degradation.unwrap();
+        assert_eq!(degradation.status, report.data.status);
+        assert!(
+            degradation.has_reason(|reason| *reason == DegradationReason::ExecutionLayerOffline)
+        );
+    }
+}
+
+/// Check that the `node health` endpoint reports an optimistic head.
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn node_health_report_optimistic_head() {
+    let num_blocks = E::slots_per_epoch() / 2;
+    let num_validators = E::slots_per_epoch();
+    let tester = post_merge_tester(num_blocks, num_validators).await;
+    let harness = &tester.harness;
+    let mock_el = harness.mock_execution_layer.as_ref().unwrap();
+
+    // EL not synced
+    harness.advance_slot();
+    mock_el.server.all_payloads_syncing(true);
+    harness
+        .extend_chain(
+            1,
+            BlockStrategy::OnCanonicalHead,
+            AttestationStrategy::AllValidators,
+        )
+        .await;
+
+    let (status, report) = tester.client.get_node_health_report().await.unwrap();
+    assert_eq!(status, StatusCode::PARTIAL_CONTENT);
+    assert!(report
+        .data
+        .reasons
+        .iter()
+        .any(|degradation| degradation.reason == DegradationReason::OptimisticHead));
+    assert!(!report
+        .data
+        .reasons
+        .iter()
+        .any(|degradation| degradation.reason == DegradationReason::ExecutionLayerOffline));
+}
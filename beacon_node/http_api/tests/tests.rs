@@ -1,9 +1,18 @@
 use beacon_chain::test_utils::RelativeSyncCommittee;
 use beacon_chain::{
+    attestation_data_cache::AttestationDataCacheKey,
+    head_history::HEAD_HISTORY_EPOCHS,
+    kzg_utils::blobs_to_data_column_sidecars,
+    migrate::DEFAULT_EPOCHS_PER_MIGRATION,
     test_utils::{AttestationStrategy, BeaconChainHarness, BlockStrategy, EphemeralHarnessType},
     BeaconChain, ChainConfig, StateSkipConfig, WhenSlotSkipped,
 };
 use eth2::{
+    event_stream::EventStreamConfig,
+    lighthouse::{
+        BlockValueRecord, DepositLog, Eth1Block, LightClientStoreImport, PayloadChoiceReason,
+        PayloadSource, ReachabilityStatus, ValidatorInfoRequestData, ValidatorMetricsRequestData,
+    },
     mixin::{RequestAccept, ResponseForkName, ResponseOptional},
     reqwest::RequestBuilder,
     types::{
@@ -24,13 +33,15 @@ use http_api::{
 };
 use lighthouse_network::{types::SyncState, Enr, EnrExt, PeerId};
 use logging::test_logger;
-use network::NetworkReceivers;
+use network::{NetworkMessage, NetworkReceivers};
+use parking_lot::Mutex;
 use proto_array::ExecutionStatus;
 use sensitive_url::SensitiveUrl;
 use slot_clock::SlotClock;
 use state_processing::per_block_processing::get_expected_withdrawals;
 use state_processing::per_slot_processing;
 use state_processing::state_advance::partial_state_advance;
+use std::collections::HashSet;
 use std::convert::TryInto;
 use std::sync::Arc;
 use tokio::time::Duration;
@@ -38,7 +49,8 @@ use tree_hash::TreeHash;
 use types::application_domain::ApplicationDomain;
 use types::{
     attestation::AttestationBase, AggregateSignature, BitList, Domain, EthSpec, ExecutionBlockHash,
-    Hash256, Keypair, MainnetEthSpec, RelativeEpoch, SelectionProof, SignedRoot, Slot,
+    Hash256, Keypair, MainnetEthSpec, RelativeEpoch, SelectionProof, SignedRoot, Slot, SubnetId,
+    SyncAggregate,
 };
 
 type E = MainnetEthSpec;
@@ -83,6 +95,8 @@ struct ApiTester {
 struct ApiTesterConfig {
     spec: ChainSpec,
     retain_historic_states: bool,
+    epochs_per_migration: u64,
+    track_attestation_sources: bool,
 }
 
 impl Default for ApiTesterConfig {
@@ -92,6 +106,8 @@ impl Default for ApiTesterConfig {
         Self {
             spec,
             retain_historic_states: false,
+            epochs_per_migration: DEFAULT_EPOCHS_PER_MIGRATION,
+            track_attestation_sources: false,
         }
     }
 }
@@ -101,6 +117,17 @@ impl ApiTesterConfig {
         self.retain_historic_states = true;
         self
     }
+
+    fn track_attestation_sources(mut self) -> Self {
+        self.track_attestation_sources = true;
+        self
+    }
+
+    /// Defer database migrations so that the split point lags behind finalization.
+    fn defer_migrations(mut self) -> Self {
+        self.epochs_per_migration = 1024;
+        self
+    }
 }
 
 impl ApiTester {
@@ -128,6 +155,8 @@ impl ApiTester {
             .spec(spec.clone())
             .chain_config(ChainConfig {
                 reconstruct_historic_states: config.retain_historic_states,
+                epochs_per_migration: config.epochs_per_migration,
+                track_attestation_sources: config.track_attestation_sources,
                 ..ChainConfig::default()
             })
             .logger(logging::test_logger())
@@ -437,6 +466,11 @@ impl ApiTester {
         self
     }
 
+    fn with_ssz_request_bodies(mut self) -> Self {
+        self.client = self.client.with_ssz_request_bodies();
+        self
+    }
+
     fn interesting_state_ids(&self) -> Vec<StateId> {
         let mut ids = vec![
             StateId(CoreStateId::Head),
@@ -1667,10 +1701,170 @@ impl ApiTester {
         self
     }
 
+    pub async fn test_get_blob_sidecars_by_versioned_hashes(self) -> Self {
+        let block_id = BlockId(CoreBlockId::Finalized);
+        let (block_root, _, _) = block_id.root(&self.chain).unwrap();
+        let (block, _, _) = block_id.full_block(&self.chain).await.unwrap();
+        let commitments = block.message().body().blob_kzg_commitments().unwrap();
+        let last_index = commitments.len() as u64 - 1;
+        let versioned_hash = commitments.last().unwrap().calculate_versioned_hash();
+
+        let result = self
+            .client
+            .get_blobs_filtered::<E>(
+                CoreBlockId::Root(block_root),
+                None,
+                Some(&[versioned_hash, Hash256::repeat_byte(0xff)]),
+            )
+            .await
+            .unwrap()
+            .unwrap()
+            .data;
+        assert_eq!(result.len(), 1);
+        assert_eq!(result.get(0).unwrap().index, last_index);
+
+        // Blobs must match the indices as well as the versioned hashes.
+        let result = self
+            .client
+            .get_blobs_filtered::<E>(
+                CoreBlockId::Root(block_root),
+                Some(&[last_index + 1]),
+                Some(&[versioned_hash]),
+            )
+            .await
+            .unwrap()
+            .unwrap()
+            .data;
+        assert!(result.is_empty());
+
+        self
+    }
+
     /// Test fetching of blob sidecars that are not available in the database due to pruning.
     ///
     /// If `zero_blobs` is false, test a block with >0 blobs, which should be unavailable.
     /// If `zero_blobs` is true, then test a block with 0 blobs, which should still be available.
+    /// Build the blob sidecars for `self.next_block`.
+    fn next_block_blob_sidecars(&self) -> Vec<Arc<BlobSidecar<E>>> {
+        let (block, blob_items) = self.next_block.clone().deconstruct();
+        let (kzg_proofs, blobs) = blob_items.expect("next block should have blobs");
+        kzg_proofs
+            .into_iter()
+            .zip(blobs)
+            .enumerate()
+            .map(|(i, (kzg_proof, blob))| {
+                Arc::new(BlobSidecar::new(i, blob, &block, kzg_proof).unwrap())
+            })
+            .collect()
+    }
+
+    pub async fn test_post_lighthouse_blobs(mut self) -> Self {
+        let block_root = self.next_block.signed_block().canonical_root();
+        let blobs = self.next_block_blob_sidecars();
+        assert!(!blobs.is_empty(), "next block should have blobs");
+
+        // A blob with an invalid KZG proof is rejected.
+        let mut invalid_blob = (*blobs[0]).clone();
+        invalid_blob.kzg_proof = KzgProof::empty();
+        let error = self
+            .client
+            .post_lighthouse_blobs(&[Arc::new(invalid_blob)])
+            .await
+            .unwrap_err();
+        assert_eq!(error.status(), Some(StatusCode::BAD_REQUEST));
+        assert_eq!(
+            self.chain
+                .data_availability_checker
+                .cached_blob_indexes(&block_root),
+            None
+        );
+
+        // Valid blobs are published and imported.
+        self.client.post_lighthouse_blobs(&blobs).await.unwrap();
+        for _ in &blobs {
+            assert!(
+                self.network_rx.network_recv.recv().await.is_some(),
+                "valid blobs should be sent to network"
+            );
+        }
+        let expected_indices = (0..blobs.len() as u64).collect::<Vec<_>>();
+        assert_eq!(
+            self.chain
+                .data_availability_checker
+                .cached_blob_indexes(&block_root),
+            Some(expected_indices.clone())
+        );
+
+        // Duplicate blobs are ignored rather than rejected or published again.
+        self.client.post_lighthouse_blobs(&blobs).await.unwrap();
+        assert!(self.network_rx.network_recv.try_recv().is_err());
+        assert_eq!(
+            self.chain
+                .data_availability_checker
+                .cached_blob_indexes(&block_root),
+            Some(expected_indices)
+        );
+
+        self
+    }
+
+    pub async fn test_post_lighthouse_data_columns(mut self) -> Self {
+        let block = self.next_block.signed_block().clone();
+        let block_root = block.canonical_root();
+        let blobs = self.next_block_blob_sidecars();
+        let blob_refs = blobs.iter().map(|blob| &blob.blob).collect::<Vec<_>>();
+        let data_columns =
+            blobs_to_data_column_sidecars(&blob_refs, &block, &self.chain.kzg, &self.chain.spec)
+                .unwrap();
+        assert!(!data_columns.is_empty(), "next block should have blobs");
+
+        // A data column with an invalid KZG proof is rejected.
+        let mut invalid_column = (*data_columns[0]).clone();
+        invalid_column.kzg_proofs[0] = KzgProof::empty();
+        let error = self
+            .client
+            .post_lighthouse_data_columns(&[Arc::new(invalid_column)])
+            .await
+            .unwrap_err();
+        assert_eq!(error.status(), Some(StatusCode::BAD_REQUEST));
+        assert_eq!(
+            self.chain
+                .data_availability_checker
+                .cached_data_column_indexes(&block_root),
+            None
+        );
+
+        // Valid data columns are published and imported.
+        self.client
+            .post_lighthouse_data_columns(&data_columns)
+            .await
+            .unwrap();
+        assert!(
+            self.network_rx.network_recv.recv().await.is_some(),
+            "valid data columns should be sent to network"
+        );
+        let cached_indices = self
+            .chain
+            .data_availability_checker
+            .cached_data_column_indexes(&block_root);
+        assert!(cached_indices.is_some());
+
+        // Duplicate data columns are ignored rather than rejected or published again.
+        self.client
+            .post_lighthouse_data_columns(&data_columns)
+            .await
+            .unwrap();
+        assert!(self.network_rx.network_recv.try_recv().is_err());
+        assert_eq!(
+            self.chain
+                .data_availability_checker
+                .cached_data_column_indexes(&block_root),
+            cached_indices
+        );
+
+        self
+    }
+
     pub async fn test_get_blob_sidecars_pruned(self, zero_blobs: bool) -> Self {
         // Prune all blobs prior to the database's split epoch.
         let store = &self.chain.store;
@@ -1997,6 +2191,67 @@ impl ApiTester {
         self
     }
 
+    pub async fn test_get_lighthouse_light_client_store(self) -> Self {
+        let result = self
+            .client
+            .get_lighthouse_light_client_store::<E>()
+            .await
+            .unwrap();
+
+        let expected = self.chain.get_light_client_store_snapshot().unwrap();
+        assert_eq!(result, expected);
+        assert_eq!(
+            result
+                .latest_finality_update
+                .clone()
+                .map(|update| update.data),
+            self.chain
+                .light_client_server_cache
+                .get_latest_finality_update()
+        );
+        assert_eq!(
+            result
+                .latest_optimistic_update
+                .clone()
+                .map(|update| update.data),
+            self.chain
+                .light_client_server_cache
+                .get_latest_optimistic_update()
+        );
+        assert!(!result.best_updates.is_empty());
+
+        // Re-importing the node's own snapshot replaces nothing, and every update is verified.
+        let import = self
+            .chain
+            .import_light_client_store_snapshot(result.clone())
+            .unwrap();
+        assert_eq!(import, LightClientStoreImport::default());
+
+        // An update whose sync aggregate doesn't match the local chain is ignored.
+        let mut tampered = result;
+        tampered.best_updates.clear();
+        tampered.latest_finality_update = None;
+        *tampered
+            .latest_optimistic_update
+            .as_mut()
+            .expect("optimistic update is cached")
+            .data
+            .sync_aggregate_mut() = SyncAggregate::new();
+        let import = self
+            .chain
+            .import_light_client_store_snapshot(tampered)
+            .unwrap();
+        assert_eq!(
+            import,
+            LightClientStoreImport {
+                rejected: 1,
+                ..LightClientStoreImport::default()
+            }
+        );
+
+        self
+    }
+
     pub async fn test_get_beacon_pool_attestations(self) -> Self {
         let result = self
             .client
@@ -2330,15 +2585,19 @@ impl ApiTester {
     pub async fn test_get_node_identity(self) -> Self {
         let result = self.client.get_node_identity().await.unwrap().data;
 
+        let mut p2p_addresses = self.local_enr.multiaddr_p2p_tcp();
+        p2p_addresses.extend(self.local_enr.multiaddr_p2p_quic());
         let expected = IdentityData {
             peer_id: self.local_enr.peer_id().to_string(),
             enr: self.local_enr.clone(),
-            p2p_addresses: self.local_enr.multiaddr_p2p_tcp(),
+            p2p_addresses,
             discovery_addresses: self.local_enr.multiaddr_p2p_udp(),
             metadata: eth2::types::MetaData {
                 seq_number: 0,
                 attnets: "0x0000000000000000".to_string(),
                 syncnets: "0x00".to_string(),
+                // The test node advertises V2 metadata, prior to PeerDAS.
+                custody_group_count: None,
             },
         };
 
@@ -3201,6 +3460,89 @@ impl ApiTester {
         self
     }
 
+    pub async fn test_lighthouse_block_production_on_parent(self) -> Self {
+        // Build upon the parent of the head, as though re-orging the head block.
+        let head_block = self.chain.head_beacon_block();
+        let parent_root = head_block.parent_root();
+        let slot = head_block.slot() + 1;
+        self.chain.slot_clock.set_slot(slot.as_u64());
+
+        let (response, _metadata) = self
+            .client
+            .get_lighthouse_validator_blocks::<E>(
+                slot,
+                parent_root,
+                &Signature::infinity().unwrap().into(),
+                None,
+                SkipRandaoVerification::Yes,
+                None,
+            )
+            .await
+            .unwrap();
+
+        let block = match response.data {
+            ProduceBlockV3Response::Full(block_contents) => block_contents.deconstruct().0,
+            ProduceBlockV3Response::Blinded(_) => panic!("Expecting a full block"),
+        };
+        assert_eq!(block.slot(), slot);
+        assert_eq!(block.parent_root(), parent_root);
+
+        // Unknown parents are rejected.
+        self.client
+            .get_lighthouse_validator_blocks::<E>(
+                slot,
+                Hash256::repeat_byte(0x42),
+                &Signature::infinity().unwrap().into(),
+                None,
+                SkipRandaoVerification::Yes,
+                None,
+            )
+            .await
+            .unwrap_err();
+
+        // Parents from the same slot or later are rejected.
+        self.client
+            .get_lighthouse_validator_blocks::<E>(
+                head_block.slot(),
+                parent_root,
+                &Signature::infinity().unwrap().into(),
+                None,
+                SkipRandaoVerification::Yes,
+                None,
+            )
+            .await
+            .unwrap_err();
+
+        // Parents prior to the finalized checkpoint are rejected.
+        let finalized_checkpoint = self
+            .chain
+            .canonical_head
+            .cached_head()
+            .finalized_checkpoint();
+        assert!(finalized_checkpoint.epoch > 0, "precondition: finality");
+        let finalized_parent_root = self
+            .chain
+            .block_root_at_slot(Slot::new(1), WhenSlotSkipped::Prev)
+            .unwrap()
+            .unwrap();
+        assert_ne!(finalized_parent_root, finalized_checkpoint.root);
+        let err = self
+            .client
+            .get_lighthouse_validator_blocks::<E>(
+                slot,
+                finalized_parent_root,
+                &Signature::infinity().unwrap().into(),
+                None,
+                SkipRandaoVerification::Yes,
+                None,
+            )
+            .await
+            .unwrap_err();
+        assert_eq!(err.status(), Some(StatusCode::BAD_REQUEST));
+
+        self
+    }
+
     pub async fn test_block_production_verify_randao_invalid(self) -> Self {
         let fork = self.chain.canonical_head.cached_head().head_fork();
         let genesis_validators_root = self.chain.genesis_validators_root;
@@ -3513,6 +3855,49 @@ impl ApiTester {
         self
     }
 
+    pub async fn test_get_validator_attestation_data_is_cached(self) -> Self {
+        let slot = self.chain.slot().unwrap();
+        let index = 0;
+
+        // Make sure requests reach the attestation data cache rather than being answered by the
+        // early attester cache.
+        self.chain.early_attester_cache.clear();
+
+        // Seed the cache with data which the chain would never produce, so that its use by the
+        // API is observable.
+        let key = AttestationDataCacheKey {
+            slot,
+            committee_index: index,
+            head_block_root: self.chain.canonical_head.cached_head().head_block_root(),
+        };
+        let cached = AttestationData {
+            beacon_block_root: Hash256::repeat_byte(0xaa),
+            ..self
+                .chain
+                .produce_unaggregated_attestation(slot, index)
+                .unwrap()
+                .data()
+                .clone()
+        };
+        self.chain
+            .attestation_data_cache
+            .get_or_compute(key, || Ok::<_, ()>(cached.clone()))
+            .unwrap();
+
+        for _ in 0..2 {
+            let result = self
+                .client
+                .get_validator_attestation_data(slot, index)
+                .await
+                .unwrap()
+                .data;
+            assert_eq!(result, cached);
+        }
+        assert_eq!(self.chain.attestation_data_cache.len(), 1);
+
+        self
+    }
+
     pub async fn test_get_validator_aggregate_attestation(self) -> Self {
         if self
             .chain
@@ -5282,6 +5667,50 @@ impl ApiTester {
         self
     }
 
+    pub async fn test_get_lighthouse_block_value(
+        self,
+        expected_choice: PayloadSource,
+        expected_reason: PayloadChoiceReason,
+    ) -> Self {
+        let slot = self.chain.slot().unwrap();
+
+        let record = self
+            .client
+            .get_lighthouse_block_value(slot)
+            .await
+            .unwrap()
+            .data;
+        assert_eq!(record.slot, slot);
+        assert_eq!(record.choice, expected_choice);
+        assert_eq!(record.reason, expected_reason);
+        assert_eq!(
+            record.local_payload.unwrap().value,
+            Uint256::from(DEFAULT_MOCK_EL_PAYLOAD_VALUE_WEI)
+        );
+        assert!(record.builder_bid.is_some());
+
+        // The record is persisted so that it survives a restart.
+        let persisted_bytes = self
+            .chain
+            .store
+            .get_block_value_record_bytes(slot)
+            .unwrap()
+            .unwrap();
+        assert_eq!(
+            serde_json::from_slice::<BlockValueRecord>(&persisted_bytes).unwrap(),
+            record
+        );
+
+        // No block has been produced at the next slot.
+        assert!(self
+            .client
+            .get_lighthouse_block_value(slot + 1)
+            .await
+            .is_err());
+
+        self
+    }
+
     pub async fn test_local_payload_v3_chosen_when_more_profitable(self) -> Self {
         // Mutate value.
         self.mock_builder
@@ -5467,75 +5896,932 @@ impl ApiTester {
         self
     }
 
-    pub async fn test_get_lighthouse_proto_array(self) -> Self {
-        self.client.get_lighthouse_proto_array().await.unwrap();
+    pub async fn test_get_lighthouse_peers(self) -> Self {
+        let peers = self.client.get_lighthouse_peers::<E>().await.unwrap();
+        let connected = self
+            .client
+            .get_lighthouse_peers_connected::<E>()
+            .await
+            .unwrap();
+
+        let network_globals = self.ctx.network_globals.as_ref().unwrap();
+        assert_eq!(peers.len(), network_globals.peers.read().peers().count());
+        assert_eq!(connected.len(), network_globals.connected_peers());
+        let external_peer_id = self.external_peer_id.to_string();
+        let peer = connected
+            .iter()
+            .find(|peer| peer.peer_id == external_peer_id)
+            .expect("connected peer should be returned");
+        assert!(peer.peer_info.is_connected());
 
         self
     }
 
-    pub async fn test_get_lighthouse_validator_inclusion_global(self) -> Self {
-        let epoch = self.chain.epoch().unwrap() - 1;
-        self.client
-            .get_lighthouse_validator_inclusion_global(epoch)
-            .await
-            .unwrap();
+    pub async fn test_get_lighthouse_nat(self) -> Self {
+        self.client.get_lighthouse_nat().await.unwrap();
 
         self
     }
 
-    pub async fn test_get_lighthouse_validator_inclusion(self) -> Self {
-        let epoch = self.chain.epoch().unwrap() - 1;
-        self.client
-            .get_lighthouse_validator_inclusion(epoch, ValidatorId::Index(0))
-            .await
-            .unwrap();
+    pub async fn test_get_lighthouse_ui_health(self) -> Self {
+        self.client.get_lighthouse_ui_health().await.unwrap();
 
         self
     }
 
-    pub async fn test_get_lighthouse_eth1_syncing(self) -> Self {
-        self.client.get_lighthouse_eth1_syncing().await.unwrap();
+    pub async fn test_get_lighthouse_ui_validator_count(self) -> Self {
+        let counts = self
+            .client
+            .get_lighthouse_ui_validator_count()
+            .await
+            .unwrap()
+            .data;
+
+        let total = counts.active_ongoing
+            + counts.active_exiting
+            + counts.active_slashed
+            + counts.pending_initialized
+            + counts.pending_queued
+            + counts.withdrawal_possible
+            + counts.withdrawal_done
+            + counts.exited_unslashed
+            + counts.exited_slashed;
+        let expected = self
+            .chain
+            .with_head(|head| Ok::<_, ()>(head.beacon_state.validators().len() as u64))
+            .unwrap();
+        assert_eq!(total, expected);
 
         self
     }
 
-    pub async fn test_get_lighthouse_eth1_block_cache(self) -> Self {
-        let blocks = self.client.get_lighthouse_eth1_block_cache().await.unwrap();
+    pub async fn test_post_lighthouse_ui_validator_monitor(self) -> Self {
+        let indices = vec![0];
 
-        assert!(blocks.data.is_empty());
+        // None of the validators are monitored so the responses are empty.
+        let metrics = self
+            .client
+            .post_lighthouse_ui_validator_metrics(&ValidatorMetricsRequestData {
+                indices: indices.clone(),
+            })
+            .await
+            .unwrap()
+            .data;
+        assert!(metrics.validators.is_empty());
+
+        let info = self
+            .client
+            .post_lighthouse_ui_validator_info(&ValidatorInfoRequestData { indices })
+            .await
+            .unwrap()
+            .data;
+        assert!(info.validators.is_empty());
 
         self
     }
 
-    pub async fn test_get_lighthouse_eth1_deposit_cache(self) -> Self {
-        let deposits = self
+    pub async fn test_get_lighthouse_merge_readiness(self) -> Self {
+        let readiness = self
             .client
-            .get_lighthouse_eth1_deposit_cache()
+            .get_lighthouse_merge_readiness()
             .await
-            .unwrap();
+            .unwrap()
+            .data;
 
-        assert!(deposits.data.is_empty());
+        let current_slot = self.chain.slot().unwrap();
+        let expected = self.chain.check_bellatrix_readiness(current_slot).await;
+        assert_eq!(readiness.to_string(), expected.to_string());
 
         self
     }
 
-    pub async fn test_get_lighthouse_staking(self) -> Self {
-        let result = self.client.get_lighthouse_staking().await.unwrap();
+    pub async fn test_post_lighthouse_analysis_block_rewards(self) -> Self {
+        let head = self.chain.head_snapshot();
+        let block_root = head.beacon_block_root;
+        let block = head.beacon_block.clone_as_blinded().deconstruct().0;
 
-        assert_eq!(result, self.chain.eth1_chain.is_some());
+        let rewards = self
+            .client
+            .post_lighthouse_analysis_block_rewards(&[block])
+            .await
+            .unwrap();
+        assert_eq!(rewards.len(), 1);
+        assert_eq!(rewards[0].block_root, block_root);
 
         self
     }
 
-    pub async fn test_get_lighthouse_database_info(self) -> Self {
-        let info = self.client.get_lighthouse_database_info().await.unwrap();
-
-        assert_eq!(info.anchor, self.chain.store.get_anchor_info());
+    pub async fn test_get_lighthouse_peers_decisions(self) -> Self {
+        let result = self
+            .client
+            .get_lighthouse_peers_decisions()
+            .await
+            .unwrap()
+            .data;
+
+        let expected = self
+            .ctx
+            .network_globals
+            .as_ref()
+            .unwrap()
+            .peers
+            .read()
+            .peer_decisions();
+        assert_eq!(result, expected);
+        // The test peer has never been disconnected or banned.
+        assert!(result.recent.is_empty());
+        assert!(result.goodbyes.is_empty());
+
+        self
+    }
+
+    pub async fn test_get_lighthouse_network_reachability(self) -> Self {
+        let result = self
+            .client
+            .get_lighthouse_network_reachability()
+            .await
+            .unwrap()
+            .data;
+
+        let local_enr = self.ctx.network_globals.as_ref().unwrap().local_enr();
+        assert_eq!(result.len(), 4);
+        for report in result {
+            assert_eq!(
+                report.advertised_port,
+                report.transport.advertised_port(&local_enr)
+            );
+            assert_eq!(
+                report.status == ReachabilityStatus::NotAdvertised,
+                report.advertised_port.is_none()
+            );
+            if report.status == ReachabilityStatus::Reachable {
+                assert!(report.inbound_connections > 0);
+            }
+        }
+
+        self
+    }
+
+    pub async fn test_get_lighthouse_network_client_diversity(self) -> Self {
+        let result = self
+            .client
+            .get_lighthouse_network_client_diversity()
+            .await
+            .unwrap()
+            .data;
+
+        let expected = self
+            .ctx
+            .network_globals
+            .as_ref()
+            .unwrap()
+            .peers
+            .read()
+            .client_diversity();
+        assert_eq!(result, expected);
+        assert_eq!(
+            result.connected_peers,
+            result.clients.values().map(|c| c.peers).sum::<usize>()
+        );
+
+        self
+    }
+
+    pub async fn test_get_lighthouse_network_fork_readiness(self) -> Self {
+        let result = self
+            .client
+            .get_lighthouse_network_fork_readiness()
+            .await
+            .unwrap()
+            .data;
+
+        assert_eq!(result.enr_fork_id, self.chain.enr_fork_id());
+        // The only connected peer has no known ENR.
+        assert_eq!(result.connected_peers, 1);
+        assert_eq!(result.unknown, 1);
+        assert_eq!(result.ready + result.not_ready, 0);
+
+        self
+    }
+
+    pub async fn test_post_lighthouse_beacon_blocks_batch(self) -> Self {
+        // The range spans the skipped slots and extends past the head.
+        let start_slot = Slot::new(FINALIZED_EPOCH * SLOTS_PER_EPOCH - 2);
+        let request = eth2::lighthouse::BlocksBatchRequest::Range {
+            start_slot,
+            count: 128,
+        };
+        let expected = (start_slot.as_u64()..=CHAIN_LENGTH)
+            .filter_map(|slot| {
+                self.chain
+                    .block_root_at_slot(Slot::new(slot), WhenSlotSkipped::None)
+                    .unwrap()
+            })
+            .collect::<Vec<_>>();
+
+        let blocks = self
+            .client
+            .post_lighthouse_beacon_blocks_batch::<E>(&request)
+            .await
+            .unwrap()
+            .data;
+        let roots = blocks
+            .iter()
+            .map(|block| block.data.canonical_root())
+            .collect::<Vec<_>>();
+        assert_eq!(roots, expected);
+        for block in &blocks {
+            assert_eq!(
+                block.version,
+                Some(block.data.fork_name(&self.chain.spec).unwrap())
+            );
+        }
+
+        let ssz_blocks = self
+            .client
+            .post_lighthouse_beacon_blocks_batch_ssz::<E>(&request, &self.chain.spec)
+            .await
+            .unwrap();
+        assert_eq!(
+            ssz_blocks,
+            blocks
+                .into_iter()
+                .map(|block| block.data)
+                .collect::<Vec<_>>()
+        );
+
+        // Unknown roots are omitted and the order of the request is preserved.
+        let request = eth2::lighthouse::BlocksBatchRequest::Roots {
+            block_roots: vec![expected[2], Hash256::repeat_byte(0xff), expected[0]],
+        };
+        let roots = self
+            .client
+            .post_lighthouse_beacon_blocks_batch_ssz::<E>(&request, &self.chain.spec)
+            .await
+            .unwrap()
+            .iter()
+            .map(|block| block.canonical_root())
+            .collect::<Vec<_>>();
+        assert_eq!(roots, vec![expected[2], expected[0]]);
+
+        // Batches over the limit are rejected.
+        let request = eth2::lighthouse::BlocksBatchRequest::Range {
+            start_slot,
+            count: 129,
+        };
+        let err = self
+            .client
+            .post_lighthouse_beacon_blocks_batch::<E>(&request)
+            .await
+            .unwrap_err();
+        assert_eq!(err.status(), Some(StatusCode::BAD_REQUEST));
+
+        self
+    }
+
+    pub async fn test_get_lighthouse_network_subnets(self) -> Self {
+        let result = self
+            .client
+            .get_lighthouse_network_subnets()
+            .await
+            .unwrap()
+            .data;
+
+        let node_id = self
+            .ctx
+            .network_globals
+            .as_ref()
+            .unwrap()
+            .local_enr()
+            .node_id()
+            .raw();
+        let expected_subnets =
+            SubnetId::compute_attestation_subnets(node_id, &self.chain.spec).collect::<Vec<_>>();
+        assert_eq!(result.node_id, Hash256::from(node_id));
+        assert_eq!(
+            result.source,
+            eth2::lighthouse::LongLivedSubnetsSource::Computed
+        );
+        assert_eq!(result.computed_subnets, expected_subnets);
+        assert_eq!(result.subscribed_subnets, expected_subnets);
+
+        self
+    }
+
+    pub async fn test_get_lighthouse_identity(self) -> Self {
+        let result = self.client.get_lighthouse_identity().await.unwrap().data;
+        let node_identity = self.client.get_node_identity().await.unwrap().data;
+
+        assert_eq!(result.identity, node_identity);
+        assert_eq!(result.quic_addresses, self.local_enr.multiaddr_p2p_quic());
+        assert_eq!(result.custody_group_count, None);
+        assert_eq!(result.fork_digest, self.chain.enr_fork_id().fork_digest);
+
+        self
+    }
+
+    pub async fn test_post_lighthouse_peers_protect(mut self) -> Self {
+        let peer_id = PeerId::random();
+        self.client
+            .post_lighthouse_peers_protect(&peer_id.to_string())
+            .await
+            .unwrap();
+
+        match self.network_rx.network_recv.recv().await {
+            Some(NetworkMessage::ProtectPeer {
+                peer_id: protected_peer_id,
+            }) => assert_eq!(protected_peer_id, peer_id),
+            other => panic!("expected a ProtectPeer message, got {:?}", other),
+        }
+
+        let result = self
+            .client
+            .post_lighthouse_peers_protect("not-a-peer-id")
+            .await;
+        assert_eq!(result.unwrap_err().status().unwrap(), 400);
+        assert!(self.network_rx.network_recv.recv().now_or_never().is_none());
+
+        self
+    }
+
+    pub async fn test_get_lighthouse_fork_choice_reorgs(self) -> Self {
+        let result = self
+            .client
+            .get_lighthouse_fork_choice_reorgs(None)
+            .await
+            .unwrap()
+            .data;
+        let expected = self.chain.reorg_summary(HEAD_HISTORY_EPOCHS).unwrap();
+        assert_eq!(result, expected);
+        assert_eq!(result.reorg_count, result.reorgs.len() as u64);
+
+        let result = self
+            .client
+            .get_lighthouse_fork_choice_reorgs(Some(1))
+            .await
+            .unwrap()
+            .data;
+        assert_eq!(result, self.chain.reorg_summary(1).unwrap());
+
+        for epochs in [0, HEAD_HISTORY_EPOCHS + 1] {
+            let result = self
+                .client
+                .get_lighthouse_fork_choice_reorgs(Some(epochs))
+                .await;
+            assert_eq!(result.unwrap_err().status().unwrap(), 400);
+        }
+
+        self
+    }
+
+    pub async fn test_get_lighthouse_block_timings(self) -> Self {
+        let report = self
+            .client
+            .get_lighthouse_analysis_block_timings(None)
+            .await
+            .unwrap();
+        assert_eq!(report, self.chain.block_timings_report(None).unwrap());
+        assert!(!report.is_empty());
+
+        for proposer in &report {
+            for block in &proposer.blocks {
+                assert_eq!(block.proposer_index, proposer.proposer_index);
+                assert_eq!(
+                    self.chain
+                        .block_root_at_slot(block.slot, WhenSlotSkipped::None)
+                        .unwrap(),
+                    Some(block.block_root)
+                );
+                assert!(block.source.is_some());
+            }
+        }
+
+        let proposer_index = report[0].proposer_index;
+        let proposer_report = self
+            .client
+            .get_lighthouse_analysis_block_timings(Some(proposer_index))
+            .await
+            .unwrap();
+        assert_eq!(proposer_report, vec![report[0].clone()]);
+
+        self
+    }
+
+    pub async fn test_get_lighthouse_proto_array(self) -> Self {
+        self.client.get_lighthouse_proto_array().await.unwrap();
+
+        self
+    }
+
+    pub async fn test_get_lighthouse_op_pool(self) -> Self {
+        let info = self.client.get_lighthouse_op_pool().await.unwrap().data;
+
+        assert_eq!(info.num_attestations, self.chain.op_pool.num_attestations());
+        assert_eq!(
+            info.num_attestations,
+            info.num_unaggregated_attestations + info.num_aggregated_attestations
+        );
+        assert_eq!(
+            info.num_voluntary_exits,
+            self.chain.op_pool.num_voluntary_exits()
+        );
+
+        self
+    }
+
+    pub async fn test_get_lighthouse_op_pool_censorship(self) -> Self {
+        let report = self
+            .client
+            .get_lighthouse_op_pool_censorship()
+            .await
+            .unwrap()
+            .data;
+
+        assert_eq!(report.current_slot, self.chain.slot().unwrap());
+        assert_eq!(
+            report.pending.len(),
+            self.chain.op_pool.pending_operation_records().len()
+        );
+        assert!(report
+            .pending
+            .windows(2)
+            .all(|pair| pair[0].first_seen_slot <= pair[1].first_seen_slot));
+
+        self
+    }
+
+    pub async fn test_get_lighthouse_beacon_processor(self) -> Self {
+        let queues = self
+            .client
+            .get_lighthouse_beacon_processor()
+            .await
+            .unwrap()
+            .data;
+
+        // Previous API requests will have been handled by the beacon processor.
+        let api_queue = queues.get("api_request_p1").unwrap();
+        assert!(api_queue.capacity > 0);
+        assert!(api_queue.length <= api_queue.capacity);
+        assert_eq!(api_queue.dropped, 0);
+
+        self
+    }
+
+    pub async fn test_get_lighthouse_validator_inclusion_global(self) -> Self {
+        let epoch = self.chain.epoch().unwrap() - 1;
+        self.client
+            .get_lighthouse_validator_inclusion_global(epoch)
+            .await
+            .unwrap();
+
+        self
+    }
+
+    pub async fn test_get_lighthouse_validator_inclusion(self) -> Self {
+        let epoch = self.chain.epoch().unwrap() - 1;
+        self.client
+            .get_lighthouse_validator_inclusion(epoch, ValidatorId::Index(0))
+            .await
+            .unwrap();
+
+        self
+    }
+
+    pub async fn test_get_lighthouse_attestation_inclusion(self) -> Self {
+        let block = self.chain.head_beacon_block();
+        let block_id = CoreBlockId::Slot(block.slot());
+        let attestations = block.message().body().attestations().collect::<Vec<_>>();
+
+        let mut num_included = 0;
+        for validator_index in 0..VALIDATOR_COUNT as u64 {
+            let result = self
+                .client
+                .get_lighthouse_attestation_inclusion(block_id, validator_index)
+                .await
+                .unwrap()
+                .data;
+
+            assert_eq!(result.block_root, block.canonical_root());
+            assert_eq!(result.validator_index, validator_index);
+            assert_eq!(result.included, !result.inclusions.is_empty());
+            for inclusion in &result.inclusions {
+                let attestation = attestations[inclusion.attestation_index as usize];
+                assert_eq!(inclusion.slot, attestation.data().slot);
+                assert_eq!(
+                    inclusion.inclusion_distance,
+                    (block.slot() - inclusion.slot).as_u64()
+                );
+            }
+            if result.included {
+                num_included += 1;
+            }
+        }
+        assert!(num_included > 0);
+
+        let result = self
+            .client
+            .get_lighthouse_attestation_inclusion(block_id, u64::MAX)
+            .await;
+        assert_eq!(result.unwrap_err().status().unwrap(), 400);
+
+        self
+    }
+
+    pub async fn test_get_lighthouse_export_attestations(self) -> Self {
+        let head = self.chain.head_beacon_block();
+        let head_slot = head.slot();
+
+        let exported = self
+            .client
+            .get_lighthouse_export_attestations(Slot::new(0), head_slot, Accept::Json)
+            .await
+            .unwrap();
+        let exported_ssz = self
+            .client
+            .get_lighthouse_export_attestations(Slot::new(0), head_slot, Accept::Ssz)
+            .await
+            .unwrap();
+        assert_eq!(exported, exported_ssz);
+
+        let head_records = exported
+            .iter()
+            .filter(|record| record.block_slot == head_slot)
+            .collect::<Vec<_>>();
+        let attestations = head.message().body().attestations().collect::<Vec<_>>();
+        assert!(!attestations.is_empty());
+        assert_eq!(head_records.len(), attestations.len());
+        for record in head_records {
+            let attestation = attestations[record.attestation_index as usize];
+            assert_eq!(record.block_root, head.canonical_root());
+            assert_eq!(record.proposer_index, head.message().proposer_index());
+            assert_eq!(&record.data, attestation.data());
+            assert_eq!(
+                record.inclusion_distance,
+                (head_slot - attestation.data().slot).as_u64()
+            );
+            assert_eq!(
+                record.attesting_indices.len(),
+                attestation.num_set_aggregation_bits()
+            );
+        }
+
+        let result = self
+            .client
+            .get_lighthouse_export_attestations(head_slot, Slot::new(0), Accept::Json)
+            .await;
+        assert_eq!(result.unwrap_err().status().unwrap(), 400);
+
+        self
+    }
+
+    pub async fn test_get_lighthouse_epoch_summary(self) -> Self {
+        let current_epoch = self.chain.epoch().unwrap();
+        let finalized_epoch = self
+            .chain
+            .canonical_head
+            .cached_head()
+            .finalized_checkpoint()
+            .epoch;
+        assert!(finalized_epoch > 1, "precondition: an epoch is summarised");
+
+        for epoch in (0..=current_epoch.as_u64()).map(Epoch::new) {
+            let result = self
+                .client
+                .get_lighthouse_epoch_summary(epoch)
+                .await
+                .unwrap()
+                .map(|res| res.data);
+
+            // An epoch is summarised once the following epoch has been finalized.
+            if epoch + 1 >= finalized_epoch {
+                assert_eq!(result, None, "epoch {epoch} should not be summarised");
+                continue;
+            }
+            let summary = result.unwrap_or_else(|| panic!("epoch {epoch} should be summarised"));
+            assert_eq!(summary.epoch, epoch);
+
+            let expected_slots = epoch
+                .slot_iter(SLOTS_PER_EPOCH)
+                .filter(|slot| !SKIPPED_SLOTS.contains(&slot.as_u64()))
+                .collect::<Vec<_>>();
+            let slots = summary
+                .blocks
+                .iter()
+                .map(|block| block.slot)
+                .collect::<Vec<_>>();
+            assert_eq!(slots, expected_slots);
+            for block in &summary.blocks {
+                let block_root = self
+                    .chain
+                    .block_root_at_slot(block.slot, WhenSlotSkipped::None)
+                    .unwrap()
+                    .unwrap();
+                assert_eq!(block.block_root, block_root);
+                let proposer_index = self
+                    .chain
+                    .get_blinded_block(&block_root)
+                    .unwrap()
+                    .unwrap()
+                    .message()
+                    .proposer_index();
+                assert_eq!(block.proposer_index, proposer_index);
+            }
+
+            if let Some(participation) = summary.participation {
+                assert_eq!(participation.active_validators, VALIDATOR_COUNT as u64);
+            }
+        }
+
+        self
+    }
+
+    pub async fn test_get_lighthouse_validator_withdrawal_projection(self) -> Self {
+        let state = self.chain.head_beacon_state_cloned();
+
+        let Ok(next_index) = state.next_withdrawal_validator_index() else {
+            let pubkey = state.validators().get(0).unwrap().pubkey;
+            let result = self
+                .client
+                .get_lighthouse_validator_withdrawal_projection(&pubkey)
+                .await;
+            assert_eq!(result.unwrap_err().status().unwrap(), 400);
+            return self;
+        };
+
+        // Validators withdrawn from by the next payload must be projected for the next slot.
+        let (next_withdrawals, _) = get_expected_withdrawals(&state, &self.chain.spec).unwrap();
+        let num_validators = state.validators().len() as u64;
+        for (index, validator) in state.validators().iter().enumerate() {
+            let projection = self
+                .client
+                .get_lighthouse_validator_withdrawal_projection(&validator.pubkey)
+                .await
+                .unwrap()
+                .data;
+
+            assert_eq!(projection.validator_index, index as u64);
+            assert_eq!(projection.head_slot, state.slot());
+            assert_eq!(
+                projection.sweep_position,
+                (index as u64 + num_validators - next_index) % num_validators
+            );
+            let is_withdrawable =
+                projection.is_fully_withdrawable || projection.is_partially_withdrawable;
+            assert_eq!(projection.estimated_slot.is_some(), is_withdrawable);
+            assert_eq!(projection.estimated_timestamp.is_some(), is_withdrawable);
+            assert_eq!(projection.expected_amount_gwei > 0, is_withdrawable);
+            if let Some(slot) = projection.estimated_slot {
+                assert!(slot > state.slot());
+            }
+
+            let next_withdrawal = next_withdrawals
+                .iter()
+                .find(|withdrawal| withdrawal.validator_index == index as u64);
+            if let Some(withdrawal) = next_withdrawal {
+                assert_eq!(projection.estimated_slot, Some(state.slot() + 1));
+                assert_eq!(projection.expected_amount_gwei, withdrawal.amount);
+            } else if is_withdrawable {
+                assert!(projection.estimated_slot > Some(state.slot() + 1));
+            }
+        }
+
+        let unknown_pubkey = Keypair::random().pk.compress();
+        let result = self
+            .client
+            .get_lighthouse_validator_withdrawal_projection(&unknown_pubkey)
+            .await;
+        assert_eq!(result.unwrap_err().status().unwrap(), 404);
+
+        self
+    }
+
+    pub async fn test_get_lighthouse_pending_queues(self) -> Self {
+        let state = self.chain.head_beacon_state_cloned();
+        let query = eth2::lighthouse::PendingQueueQuery::default();
+
+        let Ok(pending_deposits) = state.pending_balance_deposits() else {
+            let result = self
+                .client
+                .get_lighthouse_pending_deposits(CoreStateId::Head, query)
+                .await;
+            assert_eq!(result.unwrap_err().status().unwrap(), 400);
+            return self;
+        };
+
+        let page = self
+            .client
+            .get_lighthouse_pending_deposits(CoreStateId::Head, query)
+            .await
+            .unwrap()
+            .data;
+        assert_eq!(page.queue_length, pending_deposits.len() as u64);
+        assert_eq!(
+            page.queue_amount_gwei,
+            Some(pending_deposits.iter().map(|deposit| deposit.amount).sum())
+        );
+        assert!(page
+            .items
+            .iter()
+            .map(|item| &item.item)
+            .eq(pending_deposits.iter()));
+
+        let page = self
+            .client
+            .get_lighthouse_pending_partial_withdrawals(CoreStateId::Head, query)
+            .await
+            .unwrap()
+            .data;
+        let pending_partial_withdrawals = state.pending_partial_withdrawals().unwrap();
+        assert_eq!(page.queue_length, pending_partial_withdrawals.len() as u64);
+        assert!(page
+            .items
+            .iter()
+            .map(|item| &item.item)
+            .eq(pending_partial_withdrawals.iter()));
+
+        let page = self
+            .client
+            .get_lighthouse_pending_consolidations(CoreStateId::Head, query)
+            .await
+            .unwrap()
+            .data;
+        let pending_consolidations = state.pending_consolidations().unwrap();
+        assert_eq!(page.queue_length, pending_consolidations.len() as u64);
+        assert_eq!(page.queue_amount_gwei, None);
+        assert!(page
+            .items
+            .iter()
+            .map(|item| &item.item)
+            .eq(pending_consolidations.iter()));
+
+        // Pages past the end of the queue are empty.
+        let page = self
+            .client
+            .get_lighthouse_pending_deposits(
+                CoreStateId::Head,
+                eth2::lighthouse::PendingQueueQuery {
+                    offset: Some(pending_deposits.len() as u64),
+                    limit: Some(1),
+                },
+            )
+            .await
+            .unwrap()
+            .data;
+        assert!(page.items.is_empty());
+
+        let result = self
+            .client
+            .get_lighthouse_pending_deposits(
+                CoreStateId::Head,
+                eth2::lighthouse::PendingQueueQuery {
+                    offset: None,
+                    limit: Some(u64::MAX),
+                },
+            )
+            .await;
+        assert_eq!(result.unwrap_err().status().unwrap(), 400);
+
+        self
+    }
+
+    pub async fn test_get_lighthouse_eth1_syncing(self) -> Self {
+        self.client.get_lighthouse_eth1_syncing().await.unwrap();
+
+        self
+    }
+
+    pub async fn test_get_lighthouse_eth1_block_cache(self) -> Self {
+        let blocks = self.client.get_lighthouse_eth1_block_cache().await.unwrap();
+
+        assert!(blocks.data.is_empty());
+
+        self
+    }
+
+    pub async fn test_get_lighthouse_eth1_deposit_cache(self) -> Self {
+        let deposits = self
+            .client
+            .get_lighthouse_eth1_deposit_cache()
+            .await
+            .unwrap();
+
+        assert!(deposits.data.is_empty());
+
+        self
+    }
+
+    pub async fn test_get_lighthouse_eth1_deposit_proof(self) -> Self {
+        let deposit_count = 8;
+        let finalized_count = 4;
+
+        // An empty cache has no proofs.
+        assert!(self
+            .client
+            .get_lighthouse_eth1_deposit_proof(0)
+            .await
+            .unwrap()
+            .is_none());
+
+        let eth1_service = self.ctx.eth1_service.as_ref().unwrap();
+        {
+            let mut deposits = eth1_service.deposits().write();
+            for index in 0..deposit_count {
+                deposits
+                    .cache
+                    .insert_log(DepositLog {
+                        deposit_data: DepositData {
+                            pubkey: PublicKeyBytes::empty(),
+                            withdrawal_credentials: Hash256::from_low_u64_be(index),
+                            amount: 32_000_000_000,
+                            signature: SignatureBytes::empty(),
+                        },
+                        block_number: index,
+                        index,
+                        signature_is_valid: false,
+                    })
+                    .unwrap();
+            }
+            let finalized_block = Eth1Block {
+                hash: Hash256::from_low_u64_be(finalized_count - 1),
+                timestamp: 0,
+                number: finalized_count - 1,
+                deposit_root: deposits.cache.get_root(finalized_count as usize).cloned(),
+                deposit_count: Some(finalized_count),
+            };
+            deposits.cache.finalize(finalized_block).unwrap();
+            assert_eq!(
+                deposits.cache.pending_len() as u64,
+                deposit_count - finalized_count
+            );
+        }
+
+        // Finalized and unknown deposits have no proof.
+        for index in [0, finalized_count - 1, deposit_count] {
+            assert!(
+                self.client
+                    .get_lighthouse_eth1_deposit_proof(index)
+                    .await
+                    .unwrap()
+                    .is_none(),
+                "deposit {index} should have no proof"
+            );
+        }
+
+        // Pending deposits are proven against the tree containing every deposit in the cache.
+        let expected_root = *eth1_service
+            .deposits()
+            .read()
+            .cache
+            .get_root(deposit_count as usize)
+            .unwrap();
+        for index in finalized_count..deposit_count {
+            let proof = self
+                .client
+                .get_lighthouse_eth1_deposit_proof(index)
+                .await
+                .unwrap()
+                .unwrap()
+                .data;
+
+            assert_eq!(proof.deposit_count, deposit_count);
+            assert_eq!(proof.deposit_root, expected_root);
+            assert_eq!(
+                proof.deposit.data.withdrawal_credentials,
+                Hash256::from_low_u64_be(index)
+            );
+            assert!(merkle_proof::verify_merkle_proof(
+                proof.deposit.data.tree_hash_root(),
+                &proof.deposit.proof,
+                DEPOSIT_TREE_DEPTH + 1,
+                index as usize,
+                proof.deposit_root,
+            ));
+        }
+
+        self
+    }
+
+    pub async fn test_get_lighthouse_staking(self) -> Self {
+        let result = self.client.get_lighthouse_staking().await.unwrap();
+
+        assert_eq!(result, self.chain.eth1_chain.is_some());
+
+        self
+    }
+
+    pub async fn test_get_lighthouse_database_info(self) -> Self {
+        let info = self.client.get_lighthouse_database_info().await.unwrap();
+
+        assert_eq!(info.anchor, self.chain.store.get_anchor_info());
         assert_eq!(info.split, self.chain.store.get_split_info());
         assert_eq!(
             info.schema_version,
             store::metadata::CURRENT_SCHEMA_VERSION.as_u64()
         );
+        assert_eq!(
+            info.migration_lag,
+            info.finalized_slot.saturating_sub(info.split.slot).as_u64()
+        );
 
         self
     }
@@ -5550,6 +6836,108 @@ impl ApiTester {
         self
     }
 
+    pub async fn test_get_lighthouse_analysis_block_reward_sources(self) -> Self {
+        let head_slot = self.chain.head_snapshot().beacon_block.slot();
+
+        if !self.chain.config.track_attestation_sources {
+            let result = self
+                .client
+                .get_lighthouse_analysis_block_reward_sources(head_slot)
+                .await;
+            assert_eq!(result.unwrap_err().status().unwrap(), 400);
+            return self;
+        }
+
+        // Attribute the attestations of even validators to a single peer.
+        let peer = "peer_a";
+        let target_epochs = self
+            .chain
+            .head_snapshot()
+            .beacon_block
+            .message()
+            .body()
+            .attestations()
+            .map(|attestation| attestation.data().target.epoch)
+            .collect::<HashSet<_>>();
+        for target_epoch in target_epochs {
+            self.chain.attestation_sources.lock().observe(
+                target_epoch,
+                (0..VALIDATOR_COUNT as u64).filter(|index| index % 2 == 0),
+                peer,
+            );
+        }
+
+        let block_reward = self
+            .client
+            .get_lighthouse_analysis_block_rewards(head_slot, head_slot)
+            .await
+            .unwrap()
+            .pop()
+            .unwrap();
+        let (expected_peer, expected_unattributed) = block_reward
+            .attestation_rewards
+            .per_attestation_rewards
+            .iter()
+            .flatten()
+            .fold((0, 0), |(peer, unattributed), (index, reward)| {
+                if index % 2 == 0 {
+                    (peer + reward, unattributed)
+                } else {
+                    (peer, unattributed + reward)
+                }
+            });
+        assert!(expected_peer > 0, "precondition: rewards for the peer");
+
+        let sources = self
+            .client
+            .get_lighthouse_analysis_block_reward_sources(head_slot)
+            .await
+            .unwrap();
+        assert_eq!(sources.block_root, block_reward.block_root);
+        assert_eq!(
+            sources.attestation_rewards_total,
+            block_reward.attestation_rewards.total
+        );
+        assert_eq!(sources.peers.len(), 1);
+        assert_eq!(sources.peers[peer].reward, expected_peer);
+        assert_eq!(sources.unattributed.reward, expected_unattributed);
+        assert_eq!(
+            sources.peers[peer].reward + sources.unattributed.reward,
+            sources.attestation_rewards_total
+        );
+
+        self
+    }
+
+    pub async fn test_post_lighthouse_database_migrate(self) -> Self {
+        let info = self.client.get_lighthouse_database_info().await.unwrap();
+        let finalized_slot = self
+            .chain
+            .canonical_head
+            .cached_head()
+            .finalized_checkpoint()
+            .epoch
+            .start_slot(E::slots_per_epoch());
+        assert_eq!(info.finalized_slot, finalized_slot);
+
+        if info.migration_lag > 0 {
+            assert!(info.split.slot < finalized_slot);
+            let result = self.client.post_lighthouse_database_migrate().await;
+            assert_eq!(result.unwrap(), "success");
+
+            // The migrator is blocking in tests, so the split has advanced to finalization.
+            let info = self.client.get_lighthouse_database_info().await.unwrap();
+            assert_eq!(info.split.slot, finalized_slot);
+            assert_eq!(info.migration_lag, 0);
+            assert_eq!(self.chain.store.get_split_slot(), finalized_slot);
+        }
+
+        // There is nothing left to migrate.
+        let result = self.client.post_lighthouse_database_migrate().await;
+        assert_eq!(result.unwrap_err().status().unwrap(), 400);
+        self
+    }
+
     pub async fn test_post_lighthouse_liveness(self) -> Self {
         let epoch = self.chain.epoch().unwrap();
         let head_state = self.chain.head_beacon_state_cloned();
@@ -5828,6 +7216,37 @@ impl ApiTester {
         self
     }
 
+    pub async fn test_get_events_with_reconnect(self) -> Self {
+        let gaps = Arc::new(Mutex::new(vec![]));
+        let gaps_clone = gaps.clone();
+        let mut events = Box::pin(
+            self.client
+                .get_events_with_reconnect::<E, _, _>(
+                    &[EventTopic::AttesterSlashing],
+                    EventStreamConfig::default(),
+                    move |gap| {
+                        gaps_clone.lock().push(gap);
+                        async {}
+                    },
+                )
+                .unwrap(),
+        );
+
+        // The stream connects when first polled, so publish the slashing once polling has begun.
+        let (attester_slashing_events, ()) = tokio::join!(
+            poll_events(&mut events, 1, Duration::from_millis(10000)),
+            async {
+                tokio::time::sleep(Duration::from_millis(500)).await;
+                self.harness.add_attester_slashing(vec![1, 2, 3]).unwrap();
+            }
+        );
+
+        assert_eq!(attester_slashing_events.len(), 1);
+        assert!(gaps.lock().is_empty());
+
+        self
+    }
+
     pub async fn test_get_expected_withdrawals_invalid_state(self) -> Self {
         let state_id = CoreStateId::Root(Hash256::zero());
 
@@ -6031,6 +7450,14 @@ async fn get_events() {
     ApiTester::new().await.test_get_events().await;
 }
 
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn get_events_with_reconnect() {
+    ApiTester::new()
+        .await
+        .test_get_events_with_reconnect()
+        .await;
+}
+
 #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
 async fn get_events_altair() {
     let mut config = ApiTesterConfig::default();
@@ -6178,6 +7605,15 @@ async fn beacon_pools_post_attestations_valid_v2() {
         .await;
 }
 
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn beacon_pools_post_attestations_valid_v2_ssz() {
+    ApiTester::new()
+        .await
+        .with_ssz_request_bodies()
+        .test_post_beacon_pool_attestations_valid_v2()
+        .await;
+}
+
 #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
 async fn beacon_pools_post_attestations_invalid_v2() {
     ApiTester::new()
@@ -6186,6 +7622,15 @@ async fn beacon_pools_post_attestations_invalid_v2() {
         .await;
 }
 
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn beacon_pools_post_attestations_invalid_v2_ssz() {
+    ApiTester::new()
+        .await
+        .with_ssz_request_bodies()
+        .test_post_beacon_pool_attestations_invalid_v2()
+        .await;
+}
+
 #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
 async fn beacon_pools_post_attester_slashings_valid_v1() {
     ApiTester::new()
@@ -6344,6 +7789,18 @@ async fn get_light_client_finality_update() {
         .await;
 }
 
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn get_lighthouse_light_client_store() {
+    let config = ApiTesterConfig {
+        spec: ForkName::Altair.make_genesis_spec(E::default_spec()),
+        ..<_>::default()
+    };
+    ApiTester::new_from_config(config)
+        .await
+        .test_get_lighthouse_light_client_store()
+        .await;
+}
+
 #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
 async fn get_validator_duties_early() {
     ApiTester::new()
@@ -6360,6 +7817,15 @@ async fn get_validator_duties_attester() {
         .await;
 }
 
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn get_validator_duties_attester_ssz() {
+    ApiTester::new()
+        .await
+        .with_ssz_request_bodies()
+        .test_get_validator_duties_attester()
+        .await;
+}
+
 #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
 async fn get_validator_duties_attester_with_skip_slots() {
     ApiTester::new()
@@ -6369,6 +7835,29 @@ async fn get_validator_duties_attester_with_skip_slots() {
         .await;
 }
 
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn get_lighthouse_analysis_block_reward_sources() {
+    ApiTester::new()
+        .await
+        .test_get_lighthouse_analysis_block_reward_sources()
+        .await;
+    ApiTester::new_from_config(ApiTesterConfig::default().track_attestation_sources())
+        .await
+        .test_get_lighthouse_analysis_block_reward_sources()
+        .await;
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn lighthouse_database_migrate_deferred() {
+    let tester = ApiTester::new_from_config(ApiTesterConfig::default().defer_migrations()).await;
+    let info = tester.client.get_lighthouse_database_info().await.unwrap();
+    assert!(
+        info.migration_lag > 0,
+        "precondition: migration is deferred"
+    );
+    tester.test_post_lighthouse_database_migrate().await;
+}
+
 #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
 async fn get_validator_duties_proposer() {
     ApiTester::new_from_config(ApiTesterConfig::default().retain_historic_states())
@@ -6408,6 +7897,14 @@ async fn block_production_no_verify_randao() {
         .await;
 }
 
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn lighthouse_block_production_on_parent() {
+    ApiTester::new()
+        .await
+        .test_lighthouse_block_production_on_parent()
+        .await;
+}
+
 #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
 async fn block_production_verify_randao_invalid() {
     ApiTester::new()
@@ -6529,6 +8026,14 @@ async fn get_validator_attestation_data() {
         .await;
 }
 
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn get_validator_attestation_data_is_cached() {
+    ApiTester::new()
+        .await
+        .test_get_validator_attestation_data_is_cached()
+        .await;
+}
+
 #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
 async fn get_validator_attestation_data_with_skip_slots() {
     ApiTester::new()
@@ -6580,6 +8085,15 @@ async fn get_validator_aggregate_and_proofs_valid_v2() {
         .await;
 }
 
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn get_validator_aggregate_and_proofs_valid_v2_ssz() {
+    ApiTester::new()
+        .await
+        .with_ssz_request_bodies()
+        .test_get_validator_aggregate_and_proofs_valid_v2()
+        .await;
+}
+
 #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
 async fn get_validator_aggregate_and_proofs_valid_with_skip_slots_v2() {
     ApiTester::new()
@@ -6873,9 +8387,19 @@ async fn builder_payload_chosen_by_profit_v3() {
         .await
         .test_builder_payload_v3_chosen_when_more_profitable()
         .await
+        .test_get_lighthouse_block_value(
+            PayloadSource::Builder,
+            PayloadChoiceReason::BuilderMoreProfitable,
+        )
+        .await
         .test_local_payload_v3_chosen_when_equally_profitable()
         .await
         .test_local_payload_v3_chosen_when_more_profitable()
+        .await
+        .test_get_lighthouse_block_value(
+            PayloadSource::Local,
+            PayloadChoiceReason::LocalMoreProfitable,
+        )
         .await;
 }
 
@@ -6938,6 +8462,36 @@ async fn get_blob_sidecars() {
         .test_get_blob_sidecars(false)
         .await
         .test_get_blob_sidecars(true)
+        .await
+        .test_get_blob_sidecars_by_versioned_hashes()
+        .await;
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn post_lighthouse_blobs() {
+    let mut config = ApiTesterConfig::default();
+    config.spec.altair_fork_epoch = Some(Epoch::new(0));
+    config.spec.bellatrix_fork_epoch = Some(Epoch::new(0));
+    config.spec.capella_fork_epoch = Some(Epoch::new(0));
+    config.spec.deneb_fork_epoch = Some(Epoch::new(0));
+
+    ApiTester::new_from_config(config)
+        .await
+        .test_post_lighthouse_blobs()
+        .await;
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn post_lighthouse_data_columns() {
+    let mut config = ApiTesterConfig::default();
+    config.spec.altair_fork_epoch = Some(Epoch::new(0));
+    config.spec.bellatrix_fork_epoch = Some(Epoch::new(0));
+    config.spec.capella_fork_epoch = Some(Epoch::new(0));
+    config.spec.deneb_fork_epoch = Some(Epoch::new(0));
+
+    ApiTester::new_from_config(config)
+        .await
+        .test_post_lighthouse_data_columns()
         .await;
 }
 
@@ -6987,28 +8541,110 @@ async fn lighthouse_endpoints() {
         .await
         .test_get_lighthouse_syncing()
         .await
+        .test_get_lighthouse_peers()
+        .await
+        .test_get_lighthouse_nat()
+        .await
+        .test_get_lighthouse_ui_health()
+        .await
+        .test_get_lighthouse_ui_validator_count()
+        .await
+        .test_post_lighthouse_ui_validator_monitor()
+        .await
+        .test_get_lighthouse_merge_readiness()
+        .await
+        .test_post_lighthouse_analysis_block_rewards()
+        .await
+        .test_get_lighthouse_peers_decisions()
+        .await
+        .test_get_lighthouse_network_client_diversity()
+        .await
+        .test_get_lighthouse_network_fork_readiness()
+        .await
+        .test_get_lighthouse_network_reachability()
+        .await
+        .test_get_lighthouse_network_subnets()
+        .await
+        .test_post_lighthouse_beacon_blocks_batch()
+        .await
+        .test_get_lighthouse_identity()
+        .await
+        .test_post_lighthouse_peers_protect()
+        .await
+        .test_get_lighthouse_fork_choice_reorgs()
+        .await
+        .test_get_lighthouse_block_timings()
+        .await
         .test_get_lighthouse_proto_array()
         .await
+        .test_get_lighthouse_op_pool()
+        .await
+        .test_get_lighthouse_op_pool_censorship()
+        .await
+        .test_get_lighthouse_beacon_processor()
+        .await
         .test_get_lighthouse_validator_inclusion()
         .await
+        .test_get_lighthouse_attestation_inclusion()
+        .await
+        .test_get_lighthouse_export_attestations()
+        .await
+        .test_get_lighthouse_epoch_summary()
+        .await
         .test_get_lighthouse_validator_inclusion_global()
         .await
+        .test_get_lighthouse_validator_withdrawal_projection()
+        .await
+        .test_get_lighthouse_pending_queues()
+        .await
         .test_get_lighthouse_eth1_syncing()
         .await
         .test_get_lighthouse_eth1_block_cache()
         .await
         .test_get_lighthouse_eth1_deposit_cache()
         .await
+        .test_get_lighthouse_eth1_deposit_proof()
+        .await
         .test_get_lighthouse_staking()
         .await
         .test_get_lighthouse_database_info()
         .await
         .test_post_lighthouse_database_reconstruct()
         .await
+        .test_post_lighthouse_database_migrate()
+        .await
         .test_post_lighthouse_liveness()
         .await;
 }
 
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn lighthouse_withdrawal_projection_post_capella() {
+    let mut config = ApiTesterConfig::default();
+    config.spec.altair_fork_epoch = Some(Epoch::new(0));
+    config.spec.bellatrix_fork_epoch = Some(Epoch::new(0));
+    config.spec.capella_fork_epoch = Some(Epoch::new(0));
+
+    ApiTester::new_from_config(config)
+        .await
+        .test_get_lighthouse_validator_withdrawal_projection()
+        .await;
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn lighthouse_pending_queues_post_electra() {
+    let mut config = ApiTesterConfig::default();
+    config.spec.altair_fork_epoch = Some(Epoch::new(0));
+    config.spec.bellatrix_fork_epoch = Some(Epoch::new(0));
+    config.spec.capella_fork_epoch = Some(Epoch::new(0));
+    config.spec.deneb_fork_epoch = Some(Epoch::new(0));
+    config.spec.electra_fork_epoch = Some(Epoch::new(0));
+
+    ApiTester::new_from_config(config)
+        .await
+        .test_get_lighthouse_pending_queues()
+        .await;
+}
+
 #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
 async fn optimistic_responses() {
     ApiTester::new_with_hard_forks(true, true)
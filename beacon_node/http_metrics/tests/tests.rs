@@ -22,6 +22,9 @@ async fn returns_200_ok() {
                 listen_port: 0,
                 allow_origin: None,
                 allocator_metrics_enabled: true,
+                auth_token_path: None,
+                metric_allowlist: None,
+                metric_denylist: vec![],
             },
             chain: None,
             db_path: None,
@@ -56,3 +59,81 @@ async fn returns_200_ok() {
     }
     .await
 }
+
+/// Start a metrics server with the given `config`, returning the URL of its `/metrics` endpoint.
+///
+/// The server runs until `shutdown_tx` is dropped.
+fn start_server(config: Config, shutdown_rx: oneshot::Receiver<()>) -> String {
+    let context = Arc::new(Context {
+        config: Config {
+            enabled: true,
+            listen_addr: IpAddr::V4(Ipv4Addr::new(127, 0, 0, 1)),
+            listen_port: 0,
+            ..config
+        },
+        chain: None,
+        db_path: None,
+        freezer_db_path: None,
+        gossipsub_registry: None,
+        log: test_logger(),
+    });
+
+    let server_shutdown = async {
+        let _ = shutdown_rx.await;
+    };
+    let (listening_socket, server) = http_metrics::serve(context, server_shutdown).unwrap();
+    tokio::spawn(server);
+
+    format!(
+        "http://{}:{}/metrics",
+        listening_socket.ip(),
+        listening_socket.port()
+    )
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn requires_auth_token() {
+    let dir = tempfile::tempdir().unwrap();
+    let token_path = dir.path().join("metrics-token.txt");
+    std::fs::write(&token_path, "secret\n").unwrap();
+
+    let (_shutdown_tx, shutdown_rx) = oneshot::channel::<()>();
+    let url = start_server(
+        Config {
+            auth_token_path: Some(token_path),
+            ..Config::default()
+        },
+        shutdown_rx,
+    );
+    let client = reqwest::Client::new();
+
+    let response = client.get(&url).send().await.unwrap();
+    assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+
+    let response = client.get(&url).bearer_auth("wrong").send().await.unwrap();
+    assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+
+    let response = client.get(&url).bearer_auth("secret").send().await.unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn filters_metrics() {
+    let (_shutdown_tx, shutdown_rx) = oneshot::channel::<()>();
+    let url = start_server(
+        Config {
+            metric_allowlist: Some(vec!["process_*".to_string()]),
+            metric_denylist: vec!["process_num_threads".to_string()],
+            ..Config::default()
+        },
+        shutdown_rx,
+    );
+
+    let body = reqwest::get(&url).await.unwrap().text().await.unwrap();
+    assert!(body.contains("process_resident_memory_bytes"));
+    assert!(!body.contains("process_num_threads"));
+    assert!(body
+        .lines()
+        .filter(|line| !line.starts_with('#'))
+        .all(|line| line.starts_with("process_")));
+}
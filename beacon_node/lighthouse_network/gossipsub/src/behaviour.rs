@@ -454,7 +454,10 @@ where
             metrics: metrics.map(|(registry, cfg)| Metrics::new(registry, cfg)),
             events: VecDeque::new(),
             publish_config: privacy.into(),
-            duplicate_cache: DuplicateCache::new(config.duplicate_cache_time()),
+            duplicate_cache: DuplicateCache::with_max_len(
+                config.duplicate_cache_time(),
+                config.duplicate_cache_max_len(),
+            ),
             explicit_peers: HashSet::new(),
             blacklisted_peers: HashSet::new(),
             mesh: HashMap::new(),
@@ -790,6 +793,86 @@ where
         Ok(msg_id)
     }
 
+    /// Sends a message that was previously published by this node again. The message is sent to
+    /// the mesh (or fanout) and explicit peers of its topic, topped up with random peers on the
+    /// topic that are above the publish threshold until at least `mesh_n` peers are reached.
+    ///
+    /// This is intended for messages published whilst the mesh of their topic was
+    /// under-populated. Peers that already received the message will ignore it as a duplicate.
+    ///
+    /// Returns the number of peers the message was sent to.
+    pub fn republish(&mut self, msg_id: &MessageId) -> Result<usize, PublishError> {
+        let raw_message = self
+            .mcache
+            .get(msg_id)
+            .cloned()
+            .ok_or(PublishError::NotInCache)?;
+        let topic_hash = raw_message.topic.clone();
+
+        let mut recipient_peers: HashSet<PeerId> = self
+            .mesh
+            .get(&topic_hash)
+            .or_else(|| self.fanout.get(&topic_hash))
+            .cloned()
+            .unwrap_or_default()
+            .into_iter()
+            .collect();
+
+        recipient_peers.extend(
+            self.connected_peers
+                .iter()
+                .filter(|(peer_id, peer)| {
+                    peer.topics.contains(&topic_hash) && self.explicit_peers.contains(*peer_id)
+                })
+                .map(|(peer_id, _)| *peer_id),
+        );
+
+        let needed_extra_peers = self.config.mesh_n().saturating_sub(recipient_peers.len());
+        if needed_extra_peers > 0 {
+            let peer_list = get_random_peers(
+                &self.connected_peers,
+                &topic_hash,
+                needed_extra_peers,
+                |peer| {
+                    !recipient_peers.contains(peer)
+                        && !self
+                            .score_below_threshold(peer, |pst| pst.publish_threshold)
+                            .0
+                },
+            );
+            recipient_peers.extend(peer_list);
+        }
+
+        if recipient_peers.is_empty() {
+            return Err(PublishError::InsufficientPeers);
+        }
+
+        let mut sent = 0;
+        for peer_id in recipient_peers.iter() {
+            if let Some(peer) = self.connected_peers.get_mut(peer_id) {
+                match peer.sender.publish(
+                    raw_message.clone(),
+                    self.config.publish_queue_duration(),
+                    self.metrics.as_mut(),
+                ) {
+                    Ok(_) => sent += 1,
+                    Err(_) => {
+                        self.failed_messages.entry(*peer_id).or_default().priority += 1;
+                        tracing::warn!(peer_id=%peer_id, "Publish queue full. Could not republish to peer");
+                    }
+                }
+            }
+        }
+
+        if sent == 0 {
+            return Err(PublishError::AllQueuesFull(recipient_peers.len()));
+        }
+
+        tracing::debug!(message=%msg_id, peers=sent, "Republished message");
+
+        Ok(sent)
+    }
+
     /// This function should be called when [`Config::validate_messages()`] is `true` after
     /// the message got validated by the caller. Messages are stored in the ['Memcache'] and
     /// validation is expected to be fast enough that the messages should still exist in the cache.
@@ -2539,9 +2622,12 @@ where
         }
 
         tracing::debug!("Completed Heartbeat");
+        let duplicate_cache_evictions = self.duplicate_cache.take_evictions();
         if let Some(metrics) = self.metrics.as_mut() {
             let duration = u64::try_from(start.elapsed().as_millis()).unwrap_or(u64::MAX);
             metrics.observe_heartbeat_duration(duration);
+            metrics.set_duplicate_cache_len(self.duplicate_cache.len());
+            metrics.register_duplicate_cache_evictions(duplicate_cache_evictions);
         }
     }
 
@@ -756,6 +756,50 @@ fn test_publish_without_flood_publishing() {
     );
 }
 
+/// Test that a published message can be sent again to the mesh peers.
+#[test]
+fn test_republish() {
+    let config = ConfigBuilder::default()
+        .flood_publish(false)
+        .build()
+        .unwrap();
+
+    let publish_topic = String::from("test_republish");
+    let (mut gs, _, receivers, _) = inject_nodes1()
+        .peer_no(20)
+        .topics(vec![publish_topic.clone()])
+        .to_subscribe(true)
+        .gs_config(config)
+        .create_network();
+
+    let count_publishes = |receivers: &HashMap<PeerId, RpcReceiver>| {
+        receivers.values().fold(0, |mut count, c| {
+            let priority = c.priority.get_ref();
+            while !priority.is_empty() {
+                if let Ok(RpcOut::Publish { .. }) = priority.try_recv() {
+                    count += 1;
+                }
+            }
+            count
+        })
+    };
+
+    let msg_id = gs.publish(Topic::new(publish_topic), vec![0; 42]).unwrap();
+    assert_eq!(count_publishes(&receivers), gs.config.mesh_n());
+
+    assert_eq!(
+        gs.republish(&msg_id).unwrap(),
+        gs.config.mesh_n(),
+        "Should republish to the mesh peers"
+    );
+    assert_eq!(count_publishes(&receivers), gs.config.mesh_n());
+
+    assert!(matches!(
+        gs.republish(&MessageId::new(b"unknown")),
+        Err(PublishError::NotInCache)
+    ));
+}
+
 /// Test local node publish to unsubscribed topic
 #[test]
 fn test_fanout() {
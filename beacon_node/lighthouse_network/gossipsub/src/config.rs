@@ -76,6 +76,7 @@ pub struct Config {
     fanout_ttl: Duration,
     check_explicit_peers_ticks: u64,
     duplicate_cache_time: Duration,
+    duplicate_cache_max_len: Option<usize>,
     validate_messages: bool,
     message_id_fn: Arc<dyn Fn(&Message) -> MessageId + Send + Sync + 'static>,
     allow_self_origin: bool,
@@ -194,6 +195,13 @@ impl Config {
         self.duplicate_cache_time
     }
 
+    /// The maximum number of message ids stored in the duplicate cache. Once full, the oldest ids
+    /// are evicted before `duplicate_cache_time` has elapsed, so duplicates of them may be
+    /// received. The default is unbounded.
+    pub fn duplicate_cache_max_len(&self) -> Option<usize> {
+        self.duplicate_cache_max_len
+    }
+
     /// When set to `true`, prevents automatic forwarding of all received messages. This setting
     /// allows a user to validate the messages before propagating them to their peers. If set to
     /// true, the user must manually call [`crate::Behaviour::report_message_validation_result()`]
@@ -416,6 +424,7 @@ impl Default for ConfigBuilder {
                 fanout_ttl: Duration::from_secs(60),
                 check_explicit_peers_ticks: 300,
                 duplicate_cache_time: Duration::from_secs(60),
+                duplicate_cache_max_len: None,
                 validate_messages: false,
                 message_id_fn: Arc::new(|message| {
                     // default message id is: source + sequence number
@@ -621,6 +630,14 @@ impl ConfigBuilder {
         self
     }
 
+    /// The maximum number of message ids stored in the duplicate cache. Once full, the oldest ids
+    /// are evicted before `duplicate_cache_time` has elapsed, so duplicates of them may be
+    /// received. The default is unbounded.
+    pub fn duplicate_cache_max_len(&mut self, max_len: usize) -> &mut Self {
+        self.config.duplicate_cache_max_len = Some(max_len);
+        self
+    }
+
     /// When set, prevents automatic forwarding of all received messages. This setting
     /// allows a user to validate the messages before propagating them to their peers. If set,
     /// the user must manually call [`crate::Behaviour::report_message_validation_result()`] on the
@@ -879,6 +896,10 @@ impl ConfigBuilder {
             return Err(ConfigBuilderError::InvalidProtocol);
         }
 
+        if self.config.duplicate_cache_max_len == Some(0) {
+            return Err(ConfigBuilderError::DuplicateCacheMaxLenIsZero);
+        }
+
         Ok(self.config.clone())
     }
 }
@@ -899,6 +920,7 @@ impl std::fmt::Debug for Config {
         let _ = builder.field("heartbeat_interval", &self.heartbeat_interval);
         let _ = builder.field("fanout_ttl", &self.fanout_ttl);
         let _ = builder.field("duplicate_cache_time", &self.duplicate_cache_time);
+        let _ = builder.field("duplicate_cache_max_len", &self.duplicate_cache_max_len);
         let _ = builder.field("validate_messages", &self.validate_messages);
         let _ = builder.field("allow_self_origin", &self.allow_self_origin);
         let _ = builder.field("do_px", &self.do_px);
@@ -39,6 +39,8 @@ pub enum PublishError {
     /// Messages could not be sent because all queues for peers were full. The usize represents the
     /// number of peers that have full queues.
     AllQueuesFull(usize),
+    /// The message to be re-published is no longer in the message cache.
+    NotInCache,
 }
 
 impl std::fmt::Display for PublishError {
@@ -139,6 +141,8 @@ pub enum ConfigBuilderError {
     UnsubscribeBackoffIsZero,
     /// Invalid protocol
     InvalidProtocol,
+    /// duplicate_cache_max_len is zero
+    DuplicateCacheMaxLenIsZero,
 }
 
 impl std::error::Error for ConfigBuilderError {}
@@ -154,6 +158,7 @@ impl std::fmt::Display for ConfigBuilderError {
             Self::MeshOutboundInvalid => write!(f, "The inequality doesn't hold mesh_outbound_min <= self.config.mesh_n / 2"),
             Self::UnsubscribeBackoffIsZero => write!(f, "unsubscribe_backoff is zero"),
             Self::InvalidProtocol => write!(f, "Invalid protocol"),
+            Self::DuplicateCacheMaxLenIsZero => write!(f, "duplicate_cache_max_len is zero"),
         }
     }
 }
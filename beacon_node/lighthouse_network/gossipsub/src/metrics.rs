@@ -175,6 +175,11 @@ pub(crate) struct Metrics {
     /// message expires from the memcache before it can be validated, we count this a cache miss
     /// and it is an indicator that the memcache size should be increased.
     memcache_misses: Counter,
+    /// The number of message ids in the duplicate cache.
+    duplicate_cache_len: Gauge,
+    /// The number of message ids evicted from the duplicate cache before they expired because the
+    /// cache was full. Duplicates of these messages will not be recognised.
+    duplicate_cache_evictions: Counter,
     /// The number of times we have decided that an IWANT control message is required for this
     /// topic. A very high metric might indicate an underperforming network.
     topic_iwant_msgs: Family<TopicHash, Counter>,
@@ -379,6 +384,26 @@ impl Metrics {
             metric
         };
 
+        let duplicate_cache_len = {
+            let metric = Gauge::default();
+            registry.register(
+                "duplicate_cache_len",
+                "Number of message ids in the duplicate cache",
+                metric.clone(),
+            );
+            metric
+        };
+
+        let duplicate_cache_evictions = {
+            let metric = Counter::default();
+            registry.register(
+                "duplicate_cache_evictions",
+                "Number of message ids evicted from the full duplicate cache before expiring",
+                metric.clone(),
+            );
+            metric
+        };
+
         let priority_queue_size = Histogram::new(linear_buckets(0.0, 25.0, 100));
         registry.register(
             "priority_queue_size",
@@ -419,6 +444,8 @@ impl Metrics {
             peers_per_protocol,
             heartbeat_duration,
             memcache_misses,
+            duplicate_cache_len,
+            duplicate_cache_evictions,
             topic_iwant_msgs,
             idontwant_msgs,
             idontwant_bytes,
@@ -653,6 +680,16 @@ impl Metrics {
         self.heartbeat_duration.observe(millis as f64);
     }
 
+    /// Sets the number of message ids in the duplicate cache.
+    pub(crate) fn set_duplicate_cache_len(&mut self, len: usize) {
+        self.duplicate_cache_len.set(len as i64);
+    }
+
+    /// Registers message ids evicted from the duplicate cache before they expired.
+    pub(crate) fn register_duplicate_cache_evictions(&mut self, evictions: usize) {
+        self.duplicate_cache_evictions.inc_by(evictions as u64);
+    }
+
     /// Observes a priority queue size.
     pub(crate) fn observe_priority_queue_size(&mut self, len: usize) {
         self.priority_queue_size.observe(len as f64);
@@ -143,6 +143,23 @@ where
         }
     }
 
+    /// Removes the entry which expires soonest, returning `false` if the cache is empty.
+    fn remove_oldest(&mut self) -> bool {
+        while let Some(element) = self.list.pop_front() {
+            if let Occupied(entry) = self.map.entry(element.element) {
+                if entry.get().expires == element.expires {
+                    entry.remove();
+                    return true;
+                }
+            }
+        }
+        false
+    }
+
+    pub(crate) fn len(&self) -> usize {
+        self.map.len()
+    }
+
     /// Empties the entire cache.
     #[cfg(test)]
     pub(crate) fn clear(&mut self) {
@@ -155,14 +172,30 @@ where
     }
 }
 
-pub(crate) struct DuplicateCache<Key>(TimeCache<Key, ()>);
+pub(crate) struct DuplicateCache<Key> {
+    cache: TimeCache<Key, ()>,
+    /// The maximum number of entries, beyond which the oldest entries are evicted before they
+    /// expire.
+    max_len: Option<usize>,
+    /// The number of entries evicted before they expired since the last call to
+    /// `take_evictions`.
+    evictions: usize,
+}
 
 impl<Key> DuplicateCache<Key>
 where
     Key: Eq + std::hash::Hash + Clone,
 {
     pub(crate) fn new(ttl: Duration) -> Self {
-        Self(TimeCache::new(ttl))
+        Self::with_max_len(ttl, None)
+    }
+
+    pub(crate) fn with_max_len(ttl: Duration, max_len: Option<usize>) -> Self {
+        Self {
+            cache: TimeCache::new(ttl),
+            max_len,
+            evictions: 0,
+        }
     }
 
     // Inserts new elements and removes any expired elements.
@@ -170,8 +203,13 @@ where
     // If the key was not present this returns `true`. If the value was already present this
     // returns `false`.
     pub(crate) fn insert(&mut self, key: Key) -> bool {
-        if let Entry::Vacant(entry) = self.0.entry(key) {
+        if let Entry::Vacant(entry) = self.cache.entry(key) {
             entry.insert(());
+            if let Some(max_len) = self.max_len {
+                while self.cache.len() > max_len && self.cache.remove_oldest() {
+                    self.evictions += 1;
+                }
+            }
             true
         } else {
             false
@@ -179,7 +217,16 @@ where
     }
 
     pub(crate) fn contains(&self, key: &Key) -> bool {
-        self.0.contains_key(key)
+        self.cache.contains_key(key)
+    }
+
+    pub(crate) fn len(&self) -> usize {
+        self.cache.len()
+    }
+
+    /// Returns the number of entries evicted before they expired since the last call.
+    pub(crate) fn take_evictions(&mut self) -> usize {
+        std::mem::take(&mut self.evictions)
     }
 }
 
@@ -199,6 +246,22 @@ mod test {
         assert!(!cache.insert("e"));
     }
 
+    #[test]
+    fn cache_evicts_oldest_beyond_max_len() {
+        let mut cache = DuplicateCache::with_max_len(Duration::from_secs(10), Some(2));
+
+        cache.insert("t");
+        cache.insert("e");
+        cache.insert("s");
+
+        assert_eq!(cache.len(), 2);
+        assert_eq!(cache.take_evictions(), 1);
+        assert_eq!(cache.take_evictions(), 0);
+        assert!(!cache.contains(&"t"));
+        assert!(cache.contains(&"e"));
+        assert!(cache.contains(&"s"));
+    }
+
     #[test]
     fn cache_entries_expire() {
         let mut cache = DuplicateCache::new(Duration::from_millis(100));
@@ -1,5 +1,7 @@
-use crate::listen_addr::{ListenAddr, ListenAddress};
+use crate::discovery::EnrTree;
+use crate::listen_addr::{AdditionalListenAddr, ListenAddr, ListenAddress};
 use crate::rpc::config::{InboundRateLimiterConfig, OutboundRateLimiterConfig};
+use crate::service::PublishRateLimiterConfig;
 use crate::types::GossipKind;
 use crate::{Enr, PeerIdSerialized};
 use directory::{
@@ -9,17 +11,19 @@ use libp2p::Multiaddr;
 use serde::{Deserialize, Serialize};
 use sha2::{Digest, Sha256};
 use std::net::{Ipv4Addr, Ipv6Addr};
-use std::num::NonZeroU16;
+use std::num::{NonZeroU16, NonZeroU64};
 use std::path::PathBuf;
 use std::sync::Arc;
 use std::time::Duration;
-use types::{ForkContext, ForkName};
+use types::{ChainSpec, ForkContext, ForkName, SubnetId};
 
 pub const DEFAULT_IPV4_ADDRESS: Ipv4Addr = Ipv4Addr::UNSPECIFIED;
 pub const DEFAULT_TCP_PORT: u16 = 9000u16;
 pub const DEFAULT_DISC_PORT: u16 = 9000u16;
 pub const DEFAULT_QUIC_PORT: u16 = 9001u16;
 pub const DEFAULT_IDONTWANT_MESSAGE_SIZE_THRESHOLD: usize = 1000usize;
+pub const DEFAULT_GOSSIP_PUBLISH_RETRY_MIN_MESH_PEERS: usize = 4usize;
+pub const DEFAULT_GOSSIP_PUBLISH_RETRY_DELAY: Duration = Duration::from_millis(500);
 
 /// The maximum size of gossip messages.
 pub fn gossip_max_size(is_merge_enabled: bool, gossip_max_size: usize) -> usize {
@@ -33,6 +37,9 @@ pub fn gossip_max_size(is_merge_enabled: bool, gossip_max_size: usize) -> usize
 pub struct GossipsubConfigParams {
     pub message_domain_valid_snappy: [u8; 4],
     pub gossip_max_size: usize,
+    /// Overrides the default duplicate cache time of two epochs.
+    pub duplicate_cache_time: Option<Duration>,
+    pub duplicate_cache_max_len: Option<usize>,
 }
 
 #[derive(Clone, Debug, Serialize, Deserialize)]
@@ -42,9 +49,20 @@ pub struct Config {
     /// Data directory where node's keyfile is stored
     pub network_dir: PathBuf,
 
+    /// A network key to rotate to. It replaces the key in `network_dir` as soon as it is seen.
+    pub network_key_file: Option<PathBuf>,
+
+    /// How long the ENR of the previous network key is still announced after rotating to a new
+    /// `network_key_file`.
+    pub network_key_rotation_grace_period: Duration,
+
     /// IP addresses to listen on.
     pub(crate) listen_addresses: ListenAddress,
 
+    /// Further addresses libp2p listens on, e.g. on other interfaces of a multi-homed host.
+    /// Discovery and the ENR only use `listen_addresses`.
+    pub(crate) additional_listen_addresses: Vec<AdditionalListenAddr>,
+
     /// The address to broadcast to peers about which address we are listening on. None indicates
     /// that no discovery address has been set in the CLI args.
     pub enr_address: (Option<Ipv4Addr>, Option<Ipv6Addr>),
@@ -80,6 +98,9 @@ pub struct Config {
     /// List of nodes to initially connect to, on Multiaddr format.
     pub boot_nodes_multiaddr: Vec<Multiaddr>,
 
+    /// List of DNS ENR trees which are periodically resolved for nodes to connect to.
+    pub discovery_dns_trees: Vec<EnrTree>,
+
     /// List of libp2p nodes to initially connect to.
     pub libp2p_nodes: Vec<Multiaddr>,
 
@@ -89,6 +110,9 @@ pub struct Config {
     /// Disables peer scoring altogether.
     pub disable_peer_scoring: bool,
 
+    /// When pruning excess peers, prefer to disconnect peers running the most common clients.
+    pub preserve_client_diversity: bool,
+
     /// Client version
     pub client_version: String,
 
@@ -98,6 +122,13 @@ pub struct Config {
     /// Disables quic support.
     pub disable_quic_support: bool,
 
+    /// Do not listen for inbound libp2p connections, or advertise TCP and QUIC ports in the ENR.
+    /// Peers are only connected to by dialing them.
+    pub disable_inbound: bool,
+
+    /// The DSCP value to mark outgoing packets on libp2p TCP connections with.
+    pub dscp: Option<u8>,
+
     /// Attempt to construct external port mappings with UPnP.
     pub upnp_enabled: bool,
 
@@ -107,6 +138,9 @@ pub struct Config {
     /// Subscribe to all subnets for the duration of the runtime.
     pub subscribe_all_subnets: bool,
 
+    /// Long-lived attestation subnets to subscribe to instead of those computed from the node ID.
+    pub long_lived_subnets_override: Option<Vec<SubnetId>>,
+
     /// Import/aggregate all attestations received on subscribed subnets for the duration of the
     /// runtime.
     pub import_all_attestations: bool,
@@ -137,6 +171,10 @@ pub struct Config {
     /// Configuration for the outbound rate limiter (requests made by this node).
     pub outbound_rate_limiter_config: Option<OutboundRateLimiterConfig>,
 
+    /// Configuration for the rate limiter of gossip messages published by this node. Publishing
+    /// is not limited when `None`.
+    pub publish_rate_limiter_config: Option<PublishRateLimiterConfig>,
+
     /// Configures if/where invalid blocks should be stored.
     pub invalid_block_storage: Option<PathBuf>,
 
@@ -146,6 +184,25 @@ pub struct Config {
     /// Configuration for the minimum message size for which IDONTWANT messages are send in the mesh.
     /// Lower the value reduces the optimization effect of the IDONTWANT messages.
     pub idontwant_message_size_threshold: usize,
+
+    /// How long gossip message ids are remembered in order to filter duplicates. Defaults to two
+    /// epochs when `None`.
+    pub gossip_duplicate_cache_time: Option<Duration>,
+
+    /// The maximum number of gossip message ids remembered in order to filter duplicates. The
+    /// oldest ids are evicted beyond this limit. Unbounded when `None`.
+    pub gossip_duplicate_cache_max_len: Option<usize>,
+
+    /// The aggregate bandwidth in megabits per second shared by gossip publishing and backfill
+    /// sync. Backfill is deferred to keep within this cap. Unlimited when `None`.
+    pub max_bandwidth_mbps: Option<NonZeroU64>,
+
+    /// Blocks, blobs and data columns published whilst the mesh of their topic has fewer than this
+    /// many peers are re-published after `gossip_publish_retry_delay`. Disabled when `0`.
+    pub gossip_publish_retry_min_mesh_peers: usize,
+
+    /// The delay before re-publishing a message sent to an under-populated mesh.
+    pub gossip_publish_retry_delay: Duration,
 }
 
 impl Config {
@@ -268,6 +325,45 @@ impl Config {
     pub fn listen_addrs(&self) -> &ListenAddress {
         &self.listen_addresses
     }
+
+    /// Sets the addresses libp2p listens on in addition to the primary listening addresses.
+    pub fn set_additional_listening_addrs(&mut self, addrs: Vec<AdditionalListenAddr>) {
+        self.additional_listen_addresses = addrs;
+    }
+
+    pub fn additional_listen_addrs(&self) -> &[AdditionalListenAddr] {
+        &self.additional_listen_addresses
+    }
+
+    /// Returns all the addresses the Swarm will listen on, starting with the primary addresses.
+    pub fn libp2p_listen_addresses(&self) -> impl Iterator<Item = Multiaddr> + '_ {
+        self.listen_addresses.libp2p_addresses().chain(
+            self.additional_listen_addresses
+                .iter()
+                .flat_map(AdditionalListenAddr::libp2p_addresses),
+        )
+    }
+
+    /// Returns the attestation subnets which a node with the given `node_id` subscribes to for
+    /// the duration of its runtime.
+    ///
+    /// These are computed from the node ID unless all subnets are subscribed to, or unless the
+    /// subnets are overridden.
+    pub fn long_lived_attestation_subnets(
+        &self,
+        node_id: [u8; 32],
+        spec: &ChainSpec,
+    ) -> Vec<SubnetId> {
+        if self.subscribe_all_subnets {
+            (0..spec.attestation_subnet_count)
+                .map(SubnetId::new)
+                .collect()
+        } else if let Some(subnets) = &self.long_lived_subnets_override {
+            subnets.clone()
+        } else {
+            SubnetId::compute_attestation_subnets(node_id, spec).collect()
+        }
+    }
 }
 
 impl Default for Config {
@@ -325,7 +421,10 @@ impl Default for Config {
         // NOTE: Some of these get overridden by the corresponding CLI default values.
         Config {
             network_dir,
+            network_key_file: None,
+            network_key_rotation_grace_period: Duration::from_secs(3600),
             listen_addresses,
+            additional_listen_addresses: vec![],
             enr_address: (None, None),
             enr_udp4_port: None,
             enr_quic4_port: None,
@@ -337,17 +436,22 @@ impl Default for Config {
             discv5_config,
             boot_nodes_enr: vec![],
             boot_nodes_multiaddr: vec![],
+            discovery_dns_trees: vec![],
             libp2p_nodes: vec![],
             trusted_peers: vec![],
             disable_peer_scoring: false,
+            preserve_client_diversity: false,
             client_version: lighthouse_version::version_with_platform(),
             disable_discovery: false,
             disable_quic_support: false,
+            disable_inbound: false,
+            dscp: None,
             upnp_enabled: true,
             network_load: 4,
             private: false,
             subscribe_all_data_column_subnets: false,
             subscribe_all_subnets: false,
+            long_lived_subnets_override: None,
             import_all_attestations: false,
             shutdown_after_sync: false,
             topics: Vec::new(),
@@ -355,9 +459,15 @@ impl Default for Config {
             metrics_enabled: false,
             enable_light_client_server: false,
             outbound_rate_limiter_config: None,
+            publish_rate_limiter_config: None,
             invalid_block_storage: None,
             inbound_rate_limiter_config: None,
             idontwant_message_size_threshold: DEFAULT_IDONTWANT_MESSAGE_SIZE_THRESHOLD,
+            gossip_duplicate_cache_time: None,
+            gossip_duplicate_cache_max_len: None,
+            max_bandwidth_mbps: None,
+            gossip_publish_retry_min_mesh_peers: DEFAULT_GOSSIP_PUBLISH_RETRY_MIN_MESH_PEERS,
+            gossip_publish_retry_delay: DEFAULT_GOSSIP_PUBLISH_RETRY_DELAY,
         }
     }
 }
@@ -482,9 +592,12 @@ pub fn gossipsub_config(
     // To accommodate the increase, we should increase the duplicate cache time to filter older seen messages.
     // 2 epochs is quite sane for pre-deneb network parameters as well.
     // Hence we keep the same parameters for pre-deneb networks as well to avoid switching at the fork.
-    let duplicate_cache_time = Duration::from_secs(slots_per_epoch * seconds_per_slot * 2);
+    let duplicate_cache_time = gossipsub_config_params
+        .duplicate_cache_time
+        .unwrap_or_else(|| Duration::from_secs(slots_per_epoch * seconds_per_slot * 2));
 
-    gossipsub::ConfigBuilder::default()
+    let mut builder = gossipsub::ConfigBuilder::default();
+    builder
         .max_transmit_size(gossip_max_size(
             is_bellatrix_enabled,
             gossipsub_config_params.gossip_max_size,
@@ -505,15 +618,19 @@ pub fn gossipsub_config(
         .duplicate_cache_time(duplicate_cache_time)
         .message_id_fn(gossip_message_id)
         .allow_self_origin(true)
-        .idontwant_message_size_threshold(idontwant_message_size_threshold)
-        .build()
-        .expect("valid gossipsub configuration")
+        .idontwant_message_size_threshold(idontwant_message_size_threshold);
+
+    if let Some(max_len) = gossipsub_config_params.duplicate_cache_max_len {
+        builder.duplicate_cache_max_len(max_len);
+    }
+
+    builder.build().expect("valid gossipsub configuration")
 }
 
 /// Helper function to determine if the IpAddr is a global address or not. The `is_global()`
 /// function is not yet stable on IpAddr.
 #[allow(clippy::nonminimal_bool)]
-fn is_global_ipv4(addr: &Ipv4Addr) -> bool {
+pub(crate) fn is_global_ipv4(addr: &Ipv4Addr) -> bool {
     // check if this address is 192.0.0.9 or 192.0.0.10. These addresses are the only two
     // globally routable addresses in the 192.0.0.0/24 range.
     if u32::from_be_bytes(addr.octets()) == 0xc0000009
@@ -0,0 +1,443 @@
+//! Resolution of node lists published as signed DNS ENR trees, as specified by EIP-1459.
+//!
+//! A tree is identified by a URL of the form `enrtree://<public key>@<domain>`. The TXT record at
+//! `<domain>` is the signed root of the tree, which references a subtree of ENRs and a subtree of
+//! links to other trees. Every other record is stored at a subdomain named after the hash of its
+//! contents, so the whole tree is authenticated by the root signature.
+use crate::Enr;
+use data_encoding::{BASE32_NOPAD, BASE64URL_NOPAD};
+use discv5::enr::k256::ecdsa::VerifyingKey;
+use discv5::enr::EnrPublicKey;
+use futures::future::BoxFuture;
+use futures::FutureExt;
+use hickory_resolver::config::{ResolverConfig, ResolverOpts};
+use hickory_resolver::TokioAsyncResolver;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use slog::{debug, warn};
+use std::collections::HashSet;
+use std::fmt;
+use std::future::Future;
+use std::str::FromStr;
+use std::task::{ready, Context, Poll};
+use std::time::Duration;
+use tiny_keccak::{Hasher, Keccak};
+use tokio::time::{Interval, MissedTickBehavior};
+
+/// How often the DNS trees are resolved.
+const DNS_TREE_RESOLVE_INTERVAL: Duration = Duration::from_secs(30 * 60);
+/// The maximum number of records resolved from a single tree, including the trees it links to.
+const MAX_TREE_RECORDS: usize = 10_000;
+
+const ROOT_PREFIX: &str = "enrtree-root:v1";
+const BRANCH_PREFIX: &str = "enrtree-branch:";
+const LINK_PREFIX: &str = "enrtree://";
+const ENR_PREFIX: &str = "enr:";
+
+/// The location of a DNS ENR tree and the key which signs it.
+#[derive(Clone, Debug, PartialEq)]
+pub struct EnrTree {
+    public_key: VerifyingKey,
+    domain: String,
+}
+
+impl FromStr for EnrTree {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (key, domain) = s
+            .strip_prefix(LINK_PREFIX)
+            .and_then(|url| url.split_once('@'))
+            .ok_or_else(|| format!("Expected enrtree://<public key>@<domain>, got: {}", s))?;
+        let key_bytes = BASE32_NOPAD
+            .decode(key.to_ascii_uppercase().as_bytes())
+            .map_err(|e| format!("Invalid DNS tree public key {}: {}", key, e))?;
+        let public_key = VerifyingKey::from_sec1_bytes(&key_bytes)
+            .map_err(|e| format!("Invalid DNS tree public key {}: {}", key, e))?;
+        if domain.is_empty() {
+            return Err(format!("Missing DNS tree domain: {}", s));
+        }
+        Ok(EnrTree {
+            public_key,
+            domain: domain.to_string(),
+        })
+    }
+}
+
+impl fmt::Display for EnrTree {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}{}@{}",
+            LINK_PREFIX,
+            BASE32_NOPAD.encode(self.public_key.encode().as_ref()),
+            self.domain
+        )
+    }
+}
+
+impl Serialize for EnrTree {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&self.to_string())
+    }
+}
+
+impl<'de> Deserialize<'de> for EnrTree {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let s = String::deserialize(deserializer)?;
+        s.parse().map_err(serde::de::Error::custom)
+    }
+}
+
+/// The hashes of the subtrees referenced by a verified root record.
+struct Root {
+    enr_root: String,
+    link_root: String,
+}
+
+/// A record below the root of a tree.
+enum Entry {
+    Branch(Vec<String>),
+    Enr(Box<Enr>),
+    Link(EnrTree),
+}
+
+/// Parse the root record of a tree, checking that it is signed by `public_key`.
+fn parse_root(text: &str, public_key: &VerifyingKey) -> Result<Root, String> {
+    let (signed, signature) = text
+        .rsplit_once(" sig=")
+        .ok_or_else(|| format!("Missing signature in DNS tree root: {}", text))?;
+
+    let mut fields = signed.split_whitespace();
+    if fields.next() != Some(ROOT_PREFIX) {
+        return Err(format!("Invalid DNS tree root: {}", text));
+    }
+    let mut enr_root = None;
+    let mut link_root = None;
+    for field in fields {
+        match field.split_once('=') {
+            Some(("e", hash)) => enr_root = Some(hash.to_string()),
+            Some(("l", hash)) => link_root = Some(hash.to_string()),
+            // The sequence number only identifies updates, the whole tree is always resolved.
+            Some(("seq", _)) => {}
+            _ => return Err(format!("Invalid DNS tree root: {}", text)),
+        }
+    }
+
+    // The signature is followed by a recovery id, which is not needed to verify it.
+    let signature = BASE64URL_NOPAD
+        .decode(signature.trim().as_bytes())
+        .map_err(|e| format!("Invalid DNS tree root signature: {}", e))?;
+    if signature.len() != 65 || !public_key.verify_v4(signed.as_bytes(), &signature[..64]) {
+        return Err(format!("Invalid DNS tree root signature: {}", text));
+    }
+
+    match (enr_root, link_root) {
+        (Some(enr_root), Some(link_root)) => Ok(Root {
+            enr_root,
+            link_root,
+        }),
+        _ => Err(format!("Incomplete DNS tree root: {}", text)),
+    }
+}
+
+fn parse_entry(text: &str) -> Result<Entry, String> {
+    if let Some(children) = text.strip_prefix(BRANCH_PREFIX) {
+        Ok(Entry::Branch(
+            children
+                .split(',')
+                .map(str::trim)
+                .filter(|hash| !hash.is_empty())
+                .map(str::to_string)
+                .collect(),
+        ))
+    } else if text.starts_with(ENR_PREFIX) {
+        Ok(Entry::Enr(Box::new(text.parse()?)))
+    } else if text.starts_with(LINK_PREFIX) {
+        Ok(Entry::Link(text.parse()?))
+    } else {
+        Err(format!("Unknown DNS tree record: {}", text))
+    }
+}
+
+/// The subdomain name of a record: the base32 encoding of the first 16 bytes of its hash.
+fn record_hash(text: &str) -> String {
+    let mut output = [0_u8; 32];
+    let mut hasher = Keccak::v256();
+    hasher.update(text.as_bytes());
+    hasher.finalize(&mut output);
+    BASE32_NOPAD.encode(&output[..16])
+}
+
+/// Resolve all ENRs in `tree` and in the trees it links to, using `lookup` to read the TXT
+/// record at a DNS name.
+///
+/// Any invalid or unexpected record fails the whole resolution, since it indicates a broken or
+/// tampered tree.
+pub(crate) async fn resolve_tree<F, Fut>(tree: &EnrTree, lookup: F) -> Result<Vec<Enr>, String>
+where
+    F: Fn(String) -> Fut,
+    Fut: Future<Output = Result<String, String>>,
+{
+    let mut enrs = vec![];
+    let mut trees = vec![tree.clone()];
+    let mut visited = HashSet::from([tree.domain.clone()]);
+    let mut records = 0;
+
+    while let Some(tree) = trees.pop() {
+        let root = parse_root(&lookup(tree.domain.clone()).await?, &tree.public_key)?;
+
+        // The ENR subtree may only contain ENRs, and the link subtree only links.
+        let mut pending = vec![(root.enr_root, false), (root.link_root, true)];
+        while let Some((hash, is_link_subtree)) = pending.pop() {
+            records += 1;
+            if records > MAX_TREE_RECORDS {
+                return Err(format!(
+                    "DNS tree {} exceeds {} records",
+                    tree, MAX_TREE_RECORDS
+                ));
+            }
+
+            let text = lookup(format!("{}.{}", hash, tree.domain)).await?;
+            if !record_hash(&text).eq_ignore_ascii_case(&hash) {
+                return Err(format!("DNS tree record {} does not match its hash", hash));
+            }
+            match parse_entry(&text)? {
+                Entry::Branch(children) => {
+                    pending.extend(children.into_iter().map(|child| (child, is_link_subtree)))
+                }
+                Entry::Enr(enr) if !is_link_subtree => enrs.push(*enr),
+                Entry::Link(link) if is_link_subtree => {
+                    if visited.insert(link.domain.clone()) {
+                        trees.push(link);
+                    }
+                }
+                _ => return Err(format!("Unexpected record in DNS tree {}: {}", tree, text)),
+            }
+        }
+    }
+    Ok(enrs)
+}
+
+/// Read the TXT record at `name`, joining the strings it is split into.
+async fn txt_record(resolver: &TokioAsyncResolver, name: String) -> Result<String, String> {
+    let lookup = resolver
+        .txt_lookup(name.clone())
+        .await
+        .map_err(|e| format!("TXT lookup of {} failed: {}", name, e))?;
+    let record = lookup
+        .iter()
+        .next()
+        .ok_or_else(|| format!("No TXT record at {}", name))?;
+    let bytes = record
+        .txt_data()
+        .iter()
+        .flat_map(|chunk| chunk.iter().copied())
+        .collect::<Vec<_>>();
+    String::from_utf8(bytes).map_err(|_| format!("TXT record at {} is not UTF-8", name))
+}
+
+/// Periodically resolves DNS ENR trees.
+pub(crate) struct DnsDiscovery {
+    trees: Vec<EnrTree>,
+    resolver: TokioAsyncResolver,
+    /// Fires when the trees should next be resolved, starting immediately.
+    interval: Interval,
+    /// The resolution in progress.
+    query: Option<BoxFuture<'static, Vec<Enr>>>,
+    log: slog::Logger,
+}
+
+impl DnsDiscovery {
+    /// NOTE: Creating the resolver requires running within a tokio execution environment.
+    pub fn new(trees: Vec<EnrTree>, log: &slog::Logger) -> Self {
+        let resolver = TokioAsyncResolver::tokio_from_system_conf().unwrap_or_else(|e| {
+            warn!(log, "Failed to read the system DNS configuration"; "error" => %e);
+            TokioAsyncResolver::tokio(ResolverConfig::default(), ResolverOpts::default())
+        });
+        let mut interval = tokio::time::interval(DNS_TREE_RESOLVE_INTERVAL);
+        interval.set_missed_tick_behavior(MissedTickBehavior::Delay);
+
+        DnsDiscovery {
+            trees,
+            resolver,
+            interval,
+            query: None,
+            log: log.clone(),
+        }
+    }
+
+    fn resolve_trees(&self) -> BoxFuture<'static, Vec<Enr>> {
+        let trees = self.trees.clone();
+        let resolver = self.resolver.clone();
+        let log = self.log.clone();
+        async move {
+            let mut enrs = vec![];
+            for tree in trees {
+                let lookup = |name| txt_record(&resolver, name);
+                match resolve_tree(&tree, lookup).await {
+                    Ok(tree_enrs) => {
+                        debug!(log, "Resolved DNS ENR tree"; "tree" => %tree, "enrs" => tree_enrs.len());
+                        enrs.extend(tree_enrs);
+                    }
+                    Err(e) => {
+                        warn!(log, "Failed to resolve DNS ENR tree"; "tree" => %tree, "error" => e)
+                    }
+                }
+            }
+            enrs
+        }
+        .boxed()
+    }
+
+    /// Returns the ENRs found each time the trees have been resolved.
+    pub fn poll(&mut self, cx: &mut Context) -> Poll<Vec<Enr>> {
+        loop {
+            if let Some(query) = self.query.as_mut() {
+                let enrs = ready!(query.poll_unpin(cx));
+                self.query = None;
+                return Poll::Ready(enrs);
+            }
+            ready!(self.interval.poll_tick(cx));
+            self.query = Some(self.resolve_trees());
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::discovery::CombinedKey;
+    use discv5::enr::k256::ecdsa::SigningKey;
+    use discv5::enr::EnrKey;
+    use std::collections::HashMap;
+
+    /// Builds the records of a DNS tree in memory.
+    struct TreeBuilder {
+        signing_key: SigningKey,
+        domain: String,
+        records: HashMap<String, String>,
+    }
+
+    impl TreeBuilder {
+        fn new(key_byte: u8, domain: &str) -> Self {
+            TreeBuilder {
+                signing_key: SigningKey::from_slice(&[key_byte; 32]).unwrap(),
+                domain: domain.to_string(),
+                records: HashMap::new(),
+            }
+        }
+
+        fn url(&self) -> EnrTree {
+            EnrTree {
+                public_key: *self.signing_key.verifying_key(),
+                domain: self.domain.clone(),
+            }
+        }
+
+        /// Adds a record and returns its hash.
+        fn add(&mut self, text: String) -> String {
+            let hash = record_hash(&text);
+            self.records
+                .insert(format!("{}.{}", hash, self.domain), text);
+            hash
+        }
+
+        fn add_root(&mut self, enr_root: &str, link_root: &str, signing_key: &SigningKey) {
+            let signed = format!("{} e={} l={} seq=1", ROOT_PREFIX, enr_root, link_root);
+            let mut signature = signing_key.sign_v4(signed.as_bytes()).unwrap();
+            signature.push(0);
+            self.records.insert(
+                self.domain.clone(),
+                format!("{} sig={}", signed, BASE64URL_NOPAD.encode(&signature)),
+            );
+        }
+    }
+
+    fn random_enr() -> Enr {
+        Enr::builder()
+            .build(&CombinedKey::generate_secp256k1())
+            .unwrap()
+    }
+
+    async fn resolve(
+        tree: &EnrTree,
+        records: &HashMap<String, String>,
+    ) -> Result<Vec<Enr>, String> {
+        resolve_tree(tree, |name| {
+            futures::future::ready(
+                records
+                    .get(&name)
+                    .cloned()
+                    .ok_or_else(|| format!("No TXT record at {}", name)),
+            )
+        })
+        .await
+    }
+
+    /// Builds a tree with two ENRs which links to a tree with one ENR.
+    fn linked_trees() -> (TreeBuilder, Vec<Enr>) {
+        let enrs = vec![random_enr(), random_enr(), random_enr()];
+
+        let mut linked = TreeBuilder::new(2, "linked.example.org");
+        let enr_root = linked.add(enrs[2].to_base64());
+        let link_root = linked.add(BRANCH_PREFIX.to_string());
+        let signing_key = linked.signing_key.clone();
+        linked.add_root(&enr_root, &link_root, &signing_key);
+
+        let mut tree = TreeBuilder::new(1, "nodes.example.org");
+        let first = tree.add(enrs[0].to_base64());
+        let second = tree.add(enrs[1].to_base64());
+        let enr_root = tree.add(format!("{}{},{}", BRANCH_PREFIX, first, second));
+        let link_root = tree.add(linked.url().to_string());
+        let signing_key = tree.signing_key.clone();
+        tree.add_root(&enr_root, &link_root, &signing_key);
+        tree.records.extend(linked.records);
+
+        (tree, enrs)
+    }
+
+    #[test]
+    fn enr_tree_url_inverse() {
+        let tree = TreeBuilder::new(1, "nodes.example.org").url();
+        assert_eq!(tree.to_string().parse::<EnrTree>(), Ok(tree));
+        assert!("enrtree://nodes.example.org".parse::<EnrTree>().is_err());
+        assert!("enrtree://AAAA@nodes.example.org"
+            .parse::<EnrTree>()
+            .is_err());
+    }
+
+    #[tokio::test]
+    async fn resolve_linked_trees() {
+        let (tree, enrs) = linked_trees();
+        let mut resolved = resolve(&tree.url(), &tree.records).await.unwrap();
+        resolved.sort_by_key(|enr| enr.node_id());
+        let mut expected = enrs;
+        expected.sort_by_key(|enr| enr.node_id());
+        assert_eq!(resolved, expected);
+    }
+
+    #[tokio::test]
+    async fn reject_invalid_root_signature() {
+        let (mut tree, _) = linked_trees();
+        let Root {
+            enr_root,
+            link_root,
+        } = parse_root(&tree.records[&tree.domain], &tree.url().public_key).unwrap();
+        let other_key = SigningKey::from_slice(&[3; 32]).unwrap();
+        tree.add_root(&enr_root, &link_root, &other_key);
+        assert!(resolve(&tree.url(), &tree.records).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn reject_tampered_record() {
+        let (mut tree, _) = linked_trees();
+        let leaf = tree
+            .records
+            .iter()
+            .find(|(name, text)| name.ends_with(&tree.domain) && text.starts_with(ENR_PREFIX))
+            .map(|(name, _)| name.clone())
+            .unwrap();
+        tree.records.insert(leaf, random_enr().to_base64());
+        assert!(resolve(&tree.url(), &tree.records).await.is_err());
+    }
+}
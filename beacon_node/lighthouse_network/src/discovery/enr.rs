@@ -90,6 +90,41 @@ impl Eth2Enr for Enr {
     }
 }
 
+/// Checks that the `EnrForkId` advertised by a peer is compatible with the local `EnrForkId`.
+///
+/// ENRs are only refreshed periodically, so around a fork boundary a peer may still advertise
+/// the previous fork or already advertise the next one. Its fork digest must therefore be one of
+/// `accepted_fork_digests`, which should contain the local, previous and next fork digests. If
+/// both nodes are on the same fork and have scheduled a fork at the same epoch they must also
+/// agree on its version, otherwise they will diverge at that epoch.
+pub fn check_enr_fork_id(
+    peer: &EnrForkId,
+    local: &EnrForkId,
+    accepted_fork_digests: &[[u8; 4]],
+) -> Result<(), String> {
+    if peer.fork_digest != local.fork_digest {
+        if accepted_fork_digests.contains(&peer.fork_digest) {
+            return Ok(());
+        }
+        return Err(format!(
+            "fork digest 0x{} does not match local fork digest 0x{}",
+            hex::encode(peer.fork_digest),
+            hex::encode(local.fork_digest)
+        ));
+    }
+    if peer.next_fork_epoch == local.next_fork_epoch
+        && peer.next_fork_version != local.next_fork_version
+    {
+        return Err(format!(
+            "next fork version 0x{} at epoch {} does not match local next fork version 0x{}",
+            hex::encode(peer.next_fork_version),
+            peer.next_fork_epoch,
+            hex::encode(local.next_fork_version)
+        ));
+    }
+    Ok(())
+}
+
 /// Either use the given ENR or load an ENR from file if it exists and matches the current NodeId
 /// and sequence number.
 /// If an ENR exists, with the same NodeId, this function checks to see if the loaded ENR from
@@ -188,52 +223,56 @@ pub fn build_enr<E: EthSpec>(
         builder.udp6(udp6_port.get());
     }
 
-    // Add QUIC fields to the ENR.
-    // Since QUIC is used as an alternative transport for the libp2p protocols,
-    // the related fields should only be added when both QUIC and libp2p are enabled
-    if !config.disable_quic_support {
-        // If we are listening on ipv4, add the quic ipv4 port.
-        if let Some(quic4_port) = config.enr_quic4_port.or_else(|| {
+    // Without inbound connections there are no libp2p ports to advertise, and advertising them
+    // would get the node penalized by peers which fail to dial it.
+    if !config.disable_inbound {
+        // Add QUIC fields to the ENR.
+        // Since QUIC is used as an alternative transport for the libp2p protocols,
+        // the related fields should only be added when both QUIC and libp2p are enabled
+        if !config.disable_quic_support {
+            // If we are listening on ipv4, add the quic ipv4 port.
+            if let Some(quic4_port) = config.enr_quic4_port.or_else(|| {
+                config
+                    .listen_addrs()
+                    .v4()
+                    .and_then(|v4_addr| v4_addr.quic_port.try_into().ok())
+            }) {
+                builder.add_value(QUIC_ENR_KEY, &quic4_port.get());
+            }
+
+            // If we are listening on ipv6, add the quic ipv6 port.
+            if let Some(quic6_port) = config.enr_quic6_port.or_else(|| {
+                config
+                    .listen_addrs()
+                    .v6()
+                    .and_then(|v6_addr| v6_addr.quic_port.try_into().ok())
+            }) {
+                builder.add_value(QUIC6_ENR_KEY, &quic6_port.get());
+            }
+        }
+
+        // If the ENR port is not set, and we are listening over that ip version, use the listening port instead.
+        let tcp4_port = config.enr_tcp4_port.or_else(|| {
             config
                 .listen_addrs()
                 .v4()
-                .and_then(|v4_addr| v4_addr.quic_port.try_into().ok())
-        }) {
-            builder.add_value(QUIC_ENR_KEY, &quic4_port.get());
+                .and_then(|v4_addr| v4_addr.tcp_port.try_into().ok())
+        });
+        if let Some(tcp4_port) = tcp4_port {
+            builder.tcp4(tcp4_port.get());
         }
 
-        // If we are listening on ipv6, add the quic ipv6 port.
-        if let Some(quic6_port) = config.enr_quic6_port.or_else(|| {
+        let tcp6_port = config.enr_tcp6_port.or_else(|| {
             config
                 .listen_addrs()
                 .v6()
-                .and_then(|v6_addr| v6_addr.quic_port.try_into().ok())
-        }) {
-            builder.add_value(QUIC6_ENR_KEY, &quic6_port.get());
+                .and_then(|v6_addr| v6_addr.tcp_port.try_into().ok())
+        });
+        if let Some(tcp6_port) = tcp6_port {
+            builder.tcp6(tcp6_port.get());
         }
     }
 
-    // If the ENR port is not set, and we are listening over that ip version, use the listening port instead.
-    let tcp4_port = config.enr_tcp4_port.or_else(|| {
-        config
-            .listen_addrs()
-            .v4()
-            .and_then(|v4_addr| v4_addr.tcp_port.try_into().ok())
-    });
-    if let Some(tcp4_port) = tcp4_port {
-        builder.tcp4(tcp4_port.get());
-    }
-
-    let tcp6_port = config.enr_tcp6_port.or_else(|| {
-        config
-            .listen_addrs()
-            .v6()
-            .and_then(|v6_addr| v6_addr.tcp_port.try_into().ok())
-    });
-    if let Some(tcp6_port) = tcp6_port {
-        builder.tcp6(tcp6_port.get());
-    }
-
     // set the `eth2` field on our ENR
     builder.add_value::<Bytes>(ETH2_ENR_KEY, &enr_fork_id.as_ssz_bytes().into());
 
@@ -378,6 +417,73 @@ mod test {
         );
     }
 
+    #[test]
+    fn disable_inbound_omits_libp2p_ports() {
+        let spec = E::default_spec();
+        let enr = build_enr_with_config(NetworkConfig::default(), &spec).0;
+        assert!(enr.tcp4().is_some());
+        assert!(enr.quic4().is_some());
+
+        let config = NetworkConfig {
+            disable_inbound: true,
+            ..NetworkConfig::default()
+        };
+        let enr = build_enr_with_config(config, &spec).0;
+        assert_eq!(enr.tcp4(), None);
+        assert_eq!(enr.tcp6(), None);
+        assert_eq!(enr.quic4(), None);
+        assert_eq!(enr.quic6(), None);
+    }
+
+    #[test]
+    fn enr_fork_id_compatibility() {
+        let local = EnrForkId {
+            fork_digest: [1; 4],
+            next_fork_version: [2; 4],
+            next_fork_epoch: Epoch::new(10),
+        };
+
+        let previous_digest = [0; 4];
+        let next_digest = [2; 4];
+        let accepted = [previous_digest, local.fork_digest, next_digest];
+
+        assert!(check_enr_fork_id(&local, &local, &accepted).is_ok());
+        // A peer which has not yet scheduled the fork is compatible until the fork.
+        let not_ready = EnrForkId {
+            next_fork_version: [1; 4],
+            next_fork_epoch: Epoch::max_value(),
+            ..local.clone()
+        };
+        assert!(check_enr_fork_id(&not_ready, &local, &accepted).is_ok());
+
+        // A peer whose ENR has not been refreshed since the last fork, or which already
+        // advertises the next fork, is compatible.
+        let stale = EnrForkId {
+            fork_digest: previous_digest,
+            next_fork_version: [3; 4],
+            ..local.clone()
+        };
+        assert!(check_enr_fork_id(&stale, &local, &accepted).is_ok());
+        let upgraded = EnrForkId {
+            fork_digest: next_digest,
+            next_fork_version: [3; 4],
+            next_fork_epoch: Epoch::max_value(),
+        };
+        assert!(check_enr_fork_id(&upgraded, &local, &accepted).is_ok());
+
+        let other_fork = EnrForkId {
+            fork_digest: [3; 4],
+            ..local.clone()
+        };
+        assert!(check_enr_fork_id(&other_fork, &local, &accepted).is_err());
+
+        let conflicting_next_fork = EnrForkId {
+            next_fork_version: [3; 4],
+            ..local.clone()
+        };
+        assert!(check_enr_fork_id(&conflicting_next_fork, &local, &accepted).is_err());
+    }
+
     #[test]
     fn test_encode_decode_eth2_enr() {
         let (enr, _key) = build_enr_with_config(NetworkConfig::default(), &E::default_spec());
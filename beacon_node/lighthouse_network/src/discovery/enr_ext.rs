@@ -33,6 +33,9 @@ pub trait EnrExt {
     /// Returns any QUIC multiaddrs that are registered in this ENR.
     fn multiaddr_quic(&self) -> Vec<Multiaddr>;
 
+    /// Returns any QUIC multiaddrs that are registered in this ENR with the `PeerId` prepended.
+    fn multiaddr_p2p_quic(&self) -> Vec<Multiaddr>;
+
     /// Returns the quic port if one is set.
     fn quic4(&self) -> Option<u16>;
 
@@ -232,6 +235,18 @@ impl EnrExt for Enr {
         multiaddrs
     }
 
+    /// Returns a list of multiaddrs if the ENR has an `ip` and a `quic` key **or** an `ip6` and a `quic6`.
+    /// The vector remains empty if these fields are not defined.
+    ///
+    /// This also prepends the `PeerId` into each multiaddr with the `P2p` protocol.
+    fn multiaddr_p2p_quic(&self) -> Vec<Multiaddr> {
+        let peer_id = self.peer_id();
+        self.multiaddr_quic()
+            .into_iter()
+            .map(|multiaddr| multiaddr.with(Protocol::P2p(peer_id)))
+            .collect()
+    }
+
     /// Returns a list of multiaddrs if the ENR has an `ip` and either a `tcp` or `udp` key **or** an `ip6` and either a `tcp6` or `udp6`.
     fn multiaddr_tcp(&self) -> Vec<Multiaddr> {
         let mut multiaddrs: Vec<Multiaddr> = Vec::new();
@@ -3,19 +3,25 @@
 //! This module creates a libp2p dummy-behaviour built around the discv5 protocol. It handles
 //! queries and manages access to the discovery routing table.
 
+mod dns;
 pub(crate) mod enr;
 pub mod enr_ext;
 
 // Allow external use of the lighthouse ENR builder
+use crate::service::utils::PreviousNetworkKey;
 use crate::service::TARGET_SUBNET_PEERS;
 use crate::{metrics, ClearDialError};
 use crate::{Enr, NetworkConfig, NetworkGlobals, Subnet, SubnetDiscovery};
 use discv5::{enr::NodeId, Discv5};
-pub use enr::{build_enr, load_enr_from_disk, use_or_load_enr, CombinedKey, Eth2Enr};
+pub use dns::EnrTree;
+pub use enr::{
+    build_enr, check_enr_fork_id, load_enr_from_disk, use_or_load_enr, CombinedKey, Eth2Enr,
+};
 pub use enr_ext::{peer_id_to_node_id, CombinedKeyExt, EnrExt};
 pub use libp2p::identity::{Keypair, PublicKey};
 
 use alloy_rlp::bytes::Bytes;
+use dns::DnsDiscovery;
 use enr::{ATTESTATION_BITFIELD_ENR_KEY, ETH2_ENR_KEY, SYNC_COMMITTEE_BITFIELD_ENR_KEY};
 use futures::prelude::*;
 use futures::stream::FuturesUnordered;
@@ -42,7 +48,7 @@ use std::{
     pin::Pin,
     sync::Arc,
     task::{Context, Poll},
-    time::{Duration, Instant},
+    time::{Duration, Instant, SystemTime, UNIX_EPOCH},
 };
 use tokio::sync::mpsc;
 use types::{ChainSpec, EnrForkId, EthSpec};
@@ -154,6 +160,15 @@ enum EventStream {
     InActive,
 }
 
+/// A discv5 service announcing the ENR of a network key the node has rotated away from.
+struct PreviousIdentity {
+    discv5: Discv5,
+    /// A lookup which introduces the previous ENR to the nodes closest to a random target.
+    lookup: Option<Pin<Box<dyn Future<Output = Result<Vec<Enr>, discv5::QueryError>> + Send>>>,
+    /// Completes once the rotation grace period has elapsed.
+    expiry: Pin<Box<tokio::time::Sleep>>,
+}
+
 /// The main discovery service. This can be disabled via CLI arguements. When disabled the
 /// underlying processes are not started, but this struct still maintains our current ENR.
 pub struct Discovery<E: EthSpec> {
@@ -185,6 +200,12 @@ pub struct Discovery<E: EthSpec> {
     /// The discv5 event stream.
     event_stream: EventStream,
 
+    /// Resolves the configured DNS ENR trees, whose nodes are added to the routing table.
+    dns_discovery: Option<DnsDiscovery>,
+
+    /// Announces the previous ENR during a network key rotation grace period.
+    previous_identity: Option<PreviousIdentity>,
+
     /// Indicates if the discovery service has been started. When the service is disabled, this is
     /// always false.
     pub started: bool,
@@ -310,6 +331,9 @@ impl<E: EthSpec> Discovery<E> {
             }
         }
 
+        let dns_discovery = (!config.discovery_dns_trees.is_empty())
+            .then(|| DnsDiscovery::new(config.discovery_dns_trees.clone(), &log));
+
         let update_ports = UpdatePorts {
             tcp4: config.enr_tcp4_port.is_none(),
             tcp6: config.enr_tcp6_port.is_none(),
@@ -325,6 +349,8 @@ impl<E: EthSpec> Discovery<E> {
             active_queries: FuturesUnordered::new(),
             discv5,
             event_stream,
+            dns_discovery,
+            previous_identity: None,
             started: !config.disable_discovery,
             update_ports,
             log,
@@ -333,6 +359,98 @@ impl<E: EthSpec> Discovery<E> {
         })
     }
 
+    /// Announces the ENR of a network key the node has rotated away from until
+    /// `previous.announce_until`, so that peers looking up the previous node ID still find the
+    /// node while they learn its new identity.
+    ///
+    /// The previous ENR is served by a second discv5 service listening one port above the
+    /// discovery port. It only advertises that port, as the previous peer ID can no longer be
+    /// dialed.
+    pub async fn announce_previous_identity(
+        &mut self,
+        previous: PreviousNetworkKey,
+        config: &NetworkConfig,
+    ) -> Result<(), String> {
+        if config.disable_discovery {
+            return Ok(());
+        }
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_err(|e| format!("System time is before the unix epoch: {:?}", e))?;
+        let remaining = previous.announce_until.saturating_sub(now);
+
+        let next_port = |port: u16| {
+            port.checked_add(1)
+                .ok_or("The discovery port is too large to announce the previous ENR")
+        };
+        let mut previous_config = config.clone();
+        previous_config.disable_inbound = true;
+        previous_config.discv5_config.listen_config =
+            match config.discv5_config.listen_config.clone() {
+                discv5::ListenConfig::Ipv4 { ip, port } => discv5::ListenConfig::Ipv4 {
+                    ip,
+                    port: next_port(port)?,
+                },
+                discv5::ListenConfig::Ipv6 { ip, port } => discv5::ListenConfig::Ipv6 {
+                    ip,
+                    port: next_port(port)?,
+                },
+                discv5::ListenConfig::DualStack {
+                    ipv4,
+                    ipv4_port,
+                    ipv6,
+                    ipv6_port,
+                } => discv5::ListenConfig::DualStack {
+                    ipv4,
+                    ipv4_port: next_port(ipv4_port)?,
+                    ipv6,
+                    ipv6_port: next_port(ipv6_port)?,
+                },
+            };
+        previous_config.enr_udp4_port = config
+            .enr_udp4_port
+            .map(|port| port.checked_add(1).ok_or("The ENR UDP port is too large"))
+            .transpose()?;
+        previous_config.enr_udp6_port = config
+            .enr_udp6_port
+            .map(|port| port.checked_add(1).ok_or("The ENR UDP port is too large"))
+            .transpose()?;
+
+        let enr_key = CombinedKey::from_libp2p(previous.keypair)?;
+        let enr_fork_id = self.local_enr().eth2()?;
+        let mut enr = build_enr::<E>(&enr_key, &previous_config, &enr_fork_id, &self.spec)?;
+        // Peers keep the ENR with the highest sequence number, so the sequence number must exceed
+        // those of the ENRs published while the previous key was in use.
+        enr.set_seq(now.as_secs(), &enr_key)
+            .map_err(|e| format!("Could not update the previous ENR: {:?}", e))?;
+        let node_id = enr.node_id();
+
+        let mut discv5 = Discv5::new(enr, enr_key, previous_config.discv5_config)
+            .map_err(|e| format!("Previous identity discv5 service failed: {:?}", e))?;
+        for enr in self
+            .discv5
+            .table_entries_enr()
+            .into_iter()
+            .chain(config.boot_nodes_enr.iter().cloned())
+        {
+            let _ = discv5.add_enr(enr);
+        }
+        discv5.start().map_err(|e| e.to_string()).await?;
+
+        info!(
+            self.log,
+            "Announcing the previous ENR";
+            "node_id" => %node_id,
+            "remaining_secs" => remaining.as_secs(),
+        );
+        self.previous_identity = Some(PreviousIdentity {
+            lookup: Some(Box::pin(discv5.find_node(NodeId::random()))),
+            discv5,
+            expiry: Box::pin(tokio::time::sleep(remaining)),
+        });
+        Ok(())
+    }
+
     /// Return the nodes local ENR.
     pub fn local_enr(&self) -> Enr {
         self.discv5.local_enr()
@@ -959,6 +1077,61 @@ impl<E: EthSpec> Discovery<E> {
         }
         None
     }
+
+    /// Drives the announcement of the previous ENR, shutting it down once the rotation grace
+    /// period has elapsed.
+    fn poll_previous_identity(&mut self, cx: &mut Context) {
+        let Some(previous) = self.previous_identity.as_mut() else {
+            return;
+        };
+        if let Some(lookup) = previous.lookup.as_mut() {
+            if let Poll::Ready(result) = lookup.poll_unpin(cx) {
+                match result {
+                    Ok(enrs) => {
+                        debug!(self.log, "Previous ENR announced"; "peers" => enrs.len())
+                    }
+                    Err(e) => {
+                        debug!(self.log, "Previous ENR lookup failed"; "error" => ?e)
+                    }
+                }
+                previous.lookup = None;
+            }
+        }
+        if previous.expiry.poll_unpin(cx).is_ready() {
+            previous.discv5.shutdown();
+            info!(
+                self.log,
+                "Stopped announcing the previous ENR";
+                "node_id" => %previous.discv5.local_enr().node_id(),
+            );
+            self.previous_identity = None;
+        }
+    }
+
+    /// Adds the nodes found by any completed DNS ENR tree resolution to the routing table, from
+    /// which they are found by subsequent queries like boot nodes.
+    fn poll_dns_discovery(&mut self, cx: &mut Context) {
+        let Some(dns_discovery) = self.dns_discovery.as_mut() else {
+            return;
+        };
+        while let Poll::Ready(enrs) = dns_discovery.poll(cx) {
+            let local_node_id = self.discv5.local_enr().node_id();
+            let mut added = 0;
+            for enr in enrs {
+                if enr.node_id() == local_node_id {
+                    continue;
+                }
+                let node_id = enr.node_id();
+                match self.discv5.add_enr(enr) {
+                    Ok(()) => added += 1,
+                    Err(e) => {
+                        trace!(self.log, "Could not add DNS tree node to the routing table"; "node_id" => %node_id, "error" => e)
+                    }
+                }
+            }
+            debug!(self.log, "Added DNS tree nodes to the routing table"; "added" => added);
+        }
+    }
 }
 
 /* NetworkBehaviour Implementation */
@@ -1004,6 +1177,12 @@ impl<E: EthSpec> NetworkBehaviour for Discovery<E> {
             return Poll::Pending;
         }
 
+        // Add the nodes of any DNS ENR trees which have been resolved to the routing table
+        self.poll_dns_discovery(cx);
+
+        // Stop announcing the previous ENR once the rotation grace period has elapsed
+        self.poll_previous_identity(cx);
+
         // Process the query queue
         self.process_queue();
 
@@ -1335,4 +1514,42 @@ mod tests {
         // when a peer belongs to multiple subnet ids, we use the highest ttl.
         assert_eq!(results.get(&enr1).unwrap(), &instant1);
     }
+
+    #[tokio::test]
+    async fn test_announce_previous_identity() {
+        let mut discovery = build_discovery().await;
+        let mut config = NetworkConfig::default();
+        config.set_listening_addr(crate::ListenAddress::unused_v4_ports());
+        let previous_keypair: Keypair = secp256k1::Keypair::generate().into();
+        let previous_node_id = peer_id_to_node_id(&previous_keypair.public().to_peer_id()).unwrap();
+        let announce_until = SystemTime::now().duration_since(UNIX_EPOCH).unwrap();
+
+        discovery
+            .announce_previous_identity(
+                PreviousNetworkKey {
+                    keypair: previous_keypair,
+                    announce_until,
+                },
+                &config,
+            )
+            .await
+            .unwrap();
+
+        let previous = discovery.previous_identity.as_ref().unwrap();
+        let previous_enr = previous.discv5.local_enr();
+        assert_eq!(previous_enr.node_id(), previous_node_id);
+        assert_ne!(previous_enr.node_id(), discovery.local_enr().node_id());
+        assert_eq!(previous_enr.eth2(), discovery.local_enr().eth2());
+        // The previous peer ID can no longer be dialed.
+        assert_eq!(previous_enr.tcp4(), None);
+
+        // The grace period has already elapsed, so the previous ENR is no longer announced once
+        // discovery is polled.
+        futures::future::poll_fn(|cx| {
+            discovery.poll_previous_identity(cx);
+            Poll::Ready(())
+        })
+        .await;
+        assert!(discovery.previous_identity.is_none());
+    }
 }
@@ -102,7 +102,7 @@ impl std::fmt::Display for ClearDialError<'_> {
 
 pub use crate::types::{
     Enr, EnrSyncCommitteeBitfield, GossipTopic, NetworkGlobals, PubsubMessage, Subnet,
-    SubnetDiscovery,
+    SubnetDiscovery, SubnetDuty,
 };
 
 pub use prometheus_client;
@@ -116,10 +116,12 @@ pub use libp2p::{core::ConnectedPoint, PeerId, Swarm};
 pub use libp2p::{multiaddr, Multiaddr};
 pub use metrics::scrape_discovery_metrics;
 pub use peer_manager::{
-    peerdb::client::Client,
+    peerdb::client::{Client, ClientDiversity, ClientPeerCounts},
+    peerdb::decisions::{GoodbyeCounts, PeerDecision, PeerDecisionKind, PeerDecisions},
     peerdb::score::{PeerAction, ReportSource},
     peerdb::PeerDB,
-    ConnectionDirection, PeerConnectionStatus, PeerInfo, PeerManager, SyncInfo, SyncStatus,
+    ConnectionDirection, ForkReadiness, ForkReadinessSummary, PeerConnectionStatus, PeerInfo,
+    PeerManager, RangeData, SyncInfo, SyncStatus,
 };
 // pub use service::{load_private_key, Context, Libp2pEvent, Service, NETWORK_KEY_FILENAME};
 pub use service::api_types::{PeerRequestId, Response};
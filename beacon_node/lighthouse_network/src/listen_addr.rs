@@ -30,6 +30,32 @@ impl<Ip: Into<IpAddr> + Clone> ListenAddr<Ip> {
     }
 }
 
+/// An address libp2p listens on in addition to the primary [`ListenAddress`].
+///
+/// Additional addresses allow a multi-homed host to accept connections on several interfaces of
+/// the same IP version. The ENR can only advertise a single address per IP version, so these are
+/// only used by libp2p (TCP and QUIC) and discovery remains bound to the primary addresses.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct AdditionalListenAddr {
+    /// The IP address of the interface we will listen on.
+    pub addr: IpAddr,
+    /// The UDP port that QUIC will listen on.
+    pub quic_port: u16,
+    /// The TCP port that libp2p will listen on.
+    pub tcp_port: u16,
+}
+
+impl AdditionalListenAddr {
+    /// Returns the addresses the Swarm will listen on for this interface.
+    pub fn libp2p_addresses(&self) -> impl Iterator<Item = Multiaddr> {
+        let tcp_multiaddr = Multiaddr::from(self.addr).with(Protocol::Tcp(self.tcp_port));
+        let quic_multiaddr = Multiaddr::from(self.addr)
+            .with(Protocol::Udp(self.quic_port))
+            .with(Protocol::QuicV1);
+        [tcp_multiaddr, quic_multiaddr].into_iter()
+    }
+}
+
 /// Types of listening addresses Lighthouse can accept.
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub enum ListenAddress {
@@ -9,6 +9,14 @@ pub static NAT_OPEN: LazyLock<Result<IntGaugeVec>> = LazyLock::new(|| {
     )
 });
 
+pub static REACHABILITY: LazyLock<Result<IntGaugeVec>> = LazyLock::new(|| {
+    try_create_int_gauge_vec(
+        "libp2p_reachability",
+        "Set to 1 for the current reachability status of each libp2p transport",
+        &["transport", "status"],
+    )
+});
+
 pub static ADDRESS_UPDATE_COUNT: LazyLock<Result<IntCounter>> = LazyLock::new(|| {
     try_create_int_counter(
         "libp2p_address_update_total",
@@ -53,6 +61,39 @@ pub static PEER_DISCONNECT_EVENT_COUNT: LazyLock<Result<IntCounter>> = LazyLock:
         "Count of libp2p peer disconnect events",
     )
 });
+pub static DUTY_SUBNETS: LazyLock<Result<IntGauge>> = LazyLock::new(|| {
+    try_create_int_gauge(
+        "libp2p_duty_subnets",
+        "The number of subnets with an upcoming aggregation or sync committee duty",
+    )
+});
+pub static PEERS_RETAINED_FOR_DUTIES: LazyLock<Result<IntCounterVec>> = LazyLock::new(|| {
+    try_create_int_counter_vec(
+        "libp2p_peers_retained_for_duties_total",
+        "Count of times a peer was kept whilst pruning because it serves a subnet with an upcoming duty",
+        &["subnet"],
+    )
+});
+pub static BANDWIDTH_MANAGER_BYTES: LazyLock<Result<IntCounterVec>> = LazyLock::new(|| {
+    try_create_int_counter_vec(
+        "libp2p_bandwidth_manager_bytes_total",
+        "Bytes recorded against the bandwidth budget per traffic class",
+        &["class"],
+    )
+});
+pub static BANDWIDTH_MANAGER_BALANCE: LazyLock<Result<IntGauge>> = LazyLock::new(|| {
+    try_create_int_gauge(
+        "libp2p_bandwidth_manager_balance_bytes",
+        "Bytes currently available in the bandwidth budget, negative when in debt",
+    )
+});
+pub static BANDWIDTH_MANAGER_DEFERRALS: LazyLock<Result<IntCounterVec>> = LazyLock::new(|| {
+    try_create_int_counter_vec(
+        "libp2p_bandwidth_manager_deferrals_total",
+        "Count of times traffic was deferred because the bandwidth budget was exhausted",
+        &["class"],
+    )
+});
 pub static DISCOVERY_BYTES: LazyLock<Result<IntGaugeVec>> = LazyLock::new(|| {
     try_create_int_gauge_vec(
         "discovery_bytes",
@@ -93,6 +134,29 @@ pub static PEERS_PER_CLIENT: LazyLock<Result<IntGaugeVec>> = LazyLock::new(|| {
     )
 });
 
+pub static PEERS_PER_CLIENT_PLATFORM: LazyLock<Result<IntGaugeVec>> = LazyLock::new(|| {
+    try_create_int_gauge_vec(
+        "libp2p_peers_per_client_platform",
+        "The connected peers via the platform reported by their client",
+        &["platform"],
+    )
+});
+
+pub static PEERS_PER_FORK_READINESS: LazyLock<Result<IntGaugeVec>> = LazyLock::new(|| {
+    try_create_int_gauge_vec(
+        "libp2p_peers_per_fork_readiness",
+        "The connected peers by whether their ENR advertises the next fork scheduled locally",
+        &["readiness"],
+    )
+});
+
+pub static PEERS_INCOMPATIBLE_ENR_FORK_ID: LazyLock<Result<IntCounter>> = LazyLock::new(|| {
+    try_create_int_counter(
+        "libp2p_peers_incompatible_enr_fork_id_total",
+        "Count of peers whose ENR advertised an incompatible fork when they connected",
+    )
+});
+
 pub static PEERS_PER_CUSTODY_SUBNET_COUNT: LazyLock<Result<IntGaugeVec>> = LazyLock::new(|| {
     try_create_int_gauge_vec(
         "peers_per_custody_subnet_count",
@@ -101,6 +165,14 @@ pub static PEERS_PER_CUSTODY_SUBNET_COUNT: LazyLock<Result<IntGaugeVec>> = LazyL
     )
 });
 
+pub static PEERS_CUSTODY_SUBNET_COUNT_MISMATCH: LazyLock<Result<IntCounter>> =
+    LazyLock::new(|| {
+        try_create_int_counter(
+            "peers_custody_subnet_count_mismatch_total",
+            "Count of metadata responses whose custody subnet count is lower than the peer's ENR",
+        )
+    });
+
 pub static FAILED_ATTESTATION_PUBLISHES_PER_SUBNET: LazyLock<Result<IntGaugeVec>> =
     LazyLock::new(|| {
         try_create_int_gauge_vec(
@@ -123,9 +195,24 @@ pub static TOTAL_RPC_ERRORS_PER_CLIENT: LazyLock<Result<IntCounterVec>> = LazyLo
         &["client", "rpc_error", "direction"],
     )
 });
+pub static RPC_UNKNOWN_FORK_DIGESTS_PER_CLIENT: LazyLock<Result<IntCounterVec>> =
+    LazyLock::new(|| {
+        try_create_int_counter_vec(
+            "libp2p_rpc_unknown_fork_digests_per_client",
+            "RPC responses with context bytes which do not correspond to a known fork, per client",
+            &["client", "protocol"],
+        )
+    });
 pub static TOTAL_RPC_REQUESTS: LazyLock<Result<IntCounterVec>> = LazyLock::new(|| {
     try_create_int_counter_vec("libp2p_rpc_requests_total", "RPC requests total", &["type"])
 });
+pub static GOODBYES_PER_CLIENT: LazyLock<Result<IntCounterVec>> = LazyLock::new(|| {
+    try_create_int_counter_vec(
+        "libp2p_goodbyes_per_client",
+        "Goodbye messages sent to and received from peers, per client and reason",
+        &["client", "reason", "direction"],
+    )
+});
 pub static PEER_ACTION_EVENTS_PER_CLIENT: LazyLock<Result<IntCounterVec>> = LazyLock::new(|| {
     try_create_int_counter_vec(
         "libp2p_peer_actions_per_client",
@@ -165,6 +252,56 @@ pub static GOSSIP_FAILED_LATE_PUBLISH_PER_TOPIC_KIND: LazyLock<Result<IntCounter
             &["topic_kind"],
         )
     });
+pub static GOSSIP_PUBLISH_MESH_PEERS_PER_TOPIC_KIND: LazyLock<Result<HistogramVec>> =
+    LazyLock::new(|| {
+        try_create_histogram_vec_with_buckets(
+            "gossipsub_publish_mesh_peers_per_topic_kind",
+            "Number of mesh peers on the topic when a message is published to gossipsub.",
+            Ok(vec![0.0, 1.0, 2.0, 3.0, 4.0, 6.0, 8.0, 10.0, 12.0]),
+            &["topic_kind"],
+        )
+    });
+pub static GOSSIP_PUBLISH_INSUFFICIENT_MESH_PER_TOPIC_KIND: LazyLock<Result<IntCounterVec>> =
+    LazyLock::new(|| {
+        try_create_int_counter_vec(
+            "gossipsub_publish_insufficient_mesh_per_topic_kind",
+            "Messages published to a mesh with fewer than the minimum number of peers, per topic kind.",
+            &["topic_kind"],
+        )
+    });
+pub static GOSSIP_PUBLISH_DEFERRED_PER_TOPIC_KIND: LazyLock<Result<IntCounterVec>> =
+    LazyLock::new(|| {
+        try_create_int_counter_vec(
+            "gossipsub_publish_deferred_per_topic_kind",
+            "Messages whose publishing was deferred by the publish rate limiter, per topic kind.",
+            &["topic_kind"],
+        )
+    });
+pub static GOSSIP_PUBLISH_DROPPED_PER_TOPIC_KIND: LazyLock<Result<IntCounterVec>> =
+    LazyLock::new(|| {
+        try_create_int_counter_vec(
+            "gossipsub_publish_dropped_per_topic_kind",
+            "Messages dropped by the publish rate limiter rather than published, per topic kind.",
+            &["topic_kind", "reason"],
+        )
+    });
+pub static GOSSIP_PUBLISH_RETRIES_PER_TOPIC_KIND: LazyLock<Result<IntCounterVec>> =
+    LazyLock::new(|| {
+        try_create_int_counter_vec(
+            "gossipsub_publish_retries_per_topic_kind",
+            "Messages re-published to gossipsub due to an under-populated mesh, per topic kind.",
+            &["topic_kind", "result"],
+        )
+    });
+pub static GOSSIP_REPUBLISH_FANOUT_PER_TOPIC_KIND: LazyLock<Result<HistogramVec>> =
+    LazyLock::new(|| {
+        try_create_histogram_vec_with_buckets(
+            "gossipsub_republish_fanout_per_topic_kind",
+            "Number of peers a message was sent to when re-published to gossipsub.",
+            Ok(vec![0.0, 1.0, 2.0, 3.0, 4.0, 6.0, 8.0, 10.0, 12.0]),
+            &["topic_kind"],
+        )
+    });
 pub static PEER_SCORE_DISTRIBUTION: LazyLock<Result<IntGaugeVec>> = LazyLock::new(|| {
     try_create_int_gauge_vec(
         "peer_score_distribution",
@@ -179,6 +316,19 @@ pub static PEER_SCORE_PER_CLIENT: LazyLock<Result<GaugeVec>> = LazyLock::new(||
         &["client"],
     )
 });
+pub static PEER_PING_RTT: LazyLock<Result<Histogram>> = LazyLock::new(|| {
+    try_create_histogram_with_buckets(
+        "libp2p_peer_ping_rtt_seconds",
+        "The round-trip time of peers' responses to our pings",
+        Ok(vec![0.01, 0.05, 0.1, 0.25, 0.5, 1.0, 2.0, 5.0, 10.0]),
+    )
+});
+pub static PEER_MISSED_PINGS: LazyLock<Result<IntCounter>> = LazyLock::new(|| {
+    try_create_int_counter(
+        "libp2p_peer_missed_pings_total",
+        "Count of pings which were not answered before the next ping to the same peer",
+    )
+});
 
 pub static SUBNET_PEERS_FOUND: LazyLock<Result<IntCounterVec>> = LazyLock::new(|| {
     try_create_int_counter_vec(
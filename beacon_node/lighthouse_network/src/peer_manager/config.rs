@@ -1,3 +1,6 @@
+use std::sync::Arc;
+use types::ForkContext;
+
 /// The time in seconds between re-status's peers.
 pub const DEFAULT_STATUS_INTERVAL: u64 = 300;
 
@@ -22,6 +25,8 @@ pub struct Config {
     pub quic_enabled: bool,
     /// Target number of peers to connect to.
     pub target_peer_count: usize,
+    /// Whether to prefer pruning peers running the most common clients.
+    pub preserve_client_diversity: bool,
 
     /* RPC related configurations */
     /// Time in seconds between status requests sent to peers.
@@ -32,6 +37,10 @@ pub struct Config {
     pub ping_interval_inbound: u64,
     /// Interval between PING events for peers dialed by us.
     pub ping_interval_outbound: u64,
+
+    /* Fork related configurations */
+    /// The fork digests of the local chain, used to check the fork advertised in peers' ENRs.
+    pub fork_context: Option<Arc<ForkContext>>,
 }
 
 impl Default for Config {
@@ -41,9 +50,11 @@ impl Default for Config {
             metrics_enabled: false,
             quic_enabled: true,
             target_peer_count: DEFAULT_TARGET_PEERS,
+            preserve_client_diversity: false,
             status_interval: DEFAULT_STATUS_INTERVAL,
             ping_interval_inbound: DEFAULT_PING_INTERVAL_INBOUND,
             ping_interval_outbound: DEFAULT_PING_INTERVAL_OUTBOUND,
+            fork_context: None,
         }
     }
 }
@@ -1,33 +1,35 @@
 //! Implementation of Lighthouse's peer management system.
 
 use crate::discovery::enr_ext::EnrExt;
-use crate::discovery::peer_id_to_node_id;
+use crate::discovery::{check_enr_fork_id, peer_id_to_node_id, Eth2Enr};
 use crate::rpc::{GoodbyeReason, MetaData, Protocol, RPCError, RpcErrorResponse};
 use crate::service::TARGET_SUBNET_PEERS;
-use crate::{metrics, Gossipsub, NetworkGlobals, PeerId, Subnet, SubnetDiscovery};
+use crate::types::{ReachabilityStatus, Transport};
+use crate::{metrics, Gossipsub, NetworkGlobals, PeerId, Subnet, SubnetDiscovery, SubnetDuty};
 use delay_map::HashSetDelay;
 use discv5::Enr;
 use libp2p::identify::Info as IdentifyInfo;
 use lru_cache::LRUTimeCache;
 use peerdb::{BanOperation, BanResult, ScoreUpdateResult};
 use rand::seq::SliceRandom;
-use slog::{debug, error, trace, warn};
+use slog::{debug, error, info, trace, warn};
 use smallvec::SmallVec;
 use std::{
     sync::Arc,
     time::{Duration, Instant},
 };
-use types::{DataColumnSubnetId, EthSpec, SyncSubnetId};
+use types::{DataColumnSubnetId, EthSpec, ForkContext, SyncSubnetId};
 
 pub use libp2p::core::Multiaddr;
 pub use libp2p::identity::Keypair;
 
 pub mod peerdb;
 
-use crate::peer_manager::peerdb::client::ClientKind;
+use crate::peer_manager::peerdb::client::{ClientKind, ClientPlatform};
 use libp2p::multiaddr;
 pub use peerdb::peer_info::{
-    ConnectionDirection, PeerConnectionStatus, PeerConnectionStatus::*, PeerInfo,
+    ConnectionDirection, ForkReadiness, ForkReadinessSummary, PeerConnectionStatus,
+    PeerConnectionStatus::*, PeerInfo, RangeData,
 };
 use peerdb::score::{PeerAction, ReportSource};
 pub use peerdb::sync_status::{SyncInfo, SyncStatus};
@@ -63,6 +65,17 @@ pub const MIN_OUTBOUND_ONLY_FACTOR: f32 = 0.2;
 /// limit is 55, and we are at 55 peers, the following parameter provisions a few more slots of
 /// dialing priority peers we need for validator duties.
 pub const PRIORITY_PEER_EXCESS: f32 = 0.2;
+/// The interval at which peers which have not answered our last ping are pinged again.
+const UNRESPONSIVE_PING_INTERVAL: Duration = Duration::from_secs(5);
+/// The number of consecutive unanswered pings after which a peer is considered dead and
+/// disconnected.
+const MAX_MISSED_PINGS: u32 = 3;
+/// Peers whose smoothed ping round-trip time exceeds this are penalised whenever they answer a
+/// ping.
+const HIGH_PING_RTT: Duration = Duration::from_secs(3);
+/// The number of consecutive pings and pongs advertising metadata newer than the metadata the peer
+/// has given us, after which the peer is penalised for not serving its metadata.
+const MAX_STALE_META_DATA_PINGS: u32 = 3;
 
 /// The main struct that handles peer's reputation and connection status.
 pub struct PeerManager<E: EthSpec> {
@@ -78,6 +91,8 @@ pub struct PeerManager<E: EthSpec> {
     status_peers: HashSetDelay<PeerId>,
     /// The target number of peers we would like to connect to.
     target_peers: usize,
+    /// Whether to prefer pruning peers running the most common clients.
+    preserve_client_diversity: bool,
     /// Peers queued to be dialed.
     peers_to_dial: Vec<Enr>,
     /// The number of temporarily banned peers. This is used to prevent instantaneous
@@ -99,6 +114,10 @@ pub struct PeerManager<E: EthSpec> {
     /// discovery queries for subnet peers if we disconnect from existing sync
     /// committee subnet peers.
     sync_committee_subnets: HashMap<SyncSubnetId, Instant>,
+    /// Subnets on which a local validator must aggregate or serve on a sync committee, mapped to
+    /// the instant at which the last of these duties is complete. Up to `TARGET_SUBNET_PEERS` of
+    /// the best peers on each of these subnets are not pruned, whatever their score.
+    duty_subnets: HashMap<Subnet, Instant>,
     /// The heartbeat interval to perform routine maintenance.
     heartbeat: tokio::time::Interval,
     /// Keeps track of whether the discovery service is enabled or not.
@@ -107,10 +126,25 @@ pub struct PeerManager<E: EthSpec> {
     metrics_enabled: bool,
     /// Keeps track of whether the QUIC protocol is enabled or not.
     quic_enabled: bool,
+    /// The fork digests of the local chain, used to check the fork advertised in peers' ENRs.
+    fork_context: Option<Arc<ForkContext>>,
+    /// The most recently logged reachability status of each transport.
+    reachability: HashMap<Transport, ReachabilityStatus>,
     /// The logger associated with the `PeerManager`.
     log: slog::Logger,
 }
 
+/// When a peer should next be pinged, following a ping.
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum PingSchedule {
+    /// At the configured ping interval.
+    Regular,
+    /// At `UNRESPONSIVE_PING_INTERVAL`, since the peer has not answered our previous ping.
+    Unresponsive,
+    /// Never, since the peer has been disconnected.
+    Disconnected,
+}
+
 /// The events that the `PeerManager` outputs (requests).
 #[derive(Debug)]
 pub enum PeerManagerEvent {
@@ -149,10 +183,12 @@ impl<E: EthSpec> PeerManager<E> {
             discovery_enabled,
             metrics_enabled,
             target_peer_count,
+            preserve_client_diversity,
             status_interval,
             ping_interval_inbound,
             ping_interval_outbound,
             quic_enabled,
+            fork_context,
         } = cfg;
 
         // Set up the peer manager heartbeat interval
@@ -166,12 +202,16 @@ impl<E: EthSpec> PeerManager<E> {
             outbound_ping_peers: HashSetDelay::new(Duration::from_secs(ping_interval_outbound)),
             status_peers: HashSetDelay::new(Duration::from_secs(status_interval)),
             target_peers: target_peer_count,
+            preserve_client_diversity,
             temporary_banned_peers: LRUTimeCache::new(PEER_RECONNECTION_TIMEOUT),
             sync_committee_subnets: Default::default(),
+            duty_subnets: Default::default(),
             heartbeat,
             discovery_enabled,
             metrics_enabled,
             quic_enabled,
+            fork_context,
+            reachability: HashMap::new(),
             log: log.clone(),
         })
     }
@@ -201,6 +241,16 @@ impl<E: EthSpec> PeerManager<E> {
         );
     }
 
+    /// Marks a peer as trusted at runtime, as if it had been passed to `--trusted-peers`.
+    ///
+    /// A trusted peer is never penalised, pruned or banned. If the peer is currently banned, the
+    /// ban is lifted.
+    pub fn protect_peer(&mut self, peer_id: &PeerId) {
+        let action = self.network_globals.peers.write().protect_peer(peer_id);
+        debug!(self.log, "Protected peer"; "peer_id" => %peer_id);
+        self.handle_score_action(peer_id, action, None);
+    }
+
     /// Reports a peer for some action.
     ///
     /// If the peer doesn't exist, log a warning and insert defaults.
@@ -387,6 +437,17 @@ impl<E: EthSpec> PeerManager<E> {
         }
     }
 
+    /// Record the subnets on which local validators have upcoming duties, so that peers on them are
+    /// retained when pruning.
+    pub fn add_duty_subnets(&mut self, duties: impl IntoIterator<Item = SubnetDuty>) {
+        for SubnetDuty { subnet, until } in duties {
+            let entry = self.duty_subnets.entry(subnet).or_insert(until);
+            if *entry < until {
+                *entry = until;
+            }
+        }
+    }
+
     /// The maximum number of peers we allow to connect to us. This is `target_peers` * (1 +
     /// PEER_EXCESS_FACTOR)
     fn max_peers(&self) -> usize {
@@ -509,6 +570,18 @@ impl<E: EthSpec> PeerManager<E> {
                 // Peer is not complying with the protocol. This is considered a malicious action
                 PeerAction::Fatal
             }
+            RPCError::UnknownForkDigest(digest) => {
+                // The peer is likely on a fork which we do not know of, e.g. a newer fork which
+                // this version of Lighthouse does not support. Record it so that fork transition
+                // issues can be diagnosed.
+                metrics::inc_counter_vec(
+                    &metrics::RPC_UNKNOWN_FORK_DIGESTS_PER_CLIENT,
+                    &[client.kind.as_ref(), protocol.as_ref()],
+                );
+                debug!(self.log, "Peer responded on an unknown fork"; "fork_digest" => hex::encode(digest),
+                    "protocol" => %protocol, "client" => %client, "peer_id" => %peer_id);
+                PeerAction::LowToleranceError
+            }
             RPCError::IoError(_e) => {
                 // this could their fault or ours, so we tolerate this
                 PeerAction::HighToleranceError
@@ -516,38 +589,18 @@ impl<E: EthSpec> PeerManager<E> {
             RPCError::ErrorResponse(code, _) => match code {
                 RpcErrorResponse::Unknown => PeerAction::HighToleranceError,
                 RpcErrorResponse::ResourceUnavailable => {
-                    // Don't ban on this because we want to retry with a block by root request.
-                    if matches!(
-                        protocol,
-                        Protocol::BlobsByRoot | Protocol::DataColumnsByRoot
-                    ) {
-                        return;
-                    }
-
-                    // NOTE: This error only makes sense for the `BlocksByRange` and `BlocksByRoot`
-                    // protocols.
-                    //
-                    // If we are syncing, there is no point keeping these peers around and
-                    // continually failing to request blocks. We instantly ban them and hope that
-                    // by the time the ban lifts, the peers will have completed their backfill
-                    // sync.
-                    //
-                    // TODO: Potentially a more graceful way of handling such peers, would be to
-                    // implement a new sync type which tracks these peers and prevents the sync
-                    // algorithms from requesting blocks from them (at least for a set period of
-                    // time, multiple failures would then lead to a ban).
-
-                    match direction {
-                        // If the blocks request was initiated by us, then we have no use of this
-                        // peer and so we ban it.
-                        ConnectionDirection::Outgoing => PeerAction::Fatal,
-                        // If the blocks request was initiated by the peer, then we let the peer decide if
-                        // it wants to continue talking to us, we do not ban the peer.
-                        ConnectionDirection::Incoming => return,
-                    }
+                    // Don't penalise on this. For by root requests sync stops requesting the
+                    // resource from this peer and tries another peer instead. For by range
+                    // requests the peer has pruned, or not yet backfilled, the requested range.
+                    // Sync records the earliest slot the peer can serve in the `PeerDB` and stops
+                    // requesting earlier ranges from it, and penalises the peer itself if the
+                    // range is one the peer should be able to serve.
+                    return;
                 }
                 RpcErrorResponse::ServerError => PeerAction::MidToleranceError,
                 RpcErrorResponse::InvalidRequest => PeerAction::LowToleranceError,
+                // A retry-after response that could not be decoded.
+                RpcErrorResponse::RetryAfter => PeerAction::LowToleranceError,
                 RpcErrorResponse::RateLimited => match protocol {
                     Protocol::Ping => PeerAction::MidToleranceError,
                     Protocol::BlocksByRange => PeerAction::MidToleranceError,
@@ -568,6 +621,15 @@ impl<E: EthSpec> PeerManager<E> {
                 },
                 RpcErrorResponse::BlobsNotFoundForBlock => PeerAction::LowToleranceError,
             },
+            // The peer told us when to retry a by range request, possibly after serving part of
+            // it. This is not a fault, sync avoids the peer until then.
+            RPCError::RetryAfter(_)
+                if matches!(protocol, Protocol::BlocksByRange | Protocol::BlobsByRange) =>
+            {
+                return;
+            }
+            // Only by range requests are truncated with a retry-after response.
+            RPCError::RetryAfter(_) => PeerAction::LowToleranceError,
             RPCError::SSZDecodeError(_) => PeerAction::Fatal,
             RPCError::UnsupportedProtocol => {
                 // Not supporting a protocol shouldn't be considered a malicious action, but
@@ -627,9 +689,49 @@ impl<E: EthSpec> PeerManager<E> {
         );
     }
 
+    /// A ping is due for a peer.
+    ///
+    /// Peers which have not answered our previous ping are penalised and pinged again sooner, and
+    /// disconnected once they have missed `MAX_MISSED_PINGS` pings.
+    fn ping_peer(&mut self, peer_id: PeerId) -> PingSchedule {
+        let (missed_pings, is_trusted) =
+            match self.network_globals.peers.write().peer_info_mut(&peer_id) {
+                Some(info) => (info.on_ping_sent(Instant::now()), info.is_trusted()),
+                None => (0, false),
+            };
+
+        if missed_pings > 0 {
+            metrics::inc_counter(&metrics::PEER_MISSED_PINGS);
+            self.report_peer(
+                &peer_id,
+                PeerAction::HighToleranceError,
+                ReportSource::PeerManager,
+                None,
+                "missed_ping",
+            );
+        }
+
+        if missed_pings >= MAX_MISSED_PINGS && !is_trusted {
+            debug!(self.log, "Disconnecting peer which is not answering pings";
+                "peer_id" => %peer_id, "missed_pings" => missed_pings);
+            self.disconnect_peer(peer_id, GoodbyeReason::Fault);
+            return PingSchedule::Disconnected;
+        }
+
+        self.events.push(PeerManagerEvent::Ping(peer_id));
+        if missed_pings > 0 {
+            PingSchedule::Unresponsive
+        } else {
+            PingSchedule::Regular
+        }
+    }
+
     /// A ping request has been received.
     // NOTE: The behaviour responds with a PONG automatically
     pub fn ping_request(&mut self, peer_id: &PeerId, seq: u64) {
+        if let Some(peer_info) = self.network_globals.peers.write().peer_info_mut(peer_id) {
+            peer_info.on_ping_received();
+        }
         if let Some(peer_info) = self.network_globals.peers.read().peer_info(peer_id) {
             // received a ping
             // reset the to-ping timer for this peer
@@ -663,10 +765,36 @@ impl<E: EthSpec> PeerManager<E> {
             error!(self.log, "Received a PING from an unknown peer";
                 "peer_id" => %peer_id);
         }
+        self.check_meta_data_seq_number(peer_id, seq);
     }
 
     /// A PONG has been returned from a peer.
     pub fn pong_response(&mut self, peer_id: &PeerId, seq: u64) {
+        let ping_rtt = self
+            .network_globals
+            .peers
+            .write()
+            .peer_info_mut(peer_id)
+            .and_then(|peer_info| {
+                let rtt = peer_info.on_pong(Instant::now())?;
+                Some((rtt, peer_info.ping_rtt()?))
+            });
+        if let Some((rtt, smoothed_rtt)) = ping_rtt {
+            trace!(self.log, "Received a pong"; "peer_id" => %peer_id, "rtt_ms" => rtt.as_millis());
+            metrics::observe_duration(&metrics::PEER_PING_RTT, rtt);
+            if smoothed_rtt > HIGH_PING_RTT {
+                debug!(self.log, "Peer is slow to answer pings";
+                    "peer_id" => %peer_id, "ping_rtt_ms" => smoothed_rtt.as_millis());
+                self.report_peer(
+                    peer_id,
+                    PeerAction::HighToleranceError,
+                    ReportSource::PeerManager,
+                    None,
+                    "high_ping_rtt",
+                );
+            }
+        }
+
         if let Some(peer_info) = self.network_globals.peers.read().peer_info(peer_id) {
             // received a pong
 
@@ -686,11 +814,34 @@ impl<E: EthSpec> PeerManager<E> {
         } else {
             error!(self.log, "Received a PONG from an unknown peer"; "peer_id" => %peer_id);
         }
+        self.check_meta_data_seq_number(peer_id, seq);
+    }
+
+    /// Penalises a peer which keeps advertising a metadata sequence number newer than the metadata
+    /// it has given us, as it is not answering our metadata requests.
+    fn check_meta_data_seq_number(&mut self, peer_id: &PeerId, seq: u64) {
+        let stale_meta_data_pings = match self.network_globals.peers.write().peer_info_mut(peer_id)
+        {
+            Some(peer_info) => peer_info.on_meta_data_seq_number(seq),
+            None => return,
+        };
+        if stale_meta_data_pings >= MAX_STALE_META_DATA_PINGS {
+            debug!(self.log, "Peer is not serving its latest metadata";
+                "peer_id" => %peer_id, "seq_no" => seq, "stale_pings" => stale_meta_data_pings);
+            self.report_peer(
+                peer_id,
+                PeerAction::HighToleranceError,
+                ReportSource::PeerManager,
+                None,
+                "stale_metadata",
+            );
+        }
     }
 
     /// Received a metadata response from a peer.
     pub fn meta_data_response(&mut self, peer_id: &PeerId, meta_data: MetaData<E>) {
         let mut invalid_meta_data = false;
+        let mut custody_subnet_count_mismatch = false;
 
         if let Some(peer_info) = self.network_globals.peers.write().peer_info_mut(peer_id) {
             if let Some(known_meta_data) = &peer_info.meta_data() {
@@ -716,6 +867,21 @@ impl<E: EthSpec> PeerManager<E> {
                 // Gracefully ignore metadata/v2 peers. Potentially downscore after PeerDAS to
                 // prioritize PeerDAS peers.
                 if let Some(custody_subnet_count) = custody_subnet_count_opt {
+                    // A peer which custodies fewer subnets than its ENR advertises will not serve
+                    // columns that other nodes select it for based on its ENR.
+                    if let Some(enr_custody_subnet_count) = peer_info.enr().and_then(|enr| {
+                        enr.custody_subnet_count::<E>(&self.network_globals.spec)
+                            .ok()
+                    }) {
+                        if custody_subnet_count < enr_custody_subnet_count {
+                            debug!(self.log, "Peer metadata custodies fewer subnets than its ENR";
+                                "peer_id" => %peer_id,
+                                "custody_subnet_count" => custody_subnet_count,
+                                "enr_custody_subnet_count" => enr_custody_subnet_count,
+                            );
+                            custody_subnet_count_mismatch = true;
+                        }
+                    }
                     match self.compute_peer_custody_subnets(peer_id, custody_subnet_count) {
                         Ok(custody_subnets) => {
                             peer_info.set_custody_subnets(custody_subnets);
@@ -740,6 +906,16 @@ impl<E: EthSpec> PeerManager<E> {
         // Disconnect peers with invalid metadata and find other peers instead.
         if invalid_meta_data {
             self.goodbye_peer(peer_id, GoodbyeReason::Fault, ReportSource::PeerManager)
+        } else if custody_subnet_count_mismatch {
+            // Our copy of the ENR may be stale, so this is only a high tolerance error.
+            metrics::inc_counter(&metrics::PEERS_CUSTODY_SUBNET_COUNT_MISMATCH);
+            self.report_peer(
+                peer_id,
+                PeerAction::HighToleranceError,
+                ReportSource::PeerManager,
+                None,
+                "custody_subnet_count_mismatch",
+            );
         }
     }
 
@@ -853,6 +1029,57 @@ impl<E: EthSpec> PeerManager<E> {
         true
     }
 
+    /// Records whether the peer's ENR, if known, advertises a fork which is incompatible with the
+    /// local node.
+    ///
+    /// ENRs can be stale, so this is only used for metrics. Peers which really are on another
+    /// network are disconnected by the status handshake.
+    fn check_peer_enr_fork_id(&self, peer_id: &PeerId) {
+        let Ok(local_enr_fork_id) = self.network_globals.local_enr().eth2() else {
+            return;
+        };
+        // Accept the digests of the forks either side of the current fork, as peers may not have
+        // refreshed their ENR since the last fork, or may already advertise the next one.
+        let accepted_fork_digests = self
+            .fork_context
+            .as_ref()
+            .map(|fork_context| {
+                let current_fork = fork_context.current_fork();
+                [
+                    current_fork.previous_fork(),
+                    Some(current_fork),
+                    current_fork.next_fork(),
+                ]
+                .into_iter()
+                .flatten()
+                .filter_map(|fork_name| fork_context.to_context_bytes(fork_name))
+                .collect::<Vec<_>>()
+            })
+            .unwrap_or_default();
+        let Some(peer_enr_fork_id) = self
+            .network_globals
+            .peers
+            .read()
+            .peer_info(peer_id)
+            .and_then(|info| info.enr())
+            .and_then(|enr| enr.eth2().ok())
+        else {
+            return;
+        };
+
+        if let Err(reason) = check_enr_fork_id(
+            &peer_enr_fork_id,
+            &local_enr_fork_id,
+            &accepted_fork_digests,
+        ) {
+            debug!(self.log, "Peer ENR advertises an incompatible fork id";
+                "peer_id" => %peer_id,
+                "reason" => reason,
+            );
+            metrics::inc_counter(&metrics::PEERS_INCOMPATIBLE_ENR_FORK_ID);
+        }
+    }
+
     // Gracefully disconnects a peer without banning them.
     fn disconnect_peer(&mut self, peer_id: PeerId, reason: GoodbyeReason) {
         self.events
@@ -940,6 +1167,8 @@ impl<E: EthSpec> PeerManager<E> {
     ///
     /// Global rules:
     /// - Always maintain peers we need for a validator duty.
+    /// - Do not prune the best `TARGET_SUBNET_PEERS` peers on each subnet on which a local
+    ///     validator must aggregate or serve on a sync committee, even if their score is negative.
     /// - Do not prune outbound peers to exceed our outbound target.
     /// - Do not prune more peers than our target peer count.
     /// - If we have an option to remove a number of peers, remove ones that have the least
@@ -982,6 +1211,10 @@ impl<E: EthSpec> PeerManager<E> {
         // Keep track of the number of outbound peers we are pruning.
         let mut outbound_peers_pruned = 0;
 
+        // Peers needed for upcoming local duties, and those of them we would otherwise prune.
+        let duty_peers = self.duty_peers();
+        let mut duty_retained: HashMap<PeerId, Subnet> = HashMap::new();
+
         macro_rules! prune_peers {
             ($filter: expr) => {
                 let filter = $filter;
@@ -1004,6 +1237,10 @@ impl<E: EthSpec> PeerManager<E> {
                     if peers_to_prune.contains(*peer_id) {
                         continue;
                     }
+                    if let Some(subnet) = duty_peers.get(*peer_id) {
+                        duty_retained.insert(**peer_id, *subnet);
+                        continue;
+                    }
                     // Only remove up to the target outbound peer count.
                     if info.is_outbound_only() {
                         if self.target_outbound_peers() + outbound_peers_pruned
@@ -1040,12 +1277,16 @@ impl<E: EthSpec> PeerManager<E> {
                 PeerId,
                 std::collections::HashSet<SyncSubnetId>,
             > = HashMap::new();
+            // The number of remaining peers running each client, used to preserve client
+            // diversity.
+            let mut client_peer_count: HashMap<ClientKind, usize> = HashMap::new();
 
             for (peer_id, info) in self.network_globals.peers.read().connected_peers() {
                 // Ignore peers we trust or that we are already pruning
                 if info.is_trusted() || peers_to_prune.contains(peer_id) {
                     continue;
                 }
+                *client_peer_count.entry(info.client().kind).or_default() += 1;
 
                 // Count based on long-lived subnets not short-lived subnets
                 // NOTE: There are only 4 sync committees. These are likely to be denser than the
@@ -1085,7 +1326,23 @@ impl<E: EthSpec> PeerManager<E> {
                         // Order the peers by the number of subnets they are long-lived
                         // subscribed too, shuffle equal peers.
                         peers_on_subnet.shuffle(&mut rand::thread_rng());
-                        peers_on_subnet.sort_by_key(|(_, info)| info.long_lived_subnet_count());
+                        if self.preserve_client_diversity {
+                            // Of the equal peers, prefer to remove those running the most
+                            // common clients.
+                            peers_on_subnet.sort_by_key(|(_, info)| {
+                                (
+                                    info.long_lived_subnet_count(),
+                                    std::cmp::Reverse(
+                                        client_peer_count
+                                            .get(&info.client().kind)
+                                            .copied()
+                                            .unwrap_or(0),
+                                    ),
+                                )
+                            });
+                        } else {
+                            peers_on_subnet.sort_by_key(|(_, info)| info.long_lived_subnet_count());
+                        }
 
                         // Try and find a candidate peer to remove from the subnet.
                         // We ignore peers that would put us below our target outbound peers
@@ -1108,6 +1365,11 @@ impl<E: EthSpec> PeerManager<E> {
                                 continue;
                             }
 
+                            if let Some(subnet) = duty_peers.get(candidate_peer) {
+                                duty_retained.insert(*candidate_peer, *subnet);
+                                continue;
+                            }
+
                             // Check the sync committee
                             if let Some(subnets) = peer_to_sync_committee.get(candidate_peer) {
                                 // The peer is subscribed to some long-lived sync-committees
@@ -1141,7 +1403,10 @@ impl<E: EthSpec> PeerManager<E> {
                         // outbound limit or min_subnet_count. In this case, we remove all
                         // peers from the pruning logic and try another subnet.
                         if let Some(index) = removed_peer_index {
-                            let (candidate_peer, _) = peers_on_subnet.remove(index);
+                            let (candidate_peer, info) = peers_on_subnet.remove(index);
+                            if let Some(count) = client_peer_count.get_mut(&info.client().kind) {
+                                *count = count.saturating_sub(1);
+                            }
                             // Remove pruned peers from other subnet counts
                             for subnet_peers in subnet_to_peer.values_mut() {
                                 subnet_peers.retain(|(peer_id, _)| peer_id != &candidate_peer);
@@ -1171,12 +1436,61 @@ impl<E: EthSpec> PeerManager<E> {
             }
         }
 
+        for (peer_id, subnet) in duty_retained {
+            debug!(
+                self.log,
+                "Retaining peer for an upcoming duty";
+                "peer_id" => %peer_id,
+                "subnet" => ?subnet,
+            );
+            let subnet_kind = match subnet {
+                Subnet::Attestation(_) => "attestation",
+                Subnet::SyncCommittee(_) => "sync_committee",
+                Subnet::DataColumn(_) => "data_column",
+            };
+            metrics::inc_counter_vec(&metrics::PEERS_RETAINED_FOR_DUTIES, &[subnet_kind]);
+        }
+
         // Disconnect the pruned peers.
         for peer_id in peers_to_prune {
             self.disconnect_peer(peer_id, GoodbyeReason::TooManyPeers);
         }
     }
 
+    /// Returns the peers which must not be pruned because of upcoming local duties, along with a
+    /// duty subnet they serve.
+    ///
+    /// For each duty subnet, these are the best scoring `TARGET_SUBNET_PEERS` connected peers which
+    /// are subscribed to it. Trusted peers are never pruned, so they are not counted.
+    fn duty_peers(&self) -> HashMap<PeerId, Subnet> {
+        let mut duty_peers = HashMap::new();
+        if self.duty_subnets.is_empty() {
+            return duty_peers;
+        }
+        let peers = self.network_globals.peers.read();
+        let best_peers =
+            peers.best_peers_by_status(|info| info.is_connected() && !info.is_trusted());
+        for subnet in self.duty_subnets.keys() {
+            let peers_on_subnet = best_peers
+                .iter()
+                .filter(|(_, info)| {
+                    info.on_subnet_gossipsub(subnet) || info.on_subnet_metadata(subnet)
+                })
+                .take(TARGET_SUBNET_PEERS);
+            for (peer_id, _) in peers_on_subnet {
+                duty_peers.entry(**peer_id).or_insert(*subnet);
+            }
+        }
+        duty_peers
+    }
+
+    /// Removes the duty subnets whose duties are complete.
+    fn expire_duty_subnets(&mut self) {
+        let now = Instant::now();
+        self.duty_subnets.retain(|_, until| *until > now);
+        metrics::set_gauge(&metrics::DUTY_SUBNETS, self.duty_subnets.len() as i64);
+    }
+
     /// Unbans any temporarily banned peers that have served their timeout.
     fn unban_temporary_banned_peers(&mut self) {
         for peer_id in self.temporary_banned_peers.remove_expired() {
@@ -1214,6 +1528,9 @@ impl<E: EthSpec> PeerManager<E> {
         // Maintain minimum count for sync committee peers.
         self.maintain_sync_committee_peers();
 
+        // Forget duties which are complete, so that their peers may be pruned.
+        self.expire_duty_subnets();
+
         // Prune any excess peers back to our target in such a way that incentivises good scores and
         // a uniform distribution of subnets.
         self.prune_excess_peers();
@@ -1223,6 +1540,57 @@ impl<E: EthSpec> PeerManager<E> {
 
         // Maintains memory by shrinking mappings
         self.shrink_mappings();
+
+        // Report changes in the reachability of our advertised addresses.
+        self.update_reachability();
+    }
+
+    /// Logs and records metrics for changes in the reachability of each transport.
+    fn update_reachability(&mut self) {
+        let now = Instant::now();
+        let in_grace_period = self.network_globals.reachability.in_grace_period(now);
+        for report in self.network_globals.reachability() {
+            let transport: &'static str = report.transport.into();
+            if self.metrics_enabled {
+                for status in ReachabilityStatus::iter() {
+                    metrics::set_gauge_vec(
+                        &metrics::REACHABILITY,
+                        &[transport, status.into()],
+                        (status == report.status) as i64,
+                    );
+                }
+            }
+
+            let previous = self.reachability.get(&report.transport).copied();
+            if previous == Some(report.status)
+                || (previous.is_none()
+                    && report.status == ReachabilityStatus::Unconfirmed
+                    && in_grace_period)
+            {
+                continue;
+            }
+
+            match report.status {
+                ReachabilityStatus::Reachable => {
+                    info!(self.log, "Advertised address is reachable";
+                        "transport" => transport,
+                        "port" => report.advertised_port,
+                    );
+                }
+                ReachabilityStatus::Unconfirmed => {
+                    warn!(self.log, "No inbound connections on advertised address";
+                        "info" => "check that the port is forwarded and not blocked by a firewall",
+                        "transport" => transport,
+                        "port" => report.advertised_port,
+                        "inbound_connections" => report.inbound_connections,
+                    );
+                }
+                ReachabilityStatus::NotAdvertised => {
+                    debug!(self.log, "Transport not advertised in ENR"; "transport" => transport);
+                }
+            }
+            self.reachability.insert(report.transport, report.status);
+        }
     }
 
     // Reduce memory footprint by routinely shrinking associating mappings.
@@ -1232,6 +1600,7 @@ impl<E: EthSpec> PeerManager<E> {
         self.status_peers.shrink_to(5);
         self.temporary_banned_peers.shrink_to_fit();
         self.sync_committee_subnets.shrink_to_fit();
+        self.duty_subnets.shrink_to_fit();
     }
 
     // Update metrics related to peer scoring.
@@ -1307,15 +1676,27 @@ impl<E: EthSpec> PeerManager<E> {
     fn update_peer_count_metrics(&self) {
         let mut peers_connected = 0;
         let mut clients_per_peer = HashMap::new();
+        let mut platforms_per_peer: HashMap<ClientPlatform, i64> = HashMap::new();
         let mut peers_connected_mutli: HashMap<(&str, &str), i32> = HashMap::new();
         let mut peers_per_custody_subnet_count: HashMap<u64, i64> = HashMap::new();
+        let mut peers_per_fork_readiness: HashMap<ForkReadiness, i64> = HashMap::new();
+        let local_enr_fork_id = self.network_globals.local_enr().eth2().ok();
 
         for (_, peer_info) in self.network_globals.peers.read().connected_peers() {
             peers_connected += 1;
 
+            if let Some(local_enr_fork_id) = &local_enr_fork_id {
+                *peers_per_fork_readiness
+                    .entry(peer_info.fork_readiness(local_enr_fork_id))
+                    .or_default() += 1;
+            }
+
             *clients_per_peer
                 .entry(peer_info.client().kind.to_string())
                 .or_default() += 1;
+            *platforms_per_peer
+                .entry(peer_info.client().platform)
+                .or_default() += 1;
 
             let direction = match peer_info.connection_direction() {
                 Some(ConnectionDirection::Incoming) => "inbound",
@@ -1369,6 +1750,26 @@ impl<E: EthSpec> PeerManager<E> {
             );
         }
 
+        // PEERS_PER_CLIENT_PLATFORM
+        for platform in ClientPlatform::iter() {
+            metrics::set_gauge_vec(
+                &metrics::PEERS_PER_CLIENT_PLATFORM,
+                &[platform.as_ref()],
+                *platforms_per_peer.get(&platform).unwrap_or(&0),
+            );
+        }
+
+        // PEERS_PER_FORK_READINESS
+        if local_enr_fork_id.is_some() {
+            for readiness in ForkReadiness::iter() {
+                metrics::set_gauge_vec(
+                    &metrics::PEERS_PER_FORK_READINESS,
+                    &[readiness.as_ref()],
+                    *peers_per_fork_readiness.get(&readiness).unwrap_or(&0),
+                );
+            }
+        }
+
         // PEERS_CONNECTED_MULTI
         for direction in ["inbound", "outbound", "none"] {
             for transport in ["quic", "tcp", "unknown"] {
@@ -1571,6 +1972,185 @@ mod tests {
         assert_eq!(peer_manager.network_globals.connected_or_dialing_peers(), 3);
     }
 
+    #[tokio::test]
+    async fn test_peer_manager_disconnects_unresponsive_peers() {
+        let mut peer_manager = build_peer_manager(3).await;
+        let responsive = PeerId::random();
+        let unresponsive = PeerId::random();
+        for peer in [&responsive, &unresponsive] {
+            peer_manager.inject_connect_outgoing(peer, "/ip4/0.0.0.0".parse().unwrap(), None);
+        }
+
+        // A peer which answers its pings keeps the regular schedule and has its RTT measured.
+        for _ in 0..=MAX_MISSED_PINGS {
+            assert_eq!(peer_manager.ping_peer(responsive), PingSchedule::Regular);
+            peer_manager.pong_response(&responsive, 0);
+        }
+        let peer_info = peer_manager
+            .network_globals
+            .peers
+            .read()
+            .peer_info(&responsive)
+            .cloned()
+            .unwrap();
+        assert!(peer_info.ping_rtt().is_some());
+        assert_eq!(peer_info.missed_pings(), 0);
+
+        // A peer which misses its pings is pinged more often, then disconnected.
+        assert_eq!(peer_manager.ping_peer(unresponsive), PingSchedule::Regular);
+        for _ in 1..MAX_MISSED_PINGS {
+            assert_eq!(
+                peer_manager.ping_peer(unresponsive),
+                PingSchedule::Unresponsive
+            );
+        }
+        assert!(peer_manager
+            .network_globals
+            .peers
+            .read()
+            .peer_info(&unresponsive)
+            .unwrap()
+            .ping_rtt()
+            .is_none());
+        assert_eq!(
+            peer_manager.ping_peer(unresponsive),
+            PingSchedule::Disconnected
+        );
+        assert!(peer_manager.events.iter().any(|event| matches!(
+            event,
+            PeerManagerEvent::DisconnectPeer(peer_id, GoodbyeReason::Fault) if *peer_id == unresponsive
+        )));
+        assert!(!peer_manager.events.iter().any(|event| matches!(
+            event,
+            PeerManagerEvent::DisconnectPeer(peer_id, _) if *peer_id == responsive
+        )));
+    }
+
+    #[tokio::test]
+    async fn test_peer_manager_penalises_stale_metadata() {
+        let mut peer_manager = build_peer_manager(3).await;
+        let peer = PeerId::random();
+        peer_manager.inject_connect_outgoing(&peer, "/ip4/0.0.0.0".parse().unwrap(), None);
+        let metadata = crate::rpc::MetaDataV2 {
+            seq_number: 1,
+            attnets: Default::default(),
+            syncnets: Default::default(),
+        };
+        peer_manager.meta_data_response(&peer, MetaData::V2(metadata.clone()));
+        let score = |peer_manager: &PeerManager<E>| {
+            peer_manager
+                .network_globals
+                .peers
+                .read()
+                .peer_info(&peer)
+                .unwrap()
+                .score()
+                .score()
+        };
+        let initial_score = score(&peer_manager);
+
+        // Pongs advertising the metadata we know are not penalised.
+        for _ in 0..MAX_STALE_META_DATA_PINGS {
+            peer_manager.pong_response(&peer, 1);
+        }
+        assert_eq!(score(&peer_manager), initial_score);
+
+        // Newer metadata is requested, and the peer is penalised once it has advertised it too
+        // many times without serving it.
+        for _ in 1..MAX_STALE_META_DATA_PINGS {
+            peer_manager.pong_response(&peer, 2);
+        }
+        assert!(peer_manager
+            .events
+            .iter()
+            .any(|event| matches!(event, PeerManagerEvent::MetaData(peer_id) if *peer_id == peer)));
+        assert_eq!(score(&peer_manager), initial_score);
+        peer_manager.ping_request(&peer, 2);
+        assert!(score(&peer_manager) < initial_score);
+        let peer_info = peer_manager
+            .network_globals
+            .peers
+            .read()
+            .peer_info(&peer)
+            .cloned()
+            .unwrap();
+        assert_eq!(peer_info.stale_meta_data_pings(), MAX_STALE_META_DATA_PINGS);
+
+        // Serving the metadata resets the count.
+        peer_manager.meta_data_response(
+            &peer,
+            MetaData::V2(crate::rpc::MetaDataV2 {
+                seq_number: 2,
+                ..metadata
+            }),
+        );
+        let peer_info = peer_manager
+            .network_globals
+            .peers
+            .read()
+            .peer_info(&peer)
+            .cloned()
+            .unwrap();
+        assert_eq!(peer_info.stale_meta_data_pings(), 0);
+    }
+
+    #[tokio::test]
+    async fn test_peer_manager_protect_peer() {
+        let mut peer_manager = build_peer_manager(3).await;
+        let peer = PeerId::random();
+        peer_manager.inject_connect_ingoing(&peer, "/ip4/0.0.0.0".parse().unwrap(), None);
+
+        // A fatal action bans an unprotected peer.
+        peer_manager.report_peer(
+            &peer,
+            PeerAction::Fatal,
+            ReportSource::PeerManager,
+            None,
+            "test",
+        );
+        assert!(peer_manager
+            .network_globals
+            .peers
+            .read()
+            .ban_status(&peer)
+            .is_some());
+
+        // Protecting the peer lifts the ban and prevents further penalties.
+        peer_manager.protect_peer(&peer);
+        let peer_info = peer_manager
+            .network_globals
+            .peers
+            .read()
+            .peer_info(&peer)
+            .cloned()
+            .unwrap();
+        assert!(peer_info.is_trusted());
+        assert!(!matches!(
+            peer_info.connection_status(),
+            PeerConnectionStatus::Disconnecting { to_ban: true }
+        ));
+        assert!(peer_manager
+            .network_globals
+            .peers
+            .read()
+            .ban_status(&peer)
+            .is_none());
+
+        peer_manager.report_peer(
+            &peer,
+            PeerAction::Fatal,
+            ReportSource::PeerManager,
+            None,
+            "test",
+        );
+        assert!(peer_manager
+            .network_globals
+            .peers
+            .read()
+            .ban_status(&peer)
+            .is_none());
+    }
+
     #[tokio::test]
     async fn test_peer_manager_not_enough_outbound_peers_no_panic_during_heartbeat() {
         let mut peer_manager = build_peer_manager(20).await;
@@ -1826,6 +2406,47 @@ mod tests {
         assert!(peers_should_have_removed.is_empty());
     }
 
+    #[tokio::test]
+    /// Test that peers on a subnet with an upcoming local duty are not pruned, even if their score
+    /// is negative.
+    async fn test_peer_manager_retains_duty_subnet_peers() {
+        let mut peer_manager = build_peer_manager(3).await;
+
+        let peers: Vec<_> = (0..5).map(|_| PeerId::random()).collect();
+        for peer in &peers {
+            peer_manager.inject_connect_ingoing(peer, "/ip4/0.0.0.0".parse().unwrap(), None);
+        }
+
+        // The only peer on the duty subnet has the worst score.
+        let duty_peer = peers[0];
+        let subnet = Subnet::Attestation(5.into());
+        {
+            let mut peer_db = peer_manager.network_globals.peers.write();
+            peer_db.add_subscription(&duty_peer, subnet);
+            peer_db
+                .peer_info_mut(&duty_peer)
+                .unwrap()
+                .add_to_score(-1.0);
+        }
+
+        peer_manager.add_duty_subnets([
+            SubnetDuty {
+                subnet,
+                until: Instant::now() + Duration::from_secs(60),
+            },
+            SubnetDuty {
+                subnet: Subnet::SyncCommittee(1.into()),
+                until: Instant::now(),
+            },
+        ]);
+        peer_manager.heartbeat();
+
+        assert_eq!(peer_manager.network_globals.connected_or_dialing_peers(), 3);
+        assert!(peer_manager.is_connected(&duty_peer));
+        // The completed duty is forgotten.
+        assert_eq!(peer_manager.duty_subnets.len(), 1);
+    }
+
     #[tokio::test]
     /// Test the pruning logic to remove grouped subnet peers
     async fn test_peer_manager_prune_grouped_subnet_peers() {
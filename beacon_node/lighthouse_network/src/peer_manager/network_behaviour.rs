@@ -2,6 +2,7 @@
 
 use std::net::IpAddr;
 use std::task::{Context, Poll};
+use std::time::Instant;
 
 use futures::StreamExt;
 use libp2p::core::transport::PortUse;
@@ -17,10 +18,12 @@ use slog::{debug, error, trace};
 use types::EthSpec;
 
 use crate::discovery::enr_ext::EnrExt;
-use crate::types::SyncState;
+use crate::types::{SyncState, Transport};
 use crate::{metrics, ClearDialError};
 
-use super::{ConnectingType, PeerManager, PeerManagerEvent};
+use super::{
+    ConnectingType, PeerManager, PeerManagerEvent, PingSchedule, UNRESPONSIVE_PING_INTERVAL,
+};
 
 impl<E: EthSpec> NetworkBehaviour for PeerManager<E> {
     type ConnectionHandler = ConnectionHandler;
@@ -46,10 +49,13 @@ impl<E: EthSpec> NetworkBehaviour for PeerManager<E> {
         // poll the timeouts for pings and status'
         loop {
             match self.inbound_ping_peers.poll_next_unpin(cx) {
-                Poll::Ready(Some(Ok(peer_id))) => {
-                    self.inbound_ping_peers.insert(peer_id);
-                    self.events.push(PeerManagerEvent::Ping(peer_id));
-                }
+                Poll::Ready(Some(Ok(peer_id))) => match self.ping_peer(peer_id) {
+                    PingSchedule::Regular => self.inbound_ping_peers.insert(peer_id),
+                    PingSchedule::Unresponsive => self
+                        .inbound_ping_peers
+                        .insert_at(peer_id, UNRESPONSIVE_PING_INTERVAL),
+                    PingSchedule::Disconnected => {}
+                },
                 Poll::Ready(Some(Err(e))) => {
                     error!(self.log, "Failed to check for inbound peers to ping"; "error" => e.to_string())
                 }
@@ -59,10 +65,13 @@ impl<E: EthSpec> NetworkBehaviour for PeerManager<E> {
 
         loop {
             match self.outbound_ping_peers.poll_next_unpin(cx) {
-                Poll::Ready(Some(Ok(peer_id))) => {
-                    self.outbound_ping_peers.insert(peer_id);
-                    self.events.push(PeerManagerEvent::Ping(peer_id));
-                }
+                Poll::Ready(Some(Ok(peer_id))) => match self.ping_peer(peer_id) {
+                    PingSchedule::Regular => self.outbound_ping_peers.insert(peer_id),
+                    PingSchedule::Unresponsive => self
+                        .outbound_ping_peers
+                        .insert_at(peer_id, UNRESPONSIVE_PING_INTERVAL),
+                    PingSchedule::Disconnected => {}
+                },
                 Poll::Ready(Some(Err(e))) => {
                     error!(self.log, "Failed to check for outbound peers to ping"; "error" => e.to_string())
                 }
@@ -184,6 +193,21 @@ impl<E: EthSpec> NetworkBehaviour for PeerManager<E> {
         remote_addr: &libp2p::Multiaddr,
     ) -> Result<libp2p::swarm::THandler<Self>, ConnectionDenied> {
         trace!(self.log, "Inbound connection"; "peer_id" => %peer_id, "multiaddr" => %remote_addr);
+
+        // Any inbound connection from a global address shows that our advertised address for its
+        // transport is reachable, even if the connection is rejected below. Connections from the
+        // local network are not evidence of an open NAT or firewall.
+        let remote_transport = Transport::from_remote_addr(remote_addr);
+        if let Some(transport) = remote_transport {
+            if self
+                .network_globals
+                .reachability
+                .record_inbound(transport, Instant::now())
+            {
+                debug!(self.log, "First inbound connection on transport"; "transport" => ?transport);
+            }
+        }
+
         // We already checked if the peer was banned on `handle_pending_inbound_connection`.
         if self.ban_status(&peer_id).is_some() {
             return Err(ConnectionDenied::new(
@@ -205,12 +229,16 @@ impl<E: EthSpec> NetworkBehaviour for PeerManager<E> {
             ));
         }
 
-        // We have an inbound connection, this is indicative of having our libp2p NAT ports open. We
-        // distinguish between ipv4 and ipv6 here:
-        match remote_addr.iter().next() {
-            Some(Protocol::Ip4(_)) => set_gauge_vec(&NAT_OPEN, &["libp2p_ipv4"], 1),
-            Some(Protocol::Ip6(_)) => set_gauge_vec(&NAT_OPEN, &["libp2p_ipv6"], 1),
-            _ => {}
+        // We have an inbound connection from a global address, this is indicative of having our
+        // libp2p NAT ports open. We distinguish between ipv4 and ipv6 here:
+        match remote_transport {
+            Some(Transport::Tcp4 | Transport::Quic4) => {
+                set_gauge_vec(&NAT_OPEN, &["libp2p_ipv4"], 1)
+            }
+            Some(Transport::Tcp6 | Transport::Quic6) => {
+                set_gauge_vec(&NAT_OPEN, &["libp2p_ipv6"], 1)
+            }
+            None => {}
         }
 
         Ok(ConnectionHandler)
@@ -281,6 +309,8 @@ impl<E: EthSpec> PeerManager<E> {
                     .push(PeerManagerEvent::PeerConnectedOutgoing(peer_id));
             }
         };
+
+        self.check_peer_enr_fork_id(&peer_id);
     }
 
     fn on_connection_closed(
@@ -1,8 +1,14 @@
 use crate::discovery::enr::PEERDAS_CUSTODY_SUBNET_COUNT_ENR_KEY;
 use crate::discovery::{peer_id_to_node_id, CombinedKey};
+use crate::rpc::GoodbyeReason;
 use crate::{metrics, multiaddr::Multiaddr, types::Subnet, Enr, EnrExt, Gossipsub, PeerId};
+use client::{ClientDiversity, ClientKind};
+use decisions::{PeerDecision, PeerDecisionKind, PeerDecisions};
 use itertools::Itertools;
-use peer_info::{ConnectionDirection, PeerConnectionStatus, PeerInfo};
+use peer_info::{
+    ConnectionDirection, ForkReadiness, ForkReadinessSummary, PeerConnectionStatus, PeerInfo,
+    RangeData,
+};
 use score::{PeerAction, ReportSource, Score, ScoreState};
 use slog::{crit, debug, error, trace, warn};
 use std::net::IpAddr;
@@ -13,9 +19,10 @@ use std::{
     fmt::Formatter,
 };
 use sync_status::SyncStatus;
-use types::{ChainSpec, DataColumnSubnetId, EthSpec};
+use types::{ChainSpec, DataColumnSubnetId, EnrForkId, EthSpec, Slot};
 
 pub mod client;
+pub mod decisions;
 pub mod peer_info;
 pub mod score;
 pub mod sync_status;
@@ -43,6 +50,8 @@ pub struct PeerDB<E: EthSpec> {
     banned_peers_count: BannedPeersCount,
     /// Specifies if peer scoring is disabled.
     disable_peer_scoring: bool,
+    /// Goodbyes exchanged with peers and our recent decisions to ban them.
+    decisions: PeerDecisions,
     /// PeerDB's logger
     log: slog::Logger,
 }
@@ -59,6 +68,7 @@ impl<E: EthSpec> PeerDB<E> {
             disconnected_peers: 0,
             banned_peers_count: BannedPeersCount::default(),
             disable_peer_scoring,
+            decisions: PeerDecisions::default(),
             peers,
         }
     }
@@ -73,6 +83,11 @@ impl<E: EthSpec> PeerDB<E> {
             .score()
     }
 
+    /// Returns the goodbyes exchanged with peers and our recent decisions to ban them.
+    pub fn peer_decisions(&self) -> PeerDecisions {
+        self.decisions.clone()
+    }
+
     /// Returns an iterator over all peers in the db.
     pub fn peers(&self) -> impl Iterator<Item = (&PeerId, &PeerInfo<E>)> {
         self.peers.iter()
@@ -178,6 +193,43 @@ impl<E: EthSpec> PeerDB<E> {
         }
     }
 
+    /// Counts the connected peers by client, client version and platform.
+    pub fn client_diversity(&self) -> ClientDiversity {
+        let mut diversity = ClientDiversity::default();
+        for (_, info) in self.connected_peers() {
+            let client = info.client();
+            diversity.connected_peers += 1;
+            let counts = diversity
+                .clients
+                .entry(client.kind.to_string())
+                .or_default();
+            counts.peers += 1;
+            *counts.versions.entry(client.version.clone()).or_default() += 1;
+            *diversity
+                .platforms
+                .entry(client.platform.to_string())
+                .or_default() += 1;
+        }
+        diversity
+    }
+
+    /// Counts the connected peers by their readiness for the next fork in the `local` fork id.
+    pub fn fork_readiness(&self, local: &EnrForkId) -> ForkReadinessSummary {
+        let mut summary = ForkReadinessSummary {
+            enr_fork_id: local.clone(),
+            ..ForkReadinessSummary::default()
+        };
+        for (_, info) in self.connected_peers() {
+            summary.connected_peers += 1;
+            match info.fork_readiness(local) {
+                ForkReadiness::Ready => summary.ready += 1,
+                ForkReadiness::NotReady => summary.not_ready += 1,
+                ForkReadiness::Unknown => summary.unknown += 1,
+            }
+        }
+        summary
+    }
+
     /// Gives the ids and info of all known connected peers.
     pub fn connected_peers(&self) -> impl Iterator<Item = (&PeerId, &PeerInfo<E>)> {
         self.peers.iter().filter(|(_, info)| info.is_connected())
@@ -367,6 +419,27 @@ impl<E: EthSpec> PeerDB<E> {
         Some(info.update_sync_status(sync_status))
     }
 
+    /// Allows the sync module to record that a peer cannot serve `data` before `slot`, e.g.
+    /// because it has pruned them or has not yet backfilled them.
+    pub fn update_earliest_available_slot(
+        &mut self,
+        peer_id: &PeerId,
+        slot: Slot,
+        data: RangeData,
+    ) {
+        if let Some(info) = self.peers.get_mut(peer_id) {
+            info.update_earliest_available_slot(slot, data);
+        }
+    }
+
+    /// Returns `false` if `peer_id` is known to be unable to serve blocks and the accompanying
+    /// `data` starting at `start_slot`.
+    pub fn can_serve_range(&self, peer_id: &PeerId, start_slot: Slot, data: RangeData) -> bool {
+        self.peers
+            .get(peer_id)
+            .map_or(true, |info| info.can_serve_range(start_slot, data))
+    }
+
     /// Updates the scores of known peers according to their connection status and the time that
     /// has passed. This function returns a list of peers that have been unbanned.
     /// NOTE: Peer scores cannot be penalized during the update, they can only increase. Therefore
@@ -473,6 +546,7 @@ impl<E: EthSpec> PeerDB<E> {
             let result = match action {
                 ScoreTransitionResult::Banned => {
                     // The peer was banned as a result of this action.
+                    self.record_ban(&peer_id, "gossipsub_score", ReportSource::Gossipsub.into());
                     self.update_connection_state(&peer_id, NewConnectionState::Banned)
                         .into()
                 }
@@ -511,6 +585,40 @@ impl<E: EthSpec> PeerDB<E> {
         results
     }
 
+    /// Marks a peer as trusted, so that it is never penalised, pruned or banned.
+    ///
+    /// If the peer is currently banned it is unbanned, and the IP addresses which can be unbanned
+    /// are returned so that the peer manager can lift the ban.
+    pub(super) fn protect_peer(&mut self, peer_id: &PeerId) -> ScoreUpdateResult {
+        let info = self.peers.entry(*peer_id).or_default();
+        let previous_state = info.score_state();
+        info.set_trusted();
+        if let PeerConnectionStatus::Disconnecting { to_ban: true } = info.connection_status() {
+            // Don't ban the peer once the disconnection completes.
+            info.set_connection_status(PeerConnectionStatus::Disconnecting { to_ban: false });
+        }
+
+        match Self::handle_score_transition(previous_state, peer_id, info, &self.log) {
+            ScoreTransitionResult::Unbanned => {
+                self.update_connection_state(peer_id, NewConnectionState::Unbanned);
+                let seen_ip_addresses = self
+                    .peers
+                    .get(peer_id)
+                    .map(|info| {
+                        info.seen_ip_addresses()
+                            .filter(|ip| !self.is_ip_banned(ip))
+                            .collect::<Vec<_>>()
+                    })
+                    .unwrap_or_default();
+                ScoreUpdateResult::Unbanned(seen_ip_addresses)
+            }
+            // Restoring the score to the maximum can only unban the peer.
+            ScoreTransitionResult::Banned
+            | ScoreTransitionResult::Disconnected
+            | ScoreTransitionResult::NoAction => ScoreUpdateResult::NoAction,
+        }
+    }
+
     /// Reports a peer for some action.
     ///
     /// The action can only cause a negative effect. This can lead to disconnecting or banning a
@@ -527,6 +635,7 @@ impl<E: EthSpec> PeerDB<E> {
         msg: &'static str,
     ) -> ScoreUpdateResult {
         metrics::inc_counter_vec(&metrics::REPORT_PEER_MSGS, &[msg]);
+        let source: &'static str = source.into();
 
         match self.peers.get_mut(peer_id) {
             Some(info) => {
@@ -534,7 +643,7 @@ impl<E: EthSpec> PeerDB<E> {
                 info.apply_peer_action_to_score(action);
                 metrics::inc_counter_vec(
                     &metrics::PEER_ACTION_EVENTS_PER_CLIENT,
-                    &[info.client().kind.as_ref(), action.as_ref(), source.into()],
+                    &[info.client().kind.as_ref(), action.as_ref(), source],
                 );
                 let result =
                     Self::handle_score_transition(previous_state, peer_id, info, &self.log);
@@ -550,6 +659,7 @@ impl<E: EthSpec> PeerDB<E> {
                 match result {
                     ScoreTransitionResult::Banned => {
                         // The peer was banned as a result of this action.
+                        self.record_ban(peer_id, msg, source);
                         self.update_connection_state(peer_id, NewConnectionState::Banned)
                             .into()
                     }
@@ -587,6 +697,60 @@ impl<E: EthSpec> PeerDB<E> {
         }
     }
 
+    /// Records a goodbye sent to a peer.
+    pub(crate) fn goodbye_sent(&mut self, peer_id: &PeerId, reason: &GoodbyeReason) {
+        self.record_goodbye(peer_id, PeerDecisionKind::GoodbyeSent, "sent", reason);
+    }
+
+    /// Records a goodbye received from a peer.
+    pub(crate) fn goodbye_received(&mut self, peer_id: &PeerId, reason: &GoodbyeReason) {
+        self.record_goodbye(
+            peer_id,
+            PeerDecisionKind::GoodbyeReceived,
+            "received",
+            reason,
+        );
+    }
+
+    fn record_goodbye(
+        &mut self,
+        peer_id: &PeerId,
+        kind: PeerDecisionKind,
+        direction: &str,
+        reason: &GoodbyeReason,
+    ) {
+        let client = self.client_kind(peer_id);
+        metrics::inc_counter_vec(
+            &metrics::GOODBYES_PER_CLIENT,
+            &[client.as_ref(), &reason.to_string(), direction],
+        );
+        let decision = PeerDecision::goodbye(
+            peer_id.to_string(),
+            client.as_ref().to_string(),
+            kind,
+            reason,
+            self.score(peer_id),
+        );
+        self.decisions.record(decision);
+    }
+
+    fn record_ban(&mut self, peer_id: &PeerId, msg: &str, source: &str) {
+        let decision = PeerDecision::ban(
+            peer_id.to_string(),
+            self.client_kind(peer_id).as_ref().to_string(),
+            msg,
+            source,
+            self.score(peer_id),
+        );
+        self.decisions.record(decision);
+    }
+
+    fn client_kind(&self, peer_id: &PeerId) -> ClientKind {
+        self.peers
+            .get(peer_id)
+            .map_or(ClientKind::Unknown, |info| info.client().kind)
+    }
+
     /// Update min ttl of a peer.
     // VISIBILITY: Only the peer manager can update the min_ttl
     pub(super) fn update_min_ttl(&mut self, peer_id: &PeerId, min_ttl: Instant) {
@@ -1375,6 +1539,43 @@ mod tests {
         assert_eq!(peer_info.unwrap().connections(), (n_in, n_out));
     }
 
+    #[test]
+    fn test_earliest_available_slot() {
+        let mut pdb = get_db();
+        let peer = PeerId::random();
+        pdb.connect_outgoing(&peer, "/ip4/0.0.0.0".parse().unwrap(), None);
+
+        // Nothing is known about the peer's data yet.
+        assert!(pdb.can_serve_range(&peer, Slot::new(0), RangeData::Blobs));
+
+        pdb.update_earliest_available_slot(&peer, Slot::new(64), RangeData::Blobs);
+        assert!(pdb.can_serve_range(&peer, Slot::new(0), RangeData::Blocks));
+        assert!(pdb.can_serve_range(&peer, Slot::new(0), RangeData::Columns));
+        assert!(!pdb.can_serve_range(&peer, Slot::new(32), RangeData::Blobs));
+        assert!(pdb.can_serve_range(&peer, Slot::new(64), RangeData::Blobs));
+
+        pdb.update_earliest_available_slot(&peer, Slot::new(48), RangeData::Columns);
+        assert!(!pdb.can_serve_range(&peer, Slot::new(32), RangeData::Columns));
+        assert!(pdb.can_serve_range(&peer, Slot::new(48), RangeData::Columns));
+
+        // Blocks which are unavailable imply that their blobs and columns are too.
+        pdb.update_earliest_available_slot(&peer, Slot::new(56), RangeData::Blocks);
+        assert!(!pdb.can_serve_range(&peer, Slot::new(0), RangeData::Blocks));
+        assert!(pdb.can_serve_range(&peer, Slot::new(56), RangeData::Blocks));
+        assert!(!pdb.can_serve_range(&peer, Slot::new(48), RangeData::Columns));
+        assert!(!pdb.can_serve_range(&peer, Slot::new(56), RangeData::Blobs));
+
+        // The earliest available slot never moves backwards.
+        pdb.update_earliest_available_slot(&peer, Slot::new(16), RangeData::Blocks);
+        assert_eq!(
+            pdb.peer_info(&peer).unwrap().earliest_available_slot(),
+            Some(Slot::new(56))
+        );
+
+        // Nothing is known about peers which are not in the database.
+        assert!(pdb.can_serve_range(&PeerId::random(), Slot::new(0), RangeData::Blobs));
+    }
+
     #[test]
     fn test_outbound_only_peers_counted_correctly() {
         let mut pdb = get_db();
@@ -2064,6 +2265,43 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_peer_decisions() {
+        let mut pdb = get_db();
+        let peer = PeerId::random();
+        pdb.connect_ingoing(&peer, "/ip4/0.0.0.0".parse().unwrap(), None);
+
+        pdb.goodbye_received(&peer, &GoodbyeReason::TooManyPeers);
+        let _ = pdb.report_peer(
+            &peer,
+            PeerAction::Fatal,
+            ReportSource::Processor,
+            "invalid_block",
+        );
+        pdb.goodbye_sent(&peer, &GoodbyeReason::Fault);
+
+        let decisions = pdb.peer_decisions();
+        let kinds = decisions
+            .recent
+            .iter()
+            .map(|decision| decision.kind)
+            .collect::<Vec<_>>();
+        assert_eq!(
+            kinds,
+            vec![
+                PeerDecisionKind::GoodbyeReceived,
+                PeerDecisionKind::Banned,
+                PeerDecisionKind::GoodbyeSent
+            ]
+        );
+        assert_eq!(decisions.recent[1].reason, "invalid_block");
+        assert_eq!(decisions.recent[1].source.as_deref(), Some("processor"));
+
+        let counts = &decisions.goodbyes[ClientKind::Unknown.as_ref()];
+        assert_eq!(counts.received[&GoodbyeReason::TooManyPeers.to_string()], 1);
+        assert_eq!(counts.sent[&GoodbyeReason::Fault.to_string()], 1);
+    }
+
     #[test]
     fn test_disable_peer_scoring() {
         let peer = PeerId::random();
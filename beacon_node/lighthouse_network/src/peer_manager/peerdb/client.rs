@@ -3,11 +3,12 @@
 //! Currently using identify to fingerprint.
 
 use libp2p::identify::Info as IdentifyInfo;
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
 use strum::{AsRefStr, EnumIter, IntoStaticStr};
 
 /// Various client and protocol information related to a node.
-#[derive(Clone, Debug, Serialize)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct Client {
     /// The client's name (Ex: lighthouse, prism, nimbus, etc)
     pub kind: ClientKind,
@@ -15,13 +16,27 @@ pub struct Client {
     pub version: String,
     /// The OS version of the client.
     pub os_version: String,
+    /// The platform of the client, parsed from `os_version`.
+    pub platform: ClientPlatform,
     /// The libp2p protocol version.
     pub protocol_version: String,
     /// Identify agent string
     pub agent_string: Option<String>,
 }
 
-#[derive(Clone, Copy, Debug, Serialize, PartialEq, AsRefStr, IntoStaticStr, EnumIter)]
+#[derive(
+    Clone,
+    Copy,
+    Debug,
+    Serialize,
+    Deserialize,
+    PartialEq,
+    Eq,
+    Hash,
+    AsRefStr,
+    IntoStaticStr,
+    EnumIter,
+)]
 pub enum ClientKind {
     /// A lighthouse node (the best kind).
     Lighthouse,
@@ -35,16 +50,84 @@ pub enum ClientKind {
     Lodestar,
     /// A Caplin node.
     Caplin,
+    /// A Grandine node.
+    Grandine,
     /// An unknown client.
     Unknown,
 }
 
+/// The operating system which a client reports running on.
+#[derive(
+    Clone,
+    Copy,
+    Debug,
+    Serialize,
+    Deserialize,
+    PartialEq,
+    Eq,
+    Hash,
+    AsRefStr,
+    IntoStaticStr,
+    EnumIter,
+)]
+#[serde(rename_all = "lowercase")]
+#[strum(serialize_all = "lowercase")]
+pub enum ClientPlatform {
+    Linux,
+    MacOs,
+    Windows,
+    Unknown,
+}
+
+impl ClientPlatform {
+    /// Parse the platform from the OS portion of an agent string, e.g. `x86_64-linux`.
+    pub fn from_os_version(os_version: &str) -> Self {
+        let os_version = os_version.to_lowercase();
+        if os_version.contains("linux") {
+            ClientPlatform::Linux
+        } else if os_version.contains("macos")
+            || os_version.contains("darwin")
+            || os_version.contains("apple")
+        {
+            ClientPlatform::MacOs
+        } else if os_version.contains("windows") {
+            ClientPlatform::Windows
+        } else {
+            ClientPlatform::Unknown
+        }
+    }
+}
+
+impl std::fmt::Display for ClientPlatform {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_ref())
+    }
+}
+
+/// The number of connected peers by client, client version and platform.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct ClientDiversity {
+    pub connected_peers: usize,
+    /// The peers of each client, keyed by `ClientKind`.
+    pub clients: BTreeMap<String, ClientPeerCounts>,
+    /// The peers on each platform, keyed by `ClientPlatform`.
+    pub platforms: BTreeMap<String, usize>,
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct ClientPeerCounts {
+    pub peers: usize,
+    /// The peers running each version of the client.
+    pub versions: BTreeMap<String, usize>,
+}
+
 impl Default for Client {
     fn default() -> Self {
         Client {
             kind: ClientKind::Unknown,
             version: "unknown".into(),
             os_version: "unknown".into(),
+            platform: ClientPlatform::Unknown,
             protocol_version: "unknown".into(),
             agent_string: None,
         }
@@ -59,6 +142,7 @@ impl Client {
         Client {
             kind,
             version,
+            platform: ClientPlatform::from_os_version(&os_version),
             os_version,
             protocol_version: info.protocol_version.clone(),
             agent_string: Some(info.agent_version.clone()),
@@ -91,6 +175,11 @@ impl std::fmt::Display for Client {
             ),
             ClientKind::Lodestar => write!(f, "Lodestar: version: {}", self.version),
             ClientKind::Caplin => write!(f, "Caplin"),
+            ClientKind::Grandine => write!(
+                f,
+                "Grandine: version: {}, os_version: {}",
+                self.version, self.os_version
+            ),
             ClientKind::Unknown => {
                 if let Some(agent_string) = &self.agent_string {
                     write!(f, "Unknown: {}", agent_string)
@@ -185,6 +274,16 @@ fn client_from_agent_version(agent_version: &str) -> (ClientKind, String, String
             }
             (kind, version, os_version)
         }
+        Some("Grandine") => {
+            let kind = ClientKind::Grandine;
+            if let Some(agent_version) = agent_split.next() {
+                version = agent_version.into();
+                if let Some(agent_os_version) = agent_split.next() {
+                    os_version = agent_os_version.into();
+                }
+            }
+            (kind, version, os_version)
+        }
         Some("erigon") => {
             let client_kind = if let Some("caplin") = agent_split.next() {
                 ClientKind::Caplin
@@ -199,3 +298,46 @@ fn client_from_agent_version(agent_version: &str) -> (ClientKind, String, String
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_agent_strings() {
+        let (kind, version, os_version) =
+            client_from_agent_version("Lighthouse/v6.0.1-4c25a1e/x86_64-linux");
+        assert_eq!(kind, ClientKind::Lighthouse);
+        assert_eq!(version, "v6.0.1-4c25a1e");
+        assert_eq!(
+            ClientPlatform::from_os_version(&os_version),
+            ClientPlatform::Linux
+        );
+
+        let (kind, version, os_version) = client_from_agent_version(
+            "teku/teku/v24.10.0/linux-aarch_64/-eclipseadoptium-openjdk64bitservervm-java-21",
+        );
+        assert_eq!(kind, ClientKind::Teku);
+        assert_eq!(version, "v24.10.0");
+        assert_eq!(
+            ClientPlatform::from_os_version(&os_version),
+            ClientPlatform::Linux
+        );
+
+        let (kind, _, os_version) =
+            client_from_agent_version("Grandine/1.0.0-e6b6c9b/aarch64-macos");
+        assert_eq!(kind, ClientKind::Grandine);
+        assert_eq!(
+            ClientPlatform::from_os_version(&os_version),
+            ClientPlatform::MacOs
+        );
+
+        let (kind, version, os_version) = client_from_agent_version("rust-libp2p/0.54.0");
+        assert_eq!(kind, ClientKind::Unknown);
+        assert_eq!(version, "unknown");
+        assert_eq!(
+            ClientPlatform::from_os_version(&os_version),
+            ClientPlatform::Unknown
+        );
+    }
+}
@@ -0,0 +1,192 @@
+//! A record of the goodbyes exchanged with peers and of our decisions to ban them, so that users
+//! can see why peers are disconnecting from them.
+use crate::rpc::GoodbyeReason;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, VecDeque};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// The number of recent peer decisions to remember.
+pub const MAX_PEER_DECISIONS: usize = 256;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PeerDecisionKind {
+    /// We sent the peer a goodbye and disconnected it.
+    GoodbyeSent,
+    /// The peer sent us a goodbye.
+    GoodbyeReceived,
+    /// We banned the peer.
+    Banned,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct PeerDecision {
+    /// Seconds since the UNIX epoch.
+    pub timestamp: u64,
+    pub peer_id: String,
+    /// The peer's `ClientKind`.
+    pub client: String,
+    pub kind: PeerDecisionKind,
+    /// The goodbye reason, or for bans the report which caused the ban.
+    pub reason: String,
+    /// The service which reported the peer, for bans.
+    pub source: Option<String>,
+    /// The peer's score at the time of the decision.
+    pub score: f64,
+}
+
+/// The number of goodbyes exchanged with peers of a single client, keyed by `GoodbyeReason`.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct GoodbyeCounts {
+    pub sent: BTreeMap<String, u64>,
+    pub received: BTreeMap<String, u64>,
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct PeerDecisions {
+    /// The goodbyes exchanged since startup, keyed by `ClientKind`.
+    pub goodbyes: BTreeMap<String, GoodbyeCounts>,
+    /// The most recent `MAX_PEER_DECISIONS` decisions, oldest first.
+    pub recent: VecDeque<PeerDecision>,
+}
+
+impl PeerDecisions {
+    /// Record a decision, counting it towards the goodbye totals if it is a goodbye.
+    pub(super) fn record(&mut self, decision: PeerDecision) {
+        let counts = self.goodbyes.entry(decision.client.clone());
+        match decision.kind {
+            PeerDecisionKind::GoodbyeSent => {
+                *counts
+                    .or_default()
+                    .sent
+                    .entry(decision.reason.clone())
+                    .or_default() += 1
+            }
+            PeerDecisionKind::GoodbyeReceived => {
+                *counts
+                    .or_default()
+                    .received
+                    .entry(decision.reason.clone())
+                    .or_default() += 1
+            }
+            PeerDecisionKind::Banned => {}
+        }
+
+        if self.recent.len() >= MAX_PEER_DECISIONS {
+            self.recent.pop_front();
+        }
+        self.recent.push_back(decision);
+    }
+}
+
+impl PeerDecision {
+    pub(super) fn goodbye(
+        peer_id: String,
+        client: String,
+        kind: PeerDecisionKind,
+        reason: &GoodbyeReason,
+        score: f64,
+    ) -> Self {
+        PeerDecision {
+            timestamp: unix_timestamp(),
+            peer_id,
+            client,
+            kind,
+            reason: reason.to_string(),
+            source: None,
+            score,
+        }
+    }
+
+    pub(super) fn ban(
+        peer_id: String,
+        client: String,
+        msg: &str,
+        source: &str,
+        score: f64,
+    ) -> Self {
+        PeerDecision {
+            timestamp: unix_timestamp(),
+            peer_id,
+            client,
+            kind: PeerDecisionKind::Banned,
+            reason: msg.to_string(),
+            source: Some(source.to_string()),
+            score,
+        }
+    }
+}
+
+fn unix_timestamp() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |duration| duration.as_secs())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn goodbye(kind: PeerDecisionKind, client: &str, reason: GoodbyeReason) -> PeerDecision {
+        PeerDecision::goodbye("peer".into(), client.into(), kind, &reason, 0.0)
+    }
+
+    #[test]
+    fn goodbyes_are_counted_per_client_and_reason() {
+        let mut decisions = PeerDecisions::default();
+        decisions.record(goodbye(
+            PeerDecisionKind::GoodbyeSent,
+            "Teku",
+            GoodbyeReason::TooManyPeers,
+        ));
+        decisions.record(goodbye(
+            PeerDecisionKind::GoodbyeSent,
+            "Teku",
+            GoodbyeReason::TooManyPeers,
+        ));
+        decisions.record(goodbye(
+            PeerDecisionKind::GoodbyeReceived,
+            "Teku",
+            GoodbyeReason::IrrelevantNetwork,
+        ));
+        decisions.record(PeerDecision::ban(
+            "peer".into(),
+            "Prysm".into(),
+            "invalid_block",
+            "processor",
+            -100.0,
+        ));
+
+        let teku = &decisions.goodbyes["Teku"];
+        assert_eq!(teku.sent[&GoodbyeReason::TooManyPeers.to_string()], 2);
+        assert_eq!(
+            teku.received[&GoodbyeReason::IrrelevantNetwork.to_string()],
+            1
+        );
+        // Bans are not goodbyes.
+        assert!(!decisions.goodbyes.contains_key("Prysm"));
+        assert_eq!(decisions.recent.len(), 4);
+    }
+
+    #[test]
+    fn recent_decisions_are_bounded() {
+        let mut decisions = PeerDecisions::default();
+        for _ in 0..MAX_PEER_DECISIONS {
+            decisions.record(goodbye(
+                PeerDecisionKind::GoodbyeReceived,
+                "Lighthouse",
+                GoodbyeReason::ClientShutdown,
+            ));
+        }
+        decisions.record(goodbye(
+            PeerDecisionKind::GoodbyeSent,
+            "Lighthouse",
+            GoodbyeReason::Fault,
+        ));
+        assert_eq!(decisions.recent.len(), MAX_PEER_DECISIONS);
+        assert_eq!(
+            decisions.recent.back().unwrap().kind,
+            PeerDecisionKind::GoodbyeSent
+        );
+    }
+}
@@ -6,18 +6,19 @@ use crate::{rpc::MetaData, types::Subnet};
 use discv5::Enr;
 use libp2p::core::multiaddr::{Multiaddr, Protocol};
 use serde::{
+    de::{self, Deserializer},
     ser::{SerializeStruct, Serializer},
-    Serialize,
+    Deserialize, Serialize,
 };
 use std::collections::HashSet;
 use std::net::IpAddr;
-use std::time::Instant;
-use strum::AsRefStr;
-use types::{DataColumnSubnetId, EthSpec};
+use std::time::{Duration, Instant};
+use strum::{AsRefStr, EnumIter};
+use types::{DataColumnSubnetId, EnrForkId, EthSpec, Slot};
 use PeerConnectionStatus::*;
 
 /// Information about a given connected peer.
-#[derive(Clone, Debug, Serialize)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
 #[serde(bound = "E: EthSpec")]
 pub struct PeerInfo<E: EthSpec> {
     /// The peers reputation
@@ -56,6 +57,61 @@ pub struct PeerInfo<E: EthSpec> {
     connection_direction: Option<ConnectionDirection>,
     /// The enr of the peer, if known.
     enr: Option<Enr>,
+    /// The smoothed round-trip time of the peer's responses to our pings, if known.
+    #[serde(
+        rename = "ping_rtt_ms",
+        serialize_with = "serialize_ping_rtt",
+        deserialize_with = "deserialize_ping_rtt"
+    )]
+    ping_rtt: Option<Duration>,
+    /// The time at which our latest unanswered ping was sent to the peer.
+    #[serde(skip)]
+    pending_ping: Option<Instant>,
+    /// The number of consecutive pings which the peer has not answered.
+    missed_pings: u32,
+    /// The number of consecutive pings and pongs in which the peer advertised a metadata sequence
+    /// number newer than the metadata it has given us.
+    stale_meta_data_pings: u32,
+    /// A lower bound on the earliest slot for which the peer can serve blocks, learned from the
+    /// peer responding that a by-range request was unavailable. `None` if not known.
+    #[serde(default)]
+    earliest_available_slot: Option<Slot>,
+    /// As `earliest_available_slot`, but for blobs, which are pruned independently of blocks.
+    #[serde(default)]
+    earliest_available_blob_slot: Option<Slot>,
+    /// As `earliest_available_slot`, but for data columns, which are pruned independently of
+    /// blocks.
+    #[serde(default)]
+    earliest_available_column_slot: Option<Slot>,
+}
+
+/// The data requested alongside blocks by a by-range request.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RangeData {
+    /// Blocks only.
+    Blocks,
+    /// Blocks and blobs.
+    Blobs,
+    /// Blocks and data columns.
+    Columns,
+}
+
+/// The weight given to each new ping round-trip time when smoothing, as a denominator.
+const PING_RTT_SMOOTHING: u32 = 8;
+
+fn serialize_ping_rtt<S: Serializer>(
+    ping_rtt: &Option<Duration>,
+    serializer: S,
+) -> Result<S::Ok, S::Error> {
+    ping_rtt
+        .map(|rtt| rtt.as_millis() as u64)
+        .serialize(serializer)
+}
+
+fn deserialize_ping_rtt<'de, D: Deserializer<'de>>(
+    deserializer: D,
+) -> Result<Option<Duration>, D::Error> {
+    Ok(Option::<u64>::deserialize(deserializer)?.map(Duration::from_millis))
 }
 
 impl<E: EthSpec> Default for PeerInfo<E> {
@@ -74,6 +130,13 @@ impl<E: EthSpec> Default for PeerInfo<E> {
             is_trusted: false,
             connection_direction: None,
             enr: None,
+            ping_rtt: None,
+            pending_ping: None,
+            missed_pings: 0,
+            stale_meta_data_pings: 0,
+            earliest_available_slot: None,
+            earliest_available_blob_slot: None,
+            earliest_available_column_slot: None,
         }
     }
 }
@@ -117,11 +180,54 @@ impl<E: EthSpec> PeerInfo<E> {
         &self.listening_addresses
     }
 
+    /// Returns the smoothed round-trip time of the peer's responses to our pings, if known.
+    pub fn ping_rtt(&self) -> Option<Duration> {
+        self.ping_rtt
+    }
+
+    /// Returns the number of consecutive pings which the peer has not answered.
+    pub fn missed_pings(&self) -> u32 {
+        self.missed_pings
+    }
+
+    /// Returns the number of consecutive pings and pongs in which the peer advertised metadata
+    /// newer than the metadata we know.
+    pub fn stale_meta_data_pings(&self) -> u32 {
+        self.stale_meta_data_pings
+    }
+
     /// Returns the connection direction for the peer.
     pub fn connection_direction(&self) -> Option<&ConnectionDirection> {
         self.connection_direction.as_ref()
     }
 
+    /// Returns the earliest slot for which the peer is known to be able to serve blocks.
+    pub fn earliest_available_slot(&self) -> Option<Slot> {
+        self.earliest_available_slot
+    }
+
+    /// Returns the earliest slot for which the peer is known to be able to serve blobs.
+    pub fn earliest_available_blob_slot(&self) -> Option<Slot> {
+        self.earliest_available_blob_slot
+    }
+
+    /// Returns the earliest slot for which the peer is known to be able to serve data columns.
+    pub fn earliest_available_column_slot(&self) -> Option<Slot> {
+        self.earliest_available_column_slot
+    }
+
+    /// Returns `false` if the peer is known to be unable to serve blocks and the accompanying
+    /// `data` starting at `start_slot`.
+    pub fn can_serve_range(&self, start_slot: Slot, data: RangeData) -> bool {
+        let available = |earliest: Option<Slot>| earliest.map_or(true, |slot| start_slot >= slot);
+        available(self.earliest_available_slot)
+            && match data {
+                RangeData::Blocks => true,
+                RangeData::Blobs => available(self.earliest_available_blob_slot),
+                RangeData::Columns => available(self.earliest_available_column_slot),
+            }
+    }
+
     /// Returns the sync status of the peer.
     pub fn sync_status(&self) -> &SyncStatus {
         &self.sync_status
@@ -148,6 +254,20 @@ impl<E: EthSpec> PeerInfo<E> {
         self.enr.as_ref()
     }
 
+    /// Whether the peer's ENR advertises the same next fork as the `local` ENR.
+    pub fn fork_readiness(&self, local: &EnrForkId) -> ForkReadiness {
+        match self.enr.as_ref().map(|enr| enr.eth2()) {
+            Some(Ok(peer))
+                if peer.next_fork_version == local.next_fork_version
+                    && peer.next_fork_epoch == local.next_fork_epoch =>
+            {
+                ForkReadiness::Ready
+            }
+            Some(Ok(_)) => ForkReadiness::NotReady,
+            Some(Err(_)) | None => ForkReadiness::Unknown,
+        }
+    }
+
     /// An iterator over all the subnets this peer is subscribed to.
     pub fn subnets(&self) -> impl Iterator<Item = &Subnet> {
         self.subnets.iter()
@@ -339,6 +459,20 @@ impl<E: EthSpec> PeerInfo<E> {
         self.sync_status.update(sync_status)
     }
 
+    /// Records that the peer cannot serve `data` before `slot`, or blocks if `RangeData::Blocks`.
+    ///
+    /// The earliest available slot only moves forward, so that a later response covering a
+    /// smaller range does not hide an earlier observation.
+    // VISIBILITY: The network sync records the ranges that peers were unable to serve
+    pub fn update_earliest_available_slot(&mut self, slot: Slot, data: RangeData) {
+        let earliest = match data {
+            RangeData::Blocks => &mut self.earliest_available_slot,
+            RangeData::Blobs => &mut self.earliest_available_blob_slot,
+            RangeData::Columns => &mut self.earliest_available_column_slot,
+        };
+        *earliest = Some(earliest.map_or(slot, |earliest| earliest.max(slot)));
+    }
+
     /// Sets the client of the peer.
     // VISIBILITY: The peer manager is able to set the client
     pub(in crate::peer_manager) fn set_client(&mut self, client: Client) {
@@ -359,6 +493,7 @@ impl<E: EthSpec> PeerInfo<E> {
     // VISIBILITY: The peer manager is able to adjust the meta_data
     pub(in crate::peer_manager) fn set_meta_data(&mut self, meta_data: MetaData<E>) {
         self.meta_data = Some(meta_data);
+        self.stale_meta_data_pings = 0;
     }
 
     /// Sets the connection status of the peer.
@@ -393,6 +528,61 @@ impl<E: EthSpec> PeerInfo<E> {
         self.subnets.remove(subnet);
     }
 
+    /// Marks the peer as trusted and restores its score to the maximum.
+    pub(super) fn set_trusted(&mut self) {
+        self.is_trusted = true;
+        self.score = Score::max_score();
+    }
+
+    /// Records that a ping was sent to the peer, returning the number of consecutive pings which
+    /// the peer has not answered, excluding this one.
+    pub(in crate::peer_manager) fn on_ping_sent(&mut self, now: Instant) -> u32 {
+        if self.pending_ping.is_some() {
+            self.missed_pings = self.missed_pings.saturating_add(1);
+        }
+        self.pending_ping = Some(now);
+        self.missed_pings
+    }
+
+    /// Records that the peer answered our ping, returning the round-trip time.
+    ///
+    /// The round-trip time is only measured if the peer answered all of our pings, otherwise the
+    /// pong can't be matched with the ping it answers.
+    pub(in crate::peer_manager) fn on_pong(&mut self, now: Instant) -> Option<Duration> {
+        let pending_ping = self.pending_ping.take();
+        let missed_pings = std::mem::take(&mut self.missed_pings);
+        let rtt = pending_ping
+            .filter(|_| missed_pings == 0)
+            .map(|sent| now.saturating_duration_since(sent))?;
+        self.ping_rtt = Some(match self.ping_rtt {
+            Some(ping_rtt) => (ping_rtt * (PING_RTT_SMOOTHING - 1) + rtt) / PING_RTT_SMOOTHING,
+            None => rtt,
+        });
+        Some(rtt)
+    }
+
+    /// Records that the peer pinged us, which shows it is alive even if it has not answered our
+    /// pings.
+    pub(in crate::peer_manager) fn on_ping_received(&mut self) {
+        self.pending_ping = None;
+        self.missed_pings = 0;
+    }
+
+    /// Records the metadata sequence number the peer advertised in a ping or pong, returning the
+    /// number of consecutive pings and pongs in which it was newer than the metadata we know.
+    pub(in crate::peer_manager) fn on_meta_data_seq_number(&mut self, seq: u64) -> u32 {
+        let is_stale = self
+            .meta_data
+            .as_ref()
+            .map_or(true, |meta_data| *meta_data.seq_number() < seq);
+        if is_stale {
+            self.stale_meta_data_pings = self.stale_meta_data_pings.saturating_add(1);
+        } else {
+            self.stale_meta_data_pings = 0;
+        }
+        self.stale_meta_data_pings
+    }
+
     /// Removes all subnets from the peer.
     pub(super) fn clear_subnets(&mut self) {
         self.subnets.clear()
@@ -457,6 +647,9 @@ impl<E: EthSpec> PeerInfo<E> {
                     multiaddr,
                 };
                 self.connection_direction = Some(ConnectionDirection::Incoming);
+                self.pending_ping = None;
+                self.missed_pings = 0;
+                self.stale_meta_data_pings = 0;
             }
         }
     }
@@ -478,6 +671,9 @@ impl<E: EthSpec> PeerInfo<E> {
                     multiaddr,
                 };
                 self.connection_direction = Some(ConnectionDirection::Outgoing);
+                self.pending_ping = None;
+                self.missed_pings = 0;
+                self.stale_meta_data_pings = 0;
             }
         }
     }
@@ -496,8 +692,32 @@ impl<E: EthSpec> PeerInfo<E> {
     }
 }
 
+/// Whether a peer has scheduled the same next fork as the local node, according to its ENR.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, AsRefStr, EnumIter)]
+#[serde(rename_all = "snake_case")]
+#[strum(serialize_all = "snake_case")]
+pub enum ForkReadiness {
+    /// The peer advertises the same next fork version and epoch.
+    Ready,
+    /// The peer advertises a different next fork, usually because it has not been upgraded.
+    NotReady,
+    /// The peer's ENR, and therefore its next fork, is not known.
+    Unknown,
+}
+
+/// The number of connected peers which are ready for the next fork scheduled by the local node.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct ForkReadinessSummary {
+    /// The local fork id, including the next scheduled fork.
+    pub enr_fork_id: EnrForkId,
+    pub connected_peers: usize,
+    pub ready: usize,
+    pub not_ready: usize,
+    pub unknown: usize,
+}
+
 /// Connection Direction of connection.
-#[derive(Debug, Clone, Serialize, AsRefStr)]
+#[derive(Debug, Clone, Serialize, Deserialize, AsRefStr)]
 #[strum(serialize_all = "snake_case")]
 pub enum ConnectionDirection {
     /// The connection was established by a peer dialing us.
@@ -599,3 +819,48 @@ impl Serialize for PeerConnectionStatus {
         }
     }
 }
+
+/// Deserialization of the http representation, which approximates the times since which the peer
+/// has been in its state from the `last_seen` seconds.
+impl<'de> Deserialize<'de> for PeerConnectionStatus {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        #[derive(Deserialize)]
+        struct ConnectionStatus {
+            multiaddr: Option<Multiaddr>,
+            status: String,
+            connections_in: u8,
+            connections_out: u8,
+            last_seen: u64,
+        }
+
+        let status = ConnectionStatus::deserialize(deserializer)?;
+        let since = Instant::now()
+            .checked_sub(Duration::from_secs(status.last_seen))
+            .unwrap_or_else(Instant::now);
+        match status.status.as_str() {
+            "connected" => Ok(Connected {
+                multiaddr: status
+                    .multiaddr
+                    .ok_or_else(|| de::Error::missing_field("multiaddr"))?,
+                n_in: status.connections_in,
+                n_out: status.connections_out,
+            }),
+            "disconnecting" => Ok(Disconnecting { to_ban: false }),
+            "disconnected" => Ok(Disconnected { since }),
+            "banned" => Ok(Banned { since }),
+            "dialing" => Ok(Dialing { since }),
+            "unknown" => Ok(Unknown),
+            other => Err(de::Error::unknown_variant(
+                other,
+                &[
+                    "connected",
+                    "disconnecting",
+                    "disconnected",
+                    "banned",
+                    "dialing",
+                    "unknown",
+                ],
+            )),
+        }
+    }
+}
@@ -909,6 +909,9 @@ fn handle_rpc_response<E: EthSpec>(
 }
 
 /// Takes the context bytes and a fork_context and returns the corresponding fork_name.
+///
+/// Returns `RPCError::UnknownForkDigest` if the context bytes do not correspond to any fork in
+/// the `fork_context`, so that fork mismatches can be distinguished from malformed responses.
 fn context_bytes_to_fork_name(
     context_bytes: [u8; CONTEXT_BYTES_LEN],
     fork_context: Arc<ForkContext>,
@@ -916,16 +919,7 @@ fn context_bytes_to_fork_name(
     fork_context
         .from_context_bytes(context_bytes)
         .cloned()
-        .ok_or_else(|| {
-            let encoded = hex::encode(context_bytes);
-            RPCError::ErrorResponse(
-                RpcErrorResponse::InvalidRequest,
-                format!(
-                    "Context bytes {} do not correspond to a valid fork",
-                    encoded
-                ),
-            )
-        })
+        .ok_or(RPCError::UnknownForkDigest(context_bytes))
 }
 #[cfg(test)]
 mod tests {
@@ -933,6 +927,7 @@ mod tests {
     use super::*;
     use crate::rpc::protocol::*;
     use crate::types::{EnrAttestationBitfield, EnrSyncCommitteeBitfield};
+    use std::time::Duration;
     use types::{
         blob_sidecar::BlobIdentifier, BeaconBlock, BeaconBlockAltair, BeaconBlockBase,
         BeaconBlockBellatrix, DataColumnIdentifier, EmptyBlock, Epoch, FixedBytesExtended,
@@ -1683,7 +1678,7 @@ mod tests {
                 &chain_spec,
             )
             .unwrap_err(),
-            RPCError::ErrorResponse(RpcErrorResponse::InvalidRequest, _),
+            RPCError::UnknownForkDigest(_),
         ));
 
         let mut encoded_bytes = encode_response(
@@ -1706,7 +1701,7 @@ mod tests {
                 &chain_spec,
             )
             .unwrap_err(),
-            RPCError::ErrorResponse(RpcErrorResponse::InvalidRequest, _),
+            RPCError::UnknownForkDigest(_),
         ));
 
         // Trying to decode a base block with altair context bytes should give ssz decoding error
@@ -1804,7 +1799,7 @@ mod tests {
                 &chain_spec,
             )
             .unwrap_err(),
-            RPCError::ErrorResponse(RpcErrorResponse::InvalidRequest, _),
+            RPCError::UnknownForkDigest([42, 42, 42, 42]),
         ));
 
         // Sending bytes less than context bytes length should wait for more bytes by returning `Ok(None)`
@@ -2159,4 +2154,43 @@ mod tests {
             RPCError::InvalidData(_)
         ));
     }
+
+    #[test]
+    fn test_encode_then_decode_retry_after() {
+        let chain_spec = Spec::default_spec();
+        let protocol = SupportedProtocol::BlocksByRangeV2;
+        let fork_context = Arc::new(fork_context(ForkName::Base));
+        let max_packet_size = max_rpc_size(&fork_context, chain_spec.max_chunk_size as usize);
+        let decode = |message: RpcResponse<Spec>| {
+            let mut buf = encode_response(protocol, message, ForkName::Base, &chain_spec).unwrap();
+            let mut codec = SSZSnappyOutboundCodec::<Spec>::new(
+                ProtocolId::new(protocol, Encoding::SSZSnappy),
+                max_packet_size,
+                fork_context.clone(),
+            );
+            match codec.decode(&mut buf).unwrap().unwrap() {
+                RpcResponse::Error(code, message) => (code, message.as_retry_after()),
+                other => panic!("expected an error response, got {other}"),
+            }
+        };
+
+        assert_eq!(
+            decode(RpcResponse::Error(
+                RpcErrorResponse::RetryAfter,
+                ErrorType::retry_after(Duration::from_millis(1500)),
+            )),
+            (
+                RpcErrorResponse::RetryAfter,
+                Some(Duration::from_millis(1500))
+            )
+        );
+        // A rate limited response's message is not read as a retry-after hint.
+        assert_eq!(
+            decode(RpcResponse::Error(
+                RpcErrorResponse::RateLimited,
+                "Wait 1.5s".into(),
+            )),
+            (RpcErrorResponse::RateLimited, None)
+        );
+    }
 }
@@ -58,6 +58,48 @@ impl FromStr for ProtocolQuota {
     }
 }
 
+/// How the rate limiters treat trusted peers, such as paired nodes run by the same operator.
+#[derive(Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Debug, Default)]
+pub enum TrustedPeerRateLimit {
+    /// Trusted peers are subject to the same quotas as any other peer.
+    #[default]
+    Default,
+    /// Trusted peers may use this many times the tokens of every quota.
+    Multiplied(u64),
+    /// Trusted peers are not rate limited.
+    Exempt,
+}
+
+impl Display for TrustedPeerRateLimit {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            TrustedPeerRateLimit::Default => write!(f, "1"),
+            TrustedPeerRateLimit::Multiplied(multiplier) => write!(f, "{}", multiplier),
+            TrustedPeerRateLimit::Exempt => write!(f, "exempt"),
+        }
+    }
+}
+
+/// Parse either `exempt` or a positive quota multiplier, where a multiplier of 1 gives trusted
+/// peers the default quotas.
+impl FromStr for TrustedPeerRateLimit {
+    type Err = &'static str;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if s == "exempt" {
+            return Ok(TrustedPeerRateLimit::Exempt);
+        }
+        match s
+            .parse::<u64>()
+            .map_err(|_| "Trusted peer rate limit should be \"exempt\" or a multiplier.")?
+        {
+            0 => Err("Trusted peer quota multiplier must be positive."),
+            1 => Ok(TrustedPeerRateLimit::Default),
+            multiplier => Ok(TrustedPeerRateLimit::Multiplied(multiplier)),
+        }
+    }
+}
+
 #[derive(Clone, Serialize, Deserialize, PartialEq, Eq, Debug, Default)]
 pub struct OutboundRateLimiterConfig(pub RateLimiterConfig);
 
@@ -97,6 +139,9 @@ pub struct RateLimiterConfig {
     pub(super) light_client_optimistic_update_quota: Quota,
     pub(super) light_client_finality_update_quota: Quota,
     pub(super) light_client_updates_by_range_quota: Quota,
+    /// How trusted peers are rate limited.
+    #[serde(default)]
+    pub(super) trusted_peer_limit: TrustedPeerRateLimit,
 }
 
 impl RateLimiterConfig {
@@ -122,6 +167,48 @@ impl RateLimiterConfig {
     pub const DEFAULT_LIGHT_CLIENT_OPTIMISTIC_UPDATE_QUOTA: Quota = Quota::one_every(10);
     pub const DEFAULT_LIGHT_CLIENT_FINALITY_UPDATE_QUOTA: Quota = Quota::one_every(10);
     pub const DEFAULT_LIGHT_CLIENT_UPDATES_BY_RANGE_QUOTA: Quota = Quota::one_every(10);
+
+    /// Return a configuration allowing `multiplier` times the tokens of every quota over the same
+    /// time window.
+    pub(super) fn multiplied(&self, multiplier: u64) -> Self {
+        let multiply = |quota: &Quota| Quota {
+            replenish_all_every: quota.replenish_all_every,
+            max_tokens: quota.max_tokens.saturating_mul(multiplier),
+        };
+        RateLimiterConfig {
+            ping_quota: multiply(&self.ping_quota),
+            meta_data_quota: multiply(&self.meta_data_quota),
+            status_quota: multiply(&self.status_quota),
+            goodbye_quota: multiply(&self.goodbye_quota),
+            blocks_by_range_quota: multiply(&self.blocks_by_range_quota),
+            blocks_by_root_quota: multiply(&self.blocks_by_root_quota),
+            blobs_by_range_quota: multiply(&self.blobs_by_range_quota),
+            blobs_by_root_quota: multiply(&self.blobs_by_root_quota),
+            data_columns_by_root_quota: multiply(&self.data_columns_by_root_quota),
+            data_columns_by_range_quota: multiply(&self.data_columns_by_range_quota),
+            light_client_bootstrap_quota: multiply(&self.light_client_bootstrap_quota),
+            light_client_optimistic_update_quota: multiply(
+                &self.light_client_optimistic_update_quota,
+            ),
+            light_client_finality_update_quota: multiply(&self.light_client_finality_update_quota),
+            light_client_updates_by_range_quota: multiply(
+                &self.light_client_updates_by_range_quota,
+            ),
+            trusted_peer_limit: TrustedPeerRateLimit::Default,
+        }
+    }
+
+    /// Return this configuration with trusted peers rate limited according to `limit`.
+    pub fn with_trusted_peer_limit(self, limit: TrustedPeerRateLimit) -> Self {
+        RateLimiterConfig {
+            trusted_peer_limit: limit,
+            ..self
+        }
+    }
+
+    pub fn trusted_peer_limit(&self) -> TrustedPeerRateLimit {
+        self.trusted_peer_limit
+    }
 }
 
 impl Default for RateLimiterConfig {
@@ -142,6 +229,7 @@ impl Default for RateLimiterConfig {
                 Self::DEFAULT_LIGHT_CLIENT_OPTIMISTIC_UPDATE_QUOTA,
             light_client_finality_update_quota: Self::DEFAULT_LIGHT_CLIENT_FINALITY_UPDATE_QUOTA,
             light_client_updates_by_range_quota: Self::DEFAULT_LIGHT_CLIENT_UPDATES_BY_RANGE_QUOTA,
+            trusted_peer_limit: TrustedPeerRateLimit::Default,
         }
     }
 }
@@ -175,6 +263,10 @@ impl Debug for RateLimiterConfig {
                 "data_columns_by_root",
                 fmt_q!(&self.data_columns_by_root_quota),
             )
+            .field(
+                "trusted_peers",
+                &format_args!("{}", self.trusted_peer_limit),
+            )
             .finish()
     }
 }
@@ -261,6 +353,7 @@ impl FromStr for RateLimiterConfig {
                 .unwrap_or(Self::DEFAULT_LIGHT_CLIENT_FINALITY_UPDATE_QUOTA),
             light_client_updates_by_range_quota: light_client_updates_by_range_quota
                 .unwrap_or(Self::DEFAULT_LIGHT_CLIENT_UPDATES_BY_RANGE_QUOTA),
+            trusted_peer_limit: TrustedPeerRateLimit::Default,
         })
     }
 }
@@ -280,4 +373,16 @@ mod tests {
         };
         assert_eq!(quota.to_string().parse(), Ok(quota))
     }
+
+    #[test]
+    fn test_trusted_peer_rate_limit() {
+        assert_eq!("exempt".parse(), Ok(TrustedPeerRateLimit::Exempt));
+        assert_eq!("1".parse(), Ok(TrustedPeerRateLimit::Default));
+        assert_eq!("4".parse(), Ok(TrustedPeerRateLimit::Multiplied(4)));
+        assert!("0".parse::<TrustedPeerRateLimit>().is_err());
+        assert!("unlimited".parse::<TrustedPeerRateLimit>().is_err());
+
+        let limit = TrustedPeerRateLimit::Multiplied(4);
+        assert_eq!(limit.to_string().parse(), Ok(limit));
+    }
 }
@@ -3,7 +3,9 @@
 
 use super::methods::{GoodbyeReason, RpcErrorResponse, RpcResponse};
 use super::outbound::OutboundRequestContainer;
-use super::protocol::{InboundOutput, Protocol, RPCError, RPCProtocol, RequestType};
+use super::protocol::{
+    InboundOutput, Protocol, RPCError, RPCProtocol, RequestType, MAX_RETRY_AFTER,
+};
 use super::RequestId;
 use super::{RPCReceived, RPCSend, ReqId, Request};
 use crate::rpc::outbound::OutboundFramed;
@@ -680,11 +682,17 @@ where
                                 HandlerEvent::Ok(RPCReceived::Response(id, resp))
                             }
                             RpcResponse::Error(ref code, ref r) => {
-                                HandlerEvent::Err(HandlerErr::Outbound {
-                                    id,
-                                    proto,
-                                    error: RPCError::ErrorResponse(*code, r.to_string()),
-                                })
+                                let retry_after = match code {
+                                    RpcErrorResponse::RetryAfter => r.as_retry_after(),
+                                    _ => None,
+                                };
+                                let error = match retry_after {
+                                    Some(retry_after) => {
+                                        RPCError::RetryAfter(retry_after.min(MAX_RETRY_AFTER))
+                                    }
+                                    None => RPCError::ErrorResponse(*code, r.to_string()),
+                                };
+                                HandlerEvent::Err(HandlerErr::Outbound { id, proto, error })
                             }
                         };
 
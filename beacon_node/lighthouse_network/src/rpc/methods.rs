@@ -2,8 +2,8 @@
 
 use crate::types::{EnrAttestationBitfield, EnrSyncCommitteeBitfield};
 use regex::bytes::Regex;
-use serde::Serialize;
-use ssz::Encode;
+use serde::{Deserialize, Serialize};
+use ssz::{Decode, Encode};
 use ssz_derive::{Decode, Encode};
 use ssz_types::{typenum::U256, VariableList};
 use std::collections::BTreeMap;
@@ -11,6 +11,7 @@ use std::fmt::Display;
 use std::marker::PhantomData;
 use std::ops::Deref;
 use std::sync::Arc;
+use std::time::Duration;
 use strum::IntoStaticStr;
 use superstruct::superstruct;
 use types::blob_sidecar::BlobIdentifier;
@@ -29,6 +30,20 @@ pub const MAX_ERROR_LEN: u64 = 256;
 #[derive(Debug, Clone)]
 pub struct ErrorType(pub VariableList<u8, MaxErrorLen>);
 
+impl ErrorType {
+    /// The message of a `RpcErrorResponse::RetryAfter` response: the SSZ encoded number of
+    /// milliseconds the requester should wait before retrying.
+    pub fn retry_after(retry_after: Duration) -> Self {
+        let millis = u64::try_from(retry_after.as_millis()).unwrap_or(u64::MAX);
+        Self(VariableList::from(millis.as_ssz_bytes()))
+    }
+
+    /// Decodes the message of a `RpcErrorResponse::RetryAfter` response.
+    pub fn as_retry_after(&self) -> Option<Duration> {
+        u64::from_ssz_bytes(&self.0).ok().map(Duration::from_millis)
+    }
+}
+
 impl From<String> for ErrorType {
     fn from(s: String) -> Self {
         Self(VariableList::from(s.as_bytes().to_vec()))
@@ -122,11 +137,11 @@ impl<E: EthSpec> MetadataRequest<E> {
 #[superstruct(
     variants(V1, V2, V3),
     variant_attributes(
-        derive(Encode, Decode, Clone, Debug, PartialEq, Serialize),
+        derive(Encode, Decode, Clone, Debug, PartialEq, Serialize, Deserialize),
         serde(bound = "E: EthSpec", deny_unknown_fields),
     )
 )]
-#[derive(Clone, Debug, PartialEq, Serialize)]
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
 #[serde(bound = "E: EthSpec")]
 pub struct MetaData<E: EthSpec> {
     /// A sequential counter indicating when data gets modified.
@@ -601,6 +616,9 @@ pub struct LightClientBootstrapRequest {
 #[strum(serialize_all = "snake_case")]
 pub enum RpcErrorResponse {
     RateLimited,
+    /// The request was rate limited, possibly after part of it was served. The error message
+    /// holds how long to wait before retrying, see `ErrorType::retry_after`.
+    RetryAfter,
     BlobsNotFoundForBlock,
     InvalidRequest,
     ServerError,
@@ -632,6 +650,7 @@ impl<E: EthSpec> RpcResponse<E> {
             3 => RpcErrorResponse::ResourceUnavailable,
             139 => RpcErrorResponse::RateLimited,
             140 => RpcErrorResponse::BlobsNotFoundForBlock,
+            141 => RpcErrorResponse::RetryAfter,
             _ => RpcErrorResponse::Unknown,
         };
         RpcResponse::Error(code, err)
@@ -652,6 +671,7 @@ impl RpcErrorResponse {
             RpcErrorResponse::Unknown => 255,
             RpcErrorResponse::RateLimited => 139,
             RpcErrorResponse::BlobsNotFoundForBlock => 140,
+            RpcErrorResponse::RetryAfter => 141,
         }
     }
 }
@@ -688,6 +708,7 @@ impl std::fmt::Display for RpcErrorResponse {
             RpcErrorResponse::Unknown => "Unknown error occurred",
             RpcErrorResponse::RateLimited => "Rate limited",
             RpcErrorResponse::BlobsNotFoundForBlock => "No blobs for the given root",
+            RpcErrorResponse::RetryAfter => "Rate limited, retry later",
         };
         f.write_str(repr)
     }
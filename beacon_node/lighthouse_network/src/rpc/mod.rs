@@ -15,6 +15,7 @@ use libp2p::swarm::{ConnectionClosed, FromSwarm, SubstreamProtocol, THandlerInEv
 use libp2p::PeerId;
 use rate_limiter::{RPCRateLimiter as RateLimiter, RateLimitedErr};
 use slog::{crit, debug, o, trace};
+use std::collections::HashMap;
 use std::marker::PhantomData;
 use std::sync::atomic::{AtomicUsize, Ordering};
 use std::sync::Arc;
@@ -33,9 +34,10 @@ pub use methods::{
     BlocksByRangeRequest, BlocksByRootRequest, GoodbyeReason, LightClientBootstrapRequest,
     ResponseTermination, RpcErrorResponse, StatusMessage,
 };
-pub use protocol::{max_rpc_size, Protocol, RPCError};
+pub use protocol::{max_rpc_size, Protocol, RPCError, RpcErrorCause};
 
 use self::config::{InboundRateLimiterConfig, OutboundRateLimiterConfig};
+use self::methods::ErrorType;
 use self::protocol::RPCProtocol;
 use self::self_limiter::SelfRateLimiter;
 
@@ -45,7 +47,7 @@ mod handler;
 pub mod methods;
 mod outbound;
 mod protocol;
-mod rate_limiter;
+pub(crate) mod rate_limiter;
 mod self_limiter;
 
 static NEXT_REQUEST_ID: AtomicUsize = AtomicUsize::new(1);
@@ -155,6 +157,9 @@ pub struct RPC<Id: ReqId, E: EthSpec> {
     limiter: Option<RateLimiter>,
     /// Rate limiter for our own requests.
     self_limiter: Option<SelfRateLimiter<Id, E>>,
+    /// Inbound requests that were truncated to fit the rate limit, with how long the peer should
+    /// wait before requesting the remainder. Their stream is terminated with a rate limited error.
+    truncated_responses: HashMap<(PeerId, ConnectionId, SubstreamId), Duration>,
     /// Queue of events to be processed.
     events: Vec<BehaviourAction<Id, E>>,
     fork_context: Arc<ForkContext>,
@@ -192,6 +197,7 @@ impl<Id: ReqId, E: EthSpec> RPC<Id, E> {
         RPC {
             limiter: inbound_limiter,
             self_limiter,
+            truncated_responses: HashMap::new(),
             events: Vec::new(),
             fork_context,
             enable_light_client_server,
@@ -201,6 +207,16 @@ impl<Id: ReqId, E: EthSpec> RPC<Id, E> {
         }
     }
 
+    /// Applies the `TrustedPeerRateLimit` to requests to and from `peer_id`.
+    pub fn add_trusted_peer(&mut self, peer_id: PeerId) {
+        if let Some(limiter) = self.limiter.as_mut() {
+            limiter.add_trusted_peer(peer_id);
+        }
+        if let Some(self_limiter) = self.self_limiter.as_mut() {
+            self_limiter.add_trusted_peer(peer_id);
+        }
+    }
+
     /// Sends an RPC response.
     ///
     /// The peer must be connected for this to succeed.
@@ -209,8 +225,20 @@ impl<Id: ReqId, E: EthSpec> RPC<Id, E> {
         peer_id: PeerId,
         id: (ConnectionId, SubstreamId),
         _request_id: RequestId,
-        event: RpcResponse<E>,
+        mut event: RpcResponse<E>,
     ) {
+        if event.close_after() {
+            if let Some(retry_after) = self.truncated_responses.remove(&(peer_id, id.0, id.1)) {
+                // Let the peer know that the response was truncated by the rate limiter, and
+                // when to request the remainder.
+                if let RpcResponse::StreamTermination(_) = event {
+                    event = RpcResponse::Error(
+                        RpcErrorResponse::RetryAfter,
+                        ErrorType::retry_after(retry_after),
+                    );
+                }
+            }
+        }
         self.events.push(ToSwarm::NotifyHandler {
             peer_id,
             handler: NotifyHandler::One(id.0),
@@ -350,6 +378,9 @@ where
             ..
         }) = event
         {
+            self.truncated_responses
+                .retain(|(peer, conn_id, _), _| *peer != peer_id || *conn_id != connection_id);
+
             // If there are still connections remaining, do nothing.
             if remaining_established > 0 {
                 return;
@@ -403,11 +434,16 @@ where
             HandlerEvent::Ok(RPCReceived::Request(Request {
                 id,
                 substream_id,
-                r#type,
+                mut r#type,
             })) => {
                 if let Some(limiter) = self.limiter.as_mut() {
-                    // check if the request is conformant to the quota
-                    match limiter.allows(&peer_id, &r#type) {
+                    // check if the request is conformant to the quota. Requests that can be
+                    // truncated are served partially if the quota allows part of the request.
+                    let allowance = match r#type.truncation_unit() {
+                        Some(unit) => limiter.allows_partial(&peer_id, &r#type, unit).map(Some),
+                        None => limiter.allows(&peer_id, &r#type).map(|()| None),
+                    };
+                    match allowance {
                         Err(RateLimitedErr::TooLarge) => {
                             // we set the batch sizes, so this is a coding/config err for most protocols
                             let protocol = r#type.versioned_protocol().protocol();
@@ -454,8 +490,16 @@ where
                             );
                             return;
                         }
+                        Ok(Some((allowed, retry_after))) if allowed < r#type.max_responses() => {
+                            debug!(self.log, "Truncating request to the rate limit";
+                                "request" => %r#type, "peer_id" => %peer_id, "allowed" => allowed,
+                                "retry_after_ms" => retry_after.as_millis());
+                            r#type.truncate_responses(allowed);
+                            self.truncated_responses
+                                .insert((peer_id, conn_id, substream_id), retry_after);
+                        }
                         // No rate limiting, continue.
-                        Ok(()) => {}
+                        Ok(_) => {}
                     }
                 }
 
@@ -490,6 +534,10 @@ where
                 }));
             }
             HandlerEvent::Err(err) => {
+                if let HandlerErr::Inbound { id, .. } = &err {
+                    // The response stream has been closed
+                    self.truncated_responses.remove(&(peer_id, conn_id, *id));
+                }
                 self.events.push(ToSwarm::GenerateEvent(RPCMessage {
                     peer_id,
                     conn_id,
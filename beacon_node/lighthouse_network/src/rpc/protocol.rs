@@ -170,6 +170,9 @@ const PROTOCOL_PREFIX: &str = "/eth2/beacon_chain/req";
 /// The number of seconds to wait for the first bytes of a request once a protocol has been
 /// established before the stream is terminated.
 const REQUEST_TIMEOUT: u64 = 15;
+/// The longest a peer may ask us to wait before retrying a rate limited request. Longer hints are
+/// capped so that a peer cannot keep us from retrying a request indefinitely.
+pub const MAX_RETRY_AFTER: Duration = Duration::from_secs(60);
 
 /// Returns the maximum bytes that can be sent across the RPC.
 pub fn max_rpc_size(fork_context: &ForkContext, max_chunk_size: usize) -> usize {
@@ -810,6 +813,33 @@ impl<E: EthSpec> RequestType<E> {
         }
     }
 
+    /// The number of responses that a partial response to this request must be a multiple of, if
+    /// this request may be answered with a truncated response when rate limited.
+    pub fn truncation_unit(&self) -> Option<u64> {
+        match self {
+            RequestType::BlocksByRange(_) => Some(1),
+            RequestType::BlobsByRange(_) => Some(E::max_blobs_per_block() as u64),
+            _ => None,
+        }
+    }
+
+    /// Reduces the number of requested items so that at most `max_responses` are served. Has no
+    /// effect on requests that cannot be truncated.
+    pub fn truncate_responses(&mut self, max_responses: u64) {
+        let Some(unit) = self.truncation_unit() else {
+            return;
+        };
+        let count = max_responses / unit.max(1);
+        match self {
+            RequestType::BlocksByRange(req) => {
+                let req_count = req.count_mut();
+                *req_count = (*req_count).min(count);
+            }
+            RequestType::BlobsByRange(req) => req.count = req.count.min(count),
+            _ => {}
+        }
+    }
+
     /// Gives the corresponding `SupportedProtocol` to this request.
     pub fn versioned_protocol(&self) -> SupportedProtocol {
         match self {
@@ -964,6 +994,9 @@ pub enum RPCError {
     IoError(String),
     /// The peer returned a valid response but the response indicated an error.
     ErrorResponse(RpcErrorResponse, String),
+    /// The peer rate limited the request, possibly after serving part of it, and asked for it to
+    /// be retried after the given duration.
+    RetryAfter(Duration),
     /// Timed out waiting for a response.
     StreamTimeout,
     /// Peer does not support the protocol.
@@ -972,6 +1005,9 @@ pub enum RPCError {
     IncompleteStream,
     /// Peer sent invalid data.
     InvalidData(String),
+    /// The context bytes of a response do not correspond to any fork we know of, e.g. because the
+    /// peer has transitioned to a fork which we do not support.
+    UnknownForkDigest([u8; 4]),
     /// An error occurred due to internal reasons. Ex: timer failure.
     InternalError(&'static str),
     /// Negotiation with this peer timed out.
@@ -1006,12 +1042,20 @@ impl std::fmt::Display for RPCError {
         match *self {
             RPCError::SSZDecodeError(ref err) => write!(f, "Error while decoding ssz: {:?}", err),
             RPCError::InvalidData(ref err) => write!(f, "Peer sent unexpected data: {}", err),
+            RPCError::UnknownForkDigest(ref digest) => write!(
+                f,
+                "Context bytes 0x{} do not correspond to a known fork",
+                hex::encode(digest)
+            ),
             RPCError::IoError(ref err) => write!(f, "IO Error: {}", err),
             RPCError::ErrorResponse(ref code, ref reason) => write!(
                 f,
                 "RPC response was an error: {} with reason: {}",
                 code, reason
             ),
+            RPCError::RetryAfter(ref retry_after) => {
+                write!(f, "Rate limited, retry after {:?}", retry_after)
+            }
             RPCError::StreamTimeout => write!(f, "Stream Timeout"),
             RPCError::UnsupportedProtocol => write!(f, "Peer does not support the protocol"),
             RPCError::IncompleteStream => write!(f, "Stream ended unexpectedly"),
@@ -1033,8 +1077,10 @@ impl std::error::Error for RPCError {
             RPCError::UnsupportedProtocol => None,
             RPCError::IncompleteStream => None,
             RPCError::InvalidData(_) => None,
+            RPCError::UnknownForkDigest(_) => None,
             RPCError::InternalError(_) => None,
             RPCError::ErrorResponse(_, _) => None,
+            RPCError::RetryAfter(_) => None,
             RPCError::NegotiationTimeout => None,
             RPCError::HandlerRejected => None,
             RPCError::Disconnected => None,
@@ -1082,4 +1128,62 @@ impl RPCError {
             e => e.into(),
         }
     }
+
+    /// How long the peer asked us to wait before retrying the request, if it rate limited us.
+    pub fn retry_after(&self) -> Option<Duration> {
+        match self {
+            RPCError::RetryAfter(retry_after) => Some(*retry_after),
+            _ => None,
+        }
+    }
+
+    /// Classifies the error by its cause, so that the requester can decide whether and where to
+    /// retry the request.
+    pub fn cause(&self) -> RpcErrorCause {
+        match self {
+            RPCError::ErrorResponse(code, _) => match code {
+                RpcErrorResponse::ResourceUnavailable | RpcErrorResponse::BlobsNotFoundForBlock => {
+                    RpcErrorCause::ResourceUnavailable
+                }
+                RpcErrorResponse::RateLimited | RpcErrorResponse::RetryAfter => {
+                    RpcErrorCause::RateLimited
+                }
+                RpcErrorResponse::ServerError | RpcErrorResponse::Unknown => {
+                    RpcErrorCause::ServerError
+                }
+                RpcErrorResponse::InvalidRequest => RpcErrorCause::PeerFault,
+            },
+            RPCError::RetryAfter(_) => RpcErrorCause::RateLimited,
+            RPCError::SSZDecodeError(_)
+            | RPCError::InvalidData(_)
+            | RPCError::UnknownForkDigest(_)
+            | RPCError::UnsupportedProtocol
+            | RPCError::HandlerRejected => RpcErrorCause::PeerFault,
+            RPCError::IoError(_)
+            | RPCError::StreamTimeout
+            | RPCError::IncompleteStream
+            | RPCError::NegotiationTimeout
+            | RPCError::Disconnected => RpcErrorCause::Network,
+            RPCError::InternalError(_) => RpcErrorCause::Internal,
+        }
+    }
+}
+
+/// The cause of an `RPCError`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, IntoStaticStr)]
+#[strum(serialize_all = "snake_case")]
+pub enum RpcErrorCause {
+    /// The peer does not have the requested resource, e.g. because it has been pruned or the peer
+    /// is still backfilling.
+    ResourceUnavailable,
+    /// The peer is rate limiting our requests.
+    RateLimited,
+    /// The peer failed to serve the request for internal reasons.
+    ServerError,
+    /// The peer violated the protocol or rejected the request.
+    PeerFault,
+    /// The request failed due to the connection to the peer, e.g. a timeout or disconnection.
+    Network,
+    /// The request failed due to an internal error on our side.
+    Internal,
 }
@@ -1,8 +1,9 @@
-use super::config::RateLimiterConfig;
+use super::config::{RateLimiterConfig, TrustedPeerRateLimit};
 use crate::rpc::Protocol;
 use fnv::FnvHashMap;
 use libp2p::PeerId;
 use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
 use std::future::Future;
 use std::hash::Hash;
 use std::pin::Pin;
@@ -109,6 +110,13 @@ pub struct RPCRateLimiter {
     lc_finality_update_rl: Limiter<PeerId>,
     /// LightClientUpdatesByRange rate limiter.
     lc_updates_by_range_rl: Limiter<PeerId>,
+    /// Peers which are rate limited according to the `TrustedPeerRateLimit` instead of the
+    /// quotas above.
+    trusted_peers: HashSet<PeerId>,
+    /// Whether trusted peers are exempt from rate limiting.
+    exempt_trusted_peers: bool,
+    /// Rate limiter for trusted peers, when their quotas are multiplied.
+    trusted_rl: Option<Box<RPCRateLimiter>>,
 }
 
 /// Error type for non conformant requests
@@ -252,6 +260,9 @@ impl RPCRateLimiterBuilder {
             lc_optimistic_update_rl,
             lc_finality_update_rl,
             lc_updates_by_range_rl,
+            trusted_peers: HashSet::new(),
+            exempt_trusted_peers: false,
+            trusted_rl: None,
             init_time: Instant::now(),
         })
     }
@@ -274,6 +285,13 @@ impl<E: EthSpec> RateLimiterItem for super::RequestType<E> {
 
 impl RPCRateLimiter {
     pub fn new_with_config(config: RateLimiterConfig) -> Result<Self, &'static str> {
+        let trusted_rl = match config.trusted_peer_limit {
+            TrustedPeerRateLimit::Multiplied(multiplier) => Some(Box::new(Self::new_with_config(
+                config.multiplied(multiplier),
+            )?)),
+            TrustedPeerRateLimit::Default | TrustedPeerRateLimit::Exempt => None,
+        };
+
         // Destructure to make sure every configuration value is used.
         let RateLimiterConfig {
             ping_quota,
@@ -290,6 +308,7 @@ impl RPCRateLimiter {
             light_client_optimistic_update_quota,
             light_client_finality_update_quota,
             light_client_updates_by_range_quota,
+            trusted_peer_limit,
         } = config;
 
         Self::builder()
@@ -317,6 +336,11 @@ impl RPCRateLimiter {
                 light_client_updates_by_range_quota,
             )
             .build()
+            .map(|limiter| RPCRateLimiter {
+                exempt_trusted_peers: trusted_peer_limit == TrustedPeerRateLimit::Exempt,
+                trusted_rl,
+                ..limiter
+            })
     }
 
     /// Get a builder instance.
@@ -324,17 +348,65 @@ impl RPCRateLimiter {
         RPCRateLimiterBuilder::default()
     }
 
+    /// Rate limit `peer_id` according to the `TrustedPeerRateLimit` from now on.
+    pub fn add_trusted_peer(&mut self, peer_id: PeerId) {
+        self.trusted_peers.insert(peer_id);
+    }
+
+    /// Returns the rate limiter which applies to `peer_id`, or `None` if the peer is exempt from
+    /// rate limiting.
+    fn peer_limiter(&mut self, peer_id: &PeerId) -> Option<&mut Self> {
+        if self.trusted_peers.contains(peer_id) {
+            if self.exempt_trusted_peers {
+                return None;
+            }
+            if self.trusted_rl.is_some() {
+                return self.trusted_rl.as_deref_mut();
+            }
+        }
+        Some(self)
+    }
+
     pub fn allows<Item: RateLimiterItem>(
         &mut self,
         peer_id: &PeerId,
         request: &Item,
     ) -> Result<(), RateLimitedErr> {
-        let time_since_start = self.init_time.elapsed();
+        let Some(limiter) = self.peer_limiter(peer_id) else {
+            return Ok(());
+        };
+        let time_since_start = limiter.init_time.elapsed();
+        let tokens = request.max_responses().max(1);
+
+        limiter
+            .limiter_mut(request.protocol())
+            .allows(time_since_start, peer_id, tokens)
+    }
+
+    /// Checks a request that can be partially served. Returns the number of responses that may be
+    /// sent, a multiple of `unit`, and how long until the remaining responses could be requested.
+    pub fn allows_partial<Item: RateLimiterItem>(
+        &mut self,
+        peer_id: &PeerId,
+        request: &Item,
+        unit: u64,
+    ) -> Result<(u64, Duration), RateLimitedErr> {
         let tokens = request.max_responses().max(1);
+        let Some(limiter) = self.peer_limiter(peer_id) else {
+            return Ok((tokens, Duration::ZERO));
+        };
+        let time_since_start = limiter.init_time.elapsed();
+
+        limiter.limiter_mut(request.protocol()).allows_partial(
+            time_since_start,
+            peer_id,
+            tokens,
+            unit,
+        )
+    }
 
-        let check =
-            |limiter: &mut Limiter<PeerId>| limiter.allows(time_since_start, peer_id, tokens);
-        let limiter = match request.protocol() {
+    fn limiter_mut(&mut self, protocol: Protocol) -> &mut Limiter<PeerId> {
+        match protocol {
             Protocol::Ping => &mut self.ping_rl,
             Protocol::Status => &mut self.status_rl,
             Protocol::MetaData => &mut self.metadata_rl,
@@ -349,8 +421,7 @@ impl RPCRateLimiter {
             Protocol::LightClientOptimisticUpdate => &mut self.lc_optimistic_update_rl,
             Protocol::LightClientFinalityUpdate => &mut self.lc_finality_update_rl,
             Protocol::LightClientUpdatesByRange => &mut self.lc_updates_by_range_rl,
-        };
-        check(limiter)
+        }
     }
 
     pub fn prune(&mut self) {
@@ -365,6 +436,9 @@ impl RPCRateLimiter {
         self.blbroot_rl.prune(time_since_start);
         self.dcbrange_rl.prune(time_since_start);
         self.dcbroot_rl.prune(time_since_start);
+        if let Some(trusted_rl) = self.trusted_rl.as_mut() {
+            trusted_rl.prune();
+        }
     }
 }
 
@@ -450,6 +524,48 @@ impl<Key: Hash + Eq + Clone> Limiter<Key> {
         }
     }
 
+    /// Like `allows`, but if not all `tokens` are available, consumes as many as are available in
+    /// multiples of `unit`. Returns the number of tokens consumed and the time until the remaining
+    /// tokens would be available.
+    pub fn allows_partial(
+        &mut self,
+        time_since_start: Duration,
+        key: &Key,
+        tokens: u64,
+        unit: u64,
+    ) -> Result<(u64, Duration), RateLimitedErr> {
+        let time_since_start = time_since_start.as_nanos() as u64;
+        let tau = self.tau;
+        let t = self.t;
+        let unit = unit.max(1);
+        if t.saturating_mul(unit) > tau {
+            // not even a single unit can ever be processed
+            return Err(RateLimitedErr::TooLarge);
+        }
+        let tat = self
+            .tat_per_key
+            .entry(key.clone())
+            .or_insert(time_since_start);
+        // a bucket that was full before now is only full as of now
+        let base = time_since_start.max(*tat);
+        let available = (time_since_start + tau).saturating_sub(base) / t;
+        let allowed = available.min(tokens) / unit * unit;
+        if allowed == 0 {
+            return Err(RateLimitedErr::TooSoon(Duration::from_nanos(
+                (base + t * unit)
+                    .saturating_sub(tau)
+                    .saturating_sub(time_since_start),
+            )));
+        }
+        *tat = base + t * allowed;
+        // the remaining tokens can be requested once they fit in the bucket
+        let remaining = (tokens - allowed).min(tau / t);
+        let retry_after = (*tat + t * remaining)
+            .saturating_sub(tau)
+            .saturating_sub(time_since_start);
+        Ok((allowed, Duration::from_nanos(retry_after)))
+    }
+
     /// Removes keys for which their bucket is full by `time_limit`
     pub fn prune(&mut self, time_limit: Duration) {
         let lim = &mut (time_limit.as_nanos() as u64);
@@ -460,7 +576,7 @@ impl<Key: Hash + Eq + Clone> Limiter<Key> {
 
 #[cfg(test)]
 mod tests {
-    use crate::rpc::rate_limiter::{Limiter, Quota};
+    use crate::rpc::rate_limiter::{Limiter, Quota, RateLimitedErr};
     use std::time::Duration;
 
     #[test]
@@ -528,4 +644,42 @@ mod tests {
             .allows(Duration::from_secs_f32(0.4), &key, 1)
             .is_err());
     }
+
+    #[test]
+    fn allows_partial() {
+        let mut limiter = Limiter::from_quota(Quota {
+            replenish_all_every: Duration::from_secs(2),
+            max_tokens: 4,
+        })
+        .unwrap();
+        let key = 10;
+
+        // A full bucket serves part of a request larger than the bucket.
+        assert_eq!(
+            limiter
+                .allows_partial(Duration::from_secs_f32(0.0), &key, 6, 1)
+                .unwrap(),
+            (4, Duration::from_secs(1))
+        );
+        // The bucket is empty, the request must wait for a token.
+        assert!(limiter
+            .allows_partial(Duration::from_secs_f32(0.1), &key, 1, 1)
+            .is_err());
+        // One token was replenished, but two are needed for a unit.
+        assert!(limiter
+            .allows_partial(Duration::from_secs_f32(0.5), &key, 4, 2)
+            .is_err());
+        // Two tokens were replenished, serve a single unit of two.
+        assert_eq!(
+            limiter
+                .allows_partial(Duration::from_secs_f32(1.0), &key, 4, 2)
+                .unwrap(),
+            (2, Duration::from_secs(1))
+        );
+        // A unit larger than the bucket can never be served.
+        assert!(matches!(
+            limiter.allows_partial(Duration::from_secs_f32(3.0), &key, 10, 5),
+            Err(RateLimitedErr::TooLarge)
+        ));
+    }
 }
@@ -63,6 +63,11 @@ impl<Id: ReqId, E: EthSpec> SelfRateLimiter<Id, E> {
         })
     }
 
+    /// Limits requests to `peer_id` according to the `TrustedPeerRateLimit` from now on.
+    pub fn add_trusted_peer(&mut self, peer_id: PeerId) {
+        self.limiter.add_trusted_peer(peer_id);
+    }
+
     /// Checks if the rate limiter allows the request. If it's allowed, returns the
     /// [`ToSwarm`] that should be emitted. When not allowed, the request is delayed
     /// until it can be sent.
@@ -208,7 +213,7 @@ impl<Id: ReqId, E: EthSpec> SelfRateLimiter<Id, E> {
 
 #[cfg(test)]
 mod tests {
-    use crate::rpc::config::{OutboundRateLimiterConfig, RateLimiterConfig};
+    use crate::rpc::config::{OutboundRateLimiterConfig, RateLimiterConfig, TrustedPeerRateLimit};
     use crate::rpc::rate_limiter::Quota;
     use crate::rpc::self_limiter::SelfRateLimiter;
     use crate::rpc::{Ping, Protocol, RequestType};
@@ -285,4 +290,57 @@ mod tests {
             assert_eq!(limiter.ready_requests.len(), 1);
         }
     }
+
+    /// Test that requests to trusted peers use the trusted peer limit.
+    #[tokio::test]
+    async fn test_trusted_peer_limit() {
+        let config = OutboundRateLimiterConfig(RateLimiterConfig {
+            ping_quota: Quota::n_every(1, 2),
+            ..Default::default()
+        });
+        let send_pings = |limiter: &mut SelfRateLimiter<RequestId, MainnetEthSpec>,
+                          peer_id: PeerId| {
+            (1..=4u32)
+                .filter(|&i| {
+                    limiter
+                        .allows(
+                            peer_id,
+                            RequestId::Application(AppRequestId::Sync(
+                                SyncRequestId::RangeBlockAndBlobs { id: i },
+                            )),
+                            RequestType::Ping(Ping { data: i as u64 }),
+                        )
+                        .is_ok()
+                })
+                .count()
+        };
+
+        let mut limiter = SelfRateLimiter::new(
+            OutboundRateLimiterConfig(
+                config
+                    .0
+                    .clone()
+                    .with_trusted_peer_limit(TrustedPeerRateLimit::Exempt),
+            ),
+            logging::test_logger(),
+        )
+        .unwrap();
+        let trusted_peer = PeerId::random();
+        limiter.add_trusted_peer(trusted_peer);
+        assert_eq!(send_pings(&mut limiter, PeerId::random()), 1);
+        assert_eq!(send_pings(&mut limiter, trusted_peer), 4);
+
+        let mut limiter = SelfRateLimiter::new(
+            OutboundRateLimiterConfig(
+                config
+                    .0
+                    .with_trusted_peer_limit(TrustedPeerRateLimit::Multiplied(2)),
+            ),
+            logging::test_logger(),
+        )
+        .unwrap();
+        limiter.add_trusted_peer(trusted_peer);
+        assert_eq!(send_pings(&mut limiter, PeerId::random()), 1);
+        assert_eq!(send_pings(&mut limiter, trusted_peer), 2);
+    }
 }
@@ -1,4 +1,5 @@
 use self::gossip_cache::GossipCache;
+use self::publish_limiter::PublishLimiter;
 use crate::config::{gossipsub_config, GossipsubConfigParams, NetworkLoad};
 use crate::discovery::{
     subnet_predicate, DiscoveredPeers, Discovery, FIND_NODE_QUERY_CLOSEST_PEERS,
@@ -15,8 +16,9 @@ use crate::rpc::{
 };
 use crate::types::{
     attestation_sync_committee_topics, fork_core_topics, subnet_from_topic_hash, GossipEncoding,
-    GossipKind, GossipTopic, SnappyTransform, Subnet, SubnetDiscovery, ALTAIR_CORE_TOPICS,
-    BASE_CORE_TOPICS, CAPELLA_CORE_TOPICS, DENEB_CORE_TOPICS, LIGHT_CLIENT_GOSSIP_TOPICS,
+    GossipKind, GossipTopic, SnappyTransform, Subnet, SubnetDiscovery, TrafficClass,
+    ALTAIR_CORE_TOPICS, BASE_CORE_TOPICS, CAPELLA_CORE_TOPICS, DENEB_CORE_TOPICS,
+    LIGHT_CLIENT_GOSSIP_TOPICS,
 };
 use crate::EnrExt;
 use crate::Eth2Enr;
@@ -39,6 +41,7 @@ use std::path::PathBuf;
 use std::pin::Pin;
 use std::sync::Arc;
 use std::time::Duration;
+use tokio_util::time::DelayQueue;
 use types::{
     consts::altair::SYNC_COMMITTEE_SUBNET_COUNT, EnrForkId, EthSpec, ForkContext, Slot, SubnetId,
 };
@@ -48,12 +51,29 @@ use utils::{build_transport, strip_peer_id, Context as ServiceContext};
 pub mod api_types;
 mod gossip_cache;
 pub mod gossipsub_scoring_parameters;
+mod publish_limiter;
 pub mod utils;
+
+pub use publish_limiter::PublishRateLimiterConfig;
+
 /// The number of peers we target per subnet for discovery queries.
 pub const TARGET_SUBNET_PEERS: usize = 3;
 
 const MAX_IDENTIFY_ADDRESSES: usize = 10;
 
+/// The maximum number of times a block, blob or data column is re-published whilst the mesh of
+/// its topic remains under-populated.
+const MAX_PUBLISH_RETRIES: usize = 3;
+
+/// A message published whilst the mesh of its topic was under-populated, which is waiting to be
+/// sent again.
+struct PublishRetry {
+    topic: GossipTopic,
+    message_id: MessageId,
+    /// The number of times the message has been re-published so far.
+    attempt: usize,
+}
+
 /// The types of events than can be obtained from polling the behaviour.
 #[derive(Debug)]
 pub enum NetworkEvent<E: EthSpec> {
@@ -159,6 +179,10 @@ pub struct Network<E: EthSpec> {
     /// The interval for updating gossipsub scores
     update_gossipsub_scores: tokio::time::Interval,
     gossip_cache: GossipCache,
+    /// Messages which will be re-published as their topic's mesh was under-populated.
+    publish_retries: DelayQueue<PublishRetry>,
+    /// Defers messages published beyond their topic's rate limit.
+    publish_limiter: PublishLimiter,
     /// This node's PeerId.
     pub local_peer_id: PeerId,
     /// Logger for behaviour actions.
@@ -177,7 +201,10 @@ impl<E: EthSpec> Network<E> {
         let config = ctx.config.clone();
         trace!(log, "Libp2p Service starting");
         // initialise the node's ID
-        let local_keypair = utils::load_private_key(&config, &log);
+        let utils::NetworkKeys {
+            local: local_keypair,
+            previous: previous_network_key,
+        } = utils::load_or_rotate_private_key(&config, &log)?;
 
         // Trusted peers will also be marked as explicit in GossipSub.
         // Cfr. https://github.com/libp2p/specs/blob/master/pubsub/gossipsub/gossipsub-v1.1.md#explicit-peering-agreements
@@ -228,6 +255,8 @@ impl<E: EthSpec> Network<E> {
         let gossipsub_config_params = GossipsubConfigParams {
             message_domain_valid_snappy: ctx.chain_spec.message_domain_valid_snappy,
             gossip_max_size: ctx.chain_spec.gossip_max_size as usize,
+            duplicate_cache_time: config.gossip_duplicate_cache_time,
+            duplicate_cache_max_len: config.gossip_duplicate_cache_max_len,
         };
         let gs_config = gossipsub_config(
             config.network_load,
@@ -259,6 +288,13 @@ impl<E: EthSpec> Network<E> {
                 .build()
         };
 
+        // Messages deferred for longer than a slot are no longer worth publishing.
+        let publish_limiter = PublishLimiter::new(
+            config.publish_rate_limiter_config.clone(),
+            Duration::from_secs(ctx.chain_spec.seconds_per_slot),
+            log.clone(),
+        )?;
+
         let local_peer_id = network_globals.local_peer_id();
 
         let (gossipsub, update_gossipsub_scores) = {
@@ -359,7 +395,7 @@ impl<E: EthSpec> Network<E> {
             ttfb_timeout: ctx.chain_spec.ttfb_timeout(),
             resp_timeout: ctx.chain_spec.resp_timeout(),
         };
-        let eth2_rpc = RPC::new(
+        let mut eth2_rpc = RPC::new(
             ctx.fork_context.clone(),
             config.enable_light_client_server,
             config.inbound_rate_limiter_config.clone(),
@@ -368,6 +404,9 @@ impl<E: EthSpec> Network<E> {
             network_params,
             seq_number,
         );
+        for trusted_peer in config.trusted_peers.iter() {
+            eth2_rpc.add_trusted_peer(PeerId::from(trusted_peer.clone()));
+        }
 
         let discovery = {
             // Build and start the discovery sub-behaviour
@@ -379,6 +418,15 @@ impl<E: EthSpec> Network<E> {
                 &ctx.chain_spec,
             )
             .await?;
+            // keep announcing the previous ENR during a key rotation grace period
+            if let Some(previous_network_key) = previous_network_key {
+                if let Err(e) = discovery
+                    .announce_previous_identity(previous_network_key, &config)
+                    .await
+                {
+                    warn!(log, "Unable to announce the previous ENR"; "error" => e);
+                }
+            }
             // start searching for peers
             discovery.discover_peers(FIND_NODE_QUERY_CLOSEST_PEERS);
             discovery
@@ -406,6 +454,8 @@ impl<E: EthSpec> Network<E> {
                 quic_enabled: !config.disable_quic_support,
                 metrics_enabled: config.metrics_enabled,
                 target_peer_count: config.target_peers,
+                preserve_client_diversity: config.preserve_client_diversity,
+                fork_context: Some(ctx.fork_context.clone()),
                 ..Default::default()
             };
             PeerManager::new(peer_manager_cfg, network_globals.clone(), &log)?
@@ -432,9 +482,9 @@ impl<E: EthSpec> Network<E> {
             libp2p::connection_limits::Behaviour::new(limits)
         };
 
+        // Port mappings are pointless when not accepting inbound connections.
         let upnp = Toggle::from(
-            config
-                .upnp_enabled
+            (config.upnp_enabled && !config.disable_inbound)
                 .then(libp2p::upnp::tokio::Behaviour::default),
         );
         let behaviour = {
@@ -450,8 +500,16 @@ impl<E: EthSpec> Network<E> {
         };
 
         // Set up the transport - tcp/quic with noise and mplex
-        let transport = build_transport(local_keypair.clone(), !config.disable_quic_support)
-            .map_err(|e| format!("Failed to build transport: {:?}", e))?;
+        let transport = build_transport(
+            local_keypair.clone(),
+            !config.disable_quic_support,
+            config.dscp,
+            &log,
+        )
+        .map_err(|e| format!("Failed to build transport: {:?}", e))?;
+        if config.dscp.is_some() && !config.disable_quic_support {
+            warn!(log, "DSCP marking does not apply to QUIC connections"; "info" => "Use --disable-quic to mark all libp2p traffic");
+        }
 
         // use the executor for libp2p
         struct Executor(task_executor::TaskExecutor);
@@ -502,6 +560,8 @@ impl<E: EthSpec> Network<E> {
             score_settings,
             update_gossipsub_scores,
             gossip_cache,
+            publish_retries: DelayQueue::default(),
+            publish_limiter,
             local_peer_id,
             log,
         };
@@ -521,9 +581,17 @@ impl<E: EthSpec> Network<E> {
     async fn start(&mut self, config: &crate::NetworkConfig) -> Result<(), String> {
         let enr = self.network_globals.local_enr();
         info!(self.log, "Libp2p Starting"; "peer_id" => %enr.peer_id(), "bandwidth_config" => format!("{}-{}", config.network_load, NetworkLoad::from(config.network_load).name));
-        debug!(self.log, "Attempting to open listening ports"; config.listen_addrs(), "discovery_enabled" => !config.disable_discovery, "quic_enabled" => !config.disable_quic_support);
+        debug!(self.log, "Attempting to open listening ports"; config.listen_addrs(), "additional_listen_addresses" => config.additional_listen_addrs().len(), "discovery_enabled" => !config.disable_discovery, "quic_enabled" => !config.disable_quic_support);
+
+        if config.disable_inbound {
+            info!(self.log, "Inbound libp2p connections are disabled");
+        }
+
+        for listen_multiaddr in config.libp2p_listen_addresses() {
+            if config.disable_inbound {
+                break;
+            }
 
-        for listen_multiaddr in config.listen_addrs().libp2p_addresses() {
             // If QUIC is disabled, ignore listening on QUIC ports
             if config.disable_quic_support
                 && listen_multiaddr.iter().any(|v| v == MProtocol::QuicV1)
@@ -827,58 +895,195 @@ impl<E: EthSpec> Network<E> {
     }
 
     /// Publishes a list of messages on the pubsub (gossipsub) behaviour, choosing the encoding.
+    ///
+    /// Messages beyond the rate limit of their topic are deferred.
     pub fn publish(&mut self, messages: Vec<PubsubMessage<E>>) {
         for message in messages {
             for topic in message.topics(GossipEncoding::default(), self.enr_fork_id.fork_digest) {
                 let message_data = message.encode(GossipEncoding::default());
-                if let Err(e) = self
-                    .gossipsub_mut()
-                    .publish(Topic::from(topic.clone()), message_data.clone())
-                {
-                    match e {
-                        PublishError::Duplicate => {
-                            debug!(
-                                self.log,
-                                "Attempted to publish duplicate message";
-                                "kind" => %topic.kind(),
-                            );
-                        }
-                        ref e => {
-                            warn!(
-                                self.log,
-                                "Could not publish message";
-                                "error" => ?e,
-                                "kind" => %topic.kind(),
-                            );
-                        }
-                    }
+                if let Some(message_data) = self.publish_limiter.allows(&topic, message_data) {
+                    self.publish_to_topic(topic, message_data);
+                }
+            }
+        }
+    }
 
-                    // add to metrics
-                    match topic.kind() {
-                        GossipKind::Attestation(subnet_id) => {
-                            if let Some(v) = metrics::get_int_gauge(
-                                &metrics::FAILED_ATTESTATION_PUBLISHES_PER_SUBNET,
-                                &[subnet_id.as_ref()],
-                            ) {
-                                v.inc()
-                            };
-                        }
-                        kind => {
-                            if let Some(v) = metrics::get_int_gauge(
-                                &metrics::FAILED_PUBLISHES_PER_MAIN_TOPIC,
-                                &[&format!("{:?}", kind)],
-                            ) {
-                                v.inc()
-                            };
-                        }
+    /// Publishes an encoded message on `topic`.
+    fn publish_to_topic(&mut self, topic: GossipTopic, message_data: Vec<u8>) {
+        let libp2p_topic = Topic::from(topic.clone());
+        let result = self
+            .gossipsub_mut()
+            .publish(libp2p_topic.clone(), message_data.clone());
+        match result {
+            Err(e) => {
+                match e {
+                    PublishError::Duplicate => {
+                        debug!(
+                            self.log,
+                            "Attempted to publish duplicate message";
+                            "kind" => %topic.kind(),
+                        );
                     }
+                    ref e => {
+                        warn!(
+                            self.log,
+                            "Could not publish message";
+                            "error" => ?e,
+                            "kind" => %topic.kind(),
+                        );
+                    }
+                }
 
-                    if let PublishError::InsufficientPeers = e {
-                        self.gossip_cache.insert(topic, message_data);
+                // add to metrics
+                match topic.kind() {
+                    GossipKind::Attestation(subnet_id) => {
+                        if let Some(v) = metrics::get_int_gauge(
+                            &metrics::FAILED_ATTESTATION_PUBLISHES_PER_SUBNET,
+                            &[subnet_id.as_ref()],
+                        ) {
+                            v.inc()
+                        };
+                    }
+                    kind => {
+                        if let Some(v) = metrics::get_int_gauge(
+                            &metrics::FAILED_PUBLISHES_PER_MAIN_TOPIC,
+                            &[&format!("{:?}", kind)],
+                        ) {
+                            v.inc()
+                        };
                     }
                 }
+
+                if let PublishError::InsufficientPeers = e {
+                    self.gossip_cache.insert(topic, message_data);
+                }
+            }
+            Ok(message_id) => {
+                let mesh_peers = self.gossipsub().mesh_peers(&libp2p_topic.hash()).count();
+                metrics::observe_vec(
+                    &metrics::GOSSIP_PUBLISH_MESH_PEERS_PER_TOPIC_KIND,
+                    &[topic.kind().as_ref()],
+                    mesh_peers as f64,
+                );
+                // Each mesh peer is sent a copy of the message.
+                self.network_globals.bandwidth.record(
+                    TrafficClass::GossipPublish,
+                    message_data.len().saturating_mul(mesh_peers.max(1)),
+                );
+                self.schedule_publish_retry(topic, message_id, mesh_peers, 0);
+            }
+        }
+    }
+
+    /// Queues a block, blob or data column to be re-published if the mesh of its topic has fewer
+    /// than the configured minimum number of peers, so that poor initial propagation is not
+    /// silently accepted.
+    fn schedule_publish_retry(
+        &mut self,
+        topic: GossipTopic,
+        message_id: MessageId,
+        mesh_peers: usize,
+        attempt: usize,
+    ) {
+        let config = &self.network_globals.config;
+        let min_mesh_peers = config.gossip_publish_retry_min_mesh_peers;
+        let retryable = matches!(
+            topic.kind(),
+            GossipKind::BeaconBlock | GossipKind::BlobSidecar(_) | GossipKind::DataColumnSidecar(_)
+        );
+        if !retryable || mesh_peers >= min_mesh_peers {
+            return;
+        }
+
+        if attempt >= MAX_PUBLISH_RETRIES {
+            debug!(
+                self.log,
+                "Mesh still under-populated after publish retries";
+                "kind" => %topic.kind(),
+                "mesh_peers" => mesh_peers,
+                "attempts" => attempt,
+            );
+            return;
+        }
+
+        if attempt == 0 {
+            debug!(
+                self.log,
+                "Published to under-populated mesh, will retry";
+                "kind" => %topic.kind(),
+                "mesh_peers" => mesh_peers,
+                "min_mesh_peers" => min_mesh_peers,
+            );
+            metrics::inc_counter_vec(
+                &metrics::GOSSIP_PUBLISH_INSUFFICIENT_MESH_PER_TOPIC_KIND,
+                &[topic.kind().as_ref()],
+            );
+        }
+
+        let delay = config.gossip_publish_retry_delay;
+        self.publish_retries.insert(
+            PublishRetry {
+                topic,
+                message_id,
+                attempt,
+            },
+            delay,
+        );
+    }
+
+    /// Sends a message queued by `schedule_publish_retry` to the mesh, explicit (trusted) and other
+    /// well-scored peers of its topic.
+    fn retry_publish(&mut self, retry: PublishRetry) {
+        let PublishRetry {
+            topic,
+            message_id,
+            attempt,
+        } = retry;
+        let topic_kind: &str = topic.kind().as_ref();
+
+        match self.gossipsub_mut().republish(&message_id) {
+            Ok(peers) => {
+                debug!(
+                    self.log,
+                    "Gossip message re-published";
+                    "kind" => topic_kind,
+                    "peers" => peers,
+                    "attempt" => attempt + 1,
+                );
+                metrics::inc_counter_vec(
+                    &metrics::GOSSIP_PUBLISH_RETRIES_PER_TOPIC_KIND,
+                    &[topic_kind, "success"],
+                );
+                metrics::observe_vec(
+                    &metrics::GOSSIP_REPUBLISH_FANOUT_PER_TOPIC_KIND,
+                    &[topic_kind],
+                    peers as f64,
+                );
+            }
+            Err(e) => {
+                debug!(
+                    self.log,
+                    "Failed to re-publish gossip message";
+                    "kind" => topic_kind,
+                    "error" => ?e,
+                    "attempt" => attempt + 1,
+                );
+                metrics::inc_counter_vec(
+                    &metrics::GOSSIP_PUBLISH_RETRIES_PER_TOPIC_KIND,
+                    &[topic_kind, "failed"],
+                );
+                // The message has left the cache, so it can no longer be re-published.
+                if let PublishError::NotInCache = e {
+                    return;
+                }
             }
         }
+
+        let mesh_peers = self
+            .gossipsub()
+            .mesh_peers(&Topic::from(topic.clone()).hash())
+            .count();
+        self.schedule_publish_retry(topic, message_id, mesh_peers, attempt + 1);
     }
 
     /// Informs the gossipsub about the result of a message validation.
@@ -1033,6 +1238,14 @@ impl<E: EthSpec> Network<E> {
             .goodbye_peer(peer_id, reason, source);
     }
 
+    /// Marks a peer as trusted, so that it is never penalised, pruned or banned, as an explicit
+    /// peer in gossipsub, and as subject to the trusted peer rate limit.
+    pub fn protect_peer(&mut self, peer_id: &PeerId) {
+        self.peer_manager_mut().protect_peer(peer_id);
+        self.gossipsub_mut().add_explicit_peer(peer_id);
+        self.eth2_rpc_mut().add_trusted_peer(*peer_id);
+    }
+
     /// Hard (ungraceful) disconnect for testing purposes only
     /// Use goodbye_peer for disconnections, do not use this function.
     pub fn __hard_disconnect_testing_only(&mut self, peer_id: PeerId) {
@@ -1459,6 +1672,10 @@ impl<E: EthSpec> Network<E> {
                             "reason" => %reason,
                             "client" => %self.network_globals.client(&peer_id),
                         );
+                        self.network_globals
+                            .peers
+                            .write()
+                            .goodbye_received(&peer_id, &reason);
                         // NOTE: We currently do not inform the application that we are
                         // disconnecting here. The RPC handler will automatically
                         // disconnect for us.
@@ -1739,6 +1956,10 @@ impl<E: EthSpec> Network<E> {
             PeerManagerEvent::DisconnectPeer(peer_id, reason) => {
                 debug!(self.log, "Peer Manager disconnecting peer";
                        "peer_id" => %peer_id, "reason" => %reason);
+                self.network_globals
+                    .peers
+                    .write()
+                    .goodbye_sent(&peer_id, &reason);
                 // send one goodbye
                 self.eth2_rpc_mut()
                     .shutdown(peer_id, RequestId::Internal, reason);
@@ -1810,6 +2031,14 @@ impl<E: EthSpec> Network<E> {
                     let this = self.swarm.behaviour_mut();
                     this.peer_manager.update_gossipsub_scores(&this.gossipsub);
                 }
+                // re-publish messages sent to an under-populated mesh
+                Some(retry) = self.publish_retries.next() => {
+                    self.retry_publish(retry.into_inner());
+                }
+                // publish messages deferred by the publish rate limiter
+                Some((topic, message_data)) = self.publish_limiter.next() => {
+                    self.publish_to_topic(topic, message_data);
+                }
                 // poll the gossipsub cache to clear expired messages
                 Some(result) = self.gossip_cache.next() => {
                     match result {
@@ -0,0 +1,398 @@
+//! Rate limiting of the gossip messages published by this node.
+//!
+//! A validator client with many keys can submit hundreds of aggregates or sync committee messages
+//! through the HTTP API at once. Publishing them in a single burst may exceed what peers tolerate
+//! on a topic, so messages beyond a topic's quota are deferred until the quota allows them, and
+//! dropped if they could not be published in time to be useful.
+use std::collections::hash_map::Entry;
+use std::collections::{HashMap, VecDeque};
+use std::pin::Pin;
+use std::str::FromStr;
+use std::task::{Context, Poll};
+use std::time::{Duration, Instant};
+
+use serde::{Deserialize, Serialize};
+use slog::{debug, Logger};
+use tokio_util::time::DelayQueue;
+
+use crate::metrics;
+use crate::rpc::rate_limiter::{Limiter, Quota, RateLimitedErr};
+use crate::types::GossipKind;
+use crate::GossipTopic;
+
+/// The maximum number of messages deferred per topic. Further messages are dropped.
+const MAX_QUEUED_MESSAGES_PER_TOPIC: usize = 1024;
+
+/// The kinds of topic on which publishing is rate limited.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+enum LimitedKind {
+    Attestation,
+    Aggregate,
+    SyncCommitteeMessage,
+    Contribution,
+}
+
+impl LimitedKind {
+    fn from_gossip_kind(kind: &GossipKind) -> Option<Self> {
+        match kind {
+            GossipKind::Attestation(_) => Some(LimitedKind::Attestation),
+            GossipKind::BeaconAggregateAndProof => Some(LimitedKind::Aggregate),
+            GossipKind::SyncCommitteeMessage(_) => Some(LimitedKind::SyncCommitteeMessage),
+            GossipKind::SignedContributionAndProof => Some(LimitedKind::Contribution),
+            _ => None,
+        }
+    }
+}
+
+/// Auxiliary struct to aid on configuration parsing.
+///
+/// A topic kind's quota is specified as `topic_kind:tokens/time_in_seconds`, using the topic kind
+/// labels of the gossipsub metrics.
+struct TopicQuota {
+    kind: LimitedKind,
+    quota: Quota,
+}
+
+impl FromStr for TopicQuota {
+    type Err = &'static str;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (kind_str, quota_str) = s
+            .split_once(':')
+            .ok_or("Missing ':' from quota definition.")?;
+        let kind = match kind_str {
+            "beacon_attestation" => LimitedKind::Attestation,
+            "beacon_aggregate_and_proof" => LimitedKind::Aggregate,
+            "sync_committee" => LimitedKind::SyncCommitteeMessage,
+            "signed_contribution_and_proof" => LimitedKind::Contribution,
+            _ => return Err("Unknown or unlimited topic kind in quota"),
+        };
+        let (tokens_str, time_str) = quota_str
+            .split_once('/')
+            .ok_or("Quota should be defined as \"n/t\" (t in seconds). Missing '/' from quota.")?;
+        let tokens = tokens_str
+            .parse()
+            .map_err(|_| "Failed to parse tokens from quota.")?;
+        let seconds = time_str
+            .parse()
+            .map_err(|_| "Failed to parse time in seconds from quota.")?;
+        Ok(TopicQuota {
+            kind,
+            quota: Quota::n_every(tokens, seconds),
+        })
+    }
+}
+
+/// Quotas for the messages this node publishes on each topic. Every subnet topic is limited
+/// separately.
+#[derive(Clone, Serialize, Deserialize, PartialEq, Eq, Debug)]
+pub struct PublishRateLimiterConfig {
+    attestation_quota: Quota,
+    aggregate_quota: Quota,
+    sync_committee_message_quota: Quota,
+    contribution_quota: Quota,
+}
+
+impl PublishRateLimiterConfig {
+    // A subnet receives around 1/64th of the committee members of a slot, so even the largest
+    // operators publish far fewer attestations per subnet.
+    pub const DEFAULT_ATTESTATION_QUOTA: Quota = Quota::n_every(256, 12);
+    // There are at most 16 aggregators per committee and 64 committees per slot.
+    pub const DEFAULT_AGGREGATE_QUOTA: Quota = Quota::n_every(512, 12);
+    // Each of the 4 sync subnets has 128 members.
+    pub const DEFAULT_SYNC_COMMITTEE_MESSAGE_QUOTA: Quota = Quota::n_every(128, 12);
+    // There are at most 16 aggregators per sync subnet.
+    pub const DEFAULT_CONTRIBUTION_QUOTA: Quota = Quota::n_every(64, 12);
+
+    fn quota(&self, kind: LimitedKind) -> &Quota {
+        match kind {
+            LimitedKind::Attestation => &self.attestation_quota,
+            LimitedKind::Aggregate => &self.aggregate_quota,
+            LimitedKind::SyncCommitteeMessage => &self.sync_committee_message_quota,
+            LimitedKind::Contribution => &self.contribution_quota,
+        }
+    }
+}
+
+impl Default for PublishRateLimiterConfig {
+    fn default() -> Self {
+        PublishRateLimiterConfig {
+            attestation_quota: Self::DEFAULT_ATTESTATION_QUOTA,
+            aggregate_quota: Self::DEFAULT_AGGREGATE_QUOTA,
+            sync_committee_message_quota: Self::DEFAULT_SYNC_COMMITTEE_MESSAGE_QUOTA,
+            contribution_quota: Self::DEFAULT_CONTRIBUTION_QUOTA,
+        }
+    }
+}
+
+/// Parse configurations for the publish rate limiter. Topic kinds that are not specified use the
+/// default values. Topic kinds specified more than once use only the first given quota.
+///
+/// The expected format is a ';' separated list of `topic_kind:tokens/time_in_seconds`.
+impl FromStr for PublishRateLimiterConfig {
+    type Err = &'static str;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut quotas = HashMap::new();
+        for topic_def in s.split(';') {
+            let TopicQuota { kind, quota } = topic_def.parse()?;
+            quotas.entry(kind).or_insert(quota);
+        }
+        let mut quota = |kind, default| quotas.remove(&kind).unwrap_or(default);
+        Ok(PublishRateLimiterConfig {
+            attestation_quota: quota(LimitedKind::Attestation, Self::DEFAULT_ATTESTATION_QUOTA),
+            aggregate_quota: quota(LimitedKind::Aggregate, Self::DEFAULT_AGGREGATE_QUOTA),
+            sync_committee_message_quota: quota(
+                LimitedKind::SyncCommitteeMessage,
+                Self::DEFAULT_SYNC_COMMITTEE_MESSAGE_QUOTA,
+            ),
+            contribution_quota: quota(LimitedKind::Contribution, Self::DEFAULT_CONTRIBUTION_QUOTA),
+        })
+    }
+}
+
+/// A message which was deferred by the limiter.
+struct QueuedMessage {
+    data: Vec<u8>,
+    queued_at: Instant,
+}
+
+/// Defers the messages published on a topic beyond its quota. Deferred messages are yielded by the
+/// `Stream` implementation once they may be published.
+pub(crate) struct PublishLimiter {
+    /// Rate limiters per kind of topic, each keyed by topic. Absent when publishing is not limited.
+    limiters: HashMap<LimitedKind, Limiter<GossipTopic>>,
+    /// Messages deferred per topic, oldest first.
+    queued: HashMap<GossipTopic, VecDeque<QueuedMessage>>,
+    /// The delay until a topic's quota allows its next queued message.
+    next_topic_publish: DelayQueue<GossipTopic>,
+    /// Messages which may now be published.
+    ready: VecDeque<(GossipTopic, Vec<u8>)>,
+    /// Messages which have been deferred for longer than this are dropped.
+    max_delay: Duration,
+    /// The time the limiters were created, from which their token accounting is measured.
+    init_time: Instant,
+    log: Logger,
+}
+
+impl PublishLimiter {
+    /// Creates a limiter which applies the quotas of `config`, or does not limit publishing if
+    /// `config` is `None`.
+    pub fn new(
+        config: Option<PublishRateLimiterConfig>,
+        max_delay: Duration,
+        log: Logger,
+    ) -> Result<Self, &'static str> {
+        let mut limiters = HashMap::new();
+        if let Some(config) = config {
+            debug!(log, "Using publish rate limiting params"; "config" => ?config);
+            for kind in [
+                LimitedKind::Attestation,
+                LimitedKind::Aggregate,
+                LimitedKind::SyncCommitteeMessage,
+                LimitedKind::Contribution,
+            ] {
+                limiters.insert(kind, Limiter::from_quota(config.quota(kind).clone())?);
+            }
+        }
+
+        Ok(PublishLimiter {
+            limiters,
+            queued: HashMap::new(),
+            next_topic_publish: DelayQueue::new(),
+            ready: VecDeque::new(),
+            max_delay,
+            init_time: Instant::now(),
+            log,
+        })
+    }
+
+    /// Returns `data` if it may be published on `topic` now. Otherwise the message is deferred, or
+    /// dropped if too many messages are already deferred on the topic.
+    pub fn allows(&mut self, topic: &GossipTopic, data: Vec<u8>) -> Option<Vec<u8>> {
+        let Some(limiter) = LimitedKind::from_gossip_kind(topic.kind())
+            .and_then(|kind| self.limiters.get_mut(&kind))
+        else {
+            return Some(data);
+        };
+        let topic_kind: &str = topic.kind().as_ref();
+        let now = Instant::now();
+        let message = QueuedMessage {
+            data,
+            queued_at: now,
+        };
+
+        // Messages are published in order, so must wait behind those already deferred.
+        if let Some(queue) = self.queued.get_mut(topic) {
+            if queue.len() >= MAX_QUEUED_MESSAGES_PER_TOPIC {
+                metrics::inc_counter_vec(
+                    &metrics::GOSSIP_PUBLISH_DROPPED_PER_TOPIC_KIND,
+                    &[topic_kind, "queue_full"],
+                );
+            } else {
+                queue.push_back(message);
+                metrics::inc_counter_vec(
+                    &metrics::GOSSIP_PUBLISH_DEFERRED_PER_TOPIC_KIND,
+                    &[topic_kind],
+                );
+            }
+            return None;
+        }
+
+        match limiter.allows(now.duration_since(self.init_time), topic, 1) {
+            // A single message always fits into a quota.
+            Ok(()) | Err(RateLimitedErr::TooLarge) => Some(message.data),
+            Err(RateLimitedErr::TooSoon(wait_time)) => {
+                debug!(
+                    self.log,
+                    "Deferring gossip publish";
+                    "topic" => %topic.kind(),
+                    "wait_time_ms" => wait_time.as_millis(),
+                );
+                self.next_topic_publish.insert(topic.clone(), wait_time);
+                self.queued.insert(topic.clone(), VecDeque::from([message]));
+                metrics::inc_counter_vec(
+                    &metrics::GOSSIP_PUBLISH_DEFERRED_PER_TOPIC_KIND,
+                    &[topic_kind],
+                );
+                None
+            }
+        }
+    }
+
+    /// Marks as ready as many of the messages deferred on `topic` as its quota allows, dropping
+    /// those which have waited too long.
+    fn topic_ready(&mut self, topic: GossipTopic) {
+        let Some(limiter) = LimitedKind::from_gossip_kind(topic.kind())
+            .and_then(|kind| self.limiters.get_mut(&kind))
+        else {
+            return;
+        };
+        let Entry::Occupied(mut entry) = self.queued.entry(topic) else {
+            return;
+        };
+        let topic_kind: &str = entry.key().kind().as_ref();
+        let now = Instant::now();
+
+        while let Some(message) = entry.get_mut().pop_front() {
+            if now.duration_since(message.queued_at) > self.max_delay {
+                metrics::inc_counter_vec(
+                    &metrics::GOSSIP_PUBLISH_DROPPED_PER_TOPIC_KIND,
+                    &[topic_kind, "expired"],
+                );
+                continue;
+            }
+            match limiter.allows(now.duration_since(self.init_time), entry.key(), 1) {
+                Ok(()) | Err(RateLimitedErr::TooLarge) => {
+                    self.ready.push_back((entry.key().clone(), message.data))
+                }
+                Err(RateLimitedErr::TooSoon(wait_time)) => {
+                    self.next_topic_publish
+                        .insert(entry.key().clone(), wait_time);
+                    entry.get_mut().push_front(message);
+                    return;
+                }
+            }
+        }
+        entry.remove();
+    }
+}
+
+impl futures::stream::Stream for PublishLimiter {
+    type Item = (GossipTopic, Vec<u8>);
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        loop {
+            if let Some(message) = self.ready.pop_front() {
+                return Poll::Ready(Some(message));
+            }
+            match self.next_topic_publish.poll_expired(cx) {
+                Poll::Ready(Some(expired)) => self.topic_ready(expired.into_inner()),
+                // The queue is empty, but the limiter is never exhausted.
+                Poll::Ready(None) | Poll::Pending => return Poll::Pending,
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::GossipEncoding;
+    use futures::stream::StreamExt;
+
+    fn topic(kind: GossipKind) -> GossipTopic {
+        GossipTopic::new(kind, GossipEncoding::SSZSnappy, [0u8; 4])
+    }
+
+    #[test]
+    fn parse_config() {
+        let config: PublishRateLimiterConfig = "beacon_aggregate_and_proof:32/12;\
+            sync_committee:8/12;beacon_aggregate_and_proof:64/12"
+            .parse()
+            .unwrap();
+        assert_eq!(config.aggregate_quota, Quota::n_every(32, 12));
+        assert_eq!(config.sync_committee_message_quota, Quota::n_every(8, 12));
+        assert_eq!(
+            config.attestation_quota,
+            PublishRateLimiterConfig::DEFAULT_ATTESTATION_QUOTA
+        );
+
+        assert!("beacon_block:1/12"
+            .parse::<PublishRateLimiterConfig>()
+            .is_err());
+        assert!("beacon_attestation:1"
+            .parse::<PublishRateLimiterConfig>()
+            .is_err());
+    }
+
+    #[tokio::test]
+    async fn defers_messages_beyond_quota() {
+        let config = "signed_contribution_and_proof:2/1".parse().unwrap();
+        let log = logging::test_logger();
+        let mut limiter = PublishLimiter::new(Some(config), Duration::from_secs(12), log).unwrap();
+        let contribution = topic(GossipKind::SignedContributionAndProof);
+        let block = topic(GossipKind::BeaconBlock);
+
+        assert_eq!(limiter.allows(&contribution, vec![1]), Some(vec![1]));
+        assert_eq!(limiter.allows(&contribution, vec![2]), Some(vec![2]));
+        assert_eq!(limiter.allows(&contribution, vec![3]), None);
+        assert_eq!(limiter.allows(&contribution, vec![4]), None);
+        // Other topics are not limited.
+        assert_eq!(limiter.allows(&block, vec![5]), Some(vec![5]));
+
+        // Deferred messages are released in order as the quota replenishes.
+        assert_eq!(limiter.next().await, Some((contribution.clone(), vec![3])));
+        assert_eq!(limiter.next().await, Some((contribution, vec![4])));
+        assert!(limiter.queued.is_empty());
+    }
+
+    #[tokio::test]
+    async fn drops_expired_messages() {
+        let config = "beacon_aggregate_and_proof:1/1".parse().unwrap();
+        let log = logging::test_logger();
+        let mut limiter = PublishLimiter::new(Some(config), Duration::ZERO, log).unwrap();
+        let aggregate = topic(GossipKind::BeaconAggregateAndProof);
+
+        assert_eq!(limiter.allows(&aggregate, vec![1]), Some(vec![1]));
+        assert_eq!(limiter.allows(&aggregate, vec![2]), None);
+        assert_eq!(limiter.allows(&aggregate, vec![3]), None);
+
+        let next = tokio::time::timeout(Duration::from_secs(2), limiter.next()).await;
+        assert!(next.is_err());
+        assert!(limiter.queued.is_empty());
+    }
+
+    #[tokio::test]
+    async fn unlimited_without_config() {
+        let log = logging::test_logger();
+        let mut limiter = PublishLimiter::new(None, Duration::ZERO, log).unwrap();
+        let aggregate = topic(GossipKind::BeaconAggregateAndProof);
+        for i in 0..1000 {
+            assert_eq!(
+                limiter.allows(&aggregate, vec![i as u8]),
+                Some(vec![i as u8])
+            );
+        }
+    }
+}
@@ -9,19 +9,23 @@ use libp2p::core::{multiaddr::Multiaddr, muxing::StreamMuxerBox, transport::Boxe
 use libp2p::identity::{secp256k1, Keypair};
 use libp2p::{core, noise, yamux, PeerId, Transport};
 use prometheus_client::registry::Registry;
-use slog::{debug, warn};
+use slog::{debug, info, warn};
 use ssz::Decode;
 use std::collections::HashSet;
-use std::fs::File;
+use std::fs::{self, File, OpenOptions};
 use std::io::prelude::*;
 use std::path::Path;
 use std::sync::Arc;
-use std::time::Duration;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
 use types::{
     ChainSpec, DataColumnSubnetId, EnrForkId, EthSpec, ForkContext, SubnetId, SyncSubnetId,
 };
 
 pub const NETWORK_KEY_FILENAME: &str = "key";
+/// The filename to store the network key most recently rotated away from.
+pub const NETWORK_KEY_PREVIOUS_FILENAME: &str = "key_previous";
+/// The filename to record when the network key was last rotated.
+pub const NETWORK_KEY_ROTATION_FILENAME: &str = "key_rotation";
 /// The filename to store our local metadata.
 pub const METADATA_FILENAME: &str = "metadata";
 
@@ -37,9 +41,13 @@ type BoxedTransport = Boxed<(PeerId, StreamMuxerBox)>;
 
 /// The implementation supports TCP/IP, QUIC (experimental) over UDP, noise as the encryption layer, and
 /// mplex/yamux as the multiplexing layer (when using TCP).
+///
+/// If `dscp` is set, outgoing packets on TCP connections are marked with it.
 pub fn build_transport(
     local_private_key: Keypair,
     quic_support: bool,
+    dscp: Option<u8>,
+    log: &slog::Logger,
 ) -> std::io::Result<BoxedTransport> {
     // mplex config
     let mut mplex_config = libp2p_mplex::MplexConfig::new();
@@ -49,7 +57,16 @@ pub fn build_transport(
     // yamux config
     let yamux_config = yamux::Config::default();
     // Creates the TCP transport layer
+    let log = log.clone();
     let tcp = libp2p::tcp::tokio::Transport::new(libp2p::tcp::Config::default().nodelay(true))
+        .map(move |stream: libp2p::tcp::tokio::TcpStream, _| {
+            if let Some(dscp) = dscp {
+                if let Err(e) = set_dscp(&stream.0, dscp) {
+                    debug!(log, "Failed to set DSCP on connection"; "error" => %e);
+                }
+            }
+            stream
+        })
         .upgrade(core::upgrade::Version::V1)
         .authenticate(generate_noise_config(&local_private_key))
         .multiplex(core::upgrade::SelectUpgrade::new(
@@ -79,6 +96,30 @@ pub fn build_transport(
     Ok(transport)
 }
 
+/// Mark outgoing packets on `stream` with the differentiated services code point `dscp`.
+///
+/// Gossip and RPC traffic with a peer are multiplexed over a single connection, so all libp2p
+/// traffic on the connection receives the same mark.
+#[cfg(unix)]
+fn set_dscp(stream: &tokio::net::TcpStream, dscp: u8) -> std::io::Result<()> {
+    // The DSCP occupies the upper six bits of the IPv4 TOS and IPv6 traffic class fields.
+    let tos = u32::from(dscp) << 2;
+    let socket = socket2::SockRef::from(stream);
+    if stream.local_addr()?.is_ipv6() {
+        socket.set_tclass_v6(tos)
+    } else {
+        socket.set_tos(tos)
+    }
+}
+
+#[cfg(not(unix))]
+fn set_dscp(_stream: &tokio::net::TcpStream, _dscp: u8) -> std::io::Result<()> {
+    Err(std::io::Error::new(
+        std::io::ErrorKind::Unsupported,
+        "DSCP marking is not supported on this platform",
+    ))
+}
+
 // Useful helper functions for debugging. Currently not used in the client.
 #[allow(dead_code)]
 fn keypair_from_hex(hex_bytes: &str) -> Result<Keypair, String> {
@@ -146,6 +187,178 @@ pub fn load_private_key(config: &NetworkConfig, log: &slog::Logger) -> Keypair {
     local_private_key.into()
 }
 
+/// Reads a secp256k1 network key from `path`.
+pub fn read_network_key(path: &Path) -> Result<Keypair, String> {
+    let mut key_bytes =
+        fs::read(path).map_err(|e| format!("Unable to read network key {:?}: {}", path, e))?;
+    let secret_key = secp256k1::SecretKey::try_from_bytes(&mut key_bytes)
+        .map_err(|e| format!("Network key {:?} is not a valid secp256k1 key: {}", path, e))?;
+    let keypair: secp256k1::Keypair = secret_key.into();
+    Ok(keypair.into())
+}
+
+/// Writes `keypair` to `path`, readable only by the current user.
+///
+/// If `overwrite` is false, errors if a file already exists at `path`.
+fn write_network_key(path: &Path, keypair: &Keypair, overwrite: bool) -> Result<(), String> {
+    let secret_bytes = keypair
+        .clone()
+        .try_into_secp256k1()
+        .map_err(|e| format!("Network key is not a secp256k1 key: {}", e))?
+        .secret()
+        .to_bytes();
+
+    let mut options = OpenOptions::new();
+    options.write(true);
+    if overwrite {
+        options.create(true).truncate(true);
+    } else {
+        options.create_new(true);
+    }
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::OpenOptionsExt;
+        options.mode(0o600);
+    }
+    options
+        .open(path)
+        .and_then(|mut file| file.write_all(&secret_bytes))
+        .map_err(|e| format!("Unable to write network key {:?}: {}", path, e))
+}
+
+/// Generates a new secp256k1 network key and writes it to `path`, which must not already exist.
+pub fn generate_network_key(path: &Path) -> Result<Keypair, String> {
+    let keypair: Keypair = secp256k1::Keypair::generate().into();
+    write_network_key(path, &keypair, false)?;
+    Ok(keypair)
+}
+
+/// A network key which the node has rotated away from.
+pub struct PreviousNetworkKey {
+    pub keypair: Keypair,
+    /// The time, as a duration since the unix epoch, at which the previous ENR stops being
+    /// announced.
+    pub announce_until: Duration,
+}
+
+/// The network keys loaded by `load_or_rotate_private_key`.
+pub struct NetworkKeys {
+    /// The key the node's peer ID and ENR are derived from.
+    pub local: Keypair,
+    /// The key rotated away from, if the rotation grace period has not yet elapsed.
+    pub previous: Option<PreviousNetworkKey>,
+}
+
+/// Loads the network key to use, rotating to `config.network_key_file` if it is set.
+///
+/// A new key replaces the key in the network directory as soon as it is seen. The replaced key is
+/// kept until `config.network_key_rotation_grace_period` has elapsed so that discovery can keep
+/// announcing the previous ENR alongside the new one, letting peers find the node's new identity
+/// before the previous one disappears.
+pub fn load_or_rotate_private_key(
+    config: &NetworkConfig,
+    log: &slog::Logger,
+) -> Result<NetworkKeys, String> {
+    let now = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_err(|e| format!("System time is before the unix epoch: {:?}", e))?;
+
+    let Some(next_key_path) = &config.network_key_file else {
+        return Ok(NetworkKeys {
+            local: load_private_key(config, log),
+            previous: load_previous_key(config, now),
+        });
+    };
+    let next_keypair = read_network_key(next_key_path)?;
+
+    let _ = fs::create_dir_all(&config.network_dir);
+    let active_key_path = config.network_dir.join(NETWORK_KEY_FILENAME);
+
+    let active_keypair = match read_network_key(&active_key_path) {
+        Ok(keypair) if keypair.public() == next_keypair.public() => {
+            return Ok(NetworkKeys {
+                local: next_keypair,
+                previous: load_previous_key(config, now),
+            });
+        }
+        Ok(keypair) => keypair,
+        Err(_) => {
+            // There is no previous identity to announce.
+            write_network_key(&active_key_path, &next_keypair, true)?;
+            return Ok(NetworkKeys {
+                local: next_keypair,
+                previous: None,
+            });
+        }
+    };
+
+    // Retain the replaced key before the active key is overwritten, so that it is never lost.
+    let previous_key_path = config.network_dir.join(NETWORK_KEY_PREVIOUS_FILENAME);
+    let rotation_path = config.network_dir.join(NETWORK_KEY_ROTATION_FILENAME);
+    write_network_key(&previous_key_path, &active_keypair, true)?;
+    fs::write(&rotation_path, now.as_secs().to_string())
+        .map_err(|e| format!("Unable to write {:?}: {}", rotation_path, e))?;
+    write_network_key(&active_key_path, &next_keypair, true)?;
+
+    let announce_until = now.saturating_add(config.network_key_rotation_grace_period);
+    info!(
+        log,
+        "Network identity rotated";
+        "previous_peer_id" => %active_keypair.public().to_peer_id(),
+        "peer_id" => %next_keypair.public().to_peer_id(),
+        "previous_enr_announced_for_secs" => config.network_key_rotation_grace_period.as_secs(),
+    );
+
+    let previous = if now < announce_until {
+        Some(PreviousNetworkKey {
+            keypair: active_keypair,
+            announce_until,
+        })
+    } else {
+        remove_previous_key(config);
+        None
+    };
+    Ok(NetworkKeys {
+        local: next_keypair,
+        previous,
+    })
+}
+
+/// Loads the key of an earlier rotation if its grace period has not yet elapsed, otherwise
+/// removes it from the network directory.
+fn load_previous_key(config: &NetworkConfig, now: Duration) -> Option<PreviousNetworkKey> {
+    let rotation_path = config.network_dir.join(NETWORK_KEY_ROTATION_FILENAME);
+    let rotated_at = fs::read_to_string(rotation_path)
+        .ok()?
+        .trim()
+        .parse()
+        .ok()
+        .map(Duration::from_secs);
+    let announce_until = rotated_at
+        .map(|rotated_at| rotated_at.saturating_add(config.network_key_rotation_grace_period));
+
+    match announce_until {
+        Some(announce_until) if now < announce_until => {
+            read_network_key(&config.network_dir.join(NETWORK_KEY_PREVIOUS_FILENAME))
+                .ok()
+                .map(|keypair| PreviousNetworkKey {
+                    keypair,
+                    announce_until,
+                })
+        }
+        _ => {
+            remove_previous_key(config);
+            None
+        }
+    }
+}
+
+/// Removes the key of an earlier rotation from the network directory.
+fn remove_previous_key(config: &NetworkConfig) {
+    let _ = fs::remove_file(config.network_dir.join(NETWORK_KEY_PREVIOUS_FILENAME));
+    let _ = fs::remove_file(config.network_dir.join(NETWORK_KEY_ROTATION_FILENAME));
+}
+
 /// Generate authenticated XX Noise config from identity keys
 fn generate_noise_config(identity_keypair: &Keypair) -> noise::Config {
     noise::Config::new(identity_keypair).expect("signing can fail only once during starting a node")
@@ -301,3 +514,73 @@ pub(crate) fn save_metadata_to_disk<E: EthSpec>(
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn test_config(network_dir: &Path, next_key: &Path, grace_period: Duration) -> NetworkConfig {
+        NetworkConfig {
+            network_dir: network_dir.to_path_buf(),
+            network_key_file: Some(next_key.to_path_buf()),
+            network_key_rotation_grace_period: grace_period,
+            ..NetworkConfig::default()
+        }
+    }
+
+    #[test]
+    fn generate_network_key_does_not_overwrite() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("key");
+        let keypair = generate_network_key(&path).unwrap();
+        assert_eq!(read_network_key(&path).unwrap().public(), keypair.public());
+        assert!(generate_network_key(&path).is_err());
+    }
+
+    #[test]
+    fn network_key_rotation() {
+        let log = logging::test_logger();
+        let network_dir = tempfile::tempdir().unwrap();
+        let keys_dir = tempfile::tempdir().unwrap();
+        let old_key_path = keys_dir.path().join("old");
+        let old_key = generate_network_key(&old_key_path).unwrap();
+        let new_key_path = keys_dir.path().join("new");
+        let new_key = generate_network_key(&new_key_path).unwrap();
+        let previous_key_path = network_dir.path().join(NETWORK_KEY_PREVIOUS_FILENAME);
+
+        // The first key used becomes the active key with no previous key to announce.
+        let config = test_config(network_dir.path(), &old_key_path, Duration::from_secs(3600));
+        let keys = load_or_rotate_private_key(&config, &log).unwrap();
+        assert_eq!(keys.local.public(), old_key.public());
+        assert!(keys.previous.is_none());
+
+        // The new key is used immediately, and the previous key is kept to be announced during
+        // the grace period.
+        let config = test_config(network_dir.path(), &new_key_path, Duration::from_secs(3600));
+        let keys = load_or_rotate_private_key(&config, &log).unwrap();
+        assert_eq!(keys.local.public(), new_key.public());
+        assert_eq!(keys.previous.unwrap().keypair.public(), old_key.public());
+        assert_eq!(
+            read_network_key(&network_dir.path().join(NETWORK_KEY_FILENAME))
+                .unwrap()
+                .public(),
+            new_key.public()
+        );
+
+        // A restart during the grace period keeps announcing the previous key.
+        let keys = load_or_rotate_private_key(&config, &log).unwrap();
+        assert_eq!(keys.local.public(), new_key.public());
+        assert_eq!(keys.previous.unwrap().keypair.public(), old_key.public());
+
+        // Once the grace period has elapsed the previous key is removed.
+        let config = test_config(network_dir.path(), &new_key_path, Duration::ZERO);
+        let keys = load_or_rotate_private_key(&config, &log).unwrap();
+        assert_eq!(keys.local.public(), new_key.public());
+        assert!(keys.previous.is_none());
+        assert!(!previous_key_path.exists());
+        assert!(!network_dir
+            .path()
+            .join(NETWORK_KEY_ROTATION_FILENAME)
+            .exists());
+    }
+}
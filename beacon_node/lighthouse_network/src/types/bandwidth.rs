@@ -0,0 +1,196 @@
+//! An aggregate bandwidth budget shared between gossip publishing and historical sync.
+//!
+//! The budget is a token bucket which refills at the configured rate. Gossip publishing is
+//! required to stay in consensus, so it is never delayed, but the bytes it sends are drawn from
+//! the same bucket and may leave it in debt. Backfill only starts new downloads whilst the bucket
+//! holds more than a reserve, leaving headroom for the next burst of head gossip.
+use crate::metrics;
+use parking_lot::Mutex;
+use std::num::NonZeroU64;
+use std::time::{Duration, Instant};
+use strum::IntoStaticStr;
+
+/// The period of traffic at the full rate which the bucket can hold.
+const BURST_DURATION: Duration = Duration::from_secs(12);
+
+/// The fraction of the bucket which is reserved for gossip. Backfill requests are only made whilst
+/// the bucket holds more than this fraction of its capacity.
+const GOSSIP_RESERVE_DENOMINATOR: i64 = 4;
+
+/// The kinds of traffic which draw from the bandwidth budget.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, IntoStaticStr)]
+#[strum(serialize_all = "snake_case")]
+pub enum TrafficClass {
+    /// Messages published on gossipsub topics, counted once per mesh peer.
+    GossipPublish,
+    /// Blocks and blobs downloaded by backfill sync.
+    Backfill,
+    /// Data columns downloaded by backfill sync.
+    CustodyBackfill,
+}
+
+impl TrafficClass {
+    /// Whether traffic of this class is delayed until the budget allows it.
+    pub fn is_deferrable(&self) -> bool {
+        match self {
+            TrafficClass::GossipPublish => false,
+            TrafficClass::Backfill | TrafficClass::CustodyBackfill => true,
+        }
+    }
+}
+
+/// Shares an optional aggregate bandwidth cap between the kinds of traffic in `TrafficClass`.
+#[derive(Debug, Default)]
+pub struct BandwidthManager {
+    bucket: Option<Mutex<TokenBucket>>,
+}
+
+#[derive(Debug)]
+struct TokenBucket {
+    bytes_per_second: u64,
+    capacity: i64,
+    /// The bytes currently available. Negative when non-deferrable traffic has exceeded the cap.
+    balance: i64,
+    last_refill: Instant,
+}
+
+impl TokenBucket {
+    fn new(bytes_per_second: u64, now: Instant) -> Self {
+        let capacity = bytes_per_second
+            .saturating_mul(BURST_DURATION.as_secs())
+            .try_into()
+            .unwrap_or(i64::MAX);
+        Self {
+            bytes_per_second,
+            capacity,
+            balance: capacity,
+            last_refill: now,
+        }
+    }
+
+    fn refill(&mut self, now: Instant) {
+        let elapsed = now.saturating_duration_since(self.last_refill);
+        let refilled = (elapsed.as_secs_f64() * self.bytes_per_second as f64) as i64;
+        self.balance = self.balance.saturating_add(refilled).min(self.capacity);
+        self.last_refill = now;
+    }
+
+    fn reserve(&self) -> i64 {
+        self.capacity / GOSSIP_RESERVE_DENOMINATOR
+    }
+}
+
+impl BandwidthManager {
+    /// Creates a manager which caps aggregate traffic at `max_bandwidth_mbps` megabits per
+    /// second, or which never defers traffic if `None`.
+    pub fn new(max_bandwidth_mbps: Option<NonZeroU64>) -> Self {
+        Self::new_at(max_bandwidth_mbps, Instant::now())
+    }
+
+    fn new_at(max_bandwidth_mbps: Option<NonZeroU64>, now: Instant) -> Self {
+        let bucket = max_bandwidth_mbps.map(|mbps| {
+            let bytes_per_second = mbps.get().saturating_mul(1_000_000) / 8;
+            Mutex::new(TokenBucket::new(bytes_per_second, now))
+        });
+        Self { bucket }
+    }
+
+    /// Returns `true` if a bandwidth cap is configured.
+    pub fn is_limited(&self) -> bool {
+        self.bucket.is_some()
+    }
+
+    /// Records `bytes` of traffic of the given class against the budget.
+    ///
+    /// Traffic is always recorded, even if it exceeds the budget.
+    pub fn record(&self, class: TrafficClass, bytes: usize) {
+        self.record_at(class, bytes, Instant::now())
+    }
+
+    fn record_at(&self, class: TrafficClass, bytes: usize, now: Instant) {
+        metrics::inc_counter_vec_by(
+            &metrics::BANDWIDTH_MANAGER_BYTES,
+            &[class.into()],
+            bytes as u64,
+        );
+
+        if let Some(bucket) = &self.bucket {
+            let mut bucket = bucket.lock();
+            bucket.refill(now);
+            bucket.balance = bucket
+                .balance
+                .saturating_sub(bytes.try_into().unwrap_or(i64::MAX));
+            metrics::set_gauge(&metrics::BANDWIDTH_MANAGER_BALANCE, bucket.balance);
+        }
+    }
+
+    /// Returns `true` if new traffic of the given class may be started now.
+    ///
+    /// Non-deferrable traffic is always permitted.
+    pub fn has_capacity(&self, class: TrafficClass) -> bool {
+        self.has_capacity_at(class, Instant::now())
+    }
+
+    fn has_capacity_at(&self, class: TrafficClass, now: Instant) -> bool {
+        if !class.is_deferrable() {
+            return true;
+        }
+        let Some(bucket) = &self.bucket else {
+            return true;
+        };
+        let mut bucket = bucket.lock();
+        bucket.refill(now);
+        let has_capacity = bucket.balance > bucket.reserve();
+        if !has_capacity {
+            metrics::inc_counter_vec(&metrics::BANDWIDTH_MANAGER_DEFERRALS, &[class.into()]);
+        }
+        has_capacity
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn manager(mbps: u64, now: Instant) -> BandwidthManager {
+        BandwidthManager::new_at(NonZeroU64::new(mbps), now)
+    }
+
+    #[test]
+    fn unlimited_never_defers() {
+        let now = Instant::now();
+        let manager = manager(0, now);
+        assert!(!manager.is_limited());
+        manager.record_at(TrafficClass::GossipPublish, usize::MAX, now);
+        assert!(manager.has_capacity_at(TrafficClass::Backfill, now));
+    }
+
+    #[test]
+    fn gossip_defers_backfill_until_refilled() {
+        let now = Instant::now();
+        // 8 Mbps is 1 MB per second, so the bucket holds 12 MB with a 3 MB reserve.
+        let manager = manager(8, now);
+        assert!(manager.has_capacity_at(TrafficClass::Backfill, now));
+
+        // Gossip may exceed the budget, leaving it in debt.
+        manager.record_at(TrafficClass::GossipPublish, 14_000_000, now);
+        assert!(manager.has_capacity_at(TrafficClass::GossipPublish, now));
+        assert!(!manager.has_capacity_at(TrafficClass::Backfill, now));
+        assert!(!manager.has_capacity_at(TrafficClass::CustodyBackfill, now));
+
+        // The debt and the reserve must be refilled before backfill resumes.
+        let later = now + Duration::from_secs(5);
+        assert!(!manager.has_capacity_at(TrafficClass::Backfill, later));
+        let later = now + Duration::from_secs(6);
+        assert!(manager.has_capacity_at(TrafficClass::Backfill, later));
+    }
+
+    #[test]
+    fn balance_does_not_exceed_capacity() {
+        let now = Instant::now();
+        let manager = manager(8, now);
+        let later = now + Duration::from_secs(3600);
+        manager.record_at(TrafficClass::Backfill, 9_000_000, later);
+        assert!(!manager.has_capacity_at(TrafficClass::Backfill, later));
+    }
+}
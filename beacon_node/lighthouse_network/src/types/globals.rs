@@ -1,12 +1,15 @@
 //! A collection of variables that are accessible outside of the network thread itself.
 use crate::peer_manager::peerdb::PeerDB;
 use crate::rpc::{MetaData, MetaDataV3};
-use crate::types::{BackFillState, SyncState};
+use crate::types::{
+    BackFillState, BandwidthManager, Reachability, SyncState, TransportReachability,
+};
 use crate::{Client, Enr, EnrExt, GossipTopic, Multiaddr, NetworkConfig, PeerId};
 use itertools::Itertools;
 use parking_lot::RwLock;
 use std::collections::HashSet;
 use std::sync::Arc;
+use std::time::Instant;
 use types::{ChainSpec, ColumnIndex, DataColumnSubnetId, EthSpec};
 
 pub struct NetworkGlobals<E: EthSpec> {
@@ -29,6 +32,10 @@ pub struct NetworkGlobals<E: EthSpec> {
     /// The computed sampling subnets and columns is stored to avoid re-computing.
     pub sampling_subnets: Vec<DataColumnSubnetId>,
     pub sampling_columns: Vec<ColumnIndex>,
+    /// The aggregate bandwidth budget shared by gossip publishing and backfill sync.
+    pub bandwidth: BandwidthManager,
+    /// Inbound connections received on each transport, as evidence of reachability.
+    pub reachability: Reachability,
     /// Network-related configuration. Immutable after initialization.
     pub config: Arc<NetworkConfig>,
     /// Ethereum chain configuration. Immutable after initialization.
@@ -85,6 +92,8 @@ impl<E: EthSpec> NetworkGlobals<E> {
             backfill_state: RwLock::new(BackFillState::Paused),
             sampling_subnets,
             sampling_columns,
+            bandwidth: BandwidthManager::new(config.max_bandwidth_mbps),
+            reachability: Reachability::new(Instant::now()),
             config,
             spec,
         }
@@ -106,6 +115,11 @@ impl<E: EthSpec> NetworkGlobals<E> {
         self.listen_multiaddrs.read().clone()
     }
 
+    /// Returns the reachability of the local node on each transport.
+    pub fn reachability(&self) -> Vec<TransportReachability> {
+        self.reachability.report(&self.local_enr(), Instant::now())
+    }
+
     /// Returns the number of libp2p connected peers.
     pub fn connected_peers(&self) -> usize {
         self.peers.read().connected_peer_ids().count()
@@ -167,6 +181,29 @@ impl<E: EthSpec> NetworkGlobals<E> {
             .collect::<Vec<_>>()
     }
 
+    /// Returns the columns in `column_indices` which `peer_id` is assigned to custody based on the
+    /// `custody_subnet_count` from its ENR or metadata.
+    pub fn advertised_custody_columns(
+        &self,
+        peer_id: &PeerId,
+        column_indices: &[ColumnIndex],
+    ) -> Vec<ColumnIndex> {
+        let peers = self.peers.read();
+        let Some(info) = peers.peer_info(peer_id) else {
+            return vec![];
+        };
+        column_indices
+            .iter()
+            .copied()
+            .filter(|column_index| {
+                info.is_assigned_to_custody_subnet(&DataColumnSubnetId::from_column_index::<E>(
+                    *column_index as usize,
+                    &self.spec,
+                ))
+            })
+            .collect()
+    }
+
     /// TESTING ONLY. Build a dummy NetworkGlobals instance.
     pub fn new_test_globals(
         trusted_peers: Vec<PeerId>,
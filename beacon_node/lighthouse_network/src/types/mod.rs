@@ -1,5 +1,7 @@
+mod bandwidth;
 mod globals;
 mod pubsub;
+mod reachability;
 mod subnet;
 mod sync_state;
 mod topics;
@@ -11,9 +13,14 @@ pub type EnrSyncCommitteeBitfield<E> = BitVector<<E as EthSpec>::SyncCommitteeSu
 
 pub type Enr = discv5::enr::Enr<discv5::enr::CombinedKey>;
 
+pub use bandwidth::{BandwidthManager, TrafficClass};
 pub use globals::NetworkGlobals;
 pub use pubsub::{PubsubMessage, SnappyTransform};
-pub use subnet::{Subnet, SubnetDiscovery};
+pub use reachability::{
+    Reachability, ReachabilityStatus, Transport, TransportReachability, CONFIRMATION_GRACE_PERIOD,
+    REACHABLE_TTL,
+};
+pub use subnet::{Subnet, SubnetDiscovery, SubnetDuty};
 pub use sync_state::{BackFillState, SyncState};
 pub use topics::{
     attestation_sync_committee_topics, core_topics_to_subscribe, fork_core_topics,
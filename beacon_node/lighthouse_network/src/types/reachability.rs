@@ -0,0 +1,287 @@
+//! Tracks whether the local node is reachable on each transport it advertises in its ENR.
+//!
+//! An inbound connection from a globally routable address shows that the advertised address for
+//! its transport can be dialled from outside, regardless of whether the connection is subsequently
+//! accepted. Connections from private, loopback or link-local addresses (e.g. other nodes on the
+//! same LAN) say nothing about NAT or firewall configuration and are ignored. The consensus p2p
+//! stack has no protocol for asking a peer to dial us back, so a transport which is advertised but
+//! has not recently received an inbound connection is reported as unconfirmed rather than
+//! unreachable.
+use crate::config::{is_global_ipv4, is_global_ipv6};
+use crate::{Enr, EnrExt, Multiaddr};
+use libp2p::multiaddr::Protocol;
+use parking_lot::RwLock;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+use strum::{EnumIter, IntoEnumIterator, IntoStaticStr};
+
+/// How long after startup an advertised transport may go without inbound connections before it
+/// is considered unconfirmed.
+pub const CONFIRMATION_GRACE_PERIOD: Duration = Duration::from_secs(600);
+
+/// How long a transport remains reachable after its most recent inbound connection.
+pub const REACHABLE_TTL: Duration = Duration::from_secs(3600);
+
+/// The transports on which the local node may accept connections.
+#[derive(
+    Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize, IntoStaticStr, EnumIter,
+)]
+#[serde(rename_all = "snake_case")]
+#[strum(serialize_all = "snake_case")]
+pub enum Transport {
+    Tcp4,
+    Tcp6,
+    Quic4,
+    Quic6,
+}
+
+impl Transport {
+    /// Determines the transport of a connection from its address, e.g. `/ip4/1.2.3.4/tcp/9000`.
+    pub fn from_multiaddr(addr: &Multiaddr) -> Option<Self> {
+        let mut components = addr.iter();
+        let ipv4 = match components.next()? {
+            Protocol::Ip4(_) => true,
+            Protocol::Ip6(_) => false,
+            _ => return None,
+        };
+        match (components.next()?, ipv4) {
+            (Protocol::Tcp(_), true) => Some(Transport::Tcp4),
+            (Protocol::Tcp(_), false) => Some(Transport::Tcp6),
+            (Protocol::Udp(_), ipv4) => match components.next()? {
+                Protocol::QuicV1 if ipv4 => Some(Transport::Quic4),
+                Protocol::QuicV1 => Some(Transport::Quic6),
+                _ => None,
+            },
+            _ => None,
+        }
+    }
+
+    /// Determines the transport of an inbound connection from its remote address, if that
+    /// connection is evidence of external reachability.
+    ///
+    /// Returns `None` for connections from non-global addresses.
+    pub fn from_remote_addr(addr: &Multiaddr) -> Option<Self> {
+        let is_global = match addr.iter().next()? {
+            Protocol::Ip4(ip) => is_global_ipv4(&ip),
+            Protocol::Ip6(ip) => is_global_ipv6(&ip),
+            _ => false,
+        };
+        if is_global {
+            Self::from_multiaddr(addr)
+        } else {
+            None
+        }
+    }
+
+    /// The port advertised for this transport in `enr`, if any.
+    pub fn advertised_port(&self, enr: &Enr) -> Option<u16> {
+        match self {
+            Transport::Tcp4 => enr.tcp4(),
+            Transport::Tcp6 => enr.tcp6(),
+            Transport::Quic4 => enr.quic4(),
+            Transport::Quic6 => enr.quic6(),
+        }
+    }
+}
+
+/// Whether the local node is known to be reachable on a transport.
+#[derive(
+    Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize, IntoStaticStr, EnumIter,
+)]
+#[serde(rename_all = "snake_case")]
+#[strum(serialize_all = "snake_case")]
+pub enum ReachabilityStatus {
+    /// An inbound connection was received on this transport within the `REACHABLE_TTL`.
+    Reachable,
+    /// The transport is advertised but has not recently received an inbound connection.
+    Unconfirmed,
+    /// The transport is not advertised in the local ENR.
+    NotAdvertised,
+}
+
+/// The reachability of the local node on a single transport.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TransportReachability {
+    pub transport: Transport,
+    pub status: ReachabilityStatus,
+    /// The port advertised for this transport in the local ENR.
+    pub advertised_port: Option<u16>,
+    /// The number of inbound connections received on this transport since startup.
+    pub inbound_connections: u64,
+    /// The number of seconds since the most recent inbound connection on this transport.
+    pub seconds_since_last_inbound: Option<u64>,
+}
+
+#[derive(Debug, Clone, Copy)]
+struct InboundConnections {
+    count: u64,
+    last_seen: Instant,
+}
+
+/// Records inbound connections per transport.
+#[derive(Debug)]
+pub struct Reachability {
+    started: Instant,
+    inbound: RwLock<HashMap<Transport, InboundConnections>>,
+}
+
+impl Reachability {
+    pub fn new(started: Instant) -> Self {
+        Self {
+            started,
+            inbound: RwLock::new(HashMap::new()),
+        }
+    }
+
+    /// Records an inbound connection on `transport`.
+    ///
+    /// Returns `true` if this is the first inbound connection received on the transport.
+    pub fn record_inbound(&self, transport: Transport, now: Instant) -> bool {
+        let mut inbound = self.inbound.write();
+        let connections = inbound.entry(transport).or_insert(InboundConnections {
+            count: 0,
+            last_seen: now,
+        });
+        connections.count += 1;
+        connections.last_seen = now;
+        connections.count == 1
+    }
+
+    /// Returns `true` if the node started too recently for the absence of inbound connections to
+    /// be meaningful.
+    pub fn in_grace_period(&self, now: Instant) -> bool {
+        now.saturating_duration_since(self.started) < CONFIRMATION_GRACE_PERIOD
+    }
+
+    /// Reports the reachability of every transport, given the local `enr`.
+    pub fn report(&self, enr: &Enr, now: Instant) -> Vec<TransportReachability> {
+        let inbound = self.inbound.read();
+        Transport::iter()
+            .map(|transport| {
+                let advertised_port = transport.advertised_port(enr);
+                let connections = inbound.get(&transport);
+                let since_last_inbound =
+                    connections.map(|c| now.saturating_duration_since(c.last_seen));
+                let status = match (advertised_port, since_last_inbound) {
+                    (None, _) => ReachabilityStatus::NotAdvertised,
+                    (Some(_), Some(since)) if since < REACHABLE_TTL => {
+                        ReachabilityStatus::Reachable
+                    }
+                    (Some(_), _) => ReachabilityStatus::Unconfirmed,
+                };
+                TransportReachability {
+                    transport,
+                    status,
+                    advertised_port,
+                    inbound_connections: connections.map_or(0, |c| c.count),
+                    seconds_since_last_inbound: since_last_inbound.map(|since| since.as_secs()),
+                }
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::discovery::enr_ext::QUIC_ENR_KEY;
+    use discv5::enr::CombinedKey;
+    use std::net::Ipv4Addr;
+
+    fn enr() -> Enr {
+        discv5::enr::Enr::builder()
+            .ip4(Ipv4Addr::new(1, 2, 3, 4))
+            .tcp4(9000)
+            .add_value(QUIC_ENR_KEY, &9001u16)
+            .build(&CombinedKey::generate_secp256k1())
+            .unwrap()
+    }
+
+    fn status(report: &[TransportReachability], transport: Transport) -> ReachabilityStatus {
+        report
+            .iter()
+            .find(|r| r.transport == transport)
+            .unwrap()
+            .status
+    }
+
+    #[test]
+    fn transport_from_multiaddr() {
+        let parse = |addr: &str| Transport::from_multiaddr(&addr.parse().unwrap());
+        assert_eq!(parse("/ip4/1.2.3.4/tcp/9000"), Some(Transport::Tcp4));
+        assert_eq!(parse("/ip6/::1/tcp/9000"), Some(Transport::Tcp6));
+        assert_eq!(
+            parse("/ip4/1.2.3.4/udp/9001/quic-v1"),
+            Some(Transport::Quic4)
+        );
+        assert_eq!(parse("/ip6/::1/udp/9001/quic-v1"), Some(Transport::Quic6));
+        assert_eq!(parse("/ip4/1.2.3.4/udp/9000"), None);
+        assert_eq!(parse("/dns4/example.com/tcp/9000"), None);
+    }
+
+    #[test]
+    fn only_global_remote_addrs_are_counted() {
+        let parse = |addr: &str| Transport::from_remote_addr(&addr.parse().unwrap());
+        assert_eq!(parse("/ip4/1.2.3.4/tcp/9000"), Some(Transport::Tcp4));
+        assert_eq!(
+            parse("/ip6/2001:4860::8888/udp/9001/quic-v1"),
+            Some(Transport::Quic6)
+        );
+        // LAN, loopback, CGNAT, link-local and unique local addresses.
+        assert_eq!(parse("/ip4/192.168.1.10/tcp/9000"), None);
+        assert_eq!(parse("/ip4/10.0.0.5/udp/9001/quic-v1"), None);
+        assert_eq!(parse("/ip4/127.0.0.1/tcp/9000"), None);
+        assert_eq!(parse("/ip4/100.64.0.1/tcp/9000"), None);
+        assert_eq!(parse("/ip6/::1/tcp/9000"), None);
+        assert_eq!(parse("/ip6/fe80::1/tcp/9000"), None);
+        assert_eq!(parse("/ip6/fd00::1/udp/9001/quic-v1"), None);
+    }
+
+    #[test]
+    fn inbound_connections_confirm_advertised_transports() {
+        let enr = enr();
+        let now = Instant::now();
+        let reachability = Reachability::new(now);
+
+        let report = reachability.report(&enr, now);
+        assert_eq!(
+            status(&report, Transport::Tcp4),
+            ReachabilityStatus::Unconfirmed
+        );
+        assert_eq!(
+            status(&report, Transport::Quic4),
+            ReachabilityStatus::Unconfirmed
+        );
+        assert_eq!(
+            status(&report, Transport::Tcp6),
+            ReachabilityStatus::NotAdvertised
+        );
+
+        assert!(reachability.record_inbound(Transport::Tcp4, now));
+        assert!(!reachability.record_inbound(Transport::Tcp4, now));
+        let report = reachability.report(&enr, now);
+        assert_eq!(
+            status(&report, Transport::Tcp4),
+            ReachabilityStatus::Reachable
+        );
+        assert_eq!(
+            status(&report, Transport::Quic4),
+            ReachabilityStatus::Unconfirmed
+        );
+        assert_eq!(report[0].inbound_connections, 2);
+
+        // Reachability lapses without further inbound connections.
+        let later = now + REACHABLE_TTL;
+        assert!(!reachability.in_grace_period(later));
+        let report = reachability.report(&enr, later);
+        assert_eq!(
+            status(&report, Transport::Tcp4),
+            ReachabilityStatus::Unconfirmed
+        );
+        assert_eq!(
+            report[0].seconds_since_last_inbound,
+            Some(REACHABLE_TTL.as_secs())
+        );
+    }
+}
@@ -1,4 +1,4 @@
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
 use std::time::Instant;
 use types::{DataColumnSubnetId, SubnetId, SyncSubnetId};
 
@@ -6,7 +6,7 @@ use types::{DataColumnSubnetId, SubnetId, SyncSubnetId};
 ///
 /// Used for subscribing to the appropriate gossipsub subnets and mark
 /// appropriate metadata bitfields.
-#[derive(Debug, Clone, Copy, Serialize, PartialEq, Eq, Hash)]
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Hash)]
 pub enum Subnet {
     /// Represents a gossipsub attestation subnet and the metadata `attnets` field.
     Attestation(SubnetId),
@@ -23,6 +23,14 @@ pub struct SubnetDiscovery {
     pub min_ttl: Option<Instant>,
 }
 
+/// A subnet on which a local validator must aggregate or serve on a sync committee, along with
+/// the instant at which the duty is complete.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SubnetDuty {
+    pub subnet: Subnet,
+    pub until: Instant,
+}
+
 impl PartialEq for SubnetDiscovery {
     fn eq(&self, other: &SubnetDiscovery) -> bool {
         self.subnet.eq(&other.subnet)
@@ -64,18 +64,20 @@ pub static SYNC_COMMITTEE_SUBSCRIPTION_REQUESTS: LazyLock<Result<IntCounter>> =
  */
 
 // Gossip blocks.
-pub static BEACON_PROCESSOR_GOSSIP_BLOCK_VERIFIED_TOTAL: LazyLock<Result<IntCounter>> =
+pub static BEACON_PROCESSOR_GOSSIP_BLOCK_VERIFIED_TOTAL: LazyLock<Result<IntCounterVec>> =
     LazyLock::new(|| {
-        try_create_int_counter(
+        try_create_int_counter_vec(
             "beacon_processor_gossip_block_verified_total",
             "Total number of gossip blocks verified for propagation.",
+            &["fork"],
         )
     });
-pub static BEACON_PROCESSOR_GOSSIP_BLOCK_IMPORTED_TOTAL: LazyLock<Result<IntCounter>> =
+pub static BEACON_PROCESSOR_GOSSIP_BLOCK_IMPORTED_TOTAL: LazyLock<Result<IntCounterVec>> =
     LazyLock::new(|| {
-        try_create_int_counter(
+        try_create_int_counter_vec(
             "beacon_processor_gossip_block_imported_total",
             "Total number of gossip blocks imported to fork choice, etc.",
+            &["fork"],
         )
     });
 pub static BEACON_PROCESSOR_GOSSIP_BLOCK_REQUEUED_TOTAL: LazyLock<Result<IntCounter>> =
@@ -206,20 +208,24 @@ pub static BEACON_PROCESSOR_BACKFILL_CHAIN_SEGMENT_FAILED_TOTAL: LazyLock<Result
         )
     });
 // Unaggregated attestations.
-pub static BEACON_PROCESSOR_UNAGGREGATED_ATTESTATION_VERIFIED_TOTAL: LazyLock<Result<IntCounter>> =
-    LazyLock::new(|| {
-        try_create_int_counter(
-            "beacon_processor_unaggregated_attestation_verified_total",
-            "Total number of unaggregated attestations verified for gossip.",
-        )
-    });
-pub static BEACON_PROCESSOR_UNAGGREGATED_ATTESTATION_IMPORTED_TOTAL: LazyLock<Result<IntCounter>> =
-    LazyLock::new(|| {
-        try_create_int_counter(
-            "beacon_processor_unaggregated_attestation_imported_total",
-            "Total number of unaggregated attestations imported to fork choice, etc.",
-        )
-    });
+pub static BEACON_PROCESSOR_UNAGGREGATED_ATTESTATION_VERIFIED_TOTAL: LazyLock<
+    Result<IntCounterVec>,
+> = LazyLock::new(|| {
+    try_create_int_counter_vec(
+        "beacon_processor_unaggregated_attestation_verified_total",
+        "Total number of unaggregated attestations verified for gossip.",
+        &["fork"],
+    )
+});
+pub static BEACON_PROCESSOR_UNAGGREGATED_ATTESTATION_IMPORTED_TOTAL: LazyLock<
+    Result<IntCounterVec>,
+> = LazyLock::new(|| {
+    try_create_int_counter_vec(
+        "beacon_processor_unaggregated_attestation_imported_total",
+        "Total number of unaggregated attestations imported to fork choice, etc.",
+        &["fork"],
+    )
+});
 pub static BEACON_PROCESSOR_UNAGGREGATED_ATTESTATION_REQUEUED_TOTAL: LazyLock<Result<IntCounter>> =
     LazyLock::new(|| {
         try_create_int_counter(
@@ -228,18 +234,20 @@ pub static BEACON_PROCESSOR_UNAGGREGATED_ATTESTATION_REQUEUED_TOTAL: LazyLock<Re
     )
     });
 // Aggregated attestations.
-pub static BEACON_PROCESSOR_AGGREGATED_ATTESTATION_VERIFIED_TOTAL: LazyLock<Result<IntCounter>> =
+pub static BEACON_PROCESSOR_AGGREGATED_ATTESTATION_VERIFIED_TOTAL: LazyLock<Result<IntCounterVec>> =
     LazyLock::new(|| {
-        try_create_int_counter(
+        try_create_int_counter_vec(
             "beacon_processor_aggregated_attestation_verified_total",
             "Total number of aggregated attestations verified for gossip.",
+            &["fork"],
         )
     });
-pub static BEACON_PROCESSOR_AGGREGATED_ATTESTATION_IMPORTED_TOTAL: LazyLock<Result<IntCounter>> =
+pub static BEACON_PROCESSOR_AGGREGATED_ATTESTATION_IMPORTED_TOTAL: LazyLock<Result<IntCounterVec>> =
     LazyLock::new(|| {
-        try_create_int_counter(
+        try_create_int_counter_vec(
             "beacon_processor_aggregated_attestation_imported_total",
             "Total number of aggregated attestations imported to fork choice, etc.",
+            &["fork"],
         )
     });
 pub static BEACON_PROCESSOR_AGGREGATED_ATTESTATION_REQUEUED_TOTAL: LazyLock<Result<IntCounter>> =
@@ -582,6 +590,13 @@ pub static SAMPLE_VERIFY_RESULT: LazyLock<Result<IntCounterVec>> = LazyLock::new
         &["result"],
     )
 });
+pub static SYNC_CUSTODY_MISMATCHES: LazyLock<Result<IntCounterVec>> = LazyLock::new(|| {
+    try_create_int_counter_vec(
+        "sync_custody_mismatches_total",
+        "Total count of responses missing columns the peer advertises custody of",
+        &["request_type"],
+    )
+});
 pub static SAMPLING_REQUEST_RESULT: LazyLock<Result<IntCounterVec>> = LazyLock::new(|| {
     try_create_int_counter_vec(
         "beacon_sampling_request_result_total",
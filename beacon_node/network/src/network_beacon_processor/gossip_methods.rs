@@ -10,6 +10,7 @@ use beacon_chain::data_column_verification::{GossipDataColumnError, GossipVerifi
 use beacon_chain::store::Error;
 use beacon_chain::{
     attestation_verification::{self, Error as AttnError, VerifiedAttestation},
+    chain_config::BlobFetchStrategy,
     data_availability_checker::AvailabilityCheckErrorCategory,
     light_client_finality_update_verification::Error as LightClientFinalityUpdateError,
     light_client_optimistic_update_verification::Error as LightClientOptimisticUpdateError,
@@ -43,7 +44,7 @@ use types::{
 use beacon_processor::{
     work_reprocessing_queue::{
         QueuedAggregate, QueuedGossipBlock, QueuedLightClientUpdate, QueuedUnaggregate,
-        ReprocessQueueMessage,
+        QueuedUnknownParentBlock, ReprocessQueueMessage,
     },
     DuplicateCache, GossipAggregatePackage, GossipAttestationPackage,
 };
@@ -312,6 +313,9 @@ impl<T: BeaconChainTypes> NetworkBeaconProcessor<T> {
             Ok(verified_attestation) => {
                 let indexed_attestation = &verified_attestation.indexed_attestation;
                 let beacon_block_root = indexed_attestation.data().beacon_block_root;
+                let fork_label = self
+                    .chain
+                    .fork_label_at_slot(indexed_attestation.data().slot);
 
                 // Register the attestation with any monitored validators.
                 self.chain
@@ -334,10 +338,16 @@ impl<T: BeaconChainTypes> NetworkBeaconProcessor<T> {
                     return;
                 }
 
-                metrics::inc_counter(
+                metrics::inc_counter_vec(
                     &metrics::BEACON_PROCESSOR_UNAGGREGATED_ATTESTATION_VERIFIED_TOTAL,
+                    &[fork_label],
                 );
 
+                self.chain
+                    .observe_attestation_source(&verified_attestation, peer_id);
+                self.chain
+                    .observe_attestation_for_publish_safety(&verified_attestation);
+
                 if let Err(e) = self
                     .chain
                     .apply_attestation_to_fork_choice(&verified_attestation)
@@ -377,8 +387,9 @@ impl<T: BeaconChainTypes> NetworkBeaconProcessor<T> {
                     )
                 }
 
-                metrics::inc_counter(
+                metrics::inc_counter_vec(
                     &metrics::BEACON_PROCESSOR_UNAGGREGATED_ATTESTATION_IMPORTED_TOTAL,
+                    &[fork_label],
                 );
             }
             Err(RejectedUnaggregate { attestation, error }) => {
@@ -518,6 +529,9 @@ impl<T: BeaconChainTypes> NetworkBeaconProcessor<T> {
             Ok(verified_aggregate) => {
                 let aggregate = &verified_aggregate.signed_aggregate;
                 let indexed_attestation = &verified_aggregate.indexed_attestation;
+                let fork_label = self
+                    .chain
+                    .fork_label_at_slot(indexed_attestation.data().slot);
 
                 // If the attestation is still timely, propagate it.
                 self.propagate_attestation_if_timely(
@@ -537,10 +551,16 @@ impl<T: BeaconChainTypes> NetworkBeaconProcessor<T> {
                         &self.chain.slot_clock,
                     );
 
-                metrics::inc_counter(
+                metrics::inc_counter_vec(
                     &metrics::BEACON_PROCESSOR_AGGREGATED_ATTESTATION_VERIFIED_TOTAL,
+                    &[fork_label],
                 );
 
+                self.chain
+                    .observe_attestation_source(&verified_aggregate, peer_id);
+                self.chain
+                    .observe_attestation_for_publish_safety(&verified_aggregate);
+
                 if let Err(e) = self
                     .chain
                     .apply_attestation_to_fork_choice(&verified_aggregate)
@@ -577,8 +597,9 @@ impl<T: BeaconChainTypes> NetworkBeaconProcessor<T> {
                     )
                 }
 
-                metrics::inc_counter(
+                metrics::inc_counter_vec(
                     &metrics::BEACON_PROCESSOR_AGGREGATED_ATTESTATION_IMPORTED_TOTAL,
+                    &[fork_label],
                 );
             }
             Err(RejectedAggregate {
@@ -903,6 +924,26 @@ impl<T: BeaconChainTypes> NetworkBeaconProcessor<T> {
         }
     }
 
+    /// Inform the reprocess queue that a block has been imported after receiving its final
+    /// component, so that any work waiting on the block can be processed.
+    fn send_block_imported(&self, block_root: Hash256, parent_root: Hash256, source: &str) {
+        if self
+            .reprocess_tx
+            .try_send(ReprocessQueueMessage::BlockImported {
+                block_root,
+                parent_root,
+            })
+            .is_err()
+        {
+            error!(
+                self.log,
+                "Failed to inform block import";
+                "source" => source,
+                "block_root" => ?block_root,
+            )
+        };
+    }
+
     pub async fn process_gossip_verified_blob(
         self: &Arc<Self>,
         peer_id: PeerId,
@@ -913,18 +954,23 @@ impl<T: BeaconChainTypes> NetworkBeaconProcessor<T> {
         let block_root = verified_blob.block_root();
         let blob_slot = verified_blob.slot();
         let blob_index = verified_blob.id().index;
+        let parent_root = verified_blob.signed_block_header().message.parent_root;
 
         let result = self.chain.process_gossip_blob(verified_blob).await;
 
         match &result {
             Ok(AvailabilityProcessingStatus::Imported(block_root)) => {
                 // Note: Reusing block imported metric here
-                metrics::inc_counter(&metrics::BEACON_PROCESSOR_GOSSIP_BLOCK_IMPORTED_TOTAL);
+                metrics::inc_counter_vec(
+                    &metrics::BEACON_PROCESSOR_GOSSIP_BLOCK_IMPORTED_TOTAL,
+                    &[self.chain.fork_label_at_slot(blob_slot)],
+                );
                 debug!(
                     self.log,
                     "Gossipsub blob processed - imported fully available block";
                     "block_root" => %block_root
                 );
+                self.send_block_imported(*block_root, parent_root, "gossip_blob");
                 self.chain.recompute_head_at_current_slot().await;
 
                 metrics::set_gauge(
@@ -988,6 +1034,10 @@ impl<T: BeaconChainTypes> NetworkBeaconProcessor<T> {
         let block_root = verified_data_column.block_root();
         let data_column_slot = verified_data_column.slot();
         let data_column_index = verified_data_column.id().index;
+        let parent_root = verified_data_column
+            .signed_block_header()
+            .message
+            .parent_root;
 
         match self
             .chain
@@ -998,14 +1048,16 @@ impl<T: BeaconChainTypes> NetworkBeaconProcessor<T> {
                 match availability {
                     AvailabilityProcessingStatus::Imported(block_root) => {
                         // Note: Reusing block imported metric here
-                        metrics::inc_counter(
+                        metrics::inc_counter_vec(
                             &metrics::BEACON_PROCESSOR_GOSSIP_BLOCK_IMPORTED_TOTAL,
+                            &[self.chain.fork_label_at_slot(data_column_slot)],
                         );
                         info!(
                             self.log,
                             "Gossipsub data column processed, imported fully available block";
                             "block_root" => %block_root
                         );
+                        self.send_block_imported(block_root, parent_root, "gossip_data_column");
                         self.chain.recompute_head_at_current_slot().await;
 
                         metrics::set_gauge(
@@ -1142,13 +1194,18 @@ impl<T: BeaconChainTypes> NetworkBeaconProcessor<T> {
         };
 
         // Write the time the block was observed into delay cache.
-        self.chain.block_times_cache.write().set_time_observed(
-            block_root,
-            block.slot(),
-            seen_duration,
-            Some(peer_id.to_string()),
-            Some(peer_client.to_string()),
-        );
+        {
+            let mut block_times_cache = self.chain.block_times_cache.write();
+            block_times_cache.set_time_observed(
+                block_root,
+                block.slot(),
+                seen_duration,
+                BlockImportSource::Gossip,
+                Some(peer_id.to_string()),
+                Some(peer_client.to_string()),
+            );
+            block_times_cache.set_time_gossip_observed(block_root, block.slot(), seen_duration);
+        }
 
         let verified_block = match verification_result {
             Ok(verified_block) => {
@@ -1206,6 +1263,33 @@ impl<T: BeaconChainTypes> NetworkBeaconProcessor<T> {
                     "Unknown parent for gossip block";
                     "root" => ?block_root
                 );
+                // Hold the block until its parent is imported so that it can be processed as soon
+                // as possible, whilst also looking up the parent.
+                let parent_root = block.parent_root();
+                let process_fn = self.gossip_beacon_block_process_fn(
+                    message_id,
+                    peer_id,
+                    peer_client,
+                    block.clone(),
+                    seen_duration,
+                );
+                if reprocess_tx
+                    .try_send(ReprocessQueueMessage::UnknownParentBlock(
+                        QueuedUnknownParentBlock {
+                            parent_root,
+                            beacon_block_root: block_root,
+                            process_fn,
+                        },
+                    ))
+                    .is_err()
+                {
+                    error!(
+                        self.log,
+                        "Failed to queue block with unknown parent";
+                        "block_root" => ?block_root,
+                        "parent_root" => ?parent_root,
+                    );
+                }
                 self.send_sync_message(SyncMessage::UnknownParentBlock(peer_id, block, block_root));
                 return None;
             }
@@ -1322,7 +1406,10 @@ impl<T: BeaconChainTypes> NetworkBeaconProcessor<T> {
             }
         };
 
-        metrics::inc_counter(&metrics::BEACON_PROCESSOR_GOSSIP_BLOCK_VERIFIED_TOTAL);
+        metrics::inc_counter_vec(
+            &metrics::BEACON_PROCESSOR_GOSSIP_BLOCK_VERIFIED_TOTAL,
+            &[self.chain.fork_label_at_slot(verified_block.block.slot())],
+        );
 
         // Register the block with any monitored validators.
         //
@@ -1446,17 +1533,36 @@ impl<T: BeaconChainTypes> NetworkBeaconProcessor<T> {
 
         // Block is gossip valid. Attempt to fetch blobs from the EL using versioned hashes derived
         // from kzg commitments, without having to wait for all blobs to be sent from the peers.
-        let publish_blobs = true;
-        let self_clone = self.clone();
-        let block_clone = block.clone();
-        self.executor.spawn(
-            async move {
-                self_clone
-                    .fetch_engine_blobs_and_publish(block_clone, block_root, publish_blobs)
-                    .await
-            },
-            "fetch_blobs_gossip",
-        );
+        //
+        // If gossip is preferred over the EL, give the blobs some time to arrive on gossip first
+        // and only fetch from the EL if the block is still not imported.
+        if self.chain.config.engine_blobs_enabled() {
+            let gossip_wait = self
+                .chain
+                .config
+                .prefers_blob_fetch_strategy(
+                    BlobFetchStrategy::Gossip,
+                    BlobFetchStrategy::EngineGetBlobs,
+                )
+                .then_some(self.chain.config.blob_gossip_wait);
+            let publish_blobs = true;
+            let self_clone = self.clone();
+            let block_clone = block.clone();
+            self.executor.spawn(
+                async move {
+                    if let Some(gossip_wait) = gossip_wait {
+                        tokio::time::sleep(gossip_wait).await;
+                        if self_clone.chain.block_is_known_to_fork_choice(&block_root) {
+                            return;
+                        }
+                    }
+                    self_clone
+                        .fetch_engine_blobs_and_publish(block_clone, block_root, publish_blobs)
+                        .await
+                },
+                "fetch_blobs_gossip",
+            );
+        }
 
         let result = self
             .chain
@@ -1470,7 +1576,10 @@ impl<T: BeaconChainTypes> NetworkBeaconProcessor<T> {
 
         match &result {
             Ok(AvailabilityProcessingStatus::Imported(block_root)) => {
-                metrics::inc_counter(&metrics::BEACON_PROCESSOR_GOSSIP_BLOCK_IMPORTED_TOTAL);
+                metrics::inc_counter_vec(
+                    &metrics::BEACON_PROCESSOR_GOSSIP_BLOCK_IMPORTED_TOTAL,
+                    &[self.chain.fork_label_at_slot(block.slot())],
+                );
 
                 if reprocess_tx
                     .try_send(ReprocessQueueMessage::BlockImported {
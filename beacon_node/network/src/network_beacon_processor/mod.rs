@@ -13,8 +13,8 @@ use beacon_chain::{
     BeaconChainTypes, BlockError, NotifyExecutionLayer,
 };
 use beacon_processor::{
-    work_reprocessing_queue::ReprocessQueueMessage, BeaconProcessorChannels, BeaconProcessorSend,
-    DuplicateCache, GossipAggregatePackage, GossipAttestationPackage, Work,
+    work_reprocessing_queue::ReprocessQueueMessage, AsyncFn, BeaconProcessorChannels,
+    BeaconProcessorSend, DuplicateCache, GossipAggregatePackage, GossipAttestationPackage, Work,
     WorkEvent as BeaconWorkEvent,
 };
 use lighthouse_network::discovery::ConnectionId;
@@ -187,8 +187,31 @@ impl<T: BeaconChainTypes> NetworkBeaconProcessor<T> {
         block: Arc<SignedBeaconBlock<T::EthSpec>>,
         seen_timestamp: Duration,
     ) -> Result<(), Error<T::EthSpec>> {
+        let process_fn = self.gossip_beacon_block_process_fn(
+            message_id,
+            peer_id,
+            peer_client,
+            block,
+            seen_timestamp,
+        );
+
+        self.try_send(BeaconWorkEvent {
+            drop_during_sync: false,
+            work: Work::GossipBlock(process_fn),
+        })
+    }
+
+    /// Returns a future which processes `block` as a gossip block.
+    pub(crate) fn gossip_beacon_block_process_fn(
+        self: &Arc<Self>,
+        message_id: MessageId,
+        peer_id: PeerId,
+        peer_client: Client,
+        block: Arc<SignedBeaconBlock<T::EthSpec>>,
+        seen_timestamp: Duration,
+    ) -> AsyncFn {
         let processor = self.clone();
-        let process_fn = async move {
+        Box::pin(async move {
             let reprocess_tx = processor.reprocess_tx.clone();
             let invalid_block_storage = processor.invalid_block_storage.clone();
             let duplicate_cache = processor.duplicate_cache.clone();
@@ -204,11 +227,6 @@ impl<T: BeaconChainTypes> NetworkBeaconProcessor<T> {
                     seen_timestamp,
                 )
                 .await
-        };
-
-        self.try_send(BeaconWorkEvent {
-            drop_during_sync: false,
-            work: Work::GossipBlock(Box::pin(process_fn)),
         })
     }
 
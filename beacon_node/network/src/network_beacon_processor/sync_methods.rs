@@ -10,8 +10,9 @@ use beacon_chain::data_availability_checker::AvailabilityCheckError;
 use beacon_chain::data_availability_checker::MaybeAvailableBlock;
 use beacon_chain::data_column_verification::verify_kzg_for_data_column_list;
 use beacon_chain::{
-    validator_monitor::get_slot_delay_ms, AvailabilityProcessingStatus, BeaconChainTypes,
-    BlockError, ChainSegmentResult, HistoricalBlockError, NotifyExecutionLayer,
+    chain_config::BlobFetchStrategy, validator_monitor::get_slot_delay_ms,
+    AvailabilityProcessingStatus, BeaconChainTypes, BlockError, ChainSegmentResult,
+    HistoricalBlockError, NotifyExecutionLayer,
 };
 use beacon_processor::{
     work_reprocessing_queue::{QueuedRpcBlock, ReprocessQueueMessage},
@@ -183,13 +184,19 @@ impl<T: BeaconChainTypes> NetworkBeaconProcessor<T> {
                     *hash,
                     slot,
                     seen_timestamp,
+                    BlockImportSource::Lookup,
                     None,
                     None,
                 );
 
                 self.chain.recompute_head_at_current_slot().await;
             }
-            Ok(AvailabilityProcessingStatus::MissingComponents(..)) => {
+            Ok(AvailabilityProcessingStatus::MissingComponents(..))
+                if self.chain.config.prefers_blob_fetch_strategy(
+                    BlobFetchStrategy::EngineGetBlobs,
+                    BlobFetchStrategy::RpcByRoot,
+                ) =>
+            {
                 // Block is valid, we can now attempt fetching blobs from EL using version hashes
                 // derived from kzg commitments from the block, without having to wait for all blobs
                 // to be sent from the peers if we already have them.
@@ -240,6 +240,7 @@ impl TestRig {
             executor,
             current_workers: 0,
             config: beacon_processor_config,
+            queue_stats: BeaconProcessorQueueStats::default(),
             log: log.clone(),
         }
         .spawn_manager(
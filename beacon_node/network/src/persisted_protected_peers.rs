@@ -0,0 +1,93 @@
+use lighthouse_network::PeerId;
+use ssz::{Decode, Encode};
+use std::sync::Arc;
+use store::{DBColumn, Error as StoreError, HotColdDB, ItemStore, StoreItem};
+use types::{EthSpec, Hash256};
+
+/// 32-byte key for accessing the `PersistedProtectedPeers`. All zero because
+/// `PersistedProtectedPeers` has its own column.
+pub const PROTECTED_PEERS_DB_KEY: Hash256 = Hash256::ZERO;
+
+/// Load the peers which were protected via the HTTP API.
+pub fn load_protected_peers<E: EthSpec, Hot: ItemStore<E>, Cold: ItemStore<E>>(
+    store: Arc<HotColdDB<E, Hot, Cold>>,
+) -> Result<Vec<PeerId>, StoreError> {
+    Ok(store
+        .get_item::<PersistedProtectedPeers>(&PROTECTED_PEERS_DB_KEY)?
+        .map(|persisted| persisted.peers)
+        .unwrap_or_default())
+}
+
+/// Add `peer_id` to the protected peers in `store`, if it is not already present.
+pub fn persist_protected_peer<E: EthSpec, Hot: ItemStore<E>, Cold: ItemStore<E>>(
+    store: Arc<HotColdDB<E, Hot, Cold>>,
+    peer_id: PeerId,
+) -> Result<(), StoreError> {
+    let mut peers = load_protected_peers(store.clone())?;
+    if peers.contains(&peer_id) {
+        return Ok(());
+    }
+    peers.push(peer_id);
+    store.put_item(&PROTECTED_PEERS_DB_KEY, &PersistedProtectedPeers { peers })
+}
+
+/// Wrapper around the protected peers for persistence to disk.
+pub struct PersistedProtectedPeers {
+    pub peers: Vec<PeerId>,
+}
+
+impl StoreItem for PersistedProtectedPeers {
+    fn db_column() -> DBColumn {
+        DBColumn::ProtectedPeers
+    }
+
+    fn as_store_bytes(&self) -> Vec<u8> {
+        self.peers
+            .iter()
+            .map(PeerId::to_bytes)
+            .collect::<Vec<_>>()
+            .as_ssz_bytes()
+    }
+
+    fn from_store_bytes(bytes: &[u8]) -> Result<Self, StoreError> {
+        let peers = Vec::<Vec<u8>>::from_ssz_bytes(bytes)?
+            .into_iter()
+            .map(|bytes| {
+                PeerId::from_bytes(&bytes).map_err(|e| StoreError::DBError {
+                    message: format!("Invalid protected peer: {:?}", e),
+                })
+            })
+            .collect::<Result<_, _>>()?;
+        Ok(PersistedProtectedPeers { peers })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use sloggers::{null::NullLoggerBuilder, Build};
+    use store::config::StoreConfig;
+    use store::MemoryStore;
+    use types::{ChainSpec, MinimalEthSpec};
+
+    #[test]
+    fn test_persisted_protected_peers() {
+        let log = NullLoggerBuilder.build().unwrap();
+        let store: Arc<
+            HotColdDB<MinimalEthSpec, MemoryStore<MinimalEthSpec>, MemoryStore<MinimalEthSpec>>,
+        > = Arc::new(
+            HotColdDB::open_ephemeral(StoreConfig::default(), ChainSpec::minimal().into(), log)
+                .unwrap(),
+        );
+        assert!(load_protected_peers(store.clone()).unwrap().is_empty());
+
+        let peers = vec![PeerId::random(), PeerId::random()];
+        for peer_id in &peers {
+            persist_protected_peer(store.clone(), *peer_id).unwrap();
+        }
+        // Protecting a peer twice is a no-op.
+        persist_protected_peer(store.clone(), peers[0]).unwrap();
+
+        assert_eq!(load_protected_peers(store).unwrap(), peers);
+    }
+}
@@ -2,6 +2,7 @@ use crate::metrics;
 use crate::nat;
 use crate::network_beacon_processor::InvalidBlockStorage;
 use crate::persisted_dht::{clear_dht, load_dht, persist_dht};
+use crate::persisted_protected_peers::{load_protected_peers, persist_protected_peer};
 use crate::router::{Router, RouterMessage};
 use crate::subnet_service::{SubnetService, SubnetServiceMessage, Subscription};
 use crate::NetworkConfig;
@@ -101,6 +102,8 @@ pub enum NetworkMessage<E: EthSpec> {
         reason: GoodbyeReason,
         source: ReportSource,
     },
+    /// Mark a peer as trusted, so that it is never pruned or banned, and persist it to disk.
+    ProtectPeer { peer_id: PeerId },
 }
 
 /// Messages triggered by validators that may trigger a subscription to a subnet.
@@ -295,6 +298,20 @@ impl<T: BeaconChainTypes> NetworkService<T> {
             }
         }
 
+        // Restore the peers which were protected via the HTTP API.
+        match load_protected_peers::<T::EthSpec, T::HotStore, T::ColdStore>(store.clone()) {
+            Ok(protected_peers) => {
+                debug!(
+                    network_log,
+                    "Loading protected peers"; "peers" => protected_peers.len()
+                );
+                for peer_id in protected_peers {
+                    libp2p.protect_peer(&peer_id);
+                }
+            }
+            Err(e) => error!(network_log, "Failed to load protected peers"; "error" => ?e),
+        }
+
         let invalid_block_storage = config
             .invalid_block_storage
             .clone()
@@ -674,6 +691,21 @@ impl<T: BeaconChainTypes> NetworkService<T> {
                 reason,
                 source,
             } => self.libp2p.goodbye_peer(&peer_id, reason, source),
+            NetworkMessage::ProtectPeer { peer_id } => {
+                self.libp2p.protect_peer(&peer_id);
+                match persist_protected_peer::<T::EthSpec, T::HotStore, T::ColdStore>(
+                    self.store.clone(),
+                    peer_id,
+                ) {
+                    Ok(()) => info!(self.log, "Protected peer"; "peer_id" => %peer_id),
+                    Err(e) => error!(
+                        self.log,
+                        "Failed to persist protected peer";
+                        "peer_id" => %peer_id,
+                        "error" => ?e
+                    ),
+                }
+            }
             NetworkMessage::SubscribeCoreTopics => {
                 if self.subscribed_core_topics() {
                     return;
@@ -822,7 +854,7 @@ impl<T: BeaconChainTypes> NetworkService<T> {
 
     /// Handle a message sent to the network service.
     async fn on_validator_subscription_msg(&mut self, msg: ValidatorSubscriptionMessage) {
-        match msg {
+        let duties = match msg {
             ValidatorSubscriptionMessage::AttestationSubscribe { subscriptions } => {
                 let subscriptions = subscriptions.into_iter().map(Subscription::Attestation);
                 self.subnet_service.validator_subscriptions(subscriptions)
@@ -831,7 +863,8 @@ impl<T: BeaconChainTypes> NetworkService<T> {
                 let subscriptions = subscriptions.into_iter().map(Subscription::SyncCommittee);
                 self.subnet_service.validator_subscriptions(subscriptions)
             }
-        }
+        };
+        self.libp2p.peer_manager_mut().add_duty_subnets(duties);
     }
 
     fn update_gossipsub_parameters(&mut self) {
@@ -13,7 +13,7 @@ use tokio::time::Instant;
 use beacon_chain::{BeaconChain, BeaconChainTypes};
 use delay_map::HashSetDelay;
 use futures::prelude::*;
-use lighthouse_network::{discv5::enr::NodeId, NetworkConfig, Subnet, SubnetDiscovery};
+use lighthouse_network::{discv5::enr::NodeId, NetworkConfig, Subnet, SubnetDiscovery, SubnetDuty};
 use slog::{debug, error, o, warn};
 use slot_clock::SlotClock;
 use types::{
@@ -132,21 +132,17 @@ impl<T: BeaconChainTypes> SubnetService<T> {
 
         // Build the list of known permanent subscriptions, so that we know not to subscribe or
         // discover them.
-        let mut permanent_attestation_subscriptions = HashSet::default();
-        if config.subscribe_all_subnets {
-            // We are subscribed to all subnets, set all the bits to true.
-            for index in 0..beacon_chain.spec.attestation_subnet_count {
-                permanent_attestation_subscriptions
-                    .insert(Subnet::Attestation(SubnetId::from(index)));
-            }
-        } else {
-            // Not subscribed to all subnets, so just calculate the required subnets from the node
-            // id.
-            for subnet_id in
-                SubnetId::compute_attestation_subnets(node_id.raw(), &beacon_chain.spec)
-            {
-                permanent_attestation_subscriptions.insert(Subnet::Attestation(subnet_id));
-            }
+        let permanent_attestation_subscriptions: HashSet<Subnet> = config
+            .long_lived_attestation_subnets(node_id.raw(), &beacon_chain.spec)
+            .into_iter()
+            .map(Subnet::Attestation)
+            .collect();
+        if let Some(subnets) = &config.long_lived_subnets_override {
+            slog::info!(
+                log,
+                "Overriding long-lived attestation subnets";
+                "subnets" => ?subnets,
+            );
         }
 
         // Set up the sync committee subscriptions
@@ -225,14 +221,19 @@ impl<T: BeaconChainTypes> SubnetService<T> {
     /// - Request subscriptions for subnets on specific slots when required.
     /// - Build the timeouts for each of these events.
     ///
-    /// This returns a result simply for the ergonomics of using ?. The result can be
-    /// safely dropped.
-    pub fn validator_subscriptions(&mut self, subscriptions: impl Iterator<Item = Subscription>) {
+    /// Returns the subnets on which the validators must aggregate or serve on a sync committee, so
+    /// that peers on them can be retained.
+    pub fn validator_subscriptions(
+        &mut self,
+        subscriptions: impl Iterator<Item = Subscription>,
+    ) -> Vec<SubnetDuty> {
         // If the node is in a proposer-only state, we ignore all subnet subscriptions.
         if self.proposer_only {
-            return;
+            return vec![];
         }
 
+        let mut duties = vec![];
+
         // Maps each subnet subscription to it's highest slot
         let mut subnets_to_discover: HashMap<Subnet, Slot> = HashMap::new();
 
@@ -280,6 +281,17 @@ impl<T: BeaconChainTypes> SubnetService<T> {
 
                     if subscription.is_aggregator {
                         metrics::inc_counter(&metrics::SUBNET_SUBSCRIPTION_AGGREGATOR_REQUESTS);
+                        // The aggregate is published during the duty slot.
+                        if let Some(duration) = self
+                            .beacon_chain
+                            .slot_clock
+                            .duration_to_slot(subscription.slot + 1)
+                        {
+                            duties.push(SubnetDuty {
+                                subnet,
+                                until: std::time::Instant::now() + duration,
+                            });
+                        }
                         if let Err(e) = self.subscribe_to_subnet(exact_subnet) {
                             warn!(self.log,
                                 "Subscription to subnet error";
@@ -339,6 +351,10 @@ impl<T: BeaconChainTypes> SubnetService<T> {
                             continue;
                         }
 
+                        duties.push(SubnetDuty {
+                            subnet,
+                            until: std::time::Instant::now() + duration_to_unsubscribe,
+                        });
                         self.subscribe_to_sync_subnet(
                             subnet,
                             duration_to_unsubscribe,
@@ -356,6 +372,8 @@ impl<T: BeaconChainTypes> SubnetService<T> {
                 warn!(self.log, "Discovery lookup request error"; "error" => e);
             };
         }
+
+        duties
     }
 
     /// Checks if we have subscribed aggregate validators for the subnet. If not, checks the gossip
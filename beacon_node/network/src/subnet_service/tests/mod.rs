@@ -281,6 +281,38 @@ mod test {
         assert_eq!(subnet_service.subscriptions().count(), 0);
     }
 
+    #[tokio::test]
+    async fn aggregator_subscriptions_return_duties() {
+        let mut subnet_service = get_subnet_service();
+        let current_slot = subnet_service
+            .beacon_chain
+            .slot_clock
+            .now()
+            .expect("Could not get current slot");
+        let subscription_slot = current_slot + 2;
+        let committee_count = 4;
+
+        // Only the aggregator has a duty which requires peers on its subnet.
+        let subscriptions = vec![
+            get_subscription(1, subscription_slot, committee_count, true),
+            get_subscription(2, subscription_slot, committee_count, false),
+        ];
+        let duties = subnet_service.validator_subscriptions(subscriptions.into_iter());
+
+        let subnet = Subnet::Attestation(
+            SubnetId::compute_subnet::<MainnetEthSpec>(
+                subscription_slot,
+                1,
+                committee_count,
+                &subnet_service.beacon_chain.spec,
+            )
+            .unwrap(),
+        );
+        assert_eq!(duties.len(), 1);
+        assert_eq!(duties[0].subnet, subnet);
+        assert!(duties[0].until > std::time::Instant::now());
+    }
+
     /// Test to verify that we are not unsubscribing to a subnet before a required subscription.
     #[cfg(not(windows))]
     #[tokio::test]
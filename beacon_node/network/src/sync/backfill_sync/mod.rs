@@ -17,11 +17,13 @@ use crate::sync::range_sync::{
 };
 use beacon_chain::block_verification_types::RpcBlock;
 use beacon_chain::{BeaconChain, BeaconChainTypes};
+use lighthouse_network::rpc::RpcErrorCause;
 use lighthouse_network::service::api_types::Id;
-use lighthouse_network::types::{BackFillState, NetworkGlobals};
+use lighthouse_network::types::{BackFillState, BandwidthManager, NetworkGlobals, TrafficClass};
 use lighthouse_network::{PeerAction, PeerId};
 use rand::seq::SliceRandom;
 use slog::{crit, debug, error, info, warn};
+use ssz::Encode;
 use std::collections::{
     btree_map::{BTreeMap, Entry},
     HashMap, HashSet,
@@ -278,6 +280,15 @@ impl<T: BeaconChainTypes> BackFillSync<T> {
         })
     }
 
+    /// Requests any batches which were deferred because the bandwidth budget was exhausted.
+    #[must_use = "A failure here indicates the backfill sync has failed and the global sync state should be updated"]
+    pub fn request_deferred_batches(
+        &mut self,
+        network: &mut SyncNetworkContext<T>,
+    ) -> Result<(), BackFillError> {
+        self.request_batches(network)
+    }
+
     /// A fully synced peer has joined us.
     /// If we are in a failed state, update a local variable to indicate we are able to restart
     /// the failed sync on the next attempt.
@@ -346,6 +357,7 @@ impl<T: BeaconChainTypes> BackFillSync<T> {
         batch_id: BatchId,
         peer_id: &PeerId,
         request_id: Id,
+        cause: RpcErrorCause,
     ) -> Result<(), BackFillError> {
         if let Some(batch) = self.batches.get_mut(&batch_id) {
             // A batch could be retried without the peer failing the request (disconnecting/
@@ -356,11 +368,19 @@ impl<T: BeaconChainTypes> BackFillSync<T> {
             if !batch.is_expecting_block(&request_id) {
                 return Ok(());
             }
-            debug!(self.log, "Batch failed"; "batch_epoch" => batch_id, "error" => "rpc_error");
+            debug!(self.log, "Batch failed"; "batch_epoch" => batch_id, "error" => "rpc_error", "cause" => ?cause);
             if let Some(active_requests) = self.active_requests.get_mut(peer_id) {
                 active_requests.remove(&batch_id);
             }
-            match batch.download_failed(true) {
+            let outcome = match cause {
+                RpcErrorCause::RateLimited => batch.download_rate_limited(),
+                RpcErrorCause::ResourceUnavailable => {
+                    batch.record_unavailable(&mut self.network_globals.peers.write(), peer_id);
+                    batch.download_unavailable()
+                }
+                _ => batch.download_failed(true),
+            };
+            match outcome {
                 Err(e) => self.fail_sync(BackFillError::BatchInvalidState(batch_id, e.0)),
                 Ok(BatchOperationOutcome::Failed { blacklist: _ }) => {
                     self.fail_sync(BackFillError::BatchDownloadFailed(batch_id))
@@ -410,6 +430,8 @@ impl<T: BeaconChainTypes> BackFillSync<T> {
             }
         };
 
+        record_batch_bandwidth(&self.network_globals.bandwidth, &blocks);
+
         {
             // A stream termination has been sent. This batch has ended. Process a completed batch.
             // Remove the request from the peer's active batches
@@ -901,32 +923,34 @@ impl<T: BeaconChainTypes> BackFillSync<T> {
             return Ok(());
         };
 
-        // Find a peer to request the batch
+        // Find a peer to request the batch, skipping peers which have pruned its range.
         let failed_peers = batch.failed_peers();
 
-        let new_peer = self
-            .network_globals
-            .peers
-            .read()
+        let peers = self.network_globals.peers.read();
+        let new_peer = peers
             .synced_peers()
+            .filter(|peer| batch.can_be_served_by(&peers, peer))
             .map(|peer| {
                 (
+                    network.is_rate_limited(peer),
                     failed_peers.contains(peer),
                     self.active_requests.get(peer).map(|v| v.len()).unwrap_or(0),
                     rand::random::<u32>(),
                     *peer,
                 )
             })
-            // Sort peers prioritizing unrelated peers with less active requests.
+            // Sort peers prioritizing peers which have not asked us to retry later, then unrelated
+            // peers with less active requests.
             .min()
-            .map(|(_, _, _, peer)| peer);
+            .map(|(_, _, _, _, peer)| peer);
+        drop(peers);
 
         if let Some(peer) = new_peer {
             self.participating_peers.insert(peer);
             self.send_batch(network, batch_id, peer)
         } else {
-            // If we are here the chain has no more synced peers
-            info!(self.log, "Backfill sync paused"; "reason" => "insufficient_synced_peers");
+            // If we are here the chain has no more synced peers which can serve the batch
+            info!(self.log, "Backfill sync paused"; "reason" => "insufficient_synced_peers", "batch_epoch" => batch_id);
             self.set_state(BackFillState::Paused);
             Err(BackFillError::Paused)
         }
@@ -1046,9 +1070,26 @@ impl<T: BeaconChainTypes> BackFillSync<T> {
         idle_peers.shuffle(&mut rng);
 
         while let Some(peer) = idle_peers.pop() {
+            // Leave the rest of the bandwidth budget to gossip. Deferred batches are requested by
+            // `request_deferred_batches` once the budget refills.
+            if !self
+                .network_globals
+                .bandwidth
+                .has_capacity(TrafficClass::Backfill)
+            {
+                debug!(self.log, "Backfill deferred by bandwidth limit"; "idle_peers" => idle_peers.len() + 1);
+                return Ok(());
+            }
             if let Some(batch_id) = self.include_next_batch(network) {
-                // send the batch
-                self.send_batch(network, batch_id, peer)?;
+                if self.batches.get(&batch_id).is_some_and(|batch| {
+                    batch.can_be_served_by(&self.network_globals.peers.read(), &peer)
+                }) {
+                    // send the batch
+                    self.send_batch(network, batch_id, peer)?;
+                } else {
+                    // The peer has pruned the range of this batch, find another peer for it.
+                    self.retry_batch_download(network, batch_id)?;
+                }
             } else {
                 // No more batches, simply stop
                 return Ok(());
@@ -1171,3 +1212,23 @@ enum ResetEpochError {
     /// The chain has already completed.
     SyncCompleted,
 }
+
+/// Records the blocks, blobs and data columns downloaded for a batch against the bandwidth budget.
+fn record_batch_bandwidth<E: EthSpec>(bandwidth: &BandwidthManager, blocks: &[RpcBlock<E>]) {
+    let mut block_bytes = 0;
+    let mut column_bytes = 0;
+    for block in blocks {
+        block_bytes += block.as_block().ssz_bytes_len();
+        if let Some(blobs) = block.blobs() {
+            block_bytes += blobs.iter().map(|blob| blob.ssz_bytes_len()).sum::<usize>();
+        }
+        if let Some(columns) = block.custody_columns() {
+            column_bytes += columns
+                .iter()
+                .map(|column| column.as_data_column().ssz_bytes_len())
+                .sum::<usize>();
+        }
+    }
+    bandwidth.record(TrafficClass::Backfill, block_bytes);
+    bandwidth.record(TrafficClass::CustodyBackfill, column_bytes);
+}
@@ -36,6 +36,7 @@ use beacon_chain::data_availability_checker::{
 use beacon_chain::{AvailabilityProcessingStatus, BeaconChainTypes, BlockError};
 pub use common::RequestState;
 use fnv::FnvHashMap;
+use lighthouse_network::rpc::RpcErrorCause;
 use lighthouse_network::service::api_types::SingleLookupReqId;
 use lighthouse_network::{PeerAction, PeerId};
 use lru_cache::LRUTimeCache;
@@ -71,6 +72,14 @@ const LOOKUP_MAX_DURATION_STUCK_SECS: u64 = 15 * PARENT_DEPTH_TOLERANCE as u64;
 /// lookup at most after 4 seconds, the lookup should gain peers.
 const LOOKUP_MAX_DURATION_NO_PEERS_SECS: u64 = 10;
 
+/// How long to prefer other peers for a lookup after a peer reports not having the requested
+/// data, e.g. because it has been pruned. This outlives most lookups.
+const LOOKUP_RESOURCE_UNAVAILABLE_BACKOFF_SECS: u64 = 60;
+/// How long to prefer other peers for a lookup after a peer rate limits one of its requests.
+const LOOKUP_RATE_LIMITED_BACKOFF_SECS: u64 = 12;
+/// How long to prefer other peers for a lookup after a peer fails to serve one of its requests.
+const LOOKUP_SERVER_ERROR_BACKOFF_SECS: u64 = 6;
+
 /// Lookups contain untrusted data, including blocks that have not yet been validated. In case of
 /// bugs or malicious activity we want to bound how much memory these lookups can consume. Aprox the
 /// max size of a lookup is ~ 10 MB (current max size of gossip and RPC blocks). 200 lookups can
@@ -153,14 +162,7 @@ impl<T: BeaconChainTypes> BlockLookups<T> {
     pub(crate) fn active_single_lookups(&self) -> Vec<BlockLookupSummary> {
         self.single_block_lookups
             .iter()
-            .map(|(id, l)| {
-                (
-                    *id,
-                    l.block_root(),
-                    l.awaiting_parent(),
-                    l.all_peers().copied().collect(),
-                )
-            })
+            .map(|(id, l)| (*id, l.block_root(), l.awaiting_parent(), l.all_peers()))
             .collect()
     }
 
@@ -283,7 +285,7 @@ impl<T: BeaconChainTypes> BlockLookups<T> {
                         .find(|(_, l)| l.block_root() == parent_chain_tip)
                     {
                         cx.send_sync_message(SyncMessage::AddPeersForceRangeSync {
-                            peers: lookup.all_peers().copied().collect(),
+                            peers: lookup.all_peers(),
                             head_slot: tip_lookup.peek_downloaded_block_slot(),
                             head_root: parent_chain_tip,
                         });
@@ -419,14 +421,16 @@ impl<T: BeaconChainTypes> BlockLookups<T> {
 
     /* Lookup responses */
 
-    /// Process a block or blob response received from a single lookup request.
+    /// Process a block or blob response received from a single lookup request. `peer_id` is the
+    /// peer the request was sent to, if the request was sent to a single peer.
     pub fn on_download_response<R: RequestState<T>>(
         &mut self,
         id: SingleLookupReqId,
+        peer_id: Option<PeerId>,
         response: Result<(R::VerifiedResponseType, PeerGroup, Duration), RpcResponseError>,
         cx: &mut SyncNetworkContext<T>,
     ) {
-        let result = self.on_download_response_inner::<R>(id, response, cx);
+        let result = self.on_download_response_inner::<R>(id, peer_id, response, cx);
         self.on_lookup_result(id.lookup_id, result, "download_response", cx);
     }
 
@@ -434,6 +438,7 @@ impl<T: BeaconChainTypes> BlockLookups<T> {
     pub fn on_download_response_inner<R: RequestState<T>>(
         &mut self,
         id: SingleLookupReqId,
+        peer_id: Option<PeerId>,
         response: Result<(R::VerifiedResponseType, PeerGroup, Duration), RpcResponseError>,
         cx: &mut SyncNetworkContext<T>,
     ) -> Result<LookupResult, LookupRequestError> {
@@ -495,7 +500,39 @@ impl<T: BeaconChainTypes> BlockLookups<T> {
                     "error" => ?e,
                 );
 
-                request_state.on_download_failure(id.req_id)?;
+                // Decide how to retry based on the cause of the failure. Peers that may not be
+                // able to serve the request right now are avoided while other peers are
+                // available.
+                let count_failure = match (e.rpc_error_cause(), peer_id) {
+                    // The peer does not have the data, e.g. because it has been pruned.
+                    (Some(RpcErrorCause::ResourceUnavailable), Some(peer_id)) => {
+                        lookup.backoff_peer(
+                            peer_id,
+                            Duration::from_secs(LOOKUP_RESOURCE_UNAVAILABLE_BACKOFF_SECS),
+                        );
+                        true
+                    }
+                    // The peer may serve the request shortly. Only count the failure if the peer
+                    // was already backing off.
+                    (Some(RpcErrorCause::RateLimited), Some(peer_id)) => lookup.backoff_peer(
+                        peer_id,
+                        e.retry_after()
+                            .unwrap_or(Duration::from_secs(LOOKUP_RATE_LIMITED_BACKOFF_SECS)),
+                    ),
+                    (Some(RpcErrorCause::ServerError), Some(peer_id)) => {
+                        lookup.backoff_peer(
+                            peer_id,
+                            Duration::from_secs(LOOKUP_SERVER_ERROR_BACKOFF_SECS),
+                        );
+                        true
+                    }
+                    _ => true,
+                };
+
+                R::request_state_mut(lookup)
+                    .map_err(|e| LookupRequestError::BadState(e.to_owned()))?
+                    .get_state_mut()
+                    .on_download_failure(id.req_id, count_failure)?;
                 // continue_request will retry a download as the request state is AwaitingDownload
             }
         }
@@ -682,7 +719,7 @@ impl<T: BeaconChainTypes> BlockLookups<T> {
                 lookup.continue_requests(cx)
             }
             Action::ParentUnknown { parent_root } => {
-                let peers = lookup.all_peers().copied().collect::<Vec<_>>();
+                let peers = lookup.all_peers();
                 lookup.set_awaiting_parent(parent_root);
                 debug!(self.log, "Marking lookup as awaiting parent"; "id" => lookup.id, "block_root" => ?block_root, "parent_root" => ?parent_root);
                 self.search_parent_of_child(parent_root, block_root, &peers, cx);
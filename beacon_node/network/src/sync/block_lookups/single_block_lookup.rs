@@ -7,8 +7,9 @@ use crate::sync::network_context::{
 use beacon_chain::{BeaconChainTypes, BlockProcessStatus};
 use derivative::Derivative;
 use lighthouse_network::service::api_types::Id;
+use parking_lot::RwLock;
 use rand::seq::IteratorRandom;
-use std::collections::HashSet;
+use std::collections::{HashMap, HashSet};
 use std::fmt::Debug;
 use std::sync::Arc;
 use std::time::{Duration, Instant};
@@ -63,9 +64,13 @@ pub struct SingleBlockLookup<T: BeaconChainTypes> {
     pub id: Id,
     pub block_request_state: BlockRequestState<T::EthSpec>,
     pub component_requests: ComponentRequests<T::EthSpec>,
-    /// Peers that claim to have imported this set of block components
+    /// Peers that claim to have imported this set of block components. Shared with the custody
+    /// request of this lookup, which may only penalise these peers for missing columns.
     #[derivative(Debug(format_with = "fmt_peer_set_as_len"))]
-    peers: HashSet<PeerId>,
+    peers: Arc<RwLock<HashSet<PeerId>>>,
+    /// Peers that failed a request of this lookup because they could not serve it, e.g. they rate
+    /// limited us. Other peers are preferred for new requests until the backoff expires.
+    peers_backoff: HashMap<PeerId, Instant>,
     block_root: Hash256,
     awaiting_parent: Option<Hash256>,
     created: Instant,
@@ -92,7 +97,8 @@ impl<T: BeaconChainTypes> SingleBlockLookup<T> {
             id,
             block_request_state: BlockRequestState::new(requested_block_root),
             component_requests: ComponentRequests::WaitingForBlock,
-            peers: HashSet::from_iter(peers.iter().copied()),
+            peers: Arc::new(RwLock::new(HashSet::from_iter(peers.iter().copied()))),
+            peers_backoff: HashMap::new(),
             block_root: requested_block_root,
             awaiting_parent,
             created: Instant::now(),
@@ -223,7 +229,7 @@ impl<T: BeaconChainTypes> SingleBlockLookup<T> {
                     );
                 } else if cx.chain.should_fetch_custody_columns(block_epoch) {
                     self.component_requests = ComponentRequests::ActiveCustodyRequest(
-                        CustodyRequestState::new(self.block_root),
+                        CustodyRequestState::new(self.block_root, self.peers.clone()),
                     );
                 } else {
                     self.component_requests = ComponentRequests::NotNeeded("outside da window");
@@ -348,29 +354,51 @@ impl<T: BeaconChainTypes> SingleBlockLookup<T> {
     }
 
     /// Get all unique peers that claim to have imported this set of block components
-    pub fn all_peers(&self) -> impl Iterator<Item = &PeerId> + '_ {
-        self.peers.iter()
+    pub fn all_peers(&self) -> Vec<PeerId> {
+        self.peers.read().iter().copied().collect()
     }
 
     /// Add peer to all request states. The peer must be able to serve this request.
     /// Returns true if the peer was newly inserted into some request state.
     pub fn add_peer(&mut self, peer_id: PeerId) -> bool {
-        self.peers.insert(peer_id)
+        self.peers.write().insert(peer_id)
     }
 
     /// Remove peer from available peers.
     pub fn remove_peer(&mut self, peer_id: &PeerId) {
-        self.peers.remove(peer_id);
+        self.peers.write().remove(peer_id);
+        self.peers_backoff.remove(peer_id);
+    }
+
+    /// Avoid requesting from `peer_id` for `duration` if other peers are available. Returns true
+    /// if the peer was already backing off.
+    pub fn backoff_peer(&mut self, peer_id: PeerId, duration: Duration) -> bool {
+        let now = Instant::now();
+        let already_backing_off = self
+            .peers_backoff
+            .get(&peer_id)
+            .is_some_and(|until| *until > now);
+        self.peers_backoff.insert(peer_id, now + duration);
+        already_backing_off
     }
 
     /// Returns true if this lookup has zero peers
     pub fn has_no_peers(&self) -> bool {
-        self.peers.is_empty()
+        self.peers.read().is_empty()
     }
 
-    /// Selects a random peer from available peers if any
+    /// Selects a random peer from available peers if any, preferring peers that are not backing
+    /// off.
     fn use_rand_available_peer(&mut self) -> Option<PeerId> {
-        self.peers.iter().choose(&mut rand::thread_rng()).copied()
+        let now = Instant::now();
+        self.peers_backoff.retain(|_, until| *until > now);
+        let peers = self.peers.read();
+        peers
+            .iter()
+            .filter(|peer_id| !self.peers_backoff.contains_key(peer_id))
+            .choose(&mut rand::thread_rng())
+            .or_else(|| peers.iter().choose(&mut rand::thread_rng()))
+            .copied()
     }
 }
 
@@ -398,13 +426,17 @@ impl<E: EthSpec> BlobRequestState<E> {
 pub struct CustodyRequestState<E: EthSpec> {
     #[derivative(Debug = "ignore")]
     pub block_root: Hash256,
+    /// The peers of the lookup, which claim to have imported the block and its custody columns.
+    #[derivative(Debug = "ignore")]
+    pub lookup_peers: Arc<RwLock<HashSet<PeerId>>>,
     pub state: SingleLookupRequestState<DataColumnSidecarList<E>>,
 }
 
 impl<E: EthSpec> CustodyRequestState<E> {
-    pub fn new(block_root: Hash256) -> Self {
+    pub fn new(block_root: Hash256, lookup_peers: Arc<RwLock<HashSet<PeerId>>>) -> Self {
         Self {
             block_root,
+            lookup_peers,
             state: SingleLookupRequestState::new(),
         }
     }
@@ -548,8 +580,13 @@ impl<T: Clone> SingleLookupRequestState<T> {
     }
 
     /// Registers a failure in downloading a block. This might be a peer disconnection or a wrong
-    /// block.
-    pub fn on_download_failure(&mut self, req_id: ReqId) -> Result<(), LookupRequestError> {
+    /// block. If `count_failure` is false the failure does not count towards the maximum number of
+    /// attempts.
+    pub fn on_download_failure(
+        &mut self,
+        req_id: ReqId,
+        count_failure: bool,
+    ) -> Result<(), LookupRequestError> {
         match &self.state {
             State::Downloading(expected_req_id) => {
                 if req_id != *expected_req_id {
@@ -558,7 +595,9 @@ impl<T: Clone> SingleLookupRequestState<T> {
                         req_id,
                     });
                 }
-                self.failed_downloading = self.failed_downloading.saturating_add(1);
+                if count_failure {
+                    self.failed_downloading = self.failed_downloading.saturating_add(1);
+                }
                 self.state = State::AwaitingDownload("not started");
                 Ok(())
             }
@@ -689,8 +728,8 @@ impl<T: Clone> std::fmt::Debug for State<T> {
 }
 
 fn fmt_peer_set_as_len(
-    peer_set: &HashSet<PeerId>,
+    peer_set: &Arc<RwLock<HashSet<PeerId>>>,
     f: &mut std::fmt::Formatter,
 ) -> Result<(), std::fmt::Error> {
-    write!(f, "{}", peer_set.len())
+    write!(f, "{}", peer_set.read().len())
 }
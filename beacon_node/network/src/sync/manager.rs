@@ -465,6 +465,9 @@ impl<T: BeaconChainTypes> SyncManager<T> {
     /// Handles RPC errors related to requests that were emitted from the sync manager.
     fn inject_error(&mut self, peer_id: PeerId, request_id: SyncRequestId, error: RPCError) {
         trace!(self.log, "Sync manager received a failed RPC");
+        if let Some(retry_after) = error.retry_after() {
+            self.network.on_retry_after(peer_id, retry_after);
+        }
         match request_id {
             SyncRequestId::SingleBlock { id } => {
                 self.on_single_block_response(id, peer_id, RpcEvent::RPCError(error))
@@ -485,12 +488,13 @@ impl<T: BeaconChainTypes> SyncManager<T> {
                                 batch_id,
                                 chain_id,
                                 id,
+                                error.cause(),
                             );
                             self.update_sync_state();
                         }
                         RangeRequestId::BackfillSync { batch_id } => match self
                             .backfill_sync
-                            .inject_error(&mut self.network, batch_id, &peer_id, id)
+                            .inject_error(&mut self.network, batch_id, &peer_id, id, error.cause())
                         {
                             Ok(_) => {}
                             Err(_) => self.update_sync_state(),
@@ -640,20 +644,25 @@ impl<T: BeaconChainTypes> SyncManager<T> {
                     // complete a backfill sync.
                     #[cfg(not(feature = "disable-backfill"))]
                     if matches!(sync_state, SyncState::Synced) {
-                        // Determine if we need to start/resume/restart a backfill sync.
-                        match self.backfill_sync.start(&mut self.network) {
-                            Ok(SyncStart::Syncing {
-                                completed,
-                                remaining,
-                            }) => {
-                                sync_state = SyncState::BackFillSyncing {
+                        if self.chain.storage_watchdog.backfill_paused() {
+                            // Don't fill the disk with historic blocks while it is nearly full.
+                            self.backfill_sync.pause();
+                        } else {
+                            // Determine if we need to start/resume/restart a backfill sync.
+                            match self.backfill_sync.start(&mut self.network) {
+                                Ok(SyncStart::Syncing {
                                     completed,
                                     remaining,
-                                };
-                            }
-                            Ok(SyncStart::NotSyncing) => {} // Ignore updating the state if the backfill sync state didn't start.
-                            Err(e) => {
-                                error!(self.log, "Backfill sync failed to start"; "error" => ?e);
+                                }) => {
+                                    sync_state = SyncState::BackFillSyncing {
+                                        completed,
+                                        remaining,
+                                    };
+                                }
+                                Ok(SyncStart::NotSyncing) => {} // Ignore updating the state if the backfill sync state didn't start.
+                                Err(e) => {
+                                    error!(self.log, "Backfill sync failed to start"; "error" => ?e);
+                                }
                             }
                         }
                     }
@@ -725,6 +734,15 @@ impl<T: BeaconChainTypes> SyncManager<T> {
 
         let mut register_metrics_interval = tokio::time::interval(Duration::from_secs(5));
 
+        // Backfill sync is paused and resumed by `update_sync_state` as the storage watchdog's
+        // pressure changes, so re-evaluate it periodically if the watchdog is enabled.
+        let check_storage_pressure = self.chain.config.storage_watchdog.is_some();
+        let mut storage_pressure_interval = tokio::time::interval(Duration::from_secs(60));
+
+        // Backfill requests deferred by the bandwidth limit are retried as the budget refills.
+        let check_bandwidth = self.network_globals().bandwidth.is_limited();
+        let mut bandwidth_interval = tokio::time::interval(Duration::from_secs(1));
+
         // process any inbound messages
         loop {
             tokio::select! {
@@ -743,10 +761,29 @@ impl<T: BeaconChainTypes> SyncManager<T> {
                 _ = register_metrics_interval.tick() => {
                     self.network.register_metrics();
                 }
+                _ = storage_pressure_interval.tick(), if check_storage_pressure => {
+                    self.update_sync_state();
+                }
+                _ = bandwidth_interval.tick(), if check_bandwidth => {
+                    self.request_deferred_backfill_batches();
+                }
             }
         }
     }
 
+    /// Requests backfill batches which were deferred by the bandwidth limit.
+    fn request_deferred_backfill_batches(&mut self) {
+        #[cfg(not(feature = "disable-backfill"))]
+        if self
+            .backfill_sync
+            .request_deferred_batches(&mut self.network)
+            .is_err()
+        {
+            // The backfill sync has failed, errors are reported within.
+            self.update_sync_state();
+        }
+    }
+
     pub(crate) fn handle_message(&mut self, sync_message: SyncMessage<T::EthSpec>) {
         match sync_message {
             SyncMessage::AddPeer(peer_id, info) => {
@@ -1059,6 +1096,7 @@ impl<T: BeaconChainTypes> SyncManager<T> {
             self.block_lookups
                 .on_download_response::<BlockRequestState<T::EthSpec>>(
                     id,
+                    Some(peer_id),
                     resp.map(|(value, seen_timestamp)| {
                         (value, PeerGroup::from_single(peer_id), seen_timestamp)
                     }),
@@ -1133,6 +1171,7 @@ impl<T: BeaconChainTypes> SyncManager<T> {
             self.block_lookups
                 .on_download_response::<BlobRequestState<T::EthSpec>>(
                     id,
+                    Some(peer_id),
                     resp.map(|(value, seen_timestamp)| {
                         (value, PeerGroup::from_single(peer_id), seen_timestamp)
                     }),
@@ -1182,6 +1221,7 @@ impl<T: BeaconChainTypes> SyncManager<T> {
         self.block_lookups
             .on_download_response::<CustodyRequestState<T::EthSpec>>(
                 requester.0,
+                None,
                 response.map(|(columns, peer_group)| (columns, peer_group, seen_timestamp)),
                 &mut self.network,
             );
@@ -21,12 +21,15 @@ use lighthouse_network::rpc::methods::{
     BlobsByRangeRequest, DataColumnsByRangeRequest, OldBlocksByRangeRequest,
     OldBlocksByRangeRequestV1, OldBlocksByRangeRequestV2,
 };
-use lighthouse_network::rpc::{BlocksByRangeRequest, GoodbyeReason, RPCError, RequestType};
+use lighthouse_network::rpc::{
+    BlocksByRangeRequest, GoodbyeReason, RPCError, RequestType, RpcErrorCause,
+};
 use lighthouse_network::service::api_types::{
     AppRequestId, CustodyId, CustodyRequester, DataColumnsByRootRequestId,
     DataColumnsByRootRequester, Id, SingleLookupReqId, SyncRequestId,
 };
 use lighthouse_network::{Client, NetworkGlobals, PeerAction, PeerId, ReportSource};
+use parking_lot::RwLock;
 use rand::seq::SliceRandom;
 use rand::thread_rng;
 pub use requests::LookupVerifyError;
@@ -36,9 +39,9 @@ use requests::{
 };
 use slog::{debug, error, warn};
 use std::collections::hash_map::Entry;
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::sync::Arc;
-use std::time::Duration;
+use std::time::{Duration, Instant};
 use tokio::sync::mpsc;
 use types::blob_sidecar::FixedBlobSidecarList;
 use types::{
@@ -110,6 +113,24 @@ impl std::fmt::Display for RpcResponseError {
     }
 }
 
+impl RpcResponseError {
+    /// Returns the cause of the error if the request failed at the RPC layer.
+    pub fn rpc_error_cause(&self) -> Option<RpcErrorCause> {
+        match self {
+            RpcResponseError::RpcError(e) => Some(e.cause()),
+            RpcResponseError::VerifyError(_) | RpcResponseError::CustodyRequestError(_) => None,
+        }
+    }
+
+    /// Returns how long the peer asked us to wait before retrying the request, if it did.
+    pub fn retry_after(&self) -> Option<Duration> {
+        match self {
+            RpcResponseError::RpcError(e) => e.retry_after(),
+            RpcResponseError::VerifyError(_) | RpcResponseError::CustodyRequestError(_) => None,
+        }
+    }
+}
+
 impl From<RPCError> for RpcResponseError {
     fn from(e: RPCError) -> Self {
         RpcResponseError::RpcError(e)
@@ -197,6 +218,10 @@ pub struct SyncNetworkContext<T: BeaconChainTypes> {
     range_block_components_requests:
         FnvHashMap<Id, (RangeRequestId, RangeBlockComponentsRequest<T::EthSpec>)>,
 
+    /// Peers which rate limited a request and told us when to retry it. Other peers are preferred
+    /// for new by range requests until then.
+    peers_retry_after: HashMap<PeerId, Instant>,
+
     /// Whether the ee is online. If it's not, we don't allow access to the
     /// `beacon_processor_send`.
     execution_engine_state: EngineState,
@@ -245,12 +270,28 @@ impl<T: BeaconChainTypes> SyncNetworkContext<T> {
             data_columns_by_root_requests: ActiveRequests::new("data_columns_by_root"),
             custody_by_root_requests: <_>::default(),
             range_block_components_requests: FnvHashMap::default(),
+            peers_retry_after: HashMap::new(),
             network_beacon_processor,
             chain,
             log,
         }
     }
 
+    /// Records that `peer_id` asked for requests to be retried after `retry_after`.
+    pub fn on_retry_after(&mut self, peer_id: PeerId, retry_after: Duration) {
+        let now = Instant::now();
+        self.peers_retry_after.retain(|_, until| *until > now);
+        self.peers_retry_after.insert(peer_id, now + retry_after);
+    }
+
+    /// Returns true if `peer_id` asked for requests to be retried at a later time which has not
+    /// yet been reached.
+    pub fn is_rate_limited(&self, peer_id: &PeerId) -> bool {
+        self.peers_retry_after
+            .get(peer_id)
+            .is_some_and(|until| *until > Instant::now())
+    }
+
     pub fn send_sync_message(&mut self, sync_message: SyncMessage<T::EthSpec>) {
         self.network_beacon_processor
             .send_sync_message(sync_message);
@@ -728,6 +769,7 @@ impl<T: BeaconChainTypes> SyncNetworkContext<T> {
         &mut self,
         lookup_id: SingleLookupId,
         block_root: Hash256,
+        lookup_peers: Arc<RwLock<HashSet<PeerId>>>,
     ) -> Result<LookupRequestResult, RpcRequestSendError> {
         let custody_indexes_imported = self
             .chain
@@ -766,6 +808,7 @@ impl<T: BeaconChainTypes> SyncNetworkContext<T> {
             // TODO(das): req_id is duplicated here, also present in id
             CustodyId { requester, req_id },
             &custody_indexes_to_fetch,
+            lookup_peers,
             self.log.clone(),
         );
 
@@ -820,6 +863,43 @@ impl<T: BeaconChainTypes> SyncNetworkContext<T> {
             });
     }
 
+    /// Records that `peer_id` did not serve columns of `block_root` which it is assigned to custody
+    /// according to its ENR or metadata.
+    ///
+    /// Only a peer which claims to have imported the block, i.e. a peer of the block's lookup, is
+    /// at fault for missing columns and is penalised if `claims_block` is set. Other peers may not
+    /// have the block yet. The penalty is a high tolerance error, so only peers which persistently
+    /// contradict their advertised custody are banned.
+    pub fn report_custody_mismatch(
+        &self,
+        peer_id: PeerId,
+        block_root: Hash256,
+        column_indices: &[ColumnIndex],
+        request_type: &'static str,
+        claims_block: bool,
+    ) {
+        let advertised_columns = self
+            .network_globals()
+            .advertised_custody_columns(&peer_id, column_indices);
+        if advertised_columns.is_empty() {
+            return;
+        }
+
+        metrics::inc_counter_vec(&metrics::SYNC_CUSTODY_MISMATCHES, &[request_type]);
+        debug!(
+            self.log,
+            "Peer did not serve columns it advertises custody of";
+            "peer_id" => %peer_id,
+            "block_root" => ?block_root,
+            "column_indexes" => ?advertised_columns,
+            "request_type" => request_type,
+            "claims_block" => claims_block,
+        );
+        if claims_block {
+            self.report_peer(peer_id, PeerAction::HighToleranceError, "custody_mismatch");
+        }
+    }
+
     /// Subscribes to core topics.
     pub fn subscribe_core_topics(&self) {
         self.network_send
@@ -7,10 +7,15 @@ use fnv::FnvHashMap;
 use lighthouse_network::service::api_types::{CustodyId, DataColumnsByRootRequester};
 use lighthouse_network::PeerId;
 use lru_cache::LRUTimeCache;
+use parking_lot::RwLock;
 use rand::Rng;
 use slog::{debug, warn};
 use std::time::{Duration, Instant};
-use std::{collections::HashMap, marker::PhantomData, sync::Arc};
+use std::{
+    collections::{HashMap, HashSet},
+    marker::PhantomData,
+    sync::Arc,
+};
 use types::EthSpec;
 use types::{data_column_sidecar::ColumnIndex, DataColumnSidecar, Hash256};
 
@@ -32,6 +37,8 @@ pub struct ActiveCustodyRequest<T: BeaconChainTypes> {
     /// Peers that have recently failed to successfully respond to a columns by root request.
     /// Having a LRUTimeCache allows this request to not have to track disconnecting peers.
     failed_peers: LRUTimeCache<PeerId>,
+    /// Peers of the lookup, which claim to have imported the block and its custody columns.
+    lookup_peers: Arc<RwLock<HashSet<PeerId>>>,
     /// Logger for the `SyncNetworkContext`.
     pub log: slog::Logger,
     _phantom: PhantomData<T>,
@@ -64,6 +71,7 @@ impl<T: BeaconChainTypes> ActiveCustodyRequest<T> {
         block_root: Hash256,
         custody_id: CustodyId,
         column_indices: &[ColumnIndex],
+        lookup_peers: Arc<RwLock<HashSet<PeerId>>>,
         log: slog::Logger,
     ) -> Self {
         Self {
@@ -76,6 +84,7 @@ impl<T: BeaconChainTypes> ActiveCustodyRequest<T> {
             ),
             active_batch_columns_requests: <_>::default(),
             failed_peers: LRUTimeCache::new(Duration::from_secs(FAILED_PEERS_CACHE_EXPIRY_SECONDS)),
+            lookup_peers,
             log,
             _phantom: PhantomData,
         }
@@ -141,10 +150,8 @@ impl<T: BeaconChainTypes> ActiveCustodyRequest<T> {
                         // TODO(das) do not consider this case a success. We know for sure the block has
                         // data. However we allow the peer to return empty as we can't attribute fault.
                         // TODO(das): Should track which columns are missing and eventually give up
-                        // TODO(das): If the peer is in the lookup peer set it claims to have imported
-                        // the block AND its custody columns. So in this case we can downscore
                         column_request.on_download_error(req_id)?;
-                        missing_column_indexes.push(column_index);
+                        missing_column_indexes.push(*column_index);
                     }
                 }
 
@@ -163,6 +170,15 @@ impl<T: BeaconChainTypes> ActiveCustodyRequest<T> {
                         "missing_column_indexes" => ?missing_column_indexes
                     );
 
+                    // If the peer is in the lookup peer set it claims to have imported the block
+                    // AND its custody columns, so it can be penalised.
+                    cx.report_custody_mismatch(
+                        peer_id,
+                        self.block_root,
+                        &missing_column_indexes,
+                        "custody",
+                        self.lookup_peers.read().contains(&peer_id),
+                    );
                     self.failed_peers.insert(peer_id);
                 }
             }
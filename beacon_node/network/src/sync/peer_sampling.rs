@@ -261,7 +261,7 @@ impl<T: BeaconChainTypes> ActiveSamplingRequest<T> {
     /// - `Ok(None)`: Sampling request still active
     pub(crate) fn on_sample_downloaded(
         &mut self,
-        _peer_id: PeerId,
+        peer_id: PeerId,
         sampling_request_id: SamplingRequestId,
         resp: Result<(DataColumnSidecarList<T::EthSpec>, Duration), RpcResponseError>,
         cx: &mut SyncNetworkContext<T>,
@@ -298,6 +298,7 @@ impl<T: BeaconChainTypes> ActiveSamplingRequest<T> {
 
                 // Filter the data received in the response using the requested column indexes.
                 let mut data_columns = vec![];
+                let mut missing_column_indexes = vec![];
                 for column_index in column_indexes {
                     let Some(request) = self.column_requests.get_mut(column_index) else {
                         warn!(self.log,
@@ -320,12 +321,24 @@ impl<T: BeaconChainTypes> ActiveSamplingRequest<T> {
                             "column_index" => column_index
                         );
                         request.on_sampling_error()?;
+                        missing_column_indexes.push(*column_index);
                         continue;
                     };
 
                     data_columns.push(resp_data_columns.swap_remove(data_pos));
                 }
 
+                if !missing_column_indexes.is_empty() {
+                    // Sampled peers are chosen by custody alone and may not have the block yet.
+                    cx.report_custody_mismatch(
+                        peer_id,
+                        self.block_root,
+                        &missing_column_indexes,
+                        "sampling",
+                        false,
+                    );
+                }
+
                 if !resp_data_columns.is_empty() {
                     let resp_column_indexes = resp_data_columns
                         .iter()
@@ -1,7 +1,7 @@
 use beacon_chain::block_verification_types::{AsBlock, RpcBlock};
 use lighthouse_network::rpc::methods::BlocksByRangeRequest;
 use lighthouse_network::service::api_types::Id;
-use lighthouse_network::PeerId;
+use lighthouse_network::{PeerDB, PeerId, RangeData};
 use std::collections::HashSet;
 use std::hash::{Hash, Hasher};
 use std::ops::Sub;
@@ -105,6 +105,8 @@ pub struct BatchInfo<E: EthSpec, B: BatchConfig = RangeSyncBatchConfig> {
     non_faulty_processing_attempts: u8,
     /// The number of download retries this batch has undergone due to a failed request.
     failed_download_attempts: Vec<PeerId>,
+    /// Peers that have rate limited, or been unable to serve, a download request for this batch.
+    rate_limited_peers: HashSet<PeerId>,
     /// State of the batch.
     state: BatchState<E>,
     /// Whether this batch contains all blocks or all blocks and blobs.
@@ -165,6 +167,7 @@ impl<E: EthSpec, B: BatchConfig> BatchInfo<E, B> {
             end_slot,
             failed_processing_attempts: Vec::new(),
             failed_download_attempts: Vec::new(),
+            rate_limited_peers: HashSet::new(),
             non_faulty_processing_attempts: 0,
             state: BatchState::AwaitingDownload,
             batch_type,
@@ -173,11 +176,10 @@ impl<E: EthSpec, B: BatchConfig> BatchInfo<E, B> {
     }
 
     /// Gives a list of peers from which this batch has had a failed download or processing
-    /// attempt.
+    /// attempt, or that have rate limited a download of this batch.
     pub fn failed_peers(&self) -> HashSet<PeerId> {
-        let mut peers = HashSet::with_capacity(
-            self.failed_processing_attempts.len() + self.failed_download_attempts.len(),
-        );
+        let mut peers = self.rate_limited_peers.clone();
+        peers.reserve(self.failed_processing_attempts.len() + self.failed_download_attempts.len());
 
         for attempt in &self.failed_processing_attempts {
             peers.insert(attempt.peer_id);
@@ -243,6 +245,29 @@ impl<E: EthSpec, B: BatchConfig> BatchInfo<E, B> {
         )
     }
 
+    /// Returns `false` if `peer_id` is known to have pruned, or not yet backfilled, the range of
+    /// this batch.
+    pub fn can_be_served_by(&self, peers: &PeerDB<E>, peer_id: &PeerId) -> bool {
+        peers.can_serve_range(peer_id, self.start_slot, self.range_data())
+    }
+
+    /// Records in `peers` that `peer_id` was unable to serve the range of this batch, so that its
+    /// data is assumed to start at the end of the batch.
+    ///
+    /// For batches with data columns the error may have concerned either the blocks or the
+    /// columns. It is recorded against the columns, which are pruned before blocks.
+    pub fn record_unavailable(&self, peers: &mut PeerDB<E>, peer_id: &PeerId) {
+        peers.update_earliest_available_slot(peer_id, self.end_slot, self.range_data());
+    }
+
+    fn range_data(&self) -> RangeData {
+        match self.batch_type {
+            ByRangeRequestType::Blocks => RangeData::Blocks,
+            ByRangeRequestType::BlocksAndBlobs => RangeData::Blobs,
+            ByRangeRequestType::BlocksAndColumns => RangeData::Columns,
+        }
+    }
+
     /// After different operations over a batch, this could be in a state that allows it to
     /// continue, or in failed state. When the batch has failed, we check if it did mainly due to
     /// processing failures. In this case the batch is considered failed and faulty.
@@ -359,6 +384,35 @@ impl<E: EthSpec, B: BatchConfig> BatchInfo<E, B> {
         }
     }
 
+    /// Marks the download of this batch as rate limited by the peer serving it.
+    ///
+    /// The first time a peer rate limits this batch the attempt is not counted as failed, but the
+    /// peer is avoided when retrying the batch. Further rate limits from the same peer count as
+    /// failed attempts.
+    #[must_use = "Batch may have failed"]
+    pub fn download_rate_limited(&mut self) -> Result<BatchOperationOutcome, WrongState> {
+        self.download_deferred()
+    }
+
+    /// Marks the download of this batch as unavailable from the peer serving it, because the peer
+    /// has pruned or not yet backfilled the range.
+    ///
+    /// As with rate limits, the first such response from a peer is not counted as a failed
+    /// attempt since the peer is avoided when retrying the batch.
+    #[must_use = "Batch may have failed"]
+    pub fn download_unavailable(&mut self) -> Result<BatchOperationOutcome, WrongState> {
+        self.download_deferred()
+    }
+
+    fn download_deferred(&mut self) -> Result<BatchOperationOutcome, WrongState> {
+        let mark_failed = match &self.state {
+            BatchState::Downloading(peer, _) => !self.rate_limited_peers.insert(*peer),
+            // `download_failed` reports the wrong state
+            _ => true,
+        };
+        self.download_failed(mark_failed)
+    }
+
     pub fn start_downloading_from_peer(
         &mut self,
         peer: PeerId,
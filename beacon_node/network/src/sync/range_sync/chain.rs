@@ -8,6 +8,7 @@ use crate::sync::{network_context::SyncNetworkContext, BatchOperationOutcome, Ba
 use beacon_chain::block_verification_types::RpcBlock;
 use beacon_chain::BeaconChainTypes;
 use fnv::FnvHashMap;
+use lighthouse_network::rpc::RpcErrorCause;
 use lighthouse_network::service::api_types::Id;
 use lighthouse_network::{PeerAction, PeerId};
 use metrics::set_int_gauge;
@@ -866,6 +867,7 @@ impl<T: BeaconChainTypes> SyncingChain<T> {
         batch_id: BatchId,
         peer_id: &PeerId,
         request_id: Id,
+        cause: RpcErrorCause,
     ) -> ProcessingResult {
         let batch_state = self.visualize_batch_state();
         if let Some(batch) = self.batches.get_mut(&batch_id) {
@@ -893,12 +895,30 @@ impl<T: BeaconChainTypes> SyncingChain<T> {
                 "batch_state" => ?batch.state(),
                 "peer_id" => %peer_id,
                 "request_id" => %request_id,
+                "cause" => ?cause,
                 "batch_state" => batch_state
             );
             if let Some(active_requests) = self.peers.get_mut(peer_id) {
                 active_requests.remove(&batch_id);
             }
-            if let BatchOperationOutcome::Failed { blacklist } = batch.download_failed(true)? {
+            let outcome = match cause {
+                RpcErrorCause::RateLimited => batch.download_rate_limited()?,
+                RpcErrorCause::ResourceUnavailable => {
+                    batch.record_unavailable(&mut network.network_globals().peers.write(), peer_id);
+                    // Unlike backfill, range sync only requests blocks which the peer claims to
+                    // have in its status and data within the availability window, so the peer
+                    // should be able to serve them. Penalise it so that a peer which keeps
+                    // failing these requests can't stall sync.
+                    network.report_peer(
+                        *peer_id,
+                        PeerAction::MidToleranceError,
+                        "range_resource_unavailable",
+                    );
+                    batch.download_unavailable()?
+                }
+                _ => batch.download_failed(true)?,
+            };
+            if let BatchOperationOutcome::Failed { blacklist } = outcome {
                 return Err(RemoveChain::ChainFailed {
                     blacklist,
                     failing_batch: batch_id,
@@ -932,20 +952,26 @@ impl<T: BeaconChainTypes> SyncingChain<T> {
         // Find a peer to request the batch
         let failed_peers = batch.failed_peers();
 
+        let peer_db = network.network_globals().peers.read();
         let new_peer = self
             .peers
             .iter()
             .map(|(peer, requests)| {
                 (
+                    !batch.can_be_served_by(&peer_db, peer),
+                    network.is_rate_limited(peer),
                     failed_peers.contains(peer),
                     requests.len(),
                     rand::thread_rng().gen::<u32>(),
                     *peer,
                 )
             })
-            // Sort peers prioritizing unrelated peers with less active requests.
+            // Sort peers prioritizing peers which have not pruned the batch's range, then peers
+            // which have not asked us to retry later, then unrelated peers with less active
+            // requests.
             .min()
-            .map(|(_, _, _, peer)| peer);
+            .map(|(_, _, _, _, _, peer)| peer);
+        drop(peer_db);
 
         if let Some(peer) = new_peer {
             self.send_batch(network, batch_id, peer)
@@ -49,7 +49,7 @@ use crate::sync::network_context::SyncNetworkContext;
 use crate::sync::BatchProcessResult;
 use beacon_chain::block_verification_types::RpcBlock;
 use beacon_chain::{BeaconChain, BeaconChainTypes};
-use lighthouse_network::rpc::GoodbyeReason;
+use lighthouse_network::rpc::{GoodbyeReason, RpcErrorCause};
 use lighthouse_network::service::api_types::Id;
 use lighthouse_network::{PeerId, SyncInfo};
 use lru_cache::LRUTimeCache;
@@ -303,10 +303,11 @@ where
         batch_id: BatchId,
         chain_id: ChainId,
         request_id: Id,
+        cause: RpcErrorCause,
     ) {
         // check that this request is pending
         match self.chains.call_by_id(chain_id, |chain| {
-            chain.inject_error(network, batch_id, &peer_id, request_id)
+            chain.inject_error(network, batch_id, &peer_id, request_id, cause)
         }) {
             Ok((removed_chain, sync_type)) => {
                 if let Some((removed_chain, remove_reason)) = removed_chain {
@@ -391,6 +392,7 @@ mod tests {
 
     use super::*;
     use crate::sync::network_context::{BlockOrBlob, RangeRequestId};
+    use crate::sync::range_sync::{ByRangeRequestType, EPOCHS_PER_BATCH};
     use beacon_chain::builder::Witness;
     use beacon_chain::eth1_chain::CachingEth1Backend;
     use beacon_chain::parking_lot::RwLock;
@@ -400,10 +402,12 @@ mod tests {
     use lighthouse_network::service::api_types::SyncRequestId;
     use lighthouse_network::{
         rpc::StatusMessage, service::api_types::AppRequestId, NetworkConfig, NetworkGlobals,
+        PeerAction,
     };
     use slog::{o, Drain};
     use slot_clock::TestingSlotClock;
     use std::collections::HashSet;
+    use std::time::Duration;
     use store::MemoryStore;
     use tokio::sync::mpsc;
     use types::{FixedBytesExtended, ForkName, MinimalEthSpec as E};
@@ -794,6 +798,123 @@ mod tests {
         range.add_peer(&mut rig.cx, local_info, finalized_peer, remote_info);
     }
 
+    #[test]
+    fn unavailable_range_is_recorded_and_retried_elsewhere() {
+        let (mut rig, mut range) = range(false);
+        let fork = rig
+            .cx
+            .chain
+            .spec
+            .fork_name_at_epoch(rig.cx.chain.epoch().unwrap());
+        let spec = rig.cx.chain.spec.clone();
+        let peer1 = rig
+            .globals
+            .peers
+            .write()
+            .__add_connected_peer_testing_only(false, &spec);
+        let peer2 = rig
+            .globals
+            .peers
+            .write()
+            .__add_connected_peer_testing_only(false, &spec);
+
+        let (_, local_info, head_info) = rig.head_peer();
+        range.add_peer(&mut rig.cx, local_info.clone(), peer1, head_info.clone());
+        let (block_req, _) = rig.grab_request(&peer1, fork);
+        range.add_peer(&mut rig.cx, local_info, peer2, head_info);
+
+        // The first peer has pruned the range of the batch.
+        let AppRequestId::Sync(SyncRequestId::RangeBlockAndBlobs { id }) = block_req else {
+            panic!("unexpected request {:?}", block_req);
+        };
+        let (chain_id, batch_id) =
+            TestRig::unwrap_range_request_id(rig.cx.range_request_failed(id).unwrap());
+        range.inject_error(
+            &mut rig.cx,
+            peer1,
+            batch_id,
+            chain_id,
+            id,
+            RpcErrorCause::ResourceUnavailable,
+        );
+
+        // The batch's range is recorded against the peer.
+        let batch_end = (batch_id + EPOCHS_PER_BATCH).start_slot(E::slots_per_epoch());
+        let peers = rig.globals.peers.read();
+        let peer_info = peers.peer_info(&peer1).unwrap();
+        let (blocks, data) = match rig.cx.batch_type(batch_id) {
+            ByRangeRequestType::Blocks => (Some(batch_end), None),
+            ByRangeRequestType::BlocksAndBlobs => (None, peer_info.earliest_available_blob_slot()),
+            ByRangeRequestType::BlocksAndColumns => {
+                (None, peer_info.earliest_available_column_slot())
+            }
+        };
+        assert_eq!(peer_info.earliest_available_slot(), blocks);
+        if blocks.is_none() {
+            assert_eq!(data, Some(batch_end));
+        }
+        drop(peers);
+
+        // The peer is penalised, and the batch is requested from the other peer.
+        match rig.network_rx.try_recv() {
+            Ok(NetworkMessage::ReportPeer {
+                peer_id, action, ..
+            }) => {
+                assert_eq!(peer_id, peer1);
+                assert_eq!(action, PeerAction::MidToleranceError);
+            }
+            other => panic!("expected the peer to be reported, got {:?}", other),
+        }
+        rig.grab_request(&peer2, fork);
+    }
+
+    #[test]
+    fn rate_limited_batch_is_retried_from_another_peer() {
+        let (mut rig, mut range) = range(false);
+        let fork = rig
+            .cx
+            .chain
+            .spec
+            .fork_name_at_epoch(rig.cx.chain.epoch().unwrap());
+        let spec = rig.cx.chain.spec.clone();
+        let peer1 = rig
+            .globals
+            .peers
+            .write()
+            .__add_connected_peer_testing_only(false, &spec);
+        let peer2 = rig
+            .globals
+            .peers
+            .write()
+            .__add_connected_peer_testing_only(false, &spec);
+
+        let (_, local_info, head_info) = rig.head_peer();
+        range.add_peer(&mut rig.cx, local_info.clone(), peer1, head_info.clone());
+        let (block_req, _) = rig.grab_request(&peer1, fork);
+        range.add_peer(&mut rig.cx, local_info, peer2, head_info);
+
+        // The first peer truncates the response and asks us to retry later.
+        let AppRequestId::Sync(SyncRequestId::RangeBlockAndBlobs { id }) = block_req else {
+            panic!("unexpected request {:?}", block_req);
+        };
+        let (chain_id, batch_id) =
+            TestRig::unwrap_range_request_id(rig.cx.range_request_failed(id).unwrap());
+        rig.cx.on_retry_after(peer1, Duration::from_secs(10));
+        range.inject_error(
+            &mut rig.cx,
+            peer1,
+            batch_id,
+            chain_id,
+            id,
+            RpcErrorCause::RateLimited,
+        );
+        assert!(rig.cx.is_rate_limited(&peer1));
+        assert!(!rig.cx.is_rate_limited(&peer2));
+
+        // The batch is requested from the other peer.
+        rig.grab_request(&peer2, fork);
+    }
+
     #[test]
     fn pause_and_resume_on_ee_offline() {
         let (mut rig, mut range) = range(true);
@@ -891,6 +891,22 @@ impl TestRig {
             .unwrap_or_else(|e| panic!("Expected block request for {for_block:?}: {e}"))
     }
 
+    #[track_caller]
+    fn expect_block_lookup_request_to_peer(
+        &mut self,
+        for_block: Hash256,
+    ) -> (SingleLookupReqId, PeerId) {
+        self.pop_received_network_event(|ev| match ev {
+            NetworkMessage::SendRequest {
+                peer_id,
+                request: RequestType::BlocksByRoot(request),
+                request_id: AppRequestId::Sync(SyncRequestId::SingleBlock { id }),
+            } if request.block_roots().to_vec().contains(&for_block) => Some((*id, *peer_id)),
+            _ => None,
+        })
+        .unwrap_or_else(|e| panic!("Expected block request for {for_block:?}: {e}"))
+    }
+
     fn find_blob_lookup_request(
         &mut self,
         for_block: Hash256,
@@ -1420,6 +1436,55 @@ fn test_single_block_lookup_failure() {
     rig.expect_empty_network();
 }
 
+#[test]
+fn test_single_block_lookup_rate_limited_switches_peer() {
+    let mut rig = TestRig::test_setup();
+
+    let block_hash = Hash256::random();
+    let peer_a = rig.new_connected_peer();
+    let peer_b = rig.new_connected_peer();
+
+    // Trigger the request from the first peer, then learn about the second peer.
+    rig.trigger_unknown_block_from_attestation(block_hash, peer_a);
+    let (id, peer_id) = rig.expect_block_lookup_request_to_peer(block_hash);
+    assert_eq!(peer_id, peer_a);
+    rig.trigger_unknown_block_from_attestation(block_hash, peer_b);
+
+    // The first peer rate limits us. The request should be retried with the other peer.
+    rig.single_lookup_failed(
+        id,
+        peer_a,
+        RPCError::ErrorResponse(RpcErrorResponse::RateLimited, "rate limited".into()),
+    );
+    let (_, peer_id) = rig.expect_block_lookup_request_to_peer(block_hash);
+    assert_eq!(peer_id, peer_b);
+    rig.expect_empty_network();
+}
+
+#[test]
+fn test_single_block_lookup_rate_limited_not_counted() {
+    let mut rig = TestRig::test_setup();
+
+    let block_hash = Hash256::random();
+    let peer_id = rig.new_connected_peer();
+
+    // Trigger the request
+    rig.trigger_unknown_block_from_attestation(block_hash, peer_id);
+    let id = rig.expect_block_lookup_request(block_hash);
+
+    // The first rate limit from a peer does not count as a failed attempt.
+    rig.single_lookup_failed(
+        id,
+        peer_id,
+        RPCError::ErrorResponse(RpcErrorResponse::RateLimited, "rate limited".into()),
+    );
+    for _ in 0..SINGLE_BLOCK_LOOKUP_MAX_ATTEMPTS {
+        let id = rig.expect_block_lookup_request(block_hash);
+        rig.single_lookup_failed(id, peer_id, RPCError::StreamTimeout);
+    }
+    rig.expect_no_active_lookups_empty_network();
+}
+
 #[test]
 fn test_single_block_lookup_peer_disconnected_then_rpc_error() {
     let mut rig = TestRig::test_setup();
@@ -2068,6 +2133,31 @@ fn sampling_avoid_retrying_same_peer() {
     r.expect_empty_network();
 }
 
+#[test]
+fn sampling_no_penalty_for_missing_advertised_custody() {
+    let Some(mut r) = TestRig::test_setup_after_peerdas() else {
+        return;
+    };
+    let peer_id = r.new_connected_supernode_peer();
+    let (block, _) = r.rand_block_and_data_columns();
+    let block_root = block.canonical_root();
+    r.trigger_sample_block(block_root, block.slot());
+    // The sample request to the only peer is batched
+    let (request_id, _) = r
+        .expect_only_data_columns_by_root_requests(block_root, 1)
+        .pop()
+        .unwrap();
+    // The supernode advertises custody of every column but returns none of them. It never
+    // claimed to have imported the block, so it may just not have it yet.
+    r.send_sync_message(SyncMessage::RpcDataColumn {
+        request_id,
+        peer_id,
+        data_column: None,
+        seen_timestamp: timestamp_now(),
+    });
+    r.expect_no_penalty_for(peer_id);
+}
+
 #[test]
 fn sampling_batch_requests() {
     let Some(mut r) = TestRig::test_setup_after_peerdas() else {
@@ -2161,6 +2251,35 @@ fn custody_lookup_happy_path() {
     r.expect_no_active_lookups();
 }
 
+#[test]
+fn custody_lookup_penalize_lookup_peer_missing_advertised_custody() {
+    let Some(mut r) = TestRig::test_setup_after_peerdas() else {
+        return;
+    };
+    let spec = E::default_spec();
+    // The only peer is a supernode, which claims to have imported the block by attesting to it.
+    let peer_id = r.new_connected_supernode_peer();
+    let (block, _) = r.rand_block_and_data_columns();
+    let block_root = block.canonical_root();
+    r.trigger_unknown_block_from_attestation(block_root, peer_id);
+    let id = r.expect_block_lookup_request(block_root);
+    r.complete_valid_block_request(id, block.into(), true);
+    // All custody columns are requested from the supernode in a single request
+    let sample_column_count = spec.samples_per_slot * spec.data_columns_per_subnet() as u64;
+    let (request_id, _) = r
+        .expect_only_data_columns_by_root_requests(block_root, sample_column_count as usize)
+        .pop()
+        .unwrap();
+    // The supernode returns none of the columns it advertises custody of
+    r.send_sync_message(SyncMessage::RpcDataColumn {
+        request_id,
+        peer_id,
+        data_column: None,
+        seen_timestamp: timestamp_now(),
+    });
+    r.expect_single_penalty(peer_id, "custody_mismatch");
+}
+
 // TODO(das): Test retries of DataColumnByRoot:
 // - Expect request for column_index
 // - Respond with bad data
@@ -21,7 +21,10 @@ pub struct CompactAttestationData {
     pub target_root: Hash256,
 }
 
-#[superstruct(variants(Base, Electra), variant_attributes(derive(Debug, PartialEq,)))]
+#[superstruct(
+    variants(Base, Electra),
+    variant_attributes(derive(Debug, Clone, PartialEq,))
+)]
 #[derive(Debug, PartialEq)]
 pub struct CompactIndexedAttestation<E: EthSpec> {
     pub attesting_indices: Vec<u64>,
@@ -328,84 +331,68 @@ impl<E: EthSpec> AttestationMap<E> {
         }
     }
 
-    /// Aggregate Electra attestations for the same attestation data signed by different
-    /// committees.
+    /// Compute on-chain aggregates for the Electra attestations matching `checkpoint_key`, by
+    /// aggregating the best attestation from each committee which attested to the same data.
     ///
-    /// Non-Electra attestations are left as-is.
-    pub fn aggregate_across_committees(&mut self, checkpoint_key: CheckpointKey) {
-        let Some(attestation_map) = self.checkpoint_map.get_mut(&checkpoint_key) else {
-            return;
+    /// The attestations in the map are left as-is, so that they can continue to be aggregated
+    /// with attestations from the same committee as they arrive. Non-Electra attestations and
+    /// aggregates which already cover multiple committees are ignored.
+    pub fn get_on_chain_aggregates(
+        &self,
+        checkpoint_key: &CheckpointKey,
+    ) -> Vec<(&CompactAttestationData, CompactIndexedAttestation<E>)> {
+        let Some(attestation_map) = self.checkpoint_map.get(checkpoint_key) else {
+            return vec![];
         };
-        for compact_indexed_attestations in attestation_map.attestations.values_mut() {
-            let unaggregated_attestations = std::mem::take(compact_indexed_attestations);
-            let mut aggregated_attestations: Vec<CompactIndexedAttestation<E>> = vec![];
-
-            // Aggregate the best attestations for each committee and leave the rest.
-            let mut best_attestations_by_committee: BTreeMap<
-                u64,
-                CompactIndexedAttestationElectra<E>,
-            > = BTreeMap::new();
-
-            for committee_attestation in unaggregated_attestations {
-                let mut electra_attestation = match committee_attestation {
-                    CompactIndexedAttestation::Electra(att)
-                        if att.committee_bits.num_set_bits() == 1 =>
-                    {
-                        att
-                    }
-                    CompactIndexedAttestation::Electra(att) => {
-                        // Aggregate already covers multiple committees, leave it as-is.
-                        aggregated_attestations.push(CompactIndexedAttestation::Electra(att));
+        attestation_map
+            .attestations
+            .iter()
+            .filter_map(|(data, attestations)| {
+                // Find the best (most aggregation bits) attestation for each committee.
+                let mut best_attestations_by_committee: BTreeMap<
+                    u64,
+                    &CompactIndexedAttestationElectra<E>,
+                > = BTreeMap::new();
+                for attestation in attestations {
+                    let CompactIndexedAttestation::Electra(attestation) = attestation else {
                         continue;
-                    }
-                    CompactIndexedAttestation::Base(att) => {
-                        // Leave as-is.
-                        aggregated_attestations.push(CompactIndexedAttestation::Base(att));
+                    };
+                    if attestation.committee_bits.num_set_bits() != 1 {
                         continue;
                     }
-                };
-                if let Some(committee_index) = electra_attestation.committee_index() {
-                    if let Some(existing_attestation) =
-                        best_attestations_by_committee.get_mut(&committee_index)
-                    {
-                        // Search for the best (most aggregation bits) attestation for this committee
-                        // index.
-                        if electra_attestation.aggregation_bits.num_set_bits()
-                            > existing_attestation.aggregation_bits.num_set_bits()
-                        {
-                            // New attestation is better than the previously known one for this
-                            // committee. Replace it.
-                            std::mem::swap(existing_attestation, &mut electra_attestation);
-                        }
-                        // Put the inferior attestation into the list of aggregated attestations
-                        // without performing any cross-committee aggregation.
-                        aggregated_attestations
-                            .push(CompactIndexedAttestation::Electra(electra_attestation));
-                    } else {
-                        // First attestation seen for this committee. Place it in the map
-                        // provisionally.
-                        best_attestations_by_committee.insert(committee_index, electra_attestation);
-                    }
+                    let Some(committee_index) = attestation.committee_index() else {
+                        continue;
+                    };
+                    best_attestations_by_committee
+                        .entry(committee_index)
+                        .and_modify(|best| {
+                            if attestation.aggregation_bits.num_set_bits()
+                                > best.aggregation_bits.num_set_bits()
+                            {
+                                *best = attestation;
+                            }
+                        })
+                        .or_insert(attestation);
                 }
-            }
 
-            if let Some(on_chain_aggregate) =
-                Self::compute_on_chain_aggregate(best_attestations_by_committee)
-            {
-                aggregated_attestations
-                    .push(CompactIndexedAttestation::Electra(on_chain_aggregate));
-            }
-
-            *compact_indexed_attestations = aggregated_attestations;
-        }
+                // A single committee is already represented by its own attestations.
+                if best_attestations_by_committee.len() < 2 {
+                    return None;
+                }
+                let on_chain_aggregate =
+                    Self::compute_on_chain_aggregate(best_attestations_by_committee)?;
+                Some((data, CompactIndexedAttestation::Electra(on_chain_aggregate)))
+            })
+            .collect()
     }
 
     pub fn compute_on_chain_aggregate(
-        mut attestations_by_committee: BTreeMap<u64, CompactIndexedAttestationElectra<E>>,
+        attestations_by_committee: BTreeMap<u64, &CompactIndexedAttestationElectra<E>>,
     ) -> Option<CompactIndexedAttestationElectra<E>> {
-        let (_, mut on_chain_aggregate) = attestations_by_committee.pop_first()?;
-        for (_, attestation) in attestations_by_committee {
-            on_chain_aggregate.aggregate_with_disjoint_committees(&attestation);
+        let mut attestations = attestations_by_committee.into_values();
+        let mut on_chain_aggregate = attestations.next()?.clone();
+        for attestation in attestations {
+            on_chain_aggregate.aggregate_with_disjoint_committees(attestation);
         }
         Some(on_chain_aggregate)
     }
@@ -0,0 +1,271 @@
+//! Tracks how long valid exits, BLS to execution changes and slashings remain in the pool without
+//! being included in a block, in order to detect censorship by block proposers.
+use std::collections::{HashMap, HashSet};
+use types::{Hash256, Slot};
+
+/// The number of blocks which must omit an operation, despite having room for it, before it is
+/// reported as possibly censored.
+pub const CENSORSHIP_OMITTED_BLOCKS_THRESHOLD: u64 = 4;
+
+/// Identifies an operation which is awaiting inclusion in a block.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum PendingOperation {
+    /// A proposer slashing, identified by the proposer index.
+    ProposerSlashing(u64),
+    /// An attester slashing, identified by its tree hash root.
+    AttesterSlashing(Hash256),
+    /// A voluntary exit, identified by the exiting validator index.
+    VoluntaryExit(u64),
+    /// A BLS to execution change, identified by the validator index.
+    BlsToExecutionChange(u64),
+}
+
+/// The inclusion history of a pending operation.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PendingOperationRecord {
+    /// The slot of the first block at which the operation was observed to be pending.
+    pub first_seen_slot: Slot,
+    /// The number of blocks which omitted the operation despite having room for it.
+    pub blocks_omitted: u64,
+    /// The subset of `blocks_omitted` which were proposed by local validators.
+    pub local_blocks_omitted: u64,
+}
+
+impl PendingOperationRecord {
+    /// Returns `true` if enough blocks have omitted the operation to suspect censorship.
+    pub fn is_suspected_censorship(&self) -> bool {
+        self.blocks_omitted >= CENSORSHIP_OMITTED_BLOCKS_THRESHOLD
+    }
+}
+
+/// The blocks which omitted a pending operation.
+///
+/// Blocks are keyed by root so that a block which becomes the head more than once, e.g. after a
+/// re-org, is only counted once.
+#[derive(Debug)]
+struct OmittingBlocks {
+    first_seen_slot: Slot,
+    /// Whether each block was proposed by a local validator.
+    blocks: HashMap<Hash256, bool>,
+}
+
+impl OmittingBlocks {
+    fn record(&self) -> PendingOperationRecord {
+        PendingOperationRecord {
+            first_seen_slot: self.first_seen_slot,
+            blocks_omitted: self.blocks.len() as u64,
+            local_blocks_omitted: self.blocks.values().filter(|is_local| **is_local).count() as u64,
+        }
+    }
+}
+
+/// Records the pending operations omitted by each new head block.
+#[derive(Debug, Default)]
+pub struct CensorshipWatchdog {
+    records: HashMap<PendingOperation, OmittingBlocks>,
+}
+
+impl CensorshipWatchdog {
+    /// Observe the block with `block_root` at `slot` whilst the operations in `pending` were valid
+    /// for inclusion.
+    ///
+    /// Each pending operation is paired with a flag indicating whether the block had room for
+    /// another operation of its kind. Operations in `included` were included by the block and are
+    /// not counted as omitted. Observing the same block again does not count it twice. Records for
+    /// operations which are no longer pending are dropped.
+    pub fn observe_block(
+        &mut self,
+        block_root: Hash256,
+        slot: Slot,
+        pending: impl IntoIterator<Item = (PendingOperation, bool)>,
+        included: &HashSet<PendingOperation>,
+        is_local: bool,
+    ) {
+        let mut still_pending = HashSet::new();
+
+        for (operation, block_had_room) in pending {
+            if included.contains(&operation) {
+                continue;
+            }
+            still_pending.insert(operation);
+
+            let omitting_blocks = self
+                .records
+                .entry(operation)
+                .or_insert_with(|| OmittingBlocks {
+                    first_seen_slot: slot,
+                    blocks: HashMap::new(),
+                });
+            if block_had_room {
+                omitting_blocks.blocks.insert(block_root, is_local);
+            }
+        }
+
+        self.records
+            .retain(|operation, _| still_pending.contains(operation));
+    }
+
+    /// Returns the slot at which `operation` was first observed to be pending, if any.
+    pub fn first_seen_slot(&self, operation: &PendingOperation) -> Option<Slot> {
+        self.records
+            .get(operation)
+            .map(|omitting_blocks| omitting_blocks.first_seen_slot)
+    }
+
+    /// Returns all pending operations, oldest first.
+    pub fn records(&self) -> Vec<(PendingOperation, PendingOperationRecord)> {
+        let mut records = self
+            .records
+            .iter()
+            .map(|(operation, omitting_blocks)| (*operation, omitting_blocks.record()))
+            .collect::<Vec<_>>();
+        records.sort_by_key(|(_, record)| record.first_seen_slot);
+        records
+    }
+}
+
+/// Order `operations` so that those which have been pending the longest come first.
+///
+/// Operations which have not yet been observed by the watchdog are considered the newest, and the
+/// existing order is otherwise preserved.
+pub fn oldest_first<T>(
+    operations: impl IntoIterator<Item = T>,
+    watchdog: &CensorshipWatchdog,
+    id: impl Fn(&T) -> PendingOperation,
+) -> Vec<T> {
+    let mut operations = operations.into_iter().collect::<Vec<_>>();
+    operations.sort_by_key(|op| {
+        watchdog
+            .first_seen_slot(&id(op))
+            .map_or(u64::MAX, Slot::as_u64)
+    });
+    operations
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn omitted_operations_are_counted() {
+        let mut watchdog = CensorshipWatchdog::default();
+        let exit = PendingOperation::VoluntaryExit(1);
+        let change = PendingOperation::BlsToExecutionChange(2);
+
+        watchdog.observe_block(
+            Hash256::repeat_byte(10),
+            Slot::new(10),
+            [(exit, true), (change, false)],
+            &HashSet::new(),
+            false,
+        );
+        watchdog.observe_block(
+            Hash256::repeat_byte(11),
+            Slot::new(11),
+            [(exit, true), (change, true)],
+            &HashSet::new(),
+            true,
+        );
+
+        let records = watchdog.records().into_iter().collect::<HashMap<_, _>>();
+        assert_eq!(
+            records[&exit],
+            PendingOperationRecord {
+                first_seen_slot: Slot::new(10),
+                blocks_omitted: 2,
+                local_blocks_omitted: 1,
+            }
+        );
+        assert_eq!(
+            records[&change],
+            PendingOperationRecord {
+                first_seen_slot: Slot::new(10),
+                blocks_omitted: 1,
+                local_blocks_omitted: 1,
+            }
+        );
+    }
+
+    #[test]
+    fn included_operations_are_dropped() {
+        let mut watchdog = CensorshipWatchdog::default();
+        let exit = PendingOperation::VoluntaryExit(1);
+        let slashing = PendingOperation::ProposerSlashing(3);
+
+        watchdog.observe_block(
+            Hash256::repeat_byte(10),
+            Slot::new(10),
+            [(exit, true), (slashing, true)],
+            &HashSet::new(),
+            false,
+        );
+        watchdog.observe_block(
+            Hash256::repeat_byte(11),
+            Slot::new(11),
+            [(exit, true), (slashing, true)],
+            &[exit].into_iter().collect(),
+            false,
+        );
+
+        assert_eq!(watchdog.first_seen_slot(&exit), None);
+        assert_eq!(watchdog.first_seen_slot(&slashing), Some(Slot::new(10)));
+    }
+
+    #[test]
+    fn re_observed_blocks_are_counted_once() {
+        let mut watchdog = CensorshipWatchdog::default();
+        let exit = PendingOperation::VoluntaryExit(1);
+        let block_a = Hash256::repeat_byte(0xa);
+        let block_b = Hash256::repeat_byte(0xb);
+
+        // The head moves from block A to a competing block B, and is then re-orged back to A.
+        for (block_root, is_local) in [(block_a, true), (block_b, false), (block_a, true)] {
+            watchdog.observe_block(
+                block_root,
+                Slot::new(10),
+                [(exit, true)],
+                &HashSet::new(),
+                is_local,
+            );
+        }
+
+        assert_eq!(
+            watchdog.records(),
+            vec![(
+                exit,
+                PendingOperationRecord {
+                    first_seen_slot: Slot::new(10),
+                    blocks_omitted: 2,
+                    local_blocks_omitted: 1,
+                }
+            )]
+        );
+    }
+
+    #[test]
+    fn oldest_operations_are_ordered_first() {
+        let mut watchdog = CensorshipWatchdog::default();
+        watchdog.observe_block(
+            Hash256::repeat_byte(10),
+            Slot::new(10),
+            [(PendingOperation::VoluntaryExit(3), true)],
+            &HashSet::new(),
+            false,
+        );
+        watchdog.observe_block(
+            Hash256::repeat_byte(11),
+            Slot::new(11),
+            [
+                (PendingOperation::VoluntaryExit(3), true),
+                (PendingOperation::VoluntaryExit(2), true),
+            ],
+            &HashSet::new(),
+            false,
+        );
+
+        let ordered = oldest_first(vec![1, 2, 3], &watchdog, |index| {
+            PendingOperation::VoluntaryExit(*index)
+        });
+        assert_eq!(ordered, vec![3, 2, 1]);
+    }
+}
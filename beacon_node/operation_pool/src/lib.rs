@@ -2,6 +2,7 @@ mod attestation;
 mod attestation_storage;
 mod attester_slashing;
 mod bls_to_execution_changes;
+mod censorship;
 mod max_cover;
 mod metrics;
 mod persistence;
@@ -9,6 +10,9 @@ mod reward_cache;
 mod sync_aggregate_id;
 
 pub use crate::bls_to_execution_changes::ReceivedPreCapella;
+pub use crate::censorship::{
+    PendingOperation, PendingOperationRecord, CENSORSHIP_OMITTED_BLOCKS_THRESHOLD,
+};
 pub use attestation::{earliest_attestation_validators, AttMaxCover};
 pub use attestation_storage::{CompactAttestationRef, SplitAttestation};
 pub use max_cover::MaxCover;
@@ -19,8 +23,11 @@ pub use reward_cache::RewardCache;
 use state_processing::epoch_cache::is_epoch_cache_initialized;
 use types::EpochCacheError;
 
-use crate::attestation_storage::{AttestationMap, CheckpointKey};
+use crate::attestation_storage::{
+    AttestationMap, CheckpointKey, CompactAttestationData, CompactIndexedAttestation,
+};
 use crate::bls_to_execution_changes::BlsToExecutionChanges;
+use crate::censorship::{oldest_first, CensorshipWatchdog};
 use crate::sync_aggregate_id::SyncAggregateId;
 use attester_slashing::AttesterSlashingMaxCover;
 use max_cover::maximum_cover;
@@ -32,14 +39,16 @@ use state_processing::per_block_processing::{
     get_slashable_indices_modular, verify_exit, VerifySignatures,
 };
 use state_processing::{SigVerifiedOp, VerifyOperation};
-use std::collections::{hash_map::Entry, HashMap, HashSet};
+use std::collections::{hash_map::Entry, BTreeMap, HashMap, HashSet};
 use std::marker::PhantomData;
 use std::ptr;
+use tree_hash::TreeHash;
 use types::{
     sync_aggregate::Error as SyncAggregateError, typenum::Unsigned, AbstractExecPayload,
     Attestation, AttestationData, AttesterSlashing, BeaconState, BeaconStateError, ChainSpec,
-    Epoch, EthSpec, ProposerSlashing, SignedBeaconBlock, SignedBlsToExecutionChange,
-    SignedVoluntaryExit, Slot, SyncAggregate, SyncCommitteeContribution, Validator,
+    Epoch, EthSpec, ForkName, Hash256, ProposerSlashing, SignedBeaconBlock,
+    SignedBlsToExecutionChange, SignedVoluntaryExit, Slot, SyncAggregate,
+    SyncCommitteeContribution, Validator,
 };
 
 type SyncContributions<E> = RwLock<HashMap<SyncAggregateId, Vec<SyncCommitteeContribution<E>>>>;
@@ -60,6 +69,8 @@ pub struct OperationPool<E: EthSpec + Default> {
     bls_to_execution_changes: RwLock<BlsToExecutionChanges<E>>,
     /// Reward cache for accelerating attestation packing.
     reward_cache: RwLock<RewardCache>,
+    /// Inclusion history of pending exits, BLS to execution changes and slashings.
+    censorship_watchdog: RwLock<CensorshipWatchdog>,
     _phantom: PhantomData<E>,
 }
 
@@ -89,6 +100,17 @@ pub struct AttestationStats {
     pub max_aggregates_per_data: usize,
 }
 
+/// Summary of the attestations held for a single slot and committee.
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub struct AttestationCommitteeStats {
+    /// Number of attestations with a single attester.
+    pub num_unaggregated: usize,
+    /// Number of attestations with more than one attester.
+    pub num_aggregated: usize,
+    /// Number of attesters in the largest attestation.
+    pub max_attesters: usize,
+}
+
 impl From<SyncAggregateError> for OpPoolError {
     fn from(e: SyncAggregateError) -> Self {
         OpPoolError::SyncAggregateError(e)
@@ -217,12 +239,40 @@ impl<E: EthSpec> OperationPool<E> {
         self.attestations.read().stats()
     }
 
+    /// Breakdown of the attestations in the pool, keyed by `(slot, committee_index)`.
+    pub fn attestation_committee_stats(&self) -> BTreeMap<(Slot, u64), AttestationCommitteeStats> {
+        let mut stats = BTreeMap::<_, AttestationCommitteeStats>::new();
+
+        for attestation in self.attestations.read().iter() {
+            let committee_index = match attestation.indexed {
+                CompactIndexedAttestation::Base(_) => attestation.data.index,
+                CompactIndexedAttestation::Electra(indexed) => {
+                    indexed.committee_index().unwrap_or(attestation.data.index)
+                }
+            };
+            let num_attesters = attestation.indexed.attesting_indices().len();
+
+            let entry = stats
+                .entry((attestation.data.slot, committee_index))
+                .or_default();
+            if num_attesters > 1 {
+                entry.num_aggregated += 1;
+            } else {
+                entry.num_unaggregated += 1;
+            }
+            entry.max_attesters = std::cmp::max(entry.max_attesters, num_attesters);
+        }
+
+        stats
+    }
+
     /// Return all valid attestations for the given epoch, for use in max cover.
     #[allow(clippy::too_many_arguments)]
     fn get_valid_attestations_for_epoch<'a>(
         &'a self,
         checkpoint_key: &'a CheckpointKey,
         all_attestations: &'a AttestationMap<E>,
+        on_chain_aggregates: &'a [(&'a CompactAttestationData, CompactIndexedAttestation<E>)],
         state: &'a BeaconState<E>,
         reward_cache: &'a RewardCache,
         total_active_balance: u64,
@@ -231,6 +281,15 @@ impl<E: EthSpec> OperationPool<E> {
     ) -> impl Iterator<Item = AttMaxCover<'a, E>> + Send {
         all_attestations
             .get_attestations(checkpoint_key)
+            .chain(
+                on_chain_aggregates
+                    .iter()
+                    .map(|(data, indexed)| CompactAttestationRef {
+                        checkpoint: checkpoint_key,
+                        data,
+                        indexed,
+                    }),
+            )
             .filter(|att| {
                 att.data.slot + spec.min_attestation_inclusion_delay <= state.slot()
                     && state.slot() <= att.data.slot + E::slots_per_epoch()
@@ -282,20 +341,25 @@ impl<E: EthSpec> OperationPool<E> {
         let mut num_prev_valid = 0_i64;
         let mut num_curr_valid = 0_i64;
 
-        // TODO(electra): Work out how to do this more elegantly. This is a bit of a hack.
-        let mut all_attestations = self.attestations.write();
+        let all_attestations = self.attestations.read();
 
-        if fork_name.electra_enabled() {
-            all_attestations.aggregate_across_committees(prev_epoch_key);
-            all_attestations.aggregate_across_committees(curr_epoch_key);
-        }
-
-        let all_attestations = parking_lot::RwLockWriteGuard::downgrade(all_attestations);
+        // From Electra, attestations to the same data from different committees can be
+        // aggregated into a single on-chain attestation. These aggregates are considered
+        // alongside the attestations for each committee.
+        let (prev_on_chain_aggregates, curr_on_chain_aggregates) = if fork_name.electra_enabled() {
+            (
+                all_attestations.get_on_chain_aggregates(&prev_epoch_key),
+                all_attestations.get_on_chain_aggregates(&curr_epoch_key),
+            )
+        } else {
+            (vec![], vec![])
+        };
 
         let prev_epoch_att = self
             .get_valid_attestations_for_epoch(
                 &prev_epoch_key,
                 &*all_attestations,
+                &prev_on_chain_aggregates,
                 state,
                 &reward_cache,
                 total_active_balance,
@@ -307,6 +371,7 @@ impl<E: EthSpec> OperationPool<E> {
             .get_valid_attestations_for_epoch(
                 &curr_epoch_key,
                 &*all_attestations,
+                &curr_on_chain_aggregates,
                 state,
                 &reward_cache,
                 total_active_balance,
@@ -349,11 +414,10 @@ impl<E: EthSpec> OperationPool<E> {
         metrics::set_gauge(&metrics::NUM_PREV_EPOCH_ATTESTATIONS, num_prev_valid);
         metrics::set_gauge(&metrics::NUM_CURR_EPOCH_ATTESTATIONS, num_curr_valid);
 
-        Ok(max_cover::merge_solutions(
-            curr_cover,
-            prev_cover,
-            curr_epoch_limit,
-        ))
+        let attestations = max_cover::merge_solutions(curr_cover, prev_cover, curr_epoch_limit);
+        observe_packing_quality(fork_name, &attestations);
+
+        Ok(attestations)
     }
 
     /// Remove attestations which are too old to be included in a block.
@@ -385,6 +449,8 @@ impl<E: EthSpec> OperationPool<E> {
     /// This function computes both types of slashings together, because
     /// attester slashings may be invalidated by proposer slashings included
     /// earlier in the block.
+    ///
+    /// Proposer slashings and exits which have been pending the longest are included first.
     pub fn get_slashings_and_exits(
         &self,
         state: &BeaconState<E>,
@@ -394,8 +460,13 @@ impl<E: EthSpec> OperationPool<E> {
         Vec<AttesterSlashing<E>>,
         Vec<SignedVoluntaryExit>,
     ) {
+        let watchdog = self.censorship_watchdog.read();
         let proposer_slashings = filter_limit_operations(
-            self.proposer_slashings.read().values(),
+            oldest_first(
+                self.proposer_slashings.read().values(),
+                &watchdog,
+                |slashing| PendingOperation::ProposerSlashing(slashing.as_inner().proposer_index()),
+            ),
             |slashing| {
                 slashing.signature_is_still_valid(&state.fork())
                     && state
@@ -418,6 +489,7 @@ impl<E: EthSpec> OperationPool<E> {
 
         let voluntary_exits = self.get_voluntary_exits(
             state,
+            &watchdog,
             |exit| !to_be_slashed.contains(&exit.message.validator_index),
             spec,
         );
@@ -507,10 +579,11 @@ impl<E: EthSpec> OperationPool<E> {
             .insert(exit.as_inner().message.validator_index, exit);
     }
 
-    /// Get a list of voluntary exits for inclusion in a block.
+    /// Get a list of voluntary exits for inclusion in a block, oldest first.
     fn get_voluntary_exits<F>(
         &self,
         state: &BeaconState<E>,
+        watchdog: &CensorshipWatchdog,
         filter: F,
         spec: &ChainSpec,
     ) -> Vec<SignedVoluntaryExit>
@@ -518,7 +591,9 @@ impl<E: EthSpec> OperationPool<E> {
         F: Fn(&SignedVoluntaryExit) -> bool,
     {
         filter_limit_operations(
-            self.voluntary_exits.read().values(),
+            oldest_first(self.voluntary_exits.read().values(), watchdog, |exit| {
+                PendingOperation::VoluntaryExit(exit.as_inner().message.validator_index)
+            }),
             |exit| {
                 filter(exit.as_inner())
                     && exit.signature_is_still_valid(&state.fork())
@@ -571,14 +646,23 @@ impl<E: EthSpec> OperationPool<E> {
 
     /// Get a list of execution changes for inclusion in a block.
     ///
-    /// They're in random `HashMap` order, which isn't exactly fair, but isn't unfair either.
+    /// Changes which have been pending the longest are included first, so that they can't be
+    /// starved by newer changes. The remainder are in LIFO order.
     pub fn get_bls_to_execution_changes(
         &self,
         state: &BeaconState<E>,
         spec: &ChainSpec,
     ) -> Vec<SignedBlsToExecutionChange> {
         filter_limit_operations(
-            self.bls_to_execution_changes.read().iter_lifo(),
+            oldest_first(
+                self.bls_to_execution_changes.read().iter_lifo(),
+                &self.censorship_watchdog.read(),
+                |address_change| {
+                    PendingOperation::BlsToExecutionChange(
+                        address_change.as_inner().message.validator_index,
+                    )
+                },
+            ),
             |address_change| {
                 address_change.signature_is_still_valid(&state.fork())
                     && state
@@ -656,11 +740,175 @@ impl<E: EthSpec> OperationPool<E> {
         self.prune_bls_to_execution_changes(head_block, head_state, spec);
     }
 
+    /// Record which of the pending exits, BLS to execution changes and slashings were omitted by
+    /// the new head `block`, given the `state` after applying it.
+    ///
+    /// `is_local` indicates whether the block was proposed by one of this node's validators. A
+    /// block which becomes the head again after a re-org is only counted once.
+    pub fn observe_block_for_censorship<Payload: AbstractExecPayload<E>>(
+        &self,
+        block_root: Hash256,
+        block: &SignedBeaconBlock<E, Payload>,
+        state: &BeaconState<E>,
+        is_local: bool,
+        spec: &ChainSpec,
+    ) {
+        let block = block.message();
+        let body = block.body();
+
+        let mut included = HashSet::new();
+        included.extend(
+            body.proposer_slashings()
+                .iter()
+                .map(|slashing| PendingOperation::ProposerSlashing(slashing.proposer_index())),
+        );
+        included.extend(body.attester_slashings().map(|slashing| {
+            PendingOperation::AttesterSlashing(
+                slashing.clone_as_attester_slashing().tree_hash_root(),
+            )
+        }));
+        included.extend(
+            body.voluntary_exits()
+                .iter()
+                .map(|exit| PendingOperation::VoluntaryExit(exit.message.validator_index)),
+        );
+        if let Ok(changes) = body.bls_to_execution_changes() {
+            included.extend(changes.iter().map(|change| {
+                PendingOperation::BlsToExecutionChange(change.message.validator_index)
+            }));
+        }
+
+        let max_attester_slashings = if block.fork_name_unchecked().electra_enabled() {
+            E::MaxAttesterSlashingsElectra::to_usize()
+        } else {
+            E::MaxAttesterSlashings::to_usize()
+        };
+        let proposer_slashings_room =
+            body.proposer_slashings().len() < E::MaxProposerSlashings::to_usize();
+        let attester_slashings_room = body.attester_slashings_len() < max_attester_slashings;
+        let exits_room = body.voluntary_exits().len() < E::MaxVoluntaryExits::to_usize();
+        let changes_room = body.bls_to_execution_changes().map_or(false, |changes| {
+            changes.len() < E::MaxBlsToExecutionChanges::to_usize()
+        });
+
+        // Only operations which are still valid against the post-state are pending.
+        let mut pending = vec![];
+        pending.extend(
+            self.proposer_slashings
+                .read()
+                .values()
+                .filter(|slashing| {
+                    slashing.signature_is_still_valid(&state.fork())
+                        && state
+                            .get_validator(slashing.as_inner().proposer_index() as usize)
+                            .map_or(false, |validator| {
+                                validator.is_slashable_at(state.current_epoch())
+                            })
+                })
+                .map(|slashing| {
+                    (
+                        PendingOperation::ProposerSlashing(slashing.as_inner().proposer_index()),
+                        proposer_slashings_room,
+                    )
+                }),
+        );
+        pending.extend(
+            self.attester_slashings
+                .read()
+                .iter()
+                .filter(|slashing| {
+                    slashing.signature_is_still_valid(&state.fork())
+                        && AttesterSlashingMaxCover::new(
+                            slashing.as_inner().to_ref(),
+                            &HashSet::new(),
+                            state,
+                        )
+                        .map_or(false, |cover| !cover.covering_set().is_empty())
+                })
+                .map(|slashing| {
+                    (
+                        PendingOperation::AttesterSlashing(slashing.as_inner().tree_hash_root()),
+                        attester_slashings_room,
+                    )
+                }),
+        );
+        pending.extend(
+            self.voluntary_exits
+                .read()
+                .values()
+                .filter(|exit| {
+                    exit.signature_is_still_valid(&state.fork())
+                        && verify_exit(state, None, exit.as_inner(), VerifySignatures::False, spec)
+                            .is_ok()
+                })
+                .map(|exit| {
+                    (
+                        PendingOperation::VoluntaryExit(exit.as_inner().message.validator_index),
+                        exits_room,
+                    )
+                }),
+        );
+        if state.fork_name_unchecked().capella_enabled() {
+            pending.extend(
+                self.bls_to_execution_changes
+                    .read()
+                    .iter_fifo()
+                    .filter(|address_change| {
+                        address_change.signature_is_still_valid(&state.fork())
+                            && state
+                                .get_validator(
+                                    address_change.as_inner().message.validator_index as usize,
+                                )
+                                .map_or(false, |validator| {
+                                    !validator.has_execution_withdrawal_credential(spec)
+                                })
+                    })
+                    .map(|address_change| {
+                        (
+                            PendingOperation::BlsToExecutionChange(
+                                address_change.as_inner().message.validator_index,
+                            ),
+                            changes_room,
+                        )
+                    }),
+            );
+        }
+
+        let mut watchdog = self.censorship_watchdog.write();
+        watchdog.observe_block(block_root, block.slot(), pending, &included, is_local);
+
+        let records = watchdog.records();
+        metrics::set_gauge(
+            &metrics::NUM_SUSPECTED_CENSORED_OPERATIONS,
+            records
+                .iter()
+                .filter(|(_, record)| record.is_suspected_censorship())
+                .count() as i64,
+        );
+        metrics::set_gauge(
+            &metrics::OLDEST_PENDING_OPERATION_AGE,
+            records.first().map_or(0, |(_, record)| {
+                block.slot().saturating_sub(record.first_seen_slot).as_u64() as i64
+            }),
+        );
+    }
+
+    /// Returns the inclusion history of all pending exits, BLS to execution changes and
+    /// slashings, oldest first.
+    pub fn pending_operation_records(&self) -> Vec<(PendingOperation, PendingOperationRecord)> {
+        self.censorship_watchdog.read().records()
+    }
+
     /// Total number of voluntary exits in the pool.
     pub fn num_voluntary_exits(&self) -> usize {
         self.voluntary_exits.read().len()
     }
 
+    /// Total number of BLS to execution changes in the pool.
+    pub fn num_bls_to_execution_changes(&self) -> usize {
+        self.bls_to_execution_changes.read().iter_fifo().count()
+    }
+
     /// Returns all known `Attestation` objects.
     ///
     /// This method may return objects that are invalid for block inclusion.
@@ -732,6 +980,38 @@ impl<E: EthSpec> OperationPool<E> {
     }
 }
 
+/// Record the number of attestations, aggregation bits and committees packed into a block.
+fn observe_packing_quality<E: EthSpec>(fork_name: ForkName, attestations: &[Attestation<E>]) {
+    let fork_name = fork_name.to_string();
+    let num_aggregation_bits = attestations
+        .iter()
+        .map(|att| att.num_set_aggregation_bits())
+        .sum::<usize>();
+    let num_committees = attestations
+        .iter()
+        .map(|att| match att {
+            Attestation::Base(_) => 1,
+            Attestation::Electra(att) => att.committee_bits.num_set_bits(),
+        })
+        .sum::<usize>();
+
+    metrics::set_int_gauge(
+        &metrics::PACKED_ATTESTATIONS,
+        &[&fork_name],
+        attestations.len() as i64,
+    );
+    metrics::set_int_gauge(
+        &metrics::PACKED_ATTESTATION_AGGREGATION_BITS,
+        &[&fork_name],
+        num_aggregation_bits as i64,
+    );
+    metrics::set_int_gauge(
+        &metrics::PACKED_ATTESTATION_COMMITTEES,
+        &[&fork_name],
+        num_committees as i64,
+    );
+}
+
 /// Filter up to a maximum number of operations out of an iterator.
 fn filter_limit_operations<'a, T, V: 'a, I, F, G>(
     operations: I,
@@ -1000,6 +1280,17 @@ mod release_tests {
 
         assert_eq!(op_pool.num_attestations(), committees.len());
 
+        let committee_stats = op_pool.attestation_committee_stats();
+        assert_eq!(committee_stats.len(), committees.len());
+        assert_eq!(
+            committee_stats[&(slot, committees[0].index)],
+            AttestationCommitteeStats {
+                num_unaggregated: 0,
+                num_aggregated: 1,
+                max_attesters: spec.target_committee_size,
+            }
+        );
+
         // Before the min attestation inclusion delay, get_attestations shouldn't return anything.
         assert_eq!(
             op_pool
@@ -1288,6 +1579,76 @@ mod release_tests {
         }
     }
 
+    /// Packing should aggregate across committees from Electra without modifying the pool.
+    #[test]
+    fn attestation_cross_committee_packing() {
+        let num_committees = 4;
+        let (harness, ref spec) = attestation_test_state::<MainnetEthSpec>(num_committees);
+
+        let op_pool = OperationPool::<MainnetEthSpec>::new();
+        let mut state = get_current_state_initialize_epoch_cache(&harness, &spec);
+
+        let slot = state.slot();
+        let committees = state
+            .get_beacon_committees_at_slot(slot)
+            .unwrap()
+            .into_iter()
+            .map(BeaconCommittee::into_owned)
+            .collect::<Vec<_>>();
+        assert_eq!(committees.len(), num_committees);
+
+        let num_validators = num_committees
+            * MainnetEthSpec::slots_per_epoch() as usize
+            * spec.target_committee_size;
+        let attestations = harness.make_attestations(
+            (0..num_validators).collect::<Vec<_>>().as_slice(),
+            &state,
+            Hash256::zero(),
+            SignedBeaconBlockHash::from(Hash256::zero()),
+            slot,
+        );
+
+        for (atts, _) in attestations {
+            for (att, _) in atts {
+                let attesting_indices =
+                    get_attesting_indices_from_state(&state, att.to_ref()).unwrap();
+                op_pool.insert_attestation(att, attesting_indices).unwrap();
+            }
+        }
+        assert_eq!(op_pool.num_attestations(), committees.len());
+
+        *state.slot_mut() += spec.min_attestation_inclusion_delay;
+
+        for _ in 0..2 {
+            let block_attestations = op_pool
+                .get_attestations(&state, |_| true, |_| true, spec)
+                .expect("should have block attestations");
+
+            if state.fork_name_unchecked().electra_enabled() {
+                assert_eq!(block_attestations.len(), 1);
+                let Attestation::Electra(on_chain_aggregate) = &block_attestations[0] else {
+                    panic!("should be an electra attestation");
+                };
+                assert_eq!(
+                    on_chain_aggregate.committee_bits.num_set_bits(),
+                    num_committees
+                );
+            } else {
+                assert_eq!(block_attestations.len(), committees.len());
+            }
+            assert_eq!(
+                block_attestations
+                    .iter()
+                    .map(|att| att.num_set_aggregation_bits())
+                    .sum::<usize>(),
+                num_committees * spec.target_committee_size
+            );
+
+            // The attestations for each committee remain in the pool.
+            assert_eq!(op_pool.num_attestations(), committees.len());
+        }
+    }
+
     #[test]
     fn attestation_rewards() {
         let small_step_size = 2;
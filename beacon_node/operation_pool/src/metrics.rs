@@ -38,3 +38,41 @@ pub static MAX_COVER_NON_ZERO_ITEMS: LazyLock<Result<IntGaugeVec>> = LazyLock::n
         &["label"],
     )
 });
+pub static PACKED_ATTESTATIONS: LazyLock<Result<IntGaugeVec>> = LazyLock::new(|| {
+    try_create_int_gauge_vec(
+        "op_pool_packed_attestations",
+        "Number of attestations packed into the most recently produced block",
+        &["fork"],
+    )
+});
+pub static PACKED_ATTESTATION_AGGREGATION_BITS: LazyLock<Result<IntGaugeVec>> =
+    LazyLock::new(|| {
+        try_create_int_gauge_vec(
+            "op_pool_packed_attestation_aggregation_bits",
+            "Number of aggregation bits set across the attestations packed into the most recently \
+             produced block",
+            &["fork"],
+        )
+    });
+pub static PACKED_ATTESTATION_COMMITTEES: LazyLock<Result<IntGaugeVec>> = LazyLock::new(|| {
+    try_create_int_gauge_vec(
+        "op_pool_packed_attestation_committees",
+        "Number of committees covered by the attestations packed into the most recently \
+         produced block, counted once per attestation",
+        &["fork"],
+    )
+});
+pub static NUM_SUSPECTED_CENSORED_OPERATIONS: LazyLock<Result<IntGauge>> = LazyLock::new(|| {
+    try_create_int_gauge(
+        "op_pool_suspected_censored_operations",
+        "Number of exits, BLS to execution changes and slashings omitted by enough blocks with \
+         room for them to suspect censorship",
+    )
+});
+pub static OLDEST_PENDING_OPERATION_AGE: LazyLock<Result<IntGauge>> = LazyLock::new(|| {
+    try_create_int_gauge(
+        "op_pool_oldest_pending_operation_age_slots",
+        "Number of slots the oldest valid exit, BLS to execution change or slashing has been \
+         pending inclusion",
+    )
+});
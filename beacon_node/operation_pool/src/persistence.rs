@@ -212,6 +212,7 @@ impl<E: EthSpec> PersistedOperationPool<E> {
             voluntary_exits,
             bls_to_execution_changes: RwLock::new(bls_to_execution_changes),
             reward_cache: Default::default(),
+            censorship_watchdog: Default::default(),
             _phantom: Default::default(),
         };
         Ok(op_pool)
@@ -4,6 +4,9 @@ use clap::{builder::ArgPredicate, crate_version, Arg, ArgAction, ArgGroup, Comma
 use clap_utils::{get_color_style, FLAG_HEADER};
 use strum::VariantNames;
 
+/// The beacon node subcommand which generates a new network key.
+pub const GENERATE_IDENTITY_CMD: &str = "generate-identity";
+
 #[allow(clippy::large_stack_frames)]
 pub fn cli_app() -> Command {
     Command::new("beacon_node")
@@ -15,6 +18,21 @@ pub fn cli_app() -> Command {
         .about("The primary component which connects to the Ethereum 2.0 P2P network and \
                 downloads, verifies and stores blocks. Provides a HTTP API for querying \
                 the beacon chain and publishing messages to the network.")
+        .subcommand_negates_reqs(true)
+        .subcommand(
+            Command::new(GENERATE_IDENTITY_CMD)
+                .about("Generates a new libp2p network key, to be used with --network-key-file.")
+                .display_order(0)
+                .arg(
+                    Arg::new("output")
+                        .long("output")
+                        .value_name("FILE")
+                        .help("The file to write the new network key to. Must not already exist.")
+                        .required(true)
+                        .action(ArgAction::Set)
+                        .display_order(0)
+                )
+        )
         /*
          * Configuration directory locations.
          */
@@ -36,6 +54,30 @@ pub fn cli_app() -> Command {
                 .action(ArgAction::Set)
                 .display_order(0)
         )
+        .arg(
+            Arg::new("network-key-file")
+                .long("network-key-file")
+                .value_name("FILE")
+                .help("A libp2p network key to use, such as one created by the generate-identity \
+                       command. If the network dir holds a different key, the node rotates to \
+                       this key on startup and keeps announcing the previous ENR for the grace \
+                       period set by --network-key-rotation-grace-period.")
+                .action(ArgAction::Set)
+                .display_order(0)
+        )
+        .arg(
+            Arg::new("network-key-rotation-grace-period")
+                .long("network-key-rotation-grace-period")
+                .value_name("SECONDS")
+                .help("How long the ENR of the previous network key is still announced after \
+                       rotating to a new --network-key-file, so that peers looking up the \
+                       previous node ID find the node while they learn its new identity. The \
+                       previous ENR is served on the port above the discovery port.")
+                .requires("network-key-file")
+                .default_value("3600")
+                .action(ArgAction::Set)
+                .display_order(0)
+        )
         .arg(
             Arg::new("freezer-dir")
                 .long("freezer-dir")
@@ -52,6 +94,52 @@ pub fn cli_app() -> Command {
                 .action(ArgAction::Set)
                 .display_order(0)
         )
+        .arg(
+            Arg::new("freezer-column-dir")
+                .long("freezer-column-dir")
+                .value_name("TAG=DIR")
+                .help("Data directory for a single column of the freezer database, given as its \
+                       3-byte column ID and a directory. May be used to place the historic state \
+                       snapshots (bsn) and diffs (bsd) on a separate disk. Existing data must be \
+                       moved with `lighthouse db relocate` before changing this flag. \
+                       May be supplied multiple times.")
+                .action(ArgAction::Append)
+                .display_order(0)
+        )
+        .arg(
+            Arg::new("hot-db-write-batching")
+                .long("hot-db-write-batching")
+                .action(ArgAction::SetTrue)
+                .help_heading(FLAG_HEADER)
+                .help("Write to the hot database in the background via a write-ahead log, so that \
+                       block import doesn't wait on the disk. Writes which haven't been applied \
+                       to the database are replayed from the log after an unclean shutdown. \
+                       May reduce import latency on slow disks.")
+                .display_order(0)
+        )
+        .arg(
+            Arg::new("hot-tree-states")
+                .long("hot-tree-states")
+                .action(ArgAction::SetTrue)
+                .help_heading(FLAG_HEADER)
+                .help("Store epoch boundary states in the hot database as hierarchical diffs \
+                       against their ancestors, rather than in full. This greatly reduces the size \
+                       of the hot database during periods of non-finality. Existing states are \
+                       converted when the flag is enabled or disabled.")
+                .display_order(0)
+        )
+        .arg(
+            Arg::new("hot-db-fsync-policy")
+                .long("hot-db-fsync-policy")
+                .value_name("POLICY")
+                .requires("hot-db-write-batching")
+                .help("How often to flush the hot database's write-ahead log to disk when using \
+                       --hot-db-write-batching: `always`, `per-slot` or `periodic:MILLIS`. Writes \
+                       made since the last flush may be lost if the machine crashes, but not if \
+                       only the beacon node does. [default: always]")
+                .action(ArgAction::Set)
+                .display_order(0)
+        )
         /*
          * Network parameters.
          */
@@ -113,6 +201,17 @@ pub fn cli_app() -> Command {
                        This will also advertise the beacon node as being long-lived subscribed to all subnets.")
                 .display_order(0)
         )
+        .arg(
+            Arg::new("long-lived-subnets-override")
+                .long("long-lived-subnets-override")
+                .value_name("SUBNETS")
+                .help("Comma-separated list of attestation subnets to subscribe to and advertise \
+                       as long-lived, instead of those computed from the node ID. Intended for \
+                       researchers reproducing subnet backbone experiments.")
+                .action(ArgAction::Set)
+                .conflicts_with("subscribe-all-subnets")
+                .display_order(0)
+        )
         .arg(
             Arg::new("import-all-attestations")
                 .long("import-all-attestations")
@@ -161,10 +260,15 @@ pub fn cli_app() -> Command {
                       - --listen-address '0.0.0.0' will listen over IPv4.\n\
                       - --listen-address '::' will listen over IPv6.\n\
                       - --listen-address '0.0.0.0' --listen-address '::' will listen over both \
-                      IPv4 and IPv6. The order of the given addresses is not relevant. However, \
-                      multiple IPv4, or multiple IPv6 addresses will not be accepted.")
+                      IPv4 and IPv6. The order of the given addresses is not relevant.\n\
+                      - --listen-address '10.0.0.2' --listen-address '192.168.1.2:9100' will \
+                      listen on two IPv4 interfaces.\n\
+                      The first address of each IP version is used for discovery and advertised \
+                      in the ENR. Further addresses of the same IP version are only used by \
+                      libp2p and may carry their own TCP port, with QUIC using that port + 1. \
+                      A port given with the first address overrides --port or --port6.")
                 .action(ArgAction::Append)
-                .num_args(0..=2)
+                .num_args(0..)
                 .default_value("0.0.0.0")
                 .display_order(0)
         )
@@ -240,6 +344,16 @@ pub fn cli_app() -> Command {
                 .action(ArgAction::Set)
                 .display_order(0)
         )
+        .arg(
+            Arg::new("discovery-dns-tree")
+                .long("discovery-dns-tree")
+                .value_name("ENRTREE LIST")
+                .help("One or more comma-delimited DNS ENR trees (EIP-1459) of the form \
+                       enrtree://<public key>@<domain>. The trees are resolved periodically and \
+                       their nodes are used to bootstrap the p2p network alongside the boot nodes.")
+                .action(ArgAction::Set)
+                .display_order(0)
+        )
         .arg(
             Arg::new("network-load")
                 .long("network-load")
@@ -250,6 +364,17 @@ pub fn cli_app() -> Command {
                 .action(ArgAction::Set)
                 .display_order(0)
         )
+        .arg(
+            Arg::new("network-dscp")
+                .long("network-dscp")
+                .value_name("DSCP")
+                .help("Mark outgoing packets on libp2p TCP connections with this differentiated services \
+                       code point, allowing managed networks to prioritise consensus traffic. Gossip and \
+                       RPC traffic share each connection and receive the same mark. QUIC connections are \
+                       not marked. Values are in the range [0,63].")
+                .action(ArgAction::Set)
+                .display_order(0)
+        )
         .arg(
             Arg::new("disable-upnp")
                 .long("disable-upnp")
@@ -384,6 +509,17 @@ pub fn cli_app() -> Command {
                 .help("Disables the quic transport. The node will rely solely on the TCP transport for libp2p connections.")
                 .display_order(0)
         )
+        .arg(
+            Arg::new("disable-inbound")
+                .long("disable-inbound")
+                .action(ArgAction::SetTrue)
+                .help_heading(FLAG_HEADER)
+                .help("Do not listen for inbound libp2p connections and do not advertise TCP or \
+                    QUIC ports in the ENR. The node will only connect to peers it dials itself, \
+                    which is useful behind firewalls that block inbound connections. Discovery \
+                    continues to use its UDP port.")
+                .display_order(0)
+        )
         .arg(
             Arg::new("disable-peer-scoring")
                 .long("disable-peer-scoring")
@@ -394,6 +530,15 @@ pub fn cli_app() -> Command {
                 .hide(true)
                 .display_order(0)
         )
+        .arg(
+            Arg::new("preserve-client-diversity")
+                .long("preserve-client-diversity")
+                .help("When pruning excess peers, prefer to disconnect peers running the most \
+                        common clients so that a diverse set of clients remains connected.")
+                .action(ArgAction::SetTrue)
+                .help_heading(FLAG_HEADER)
+                .display_order(0)
+        )
         .arg(
             Arg::new("trusted-peers")
                 .long("trusted-peers")
@@ -446,6 +591,29 @@ pub fn cli_app() -> Command {
             .conflicts_with("disable-self-limiter")
             .display_order(0)
         )
+        .arg(
+            Arg::new("disable-publish-limiter")
+                .long("disable-publish-limiter")
+                .help("Disables the rate limiter of gossip messages published by this node.")
+                .action(ArgAction::SetTrue)
+                .help_heading(FLAG_HEADER)
+                .display_order(0)
+        )
+        .arg(
+            Arg::new("publish-limiter-topics")
+                .long("publish-limiter-topics")
+                .value_name("QUOTAS")
+                .help("Rate limit quotas for the gossip messages published by this node, in the \
+                       form of <topic_kind>:<tokens>/<time_in_seconds>, where the topic kind is \
+                       one of beacon_attestation, beacon_aggregate_and_proof, sync_committee or \
+                       signed_contribution_and_proof. Each subnet topic is limited separately. To \
+                       set quotas for multiple topic kinds, separate them by ';'. Topic kinds \
+                       which are not configured use their default quota. Messages beyond a quota \
+                       are deferred, and dropped if they cannot be published within a slot.")
+                .action(ArgAction::Set)
+                .conflicts_with("disable-publish-limiter")
+                .display_order(0)
+        )
         .arg(
             Arg::new("proposer-only")
                 .long("proposer-only")
@@ -483,6 +651,19 @@ pub fn cli_app() -> Command {
             .conflicts_with("disable-inbound-rate-limiter")
             .display_order(0)
         )
+        .arg(
+            Arg::new("trusted-peer-rate-limit")
+            .long("trusted-peer-rate-limit")
+            .value_name("LIMIT")
+            .help(
+                "Configures how the inbound and self rate limiters treat trusted peers, such as \
+                paired nodes run by the same operator. Either \"exempt\" to disable rate \
+                limiting for trusted peers, or a multiplier which is applied to the tokens of \
+                every quota. Defaults to 1, treating trusted peers like any other peer."
+            )
+            .action(ArgAction::Set)
+            .display_order(0)
+        )
         .arg(
             Arg::new("disable-backfill-rate-limiting")
                 .long("disable-backfill-rate-limiting")
@@ -534,6 +715,49 @@ pub fn cli_app() -> Command {
                 .action(ArgAction::Set)
                 .display_order(0)
         )
+        .arg(
+            Arg::new("http-admin-address")
+                .long("http-admin-address")
+                .requires("enable_http")
+                .value_name("ADDRESS")
+                .help("Serve the /lighthouse endpoints on a separate listener bound to this \
+                    address instead of the main HTTP API listener. Defaults to 127.0.0.1 if \
+                    --http-admin-port is set.")
+                .action(ArgAction::Set)
+                .display_order(0)
+        )
+        .arg(
+            Arg::new("http-admin-port")
+                .long("http-admin-port")
+                .requires("enable_http")
+                .value_name("PORT")
+                .help("Serve the /lighthouse endpoints on a separate listener bound to this TCP \
+                    port instead of the main HTTP API listener. Defaults to 5053 if \
+                    --http-admin-address is set.")
+                .action(ArgAction::Set)
+                .display_order(0)
+        )
+        .arg(
+            Arg::new("http-admin-allow-origin")
+                .long("http-admin-allow-origin")
+                .requires("enable_http")
+                .value_name("ORIGIN")
+                .help("Set the value of the Access-Control-Allow-Origin response HTTP header \
+                    for the admin listener. If no value is supplied, the CORS allowed origin is \
+                    set to the listen address of the admin listener.")
+                .action(ArgAction::Set)
+                .display_order(0)
+        )
+        .arg(
+            Arg::new("http-admin-token-file")
+                .long("http-admin-token-file")
+                .requires("enable_http")
+                .value_name("PATH")
+                .help("Path to a file containing a token which must be sent as a bearer token \
+                    in the Authorization header of every request to the admin listener.")
+                .action(ArgAction::Set)
+                .display_order(0)
+        )
         .arg(
             Arg::new("http-enable-tls")
                 .long("http-enable-tls")
@@ -545,6 +769,17 @@ pub fn cli_app() -> Command {
                 .requires("http-tls-key")
                 .display_order(0)
         )
+        .arg(
+            Arg::new("http-enable-unsafe-block-production")
+                .long("http-enable-unsafe-block-production")
+                .requires("enable_http")
+                .help("Enables the /lighthouse/validator/blocks/{slot} endpoint, which produces \
+                    blocks on top of an arbitrary parent. This is intended for testing only and \
+                    should not be enabled on a node that serves validators.")
+                .action(ArgAction::SetTrue)
+                .help_heading(FLAG_HEADER)
+                .display_order(0)
+        )
         .arg(
             Arg::new("http-tls-cert")
                 .long("http-tls-cert")
@@ -585,6 +820,19 @@ pub fn cli_app() -> Command {
                        HTTP API.")
                 .display_order(0)
         )
+        .arg(
+            Arg::new("http-archive-query-budget")
+                .long("http-archive-query-budget")
+                .requires("enable_http")
+                .action(ArgAction::Set)
+                .value_name("COST")
+                .help("Maximum total estimated cost of concurrent HTTP queries for finalized \
+                       states, such as debug state and rewards queries. The cost of a query is \
+                       the number of blocks to replay plus 8 for each state diff to apply. \
+                       Queries which exceed the budget, or which would exceed it alongside \
+                       running queries, are rejected with a 503 error. Unlimited by default.")
+                .display_order(0)
+        )
         .arg(
             Arg::new("http-enable-beacon-processor")
                 .long("http-enable-beacon-processor")
@@ -639,6 +887,38 @@ pub fn cli_app() -> Command {
                 .action(ArgAction::Set)
                 .display_order(0)
         )
+        .arg(
+            Arg::new("metrics-auth-token-file")
+                .long("metrics-auth-token-file")
+                .value_name("PATH")
+                .requires("metrics")
+                .help("Path to a file containing a token which clients must supply as a bearer \
+                    token in the Authorization header to access the metrics server.")
+                .action(ArgAction::Set)
+                .display_order(0)
+        )
+        .arg(
+            Arg::new("metrics-allowlist")
+                .long("metrics-allowlist")
+                .value_name("NAMES")
+                .requires("metrics")
+                .help("Comma-separated list of metric names to serve. All other metrics are \
+                    omitted. A trailing * matches any metric name with the given prefix, \
+                    e.g. beacon_*.")
+                .action(ArgAction::Set)
+                .display_order(0)
+        )
+        .arg(
+            Arg::new("metrics-denylist")
+                .long("metrics-denylist")
+                .value_name("NAMES")
+                .requires("metrics")
+                .help("Comma-separated list of metric names to omit from the metrics server. \
+                    A trailing * matches any metric name with the given prefix. Takes precedence \
+                    over --metrics-allowlist.")
+                .action(ArgAction::Set)
+                .display_order(0)
+        )
         .arg(
             Arg::new("shuffling-cache-size")
             .long("shuffling-cache-size")
@@ -657,6 +937,60 @@ pub fn cli_app() -> Command {
                 .hide(true)
                 .display_order(0)
         )
+        .arg(
+            Arg::new("gossip-duplicate-cache-time")
+                .long("gossip-duplicate-cache-time")
+                .value_name("SECONDS")
+                .help("The number of seconds gossip message ids are remembered in order to filter \
+                       duplicate messages. Defaults to two epochs.")
+                .action(ArgAction::Set)
+                .display_order(0)
+        )
+        .arg(
+            Arg::new("gossip-duplicate-cache-max-len")
+                .long("gossip-duplicate-cache-max-len")
+                .value_name("COUNT")
+                .help("The maximum number of gossip message ids remembered in order to filter \
+                       duplicate messages. Once full, the oldest ids are evicted. Reducing this \
+                       lowers memory usage at the risk of forwarding duplicates. Unbounded by \
+                       default.")
+                .action(ArgAction::Set)
+                .display_order(0)
+        )
+        .arg(
+            Arg::new("max-bandwidth-mbps")
+                .long("max-bandwidth-mbps")
+                .value_name("MBPS")
+                .help("Caps the aggregate bandwidth in megabits per second used by gossip \
+                       publishing and backfill sync. Gossip is always published so that the node \
+                       stays in consensus, whilst backfill requests are deferred until the budget \
+                       allows them. Intended for nodes on metered or mobile connections. \
+                       Unlimited by default.")
+                .action(ArgAction::Set)
+                .display_order(0)
+        )
+        .arg(
+            Arg::new("gossip-publish-retry-min-peers")
+                .long("gossip-publish-retry-min-peers")
+                .value_name("COUNT")
+                .help("Blocks, blobs and data columns published whilst the gossipsub mesh of \
+                       their topic has fewer than this many peers are re-published to the mesh, \
+                       trusted peers and other well-scored peers on the topic, up to 3 times. \
+                       Set to 0 to disable.")
+                .default_value("4")
+                .action(ArgAction::Set)
+                .display_order(0)
+        )
+        .arg(
+            Arg::new("gossip-publish-retry-delay")
+                .long("gossip-publish-retry-delay")
+                .value_name("MILLISECONDS")
+                .help("The delay before re-publishing a message that was published to an \
+                       under-populated gossipsub mesh.")
+                .default_value("500")
+                .action(ArgAction::Set)
+                .display_order(0)
+        )
         /*
          * Monitoring metrics
          */
@@ -795,6 +1129,28 @@ pub fn cli_app() -> Command {
                 .action(ArgAction::Set)
                 .display_order(0)
         )
+        .arg(
+            Arg::new("block-root-filter")
+                .long("block-root-filter")
+                .action(ArgAction::SetTrue)
+                .help_heading(FLAG_HEADER)
+                .help("Keep an in-memory filter over the roots of all stored blocks, so that the \
+                       database can answer that a block is unknown without reading from disk. \
+                       The filter is populated in the background on start-up and uses ~10 bits \
+                       of memory per block.")
+                .display_order(0)
+        )
+        .arg(
+            Arg::new("block-root-filter-capacity")
+                .long("block-root-filter-capacity")
+                .value_name("SIZE")
+                .help("Number of block roots to initially size the in-memory block root filter \
+                       for. The filter grows as required, but each growth step slightly raises \
+                       its false positive rate. Only applies if --block-root-filter is set.")
+                .requires("block-root-filter")
+                .action(ArgAction::Set)
+                .display_order(0)
+        )
         .arg(
             Arg::new("historic-state-cache-size")
                 .long("historic-state-cache-size")
@@ -1173,6 +1529,18 @@ pub fn cli_app() -> Command {
                 .default_missing_value("true")
                 .display_order(0)
         )
+        .arg(
+            Arg::new("slasher-fast-path")
+                .long("slasher-fast-path")
+                .help("Process attestations immediately when a validator is observed attesting \
+                       twice in the same epoch on gossip, rather than waiting for the next \
+                       slasher update. Slashings for double votes are then produced within \
+                       seconds.")
+                .action(ArgAction::SetTrue)
+                .help_heading(FLAG_HEADER)
+                .requires("slasher")
+                .display_order(0)
+        )
         .arg(
             Arg::new("slasher-backend")
                 .long("slasher-backend")
@@ -1370,6 +1738,59 @@ pub fn cli_app() -> Command {
                 .conflicts_with("disable-proposer-reorgs")
                 .display_order(0)
         )
+        .arg(
+            Arg::new("re-org-analysis")
+                .long("re-org-analysis")
+                .help("Simulate the proposer reorg decision at every slot under alternative \
+                       parameters and log where it differs from the decision under the configured \
+                       parameters. The alternative decisions are never acted on. The alternative \
+                       parameters default to the configured ones, except that up to 2 blocks may \
+                       be reorged.")
+                .action(ArgAction::SetTrue)
+                .help_heading(FLAG_HEADER)
+                .display_order(0)
+        )
+        .arg(
+            Arg::new("re-org-analysis-head-threshold")
+                .long("re-org-analysis-head-threshold")
+                .value_name("PERCENT")
+                .help("Percentage of head vote weight below which to simulate a proposer reorg \
+                       when using --re-org-analysis.")
+                .requires("re-org-analysis")
+                .action(ArgAction::Set)
+                .display_order(0)
+        )
+        .arg(
+            Arg::new("re-org-analysis-parent-threshold")
+                .long("re-org-analysis-parent-threshold")
+                .value_name("PERCENT")
+                .help("Percentage of parent vote weight above which to simulate a proposer reorg \
+                       when using --re-org-analysis.")
+                .requires("re-org-analysis")
+                .action(ArgAction::Set)
+                .display_order(0)
+        )
+        .arg(
+            Arg::new("re-org-analysis-proposer-boost")
+                .long("re-org-analysis-proposer-boost")
+                .value_name("PERCENT")
+                .help("Proposer boost as a percentage of committee weight to assume when \
+                       simulating proposer reorgs with --re-org-analysis. A simulated reorg is \
+                       only viable if the boost outweighs the reorged blocks.")
+                .requires("re-org-analysis")
+                .action(ArgAction::Set)
+                .display_order(0)
+        )
+        .arg(
+            Arg::new("re-org-analysis-max-depth")
+                .long("re-org-analysis-max-depth")
+                .value_name("BLOCKS")
+                .help("Maximum number of blocks which may be reorged when simulating proposer \
+                       reorgs with --re-org-analysis. Default: 2")
+                .requires("re-org-analysis")
+                .action(ArgAction::Set)
+                .display_order(0)
+        )
         .arg(
             Arg::new("prepare-payload-lookahead")
                 .long("prepare-payload-lookahead")
@@ -1510,6 +1931,150 @@ pub fn cli_app() -> Command {
                 .help_heading(FLAG_HEADER)
                 .display_order(0)
         )
+        .arg(
+            Arg::new("light-client-store-snapshot")
+                .long("light-client-store-snapshot")
+                .value_name("PATH")
+                .help("Path to a JSON snapshot downloaded from the \
+                       /lighthouse/light_client/store HTTP endpoint. The snapshot is loaded into \
+                       the light client server's cache on startup, so that updates can be served \
+                       before new blocks are imported. Each update is checked against the blocks \
+                       this node has imported, and updates which cannot be verified are ignored.")
+                .action(ArgAction::Set)
+                .requires("light-client-server")
+                .display_order(0)
+        )
+        .arg(
+            Arg::new("track-attestation-sources")
+                .long("track-attestation-sources")
+                .help("Record the peer which first delivered each gossip attestation, so that \
+                       the attestation rewards of a block can be attributed to peers via the \
+                       /lighthouse/analysis/block_reward_sources/{slot} HTTP endpoint. This \
+                       increases memory usage.")
+                .action(ArgAction::SetTrue)
+                .help_heading(FLAG_HEADER)
+                .display_order(0)
+        )
+        .arg(
+            Arg::new("clock-skew-threshold")
+                .long("clock-skew-threshold")
+                .value_name("MILLIS")
+                .help("Log a warning when the system clock is estimated to differ from the \
+                       network by more than this many milliseconds. The skew is estimated from \
+                       the arrival times of gossip blocks and, if --ntp-server is set, by \
+                       querying an NTP server.")
+                .default_value("250")
+                .action(ArgAction::Set)
+                .display_order(0)
+        )
+        .arg(
+            Arg::new("ntp-server")
+                .long("ntp-server")
+                .value_name("ADDRESS")
+                .help("An NTP server to periodically check the system clock against, in the \
+                       form host:port, e.g. pool.ntp.org:123. Disabled by default.")
+                .action(ArgAction::Set)
+                .display_order(0)
+        )
+        .arg(
+            Arg::new("epoch-processing-threads")
+                .long("epoch-processing-threads")
+                .value_name("THREADS")
+                .help("The number of threads used to build the effective balance and \
+                       progressive balance caches at each epoch boundary. Values greater than 1 \
+                       reduce block import times at epoch boundaries for large validator sets.")
+                .default_value("1")
+                .action(ArgAction::Set)
+                .display_order(0)
+        )
+        .arg(
+            Arg::new("kzg-verification-threads")
+                .long("kzg-verification-threads")
+                .value_name("THREADS")
+                .help("The number of threads used to verify the KZG proofs of batches of blobs \
+                       and data columns received via gossip or RPC. A value of 1 verifies each \
+                       batch on a single thread.")
+                .default_value("4")
+                .action(ArgAction::Set)
+                .display_order(0)
+        )
+        .arg(
+            Arg::new("storage-watchdog")
+                .long("storage-watchdog")
+                .help("Monitor the free space on the disk holding the database and take \
+                       increasingly drastic measures as it runs out: log a warning, force blob \
+                       pruning, pause backfill sync and finally refuse non-essential writes such \
+                       as historic state reconstruction.")
+                .action(ArgAction::SetTrue)
+                .help_heading(FLAG_HEADER)
+                .display_order(0)
+        )
+        .arg(
+            Arg::new("storage-watchdog-thresholds")
+                .long("storage-watchdog-thresholds")
+                .value_name("PERCENTAGES")
+                .help("The percentages of free disk space below which the storage watchdog \
+                       warns, prunes blobs, pauses backfill and refuses non-essential writes, \
+                       as a comma-separated list in that order.")
+                .requires("storage-watchdog")
+                .default_value("10,5,3,1")
+                .action(ArgAction::Set)
+                .display_order(0)
+        )
+        .arg(
+            Arg::new("publish-safety-checks")
+                .long("publish-safety-checks")
+                .help("Refuse to publish attestations and blocks submitted via the HTTP API which \
+                       would be slashable with respect to those recently observed from the same \
+                       validator. This is a last line of defence against misconfigured redundant \
+                       validator clients and does not replace slashing protection.")
+                .action(ArgAction::SetTrue)
+                .help_heading(FLAG_HEADER)
+                .display_order(0)
+        )
+        .arg(
+            Arg::new("blob-fetch-order")
+                .long("blob-fetch-order")
+                .value_name("STRATEGIES")
+                .help("The order in which to prefer the ways of acquiring blobs, as a \
+                       comma-separated list of `gossip`, `engine_get_blobs` and `rpc_by_root`. \
+                       Omitting `engine_get_blobs` disables fetching blobs from the execution \
+                       layer. Gossip and RPC cannot be disabled.")
+                .default_value("engine_get_blobs,gossip,rpc_by_root")
+                .action(ArgAction::Set)
+                .display_order(0)
+        )
+        .arg(
+            Arg::new("blob-gossip-wait")
+                .long("blob-gossip-wait")
+                .value_name("MILLISECONDS")
+                .help("How long to wait for blobs on gossip before fetching them from the \
+                       execution layer. Only applies if `gossip` precedes `engine_get_blobs` in \
+                       --blob-fetch-order.")
+                .default_value("500")
+                .action(ArgAction::Set)
+                .display_order(0)
+        )
+        .arg(
+            Arg::new("max-chain-segment-length")
+                .long("max-chain-segment-length")
+                .value_name("BLOCKS")
+                .help("The maximum number of blocks from a range sync chain segment which are \
+                       signature-verified together. Smaller batches allow the signatures of the \
+                       next batch to be verified whilst the current batch is imported.")
+                .default_value("8")
+                .action(ArgAction::Set)
+                .display_order(0)
+        )
+        .arg(
+            Arg::new("disable-chain-segment-pipelining")
+                .long("disable-chain-segment-pipelining")
+                .help("Wait for each batch of a range sync chain segment to be imported before \
+                       verifying the signatures of the next batch.")
+                .action(ArgAction::SetTrue)
+                .help_heading(FLAG_HEADER)
+                .display_order(0)
+        )
         .arg(
             Arg::new("gui")
                 .long("gui")
@@ -1591,5 +2156,28 @@ pub fn cli_app() -> Command {
                 .action(ArgAction::Set)
                 .display_order(0)
         )
+        .arg(
+            Arg::new("beacon-processor-queue-lengths")
+                .long("beacon-processor-queue-lengths")
+                .value_name("WORK_TYPE=LENGTH,...")
+                .help("Overrides the capacity of individual beacon processor work queues. \
+                       Accepts a comma-separated list of work types and lengths, e.g. \
+                       `status=2048,chain_segment_backfill=16`. Work types use the names \
+                       returned by the /lighthouse/beacon_processor endpoint.")
+                .hide(true)
+                .action(ArgAction::Set)
+                .display_order(0)
+        )
+        .arg(
+            Arg::new("beacon-processor-priority-order")
+                .long("beacon-processor-priority-order")
+                .value_name("WORK_TYPES")
+                .help("A comma-separated list of beacon processor work types which will be \
+                       processed, in order, before any other queued work. Work types which are \
+                       not listed retain their default priority.")
+                .hide(true)
+                .action(ArgAction::Set)
+                .display_order(0)
+        )
         .group(ArgGroup::new("enable_http").args(["http", "gui", "staking"]).multiple(true))
 }
@@ -1,11 +1,13 @@
 use account_utils::{read_input_from_user, STDIN_INPUTS_FLAG};
 use beacon_chain::chain_config::{
-    DisallowedReOrgOffsets, ReOrgThreshold, DEFAULT_PREPARE_PAYLOAD_LOOKAHEAD_FACTOR,
+    BlobFetchStrategy, DisallowedReOrgOffsets, ReOrgSimulationParams, ReOrgThreshold,
+    DEFAULT_PREPARE_PAYLOAD_LOOKAHEAD_FACTOR, DEFAULT_RE_ORG_ANALYSIS_MAX_DEPTH,
     DEFAULT_RE_ORG_HEAD_THRESHOLD, DEFAULT_RE_ORG_MAX_EPOCHS_SINCE_FINALIZATION,
     DEFAULT_RE_ORG_PARENT_THRESHOLD,
 };
 use beacon_chain::graffiti_calculator::GraffitiOrigin;
 use beacon_chain::TrustedSetup;
+use beacon_processor::WorkType;
 use clap::{parser::ValueSource, ArgMatches, Id};
 use clap_utils::flags::DISABLE_MALLOC_TUNING_FLAG;
 use clap_utils::{parse_flag, parse_required};
@@ -14,9 +16,13 @@ use directory::{DEFAULT_BEACON_NODE_DIR, DEFAULT_NETWORK_DIR, DEFAULT_ROOT_DIR};
 use environment::RuntimeContext;
 use execution_layer::DEFAULT_JWT_FILE;
 use genesis::Eth1Endpoint;
-use http_api::TlsConfig;
-use lighthouse_network::ListenAddress;
+use http_api::{AdminConfig, TlsConfig};
+use lighthouse_network::discovery::EnrTree;
+use lighthouse_network::rpc::config::{
+    InboundRateLimiterConfig, OutboundRateLimiterConfig, TrustedPeerRateLimit,
+};
 use lighthouse_network::{multiaddr::Protocol, Enr, Multiaddr, NetworkConfig, PeerIdSerialized};
+use lighthouse_network::{AdditionalListenAddr, ListenAddress};
 use sensitive_url::SensitiveUrl;
 use slog::{info, warn, Logger};
 use std::cmp::max;
@@ -24,13 +30,13 @@ use std::fmt::Debug;
 use std::fs;
 use std::io::IsTerminal;
 use std::net::Ipv6Addr;
-use std::net::{IpAddr, Ipv4Addr, ToSocketAddrs};
-use std::num::NonZeroU16;
+use std::net::{IpAddr, Ipv4Addr, SocketAddr, ToSocketAddrs};
+use std::num::{NonZeroU16, NonZeroU64};
 use std::path::{Path, PathBuf};
 use std::str::FromStr;
 use std::time::Duration;
 use types::graffiti::GraffitiString;
-use types::{Checkpoint, Epoch, EthSpec, Hash256, PublicKeyBytes};
+use types::{Checkpoint, Epoch, EthSpec, Hash256, PublicKeyBytes, SubnetId};
 
 const PURGE_DB_CONFIRMATION: &str = "confirm";
 
@@ -114,6 +120,29 @@ pub fn get_config<E: EthSpec>(
 
     set_network_config(&mut client_config.network, cli_args, &data_dir_ref, log)?;
 
+    // Parsed here rather than in `set_network_config` since the subnets are checked against the
+    // spec.
+    if let Some(subnets) = cli_args.get_one::<String>("long-lived-subnets-override") {
+        client_config.network.long_lived_subnets_override = Some(
+            subnets
+                .split(',')
+                .map(|subnet| {
+                    let subnet = subnet
+                        .trim()
+                        .parse::<u64>()
+                        .map_err(|_| format!("Invalid long-lived subnet: {subnet:?}"))?;
+                    if subnet >= spec.attestation_subnet_count {
+                        return Err(format!(
+                            "Long-lived subnet {subnet} must be less than {}",
+                            spec.attestation_subnet_count
+                        ));
+                    }
+                    Ok(SubnetId::new(subnet))
+                })
+                .collect::<Result<_, String>>()?,
+        );
+    }
+
     /*
      * Staking flag
      * Note: the config values set here can be overwritten by other more specific cli params
@@ -151,6 +180,42 @@ pub fn get_config<E: EthSpec>(
             client_config.http_api.allow_origin = Some(allow_origin.to_string());
         }
 
+        let admin_address = cli_args.get_one::<String>("http-admin-address");
+        let admin_port = cli_args.get_one::<String>("http-admin-port");
+        let admin_allow_origin = cli_args.get_one::<String>("http-admin-allow-origin");
+        let admin_token_file = cli_args.get_one::<String>("http-admin-token-file");
+        if admin_address.is_some() || admin_port.is_some() {
+            let mut admin = AdminConfig::default();
+
+            if let Some(address) = admin_address {
+                admin.listen_addr = address
+                    .parse::<IpAddr>()
+                    .map_err(|_| "http-admin-address is not a valid IP address.")?;
+            }
+
+            if let Some(port) = admin_port {
+                admin.listen_port = port
+                    .parse::<u16>()
+                    .map_err(|_| "http-admin-port is not a valid u16.")?;
+            }
+
+            if let Some(allow_origin) = admin_allow_origin {
+                hyper::header::HeaderValue::from_str(allow_origin)
+                    .map_err(|_| "Invalid admin allow-origin value")?;
+                admin.allow_origin = Some(allow_origin.to_string());
+            }
+
+            admin.api_token_path = admin_token_file.map(PathBuf::from);
+
+            client_config.http_api.admin = Some(admin);
+        } else if admin_allow_origin.is_some() || admin_token_file.is_some() {
+            return Err(
+                "--http-admin-address or --http-admin-port is required to configure the \
+                admin listener"
+                    .into(),
+            );
+        }
+
         if cli_args.get_flag("http-enable-tls") {
             client_config.http_api.tls_config = Some(TlsConfig {
                 cert: cli_args
@@ -175,14 +240,23 @@ pub fn get_config<E: EthSpec>(
         client_config.http_api.duplicate_block_status_code =
             parse_required(cli_args, "http-duplicate-block-status")?;
 
+        client_config.http_api.archive_query_budget =
+            clap_utils::parse_optional(cli_args, "http-archive-query-budget")?;
+
         client_config.http_api.enable_light_client_server =
             cli_args.get_flag("light-client-server");
+
+        client_config.http_api.enable_unsafe_block_production =
+            cli_args.get_flag("http-enable-unsafe-block-production");
     }
 
     if cli_args.get_flag("light-client-server") {
         client_config.chain.enable_light_client_server = true;
     }
 
+    client_config.chain.light_client_store_snapshot =
+        clap_utils::parse_optional(cli_args, "light-client-store-snapshot")?;
+
     if let Some(cache_size) = clap_utils::parse_optional(cli_args, "shuffling-cache-size")? {
         client_config.chain.shuffling_cache_size = cache_size;
     }
@@ -229,6 +303,28 @@ pub fn get_config<E: EthSpec>(
         client_config.http_metrics.allow_origin = Some(allow_origin.to_string());
     }
 
+    if let Some(path) = clap_utils::parse_optional(cli_args, "metrics-auth-token-file")? {
+        client_config.http_metrics.auth_token_path = Some(path);
+    }
+
+    if let Some(allowlist) = cli_args.get_one::<String>("metrics-allowlist") {
+        client_config.http_metrics.metric_allowlist = Some(
+            allowlist
+                .split(',')
+                .map(|name| name.trim().to_string())
+                .filter(|name| !name.is_empty())
+                .collect(),
+        );
+    }
+
+    if let Some(denylist) = cli_args.get_one::<String>("metrics-denylist") {
+        client_config.http_metrics.metric_denylist = denylist
+            .split(',')
+            .map(|name| name.trim().to_string())
+            .filter(|name| !name.is_empty())
+            .collect();
+    }
+
     /*
      * Explorer metrics
      */
@@ -393,12 +489,29 @@ pub fn get_config<E: EthSpec>(
         client_config.blobs_db_path = Some(PathBuf::from(blobs_db_dir));
     }
 
+    if let Some(column_dirs) = cli_args.get_many::<String>("freezer-column-dir") {
+        client_config.store.freezer_column_dirs = column_dirs
+            .map(|value| store::config::parse_freezer_column_dir(value))
+            .collect::<Result<_, _>>()?;
+    }
+
+    client_config.store.hot_write_batching = cli_args.get_flag("hot-db-write-batching");
+    client_config.store.hot_tree_states = cli_args.get_flag("hot-tree-states");
+    if let Some(hot_fsync_policy) = clap_utils::parse_optional(cli_args, "hot-db-fsync-policy")? {
+        client_config.store.hot_fsync_policy = hot_fsync_policy;
+    }
+
     if let Some(block_cache_size) = cli_args.get_one::<String>("block-cache-size") {
         client_config.store.block_cache_size = block_cache_size
             .parse()
             .map_err(|_| "block-cache-size is not a valid integer".to_string())?;
     }
 
+    client_config.store.block_root_filter = cli_args.get_flag("block-root-filter");
+    if let Some(capacity) = clap_utils::parse_optional(cli_args, "block-root-filter-capacity")? {
+        client_config.store.block_root_filter_capacity = capacity;
+    }
+
     if let Some(cache_size) = cli_args.get_one::<String>("state-cache-size") {
         client_config.store.state_cache_size = cache_size
             .parse()
@@ -712,6 +825,8 @@ pub fn get_config<E: EthSpec>(
             slasher_config.broadcast = broadcast;
         }
 
+        slasher_config.fast_path = cli_args.get_flag("slasher-fast-path");
+
         if let Some(backend) = clap_utils::parse_optional(cli_args, "slasher-backend")? {
             slasher_config.backend = backend;
         }
@@ -799,6 +914,39 @@ pub fn get_config<E: EthSpec>(
         }
     }
 
+    if cli_args.get_flag("re-org-analysis") {
+        let max_re_org_depth = clap_utils::parse_optional(cli_args, "re-org-analysis-max-depth")?
+            .unwrap_or(DEFAULT_RE_ORG_ANALYSIS_MAX_DEPTH);
+        if max_re_org_depth == 0 {
+            return Err("--re-org-analysis-max-depth must be at least 1".into());
+        }
+        let chain = &client_config.chain;
+        let re_org_analysis = ReOrgSimulationParams {
+            re_org_head_threshold: clap_utils::parse_optional(
+                cli_args,
+                "re-org-analysis-head-threshold",
+            )?
+            .map(ReOrgThreshold)
+            .or(chain.re_org_head_threshold)
+            .unwrap_or(DEFAULT_RE_ORG_HEAD_THRESHOLD),
+            re_org_parent_threshold: clap_utils::parse_optional(
+                cli_args,
+                "re-org-analysis-parent-threshold",
+            )?
+            .map(ReOrgThreshold)
+            .or(chain.re_org_parent_threshold)
+            .unwrap_or(DEFAULT_RE_ORG_PARENT_THRESHOLD),
+            proposer_score_boost: clap_utils::parse_optional(
+                cli_args,
+                "re-org-analysis-proposer-boost",
+            )?
+            .or(spec.proposer_score_boost)
+            .unwrap_or(0),
+            max_re_org_depth,
+        };
+        client_config.chain.re_org_analysis = Some(re_org_analysis);
+    }
+
     // Note: This overrides any previous flags that enable this option.
     if cli_args.get_flag("disable-deposit-contract-sync") {
         client_config.sync_eth1_chain = false;
@@ -824,6 +972,56 @@ pub fn get_config<E: EthSpec>(
 
     client_config.chain.paranoid_block_proposal = cli_args.get_flag("paranoid-block-proposal");
 
+    client_config.chain.track_attestation_sources = cli_args.get_flag("track-attestation-sources");
+
+    client_config.chain.clock_skew_threshold =
+        Duration::from_millis(parse_required(cli_args, "clock-skew-threshold")?);
+    client_config.chain.ntp_server = clap_utils::parse_optional(cli_args, "ntp-server")?;
+
+    client_config.chain.epoch_processing_threads =
+        clap_utils::parse_required(cli_args, "epoch-processing-threads")?;
+    client_config.chain.kzg_verification_threads =
+        clap_utils::parse_required(cli_args, "kzg-verification-threads")?;
+
+    if cli_args.get_flag("storage-watchdog") {
+        client_config.chain.storage_watchdog = Some(clap_utils::parse_required(
+            cli_args,
+            "storage-watchdog-thresholds",
+        )?);
+    }
+
+    client_config.chain.publish_safety_checks = cli_args.get_flag("publish-safety-checks");
+
+    if let Some(order) = clap_utils::parse_optional::<String>(cli_args, "blob-fetch-order")? {
+        let order = order
+            .split(',')
+            .map(|strategy| {
+                BlobFetchStrategy::from_str(strategy.trim())
+                    .map_err(|_| format!("Invalid blob fetch strategy: {}", strategy))
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        if (1..order.len()).any(|i| order[..i].contains(&order[i])) {
+            return Err("--blob-fetch-order must not contain duplicates".to_string());
+        }
+        if !order.contains(&BlobFetchStrategy::Gossip)
+            || !order.contains(&BlobFetchStrategy::RpcByRoot)
+        {
+            return Err("--blob-fetch-order must contain gossip and rpc_by_root".to_string());
+        }
+        client_config.chain.blob_fetch_order = order;
+    }
+
+    client_config.chain.blob_gossip_wait =
+        Duration::from_millis(parse_required(cli_args, "blob-gossip-wait")?);
+
+    client_config.chain.max_chain_segment_length =
+        parse_required(cli_args, "max-chain-segment-length")?;
+    if client_config.chain.max_chain_segment_length == 0 {
+        return Err("--max-chain-segment-length must be greater than 0".to_string());
+    }
+    client_config.chain.pipeline_chain_segment_verification =
+        !cli_args.get_flag("disable-chain-segment-pipelining");
+
     /*
      * Builder fallback configs.
      */
@@ -883,48 +1081,101 @@ pub fn get_config<E: EthSpec>(
         .max_gossip_aggregate_batch_size =
         clap_utils::parse_required(cli_args, "beacon-processor-aggregate-batch-size")?;
 
+    if let Some(queue_lengths) = cli_args.get_one::<String>("beacon-processor-queue-lengths") {
+        client_config.beacon_processor.queue_length_overrides = queue_lengths
+            .split(',')
+            .map(|entry| {
+                let (work_type, length) = entry.split_once('=').ok_or_else(|| {
+                    format!(
+                        "Invalid beacon processor queue length, expected WORK_TYPE=LENGTH: {entry}"
+                    )
+                })?;
+                let work_type = WorkType::from_str(work_type.trim())
+                    .map_err(|_| format!("Unknown beacon processor work type: {work_type}"))?;
+                let length = length
+                    .trim()
+                    .parse::<usize>()
+                    .map_err(|e| format!("Invalid queue length for {entry}: {e:?}"))?;
+                Ok((work_type, length))
+            })
+            .collect::<Result<_, String>>()?;
+    }
+
+    if let Some(priority_order) = cli_args.get_one::<String>("beacon-processor-priority-order") {
+        client_config.beacon_processor.prioritized_work_types = priority_order
+            .split(',')
+            .map(|work_type| {
+                WorkType::from_str(work_type.trim())
+                    .map_err(|_| format!("Unknown beacon processor work type: {work_type}"))
+            })
+            .collect::<Result<_, String>>()?;
+    }
+
     Ok(client_config)
 }
 
+/// Parses a `--listen-address` value, which is either an IP address or an IP address with a TCP
+/// port (e.g. `10.0.0.2:9000` or `[2001:db8::2]:9000`).
+fn parse_listen_address_value(addr_str: &str) -> Result<(IpAddr, Option<u16>), String> {
+    if let Ok(addr) = addr_str.parse::<IpAddr>() {
+        return Ok((addr, None));
+    }
+    addr_str
+        .parse::<SocketAddr>()
+        .map(|socket_addr| (socket_addr.ip(), Some(socket_addr.port())))
+        .map_err(|parse_error| {
+            format!(
+                "Failed to parse listen-address ({addr_str}) as an Ip or socket address: \
+                {parse_error}"
+            )
+        })
+}
+
+/// Ensures the listening addresses of one IP version can be bound together.
+fn verify_listen_addresses<Ip: PartialEq + std::fmt::Display>(
+    addrs: &[(Ip, Option<u16>)],
+    unspecified: Ip,
+) -> Result<(), String> {
+    for (i, (addr, _)) in addrs.iter().enumerate() {
+        if addrs[..i].iter().any(|(other, _)| other == addr) {
+            return Err(format!(
+                "The listen-address {addr} was provided more than once"
+            ));
+        }
+    }
+    if addrs.len() > 1 && addrs.iter().any(|(addr, _)| *addr == unspecified) {
+        return Err(format!(
+            "The listen-address {unspecified} already listens on all interfaces and cannot be \
+            combined with other addresses of the same IP version"
+        ));
+    }
+    Ok(())
+}
+
 /// Gets the listening_addresses for lighthouse based on the cli options.
 pub fn parse_listening_addresses(
     cli_args: &ArgMatches,
     log: &Logger,
-) -> Result<ListenAddress, String> {
+) -> Result<(ListenAddress, Vec<AdditionalListenAddr>), String> {
     let listen_addresses_str = cli_args
         .get_many::<String>("listen-address")
         .expect("--listen_addresses has a default value");
     let use_zero_ports = parse_flag(cli_args, "zero-ports");
 
-    // parse the possible ips
-    let mut maybe_ipv4 = None;
-    let mut maybe_ipv6 = None;
+    // parse the possible ips. The first address of each IP version is the primary address, used
+    // by discovery and advertised in the ENR. Any others are additional libp2p interfaces.
+    let mut ipv4_addrs = vec![];
+    let mut ipv6_addrs = vec![];
     for addr_str in listen_addresses_str {
-        let addr = addr_str.parse::<IpAddr>().map_err(|parse_error| {
-            format!("Failed to parse listen-address ({addr_str}) as an Ip address: {parse_error}")
-        })?;
-
-        match addr {
-            IpAddr::V4(v4_addr) => match &maybe_ipv4 {
-                Some(first_ipv4_addr) => {
-                    return Err(format!(
-                                "When setting the --listen-address option twice, use an IpV4 address and an Ipv6 address. \
-                                Got two IpV4 addresses {first_ipv4_addr} and {v4_addr}"
-                            ));
-                }
-                None => maybe_ipv4 = Some(v4_addr),
-            },
-            IpAddr::V6(v6_addr) => match &maybe_ipv6 {
-                Some(first_ipv6_addr) => {
-                    return Err(format!(
-                                "When setting the --listen-address option twice, use an IpV4 address and an Ipv6 address. \
-                                Got two IpV6 addresses {first_ipv6_addr} and {v6_addr}"
-                            ));
-                }
-                None => maybe_ipv6 = Some(v6_addr),
-            },
+        match parse_listen_address_value(addr_str)? {
+            (IpAddr::V4(v4_addr), port) => ipv4_addrs.push((v4_addr, port)),
+            (IpAddr::V6(v6_addr), port) => ipv6_addrs.push((v6_addr, port)),
         }
     }
+    verify_listen_addresses(&ipv4_addrs, Ipv4Addr::UNSPECIFIED)?;
+    verify_listen_addresses(&ipv6_addrs, Ipv6Addr::UNSPECIFIED)?;
+    let maybe_ipv4 = ipv4_addrs.first().map(|(addr, _)| *addr);
+    let maybe_ipv6 = ipv6_addrs.first().map(|(addr, _)| *addr);
 
     // parse the possible tcp ports
     let port = cli_args
@@ -939,6 +1190,18 @@ pub fn parse_listening_addresses(
         .map_err(|parse_error| format!("Failed to parse --port6 as an integer: {parse_error}"))?
         .unwrap_or(9090);
 
+    // A port given with a primary address takes precedence over --port and --port6. When only
+    // listening over IPv6, --port applies to the IPv6 address.
+    let primary_port4 = ipv4_addrs.first().and_then(|(_, port)| *port);
+    let primary_port6 = ipv6_addrs.first().and_then(|(_, port)| *port);
+    let port6 = primary_port6.unwrap_or(port6);
+    let port = if maybe_ipv4.is_some() {
+        primary_port4
+    } else {
+        primary_port6
+    }
+    .unwrap_or(port);
+
     // parse the possible discovery ports.
     let maybe_disc_port = cli_args
         .get_one::<String>("discovery-port")
@@ -1107,7 +1370,52 @@ pub fn parse_listening_addresses(
         }
     };
 
-    Ok(listening_addresses)
+    // Additional interfaces use the ports of the primary address of the same IP version, unless
+    // a port is given with the address.
+    let mut additional_addresses = vec![];
+    let additional_ips = ipv4_addrs
+        .iter()
+        .skip(1)
+        .map(|(addr, port)| (IpAddr::V4(*addr), *port))
+        .chain(
+            ipv6_addrs
+                .iter()
+                .skip(1)
+                .map(|(addr, port)| (IpAddr::V6(*addr), *port)),
+        );
+    for (addr, maybe_port) in additional_ips {
+        let (tcp_port, quic_port) = if use_zero_ports {
+            match addr {
+                IpAddr::V4(_) => (
+                    unused_port::unused_tcp4_port()?,
+                    unused_port::unused_udp4_port()?,
+                ),
+                IpAddr::V6(_) => (
+                    unused_port::unused_tcp6_port()?,
+                    unused_port::unused_udp6_port()?,
+                ),
+            }
+        } else if let Some(tcp_port) = maybe_port {
+            (tcp_port, if tcp_port == 0 { 0 } else { tcp_port + 1 })
+        } else {
+            match addr {
+                IpAddr::V4(_) => listening_addresses
+                    .v4()
+                    .map(|primary| (primary.tcp_port, primary.quic_port)),
+                IpAddr::V6(_) => listening_addresses
+                    .v6()
+                    .map(|primary| (primary.tcp_port, primary.quic_port)),
+            }
+            .ok_or("Additional listen-address without a primary address")?
+        };
+        additional_addresses.push(AdditionalListenAddr {
+            addr,
+            quic_port,
+            tcp_port,
+        });
+    }
+
+    Ok((listening_addresses, additional_addresses))
 }
 
 /// Sets the network config from the command line arguments.
@@ -1124,6 +1432,12 @@ pub fn set_network_config(
         config.network_dir = data_dir.join(DEFAULT_NETWORK_DIR);
     };
 
+    config.network_key_file = clap_utils::parse_optional(cli_args, "network-key-file")?;
+    config.network_key_rotation_grace_period = Duration::from_secs(clap_utils::parse_required(
+        cli_args,
+        "network-key-rotation-grace-period",
+    )?);
+
     if parse_flag(cli_args, "subscribe-all-data-column-subnets") {
         config.subscribe_all_data_column_subnets = true;
     }
@@ -1140,7 +1454,9 @@ pub fn set_network_config(
         config.shutdown_after_sync = true;
     }
 
-    config.set_listening_addr(parse_listening_addresses(cli_args, log)?);
+    let (listen_addresses, additional_listen_addresses) = parse_listening_addresses(cli_args, log)?;
+    config.set_listening_addr(listen_addresses);
+    config.set_additional_listening_addrs(additional_listen_addresses);
 
     // A custom target-peers command will overwrite the --proposer-only default.
     if let Some(target_peers_str) = cli_args.get_one::<String>("target-peers") {
@@ -1156,6 +1472,15 @@ pub fn set_network_config(
         config.network_load = network_load;
     }
 
+    if let Some(value) = cli_args.get_one::<String>("network-dscp") {
+        let dscp = value
+            .parse::<u8>()
+            .ok()
+            .filter(|dscp| *dscp < 64)
+            .ok_or_else(|| format!("Invalid DSCP value, must be in the range [0,63]: {}", value))?;
+        config.dscp = Some(dscp);
+    }
+
     if let Some(boot_enr_str) = cli_args.get_one::<String>("boot-nodes") {
         let mut enrs: Vec<Enr> = vec![];
         let mut multiaddrs: Vec<Multiaddr> = vec![];
@@ -1181,6 +1506,16 @@ pub fn set_network_config(
         config.boot_nodes_multiaddr = multiaddrs;
     }
 
+    if let Some(dns_trees_str) = cli_args.get_one::<String>("discovery-dns-tree") {
+        config.discovery_dns_trees = dns_trees_str
+            .split(',')
+            .map(|tree| {
+                tree.parse::<EnrTree>()
+                    .map_err(|e| format!("Invalid DNS ENR tree {}: {}", tree, e))
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+    }
+
     if let Some(libp2p_addresses_str) = cli_args.get_one::<String>("libp2p-addresses") {
         config.libp2p_nodes = libp2p_addresses_str
             .split(',')
@@ -1196,6 +1531,10 @@ pub fn set_network_config(
         config.disable_peer_scoring = true;
     }
 
+    if parse_flag(cli_args, "preserve-client-diversity") {
+        config.preserve_client_diversity = true;
+    }
+
     if let Some(trusted_peers_str) = cli_args.get_one::<String>("trusted-peers") {
         config.trusted_peers = trusted_peers_str
             .split(',')
@@ -1392,6 +1731,10 @@ pub fn set_network_config(
         config.disable_quic_support = true;
     }
 
+    if parse_flag(cli_args, "disable-inbound") {
+        config.disable_inbound = true;
+    }
+
     if parse_flag(cli_args, "disable-upnp") {
         config.upnp_enabled = false;
     }
@@ -1421,6 +1764,16 @@ pub fn set_network_config(
         Some(Default::default())
     };
 
+    // The publish limiter is enabled by default, with default quotas for topic kinds which are not
+    // configured.
+    config.publish_rate_limiter_config = if parse_flag(cli_args, "disable-publish-limiter") {
+        None
+    } else if let Some(quotas) = cli_args.get_one::<String>("publish-limiter-topics") {
+        Some(quotas.parse()?)
+    } else {
+        Some(Default::default())
+    };
+
     // Proposer-only mode overrides a number of previous configuration parameters.
     // Specifically, we avoid subscribing to long-lived subnets and wish to maintain a minimal set
     // of peers.
@@ -1447,6 +1800,17 @@ pub fn set_network_config(
         }
     };
 
+    // Trusted peers are treated the same way by the inbound and self rate limiters.
+    if let Some(limit) = cli_args.get_one::<String>("trusted-peer-rate-limit") {
+        let limit = limit.parse::<TrustedPeerRateLimit>()?;
+        config.inbound_rate_limiter_config = config
+            .inbound_rate_limiter_config
+            .map(|limiter| InboundRateLimiterConfig(limiter.0.with_trusted_peer_limit(limit)));
+        config.outbound_rate_limiter_config = config
+            .outbound_rate_limiter_config
+            .map(|limiter| OutboundRateLimiterConfig(limiter.0.with_trusted_peer_limit(limit)));
+    }
+
     if let Some(idontwant_message_size_threshold) =
         cli_args.get_one::<String>("idontwant-message-size-threshold")
     {
@@ -1460,6 +1824,34 @@ pub fn set_network_config(
             })?;
     }
 
+    if let Some(seconds) =
+        clap_utils::parse_optional::<u64>(cli_args, "gossip-duplicate-cache-time")?
+    {
+        if seconds == 0 {
+            return Err("--gossip-duplicate-cache-time must be greater than 0".to_string());
+        }
+        config.gossip_duplicate_cache_time = Some(Duration::from_secs(seconds));
+    }
+
+    if let Some(max_len) =
+        clap_utils::parse_optional::<usize>(cli_args, "gossip-duplicate-cache-max-len")?
+    {
+        if max_len == 0 {
+            return Err("--gossip-duplicate-cache-max-len must be greater than 0".to_string());
+        }
+        config.gossip_duplicate_cache_max_len = Some(max_len);
+    }
+
+    if let Some(mbps) = clap_utils::parse_optional::<u64>(cli_args, "max-bandwidth-mbps")? {
+        config.max_bandwidth_mbps =
+            Some(NonZeroU64::new(mbps).ok_or("--max-bandwidth-mbps must be greater than 0")?);
+    }
+
+    config.gossip_publish_retry_min_mesh_peers =
+        parse_required(cli_args, "gossip-publish-retry-min-peers")?;
+    config.gossip_publish_retry_delay =
+        Duration::from_millis(parse_required(cli_args, "gossip-publish-retry-delay")?);
+
     Ok(())
 }
 
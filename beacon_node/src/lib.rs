@@ -7,14 +7,16 @@ use beacon_chain::{
     builder::Witness, eth1_chain::CachingEth1Backend, slot_clock::SystemTimeSlotClock,
 };
 use clap::ArgMatches;
-pub use cli::cli_app;
+pub use cli::{cli_app, GENERATE_IDENTITY_CMD};
 pub use client::{Client, ClientBuilder, ClientConfig, ClientGenesis};
 pub use config::{get_config, get_data_dir, set_network_config};
 use environment::RuntimeContext;
 pub use eth2_config::Eth2Config;
+use lighthouse_network::generate_network_key;
 use slasher::{DatabaseBackendOverride, Slasher};
 use slog::{info, warn};
 use std::ops::{Deref, DerefMut};
+use std::path::PathBuf;
 use std::sync::Arc;
 use types::{ChainSpec, Epoch, EthSpec, ForkName};
 
@@ -245,3 +247,17 @@ mod test {
         );
     }
 }
+
+/// Generates a new network key at the path given by the `generate-identity` subcommand.
+pub fn generate_identity(matches: &ArgMatches) -> Result<(), String> {
+    let output: PathBuf = clap_utils::parse_required(matches, "output")?;
+    let keypair = generate_network_key(&output)?;
+
+    println!(
+        "Generated network key for peer ID {} at {:?}",
+        keypair.public().to_peer_id(),
+        output
+    );
+    println!("Start the beacon node with --network-key-file to rotate to this key.");
+    Ok(())
+}
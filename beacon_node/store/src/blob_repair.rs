@@ -0,0 +1,291 @@
+//! Offline repair of the blobs DB.
+//!
+//! Blobs and data columns are keyed by the root of their block, so there is no separate index to
+//! rebuild. What can drift after a partial write or an interrupted prune is everything derived
+//! from them: entries whose block is gone are never reached by pruning (which walks the canonical
+//! block roots), and the oldest blob and data column slots recorded in the metadata may claim data
+//! which is missing, or hide data which is present. This module rebuilds the root to slot map of
+//! every stored entry from the entries themselves and uses it to repair both.
+use crate::hot_cold_store::HotColdDB;
+use crate::metadata::DataColumnInfo;
+use crate::{
+    get_key_for_col, parse_data_column_key, BlobInfo, DBColumn, Error, ItemStore, KeyValueStoreOp,
+};
+use slog::{debug, info};
+use ssz::Decode;
+use std::cmp::{max, min};
+use std::collections::HashMap;
+use types::data_column_sidecar::DataColumnSidecar;
+use types::{BlobSidecarList, EthSpec, Hash256, Slot};
+
+/// The number of entries or slots between progress logs.
+const PROGRESS_INTERVAL: u64 = 8192;
+
+/// The number of deletions written to the blobs DB at once.
+const DELETE_BATCH_SIZE: usize = 1024;
+
+/// The outcome of a call to `HotColdDB::repair_blobs_db`.
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct BlobRepairReport {
+    pub blob_lists_scanned: u64,
+    pub data_columns_scanned: u64,
+    /// Entries removed because their block is not stored.
+    pub orphans_removed: u64,
+    /// Entries removed because they could not be decoded or are stored under the wrong key.
+    pub invalid_removed: u64,
+    /// Finalized blocks loaded to check whether they have blobs or data columns.
+    pub blocks_checked: u64,
+    pub previous_oldest_blob_slot: Option<Slot>,
+    pub oldest_blob_slot: Option<Slot>,
+    pub previous_oldest_data_column_slot: Option<Slot>,
+    pub oldest_data_column_slot: Option<Slot>,
+}
+
+impl BlobRepairReport {
+    /// Return `true` if the repair changed (or in a dry run, would change) anything.
+    pub fn is_repaired(&self) -> bool {
+        self.orphans_removed != 0
+            || self.invalid_removed != 0
+            || self.previous_oldest_blob_slot != self.oldest_blob_slot
+            || self.previous_oldest_data_column_slot != self.oldest_data_column_slot
+    }
+}
+
+impl<E, Hot, Cold> HotColdDB<E, Hot, Cold>
+where
+    E: EthSpec,
+    Hot: ItemStore<E>,
+    Cold: ItemStore<E>,
+{
+    /// Scan the blob and data column columns, removing entries which can never be served or
+    /// pruned, and recompute the oldest blob and data column slots from what remains.
+    ///
+    /// The oldest slots are set to the first slot after the last finalized block whose blobs (or
+    /// data columns) are missing. They are only lowered as far as the oldest entry which is
+    /// actually stored, so that history which has been pruned is not checked block by block.
+    ///
+    /// A block's data columns are counted as present if any of its columns is stored. Whether all
+    /// of the columns in the node's custody set are stored is not checked, as the custody set is
+    /// not known whilst the beacon node is stopped.
+    ///
+    /// If `dry_run` is set nothing is written, and the report describes the changes which would
+    /// have been made. The beacon node must not be running.
+    pub fn repair_blobs_db(&self, dry_run: bool) -> Result<BlobRepairReport, Error> {
+        let blob_info = self.get_blob_info();
+        let data_column_info = self.get_data_column_info();
+        let mut report = BlobRepairReport {
+            previous_oldest_blob_slot: blob_info.oldest_blob_slot,
+            previous_oldest_data_column_slot: data_column_info.oldest_data_column_slot,
+            ..BlobRepairReport::default()
+        };
+        let mut deletions = vec![];
+
+        // Rebuild the slot of every stored blob list from its sidecars.
+        let mut blob_slots = HashMap::new();
+        for result in self.blobs_db.iter_column::<Hash256>(DBColumn::BeaconBlob) {
+            let (block_root, bytes) = result?;
+            report.blob_lists_scanned += 1;
+            let slot = BlobSidecarList::<E>::from_ssz_bytes(&bytes)
+                .ok()
+                .and_then(|blobs| {
+                    let slot = blobs.first()?.slot();
+                    blobs
+                        .iter()
+                        .all(|blob| blob.block_root() == block_root && blob.slot() == slot)
+                        .then_some(slot)
+                });
+            let key = get_key_for_col(DBColumn::BeaconBlob.into(), block_root.as_slice());
+            match slot {
+                Some(slot) if self.block_exists(&block_root)? => {
+                    blob_slots.insert(block_root, slot);
+                }
+                Some(_) => {
+                    report.orphans_removed += 1;
+                    deletions.push(KeyValueStoreOp::DeleteKey(key));
+                }
+                None => {
+                    debug!(self.log, "Invalid blob list"; "block_root" => ?block_root);
+                    report.invalid_removed += 1;
+                    deletions.push(KeyValueStoreOp::DeleteKey(key));
+                }
+            }
+            self.flush_blob_deletions(&mut deletions, dry_run, false)?;
+            if report.blob_lists_scanned % PROGRESS_INTERVAL == 0 {
+                info!(
+                    self.log,
+                    "Blob repair in progress";
+                    "blob_lists_scanned" => report.blob_lists_scanned,
+                );
+            }
+        }
+
+        // As above for data columns, which are keyed by block root and column index.
+        let mut data_column_slots = HashMap::new();
+        for result in self
+            .blobs_db
+            .iter_column::<Vec<u8>>(DBColumn::BeaconDataColumn)
+        {
+            let (raw_key, bytes) = result?;
+            report.data_columns_scanned += 1;
+            let key = get_key_for_col(DBColumn::BeaconDataColumn.into(), &raw_key);
+            let entry = parse_data_column_key(raw_key)
+                .ok()
+                .and_then(|(block_root, index)| {
+                    let column = DataColumnSidecar::<E>::from_ssz_bytes(&bytes).ok()?;
+                    (column.block_root() == block_root && column.index == index)
+                        .then_some((block_root, column.slot()))
+                });
+            match entry {
+                Some((block_root, slot)) if self.block_exists(&block_root)? => {
+                    data_column_slots.insert(block_root, slot);
+                }
+                Some(_) => {
+                    report.orphans_removed += 1;
+                    deletions.push(KeyValueStoreOp::DeleteKey(key));
+                }
+                None => {
+                    debug!(self.log, "Invalid data column"; "key" => ?key);
+                    report.invalid_removed += 1;
+                    deletions.push(KeyValueStoreOp::DeleteKey(key));
+                }
+            }
+            self.flush_blob_deletions(&mut deletions, dry_run, false)?;
+            if report.data_columns_scanned % PROGRESS_INTERVAL == 0 {
+                info!(
+                    self.log,
+                    "Blob repair in progress";
+                    "data_columns_scanned" => report.data_columns_scanned,
+                );
+            }
+        }
+        self.flush_blob_deletions(&mut deletions, dry_run, true)?;
+
+        // Walk the finalized blocks which should have blobs or data columns, and find the last one
+        // whose data is missing.
+        let split_slot = self.get_split_slot();
+        let oldest_block_slot = self.get_anchor_info().oldest_block_slot;
+        let scan_start = |oldest: Option<Slot>, stored: &HashMap<Hash256, Slot>| {
+            let oldest = oldest?;
+            let stored_oldest = stored.values().min().copied().unwrap_or(oldest);
+            Some(max(oldest_block_slot, min(oldest, stored_oldest)))
+        };
+        let blob_start = scan_start(blob_info.oldest_blob_slot, &blob_slots);
+        let data_column_start =
+            scan_start(data_column_info.oldest_data_column_slot, &data_column_slots);
+        report.oldest_blob_slot = blob_info.oldest_blob_slot;
+        report.oldest_data_column_slot = data_column_info.oldest_data_column_slot;
+
+        let Some(start_slot) = [blob_start, data_column_start]
+            .into_iter()
+            .flatten()
+            .min()
+            .filter(|start_slot| *start_slot < split_slot)
+        else {
+            return self.store_repaired_blob_info(report, dry_run);
+        };
+
+        let mut last_missing_blobs = None;
+        let mut last_missing_data_columns = None;
+        let mut previous_block_root = None;
+        let block_roots =
+            self.forwards_block_roots_iterator_until(start_slot, split_slot - 1, || {
+                Err(Error::StateShouldNotBeRequired(split_slot - 1))
+            })?;
+        for (i, result) in block_roots.enumerate() {
+            let (block_root, slot) = result?;
+            if (i as u64 + 1) % PROGRESS_INTERVAL == 0 {
+                info!(
+                    self.log,
+                    "Blob repair in progress";
+                    "slot" => slot,
+                    "remaining" => split_slot - slot,
+                );
+            }
+            // Skipped slots repeat the root of the previous block.
+            if previous_block_root == Some(block_root) {
+                continue;
+            }
+            previous_block_root = Some(block_root);
+
+            let is_peer_das = self
+                .spec
+                .is_peer_das_enabled_for_epoch(slot.epoch(E::slots_per_epoch()));
+            let (start, stored, last_missing) = if is_peer_das {
+                (
+                    data_column_start,
+                    &data_column_slots,
+                    &mut last_missing_data_columns,
+                )
+            } else {
+                (blob_start, &blob_slots, &mut last_missing_blobs)
+            };
+            if start.map_or(true, |start| slot < start) || stored.contains_key(&block_root) {
+                continue;
+            }
+
+            report.blocks_checked += 1;
+            match self.get_blinded_block(&block_root)? {
+                // The first slot may be skipped, in which case its root is of an earlier block.
+                Some(block) if block.slot() != slot => {}
+                Some(block) if block.num_expected_blobs() == 0 => {}
+                _ => *last_missing = Some(slot),
+            }
+        }
+
+        if let Some(start) = blob_start {
+            report.oldest_blob_slot = Some(last_missing_blobs.map_or(start, |slot| slot + 1));
+        }
+        if let Some(start) = data_column_start {
+            report.oldest_data_column_slot =
+                Some(last_missing_data_columns.map_or(start, |slot| slot + 1));
+        }
+        self.store_repaired_blob_info(report, dry_run)
+    }
+
+    /// Write out `deletions` once a full batch has accumulated, or unconditionally if `force` is
+    /// set.
+    fn flush_blob_deletions(
+        &self,
+        deletions: &mut Vec<KeyValueStoreOp>,
+        dry_run: bool,
+        force: bool,
+    ) -> Result<(), Error> {
+        if deletions.is_empty() || (!force && deletions.len() < DELETE_BATCH_SIZE) {
+            return Ok(());
+        }
+        let deletions = std::mem::take(deletions);
+        if dry_run {
+            return Ok(());
+        }
+        self.blobs_db.do_atomically(deletions)
+    }
+
+    fn store_repaired_blob_info(
+        &self,
+        report: BlobRepairReport,
+        dry_run: bool,
+    ) -> Result<BlobRepairReport, Error> {
+        if dry_run {
+            return Ok(report);
+        }
+        if report.oldest_blob_slot != report.previous_oldest_blob_slot {
+            let blob_info = self.get_blob_info();
+            let new_blob_info = BlobInfo {
+                oldest_blob_slot: report.oldest_blob_slot,
+                ..blob_info.clone()
+            };
+            self.compare_and_set_blob_info_with_write(blob_info, new_blob_info)?;
+        }
+        if report.oldest_data_column_slot != report.previous_oldest_data_column_slot {
+            let data_column_info = self.get_data_column_info();
+            let new_data_column_info = DataColumnInfo {
+                oldest_data_column_slot: report.oldest_data_column_slot,
+            };
+            self.compare_and_set_data_column_info_with_write(
+                data_column_info,
+                new_data_column_info,
+            )?;
+        }
+        Ok(report)
+    }
+}
@@ -0,0 +1,255 @@
+//! An in-memory bloom filter over the roots of all blocks in the database.
+//!
+//! Gossip blocks and lookups frequently reference block roots which we don't have, and the filter
+//! allows the store to answer that a root is unknown without reading from disk. Positive answers
+//! may be false, so they are always confirmed by reading from disk.
+//!
+//! The filter is made up of one or more layers. Once the newest layer reaches its capacity a new
+//! layer twice as large is added, so the filter grows with the database without needing to be
+//! rebuilt. Each layer contributes ~1% to the false positive rate.
+//!
+//! Bits are never cleared, so a deleted block leaves its bits set. This only raises the false
+//! positive rate, and the filter is rebuilt from the database on the next start.
+use crate::Hash256;
+use parking_lot::RwLock;
+use std::sync::atomic::{AtomicBool, AtomicU64, AtomicUsize, Ordering};
+
+/// Number of filter bits per block root, giving a false positive rate of ~1% at capacity.
+const BITS_PER_ITEM: usize = 10;
+/// Number of bits set per block root, optimal for `BITS_PER_ITEM`.
+const NUM_HASHES: u64 = 7;
+/// Factor by which each new layer's capacity exceeds that of the previous layer.
+const GROWTH_FACTOR: usize = 2;
+
+#[derive(Debug)]
+struct Layer {
+    bits: Vec<AtomicU64>,
+    capacity: usize,
+    /// Number of roots inserted into this layer, including duplicates.
+    num_items: AtomicUsize,
+}
+
+impl Layer {
+    fn new(capacity: usize) -> Self {
+        let num_words = capacity.saturating_mul(BITS_PER_ITEM).div_ceil(64);
+        Self {
+            bits: (0..num_words).map(|_| AtomicU64::new(0)).collect(),
+            capacity,
+            num_items: AtomicUsize::new(0),
+        }
+    }
+
+    /// Return the positions of the bits for `block_root`.
+    ///
+    /// Block roots are uniformly distributed hashes, so the bit positions are derived directly
+    /// from the root's bytes by double hashing.
+    fn bit_indices(&self, block_root: &Hash256) -> impl Iterator<Item = u64> {
+        let bytes = block_root.as_slice();
+        let mut h1 = [0; 8];
+        let mut h2 = [0; 8];
+        h1.copy_from_slice(&bytes[0..8]);
+        h2.copy_from_slice(&bytes[8..16]);
+        let h1 = u64::from_le_bytes(h1);
+        let h2 = u64::from_le_bytes(h2) | 1;
+        let num_bits = self.bits.len() as u64 * 64;
+
+        (0..NUM_HASHES).map(move |i| h1.wrapping_add(i.wrapping_mul(h2)) % num_bits)
+    }
+
+    fn is_full(&self) -> bool {
+        self.num_items.load(Ordering::Relaxed) >= self.capacity
+    }
+
+    fn insert(&self, block_root: &Hash256) {
+        for index in self.bit_indices(block_root) {
+            self.bits[(index / 64) as usize].fetch_or(1 << (index % 64), Ordering::Relaxed);
+        }
+        self.num_items.fetch_add(1, Ordering::Relaxed);
+    }
+
+    fn may_contain(&self, block_root: &Hash256) -> bool {
+        self.bit_indices(block_root).all(|index| {
+            self.bits[(index / 64) as usize].load(Ordering::Relaxed) & (1 << (index % 64)) != 0
+        })
+    }
+
+    fn estimated_false_positive_rate(&self) -> f64 {
+        let num_bits = (self.bits.len() * 64) as f64;
+        let num_hashes = NUM_HASHES as f64;
+        let num_items = self.num_items.load(Ordering::Relaxed) as f64;
+        let fill = 1.0 - (-num_hashes * num_items / num_bits).exp();
+        fill.powf(num_hashes)
+    }
+}
+
+#[derive(Debug)]
+pub struct BlockRootFilter {
+    /// Layers in order of creation. Only the last layer is inserted into.
+    layers: RwLock<Vec<Layer>>,
+    /// Whether all roots in the database have been inserted.
+    ///
+    /// The filter must not be used to answer queries until it is ready.
+    ready: AtomicBool,
+}
+
+impl BlockRootFilter {
+    /// Create a filter whose first layer is sized for `capacity` block roots.
+    ///
+    /// Return `None` if `capacity` is zero, which disables the filter.
+    pub fn new(capacity: usize) -> Option<Self> {
+        if capacity == 0 {
+            return None;
+        }
+        Some(Self {
+            layers: RwLock::new(vec![Layer::new(capacity)]),
+            ready: AtomicBool::new(false),
+        })
+    }
+
+    pub fn insert(&self, block_root: &Hash256) {
+        {
+            let layers = self.layers.read();
+            if let Some(layer) = layers.last().filter(|layer| !layer.is_full()) {
+                layer.insert(block_root);
+                return;
+            }
+        }
+
+        let mut layers = self.layers.write();
+        // Another thread may have added a layer while we waited for the lock.
+        if let Some(capacity) = layers
+            .last()
+            .filter(|layer| layer.is_full())
+            .map(|layer| layer.capacity.saturating_mul(GROWTH_FACTOR))
+        {
+            layers.push(Layer::new(capacity));
+        }
+        if let Some(layer) = layers.last() {
+            layer.insert(block_root);
+        }
+    }
+
+    /// Return `false` if `block_root` is definitely not in the database.
+    pub fn may_contain(&self, block_root: &Hash256) -> bool {
+        self.layers
+            .read()
+            .iter()
+            .any(|layer| layer.may_contain(block_root))
+    }
+
+    pub fn is_ready(&self) -> bool {
+        self.ready.load(Ordering::Acquire)
+    }
+
+    /// Mark the filter as containing every block root in the database.
+    pub fn set_ready(&self) {
+        self.ready.store(true, Ordering::Release);
+    }
+
+    /// Return the number of roots inserted, including duplicates.
+    pub fn len(&self) -> usize {
+        self.layers
+            .read()
+            .iter()
+            .map(|layer| layer.num_items.load(Ordering::Relaxed))
+            .sum()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Return the number of roots the filter can hold before adding another layer.
+    pub fn capacity(&self) -> usize {
+        self.layers.read().iter().map(|layer| layer.capacity).sum()
+    }
+
+    /// Return the number of layers the filter has grown to.
+    pub fn num_layers(&self) -> usize {
+        self.layers.read().len()
+    }
+
+    /// Return the memory used by the filter's bits, in bytes.
+    pub fn size_bytes(&self) -> usize {
+        self.layers
+            .read()
+            .iter()
+            .map(|layer| layer.bits.len() * std::mem::size_of::<u64>())
+            .sum()
+    }
+
+    /// Estimate the probability that `may_contain` returns `true` for an absent root.
+    pub fn estimated_false_positive_rate(&self) -> f64 {
+        let true_negative_rate = self
+            .layers
+            .read()
+            .iter()
+            .map(|layer| 1.0 - layer.estimated_false_positive_rate())
+            .product::<f64>();
+        1.0 - true_negative_rate
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn zero_capacity_disables_filter() {
+        assert!(BlockRootFilter::new(0).is_none());
+    }
+
+    #[test]
+    fn inserted_roots_are_contained() {
+        let filter = BlockRootFilter::new(1024).unwrap();
+        let roots = (0..1024).map(|_| Hash256::random()).collect::<Vec<_>>();
+        for root in &roots {
+            filter.insert(root);
+        }
+        assert!(roots.iter().all(|root| filter.may_contain(root)));
+        assert_eq!(filter.len(), roots.len());
+    }
+
+    #[test]
+    fn false_positive_rate_at_capacity() {
+        let capacity = 10_000;
+        let filter = BlockRootFilter::new(capacity).unwrap();
+        for _ in 0..capacity {
+            filter.insert(&Hash256::random());
+        }
+
+        let trials = 100_000;
+        let false_positives = (0..trials)
+            .filter(|_| filter.may_contain(&Hash256::random()))
+            .count();
+        let observed_rate = false_positives as f64 / trials as f64;
+        assert!(observed_rate < 0.02, "false positive rate {observed_rate}");
+        assert!(filter.estimated_false_positive_rate() < 0.02);
+    }
+
+    #[test]
+    fn filter_grows_beyond_capacity() {
+        let capacity = 1000;
+        let filter = BlockRootFilter::new(capacity).unwrap();
+        let roots = (0..4 * capacity)
+            .map(|_| Hash256::random())
+            .collect::<Vec<_>>();
+        for root in &roots {
+            filter.insert(root);
+        }
+        assert!(roots.iter().all(|root| filter.may_contain(root)));
+        assert_eq!(filter.len(), roots.len());
+        // Layers of 1000, 2000 and 4000 roots hold 4000 roots.
+        assert_eq!(filter.num_layers(), 3);
+        assert_eq!(filter.capacity(), 7 * capacity);
+
+        // Each layer stays within its own false positive budget.
+        let trials = 100_000;
+        let false_positives = (0..trials)
+            .filter(|_| filter.may_contain(&Hash256::random()))
+            .count();
+        let observed_rate = false_positives as f64 / trials as f64;
+        assert!(observed_rate < 0.04, "false positive rate {observed_rate}");
+        assert!(filter.estimated_false_positive_rate() < 0.04);
+    }
+}
@@ -5,6 +5,9 @@ use ssz::{Decode, Encode};
 use ssz_derive::{Decode, Encode};
 use std::io::Write;
 use std::num::NonZeroUsize;
+use std::path::PathBuf;
+use std::str::FromStr;
+use std::time::Duration;
 use superstruct::superstruct;
 use types::non_zero_usize::new_non_zero_usize;
 use types::EthSpec;
@@ -17,15 +20,28 @@ pub const DEFAULT_STATE_CACHE_SIZE: NonZeroUsize = new_non_zero_usize(128);
 pub const DEFAULT_COMPRESSION_LEVEL: i32 = 1;
 pub const DEFAULT_HISTORIC_STATE_CACHE_SIZE: NonZeroUsize = new_non_zero_usize(1);
 pub const DEFAULT_HDIFF_BUFFER_CACHE_SIZE: NonZeroUsize = new_non_zero_usize(16);
+pub const DEFAULT_BLOCK_ROOT_FILTER_CAPACITY: usize = 1 << 24;
 const EST_COMPRESSION_FACTOR: usize = 2;
 pub const DEFAULT_EPOCHS_PER_BLOB_PRUNE: u64 = 1;
 pub const DEFAULT_BLOB_PUNE_MARGIN_EPOCHS: u64 = 0;
 
+/// Freezer DB columns which may be stored in a directory other than the freezer DB's.
+///
+/// Entries in these columns are only ever referenced via other freezer columns. Writes to them are
+/// applied before the rest of a batch, so an interrupted write can only leave unreferenced data.
+pub const RELOCATABLE_FREEZER_COLUMNS: [DBColumn; 2] =
+    [DBColumn::BeaconStateSnapshot, DBColumn::BeaconStateDiff];
+
 /// Database configuration parameters.
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct StoreConfig {
     /// Maximum number of blocks to store in the in-memory block cache.
     pub block_cache_size: NonZeroUsize,
+    /// Whether to maintain an in-memory filter over the roots of all stored blocks.
+    pub block_root_filter: bool,
+    /// Number of block roots the block root filter is initially sized for. The filter grows
+    /// beyond this as required.
+    pub block_root_filter_capacity: usize,
     /// Maximum number of states to store in the in-memory state cache.
     pub state_cache_size: NonZeroUsize,
     /// Compression level for blocks, state diffs and other compressed values.
@@ -42,6 +58,9 @@ pub struct StoreConfig {
     pub prune_payloads: bool,
     /// State diff hierarchy.
     pub hierarchy_config: HierarchyConfig,
+    /// Whether to store epoch boundary states in the hot database as diffs, using the same
+    /// hierarchy as the freezer database.
+    pub hot_tree_states: bool,
     /// Whether to prune blobs older than the blob data availability boundary.
     pub prune_blobs: bool,
     /// Frequency of blob pruning in epochs. Default: 1 (every epoch).
@@ -49,6 +68,73 @@ pub struct StoreConfig {
     /// The margin for blob pruning in epochs. The oldest blobs are pruned up until
     /// data_availability_boundary - blob_prune_margin_epochs. Default: 0.
     pub blob_prune_margin_epochs: u64,
+    /// Directories for freezer DB columns stored separately from the rest of the freezer DB.
+    pub freezer_column_dirs: Vec<(DBColumn, PathBuf)>,
+    /// Whether to write to the hot DB in the background, via a write-ahead log.
+    pub hot_write_batching: bool,
+    /// How often the hot DB's write-ahead log is flushed to disk, if write batching is enabled.
+    pub hot_fsync_policy: FsyncPolicy,
+}
+
+/// How often the hot DB's write-ahead log is flushed to disk.
+///
+/// Writes which haven't been flushed may be lost if the machine crashes, but not if only the
+/// beacon node does.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum FsyncPolicy {
+    /// Flush after every batch of writes.
+    Always,
+    /// Flush once per slot.
+    PerSlot,
+    /// Flush at the given interval.
+    Periodic(Duration),
+}
+
+impl FsyncPolicy {
+    /// Return the interval between flushes, or `None` if every batch is flushed.
+    pub fn interval(&self, seconds_per_slot: u64) -> Option<Duration> {
+        match self {
+            Self::Always => None,
+            Self::PerSlot => Some(Duration::from_secs(seconds_per_slot)),
+            Self::Periodic(interval) => Some(*interval),
+        }
+    }
+}
+
+impl FromStr for FsyncPolicy {
+    type Err = String;
+
+    /// Parse `always`, `per-slot` or `periodic:MILLIS`.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "always" => Ok(Self::Always),
+            "per-slot" => Ok(Self::PerSlot),
+            _ => {
+                let millis = s
+                    .strip_prefix("periodic:")
+                    .ok_or_else(|| {
+                        format!(
+                            "Invalid fsync policy {:?}, expected always, per-slot or \
+                             periodic:MILLIS",
+                            s
+                        )
+                    })?
+                    .parse::<u64>()
+                    .map_err(|e| format!("Invalid fsync interval in {:?}: {}", s, e))?;
+                Ok(Self::Periodic(Duration::from_millis(millis)))
+            }
+        }
+    }
+}
+
+impl std::fmt::Display for FsyncPolicy {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            Self::Always => write!(f, "always"),
+            Self::PerSlot => write!(f, "per-slot"),
+            Self::Periodic(interval) => write!(f, "periodic:{}", interval.as_millis()),
+        }
+    }
 }
 
 /// Variant of `StoreConfig` that gets written to disk. Contains immutable configuration params.
@@ -91,12 +177,17 @@ pub enum StoreConfigError {
     },
     ZeroEpochsPerBlobPrune,
     InvalidVersionByte(Option<u8>),
+    ColumnNotRelocatable(DBColumn),
+    DuplicateFreezerColumnDir(DBColumn),
+    ZeroFsyncInterval,
 }
 
 impl Default for StoreConfig {
     fn default() -> Self {
         Self {
             block_cache_size: DEFAULT_BLOCK_CACHE_SIZE,
+            block_root_filter: false,
+            block_root_filter_capacity: DEFAULT_BLOCK_ROOT_FILTER_CAPACITY,
             state_cache_size: DEFAULT_STATE_CACHE_SIZE,
             historic_state_cache_size: DEFAULT_HISTORIC_STATE_CACHE_SIZE,
             hdiff_buffer_cache_size: DEFAULT_HDIFF_BUFFER_CACHE_SIZE,
@@ -105,9 +196,13 @@ impl Default for StoreConfig {
             compact_on_prune: true,
             prune_payloads: true,
             hierarchy_config: HierarchyConfig::default(),
+            hot_tree_states: false,
             prune_blobs: true,
             epochs_per_blob_prune: DEFAULT_EPOCHS_PER_BLOB_PRUNE,
             blob_prune_margin_epochs: DEFAULT_BLOB_PUNE_MARGIN_EPOCHS,
+            freezer_column_dirs: vec![],
+            hot_write_batching: false,
+            hot_fsync_policy: FsyncPolicy::Always,
         }
     }
 }
@@ -145,7 +240,9 @@ impl StoreConfig {
     /// Check that the configuration is valid.
     pub fn verify<E: EthSpec>(&self) -> Result<(), StoreConfigError> {
         self.verify_compression_level()?;
-        self.verify_epochs_per_blob_prune()
+        self.verify_epochs_per_blob_prune()?;
+        self.verify_freezer_column_dirs()?;
+        self.verify_hot_fsync_policy()
     }
 
     /// Check that the compression level is valid.
@@ -169,6 +266,31 @@ impl StoreConfig {
         }
     }
 
+    /// Check that only relocatable columns are given a separate directory, at most once each.
+    fn verify_freezer_column_dirs(&self) -> Result<(), StoreConfigError> {
+        for (i, (column, _)) in self.freezer_column_dirs.iter().enumerate() {
+            if !RELOCATABLE_FREEZER_COLUMNS.contains(column) {
+                return Err(StoreConfigError::ColumnNotRelocatable(*column));
+            }
+            if self.freezer_column_dirs[..i]
+                .iter()
+                .any(|(other, _)| other == column)
+            {
+                return Err(StoreConfigError::DuplicateFreezerColumnDir(*column));
+            }
+        }
+        Ok(())
+    }
+
+    /// Check that a periodic fsync policy has a non-zero interval.
+    fn verify_hot_fsync_policy(&self) -> Result<(), StoreConfigError> {
+        if self.hot_fsync_policy == FsyncPolicy::Periodic(Duration::ZERO) {
+            Err(StoreConfigError::ZeroFsyncInterval)
+        } else {
+            Ok(())
+        }
+    }
+
     /// Estimate the size of `len` bytes after compression at the current compression level.
     pub fn estimate_compressed_size(&self, len: usize) -> usize {
         // This is a rough estimate, but for our data it seems that all non-zero compression levels
@@ -201,6 +323,19 @@ impl StoreConfig {
     }
 }
 
+/// Parse a freezer column directory of the form `TAG=DIR`, where `TAG` is the 3-byte column ID.
+pub fn parse_freezer_column_dir(value: &str) -> Result<(DBColumn, PathBuf), String> {
+    let (tag, dir) = value
+        .split_once('=')
+        .ok_or_else(|| format!("Freezer column dir {:?} is not of the form TAG=DIR", value))?;
+    let column =
+        DBColumn::from_str(tag).map_err(|_| format!("Unknown database column: {:?}", tag))?;
+    if dir.is_empty() {
+        return Err(format!("No directory given for database column {:?}", tag));
+    }
+    Ok((column, PathBuf::from(dir)))
+}
+
 impl StoreItem for OnDiskStoreConfig {
     fn db_column() -> DBColumn {
         DBColumn::BeaconMeta
@@ -233,7 +368,7 @@ mod test {
         AnchorInfo, Split,
     };
     use ssz::DecodeError;
-    use types::{Hash256, Slot};
+    use types::{Hash256, MinimalEthSpec, Slot};
 
     #[test]
     fn check_compatibility_ok() {
@@ -339,4 +474,85 @@ mod test {
         let config_out = OnDiskStoreConfig::from_store_bytes(&bytes).unwrap();
         assert_eq!(config_out, config);
     }
+
+    #[test]
+    fn parse_freezer_column_dir_ok() {
+        assert_eq!(
+            parse_freezer_column_dir("bsd=/mnt/diffs").unwrap(),
+            (DBColumn::BeaconStateDiff, PathBuf::from("/mnt/diffs"))
+        );
+    }
+
+    #[test]
+    fn parse_freezer_column_dir_invalid() {
+        assert!(parse_freezer_column_dir("/mnt/diffs").is_err());
+        assert!(parse_freezer_column_dir("xyz=/mnt/diffs").is_err());
+        assert!(parse_freezer_column_dir("bsd=").is_err());
+    }
+
+    #[test]
+    fn parse_fsync_policy() {
+        for policy in [
+            FsyncPolicy::Always,
+            FsyncPolicy::PerSlot,
+            FsyncPolicy::Periodic(Duration::from_millis(500)),
+        ] {
+            assert_eq!(policy.to_string().parse(), Ok(policy));
+        }
+        assert_eq!(
+            "periodic:2000".parse(),
+            Ok(FsyncPolicy::Periodic(Duration::from_secs(2)))
+        );
+        assert!("sometimes".parse::<FsyncPolicy>().is_err());
+        assert!("periodic:".parse::<FsyncPolicy>().is_err());
+
+        let store_config = StoreConfig {
+            hot_fsync_policy: FsyncPolicy::Periodic(Duration::ZERO),
+            ..Default::default()
+        };
+        assert!(matches!(
+            store_config.verify::<MinimalEthSpec>(),
+            Err(StoreConfigError::ZeroFsyncInterval)
+        ));
+    }
+
+    #[test]
+    fn verify_freezer_column_dirs() {
+        let store_config = StoreConfig {
+            freezer_column_dirs: vec![
+                (
+                    DBColumn::BeaconStateSnapshot,
+                    PathBuf::from("/mnt/snapshots"),
+                ),
+                (DBColumn::BeaconStateDiff, PathBuf::from("/mnt/diffs")),
+            ],
+            ..Default::default()
+        };
+        assert!(store_config.verify::<MinimalEthSpec>().is_ok());
+
+        let store_config = StoreConfig {
+            freezer_column_dirs: vec![(DBColumn::BeaconColdStateSummary, PathBuf::from("/mnt"))],
+            ..Default::default()
+        };
+        assert!(matches!(
+            store_config.verify::<MinimalEthSpec>(),
+            Err(StoreConfigError::ColumnNotRelocatable(
+                DBColumn::BeaconColdStateSummary
+            ))
+        ));
+
+        let store_config = StoreConfig {
+            freezer_column_dirs: vec![
+                (DBColumn::BeaconStateDiff, PathBuf::from("/mnt/a")),
+                (DBColumn::BeaconStateDiff, PathBuf::from("/mnt/b")),
+            ],
+            ..Default::default()
+        };
+        assert!(matches!(
+            store_config.verify::<MinimalEthSpec>(),
+            Err(StoreConfigError::DuplicateFreezerColumnDir(
+                DBColumn::BeaconStateDiff
+            ))
+        ));
+    }
 }
@@ -0,0 +1,187 @@
+//! Compact summaries of finalized epochs, stored in the freezer DB keyed by epoch.
+//!
+//! Summaries are written during the freezer migration so that epoch-based queries such as "who
+//! proposed in epoch N" can be answered with a single read instead of loading historic states.
+//! Epochs which were frozen before summaries were written are summarised from historic states by
+//! `backfill_epoch_summaries`.
+use crate::hot_cold_store::{HotColdDB, HotColdDBError};
+use crate::{get_key_for_col, DBColumn, Error, ItemStore, KeyValueStoreOp};
+use serde::{Deserialize, Serialize};
+use slog::debug;
+use ssz::{Decode, Encode};
+use ssz_derive::{Decode, Encode};
+use types::consts::altair::{
+    TIMELY_HEAD_FLAG_INDEX, TIMELY_SOURCE_FLAG_INDEX, TIMELY_TARGET_FLAG_INDEX,
+};
+use types::{BeaconState, Epoch, EthSpec, Hash256, Slot};
+
+/// The canonical blocks and participation of a finalized epoch.
+#[derive(Debug, PartialEq, Eq, Clone, Encode, Decode, Serialize, Deserialize)]
+pub struct EpochSummary {
+    pub epoch: Epoch,
+    /// The canonical blocks of the epoch in slot order. Skipped slots have no entry.
+    pub blocks: Vec<EpochSummaryBlock>,
+    /// The participation of active validators, as included on chain by the end of the following
+    /// epoch. `None` for epochs prior to Altair.
+    pub participation: Option<EpochParticipationSummary>,
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, Encode, Decode, Serialize, Deserialize)]
+pub struct EpochSummaryBlock {
+    pub slot: Slot,
+    pub block_root: Hash256,
+    #[serde(with = "serde_utils::quoted_u64")]
+    pub proposer_index: u64,
+}
+
+#[derive(Debug, Default, PartialEq, Eq, Clone, Encode, Decode, Serialize, Deserialize)]
+pub struct EpochParticipationSummary {
+    #[serde(with = "serde_utils::quoted_u64")]
+    pub active_validators: u64,
+    #[serde(with = "serde_utils::quoted_u64")]
+    pub timely_source: u64,
+    #[serde(with = "serde_utils::quoted_u64")]
+    pub timely_target: u64,
+    #[serde(with = "serde_utils::quoted_u64")]
+    pub timely_head: u64,
+}
+
+impl<E, Hot, Cold> HotColdDB<E, Hot, Cold>
+where
+    E: EthSpec,
+    Hot: ItemStore<E>,
+    Cold: ItemStore<E>,
+{
+    /// Load the summary of `epoch` from the freezer DB.
+    ///
+    /// Returns `None` if the epoch is not yet finalized, or was finalized before summaries were
+    /// written by this node.
+    pub fn get_epoch_summary(&self, epoch: Epoch) -> Result<Option<EpochSummary>, Error> {
+        self.cold_db
+            .get_bytes(
+                DBColumn::BeaconEpochSummary.into(),
+                &epoch.as_u64().to_be_bytes(),
+            )?
+            .map(|bytes| EpochSummary::from_ssz_bytes(&bytes).map_err(Into::into))
+            .transpose()
+    }
+
+    /// Compute the summary of `epoch` from the hot state with `state_root`, which must be the
+    /// state at the last slot of the following epoch.
+    pub(crate) fn compute_epoch_summary(
+        &self,
+        epoch: Epoch,
+        state_root: &Hash256,
+    ) -> Result<EpochSummary, Error> {
+        let state = self
+            .get_hot_state(state_root)?
+            .ok_or(HotColdDBError::MissingStateToFreeze(*state_root))?;
+        self.summarise_epoch(epoch, &state)
+    }
+
+    /// Summarise the finalized epochs which were frozen before summaries were written, e.g. by an
+    /// older version of Lighthouse.
+    ///
+    /// Epochs whose blocks have not been backfilled, or whose states are not stored in the
+    /// freezer DB, are skipped. Returns the number of epochs summarised.
+    pub fn backfill_epoch_summaries(&self) -> Result<usize, Error> {
+        let slots_per_epoch = E::slots_per_epoch();
+        let split_slot = self.get_split_slot();
+        let oldest_block_slot = self.get_oldest_block_slot();
+        let (state_lower_limit, state_upper_limit) = self.get_historic_state_limits();
+
+        let mut num_summarised = 0;
+        let mut epoch = oldest_block_slot.epoch(slots_per_epoch);
+        if epoch.start_slot(slots_per_epoch) < oldest_block_slot {
+            epoch += 1;
+        }
+        loop {
+            // Each epoch is summarised using the state at the last slot of the next epoch, which
+            // must have been frozen.
+            let state_slot = (epoch + 2).start_slot(slots_per_epoch) - 1;
+            if state_slot >= split_slot {
+                break;
+            }
+            let state_available =
+                state_slot <= state_lower_limit || state_slot >= state_upper_limit;
+            if state_available && self.get_epoch_summary(epoch)?.is_none() {
+                let state = self.load_cold_state_by_slot(state_slot)?;
+                let summary = self.summarise_epoch(epoch, &state)?;
+                self.cold_db
+                    .do_atomically(vec![self.epoch_summary_op(&summary)])?;
+                num_summarised += 1;
+            }
+            epoch += 1;
+        }
+
+        debug!(
+            self.log,
+            "Backfilled epoch summaries";
+            "num_summarised" => num_summarised,
+        );
+        Ok(num_summarised)
+    }
+
+    /// Compute the summary of `epoch` from `state`, which must be the state at the last slot of
+    /// the following epoch.
+    fn summarise_epoch(&self, epoch: Epoch, state: &BeaconState<E>) -> Result<EpochSummary, Error> {
+        let mut blocks = vec![];
+        let start_slot = epoch.start_slot(E::slots_per_epoch());
+        let mut previous_block_root = if start_slot == 0 {
+            None
+        } else {
+            Some(*state.get_block_root(start_slot - 1)?)
+        };
+        for slot in epoch.slot_iter(E::slots_per_epoch()) {
+            // Skipped slots repeat the root of the previous block.
+            let block_root = *state.get_block_root(slot)?;
+            if previous_block_root == Some(block_root) {
+                continue;
+            }
+            previous_block_root = Some(block_root);
+
+            let block = self
+                .get_blinded_block(&block_root)?
+                .ok_or(Error::BlockNotFound(block_root))?;
+            blocks.push(EpochSummaryBlock {
+                slot,
+                block_root,
+                proposer_index: block.message().proposer_index(),
+            });
+        }
+
+        let participation = match state.previous_epoch_participation() {
+            Ok(participation) => {
+                let mut summary = EpochParticipationSummary::default();
+                for (validator, flags) in state.validators().iter().zip(participation.iter()) {
+                    if !validator.is_active_at(epoch) {
+                        continue;
+                    }
+                    summary.active_validators += 1;
+                    summary.timely_source += flags.has_flag(TIMELY_SOURCE_FLAG_INDEX)? as u64;
+                    summary.timely_target += flags.has_flag(TIMELY_TARGET_FLAG_INDEX)? as u64;
+                    summary.timely_head += flags.has_flag(TIMELY_HEAD_FLAG_INDEX)? as u64;
+                }
+                Some(summary)
+            }
+            // Participation flags do not exist prior to Altair.
+            Err(_) => None,
+        };
+
+        Ok(EpochSummary {
+            epoch,
+            blocks,
+            participation,
+        })
+    }
+
+    pub(crate) fn epoch_summary_op(&self, summary: &EpochSummary) -> KeyValueStoreOp {
+        KeyValueStoreOp::PutKeyValue(
+            get_key_for_col(
+                DBColumn::BeaconEpochSummary.into(),
+                &summary.epoch.as_u64().to_be_bytes(),
+            ),
+            summary.as_ssz_bytes(),
+        )
+    }
+}
@@ -12,7 +12,10 @@ use std::str::FromStr;
 use std::sync::LazyLock;
 use superstruct::superstruct;
 use types::historical_summary::HistoricalSummary;
-use types::{BeaconState, ChainSpec, Epoch, EthSpec, Hash256, List, Slot, Validator};
+use types::{
+    typenum::Unsigned, BeaconBlockHeader, BeaconState, BitVector, ChainSpec, Checkpoint, Epoch,
+    Eth1Data, EthSpec, Fork, ForkName, Hash256, List, Slot, SyncCommittee, Validator,
+};
 use zstd::{Decoder, Encoder};
 
 static EMPTY_PUBKEY: LazyLock<PublicKeyBytes> = LazyLock::new(PublicKeyBytes::empty);
@@ -27,6 +30,7 @@ pub enum Error {
     Compression(std::io::Error),
     InvalidSszState(ssz::DecodeError),
     InvalidBalancesLength,
+    InvalidSszStateLayout,
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, Encode, Decode)]
@@ -230,6 +234,66 @@ impl HDiffBuffer {
         Ok(state)
     }
 
+    /// Encode the state held by this buffer as SSZ, without first converting it into a
+    /// `BeaconState`.
+    ///
+    /// This splices the separately stored lists back into the SSZ bytes of the rest of the state,
+    /// which is much faster than `as_state` for large states since it avoids building the tree
+    /// for each list.
+    pub fn as_ssz_bytes<E: EthSpec>(&self, spec: &ChainSpec) -> Result<Vec<u8>, Error> {
+        let _t = metrics::start_timer(&metrics::STORE_BEACON_HDIFF_BUFFER_INTO_SSZ_TIME);
+        let slot = self
+            .state
+            .get(STATE_SLOT_POSITION..STATE_SLOT_POSITION + 8)
+            .and_then(|bytes| bytes.try_into().ok())
+            .map(|bytes| Slot::new(u64::from_le_bytes(bytes)))
+            .ok_or(Error::InvalidSszStateLayout)?;
+        let layout = StateLayout::new::<E>(spec.fork_name_at_slot::<E>(slot));
+
+        let read_offset = |position: usize| {
+            self.state
+                .get(position..position + ssz::BYTES_PER_LENGTH_OFFSET)
+                .and_then(|bytes| bytes.try_into().ok())
+                .map(|bytes| u32::from_le_bytes(bytes) as usize)
+                .ok_or(Error::InvalidSszStateLayout)
+        };
+        let offsets = layout
+            .variable_fields
+            .iter()
+            .map(|(position, _)| read_offset(*position))
+            .collect::<Result<Vec<_>, _>>()?;
+        if offsets.first() != Some(&layout.fixed_len)
+            || offsets.windows(2).any(|w| w[0] > w[1])
+            || offsets.last() > Some(&self.state.len())
+        {
+            return Err(Error::InvalidSszStateLayout);
+        }
+
+        let mut bytes = Vec::with_capacity(self.state.len() + self.size());
+        bytes.extend_from_slice(&self.state[..layout.fixed_len]);
+        for (i, (position, field)) in layout.variable_fields.iter().enumerate() {
+            let offset = u32::try_from(bytes.len()).map_err(|_| Error::InvalidSszStateLayout)?;
+            bytes[*position..*position + ssz::BYTES_PER_LENGTH_OFFSET]
+                .copy_from_slice(&offset.to_le_bytes());
+
+            match field {
+                VariableField::HistoricalRoots => append_ssz(&self.historical_roots, &mut bytes),
+                VariableField::Validators => append_ssz(&self.validators, &mut bytes),
+                VariableField::Balances => append_ssz(&self.balances, &mut bytes),
+                VariableField::InactivityScores => append_ssz(&self.inactivity_scores, &mut bytes),
+                VariableField::HistoricalSummaries => {
+                    append_ssz(&self.historical_summaries, &mut bytes)
+                }
+                VariableField::Other => {
+                    let end = offsets.get(i + 1).copied().unwrap_or(self.state.len());
+                    bytes.extend_from_slice(&self.state[offsets[i]..end]);
+                }
+            }
+        }
+
+        Ok(bytes)
+    }
+
     /// Byte size of this instance
     pub fn size(&self) -> usize {
         self.state.len()
@@ -241,6 +305,106 @@ impl HDiffBuffer {
     }
 }
 
+/// The position of the `slot` field in an SSZ encoded `BeaconState`.
+const STATE_SLOT_POSITION: usize = 8 + 32;
+
+fn append_ssz<T: Encode>(items: &[T], bytes: &mut Vec<u8>) {
+    for item in items {
+        item.ssz_append(bytes);
+    }
+}
+
+/// The variable-length fields of a `BeaconState`, distinguishing those stored outside of the
+/// `HDiffBuffer::state` bytes.
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum VariableField {
+    HistoricalRoots,
+    Validators,
+    Balances,
+    InactivityScores,
+    HistoricalSummaries,
+    Other,
+}
+
+/// The layout of the fixed-length part of an SSZ encoded `BeaconState`.
+///
+/// This must be kept in sync with the fields of `BeaconState` for each fork.
+struct StateLayout {
+    /// The length of the fixed-length part.
+    fixed_len: usize,
+    /// The position of the offset of each variable-length field, in order.
+    variable_fields: Vec<(usize, VariableField)>,
+}
+
+impl StateLayout {
+    fn new<E: EthSpec>(fork_name: ForkName) -> Self {
+        let mut layout = Self {
+            fixed_len: 0,
+            variable_fields: vec![],
+        };
+        let hash_len = <Hash256 as Encode>::ssz_fixed_len();
+        let u64_len = <u64 as Encode>::ssz_fixed_len();
+
+        // genesis_time, genesis_validators_root, slot, fork, latest_block_header
+        layout.fixed(u64_len + hash_len + u64_len);
+        layout.fixed(<Fork as Encode>::ssz_fixed_len());
+        layout.fixed(<BeaconBlockHeader as Encode>::ssz_fixed_len());
+        // block_roots, state_roots
+        layout.fixed(2 * E::SlotsPerHistoricalRoot::to_usize() * hash_len);
+        layout.variable(VariableField::HistoricalRoots);
+        // eth1_data, eth1_data_votes, eth1_deposit_index
+        layout.fixed(<Eth1Data as Encode>::ssz_fixed_len());
+        layout.variable(VariableField::Other);
+        layout.fixed(u64_len);
+        layout.variable(VariableField::Validators);
+        layout.variable(VariableField::Balances);
+        // randao_mixes, slashings
+        layout.fixed(E::EpochsPerHistoricalVector::to_usize() * hash_len);
+        layout.fixed(E::EpochsPerSlashingsVector::to_usize() * u64_len);
+        // previous and current epoch attestations or participation
+        layout.variable(VariableField::Other);
+        layout.variable(VariableField::Other);
+        // justification_bits and checkpoints
+        layout.fixed(<BitVector<E::JustificationBitsLength> as Encode>::ssz_fixed_len());
+        layout.fixed(3 * <Checkpoint as Encode>::ssz_fixed_len());
+
+        if fork_name.altair_enabled() {
+            layout.variable(VariableField::InactivityScores);
+            // current_sync_committee, next_sync_committee
+            layout.fixed(2 * <SyncCommittee<E> as Encode>::ssz_fixed_len());
+        }
+        if fork_name.bellatrix_enabled() {
+            // latest_execution_payload_header
+            layout.variable(VariableField::Other);
+        }
+        if fork_name.capella_enabled() {
+            // next_withdrawal_index, next_withdrawal_validator_index
+            layout.fixed(2 * u64_len);
+            layout.variable(VariableField::HistoricalSummaries);
+        }
+        if fork_name.electra_enabled() {
+            // deposit_requests_start_index, deposit_balance_to_consume, exit_balance_to_consume,
+            // earliest_exit_epoch, consolidation_balance_to_consume, earliest_consolidation_epoch
+            layout.fixed(6 * u64_len);
+            // pending_balance_deposits, pending_partial_withdrawals, pending_consolidations
+            layout.variable(VariableField::Other);
+            layout.variable(VariableField::Other);
+            layout.variable(VariableField::Other);
+        }
+
+        layout
+    }
+
+    fn fixed(&mut self, len: usize) {
+        self.fixed_len += len;
+    }
+
+    fn variable(&mut self, field: VariableField) {
+        self.variable_fields.push((self.fixed_len, field));
+        self.fixed_len += ssz::BYTES_PER_LENGTH_OFFSET;
+    }
+}
+
 impl HDiff {
     pub fn compute(
         source: &HDiffBuffer,
@@ -64,6 +64,14 @@ impl<E: EthSpec> HistoricStateCache<E> {
         }
     }
 
+    /// Return `true` if a state or diff buffer for `slot` is cached, without updating recency.
+    ///
+    /// Either may be obtained from the other, so both `get_state` and `get_hdiff_buffer` will
+    /// succeed for such a `slot`.
+    pub fn contains(&self, slot: Slot) -> bool {
+        self.states.contains(&slot) || self.hdiff_buffers.contains(&slot)
+    }
+
     pub fn put_state(&mut self, slot: Slot, state: BeaconState<E>) {
         self.states.put(slot, state);
     }
@@ -1,3 +1,4 @@
+use crate::block_root_filter::BlockRootFilter;
 use crate::config::{OnDiskStoreConfig, StoreConfig};
 use crate::forwards_iter::{HybridForwardsBlockRootsIterator, HybridForwardsStateRootsIterator};
 use crate::hdiff::{HDiff, HDiffBuffer, HierarchyModuli, StorageStrategy};
@@ -7,10 +8,11 @@ use crate::iter::{BlockRootsIterator, ParentRootBlockIterator, RootsIterator};
 use crate::leveldb_store::{BytesKey, LevelDB};
 use crate::memory_store::MemoryStore;
 use crate::metadata::{
-    AnchorInfo, BlobInfo, CompactionTimestamp, DataColumnInfo, PruningCheckpoint, SchemaVersion,
-    ANCHOR_FOR_ARCHIVE_NODE, ANCHOR_INFO_KEY, ANCHOR_UNINITIALIZED, BLOB_INFO_KEY,
+    AnchorInfo, BlobInfo, CompactionTimestamp, DataColumnInfo, HotStateStorage, PruningCheckpoint,
+    SchemaVersion, ANCHOR_FOR_ARCHIVE_NODE, ANCHOR_INFO_KEY, ANCHOR_UNINITIALIZED, BLOB_INFO_KEY,
     COMPACTION_TIMESTAMP_KEY, CONFIG_KEY, CURRENT_SCHEMA_VERSION, DATA_COLUMN_INFO_KEY,
-    PRUNING_CHECKPOINT_KEY, SCHEMA_VERSION_KEY, SPLIT_KEY, STATE_UPPER_LIMIT_NO_RETAIN,
+    HOT_STATE_STORAGE_KEY, PRUNING_CHECKPOINT_KEY, SCHEMA_VERSION_KEY, SPLIT_KEY,
+    STATE_UPPER_LIMIT_NO_RETAIN,
 };
 use crate::state_cache::{PutStateOutcome, StateCache};
 use crate::{
@@ -36,7 +38,7 @@ use std::collections::{HashMap, HashSet};
 use std::io::{Read, Write};
 use std::marker::PhantomData;
 use std::num::NonZeroUsize;
-use std::path::Path;
+use std::path::{Path, PathBuf};
 use std::sync::Arc;
 use std::time::Duration;
 use types::data_column_sidecar::{ColumnIndex, DataColumnSidecar, DataColumnSidecarList};
@@ -72,6 +74,10 @@ pub struct HotColdDB<E: EthSpec, Hot: ItemStore<E>, Cold: ItemStore<E>> {
     pub hot_db: Hot,
     /// LRU cache of deserialized blocks and blobs. Updated whenever a block or blob is loaded.
     block_cache: Mutex<BlockCache<E>>,
+    /// Filter over the roots of all stored blocks, used to skip disk reads for unknown blocks.
+    ///
+    /// `None` if disabled by the config.
+    block_root_filter: Option<BlockRootFilter>,
     /// Cache of beacon states.
     ///
     /// LOCK ORDERING: this lock must always be locked *after* the `split` if both are required.
@@ -172,6 +178,15 @@ pub enum HotColdDBError {
     MissingFrozenBlock(Slot),
     MissingPathToBlobsDatabase,
     BlobsPreviouslyInDefaultStore,
+    /// A freezer column directory is the same as another database directory.
+    FreezerColumnDirConflict {
+        column: DBColumn,
+        path: PathBuf,
+    },
+    /// A freezer column is configured to use a separate directory but has data in the freezer DB.
+    FreezerColumnNotRelocated(DBColumn),
+    /// A freezer column was previously stored in a separate directory which is not configured.
+    MissingFreezerColumnDir(DBColumn),
     HotStateSummaryError(BeaconStateError),
     RestorePointDecodeError(ssz::DecodeError),
     BlockReplayBeaconError(BeaconStateError),
@@ -194,6 +209,8 @@ pub enum HotColdDBError {
         block_root: Hash256,
     },
     Rollback,
+    /// The block root filter's population thread couldn't be spawned.
+    BlockRootFilterThread(String),
 }
 
 impl<E: EthSpec> HotColdDB<E, MemoryStore<E>, MemoryStore<E>> {
@@ -215,6 +232,10 @@ impl<E: EthSpec> HotColdDB<E, MemoryStore<E>, MemoryStore<E>> {
             blobs_db: MemoryStore::open(),
             hot_db: MemoryStore::open(),
             block_cache: Mutex::new(BlockCache::new(config.block_cache_size)),
+            block_root_filter: config
+                .block_root_filter
+                .then(|| BlockRootFilter::new(config.block_root_filter_capacity))
+                .flatten(),
             state_cache: Mutex::new(StateCache::new(config.state_cache_size)),
             historic_state_cache: Mutex::new(HistoricStateCache::new(
                 config.hdiff_buffer_cache_size,
@@ -227,6 +248,11 @@ impl<E: EthSpec> HotColdDB<E, MemoryStore<E>, MemoryStore<E>> {
             _phantom: PhantomData,
         };
 
+        // The in-memory database starts empty, so the block root filter is complete.
+        if let Some(filter) = &db.block_root_filter {
+            filter.set_ready();
+        }
+
         Ok(db)
     }
 }
@@ -247,9 +273,33 @@ impl<E: EthSpec> HotColdDB<E, LevelDB<E>, LevelDB<E>> {
     ) -> Result<Arc<Self>, Error> {
         config.verify::<E>()?;
 
+        for (i, (column, path)) in config.freezer_column_dirs.iter().enumerate() {
+            let conflicts_with_db = [hot_path, cold_path, blobs_db_path].contains(&path.as_path());
+            let conflicts_with_column = config.freezer_column_dirs[..i]
+                .iter()
+                .any(|(_, other)| other == path);
+            if conflicts_with_db || conflicts_with_column {
+                return Err(HotColdDBError::FreezerColumnDirConflict {
+                    column: *column,
+                    path: path.clone(),
+                }
+                .into());
+            }
+        }
+
         let hierarchy = config.hierarchy_config.to_moduli()?;
 
-        let hot_db = LevelDB::open(hot_path)?;
+        // Writes batched before an unclean shutdown are replayed whether or not write batching is
+        // still enabled.
+        let wal_path = hot_path.with_extension("wal");
+        let mut hot_db = LevelDB::open(hot_path)?;
+        let replayed_batches = hot_db.replay_write_ahead_log(&wal_path)?;
+        if config.hot_write_batching {
+            hot_db.enable_write_batching(
+                &wal_path,
+                config.hot_fsync_policy.interval(spec.seconds_per_slot),
+            )?;
+        }
         let anchor_info = RwLock::new(Self::load_anchor_info(&hot_db)?);
 
         let db = HotColdDB {
@@ -257,10 +307,14 @@ impl<E: EthSpec> HotColdDB<E, LevelDB<E>, LevelDB<E>> {
             anchor_info,
             blob_info: RwLock::new(BlobInfo::default()),
             data_column_info: RwLock::new(DataColumnInfo::default()),
-            cold_db: LevelDB::open(cold_path)?,
+            cold_db: LevelDB::open_with_column_dirs(cold_path, &config.freezer_column_dirs)?,
             blobs_db: LevelDB::open(blobs_db_path)?,
             hot_db,
             block_cache: Mutex::new(BlockCache::new(config.block_cache_size)),
+            block_root_filter: config
+                .block_root_filter
+                .then(|| BlockRootFilter::new(config.block_root_filter_capacity))
+                .flatten(),
             state_cache: Mutex::new(StateCache::new(config.state_cache_size)),
             historic_state_cache: Mutex::new(HistoricStateCache::new(
                 config.hdiff_buffer_cache_size,
@@ -273,6 +327,24 @@ impl<E: EthSpec> HotColdDB<E, LevelDB<E>, LevelDB<E>> {
             _phantom: PhantomData,
         };
 
+        if replayed_batches > 0 {
+            warn!(
+                db.log,
+                "Replayed hot DB writes after unclean shutdown";
+                "batches" => replayed_batches,
+            );
+        }
+        if db.config.hot_write_batching {
+            info!(
+                db.log,
+                "Hot DB write batching enabled";
+                "fsync_policy" => %db.config.hot_fsync_policy,
+            );
+        }
+
+        // Check that the freezer columns stored in separate directories match the previous run.
+        db.cold_db.verify_column_dirs()?;
+
         // Load the config from disk but don't error on a failed read because the config itself may
         // need migrating.
         let _ = db.load_config();
@@ -403,6 +475,10 @@ impl<E: EthSpec> HotColdDB<E, LevelDB<E>, LevelDB<E>> {
             info!(db.log, "Foreground compaction complete");
         }
 
+        db.convert_hot_state_storage(db.config.hot_tree_states)?;
+
+        db.spawn_populate_block_root_filter()?;
+
         Ok(db)
     }
 
@@ -412,19 +488,23 @@ impl<E: EthSpec> HotColdDB<E, LevelDB<E>, LevelDB<E>> {
         let start_key =
             BytesKey::from_vec(get_key_for_col(column.into(), Hash256::zero().as_slice()));
 
+        // Batched writes must be applied before iterating the database.
+        let flush_result = self.hot_db.flush_writes();
         let keys_iter = self.hot_db.keys_iter();
         keys_iter.seek(&start_key);
 
-        keys_iter
-            .take_while(move |key| key.matches_column(column))
-            .map(move |bytes_key| {
-                bytes_key.remove_column(column).ok_or_else(|| {
-                    HotColdDBError::IterationError {
-                        unexpected_key: bytes_key,
-                    }
-                    .into()
-                })
-            })
+        flush_result.err().map(Err).into_iter().chain(
+            keys_iter
+                .take_while(move |key| key.matches_column(column))
+                .map(move |bytes_key| {
+                    bytes_key.remove_column(column).ok_or_else(|| {
+                        HotColdDBError::IterationError {
+                            unexpected_key: bytes_key,
+                        }
+                        .into()
+                    })
+                }),
+        )
     }
 }
 
@@ -440,6 +520,71 @@ impl<E: EthSpec, Hot: ItemStore<E>, Cold: ItemStore<E>> HotColdDB<E, Hot, Cold>
             .update_finalized_state(state_root, block_root, state)
     }
 
+    pub fn block_root_filter(&self) -> Option<&BlockRootFilter> {
+        self.block_root_filter.as_ref()
+    }
+
+    /// Populate the block root filter from the database on a background thread.
+    ///
+    /// Reading every block root takes a while on a large database, so it is done off the startup
+    /// path. The filter isn't consulted until population completes, and blocks stored in the
+    /// meantime are inserted as usual.
+    fn spawn_populate_block_root_filter(self: &Arc<Self>) -> Result<(), Error> {
+        if self.block_root_filter.is_none() {
+            return Ok(());
+        }
+
+        let db = self.clone();
+        std::thread::Builder::new()
+            .name("block_root_filter".into())
+            .spawn(move || {
+                if let Err(e) = db.populate_block_root_filter() {
+                    warn!(
+                        db.log,
+                        "Failed to populate block root filter";
+                        "info" => "the filter will remain disabled until restart",
+                        "error" => ?e,
+                    );
+                }
+            })
+            .map_err(|e| HotColdDBError::BlockRootFilterThread(e.to_string()))?;
+        Ok(())
+    }
+
+    /// Insert the root of every stored block into the block root filter.
+    ///
+    /// The filter isn't consulted until this completes successfully.
+    fn populate_block_root_filter(self: &Arc<Self>) -> Result<(), Error> {
+        let Some(filter) = &self.block_root_filter else {
+            return Ok(());
+        };
+
+        let t = std::time::Instant::now();
+        for (i, block_root) in self
+            .hot_db
+            .iter_column_keys::<Hash256>(DBColumn::BeaconBlock)
+            .enumerate()
+        {
+            // Stop early if the database is being shut down.
+            if i % 65_536 == 0 && Arc::strong_count(self) == 1 {
+                debug!(self.log, "Block root filter population cancelled");
+                return Ok(());
+            }
+            filter.insert(&block_root?);
+        }
+        filter.set_ready();
+
+        info!(
+            self.log,
+            "Block root filter populated";
+            "num_blocks" => filter.len(),
+            "size_bytes" => filter.size_bytes(),
+            "layers" => filter.num_layers(),
+            "time_ms" => t.elapsed().as_millis(),
+        );
+        Ok(())
+    }
+
     pub fn state_cache_len(&self) -> usize {
         self.state_cache.lock().len()
     }
@@ -451,6 +596,17 @@ impl<E: EthSpec, Hot: ItemStore<E>, Cold: ItemStore<E>> HotColdDB<E, Hot, Cold>
             &metrics::STORE_BEACON_BLOCK_CACHE_SIZE,
             self.block_cache.lock().block_cache.len() as i64,
         );
+        if let Some(filter) = &self.block_root_filter {
+            metrics::set_gauge(
+                &metrics::STORE_BLOCK_ROOT_FILTER_SIZE,
+                filter.size_bytes() as i64,
+            );
+            metrics::set_gauge(&metrics::STORE_BLOCK_ROOT_FILTER_ITEMS, filter.len() as i64);
+            metrics::set_float_gauge(
+                &metrics::STORE_BLOCK_ROOT_FILTER_ESTIMATED_FALSE_POSITIVE_RATE,
+                filter.estimated_false_positive_rate(),
+            );
+        }
         metrics::set_gauge(
             &metrics::STORE_BEACON_BLOB_CACHE_SIZE,
             self.block_cache.lock().blob_cache.len() as i64,
@@ -536,6 +692,12 @@ impl<E: EthSpec, Hot: ItemStore<E>, Cold: ItemStore<E>> HotColdDB<E, Hot, Cold>
         blinded_block: &SignedBeaconBlock<E, BlindedPayload<E>>,
         ops: &mut Vec<KeyValueStoreOp>,
     ) {
+        // Insert into the filter before the block is written so that the filter never reports a
+        // stored block as absent.
+        if let Some(filter) = &self.block_root_filter {
+            filter.insert(key);
+        }
+
         let db_key = get_key_for_col(DBColumn::BeaconBlock.into(), key.as_slice());
         ops.push(KeyValueStoreOp::PutKeyValue(
             db_key,
@@ -833,6 +995,41 @@ impl<E: EthSpec, Hot: ItemStore<E>, Cold: ItemStore<E>> HotColdDB<E, Hot, Cold>
         Ok(())
     }
 
+    /// Load the encoded record of the payloads considered when producing a block at `slot`.
+    pub fn get_block_value_record_bytes(&self, slot: Slot) -> Result<Option<Vec<u8>>, Error> {
+        self.hot_db.get_bytes(
+            DBColumn::BlockValueRecord.into(),
+            &slot.as_u64().to_be_bytes(),
+        )
+    }
+
+    /// Store the encoded record of the payloads considered when producing a block at `slot`, and
+    /// delete the records of slots prior to `oldest_retained`.
+    pub fn put_block_value_record_bytes(
+        &self,
+        slot: Slot,
+        record_bytes: &[u8],
+        oldest_retained: Slot,
+    ) -> Result<(), Error> {
+        let column = DBColumn::BlockValueRecord;
+        let mut ops = vec![KeyValueStoreOp::PutKeyValue(
+            get_key_for_col(column.into(), &slot.as_u64().to_be_bytes()),
+            record_bytes.to_vec(),
+        )];
+        // Keys are big-endian, so they are iterated in slot order.
+        for key in self.hot_db.iter_column_keys::<Vec<u8>>(column) {
+            let key = key?;
+            if key.as_slice() >= oldest_retained.as_u64().to_be_bytes().as_slice() {
+                break;
+            }
+            ops.push(KeyValueStoreOp::DeleteKey(get_key_for_col(
+                column.into(),
+                &key,
+            )));
+        }
+        self.hot_db.do_atomically(ops)
+    }
+
     /// Check if the blobs for a block exists on disk.
     pub fn blobs_exist(&self, block_root: &Hash256) -> Result<bool, Error> {
         self.blobs_db
@@ -840,9 +1037,30 @@ impl<E: EthSpec, Hot: ItemStore<E>, Cold: ItemStore<E>> HotColdDB<E, Hot, Cold>
     }
 
     /// Determine whether a block exists in the database.
+    ///
+    /// Unknown blocks are usually answered by the block root filter without reading from disk.
     pub fn block_exists(&self, block_root: &Hash256) -> Result<bool, Error> {
-        self.hot_db
-            .key_exists(DBColumn::BeaconBlock.into(), block_root.as_slice())
+        let filter = self
+            .block_root_filter
+            .as_ref()
+            .filter(|filter| filter.is_ready());
+        if filter.is_some_and(|filter| !filter.may_contain(block_root)) {
+            metrics::inc_counter_vec(&metrics::STORE_BLOCK_ROOT_FILTER_CHECKS, &["negative"]);
+            return Ok(false);
+        }
+
+        let exists = self
+            .hot_db
+            .key_exists(DBColumn::BeaconBlock.into(), block_root.as_slice())?;
+        if filter.is_some() {
+            let result = if exists {
+                "true_positive"
+            } else {
+                "false_positive"
+            };
+            metrics::inc_counter_vec(&metrics::STORE_BLOCK_ROOT_FILTER_CHECKS, &[result]);
+        }
+        Ok(exists)
     }
 
     /// Delete a block from the store and the block cache.
@@ -917,7 +1135,10 @@ impl<E: EthSpec, Hot: ItemStore<E>, Cold: ItemStore<E>> HotColdDB<E, Hot, Cold>
         temporary: bool,
     ) -> Result<(), Error> {
         let mut ops: Vec<KeyValueStoreOp> = Vec::new();
-        if state.slot() < self.get_split_slot() {
+        // Hold the split until the state is written, so that a diff can't be computed against a
+        // base which a concurrent migration is about to prune.
+        let split = self.split.read_recursive();
+        if state.slot() < split.slot {
             self.store_cold_state(state_root, state, &mut ops)?;
             self.cold_db.do_atomically(ops)
         } else {
@@ -1245,6 +1466,12 @@ impl<E: EthSpec, Hot: ItemStore<E>, Cold: ItemStore<E>> HotColdDB<E, Hot, Cold>
                         let state_key =
                             get_key_for_col(DBColumn::BeaconState.into(), state_root.as_slice());
                         key_value_batch.push(KeyValueStoreOp::DeleteKey(state_key));
+
+                        let diff_key = get_key_for_col(
+                            DBColumn::BeaconStateHotDiff.into(),
+                            state_root.as_slice(),
+                        );
+                        key_value_batch.push(KeyValueStoreOp::DeleteKey(diff_key));
                     }
                 }
 
@@ -1333,11 +1560,15 @@ impl<E: EthSpec, Hot: ItemStore<E>, Cold: ItemStore<E>> HotColdDB<E, Hot, Cold>
             .do_atomically(self.convert_to_kv_batch(blobs_ops)?)?;
 
         let hot_db_cache_ops = hot_db_ops.clone();
-        // Try to execute hot db store ops.
+        // Try to execute hot db store ops. The split is held until the ops are written, so that
+        // hot state diffs can't be computed against a base which a concurrent migration is about
+        // to prune.
+        let split_guard = self.split.read_recursive();
         let tx_res = match self.convert_to_kv_batch(hot_db_ops) {
             Ok(kv_store_ops) => self.hot_db.do_atomically(kv_store_ops),
             Err(e) => Err(e),
         };
+        drop(split_guard);
         // Rollback on failure
         if let Err(e) = tx_res {
             error!(
@@ -1461,8 +1692,63 @@ impl<E: EthSpec, Hot: ItemStore<E>, Cold: ItemStore<E>> HotColdDB<E, Hot, Cold>
             return Ok(());
         }
 
-        // On the epoch boundary, store the full state.
+        // On the epoch boundary, store the full state or a diff.
         if state.slot() % E::slots_per_epoch() == 0 {
+            self.store_hot_epoch_boundary_state(state_root, state, &self.get_split_info(), ops)?;
+        }
+
+        // Store a summary of the state.
+        // We store one even for the epoch boundary states, as we may need their slots
+        // when doing a look up by state root.
+        let hot_state_summary = HotStateSummary::new(state_root, state)?;
+        let op = hot_state_summary.as_kv_store_op(*state_root);
+        ops.push(op);
+
+        Ok(())
+    }
+
+    /// Store an epoch boundary state in the hot database, as a diff if hot tree-states is enabled
+    /// and a suitable base state is stored, or else as a full state.
+    fn store_hot_epoch_boundary_state(
+        &self,
+        state_root: &Hash256,
+        state: &BeaconState<E>,
+        split: &Split,
+        ops: &mut Vec<KeyValueStoreOp>,
+    ) -> Result<(), Error> {
+        if let Some((base_state_root, base_slot)) = self.hot_state_diff_base(state, split)? {
+            trace!(
+                self.log,
+                "Storing state diff on epoch boundary";
+                "slot" => state.slot().as_u64(),
+                "state_root" => ?state_root,
+                "base_slot" => base_slot.as_u64(),
+            );
+            let (base_buffer, _) = {
+                let _t =
+                    metrics::start_timer(&metrics::STORE_BEACON_HDIFF_BUFFER_LOAD_FOR_STORE_TIME);
+                self.load_hot_hdiff_buffer(&base_state_root)?
+            };
+            let target_buffer = HDiffBuffer::from_state(state.clone());
+            let diff = {
+                let _t = metrics::start_timer(&metrics::STORE_BEACON_HDIFF_BUFFER_COMPUTE_TIME);
+                HDiff::compute(&base_buffer, &target_buffer, &self.config)?
+            };
+            let hot_diff = HotStateDiff {
+                base_state_root,
+                base_slot,
+                diff,
+            };
+            let op = hot_diff.as_kv_store_op(*state_root);
+            if let KeyValueStoreOp::PutKeyValue(_, bytes) = &op {
+                metrics::inc_counter_by(
+                    &metrics::BEACON_HOT_STATE_DIFF_WRITE_BYTES,
+                    bytes.len() as u64,
+                );
+            }
+            metrics::inc_counter(&metrics::BEACON_HOT_STATE_DIFF_WRITE_COUNT);
+            ops.push(op);
+        } else {
             trace!(
                 self.log,
                 "Storing full state on epoch boundary";
@@ -1471,17 +1757,270 @@ impl<E: EthSpec, Hot: ItemStore<E>, Cold: ItemStore<E>> HotColdDB<E, Hot, Cold>
             );
             store_full_state(state_root, state, ops)?;
         }
+        Ok(())
+    }
 
-        // Store a summary of the state.
-        // We store one even for the epoch boundary states, as we may need their slots
-        // when doing a look up by state root.
-        let hot_state_summary = HotStateSummary::new(state_root, state)?;
-        let op = hot_state_summary.as_kv_store_op(*state_root);
-        ops.push(op);
+    /// Return the root and slot of the state that the hot epoch boundary `state` should be stored
+    /// as a diff against, or `None` if it should be stored in full.
+    ///
+    /// Diffs follow the hierarchy of the freezer database, offset so that the split state acts as
+    /// the snapshot. The base must be a stored, non-temporary ancestor of `state`.
+    fn hot_state_diff_base(
+        &self,
+        state: &BeaconState<E>,
+        split: &Split,
+    ) -> Result<Option<(Hash256, Slot)>, Error> {
+        if !self.config.hot_tree_states || state.slot() <= split.slot {
+            return Ok(None);
+        }
+
+        let base_offset = match self.hierarchy.storage_strategy(state.slot() - split.slot)? {
+            StorageStrategy::Snapshot => return Ok(None),
+            StorageStrategy::DiffFrom(offset) | StorageStrategy::ReplayFrom(offset) => offset,
+        };
+        // The split lies on an epoch boundary, so this can't move the base below it.
+        let base_slot = (split.slot + base_offset) / E::slots_per_epoch() * E::slots_per_epoch();
+
+        let base_state_root = match state.get_state_root(base_slot) {
+            Ok(state_root) => *state_root,
+            Err(_) if base_slot == split.slot => split.state_root,
+            // The base is too old to be found in the state's roots.
+            Err(_) => return Ok(None),
+        };
+
+        let base_stored = self.load_state_temporary_flag(&base_state_root)?.is_none()
+            && (self
+                .hot_db
+                .key_exists(DBColumn::BeaconState.into(), base_state_root.as_slice())?
+                || self.hot_db.key_exists(
+                    DBColumn::BeaconStateHotDiff.into(),
+                    base_state_root.as_slice(),
+                )?);
+
+        Ok(base_stored.then_some((base_state_root, base_slot)))
+    }
+
+    /// Load the `HDiffBuffer` for a hot epoch boundary state.
+    ///
+    /// If the buffer was derived from a state in the state cache, that state is also returned so
+    /// that states built from the buffer can share memory with it.
+    fn load_hot_hdiff_buffer(
+        &self,
+        state_root: &Hash256,
+    ) -> Result<(HDiffBuffer, Option<BeaconState<E>>), Error> {
+        if let Some(state) = self.state_cache.lock().get_by_state_root(*state_root) {
+            return Ok((HDiffBuffer::from_state(state.clone()), Some(state)));
+        }
+
+        if let Some(state) = get_full_state(&self.hot_db, state_root, &self.spec)? {
+            return Ok((HDiffBuffer::from_state(state), None));
+        }
+
+        // This amount of recursion (one level per diff layer) should be OK.
+        let hot_diff = self
+            .hot_db
+            .get::<HotStateDiff>(state_root)?
+            .ok_or(HotColdDBError::MissingEpochBoundaryState(*state_root))?;
+        let (mut buffer, cached_state) = self.load_hot_hdiff_buffer(&hot_diff.base_state_root)?;
+        {
+            let _t = metrics::start_timer(&metrics::STORE_BEACON_HDIFF_BUFFER_APPLY_TIME);
+            hot_diff.diff.apply(&mut buffer, &self.config)?;
+        }
+        Ok((buffer, cached_state))
+    }
 
+    /// Load an epoch boundary state from the hot database, whether it is stored in full or as a
+    /// diff.
+    ///
+    /// The state is rebased on the cached state its diffs were applied to, or otherwise on the
+    /// finalized state, so that it shares as much memory as possible with states in the cache.
+    fn load_hot_epoch_boundary_state(
+        &self,
+        state_root: &Hash256,
+    ) -> Result<Option<BeaconState<E>>, Error> {
+        if let Some(mut state) = get_full_state(&self.hot_db, state_root, &self.spec)? {
+            self.state_cache
+                .lock()
+                .rebase_on_finalized(&mut state, &self.spec)?;
+            return Ok(Some(state));
+        }
+
+        let Some(hot_diff) = self.hot_db.get::<HotStateDiff>(state_root)? else {
+            return Ok(None);
+        };
+        let _t = metrics::start_timer(&metrics::STORE_BEACON_HOT_STATE_DIFF_LOAD_TIME);
+        let (mut buffer, cached_base_state) =
+            self.load_hot_hdiff_buffer(&hot_diff.base_state_root)?;
+        {
+            let _t = metrics::start_timer(&metrics::STORE_BEACON_HDIFF_BUFFER_APPLY_TIME);
+            hot_diff.diff.apply(&mut buffer, &self.config)?;
+        }
+        let mut state = buffer.as_state::<E>(&self.spec)?;
+
+        if let Some(base_state) = cached_base_state {
+            state.rebase_on(&base_state, &self.spec)?;
+        } else {
+            self.state_cache
+                .lock()
+                .rebase_on_finalized(&mut state, &self.spec)?;
+        }
+        Ok(Some(state))
+    }
+
+    /// Re-store the hot states whose diff base would be pruned by advancing the split to
+    /// `new_split`.
+    ///
+    /// The new split state is stored in full, and other states are diffed relative to it. This
+    /// must be committed before the split is advanced, while holding the split write lock.
+    fn rebase_hot_state_diffs(&self, new_split: &Split) -> Result<(), Error> {
+        let mut ops = vec![];
+        let mut num_rebased = 0;
+        for res in self
+            .hot_db
+            .iter_column::<Hash256>(DBColumn::BeaconStateHotDiff)
+        {
+            let (state_root, bytes) = res?;
+            let hot_diff = HotStateDiff::from_store_bytes(&bytes)?;
+            if hot_diff.base_slot >= new_split.slot {
+                continue;
+            }
+
+            // Skip states which are about to be pruned.
+            let Some(summary) = self.load_hot_state_summary(&state_root)? else {
+                continue;
+            };
+            if summary.slot < new_split.slot
+                || (summary.slot == new_split.slot && state_root != new_split.state_root)
+            {
+                continue;
+            }
+
+            let state = self
+                .load_hot_epoch_boundary_state(&state_root)?
+                .ok_or(HotColdDBError::MissingEpochBoundaryState(state_root))?;
+            let mut state_ops = vec![];
+            self.store_hot_epoch_boundary_state(&state_root, &state, new_split, &mut state_ops)?;
+            // Delete the previous diff if the state is now stored in full.
+            if !state_ops.iter().any(|op| {
+                matches!(op, KeyValueStoreOp::PutKeyValue(key, _)
+                    if key.starts_with(DBColumn::BeaconStateHotDiff.as_bytes()))
+            }) {
+                ops.push(KeyValueStoreOp::DeleteKey(get_key_for_col(
+                    DBColumn::BeaconStateHotDiff.into(),
+                    state_root.as_slice(),
+                )));
+            }
+            ops.extend(state_ops);
+            num_rebased += 1;
+        }
+
+        if num_rebased > 0 {
+            self.hot_db.do_atomically(ops)?;
+            debug!(
+                self.log,
+                "Rebased hot state diffs";
+                "num_states" => num_rebased,
+                "split_slot" => new_split.slot,
+            );
+        }
         Ok(())
     }
 
+    /// Convert the epoch boundary states in the hot database to the format determined by
+    /// `hot_tree_states`: diffs if `true`, otherwise full states.
+    ///
+    /// States are converted one at a time, so that the database remains consistent if this is
+    /// interrupted. The format is recorded once every state has been converted, and the
+    /// conversion is skipped while the recorded format matches.
+    pub fn convert_hot_state_storage(&self, hot_tree_states: bool) -> Result<(), Error> {
+        self.convert_hot_state_storage_up_to(hot_tree_states, usize::MAX)
+            .map(|_| ())
+    }
+
+    /// Convert at most `max_states` hot epoch boundary states, as in `convert_hot_state_storage`.
+    ///
+    /// Return `true` if the conversion is complete. Stopping early leaves the database as it
+    /// would be if the conversion were interrupted.
+    pub fn convert_hot_state_storage_up_to(
+        &self,
+        hot_tree_states: bool,
+        max_states: usize,
+    ) -> Result<bool, Error> {
+        let target = HotStateStorage { hot_tree_states };
+        match self.hot_db.get::<HotStateStorage>(&HOT_STATE_STORAGE_KEY)? {
+            Some(storage) if storage == target => return Ok(true),
+            // Forget the previous format until this conversion completes.
+            Some(_) => self
+                .hot_db
+                .delete::<HotStateStorage>(&HOT_STATE_STORAGE_KEY)?,
+            None => (),
+        }
+
+        let (from_column, to_column) = if hot_tree_states {
+            (DBColumn::BeaconState, DBColumn::BeaconStateHotDiff)
+        } else {
+            (DBColumn::BeaconStateHotDiff, DBColumn::BeaconState)
+        };
+        let split = self.get_split_info();
+        let state_roots = self
+            .hot_db
+            .iter_column_keys::<Hash256>(from_column)
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let mut num_converted = 0;
+        for state_root in state_roots {
+            if state_root == split.state_root {
+                continue;
+            }
+            if num_converted == max_states {
+                return Ok(false);
+            }
+            let state = self
+                .load_hot_epoch_boundary_state(&state_root)?
+                .ok_or(HotColdDBError::MissingEpochBoundaryState(state_root))?;
+
+            let mut ops = vec![];
+            if hot_tree_states {
+                self.store_hot_epoch_boundary_state(&state_root, &state, &split, &mut ops)?;
+            } else {
+                store_full_state(&state_root, &state, &mut ops)?;
+            }
+            let converted = ops.iter().any(|op| {
+                matches!(op, KeyValueStoreOp::PutKeyValue(key, _)
+                    if key.starts_with(to_column.as_bytes()))
+            });
+            if !converted {
+                continue;
+            }
+            ops.push(KeyValueStoreOp::DeleteKey(get_key_for_col(
+                from_column.into(),
+                state_root.as_slice(),
+            )));
+            self.hot_db.do_atomically(ops)?;
+
+            num_converted += 1;
+            if num_converted % 64 == 0 {
+                info!(
+                    self.log,
+                    "Converting hot states";
+                    "num_converted" => num_converted,
+                );
+            }
+        }
+
+        self.hot_db.put_sync(&HOT_STATE_STORAGE_KEY, &target)?;
+
+        if num_converted > 0 {
+            info!(
+                self.log,
+                "Hot state conversion complete";
+                "num_states" => num_converted,
+                "hot_tree_states" => hot_tree_states,
+            );
+        }
+        Ok(true)
+    }
+
     /// Get a post-finalization state from the database or store.
     pub fn get_hot_state(&self, state_root: &Hash256) -> Result<Option<BeaconState<E>>, Error> {
         if let Some(state) = self.state_cache.lock().get_by_state_root(*state_root) {
@@ -1541,16 +2080,13 @@ impl<E: EthSpec, Hot: ItemStore<E>, Cold: ItemStore<E>> HotColdDB<E, Hot, Cold>
             epoch_boundary_state_root,
         }) = self.load_hot_state_summary(state_root)?
         {
-            let mut boundary_state =
-                get_full_state(&self.hot_db, &epoch_boundary_state_root, &self.spec)?.ok_or(
-                    HotColdDBError::MissingEpochBoundaryState(epoch_boundary_state_root),
-                )?;
-
-            // Immediately rebase the state from disk on the finalized state so that we can reuse
+            // The boundary state is rebased on a cached state immediately so that we can reuse
             // parts of the tree for state root calculation in `replay_blocks`.
-            self.state_cache
-                .lock()
-                .rebase_on_finalized(&mut boundary_state, &self.spec)?;
+            let boundary_state = self
+                .load_hot_epoch_boundary_state(&epoch_boundary_state_root)?
+                .ok_or(HotColdDBError::MissingEpochBoundaryState(
+                    epoch_boundary_state_root,
+                ))?;
 
             // Optimization to avoid even *thinking* about replaying blocks if we're already
             // on an epoch boundary.
@@ -1804,6 +2340,72 @@ impl<E: EthSpec, Hot: ItemStore<E>, Cold: ItemStore<E>> HotColdDB<E, Hot, Cold>
         }
     }
 
+    /// Load the SSZ bytes of a pre-finalization state directly from the diff hierarchy.
+    ///
+    /// This avoids decoding the state, which makes it much faster than `load_cold_state_by_slot`
+    /// for serving states over the network. Return `None` if the state at `slot` is not stored as
+    /// a snapshot or diff, in which case it can only be reconstructed by replaying blocks.
+    pub fn load_cold_state_ssz_by_slot(&self, slot: Slot) -> Result<Option<Vec<u8>>, Error> {
+        match self.hierarchy.storage_strategy(slot)? {
+            StorageStrategy::Snapshot | StorageStrategy::DiffFrom(_) => {
+                let (_, buffer) = self.load_hdiff_buffer_for_slot(slot)?;
+                Ok(Some(buffer.as_ssz_bytes::<E>(&self.spec)?))
+            }
+            StorageStrategy::ReplayFrom(_) => Ok(None),
+        }
+    }
+
+    /// Estimate the work required to load the pre-finalization state at `slot`, without loading
+    /// it.
+    ///
+    /// This follows the same path as `load_cold_state_by_slot`, taking into account states and
+    /// diff buffers which are already present in the historic state cache.
+    pub fn estimate_cold_state_load_cost(&self, slot: Slot) -> Result<StateLoadCost, Error> {
+        let storage_strategy = self.hierarchy.storage_strategy(slot)?;
+        let cached_slot = {
+            let historic_state_cache = self.historic_state_cache.lock();
+            storage_strategy
+                .replay_from_range(slot)
+                .rev()
+                .find(|prior_slot| historic_state_cache.contains(*prior_slot))
+        };
+        if let Some(cached_slot) = cached_slot {
+            return Ok(StateLoadCost {
+                blocks_to_replay: slot.as_u64().saturating_sub(cached_slot.as_u64()),
+                diffs_to_apply: 0,
+            });
+        }
+
+        match storage_strategy {
+            StorageStrategy::Snapshot | StorageStrategy::DiffFrom(_) => Ok(StateLoadCost {
+                blocks_to_replay: 0,
+                diffs_to_apply: self.count_hdiffs_to_apply(slot)?,
+            }),
+            StorageStrategy::ReplayFrom(from) => {
+                let base_cost = self.estimate_cold_state_load_cost(from)?;
+                Ok(StateLoadCost {
+                    blocks_to_replay: base_cost
+                        .blocks_to_replay
+                        .saturating_add(slot.as_u64().saturating_sub(from.as_u64())),
+                    diffs_to_apply: base_cost.diffs_to_apply,
+                })
+            }
+        }
+    }
+
+    /// Count the diffs which `load_hdiff_buffer_for_slot` would apply to load the buffer at
+    /// `slot`.
+    fn count_hdiffs_to_apply(&self, slot: Slot) -> Result<u64, Error> {
+        if self.historic_state_cache.lock().contains(slot) {
+            return Ok(0);
+        }
+        match self.hierarchy.storage_strategy(slot)? {
+            StorageStrategy::Snapshot => Ok(0),
+            StorageStrategy::DiffFrom(from) => Ok(self.count_hdiffs_to_apply(from)? + 1),
+            StorageStrategy::ReplayFrom(from) => self.count_hdiffs_to_apply(from),
+        }
+    }
+
     fn load_cold_state_by_slot_using_replay(
         &self,
         mut base_state: BeaconState<E>,
@@ -3094,6 +3696,24 @@ pub fn migrate_database<E: EthSpec, Hot: ItemStore<E>, Cold: ItemStore<E>>(
             non_checkpoint_block_roots.remove(&block_root);
         }
 
+        // Participation in an epoch is final once the following epoch has ended, so each epoch is
+        // summarised using the state at the last slot of the next epoch. Epochs whose blocks are
+        // yet to be backfilled are not summarised.
+        if (slot + 1) % E::slots_per_epoch() == 0 && slot >= E::slots_per_epoch() {
+            let epoch = slot.epoch(E::slots_per_epoch()) - 1;
+            if epoch.start_slot(E::slots_per_epoch()) >= anchor_info.oldest_block_slot {
+                match store.compute_epoch_summary(epoch, &state_root) {
+                    Ok(summary) => cold_db_block_ops.push(store.epoch_summary_op(&summary)),
+                    Err(e) => warn!(
+                        store.log,
+                        "Unable to summarise epoch";
+                        "epoch" => epoch,
+                        "error" => ?e,
+                    ),
+                }
+            }
+        }
+
         // Delete the old summary, and the full state if we lie on an epoch boundary.
         hot_db_ops.push(StoreOp::DeleteState(state_root, Some(slot)));
 
@@ -3153,6 +3773,13 @@ pub fn migrate_database<E: EthSpec, Hot: ItemStore<E>, Cold: ItemStore<E>>(
     // exceedingly rare event, this should be an acceptable tradeoff.
     store.cold_db.do_atomically(cold_db_block_ops)?;
     store.cold_db.sync()?;
+
+    let new_split = Split {
+        slot: finalized_state.slot(),
+        state_root: finalized_state_root,
+        block_root: finalized_block_root,
+    };
+
     {
         let mut split_guard = store.split.write();
         let latest_split_slot = split_guard.slot;
@@ -3174,19 +3801,20 @@ pub fn migrate_database<E: EthSpec, Hot: ItemStore<E>, Cold: ItemStore<E>>(
             ));
         }
 
+        // Re-store hot states which are diffed against states that are about to be pruned. This
+        // is written before the split is advanced, so that the hot states remain loadable from
+        // either split. Holding the split lock prevents new diffs from being written against the
+        // old split in the meantime.
+        store.rebase_hot_state_diffs(&new_split)?;
+
         // Before updating the in-memory split value, we flush it to disk first, so that should the
         // OS process die at this point, we pick up from the right place after a restart.
-        let split = Split {
-            slot: finalized_state.slot(),
-            state_root: finalized_state_root,
-            block_root: finalized_block_root,
-        };
-        store.hot_db.put_sync(&SPLIT_KEY, &split)?;
+        store.hot_db.put_sync(&SPLIT_KEY, &new_split)?;
 
         // Split point is now persisted in the hot database on disk. The in-memory split point
         // hasn't been modified elsewhere since we keep a write lock on it. It's safe to update
         // the in-memory split point now.
-        *split_guard = split;
+        *split_guard = new_split;
     }
 
     // Delete the blocks and states from the hot database if we got this far.
@@ -3208,6 +3836,15 @@ pub fn migrate_database<E: EthSpec, Hot: ItemStore<E>, Cold: ItemStore<E>>(
     Ok(())
 }
 
+/// An estimate of the work required to load a state from the database.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct StateLoadCost {
+    /// The number of blocks which must be replayed on top of a stored or cached state.
+    pub blocks_to_replay: u64,
+    /// The number of hierarchical diffs which must be applied to a snapshot.
+    pub diffs_to_apply: u64,
+}
+
 /// Struct for storing the split slot and state root in the database.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Encode, Decode, Deserialize, Serialize)]
 pub struct Split {
@@ -3289,6 +3926,29 @@ impl HotStateSummary {
     }
 }
 
+/// An epoch boundary state in the hot database, stored as a diff against an ancestor epoch
+/// boundary state.
+#[derive(Debug, Encode, Decode)]
+pub struct HotStateDiff {
+    pub base_state_root: Hash256,
+    pub base_slot: Slot,
+    pub diff: HDiff,
+}
+
+impl StoreItem for HotStateDiff {
+    fn db_column() -> DBColumn {
+        DBColumn::BeaconStateHotDiff
+    }
+
+    fn as_store_bytes(&self) -> Vec<u8> {
+        self.as_ssz_bytes()
+    }
+
+    fn from_store_bytes(bytes: &[u8]) -> Result<Self, Error> {
+        Ok(Self::from_ssz_bytes(bytes)?)
+    }
+}
+
 /// Struct for summarising a state in the freezer database.
 #[derive(Debug, Clone, Copy, Default, Encode, Decode)]
 pub(crate) struct ColdStateSummary {
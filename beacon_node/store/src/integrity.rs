@@ -0,0 +1,246 @@
+//! Verification of the finalized blocks, blobs and states in the freezer against their indices.
+//!
+//! Corruption of historic data otherwise goes unnoticed until the data is requested, so these
+//! checks re-hash every item and compare it to the root it is indexed by.
+use crate::hdiff::StorageStrategy;
+use crate::hot_cold_store::HotColdDB;
+use crate::{DBColumn, Error, ItemStore};
+use std::cmp::{max, min};
+use types::{BlindedPayload, BlobSidecarList, EthSpec, Hash256, SignedBeaconBlock, Slot};
+
+/// An item which is missing or does not match the root it is indexed by.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Corruption {
+    /// The block at `slot` in the block roots index is missing.
+    MissingBlock { slot: Slot, block_root: Hash256 },
+    /// The block at `slot` in the block roots index could not be decoded, or is not the block
+    /// with root `block_root`.
+    InvalidBlock {
+        slot: Slot,
+        block_root: Hash256,
+        error: String,
+    },
+    /// The blobs of a block which should still have them are missing.
+    MissingBlobs { slot: Slot, block_root: Hash256 },
+    /// The blobs of a block could not be decoded or do not match its commitments.
+    InvalidBlobs {
+        slot: Slot,
+        block_root: Hash256,
+        error: String,
+    },
+    /// The state at `slot` is missing from the state roots index, could not be loaded or does not
+    /// have the indexed root.
+    InvalidState {
+        slot: Slot,
+        state_root: Option<Hash256>,
+        error: String,
+    },
+}
+
+/// The outcome of a call to `HotColdDB::verify_freezer_integrity`.
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct IntegrityReport {
+    pub blocks_checked: u64,
+    pub blobs_checked: u64,
+    pub states_checked: u64,
+    pub corruptions: Vec<Corruption>,
+}
+
+impl<E, Hot, Cold> HotColdDB<E, Hot, Cold>
+where
+    E: EthSpec,
+    Hot: ItemStore<E>,
+    Cold: ItemStore<E>,
+{
+    /// Check the finalized data from `start_slot` up to (but excluding) `end_slot`.
+    ///
+    /// Every block in the block roots index is re-hashed, along with its blobs if they have not
+    /// been pruned. If `verify_states` is set, every state which is stored as a snapshot or diff is
+    /// also loaded and re-hashed, which is much slower. States which are only reconstructed by
+    /// replaying blocks are not checked since they are not stored.
+    ///
+    /// The contents of blobs are not checked against their KZG commitments, only that the
+    /// commitments match the block.
+    pub fn verify_freezer_integrity(
+        &self,
+        start_slot: Slot,
+        end_slot: Slot,
+        verify_states: bool,
+    ) -> Result<IntegrityReport, Error> {
+        let end_slot = min(end_slot, self.get_split_slot());
+        let anchor = self.get_anchor_info();
+        let mut report = IntegrityReport::default();
+
+        let block_start_slot = max(start_slot, anchor.oldest_block_slot);
+        if block_start_slot < end_slot {
+            let mut previous_block_root = None;
+            let block_roots =
+                self.forwards_block_roots_iterator_until(block_start_slot, end_slot - 1, || {
+                    Err(Error::StateShouldNotBeRequired(end_slot - 1))
+                })?;
+            for result in block_roots {
+                let (block_root, slot) = result?;
+                // Skipped slots repeat the root of the previous block.
+                if previous_block_root == Some(block_root) {
+                    continue;
+                }
+                previous_block_root = Some(block_root);
+                self.verify_frozen_block(block_root, slot, &mut report);
+            }
+        }
+
+        if verify_states {
+            for slot in (start_slot.as_u64()..end_slot.as_u64()).map(Slot::new) {
+                let is_stored = matches!(
+                    self.hierarchy.storage_strategy(slot)?,
+                    StorageStrategy::Snapshot | StorageStrategy::DiffFrom(_)
+                );
+                let is_available =
+                    slot <= anchor.state_lower_limit || slot >= anchor.state_upper_limit;
+                if is_stored && is_available {
+                    self.verify_frozen_state(slot, &mut report)?;
+                }
+            }
+        }
+
+        Ok(report)
+    }
+
+    fn verify_frozen_block(&self, block_root: Hash256, slot: Slot, report: &mut IntegrityReport) {
+        report.blocks_checked += 1;
+        let block = match self.get_blinded_block(&block_root) {
+            Ok(Some(block)) => block,
+            Ok(None) => {
+                report
+                    .corruptions
+                    .push(Corruption::MissingBlock { slot, block_root });
+                return;
+            }
+            Err(e) => {
+                report.corruptions.push(Corruption::InvalidBlock {
+                    slot,
+                    block_root,
+                    error: format!("{e:?}"),
+                });
+                return;
+            }
+        };
+
+        let computed_root = block.canonical_root();
+        let error = if computed_root != block_root {
+            Some(format!("block has root {computed_root:?}"))
+        } else if block.slot() > slot {
+            Some(format!("block has later slot {}", block.slot()))
+        } else {
+            None
+        };
+        if let Some(error) = error {
+            report.corruptions.push(Corruption::InvalidBlock {
+                slot,
+                block_root,
+                error,
+            });
+            return;
+        }
+
+        // Blobs are pruned below the oldest blob slot and replaced by data columns under PeerDAS.
+        let num_blobs = block.num_expected_blobs();
+        let has_blobs = self
+            .get_blob_info()
+            .oldest_blob_slot
+            .is_some_and(|oldest_blob_slot| block.slot() >= oldest_blob_slot)
+            && !self.spec.is_peer_das_enabled_for_epoch(block.epoch());
+        if num_blobs == 0 || !has_blobs {
+            return;
+        }
+        report.blobs_checked += num_blobs as u64;
+        match self.get_blobs(&block_root) {
+            Ok(Some(blobs)) => {
+                if let Err(error) = check_blobs(&block, block_root, &blobs) {
+                    report.corruptions.push(Corruption::InvalidBlobs {
+                        slot,
+                        block_root,
+                        error,
+                    });
+                }
+            }
+            Ok(None) => report
+                .corruptions
+                .push(Corruption::MissingBlobs { slot, block_root }),
+            Err(e) => report.corruptions.push(Corruption::InvalidBlobs {
+                slot,
+                block_root,
+                error: format!("{e:?}"),
+            }),
+        }
+    }
+
+    fn verify_frozen_state(&self, slot: Slot, report: &mut IntegrityReport) -> Result<(), Error> {
+        report.states_checked += 1;
+        let Some(state_root) = self
+            .cold_db
+            .get_bytes(
+                DBColumn::BeaconStateRoots.into(),
+                &slot.as_u64().to_be_bytes(),
+            )?
+            .filter(|bytes| bytes.len() == 32)
+            .map(|bytes| Hash256::from_slice(&bytes))
+        else {
+            report.corruptions.push(Corruption::InvalidState {
+                slot,
+                state_root: None,
+                error: "missing from the state roots index".to_string(),
+            });
+            return Ok(());
+        };
+
+        let error = match self.load_cold_state_by_slot(slot) {
+            Ok(mut state) => match state.canonical_root() {
+                Ok(computed_root) if computed_root == state_root && state.slot() == slot => None,
+                Ok(computed_root) => Some(format!(
+                    "state at slot {} has root {computed_root:?}",
+                    state.slot()
+                )),
+                Err(e) => Some(format!("{e:?}")),
+            },
+            Err(e) => Some(format!("{e:?}")),
+        };
+        if let Some(error) = error {
+            report.corruptions.push(Corruption::InvalidState {
+                slot,
+                state_root: Some(state_root),
+                error,
+            });
+        }
+        Ok(())
+    }
+}
+
+/// Check that `blobs` are the blobs committed to by `block`.
+fn check_blobs<E: EthSpec>(
+    block: &SignedBeaconBlock<E, BlindedPayload<E>>,
+    block_root: Hash256,
+    blobs: &BlobSidecarList<E>,
+) -> Result<(), String> {
+    let commitments = block
+        .message()
+        .body()
+        .blob_kzg_commitments()
+        .map_err(|_| "block has no blob commitments".to_string())?;
+    if blobs.len() != commitments.len() {
+        return Err(format!(
+            "expected {} blobs, found {}",
+            commitments.len(),
+            blobs.len()
+        ));
+    }
+    for (blob, commitment) in blobs.iter().zip(commitments.iter()) {
+        if blob.block_root() != block_root
+            || blob.kzg_commitment != *commitment
+            || !blob.verify_blob_sidecar_inclusion_proof()
+        {
+            return Err(format!("blob {} does not match the block", blob.index));
+        }
+    }
+    Ok(())
+}
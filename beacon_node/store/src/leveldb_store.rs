@@ -1,5 +1,7 @@
 use super::*;
 use crate::hot_cold_store::HotColdDBError;
+use crate::metadata::FREEZER_COLUMN_DIRS_KEY;
+use crate::write_batcher::{self, WriteBatcher};
 use leveldb::compaction::Compaction;
 use leveldb::database::batch::{Batch, Writebatch};
 use leveldb::database::kv::KV;
@@ -9,33 +11,170 @@ use leveldb::iterator::{Iterable, KeyIterator, LevelDBIterator};
 use leveldb::options::{Options, ReadOptions, WriteOptions};
 use parking_lot::Mutex;
 use std::marker::PhantomData;
-use std::path::Path;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+use std::sync::Arc;
+use std::time::Duration;
 
 /// A wrapped leveldb database.
 pub struct LevelDB<E: EthSpec> {
-    db: Database<BytesKey>,
+    db: Arc<Database<BytesKey>>,
+    /// Databases holding columns which are stored in a separate directory to `db`.
+    column_dbs: Vec<(DBColumn, Database<BytesKey>)>,
     /// A mutex to synchronise sensitive read-write transactions.
     transaction_mutex: Mutex<()>,
+    /// Applies writes to `db` in the background, if write batching is enabled.
+    write_batcher: Option<WriteBatcher>,
     _phantom: PhantomData<E>,
 }
 
 impl<E: EthSpec> LevelDB<E> {
     /// Open a database at `path`, creating a new database if one does not already exist.
     pub fn open(path: &Path) -> Result<Self, Error> {
-        let mut options = Options::new();
-
-        options.create_if_missing = true;
+        Self::open_with_column_dirs(path, &[])
+    }
 
-        let db = Database::open(path, options)?;
+    /// Open a database at `path`, storing each of the given columns in its own database in the
+    /// accompanying directory rather than at `path`.
+    pub fn open_with_column_dirs(
+        path: &Path,
+        column_dirs: &[(DBColumn, PathBuf)],
+    ) -> Result<Self, Error> {
+        let open_db = |path: &Path| {
+            let mut options = Options::new();
+            options.create_if_missing = true;
+            Database::open(path, options)
+        };
+
+        let db = Arc::new(open_db(path)?);
+        let column_dbs = column_dirs
+            .iter()
+            .map(|(column, dir)| Ok((*column, open_db(dir)?)))
+            .collect::<Result<_, Error>>()?;
         let transaction_mutex = Mutex::new(());
 
         Ok(Self {
             db,
+            column_dbs,
             transaction_mutex,
+            write_batcher: None,
             _phantom: PhantomData,
         })
     }
 
+    /// Apply any writes left in the write-ahead log at `wal_path` by an unclean shutdown.
+    ///
+    /// Returns the number of batches replayed. This must be called before write batching is
+    /// enabled, and should be called even if it won't be.
+    pub fn replay_write_ahead_log(&self, wal_path: &Path) -> Result<usize, Error> {
+        write_batcher::replay(&self.db, wal_path)
+    }
+
+    /// Write to the database in the background via the write-ahead log at `wal_path`, which is
+    /// fsynced every `fsync_interval` or after every batch if `None`.
+    pub fn enable_write_batching(
+        &mut self,
+        wal_path: &Path,
+        fsync_interval: Option<Duration>,
+    ) -> Result<(), Error> {
+        if !self.column_dbs.is_empty() {
+            return Err(Error::DBError {
+                message: "Write batching is not supported with relocated columns".into(),
+            });
+        }
+        self.write_batcher = Some(WriteBatcher::start(
+            self.db.clone(),
+            wal_path,
+            fsync_interval,
+        )?);
+        Ok(())
+    }
+
+    /// Wait for all batched writes to be applied to the database.
+    ///
+    /// This is required before reading from the database other than via `get_bytes` or
+    /// `key_exists`, which also read writes which are still pending.
+    pub fn flush_writes(&self) -> Result<(), Error> {
+        match &self.write_batcher {
+            Some(write_batcher) => write_batcher.flush(),
+            None => Ok(()),
+        }
+    }
+
+    /// Return the database which holds `col`.
+    fn db_for_col(&self, col: &str) -> &Database<BytesKey> {
+        self.column_dbs
+            .iter()
+            .find(|(column, _)| column.as_str() == col)
+            .map_or(&*self.db, |(_, db)| db)
+    }
+
+    /// Check that the columns stored in separate directories match those recorded on disk, then
+    /// record the current set of columns.
+    ///
+    /// Data is not moved between directories automatically, so this fails if a column which is
+    /// configured to use a separate directory still has data in the main database, or if a
+    /// column which was previously stored in a separate directory is no longer configured to be.
+    pub fn verify_column_dirs(&self) -> Result<(), Error> {
+        for (column, _) in &self.column_dbs {
+            let start_key = BytesKey::from_vec(get_key_for_col(column.as_str(), &[]));
+            let iter = self.db.keys_iter(self.read_options());
+            iter.seek(&start_key);
+            if iter.take(1).any(|key| key.matches_column(*column)) {
+                return Err(HotColdDBError::FreezerColumnNotRelocated(*column).into());
+            }
+        }
+
+        for column in self.load_relocated_columns()? {
+            if !self.column_dbs.iter().any(|(other, _)| *other == column) {
+                return Err(HotColdDBError::MissingFreezerColumnDir(column).into());
+            }
+        }
+
+        let columns = self
+            .column_dbs
+            .iter()
+            .map(|(column, _)| *column)
+            .collect::<Vec<_>>();
+        self.store_relocated_columns(&columns)
+    }
+
+    /// Load the columns which are recorded as being stored in separate directories.
+    pub fn load_relocated_columns(&self) -> Result<Vec<DBColumn>, Error> {
+        let Some(bytes) = self.get_bytes(
+            DBColumn::BeaconMeta.into(),
+            FREEZER_COLUMN_DIRS_KEY.as_slice(),
+        )?
+        else {
+            return Ok(vec![]);
+        };
+        bytes
+            .chunks(DBColumn::BeaconMeta.as_bytes().len())
+            .map(|tag| {
+                std::str::from_utf8(tag)
+                    .ok()
+                    .and_then(|tag| DBColumn::from_str(tag).ok())
+                    .ok_or_else(|| Error::DBError {
+                        message: format!("Invalid relocated column: {:?}", tag),
+                    })
+            })
+            .collect()
+    }
+
+    /// Record the columns which are stored in separate directories.
+    pub fn store_relocated_columns(&self, columns: &[DBColumn]) -> Result<(), Error> {
+        let bytes = columns
+            .iter()
+            .flat_map(|column| column.as_bytes())
+            .copied()
+            .collect::<Vec<_>>();
+        self.put_bytes_sync(
+            DBColumn::BeaconMeta.into(),
+            FREEZER_COLUMN_DIRS_KEY.as_slice(),
+            &bytes,
+        )
+    }
+
     fn read_options(&self) -> ReadOptions<BytesKey> {
         ReadOptions::new()
     }
@@ -63,7 +202,15 @@ impl<E: EthSpec> LevelDB<E> {
         metrics::inc_counter_vec_by(&metrics::DISK_DB_WRITE_BYTES, &[col], val.len() as u64);
         let _timer = metrics::start_timer(&metrics::DISK_DB_WRITE_TIMES);
 
-        self.db
+        if let Some(write_batcher) = &self.write_batcher {
+            write_batcher.write(vec![KeyValueStoreOp::PutKeyValue(column_key, val.to_vec())])?;
+            if opts.sync {
+                write_batcher.checkpoint()?;
+            }
+            return Ok(());
+        }
+
+        self.db_for_col(col)
             .put(opts, BytesKey::from_vec(column_key), val)
             .map_err(Into::into)
     }
@@ -84,6 +231,16 @@ impl<E: EthSpec> KeyValueStore<E> for LevelDB<E> {
     }
 
     fn sync(&self) -> Result<(), Error> {
+        if let Some(write_batcher) = &self.write_batcher {
+            return write_batcher.checkpoint();
+        }
+        for (_, db) in &self.column_dbs {
+            db.put(
+                self.write_options_sync(),
+                BytesKey::from_vec(get_key_for_col("sync", b"sync")),
+                b"sync",
+            )?;
+        }
         self.put_bytes_sync("sync", b"sync", b"sync")
     }
 
@@ -94,7 +251,15 @@ impl<E: EthSpec> KeyValueStore<E> for LevelDB<E> {
         metrics::inc_counter_vec(&metrics::DISK_DB_READ_COUNT, &[col]);
         let timer = metrics::start_timer(&metrics::DISK_DB_READ_TIMES);
 
-        self.db
+        if let Some(pending) = self
+            .write_batcher
+            .as_ref()
+            .and_then(|write_batcher| write_batcher.get(&column_key))
+        {
+            return Ok(pending);
+        }
+
+        self.db_for_col(col)
             .get(self.read_options(), BytesKey::from_vec(column_key))
             .map_err(Into::into)
             .map(|opt| {
@@ -115,7 +280,15 @@ impl<E: EthSpec> KeyValueStore<E> for LevelDB<E> {
 
         metrics::inc_counter_vec(&metrics::DISK_DB_EXISTS_COUNT, &[col]);
 
-        self.db
+        if let Some(pending) = self
+            .write_batcher
+            .as_ref()
+            .and_then(|write_batcher| write_batcher.get(&column_key))
+        {
+            return Ok(pending.is_some());
+        }
+
+        self.db_for_col(col)
             .get(self.read_options(), BytesKey::from_vec(column_key))
             .map_err(Into::into)
             .map(|val| val.is_some())
@@ -127,17 +300,68 @@ impl<E: EthSpec> KeyValueStore<E> for LevelDB<E> {
 
         metrics::inc_counter_vec(&metrics::DISK_DB_DELETE_COUNT, &[col]);
 
-        self.db
+        if let Some(write_batcher) = &self.write_batcher {
+            return write_batcher.write(vec![KeyValueStoreOp::DeleteKey(column_key)]);
+        }
+
+        self.db_for_col(col)
             .delete(self.write_options(), BytesKey::from_vec(column_key))
             .map_err(Into::into)
     }
 
+    /// Atomically apply the batch to each underlying database.
+    ///
+    /// Ops on columns stored in separate directories are written before the rest of the batch,
+    /// but the batch as a whole is not atomic across directories.
     fn do_atomically(&self, ops_batch: Vec<KeyValueStoreOp>) -> Result<(), Error> {
+        if let Some(write_batcher) = &self.write_batcher {
+            for op in &ops_batch {
+                match op {
+                    KeyValueStoreOp::PutKeyValue(key, value) => {
+                        let col = get_col_from_key(key).unwrap_or("unknown".to_owned());
+                        metrics::inc_counter_vec(&metrics::DISK_DB_WRITE_COUNT, &[&col]);
+                        metrics::inc_counter_vec_by(
+                            &metrics::DISK_DB_WRITE_BYTES,
+                            &[&col],
+                            value.len() as u64,
+                        );
+                    }
+                    KeyValueStoreOp::DeleteKey(key) => {
+                        let col = get_col_from_key(key).unwrap_or("unknown".to_owned());
+                        metrics::inc_counter_vec(&metrics::DISK_DB_DELETE_COUNT, &[&col]);
+                    }
+                }
+            }
+            let _timer = metrics::start_timer(&metrics::DISK_DB_WRITE_TIMES);
+            return write_batcher.write(ops_batch);
+        }
+
         let mut leveldb_batch = Writebatch::new();
+        let mut column_batches = self
+            .column_dbs
+            .iter()
+            .map(|_| (Writebatch::new(), false))
+            .collect::<Vec<_>>();
         for op in ops_batch {
+            let col = match &op {
+                KeyValueStoreOp::PutKeyValue(key, _) | KeyValueStoreOp::DeleteKey(key) => {
+                    get_col_from_key(key).unwrap_or("unknown".to_owned())
+                }
+            };
+            let batch = match self
+                .column_dbs
+                .iter()
+                .position(|(column, _)| column.as_str() == col)
+            {
+                Some(i) => {
+                    let (batch, is_used) = &mut column_batches[i];
+                    *is_used = true;
+                    batch
+                }
+                None => &mut leveldb_batch,
+            };
             match op {
                 KeyValueStoreOp::PutKeyValue(key, value) => {
-                    let col = get_col_from_key(&key).unwrap_or("unknown".to_owned());
                     metrics::inc_counter_vec(&metrics::DISK_DB_WRITE_COUNT, &[&col]);
                     metrics::inc_counter_vec_by(
                         &metrics::DISK_DB_WRITE_BYTES,
@@ -145,20 +369,24 @@ impl<E: EthSpec> KeyValueStore<E> for LevelDB<E> {
                         value.len() as u64,
                     );
 
-                    leveldb_batch.put(BytesKey::from_vec(key), &value);
+                    batch.put(BytesKey::from_vec(key), &value);
                 }
 
                 KeyValueStoreOp::DeleteKey(key) => {
-                    let col = get_col_from_key(&key).unwrap_or("unknown".to_owned());
                     metrics::inc_counter_vec(&metrics::DISK_DB_DELETE_COUNT, &[&col]);
 
-                    leveldb_batch.delete(BytesKey::from_vec(key));
+                    batch.delete(BytesKey::from_vec(key));
                 }
             }
         }
 
         let _timer = metrics::start_timer(&metrics::DISK_DB_WRITE_TIMES);
 
+        for ((_, db), (batch, is_used)) in self.column_dbs.iter().zip(&column_batches) {
+            if *is_used {
+                db.write(self.write_options(), batch)?;
+            }
+        }
         self.db.write(self.write_options(), &leveldb_batch)?;
         Ok(())
     }
@@ -168,6 +396,7 @@ impl<E: EthSpec> KeyValueStore<E> for LevelDB<E> {
     }
 
     fn compact_column(&self, column: DBColumn) -> Result<(), Error> {
+        self.flush_writes()?;
         // Use key-size-agnostic keys [] and 0xff..ff with a minimum of 32 bytes to account for
         // columns that may change size between sub-databases or schema versions.
         let start_key = BytesKey::from_vec(get_key_for_col(column.as_str(), &[]));
@@ -175,13 +404,17 @@ impl<E: EthSpec> KeyValueStore<E> for LevelDB<E> {
             column.as_str(),
             &vec![0xff; std::cmp::max(column.key_size(), 32)],
         ));
-        self.db.compact(&start_key, &end_key);
+        self.db_for_col(column.as_str())
+            .compact(&start_key, &end_key);
         Ok(())
     }
 
     fn iter_column_from<K: Key>(&self, column: DBColumn, from: &[u8]) -> ColumnIter<K> {
+        if let Err(e) = self.flush_writes() {
+            return Box::new(std::iter::once(Err(e)));
+        }
         let start_key = BytesKey::from_vec(get_key_for_col(column.into(), from));
-        let iter = self.db.iter(self.read_options());
+        let iter = self.db_for_col(column.as_str()).iter(self.read_options());
         iter.seek(&start_key);
 
         Box::new(
@@ -198,9 +431,12 @@ impl<E: EthSpec> KeyValueStore<E> for LevelDB<E> {
     }
 
     fn iter_raw_entries(&self, column: DBColumn, prefix: &[u8]) -> RawEntryIter {
+        if let Err(e) = self.flush_writes() {
+            return Box::new(std::iter::once(Err(e)));
+        }
         let start_key = BytesKey::from_vec(get_key_for_col(column.into(), prefix));
 
-        let iter = self.db.iter(self.read_options());
+        let iter = self.db_for_col(column.as_str()).iter(self.read_options());
         iter.seek(&start_key);
 
         Box::new(
@@ -213,9 +449,14 @@ impl<E: EthSpec> KeyValueStore<E> for LevelDB<E> {
     }
 
     fn iter_raw_keys(&self, column: DBColumn, prefix: &[u8]) -> RawKeyIter {
+        if let Err(e) = self.flush_writes() {
+            return Box::new(std::iter::once(Err(e)));
+        }
         let start_key = BytesKey::from_vec(get_key_for_col(column.into(), prefix));
 
-        let iter = self.db.keys_iter(self.read_options());
+        let iter = self
+            .db_for_col(column.as_str())
+            .keys_iter(self.read_options());
         iter.seek(&start_key);
 
         Box::new(
@@ -229,10 +470,15 @@ impl<E: EthSpec> KeyValueStore<E> for LevelDB<E> {
 
     /// Iterate through all keys and values in a particular column.
     fn iter_column_keys<K: Key>(&self, column: DBColumn) -> ColumnKeyIter<K> {
+        if let Err(e) = self.flush_writes() {
+            return Box::new(std::iter::once(Err(e)));
+        }
         let start_key =
             BytesKey::from_vec(get_key_for_col(column.into(), &vec![0; column.key_size()]));
 
-        let iter = self.db.keys_iter(self.read_options());
+        let iter = self
+            .db_for_col(column.as_str())
+            .keys_iter(self.read_options());
         iter.seek(&start_key);
 
         Box::new(
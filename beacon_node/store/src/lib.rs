@@ -7,10 +7,13 @@
 //!
 //! Provides a simple API for storing/retrieving all types that sometimes needs type-hints. See
 //! tests for implementation examples.
+pub mod blob_repair;
+pub mod block_root_filter;
 pub mod chunked_iter;
 pub mod chunked_vector;
 pub mod config;
 pub mod consensus_context;
+pub mod epoch_summary;
 pub mod errors;
 mod forwards_iter;
 mod garbage_collection;
@@ -18,6 +21,7 @@ pub mod hdiff;
 pub mod historic_state_cache;
 pub mod hot_cold_store;
 mod impls;
+pub mod integrity;
 mod leveldb_store;
 mod memory_store;
 pub mod metadata;
@@ -25,12 +29,14 @@ pub mod metrics;
 pub mod partial_beacon_state;
 pub mod reconstruct;
 pub mod state_cache;
+mod write_batcher;
 
 pub mod iter;
 
 pub use self::config::StoreConfig;
 pub use self::consensus_context::OnDiskConsensusContext;
-pub use self::hot_cold_store::{HotColdDB, HotStateSummary, Split};
+pub use self::epoch_summary::EpochSummary;
+pub use self::hot_cold_store::{HotColdDB, HotStateSummary, Split, StateLoadCost};
 pub use self::leveldb_store::LevelDB;
 pub use self::memory_store::MemoryStore;
 pub use crate::metadata::BlobInfo;
@@ -39,6 +45,7 @@ pub use impls::beacon_state::StorageContainer as BeaconStateStorageContainer;
 pub use metadata::AnchorInfo;
 pub use metrics::scrape_for_metrics;
 use parking_lot::MutexGuard;
+use serde::{Deserialize, Serialize};
 use std::sync::Arc;
 use strum::{EnumString, IntoStaticStr};
 pub use types::*;
@@ -90,6 +97,7 @@ pub trait KeyValueStore<E: EthSpec>: Sync + Send + Sized + 'static {
         // i.e. entries being created and deleted.
         for column in [
             DBColumn::BeaconState,
+            DBColumn::BeaconStateHotDiff,
             DBColumn::BeaconStateSummary,
             DBColumn::BeaconBlock,
         ] {
@@ -245,7 +253,7 @@ pub enum StoreOp<'a, E: EthSpec> {
 }
 
 /// A unique column identifier.
-#[derive(Debug, Clone, Copy, PartialEq, IntoStaticStr, EnumString)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, IntoStaticStr, EnumString, Serialize, Deserialize)]
 pub enum DBColumn {
     /// For data related to the database itself.
     #[strum(serialize = "bma")]
@@ -264,6 +272,10 @@ pub enum DBColumn {
     /// For full `BeaconState`s in the hot database (finalized or fork-boundary states).
     #[strum(serialize = "ste")]
     BeaconState,
+    /// For epoch boundary states in the hot database stored as a `HotStateDiff` against an
+    /// ancestor state.
+    #[strum(serialize = "bhd")]
+    BeaconStateHotDiff,
     /// For beacon state snapshots in the freezer DB.
     #[strum(serialize = "bsn")]
     BeaconStateSnapshot,
@@ -324,6 +336,9 @@ pub enum DBColumn {
     /// Can be removed once schema v22 is buried by a hard fork.
     #[strum(serialize = "bbr")]
     BeaconBlockRootsChunked,
+    /// Mapping from epoch to `EpochSummary` in the freezer DB.
+    #[strum(serialize = "bes")]
+    BeaconEpochSummary,
     /// DEPRECATED. Can be removed once schema v22 is buried by a hard fork.
     #[strum(serialize = "bhr")]
     BeaconHistoricalRoots,
@@ -332,6 +347,12 @@ pub enum DBColumn {
     BeaconRandaoMixes,
     #[strum(serialize = "dht")]
     DhtEnrs,
+    /// For peers protected from disconnection via the HTTP API.
+    #[strum(serialize = "ppr")]
+    ProtectedPeers,
+    /// Mapping from slot to the payloads considered when producing a block at that slot.
+    #[strum(serialize = "bvr")]
+    BlockValueRecord,
     /// For Optimistically Imported Merge Transition Blocks
     #[strum(serialize = "otb")]
     OptimisticTransitionBlock,
@@ -375,6 +396,7 @@ impl DBColumn {
             Self::BeaconMeta
             | Self::BeaconBlock
             | Self::BeaconState
+            | Self::BeaconStateHotDiff
             | Self::BeaconBlob
             | Self::BeaconStateSummary
             | Self::BeaconColdStateSummary
@@ -387,9 +409,11 @@ impl DBColumn {
             | Self::PubkeyCache
             | Self::BeaconRestorePoint
             | Self::DhtEnrs
+            | Self::ProtectedPeers
             | Self::OptimisticTransitionBlock => 32,
             Self::BeaconBlockRoots
             | Self::BeaconBlockRootsChunked
+            | Self::BeaconEpochSummary
             | Self::BeaconStateRoots
             | Self::BeaconStateRootsChunked
             | Self::BeaconHistoricalRoots
@@ -399,7 +423,8 @@ impl DBColumn {
             | Self::BeaconStateDiff
             | Self::SyncCommittee
             | Self::SyncCommitteeBranch
-            | Self::LightClientUpdate => 8,
+            | Self::LightClientUpdate
+            | Self::BlockValueRecord => 8,
             Self::BeaconDataColumn => DATA_COLUMN_DB_KEY_SIZE,
         }
     }
@@ -427,6 +452,7 @@ pub trait StoreItem: Sized {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::hot_cold_store::HotColdDBError;
     use ssz::{Decode, Encode};
     use ssz_derive::{Decode, Encode};
     use tempfile::tempdir;
@@ -480,6 +506,37 @@ mod tests {
         test_impl(store);
     }
 
+    #[test]
+    fn diskdb_with_column_dirs() {
+        let dir = tempdir().unwrap();
+        let column_dir = tempdir().unwrap();
+        let column_dirs = [(DBColumn::BeaconBlock, column_dir.path().to_path_buf())];
+
+        let store = LevelDB::open_with_column_dirs(dir.path(), &column_dirs).unwrap();
+        store.verify_column_dirs().unwrap();
+        test_impl(store);
+
+        // Items in the relocated column are only stored in its own directory.
+        let key = Hash256::random();
+        let item = StorableThing { a: 1, b: 42 };
+        let store = LevelDB::open_with_column_dirs(dir.path(), &column_dirs).unwrap();
+        store.do_atomically(vec![item.as_kv_store_op(key)]).unwrap();
+        drop(store);
+
+        let column_store = LevelDB::<MinimalEthSpec>::open(column_dir.path()).unwrap();
+        assert_eq!(column_store.get::<StorableThing>(&key).unwrap(), Some(item));
+        drop(column_store);
+
+        // The column may not be read from the main directory once it has been relocated.
+        let store = LevelDB::<MinimalEthSpec>::open(dir.path()).unwrap();
+        assert!(matches!(
+            store.verify_column_dirs(),
+            Err(Error::HotColdDBError(
+                HotColdDBError::MissingFreezerColumnDir(DBColumn::BeaconBlock)
+            ))
+        ));
+    }
+
     #[test]
     fn memorydb() {
         let store = MemoryStore::open();
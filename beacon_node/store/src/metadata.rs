@@ -4,7 +4,7 @@ use ssz::{Decode, Encode};
 use ssz_derive::{Decode, Encode};
 use types::{Checkpoint, Hash256, Slot};
 
-pub const CURRENT_SCHEMA_VERSION: SchemaVersion = SchemaVersion(22);
+pub const CURRENT_SCHEMA_VERSION: SchemaVersion = SchemaVersion(23);
 
 // All the keys that get stored under the `BeaconMeta` column.
 //
@@ -17,6 +17,8 @@ pub const COMPACTION_TIMESTAMP_KEY: Hash256 = Hash256::repeat_byte(4);
 pub const ANCHOR_INFO_KEY: Hash256 = Hash256::repeat_byte(5);
 pub const BLOB_INFO_KEY: Hash256 = Hash256::repeat_byte(6);
 pub const DATA_COLUMN_INFO_KEY: Hash256 = Hash256::repeat_byte(7);
+pub const FREEZER_COLUMN_DIRS_KEY: Hash256 = Hash256::repeat_byte(8);
+pub const HOT_STATE_STORAGE_KEY: Hash256 = Hash256::repeat_byte(9);
 
 /// State upper limit value used to indicate that a node is not storing historic states.
 pub const STATE_UPPER_LIMIT_NO_RETAIN: Slot = Slot::new(u64::MAX);
@@ -106,6 +108,31 @@ impl StoreItem for CompactionTimestamp {
     }
 }
 
+/// The storage format that every hot epoch boundary state was last converted to.
+///
+/// Written once a conversion completes, so that it isn't repeated when the database is reopened.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct HotStateStorage {
+    /// `true` if states are stored as diffs where possible, `false` if all are stored in full.
+    pub hot_tree_states: bool,
+}
+
+impl StoreItem for HotStateStorage {
+    fn db_column() -> DBColumn {
+        DBColumn::BeaconMeta
+    }
+
+    fn as_store_bytes(&self) -> Vec<u8> {
+        self.hot_tree_states.as_ssz_bytes()
+    }
+
+    fn from_store_bytes(bytes: &[u8]) -> Result<Self, Error> {
+        Ok(HotStateStorage {
+            hot_tree_states: bool::from_ssz_bytes(bytes)?,
+        })
+    }
+}
+
 /// Database parameters relevant to weak subjectivity sync.
 #[derive(Debug, PartialEq, Eq, Clone, Encode, Decode, Serialize, Deserialize)]
 pub struct AnchorInfo {
@@ -73,6 +73,51 @@ pub static DISK_DB_DELETE_COUNT: LazyLock<Result<IntCounterVec>> = LazyLock::new
         &["col"],
     )
 });
+/*
+ * Hot DB write batching
+ */
+pub static HOT_DB_PENDING_WRITE_BATCHES: LazyLock<Result<IntGauge>> = LazyLock::new(|| {
+    try_create_int_gauge(
+        "store_hot_db_pending_write_batches",
+        "Number of batches in the write-ahead log waiting to be written to the hot DB",
+    )
+});
+pub static HOT_DB_BATCHED_WRITES: LazyLock<Result<IntCounter>> = LazyLock::new(|| {
+    try_create_int_counter(
+        "store_hot_db_batched_writes_total",
+        "Total number of batches written to the hot DB by the background writer",
+    )
+});
+pub static HOT_DB_BATCHED_WRITE_TIMES: LazyLock<Result<Histogram>> = LazyLock::new(|| {
+    try_create_histogram(
+        "store_hot_db_batched_write_seconds",
+        "Time taken by the background writer to write queued batches to the hot DB",
+    )
+});
+pub static HOT_DB_WAL_APPEND_TIMES: LazyLock<Result<Histogram>> = LazyLock::new(|| {
+    try_create_histogram(
+        "store_hot_db_wal_append_seconds",
+        "Time taken to append a batch to the hot DB write-ahead log",
+    )
+});
+pub static HOT_DB_WAL_FSYNC_TIMES: LazyLock<Result<Histogram>> = LazyLock::new(|| {
+    try_create_histogram(
+        "store_hot_db_wal_fsync_seconds",
+        "Time taken by periodic fsyncs of the hot DB write-ahead log",
+    )
+});
+pub static HOT_DB_CHECKPOINT_TIMES: LazyLock<Result<Histogram>> = LazyLock::new(|| {
+    try_create_histogram(
+        "store_hot_db_checkpoint_seconds",
+        "Time taken to sync the hot DB and truncate its write-ahead log",
+    )
+});
+pub static HOT_DB_WAL_REPLAYED_BATCHES: LazyLock<Result<IntCounter>> = LazyLock::new(|| {
+    try_create_int_counter(
+        "store_hot_db_wal_replayed_batches_total",
+        "Number of batches replayed from the hot DB write-ahead log after an unclean shutdown",
+    )
+});
 /*
  * Anchor Info
  */
@@ -151,6 +196,25 @@ pub static BEACON_STATE_WRITE_BYTES: LazyLock<Result<IntCounter>> = LazyLock::ne
         "Total number of beacon state bytes written to the DB",
     )
 });
+pub static BEACON_HOT_STATE_DIFF_WRITE_COUNT: LazyLock<Result<IntCounter>> = LazyLock::new(|| {
+    try_create_int_counter(
+        "store_beacon_hot_state_diff_write_total",
+        "Total number of hot epoch boundary states written to the DB as diffs",
+    )
+});
+pub static BEACON_HOT_STATE_DIFF_WRITE_BYTES: LazyLock<Result<IntCounter>> = LazyLock::new(|| {
+    try_create_int_counter(
+        "store_beacon_hot_state_diff_write_bytes_total",
+        "Total number of hot state diff bytes written to the DB",
+    )
+});
+pub static STORE_BEACON_HOT_STATE_DIFF_LOAD_TIME: LazyLock<Result<Histogram>> =
+    LazyLock::new(|| {
+        try_create_histogram(
+            "store_beacon_hot_state_diff_load_seconds",
+            "Time taken to load a hot epoch boundary state stored as a diff",
+        )
+    });
 pub static BEACON_HDIFF_READ_TIMES: LazyLock<Result<Histogram>> = LazyLock::new(|| {
     try_create_histogram(
         "store_hdiff_read_seconds",
@@ -200,6 +264,32 @@ pub static STORE_BEACON_BLOCK_CACHE_SIZE: LazyLock<Result<IntGauge>> = LazyLock:
         "Current count of items in beacon store block cache",
     )
 });
+pub static STORE_BLOCK_ROOT_FILTER_SIZE: LazyLock<Result<IntGauge>> = LazyLock::new(|| {
+    try_create_int_gauge(
+        "store_block_root_filter_size",
+        "Memory used by the block root filter (bytes)",
+    )
+});
+pub static STORE_BLOCK_ROOT_FILTER_ITEMS: LazyLock<Result<IntGauge>> = LazyLock::new(|| {
+    try_create_int_gauge(
+        "store_block_root_filter_items",
+        "Number of block roots inserted into the block root filter",
+    )
+});
+pub static STORE_BLOCK_ROOT_FILTER_ESTIMATED_FALSE_POSITIVE_RATE: LazyLock<Result<Gauge>> =
+    LazyLock::new(|| {
+        try_create_float_gauge(
+            "store_block_root_filter_estimated_false_positive_rate",
+            "Estimated false positive rate of the block root filter, from its occupancy",
+        )
+    });
+pub static STORE_BLOCK_ROOT_FILTER_CHECKS: LazyLock<Result<IntCounterVec>> = LazyLock::new(|| {
+    try_create_int_counter_vec(
+        "store_block_root_filter_checks_total",
+        "Block existence checks answered using the block root filter, by result",
+        &["result"],
+    )
+});
 pub static STORE_BEACON_BLOB_CACHE_SIZE: LazyLock<Result<IntGauge>> = LazyLock::new(|| {
     try_create_int_gauge(
         "store_beacon_blob_cache_size",
@@ -308,6 +398,13 @@ pub static STORE_BEACON_HDIFF_BUFFER_INTO_STATE_TIME: LazyLock<Result<Histogram>
             "Time taken to recreate a BeaconState from an hdiff buffer",
         )
     });
+pub static STORE_BEACON_HDIFF_BUFFER_INTO_SSZ_TIME: LazyLock<Result<Histogram>> =
+    LazyLock::new(|| {
+        try_create_histogram(
+            "store_beacon_hdiff_buffer_into_ssz_seconds",
+            "Time taken to encode an hdiff buffer as SSZ without recreating the BeaconState",
+        )
+    });
 pub static STORE_BEACON_HDIFF_BUFFER_FROM_STATE_TIME: LazyLock<Result<Histogram>> =
     LazyLock::new(|| {
         try_create_histogram(
@@ -0,0 +1,554 @@
+//! Asynchronous batching of writes to the hot database.
+//!
+//! When enabled, each batch of writes is appended to a write-ahead log and made visible to reads
+//! immediately, while a background thread applies queued batches to the database. Block import
+//! therefore doesn't wait on the database, which can stall for long periods on slow disks while
+//! it compacts.
+//!
+//! The write-ahead log is flushed to disk according to the configured `FsyncPolicy`, which bounds
+//! the writes that may be lost if the machine crashes. If the process exits uncleanly, the log is
+//! replayed into the database when it is next opened. The log is truncated once all of its
+//! batches have been durably written to the database.
+use crate::leveldb_store::BytesKey;
+use crate::{metrics, Error, KeyValueStoreOp};
+use leveldb::database::batch::{Batch, Writebatch};
+use leveldb::database::Database;
+use leveldb::options::WriteOptions;
+use parking_lot::{Condvar, Mutex};
+use std::collections::HashMap;
+use std::fs::{File, OpenOptions};
+use std::io::{ErrorKind, Read, Write};
+use std::path::Path;
+use std::sync::Arc;
+use std::thread::JoinHandle;
+use std::time::{Duration, Instant};
+
+/// The size of the write-ahead log above which it is truncated, once all of its batches have been
+/// durably written to the database.
+pub const CHECKPOINT_BYTES: u64 = 256 * 1024 * 1024;
+
+/// The length of the header of each record in the write-ahead log: an 8-byte payload length
+/// followed by the 32-byte hash of the payload.
+const RECORD_HEADER_LEN: usize = 8 + 32;
+
+const PUT_TAG: u8 = 0;
+const DELETE_TAG: u8 = 1;
+
+/// A single write, sharing its value with the overlay of pending writes.
+type PendingOp = (Vec<u8>, Option<Arc<Vec<u8>>>);
+
+/// Applies batches of writes to a database in the background.
+pub struct WriteBatcher {
+    shared: Arc<Shared>,
+    writer: Option<JoinHandle<()>>,
+}
+
+struct Shared {
+    db: Arc<Database<BytesKey>>,
+    pending: Mutex<Pending>,
+    /// Notified when a batch is queued or on shutdown.
+    queued: Condvar,
+    /// Notified when queued batches have been written to the database.
+    written: Condvar,
+    wal: Mutex<File>,
+    /// The interval between fsyncs of the write-ahead log, or `None` to fsync every batch.
+    fsync_interval: Option<Duration>,
+}
+
+#[derive(Default)]
+struct Pending {
+    /// Batches which are in the write-ahead log but haven't been written to the database.
+    queue: Vec<Vec<PendingOp>>,
+    /// The most recent pending value of each key, and the sequence number of the batch which
+    /// wrote it. A value of `None` means that the key is being deleted.
+    overlay: HashMap<Vec<u8>, (u64, Option<Arc<Vec<u8>>>)>,
+    /// The sequence number of the most recently queued batch.
+    queued_seq: u64,
+    /// The sequence number of the most recent batch written to the database.
+    written_seq: u64,
+    /// The number of bytes appended to the write-ahead log since it was last truncated.
+    wal_bytes: u64,
+    /// The first error encountered by the writer, after which no further writes are accepted.
+    error: Option<String>,
+    shutdown: bool,
+}
+
+impl WriteBatcher {
+    /// Start applying writes to `db` in the background, logging them to the file at `wal_path`.
+    ///
+    /// The log is fsynced every `fsync_interval`, or after every batch if `None`. It must have
+    /// been replayed with `replay` beforehand.
+    pub fn start(
+        db: Arc<Database<BytesKey>>,
+        wal_path: &Path,
+        fsync_interval: Option<Duration>,
+    ) -> Result<Self, Error> {
+        let wal = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(wal_path)
+            .map_err(|e| wal_error("open", e))?;
+        let shared = Arc::new(Shared {
+            db,
+            pending: Mutex::new(Pending::default()),
+            queued: Condvar::new(),
+            written: Condvar::new(),
+            wal: Mutex::new(wal),
+            fsync_interval,
+        });
+
+        let writer_shared = shared.clone();
+        let writer = std::thread::Builder::new()
+            .name("hot_db_writer".into())
+            .spawn(move || writer_shared.run())
+            .map_err(|e| wal_error("spawn writer for", e))?;
+
+        Ok(Self {
+            shared,
+            writer: Some(writer),
+        })
+    }
+
+    /// Log `ops` and queue them to be written to the database.
+    ///
+    /// The writes are visible to `get` as soon as this function returns.
+    pub fn write(&self, ops: Vec<KeyValueStoreOp>) -> Result<(), Error> {
+        let batch = ops
+            .into_iter()
+            .map(|op| match op {
+                KeyValueStoreOp::PutKeyValue(key, value) => (key, Some(Arc::new(value))),
+                KeyValueStoreOp::DeleteKey(key) => (key, None),
+            })
+            .collect::<Vec<_>>();
+        let record = encode_record(&batch);
+
+        let _timer = metrics::start_timer(&metrics::HOT_DB_WAL_APPEND_TIMES);
+        let mut wal = self.shared.wal.lock();
+        if let Some(error) = &self.shared.pending.lock().error {
+            return Err(writer_error(error));
+        }
+        wal.write_all(&record)
+            .map_err(|e| wal_error("append to", e))?;
+        if self.shared.fsync_interval.is_none() {
+            wal.sync_data().map_err(|e| wal_error("sync", e))?;
+        }
+
+        // Queue the batch while holding the log's lock, so batches are queued in log order.
+        let mut pending = self.shared.pending.lock();
+        pending.queued_seq += 1;
+        pending.wal_bytes += record.len() as u64;
+        let seq = pending.queued_seq;
+        for (key, value) in &batch {
+            pending.overlay.insert(key.clone(), (seq, value.clone()));
+        }
+        pending.queue.push(batch);
+        metrics::set_gauge(
+            &metrics::HOT_DB_PENDING_WRITE_BATCHES,
+            pending.queue.len() as i64,
+        );
+        drop(pending);
+        drop(wal);
+
+        self.shared.queued.notify_one();
+        Ok(())
+    }
+
+    /// Return the pending value of `column_key`, if any.
+    ///
+    /// The outer `Option` is `None` if there is no pending write to the key, and the inner
+    /// `Option` is `None` if the key is being deleted.
+    pub fn get(&self, column_key: &[u8]) -> Option<Option<Vec<u8>>> {
+        self.shared
+            .pending
+            .lock()
+            .overlay
+            .get(column_key)
+            .map(|(_, value)| value.as_ref().map(|value| value.to_vec()))
+    }
+
+    /// Wait until all queued batches have been written to the database.
+    pub fn flush(&self) -> Result<(), Error> {
+        let mut pending = self.shared.pending.lock();
+        let seq = pending.queued_seq;
+        while pending.written_seq < seq && pending.error.is_none() {
+            self.shared.written.wait(&mut pending);
+        }
+        match &pending.error {
+            Some(error) => Err(writer_error(error)),
+            None => Ok(()),
+        }
+    }
+
+    /// Durably write all queued batches to the database and truncate the write-ahead log.
+    ///
+    /// Batches queued while this is waiting are not waited for, and keep the log from being
+    /// truncated.
+    pub fn checkpoint(&self) -> Result<(), Error> {
+        self.flush()?;
+        self.shared.checkpoint()
+    }
+}
+
+impl Drop for WriteBatcher {
+    fn drop(&mut self) {
+        self.shared.pending.lock().shutdown = true;
+        self.shared.queued.notify_one();
+        if let Some(writer) = self.writer.take() {
+            let _ = writer.join();
+        }
+        // Leave the log in place if this fails, so that it is replayed on the next start.
+        let _ = self.shared.checkpoint();
+    }
+}
+
+impl Shared {
+    fn run(&self) {
+        let fsync_interval = self.fsync_interval;
+        let mut last_fsync = Instant::now();
+
+        loop {
+            let mut pending = self.pending.lock();
+            while pending.queue.is_empty() && !pending.shutdown {
+                let timed_out = match fsync_interval {
+                    Some(interval) => self
+                        .queued
+                        .wait_until(&mut pending, last_fsync + interval)
+                        .timed_out(),
+                    None => {
+                        self.queued.wait(&mut pending);
+                        false
+                    }
+                };
+                if timed_out {
+                    break;
+                }
+            }
+            let batches = std::mem::take(&mut pending.queue);
+            let seq = pending.queued_seq;
+            let shutdown = pending.shutdown;
+            drop(pending);
+
+            if !batches.is_empty() {
+                let result = self.write_batches(&batches);
+                let mut pending = self.pending.lock();
+                match result {
+                    Ok(()) => {
+                        pending.written_seq = seq;
+                        pending
+                            .overlay
+                            .retain(|_, (written_by, _)| *written_by > seq);
+                    }
+                    Err(e) => {
+                        pending.error.get_or_insert(format!("{:?}", e));
+                    }
+                }
+                metrics::set_gauge(
+                    &metrics::HOT_DB_PENDING_WRITE_BATCHES,
+                    pending.queue.len() as i64,
+                );
+                let wal_bytes = pending.wal_bytes;
+                drop(pending);
+                self.written.notify_all();
+
+                if wal_bytes >= CHECKPOINT_BYTES {
+                    if let Err(e) = self.checkpoint() {
+                        self.pending.lock().error.get_or_insert(format!("{:?}", e));
+                        self.written.notify_all();
+                    }
+                }
+            }
+
+            if fsync_interval.is_some_and(|interval| last_fsync.elapsed() >= interval) {
+                let _timer = metrics::start_timer(&metrics::HOT_DB_WAL_FSYNC_TIMES);
+                if let Err(e) = self.wal.lock().sync_data() {
+                    self.pending.lock().error.get_or_insert(format!("{:?}", e));
+                    self.written.notify_all();
+                }
+                last_fsync = Instant::now();
+            }
+
+            if shutdown {
+                return;
+            }
+        }
+    }
+
+    /// Write `batches` to the database as a single atomic batch.
+    fn write_batches(&self, batches: &[Vec<PendingOp>]) -> Result<(), Error> {
+        let _timer = metrics::start_timer(&metrics::HOT_DB_BATCHED_WRITE_TIMES);
+        let mut leveldb_batch = Writebatch::new();
+        for (key, value) in batches.iter().flatten() {
+            match value {
+                Some(value) => leveldb_batch.put(BytesKey::from_vec(key.clone()), value),
+                None => leveldb_batch.delete(BytesKey::from_vec(key.clone())),
+            }
+        }
+        metrics::inc_counter_by(&metrics::HOT_DB_BATCHED_WRITES, batches.len() as u64);
+        self.db
+            .write(WriteOptions::new(), &leveldb_batch)
+            .map_err(Into::into)
+    }
+
+    /// Make the batches written to the database durable, then truncate the write-ahead log if all
+    /// of its batches have been written.
+    ///
+    /// Returns the writer's error if it has failed, as the database may be missing batches.
+    fn checkpoint(&self) -> Result<(), Error> {
+        let wal = self.wal.lock();
+        let mut pending = self.pending.lock();
+        if let Some(error) = &pending.error {
+            return Err(writer_error(error));
+        }
+
+        let _timer = metrics::start_timer(&metrics::HOT_DB_CHECKPOINT_TIMES);
+        sync_database(&self.db)?;
+        // Batches which are still queued must be kept in the log, until a later checkpoint.
+        if pending.written_seq == pending.queued_seq {
+            wal.set_len(0).map_err(|e| wal_error("truncate", e))?;
+            pending.wal_bytes = 0;
+        }
+        Ok(())
+    }
+}
+
+/// Apply all complete batches in the write-ahead log at `wal_path` to `db`, make them durable,
+/// then remove the log.
+///
+/// A batch which was only partially appended before a crash is discarded, along with anything
+/// after it. Returns the number of batches replayed.
+pub fn replay(db: &Database<BytesKey>, wal_path: &Path) -> Result<usize, Error> {
+    let mut bytes = vec![];
+    match File::open(wal_path) {
+        Ok(mut file) => file
+            .read_to_end(&mut bytes)
+            .map_err(|e| wal_error("read", e))?,
+        Err(e) if e.kind() == ErrorKind::NotFound => return Ok(0),
+        Err(e) => return Err(wal_error("open", e)),
+    };
+
+    let batches = decode_records(&bytes);
+    for batch in &batches {
+        let mut leveldb_batch = Writebatch::new();
+        for op in batch {
+            match op {
+                KeyValueStoreOp::PutKeyValue(key, value) => {
+                    leveldb_batch.put(BytesKey::from_vec(key.clone()), value)
+                }
+                KeyValueStoreOp::DeleteKey(key) => {
+                    leveldb_batch.delete(BytesKey::from_vec(key.clone()))
+                }
+            }
+        }
+        db.write(WriteOptions::new(), &leveldb_batch)?;
+    }
+    sync_database(db)?;
+    std::fs::remove_file(wal_path).map_err(|e| wal_error("remove", e))?;
+
+    metrics::inc_counter_by(&metrics::HOT_DB_WAL_REPLAYED_BATCHES, batches.len() as u64);
+    Ok(batches.len())
+}
+
+/// Flush all previous writes to `db` to disk.
+fn sync_database(db: &Database<BytesKey>) -> Result<(), Error> {
+    let mut options = WriteOptions::new();
+    options.sync = true;
+    db.write(options, &Writebatch::new()).map_err(Into::into)
+}
+
+fn writer_error(error: &str) -> Error {
+    Error::DBError {
+        message: format!("Hot database writer failed: {}", error),
+    }
+}
+
+fn wal_error(action: &str, e: std::io::Error) -> Error {
+    Error::DBError {
+        message: format!("Unable to {} hot database write-ahead log: {:?}", action, e),
+    }
+}
+
+fn encode_record(batch: &[PendingOp]) -> Vec<u8> {
+    let mut payload = vec![];
+    for (key, value) in batch {
+        payload.push(if value.is_some() { PUT_TAG } else { DELETE_TAG });
+        payload.extend_from_slice(&(key.len() as u64).to_le_bytes());
+        payload.extend_from_slice(key);
+        if let Some(value) = value {
+            payload.extend_from_slice(&(value.len() as u64).to_le_bytes());
+            payload.extend_from_slice(value);
+        }
+    }
+
+    let mut record = Vec::with_capacity(RECORD_HEADER_LEN + payload.len());
+    record.extend_from_slice(&(payload.len() as u64).to_le_bytes());
+    record.extend_from_slice(&ethereum_hashing::hash(&payload));
+    record.extend_from_slice(&payload);
+    record
+}
+
+/// Decode the batches of all complete and uncorrupted records at the start of `bytes`.
+fn decode_records(mut bytes: &[u8]) -> Vec<Vec<KeyValueStoreOp>> {
+    let mut batches = vec![];
+    while let Some((batch, rest)) = decode_record(bytes) {
+        batches.push(batch);
+        bytes = rest;
+    }
+    batches
+}
+
+fn decode_record(bytes: &[u8]) -> Option<(Vec<KeyValueStoreOp>, &[u8])> {
+    let (payload_len, rest) = take_u64(bytes)?;
+    let (hash, rest) = take(rest, 32)?;
+    let (mut payload, rest) = take(rest, usize::try_from(payload_len).ok()?)?;
+    if ethereum_hashing::hash(payload) != hash {
+        return None;
+    }
+
+    let mut batch = vec![];
+    while let Some((&tag, rest)) = payload.split_first() {
+        let (key_len, rest) = take_u64(rest)?;
+        let (key, rest) = take(rest, usize::try_from(key_len).ok()?)?;
+        payload = match tag {
+            PUT_TAG => {
+                let (value_len, rest) = take_u64(rest)?;
+                let (value, rest) = take(rest, usize::try_from(value_len).ok()?)?;
+                batch.push(KeyValueStoreOp::PutKeyValue(key.to_vec(), value.to_vec()));
+                rest
+            }
+            DELETE_TAG => {
+                batch.push(KeyValueStoreOp::DeleteKey(key.to_vec()));
+                rest
+            }
+            _ => return None,
+        };
+    }
+    Some((batch, rest))
+}
+
+fn take(bytes: &[u8], len: usize) -> Option<(&[u8], &[u8])> {
+    (bytes.len() >= len).then(|| bytes.split_at(len))
+}
+
+fn take_u64(bytes: &[u8]) -> Option<(u64, &[u8])> {
+    let (int_bytes, rest) = take(bytes, 8)?;
+    Some((u64::from_le_bytes(int_bytes.try_into().ok()?), rest))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use leveldb::database::kv::KV;
+    use leveldb::options::{Options, ReadOptions};
+    use tempfile::tempdir;
+
+    fn put(key: &[u8], value: &[u8]) -> KeyValueStoreOp {
+        KeyValueStoreOp::PutKeyValue(key.to_vec(), value.to_vec())
+    }
+
+    fn open(path: &Path) -> Arc<Database<BytesKey>> {
+        let mut options = Options::new();
+        options.create_if_missing = true;
+        Arc::new(Database::open(path, options).unwrap())
+    }
+
+    fn get(db: &Database<BytesKey>, key: &[u8]) -> Option<Vec<u8>> {
+        db.get(ReadOptions::new(), BytesKey::from_vec(key.to_vec()))
+            .unwrap()
+    }
+
+    #[test]
+    fn decode_truncated_log() {
+        let batches = [
+            vec![(b"bmakey1".to_vec(), Some(Arc::new(vec![1; 100])))],
+            vec![(b"bmakey2".to_vec(), None)],
+        ];
+        let bytes = batches
+            .iter()
+            .flat_map(|batch| encode_record(batch))
+            .collect::<Vec<_>>();
+        assert_eq!(decode_records(&bytes).len(), 2);
+
+        // A partially appended record is discarded.
+        assert_eq!(decode_records(&bytes[..bytes.len() - 1]).len(), 1);
+
+        // As is a corrupted record, and everything after it.
+        let mut corrupted = bytes.clone();
+        corrupted[RECORD_HEADER_LEN] ^= 1;
+        assert_eq!(decode_records(&corrupted).len(), 0);
+    }
+
+    #[test]
+    fn reads_see_pending_writes() {
+        let dir = tempdir().unwrap();
+        let db = open(&dir.path().join("db"));
+        let wal_path = dir.path().join("db.wal");
+        let batcher =
+            WriteBatcher::start(db.clone(), &wal_path, Some(Duration::from_secs(60))).unwrap();
+        // Read pending writes first, as `LevelDB` does.
+        let read = |key: &[u8]| batcher.get(key).unwrap_or_else(|| get(&db, key));
+
+        batcher.write(vec![put(b"bmakey", b"value")]).unwrap();
+        assert_eq!(read(b"bmakey"), Some(b"value".to_vec()));
+        batcher
+            .write(vec![KeyValueStoreOp::DeleteKey(b"bmakey".to_vec())])
+            .unwrap();
+        assert_eq!(read(b"bmakey"), None);
+
+        batcher.write(vec![put(b"bmaother", b"value")]).unwrap();
+        batcher.flush().unwrap();
+        assert_eq!(batcher.get(b"bmaother"), None);
+        assert_eq!(get(&db, b"bmaother"), Some(b"value".to_vec()));
+        assert_eq!(get(&db, b"bmakey"), None);
+
+        // A clean shutdown truncates the log.
+        drop(batcher);
+        assert_eq!(std::fs::metadata(&wal_path).unwrap().len(), 0);
+    }
+
+    #[test]
+    fn checkpoint_writes_queued_batches() {
+        let dir = tempdir().unwrap();
+        let db = open(&dir.path().join("db"));
+        let wal_path = dir.path().join("db.wal");
+        let batcher =
+            WriteBatcher::start(db.clone(), &wal_path, Some(Duration::from_secs(60))).unwrap();
+
+        batcher.write(vec![put(b"bmakey", b"value")]).unwrap();
+        assert!(std::fs::metadata(&wal_path).unwrap().len() > 0);
+        batcher.checkpoint().unwrap();
+        assert_eq!(get(&db, b"bmakey"), Some(b"value".to_vec()));
+        assert_eq!(std::fs::metadata(&wal_path).unwrap().len(), 0);
+    }
+
+    #[test]
+    fn replay_after_crash() {
+        let dir = tempdir().unwrap();
+        let wal_path = dir.path().join("db.wal");
+        let batches = [
+            vec![
+                (b"bmakey1".to_vec(), Some(Arc::new(b"old".to_vec()))),
+                (b"bmakey2".to_vec(), Some(Arc::new(b"value".to_vec()))),
+            ],
+            vec![
+                (b"bmakey1".to_vec(), Some(Arc::new(b"new".to_vec()))),
+                (b"bmakey2".to_vec(), None),
+            ],
+        ];
+        let mut bytes = batches
+            .iter()
+            .flat_map(|batch| encode_record(batch))
+            .collect::<Vec<_>>();
+        // Simulate a crash part way through appending a third batch.
+        bytes.extend_from_slice(&encode_record(&batches[0])[..10]);
+        std::fs::write(&wal_path, bytes).unwrap();
+
+        let db = open(&dir.path().join("db"));
+        assert_eq!(replay(&db, &wal_path).unwrap(), 2);
+        assert_eq!(get(&db, b"bmakey1"), Some(b"new".to_vec()));
+        assert_eq!(get(&db, b"bmakey2"), None);
+        assert!(!wal_path.exists());
+
+        // Replaying without a log is a no-op.
+        assert_eq!(replay(&db, &wal_path).unwrap(), 0);
+    }
+}
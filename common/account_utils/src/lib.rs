@@ -101,6 +101,12 @@ pub fn write_file_via_temporary(
         create_with_600_perms(temp_path, bytes)?;
     }
 
+    // Flush the temporary file to disk so that a crash after the rename cannot leave the file
+    // at `file_path` empty or truncated.
+    File::open(temp_path)
+        .and_then(|file| file.sync_all())
+        .map_err(FsError::UnableToWriteFile)?;
+
     // With the temporary file created, perform an atomic rename.
     fs::rename(temp_path, file_path).map_err(FsError::UnableToRenameFile)?;
 
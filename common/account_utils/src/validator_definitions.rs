@@ -2,19 +2,26 @@
 //!
 //! Serves as the source-of-truth of which validators this validator client should attempt (or not
 //! attempt) to load into the `crate::intialized_validators::InitializedValidators` struct.
+//!
+//! The definitions are either stored as a list in a single `CONFIG_FILENAME` file, or as one file
+//! per validator in the `DEFINITIONS_DIRNAME` directory (see `DefinitionsLayout`). Reads and writes
+//! hold an advisory lock on `LOCK_FILENAME` so that external tooling can safely edit the
+//! definitions whilst the validator client is running.
 
 use crate::{
     default_keystore_password_path, read_password_string, write_file_via_temporary, ZeroizeString,
 };
 use directory::ensure_dir_exists;
 use eth2_keystore::Keystore;
+use fs2::FileExt;
 use regex::Regex;
 use serde::{Deserialize, Serialize};
 use slog::{error, Logger};
-use std::collections::HashSet;
+use std::collections::{HashMap, HashSet};
 use std::fs::{self, File};
 use std::io;
 use std::path::{Path, PathBuf};
+use std::str::FromStr;
 use types::{graffiti::GraffitiString, Address, PublicKey};
 use validator_dir::VOTING_KEYSTORE_FILE;
 
@@ -27,6 +34,22 @@ pub const CONFIG_FILENAME: &str = "validator_definitions.yml";
 /// See: https://github.com/sigp/lighthouse/issues/2159
 pub const CONFIG_TEMP_FILENAME: &str = ".validator_definitions.yml.tmp";
 
+/// The directory which holds one file per validator when `DefinitionsLayout::PerValidatorFiles`
+/// is used. Each file is named after the validator's public key, e.g. `0xabcd...1234.yml`.
+pub const DEFINITIONS_DIRNAME: &str = "validators.d";
+
+/// The file which is locked whilst the definitions are read (shared) or written (exclusive).
+///
+/// The lock is advisory: tools which edit the definitions whilst the validator client is running
+/// should hold an exclusive `flock` on this file whilst doing so.
+pub const LOCK_FILENAME: &str = ".validator_definitions.lock";
+
+/// The version of the `CONFIG_FILENAME` schema which records the `DefinitionsLayout`.
+///
+/// Definitions using `DefinitionsLayout::SingleFile` are still written in the original,
+/// unversioned format (a plain list) so that they remain readable by older versions.
+pub const SCHEMA_VERSION: u64 = 2;
+
 #[derive(Debug)]
 pub enum Error {
     /// The config file could not be opened.
@@ -47,6 +70,58 @@ pub enum Error {
     UnableToCreateValidatorDir(PathBuf),
     UnableToReadKeystorePassword(String),
     KeystoreWithoutPassword,
+    /// The lock file could not be opened or locked.
+    UnableToLockFile(io::Error),
+    /// The config file has a schema version which is not supported.
+    UnsupportedSchemaVersion(u64),
+    /// The `DEFINITIONS_DIRNAME` directory could not be read.
+    UnableToReadDefinitionsDir(io::Error),
+    /// A file in the `DEFINITIONS_DIRNAME` directory could not be parsed as YAML.
+    UnableToParseDefinitionFile(PathBuf, serde_yaml::Error),
+    /// A stale file in the `DEFINITIONS_DIRNAME` directory could not be deleted.
+    UnableToDeleteDefinitionFile(PathBuf, io::Error),
+    /// Two definitions share a public key, so they cannot be stored in separate files.
+    DuplicateDefinition(PublicKey),
+}
+
+/// Defines how the validator definitions are stored on disk.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum DefinitionsLayout {
+    /// All definitions are stored in the `CONFIG_FILENAME` file.
+    #[default]
+    SingleFile,
+    /// Each definition is stored in its own file in the `DEFINITIONS_DIRNAME` directory, so that
+    /// changing one validator does not rewrite the definitions of every other validator.
+    PerValidatorFiles,
+}
+
+impl FromStr for DefinitionsLayout {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "single-file" => Ok(Self::SingleFile),
+            "per-validator-files" => Ok(Self::PerValidatorFiles),
+            other => Err(format!(
+                "{} is not a valid layout, expected single-file or per-validator-files",
+                other
+            )),
+        }
+    }
+}
+
+/// The `SCHEMA_VERSION` format of the `CONFIG_FILENAME` file.
+#[derive(Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct VersionedDefinitionsFile {
+    version: u64,
+    layout: DefinitionsLayout,
+    /// Definitions which are stored in `CONFIG_FILENAME`, in addition to those in
+    /// `DEFINITIONS_DIRNAME`. These are moved into `DEFINITIONS_DIRNAME` when next saved.
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    validators: Vec<ValidatorDefinition>,
 }
 
 /// Defines how a password for a validator keystore will be persisted.
@@ -216,14 +291,24 @@ impl ValidatorDefinition {
     }
 }
 
-/// A list of `ValidatorDefinition` that serves as a serde-able configuration file which defines a
-/// list of validators to be initialized by this validator client.
-#[derive(Default, Serialize, Deserialize)]
-pub struct ValidatorDefinitions(Vec<ValidatorDefinition>);
+/// A list of `ValidatorDefinition` that is persisted as a configuration file (or directory) which
+/// defines a list of validators to be initialized by this validator client.
+#[derive(Default)]
+pub struct ValidatorDefinitions {
+    definitions: Vec<ValidatorDefinition>,
+    layout: DefinitionsLayout,
+    /// The definitions as they were last read from or written to `DEFINITIONS_DIRNAME`, keyed by
+    /// file name. Used to only write the files of definitions which have changed, so that edits
+    /// made to other files by external tooling are not overwritten.
+    persisted: HashMap<String, ValidatorDefinition>,
+}
 
 impl From<Vec<ValidatorDefinition>> for ValidatorDefinitions {
-    fn from(vec: Vec<ValidatorDefinition>) -> Self {
-        Self(vec)
+    fn from(definitions: Vec<ValidatorDefinition>) -> Self {
+        Self {
+            definitions,
+            ..Self::default()
+        }
     }
 }
 
@@ -235,7 +320,7 @@ impl ValidatorDefinitions {
         })?;
         let config_path = validators_dir.as_ref().join(CONFIG_FILENAME);
         if !config_path.exists() {
-            let this = Self::default();
+            let mut this = Self::default();
             this.save(&validators_dir)?;
         }
         Self::open(validators_dir)
@@ -243,14 +328,74 @@ impl ValidatorDefinitions {
 
     /// Open an existing file, returning an error if the file does not exist.
     pub fn open<P: AsRef<Path>>(validators_dir: P) -> Result<Self, Error> {
-        let config_path = validators_dir.as_ref().join(CONFIG_FILENAME);
+        let validators_dir = validators_dir.as_ref();
+        let _lock = lock_definitions(validators_dir, false)?;
+
+        let config_path = validators_dir.join(CONFIG_FILENAME);
         let file = File::options()
             .write(true)
             .read(true)
             .create_new(false)
             .open(config_path)
             .map_err(Error::UnableToOpenFile)?;
-        serde_yaml::from_reader(file).map_err(Error::UnableToParseFile)
+        let value: serde_yaml::Value =
+            serde_yaml::from_reader(file).map_err(Error::UnableToParseFile)?;
+
+        // The original format is a plain list of definitions.
+        if value.is_sequence() {
+            return serde_yaml::from_value(value)
+                .map(Self::from)
+                .map_err(Error::UnableToParseFile);
+        }
+
+        let versioned: VersionedDefinitionsFile =
+            serde_yaml::from_value(value).map_err(Error::UnableToParseFile)?;
+        if versioned.version != SCHEMA_VERSION {
+            return Err(Error::UnsupportedSchemaVersion(versioned.version));
+        }
+
+        let mut this = Self {
+            definitions: versioned.validators,
+            layout: versioned.layout,
+            persisted: HashMap::new(),
+        };
+        if this.layout == DefinitionsLayout::PerValidatorFiles {
+            this.read_definition_files(validators_dir)?;
+        }
+        Ok(this)
+    }
+
+    /// Appends the definitions in the `DEFINITIONS_DIRNAME` directory to `self`, in order of file
+    /// name.
+    fn read_definition_files(&mut self, validators_dir: &Path) -> Result<(), Error> {
+        let definitions_dir = validators_dir.join(DEFINITIONS_DIRNAME);
+        if !definitions_dir.exists() {
+            return Ok(());
+        }
+
+        let mut files = fs::read_dir(&definitions_dir)
+            .and_then(|entries| {
+                entries
+                    .map(|entry| entry.map(|entry| entry.file_name()))
+                    .collect::<Result<Vec<_>, _>>()
+            })
+            .map_err(Error::UnableToReadDefinitionsDir)?
+            .into_iter()
+            .filter_map(|file_name| file_name.into_string().ok())
+            // Skip hidden files, which include the temporary files used for atomic writes.
+            .filter(|file_name| !file_name.starts_with('.') && file_name.ends_with(".yml"))
+            .collect::<Vec<_>>();
+        files.sort();
+
+        for file_name in files {
+            let path = definitions_dir.join(&file_name);
+            let file = File::open(&path).map_err(Error::UnableToOpenFile)?;
+            let def: ValidatorDefinition = serde_yaml::from_reader(file)
+                .map_err(|e| Error::UnableToParseDefinitionFile(path, e))?;
+            self.persisted.insert(file_name, def.clone());
+            self.definitions.push(def);
+        }
+        Ok(())
     }
 
     /// Perform a recursive, exhaustive search through `validators_dir` and add any keystores
@@ -276,7 +421,7 @@ impl ValidatorDefinitions {
             .map_err(Error::UnableToSearchForKeystores)?;
 
         let known_paths: HashSet<&PathBuf> = self
-            .0
+            .definitions
             .iter()
             .filter_map(|def| match &def.signing_definition {
                 SigningDefinition::LocalKeystore {
@@ -289,7 +434,7 @@ impl ValidatorDefinitions {
             .collect();
 
         let known_pubkeys: HashSet<PublicKey> = self
-            .0
+            .definitions
             .iter()
             .map(|def| def.voting_public_key.clone())
             .collect();
@@ -368,55 +513,178 @@ impl ValidatorDefinitions {
 
         let new_defs_count = new_defs.len();
 
-        self.0.append(&mut new_defs);
+        self.definitions.append(&mut new_defs);
 
         Ok(new_defs_count)
     }
 
-    /// Encodes `self` as a YAML string and atomically writes it to the `CONFIG_FILENAME` file in
-    /// the `validators_dir` directory.
+    /// Encodes `self` as YAML and atomically writes it to the `validators_dir` directory, using
+    /// the layout given by `Self::layout`.
     ///
-    /// Will create a new file if it does not exist or overwrite any existing file.
-    pub fn save<P: AsRef<Path>>(&self, validators_dir: P) -> Result<(), Error> {
-        let config_path = validators_dir.as_ref().join(CONFIG_FILENAME);
-        let temp_path = validators_dir.as_ref().join(CONFIG_TEMP_FILENAME);
+    /// Will create a new file if it does not exist or overwrite any existing file. With
+    /// `DefinitionsLayout::PerValidatorFiles`, only the files of definitions which have been
+    /// added, changed or removed since they were last read or written are modified.
+    pub fn save<P: AsRef<Path>>(&mut self, validators_dir: P) -> Result<(), Error> {
+        let validators_dir = validators_dir.as_ref();
+        let _lock = lock_definitions(validators_dir, true)?;
+
         let mut bytes = vec![];
-        serde_yaml::to_writer(&mut bytes, self).map_err(Error::UnableToEncodeFile)?;
+        match self.layout {
+            DefinitionsLayout::SingleFile => {
+                serde_yaml::to_writer(&mut bytes, &self.definitions)
+                    .map_err(Error::UnableToEncodeFile)?;
+            }
+            DefinitionsLayout::PerValidatorFiles => {
+                self.write_definition_files(validators_dir)?;
+                let versioned = VersionedDefinitionsFile {
+                    version: SCHEMA_VERSION,
+                    layout: self.layout,
+                    validators: vec![],
+                };
+                serde_yaml::to_writer(&mut bytes, &versioned).map_err(Error::UnableToEncodeFile)?;
+            }
+        }
 
+        let config_path = validators_dir.join(CONFIG_FILENAME);
+        let temp_path = validators_dir.join(CONFIG_TEMP_FILENAME);
         write_file_via_temporary(&config_path, &temp_path, &bytes)
             .map_err(Error::UnableToWriteFile)?;
 
+        // The definitions are now all stored in `CONFIG_FILENAME`, so remove any files left over
+        // from `DefinitionsLayout::PerValidatorFiles`.
+        if self.layout == DefinitionsLayout::SingleFile {
+            self.delete_stale_definition_files(validators_dir, &HashSet::new())?;
+        }
+
         Ok(())
     }
 
+    /// Writes each definition which differs from `self.persisted` to its own file in the
+    /// `DEFINITIONS_DIRNAME` directory, and deletes the files of definitions which have been
+    /// removed from `self`.
+    fn write_definition_files(&mut self, validators_dir: &Path) -> Result<(), Error> {
+        let definitions_dir = validators_dir.join(DEFINITIONS_DIRNAME);
+        ensure_dir_exists(&definitions_dir)
+            .map_err(|_| Error::UnableToCreateValidatorDir(definitions_dir.clone()))?;
+
+        let mut file_names = HashSet::with_capacity(self.definitions.len());
+        for def in &self.definitions {
+            let file_name = definition_file_name(&def.voting_public_key);
+            if !file_names.insert(file_name.clone()) {
+                return Err(Error::DuplicateDefinition(def.voting_public_key.clone()));
+            }
+            if self.persisted.get(&file_name) == Some(def) {
+                continue;
+            }
+
+            let mut bytes = vec![];
+            serde_yaml::to_writer(&mut bytes, def).map_err(Error::UnableToEncodeFile)?;
+            write_file_via_temporary(
+                &definitions_dir.join(&file_name),
+                &definitions_dir.join(format!(".{}.tmp", file_name)),
+                &bytes,
+            )
+            .map_err(Error::UnableToWriteFile)?;
+            self.persisted.insert(file_name, def.clone());
+        }
+
+        self.delete_stale_definition_files(validators_dir, &file_names)
+    }
+
+    /// Deletes the files in `self.persisted` which are not in `keep`.
+    ///
+    /// Files which were added to `DEFINITIONS_DIRNAME` by external tooling since the definitions
+    /// were read are left untouched.
+    fn delete_stale_definition_files(
+        &mut self,
+        validators_dir: &Path,
+        keep: &HashSet<String>,
+    ) -> Result<(), Error> {
+        let definitions_dir = validators_dir.join(DEFINITIONS_DIRNAME);
+        let stale = self
+            .persisted
+            .keys()
+            .filter(|file_name| !keep.contains(*file_name))
+            .cloned()
+            .collect::<Vec<_>>();
+        for file_name in stale {
+            let path = definitions_dir.join(&file_name);
+            match fs::remove_file(&path) {
+                Ok(()) => (),
+                Err(e) if e.kind() == io::ErrorKind::NotFound => (),
+                Err(e) => return Err(Error::UnableToDeleteDefinitionFile(path, e)),
+            }
+            self.persisted.remove(&file_name);
+        }
+        Ok(())
+    }
+
+    /// Returns the layout which is used when `self` is saved.
+    pub fn layout(&self) -> DefinitionsLayout {
+        self.layout
+    }
+
+    /// Sets the layout which is used when `self` is next saved.
+    pub fn set_layout(&mut self, layout: DefinitionsLayout) {
+        self.layout = layout;
+    }
+
     /// Retain only the definitions matching the given predicate.
     pub fn retain(&mut self, f: impl FnMut(&ValidatorDefinition) -> bool) {
-        self.0.retain(f);
+        self.definitions.retain(f);
     }
 
     /// Adds a new `ValidatorDefinition` to `self`.
     pub fn push(&mut self, def: ValidatorDefinition) {
-        self.0.push(def)
+        self.definitions.push(def)
     }
 
     /// Returns a slice of all `ValidatorDefinition` in `self`.
     pub fn as_slice(&self) -> &[ValidatorDefinition] {
-        self.0.as_slice()
+        self.definitions.as_slice()
     }
 
     /// Returns a mutable slice of all `ValidatorDefinition` in `self`.
     pub fn as_mut_slice(&mut self) -> &mut [ValidatorDefinition] {
-        self.0.as_mut_slice()
+        self.definitions.as_mut_slice()
     }
 
     // Returns an iterator over all the `voting_keystore_password_paths` in self.
     pub fn iter_voting_keystore_password_paths(&self) -> impl Iterator<Item = &PathBuf> {
-        self.0
+        self.definitions
             .iter()
             .filter_map(|def| def.signing_definition.voting_keystore_password_path())
     }
 }
 
+/// Returns the name of the file in `DEFINITIONS_DIRNAME` which holds the definition of the
+/// validator with the given `voting_public_key`.
+pub fn definition_file_name(voting_public_key: &PublicKey) -> String {
+    format!("{}.yml", voting_public_key.as_hex_string())
+}
+
+/// Opens `LOCK_FILENAME` in `validators_dir` and takes an advisory lock on it, blocking until the
+/// lock is acquired. The lock is released when the returned file is dropped.
+///
+/// The lock file is never deleted, since deleting a file that another process is waiting to lock
+/// would allow two processes to hold the "same" lock.
+fn lock_definitions(validators_dir: &Path, exclusive: bool) -> Result<File, Error> {
+    let file = File::options()
+        .read(true)
+        .write(true)
+        .create(true)
+        .truncate(false)
+        .open(validators_dir.join(LOCK_FILENAME))
+        .map_err(Error::UnableToLockFile)?;
+    if exclusive {
+        file.lock_exclusive()
+    } else {
+        file.lock_shared()
+    }
+    .map_err(Error::UnableToLockFile)?;
+    Ok(file)
+}
+
 /// Perform an exhaustive tree search of `dir`, adding any discovered voting keystore paths to
 /// `matches`.
 ///
@@ -491,6 +759,29 @@ pub fn is_voting_keystore(file_name: &str) -> bool {
 mod tests {
     use super::*;
     use std::str::FromStr;
+    use tempfile::tempdir;
+    use types::Keypair;
+
+    fn web3signer_definition() -> ValidatorDefinition {
+        ValidatorDefinition {
+            enabled: true,
+            voting_public_key: Keypair::random().pk,
+            graffiti: None,
+            suggested_fee_recipient: None,
+            gas_limit: None,
+            builder_proposals: None,
+            builder_boost_factor: None,
+            prefer_builder_proposals: None,
+            description: String::new(),
+            signing_definition: SigningDefinition::Web3Signer(Web3SignerDefinition {
+                url: "http://localhost:9000".into(),
+                root_certificate_path: None,
+                request_timeout_ms: None,
+                client_identity_path: None,
+                client_identity_password: None,
+            }),
+        }
+    }
 
     #[test]
     fn voting_keystore_filename_lighthouse() {
@@ -687,4 +978,94 @@ mod tests {
         let def: ValidatorDefinition = serde_yaml::from_str(valid_builder_proposals).unwrap();
         assert_eq!(def.builder_proposals, Some(true));
     }
+
+    #[test]
+    fn single_file_layout_is_unversioned() {
+        let dir = tempdir().unwrap();
+        let mut defs = ValidatorDefinitions::open_or_create(dir.path()).unwrap();
+        assert_eq!(defs.layout(), DefinitionsLayout::SingleFile);
+        defs.push(web3signer_definition());
+        defs.save(dir.path()).unwrap();
+
+        // Older versions expect a plain list of definitions.
+        let file = File::open(dir.path().join(CONFIG_FILENAME)).unwrap();
+        let list: Vec<ValidatorDefinition> = serde_yaml::from_reader(file).unwrap();
+        assert!(list == defs.as_slice());
+        assert!(!dir.path().join(DEFINITIONS_DIRNAME).exists());
+    }
+
+    #[test]
+    fn per_validator_files_layout() {
+        let dir = tempdir().unwrap();
+        let definitions_dir = dir.path().join(DEFINITIONS_DIRNAME);
+        let mut defs = ValidatorDefinitions::open_or_create(dir.path()).unwrap();
+        let (a, b) = (web3signer_definition(), web3signer_definition());
+        defs.push(a.clone());
+        defs.push(b.clone());
+        defs.set_layout(DefinitionsLayout::PerValidatorFiles);
+        defs.save(dir.path()).unwrap();
+
+        let a_path = definitions_dir.join(definition_file_name(&a.voting_public_key));
+        let b_path = definitions_dir.join(definition_file_name(&b.voting_public_key));
+        assert!(a_path.exists());
+        assert!(b_path.exists());
+
+        // A definition added by external tooling is loaded, and is not deleted or overwritten
+        // when unrelated changes are saved.
+        let mut defs = ValidatorDefinitions::open(dir.path()).unwrap();
+        assert_eq!(defs.layout(), DefinitionsLayout::PerValidatorFiles);
+        assert_eq!(defs.as_slice().len(), 2);
+
+        let c = web3signer_definition();
+        let c_path = definitions_dir.join(definition_file_name(&c.voting_public_key));
+        fs::write(&c_path, serde_yaml::to_string(&c).unwrap()).unwrap();
+
+        let mut b_external = b.clone();
+        b_external.description = "edited externally".into();
+        fs::write(&b_path, serde_yaml::to_string(&b_external).unwrap()).unwrap();
+
+        defs.retain(|def| def.voting_public_key != a.voting_public_key);
+        defs.save(dir.path()).unwrap();
+        assert!(!a_path.exists());
+        assert!(c_path.exists());
+
+        let mut defs = ValidatorDefinitions::open(dir.path()).unwrap();
+        assert_eq!(defs.as_slice().len(), 2);
+        assert!(defs.as_slice().contains(&b_external));
+        assert!(defs.as_slice().contains(&c));
+
+        // Converting back to a single file removes the per-validator files.
+        defs.set_layout(DefinitionsLayout::SingleFile);
+        defs.save(dir.path()).unwrap();
+        assert_eq!(fs::read_dir(&definitions_dir).unwrap().count(), 0);
+        let reopened = ValidatorDefinitions::open(dir.path()).unwrap();
+        assert_eq!(reopened.layout(), DefinitionsLayout::SingleFile);
+        assert!(reopened.as_slice() == defs.as_slice());
+    }
+
+    #[test]
+    fn per_validator_files_reject_duplicates() {
+        let dir = tempdir().unwrap();
+        let def = web3signer_definition();
+        let mut defs = ValidatorDefinitions::from(vec![def.clone(), def.clone()]);
+        defs.set_layout(DefinitionsLayout::PerValidatorFiles);
+        assert!(matches!(
+            defs.save(dir.path()),
+            Err(Error::DuplicateDefinition(pubkey)) if pubkey == def.voting_public_key
+        ));
+    }
+
+    #[test]
+    fn unsupported_schema_version() {
+        let dir = tempdir().unwrap();
+        fs::write(
+            dir.path().join(CONFIG_FILENAME),
+            "version: 3\nlayout: single-file\n",
+        )
+        .unwrap();
+        assert!(matches!(
+            ValidatorDefinitions::open(dir.path()),
+            Err(Error::UnsupportedSchemaVersion(3))
+        ));
+    }
 }
@@ -0,0 +1,487 @@
+//! A server-sent events client which reconnects when the connection to the beacon node is lost.
+//!
+//! Events are parsed from the byte stream as complete SSE frames, rather than assuming that each
+//! chunk of bytes contains exactly one event. If the server includes an `id` with each event, the
+//! id of the last event received is sent in the `Last-Event-ID` header when reconnecting so that
+//! the server may replay missed events.
+//!
+//! Since events may be missed whilst disconnected, or skipped by the server, the caller is
+//! notified of each potential gap in the stream via an async callback. The callback is awaited
+//! before any later event is yielded, so that the caller can re-query the state it derives from
+//! events without an event older than that state following it.
+use crate::types::{EventKind, EventTopic};
+use crate::{BeaconNodeHttpClient, Error};
+use futures::stream::{self, BoxStream, Stream, StreamExt};
+use futures::Future;
+use reqwest::Url;
+use std::collections::VecDeque;
+use std::time::Duration;
+use types::EthSpec;
+
+/// The name of the header used to resume a stream from the last event received.
+pub const LAST_EVENT_ID_HEADER: &str = "Last-Event-ID";
+
+/// Configures the reconnection behaviour of an event stream.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct EventStreamConfig {
+    /// The delay before the first reconnection attempt. Doubled after each consecutive failure.
+    pub reconnect_delay: Duration,
+    /// The maximum delay between reconnection attempts.
+    pub max_reconnect_delay: Duration,
+    /// The number of consecutive failed connection attempts after which the stream returns an
+    /// error and ends. If `None`, the stream reconnects indefinitely.
+    ///
+    /// The stream always ends if the server rejects the request with a 4xx status, as retrying
+    /// the same request cannot succeed.
+    pub max_reconnect_attempts: Option<usize>,
+}
+
+impl Default for EventStreamConfig {
+    fn default() -> Self {
+        Self {
+            reconnect_delay: Duration::from_millis(500),
+            max_reconnect_delay: Duration::from_secs(30),
+            max_reconnect_attempts: None,
+        }
+    }
+}
+
+impl EventStreamConfig {
+    fn delay_after_failures(&self, failures: usize) -> Duration {
+        let exponent = u32::try_from(failures.saturating_sub(1)).unwrap_or(u32::MAX);
+        self.reconnect_delay
+            .checked_mul(2_u32.saturating_pow(exponent))
+            .unwrap_or(self.max_reconnect_delay)
+            .min(self.max_reconnect_delay)
+    }
+}
+
+/// A point in an event stream at which events may have been missed.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum EventStreamGap {
+    /// The stream reconnected after the connection was lost.
+    ///
+    /// Events published whilst disconnected were missed unless the server replayed them from
+    /// `last_event_id`. If the server does not provide event ids, `last_event_id` is `None` and the
+    /// missed events cannot be recovered, so the caller should re-query any state it derives from
+    /// them. The new connection is established before the callback is called, so events published
+    /// whilst re-querying are yielded once it completes.
+    Reconnected { last_event_id: Option<String> },
+    /// The server provided numeric event ids and the id following `last_event_id` was not
+    /// `last_event_id + 1`.
+    MissedEvents {
+        last_event_id: u64,
+        next_event_id: u64,
+    },
+}
+
+/// A single frame of a server-sent event stream.
+#[derive(Debug, Default, PartialEq, Eq)]
+struct SseFrame {
+    event: Option<String>,
+    data: Vec<String>,
+    id: Option<String>,
+}
+
+impl SseFrame {
+    fn parse(frame: &str) -> Self {
+        let mut parsed = Self::default();
+        for line in frame.lines() {
+            let (field, value) = line.split_once(':').unwrap_or((line, ""));
+            let value = value.strip_prefix(' ').unwrap_or(value);
+            match field {
+                "event" => parsed.event = Some(value.to_string()),
+                "data" => parsed.data.push(value.to_string()),
+                "id" => parsed.id = Some(value.to_string()),
+                // Comments and unknown fields are ignored.
+                _ => {}
+            }
+        }
+        parsed
+    }
+
+    fn into_event<E: EthSpec>(self) -> Option<Result<EventKind<E>, Error>> {
+        let event = self.event?;
+        // `EventKind::from_sse_bytes` expects the data on a single line. Newlines are not permitted
+        // within JSON strings, so they can be replaced by spaces.
+        let bytes = format!("event:{}\ndata:{}", event, self.data.join(" "));
+        Some(EventKind::from_sse_bytes(bytes.as_bytes()))
+    }
+}
+
+/// An item waiting to be yielded by a `ReconnectingStream`.
+enum PendingItem<E: EthSpec> {
+    Gap(EventStreamGap),
+    Event(Result<EventKind<E>, Error>),
+}
+
+/// Splits complete SSE frames from the front of `buffer`, leaving any incomplete frame in place.
+fn take_frames(buffer: &mut String) -> Vec<SseFrame> {
+    // Line endings may be `\r\n`, `\r` or `\n`.
+    if buffer.contains('\r') {
+        *buffer = buffer.replace("\r\n", "\n").replace('\r', "\n");
+    }
+
+    let mut frames = vec![];
+    while let Some(end) = buffer.find("\n\n") {
+        let frame = buffer[..end].to_string();
+        buffer.replace_range(..end + 2, "");
+        if !frame.is_empty() {
+            frames.push(SseFrame::parse(&frame));
+        }
+    }
+    frames
+}
+
+struct ReconnectingStream<E: EthSpec, F> {
+    client: BeaconNodeHttpClient,
+    url: Url,
+    config: EventStreamConfig,
+    on_gap: F,
+    connection: Option<BoxStream<'static, Result<bytes::Bytes, reqwest::Error>>>,
+    /// Bytes received which do not yet form a complete UTF-8 character.
+    partial_bytes: Vec<u8>,
+    buffer: String,
+    pending: VecDeque<PendingItem<E>>,
+    last_event_id: Option<String>,
+    has_connected: bool,
+    failures: usize,
+    finished: bool,
+}
+
+impl<E, F, Fut> ReconnectingStream<E, F>
+where
+    E: EthSpec,
+    F: FnMut(EventStreamGap) -> Fut + Send + 'static,
+    Fut: Future<Output = ()> + Send,
+{
+    async fn connect(&mut self) -> Result<(), Error> {
+        let mut request = self.client.client.get(self.url.clone());
+        if let Some(last_event_id) = &self.last_event_id {
+            request = request.header(LAST_EVENT_ID_HEADER, last_event_id.as_str());
+        }
+        let response = request.send().await?.error_for_status()?;
+        self.connection = Some(response.bytes_stream().boxed());
+        self.partial_bytes.clear();
+        self.buffer.clear();
+
+        if self.has_connected {
+            self.pending
+                .push_back(PendingItem::Gap(EventStreamGap::Reconnected {
+                    last_event_id: self.last_event_id.clone(),
+                }));
+        }
+        self.has_connected = true;
+        Ok(())
+    }
+
+    fn process_frame(&mut self, frame: SseFrame) {
+        if let Some(id) = &frame.id {
+            if let (Some(last), Ok(next)) = (
+                self.last_event_id
+                    .as_ref()
+                    .and_then(|last| last.parse::<u64>().ok()),
+                id.parse::<u64>(),
+            ) {
+                if next > last.saturating_add(1) {
+                    self.pending
+                        .push_back(PendingItem::Gap(EventStreamGap::MissedEvents {
+                            last_event_id: last,
+                            next_event_id: next,
+                        }));
+                }
+            }
+            self.last_event_id = Some(id.clone());
+        }
+
+        if let Some(event) = frame.into_event() {
+            self.pending.push_back(PendingItem::Event(event));
+        }
+    }
+
+    /// Return the next item of the stream, or `None` if the stream has ended.
+    async fn next_item(&mut self) -> Option<Result<EventKind<E>, Error>> {
+        loop {
+            match self.pending.pop_front() {
+                Some(PendingItem::Event(event)) => return Some(event),
+                Some(PendingItem::Gap(gap)) => {
+                    (self.on_gap)(gap).await;
+                    continue;
+                }
+                None => {}
+            }
+            if self.finished {
+                return None;
+            }
+
+            let Some(connection) = self.connection.as_mut() else {
+                if self.failures > 0 {
+                    tokio::time::sleep(self.config.delay_after_failures(self.failures)).await;
+                }
+                match self.connect().await {
+                    Ok(()) => self.failures = 0,
+                    Err(e) => {
+                        self.failures += 1;
+                        let rejected = e.status().is_some_and(|status| status.is_client_error());
+                        if rejected
+                            || self
+                                .config
+                                .max_reconnect_attempts
+                                .is_some_and(|max| self.failures > max)
+                        {
+                            self.finished = true;
+                            return Some(Err(e));
+                        }
+                    }
+                }
+                continue;
+            };
+
+            match connection.next().await {
+                Some(Ok(bytes)) => {
+                    self.partial_bytes.extend_from_slice(&bytes);
+                    let valid_up_to = match std::str::from_utf8(&self.partial_bytes) {
+                        Ok(_) => self.partial_bytes.len(),
+                        // The chunk ends part way through a character.
+                        Err(e) if e.error_len().is_none() => e.valid_up_to(),
+                        Err(e) => {
+                            self.partial_bytes.clear();
+                            self.pending.push_back(PendingItem::Event(Err(
+                                Error::InvalidServerSentEvent(format!("{:?}", e)),
+                            )));
+                            continue;
+                        }
+                    };
+                    let remaining = self.partial_bytes.split_off(valid_up_to);
+                    let valid = std::mem::replace(&mut self.partial_bytes, remaining);
+                    self.buffer.push_str(&String::from_utf8_lossy(&valid));
+                    for frame in take_frames(&mut self.buffer) {
+                        self.process_frame(frame);
+                    }
+                }
+                // The connection was lost, reconnect after a delay.
+                Some(Err(_)) | None => {
+                    self.connection = None;
+                    self.failures = 1;
+                }
+            }
+        }
+    }
+}
+
+/// Returns a stream of events for `topics` which reconnects whenever the connection is lost.
+pub(crate) fn reconnecting_event_stream<E, F, Fut>(
+    client: BeaconNodeHttpClient,
+    url: Url,
+    config: EventStreamConfig,
+    on_gap: F,
+) -> impl Stream<Item = Result<EventKind<E>, Error>>
+where
+    E: EthSpec,
+    F: FnMut(EventStreamGap) -> Fut + Send + 'static,
+    Fut: Future<Output = ()> + Send,
+{
+    let state = ReconnectingStream {
+        client,
+        url,
+        config,
+        on_gap,
+        connection: None,
+        partial_bytes: vec![],
+        buffer: String::new(),
+        pending: VecDeque::new(),
+        last_event_id: None,
+        has_connected: false,
+        failures: 0,
+        finished: false,
+    };
+    stream::unfold(state, |mut state| async move {
+        let item = state.next_item().await?;
+        Some((item, state))
+    })
+}
+
+/// Returns the `topics` query string value for an events request.
+pub(crate) fn topics_query(topics: &[EventTopic]) -> String {
+    topics
+        .iter()
+        .map(|i| i.to_string())
+        .collect::<Vec<_>>()
+        .join(",")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{SensitiveUrl, Timeouts};
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::{Arc, Mutex};
+    use types::{Epoch, Hash256, MainnetEthSpec, SseFinalizedCheckpoint};
+    use warp::Filter;
+
+    fn finalized_checkpoint_frame(epoch: u64) -> String {
+        let checkpoint = SseFinalizedCheckpoint {
+            block: Hash256::repeat_byte(1),
+            state: Hash256::repeat_byte(2),
+            epoch: Epoch::new(epoch),
+            execution_optimistic: false,
+        };
+        format!(
+            "event:finalized_checkpoint\ndata:{}\n\n",
+            serde_json::to_string(&checkpoint).unwrap()
+        )
+    }
+
+    /// Serves an events endpoint whose first connection sends a single event and is then killed.
+    /// Later connections send a second event and are held open. Returns the server URL and the
+    /// number of connections made.
+    fn serve_events_killed_after_first_event() -> (SensitiveUrl, Arc<AtomicUsize>) {
+        let connections = Arc::new(AtomicUsize::new(0));
+        let connections_filter = connections.clone();
+        let route = warp::path!("eth" / "v1" / "events").map(move || {
+            let connection = connections_filter.fetch_add(1, Ordering::SeqCst);
+            let frame = finalized_checkpoint_frame(connection as u64 + 1);
+            let first =
+                futures::stream::once(async move { Ok::<_, std::convert::Infallible>(frame) });
+            let body = if connection == 0 {
+                first.boxed()
+            } else {
+                first.chain(futures::stream::pending()).boxed()
+            };
+            warp::http::Response::new(warp::hyper::Body::wrap_stream(body))
+        });
+        let (addr, server) = warp::serve(route).bind_ephemeral(([127, 0, 0, 1], 0));
+        tokio::spawn(server);
+        let url = SensitiveUrl::parse(&format!("http://{addr}")).unwrap();
+        (url, connections)
+    }
+
+    fn test_config() -> EventStreamConfig {
+        EventStreamConfig {
+            reconnect_delay: Duration::from_millis(10),
+            max_reconnect_delay: Duration::from_millis(10),
+            max_reconnect_attempts: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn reconnects_and_resyncs_after_the_connection_is_killed() {
+        let (url, connections) = serve_events_killed_after_first_event();
+        let client = BeaconNodeHttpClient::new(url, Timeouts::set_all(Duration::from_secs(5)));
+        let gaps = Arc::new(Mutex::new(vec![]));
+        let gaps_hook = gaps.clone();
+        let mut events = Box::pin(
+            client
+                .get_events_with_reconnect::<MainnetEthSpec, _, _>(
+                    &[EventTopic::FinalizedCheckpoint],
+                    test_config(),
+                    move |gap| {
+                        let gaps = gaps_hook.clone();
+                        async move { gaps.lock().unwrap().push(gap) }
+                    },
+                )
+                .unwrap(),
+        );
+
+        let epochs = tokio::time::timeout(Duration::from_secs(5), async {
+            let mut epochs = vec![];
+            for _ in 0..2 {
+                match events.next().await.unwrap().unwrap() {
+                    EventKind::FinalizedCheckpoint(checkpoint) => epochs.push(checkpoint.epoch),
+                    other => panic!("unexpected event {other:?}"),
+                }
+            }
+            epochs
+        })
+        .await
+        .unwrap();
+
+        // The event sent after the connection was killed is received over the new connection,
+        // after the caller has been told to re-query its state.
+        assert_eq!(epochs, vec![Epoch::new(1), Epoch::new(2)]);
+        assert_eq!(connections.load(Ordering::SeqCst), 2);
+        assert_eq!(
+            *gaps.lock().unwrap(),
+            vec![EventStreamGap::Reconnected {
+                last_event_id: None
+            }]
+        );
+    }
+
+    #[tokio::test]
+    async fn client_errors_are_not_retried() {
+        let connections = Arc::new(AtomicUsize::new(0));
+        let connections_filter = connections.clone();
+        let route = warp::path!("eth" / "v1" / "events").map(move || {
+            connections_filter.fetch_add(1, Ordering::SeqCst);
+            warp::reply::with_status(
+                warp::reply::json(&serde_json::json!({
+                    "code": 400,
+                    "message": "invalid topic",
+                })),
+                warp::http::StatusCode::BAD_REQUEST,
+            )
+        });
+        let (addr, server) = warp::serve(route).bind_ephemeral(([127, 0, 0, 1], 0));
+        tokio::spawn(server);
+        let url = SensitiveUrl::parse(&format!("http://{addr}")).unwrap();
+        let client = BeaconNodeHttpClient::new(url, Timeouts::set_all(Duration::from_secs(5)));
+        let mut events = Box::pin(
+            client
+                .get_events_with_reconnect::<MainnetEthSpec, _, _>(
+                    &[EventTopic::FinalizedCheckpoint],
+                    test_config(),
+                    |_| async {},
+                )
+                .unwrap(),
+        );
+
+        let error = events.next().await.unwrap().unwrap_err();
+        assert_eq!(error.status(), Some(reqwest::StatusCode::BAD_REQUEST));
+        assert!(events.next().await.is_none());
+        assert_eq!(connections.load(Ordering::SeqCst), 1);
+    }
+
+    #[test]
+    fn frames_split_across_chunks() {
+        let mut buffer = String::from("event:head\ndata:{\"a\":1}\nid:7\n\nevent:blo");
+        let frames = take_frames(&mut buffer);
+        assert_eq!(
+            frames,
+            vec![SseFrame {
+                event: Some("head".to_string()),
+                data: vec!["{\"a\":1}".to_string()],
+                id: Some("7".to_string()),
+            }]
+        );
+        assert_eq!(buffer, "event:blo");
+
+        buffer.push_str("ck\r\ndata: {}\r\n\r\n");
+        let frames = take_frames(&mut buffer);
+        assert_eq!(frames.len(), 1);
+        assert_eq!(frames[0].event.as_deref(), Some("block"));
+        assert_eq!(frames[0].data, vec!["{}".to_string()]);
+        assert!(buffer.is_empty());
+    }
+
+    #[test]
+    fn comments_are_ignored() {
+        let mut buffer = String::from(":keep-alive\n\n");
+        let frames = take_frames(&mut buffer);
+        assert_eq!(frames, vec![SseFrame::default()]);
+    }
+
+    #[test]
+    fn reconnect_delay_backs_off() {
+        let config = EventStreamConfig {
+            reconnect_delay: Duration::from_secs(1),
+            max_reconnect_delay: Duration::from_secs(5),
+            max_reconnect_attempts: None,
+        };
+        assert_eq!(config.delay_after_failures(1), Duration::from_secs(1));
+        assert_eq!(config.delay_after_failures(2), Duration::from_secs(2));
+        assert_eq!(config.delay_after_failures(3), Duration::from_secs(4));
+        assert_eq!(config.delay_after_failures(4), Duration::from_secs(5));
+        assert_eq!(config.delay_after_failures(100), Duration::from_secs(5));
+    }
+}
@@ -7,13 +7,16 @@
 //! Eventually it would be ideal to publish this crate on crates.io, however we have some local
 //! dependencies preventing this presently.
 
+pub mod event_stream;
 #[cfg(feature = "lighthouse")]
 pub mod lighthouse;
 #[cfg(feature = "lighthouse")]
 pub mod lighthouse_vc;
+mod metrics;
 pub mod mixin;
 pub mod types;
 
+use self::event_stream::{EventStreamConfig, EventStreamGap};
 use self::mixin::{RequestAccept, ResponseOptional};
 use self::types::{Error as ResponseError, *};
 use derivative::Derivative;
@@ -33,8 +36,11 @@ use ssz::Encode;
 use std::fmt;
 use std::future::Future;
 use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
 use std::time::Duration;
 use store::fork_versioned_response::ExecutionOptimisticFinalizedForkVersionedResponse;
+use tokio::sync::{OwnedSemaphorePermit, Semaphore};
 
 pub const V1: EndpointVersion = EndpointVersion(1);
 pub const V2: EndpointVersion = EndpointVersion(2);
@@ -162,6 +168,13 @@ pub struct BeaconNodeHttpClient {
     client: reqwest::Client,
     server: SensitiveUrl,
     timeouts: Timeouts,
+    /// Limits the number of requests which may be in-flight at once, if set.
+    #[derivative(PartialEq = "ignore")]
+    request_permits: Option<Arc<Semaphore>>,
+    /// Send SSZ request bodies to endpoints which support them. Set to `false` if the server
+    /// rejects an SSZ request body.
+    #[derivative(PartialEq = "ignore")]
+    ssz_request_bodies: Arc<AtomicBool>,
 }
 
 impl Eq for BeaconNodeHttpClient {}
@@ -184,6 +197,8 @@ impl BeaconNodeHttpClient {
             client: reqwest::Client::new(),
             server,
             timeouts,
+            request_permits: None,
+            ssz_request_bodies: Arc::new(AtomicBool::new(false)),
         }
     }
 
@@ -196,9 +211,76 @@ impl BeaconNodeHttpClient {
             client,
             server,
             timeouts,
+            request_permits: None,
+            ssz_request_bodies: Arc::new(AtomicBool::new(false)),
         }
     }
 
+    /// Limit the number of requests which may be awaiting a response from the server at once.
+    ///
+    /// Requests beyond the limit are queued until an earlier request completes. This prevents a
+    /// burst of requests at a slot boundary from overwhelming the server (or a single HTTP/2
+    /// connection) and delaying time-critical requests.
+    pub fn with_max_concurrent_requests(mut self, max_concurrent_requests: usize) -> Self {
+        self.request_permits = Some(Arc::new(Semaphore::new(max_concurrent_requests)));
+        self
+    }
+
+    /// Send SSZ-encoded request bodies to the attestation, aggregate and duties endpoints.
+    ///
+    /// If the server rejects an SSZ request body with any `4xx` status the request is retried
+    /// using JSON, and all future requests to this server will use JSON.
+    pub fn with_ssz_request_bodies(mut self) -> Self {
+        self.ssz_request_bodies = Arc::new(AtomicBool::new(true));
+        self
+    }
+
+    /// Send a request to the server, subject to the concurrency limit.
+    ///
+    /// The request counts against the limit until the body of the response has been read (or the
+    /// response is dropped), so that a slow response body can't be overtaken by further requests.
+    ///
+    /// The time taken to receive the response headers is recorded against the category of the
+    /// endpoint being requested (e.g. `validator/duties/attester`).
+    async fn send(&self, builder: RequestBuilder) -> Result<Response, Error> {
+        let request = builder.build()?;
+        let category = endpoint_category(request.url());
+
+        let permit = if let Some(permits) = &self.request_permits {
+            let _timer = metrics::start_timer_vec(
+                &metrics::BEACON_NODE_HTTP_CLIENT_PERMIT_WAIT_TIMES,
+                &[&category],
+            );
+            // The semaphore is never closed so this will not return an error.
+            permits.clone().acquire_owned().await.ok()
+        } else {
+            None
+        };
+
+        let timer = metrics::start_timer_vec(
+            &metrics::BEACON_NODE_HTTP_CLIENT_REQUEST_TIMES,
+            &[&category],
+        );
+        let result = self.client.execute(request).await;
+        drop(timer);
+
+        if result
+            .as_ref()
+            .map_or(true, |response| response.status().is_server_error())
+        {
+            metrics::inc_counter_vec(
+                &metrics::BEACON_NODE_HTTP_CLIENT_REQUEST_ERRORS,
+                &[&category],
+            );
+        }
+
+        let response = result?;
+        Ok(match permit {
+            Some(permit) => hold_permit_until_body_read(response, permit),
+            None => response,
+        })
+    }
+
     /// Return the path with the standard `/eth/vX` prefix applied.
     fn eth_path(&self, version: EndpointVersion) -> Result<Url, Error> {
         let mut path = self.server.full.clone();
@@ -223,7 +305,7 @@ impl BeaconNodeHttpClient {
         url: U,
         builder: impl FnOnce(RequestBuilder) -> RequestBuilder,
     ) -> Result<Response, Error> {
-        let response = builder(self.client.get(url)).send().await?;
+        let response = self.send(builder(self.client.get(url))).await?;
         ok_or_error(response).await
     }
 
@@ -353,19 +435,6 @@ impl BeaconNodeHttpClient {
         Ok(())
     }
 
-    /// Perform a HTTP POST request with a custom timeout and consensus header.
-    async fn post_with_timeout_and_consensus_header<T: Serialize, U: IntoUrl>(
-        &self,
-        url: U,
-        body: &T,
-        timeout: Duration,
-        fork_name: ForkName,
-    ) -> Result<(), Error> {
-        self.post_generic_with_consensus_version(url, body, Some(timeout), fork_name)
-            .await?;
-        Ok(())
-    }
-
     /// Perform a HTTP POST request with a custom timeout, returning a JSON response.
     async fn post_with_timeout_and_response<T: DeserializeOwned, U: IntoUrl, V: Serialize>(
         &self,
@@ -392,7 +461,7 @@ impl BeaconNodeHttpClient {
             builder = builder.timeout(timeout);
         }
 
-        let response = builder.json(body).send().await?;
+        let response = self.send(builder.json(body)).await?;
         ok_or_error(response).await
     }
 
@@ -408,14 +477,57 @@ impl BeaconNodeHttpClient {
         if let Some(timeout) = timeout {
             builder = builder.timeout(timeout);
         }
-        let response = builder
-            .header(CONSENSUS_VERSION_HEADER, fork.to_string())
-            .json(body)
-            .send()
+        let response = self
+            .send(
+                builder
+                    .header(CONSENSUS_VERSION_HEADER, fork.to_string())
+                    .json(body),
+            )
             .await?;
         ok_or_error(response).await
     }
 
+    /// Generic POST function which sends `body` as SSZ if SSZ request bodies are enabled,
+    /// otherwise as JSON.
+    ///
+    /// If the server rejects the SSZ body with `415 Unsupported Media Type` the request is retried
+    /// as JSON, and SSZ request bodies are disabled. Other errors are returned as they are, since
+    /// they most likely concern the object being posted rather than its encoding.
+    async fn post_generic_json_or_ssz<T: Serialize>(
+        &self,
+        url: Url,
+        body: &T,
+        ssz_body: impl FnOnce() -> Vec<u8>,
+        timeout: Duration,
+        fork_name: Option<ForkName>,
+    ) -> Result<Response, Error> {
+        if self.ssz_request_bodies.load(Ordering::Relaxed) {
+            let mut builder = self
+                .client
+                .post(url.clone())
+                .timeout(timeout)
+                .header(CONTENT_TYPE_HEADER, SSZ_CONTENT_TYPE_HEADER);
+            if let Some(fork_name) = fork_name {
+                builder = builder.header(CONSENSUS_VERSION_HEADER, fork_name.to_string());
+            }
+
+            match ok_or_error(self.send(builder.body(ssz_body())).await?).await {
+                Err(e) if e.status() == Some(StatusCode::UNSUPPORTED_MEDIA_TYPE) => {
+                    self.ssz_request_bodies.store(false, Ordering::Relaxed);
+                }
+                result => return result,
+            }
+        }
+
+        match fork_name {
+            Some(fork_name) => {
+                self.post_generic_with_consensus_version(url, body, Some(timeout), fork_name)
+                    .await
+            }
+            None => self.post_generic(url, body, Some(timeout)).await,
+        }
+    }
+
     /// Generic POST function that includes octet-stream content type header.
     async fn post_generic_with_ssz_header<T: Serialize, U: IntoUrl>(
         &self,
@@ -429,7 +541,7 @@ impl BeaconNodeHttpClient {
             "Content-Type",
             HeaderValue::from_static("application/octet-stream"),
         );
-        let response = builder.headers(headers).json(body).send().await?;
+        let response = self.send(builder.headers(headers).json(body)).await?;
         ok_or_error(response).await
     }
 
@@ -454,7 +566,7 @@ impl BeaconNodeHttpClient {
             "Content-Type",
             HeaderValue::from_static("application/octet-stream"),
         );
-        let response = builder.headers(headers).body(body).send().await?;
+        let response = self.send(builder.headers(headers).body(body)).await?;
         ok_or_error(response).await
     }
 
@@ -1147,6 +1259,21 @@ impl BeaconNodeHttpClient {
         block_id: BlockId,
         indices: Option<&[u64]>,
     ) -> Result<Option<ExecutionOptimisticFinalizedForkVersionedResponse<BlobSidecarList<E>>>, Error>
+    {
+        self.get_blobs_filtered(block_id, indices, None).await
+    }
+
+    /// `GET v1/beacon/blob_sidecars/{block_id}?versioned_hashes`
+    ///
+    /// Only blobs matching both `indices` and `versioned_hashes` are returned, if supplied.
+    ///
+    /// Returns `Ok(None)` on a 404 error.
+    pub async fn get_blobs_filtered<E: EthSpec>(
+        &self,
+        block_id: BlockId,
+        indices: Option<&[u64]>,
+        versioned_hashes: Option<&[Hash256]>,
+    ) -> Result<Option<ExecutionOptimisticFinalizedForkVersionedResponse<BlobSidecarList<E>>>, Error>
     {
         let mut path = self.get_blobs_path(block_id)?;
         if let Some(indices) = indices {
@@ -1158,6 +1285,15 @@ impl BeaconNodeHttpClient {
             path.query_pairs_mut()
                 .append_pair("indices", &indices_string);
         }
+        if let Some(versioned_hashes) = versioned_hashes {
+            let versioned_hashes_string = versioned_hashes
+                .iter()
+                .map(|hash| format!("{hash:?}"))
+                .collect::<Vec<_>>()
+                .join(",");
+            path.query_pairs_mut()
+                .append_pair("versioned_hashes", &versioned_hashes_string);
+        }
 
         let Some(response) = self.get_response(path, |b| b).await.optional()? else {
             return Ok(None);
@@ -1307,8 +1443,14 @@ impl BeaconNodeHttpClient {
             .push("pool")
             .push("attestations");
 
-        self.post_with_timeout(path, &attestations, self.timeouts.attestation)
-            .await?;
+        self.post_generic_json_or_ssz(
+            path,
+            &attestations,
+            || ssz_list_bytes(attestations),
+            self.timeouts.attestation,
+            None,
+        )
+        .await?;
 
         Ok(())
     }
@@ -1327,11 +1469,12 @@ impl BeaconNodeHttpClient {
             .push("pool")
             .push("attestations");
 
-        self.post_with_timeout_and_consensus_header(
+        self.post_generic_json_or_ssz(
             path,
             &attestations,
+            || ssz_list_bytes(attestations),
             self.timeouts.attestation,
-            fork_name,
+            Some(fork_name),
         )
         .await?;
 
@@ -1784,7 +1927,7 @@ impl BeaconNodeHttpClient {
             .push("node")
             .push("health");
 
-        let status = self.client.get(path).send().await?.status();
+        let status = self.send(self.client.get(path)).await?.status();
         if status == StatusCode::OK || status == StatusCode::PARTIAL_CONTENT {
             Ok(status)
         } else {
@@ -2085,6 +2228,14 @@ impl BeaconNodeHttpClient {
             )
             .await?;
 
+        self.get_validator_blocks_v3_response(path).await
+    }
+
+    /// Fetch a JSON response from a v3-style block production endpoint at `path`.
+    pub(crate) async fn get_validator_blocks_v3_response<E: EthSpec>(
+        &self,
+        path: Url,
+    ) -> Result<(JsonProduceBlockV3Response<E>, ProduceBlockV3Metadata), Error> {
         let opt_result = self
             .get_response_with_response_headers(
                 path,
@@ -2486,12 +2637,17 @@ impl BeaconNodeHttpClient {
             .push("attester")
             .push(&epoch.to_string());
 
-        self.post_with_timeout_and_response(
+        self.post_generic_json_or_ssz(
             path,
             &ValidatorIndexDataRef(indices),
+            || ssz_list_bytes(indices),
             self.timeouts.attester_duties,
+            None,
         )
+        .await?
+        .json()
         .await
+        .map_err(Into::into)
     }
 
     /// `POST v1/validator/aggregate_and_proofs`
@@ -2506,8 +2662,14 @@ impl BeaconNodeHttpClient {
             .push("validator")
             .push("aggregate_and_proofs");
 
-        self.post_with_timeout(path, &aggregates, self.timeouts.attestation)
-            .await?;
+        self.post_generic_json_or_ssz(
+            path,
+            &aggregates,
+            || ssz_list_bytes(aggregates),
+            self.timeouts.attestation,
+            None,
+        )
+        .await?;
 
         Ok(())
     }
@@ -2525,11 +2687,12 @@ impl BeaconNodeHttpClient {
             .push("validator")
             .push("aggregate_and_proofs");
 
-        self.post_with_timeout_and_consensus_header(
+        self.post_generic_json_or_ssz(
             path,
             &aggregates,
+            || ssz_list_bytes(aggregates),
             self.timeouts.attestation,
-            fork_name,
+            Some(fork_name),
         )
         .await?;
 
@@ -2585,12 +2748,8 @@ impl BeaconNodeHttpClient {
             .map_err(|()| Error::InvalidUrl(self.server.clone()))?
             .push("events");
 
-        let topic_string = topic
-            .iter()
-            .map(|i| i.to_string())
-            .collect::<Vec<_>>()
-            .join(",");
-        path.query_pairs_mut().append_pair("topics", &topic_string);
+        path.query_pairs_mut()
+            .append_pair("topics", &event_stream::topics_query(topic));
 
         Ok(self
             .client
@@ -2604,6 +2763,37 @@ impl BeaconNodeHttpClient {
             }))
     }
 
+    /// `GET events?topics`, reconnecting whenever the connection is lost.
+    ///
+    /// Unlike `Self::get_events`, the stream only ends if the request is rejected with a 4xx
+    /// status or `config.max_reconnect_attempts` consecutive connection attempts fail. The
+    /// `on_gap` callback is awaited whenever events may have been missed, before any later event
+    /// is yielded, see `EventStreamGap`.
+    pub fn get_events_with_reconnect<E: EthSpec, F, Fut>(
+        &self,
+        topic: &[EventTopic],
+        config: EventStreamConfig,
+        on_gap: F,
+    ) -> Result<impl Stream<Item = Result<EventKind<E>, Error>>, Error>
+    where
+        F: FnMut(EventStreamGap) -> Fut + Send + 'static,
+        Fut: Future<Output = ()> + Send,
+    {
+        let mut path = self.eth_path(V1)?;
+        path.path_segments_mut()
+            .map_err(|()| Error::InvalidUrl(self.server.clone()))?
+            .push("events");
+        path.query_pairs_mut()
+            .append_pair("topics", &event_stream::topics_query(topic));
+
+        Ok(event_stream::reconnecting_event_stream(
+            self.clone(),
+            path,
+            config,
+            on_gap,
+        ))
+    }
+
     /// `POST validator/duties/sync/{epoch}`
     pub async fn post_validator_duties_sync(
         &self,
@@ -2619,12 +2809,90 @@ impl BeaconNodeHttpClient {
             .push("sync")
             .push(&epoch.to_string());
 
-        self.post_with_timeout_and_response(
+        self.post_generic_json_or_ssz(
             path,
             &ValidatorIndexDataRef(indices),
+            || ssz_list_bytes(indices),
             self.timeouts.sync_duties,
+            None,
         )
+        .await?
+        .json()
         .await
+        .map_err(Into::into)
+    }
+}
+
+/// Rebuild `response` with a body which holds `permit` until it has been read to completion or
+/// dropped.
+fn hold_permit_until_body_read(response: Response, permit: OwnedSemaphorePermit) -> Response {
+    let mut builder = http::Response::builder()
+        .status(response.status())
+        .version(response.version());
+    if let Some(headers) = builder.headers_mut() {
+        *headers = response.headers().clone();
+    }
+    let body = Body::wrap_stream(response.bytes_stream().map(move |chunk| {
+        let _permit = &permit;
+        chunk
+    }));
+    // The status and headers are copied from a valid response, so this will not fail.
+    builder
+        .body(body)
+        .map(Response::from)
+        .expect("status and headers are valid")
+}
+
+/// Encode `items` as an SSZ list without first collecting them into a `Vec`.
+fn ssz_list_bytes<T: Encode>(items: &[T]) -> Vec<u8> {
+    let item_len = if T::is_ssz_fixed_len() {
+        T::ssz_fixed_len()
+    } else {
+        ssz::BYTES_PER_LENGTH_OFFSET
+    };
+    let mut bytes = Vec::with_capacity(items.len() * item_len);
+    let mut encoder = ssz::SszEncoder::container(&mut bytes, items.len() * item_len);
+    for item in items {
+        encoder.append(item);
+    }
+    encoder.finalize();
+    bytes
+}
+
+/// Returns a low-cardinality label describing the endpoint requested by `url`, for use in metrics.
+///
+/// This is the two path segments following the `/eth/vX` prefix (or the first two segments of a
+/// `/lighthouse` path), which is enough to distinguish the endpoint without including any path
+/// parameters. Duties requests additionally include the duty type.
+fn endpoint_category(url: &Url) -> String {
+    let mut segments = url
+        .path_segments()
+        .into_iter()
+        .flatten()
+        .skip_while(|segment| *segment != "eth" && *segment != "lighthouse");
+
+    let mut category = match segments.next() {
+        Some("eth") => {
+            // Skip the version.
+            segments.next();
+            segments.by_ref().take(2).collect::<Vec<_>>()
+        }
+        Some("lighthouse") => {
+            let mut category = vec!["lighthouse"];
+            category.extend(segments.next());
+            category
+        }
+        _ => vec![],
+    };
+
+    if category.as_slice() == ["validator", "duties"] {
+        category.extend(segments.next());
+    }
+
+    if category.is_empty() {
+        "other".to_string()
+    } else {
+        category.join("/")
     }
 }
 
@@ -2644,3 +2912,172 @@ pub async fn ok_or_error(response: Response) -> Result<Response, Error> {
         Err(Error::StatusCode(status))
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Mutex;
+    use warp::Filter;
+
+    /// Serve the sync duties endpoint, rejecting SSZ request bodies with `ssz_status`. Returns the
+    /// server URL and whether each request received was SSZ.
+    fn serve_sync_duties_without_ssz(
+        ssz_status: warp::http::StatusCode,
+    ) -> (SensitiveUrl, Arc<Mutex<Vec<bool>>>) {
+        let requests = Arc::new(Mutex::new(vec![]));
+        let requests_filter = requests.clone();
+        let route = warp::post()
+            .and(warp::path!(
+                "eth" / "v1" / "validator" / "duties" / "sync" / u64
+            ))
+            .and(warp::header::optional::<String>(CONTENT_TYPE_HEADER))
+            .map(move |_epoch, content_type: Option<String>| {
+                let is_ssz = content_type.as_deref() == Some(SSZ_CONTENT_TYPE_HEADER);
+                requests_filter.lock().unwrap().push(is_ssz);
+                if is_ssz {
+                    warp::reply::with_status(
+                        warp::reply::json(&serde_json::json!({
+                            "code": ssz_status.as_u16(),
+                            "message": "unsupported content type",
+                        })),
+                        ssz_status,
+                    )
+                } else {
+                    warp::reply::with_status(
+                        warp::reply::json(&serde_json::json!({ "data": [] })),
+                        warp::http::StatusCode::OK,
+                    )
+                }
+            });
+        let (addr, server) = warp::serve(route).bind_ephemeral(([127, 0, 0, 1], 0));
+        tokio::spawn(server);
+        let url = SensitiveUrl::parse(&format!("http://{addr}")).unwrap();
+        (url, requests)
+    }
+
+    fn category(url: &str) -> String {
+        endpoint_category(&Url::parse(url).unwrap())
+    }
+
+    #[test]
+    fn endpoint_categories() {
+        assert_eq!(
+            category("http://localhost:5052/eth/v1/validator/duties/attester/10"),
+            "validator/duties/attester"
+        );
+        assert_eq!(
+            category("http://localhost:5052/eth/v1/beacon/states/head/validators?id=1"),
+            "beacon/states"
+        );
+        assert_eq!(
+            category("http://localhost:5052/prefix/eth/v2/beacon/blocks"),
+            "beacon/blocks"
+        );
+        assert_eq!(
+            category("http://localhost:5052/lighthouse/peers/connected"),
+            "lighthouse/peers"
+        );
+        assert_eq!(category("http://localhost:5052/"), "other");
+    }
+
+    #[tokio::test]
+    async fn ssz_request_bodies_fall_back_to_json_on_unsupported_media_type() {
+        let (url, requests) =
+            serve_sync_duties_without_ssz(warp::http::StatusCode::UNSUPPORTED_MEDIA_TYPE);
+        let client = BeaconNodeHttpClient::new(url, Timeouts::set_all(Duration::from_secs(5)))
+            .with_ssz_request_bodies();
+
+        // The SSZ request is rejected and retried as JSON.
+        let duties = client
+            .post_validator_duties_sync(Epoch::new(1), &[0, 1])
+            .await
+            .unwrap();
+        assert!(duties.data.is_empty());
+        assert_eq!(*requests.lock().unwrap(), vec![true, false]);
+
+        // Later requests to the same server go straight to JSON.
+        client
+            .post_validator_duties_sync(Epoch::new(2), &[0, 1])
+            .await
+            .unwrap();
+        assert_eq!(*requests.lock().unwrap(), vec![true, false, false]);
+    }
+
+    #[tokio::test]
+    async fn ssz_request_bodies_kept_on_bad_request() {
+        let (url, requests) = serve_sync_duties_without_ssz(warp::http::StatusCode::BAD_REQUEST);
+        let client = BeaconNodeHttpClient::new(url, Timeouts::set_all(Duration::from_secs(5)))
+            .with_ssz_request_bodies();
+
+        // The error is returned without retrying as JSON.
+        let error = client
+            .post_validator_duties_sync(Epoch::new(1), &[0, 1])
+            .await
+            .unwrap_err();
+        assert_eq!(error.status(), Some(StatusCode::BAD_REQUEST));
+        assert_eq!(*requests.lock().unwrap(), vec![true]);
+
+        // SSZ remains enabled for later requests.
+        client
+            .post_validator_duties_sync(Epoch::new(2), &[0, 1])
+            .await
+            .unwrap_err();
+        assert_eq!(*requests.lock().unwrap(), vec![true, true]);
+    }
+
+    #[tokio::test]
+    async fn ssz_request_bodies_are_opt_in() {
+        let (url, requests) =
+            serve_sync_duties_without_ssz(warp::http::StatusCode::UNSUPPORTED_MEDIA_TYPE);
+        let client = BeaconNodeHttpClient::new(url, Timeouts::set_all(Duration::from_secs(5)));
+
+        client
+            .post_validator_duties_sync(Epoch::new(1), &[0, 1])
+            .await
+            .unwrap();
+        assert_eq!(*requests.lock().unwrap(), vec![false]);
+    }
+
+    #[tokio::test]
+    async fn concurrency_limit_held_until_body_read() {
+        // The slow route sends its headers immediately, but takes a while to send its body.
+        let slow = warp::path("slow").map(|| {
+            let chunks = futures::stream::iter(["slow", " body"]).then(|chunk| async move {
+                tokio::time::sleep(Duration::from_millis(100)).await;
+                Ok::<_, std::convert::Infallible>(chunk)
+            });
+            warp::http::Response::new(warp::hyper::Body::wrap_stream(chunks))
+        });
+        let fast = warp::path("fast").map(|| "fast body");
+        let (addr, server) = warp::serve(slow.or(fast)).bind_ephemeral(([127, 0, 0, 1], 0));
+        tokio::spawn(server);
+
+        let url = SensitiveUrl::parse(&format!("http://{addr}")).unwrap();
+        let client = BeaconNodeHttpClient::new(url, Timeouts::set_all(Duration::from_secs(5)))
+            .with_max_concurrent_requests(1);
+        let slow_url = client.server.full.join("slow").unwrap();
+        let fast_url = client.server.full.join("fast").unwrap();
+
+        let slow_response = client.get_response(slow_url, |b| b).await.unwrap();
+
+        // The slow response's headers have arrived but its body has not been read, so the next
+        // request must wait for it.
+        let fast_client = client.clone();
+        let mut fast_request =
+            tokio::spawn(async move { fast_client.get_response(fast_url, |b| b).await });
+        assert!(
+            tokio::time::timeout(Duration::from_millis(50), &mut fast_request)
+                .await
+                .is_err(),
+            "request should be queued behind the unread body"
+        );
+
+        assert_eq!(slow_response.bytes().await.unwrap(), "slow body");
+        let fast_response = tokio::time::timeout(Duration::from_secs(1), fast_request)
+            .await
+            .expect("request should proceed once the body is read")
+            .unwrap()
+            .unwrap();
+        assert_eq!(fast_response.bytes().await.unwrap(), "fast body");
+    }
+}
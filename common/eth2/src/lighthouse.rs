@@ -1,44 +1,160 @@
 //! This module contains endpoints that are non-standard and only available on Lighthouse servers.
 
+mod attestation_export;
+mod attestation_inclusion;
 mod attestation_performance;
 pub mod attestation_rewards;
+mod bellatrix_readiness;
 mod block_packing_efficiency;
 mod block_rewards;
+mod block_timing;
+mod block_value;
+mod light_client_store;
 mod standard_block_rewards;
 mod sync_committee_rewards;
+mod ui;
 
 use crate::{
+    mixin::RequestAccept,
+    ok_or_error,
     types::{
-        DepositTreeSnapshot, Epoch, EthSpec, FinalizedExecutionBlock, GenericResponse, ValidatorId,
+        Accept, BlindedBeaconBlock, BlobSidecar, BlockId, ChainSpec, DataColumnSidecar, Deposit,
+        DepositTreeSnapshot, Epoch, EthSpec, ExecutionOptimisticFinalizedResponse,
+        FinalizedExecutionBlock, ForkVersionedResponse, GenericResponse, Graffiti, IdentityData,
+        JsonProduceBlockV3Response, PendingBalanceDeposit, PendingConsolidation,
+        PendingPartialWithdrawal, ProduceBlockV3Metadata, PublicKeyBytes, SignatureBytes,
+        SignedBeaconBlock, SkipRandaoVerification, StateId, SubnetId, ValidatorId,
     },
-    BeaconNodeHttpClient, DepositData, Error, Eth1Data, Hash256, Slot,
+    BeaconNodeHttpClient, DepositData, Error, Eth1Data, Hash256, Slot, StatusCode, Url, V1,
 };
+use lighthouse_network::Multiaddr;
 use proto_array::core::ProtoArray;
 use serde::{Deserialize, Serialize};
+use serde_utils::quoted_u64::Quoted;
 use ssz::four_byte_option_impl;
 use ssz_derive::{Decode, Encode};
-use store::{AnchorInfo, BlobInfo, Split, StoreConfig};
+use std::collections::BTreeMap;
+use std::sync::Arc;
+use store::{AnchorInfo, BlobInfo, EpochSummary, Split, StoreConfig};
 
+pub use attestation_export::{
+    AttestationExportQuery, ExportedAttestation, SSZ_RECORD_LENGTH_PREFIX_BYTES,
+};
+pub use attestation_inclusion::{AttestationInclusion, AttestationInclusionRecord};
 pub use attestation_performance::{
     AttestationPerformance, AttestationPerformanceQuery, AttestationPerformanceStatistics,
 };
 pub use attestation_rewards::StandardAttestationRewards;
+pub use bellatrix_readiness::{BellatrixReadiness, MergeConfig};
 pub use block_packing_efficiency::{
     BlockPackingEfficiency, BlockPackingEfficiencyQuery, ProposerInfo, UniqueAttestation,
 };
-pub use block_rewards::{AttestationRewards, BlockReward, BlockRewardMeta, BlockRewardsQuery};
-pub use lighthouse_network::{types::SyncState, PeerInfo};
+pub use block_rewards::{
+    AttestationRewards, BlockReward, BlockRewardMeta, BlockRewardSources, BlockRewardsQuery,
+    PeerRewardAttribution,
+};
+pub use block_timing::{
+    BlockTimingRecord, BlockTimingSource, BlockTimingsQuery, ProposerBlockTimings,
+};
+pub use block_value::{
+    BlockValueRecord, BuilderBidRecord, LocalPayloadRecord, PayloadChoiceReason, PayloadSource,
+};
+pub use light_client_store::{LightClientStoreImport, LightClientStoreSnapshot};
+pub use lighthouse_network::{
+    types::{ReachabilityStatus, SyncState, Transport, TransportReachability},
+    ClientDiversity, ClientPeerCounts, ForkReadinessSummary, GoodbyeCounts, PeerDecision,
+    PeerDecisionKind, PeerDecisions, PeerInfo,
+};
 pub use standard_block_rewards::StandardBlockReward;
 pub use sync_committee_rewards::SyncCommitteeReward;
+pub use system_health::{NatState, SystemHealthBN};
+pub use ui::{
+    ValidatorCountResponse, ValidatorInfo, ValidatorInfoRequestData, ValidatorInfoResponse,
+    ValidatorInfoValues, ValidatorMetrics, ValidatorMetricsRequestData, ValidatorMetricsResponse,
+};
 
 // Define "legacy" implementations of `Option<T>` which use four bytes for encoding the union
 // selector.
 four_byte_option_impl!(four_byte_option_u64, u64);
 four_byte_option_impl!(four_byte_option_hash256, Hash256);
 
+/// Query parameters for the `/lighthouse/validator/blocks/{slot}` endpoint, in addition to those
+/// of the standard block production endpoints.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct ProduceBlockOnParentQuery {
+    /// The root of the block to build upon.
+    pub parent_root: Hash256,
+}
+
+/// Request body for the `/lighthouse/beacon/blocks/batch` endpoint.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum BlocksBatchRequest {
+    /// The blocks with the given roots, in the order given. Unknown roots are omitted.
+    Roots { block_roots: Vec<Hash256> },
+    /// The canonical blocks at slots `start_slot..start_slot + count`. Skipped slots are omitted.
+    Range {
+        start_slot: Slot,
+        #[serde(with = "serde_utils::quoted_u64")]
+        count: u64,
+    },
+}
+
+/// Decode the SSZ response of the `/lighthouse/beacon/blocks/batch` endpoint.
+///
+/// The response is an SSZ list of blocks. Each block's fork is determined from its slot, so the
+/// list may contain blocks from several forks.
+pub fn decode_blocks_batch_ssz<E: EthSpec>(
+    bytes: &[u8],
+    spec: &ChainSpec,
+) -> Result<Vec<SignedBeaconBlock<E>>, ssz::DecodeError> {
+    if bytes.is_empty() {
+        return Ok(vec![]);
+    }
+
+    let read_offset = |index: usize| {
+        let start = index * ssz::BYTES_PER_LENGTH_OFFSET;
+        bytes
+            .get(start..start + ssz::BYTES_PER_LENGTH_OFFSET)
+            .and_then(|offset| offset.try_into().ok())
+            .map(|offset| u32::from_le_bytes(offset) as usize)
+            .ok_or(ssz::DecodeError::InvalidByteLength {
+                len: bytes.len(),
+                expected: start + ssz::BYTES_PER_LENGTH_OFFSET,
+            })
+    };
+
+    let first_offset = read_offset(0)?;
+    if first_offset == 0 || first_offset % ssz::BYTES_PER_LENGTH_OFFSET != 0 {
+        return Err(ssz::DecodeError::OffsetIntoFixedPortion(first_offset));
+    }
+    let mut offsets = (0..first_offset / ssz::BYTES_PER_LENGTH_OFFSET)
+        .map(read_offset)
+        .collect::<Result<Vec<_>, _>>()?;
+    offsets.push(bytes.len());
+
+    offsets
+        .windows(2)
+        .map(|window| {
+            let block_bytes = bytes
+                .get(window[0]..window[1])
+                .ok_or(ssz::DecodeError::OffsetOutOfBounds(window[1]))?;
+            SignedBeaconBlock::from_ssz_bytes(block_bytes, spec)
+        })
+        .collect()
+}
+
+/// Query parameters for the `/lighthouse/fork_choice/reorgs` endpoint.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct ReorgsQuery {
+    /// The number of epochs to summarise, up to and including the current epoch.
+    ///
+    /// Defaults to all of the head changes retained by the beacon node.
+    pub epochs: Option<u64>,
+}
+
 /// Information returned by `peers` and `connected_peers`.
-// TODO: this should be deserializable..
-#[derive(Debug, Clone, Serialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(bound = "E: EthSpec")]
 pub struct Peer<E: EthSpec> {
     /// The Peer's ID
@@ -88,6 +204,211 @@ pub struct ValidatorInclusionData {
     pub is_previous_epoch_head_attester: bool,
 }
 
+/// Query parameters for the `/lighthouse/states/{state_id}/pending_*` endpoints.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub struct PendingQueueQuery {
+    /// The position of the first item to return. Defaults to the front of the queue.
+    pub offset: Option<u64>,
+    /// The maximum number of items to return. Defaults to the largest page the server allows.
+    pub limit: Option<u64>,
+}
+
+/// A page of one of the pending deposit, partial withdrawal or consolidation queues of a state.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(bound = "T: Serialize + serde::de::DeserializeOwned")]
+pub struct PendingQueuePage<T: Serialize + serde::de::DeserializeOwned> {
+    /// The number of items in the whole queue.
+    pub queue_length: u64,
+    /// The sum of the amounts of all items in the queue, for queues whose items have amounts.
+    pub queue_amount_gwei: Option<u64>,
+    pub items: Vec<PendingQueueItem<T>>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(bound = "T: Serialize + serde::de::DeserializeOwned")]
+pub struct PendingQueueItem<T: Serialize + serde::de::DeserializeOwned> {
+    /// The position of the item in the queue, counting from zero at the front.
+    pub position: u64,
+    #[serde(flatten)]
+    pub item: T,
+}
+
+/// A projection of a validator's next withdrawal, computed from the head state.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct WithdrawalProjection {
+    pub validator_index: u64,
+    /// The slot of the head state from which the projection was computed.
+    pub head_slot: Slot,
+    /// The number of validators which the withdrawal sweep will visit before this validator.
+    pub sweep_position: u64,
+    pub is_fully_withdrawable: bool,
+    pub is_partially_withdrawable: bool,
+    /// The amount of the next withdrawal, or zero if the validator has nothing to withdraw.
+    pub expected_amount_gwei: u64,
+    /// The estimated slot of the next withdrawal, assuming that every slot has a block.
+    ///
+    /// `None` if the validator has nothing to withdraw.
+    pub estimated_slot: Option<Slot>,
+    /// The UNIX timestamp of the start of `estimated_slot`, in seconds.
+    pub estimated_timestamp: Option<u64>,
+}
+
+/// Summary of the operations currently held in the operation pool.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct OperationPoolInfo {
+    /// Total number of attestations, including those for the same data.
+    pub num_attestations: usize,
+    /// Number of attestations with a single attester.
+    pub num_unaggregated_attestations: usize,
+    /// Number of attestations with more than one attester.
+    pub num_aggregated_attestations: usize,
+    pub num_sync_contributions: usize,
+    pub num_attester_slashings: usize,
+    pub num_proposer_slashings: usize,
+    pub num_voluntary_exits: usize,
+    pub num_bls_to_execution_changes: usize,
+    /// Breakdown of the attestations in the pool, ordered by slot and committee index.
+    pub attestations: Vec<OperationPoolCommitteeInfo>,
+}
+
+/// The identity of a beacon node, as returned by `/eth/v1/node/identity`, along with the
+/// transport and PeerDAS details which are useful when debugging connectivity.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct LighthouseIdentityData {
+    #[serde(flatten)]
+    pub identity: IdentityData,
+    /// The QUIC multiaddrs advertised in the node's ENR.
+    pub quic_addresses: Vec<Multiaddr>,
+    /// The custody group count advertised in the node's ENR, if any.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub custody_group_count: Option<Quoted<u64>>,
+    /// The fork digest of the fork at the current slot.
+    #[serde(with = "serde_utils::bytes_4_hex")]
+    pub fork_digest: [u8; 4],
+}
+
+/// The long-lived attestation subnets of a beacon node.
+///
+/// Long-lived subnets are a function of the node ID alone, so they do not expire while the node ID
+/// is unchanged.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct LongLivedSubnets {
+    /// The node ID from which the subnets are computed.
+    pub node_id: Hash256,
+    /// The subnets computed from the node ID, per the consensus specs.
+    pub computed_subnets: Vec<SubnetId>,
+    /// The subnets which the node subscribes to and advertises in its ENR.
+    pub subscribed_subnets: Vec<SubnetId>,
+    /// How `subscribed_subnets` were determined.
+    pub source: LongLivedSubnetsSource,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum LongLivedSubnetsSource {
+    /// Computed from the node ID.
+    Computed,
+    /// Overridden by `--long-lived-subnets-override`.
+    Override,
+    /// Subscribed to all subnets by `--subscribe-all-subnets`.
+    All,
+}
+
+/// A summary of the re-orgs observed by a beacon node.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ReorgSummary {
+    /// The first slot of the summarised epochs.
+    pub start_slot: Slot,
+    /// The number of times the canonical head changed, including re-orgs.
+    pub head_changes: u64,
+    /// The number of head changes which were re-orgs.
+    pub reorg_count: u64,
+    /// The number of re-orgs at each depth, ordered by depth.
+    pub depth_distribution: Vec<ReorgDepthCount>,
+    /// The re-orgs, in the order they were observed.
+    pub reorgs: Vec<ReorgInfo>,
+}
+
+/// The number of re-orgs of a given depth.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct ReorgDepthCount {
+    pub depth: u64,
+    pub count: u64,
+}
+
+/// A change of the canonical head to a block which does not descend from the previous head.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct ReorgInfo {
+    /// The number of slots between the previous head and the common ancestor of both heads.
+    pub depth: u64,
+    pub old_head_block: Hash256,
+    pub old_head_slot: Slot,
+    pub new_head_block: Hash256,
+    pub new_head_slot: Slot,
+    pub new_head_parent: Hash256,
+}
+
+/// The state of a single beacon processor work queue.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct BeaconProcessorQueueInfo {
+    /// Number of work items currently queued.
+    pub length: usize,
+    /// Maximum number of work items the queue will hold.
+    pub capacity: usize,
+    /// Number of work items dropped because the queue was full.
+    pub dropped: u64,
+}
+
+/// Attestations held in the operation pool for a single slot and committee.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct OperationPoolCommitteeInfo {
+    pub slot: Slot,
+    pub committee_index: u64,
+    pub num_unaggregated: usize,
+    pub num_aggregated: usize,
+    /// Number of attesters in the largest attestation for this committee.
+    pub max_attesters: usize,
+}
+
+/// The kind of an operation awaiting inclusion in a block.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PendingOperationKind {
+    ProposerSlashing,
+    AttesterSlashing,
+    VoluntaryExit,
+    BlsToExecutionChange,
+}
+
+/// The inclusion history of an exit, BLS to execution change or slashing in the operation pool.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PendingOperationInclusion {
+    pub kind: PendingOperationKind,
+    /// The exiting, changing or slashed validator. Not set for attester slashings.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub validator_index: Option<u64>,
+    /// The tree hash root of an attester slashing.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub root: Option<Hash256>,
+    /// The slot of the first block at which the operation was observed to be pending.
+    pub first_seen_slot: Slot,
+    /// Number of blocks which omitted the operation despite having room for it.
+    pub blocks_omitted: u64,
+    /// Number of those blocks proposed by validators monitored by this node.
+    pub local_blocks_omitted: u64,
+    pub suspected_censorship: bool,
+}
+
+/// Report of the valid exits, BLS to execution changes and slashings awaiting inclusion.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CensorshipReport {
+    pub current_slot: Slot,
+    /// Number of blocks which must omit an operation before censorship is suspected.
+    pub omitted_blocks_threshold: u64,
+    /// Pending operations, oldest first.
+    pub pending: Vec<PendingOperationInclusion>,
+}
+
 #[cfg(target_os = "linux")]
 use {
     psutil::cpu::os::linux::CpuTimesExt, psutil::memory::os::linux::VirtualMemoryExt,
@@ -101,6 +422,9 @@ pub struct Health {
     pub system: SystemHealth,
     #[serde(flatten)]
     pub process: ProcessHealth,
+    /// Reasons that the node is degraded, only reported by beacon nodes.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub degradation: Option<HealthReport>,
 }
 
 /// System related health.
@@ -293,10 +617,96 @@ impl Health {
         Ok(Self {
             process: ProcessHealth::observe()?,
             system: SystemHealth::observe()?,
+            degradation: None,
         })
     }
 }
 
+/// The overall health of a beacon node, determined by its most severe degradation.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum HealthStatus {
+    Healthy,
+    Degraded,
+    Unhealthy,
+}
+
+/// The severity of a single reason that a beacon node is degraded.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum HealthSeverity {
+    /// The node is functional but may perform poorly, e.g. miss some attestations.
+    Warning,
+    /// The node is unable to perform its duties.
+    Critical,
+}
+
+/// A reason that a beacon node is degraded.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "reason", rename_all = "snake_case")]
+pub enum DegradationReason {
+    /// The execution layer is offline or returning errors.
+    ExecutionLayerOffline,
+    /// The node is syncing.
+    Syncing,
+    /// The head block has not been fully verified by the execution layer.
+    OptimisticHead,
+    /// The node is connected to fewer peers than it requires to function reliably.
+    LowPeerCount {
+        connected_peers: usize,
+        target_peers: usize,
+    },
+    /// The head block is older than expected.
+    StaleHead {
+        head_slot: Slot,
+        current_slot: Slot,
+        slots_behind: u64,
+    },
+    /// The disk holding the database is nearly full.
+    DiskNearlyFull {
+        disk_bytes_free: u64,
+        disk_bytes_total: u64,
+    },
+    /// The head block was received before the start of its slot according to the local clock,
+    /// indicating that the local clock is running behind.
+    ClockSkew { skew_millis: u64 },
+    /// The storage watchdog has taken measures to preserve disk space, e.g. `prune_blobs` or
+    /// `read_only`.
+    StoragePressure { pressure: String },
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Degradation {
+    #[serde(flatten)]
+    pub reason: DegradationReason,
+    pub severity: HealthSeverity,
+}
+
+/// A machine-readable summary of the reasons that a beacon node is degraded.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct HealthReport {
+    pub status: HealthStatus,
+    pub reasons: Vec<Degradation>,
+}
+
+impl HealthReport {
+    pub fn new(reasons: Vec<Degradation>) -> Self {
+        let status = match reasons.iter().map(|degradation| degradation.severity).max() {
+            None => HealthStatus::Healthy,
+            Some(HealthSeverity::Warning) => HealthStatus::Degraded,
+            Some(HealthSeverity::Critical) => HealthStatus::Unhealthy,
+        };
+        Self { status, reasons }
+    }
+
+    /// Returns `true` if any of the reasons match `predicate`.
+    pub fn has_reason(&self, predicate: impl Fn(&DegradationReason) -> bool) -> bool {
+        self.reasons
+            .iter()
+            .any(|degradation| predicate(&degradation.reason))
+    }
+}
+
 /// Indicates how up-to-date the Eth1 caches are.
 #[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
 pub struct Eth1SyncStatusData {
@@ -321,6 +731,15 @@ pub struct DepositLog {
     pub signature_is_valid: bool,
 }
 
+/// A pending deposit with a proof of inclusion in the deposit tree, as of `deposit_count`.
+#[derive(Debug, PartialEq, Clone, Serialize, Deserialize)]
+pub struct DepositProof {
+    pub deposit_root: Hash256,
+    #[serde(with = "serde_utils::quoted_u64")]
+    pub deposit_count: u64,
+    pub deposit: Deposit,
+}
+
 /// A block of the eth1 chain.
 #[derive(Debug, PartialEq, Clone, Serialize, Deserialize, Encode, Decode)]
 pub struct Eth1Block {
@@ -363,6 +782,12 @@ pub struct DatabaseInfo {
     pub split: Split,
     pub anchor: AnchorInfo,
     pub blob_info: BlobInfo,
+    /// The first slot of the finalized epoch according to fork choice.
+    ///
+    /// The split slot trails this value until the next migration to the freezer completes.
+    pub finalized_slot: Slot,
+    /// The number of slots between the split point and the finalized slot.
+    pub migration_lag: u64,
 }
 
 impl BeaconNodeHttpClient {
@@ -378,6 +803,32 @@ impl BeaconNodeHttpClient {
         self.get(path).await
     }
 
+    /// `GET eth/v1/node/health`
+    ///
+    /// Returns the status code along with the reasons that the node is degraded, which are only
+    /// included in the response body by Lighthouse.
+    pub async fn get_node_health_report(
+        &self,
+    ) -> Result<(StatusCode, GenericResponse<HealthReport>), Error> {
+        let mut path = self.eth_path(V1)?;
+
+        path.path_segments_mut()
+            .map_err(|()| Error::InvalidUrl(self.server.clone()))?
+            .push("node")
+            .push("health");
+
+        let response = self.send(self.client.get(path)).await?;
+        let status = response.status();
+        if status == StatusCode::OK
+            || status == StatusCode::PARTIAL_CONTENT
+            || status == StatusCode::SERVICE_UNAVAILABLE
+        {
+            Ok((status, response.json().await?))
+        } else {
+            Err(Error::StatusCode(status))
+        }
+    }
+
     /// `GET lighthouse/syncing`
     pub async fn get_lighthouse_syncing(&self) -> Result<GenericResponse<SyncState>, Error> {
         let mut path = self.server.full.clone();
@@ -390,13 +841,382 @@ impl BeaconNodeHttpClient {
         self.get(path).await
     }
 
-    /*
-     * Note:
-     *
-     * The `lighthouse/peers` endpoints do not have functions here. We are yet to implement
-     * `Deserialize` on the `PeerInfo` struct since it contains use of `Instant`. This could be
-     * fairly simply achieved, if desired.
-     */
+    /// `GET lighthouse/ui/health`
+    pub async fn get_lighthouse_ui_health(&self) -> Result<GenericResponse<SystemHealthBN>, Error> {
+        let mut path = self.server.full.clone();
+
+        path.path_segments_mut()
+            .map_err(|()| Error::InvalidUrl(self.server.clone()))?
+            .push("lighthouse")
+            .push("ui")
+            .push("health");
+
+        self.get(path).await
+    }
+
+    /// `GET lighthouse/ui/validator_count`
+    pub async fn get_lighthouse_ui_validator_count(
+        &self,
+    ) -> Result<GenericResponse<ValidatorCountResponse>, Error> {
+        let mut path = self.server.full.clone();
+
+        path.path_segments_mut()
+            .map_err(|()| Error::InvalidUrl(self.server.clone()))?
+            .push("lighthouse")
+            .push("ui")
+            .push("validator_count");
+
+        self.get(path).await
+    }
+
+    /// `POST lighthouse/ui/validator_metrics`
+    pub async fn post_lighthouse_ui_validator_metrics(
+        &self,
+        request: &ValidatorMetricsRequestData,
+    ) -> Result<GenericResponse<ValidatorMetricsResponse>, Error> {
+        let mut path = self.server.full.clone();
+
+        path.path_segments_mut()
+            .map_err(|()| Error::InvalidUrl(self.server.clone()))?
+            .push("lighthouse")
+            .push("ui")
+            .push("validator_metrics");
+
+        self.post_with_response(path, request).await
+    }
+
+    /// `POST lighthouse/ui/validator_info`
+    pub async fn post_lighthouse_ui_validator_info(
+        &self,
+        request: &ValidatorInfoRequestData,
+    ) -> Result<GenericResponse<ValidatorInfoResponse>, Error> {
+        let mut path = self.server.full.clone();
+
+        path.path_segments_mut()
+            .map_err(|()| Error::InvalidUrl(self.server.clone()))?
+            .push("lighthouse")
+            .push("ui")
+            .push("validator_info");
+
+        self.post_with_response(path, request).await
+    }
+
+    /// `GET lighthouse/merge_readiness`
+    pub async fn get_lighthouse_merge_readiness(
+        &self,
+    ) -> Result<GenericResponse<BellatrixReadiness>, Error> {
+        let mut path = self.server.full.clone();
+
+        path.path_segments_mut()
+            .map_err(|()| Error::InvalidUrl(self.server.clone()))?
+            .push("lighthouse")
+            .push("merge_readiness");
+
+        self.get(path).await
+    }
+
+    /// `GET lighthouse/light_client/store`
+    pub async fn get_lighthouse_light_client_store<E: EthSpec>(
+        &self,
+    ) -> Result<LightClientStoreSnapshot<E>, Error> {
+        let mut path = self.server.full.clone();
+
+        path.path_segments_mut()
+            .map_err(|()| Error::InvalidUrl(self.server.clone()))?
+            .push("lighthouse")
+            .push("light_client")
+            .push("store");
+
+        self.get(path).await
+    }
+
+    /// `GET lighthouse/nat`
+    pub async fn get_lighthouse_nat(&self) -> Result<GenericResponse<NatState>, Error> {
+        let mut path = self.server.full.clone();
+
+        path.path_segments_mut()
+            .map_err(|()| Error::InvalidUrl(self.server.clone()))?
+            .push("lighthouse")
+            .push("nat");
+
+        self.get(path).await
+    }
+
+    /// `GET lighthouse/peers`
+    pub async fn get_lighthouse_peers<E: EthSpec>(&self) -> Result<Vec<Peer<E>>, Error> {
+        let mut path = self.server.full.clone();
+
+        path.path_segments_mut()
+            .map_err(|()| Error::InvalidUrl(self.server.clone()))?
+            .push("lighthouse")
+            .push("peers");
+
+        self.get(path).await
+    }
+
+    /// `GET lighthouse/peers/connected`
+    pub async fn get_lighthouse_peers_connected<E: EthSpec>(&self) -> Result<Vec<Peer<E>>, Error> {
+        let mut path = self.server.full.clone();
+
+        path.path_segments_mut()
+            .map_err(|()| Error::InvalidUrl(self.server.clone()))?
+            .push("lighthouse")
+            .push("peers")
+            .push("connected");
+
+        self.get(path).await
+    }
+
+    /// `POST lighthouse/peers/{peer_id}/protect`
+    pub async fn post_lighthouse_peers_protect(&self, peer_id: &str) -> Result<(), Error> {
+        let mut path = self.server.full.clone();
+
+        path.path_segments_mut()
+            .map_err(|()| Error::InvalidUrl(self.server.clone()))?
+            .push("lighthouse")
+            .push("peers")
+            .push(peer_id)
+            .push("protect");
+
+        self.post(path, &()).await
+    }
+
+    /// `GET lighthouse/identity`
+    pub async fn get_lighthouse_identity(
+        &self,
+    ) -> Result<GenericResponse<LighthouseIdentityData>, Error> {
+        let mut path = self.server.full.clone();
+
+        path.path_segments_mut()
+            .map_err(|()| Error::InvalidUrl(self.server.clone()))?
+            .push("lighthouse")
+            .push("identity");
+
+        self.get(path).await
+    }
+
+    /// `GET lighthouse/peers/decisions`
+    pub async fn get_lighthouse_peers_decisions(
+        &self,
+    ) -> Result<GenericResponse<PeerDecisions>, Error> {
+        let mut path = self.server.full.clone();
+
+        path.path_segments_mut()
+            .map_err(|()| Error::InvalidUrl(self.server.clone()))?
+            .push("lighthouse")
+            .push("peers")
+            .push("decisions");
+
+        self.get(path).await
+    }
+
+    /// `GET lighthouse/network/client_diversity`
+    pub async fn get_lighthouse_network_client_diversity(
+        &self,
+    ) -> Result<GenericResponse<ClientDiversity>, Error> {
+        let mut path = self.server.full.clone();
+
+        path.path_segments_mut()
+            .map_err(|()| Error::InvalidUrl(self.server.clone()))?
+            .push("lighthouse")
+            .push("network")
+            .push("client_diversity");
+
+        self.get(path).await
+    }
+
+    /// `GET lighthouse/network/fork_readiness`
+    pub async fn get_lighthouse_network_fork_readiness(
+        &self,
+    ) -> Result<GenericResponse<ForkReadinessSummary>, Error> {
+        let mut path = self.server.full.clone();
+
+        path.path_segments_mut()
+            .map_err(|()| Error::InvalidUrl(self.server.clone()))?
+            .push("lighthouse")
+            .push("network")
+            .push("fork_readiness");
+
+        self.get(path).await
+    }
+
+    /// `GET lighthouse/network/reachability`
+    pub async fn get_lighthouse_network_reachability(
+        &self,
+    ) -> Result<GenericResponse<Vec<TransportReachability>>, Error> {
+        let mut path = self.server.full.clone();
+
+        path.path_segments_mut()
+            .map_err(|()| Error::InvalidUrl(self.server.clone()))?
+            .push("lighthouse")
+            .push("network")
+            .push("reachability");
+
+        self.get(path).await
+    }
+
+    /// `GET lighthouse/network/subnets`
+    pub async fn get_lighthouse_network_subnets(
+        &self,
+    ) -> Result<GenericResponse<LongLivedSubnets>, Error> {
+        let mut path = self.server.full.clone();
+
+        path.path_segments_mut()
+            .map_err(|()| Error::InvalidUrl(self.server.clone()))?
+            .push("lighthouse")
+            .push("network")
+            .push("subnets");
+
+        self.get(path).await
+    }
+
+    /// `GET lighthouse/fork_choice/reorgs?epochs`
+    pub async fn get_lighthouse_fork_choice_reorgs(
+        &self,
+        epochs: Option<u64>,
+    ) -> Result<GenericResponse<ReorgSummary>, Error> {
+        let mut path = self.server.full.clone();
+
+        path.path_segments_mut()
+            .map_err(|()| Error::InvalidUrl(self.server.clone()))?
+            .push("lighthouse")
+            .push("fork_choice")
+            .push("reorgs");
+
+        if let Some(epochs) = epochs {
+            path.query_pairs_mut()
+                .append_pair("epochs", &epochs.to_string());
+        }
+
+        self.get(path).await
+    }
+
+    /// `GET lighthouse/block_value/{slot}`
+    pub async fn get_lighthouse_block_value(
+        &self,
+        slot: Slot,
+    ) -> Result<GenericResponse<BlockValueRecord>, Error> {
+        let mut path = self.server.full.clone();
+
+        path.path_segments_mut()
+            .map_err(|()| Error::InvalidUrl(self.server.clone()))?
+            .push("lighthouse")
+            .push("block_value")
+            .push(&slot.to_string());
+
+        self.get(path).await
+    }
+
+    /// `GET lighthouse/op_pool`
+    pub async fn get_lighthouse_op_pool(
+        &self,
+    ) -> Result<GenericResponse<OperationPoolInfo>, Error> {
+        let mut path = self.server.full.clone();
+
+        path.path_segments_mut()
+            .map_err(|()| Error::InvalidUrl(self.server.clone()))?
+            .push("lighthouse")
+            .push("op_pool");
+
+        self.get(path).await
+    }
+
+    /// `GET lighthouse/op_pool/censorship`
+    pub async fn get_lighthouse_op_pool_censorship(
+        &self,
+    ) -> Result<GenericResponse<CensorshipReport>, Error> {
+        let mut path = self.server.full.clone();
+
+        path.path_segments_mut()
+            .map_err(|()| Error::InvalidUrl(self.server.clone()))?
+            .push("lighthouse")
+            .push("op_pool")
+            .push("censorship");
+
+        self.get(path).await
+    }
+
+    /// `GET lighthouse/beacon_processor`
+    pub async fn get_lighthouse_beacon_processor(
+        &self,
+    ) -> Result<GenericResponse<BTreeMap<String, BeaconProcessorQueueInfo>>, Error> {
+        let mut path = self.server.full.clone();
+
+        path.path_segments_mut()
+            .map_err(|()| Error::InvalidUrl(self.server.clone()))?
+            .push("lighthouse")
+            .push("beacon_processor");
+
+        self.get(path).await
+    }
+
+    /// `POST lighthouse/data_columns`
+    pub async fn post_lighthouse_data_columns<E: EthSpec>(
+        &self,
+        data_columns: &[Arc<DataColumnSidecar<E>>],
+    ) -> Result<(), Error> {
+        let mut path = self.server.full.clone();
+
+        path.path_segments_mut()
+            .map_err(|()| Error::InvalidUrl(self.server.clone()))?
+            .push("lighthouse")
+            .push("data_columns");
+
+        self.post(path, &data_columns).await
+    }
+
+    fn lighthouse_beacon_blocks_batch_path(&self) -> Result<Url, Error> {
+        let mut path = self.server.full.clone();
+
+        path.path_segments_mut()
+            .map_err(|()| Error::InvalidUrl(self.server.clone()))?
+            .push("lighthouse")
+            .push("beacon")
+            .push("blocks")
+            .push("batch");
+
+        Ok(path)
+    }
+
+    /// `POST lighthouse/beacon/blocks/batch`
+    pub async fn post_lighthouse_beacon_blocks_batch<E: EthSpec>(
+        &self,
+        request: &BlocksBatchRequest,
+    ) -> Result<GenericResponse<Vec<ForkVersionedResponse<SignedBeaconBlock<E>>>>, Error> {
+        let path = self.lighthouse_beacon_blocks_batch_path()?;
+
+        self.post_with_response(path, request).await
+    }
+
+    /// `POST lighthouse/beacon/blocks/batch` as SSZ
+    pub async fn post_lighthouse_beacon_blocks_batch_ssz<E: EthSpec>(
+        &self,
+        request: &BlocksBatchRequest,
+        spec: &ChainSpec,
+    ) -> Result<Vec<SignedBeaconBlock<E>>, Error> {
+        let path = self.lighthouse_beacon_blocks_batch_path()?;
+
+        let response = self
+            .send(self.client.post(path).accept(Accept::Ssz).json(request))
+            .await?;
+        let bytes = ok_or_error(response).await?.bytes().await?;
+        decode_blocks_batch_ssz(&bytes, spec).map_err(Error::InvalidSsz)
+    }
+
+    /// `POST lighthouse/blobs`
+    pub async fn post_lighthouse_blobs<E: EthSpec>(
+        &self,
+        blobs: &[Arc<BlobSidecar<E>>],
+    ) -> Result<(), Error> {
+        let mut path = self.server.full.clone();
+
+        path.path_segments_mut()
+            .map_err(|()| Error::InvalidUrl(self.server.clone()))?
+            .push("lighthouse")
+            .push("blobs");
+
+        self.post(path, &blobs).await
+    }
 
     /// `GET lighthouse/proto_array`
     pub async fn get_lighthouse_proto_array(&self) -> Result<GenericResponse<ProtoArray>, Error> {
@@ -445,6 +1265,142 @@ impl BeaconNodeHttpClient {
         self.get(path).await
     }
 
+    /// `GET lighthouse/validators/{pubkey}/withdrawal_projection`
+    pub async fn get_lighthouse_validator_withdrawal_projection(
+        &self,
+        pubkey: &PublicKeyBytes,
+    ) -> Result<GenericResponse<WithdrawalProjection>, Error> {
+        let mut path = self.server.full.clone();
+
+        path.path_segments_mut()
+            .map_err(|()| Error::InvalidUrl(self.server.clone()))?
+            .push("lighthouse")
+            .push("validators")
+            .push(&pubkey.to_string())
+            .push("withdrawal_projection");
+
+        self.get(path).await
+    }
+
+    /// `GET lighthouse/states/{state_id}/pending_deposits`
+    pub async fn get_lighthouse_pending_deposits(
+        &self,
+        state_id: StateId,
+        query: PendingQueueQuery,
+    ) -> Result<ExecutionOptimisticFinalizedResponse<PendingQueuePage<PendingBalanceDeposit>>, Error>
+    {
+        self.get_lighthouse_pending_queue(state_id, "pending_deposits", query)
+            .await
+    }
+
+    /// `GET lighthouse/states/{state_id}/pending_partial_withdrawals`
+    pub async fn get_lighthouse_pending_partial_withdrawals(
+        &self,
+        state_id: StateId,
+        query: PendingQueueQuery,
+    ) -> Result<
+        ExecutionOptimisticFinalizedResponse<PendingQueuePage<PendingPartialWithdrawal>>,
+        Error,
+    > {
+        self.get_lighthouse_pending_queue(state_id, "pending_partial_withdrawals", query)
+            .await
+    }
+
+    /// `GET lighthouse/states/{state_id}/pending_consolidations`
+    pub async fn get_lighthouse_pending_consolidations(
+        &self,
+        state_id: StateId,
+        query: PendingQueueQuery,
+    ) -> Result<ExecutionOptimisticFinalizedResponse<PendingQueuePage<PendingConsolidation>>, Error>
+    {
+        self.get_lighthouse_pending_queue(state_id, "pending_consolidations", query)
+            .await
+    }
+
+    async fn get_lighthouse_pending_queue<T: Serialize + serde::de::DeserializeOwned>(
+        &self,
+        state_id: StateId,
+        queue: &str,
+        query: PendingQueueQuery,
+    ) -> Result<ExecutionOptimisticFinalizedResponse<PendingQueuePage<T>>, Error> {
+        let mut path = self.server.full.clone();
+
+        path.path_segments_mut()
+            .map_err(|()| Error::InvalidUrl(self.server.clone()))?
+            .push("lighthouse")
+            .push("states")
+            .push(&state_id.to_string())
+            .push(queue);
+
+        if let Some(offset) = query.offset {
+            path.query_pairs_mut()
+                .append_pair("offset", &offset.to_string());
+        }
+        if let Some(limit) = query.limit {
+            path.query_pairs_mut()
+                .append_pair("limit", &limit.to_string());
+        }
+
+        self.get(path).await
+    }
+
+    /// `GET lighthouse/analysis/block_timings`
+    pub async fn get_lighthouse_analysis_block_timings(
+        &self,
+        proposer_index: Option<u64>,
+    ) -> Result<Vec<ProposerBlockTimings>, Error> {
+        let mut path = self.server.full.clone();
+
+        path.path_segments_mut()
+            .map_err(|()| Error::InvalidUrl(self.server.clone()))?
+            .push("lighthouse")
+            .push("analysis")
+            .push("block_timings");
+
+        if let Some(proposer_index) = proposer_index {
+            path.query_pairs_mut()
+                .append_pair("proposer_index", &proposer_index.to_string());
+        }
+
+        self.get(path).await
+    }
+
+    /// `GET lighthouse/blocks/{block_id}/attestation_inclusion/{validator_index}`
+    pub async fn get_lighthouse_attestation_inclusion(
+        &self,
+        block_id: BlockId,
+        validator_index: u64,
+    ) -> Result<GenericResponse<AttestationInclusion>, Error> {
+        let mut path = self.server.full.clone();
+
+        path.path_segments_mut()
+            .map_err(|()| Error::InvalidUrl(self.server.clone()))?
+            .push("lighthouse")
+            .push("blocks")
+            .push(&block_id.to_string())
+            .push("attestation_inclusion")
+            .push(&validator_index.to_string());
+
+        self.get(path).await
+    }
+
+    /// `GET lighthouse/epochs/{epoch}/summary`
+    pub async fn get_lighthouse_epoch_summary(
+        &self,
+        epoch: Epoch,
+    ) -> Result<Option<GenericResponse<EpochSummary>>, Error> {
+        let mut path = self.server.full.clone();
+
+        path.path_segments_mut()
+            .map_err(|()| Error::InvalidUrl(self.server.clone()))?
+            .push("lighthouse")
+            .push("epochs")
+            .push(&epoch.to_string())
+            .push("summary");
+
+        self.get_opt(path).await
+    }
+
     /// `GET lighthouse/eth1/syncing`
     pub async fn get_lighthouse_eth1_syncing(
         &self,
@@ -490,6 +1446,25 @@ impl BeaconNodeHttpClient {
         self.get(path).await
     }
 
+    /// `GET lighthouse/eth1/deposit_proof/{deposit_index}`
+    ///
+    /// Returns `Ok(None)` on a 404 error.
+    pub async fn get_lighthouse_eth1_deposit_proof(
+        &self,
+        deposit_index: u64,
+    ) -> Result<Option<GenericResponse<DepositProof>>, Error> {
+        let mut path = self.server.full.clone();
+
+        path.path_segments_mut()
+            .map_err(|()| Error::InvalidUrl(self.server.clone()))?
+            .push("lighthouse")
+            .push("eth1")
+            .push("deposit_proof")
+            .push(&deposit_index.to_string());
+
+        self.get_opt(path).await
+    }
+
     /// `GET lighthouse/staking`
     pub async fn get_lighthouse_staking(&self) -> Result<bool, Error> {
         let mut path = self.server.full.clone();
@@ -528,6 +1503,62 @@ impl BeaconNodeHttpClient {
         self.post_with_response(path, &()).await
     }
 
+    /// `GET lighthouse/validator/blocks/{slot}?parent_root`
+    ///
+    /// This endpoint is disabled by default and is only intended for testing.
+    pub async fn get_lighthouse_validator_blocks<E: EthSpec>(
+        &self,
+        slot: Slot,
+        parent_root: Hash256,
+        randao_reveal: &SignatureBytes,
+        graffiti: Option<&Graffiti>,
+        skip_randao_verification: SkipRandaoVerification,
+        builder_boost_factor: Option<u64>,
+    ) -> Result<(JsonProduceBlockV3Response<E>, ProduceBlockV3Metadata), Error> {
+        let mut path = self.server.full.clone();
+
+        path.path_segments_mut()
+            .map_err(|()| Error::InvalidUrl(self.server.clone()))?
+            .push("lighthouse")
+            .push("validator")
+            .push("blocks")
+            .push(&slot.to_string());
+
+        path.query_pairs_mut()
+            .append_pair("parent_root", &format!("{parent_root:?}"))
+            .append_pair("randao_reveal", &randao_reveal.to_string());
+
+        if let Some(graffiti) = graffiti {
+            path.query_pairs_mut()
+                .append_pair("graffiti", &graffiti.to_string());
+        }
+
+        if skip_randao_verification == SkipRandaoVerification::Yes {
+            path.query_pairs_mut()
+                .append_pair("skip_randao_verification", "");
+        }
+
+        if let Some(builder_boost_factor) = builder_boost_factor {
+            path.query_pairs_mut()
+                .append_pair("builder_boost_factor", &builder_boost_factor.to_string());
+        }
+
+        self.get_validator_blocks_v3_response(path).await
+    }
+
+    /// `POST lighthouse/database/migrate`
+    pub async fn post_lighthouse_database_migrate(&self) -> Result<String, Error> {
+        let mut path = self.server.full.clone();
+
+        path.path_segments_mut()
+            .map_err(|()| Error::InvalidUrl(self.server.clone()))?
+            .push("lighthouse")
+            .push("database")
+            .push("migrate");
+
+        self.post_with_response(path, &()).await
+    }
+
     /*
      Analysis endpoints.
     */
@@ -553,6 +1584,39 @@ impl BeaconNodeHttpClient {
         self.get(path).await
     }
 
+    /// `POST` lighthouse/analysis/block_rewards
+    pub async fn post_lighthouse_analysis_block_rewards<E: EthSpec>(
+        &self,
+        blocks: &[BlindedBeaconBlock<E>],
+    ) -> Result<Vec<BlockReward>, Error> {
+        let mut path = self.server.full.clone();
+
+        path.path_segments_mut()
+            .map_err(|()| Error::InvalidUrl(self.server.clone()))?
+            .push("lighthouse")
+            .push("analysis")
+            .push("block_rewards");
+
+        self.post_with_response(path, &blocks).await
+    }
+
+    /// `GET` lighthouse/analysis/block_reward_sources/{slot}
+    pub async fn get_lighthouse_analysis_block_reward_sources(
+        &self,
+        slot: Slot,
+    ) -> Result<BlockRewardSources, Error> {
+        let mut path = self.server.full.clone();
+
+        path.path_segments_mut()
+            .map_err(|()| Error::InvalidUrl(self.server.clone()))?
+            .push("lighthouse")
+            .push("analysis")
+            .push("block_reward_sources")
+            .push(&slot.to_string());
+
+        self.get(path).await
+    }
+
     /// `GET` lighthouse/analysis/block_packing?start_epoch,end_epoch
     pub async fn get_lighthouse_analysis_block_packing(
         &self,
@@ -574,6 +1638,42 @@ impl BeaconNodeHttpClient {
         self.get(path).await
     }
 
+    /// `GET lighthouse/export/attestations?start_slot,end_slot`
+    ///
+    /// Set `accept` to `Accept::Ssz` to download length-prefixed SSZ rather than NDJSON.
+    pub async fn get_lighthouse_export_attestations(
+        &self,
+        start_slot: Slot,
+        end_slot: Slot,
+        accept: Accept,
+    ) -> Result<Vec<ExportedAttestation>, Error> {
+        let mut path = self.server.full.clone();
+
+        path.path_segments_mut()
+            .map_err(|()| Error::InvalidUrl(self.server.clone()))?
+            .push("lighthouse")
+            .push("export")
+            .push("attestations");
+
+        path.query_pairs_mut()
+            .append_pair("start_slot", &start_slot.to_string())
+            .append_pair("end_slot", &end_slot.to_string());
+
+        let bytes = self
+            .get_response(path, |b| b.accept(accept))
+            .await?
+            .bytes()
+            .await?;
+        match accept {
+            Accept::Ssz => ExportedAttestation::from_ssz_stream(&bytes).map_err(Error::InvalidSsz),
+            Accept::Json | Accept::Any => bytes
+                .split(|byte| *byte == b'\n')
+                .filter(|line| !line.is_empty())
+                .map(|line| serde_json::from_slice(line).map_err(Error::InvalidJson))
+                .collect(),
+        }
+    }
+
     /// `GET` lighthouse/analysis/attestation_performance/{index}?start_epoch,end_epoch
     pub async fn get_lighthouse_analysis_attestation_performance(
         &self,
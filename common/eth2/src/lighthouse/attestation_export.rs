@@ -0,0 +1,61 @@
+use serde::{Deserialize, Serialize};
+use ssz::{Decode, DecodeError};
+use ssz_derive::{Decode, Encode};
+use types::{AttestationData, Hash256, Slot};
+
+/// The number of bytes of the little-endian length prefix of each record in an SSZ export.
+pub const SSZ_RECORD_LENGTH_PREFIX_BYTES: usize = 4;
+
+/// Query parameters for the `/lighthouse/export/attestations` endpoint.
+#[derive(Debug, PartialEq, Clone, Serialize, Deserialize)]
+pub struct AttestationExportQuery {
+    pub start_slot: Slot,
+    /// The last slot of the range, inclusive.
+    pub end_slot: Slot,
+}
+
+/// An attestation included on chain, along with the block which included it.
+#[derive(Debug, PartialEq, Clone, Serialize, Deserialize, Encode, Decode)]
+pub struct ExportedAttestation {
+    pub block_slot: Slot,
+    pub block_root: Hash256,
+    #[serde(with = "serde_utils::quoted_u64")]
+    pub proposer_index: u64,
+    /// The position of the attestation in the block body.
+    #[serde(with = "serde_utils::quoted_u64")]
+    pub attestation_index: u64,
+    /// The number of slots between the attestation's slot and the block.
+    #[serde(with = "serde_utils::quoted_u64")]
+    pub inclusion_distance: u64,
+    pub data: AttestationData,
+    /// The indices of the validators whose votes are aggregated in the attestation, in
+    /// increasing order.
+    #[serde(with = "serde_utils::quoted_u64_vec")]
+    pub attesting_indices: Vec<u64>,
+}
+
+impl ExportedAttestation {
+    /// Decode a concatenation of length-prefixed SSZ records, as served by the export endpoint.
+    pub fn from_ssz_stream(mut bytes: &[u8]) -> Result<Vec<Self>, DecodeError> {
+        let mut records = vec![];
+        while !bytes.is_empty() {
+            let (prefix, rest) = bytes
+                .split_first_chunk::<SSZ_RECORD_LENGTH_PREFIX_BYTES>()
+                .ok_or(DecodeError::InvalidByteLength {
+                    len: bytes.len(),
+                    expected: SSZ_RECORD_LENGTH_PREFIX_BYTES,
+                })?;
+            let len = u32::from_le_bytes(*prefix) as usize;
+            if rest.len() < len {
+                return Err(DecodeError::InvalidByteLength {
+                    len: rest.len(),
+                    expected: len,
+                });
+            }
+            let (record, rest) = rest.split_at(len);
+            records.push(Self::from_ssz_bytes(record)?);
+            bytes = rest;
+        }
+        Ok(records)
+    }
+}
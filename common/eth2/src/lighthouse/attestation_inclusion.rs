@@ -0,0 +1,34 @@
+use serde::{Deserialize, Serialize};
+use types::{Hash256, Slot};
+
+/// Response for the `/lighthouse/blocks/{block_id}/attestation_inclusion/{validator_index}`
+/// endpoint.
+#[derive(Debug, PartialEq, Clone, Serialize, Deserialize)]
+pub struct AttestationInclusion {
+    pub block_root: Hash256,
+    pub block_slot: Slot,
+    #[serde(with = "serde_utils::quoted_u64")]
+    pub validator_index: u64,
+    /// Whether any attestation in the block includes a vote from the validator.
+    pub included: bool,
+    /// Every attestation in the block which includes a vote from the validator.
+    ///
+    /// A validator's vote may be included more than once if it was aggregated differently.
+    pub inclusions: Vec<AttestationInclusionRecord>,
+}
+
+/// An attestation in a block which includes a vote from the validator.
+#[derive(Debug, PartialEq, Clone, Serialize, Deserialize)]
+pub struct AttestationInclusionRecord {
+    /// The position of the attestation in the block body.
+    #[serde(with = "serde_utils::quoted_u64")]
+    pub attestation_index: u64,
+    /// The slot the attestation was made for.
+    pub slot: Slot,
+    /// The index of the validator's committee at that slot.
+    #[serde(with = "serde_utils::quoted_u64")]
+    pub committee_index: u64,
+    /// The number of slots between the attestation's slot and the block.
+    #[serde(with = "serde_utils::quoted_u64")]
+    pub inclusion_distance: u64,
+}
@@ -1,5 +1,5 @@
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 use types::{AttestationData, Hash256, Slot};
 
 /// Details about the rewards paid to a block proposer for proposing a block.
@@ -58,3 +58,32 @@ pub struct BlockRewardsQuery {
     #[serde(default)]
     pub include_attestations: bool,
 }
+
+/// Attribution of the attestation rewards of a block to the peers which first delivered the
+/// included attestations via gossip.
+///
+/// All rewards in GWei.
+#[derive(Debug, PartialEq, Clone, Serialize, Deserialize)]
+pub struct BlockRewardSources {
+    /// Block root of the block that these rewards are for.
+    pub block_root: Hash256,
+    /// Metadata about the block.
+    pub meta: BlockRewardMeta,
+    /// Total block reward from attestations included.
+    pub attestation_rewards_total: u64,
+    /// Rewards attributed to each peer, keyed by peer ID.
+    pub peers: BTreeMap<String, PeerRewardAttribution>,
+    /// Rewards from attesters for which no source peer is known.
+    ///
+    /// This includes attestations from local validators, attestations received via the HTTP API
+    /// and attestations received before source tracking was enabled.
+    pub unattributed: PeerRewardAttribution,
+}
+
+#[derive(Debug, Default, PartialEq, Clone, Serialize, Deserialize)]
+pub struct PeerRewardAttribution {
+    /// Total reward from the attesters attributed to this peer.
+    pub reward: u64,
+    /// Number of rewarded attesters attributed to this peer.
+    pub attesters: u64,
+}
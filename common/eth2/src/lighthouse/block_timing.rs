@@ -0,0 +1,60 @@
+use super::PayloadSource;
+use serde::{Deserialize, Serialize};
+use types::{BlockImportSource, Hash256, Slot};
+
+/// How a block was first received.
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum BlockTimingSource {
+    Gossip,
+    Lookup,
+    RangeSync,
+    /// The block was published to this node's HTTP API, usually by its own validator client.
+    HttpApi,
+    /// The block was published to this node's HTTP API as a blinded block, and its payload was
+    /// revealed by a builder relay.
+    BuilderRelay,
+}
+
+impl From<BlockImportSource> for BlockTimingSource {
+    fn from(source: BlockImportSource) -> Self {
+        match source {
+            BlockImportSource::Gossip => Self::Gossip,
+            BlockImportSource::Lookup => Self::Lookup,
+            BlockImportSource::RangeSync => Self::RangeSync,
+            BlockImportSource::HttpApi => Self::HttpApi,
+        }
+    }
+}
+
+/// When and how a block was first seen by this node.
+#[derive(Debug, PartialEq, Clone, Serialize, Deserialize)]
+pub struct BlockTimingRecord {
+    pub slot: Slot,
+    pub block_root: Hash256,
+    pub proposer_index: u64,
+    pub source: Option<BlockTimingSource>,
+    /// The peer which first sent the block over gossip.
+    pub peer_id: Option<String>,
+    pub peer_client: Option<String>,
+    /// The time after the start of the slot at which the block was first seen.
+    pub observed_delay_ms: Option<u64>,
+    /// The source of the execution payload, if this node produced the block.
+    pub payload_source: Option<PayloadSource>,
+}
+
+/// The timings of the canonical blocks of a single proposer.
+#[derive(Debug, PartialEq, Clone, Serialize, Deserialize)]
+pub struct ProposerBlockTimings {
+    pub proposer_index: u64,
+    pub mean_observed_delay_ms: Option<u64>,
+    pub max_observed_delay_ms: Option<u64>,
+    pub blocks: Vec<BlockTimingRecord>,
+}
+
+/// Query parameters for the `/lighthouse/analysis/block_timings` endpoint.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub struct BlockTimingsQuery {
+    /// Only report the blocks of this proposer.
+    pub proposer_index: Option<u64>,
+}
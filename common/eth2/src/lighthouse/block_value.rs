@@ -0,0 +1,69 @@
+use serde::{Deserialize, Serialize};
+use types::{ExecutionBlockHash, Slot, Uint256};
+
+/// The source of the payload chosen for a locally produced block.
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PayloadSource {
+    Local,
+    Builder,
+    /// Neither payload could be used and block production failed.
+    None,
+}
+
+/// Why a payload source was chosen.
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PayloadChoiceReason {
+    /// No builder is configured.
+    NoBuilder,
+    /// The chain did not meet the health requirements for using a builder.
+    ChainUnhealthy,
+    /// The builder request failed.
+    BuilderError,
+    /// The builder did not return a bid.
+    NoBuilderBid,
+    /// The builder bid failed verification.
+    InvalidBuilderBid,
+    /// The local payload was at least as valuable as the boosted builder bid.
+    LocalMoreProfitable,
+    /// The execution engine suggested ignoring the builder payload.
+    EngineOverride,
+    /// The boosted builder bid was more valuable than the local payload.
+    BuilderMoreProfitable,
+    /// The local payload could not be built.
+    LocalError,
+}
+
+/// The payload built by the local execution engine.
+#[derive(Debug, PartialEq, Clone, Serialize, Deserialize)]
+pub struct LocalPayloadRecord {
+    pub block_hash: ExecutionBlockHash,
+    #[serde(with = "serde_utils::u256_dec")]
+    pub value: Uint256,
+}
+
+/// A bid received from the builder.
+#[derive(Debug, PartialEq, Clone, Serialize, Deserialize)]
+pub struct BuilderBidRecord {
+    pub block_hash: ExecutionBlockHash,
+    #[serde(with = "serde_utils::u256_dec")]
+    pub value: Uint256,
+    /// The value after applying the builder boost factor.
+    #[serde(with = "serde_utils::u256_dec")]
+    pub boosted_value: Uint256,
+    pub builder_boost_factor: Option<u64>,
+    /// The reason the bid failed verification, if it did.
+    pub rejection_reason: Option<String>,
+}
+
+/// The payloads considered when producing a block and the one which was chosen.
+#[derive(Debug, PartialEq, Clone, Serialize, Deserialize)]
+pub struct BlockValueRecord {
+    pub slot: Slot,
+    pub parent_hash: ExecutionBlockHash,
+    pub local_payload: Option<LocalPayloadRecord>,
+    pub builder_bid: Option<BuilderBidRecord>,
+    pub choice: PayloadSource,
+    pub reason: PayloadChoiceReason,
+}
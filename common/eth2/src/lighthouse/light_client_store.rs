@@ -0,0 +1,33 @@
+use serde::{Deserialize, Serialize};
+use types::{
+    EthSpec, ForkVersionedResponse, LightClientFinalityUpdate, LightClientOptimisticUpdate,
+    LightClientUpdate,
+};
+
+/// The data cached by a light client server, as served by `/lighthouse/light_client/store`.
+///
+/// Every update is tagged with the fork it was produced in, so that a snapshot can be loaded by a
+/// node regardless of the forks it spans.
+#[derive(Debug, PartialEq, Clone, Serialize, Deserialize)]
+#[serde(bound = "E: EthSpec")]
+pub struct LightClientStoreSnapshot<E: EthSpec> {
+    pub latest_finality_update: Option<ForkVersionedResponse<LightClientFinalityUpdate<E>>>,
+    pub latest_optimistic_update: Option<ForkVersionedResponse<LightClientOptimisticUpdate<E>>>,
+    /// The best update of each of the most recent sync committee periods, in increasing order of
+    /// period.
+    pub best_updates: Vec<ForkVersionedResponse<LightClientUpdate<E>>>,
+}
+
+/// The result of importing a `LightClientStoreSnapshot`.
+#[derive(Debug, PartialEq, Clone, Default, Serialize, Deserialize)]
+pub struct LightClientStoreImport {
+    /// Whether the snapshot's finality update replaced the cached one.
+    pub finality_update_imported: bool,
+    /// Whether the snapshot's optimistic update replaced the cached one.
+    pub optimistic_update_imported: bool,
+    /// The number of periods whose best update was replaced by one from the snapshot.
+    pub best_updates_imported: usize,
+    /// The number of updates which could not be verified against the local chain and were
+    /// ignored.
+    pub rejected: usize,
+}
@@ -0,0 +1,63 @@
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ValidatorCountResponse {
+    pub active_ongoing: u64,
+    pub active_exiting: u64,
+    pub active_slashed: u64,
+    pub pending_initialized: u64,
+    pub pending_queued: u64,
+    pub withdrawal_possible: u64,
+    pub withdrawal_done: u64,
+    pub exited_unslashed: u64,
+    pub exited_slashed: u64,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ValidatorInfoRequestData {
+    #[serde(with = "serde_utils::quoted_u64_vec")]
+    pub indices: Vec<u64>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ValidatorInfoValues {
+    #[serde(with = "serde_utils::quoted_u64")]
+    pub epoch: u64,
+    #[serde(with = "serde_utils::quoted_u64")]
+    pub total_balance: u64,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ValidatorInfo {
+    pub info: Vec<ValidatorInfoValues>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ValidatorInfoResponse {
+    pub validators: HashMap<String, ValidatorInfo>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ValidatorMetricsRequestData {
+    pub indices: Vec<u64>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ValidatorMetrics {
+    pub attestation_hits: u64,
+    pub attestation_misses: u64,
+    pub attestation_hit_percentage: f64,
+    pub attestation_head_hits: u64,
+    pub attestation_head_misses: u64,
+    pub attestation_head_hit_percentage: f64,
+    pub attestation_target_hits: u64,
+    pub attestation_target_misses: u64,
+    pub attestation_target_hit_percentage: f64,
+    pub latest_attestation_inclusion_distance: u64,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ValidatorMetricsResponse {
+    pub validators: HashMap<String, ValidatorMetrics>,
+}
@@ -305,6 +305,20 @@ impl ValidatorClientHttpClient {
         self.get(path).await
     }
 
+    /// `GET lighthouse/signers`
+    pub async fn get_lighthouse_signers(
+        &self,
+    ) -> Result<GenericResponse<Vec<SignerHealthData>>, Error> {
+        let mut path = self.server.full.clone();
+
+        path.path_segments_mut()
+            .map_err(|()| Error::InvalidUrl(self.server.clone()))?
+            .push("lighthouse")
+            .push("signers");
+
+        self.get(path).await
+    }
+
     /// `GET lighthouse/spec`
     pub async fn get_lighthouse_spec<T: Serialize + DeserializeOwned>(
         &self,
@@ -446,6 +460,23 @@ impl ValidatorClientHttpClient {
         .await
     }
 
+    /// `POST lighthouse/validators/{validator_pubkey}/presigned_exit`
+    pub async fn post_lighthouse_validators_presigned_exit(
+        &self,
+        voting_pubkey: &PublicKeyBytes,
+    ) -> Result<GenericResponse<SignedVoluntaryExit>, Error> {
+        let mut path = self.server.full.clone();
+
+        path.path_segments_mut()
+            .map_err(|()| Error::InvalidUrl(self.server.clone()))?
+            .push("lighthouse")
+            .push("validators")
+            .push(&voting_pubkey.to_string())
+            .push("presigned_exit");
+
+        self.post(path, &()).await
+    }
+
     /// `DELETE eth/v1/keystores`
     pub async fn delete_lighthouse_keystores(
         &self,
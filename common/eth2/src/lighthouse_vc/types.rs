@@ -16,6 +16,23 @@ pub struct ValidatorData {
     pub voting_pubkey: PublicKeyBytes,
 }
 
+/// The health of a Web3Signer instance, as returned by `GET lighthouse/signers`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SignerHealthData {
+    pub url: String,
+    pub healthy: bool,
+    /// The time of the most recent probe, in seconds since the UNIX epoch.
+    #[serde(with = "serde_utils::quoted_u64")]
+    pub last_checked: u64,
+    pub error: Option<String>,
+    /// The validators of the signer. These are excluded from duties while it is unhealthy.
+    pub validators: Vec<PublicKeyBytes>,
+    /// The validators which the signer failed to sign for, although it is healthy. These are
+    /// excluded from duties until the signer signs for them again.
+    #[serde(default)]
+    pub unavailable_validators: Vec<PublicKeyBytes>,
+}
+
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct ValidatorRequest {
     pub enable: bool,
@@ -0,0 +1,31 @@
+pub use metrics::*;
+use std::sync::LazyLock;
+
+/*
+ * Beacon node HTTP client
+ */
+pub static BEACON_NODE_HTTP_CLIENT_REQUEST_TIMES: LazyLock<Result<HistogramVec>> = LazyLock::new(
+    || {
+        try_create_histogram_vec(
+            "beacon_node_http_client_request_times",
+            "Duration between sending a request to a beacon node and receiving the response headers",
+            &["category"],
+        )
+    },
+);
+pub static BEACON_NODE_HTTP_CLIENT_REQUEST_ERRORS: LazyLock<Result<IntCounterVec>> =
+    LazyLock::new(|| {
+        try_create_int_counter_vec(
+            "beacon_node_http_client_request_errors_total",
+            "Count of requests to a beacon node which failed or returned a server error",
+            &["category"],
+        )
+    });
+pub static BEACON_NODE_HTTP_CLIENT_PERMIT_WAIT_TIMES: LazyLock<Result<HistogramVec>> =
+    LazyLock::new(|| {
+        try_create_histogram_vec(
+            "beacon_node_http_client_permit_wait_times",
+            "Time spent waiting for the concurrent request limit before sending a request",
+            &["category"],
+        )
+    });
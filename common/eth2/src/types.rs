@@ -10,6 +10,7 @@ use mediatype::{names, MediaType, MediaTypeList};
 use reqwest::header::HeaderMap;
 use serde::{Deserialize, Deserializer, Serialize};
 use serde_json::Value;
+use serde_utils::quoted_u64::Quoted;
 use ssz::{Decode, DecodeError};
 use ssz_derive::{Decode, Encode};
 use std::fmt::{self, Display};
@@ -47,6 +48,22 @@ pub struct IndexedErrorMessage {
     pub failures: Vec<Failure>,
 }
 
+/// An API error returned when the estimated cost of a query for historical data exceeds the
+/// beacon node's budget, serializable to JSON.
+///
+/// This is a superset of `ErrorMessage`, so clients which are unaware of it still receive the
+/// `message`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ArchiveQueryErrorMessage {
+    pub code: u16,
+    pub message: String,
+    pub blocks_to_replay: u64,
+    pub diffs_to_apply: u64,
+    pub estimated_cost: u64,
+    pub budget: u64,
+    pub hint: String,
+}
+
 /// A single failure in an index of API errors, serializable to JSON.
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct Failure {
@@ -590,6 +607,9 @@ pub struct MetaData {
     pub seq_number: u64,
     pub attnets: String,
     pub syncnets: String,
+    /// Only present once the node advertises PeerDAS (V3) metadata.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub custody_group_count: Option<Quoted<u64>>,
 }
 
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
@@ -688,6 +708,9 @@ pub struct ValidatorBalancesRequestBody {
 pub struct BlobIndicesQuery {
     #[serde(default, deserialize_with = "option_query_vec")]
     pub indices: Option<Vec<u64>>,
+    /// Only return blobs whose KZG commitment has one of these versioned hashes.
+    #[serde(default, deserialize_with = "option_query_vec")]
+    pub versioned_hashes: Option<Vec<Hash256>>,
 }
 
 #[derive(Clone, Serialize, Deserialize)]
@@ -1055,6 +1078,47 @@ pub struct SseExtendedPayloadAttributesGeneric<T> {
 
     pub parent_block_hash: ExecutionBlockHash,
     pub payload_attributes: T,
+    /// Lighthouse-specific: where the `suggested_fee_recipient` was obtained from.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub fee_recipient_source: Option<FeeRecipientSource>,
+    /// Lighthouse-specific: where the `parent_block_hash` was obtained from.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub parent_block_hash_source: Option<ParentBlockHashSource>,
+    /// Lighthouse-specific: whether the attributes build on the parent of the head because a
+    /// proposer re-org of the head is intended.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub re_org_active: Option<bool>,
+}
+
+/// The source of the `suggested_fee_recipient` in a `payload_attributes` event.
+#[derive(PartialEq, Eq, Debug, Serialize, Deserialize, Clone, Copy)]
+#[serde(rename_all = "snake_case")]
+pub enum FeeRecipientSource {
+    /// Provided by the validator client via the `prepare_beacon_proposer` endpoint.
+    ProposerPreparation,
+    /// Provided by the validator client in a validator registration for the builder via the
+    /// `register_validator` endpoint, overriding the one from `prepare_beacon_proposer`.
+    BuilderOverride,
+    /// The default set on the beacon node with `--suggested-fee-recipient`.
+    Default,
+    /// No fee recipient was known, so a placeholder address was used.
+    Placeholder,
+    /// The fee recipient does not match the current configuration, e.g. because the proposer
+    /// preparation data changed after the attributes were computed.
+    Unknown,
+}
+
+/// The source of the `parent_block_hash` in a `payload_attributes` event.
+#[derive(PartialEq, Eq, Debug, Serialize, Deserialize, Clone, Copy)]
+#[serde(rename_all = "snake_case")]
+pub enum ParentBlockHashSource {
+    /// The execution block hash of the canonical head.
+    Head,
+    /// The execution block hash of the parent of the canonical head, which the proposer intends to
+    /// build on in order to re-org the head.
+    ReOrgParent,
+    /// The parent block has no execution payload, so the zero hash was used.
+    PreMerge,
 }
 
 pub type SseExtendedPayloadAttributes = SseExtendedPayloadAttributesGeneric<SsePayloadAttributes>;
@@ -1102,6 +1166,9 @@ impl ForkVersionDeserialize for SseExtendedPayloadAttributes {
                 helper.payload_attributes,
                 fork_name,
             )?,
+            fee_recipient_source: helper.fee_recipient_source,
+            parent_block_hash_source: helper.parent_block_hash_source,
+            re_org_active: helper.re_org_active,
         })
     }
 }
@@ -2,7 +2,7 @@ use crate::{
     filesystem::{read, update},
     Error,
 };
-use eth2_wallet::{Uuid, ValidatorKeystores, Wallet};
+use eth2_wallet::{KdfParams, Uuid, ValidatorKeystores, Wallet};
 use lockfile::Lockfile;
 use std::path::{Path, PathBuf};
 
@@ -67,7 +67,7 @@ impl LockedWallet {
         &self.wallet
     }
 
-    /// Calls `Wallet::next_validator` on the underlying `wallet`.
+    /// Calls `Wallet::next_validator_with_kdf_params` on the underlying `wallet`.
     ///
     /// Ensures that the wallet JSON file is updated after each call.
     ///
@@ -80,11 +80,13 @@ impl LockedWallet {
         wallet_password: &[u8],
         voting_keystore_password: &[u8],
         withdrawal_keystore_password: &[u8],
+        kdf_params: KdfParams,
     ) -> Result<ValidatorKeystores, Error> {
-        let keystores = self.wallet.next_validator(
+        let keystores = self.wallet.next_validator_with_kdf_params(
             wallet_password,
             voting_keystore_password,
             withdrawal_keystore_password,
+            kdf_params,
         )?;
 
         update(&self.wallet_dir, &self.wallet)?;
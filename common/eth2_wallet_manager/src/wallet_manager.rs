@@ -2,7 +2,7 @@ use crate::{
     filesystem::{create, Error as FilesystemError},
     LockedWallet,
 };
-use eth2_wallet::{bip39::Mnemonic, Error as WalletError, Uuid, Wallet, WalletBuilder};
+use eth2_wallet::{bip39::Mnemonic, Error as WalletError, KdfParams, Uuid, Wallet, WalletBuilder};
 use lockfile::LockfileError;
 use std::collections::HashMap;
 use std::ffi::OsString;
@@ -111,7 +111,7 @@ impl WalletManager {
     }
 
     /// Creates a new wallet with the given `name` in `self.dir` with the given `mnemonic` as a
-    /// seed, encrypted with `password`.
+    /// seed, encrypted with `password` using a KDF with the given `kdf_params`.
     ///
     /// ## Errors
     ///
@@ -123,12 +123,15 @@ impl WalletManager {
         _wallet_type: WalletType,
         mnemonic: &Mnemonic,
         password: &[u8],
+        kdf_params: KdfParams,
     ) -> Result<LockedWallet, Error> {
         if self.wallets()?.contains_key(&name) {
             return Err(Error::NameAlreadyTaken(name));
         }
 
-        let wallet = WalletBuilder::from_mnemonic(mnemonic, password, name)?.build()?;
+        let wallet = WalletBuilder::from_mnemonic(mnemonic, password, name)?
+            .kdf_params(kdf_params)
+            .build()?;
         let uuid = *wallet.uuid();
 
         let wallet_dir = self.dir.join(format!("{}", uuid));
@@ -216,6 +219,7 @@ mod tests {
                 WalletType::Hd,
                 &get_mnemonic(),
                 WALLET_PASSWORD,
+                KdfParams::default(),
             )
             .expect("should create wallet");
 
@@ -266,6 +270,7 @@ mod tests {
             WalletType::Hd,
             &get_mnemonic(),
             WALLET_PASSWORD,
+            KdfParams::default(),
         )
         .expect("should create first wallet");
 
@@ -274,6 +279,7 @@ mod tests {
             WalletType::Hd,
             &get_mnemonic(),
             WALLET_PASSWORD,
+            KdfParams::default(),
         ) {
             Err(Error::NameAlreadyTaken(_)) => {}
             _ => panic!("expected name error"),
@@ -293,6 +299,7 @@ mod tests {
                 WalletType::Hd,
                 &get_mnemonic(),
                 WALLET_PASSWORD,
+                KdfParams::default(),
             )
             .expect("should create first wallet");
 
@@ -305,7 +312,7 @@ mod tests {
         );
 
         for i in 1..3 {
-            w.next_validator(WALLET_PASSWORD, &[50; 32], &[51; 32])
+            w.next_validator(WALLET_PASSWORD, &[50; 32], &[51; 32], KdfParams::default())
                 .expect("should create validator");
             assert_eq!(
                 load_wallet_raw(&base_dir, &uuid).nextaccount(),
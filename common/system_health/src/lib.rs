@@ -75,6 +75,63 @@ pub struct SystemHealthBN {
     pub sync_state: SyncState,
 }
 
+/// Returns the total and available bytes of the file system which holds `data_dir`.
+pub fn observe_disk_space(sysinfo: &System, data_dir: &Path) -> (u64, u64) {
+    let disks = sysinfo.disks();
+
+    // There is no clean way to find this in an OS-agnostic way. We take a simple approach,
+    // which is attempt to match the mount_point to the data_dir. If this cannot be done, we
+    // just fallback to the root fs.
+
+    let mut root_fs_disk = None;
+    let mut other_matching_fs = None;
+
+    for disk in disks.iter() {
+        if disk.mount_point() == Path::new("/")
+            || disk.mount_point() == Path::new("C:\\")
+            || disk.mount_point() == Path::new("/System/Volumes/Data")
+        {
+            // Found the usual default root_fs
+            root_fs_disk = Some(disk);
+            continue;
+        }
+
+        // If we have other file systems, compare these to the data_dir of Lighthouse and
+        // prioritize these.
+        if data_dir
+            .to_str()
+            .map(|path| {
+                if let Some(mount_str) = disk.mount_point().to_str() {
+                    path.contains(mount_str)
+                } else {
+                    false
+                }
+            })
+            .unwrap_or(false)
+        {
+            other_matching_fs = Some(disk);
+            break; // Don't bother finding other competing fs.
+        }
+    }
+
+    // If we found a file system other than the root, report this, otherwise just report the
+    // root fs
+    let fs = other_matching_fs.or(root_fs_disk);
+
+    // If the root fs is not known, just add up the total of all known partitions
+    match fs {
+        Some(fs) => (fs.total_space(), fs.available_space()),
+        None => {
+            // If we can't find a known partition, just add them all up
+            disks.iter().fold((0, 0), |mut current_sizes, disk| {
+                current_sizes.0 += disk.total_space();
+                current_sizes.1 += disk.available_space();
+                current_sizes
+            })
+        }
+    }
+}
+
 /// Populates the system health.
 fn observe_system_health(
     sysinfo: Arc<RwLock<System>>,
@@ -86,66 +143,12 @@ fn observe_system_health(
 
     let cpus = sysinfo.cpus();
 
-    let disks = sysinfo.disks();
-
     let system_uptime = sysinfo.uptime();
 
     // Helper functions to extract specific data
 
     // Find fs associated with the data dir location and report this
-    let (disk_bytes_total, disk_bytes_free) = {
-        // There is no clean way to find this in an OS-agnostic way. We take a simple approach,
-        // which is attempt to match the mount_point to the data_dir. If this cannot be done, we
-        // just fallback to the root fs.
-
-        let mut root_fs_disk = None;
-        let mut other_matching_fs = None;
-
-        for disk in disks.iter() {
-            if disk.mount_point() == Path::new("/")
-                || disk.mount_point() == Path::new("C:\\")
-                || disk.mount_point() == Path::new("/System/Volumes/Data")
-            {
-                // Found the usual default root_fs
-                root_fs_disk = Some(disk);
-                continue;
-            }
-
-            // If we have other file systems, compare these to the data_dir of Lighthouse and
-            // prioritize these.
-            if data_dir
-                .to_str()
-                .map(|path| {
-                    if let Some(mount_str) = disk.mount_point().to_str() {
-                        path.contains(mount_str)
-                    } else {
-                        false
-                    }
-                })
-                .unwrap_or(false)
-            {
-                other_matching_fs = Some(disk);
-                break; // Don't bother finding other competing fs.
-            }
-        }
-
-        // If we found a file system other than the root, report this, otherwise just report the
-        // root fs
-        let fs = other_matching_fs.or(root_fs_disk);
-
-        // If the root fs is not known, just add up the total of all known partitions
-        match fs {
-            Some(fs) => (fs.total_space(), fs.available_space()),
-            None => {
-                // If we can't find a known partition, just add them all up
-                disks.iter().fold((0, 0), |mut current_sizes, disk| {
-                    current_sizes.0 += disk.total_space();
-                    current_sizes.1 += disk.available_space();
-                    current_sizes
-                })
-            }
-        }
-    };
+    let (disk_bytes_total, disk_bytes_free) = observe_disk_space(&sysinfo, &data_dir);
 
     // Attempt to get the clock speed from the name of the CPU
     let cpu_frequency_from_name = cpus.iter().next().and_then(|cpu| {
@@ -2,12 +2,15 @@
 //!
 //! - `ValidatorDir`: manages a directory containing validator keypairs, deposit info and other
 //!   things.
+//! - `EncryptedPresignedExit`: a voluntary exit signed in advance and stored encrypted alongside
+//!   the voting keystore.
 //!
 //! This crate is intended to be used by the account manager to create validators and the validator
 //! client to load those validators.
 
 mod builder;
 pub mod insecure_keys;
+mod presigned_exit;
 mod validator_dir;
 
 pub use crate::validator_dir::{
@@ -18,3 +21,6 @@ pub use builder::{
     keystore_password_path, Builder, Error as BuilderError, ETH1_DEPOSIT_DATA_FILE,
     VOTING_KEYSTORE_FILE, WITHDRAWAL_KEYSTORE_FILE,
 };
+pub use presigned_exit::{
+    presigned_exit_path, EncryptedPresignedExit, Error as PresignedExitError, PRESIGNED_EXIT_FILE,
+};
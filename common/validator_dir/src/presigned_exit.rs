@@ -0,0 +1,141 @@
+//! Voluntary exits which are signed ahead of time and stored alongside the voting keystore.
+//!
+//! A signed voluntary exit allows anyone who holds it to exit the validator, so it is encrypted
+//! using the same scheme (and typically the same password) as the EIP-2335 voting keystore.
+use eth2_keystore::json_keystore::{
+    Aes128Ctr, ChecksumModule, Cipher, CipherModule, Crypto, EmptyMap, EmptyString, KdfModule,
+    Sha256Checksum,
+};
+use eth2_keystore::{decrypt, default_kdf, encrypt, Error as KeystoreError, IV_SIZE, SALT_SIZE};
+use filesystem::create_with_600_perms;
+use rand::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::fs::File;
+use std::io;
+use std::path::{Path, PathBuf};
+use types::{Epoch, PublicKeyBytes, SignedVoluntaryExit};
+
+/// The file used to save a presigned voluntary exit, in the same directory as the voting keystore.
+pub const PRESIGNED_EXIT_FILE: &str = "presigned-voluntary-exit.json";
+
+#[derive(Debug)]
+pub enum Error {
+    PresignedExitExists(PathBuf),
+    UnableToOpenPresignedExit(io::Error),
+    UnableToParsePresignedExit(serde_json::Error),
+    UnableToSerializePresignedExit(serde_json::Error),
+    UnableToSavePresignedExit(filesystem::Error),
+    UnableToEncryptPresignedExit(KeystoreError),
+    UnableToDecryptPresignedExit(KeystoreError),
+    /// The decrypted exit does not match the epoch stored in plain text.
+    PresignedExitEpochMismatch {
+        expected: Epoch,
+        found: Epoch,
+    },
+}
+
+/// Returns the path of the presigned exit for the keystore at `voting_keystore_path`.
+pub fn presigned_exit_path(voting_keystore_path: &Path) -> PathBuf {
+    voting_keystore_path
+        .parent()
+        .unwrap_or_else(|| Path::new(""))
+        .join(PRESIGNED_EXIT_FILE)
+}
+
+/// A `SignedVoluntaryExit` encrypted with a password.
+///
+/// The public key and epoch are stored in plain text so that the exit can be identified without
+/// decrypting it.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct EncryptedPresignedExit {
+    pub pubkey: PublicKeyBytes,
+    pub epoch: Epoch,
+    pub crypto: Crypto,
+}
+
+impl EncryptedPresignedExit {
+    /// Encrypts `signed_exit` for the validator with `pubkey` using `password`.
+    ///
+    /// Generates the KDF `salt` and AES `IV` using `rand::thread_rng()`.
+    pub fn encrypt(
+        signed_exit: &SignedVoluntaryExit,
+        pubkey: PublicKeyBytes,
+        password: &[u8],
+    ) -> Result<Self, Error> {
+        let salt = rand::thread_rng().gen::<[u8; SALT_SIZE]>();
+        let iv = rand::thread_rng().gen::<[u8; IV_SIZE]>().to_vec().into();
+        let kdf = default_kdf(salt.to_vec());
+        let cipher = Cipher::Aes128Ctr(Aes128Ctr { iv });
+
+        let plain_text =
+            serde_json::to_vec(signed_exit).map_err(Error::UnableToSerializePresignedExit)?;
+        let (cipher_text, checksum) = encrypt(&plain_text, password, &kdf, &cipher)
+            .map_err(Error::UnableToEncryptPresignedExit)?;
+
+        Ok(Self {
+            pubkey,
+            epoch: signed_exit.message.epoch,
+            crypto: Crypto {
+                kdf: KdfModule {
+                    function: kdf.function(),
+                    params: kdf,
+                    message: EmptyString,
+                },
+                checksum: ChecksumModule {
+                    function: Sha256Checksum::function(),
+                    params: EmptyMap,
+                    message: checksum.to_vec().into(),
+                },
+                cipher: CipherModule {
+                    function: cipher.function(),
+                    params: cipher,
+                    message: cipher_text.into(),
+                },
+            },
+        })
+    }
+
+    /// Decrypts the `SignedVoluntaryExit` using `password`.
+    ///
+    /// ## Errors
+    ///
+    /// If the password is incorrect or the decrypted exit is malformed.
+    pub fn decrypt(&self, password: &[u8]) -> Result<SignedVoluntaryExit, Error> {
+        let plain_text =
+            decrypt(password, &self.crypto).map_err(Error::UnableToDecryptPresignedExit)?;
+        let signed_exit: SignedVoluntaryExit = serde_json::from_slice(plain_text.as_bytes())
+            .map_err(Error::UnableToParsePresignedExit)?;
+
+        if signed_exit.message.epoch != self.epoch {
+            return Err(Error::PresignedExitEpochMismatch {
+                expected: self.epoch,
+                found: signed_exit.message.epoch,
+            });
+        }
+
+        Ok(signed_exit)
+    }
+
+    /// Reads a presigned exit from the JSON file at `path`.
+    pub fn from_json_file<P: AsRef<Path>>(path: P) -> Result<Self, Error> {
+        let file = File::open(path).map_err(Error::UnableToOpenPresignedExit)?;
+        serde_json::from_reader(file).map_err(Error::UnableToParsePresignedExit)
+    }
+
+    /// Writes `self` to a new file at `path`, readable only by the current user.
+    ///
+    /// ## Errors
+    ///
+    /// If a file already exists at `path`.
+    pub fn save<P: AsRef<Path>>(&self, path: P) -> Result<(), Error> {
+        let path = path.as_ref();
+        if path.exists() {
+            return Err(Error::PresignedExitExists(path.into()));
+        }
+
+        let bytes =
+            serde_json::to_vec_pretty(self).map_err(Error::UnableToSerializePresignedExit)?;
+        create_with_600_perms(path, &bytes).map_err(Error::UnableToSavePresignedExit)
+    }
+}
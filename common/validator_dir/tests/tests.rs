@@ -4,9 +4,13 @@ use eth2_keystore::{Keystore, KeystoreBuilder, PlainText};
 use std::fs::{self, File};
 use std::path::Path;
 use tempfile::{tempdir, TempDir};
-use types::{test_utils::generate_deterministic_keypair, EthSpec, Keypair, MainnetEthSpec};
+use types::{
+    test_utils::generate_deterministic_keypair, Epoch, EthSpec, Hash256, Keypair, MainnetEthSpec,
+    VoluntaryExit,
+};
 use validator_dir::{
-    Builder, BuilderError, ValidatorDir, ETH1_DEPOSIT_DATA_FILE, ETH1_DEPOSIT_TX_HASH_FILE,
+    presigned_exit_path, Builder, BuilderError, EncryptedPresignedExit, PresignedExitError,
+    ValidatorDir, ETH1_DEPOSIT_DATA_FILE, ETH1_DEPOSIT_TX_HASH_FILE, PRESIGNED_EXIT_FILE,
     VOTING_KEYSTORE_FILE, WITHDRAWAL_KEYSTORE_FILE,
 };
 
@@ -375,3 +379,44 @@ fn store_withdrawal_keystore_with_eth1_data() {
 
     harness.create_and_test(&config);
 }
+
+#[test]
+fn presigned_exit() {
+    let harness = Harness::new();
+    let validator = harness.create_and_test(&BuildConfig::default());
+    let keypair = validator
+        .voting_keypair(harness.password_dir.path())
+        .unwrap();
+
+    let spec = MainnetEthSpec::default_spec();
+    let signed_exit = VoluntaryExit {
+        epoch: Epoch::new(1024),
+        validator_index: 42,
+    }
+    .sign(&keypair.sk, Hash256::zero(), &spec);
+
+    let path = presigned_exit_path(&validator.voting_keystore_path());
+    assert_eq!(path, validator.dir().join(PRESIGNED_EXIT_FILE));
+
+    let encrypted =
+        EncryptedPresignedExit::encrypt(&signed_exit, keypair.pk.compress(), INSECURE_PASSWORD)
+            .unwrap();
+    encrypted.save(&path).unwrap();
+    assert!(
+        matches!(
+            encrypted.save(&path),
+            Err(PresignedExitError::PresignedExitExists(_))
+        ),
+        "should not overwrite an existing presigned exit"
+    );
+
+    let loaded = EncryptedPresignedExit::from_json_file(&path).unwrap();
+    assert_eq!(loaded, encrypted);
+    assert_eq!(loaded.pubkey, keypair.pk.compress());
+    assert_eq!(loaded.epoch, Epoch::new(1024));
+    assert_eq!(loaded.decrypt(INSECURE_PASSWORD).unwrap(), signed_exit);
+    assert!(
+        loaded.decrypt(b"wrong password").is_err(),
+        "should not decrypt with the wrong password"
+    );
+}
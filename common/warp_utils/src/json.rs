@@ -1,7 +1,9 @@
 use bytes::Bytes;
-use eth2::{CONTENT_TYPE_HEADER, SSZ_CONTENT_TYPE_HEADER};
+use eth2::{CONSENSUS_VERSION_HEADER, CONTENT_TYPE_HEADER, SSZ_CONTENT_TYPE_HEADER};
 use serde::de::DeserializeOwned;
 use std::error::Error as StdError;
+use std::fmt::Debug;
+use types::ForkName;
 use warp::{Filter, Rejection};
 
 use crate::reject;
@@ -31,3 +33,30 @@ pub fn json<T: DeserializeOwned + Send>() -> impl Filter<Extract = (T,), Error =
                 .map_err(|err| reject::custom_deserialize_error(format!("{:?}", err)))
         })
 }
+
+/// Decode the request body with `decode_ssz` if the request has an `application/octet-stream`
+/// content-type, otherwise decode it as JSON.
+///
+/// The SSZ encoding of some types varies by fork, so the value of the `Eth-Consensus-Version`
+/// header (if any) is passed to `decode_ssz`.
+pub fn json_or_ssz<T, F, E>(decode_ssz: F) -> impl Filter<Extract = (T,), Error = Rejection> + Copy
+where
+    T: DeserializeOwned + Send,
+    F: Fn(&[u8], Option<ForkName>) -> Result<T, E> + Copy + Send + Sync + 'static,
+    E: Debug,
+{
+    warp::header::optional::<String>(CONTENT_TYPE_HEADER)
+        .and(warp::header::optional::<ForkName>(CONSENSUS_VERSION_HEADER))
+        .and(warp::body::bytes())
+        .and_then(
+            move |header: Option<String>, fork_name: Option<ForkName>, bytes: Bytes| async move {
+                if header.as_deref() == Some(SSZ_CONTENT_TYPE_HEADER) {
+                    decode_ssz(&bytes, fork_name)
+                        .map_err(|e| reject::custom_bad_request(format!("invalid SSZ: {e:?}")))
+                } else {
+                    Json::decode(bytes)
+                        .map_err(|err| reject::custom_deserialize_error(format!("{:?}", err)))
+                }
+            },
+        )
+}
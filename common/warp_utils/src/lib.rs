@@ -4,6 +4,7 @@
 pub mod cors;
 pub mod json;
 pub mod metrics;
+pub mod metrics_access;
 pub mod query;
 pub mod reject;
 pub mod task;
@@ -0,0 +1,191 @@
+//! Access controls shared by the beacon node and validator client metrics HTTP servers.
+//!
+//! Provides optional bearer token authentication and filtering of the served metrics by name, so
+//! that a metrics server can be exposed beyond localhost without serving every metric to anyone.
+use std::fs;
+use std::path::Path;
+use warp::http::Response;
+
+/// Read a bearer token from the file at `path`, ignoring surrounding whitespace.
+pub fn load_auth_token(path: &Path) -> Result<String, String> {
+    let token = fs::read_to_string(path)
+        .map_err(|e| format!("Unable to read metrics auth token file {:?}: {:?}", path, e))?
+        .trim()
+        .to_string();
+
+    if token.is_empty() {
+        return Err(format!("Metrics auth token file {:?} is empty", path));
+    }
+
+    Ok(token)
+}
+
+/// Returns `true` if no token is required, or the `Authorization` header supplies the expected
+/// bearer token.
+pub fn is_authorized(expected_token: Option<&str>, authorization: Option<&str>) -> bool {
+    let Some(expected_token) = expected_token else {
+        return true;
+    };
+
+    authorization
+        .and_then(|header| header.strip_prefix("Bearer "))
+        .is_some_and(|token| constant_time_eq(token.as_bytes(), expected_token.as_bytes()))
+}
+
+/// The response returned to requests which fail authentication.
+pub fn unauthorized_response() -> Response<String> {
+    Response::builder()
+        .status(401)
+        .header("Content-Type", "text/plain")
+        .header("WWW-Authenticate", "Bearer")
+        .body("Unauthorized".to_string())
+        .unwrap()
+}
+
+/// Compare two byte strings in time independent of the position of the first mismatch.
+pub fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    a.len() == b.len() && a.iter().zip(b).fold(0, |acc, (x, y)| acc | (x ^ y)) == 0
+}
+
+/// Filters metrics in the Prometheus text exposition format by metric family name.
+///
+/// Patterns either match a name exactly or, when ending with `*`, match any name with the
+/// preceding prefix.
+#[derive(Debug, Clone, Default)]
+pub struct MetricFilter {
+    /// If set, only metrics matching one of these patterns are served.
+    allowlist: Option<Vec<String>>,
+    /// Metrics matching one of these patterns are never served.
+    denylist: Vec<String>,
+}
+
+impl MetricFilter {
+    pub fn new(allowlist: Option<Vec<String>>, denylist: Vec<String>) -> Self {
+        Self {
+            allowlist,
+            denylist,
+        }
+    }
+
+    /// Returns `true` if no filtering is configured.
+    pub fn is_empty(&self) -> bool {
+        self.allowlist.is_none() && self.denylist.is_empty()
+    }
+
+    pub fn is_allowed(&self, name: &str) -> bool {
+        let matches = |pattern: &String| match pattern.strip_suffix('*') {
+            Some(prefix) => name.starts_with(prefix),
+            None => name == pattern,
+        };
+
+        self.allowlist
+            .as_ref()
+            .map_or(true, |allowlist| allowlist.iter().any(matches))
+            && !self.denylist.iter().any(matches)
+    }
+
+    /// Remove all disallowed metric families from `text`.
+    ///
+    /// Samples are attributed to the family named by the preceding `# HELP` or `# TYPE` line,
+    /// so that e.g. the `_bucket` samples of a histogram are filtered along with the histogram.
+    pub fn filter_text(&self, text: &str) -> String {
+        if self.is_empty() {
+            return text.to_string();
+        }
+
+        let mut output = String::with_capacity(text.len());
+        let mut family: Option<(&str, bool)> = None;
+
+        for line in text.lines() {
+            let allowed = if let Some(comment) = line.strip_prefix('#') {
+                let mut words = comment.split_whitespace();
+                match (words.next(), words.next()) {
+                    (Some("HELP" | "TYPE"), Some(name)) => {
+                        let allowed = self.is_allowed(name);
+                        family = Some((name, allowed));
+                        allowed
+                    }
+                    // Retain other comments, such as the `# EOF` marker.
+                    _ => true,
+                }
+            } else {
+                let name = line
+                    .split(|c: char| c == '{' || c.is_whitespace())
+                    .next()
+                    .unwrap_or_default();
+                match family {
+                    Some((family_name, allowed)) if name.starts_with(family_name) => allowed,
+                    _ => name.is_empty() || self.is_allowed(name),
+                }
+            };
+
+            if allowed {
+                output.push_str(line);
+                output.push('\n');
+            }
+        }
+
+        output
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const METRICS: &str = "\
+# HELP beacon_head_slot Slot of the head block.
+# TYPE beacon_head_slot gauge
+beacon_head_slot 10
+# HELP beacon_block_processing_seconds Block processing time.
+# TYPE beacon_block_processing_seconds histogram
+beacon_block_processing_seconds_bucket{le=\"0.5\"} 1
+beacon_block_processing_seconds_sum 0.2
+beacon_block_processing_seconds_count 1
+# HELP libp2p_peers Connected peers.
+# TYPE libp2p_peers gauge
+libp2p_peers 50
+";
+
+    fn filter(allowlist: Option<&[&str]>, denylist: &[&str]) -> MetricFilter {
+        let to_vec = |patterns: &[&str]| patterns.iter().map(|p| p.to_string()).collect();
+        MetricFilter::new(allowlist.map(to_vec), to_vec(denylist))
+    }
+
+    #[test]
+    fn no_filter_is_noop() {
+        assert_eq!(filter(None, &[]).filter_text(METRICS), METRICS);
+    }
+
+    #[test]
+    fn allowlist_with_prefix() {
+        let output = filter(Some(&["beacon_*"]), &[]).filter_text(METRICS);
+        assert!(output.contains("beacon_head_slot 10"));
+        assert!(output.contains("beacon_block_processing_seconds_count 1"));
+        assert!(!output.contains("libp2p_peers"));
+    }
+
+    #[test]
+    fn denylist_removes_whole_family() {
+        let output = filter(None, &["beacon_block_processing_seconds"]).filter_text(METRICS);
+        assert!(output.contains("beacon_head_slot 10"));
+        assert!(output.contains("libp2p_peers 50"));
+        assert!(!output.contains("beacon_block_processing_seconds"));
+    }
+
+    #[test]
+    fn denylist_overrides_allowlist() {
+        let output = filter(Some(&["beacon_*"]), &["beacon_head_slot"]).filter_text(METRICS);
+        assert!(!output.contains("beacon_head_slot"));
+        assert!(output.contains("beacon_block_processing_seconds_sum 0.2"));
+    }
+
+    #[test]
+    fn authorization() {
+        assert!(is_authorized(None, None));
+        assert!(is_authorized(Some("secret"), Some("Bearer secret")));
+        assert!(!is_authorized(Some("secret"), None));
+        assert!(!is_authorized(Some("secret"), Some("Bearer wrong")));
+        assert!(!is_authorized(Some("secret"), Some("secret")));
+    }
+}
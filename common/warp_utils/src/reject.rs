@@ -1,8 +1,14 @@
-use eth2::types::{ErrorMessage, Failure, IndexedErrorMessage};
+use eth2::types::{ArchiveQueryErrorMessage, ErrorMessage, Failure, IndexedErrorMessage};
 use std::convert::Infallible;
 use std::error::Error;
 use std::fmt;
-use warp::{http::StatusCode, reject::Reject, reply::Response, Reply};
+use std::time::Duration;
+use warp::{
+    http::{header::RETRY_AFTER, StatusCode},
+    reject::Reject,
+    reply::Response,
+    Reply,
+};
 
 #[derive(Debug)]
 pub struct ServerSentEventError(pub String);
@@ -157,6 +163,24 @@ pub fn indexed_bad_request(message: String, failures: Vec<Failure>) -> warp::rej
     warp::reject::custom(IndexedBadRequestErrors { message, failures })
 }
 
+#[derive(Debug)]
+pub struct ArchiveQueryRejected {
+    pub reason: String,
+    pub blocks_to_replay: u64,
+    pub diffs_to_apply: u64,
+    pub estimated_cost: u64,
+    pub budget: u64,
+    pub hint: String,
+    /// Sent as a `Retry-After` header, if the query may succeed when retried.
+    pub retry_after: Option<Duration>,
+}
+
+impl Reject for ArchiveQueryRejected {}
+
+pub fn archive_query_rejected(rejected: ArchiveQueryRejected) -> warp::reject::Rejection {
+    warp::reject::custom(rejected)
+}
+
 /// This function receives a `Rejection` and tries to return a custom
 /// value, otherwise simply passes the rejection along.
 pub async fn handle_rejection(err: warp::Rejection) -> Result<impl warp::Reply, Infallible> {
@@ -173,7 +197,29 @@ pub async fn handle_rejection(err: warp::Rejection) -> Result<impl warp::Reply,
             failures: e.failures.clone(),
         });
 
-        return Ok(warp::reply::with_status(json, code));
+        return Ok(warp::reply::with_status(json, code).into_response());
+    }
+
+    if let Some(e) = err.find::<crate::reject::ArchiveQueryRejected>() {
+        code = StatusCode::SERVICE_UNAVAILABLE;
+
+        let json = warp::reply::json(&ArchiveQueryErrorMessage {
+            code: code.as_u16(),
+            message: format!("SERVICE_UNAVAILABLE: archive query rejected: {}", e.reason),
+            blocks_to_replay: e.blocks_to_replay,
+            diffs_to_apply: e.diffs_to_apply,
+            estimated_cost: e.estimated_cost,
+            budget: e.budget,
+            hint: e.hint.clone(),
+        });
+
+        let mut response = warp::reply::with_status(json, code).into_response();
+        if let Some(retry_after) = e.retry_after {
+            response
+                .headers_mut()
+                .insert(RETRY_AFTER, retry_after.as_secs().into());
+        }
+        return Ok(response);
     }
 
     if err.is_not_found() {
@@ -253,7 +299,7 @@ pub async fn handle_rejection(err: warp::Rejection) -> Result<impl warp::Reply,
         stacktraces: vec![],
     });
 
-    Ok(warp::reply::with_status(json, code))
+    Ok(warp::reply::with_status(json, code).into_response())
 }
 
 /// Convert a warp `Rejection` into a `Response`.
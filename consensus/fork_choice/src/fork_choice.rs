@@ -2,7 +2,7 @@ use crate::metrics::{self, scrape_for_metrics};
 use crate::{ForkChoiceStore, InvalidationOperation};
 use proto_array::{
     Block as ProtoBlock, DisallowedReOrgOffsets, ExecutionStatus, ProposerHeadError,
-    ProposerHeadInfo, ProtoArrayForkChoice, ReOrgThreshold,
+    ProposerHeadInfo, ProtoArrayForkChoice, ReOrgSimulationParams, ReOrgThreshold, SimulatedReOrg,
 };
 use slog::{crit, debug, warn, Logger};
 use ssz_derive::{Decode, Encode};
@@ -586,6 +586,40 @@ where
             .map_err(ProposerHeadError::convert_inner_error)
     }
 
+    /// Simulate the block to propose on during the current slot under alternative re-org
+    /// parameters, for analysis only.
+    ///
+    /// See `ProtoArrayForkChoice::simulate_proposer_head`. Like `get_proposer_head`, this must
+    /// be called after `get_head` for the current slot and before any block for the current slot
+    /// has been boosted.
+    pub fn simulate_proposer_head(
+        &self,
+        canonical_head: Hash256,
+        params: &ReOrgSimulationParams,
+        disallowed_offsets: &DisallowedReOrgOffsets,
+        max_epochs_since_finalization: Epoch,
+    ) -> Result<SimulatedReOrg, ProposerHeadError<Error<proto_array::Error>>> {
+        let proposer_boost_root = self.fc_store.proposer_boost_root();
+        if !proposer_boost_root.is_zero() {
+            return Err(ProposerHeadError::Error(
+                Error::ProposerBoostNotExpiredForGetProposerHead {
+                    proposer_boost_root,
+                },
+            ));
+        }
+
+        self.proto_array
+            .simulate_proposer_head::<E>(
+                self.fc_store.get_current_slot(),
+                canonical_head,
+                self.fc_store.justified_balances(),
+                params,
+                disallowed_offsets,
+                max_epochs_since_finalization,
+            )
+            .map_err(ProposerHeadError::convert_inner_error)
+    }
+
     /// Return information about:
     ///
     /// - The LMD head of the chain.
@@ -1,5 +1,5 @@
 use safe_arith::{ArithError, SafeArith};
-use types::{BeaconState, EthSpec};
+use types::{BeaconState, Epoch, EthSpec, Validators};
 
 #[derive(Debug, PartialEq, Clone, Default)]
 pub struct JustifiedBalances {
@@ -16,12 +16,21 @@ pub struct JustifiedBalances {
 
 impl JustifiedBalances {
     pub fn from_justified_state<E: EthSpec>(state: &BeaconState<E>) -> Result<Self, ArithError> {
-        let current_epoch = state.current_epoch();
+        Self::from_validators::<E>(state.validators(), state.current_epoch())
+    }
+
+    /// Compute the balances from the validator registry of a state in `current_epoch`.
+    ///
+    /// The registry shares its tree with the state it was taken from, so it can be retained
+    /// cheaply and the balances computed only when required.
+    pub fn from_validators<E: EthSpec>(
+        validators: &Validators<E>,
+        current_epoch: Epoch,
+    ) -> Result<Self, ArithError> {
         let mut total_effective_balance = 0u64;
         let mut num_active_validators = 0u64;
 
-        let effective_balances = state
-            .validators()
+        let effective_balances = validators
             .iter()
             .map(|validator| {
                 if !validator.slashed && validator.is_active_at(current_epoch) {
@@ -204,6 +204,29 @@ pub struct ProposerHeadInfo {
     pub current_slot: Slot,
 }
 
+/// Alternative parameters under which to simulate proposer re-orgs, see
+/// `ProtoArrayForkChoice::simulate_proposer_head`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ReOrgSimulationParams {
+    /// Maximum percentage of the committee weight of the orphaned blocks.
+    pub re_org_head_threshold: ReOrgThreshold,
+    /// Minimum percentage of the committee weight of the block built upon.
+    pub re_org_parent_threshold: ReOrgThreshold,
+    /// Percentage of the committee weight applied as proposer boost.
+    pub proposer_score_boost: u64,
+    /// Maximum number of blocks which may be orphaned.
+    pub max_re_org_depth: u64,
+}
+
+/// The outcome of a simulated proposer re-org.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SimulatedReOrg {
+    /// The block which would be built upon.
+    pub parent_root: Hash256,
+    /// The number of blocks which would be orphaned.
+    pub depth: u64,
+}
+
 /// Error type to enable short-circuiting checks in `get_proposer_head`.
 ///
 /// This type intentionally does not implement `Debug` so that callers are forced to handle the
@@ -270,6 +293,10 @@ pub enum DoNotReOrg {
     HeadNotLate,
     NotProposing,
     ReOrgsDisabled,
+    InsufficientProposerBoost {
+        head_weight: u64,
+        proposer_score: u64,
+    },
 }
 
 impl std::fmt::Display for DoNotReOrg {
@@ -319,6 +346,15 @@ impl std::fmt::Display for DoNotReOrg {
             Self::ReOrgsDisabled => {
                 write!(f, "re-orgs disabled in config")
             }
+            Self::InsufficientProposerBoost {
+                head_weight,
+                proposer_score,
+            } => {
+                write!(
+                    f,
+                    "proposer boost does not outweigh head ({proposer_score}/{head_weight})"
+                )
+            }
         }
     }
 }
@@ -648,6 +684,122 @@ impl ProtoArrayForkChoice {
         })
     }
 
+    /// Simulate the block to propose on during `current_slot` under the alternative `params`.
+    ///
+    /// Unlike `get_proposer_head`, up to `params.max_re_org_depth` blocks may be orphaned and a
+    /// re-org is only viable if the proposer boost outweighs the orphaned blocks. The shallowest
+    /// viable re-org is returned. Lateness of the head is not considered.
+    ///
+    /// This function is intended for analysis only, its result must not be acted on.
+    #[allow(clippy::too_many_arguments)]
+    pub fn simulate_proposer_head<E: EthSpec>(
+        &self,
+        current_slot: Slot,
+        canonical_head: Hash256,
+        justified_balances: &JustifiedBalances,
+        params: &ReOrgSimulationParams,
+        disallowed_offsets: &DisallowedReOrgOffsets,
+        max_epochs_since_finalization: Epoch,
+    ) -> Result<SimulatedReOrg, ProposerHeadError<Error>> {
+        let nodes = self
+            .proto_array
+            .iter_nodes(&canonical_head)
+            .take(params.max_re_org_depth.saturating_add(1) as usize)
+            .collect::<Vec<_>>();
+        let head_node = nodes.first().ok_or(DoNotReOrg::MissingHeadOrParentNode)?;
+
+        if head_node.slot + 1 != current_slot {
+            return Err(DoNotReOrg::HeadDistance.into());
+        }
+
+        // Check finalization distance.
+        let proposal_epoch = current_slot.epoch(E::slots_per_epoch());
+        let finalized_epoch = head_node
+            .unrealized_finalized_checkpoint
+            .ok_or(DoNotReOrg::MissingHeadFinalizedCheckpoint)?
+            .epoch;
+        let epochs_since_finalization = proposal_epoch.saturating_sub(finalized_epoch).as_u64();
+        if epochs_since_finalization > max_epochs_since_finalization.as_u64() {
+            return Err(DoNotReOrg::ChainNotFinalizing {
+                epochs_since_finalization,
+            }
+            .into());
+        }
+
+        // Check allowed slot offsets.
+        let offset = (current_slot % E::slots_per_epoch()).as_u64();
+        if disallowed_offsets.offsets.contains(&offset) {
+            return Err(DoNotReOrg::DisallowedOffset { offset }.into());
+        }
+
+        let re_org_head_weight_threshold =
+            calculate_committee_fraction::<E>(justified_balances, params.re_org_head_threshold.0)
+                .ok_or(Error::ReOrgThresholdOverflow)?;
+        let re_org_parent_weight_threshold =
+            calculate_committee_fraction::<E>(justified_balances, params.re_org_parent_threshold.0)
+                .ok_or(Error::ReOrgThresholdOverflow)?;
+        let proposer_score =
+            calculate_committee_fraction::<E>(justified_balances, params.proposer_score_boost)
+                .ok_or(Error::ReOrgThresholdOverflow)?;
+
+        // Try building on each ancestor in turn. The weight of each block includes the weight of
+        // its descendants, so if the orphaned blocks are too heavy at some depth then they are too
+        // heavy at every greater depth.
+        let mut reason = DoNotReOrg::MissingHeadOrParentNode;
+        for (i, pair) in nodes.windows(2).enumerate() {
+            let [orphaned_node, parent_node] = pair else {
+                break;
+            };
+
+            if parent_node.slot + 1 != orphaned_node.slot {
+                return Err(DoNotReOrg::ParentDistance.into());
+            }
+
+            // All orphaned blocks must be in the proposal epoch so that the shuffling is stable.
+            if orphaned_node.slot.epoch(E::slots_per_epoch()) != proposal_epoch {
+                return Err(DoNotReOrg::ShufflingUnstable.into());
+            }
+
+            let ffg_competitive = parent_node.unrealized_justified_checkpoint
+                == head_node.unrealized_justified_checkpoint
+                && parent_node.unrealized_finalized_checkpoint
+                    == head_node.unrealized_finalized_checkpoint;
+            if !ffg_competitive {
+                return Err(DoNotReOrg::JustificationAndFinalizationNotCompetitive.into());
+            }
+
+            let head_weight = orphaned_node.weight;
+            if head_weight >= re_org_head_weight_threshold {
+                return Err(DoNotReOrg::HeadNotWeak {
+                    head_weight,
+                    re_org_head_weight_threshold,
+                }
+                .into());
+            }
+            if head_weight >= proposer_score {
+                return Err(DoNotReOrg::InsufficientProposerBoost {
+                    head_weight,
+                    proposer_score,
+                }
+                .into());
+            }
+
+            let parent_weight = parent_node.weight;
+            if parent_weight > re_org_parent_weight_threshold {
+                return Ok(SimulatedReOrg {
+                    parent_root: parent_node.root,
+                    depth: i as u64 + 1,
+                });
+            }
+            reason = DoNotReOrg::ParentNotStrong {
+                parent_weight,
+                re_org_parent_weight_threshold,
+            };
+        }
+
+        Err(reason.into())
+    }
+
     /// Returns `true` if there are any blocks in `self` with an `INVALID` execution payload status.
     ///
     /// This will operate on *all* blocks, even those that do not descend from the finalized
@@ -1105,6 +1257,125 @@ mod test_compute_deltas {
         assert!(!fc.is_descendant(not_finalized_desc, unknown));
     }
 
+    #[test]
+    fn simulate_multi_slot_re_org() {
+        const BALANCE: u64 = 32_000_000_000;
+        const VALIDATOR_COUNT: usize = 320;
+
+        let genesis_slot = Slot::new(0);
+        let genesis_checkpoint = Checkpoint {
+            epoch: Epoch::new(0),
+            root: hash_from_index(0),
+        };
+        let junk_shuffling_id =
+            AttestationShufflingId::from_components(Epoch::new(0), Hash256::zero());
+        let execution_status = ExecutionStatus::irrelevant();
+        let spec = MainnetEthSpec::default_spec();
+
+        let mut fc = ProtoArrayForkChoice::new::<MainnetEthSpec>(
+            genesis_slot,
+            genesis_slot,
+            Hash256::zero(),
+            genesis_checkpoint,
+            genesis_checkpoint,
+            junk_shuffling_id.clone(),
+            junk_shuffling_id.clone(),
+            execution_status,
+        )
+        .unwrap();
+
+        // Build a chain of blocks at slots 1, 2 and 3.
+        for i in 1..=3 {
+            fc.process_block::<MainnetEthSpec>(
+                Block {
+                    slot: Slot::new(i as u64),
+                    root: hash_from_index(i),
+                    parent_root: Some(hash_from_index(i - 1)),
+                    state_root: Hash256::zero(),
+                    target_root: genesis_checkpoint.root,
+                    current_epoch_shuffling_id: junk_shuffling_id.clone(),
+                    next_epoch_shuffling_id: junk_shuffling_id.clone(),
+                    justified_checkpoint: genesis_checkpoint,
+                    finalized_checkpoint: genesis_checkpoint,
+                    execution_status,
+                    unrealized_justified_checkpoint: Some(genesis_checkpoint),
+                    unrealized_finalized_checkpoint: Some(genesis_checkpoint),
+                },
+                Slot::new(i as u64),
+            )
+            .unwrap();
+        }
+
+        // One committee is worth 10 validators. Give the block at slot 1 a strong vote, the block
+        // at slot 2 a weak vote and the block at slot 3 no votes.
+        for validator_index in 0..20 {
+            fc.process_attestation(validator_index, hash_from_index(1), Epoch::new(0))
+                .unwrap();
+        }
+        fc.process_attestation(20, hash_from_index(2), Epoch::new(0))
+            .unwrap();
+
+        let current_slot = Slot::new(4);
+        let balances =
+            JustifiedBalances::from_effective_balances(vec![BALANCE; VALIDATOR_COUNT]).unwrap();
+        let head = fc
+            .find_head::<MainnetEthSpec>(
+                genesis_checkpoint,
+                genesis_checkpoint,
+                &balances,
+                Hash256::zero(),
+                &BTreeSet::new(),
+                current_slot,
+                &spec,
+            )
+            .unwrap();
+        assert_eq!(head, hash_from_index(3));
+
+        let mut params = ReOrgSimulationParams {
+            re_org_head_threshold: ReOrgThreshold(20),
+            re_org_parent_threshold: ReOrgThreshold(160),
+            proposer_score_boost: 40,
+            max_re_org_depth: 1,
+        };
+        let simulate = |params: &ReOrgSimulationParams| {
+            fc.simulate_proposer_head::<MainnetEthSpec>(
+                current_slot,
+                head,
+                &balances,
+                params,
+                &DisallowedReOrgOffsets::default(),
+                Epoch::new(2),
+            )
+        };
+
+        // The parent of the head is too weak to build upon.
+        assert!(matches!(
+            simulate(&params),
+            Err(ProposerHeadError::DoNotReOrg(
+                DoNotReOrg::ParentNotStrong { .. }
+            ))
+        ));
+
+        // Building on the grandparent orphans two weak blocks.
+        params.max_re_org_depth = 2;
+        assert_eq!(
+            simulate(&params),
+            Ok(SimulatedReOrg {
+                parent_root: hash_from_index(1),
+                depth: 2,
+            })
+        );
+
+        // The proposer boost must outweigh the orphaned blocks.
+        params.proposer_score_boost = 5;
+        assert!(matches!(
+            simulate(&params),
+            Err(ProposerHeadError::DoNotReOrg(
+                DoNotReOrg::InsufficientProposerBoost { .. }
+            ))
+        ));
+    }
+
     /// This test covers an interesting case where a block can be a descendant
     /// of the finalized *block*, but not a descenant of the finalized
     /// *checkpoint*.
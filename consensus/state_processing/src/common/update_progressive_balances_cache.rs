@@ -3,8 +3,11 @@ use crate::metrics::{
     self, PARTICIPATION_CURR_EPOCH_TARGET_ATTESTING_GWEI_PROGRESSIVE_TOTAL,
     PARTICIPATION_PREV_EPOCH_TARGET_ATTESTING_GWEI_PROGRESSIVE_TOTAL,
 };
+use crate::parallelism::map_validator_chunks;
 use crate::{BlockProcessingError, EpochProcessingError};
 use metrics::set_gauge;
+use safe_arith::SafeArith;
+use types::consts::altair::NUM_FLAG_INDICES;
 use types::{
     is_progressive_balances_enabled, BeaconState, BeaconStateError, ChainSpec, Epoch,
     EpochTotalBalances, EthSpec, ParticipationFlags, ProgressiveBalancesCache, Validator,
@@ -28,30 +31,52 @@ pub fn initialize_progressive_balances_cache<E: EthSpec>(
     // the current and previous epoch.
     let current_epoch = state.current_epoch();
     let previous_epoch = state.previous_epoch();
-    let mut previous_epoch_cache = EpochTotalBalances::new(spec);
-    let mut current_epoch_cache = EpochTotalBalances::new(spec);
-    for ((validator, current_epoch_flags), previous_epoch_flags) in state
-        .validators()
-        .iter()
-        .zip(state.current_epoch_participation()?)
-        .zip(state.previous_epoch_participation()?)
-    {
-        // Exclude slashed validators. We are calculating *unslashed* participating totals.
-        if validator.slashed {
-            continue;
+    let validators = state.validators();
+    let current_epoch_participation = state.current_epoch_participation()?;
+    let previous_epoch_participation = state.previous_epoch_participation()?;
+    let chunks = map_validator_chunks(validators.len(), |chunk| {
+        let mut previous_epoch_totals = [0; NUM_FLAG_INDICES];
+        let mut current_epoch_totals = [0; NUM_FLAG_INDICES];
+        for ((validator, current_epoch_flags), previous_epoch_flags) in validators
+            .iter_from(chunk.start)?
+            .zip(current_epoch_participation.iter_from(chunk.start)?)
+            .zip(previous_epoch_participation.iter_from(chunk.start)?)
+            .take(chunk.len())
+        {
+            // Exclude slashed validators. We are calculating *unslashed* participating totals.
+            if validator.slashed {
+                continue;
+            }
+
+            // Update current epoch flag balances.
+            if validator.is_active_at(current_epoch) {
+                update_flag_totals(&mut current_epoch_totals, *current_epoch_flags, validator)?;
+            }
+            // Update previous epoch flag balances.
+            if validator.is_active_at(previous_epoch) {
+                update_flag_totals(&mut previous_epoch_totals, *previous_epoch_flags, validator)?;
+            }
         }
+        Ok::<_, BeaconStateError>((previous_epoch_totals, current_epoch_totals))
+    });
 
-        // Update current epoch flag balances.
-        if validator.is_active_at(current_epoch) {
-            update_flag_total_balances(&mut current_epoch_cache, *current_epoch_flags, validator)?;
+    let mut previous_epoch_cache = EpochTotalBalances::new(spec);
+    let mut current_epoch_cache = EpochTotalBalances::new(spec);
+    for chunk in chunks {
+        let (previous_epoch_totals, current_epoch_totals) = chunk?;
+        for (balance, total) in previous_epoch_cache
+            .total_flag_balances
+            .iter_mut()
+            .zip(previous_epoch_totals)
+        {
+            balance.safe_add_assign(total)?;
         }
-        // Update previous epoch flag balances.
-        if validator.is_active_at(previous_epoch) {
-            update_flag_total_balances(
-                &mut previous_epoch_cache,
-                *previous_epoch_flags,
-                validator,
-            )?;
+        for (balance, total) in current_epoch_cache
+            .total_flag_balances
+            .iter_mut()
+            .zip(current_epoch_totals)
+        {
+            balance.safe_add_assign(total)?;
         }
     }
 
@@ -72,15 +97,15 @@ pub fn initialize_progressive_balances_cache<E: EthSpec>(
 /// Pre-conditions:
 ///
 /// - `validator` must not be slashed
-/// - the `participation_flags` must be for `validator` in the same epoch as the `total_balances`
-fn update_flag_total_balances(
-    total_balances: &mut EpochTotalBalances,
+/// - the `participation_flags` must be for `validator` in the same epoch as the `flag_totals`
+fn update_flag_totals(
+    flag_totals: &mut [u64; NUM_FLAG_INDICES],
     participation_flags: ParticipationFlags,
     validator: &Validator,
 ) -> Result<(), BeaconStateError> {
-    for (flag, balance) in total_balances.total_flag_balances.iter_mut().enumerate() {
+    for (flag, total) in flag_totals.iter_mut().enumerate() {
         if participation_flags.has_flag(flag)? {
-            balance.safe_add_assign(validator.effective_balance)?;
+            total.safe_add_assign(validator.effective_balance)?;
         }
     }
     Ok(())
@@ -2,10 +2,12 @@ use crate::common::altair::BaseRewardPerIncrement;
 use crate::common::base::SqrtTotalActiveBalance;
 use crate::common::{altair, base};
 use crate::metrics;
+use crate::parallelism::map_validator_chunks;
 use safe_arith::SafeArith;
 use types::epoch_cache::{EpochCache, EpochCacheError, EpochCacheKey};
 use types::{
-    ActivationQueue, BeaconState, ChainSpec, EthSpec, FixedBytesExtended, ForkName, Hash256,
+    ActivationQueue, BeaconState, BeaconStateError, ChainSpec, EthSpec, FixedBytesExtended,
+    ForkName, Hash256,
 };
 
 /// Precursor to an `EpochCache`.
@@ -151,15 +153,29 @@ pub fn initialize_epoch_cache<E: EthSpec>(
     let total_active_balance = state.get_total_active_balance_at_epoch(current_epoch)?;
 
     // Collect effective balances and compute activation queue.
-    let mut effective_balances = Vec::with_capacity(state.validators().len());
-    let mut activation_queue = ActivationQueue::default();
+    let validators = state.validators();
+    let chunks = map_validator_chunks(validators.len(), |chunk| {
+        let mut effective_balances = Vec::with_capacity(chunk.len());
+        let mut activation_queue = ActivationQueue::default();
+
+        let validators_iter = validators.iter_from(chunk.start)?;
 
-    for (index, validator) in state.validators().iter().enumerate() {
-        effective_balances.push(validator.effective_balance);
+        for (index, validator) in chunk.zip(validators_iter) {
+            effective_balances.push(validator.effective_balance);
 
-        // Add to speculative activation queue.
-        activation_queue
-            .add_if_could_be_eligible_for_activation(index, validator, next_epoch, spec);
+            // Add to speculative activation queue.
+            activation_queue
+                .add_if_could_be_eligible_for_activation(index, validator, next_epoch, spec);
+        }
+        Ok::<_, BeaconStateError>((effective_balances, activation_queue))
+    });
+
+    let mut effective_balances = Vec::with_capacity(validators.len());
+    let mut activation_queue = ActivationQueue::default();
+    for chunk in chunks {
+        let (chunk_effective_balances, chunk_activation_queue) = chunk?;
+        effective_balances.extend(chunk_effective_balances);
+        activation_queue.merge(chunk_activation_queue);
     }
 
     // Compute base rewards.
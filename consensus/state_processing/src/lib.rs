@@ -22,6 +22,7 @@ pub mod common;
 pub mod consensus_context;
 pub mod epoch_cache;
 pub mod genesis;
+pub mod parallelism;
 pub mod per_block_processing;
 pub mod per_epoch_processing;
 pub mod per_slot_processing;
@@ -36,6 +37,7 @@ pub use genesis::{
     eth2_genesis_time, initialize_beacon_state_from_eth1, is_valid_genesis_state,
     process_activations,
 };
+pub use parallelism::set_epoch_processing_threads;
 pub use per_block_processing::{
     block_signature_verifier, errors::BlockProcessingError, per_block_processing, signature_sets,
     BlockSignatureStrategy, BlockSignatureVerifier, VerifyBlockRoot, VerifySignatures,
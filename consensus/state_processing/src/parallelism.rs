@@ -0,0 +1,85 @@
+//! Optional parallelism for the parts of epoch processing which iterate over every validator.
+//!
+//! By default these computations run on the calling thread. Calling
+//! `set_epoch_processing_threads` with more than one thread runs them on a dedicated rayon pool of
+//! that size instead, which reduces the time spent at epoch boundaries for large validator sets.
+use rayon::prelude::*;
+use rayon::{ThreadPool, ThreadPoolBuilder};
+use std::ops::Range;
+use std::sync::OnceLock;
+
+/// The number of validators processed by each task.
+const VALIDATOR_CHUNK_SIZE: usize = 16_384;
+
+static EPOCH_PROCESSING_POOL: OnceLock<ThreadPool> = OnceLock::new();
+
+/// Use `threads` threads for epoch processing computations.
+///
+/// Values of 0 or 1 disable parallelism. This can only be set once per process.
+pub fn set_epoch_processing_threads(threads: usize) -> Result<(), String> {
+    if threads <= 1 {
+        return Ok(());
+    }
+    let pool = ThreadPoolBuilder::new()
+        .num_threads(threads)
+        .thread_name(|i| format!("epoch_processing_{i}"))
+        .build()
+        .map_err(|e| format!("Unable to build epoch processing thread pool: {e:?}"))?;
+    EPOCH_PROCESSING_POOL
+        .set(pool)
+        .map_err(|_| "Epoch processing threads have already been set".to_string())
+}
+
+/// Returns the number of threads used for epoch processing computations.
+pub fn epoch_processing_threads() -> usize {
+    EPOCH_PROCESSING_POOL
+        .get()
+        .map_or(1, ThreadPool::current_num_threads)
+}
+
+/// Apply `f` to consecutive chunks of the validator indices `0..num_validators`, returning the
+/// results in order.
+///
+/// The chunks are processed in parallel if `set_epoch_processing_threads` has been called.
+pub(crate) fn map_validator_chunks<T, F>(num_validators: usize, f: F) -> Vec<T>
+where
+    T: Send,
+    F: Fn(Range<usize>) -> T + Sync,
+{
+    let chunks = (0..num_validators)
+        .step_by(VALIDATOR_CHUNK_SIZE)
+        .map(|start| {
+            start
+                ..start
+                    .saturating_add(VALIDATOR_CHUNK_SIZE)
+                    .min(num_validators)
+        });
+
+    match EPOCH_PROCESSING_POOL.get() {
+        Some(pool) => {
+            let chunks = chunks.collect::<Vec<_>>();
+            pool.install(|| chunks.into_par_iter().map(&f).collect())
+        }
+        None => chunks.map(f).collect(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn chunks_cover_all_validators() {
+        for num_validators in [0, 1, VALIDATOR_CHUNK_SIZE, 3 * VALIDATOR_CHUNK_SIZE + 7] {
+            let chunks = map_validator_chunks(num_validators, |chunk| chunk);
+            assert_eq!(
+                chunks.iter().map(ExactSizeIterator::len).sum::<usize>(),
+                num_validators
+            );
+            assert!(chunks.windows(2).all(|w| w[0].end == w[1].start));
+            assert!(chunks
+                .iter()
+                .all(|chunk| chunk.len() <= VALIDATOR_CHUNK_SIZE));
+        }
+    }
+}
@@ -4,6 +4,7 @@ use crate::{
         update_progressive_balances_cache::initialize_progressive_balances_cache,
     },
     epoch_cache::{initialize_epoch_cache, PreEpochCache},
+    parallelism::{epoch_processing_threads, map_validator_chunks},
     per_epoch_processing::{Delta, Error, ParticipationEpochSummary},
 };
 use itertools::izip;
@@ -16,9 +17,9 @@ use types::{
         TIMELY_TARGET_FLAG_INDEX, WEIGHT_DENOMINATOR,
     },
     milhouse::Cow,
-    ActivationQueue, BeaconState, BeaconStateError, ChainSpec, Checkpoint, Epoch, EthSpec,
-    ExitCache, ForkName, List, ParticipationFlags, PendingBalanceDeposit, ProgressiveBalancesCache,
-    RelativeEpoch, Unsigned, Validator,
+    ActivationQueue, BeaconState, BeaconStateError, ChainSpec, Checkpoint, Epoch, EpochCache,
+    EthSpec, ExitCache, ForkName, List, ParticipationFlags, PendingBalanceDeposit,
+    ProgressiveBalancesCache, RelativeEpoch, Unsigned, Validator,
 };
 
 pub struct SinglePassConfig {
@@ -117,6 +118,39 @@ pub struct ValidatorInfo {
 }
 
 impl ValidatorInfo {
+    fn new(
+        index: usize,
+        validator: &Validator,
+        previous_epoch_participation: ParticipationFlags,
+        current_epoch_participation: ParticipationFlags,
+        epoch_cache: &EpochCache,
+        previous_epoch: Epoch,
+        current_epoch: Epoch,
+    ) -> Result<Self, Error> {
+        let is_active_current_epoch = validator.is_active_at(current_epoch);
+        let is_active_previous_epoch = validator.is_active_at(previous_epoch);
+        let is_eligible = is_active_previous_epoch
+            || (validator.slashed && previous_epoch.safe_add(1)? < validator.withdrawable_epoch);
+
+        let base_reward = if is_eligible {
+            epoch_cache.get_base_reward(index)?
+        } else {
+            0
+        };
+
+        Ok(Self {
+            index,
+            effective_balance: validator.effective_balance,
+            base_reward,
+            is_eligible,
+            is_slashed: validator.slashed,
+            is_active_current_epoch,
+            is_active_previous_epoch,
+            previous_epoch_participation,
+            current_epoch_participation,
+        })
+    }
+
     #[inline]
     pub fn is_unslashed_participating_index(&self, flag_index: usize) -> Result<bool, Error> {
         Ok(self.is_active_previous_epoch
@@ -214,6 +248,71 @@ pub fn process_epoch_single_pass<E: EthSpec>(
     };
     let effective_balances_ctxt = &EffectiveBalancesContext::new(spec)?;
 
+    // The inactivity updates and rewards and penalties only depend on the validator itself, so
+    // when epoch processing threads are configured they are computed in parallel up-front and
+    // applied in the single pass below.
+    let mut precomputed_updates = if epoch_processing_threads() > 1
+        && current_epoch != E::genesis_epoch()
+        && (conf.inactivity_updates || conf.rewards_and_penalties)
+    {
+        let validators = &*validators;
+        let inactivity_scores = &*inactivity_scores;
+        let epoch_cache = &*epoch_cache;
+        let chunks = map_validator_chunks(num_validators, |chunk| {
+            let mut updates = Vec::with_capacity(chunk.len());
+            for (
+                index,
+                validator,
+                &previous_epoch_participation,
+                &current_epoch_participation,
+                &inactivity_score,
+            ) in izip!(
+                chunk.clone(),
+                validators.iter_from(chunk.start)?,
+                previous_epoch_participation.iter_from(chunk.start)?,
+                current_epoch_participation.iter_from(chunk.start)?,
+                inactivity_scores.iter_from(chunk.start)?,
+            ) {
+                let validator_info = &ValidatorInfo::new(
+                    index,
+                    validator,
+                    previous_epoch_participation,
+                    current_epoch_participation,
+                    epoch_cache,
+                    previous_epoch,
+                    current_epoch,
+                )?;
+                let inactivity_score = if conf.inactivity_updates {
+                    new_inactivity_score(inactivity_score, validator_info, state_ctxt, spec)?
+                } else {
+                    inactivity_score
+                };
+                let delta = if conf.rewards_and_penalties {
+                    get_reward_and_penalty_delta(
+                        inactivity_score,
+                        validator_info,
+                        rewards_ctxt,
+                        state_ctxt,
+                        spec,
+                    )?
+                } else {
+                    Delta::default()
+                };
+                updates.push((inactivity_score, delta));
+            }
+            Ok::<_, Error>(updates)
+        });
+        Some(
+            chunks
+                .into_iter()
+                .collect::<Result<Vec<_>, _>>()?
+                .into_iter()
+                .flatten(),
+        )
+    } else {
+        None
+    };
+
     // Iterate over the validators and related fields in one pass.
     let mut validators_iter = validators.iter_cow();
     let mut balances_iter = balances.iter_cow();
@@ -234,30 +333,25 @@ pub fn process_epoch_single_pass<E: EthSpec>(
             .next_cow()
             .ok_or(BeaconStateError::UnknownValidator(index))?;
 
-        let is_active_current_epoch = validator.is_active_at(current_epoch);
-        let is_active_previous_epoch = validator.is_active_at(previous_epoch);
-        let is_eligible = is_active_previous_epoch
-            || (validator.slashed && previous_epoch.safe_add(1)? < validator.withdrawable_epoch);
-
-        let base_reward = if is_eligible {
-            epoch_cache.get_base_reward(index)?
-        } else {
-            0
-        };
-
-        let validator_info = &ValidatorInfo {
+        let validator_info = &ValidatorInfo::new(
             index,
-            effective_balance: validator.effective_balance,
-            base_reward,
-            is_eligible,
-            is_slashed: validator.slashed,
-            is_active_current_epoch,
-            is_active_previous_epoch,
+            &validator,
             previous_epoch_participation,
             current_epoch_participation,
-        };
+            epoch_cache,
+            previous_epoch,
+            current_epoch,
+        )?;
 
-        if current_epoch != E::genesis_epoch() {
+        if let Some(precomputed_updates) = &mut precomputed_updates {
+            let (new_inactivity_score, delta) = precomputed_updates
+                .next()
+                .ok_or(BeaconStateError::UnknownValidator(index))?;
+            if new_inactivity_score != *inactivity_score {
+                *inactivity_score.make_mut()? = new_inactivity_score;
+            }
+            apply_delta(&mut balance, delta)?;
+        } else if current_epoch != E::genesis_epoch() {
             // `process_inactivity_updates`
             if conf.inactivity_updates {
                 process_single_inactivity_update(
@@ -391,31 +485,43 @@ fn process_single_inactivity_update(
     state_ctxt: &StateContext,
     spec: &ChainSpec,
 ) -> Result<(), Error> {
+    let new_inactivity_score =
+        new_inactivity_score(**inactivity_score, validator_info, state_ctxt, spec)?;
+    // Avoid mutating when the inactivity score is unchanged -- the common case.
+    if new_inactivity_score != **inactivity_score {
+        *inactivity_score.make_mut()? = new_inactivity_score;
+    }
+    Ok(())
+}
+
+/// Compute the inactivity score of a validator after `process_inactivity_updates`.
+fn new_inactivity_score(
+    mut inactivity_score: u64,
+    validator_info: &ValidatorInfo,
+    state_ctxt: &StateContext,
+    spec: &ChainSpec,
+) -> Result<u64, Error> {
     if !validator_info.is_eligible {
-        return Ok(());
+        return Ok(inactivity_score);
     }
 
     // Increase inactivity score of inactive validators
     if validator_info.is_unslashed_participating_index(TIMELY_TARGET_FLAG_INDEX)? {
-        // Avoid mutating when the inactivity score is 0 and can't go any lower -- the common
-        // case.
-        if **inactivity_score == 0 {
-            return Ok(());
+        if inactivity_score == 0 {
+            return Ok(0);
         }
-        inactivity_score.make_mut()?.safe_sub_assign(1)?;
+        inactivity_score.safe_sub_assign(1)?;
     } else {
-        inactivity_score
-            .make_mut()?
-            .safe_add_assign(spec.inactivity_score_bias)?;
+        inactivity_score.safe_add_assign(spec.inactivity_score_bias)?;
     }
 
     // Decrease the score of all validators for forgiveness when not during a leak
     if !state_ctxt.is_in_inactivity_leak {
-        let deduction = min(spec.inactivity_score_recovery_rate, **inactivity_score);
-        inactivity_score.make_mut()?.safe_sub_assign(deduction)?;
+        let deduction = min(spec.inactivity_score_recovery_rate, inactivity_score);
+        inactivity_score.safe_sub_assign(deduction)?;
     }
 
-    Ok(())
+    Ok(inactivity_score)
 }
 
 fn process_single_reward_and_penalty(
@@ -426,11 +532,38 @@ fn process_single_reward_and_penalty(
     state_ctxt: &StateContext,
     spec: &ChainSpec,
 ) -> Result<(), Error> {
-    if !validator_info.is_eligible {
-        return Ok(());
+    let delta = get_reward_and_penalty_delta(
+        *inactivity_score,
+        validator_info,
+        rewards_ctxt,
+        state_ctxt,
+        spec,
+    )?;
+    apply_delta(balance, delta)
+}
+
+fn apply_delta(balance: &mut Cow<u64>, delta: Delta) -> Result<(), Error> {
+    if delta.rewards != 0 || delta.penalties != 0 {
+        let balance = balance.make_mut()?;
+        balance.safe_add_assign(delta.rewards)?;
+        *balance = balance.saturating_sub(delta.penalties);
     }
+    Ok(())
+}
 
+/// Compute the change to a validator's balance from `process_rewards_and_penalties`.
+fn get_reward_and_penalty_delta(
+    inactivity_score: u64,
+    validator_info: &ValidatorInfo,
+    rewards_ctxt: &RewardsAndPenaltiesContext,
+    state_ctxt: &StateContext,
+    spec: &ChainSpec,
+) -> Result<Delta, Error> {
     let mut delta = Delta::default();
+    if !validator_info.is_eligible {
+        return Ok(delta);
+    }
+
     for flag_index in 0..NUM_FLAG_INDICES {
         get_flag_index_delta(
             &mut delta,
@@ -443,18 +576,12 @@ fn process_single_reward_and_penalty(
     get_inactivity_penalty_delta(
         &mut delta,
         validator_info,
-        inactivity_score,
+        &inactivity_score,
         state_ctxt,
         spec,
     )?;
 
-    if delta.rewards != 0 || delta.penalties != 0 {
-        let balance = balance.make_mut()?;
-        balance.safe_add_assign(delta.rewards)?;
-        *balance = balance.saturating_sub(delta.penalties);
-    }
-
-    Ok(())
+    Ok(delta)
 }
 
 fn get_flag_index_delta(
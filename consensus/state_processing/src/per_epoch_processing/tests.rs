@@ -1,10 +1,12 @@
 #![cfg(test)]
 use crate::per_epoch_processing::process_epoch;
+use crate::set_epoch_processing_threads;
 use beacon_chain::test_utils::BeaconChainHarness;
 use beacon_chain::types::{EthSpec, MinimalEthSpec};
 use bls::{FixedBytesExtended, Hash256};
 use env_logger::{Builder, Env};
-use types::Slot;
+use std::sync::Arc;
+use types::{ForkName, Slot};
 
 #[tokio::test]
 async fn runs_without_error() {
@@ -38,6 +40,56 @@ async fn runs_without_error() {
     process_epoch(&mut new_head_state, &spec).unwrap();
 }
 
+// Check that the parallel inactivity and rewards computations match the sequential ones.
+#[tokio::test]
+async fn parallel_single_pass_matches_sequential() {
+    let spec = ForkName::Altair.make_genesis_spec(MinimalEthSpec::default_spec());
+    let harness = BeaconChainHarness::builder(MinimalEthSpec)
+        .spec(Arc::new(spec.clone()))
+        .deterministic_keypairs(16)
+        .fresh_ephemeral_store()
+        .build();
+    harness.advance_slot();
+
+    // Only half of the validators attest, so that they accrue different rewards, penalties and
+    // inactivity scores.
+    let target_slot =
+        (MinimalEthSpec::genesis_epoch() + 4).end_slot(MinimalEthSpec::slots_per_epoch());
+    let state = harness.get_current_state();
+    harness
+        .add_attested_blocks_at_slots(
+            state,
+            Hash256::zero(),
+            (1..target_slot.as_u64())
+                .map(Slot::new)
+                .collect::<Vec<_>>()
+                .as_slice(),
+            (0..8).collect::<Vec<_>>().as_slice(),
+        )
+        .await;
+
+    let mut sequential_state = harness.get_current_state();
+    let mut parallel_state = sequential_state.clone();
+
+    process_epoch(&mut sequential_state, &spec).unwrap();
+    set_epoch_processing_threads(2).unwrap();
+    process_epoch(&mut parallel_state, &spec).unwrap();
+
+    assert_ne!(
+        sequential_state.balances().get(0),
+        sequential_state.balances().get(15)
+    );
+    assert_eq!(sequential_state.balances(), parallel_state.balances());
+    assert_eq!(
+        sequential_state.inactivity_scores().unwrap(),
+        parallel_state.inactivity_scores().unwrap()
+    );
+    assert_eq!(
+        sequential_state.canonical_root().unwrap(),
+        parallel_state.canonical_root().unwrap()
+    );
+}
+
 #[cfg(not(debug_assertions))]
 mod release_tests {
     use super::*;
@@ -27,6 +27,11 @@ impl ActivationQueue {
         }
     }
 
+    /// Add all the validators from `other` to this queue.
+    pub fn merge(&mut self, other: Self) {
+        self.queue.extend(other.queue);
+    }
+
     /// Determine the final activation queue after accounting for finalization & the churn limit.
     pub fn get_validators_eligible_for_activation(
         &self,
@@ -864,6 +864,8 @@ impl<E: EthSpec, Payload: AbstractExecPayload<E>> ForkVersionDeserialize
         ))
     }
 }
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum BlockImportSource {
     Gossip,
     Lookup,
@@ -3,6 +3,7 @@ use crate::*;
 use derivative::Derivative;
 use merkle_proof::{MerkleTree, MerkleTreeError};
 use metastruct::metastruct;
+use rayon::prelude::*;
 use serde::{Deserialize, Serialize};
 use ssz_derive::{Decode, Encode};
 use std::marker::PhantomData;
@@ -202,46 +203,11 @@ impl<'a, E: EthSpec, Payload: AbstractExecPayload<E>> BeaconBlockBodyRef<'a, E,
             Self::Base(_) | Self::Altair(_) | Self::Bellatrix(_) | Self::Capella(_) => {
                 Err(Error::IncorrectStateVariant)
             }
-            Self::Deneb(_) | Self::Electra(_) => {
-                // We compute the branches by generating 2 merkle trees:
-                // 1. Merkle tree for the `blob_kzg_commitments` List object
-                // 2. Merkle tree for the `BeaconBlockBody` container
-                // We then merge the branches for both the trees all the way up to the root.
-
-                // Part1 (Branches for the subtree rooted at `blob_kzg_commitments`)
-                //
-                // Branches for `blob_kzg_commitments` without length mix-in
-                let blob_leaves = self
-                    .blob_kzg_commitments()?
-                    .iter()
-                    .map(|commitment| commitment.tree_hash_root())
-                    .collect::<Vec<_>>();
-                let depth = E::max_blob_commitments_per_block()
-                    .next_power_of_two()
-                    .ilog2();
-                let tree = MerkleTree::create(&blob_leaves, depth as usize);
-                let (_, mut proof) = tree
-                    .generate_proof(index, depth as usize)
-                    .map_err(Error::MerkleTreeError)?;
-
-                // Add the branch corresponding to the length mix-in.
-                let length = blob_leaves.len();
-                let usize_len = std::mem::size_of::<usize>();
-                let mut length_bytes = [0; BYTES_PER_CHUNK];
-                length_bytes
-                    .get_mut(0..usize_len)
-                    .ok_or(Error::MerkleTreeError(MerkleTreeError::PleaseNotifyTheDevs))?
-                    .copy_from_slice(&length.to_le_bytes());
-                let length_root = Hash256::from_slice(length_bytes.as_slice());
-                proof.push(length_root);
-
-                // Part 2
-                // Branches for `BeaconBlockBody` container
-                // Join the proofs for the subtree and the main tree
-                proof.extend_from_slice(kzg_commitments_proof);
-
-                Ok(FixedVector::new(proof)?)
-            }
+            Self::Deneb(_) | Self::Electra(_) => complete_kzg_commitment_merkle_proof::<E>(
+                self.blob_kzg_commitments()?,
+                index,
+                kzg_commitments_proof,
+            ),
         }
     }
 
@@ -258,6 +224,27 @@ impl<'a, E: EthSpec, Payload: AbstractExecPayload<E>> BeaconBlockBodyRef<'a, E,
         Ok(FixedVector::new(proof)?)
     }
 
+    /// Calculate the KZG commitment merkle proofs of all blobs in the block.
+    ///
+    /// The proof for the `blob_kzg_commitments` field is shared by all blobs, so it is computed
+    /// once and the proofs for each blob are then completed in parallel.
+    pub fn kzg_commitment_merkle_proofs(
+        &self,
+    ) -> Result<Vec<FixedVector<Hash256, E::KzgCommitmentInclusionProofDepth>>, Error> {
+        let blob_kzg_commitments = self.blob_kzg_commitments()?;
+        let kzg_commitments_proof = self.kzg_commitments_merkle_proof()?;
+        (0..blob_kzg_commitments.len())
+            .into_par_iter()
+            .map(|index| {
+                complete_kzg_commitment_merkle_proof::<E>(
+                    blob_kzg_commitments,
+                    index,
+                    &kzg_commitments_proof,
+                )
+            })
+            .collect()
+    }
+
     pub fn block_body_merkle_proof(&self, generalized_index: usize) -> Result<Vec<Hash256>, Error> {
         let field_index = match generalized_index {
             light_client_update::EXECUTION_PAYLOAD_INDEX => {
@@ -394,6 +381,52 @@ impl<E: EthSpec, Payload: AbstractExecPayload<E>> BeaconBlockBodyRef<'_, E, Payl
     }
 }
 
+/// Produces the proof of inclusion for the `KzgCommitment` at `index` in `blob_kzg_commitments`
+/// using an existing proof for the `blob_kzg_commitments` field.
+fn complete_kzg_commitment_merkle_proof<E: EthSpec>(
+    blob_kzg_commitments: &KzgCommitments<E>,
+    index: usize,
+    kzg_commitments_proof: &[Hash256],
+) -> Result<FixedVector<Hash256, E::KzgCommitmentInclusionProofDepth>, Error> {
+    // We compute the branches by generating 2 merkle trees:
+    // 1. Merkle tree for the `blob_kzg_commitments` List object
+    // 2. Merkle tree for the `BeaconBlockBody` container
+    // We then merge the branches for both the trees all the way up to the root.
+
+    // Part1 (Branches for the subtree rooted at `blob_kzg_commitments`)
+    //
+    // Branches for `blob_kzg_commitments` without length mix-in
+    let blob_leaves = blob_kzg_commitments
+        .iter()
+        .map(|commitment| commitment.tree_hash_root())
+        .collect::<Vec<_>>();
+    let depth = E::max_blob_commitments_per_block()
+        .next_power_of_two()
+        .ilog2();
+    let tree = MerkleTree::create(&blob_leaves, depth as usize);
+    let (_, mut proof) = tree
+        .generate_proof(index, depth as usize)
+        .map_err(Error::MerkleTreeError)?;
+
+    // Add the branch corresponding to the length mix-in.
+    let length = blob_leaves.len();
+    let usize_len = std::mem::size_of::<usize>();
+    let mut length_bytes = [0; BYTES_PER_CHUNK];
+    length_bytes
+        .get_mut(0..usize_len)
+        .ok_or(Error::MerkleTreeError(MerkleTreeError::PleaseNotifyTheDevs))?
+        .copy_from_slice(&length.to_le_bytes());
+    let length_root = Hash256::from_slice(length_bytes.as_slice());
+    proof.push(length_root);
+
+    // Part 2
+    // Branches for `BeaconBlockBody` container
+    // Join the proofs for the subtree and the main tree
+    proof.extend_from_slice(kzg_commitments_proof);
+
+    Ok(FixedVector::new(proof)?)
+}
+
 // We can convert pre-Bellatrix block bodies without payloads into block bodies "with" payloads.
 impl<E: EthSpec> From<BeaconBlockBodyBase<E, BlindedPayload<E>>>
     for BeaconBlockBodyBase<E, FullPayload<E>>
@@ -181,6 +181,35 @@ impl<E: EthSpec> BlobSidecar<E> {
         })
     }
 
+    /// Construct a sidecar using a complete inclusion proof which was computed in advance, e.g.
+    /// with `BeaconBlockBodyRef::kzg_commitment_merkle_proofs`.
+    pub fn new_with_inclusion_proof(
+        index: usize,
+        blob: Blob<E>,
+        signed_block: &SignedBeaconBlock<E>,
+        signed_block_header: SignedBeaconBlockHeader,
+        kzg_commitment_inclusion_proof: FixedVector<Hash256, E::KzgCommitmentInclusionProofDepth>,
+        kzg_proof: KzgProof,
+    ) -> Result<Self, BlobSidecarError> {
+        let expected_kzg_commitments = signed_block
+            .message()
+            .body()
+            .blob_kzg_commitments()
+            .map_err(|_e| BlobSidecarError::PreDeneb)?;
+        let kzg_commitment = *expected_kzg_commitments
+            .get(index)
+            .ok_or(BlobSidecarError::MissingKzgCommitment)?;
+
+        Ok(Self {
+            index: index as u64,
+            blob,
+            kzg_commitment,
+            kzg_proof,
+            signed_block_header,
+            kzg_commitment_inclusion_proof,
+        })
+    }
+
     pub fn id(&self) -> BlobIdentifier {
         BlobIdentifier {
             block_root: self.block_root(),
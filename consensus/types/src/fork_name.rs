@@ -41,6 +41,18 @@ impl ForkName {
         ]
     }
 
+    /// The lowercase name of the fork, as used in the `Display` impl and metric labels.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ForkName::Base => "phase0",
+            ForkName::Altair => "altair",
+            ForkName::Bellatrix => "bellatrix",
+            ForkName::Capella => "capella",
+            ForkName::Deneb => "deneb",
+            ForkName::Electra => "electra",
+        }
+    }
+
     pub fn latest() -> ForkName {
         // This unwrap is safe as long as we have 1+ forks. It is tested below.
         *ForkName::list_all().last().unwrap()
@@ -222,14 +234,7 @@ impl FromStr for ForkName {
 
 impl Display for ForkName {
     fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), fmt::Error> {
-        match self {
-            ForkName::Base => "phase0".fmt(f),
-            ForkName::Altair => "altair".fmt(f),
-            ForkName::Bellatrix => "bellatrix".fmt(f),
-            ForkName::Capella => "capella".fmt(f),
-            ForkName::Deneb => "deneb".fmt(f),
-            ForkName::Electra => "electra".fmt(f),
-        }
+        self.as_str().fmt(f)
     }
 }
 
@@ -287,6 +292,14 @@ mod test {
         assert!(ForkName::from_str("no_name").is_err());
     }
 
+    #[test]
+    fn fork_name_as_str_round_trip() {
+        for fork in ForkName::list_all() {
+            assert_eq!(fork.as_str(), fork.to_string());
+            assert_eq!(ForkName::from_str(fork.as_str()), Ok(fork));
+        }
+    }
+
     #[test]
     fn fork_name_bellatrix_or_merge() {
         assert_eq!(ForkName::from_str("bellatrix"), Ok(ForkName::Bellatrix));
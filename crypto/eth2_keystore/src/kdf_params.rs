@@ -0,0 +1,211 @@
+//! Configurable parameters for the key derivation function (KDF) used to encrypt new keystores.
+//!
+//! Stronger parameters make an encrypted keystore more expensive to brute-force, at the cost of
+//! making every decryption (e.g., each time the validator client starts) slower.
+
+use crate::json_keystore::{Kdf, Pbkdf2, Prf, Scrypt};
+use crate::keystore::{validate_parameters, DEFAULT_PBKDF2_C};
+use crate::{Error, DKLEN, SALT_SIZE};
+use std::fmt;
+use std::str::FromStr;
+
+/// Keystores whose scrypt parameters require no more than this much memory (`128 * n * r * p`
+/// bytes) are considered fast to decrypt.
+pub const FAST_SCRYPT_MEMORY: u64 = 16 * 1024 * 1024;
+
+/// Keystores with no more than this many pbkdf2 iterations are considered fast to decrypt.
+pub const FAST_PBKDF2_C: u32 = 16_384;
+
+/// The parameters of the KDF used to encrypt a keystore, excluding the salt.
+///
+/// Parses from and displays as `scrypt:n=<N>,r=<R>,p=<P>` or `pbkdf2:c=<C>`. Parameters which
+/// are omitted take their default values, so `scrypt` and `pbkdf2` alone are also valid.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum KdfParams {
+    Scrypt { n: u32, r: u32, p: u32 },
+    Pbkdf2 { c: u32 },
+}
+
+impl Default for KdfParams {
+    /// The parameters of `crate::default_kdf`.
+    fn default() -> Self {
+        Self::default_scrypt()
+    }
+}
+
+impl KdfParams {
+    fn default_scrypt() -> Self {
+        let scrypt = Scrypt::default_scrypt(vec![]);
+        Self::Scrypt {
+            n: scrypt.n,
+            r: scrypt.r,
+            p: scrypt.p,
+        }
+    }
+
+    fn default_pbkdf2() -> Self {
+        Self::Pbkdf2 {
+            c: DEFAULT_PBKDF2_C,
+        }
+    }
+
+    /// Returns a `Kdf` with these parameters and the given `salt`.
+    pub fn kdf(&self, salt: Vec<u8>) -> Kdf {
+        match *self {
+            KdfParams::Scrypt { n, r, p } => Kdf::Scrypt(Scrypt {
+                dklen: DKLEN,
+                n,
+                r,
+                p,
+                salt: salt.into(),
+            }),
+            KdfParams::Pbkdf2 { c } => Kdf::Pbkdf2(Pbkdf2 {
+                c,
+                dklen: DKLEN,
+                prf: Prf::default(),
+                salt: salt.into(),
+            }),
+        }
+    }
+
+    /// Checks that keystores encrypted with these parameters can be decrypted.
+    ///
+    /// Parameters which are valid but weaker than the defaults produce a warning.
+    pub fn validate(&self) -> Result<(), Error> {
+        validate_parameters(&self.kdf(vec![0; SALT_SIZE]))
+    }
+}
+
+impl FromStr for KdfParams {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (function, params) = s.split_once(':').unwrap_or((s, ""));
+        let mut kdf_params = match function {
+            "scrypt" => Self::default_scrypt(),
+            "pbkdf2" => Self::default_pbkdf2(),
+            other => {
+                return Err(format!(
+                    "unsupported kdf function {}, expected scrypt or pbkdf2",
+                    other
+                ))
+            }
+        };
+
+        for param in params.split(',').filter(|param| !param.is_empty()) {
+            let (name, value) = param
+                .split_once('=')
+                .ok_or_else(|| format!("kdf parameter {} must be of the form name=value", param))?;
+            let value = value
+                .parse::<u32>()
+                .map_err(|e| format!("invalid value for kdf parameter {}: {}", name, e))?;
+            match (&mut kdf_params, name) {
+                (KdfParams::Scrypt { n, .. }, "n") => *n = value,
+                (KdfParams::Scrypt { r, .. }, "r") => *r = value,
+                (KdfParams::Scrypt { p, .. }, "p") => *p = value,
+                (KdfParams::Pbkdf2 { c }, "c") => *c = value,
+                _ => return Err(format!("unknown {} parameter {}", function, name)),
+            }
+        }
+
+        kdf_params
+            .validate()
+            .map_err(|e| format!("invalid {} parameters: {:?}", function, e))?;
+        Ok(kdf_params)
+    }
+}
+
+impl fmt::Display for KdfParams {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            KdfParams::Scrypt { n, r, p } => write!(f, "scrypt:n={},r={},p={}", n, r, p),
+            KdfParams::Pbkdf2 { c } => write!(f, "pbkdf2:c={}", c),
+        }
+    }
+}
+
+/// Returns `true` if keys derived with `kdf` are cheap to compute, such that decrypting the
+/// keystore takes a negligible amount of time.
+pub fn is_fast_kdf(kdf: &Kdf) -> bool {
+    match kdf {
+        Kdf::Scrypt(params) => {
+            128 * u64::from(params.n) * u64::from(params.r) * u64::from(params.p)
+                <= FAST_SCRYPT_MEMORY
+        }
+        Kdf::Pbkdf2(params) => params.c <= FAST_PBKDF2_C,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_and_display() {
+        assert_eq!("scrypt".parse(), Ok(KdfParams::default()));
+        assert_eq!(
+            "scrypt:n=16384".parse(),
+            Ok(KdfParams::Scrypt {
+                n: 16384,
+                r: 8,
+                p: 1
+            })
+        );
+        assert_eq!(
+            "pbkdf2".parse(),
+            Ok(KdfParams::Pbkdf2 {
+                c: DEFAULT_PBKDF2_C
+            })
+        );
+
+        for kdf_params in [
+            KdfParams::default(),
+            KdfParams::Scrypt {
+                n: 1024,
+                r: 4,
+                p: 2,
+            },
+            KdfParams::Pbkdf2 { c: 1_000_000 },
+        ] {
+            assert_eq!(kdf_params.to_string().parse(), Ok(kdf_params));
+        }
+    }
+
+    #[test]
+    fn parse_invalid() {
+        for invalid in [
+            "argon2id",
+            "scrypt:c=1",
+            "pbkdf2:n=1024",
+            "scrypt:n",
+            "scrypt:n=abc",
+            // `n` must be a power of two.
+            "scrypt:n=1000",
+            "pbkdf2:c=0",
+            "pbkdf2:c=100000000",
+        ] {
+            assert!(invalid.parse::<KdfParams>().is_err(), "{}", invalid);
+        }
+    }
+
+    #[test]
+    fn fast_kdf() {
+        let salt = vec![0; SALT_SIZE];
+        assert!(!is_fast_kdf(&KdfParams::default().kdf(salt.clone())));
+        assert!(!is_fast_kdf(
+            &KdfParams::Pbkdf2 {
+                c: DEFAULT_PBKDF2_C
+            }
+            .kdf(salt.clone())
+        ));
+        assert!(is_fast_kdf(
+            &KdfParams::Scrypt {
+                n: 16384,
+                r: 8,
+                p: 1
+            }
+            .kdf(salt.clone())
+        ));
+        assert!(is_fast_kdf(&KdfParams::Pbkdf2 { c: 1024 }.kdf(salt)));
+    }
+}
@@ -6,6 +6,7 @@ use crate::json_keystore::{
     Aes128Ctr, ChecksumModule, Cipher, CipherModule, Crypto, EmptyMap, EmptyString, JsonKeystore,
     Kdf, KdfModule, Scrypt, Sha256Checksum, Version,
 };
+use crate::kdf_params::{is_fast_kdf, KdfParams};
 use crate::Uuid;
 use aes::cipher::generic_array::GenericArray;
 use aes::cipher::{NewCipher, StreamCipher};
@@ -169,6 +170,13 @@ impl<'a> KeystoreBuilder<'a> {
         self
     }
 
+    /// Build the keystore using a KDF with the supplied `params` and a new random salt, instead
+    /// of `crate::default_kdf`.
+    pub fn kdf_params(self, params: KdfParams) -> Self {
+        let salt = rand::thread_rng().gen::<[u8; SALT_SIZE]>();
+        self.kdf(params.kdf(salt.to_vec()))
+    }
+
     /// Consumes `self`, returning a `Keystore`.
     pub fn build(self) -> Result<Keystore, Error> {
         Keystore::encrypt(
@@ -306,6 +314,12 @@ impl Keystore {
         &self.json.crypto.kdf.params
     }
 
+    /// Returns `true` if the parameters of the key derivation function make decrypting the
+    /// keystore cheap. See `crate::kdf_params::is_fast_kdf`.
+    pub fn has_fast_kdf(&self) -> bool {
+        is_fast_kdf(self.kdf())
+    }
+
     /// Encodes `self` as a JSON object.
     pub fn to_json_string(&self) -> Result<String, Error> {
         serde_json::to_string(self).map_err(|e| Error::UnableToSerialize(format!("{}", e)))
@@ -530,7 +544,7 @@ fn validate_aes_iv(iv: &[u8]) -> Result<(), Error> {
 
 // Validates the kdf parameters to ensure they are sufficiently secure, in addition to
 // preventing DoS attacks from excessively large parameters.
-fn validate_parameters(kdf: &Kdf) -> Result<(), Error> {
+pub(crate) fn validate_parameters(kdf: &Kdf) -> Result<(), Error> {
     match kdf {
         Kdf::Pbkdf2(params) => {
             // We always compute a derived key of 32 bytes so reject anything that
@@ -2,12 +2,14 @@
 //! [EIP-2335](https://eips.ethereum.org/EIPS/eip-2335).
 
 mod derived_key;
+mod kdf_params;
 mod keystore;
 
 pub mod json_keystore;
 
 pub use bls::ZeroizeHash;
 pub use eth2_key_derivation::PlainText;
+pub use kdf_params::{is_fast_kdf, KdfParams};
 pub use keystore::{
     decrypt, default_kdf, encrypt, keypair_from_secret, Error, Keystore, KeystoreBuilder, DKLEN,
     HASH_SIZE, IV_SIZE, SALT_SIZE,
@@ -6,6 +6,6 @@ pub mod json_wallet;
 pub use bip39;
 pub use validator_path::{KeyType, ValidatorPath, COIN_TYPE, PURPOSE};
 pub use wallet::{
-    recover_validator_secret, recover_validator_secret_from_mnemonic, DerivedKey, Error,
+    recover_validator_secret, recover_validator_secret_from_mnemonic, DerivedKey, Error, KdfParams,
     KeystoreError, PlainText, Uuid, ValidatorKeystores, Wallet, WalletBuilder,
 };
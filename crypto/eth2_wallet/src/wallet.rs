@@ -11,7 +11,7 @@ use eth2_keystore::{
     decrypt, default_kdf, encrypt, keypair_from_secret, Keystore, KeystoreBuilder, IV_SIZE,
     SALT_SIZE,
 };
-pub use eth2_keystore::{Error as KeystoreError, PlainText};
+pub use eth2_keystore::{Error as KeystoreError, KdfParams, PlainText};
 use rand::prelude::*;
 use serde::{Deserialize, Serialize};
 use std::io::{Read, Write};
@@ -105,6 +105,14 @@ impl<'a> WalletBuilder<'a> {
         }
     }
 
+    /// Encrypt the wallet using a KDF with the supplied `params` and a new random salt, instead
+    /// of `eth2_keystore::default_kdf`.
+    pub fn kdf_params(mut self, params: KdfParams) -> Self {
+        let salt = rand::thread_rng().gen::<[u8; SALT_SIZE]>();
+        self.kdf = params.kdf(salt.to_vec());
+        self
+    }
+
     /// Consumes `self`, returning an encrypted `Wallet`.
     pub fn build(self) -> Result<Wallet, Error> {
         Wallet::encrypt(
@@ -185,6 +193,23 @@ impl Wallet {
         wallet_password: &[u8],
         voting_keystore_password: &[u8],
         withdrawal_keystore_password: &[u8],
+    ) -> Result<ValidatorKeystores, Error> {
+        self.next_validator_with_kdf_params(
+            wallet_password,
+            voting_keystore_password,
+            withdrawal_keystore_password,
+            KdfParams::default(),
+        )
+    }
+
+    /// As per `Self::next_validator`, but encrypts the keystores using a KDF with the supplied
+    /// `kdf_params`.
+    pub fn next_validator_with_kdf_params(
+        &mut self,
+        wallet_password: &[u8],
+        voting_keystore_password: &[u8],
+        withdrawal_keystore_password: &[u8],
+        kdf_params: KdfParams,
     ) -> Result<ValidatorKeystores, Error> {
         // Helper closure to reduce code duplication when generating keys.
         //
@@ -197,6 +222,7 @@ impl Wallet {
             let keypair = keypair_from_secret(secret.as_bytes())?;
 
             KeystoreBuilder::new(&keypair, password, format!("{}", path))?
+                .kdf_params(kdf_params)
                 .build()
                 .map_err(Into::into)
         };
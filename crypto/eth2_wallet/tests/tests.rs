@@ -1,8 +1,10 @@
 #![cfg(not(debug_assertions))]
 
+use eth2_keystore::json_keystore::Kdf;
 use eth2_wallet::{
     bip39::{Language, Mnemonic, Seed},
-    recover_validator_secret, DerivedKey, Error, KeyType, KeystoreError, Wallet, WalletBuilder,
+    recover_validator_secret, DerivedKey, Error, KdfParams, KeyType, KeystoreError, Wallet,
+    WalletBuilder,
 };
 use std::fs::File;
 use tempfile::tempdir;
@@ -209,6 +211,47 @@ fn empty_keystore_password() {
     assert_eq!(wallet.nextaccount(), 0, "next account should not update");
 }
 
+#[test]
+fn custom_kdf_params() {
+    let kdf_params = KdfParams::Pbkdf2 { c: 1_024 };
+    let mut wallet = WalletBuilder::from_seed_bytes(SEED, WALLET_PASSWORD, NAME.into())
+        .expect("should init builder")
+        .kdf_params(kdf_params)
+        .build()
+        .expect("should build wallet");
+
+    let keystores = wallet
+        .next_validator_with_kdf_params(
+            WALLET_PASSWORD,
+            VOTING_KEYSTORE_PASSWORD,
+            WITHDRAWAL_KEYSTORE_PASSWORD,
+            kdf_params,
+        )
+        .expect("should create keystores");
+
+    for keystore in [&keystores.voting, &keystores.withdrawal] {
+        assert!(
+            matches!(keystore.kdf(), Kdf::Pbkdf2(params) if params.c == 1_024),
+            "keystore should use the custom kdf"
+        );
+        assert!(keystore.has_fast_kdf());
+    }
+    let voting_keypair = keystores
+        .voting
+        .decrypt_keypair(VOTING_KEYSTORE_PASSWORD)
+        .expect("should decrypt voting keypair");
+    assert_eq!(
+        voting_keypair.sk.serialize().as_ref(),
+        &manually_derived_voting_key(0)[..],
+        "voting secret should match"
+    );
+    assert_eq!(
+        recovered_voting_key(&wallet, 0),
+        manually_derived_voting_key(0),
+        "wallet should decrypt"
+    );
+}
+
 #[test]
 fn key_derivation_from_seed() {
     let mut wallet = wallet_from_seed();
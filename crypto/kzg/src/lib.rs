@@ -4,6 +4,7 @@ pub mod trusted_setup;
 
 use rust_eth_kzg::{CellIndex, DASContext};
 use std::fmt::Debug;
+use std::sync::{Arc, LazyLock, Mutex};
 
 pub use crate::{
     kzg_commitment::{KzgCommitment, VERSIONED_HASH_VERSION_KZG},
@@ -47,6 +48,14 @@ impl From<c_kzg::Error> for Error {
     }
 }
 
+/// `Kzg` instances which have been loaded by this process, keyed by their trusted setup and whether
+/// precomputation is enabled.
+///
+/// Loading a trusted setup is expensive in both time and memory, so components which require the
+/// same setup share a single instance via `Kzg::shared`.
+static SHARED_KZG: LazyLock<Mutex<Vec<(TrustedSetup, bool, Arc<Kzg>)>>> =
+    LazyLock::new(|| Mutex::new(vec![]));
+
 /// A wrapper over a kzg library that holds the trusted setup parameters.
 #[derive(Debug)]
 pub struct Kzg {
@@ -115,6 +124,31 @@ impl Kzg {
         })
     }
 
+    /// Returns a `Kzg` for `trusted_setup`, only loading it if an instance with the same trusted
+    /// setup and precomputation setting has not already been loaded by this process.
+    pub fn shared(trusted_setup: TrustedSetup, use_precomp: bool) -> Result<Arc<Self>, Error> {
+        // Hold the lock whilst loading so that concurrent callers do not load the same setup twice.
+        let mut shared = SHARED_KZG
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+
+        if let Some((_, _, kzg)) = shared
+            .iter()
+            .find(|(setup, precomp, _)| *precomp == use_precomp && *setup == trusted_setup)
+        {
+            return Ok(kzg.clone());
+        }
+
+        let kzg = if use_precomp {
+            Self::new_from_trusted_setup(trusted_setup.clone())?
+        } else {
+            Self::new_from_trusted_setup_no_precomp(trusted_setup.clone())?
+        };
+        let kzg = Arc::new(kzg);
+        shared.push((trusted_setup, use_precomp, kzg.clone()));
+        Ok(kzg)
+    }
+
     fn context(&self) -> &DASContext {
         &self.context
     }
@@ -152,7 +186,7 @@ impl Kzg {
     /// Verify a batch of blob commitment proof triplets.
     ///
     /// Note: This method is slightly faster than calling `Self::verify_blob_kzg_proof` in a loop sequentially.
-    /// TODO(pawan): test performance against a parallelized rayon impl.
+    /// Large batches can be split into chunks and verified in parallel by the caller.
     pub fn verify_blob_kzg_proof_batch(
         &self,
         blobs: &[Blob],
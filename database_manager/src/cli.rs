@@ -57,6 +57,16 @@ pub struct DatabaseManager {
     )]
     pub blobs_dir: Option<PathBuf>,
 
+    #[clap(
+        long,
+        global = true,
+        value_name = "TAG=DIR",
+        help = "Data directory for a single column of the freezer database, given as its 3-byte \
+                column ID and a directory. May be supplied multiple times.",
+        display_order = 0
+    )]
+    pub freezer_column_dir: Vec<String>,
+
     #[clap(
         long,
         global = true,
@@ -81,6 +91,10 @@ pub enum DatabaseManagerSubcommand {
     PruneBlobs(PruneBlobs),
     PruneStates(PruneStates),
     Compact(Compact),
+    ExportParticipation(ExportParticipation),
+    Relocate(Relocate),
+    Verify(Verify),
+    RepairBlobs(RepairBlobs),
 }
 
 #[derive(Parser, Clone, Deserialize, Serialize, Debug)]
@@ -229,3 +243,130 @@ pub struct Compact {
     )]
     pub output_dir: Option<PathBuf>,
 }
+
+#[derive(Parser, Clone, Deserialize, Serialize, Debug)]
+#[clap(
+    about = "Export the participation flags of every active validator for a range of finalized \
+             epochs. Requires the historic states of the range to be stored in the freezer DB.",
+    alias = "export_participation"
+)]
+pub struct ExportParticipation {
+    #[clap(
+        long,
+        value_name = "EPOCH",
+        help = "The first epoch to export. Must not be prior to the Altair fork.",
+        display_order = 0
+    )]
+    pub start_epoch: u64,
+
+    #[clap(
+        long,
+        value_name = "EPOCH",
+        help = "The last epoch to export (inclusive).",
+        display_order = 0
+    )]
+    pub end_epoch: u64,
+
+    #[clap(
+        long,
+        help = "Output one CSV row per active validator per epoch. By default one JSON object \
+                containing the raw participation flags of all validators is output per epoch.",
+        display_order = 0,
+        help_heading = FLAG_HEADER
+    )]
+    pub csv: bool,
+
+    #[clap(
+        long,
+        value_name = "FILE",
+        help = "File to write the output to. Defaults to stdout.",
+        display_order = 0
+    )]
+    pub output: Option<PathBuf>,
+}
+
+#[derive(Parser, Clone, Deserialize, Serialize, Debug)]
+#[clap(
+    about = "Move a column of the freezer DB between the freezer DB directory and a separate \
+             directory. The column is read from the directory given by --freezer-column-dir, or \
+             from the freezer DB if none is given. The beacon node must be stopped, and must be \
+             restarted with --freezer-column-dir updated to match the new location."
+)]
+pub struct Relocate {
+    #[clap(
+        long,
+        value_name = "TAG",
+        help = "3-byte column ID of the freezer column to move (bsn or bsd)",
+        display_order = 0
+    )]
+    pub column: String,
+
+    #[clap(
+        long,
+        value_name = "DIR",
+        required_unless_present = "to_freezer",
+        conflicts_with = "to_freezer",
+        help = "Directory to move the column to",
+        display_order = 0
+    )]
+    pub to: Option<PathBuf>,
+
+    #[clap(
+        long,
+        help = "Move the column back into the freezer DB directory",
+        display_order = 0,
+        help_heading = FLAG_HEADER
+    )]
+    pub to_freezer: bool,
+}
+
+#[derive(Parser, Clone, Deserialize, Serialize, Debug)]
+#[clap(
+    about = "Verify the finalized blocks, blobs and states in the freezer DB by re-hashing them \
+             against the roots they are indexed by. Exits with an error if any item is missing \
+             or corrupt."
+)]
+pub struct Verify {
+    #[clap(
+        long,
+        value_name = "SLOT",
+        help = "The first slot to verify. Defaults to the oldest stored block.",
+        display_order = 0
+    )]
+    pub start_slot: Option<u64>,
+
+    #[clap(
+        long,
+        value_name = "SLOT",
+        help = "The slot to stop verifying at (exclusive). Defaults to the split slot.",
+        display_order = 0
+    )]
+    pub end_slot: Option<u64>,
+
+    #[clap(
+        long,
+        help = "Only verify blocks and blobs. Loading and re-hashing every stored state is much \
+                slower than verifying blocks.",
+        display_order = 0,
+        help_heading = FLAG_HEADER
+    )]
+    pub skip_states: bool,
+}
+
+#[derive(Parser, Clone, Deserialize, Serialize, Debug)]
+#[clap(
+    about = "Repair the blobs DB in place. Blob and data column entries which cannot be decoded, \
+             are stored under the wrong key or whose block is missing are removed, and the oldest \
+             blob and data column slots are recomputed from the entries which remain. A block's \
+             data columns count as present if any of them is stored, the node's full custody set \
+             is not checked. The beacon node must be stopped."
+)]
+pub struct RepairBlobs {
+    #[clap(
+        long,
+        help = "Log the repairs which would be made without writing anything to the database.",
+        display_order = 0,
+        help_heading = FLAG_HEADER
+    )]
+    pub dry_run: bool,
+}
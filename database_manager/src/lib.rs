@@ -9,20 +9,25 @@ use beacon_chain::{
 use beacon_node::{get_data_dir, ClientConfig};
 use clap::ArgMatches;
 use clap::ValueEnum;
-use cli::{Compact, Inspect};
+use cli::{Compact, ExportParticipation, Inspect, Relocate, RepairBlobs, Verify};
 use environment::{Environment, RuntimeContext};
 use serde::{Deserialize, Serialize};
-use slog::{info, warn, Logger};
-use std::fs;
-use std::io::Write;
+use slog::{debug, info, warn, Logger};
+use std::fs::{self, File};
+use std::io::{BufWriter, Write};
 use std::path::PathBuf;
 use store::{
+    config::{parse_freezer_column_dir, RELOCATABLE_FREEZER_COLUMNS},
     errors::Error,
+    get_key_for_col,
     metadata::{SchemaVersion, CURRENT_SCHEMA_VERSION},
-    DBColumn, HotColdDB, KeyValueStore, LevelDB,
+    DBColumn, HotColdDB, KeyValueStore, KeyValueStoreOp, LevelDB,
 };
 use strum::{EnumString, EnumVariantNames};
-use types::{BeaconState, EthSpec, Slot};
+use types::consts::altair::{
+    TIMELY_HEAD_FLAG_INDEX, TIMELY_SOURCE_FLAG_INDEX, TIMELY_TARGET_FLAG_INDEX,
+};
+use types::{BeaconState, Epoch, EthSpec, ParticipationFlags, Slot};
 
 fn parse_client_config<E: EthSpec>(
     cli_args: &ArgMatches,
@@ -40,6 +45,11 @@ fn parse_client_config<E: EthSpec>(
         .clone_from(&database_manager_config.blobs_dir);
     client_config.store.blob_prune_margin_epochs = database_manager_config.blob_prune_margin_epochs;
     client_config.store.hierarchy_config = database_manager_config.hierarchy_exponents.clone();
+    client_config.store.freezer_column_dirs = database_manager_config
+        .freezer_column_dir
+        .iter()
+        .map(|value| parse_freezer_column_dir(value))
+        .collect::<Result<_, _>>()?;
 
     Ok(client_config)
 }
@@ -145,7 +155,8 @@ pub fn inspect_db<E: EthSpec>(
     let mut num_keys = 0;
 
     let sub_db = if inspect_config.freezer {
-        LevelDB::<E>::open(&cold_path).map_err(|e| format!("Unable to open freezer DB: {e:?}"))?
+        LevelDB::<E>::open_with_column_dirs(&cold_path, &client_config.store.freezer_column_dirs)
+            .map_err(|e| format!("Unable to open freezer DB: {e:?}"))?
     } else if inspect_config.blobs_db {
         LevelDB::<E>::open(&blobs_path).map_err(|e| format!("Unable to open blobs DB: {e:?}"))?
     } else {
@@ -263,7 +274,13 @@ pub fn compact_db<E: EthSpec>(
     let column = compact_config.column;
 
     let (sub_db, db_name) = if compact_config.freezer {
-        (LevelDB::<E>::open(&cold_path)?, "freezer_db")
+        (
+            LevelDB::<E>::open_with_column_dirs(
+                &cold_path,
+                &client_config.store.freezer_column_dirs,
+            )?,
+            "freezer_db",
+        )
     } else if compact_config.blobs_db {
         (LevelDB::<E>::open(&blobs_path)?, "blobs_db")
     } else {
@@ -279,6 +296,204 @@ pub fn compact_db<E: EthSpec>(
     Ok(())
 }
 
+/// The number of value bytes to copy in each write when relocating a freezer column.
+const RELOCATE_BATCH_BYTES: usize = 64 * 1024 * 1024;
+
+/// The number of keys to delete in each write when relocating a freezer column.
+const RELOCATE_DELETE_BATCH_SIZE: usize = 4096;
+
+pub struct RelocateConfig {
+    column: DBColumn,
+    /// The directory to move the column to, or `None` to move it into the freezer DB.
+    to: Option<PathBuf>,
+}
+
+fn parse_relocate_config(relocate_config: &Relocate) -> Result<RelocateConfig, String> {
+    let column: DBColumn = relocate_config
+        .column
+        .parse()
+        .map_err(|_| format!("Unknown database column: {:?}", relocate_config.column))?;
+    if !RELOCATABLE_FREEZER_COLUMNS.contains(&column) {
+        return Err(format!(
+            "Column {:?} cannot be stored outside of the freezer DB",
+            column
+        ));
+    }
+    let to = if relocate_config.to_freezer {
+        None
+    } else {
+        relocate_config.to.clone()
+    };
+    Ok(RelocateConfig { column, to })
+}
+
+/// Move a freezer column between the freezer DB directory and a separate directory.
+///
+/// The column is copied to its destination and verified before it is deleted from its source, and
+/// the set of relocated columns is only updated once the source is empty. An interrupted
+/// relocation can therefore be resumed by running it again with the same arguments.
+pub fn relocate_column<E: EthSpec>(
+    relocate_config: RelocateConfig,
+    client_config: ClientConfig,
+    log: Logger,
+) -> Result<(), String> {
+    let column = relocate_config.column;
+    let hot_path = client_config.get_db_path();
+    let cold_path = client_config.get_freezer_db_path();
+    let blobs_path = client_config.get_blobs_db_path();
+    let source_path = client_config
+        .store
+        .freezer_column_dirs
+        .iter()
+        .find(|(other, _)| *other == column)
+        .map(|(_, path)| path.clone());
+    let destination_path = relocate_config.to;
+
+    if source_path == destination_path {
+        return Err(format!(
+            "Column {:?} is already stored in {:?}",
+            column,
+            source_path.unwrap_or(cold_path)
+        ));
+    }
+    if let Some(path) = &destination_path {
+        if [&hot_path, &cold_path, &blobs_path].contains(&path) {
+            return Err(format!(
+                "Column {:?} cannot be moved into the directory of another database: {:?}",
+                column, path
+            ));
+        }
+    }
+
+    let db_err = |e: Error| format!("Database error: {e:?}");
+    let freezer_db =
+        LevelDB::<E>::open(&cold_path).map_err(|e| format!("Unable to open freezer DB: {e:?}"))?;
+    let mut relocated_columns = freezer_db.load_relocated_columns().map_err(db_err)?;
+    if source_path.is_none() && relocated_columns.contains(&column) {
+        return Err(format!(
+            "Column {:?} is stored outside of the freezer DB. Provide its current directory with \
+             --freezer-column-dir",
+            column
+        ));
+    }
+
+    let open_column_db = |path: &Option<PathBuf>| {
+        path.as_ref()
+            .map(|path| LevelDB::<E>::open(path))
+            .transpose()
+            .map_err(|e| format!("Unable to open column DB: {e:?}"))
+    };
+    let source_db = open_column_db(&source_path)?;
+    let destination_db = open_column_db(&destination_path)?;
+    let source = source_db.as_ref().unwrap_or(&freezer_db);
+    let destination = destination_db.as_ref().unwrap_or(&freezer_db);
+
+    let keys = source
+        .iter_raw_keys(column, &[])
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(db_err)?;
+    let total = keys.len();
+    info!(
+        log,
+        "Relocating freezer column";
+        "column" => ?column,
+        "from" => ?source_path.as_ref().unwrap_or(&cold_path),
+        "to" => ?destination_path.as_ref().unwrap_or(&cold_path),
+        "entries" => total,
+    );
+    let log_progress = |stage: &str, done: usize| {
+        info!(
+            log,
+            "Relocation in progress";
+            "stage" => stage,
+            "done" => done,
+            "total" => total,
+            "percent" => format!("{:.1}", done as f64 * 100.0 / total.max(1) as f64),
+        );
+    };
+
+    // Copy the column to its destination.
+    let mut copied = 0;
+    let mut batch = vec![];
+    let mut batch_bytes = 0;
+    for key in &keys {
+        let value = source
+            .get_bytes(column.into(), key)
+            .map_err(db_err)?
+            .ok_or_else(|| format!("Entry {:?} was removed during relocation", key))?;
+        batch_bytes += value.len();
+        batch.push(KeyValueStoreOp::PutKeyValue(
+            get_key_for_col(column.into(), key),
+            value,
+        ));
+
+        if batch_bytes >= RELOCATE_BATCH_BYTES || copied + batch.len() == total {
+            copied += batch.len();
+            destination
+                .do_atomically(std::mem::take(&mut batch))
+                .map_err(db_err)?;
+            batch_bytes = 0;
+            log_progress("copy", copied);
+        }
+    }
+    destination.sync().map_err(db_err)?;
+
+    // Verify that every entry exists at the destination before deleting the source.
+    for key in &keys {
+        if !destination.key_exists(column.into(), key).map_err(db_err)? {
+            return Err(format!(
+                "Entry {:?} is missing from the destination, the source has not been modified",
+                key
+            ));
+        }
+    }
+
+    let mut deleted = 0;
+    for chunk in keys.chunks(RELOCATE_DELETE_BATCH_SIZE) {
+        let ops = chunk
+            .iter()
+            .map(|key| KeyValueStoreOp::DeleteKey(get_key_for_col(column.into(), key)))
+            .collect();
+        source.do_atomically(ops).map_err(db_err)?;
+        deleted += chunk.len();
+        log_progress("delete", deleted);
+    }
+    source.sync().map_err(db_err)?;
+    source.compact_column(column).map_err(db_err)?;
+
+    relocated_columns.retain(|other| *other != column);
+    if destination_path.is_some() {
+        relocated_columns.push(column);
+    }
+    freezer_db
+        .store_relocated_columns(&relocated_columns)
+        .map_err(db_err)?;
+
+    match destination_path {
+        Some(path) => info!(
+            log,
+            "Freezer column relocated";
+            "column" => ?column,
+            "hint" => format!(
+                "start the beacon node with --freezer-column-dir {}={}",
+                column.as_str(),
+                path.display()
+            ),
+        ),
+        None => info!(
+            log,
+            "Freezer column relocated";
+            "column" => ?column,
+            "hint" => format!(
+                "start the beacon node without --freezer-column-dir for {}",
+                column.as_str()
+            ),
+        ),
+    }
+
+    Ok(())
+}
+
 pub struct MigrateConfig {
     to: SchemaVersion,
 }
@@ -461,6 +676,319 @@ pub fn prune_states<E: EthSpec>(
     Ok(())
 }
 
+pub struct ExportParticipationConfig {
+    start_epoch: Epoch,
+    end_epoch: Epoch,
+    csv: bool,
+    output: Option<PathBuf>,
+}
+
+fn parse_export_participation_config(
+    export_config: &ExportParticipation,
+) -> Result<ExportParticipationConfig, String> {
+    let start_epoch = Epoch::new(export_config.start_epoch);
+    let end_epoch = Epoch::new(export_config.end_epoch);
+    if start_epoch > end_epoch {
+        return Err("--start-epoch must not be greater than --end-epoch".into());
+    }
+    Ok(ExportParticipationConfig {
+        start_epoch,
+        end_epoch,
+        csv: export_config.csv,
+        output: export_config.output.clone(),
+    })
+}
+
+/// The participation flags of all validators for a single epoch, indexed by validator index.
+#[derive(Serialize)]
+struct EpochParticipation {
+    epoch: Epoch,
+    participation: Vec<u8>,
+}
+
+/// Export the participation flags of every epoch in the configured range from the freezer DB.
+///
+/// The flags for an epoch are final at the last slot of the following epoch, before the epoch
+/// transition discards them. That state is loaded via the hierarchical diffs, so each epoch costs
+/// at most one diff lookup and an epoch of block replay.
+pub fn export_participation<E: EthSpec>(
+    export_config: ExportParticipationConfig,
+    client_config: ClientConfig,
+    runtime_context: &RuntimeContext<E>,
+    log: Logger,
+) -> Result<(), String> {
+    let spec = &runtime_context.eth2_config.spec;
+    let hot_path = client_config.get_db_path();
+    let cold_path = client_config.get_freezer_db_path();
+    let blobs_path = client_config.get_blobs_db_path();
+
+    let db = HotColdDB::<E, LevelDB<E>, LevelDB<E>>::open(
+        &hot_path,
+        &cold_path,
+        &blobs_path,
+        |_, _, _| Ok(()),
+        client_config.store,
+        spec.clone(),
+        log.clone(),
+    )
+    .map_err(|e| format!("Unable to open database: {e:?}"))?;
+
+    let altair_fork_epoch = spec
+        .altair_fork_epoch
+        .ok_or("Participation flags are not available on networks without Altair")?;
+    if export_config.start_epoch < altair_fork_epoch {
+        return Err(format!(
+            "Participation flags are not available prior to the Altair fork at epoch {}",
+            altair_fork_epoch
+        ));
+    }
+
+    let epochs = (export_config.start_epoch.as_u64()..=export_config.end_epoch.as_u64())
+        .map(Epoch::new)
+        .collect::<Vec<_>>();
+    let final_slot = |epoch: Epoch| (epoch + 2).start_slot(E::slots_per_epoch()) - 1;
+
+    // Check that all of the required states are stored before writing any output.
+    let split_slot = db.get_split_slot();
+    let anchor_info = db.get_anchor_info();
+    for &epoch in &epochs {
+        let slot = final_slot(epoch);
+        if slot >= split_slot {
+            return Err(format!(
+                "Epoch {epoch} is not yet finalized and migrated to the freezer DB (requires \
+                 slot {slot}, split slot is {split_slot})"
+            ));
+        }
+        if slot > anchor_info.state_lower_limit && slot < anchor_info.state_upper_limit {
+            return Err(format!(
+                "The historic state required for epoch {epoch} at slot {slot} is not stored. \
+                 Historic states are available up to slot {} and from slot {}",
+                anchor_info.state_lower_limit, anchor_info.state_upper_limit
+            ));
+        }
+    }
+
+    let mut output: Box<dyn Write> = match &export_config.output {
+        Some(path) => Box::new(BufWriter::new(
+            File::create(path).map_err(|e| format!("Unable to create {path:?}: {e:?}"))?,
+        )),
+        None => Box::new(BufWriter::new(std::io::stdout().lock())),
+    };
+    let write_err = |e| format!("Unable to write output: {e:?}");
+    let flag = |flags: &ParticipationFlags, index| {
+        flags
+            .has_flag(index)
+            .map(u8::from)
+            .map_err(|e| format!("Invalid participation flag index {index}: {e:?}"))
+    };
+
+    if export_config.csv {
+        writeln!(
+            output,
+            "epoch,validator_index,timely_source,timely_target,timely_head"
+        )
+        .map_err(write_err)?;
+    }
+
+    for epoch in epochs {
+        let slot = final_slot(epoch);
+        let state = db
+            .load_cold_state_by_slot(slot)
+            .map_err(|e| format!("Unable to load state at slot {slot}: {e:?}"))?;
+        let participation = state
+            .previous_epoch_participation()
+            .map_err(|e| format!("State at slot {slot} has no participation flags: {e:?}"))?;
+
+        if export_config.csv {
+            for (validator_index, (validator, flags)) in state
+                .validators()
+                .iter()
+                .zip(participation.iter())
+                .enumerate()
+            {
+                if !validator.is_active_at(epoch) {
+                    continue;
+                }
+                writeln!(
+                    output,
+                    "{},{},{},{},{}",
+                    epoch,
+                    validator_index,
+                    flag(flags, TIMELY_SOURCE_FLAG_INDEX)?,
+                    flag(flags, TIMELY_TARGET_FLAG_INDEX)?,
+                    flag(flags, TIMELY_HEAD_FLAG_INDEX)?,
+                )
+                .map_err(write_err)?;
+            }
+        } else {
+            let epoch_participation = EpochParticipation {
+                epoch,
+                participation: participation.iter().map(|flags| flags.into_u8()).collect(),
+            };
+            serde_json::to_writer(&mut output, &epoch_participation)
+                .map_err(|e| format!("Unable to serialize participation: {e:?}"))?;
+            writeln!(output).map_err(write_err)?;
+        }
+        debug!(log, "Exported epoch participation"; "epoch" => epoch, "slot" => slot);
+    }
+    output.flush().map_err(write_err)?;
+
+    info!(
+        log,
+        "Participation exported";
+        "start_epoch" => export_config.start_epoch,
+        "end_epoch" => export_config.end_epoch,
+    );
+    Ok(())
+}
+
+pub struct VerifyConfig {
+    start_slot: Option<Slot>,
+    end_slot: Option<Slot>,
+    skip_states: bool,
+}
+
+fn parse_verify_config(verify_config: &Verify) -> Result<VerifyConfig, String> {
+    let start_slot = verify_config.start_slot.map(Slot::new);
+    let end_slot = verify_config.end_slot.map(Slot::new);
+    if let (Some(start_slot), Some(end_slot)) = (start_slot, end_slot) {
+        if start_slot >= end_slot {
+            return Err("--start-slot must be less than --end-slot".into());
+        }
+    }
+    Ok(VerifyConfig {
+        start_slot,
+        end_slot,
+        skip_states: verify_config.skip_states,
+    })
+}
+
+/// Verify the integrity of the freezer DB, logging every missing or corrupt item.
+pub fn verify_db<E: EthSpec>(
+    verify_config: VerifyConfig,
+    client_config: ClientConfig,
+    runtime_context: &RuntimeContext<E>,
+    log: Logger,
+) -> Result<(), String> {
+    let spec = &runtime_context.eth2_config.spec;
+    let hot_path = client_config.get_db_path();
+    let cold_path = client_config.get_freezer_db_path();
+    let blobs_path = client_config.get_blobs_db_path();
+
+    let db = HotColdDB::<E, LevelDB<E>, LevelDB<E>>::open(
+        &hot_path,
+        &cold_path,
+        &blobs_path,
+        |_, _, _| Ok(()),
+        client_config.store,
+        spec.clone(),
+        log.clone(),
+    )
+    .map_err(|e| format!("Unable to open database: {e:?}"))?;
+
+    let start_slot = verify_config
+        .start_slot
+        .unwrap_or(db.get_anchor_info().oldest_block_slot);
+    let end_slot = verify_config.end_slot.unwrap_or(db.get_split_slot());
+    info!(
+        log,
+        "Verifying freezer database";
+        "start_slot" => start_slot,
+        "end_slot" => end_slot,
+        "verify_states" => !verify_config.skip_states,
+    );
+
+    let report = db
+        .verify_freezer_integrity(start_slot, end_slot, !verify_config.skip_states)
+        .map_err(|e| format!("Unable to verify database: {e:?}"))?;
+    for corruption in &report.corruptions {
+        warn!(log, "Corrupt database item"; "item" => ?corruption);
+    }
+    info!(
+        log,
+        "Database verification complete";
+        "blocks_checked" => report.blocks_checked,
+        "blobs_checked" => report.blobs_checked,
+        "states_checked" => report.states_checked,
+        "corrupt_items" => report.corruptions.len(),
+    );
+
+    if report.corruptions.is_empty() {
+        Ok(())
+    } else {
+        Err(format!(
+            "Found {} missing or corrupt items",
+            report.corruptions.len()
+        ))
+    }
+}
+
+pub struct RepairBlobsConfig {
+    dry_run: bool,
+}
+
+fn parse_repair_blobs_config(repair_config: &RepairBlobs) -> RepairBlobsConfig {
+    RepairBlobsConfig {
+        dry_run: repair_config.dry_run,
+    }
+}
+
+/// Remove unusable entries from the blobs DB and recompute the oldest blob and data column slots.
+pub fn repair_blobs<E: EthSpec>(
+    repair_config: RepairBlobsConfig,
+    client_config: ClientConfig,
+    runtime_context: &RuntimeContext<E>,
+    log: Logger,
+) -> Result<(), String> {
+    let spec = &runtime_context.eth2_config.spec;
+    let hot_path = client_config.get_db_path();
+    let cold_path = client_config.get_freezer_db_path();
+    let blobs_path = client_config.get_blobs_db_path();
+
+    let db = HotColdDB::<E, LevelDB<E>, LevelDB<E>>::open(
+        &hot_path,
+        &cold_path,
+        &blobs_path,
+        |_, _, _| Ok(()),
+        client_config.store,
+        spec.clone(),
+        log.clone(),
+    )
+    .map_err(|e| format!("Unable to open database: {e:?}"))?;
+
+    info!(
+        log,
+        "Repairing blobs database";
+        "dry_run" => repair_config.dry_run,
+    );
+    let report = db
+        .repair_blobs_db(repair_config.dry_run)
+        .map_err(|e| format!("Unable to repair database: {e:?}"))?;
+    info!(
+        log,
+        "Blobs database repair complete";
+        "dry_run" => repair_config.dry_run,
+        "blob_lists_scanned" => report.blob_lists_scanned,
+        "data_columns_scanned" => report.data_columns_scanned,
+        "blocks_checked" => report.blocks_checked,
+        "orphans_removed" => report.orphans_removed,
+        "invalid_removed" => report.invalid_removed,
+        "previous_oldest_blob_slot" => ?report.previous_oldest_blob_slot,
+        "oldest_blob_slot" => ?report.oldest_blob_slot,
+        "previous_oldest_data_column_slot" => ?report.previous_oldest_data_column_slot,
+        "oldest_data_column_slot" => ?report.oldest_data_column_slot,
+    );
+    if !report.is_repaired() {
+        info!(log, "No repairs were required");
+    } else if repair_config.dry_run {
+        warn!(
+            log,
+            "Repairs required, re-run without --dry-run to apply them"
+        );
+    }
+    Ok(())
+}
+
 /// Run the database manager, returning an error string if the operation did not succeed.
 pub fn run<E: EthSpec>(
     cli_args: &ArgMatches,
@@ -522,5 +1050,21 @@ pub fn run<E: EthSpec>(
             let compact_config = parse_compact_config(compact_config)?;
             compact_db::<E>(compact_config, client_config, log).map_err(format_err)
         }
+        cli::DatabaseManagerSubcommand::ExportParticipation(export_config) => {
+            let export_config = parse_export_participation_config(export_config)?;
+            export_participation(export_config, client_config, &context, log)
+        }
+        cli::DatabaseManagerSubcommand::Relocate(relocate_config) => {
+            let relocate_config = parse_relocate_config(relocate_config)?;
+            relocate_column::<E>(relocate_config, client_config, log)
+        }
+        cli::DatabaseManagerSubcommand::Verify(verify_config) => {
+            let verify_config = parse_verify_config(verify_config)?;
+            verify_db(verify_config, client_config, &context, log)
+        }
+        cli::DatabaseManagerSubcommand::RepairBlobs(repair_config) => {
+            let repair_config = parse_repair_blobs_config(repair_config);
+            repair_blobs(repair_config, client_config, &context, log)
+        }
     }
 }
@@ -5,6 +5,7 @@ mod http_sync;
 mod indexed_attestations;
 mod mnemonic_validators;
 mod mock_el;
+mod new_testnet;
 mod parse_ssz;
 mod skip_slots;
 mod state_root;
@@ -383,6 +384,195 @@ fn main() {
                         .display_order(0)
                 )
         )
+        .subcommand(
+            Command::new("new-testnet")
+                .about("Produces a testnet directory containing the config and genesis state of a \
+                        new network, with genesis validator keys derived from a mnemonic. The \
+                        directory is written to --testnet-dir. For testing purposes only, DO NOT \
+                        USE IN PRODUCTION!")
+                .arg(
+                    Arg::new("force")
+                        .long("force")
+                        .short('f')
+                        .action(ArgAction::SetTrue)
+                        .help_heading(FLAG_HEADER)
+                        .help("Overwrites any previous testnet configurations.")
+                        .display_order(0)
+                )
+                .arg(
+                    Arg::new("validator-count")
+                        .long("validator-count")
+                        .value_name("INTEGER")
+                        .action(ArgAction::Set)
+                        .required(true)
+                        .help("The number of genesis validators.")
+                        .display_order(0)
+                )
+                .arg(
+                    Arg::new("mnemonic-phrase")
+                        .long("mnemonic-phrase")
+                        .value_name("MNEMONIC_PHRASE")
+                        .action(ArgAction::Set)
+                        .required(true)
+                        .help("The mnemonic from which the genesis validator keys are derived.")
+                        .display_order(0)
+                )
+                .arg(
+                    Arg::new("keystores-dir")
+                        .long("keystores-dir")
+                        .value_name("PATH")
+                        .action(ArgAction::Set)
+                        .help("If present, write validator keystores and secrets for the genesis \
+                               validators to this directory, in a layout which can be used \
+                               directly by the validator client.")
+                        .display_order(0)
+                )
+                .arg(
+                    Arg::new("node-count")
+                        .long("node-count")
+                        .value_name("NODE_COUNT")
+                        .action(ArgAction::Set)
+                        .help("Divide the keystores written to --keystores-dir between this many \
+                               nodes.")
+                        .display_order(0)
+                )
+                .arg(
+                    Arg::new("genesis-time")
+                        .long("genesis-time")
+                        .value_name("UNIX_SECONDS")
+                        .action(ArgAction::Set)
+                        .help("The genesis time. Defaults to the current time plus --genesis-delay.")
+                        .display_order(0)
+                )
+                .arg(
+                    Arg::new("genesis-delay")
+                        .long("genesis-delay")
+                        .value_name("SECONDS")
+                        .action(ArgAction::Set)
+                        .default_value("120")
+                        .help("The delay before genesis when --genesis-time is not set.")
+                        .display_order(0)
+                )
+                .arg(
+                    Arg::new("genesis-fork-version")
+                        .long("genesis-fork-version")
+                        .value_name("HEX")
+                        .action(ArgAction::Set)
+                        .help("Used to avoid replay attacks between testnets. Recommended to set to \
+                               non-default.")
+                        .display_order(0)
+                )
+                .arg(
+                    Arg::new("seconds-per-slot")
+                        .long("seconds-per-slot")
+                        .value_name("SECONDS")
+                        .action(ArgAction::Set)
+                        .help("The number of seconds in each slot.")
+                        .display_order(0)
+                )
+                .arg(
+                    Arg::new("altair-fork-epoch")
+                        .long("altair-fork-epoch")
+                        .value_name("EPOCH")
+                        .action(ArgAction::Set)
+                        .default_value("0")
+                        .help("The epoch at which to enable the Altair hard fork.")
+                        .display_order(0)
+                )
+                .arg(
+                    Arg::new("bellatrix-fork-epoch")
+                        .long("bellatrix-fork-epoch")
+                        .value_name("EPOCH")
+                        .action(ArgAction::Set)
+                        .default_value("0")
+                        .help("The epoch at which to enable the Bellatrix hard fork.")
+                        .display_order(0)
+                )
+                .arg(
+                    Arg::new("capella-fork-epoch")
+                        .long("capella-fork-epoch")
+                        .value_name("EPOCH")
+                        .action(ArgAction::Set)
+                        .default_value("0")
+                        .help("The epoch at which to enable the Capella hard fork.")
+                        .display_order(0)
+                )
+                .arg(
+                    Arg::new("deneb-fork-epoch")
+                        .long("deneb-fork-epoch")
+                        .value_name("EPOCH")
+                        .action(ArgAction::Set)
+                        .default_value("0")
+                        .help("The epoch at which to enable the Deneb hard fork.")
+                        .display_order(0)
+                )
+                .arg(
+                    Arg::new("electra-fork-epoch")
+                        .long("electra-fork-epoch")
+                        .value_name("EPOCH")
+                        .action(ArgAction::Set)
+                        .default_value("0")
+                        .help("The epoch at which to enable the Electra hard fork.")
+                        .display_order(0)
+                )
+                .arg(
+                    Arg::new("eip7594-fork-epoch")
+                        .long("eip7594-fork-epoch")
+                        .value_name("EPOCH")
+                        .action(ArgAction::Set)
+                        .help("The epoch at which to enable PeerDAS. PeerDAS is disabled if not set.")
+                        .display_order(0)
+                )
+                .arg(
+                    Arg::new("custody-requirement")
+                        .long("custody-requirement")
+                        .value_name("INTEGER")
+                        .action(ArgAction::Set)
+                        .help("The minimum number of data column subnets custodied by each node.")
+                        .display_order(0)
+                )
+                .arg(
+                    Arg::new("data-column-sidecar-subnet-count")
+                        .long("data-column-sidecar-subnet-count")
+                        .value_name("INTEGER")
+                        .action(ArgAction::Set)
+                        .help("The number of data column sidecar subnets.")
+                        .display_order(0)
+                )
+                .arg(
+                    Arg::new("samples-per-slot")
+                        .long("samples-per-slot")
+                        .value_name("INTEGER")
+                        .action(ArgAction::Set)
+                        .help("The number of data columns sampled by each node per slot.")
+                        .display_order(0)
+                )
+                .arg(
+                    Arg::new("execution-genesis-hash")
+                        .long("execution-genesis-hash")
+                        .value_name("HASH")
+                        .action(ArgAction::Set)
+                        .help("The block hash of the execution layer genesis block. Required if \
+                               Bellatrix is enabled at genesis.")
+                        .display_order(0)
+                )
+                .arg(
+                    Arg::new("execution-chain-id")
+                        .long("execution-chain-id")
+                        .value_name("INTEGER")
+                        .action(ArgAction::Set)
+                        .help("The chain ID of the execution layer.")
+                        .display_order(0)
+                )
+                .arg(
+                    Arg::new("deposit-contract-address")
+                        .long("deposit-contract-address")
+                        .value_name("ADDRESS")
+                        .action(ArgAction::Set)
+                        .help("The address of the deposit contract on the execution layer.")
+                        .display_order(0)
+                )
+        )
         .subcommand(
             Command::new("indexed-attestations")
                 .about("Convert attestations to indexed form, using the committees from a state.")
@@ -552,6 +742,46 @@ fn main() {
                                 until Prague is triggered on mainnet.")
                         .display_order(0)
                 )
+                .arg(
+                    Arg::new("payload-value")
+                        .long("payload-value")
+                        .value_name("WEI")
+                        .action(ArgAction::Set)
+                        .help("The block value returned with locally built payloads. Useful for \
+                            comparing local payloads against builder bids.")
+                        .default_value("10000000000000000")
+                        .display_order(0)
+                )
+                .arg(
+                    Arg::new("response-delay-ms")
+                        .long("response-delay-ms")
+                        .value_name("MILLISECONDS")
+                        .action(ArgAction::Set)
+                        .help("Delay the response to each request by this many milliseconds.")
+                        .default_value("0")
+                        .display_order(0)
+                )
+                .arg(
+                    Arg::new("error-probability")
+                        .long("error-probability")
+                        .value_name("PROBABILITY")
+                        .action(ArgAction::Set)
+                        .help("The probability between 0 and 1 that a request fails with an \
+                            error.")
+                        .default_value("0")
+                        .display_order(0)
+                )
+                .arg(
+                    Arg::new("fault-methods")
+                        .long("fault-methods")
+                        .value_name("METHODS")
+                        .action(ArgAction::Set)
+                        .help("Comma-separated list of JSON-RPC methods affected by \
+                            --response-delay-ms and --error-probability, e.g. \
+                            'engine_getPayloadV3,engine_newPayloadV3'. All methods are affected \
+                            if not set.")
+                        .display_order(0)
+                )
         )
         .subcommand(
             Command::new("http-sync")
@@ -713,6 +943,11 @@ fn run<E: EthSpec>(env_builder: EnvironmentBuilder<E>, matches: &ArgMatches) ->
         }
         Some(("mnemonic-validators", matches)) => mnemonic_validators::run(matches)
             .map_err(|e| format!("Failed to run mnemonic-validators command: {}", e)),
+        Some(("new-testnet", matches)) => {
+            let testnet_dir = testnet_dir.ok_or("new-testnet requires --testnet-dir")?;
+            new_testnet::run::<E>(testnet_dir, (*env.eth2_config.spec).clone(), matches)
+                .map_err(|e| format!("Failed to run new-testnet command: {}", e))
+        }
         Some(("indexed-attestations", matches)) => indexed_attestations::run::<E>(matches)
             .map_err(|e| format!("Failed to run indexed-attestations command: {}", e)),
         Some(("block-root", matches)) => {
@@ -1,4 +1,4 @@
-use account_utils::eth2_keystore::{keypair_from_secret, Keystore, KeystoreBuilder};
+use account_utils::eth2_keystore::{keypair_from_secret, KeystoreBuilder};
 use account_utils::random_password;
 use clap::ArgMatches;
 use eth2_wallet::bip39::Seed;
@@ -6,9 +6,36 @@ use eth2_wallet::bip39::{Language, Mnemonic};
 use eth2_wallet::{recover_validator_secret_from_mnemonic, KeyType};
 use rayon::prelude::*;
 use std::fs;
-use std::path::PathBuf;
+use std::ops::Range;
+use std::path::{Path, PathBuf};
+use types::Keypair;
 use validator_dir::Builder as ValidatorBuilder;
 
+/// Parses a BIP-39 mnemonic phrase into the seed from which validator keys are derived.
+pub fn seed_from_mnemonic_phrase(mnemonic_phrase: &str) -> Result<Seed, String> {
+    let mnemonic = Mnemonic::from_phrase(mnemonic_phrase, Language::English).map_err(|e| {
+        format!(
+            "Unable to derive mnemonic from string {:?}: {:?}",
+            mnemonic_phrase, e
+        )
+    })?;
+
+    Ok(Seed::new(&mnemonic, ""))
+}
+
+/// Derives the voting keypair of the validator at `index` from `seed`, along with its EIP-2334
+/// derivation path.
+pub fn derive_voting_keypair(seed: &Seed, index: u32) -> Result<(Keypair, String), String> {
+    let (secret, path) =
+        recover_validator_secret_from_mnemonic(seed.as_bytes(), index, KeyType::Voting)
+            .map_err(|e| format!("Unable to recover validator keys: {:?}", e))?;
+
+    let keypair = keypair_from_secret(secret.as_bytes())
+        .map_err(|e| format!("Unable build keystore: {:?}", e))?;
+
+    Ok((keypair, format!("{}", path)))
+}
+
 /// Generates validator directories with keys derived from the given mnemonic.
 pub fn generate_validator_dirs(
     indices: &[usize],
@@ -25,38 +52,21 @@ pub fn generate_validator_dirs(
         fs::create_dir_all(&secrets_dir)
             .map_err(|e| format!("Unable to create secrets dir: {:?}", e))?;
     }
-    let mnemonic = Mnemonic::from_phrase(mnemonic_phrase, Language::English).map_err(|e| {
-        format!(
-            "Unable to derive mnemonic from string {:?}: {:?}",
-            mnemonic_phrase, e
-        )
-    })?;
-
-    let seed = Seed::new(&mnemonic, "");
+    let seed = seed_from_mnemonic_phrase(mnemonic_phrase)?;
 
     let _: Vec<_> = indices
         .par_iter()
         .map(|index| {
             let voting_password = random_password();
 
-            let derive = |key_type: KeyType, password: &[u8]| -> Result<Keystore, String> {
-                let (secret, path) = recover_validator_secret_from_mnemonic(
-                    seed.as_bytes(),
-                    *index as u32,
-                    key_type,
-                )
-                .map_err(|e| format!("Unable to recover validator keys: {:?}", e))?;
-
-                let keypair = keypair_from_secret(secret.as_bytes())
-                    .map_err(|e| format!("Unable build keystore: {:?}", e))?;
-
-                KeystoreBuilder::new(&keypair, password, format!("{}", path))
-                    .map_err(|e| format!("Unable build keystore: {:?}", e))?
-                    .build()
-                    .map_err(|e| format!("Unable build keystore: {:?}", e))
-            };
-
-            let voting_keystore = derive(KeyType::Voting, voting_password.as_bytes()).unwrap();
+            let voting_keystore = derive_voting_keypair(&seed, *index as u32)
+                .and_then(|(keypair, path)| {
+                    KeystoreBuilder::new(&keypair, voting_password.as_bytes(), path)
+                        .map_err(|e| format!("Unable build keystore: {:?}", e))?
+                        .build()
+                        .map_err(|e| format!("Unable build keystore: {:?}", e))
+                })
+                .unwrap();
 
             println!("Validator {}", index + 1);
 
@@ -78,27 +88,101 @@ pub fn run(matches: &ArgMatches) -> Result<(), String> {
     let base_dir: PathBuf = clap_utils::parse_required(matches, "base-dir")?;
     let node_count: Option<usize> = clap_utils::parse_optional(matches, "node-count")?;
     let mnemonic_phrase: String = clap_utils::parse_required(matches, "mnemonic-phrase")?;
+    generate_node_validator_dirs(validator_count, node_count, &mnemonic_phrase, &base_dir)
+}
+
+/// Generates validator directories for validators `0..validator_count` in `base_dir`, optionally
+/// divided between `node_count` nodes in `base_dir/node_{i}` directories.
+pub fn generate_node_validator_dirs(
+    validator_count: usize,
+    node_count: Option<usize>,
+    mnemonic_phrase: &str,
+    base_dir: &Path,
+) -> Result<(), String> {
     if let Some(node_count) = node_count {
-        let validators_per_node = validator_count / node_count;
-        let validator_range = (0..validator_count).collect::<Vec<_>>();
-        let indices_range = validator_range
-            .chunks(validators_per_node)
-            .collect::<Vec<_>>();
+        let node_ranges = node_validator_ranges(validator_count, node_count)?;
 
-        for (i, indices) in indices_range.iter().enumerate() {
+        for (i, indices) in node_ranges.into_iter().enumerate() {
             let validators_dir = base_dir.join(format!("node_{}", i + 1)).join("validators");
             let secrets_dir = base_dir.join(format!("node_{}", i + 1)).join("secrets");
-            generate_validator_dirs(indices, &mnemonic_phrase, validators_dir, secrets_dir)?;
+            generate_validator_dirs(
+                indices.collect::<Vec<_>>().as_slice(),
+                mnemonic_phrase,
+                validators_dir,
+                secrets_dir,
+            )?;
         }
     } else {
         let validators_dir = base_dir.join("validators");
         let secrets_dir = base_dir.join("secrets");
         generate_validator_dirs(
             (0..validator_count).collect::<Vec<_>>().as_slice(),
-            &mnemonic_phrase,
+            mnemonic_phrase,
             validators_dir,
             secrets_dir,
         )?;
     }
     Ok(())
 }
+
+/// Divides validators `0..validator_count` between `node_count` nodes as evenly as possible, with
+/// the first nodes taking one extra validator each if they don't divide exactly.
+fn node_validator_ranges(
+    validator_count: usize,
+    node_count: usize,
+) -> Result<Vec<Range<usize>>, String> {
+    if node_count == 0 || node_count > validator_count {
+        return Err(format!(
+            "Node count must be between 1 and the validator count ({}), got {}",
+            validator_count, node_count
+        ));
+    }
+
+    let validators_per_node = validator_count / node_count;
+    let remainder = validator_count % node_count;
+    let mut start = 0;
+    Ok((0..node_count)
+        .map(|i| {
+            let end = start + validators_per_node + usize::from(i < remainder);
+            let range = start..end;
+            start = end;
+            range
+        })
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn node_validator_ranges_even_split() {
+        assert_eq!(
+            node_validator_ranges(8, 4),
+            Ok(vec![0..2, 2..4, 4..6, 6..8])
+        );
+        assert_eq!(node_validator_ranges(3, 1), Ok(vec![0..3]));
+        assert_eq!(node_validator_ranges(3, 3), Ok(vec![0..1, 1..2, 2..3]));
+    }
+
+    #[test]
+    fn node_validator_ranges_spread_remainder() {
+        // The remainder is spread over the first nodes rather than creating an extra node.
+        assert_eq!(
+            node_validator_ranges(10, 4),
+            Ok(vec![0..3, 3..6, 6..8, 8..10])
+        );
+        assert_eq!(node_validator_ranges(5, 3), Ok(vec![0..2, 2..4, 4..5]));
+    }
+
+    #[test]
+    fn node_validator_dirs_invalid_node_count() {
+        let base_dir = Path::new("/nonexistent");
+        let mnemonic = "abandon abandon abandon abandon abandon abandon abandon abandon abandon \
+                        abandon abandon about";
+
+        assert!(generate_node_validator_dirs(4, Some(0), mnemonic, base_dir).is_err());
+        assert!(generate_node_validator_dirs(4, Some(5), mnemonic, base_dir).is_err());
+        assert!(generate_node_validator_dirs(0, Some(1), mnemonic, base_dir).is_err());
+    }
+}
@@ -4,11 +4,13 @@ use environment::Environment;
 use execution_layer::{
     auth::JwtKey,
     test_utils::{
-        Config, MockExecutionConfig, MockServer, DEFAULT_JWT_SECRET, DEFAULT_TERMINAL_BLOCK,
+        Config, MockExecutionConfig, MockExecutionFaults, MockServer, DEFAULT_JWT_SECRET,
+        DEFAULT_TERMINAL_BLOCK,
     },
 };
 use std::net::Ipv4Addr;
 use std::path::PathBuf;
+use std::time::Duration;
 use types::*;
 
 pub fn run<E: EthSpec>(mut env: Environment<E>, matches: &ArgMatches) -> Result<(), String> {
@@ -19,6 +21,16 @@ pub fn run<E: EthSpec>(mut env: Environment<E>, matches: &ArgMatches) -> Result<
     let shanghai_time = parse_required(matches, "shanghai-time")?;
     let cancun_time = parse_optional(matches, "cancun-time")?;
     let prague_time = parse_optional(matches, "prague-time")?;
+    let payload_value: Uint256 = parse_required(matches, "payload-value")?;
+    let response_delay_ms: u64 = parse_required(matches, "response-delay-ms")?;
+    let error_probability: f64 = parse_required(matches, "error-probability")?;
+    let fault_methods: Option<String> = parse_optional(matches, "fault-methods")?;
+
+    if !(0.0..=1.0).contains(&error_probability) {
+        return Err(format!(
+            "--error-probability must be between 0 and 1, got {error_probability}"
+        ));
+    }
 
     let handle = env.core_context().executor.handle().unwrap();
     let spec = &E::default_spec();
@@ -50,6 +62,25 @@ pub fn run<E: EthSpec>(mut env: Environment<E>, matches: &ArgMatches) -> Result<
         server.all_payloads_valid();
     }
 
+    server.set_payload_value(payload_value);
+    let faults = MockExecutionFaults {
+        response_delay: Duration::from_millis(response_delay_ms),
+        error_probability,
+        methods: fault_methods
+            .map(|methods| {
+                methods
+                    .split(',')
+                    .map(|method| method.trim().to_string())
+                    .filter(|method| !method.is_empty())
+                    .collect()
+            })
+            .unwrap_or_default(),
+    };
+    if response_delay_ms > 0 || error_probability > 0.0 {
+        eprintln!("Injecting faults: {:?}", faults);
+    }
+    server.set_faults(faults);
+
     eprintln!(
         "This tool is for TESTING PURPOSES ONLY. Do not use in production or on mainnet. \
         It cannot perform validator duties. It may cause nodes to follow an invalid chain."
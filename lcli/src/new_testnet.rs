@@ -0,0 +1,250 @@
+//! Generates a complete testnet directory for a custom devnet in a single step.
+//!
+//! The genesis validators are derived from a BIP-39 mnemonic, so the keystores written alongside
+//! the testnet directory can be imported directly by the validator client.
+use crate::mnemonic_validators::{
+    derive_voting_keypair, generate_node_validator_dirs, seed_from_mnemonic_phrase,
+};
+use clap::ArgMatches;
+use clap_utils::{parse_optional, parse_required, parse_ssz_optional};
+use eth2_network_config::{Eth2NetworkConfig, GenesisStateSource};
+use genesis::{bls_withdrawal_credentials, interop_genesis_state_with_withdrawal_credentials};
+use kzg::trusted_setup::get_trusted_setup;
+use rayon::prelude::*;
+use ssz::Encode;
+use std::path::PathBuf;
+use std::time::{SystemTime, UNIX_EPOCH};
+use types::{
+    Address, ChainSpec, Config, Epoch, EthSpec, ExecutionBlockHash, ExecutionPayloadHeader,
+    ExecutionPayloadHeaderBellatrix, ExecutionPayloadHeaderCapella, ExecutionPayloadHeaderDeneb,
+    ExecutionPayloadHeaderElectra, ForkName, Hash256,
+};
+
+pub fn run<E: EthSpec>(
+    testnet_dir: PathBuf,
+    mut spec: ChainSpec,
+    matches: &ArgMatches,
+) -> Result<(), String> {
+    let force = matches.get_flag("force");
+    let validator_count: usize = parse_required(matches, "validator-count")?;
+    let mnemonic_phrase: String = parse_required(matches, "mnemonic-phrase")?;
+    let keystores_dir: Option<PathBuf> = parse_optional(matches, "keystores-dir")?;
+    let node_count: Option<usize> = parse_optional(matches, "node-count")?;
+    let execution_genesis_hash: Option<Hash256> =
+        parse_optional(matches, "execution-genesis-hash")?;
+
+    if testnet_dir.exists() && !force {
+        return Err(format!(
+            "{:?} already exists, use --force to overwrite",
+            testnet_dir
+        ));
+    }
+
+    if validator_count == 0 {
+        return Err("--validator-count must be greater than 0".to_string());
+    }
+
+    /*
+     * Configure the chain spec.
+     */
+    if let Some(genesis_fork_version) = parse_ssz_optional(matches, "genesis-fork-version")? {
+        spec.genesis_fork_version = genesis_fork_version;
+    }
+    if let Some(seconds_per_slot) = parse_optional(matches, "seconds-per-slot")? {
+        spec.seconds_per_slot = seconds_per_slot;
+    }
+    if let Some(chain_id) = parse_optional(matches, "execution-chain-id")? {
+        spec.deposit_chain_id = chain_id;
+        spec.deposit_network_id = chain_id;
+    }
+    if let Some(address) = parse_optional::<Address>(matches, "deposit-contract-address")? {
+        spec.deposit_contract_address = address;
+    }
+
+    spec.altair_fork_epoch = Some(parse_required(matches, "altair-fork-epoch")?);
+    spec.bellatrix_fork_epoch = Some(parse_required(matches, "bellatrix-fork-epoch")?);
+    spec.capella_fork_epoch = Some(parse_required(matches, "capella-fork-epoch")?);
+    spec.deneb_fork_epoch = Some(parse_required(matches, "deneb-fork-epoch")?);
+    spec.electra_fork_epoch = Some(parse_required(matches, "electra-fork-epoch")?);
+    spec.eip7594_fork_epoch = parse_optional(matches, "eip7594-fork-epoch")?;
+    check_fork_epochs(&spec)?;
+
+    if let Some(custody_requirement) = parse_optional(matches, "custody-requirement")? {
+        spec.custody_requirement = custody_requirement;
+    }
+    if let Some(subnet_count) = parse_optional(matches, "data-column-sidecar-subnet-count")? {
+        spec.data_column_sidecar_subnet_count = subnet_count;
+    }
+    if let Some(samples_per_slot) = parse_optional(matches, "samples-per-slot")? {
+        spec.samples_per_slot = samples_per_slot;
+    }
+    check_custody_parameters(&spec)?;
+
+    let genesis_time = match parse_optional(matches, "genesis-time")? {
+        Some(genesis_time) => genesis_time,
+        None => {
+            let genesis_delay: u64 = parse_required(matches, "genesis-delay")?;
+            SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .map_err(|e| format!("Unable to read system time: {:?}", e))?
+                .as_secs()
+                + genesis_delay
+        }
+    };
+
+    // The genesis state is created directly rather than from deposit contract logs, so the
+    // genesis parameters are set to match it exactly.
+    spec.min_genesis_time = genesis_time;
+    spec.genesis_delay = 0;
+    spec.min_genesis_active_validator_count = validator_count as u64;
+
+    /*
+     * Build the genesis state.
+     */
+    let genesis_fork = spec.fork_name_at_epoch(E::genesis_epoch());
+    let execution_payload_header = match (genesis_fork.bellatrix_enabled(), execution_genesis_hash)
+    {
+        (false, _) => None,
+        (true, Some(block_hash)) => Some(genesis_execution_payload_header::<E>(
+            genesis_fork,
+            ExecutionBlockHash::from_root(block_hash),
+        )?),
+        (true, None) => {
+            return Err(
+                "--execution-genesis-hash is required when Bellatrix is enabled at genesis"
+                    .to_string(),
+            )
+        }
+    };
+    let eth1_block_hash = execution_genesis_hash
+        .unwrap_or_else(|| Hash256::from_slice(genesis::DEFAULT_ETH1_BLOCK_HASH));
+
+    let seed = seed_from_mnemonic_phrase(&mnemonic_phrase)?;
+    let keypairs = (0..validator_count as u32)
+        .into_par_iter()
+        .map(|index| derive_voting_keypair(&seed, index).map(|(keypair, _)| keypair))
+        .collect::<Result<Vec<_>, _>>()?;
+    let withdrawal_credentials = keypairs
+        .iter()
+        .map(|keypair| bls_withdrawal_credentials(&keypair.pk, &spec))
+        .collect::<Vec<_>>();
+
+    let genesis_state = interop_genesis_state_with_withdrawal_credentials::<E>(
+        &keypairs,
+        &withdrawal_credentials,
+        genesis_time,
+        eth1_block_hash,
+        execution_payload_header,
+        &spec,
+    )?;
+
+    /*
+     * Write the testnet directory and keystores.
+     */
+    let network_config = Eth2NetworkConfig {
+        deposit_contract_deploy_block: 0,
+        boot_enr: None,
+        genesis_state_source: GenesisStateSource::IncludedBytes,
+        genesis_state_bytes: Some(genesis_state.as_ssz_bytes().into()),
+        config: Config::from_chain_spec::<E>(&spec),
+        kzg_trusted_setup: get_trusted_setup(),
+    };
+    network_config.write_to_file(testnet_dir.clone(), force)?;
+
+    println!(
+        "Wrote {} genesis at {} with {} validators to {:?}",
+        genesis_fork, genesis_time, validator_count, testnet_dir
+    );
+
+    if let Some(keystores_dir) = keystores_dir {
+        generate_node_validator_dirs(
+            validator_count,
+            node_count,
+            &mnemonic_phrase,
+            &keystores_dir,
+        )?;
+    }
+
+    Ok(())
+}
+
+/// Returns an execution payload header for `fork_name` which builds upon the execution genesis
+/// block with the given `block_hash`.
+fn genesis_execution_payload_header<E: EthSpec>(
+    fork_name: ForkName,
+    block_hash: ExecutionBlockHash,
+) -> Result<ExecutionPayloadHeader<E>, String> {
+    let header = match fork_name {
+        ForkName::Base | ForkName::Altair => {
+            return Err(format!("{} has no execution payload", fork_name))
+        }
+        ForkName::Bellatrix => ExecutionPayloadHeader::Bellatrix(ExecutionPayloadHeaderBellatrix {
+            block_hash,
+            ..Default::default()
+        }),
+        ForkName::Capella => ExecutionPayloadHeader::Capella(ExecutionPayloadHeaderCapella {
+            block_hash,
+            ..Default::default()
+        }),
+        ForkName::Deneb => ExecutionPayloadHeader::Deneb(ExecutionPayloadHeaderDeneb {
+            block_hash,
+            ..Default::default()
+        }),
+        ForkName::Electra => ExecutionPayloadHeader::Electra(ExecutionPayloadHeaderElectra {
+            block_hash,
+            ..Default::default()
+        }),
+    };
+    Ok(header)
+}
+
+/// Check that the fork epochs are scheduled in order.
+fn check_fork_epochs(spec: &ChainSpec) -> Result<(), String> {
+    let fork_epochs = [
+        ("altair", spec.altair_fork_epoch),
+        ("bellatrix", spec.bellatrix_fork_epoch),
+        ("capella", spec.capella_fork_epoch),
+        ("deneb", spec.deneb_fork_epoch),
+        ("electra", spec.electra_fork_epoch),
+        ("eip7594", spec.eip7594_fork_epoch),
+    ];
+
+    let mut previous: Option<(&str, Epoch)> = None;
+    for (name, epoch) in fork_epochs {
+        let Some(epoch) = epoch else { continue };
+        if let Some((previous_name, previous_epoch)) = previous {
+            if epoch < previous_epoch {
+                return Err(format!(
+                    "{} fork epoch ({}) must not be earlier than the {} fork epoch ({})",
+                    name, epoch, previous_name, previous_epoch
+                ));
+            }
+        }
+        previous = Some((name, epoch));
+    }
+    Ok(())
+}
+
+/// Check that the PeerDAS custody parameters are consistent with one another.
+fn check_custody_parameters(spec: &ChainSpec) -> Result<(), String> {
+    let subnet_count = spec.data_column_sidecar_subnet_count;
+    if subnet_count == 0 || spec.number_of_columns as u64 % subnet_count != 0 {
+        return Err(format!(
+            "--data-column-sidecar-subnet-count ({}) must divide the number of columns ({})",
+            subnet_count, spec.number_of_columns
+        ));
+    }
+    if spec.custody_requirement > subnet_count {
+        return Err(format!(
+            "--custody-requirement ({}) must not exceed the number of data column subnets ({})",
+            spec.custody_requirement, subnet_count
+        ));
+    }
+    if spec.samples_per_slot > spec.number_of_columns as u64 {
+        return Err(format!(
+            "--samples-per-slot ({}) must not exceed the number of columns ({})",
+            spec.samples_per_slot, spec.number_of_columns
+        ));
+    }
+    Ok(())
+}
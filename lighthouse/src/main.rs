@@ -686,6 +686,12 @@ fn run<E: EthSpec>(
 
     match matches.subcommand() {
         Some(("beacon_node", matches)) => {
+            if let Some(identity_matches) =
+                matches.subcommand_matches(beacon_node::GENERATE_IDENTITY_CMD)
+            {
+                return beacon_node::generate_identity(identity_matches);
+            }
+
             let context = environment.core_context();
             let log = context.log().clone();
             let executor = context.executor.clone();
@@ -13,7 +13,7 @@ use account_manager::{
     CMD as ACCOUNT_CMD, WALLETS_DIR_FLAG, *,
 };
 use account_utils::{
-    eth2_keystore::KeystoreBuilder,
+    eth2_keystore::{json_keystore::Kdf, Keystore, KeystoreBuilder},
     validator_definitions::{SigningDefinition, ValidatorDefinition, ValidatorDefinitions},
     ZeroizeString, STDIN_INPUTS_FLAG,
 };
@@ -399,6 +399,55 @@ fn validator_create() {
     assert_eq!(dir_validator_count(validator_dir.path()), 6);
 }
 
+#[test]
+fn validator_create_with_kdf() {
+    let base_dir = tempdir().unwrap();
+    let validator_dir = tempdir().unwrap();
+    let secrets_dir = tempdir().unwrap();
+
+    let wallet = TestWallet::new(base_dir.path(), "wally");
+    wallet.create_expect_success();
+
+    let create = |kdf: &str| {
+        output_result(
+            validator_cmd()
+                .arg(format!("--{}", VALIDATOR_DIR_FLAG))
+                .arg(validator_dir.path().as_os_str())
+                .arg(CREATE_CMD)
+                .arg(format!("--{}", WALLETS_DIR_FLAG))
+                .arg(wallet.base_dir().into_os_string())
+                .arg(format!("--{}", WALLET_NAME_FLAG))
+                .arg(&wallet.name)
+                .arg(format!("--{}", WALLET_PASSWORD_FLAG))
+                .arg(wallet.password_path().into_os_string())
+                .arg(format!("--{}", SECRETS_DIR_FLAG))
+                .arg(secrets_dir.path().as_os_str())
+                .arg(format!("--{}", COUNT_FLAG))
+                .arg("1")
+                .arg("--kdf")
+                .arg(kdf),
+        )
+    };
+
+    create("argon2id").unwrap_err();
+    create("scrypt:n=1000").unwrap_err();
+    assert_eq!(dir_validator_count(validator_dir.path()), 0);
+
+    let stdout = from_utf8(&create("pbkdf2:c=1024").unwrap().stdout)
+        .expect("stdout is not utf8")
+        .to_string();
+    let (_, pubkey) = stdout
+        .trim_end()
+        .split_once('\t')
+        .expect("line must have tab");
+
+    let dir = ValidatorDir::open(validator_dir.path().join(pubkey)).unwrap();
+    let keystore = Keystore::from_json_file(dir.voting_keystore_path()).unwrap();
+    assert!(matches!(keystore.kdf(), Kdf::Pbkdf2(params) if params.c == 1024));
+    assert!(keystore.has_fast_kdf());
+    dir.voting_keypair(secrets_dir.path()).unwrap();
+}
+
 #[test]
 fn validator_import_launchpad() {
     const PASSWORD: &str = "cats";
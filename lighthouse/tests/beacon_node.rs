@@ -2,17 +2,24 @@ use beacon_node::ClientConfig as Config;
 
 use crate::exec::{CommandLineTestExec, CompletedTest};
 use beacon_node::beacon_chain::chain_config::{
-    DisallowedReOrgOffsets, DEFAULT_RE_ORG_CUTOFF_DENOMINATOR, DEFAULT_RE_ORG_HEAD_THRESHOLD,
-    DEFAULT_RE_ORG_MAX_EPOCHS_SINCE_FINALIZATION,
+    BlobFetchStrategy, DisallowedReOrgOffsets, ReOrgSimulationParams, ReOrgThreshold,
+    StorageThresholds, DEFAULT_KZG_VERIFICATION_THREADS, DEFAULT_RE_ORG_ANALYSIS_MAX_DEPTH,
+    DEFAULT_RE_ORG_CUTOFF_DENOMINATOR, DEFAULT_RE_ORG_HEAD_THRESHOLD,
+    DEFAULT_RE_ORG_MAX_EPOCHS_SINCE_FINALIZATION, DEFAULT_RE_ORG_PARENT_THRESHOLD,
 };
 use beacon_node::beacon_chain::graffiti_calculator::GraffitiOrigin;
-use beacon_processor::BeaconProcessorConfig;
+use beacon_node::beacon_chain::store::config::FsyncPolicy;
+use beacon_node::beacon_chain::store::DBColumn;
+use beacon_processor::{BeaconProcessorConfig, WorkType};
 use eth1::Eth1Endpoint;
+use lighthouse_network::rpc::config::TrustedPeerRateLimit;
+use lighthouse_network::service::PublishRateLimiterConfig;
 use lighthouse_network::PeerId;
 use lighthouse_version;
 use std::fs::File;
 use std::io::{Read, Write};
 use std::net::{IpAddr, Ipv4Addr, Ipv6Addr};
+use std::num::NonZeroU64;
 use std::path::Path;
 use std::path::PathBuf;
 use std::process::Command;
@@ -21,7 +28,7 @@ use std::string::ToString;
 use std::time::Duration;
 use tempfile::TempDir;
 use types::non_zero_usize::new_non_zero_usize;
-use types::{Address, Checkpoint, Epoch, Hash256, MainnetEthSpec};
+use types::{Address, ChainSpec, Checkpoint, Epoch, Hash256, MainnetEthSpec, SubnetId};
 use unused_port::{unused_tcp4_port, unused_tcp6_port, unused_udp4_port, unused_udp6_port};
 
 const DEFAULT_EXECUTION_ENDPOINT: &str = "http://localhost:8551/";
@@ -297,6 +304,21 @@ fn paranoid_block_proposal_on() {
         .with_config(|config| assert!(config.chain.paranoid_block_proposal));
 }
 
+#[test]
+fn track_attestation_sources_default() {
+    CommandLineTest::new()
+        .run_with_zero_port()
+        .with_config(|config| assert!(!config.chain.track_attestation_sources));
+}
+
+#[test]
+fn track_attestation_sources_on() {
+    CommandLineTest::new()
+        .flag("track-attestation-sources", None)
+        .run_with_zero_port()
+        .with_config(|config| assert!(config.chain.track_attestation_sources));
+}
+
 #[test]
 fn reset_payload_statuses_default() {
     CommandLineTest::new()
@@ -321,6 +343,97 @@ fn freezer_dir_flag() {
         .with_config(|config| assert_eq!(config.freezer_db_path, Some(dir.path().to_path_buf())));
 }
 
+#[test]
+fn freezer_column_dir_default() {
+    CommandLineTest::new()
+        .run_with_zero_port()
+        .with_config(|config| assert!(config.store.freezer_column_dirs.is_empty()));
+}
+
+#[test]
+fn freezer_column_dir_flag() {
+    let snapshot_dir = TempDir::new().expect("Unable to create temporary directory");
+    let diff_dir = TempDir::new().expect("Unable to create temporary directory");
+    CommandLineTest::new()
+        .flag(
+            "freezer-column-dir",
+            Some(&format!("bsn={}", snapshot_dir.path().display())),
+        )
+        .flag(
+            "freezer-column-dir",
+            Some(&format!("bsd={}", diff_dir.path().display())),
+        )
+        .run_with_zero_port()
+        .with_config(|config| {
+            assert_eq!(
+                config.store.freezer_column_dirs,
+                vec![
+                    (
+                        DBColumn::BeaconStateSnapshot,
+                        snapshot_dir.path().to_path_buf()
+                    ),
+                    (DBColumn::BeaconStateDiff, diff_dir.path().to_path_buf()),
+                ]
+            )
+        });
+}
+
+#[test]
+#[should_panic]
+fn freezer_column_dir_invalid() {
+    CommandLineTest::new()
+        .flag("freezer-column-dir", Some("/tmp/no-column"))
+        .run_with_zero_port();
+}
+
+#[test]
+fn hot_db_write_batching_default() {
+    CommandLineTest::new()
+        .run_with_zero_port()
+        .with_config(|config| {
+            assert!(!config.store.hot_write_batching);
+            assert_eq!(config.store.hot_fsync_policy, FsyncPolicy::Always);
+        });
+}
+
+#[test]
+fn hot_tree_states_default() {
+    CommandLineTest::new()
+        .run_with_zero_port()
+        .with_config(|config| assert!(!config.store.hot_tree_states));
+}
+
+#[test]
+fn hot_tree_states_flag() {
+    CommandLineTest::new()
+        .flag("hot-tree-states", None)
+        .run_with_zero_port()
+        .with_config(|config| assert!(config.store.hot_tree_states));
+}
+
+#[test]
+fn hot_db_write_batching_flag() {
+    CommandLineTest::new()
+        .flag("hot-db-write-batching", None)
+        .flag("hot-db-fsync-policy", Some("periodic:500"))
+        .run_with_zero_port()
+        .with_config(|config| {
+            assert!(config.store.hot_write_batching);
+            assert_eq!(
+                config.store.hot_fsync_policy,
+                FsyncPolicy::Periodic(Duration::from_millis(500))
+            );
+        });
+}
+
+#[test]
+#[should_panic]
+fn hot_db_fsync_policy_without_write_batching() {
+    CommandLineTest::new()
+        .flag("hot-db-fsync-policy", Some("per-slot"))
+        .run_with_zero_port();
+}
+
 #[test]
 fn graffiti_flag() {
     CommandLineTest::new()
@@ -793,6 +906,41 @@ fn network_dir_flag() {
         .with_config(|config| assert_eq!(config.network.network_dir, dir.path()));
 }
 #[test]
+fn network_key_file_default() {
+    CommandLineTest::new()
+        .run_with_zero_port()
+        .with_config(|config| {
+            assert_eq!(config.network.network_key_file, None);
+            assert_eq!(
+                config.network.network_key_rotation_grace_period,
+                Duration::from_secs(3600)
+            );
+        });
+}
+#[test]
+fn network_key_file_flag() {
+    let dir = TempDir::new().expect("Unable to create temporary directory");
+    let key_file = dir.path().join("next_key");
+    CommandLineTest::new()
+        .flag("network-key-file", key_file.as_os_str().to_str())
+        .flag("network-key-rotation-grace-period", Some("86400"))
+        .run_with_zero_port()
+        .with_config(|config| {
+            assert_eq!(config.network.network_key_file, Some(key_file.clone()));
+            assert_eq!(
+                config.network.network_key_rotation_grace_period,
+                Duration::from_secs(86400)
+            );
+        });
+}
+#[test]
+#[should_panic]
+fn network_key_rotation_grace_period_requires_key_file() {
+    CommandLineTest::new()
+        .flag("network-key-rotation-grace-period", Some("86400"))
+        .run_with_zero_port();
+}
+#[test]
 fn network_target_peers_flag() {
     CommandLineTest::new()
         .flag("target-peers", Some("55"))
@@ -850,6 +998,39 @@ fn network_subscribe_all_subnets_flag() {
         .with_config(|config| assert!(config.network.subscribe_all_subnets));
 }
 #[test]
+fn network_long_lived_subnets_override_default() {
+    CommandLineTest::new()
+        .run_with_zero_port()
+        .with_config(|config| assert_eq!(config.network.long_lived_subnets_override, None));
+}
+#[test]
+fn network_long_lived_subnets_override_flag() {
+    CommandLineTest::new()
+        .flag("long-lived-subnets-override", Some("3, 60"))
+        .run_with_zero_port()
+        .with_config(|config| {
+            assert_eq!(
+                config.network.long_lived_subnets_override,
+                Some(vec![SubnetId::new(3), SubnetId::new(60)])
+            )
+        });
+}
+#[test]
+#[should_panic]
+fn network_long_lived_subnets_override_out_of_range() {
+    CommandLineTest::new()
+        .flag("long-lived-subnets-override", Some("64"))
+        .run_with_zero_port();
+}
+#[test]
+#[should_panic]
+fn network_long_lived_subnets_override_conflicts_with_all_subnets() {
+    CommandLineTest::new()
+        .flag("long-lived-subnets-override", Some("1"))
+        .flag("subscribe-all-subnets", None)
+        .run_with_zero_port();
+}
+#[test]
 fn network_import_all_attestations_flag() {
     CommandLineTest::new()
         .flag("import-all-attestations", None)
@@ -920,9 +1101,8 @@ fn network_listen_address_flag_dual_stack() {
 #[test]
 #[should_panic]
 fn network_listen_address_flag_wrong_double_v4_value_config() {
-    // It's actually possible to listen over multiple sockets in libp2p over the same ip version.
-    // However this is not compatible with the single contactable address over each version in ENR.
-    // Because of this, it's important to test this is disallowed.
+    // The unspecified address already listens on all interfaces, so it can't be combined with
+    // other addresses of the same ip version.
     const V4_ADDR1: &str = "127.0.0.1";
     const V4_ADDR2: &str = "0.0.0.0";
     CommandLineTest::new()
@@ -931,16 +1111,82 @@ fn network_listen_address_flag_wrong_double_v4_value_config() {
         .run_with_zero_port();
 }
 #[test]
-#[should_panic]
-fn network_listen_address_flag_wrong_double_v6_value_config() {
-    // It's actually possible to listen over multiple sockets in libp2p over the same ip version.
-    // However this is not compatible with the single contactable address over each version in ENR.
-    // Because of this, it's important to test this is disallowed.
+fn network_listen_address_flag_double_v6_value_config() {
+    // The first address is the primary address used by discovery and the ENR, the second is only
+    // used by libp2p.
     const V6_ADDR1: &str = "::3";
     const V6_ADDR2: &str = "::1";
     CommandLineTest::new()
         .flag("listen-address", Some(V6_ADDR1))
         .flag("listen-address", Some(V6_ADDR2))
+        .run_with_zero_port()
+        .with_config(|config| {
+            assert_eq!(
+                config.network.listen_addrs().v6().map(|addr| addr.addr),
+                Some(V6_ADDR1.parse::<Ipv6Addr>().unwrap())
+            );
+            let additional = config.network.additional_listen_addrs();
+            assert_eq!(additional.len(), 1);
+            assert_eq!(additional[0].addr, V6_ADDR2.parse::<IpAddr>().unwrap());
+        });
+}
+#[test]
+fn network_listen_address_flag_multi_homed() {
+    CommandLineTest::new()
+        .flag("listen-address", Some("127.0.0.2:9200"))
+        .flag("listen-address", Some("::1"))
+        .flag("listen-address", Some("127.0.0.3"))
+        .flag("listen-address", Some("127.0.0.4:9300"))
+        .flag("port6", Some("9400"))
+        .flag("allow-insecure-genesis-sync", None)
+        .run()
+        .with_config(|config| {
+            assert_eq!(
+                config.network.listen_addrs().v4().map(|listen_addr| (
+                    listen_addr.addr,
+                    listen_addr.disc_port,
+                    listen_addr.quic_port,
+                    listen_addr.tcp_port
+                )),
+                Some((Ipv4Addr::new(127, 0, 0, 2), 9200, 9201, 9200))
+            );
+            assert_eq!(
+                config
+                    .network
+                    .listen_addrs()
+                    .v6()
+                    .map(|listen_addr| (listen_addr.addr, listen_addr.tcp_port)),
+                Some((Ipv6Addr::LOCALHOST, 9400))
+            );
+            let additional = config
+                .network
+                .additional_listen_addrs()
+                .iter()
+                .map(|listen_addr| {
+                    (
+                        listen_addr.addr,
+                        listen_addr.quic_port,
+                        listen_addr.tcp_port,
+                    )
+                })
+                .collect::<Vec<_>>();
+            assert_eq!(
+                additional,
+                vec![
+                    (IpAddr::V4(Ipv4Addr::new(127, 0, 0, 3)), 9201, 9200),
+                    (IpAddr::V4(Ipv4Addr::new(127, 0, 0, 4)), 9301, 9300),
+                ]
+            );
+            // TCP and QUIC for each of the primary and additional addresses.
+            assert_eq!(config.network.libp2p_listen_addresses().count(), 8);
+        });
+}
+#[test]
+#[should_panic]
+fn network_listen_address_flag_duplicate_value() {
+    CommandLineTest::new()
+        .flag("listen-address", Some("127.0.0.2"))
+        .flag("listen-address", Some("127.0.0.2:9200"))
         .run_with_zero_port();
 }
 #[test]
@@ -1212,171 +1458,376 @@ fn disable_quic_flag() {
         .with_config(|config| assert!(config.network.disable_quic_support));
 }
 #[test]
-fn disable_peer_scoring_flag() {
+fn disable_inbound_flag() {
     CommandLineTest::new()
-        .flag("disable-peer-scoring", None)
+        .flag("disable-inbound", None)
         .run_with_zero_port()
-        .with_config(|config| assert!(config.network.disable_peer_scoring));
+        .with_config(|config| assert!(config.network.disable_inbound));
 }
 #[test]
-fn disable_upnp_flag() {
+fn disable_inbound_default() {
     CommandLineTest::new()
-        .flag("disable-upnp", None)
         .run_with_zero_port()
-        .with_config(|config| assert!(!config.network.upnp_enabled));
+        .with_config(|config| assert!(!config.network.disable_inbound));
 }
 #[test]
-fn disable_backfill_rate_limiting_flag() {
+fn disable_peer_scoring_flag() {
     CommandLineTest::new()
-        .flag("disable-backfill-rate-limiting", None)
+        .flag("disable-peer-scoring", None)
         .run_with_zero_port()
-        .with_config(|config| assert!(!config.beacon_processor.enable_backfill_rate_limiting));
+        .with_config(|config| assert!(config.network.disable_peer_scoring));
 }
 #[test]
-fn default_backfill_rate_limiting_flag() {
+fn preserve_client_diversity_default() {
     CommandLineTest::new()
         .run_with_zero_port()
-        .with_config(|config| assert!(config.beacon_processor.enable_backfill_rate_limiting));
+        .with_config(|config| assert!(!config.network.preserve_client_diversity));
 }
 #[test]
-fn default_boot_nodes() {
-    let number_of_boot_nodes = 15;
-
+fn preserve_client_diversity_flag() {
     CommandLineTest::new()
+        .flag("preserve-client-diversity", None)
         .run_with_zero_port()
-        .with_config(|config| {
-            // Lighthouse Team (Sigma Prime)
-            assert_eq!(config.network.boot_nodes_enr.len(), number_of_boot_nodes);
-        });
+        .with_config(|config| assert!(config.network.preserve_client_diversity));
 }
 #[test]
-fn boot_nodes_flag() {
-    let nodes = "enr:-IS4QHCYrYZbAKWCBRlAy5zzaDZXJBGkcnh4MHcBFZntXNFrdvJjX04jRzjzCBOonrkTfj499SZuOh8R33Ls8RRcy5wBgmlkgnY0gmlwhH8AAAGJc2VjcDI1NmsxoQPKY0yuDUmstAHYpMa2_oxVtw0RW_QAdpzBQA8yWM0xOIN1ZHCCdl8,\
-                enr:-LK4QFOFWca5ABQzxiCRcy37G7wy1K6zD4qMYBSN5ozzanwze_XVvXVhCk9JvF0cHXOBZrHK1E4vU7Gn-a0bHVczoDU6h2F0dG5ldHOIAAAAAAAAAACEZXRoMpA7CIeVAAAgCf__________gmlkgnY0gmlwhNIy-4iJc2VjcDI1NmsxoQJA3AXQJ6M3NpBWtJS3HPtbXG14t7qHjXuIaL6IOz89T4N0Y3CCIyiDdWRwgiMo";
-    let enr: Vec<&str> = nodes.split(',').collect();
+fn gossip_duplicate_cache_default() {
     CommandLineTest::new()
-        .flag("boot-nodes", Some(nodes))
         .run_with_zero_port()
         .with_config(|config| {
-            assert_eq!(config.network.boot_nodes_enr[0].to_base64(), enr[0]);
-            assert_eq!(config.network.boot_nodes_enr[1].to_base64(), enr[1]);
+            assert_eq!(config.network.gossip_duplicate_cache_time, None);
+            assert_eq!(config.network.gossip_duplicate_cache_max_len, None);
         });
 }
 #[test]
-fn boot_nodes_multiaddr_flag() {
-    let nodes = "/ip4/0.0.0.0/tcp/9000/p2p/16Uiu2HAkynrfLjeoAP7R3WFySad2NfduShkTpx8f8ygpSSfP1yen,\
-                /ip4/192.167.55.55/tcp/9000/p2p/16Uiu2HAkynrfLjeoBP7R3WFyDad2NfduVhkWpx8f8ygpSSfP1yen";
-    let multiaddr: Vec<&str> = nodes.split(',').collect();
+fn gossip_duplicate_cache_flags() {
     CommandLineTest::new()
-        .flag("boot-nodes", Some(nodes))
+        .flag("gossip-duplicate-cache-time", Some("192"))
+        .flag("gossip-duplicate-cache-max-len", Some("50000"))
         .run_with_zero_port()
         .with_config(|config| {
             assert_eq!(
-                config.network.boot_nodes_multiaddr[0].to_string(),
-                multiaddr[0]
-            );
-            assert_eq!(
-                config.network.boot_nodes_multiaddr[1].to_string(),
-                multiaddr[1]
+                config.network.gossip_duplicate_cache_time,
+                Some(Duration::from_secs(192))
             );
+            assert_eq!(config.network.gossip_duplicate_cache_max_len, Some(50000));
         });
 }
 #[test]
-fn private_flag() {
+#[should_panic]
+fn gossip_duplicate_cache_max_len_zero() {
     CommandLineTest::new()
-        .flag("private", None)
-        .run_with_zero_port()
-        .with_config(|config| {
-            assert!(config.network.private);
-            assert!(matches!(
-                config.beacon_graffiti,
-                GraffitiOrigin::UserSpecified(_)
-            ));
-            assert_eq!(
-                config.beacon_graffiti.graffiti().to_string(),
-                "0x0000000000000000000000000000000000000000000000000000000000000000".to_string(),
-            );
-        });
+        .flag("gossip-duplicate-cache-max-len", Some("0"))
+        .run_with_zero_port();
 }
 #[test]
-fn zero_ports_flag() {
+fn max_bandwidth_mbps_default() {
     CommandLineTest::new()
         .run_with_zero_port()
-        .with_config(|config| {
-            assert_eq!(config.http_api.listen_port, 0);
-            assert_eq!(config.http_metrics.listen_port, 0);
-        });
+        .with_config(|config| assert_eq!(config.network.max_bandwidth_mbps, None));
 }
 #[test]
-fn network_load_flag() {
+fn max_bandwidth_mbps_flag() {
     CommandLineTest::new()
-        .flag("network-load", Some("4"))
+        .flag("max-bandwidth-mbps", Some("20"))
         .run_with_zero_port()
-        .with_config(|config| {
-            assert_eq!(config.network.network_load, 4);
-        });
+        .with_config(|config| assert_eq!(config.network.max_bandwidth_mbps, NonZeroU64::new(20)));
 }
-
-// Tests for ENR flags.
 #[test]
-fn enr_udp_port_flag() {
-    let port = DUMMY_ENR_UDP_PORT;
-    assert!(port != 0);
+#[should_panic]
+fn max_bandwidth_mbps_zero() {
     CommandLineTest::new()
-        .flag("enr-udp-port", Some(port.to_string().as_str()))
-        .run_with_zero_port()
-        .with_config(|config| {
-            assert_eq!(
-                config.network.enr_udp4_port.map(|port| port.get()),
-                Some(port)
-            )
-        });
+        .flag("max-bandwidth-mbps", Some("0"))
+        .run_with_zero_port();
 }
 #[test]
-fn enr_quic_port_flag() {
-    let port = DUMMY_ENR_QUIC_PORT;
+fn gossip_publish_retry_default() {
     CommandLineTest::new()
-        .flag("enr-quic-port", Some(port.to_string().as_str()))
         .run_with_zero_port()
         .with_config(|config| {
+            assert_eq!(config.network.gossip_publish_retry_min_mesh_peers, 4);
             assert_eq!(
-                config.network.enr_quic4_port.map(|port| port.get()),
-                Some(port)
-            )
+                config.network.gossip_publish_retry_delay,
+                Duration::from_millis(500)
+            );
         });
 }
 #[test]
-fn enr_tcp_port_flag() {
-    let port = DUMMY_ENR_TCP_PORT;
+fn publish_limiter_default() {
     CommandLineTest::new()
-        .flag("enr-tcp-port", Some(port.to_string().as_str()))
         .run_with_zero_port()
         .with_config(|config| {
             assert_eq!(
-                config.network.enr_tcp4_port.map(|port| port.get()),
-                Some(port)
+                config.network.publish_rate_limiter_config,
+                Some(PublishRateLimiterConfig::default())
             )
         });
 }
+
 #[test]
-fn enr_udp6_port_flag() {
-    let port = DUMMY_ENR_UDP_PORT;
+fn publish_limiter_topics_flag() {
     CommandLineTest::new()
-        .flag("enr-udp6-port", Some(port.to_string().as_str()))
+        .flag(
+            "publish-limiter-topics",
+            Some("beacon_aggregate_and_proof:64/12;sync_committee:32/12"),
+        )
         .run_with_zero_port()
         .with_config(|config| {
             assert_eq!(
-                config.network.enr_udp6_port.map(|port| port.get()),
-                Some(port)
-            )
+                config.network.publish_rate_limiter_config,
+                Some(
+                    "beacon_aggregate_and_proof:64/12;sync_committee:32/12"
+                        .parse()
+                        .unwrap()
+                )
+            );
+            assert_ne!(
+                config.network.publish_rate_limiter_config,
+                Some(PublishRateLimiterConfig::default())
+            );
         });
 }
+
 #[test]
-fn enr_quic6_port_flag() {
-    let port = DUMMY_ENR_QUIC_PORT;
+#[should_panic]
+fn publish_limiter_topics_invalid() {
     CommandLineTest::new()
-        .flag("enr-quic6-port", Some(port.to_string().as_str()))
-        .run_with_zero_port()
+        .flag("publish-limiter-topics", Some("beacon_block:1/12"))
+        .run_with_zero_port();
+}
+
+#[test]
+fn disable_publish_limiter_flag() {
+    CommandLineTest::new()
+        .flag("disable-publish-limiter", None)
+        .run_with_zero_port()
+        .with_config(|config| assert_eq!(config.network.publish_rate_limiter_config, None));
+}
+
+#[test]
+fn gossip_publish_retry_flags() {
+    CommandLineTest::new()
+        .flag("gossip-publish-retry-min-peers", Some("0"))
+        .flag("gossip-publish-retry-delay", Some("250"))
+        .run_with_zero_port()
+        .with_config(|config| {
+            assert_eq!(config.network.gossip_publish_retry_min_mesh_peers, 0);
+            assert_eq!(
+                config.network.gossip_publish_retry_delay,
+                Duration::from_millis(250)
+            );
+        });
+}
+#[test]
+fn disable_upnp_flag() {
+    CommandLineTest::new()
+        .flag("disable-upnp", None)
+        .run_with_zero_port()
+        .with_config(|config| assert!(!config.network.upnp_enabled));
+}
+#[test]
+fn disable_backfill_rate_limiting_flag() {
+    CommandLineTest::new()
+        .flag("disable-backfill-rate-limiting", None)
+        .run_with_zero_port()
+        .with_config(|config| assert!(!config.beacon_processor.enable_backfill_rate_limiting));
+}
+#[test]
+fn default_backfill_rate_limiting_flag() {
+    CommandLineTest::new()
+        .run_with_zero_port()
+        .with_config(|config| assert!(config.beacon_processor.enable_backfill_rate_limiting));
+}
+#[test]
+fn default_boot_nodes() {
+    let number_of_boot_nodes = 15;
+
+    CommandLineTest::new()
+        .run_with_zero_port()
+        .with_config(|config| {
+            // Lighthouse Team (Sigma Prime)
+            assert_eq!(config.network.boot_nodes_enr.len(), number_of_boot_nodes);
+        });
+}
+#[test]
+fn boot_nodes_flag() {
+    let nodes = "enr:-IS4QHCYrYZbAKWCBRlAy5zzaDZXJBGkcnh4MHcBFZntXNFrdvJjX04jRzjzCBOonrkTfj499SZuOh8R33Ls8RRcy5wBgmlkgnY0gmlwhH8AAAGJc2VjcDI1NmsxoQPKY0yuDUmstAHYpMa2_oxVtw0RW_QAdpzBQA8yWM0xOIN1ZHCCdl8,\
+                enr:-LK4QFOFWca5ABQzxiCRcy37G7wy1K6zD4qMYBSN5ozzanwze_XVvXVhCk9JvF0cHXOBZrHK1E4vU7Gn-a0bHVczoDU6h2F0dG5ldHOIAAAAAAAAAACEZXRoMpA7CIeVAAAgCf__________gmlkgnY0gmlwhNIy-4iJc2VjcDI1NmsxoQJA3AXQJ6M3NpBWtJS3HPtbXG14t7qHjXuIaL6IOz89T4N0Y3CCIyiDdWRwgiMo";
+    let enr: Vec<&str> = nodes.split(',').collect();
+    CommandLineTest::new()
+        .flag("boot-nodes", Some(nodes))
+        .run_with_zero_port()
+        .with_config(|config| {
+            assert_eq!(config.network.boot_nodes_enr[0].to_base64(), enr[0]);
+            assert_eq!(config.network.boot_nodes_enr[1].to_base64(), enr[1]);
+        });
+}
+#[test]
+fn discovery_dns_tree_flag() {
+    let trees =
+        "enrtree://AM5FCQLWIZX2QFPNJAP7VUERCCRNGRHWZG3YYHIUV7BVDQ5FDPRT2@nodes.example.org,\
+                enrtree://AM5FCQLWIZX2QFPNJAP7VUERCCRNGRHWZG3YYHIUV7BVDQ5FDPRT2@other.example.org";
+    CommandLineTest::new()
+        .flag("discovery-dns-tree", Some(trees))
+        .run_with_zero_port()
+        .with_config(|config| {
+            let parsed = config
+                .network
+                .discovery_dns_trees
+                .iter()
+                .map(|tree| tree.to_string())
+                .collect::<Vec<_>>();
+            assert_eq!(parsed, trees.split(',').collect::<Vec<_>>());
+        });
+}
+#[test]
+fn discovery_dns_tree_default() {
+    CommandLineTest::new()
+        .run_with_zero_port()
+        .with_config(|config| assert!(config.network.discovery_dns_trees.is_empty()));
+}
+#[test]
+#[should_panic]
+fn discovery_dns_tree_invalid_flag() {
+    CommandLineTest::new()
+        .flag("discovery-dns-tree", Some("enrtree://nodes.example.org"))
+        .run_with_zero_port();
+}
+#[test]
+fn boot_nodes_multiaddr_flag() {
+    let nodes = "/ip4/0.0.0.0/tcp/9000/p2p/16Uiu2HAkynrfLjeoAP7R3WFySad2NfduShkTpx8f8ygpSSfP1yen,\
+                /ip4/192.167.55.55/tcp/9000/p2p/16Uiu2HAkynrfLjeoBP7R3WFyDad2NfduVhkWpx8f8ygpSSfP1yen";
+    let multiaddr: Vec<&str> = nodes.split(',').collect();
+    CommandLineTest::new()
+        .flag("boot-nodes", Some(nodes))
+        .run_with_zero_port()
+        .with_config(|config| {
+            assert_eq!(
+                config.network.boot_nodes_multiaddr[0].to_string(),
+                multiaddr[0]
+            );
+            assert_eq!(
+                config.network.boot_nodes_multiaddr[1].to_string(),
+                multiaddr[1]
+            );
+        });
+}
+#[test]
+fn private_flag() {
+    CommandLineTest::new()
+        .flag("private", None)
+        .run_with_zero_port()
+        .with_config(|config| {
+            assert!(config.network.private);
+            assert!(matches!(
+                config.beacon_graffiti,
+                GraffitiOrigin::UserSpecified(_)
+            ));
+            assert_eq!(
+                config.beacon_graffiti.graffiti().to_string(),
+                "0x0000000000000000000000000000000000000000000000000000000000000000".to_string(),
+            );
+        });
+}
+#[test]
+fn zero_ports_flag() {
+    CommandLineTest::new()
+        .run_with_zero_port()
+        .with_config(|config| {
+            assert_eq!(config.http_api.listen_port, 0);
+            assert_eq!(config.http_metrics.listen_port, 0);
+        });
+}
+#[test]
+fn network_load_flag() {
+    CommandLineTest::new()
+        .flag("network-load", Some("4"))
+        .run_with_zero_port()
+        .with_config(|config| {
+            assert_eq!(config.network.network_load, 4);
+        });
+}
+#[test]
+fn network_dscp_default() {
+    CommandLineTest::new()
+        .run_with_zero_port()
+        .with_config(|config| assert_eq!(config.network.dscp, None));
+}
+#[test]
+fn network_dscp_flag() {
+    CommandLineTest::new()
+        .flag("network-dscp", Some("46"))
+        .run_with_zero_port()
+        .with_config(|config| assert_eq!(config.network.dscp, Some(46)));
+}
+#[test]
+#[should_panic]
+fn network_dscp_flag_out_of_range() {
+    CommandLineTest::new()
+        .flag("network-dscp", Some("64"))
+        .run_with_zero_port();
+}
+
+// Tests for ENR flags.
+#[test]
+fn enr_udp_port_flag() {
+    let port = DUMMY_ENR_UDP_PORT;
+    assert!(port != 0);
+    CommandLineTest::new()
+        .flag("enr-udp-port", Some(port.to_string().as_str()))
+        .run_with_zero_port()
+        .with_config(|config| {
+            assert_eq!(
+                config.network.enr_udp4_port.map(|port| port.get()),
+                Some(port)
+            )
+        });
+}
+#[test]
+fn enr_quic_port_flag() {
+    let port = DUMMY_ENR_QUIC_PORT;
+    CommandLineTest::new()
+        .flag("enr-quic-port", Some(port.to_string().as_str()))
+        .run_with_zero_port()
+        .with_config(|config| {
+            assert_eq!(
+                config.network.enr_quic4_port.map(|port| port.get()),
+                Some(port)
+            )
+        });
+}
+#[test]
+fn enr_tcp_port_flag() {
+    let port = DUMMY_ENR_TCP_PORT;
+    CommandLineTest::new()
+        .flag("enr-tcp-port", Some(port.to_string().as_str()))
+        .run_with_zero_port()
+        .with_config(|config| {
+            assert_eq!(
+                config.network.enr_tcp4_port.map(|port| port.get()),
+                Some(port)
+            )
+        });
+}
+#[test]
+fn enr_udp6_port_flag() {
+    let port = DUMMY_ENR_UDP_PORT;
+    CommandLineTest::new()
+        .flag("enr-udp6-port", Some(port.to_string().as_str()))
+        .run_with_zero_port()
+        .with_config(|config| {
+            assert_eq!(
+                config.network.enr_udp6_port.map(|port| port.get()),
+                Some(port)
+            )
+        });
+}
+#[test]
+fn enr_quic6_port_flag() {
+    let port = DUMMY_ENR_QUIC_PORT;
+    CommandLineTest::new()
+        .flag("enr-quic6-port", Some(port.to_string().as_str()))
+        .run_with_zero_port()
         .with_config(|config| {
             assert_eq!(
                 config.network.enr_quic6_port.map(|port| port.get()),
@@ -1581,176 +2032,540 @@ fn http_flag() {
         .with_config(|config| assert!(config.http_api.enabled));
 }
 #[test]
-fn http_address_flag() {
-    let addr = "127.0.0.99".parse::<IpAddr>().unwrap();
+fn http_address_flag() {
+    let addr = "127.0.0.99".parse::<IpAddr>().unwrap();
+    CommandLineTest::new()
+        .flag("http", None)
+        .flag("http-address", Some("127.0.0.99"))
+        .run_with_zero_port()
+        .with_config(|config| assert_eq!(config.http_api.listen_addr, addr));
+}
+#[test]
+fn http_address_ipv6_flag() {
+    let addr = "::1".parse::<IpAddr>().unwrap();
+    CommandLineTest::new()
+        .flag("http", None)
+        .flag("http-address", Some("::1"))
+        .run_with_zero_port()
+        .with_config(|config| assert_eq!(config.http_api.listen_addr, addr));
+}
+#[test]
+fn http_port_flag() {
+    let port1 = 0;
+    let port2 = 0;
+    CommandLineTest::new()
+        .flag("http", None)
+        .flag("http-port", Some(port1.to_string().as_str()))
+        .flag("port", Some(port2.to_string().as_str()))
+        .flag("allow-insecure-genesis-sync", None)
+        .run()
+        .with_config(|config| assert_eq!(config.http_api.listen_port, port1));
+}
+
+#[test]
+fn empty_inbound_rate_limiter_flag() {
+    CommandLineTest::new()
+        .run_with_zero_port()
+        .with_config(|config| {
+            assert_eq!(
+                config.network.inbound_rate_limiter_config,
+                Some(lighthouse_network::rpc::config::InboundRateLimiterConfig::default())
+            )
+        });
+}
+#[test]
+fn disable_inbound_rate_limiter_flag() {
+    CommandLineTest::new()
+        .flag("disable-inbound-rate-limiter", None)
+        .run_with_zero_port()
+        .with_config(|config| assert_eq!(config.network.inbound_rate_limiter_config, None));
+}
+
+#[test]
+fn trusted_peer_rate_limit_default() {
+    CommandLineTest::new()
+        .run_with_zero_port()
+        .with_config(|config| {
+            let network = &config.network;
+            let inbound = network.inbound_rate_limiter_config.as_ref().unwrap();
+            let outbound = network.outbound_rate_limiter_config.as_ref().unwrap();
+            assert_eq!(
+                inbound.0.trusted_peer_limit(),
+                TrustedPeerRateLimit::Default
+            );
+            assert_eq!(
+                outbound.0.trusted_peer_limit(),
+                TrustedPeerRateLimit::Default
+            );
+        });
+}
+#[test]
+fn trusted_peer_rate_limit_exempt_flag() {
+    CommandLineTest::new()
+        .flag("trusted-peer-rate-limit", Some("exempt"))
+        .run_with_zero_port()
+        .with_config(|config| {
+            let network = &config.network;
+            let inbound = network.inbound_rate_limiter_config.as_ref().unwrap();
+            let outbound = network.outbound_rate_limiter_config.as_ref().unwrap();
+            assert_eq!(inbound.0.trusted_peer_limit(), TrustedPeerRateLimit::Exempt);
+            assert_eq!(
+                outbound.0.trusted_peer_limit(),
+                TrustedPeerRateLimit::Exempt
+            );
+        });
+}
+#[test]
+fn trusted_peer_rate_limit_multiplier_flag() {
+    CommandLineTest::new()
+        .flag("trusted-peer-rate-limit", Some("8"))
+        .run_with_zero_port()
+        .with_config(|config| {
+            let network = &config.network;
+            let inbound = network.inbound_rate_limiter_config.as_ref().unwrap();
+            let outbound = network.outbound_rate_limiter_config.as_ref().unwrap();
+            assert_eq!(
+                inbound.0.trusted_peer_limit(),
+                TrustedPeerRateLimit::Multiplied(8)
+            );
+            assert_eq!(
+                outbound.0.trusted_peer_limit(),
+                TrustedPeerRateLimit::Multiplied(8)
+            );
+        });
+}
+
+#[test]
+fn http_admin_flags() {
+    CommandLineTest::new()
+        .flag("http", None)
+        .flag("http-admin-port", Some("5060"))
+        .flag("http-admin-allow-origin", Some("http://127.0.0.99"))
+        .flag("http-admin-token-file", Some("/tmp/admin-token"))
+        .run_with_zero_port()
+        .with_config(|config| {
+            let admin = config.http_api.admin.as_ref().unwrap();
+            assert_eq!(admin.listen_addr, "127.0.0.1".parse::<IpAddr>().unwrap());
+            assert_eq!(admin.listen_port, 5060);
+            assert_eq!(admin.allow_origin, Some("http://127.0.0.99".to_string()));
+            assert_eq!(
+                admin.api_token_path,
+                Some(PathBuf::from("/tmp/admin-token"))
+            );
+        });
+}
+#[test]
+fn http_admin_address_flag() {
+    CommandLineTest::new()
+        .flag("http", None)
+        .flag("http-admin-address", Some("::1"))
+        .run_with_zero_port()
+        .with_config(|config| {
+            let admin = config.http_api.admin.as_ref().unwrap();
+            assert_eq!(admin.listen_addr, "::1".parse::<IpAddr>().unwrap());
+            assert_eq!(admin.listen_port, 5053);
+            assert_eq!(admin.api_token_path, None);
+        });
+}
+#[test]
+fn http_admin_disabled_by_default() {
+    CommandLineTest::new()
+        .flag("http", None)
+        .run_with_zero_port()
+        .with_config(|config| assert_eq!(config.http_api.admin, None));
+}
+#[test]
+#[should_panic]
+fn http_admin_token_file_without_listener() {
+    CommandLineTest::new()
+        .flag("http", None)
+        .flag("http-admin-token-file", Some("/tmp/admin-token"))
+        .run_with_zero_port();
+}
+#[test]
+fn http_allow_origin_flag() {
+    CommandLineTest::new()
+        .flag("http", None)
+        .flag("http-allow-origin", Some("http://127.0.0.99"))
+        .run_with_zero_port()
+        .with_config(|config| {
+            assert_eq!(
+                config.http_api.allow_origin,
+                Some("http://127.0.0.99".to_string())
+            );
+        });
+}
+#[test]
+fn http_allow_origin_all_flag() {
+    CommandLineTest::new()
+        .flag("http", None)
+        .flag("http-allow-origin", Some("*"))
+        .run_with_zero_port()
+        .with_config(|config| assert_eq!(config.http_api.allow_origin, Some("*".to_string())));
+}
+
+#[test]
+fn http_enable_beacon_processor() {
+    CommandLineTest::new()
+        .flag("http", None)
+        .run_with_zero_port()
+        .with_config(|config| assert_eq!(config.http_api.enable_beacon_processor, true));
+
+    CommandLineTest::new()
+        .flag("http", None)
+        .flag("http-enable-beacon-processor", Some("true"))
+        .run_with_zero_port()
+        .with_config(|config| assert_eq!(config.http_api.enable_beacon_processor, true));
+
+    CommandLineTest::new()
+        .flag("http", None)
+        .flag("http-enable-beacon-processor", Some("false"))
+        .run_with_zero_port()
+        .with_config(|config| assert_eq!(config.http_api.enable_beacon_processor, false));
+}
+#[test]
+fn http_enable_unsafe_block_production() {
+    CommandLineTest::new()
+        .flag("http", None)
+        .run_with_zero_port()
+        .with_config(|config| assert!(!config.http_api.enable_unsafe_block_production));
+
+    CommandLineTest::new()
+        .flag("http", None)
+        .flag("http-enable-unsafe-block-production", None)
+        .run_with_zero_port()
+        .with_config(|config| assert!(config.http_api.enable_unsafe_block_production));
+}
+#[test]
+fn http_tls_flags() {
+    CommandLineTest::new()
+        .flag("http", None)
+        .flag("http-enable-tls", None)
+        .flag("http-tls-cert", Some("tests/tls/cert.pem"))
+        .flag("http-tls-key", Some("tests/tls/key.rsa"))
+        .run_with_zero_port()
+        .with_config(|config| {
+            let tls_config = config
+                .http_api
+                .tls_config
+                .as_ref()
+                .expect("tls_config was empty.");
+            assert_eq!(tls_config.cert, Path::new("tests/tls/cert.pem"));
+            assert_eq!(tls_config.key, Path::new("tests/tls/key.rsa"));
+        });
+}
+
+// Tests for Metrics flags.
+#[test]
+fn metrics_flag() {
+    CommandLineTest::new()
+        .flag("metrics", None)
+        .run_with_zero_port()
+        .with_config(|config| {
+            assert!(config.http_metrics.enabled);
+            assert!(config.network.metrics_enabled);
+        });
+}
+#[test]
+fn metrics_address_flag() {
+    let addr = "127.0.0.99".parse::<IpAddr>().unwrap();
+    CommandLineTest::new()
+        .flag("metrics", None)
+        .flag("metrics-address", Some("127.0.0.99"))
+        .run_with_zero_port()
+        .with_config(|config| assert_eq!(config.http_metrics.listen_addr, addr));
+}
+#[test]
+fn metrics_address_ipv6_flag() {
+    let addr = "::1".parse::<IpAddr>().unwrap();
+    CommandLineTest::new()
+        .flag("metrics", None)
+        .flag("metrics-address", Some("::1"))
+        .run_with_zero_port()
+        .with_config(|config| assert_eq!(config.http_metrics.listen_addr, addr));
+}
+#[test]
+fn metrics_port_flag() {
+    let port1 = 0;
+    let port2 = 0;
+    CommandLineTest::new()
+        .flag("metrics", None)
+        .flag("metrics-port", Some(port1.to_string().as_str()))
+        .flag("port", Some(port2.to_string().as_str()))
+        .flag("allow-insecure-genesis-sync", None)
+        .run()
+        .with_config(|config| assert_eq!(config.http_metrics.listen_port, port1));
+}
+#[test]
+fn metrics_allow_origin_flag() {
+    CommandLineTest::new()
+        .flag("metrics", None)
+        .flag("metrics-allow-origin", Some("http://localhost:5059"))
+        .run_with_zero_port()
+        .with_config(|config| {
+            assert_eq!(
+                config.http_metrics.allow_origin,
+                Some("http://localhost:5059".to_string())
+            )
+        });
+}
+#[test]
+fn metrics_allow_origin_all_flag() {
+    CommandLineTest::new()
+        .flag("metrics", None)
+        .flag("metrics-allow-origin", Some("*"))
+        .run_with_zero_port()
+        .with_config(|config| assert_eq!(config.http_metrics.allow_origin, Some("*".to_string())));
+}
+#[test]
+fn metrics_auth_token_file_flag() {
+    CommandLineTest::new()
+        .flag("metrics", None)
+        .flag("metrics-auth-token-file", Some("/tmp/metrics-token"))
+        .run_with_zero_port()
+        .with_config(|config| {
+            assert_eq!(
+                config.http_metrics.auth_token_path,
+                Some(PathBuf::from("/tmp/metrics-token"))
+            )
+        });
+}
+#[test]
+fn metrics_allowlist_and_denylist_flags() {
+    CommandLineTest::new()
+        .flag("metrics", None)
+        .flag(
+            "metrics-allowlist",
+            Some("beacon_*, process_cpu_seconds_total"),
+        )
+        .flag("metrics-denylist", Some("beacon_head_slot,"))
+        .run_with_zero_port()
+        .with_config(|config| {
+            assert_eq!(
+                config.http_metrics.metric_allowlist,
+                Some(vec![
+                    "beacon_*".to_string(),
+                    "process_cpu_seconds_total".to_string()
+                ])
+            );
+            assert_eq!(
+                config.http_metrics.metric_denylist,
+                vec!["beacon_head_slot".to_string()]
+            );
+        });
+}
+#[test]
+fn metrics_filter_default() {
+    CommandLineTest::new()
+        .flag("metrics", None)
+        .run_with_zero_port()
+        .with_config(|config| {
+            assert_eq!(config.http_metrics.auth_token_path, None);
+            assert_eq!(config.http_metrics.metric_allowlist, None);
+            assert!(config.http_metrics.metric_denylist.is_empty());
+        });
+}
+
+#[test]
+fn clock_skew_threshold_default() {
+    CommandLineTest::new()
+        .run_with_zero_port()
+        .with_config(|config| {
+            assert_eq!(
+                config.chain.clock_skew_threshold,
+                Duration::from_millis(250)
+            );
+            assert_eq!(config.chain.ntp_server, None);
+        });
+}
+#[test]
+fn clock_skew_threshold_flag() {
+    CommandLineTest::new()
+        .flag("clock-skew-threshold", Some("100"))
+        .run_with_zero_port()
+        .with_config(|config| {
+            assert_eq!(
+                config.chain.clock_skew_threshold,
+                Duration::from_millis(100)
+            )
+        });
+}
+#[test]
+fn ntp_server_flag() {
     CommandLineTest::new()
-        .flag("http", None)
-        .flag("http-address", Some("127.0.0.99"))
+        .flag("ntp-server", Some("pool.ntp.org:123"))
         .run_with_zero_port()
-        .with_config(|config| assert_eq!(config.http_api.listen_addr, addr));
+        .with_config(|config| {
+            assert_eq!(
+                config.chain.ntp_server,
+                Some("pool.ntp.org:123".to_string())
+            )
+        });
 }
 #[test]
-fn http_address_ipv6_flag() {
-    let addr = "::1".parse::<IpAddr>().unwrap();
+fn epoch_processing_threads_default() {
     CommandLineTest::new()
-        .flag("http", None)
-        .flag("http-address", Some("::1"))
         .run_with_zero_port()
-        .with_config(|config| assert_eq!(config.http_api.listen_addr, addr));
+        .with_config(|config| assert_eq!(config.chain.epoch_processing_threads, 1));
 }
 #[test]
-fn http_port_flag() {
-    let port1 = 0;
-    let port2 = 0;
+fn epoch_processing_threads_flag() {
     CommandLineTest::new()
-        .flag("http", None)
-        .flag("http-port", Some(port1.to_string().as_str()))
-        .flag("port", Some(port2.to_string().as_str()))
-        .flag("allow-insecure-genesis-sync", None)
-        .run()
-        .with_config(|config| assert_eq!(config.http_api.listen_port, port1));
+        .flag("epoch-processing-threads", Some("4"))
+        .run_with_zero_port()
+        .with_config(|config| assert_eq!(config.chain.epoch_processing_threads, 4));
 }
-
 #[test]
-fn empty_inbound_rate_limiter_flag() {
+fn kzg_verification_threads_default() {
     CommandLineTest::new()
         .run_with_zero_port()
         .with_config(|config| {
             assert_eq!(
-                config.network.inbound_rate_limiter_config,
-                Some(lighthouse_network::rpc::config::InboundRateLimiterConfig::default())
+                config.chain.kzg_verification_threads,
+                DEFAULT_KZG_VERIFICATION_THREADS
             )
         });
 }
 #[test]
-fn disable_inbound_rate_limiter_flag() {
+fn kzg_verification_threads_flag() {
     CommandLineTest::new()
-        .flag("disable-inbound-rate-limiter", None)
+        .flag("kzg-verification-threads", Some("8"))
         .run_with_zero_port()
-        .with_config(|config| assert_eq!(config.network.inbound_rate_limiter_config, None));
+        .with_config(|config| assert_eq!(config.chain.kzg_verification_threads, 8));
 }
-
 #[test]
-fn http_allow_origin_flag() {
+fn storage_watchdog_default() {
     CommandLineTest::new()
-        .flag("http", None)
-        .flag("http-allow-origin", Some("http://127.0.0.99"))
+        .run_with_zero_port()
+        .with_config(|config| assert_eq!(config.chain.storage_watchdog, None));
+}
+#[test]
+fn storage_watchdog_flag() {
+    CommandLineTest::new()
+        .flag("storage-watchdog", None)
         .run_with_zero_port()
         .with_config(|config| {
             assert_eq!(
-                config.http_api.allow_origin,
-                Some("http://127.0.0.99".to_string())
-            );
+                config.chain.storage_watchdog,
+                Some(StorageThresholds::default())
+            )
         });
 }
 #[test]
-fn http_allow_origin_all_flag() {
+fn storage_watchdog_thresholds_flag() {
     CommandLineTest::new()
-        .flag("http", None)
-        .flag("http-allow-origin", Some("*"))
+        .flag("storage-watchdog", None)
+        .flag("storage-watchdog-thresholds", Some("20,10,5,2"))
         .run_with_zero_port()
-        .with_config(|config| assert_eq!(config.http_api.allow_origin, Some("*".to_string())));
+        .with_config(|config| {
+            assert_eq!(
+                config.chain.storage_watchdog,
+                Some(StorageThresholds {
+                    warning: 20,
+                    prune_blobs: 10,
+                    pause_backfill: 5,
+                    read_only: 2,
+                })
+            )
+        });
 }
-
 #[test]
-fn http_enable_beacon_processor() {
+#[should_panic]
+fn storage_watchdog_thresholds_increasing() {
     CommandLineTest::new()
-        .flag("http", None)
-        .run_with_zero_port()
-        .with_config(|config| assert_eq!(config.http_api.enable_beacon_processor, true));
-
+        .flag("storage-watchdog", None)
+        .flag("storage-watchdog-thresholds", Some("1,3,5,10"))
+        .run_with_zero_port();
+}
+#[test]
+fn publish_safety_checks_default() {
     CommandLineTest::new()
-        .flag("http", None)
-        .flag("http-enable-beacon-processor", Some("true"))
         .run_with_zero_port()
-        .with_config(|config| assert_eq!(config.http_api.enable_beacon_processor, true));
-
+        .with_config(|config| assert!(!config.chain.publish_safety_checks));
+}
+#[test]
+fn publish_safety_checks_flag() {
     CommandLineTest::new()
-        .flag("http", None)
-        .flag("http-enable-beacon-processor", Some("false"))
+        .flag("publish-safety-checks", None)
         .run_with_zero_port()
-        .with_config(|config| assert_eq!(config.http_api.enable_beacon_processor, false));
+        .with_config(|config| assert!(config.chain.publish_safety_checks));
 }
 #[test]
-fn http_tls_flags() {
+fn blob_fetch_order_default() {
     CommandLineTest::new()
-        .flag("http", None)
-        .flag("http-enable-tls", None)
-        .flag("http-tls-cert", Some("tests/tls/cert.pem"))
-        .flag("http-tls-key", Some("tests/tls/key.rsa"))
         .run_with_zero_port()
         .with_config(|config| {
-            let tls_config = config
-                .http_api
-                .tls_config
-                .as_ref()
-                .expect("tls_config was empty.");
-            assert_eq!(tls_config.cert, Path::new("tests/tls/cert.pem"));
-            assert_eq!(tls_config.key, Path::new("tests/tls/key.rsa"));
+            assert_eq!(
+                config.chain.blob_fetch_order,
+                BlobFetchStrategy::default_order()
+            );
+            assert_eq!(config.chain.blob_gossip_wait, Duration::from_millis(500));
+            assert!(config.chain.engine_blobs_enabled());
         });
 }
-
-// Tests for Metrics flags.
 #[test]
-fn metrics_flag() {
+fn blob_fetch_order_flag() {
     CommandLineTest::new()
-        .flag("metrics", None)
+        .flag(
+            "blob-fetch-order",
+            Some("gossip,engine_get_blobs,rpc_by_root"),
+        )
+        .flag("blob-gossip-wait", Some("1000"))
         .run_with_zero_port()
         .with_config(|config| {
-            assert!(config.http_metrics.enabled);
-            assert!(config.network.metrics_enabled);
+            assert!(config.chain.prefers_blob_fetch_strategy(
+                BlobFetchStrategy::Gossip,
+                BlobFetchStrategy::EngineGetBlobs
+            ));
+            assert_eq!(config.chain.blob_gossip_wait, Duration::from_millis(1000));
         });
 }
 #[test]
-fn metrics_address_flag() {
-    let addr = "127.0.0.99".parse::<IpAddr>().unwrap();
+fn blob_fetch_order_without_engine() {
     CommandLineTest::new()
-        .flag("metrics", None)
-        .flag("metrics-address", Some("127.0.0.99"))
+        .flag("blob-fetch-order", Some("gossip,rpc_by_root"))
         .run_with_zero_port()
-        .with_config(|config| assert_eq!(config.http_metrics.listen_addr, addr));
+        .with_config(|config| {
+            assert!(!config.chain.engine_blobs_enabled());
+            assert!(config.chain.prefers_blob_fetch_strategy(
+                BlobFetchStrategy::RpcByRoot,
+                BlobFetchStrategy::EngineGetBlobs
+            ));
+        });
 }
 #[test]
-fn metrics_address_ipv6_flag() {
-    let addr = "::1".parse::<IpAddr>().unwrap();
+#[should_panic]
+fn blob_fetch_order_without_rpc() {
     CommandLineTest::new()
-        .flag("metrics", None)
-        .flag("metrics-address", Some("::1"))
-        .run_with_zero_port()
-        .with_config(|config| assert_eq!(config.http_metrics.listen_addr, addr));
+        .flag("blob-fetch-order", Some("engine_get_blobs,gossip"))
+        .run_with_zero_port();
 }
 #[test]
-fn metrics_port_flag() {
-    let port1 = 0;
-    let port2 = 0;
+fn chain_segment_verification_default() {
     CommandLineTest::new()
-        .flag("metrics", None)
-        .flag("metrics-port", Some(port1.to_string().as_str()))
-        .flag("port", Some(port2.to_string().as_str()))
-        .flag("allow-insecure-genesis-sync", None)
-        .run()
-        .with_config(|config| assert_eq!(config.http_metrics.listen_port, port1));
+        .run_with_zero_port()
+        .with_config(|config| {
+            assert_eq!(config.chain.max_chain_segment_length, 8);
+            assert!(config.chain.pipeline_chain_segment_verification);
+        });
 }
 #[test]
-fn metrics_allow_origin_flag() {
+fn chain_segment_verification_flags() {
     CommandLineTest::new()
-        .flag("metrics", None)
-        .flag("metrics-allow-origin", Some("http://localhost:5059"))
+        .flag("max-chain-segment-length", Some("16"))
+        .flag("disable-chain-segment-pipelining", None)
         .run_with_zero_port()
         .with_config(|config| {
-            assert_eq!(
-                config.http_metrics.allow_origin,
-                Some("http://localhost:5059".to_string())
-            )
+            assert_eq!(config.chain.max_chain_segment_length, 16);
+            assert!(!config.chain.pipeline_chain_segment_verification);
         });
 }
 #[test]
-fn metrics_allow_origin_all_flag() {
+#[should_panic]
+fn max_chain_segment_length_zero() {
     CommandLineTest::new()
-        .flag("metrics", None)
-        .flag("metrics-allow-origin", Some("*"))
-        .run_with_zero_port()
-        .with_config(|config| assert_eq!(config.http_metrics.allow_origin, Some("*".to_string())));
+        .flag("max-chain-segment-length", Some("0"))
+        .run_with_zero_port();
 }
 
 // Tests for Validator Monitor flags.
@@ -1836,6 +2651,37 @@ fn block_cache_size_flag() {
         .with_config(|config| assert_eq!(config.store.block_cache_size, new_non_zero_usize(4)));
 }
 #[test]
+fn block_root_filter_default() {
+    use beacon_node::beacon_chain::store::config::DEFAULT_BLOCK_ROOT_FILTER_CAPACITY;
+    CommandLineTest::new()
+        .run_with_zero_port()
+        .with_config(|config| {
+            assert!(!config.store.block_root_filter);
+            assert_eq!(
+                config.store.block_root_filter_capacity,
+                DEFAULT_BLOCK_ROOT_FILTER_CAPACITY
+            )
+        });
+}
+#[test]
+fn block_root_filter_flag() {
+    CommandLineTest::new()
+        .flag("block-root-filter", None)
+        .run_with_zero_port()
+        .with_config(|config| assert!(config.store.block_root_filter));
+}
+#[test]
+fn block_root_filter_capacity_flag() {
+    CommandLineTest::new()
+        .flag("block-root-filter", None)
+        .flag("block-root-filter-capacity", Some("1024"))
+        .run_with_zero_port()
+        .with_config(|config| {
+            assert!(config.store.block_root_filter);
+            assert_eq!(config.store.block_root_filter_capacity, 1024)
+        });
+}
+#[test]
 fn state_cache_size_default() {
     CommandLineTest::new()
         .run_with_zero_port()
@@ -2035,6 +2881,29 @@ fn slasher_dir_flag() {
         });
 }
 #[test]
+fn slasher_fast_path_flag() {
+    CommandLineTest::new()
+        .flag("slasher", None)
+        .flag("slasher-max-db-size", Some("1"))
+        .flag("slasher-fast-path", None)
+        .run_with_zero_port()
+        .with_config(|config| {
+            let slasher_config = config.slasher.as_ref().unwrap();
+            assert!(slasher_config.fast_path);
+        });
+}
+#[test]
+fn slasher_fast_path_default() {
+    CommandLineTest::new()
+        .flag("slasher", None)
+        .flag("slasher-max-db-size", Some("1"))
+        .run_with_zero_port()
+        .with_config(|config| {
+            let slasher_config = config.slasher.as_ref().unwrap();
+            assert!(!slasher_config.fast_path);
+        });
+}
+#[test]
 fn slasher_update_period_flag() {
     CommandLineTest::new()
         .flag("slasher", None)
@@ -2367,6 +3236,65 @@ fn proposer_re_org_disallowed_offsets_invalid() {
         .run_with_zero_port();
 }
 
+#[test]
+fn re_org_analysis_default() {
+    CommandLineTest::new()
+        .run_with_zero_port()
+        .with_config(|config| assert_eq!(config.chain.re_org_analysis, None));
+}
+
+#[test]
+fn re_org_analysis_defaults_to_configured_params() {
+    CommandLineTest::new()
+        .flag("re-org-analysis", None)
+        .flag("proposer-reorg-threshold", Some("30"))
+        .run_with_zero_port()
+        .with_config(|config| {
+            let params = config.chain.re_org_analysis.unwrap();
+            assert_eq!(params.re_org_head_threshold.0, 30);
+            assert_eq!(
+                params.re_org_parent_threshold,
+                DEFAULT_RE_ORG_PARENT_THRESHOLD
+            );
+            assert_eq!(
+                params.proposer_score_boost,
+                ChainSpec::mainnet().proposer_score_boost.unwrap()
+            );
+            assert_eq!(params.max_re_org_depth, DEFAULT_RE_ORG_ANALYSIS_MAX_DEPTH);
+        });
+}
+
+#[test]
+fn re_org_analysis_override() {
+    CommandLineTest::new()
+        .flag("re-org-analysis", None)
+        .flag("re-org-analysis-head-threshold", Some("35"))
+        .flag("re-org-analysis-parent-threshold", Some("120"))
+        .flag("re-org-analysis-proposer-boost", Some("60"))
+        .flag("re-org-analysis-max-depth", Some("3"))
+        .run_with_zero_port()
+        .with_config(|config| {
+            assert_eq!(
+                config.chain.re_org_analysis,
+                Some(ReOrgSimulationParams {
+                    re_org_head_threshold: ReOrgThreshold(35),
+                    re_org_parent_threshold: ReOrgThreshold(120),
+                    proposer_score_boost: 60,
+                    max_re_org_depth: 3,
+                })
+            )
+        });
+}
+
+#[test]
+#[should_panic]
+fn re_org_analysis_max_depth_zero() {
+    CommandLineTest::new()
+        .flag("re-org-analysis", None)
+        .flag("re-org-analysis-max-depth", Some("0"))
+        .run_with_zero_port();
+}
+
 #[test]
 fn monitoring_endpoint() {
     CommandLineTest::new()
@@ -2532,6 +3460,37 @@ fn light_client_http_server_enabled() {
         });
 }
 
+#[test]
+fn light_client_store_snapshot_default() {
+    CommandLineTest::new()
+        .run_with_zero_port()
+        .with_config(|config| assert_eq!(config.chain.light_client_store_snapshot, None));
+}
+
+#[test]
+fn light_client_store_snapshot_flag() {
+    let dir = TempDir::new().expect("Unable to create temporary directory");
+    let path = dir.path().join("light_client_store.json");
+    CommandLineTest::new()
+        .flag("light-client-server", None)
+        .flag("light-client-store-snapshot", path.as_os_str().to_str())
+        .run_with_zero_port()
+        .with_config(|config| {
+            assert_eq!(config.chain.light_client_store_snapshot, Some(path.clone()))
+        });
+}
+
+#[test]
+#[should_panic]
+fn light_client_store_snapshot_without_light_client_server() {
+    CommandLineTest::new()
+        .flag(
+            "light-client-store-snapshot",
+            Some("light_client_store.json"),
+        )
+        .run_with_zero_port();
+}
+
 #[test]
 fn gui_flag() {
     CommandLineTest::new()
@@ -2618,12 +3577,49 @@ fn beacon_processor() {
                     max_scheduled_work_queue_len: 3,
                     max_gossip_attestation_batch_size: 4,
                     max_gossip_aggregate_batch_size: 5,
-                    enable_backfill_rate_limiting: false
+                    enable_backfill_rate_limiting: false,
+                    queue_length_overrides: vec![],
+                    prioritized_work_types: vec![],
                 }
             )
         });
 }
 
+#[test]
+fn beacon_processor_queue_overrides() {
+    CommandLineTest::new()
+        .flag(
+            "beacon-processor-queue-lengths",
+            Some("status=2048,chain_segment_backfill=16"),
+        )
+        .flag(
+            "beacon-processor-priority-order",
+            Some("chain_segment_backfill,status"),
+        )
+        .run_with_zero_port()
+        .with_config(|config| {
+            assert_eq!(
+                config.beacon_processor.queue_length_overrides,
+                vec![
+                    (WorkType::Status, 2048),
+                    (WorkType::ChainSegmentBackfill, 16)
+                ]
+            );
+            assert_eq!(
+                config.beacon_processor.prioritized_work_types,
+                vec![WorkType::ChainSegmentBackfill, WorkType::Status]
+            );
+        });
+}
+
+#[test]
+#[should_panic]
+fn beacon_processor_queue_lengths_unknown_work_type() {
+    CommandLineTest::new()
+        .flag("beacon-processor-queue-lengths", Some("not_a_queue=1"))
+        .run_with_zero_port();
+}
+
 #[test]
 #[should_panic]
 fn beacon_processor_zero_workers() {
@@ -2648,6 +3644,22 @@ fn http_sse_capacity_multiplier_override() {
         .with_config(|config| assert_eq!(config.http_api.sse_capacity_multiplier, 10));
 }
 
+#[test]
+fn http_archive_query_budget_default() {
+    CommandLineTest::new()
+        .run_with_zero_port()
+        .with_config(|config| assert_eq!(config.http_api.archive_query_budget, None));
+}
+
+#[test]
+fn http_archive_query_budget_override() {
+    CommandLineTest::new()
+        .flag("http", None)
+        .flag("http-archive-query-budget", Some("512"))
+        .run_with_zero_port()
+        .with_config(|config| assert_eq!(config.http_api.archive_query_budget, Some(512)));
+}
+
 #[test]
 fn http_duplicate_block_status_default() {
     CommandLineTest::new()
@@ -1,8 +1,12 @@
-use beacon_node_fallback::{beacon_node_health::BeaconNodeSyncDistanceTiers, ApiTopic};
+use account_utils::validator_definitions::DefinitionsLayout;
+use beacon_node_fallback::{
+    beacon_node_health::BeaconNodeSyncDistanceTiers, circuit_breaker::CircuitBreakerConfig,
+    ApiTopic,
+};
 
 use crate::exec::CommandLineTestExec;
 use bls::{Keypair, PublicKeyBytes};
-use initialized_validators::DEFAULT_WEB3SIGNER_KEEP_ALIVE;
+use initialized_validators::{default_keystore_decryption_workers, DEFAULT_WEB3SIGNER_KEEP_ALIVE};
 use sensitive_url::SensitiveUrl;
 use std::fs::File;
 use std::io::Write;
@@ -14,7 +18,8 @@ use std::string::ToString;
 use std::time::Duration;
 use tempfile::TempDir;
 use types::{Address, Slot};
-use validator_client::Config;
+use validator_client::{Config, StartupMode};
+use validator_services::duty_deadlines::{DutyDeadline, DutyDeadlines};
 
 /// Returns the `lighthouse validator_client` command.
 fn base_cmd() -> Command {
@@ -113,6 +118,49 @@ fn disable_auto_discover_flag() {
         .with_config(|config| assert!(config.disable_auto_discover));
 }
 
+#[test]
+fn slashing_protection_comparison_file_default() {
+    CommandLineTest::new()
+        .run()
+        .with_config(|config| assert_eq!(config.slashing_protection_comparison_file, None));
+}
+
+#[test]
+fn slashing_protection_comparison_file_flag() {
+    CommandLineTest::new()
+        .flag(
+            "slashing-protection-comparison-file",
+            Some("/tmp/interchange.json"),
+        )
+        .run()
+        .with_config(|config| {
+            assert_eq!(
+                config.slashing_protection_comparison_file,
+                Some(PathBuf::from("/tmp/interchange.json"))
+            )
+        });
+}
+
+#[test]
+fn validator_definitions_layout_default() {
+    CommandLineTest::new()
+        .run()
+        .with_config(|config| assert_eq!(config.validator_definitions_layout, None));
+}
+
+#[test]
+fn validator_definitions_layout_flag() {
+    CommandLineTest::new()
+        .flag("validator-definitions-layout", Some("per-validator-files"))
+        .run()
+        .with_config(|config| {
+            assert_eq!(
+                config.validator_definitions_layout,
+                Some(DefinitionsLayout::PerValidatorFiles)
+            )
+        });
+}
+
 #[test]
 fn init_slashing_protections_flag() {
     CommandLineTest::new()
@@ -156,6 +204,289 @@ fn beacon_nodes_tls_certs_flag() {
         });
 }
 
+#[test]
+fn beacon_nodes_tls_config_flag() {
+    let dir = TempDir::new().expect("Unable to create temporary directory");
+    let config_path = dir.path().join("tls.yaml");
+    let mut file = File::create(&config_path).expect("Unable to create TLS config");
+    write!(
+        file,
+        "- beacon_node: http://localhost:1001\n  \
+           root_certificate_path: /ca.pem\n\
+         - beacon_node: http://localhost:1002\n  \
+           client_identity_path: /identity.p12\n  \
+           client_identity_password: secret\n"
+    )
+    .expect("Unable to write TLS config");
+
+    CommandLineTest::new()
+        .flag(
+            "beacon-nodes",
+            Some("http://localhost:1001,http://localhost:1002"),
+        )
+        .flag("beacon-nodes-tls-config", config_path.to_str())
+        .run()
+        .with_config(|config| {
+            let tls_config = &config.beacon_nodes_tls_config;
+            assert_eq!(tls_config.len(), 2);
+            assert_eq!(tls_config[0].beacon_node, config.beacon_nodes[0]);
+            assert_eq!(
+                tls_config[0].root_certificate_path,
+                Some(PathBuf::from("/ca.pem"))
+            );
+            assert_eq!(tls_config[0].client_identity_path, None);
+            assert_eq!(tls_config[1].beacon_node, config.beacon_nodes[1]);
+            assert_eq!(
+                tls_config[1].client_identity_path,
+                Some(PathBuf::from("/identity.p12"))
+            );
+            assert_eq!(
+                tls_config[1].client_identity_password.as_deref(),
+                Some("secret")
+            );
+        });
+}
+
+#[test]
+#[should_panic]
+fn beacon_nodes_tls_config_unknown_beacon_node() {
+    let dir = TempDir::new().expect("Unable to create temporary directory");
+    let config_path = dir.path().join("tls.yaml");
+    let mut file = File::create(&config_path).expect("Unable to create TLS config");
+    write!(file, "- beacon_node: http://localhost:1003\n").expect("Unable to write TLS config");
+
+    CommandLineTest::new()
+        .flag("beacon-nodes", Some("http://localhost:1001"))
+        .flag("beacon-nodes-tls-config", config_path.to_str())
+        .run();
+}
+
+#[test]
+fn beacon_nodes_http2_flag() {
+    CommandLineTest::new()
+        .run()
+        .with_config(|config| assert!(!config.beacon_nodes_http2));
+    CommandLineTest::new()
+        .flag("beacon-nodes-http2", None)
+        .run()
+        .with_config(|config| assert!(config.beacon_nodes_http2));
+}
+
+#[test]
+fn beacon_nodes_ssz_requests_flag() {
+    CommandLineTest::new()
+        .run()
+        .with_config(|config| assert!(!config.beacon_nodes_ssz_requests));
+    CommandLineTest::new()
+        .flag("beacon-nodes-ssz-requests", None)
+        .run()
+        .with_config(|config| assert!(config.beacon_nodes_ssz_requests));
+}
+
+#[test]
+fn beacon_nodes_max_concurrent_requests_flag() {
+    CommandLineTest::new()
+        .run()
+        .with_config(|config| assert_eq!(config.beacon_nodes_max_concurrent_requests, None));
+    CommandLineTest::new()
+        .flag("beacon-nodes-max-concurrent-requests", Some("16"))
+        .run()
+        .with_config(|config| assert_eq!(config.beacon_nodes_max_concurrent_requests, Some(16)));
+}
+
+#[test]
+#[should_panic]
+fn beacon_nodes_max_concurrent_requests_zero() {
+    CommandLineTest::new()
+        .flag("beacon-nodes-max-concurrent-requests", Some("0"))
+        .run();
+}
+
+#[test]
+fn precompute_selection_proofs_flag() {
+    CommandLineTest::new()
+        .run()
+        .with_config(|config| assert!(!config.precompute_selection_proofs));
+    CommandLineTest::new()
+        .flag("precompute-selection-proofs", None)
+        .run()
+        .with_config(|config| assert!(config.precompute_selection_proofs));
+}
+
+#[test]
+#[should_panic]
+fn precompute_selection_proofs_distributed_conflict() {
+    CommandLineTest::new()
+        .flag("precompute-selection-proofs", None)
+        .flag("distributed", None)
+        .run();
+}
+
+#[test]
+fn selection_proof_signing_concurrency_flag() {
+    CommandLineTest::new()
+        .run()
+        .with_config(|config| assert_eq!(config.selection_proof_signing_concurrency, 1));
+    CommandLineTest::new()
+        .flag("selection-proof-signing-concurrency", Some("8"))
+        .run()
+        .with_config(|config| assert_eq!(config.selection_proof_signing_concurrency, 8));
+}
+
+#[test]
+#[should_panic]
+fn selection_proof_signing_concurrency_zero() {
+    CommandLineTest::new()
+        .flag("selection-proof-signing-concurrency", Some("0"))
+        .run();
+}
+
+#[test]
+fn duty_deadlines_default() {
+    CommandLineTest::new()
+        .run()
+        .with_config(|config| assert_eq!(config.duty_deadlines, DutyDeadlines::default()));
+}
+
+#[test]
+fn duty_deadlines_flag() {
+    CommandLineTest::new()
+        .flag(
+            "duty-deadlines",
+            Some("attestation:6000:8000,sync_committee_contribution:10000:11000"),
+        )
+        .flag("skip-late-duties", Some("attestation"))
+        .run()
+        .with_config(|config| {
+            let deadlines = &config.duty_deadlines;
+            assert_eq!(
+                deadlines.attestation,
+                DutyDeadline {
+                    sign_by: Some(Duration::from_millis(6000)),
+                    publish_by: Some(Duration::from_millis(8000)),
+                    skip_late: true,
+                }
+            );
+            assert_eq!(
+                deadlines.sync_committee_contribution,
+                DutyDeadline {
+                    sign_by: Some(Duration::from_millis(10000)),
+                    publish_by: Some(Duration::from_millis(11000)),
+                    skip_late: false,
+                }
+            );
+            assert_eq!(deadlines.aggregate, DutyDeadline::default());
+        });
+}
+
+#[test]
+fn aggregation_circuit_breaker_default() {
+    CommandLineTest::new().run().with_config(|config| {
+        assert_eq!(
+            config.beacon_node_fallback.aggregation_circuit_breaker,
+            None
+        )
+    });
+}
+
+#[test]
+fn aggregation_circuit_breaker_flag() {
+    CommandLineTest::new()
+        .flag("aggregation-circuit-breaker", Some("8,10,24"))
+        .run()
+        .with_config(|config| {
+            assert_eq!(
+                config.beacon_node_fallback.aggregation_circuit_breaker,
+                Some(CircuitBreakerConfig {
+                    failure_threshold: 8,
+                    window: 10,
+                    cooldown: Duration::from_secs(24),
+                })
+            );
+        });
+}
+
+#[test]
+#[should_panic]
+fn aggregation_circuit_breaker_invalid() {
+    CommandLineTest::new()
+        .flag("aggregation-circuit-breaker", Some("11,10,24"))
+        .run();
+}
+
+#[test]
+fn startup_mode_default() {
+    CommandLineTest::new().run().with_config(|config| {
+        assert_eq!(config.startup_mode, StartupMode::Immediate);
+        assert_eq!(config.startup_sync_timeout, None);
+        assert!(!config.require_synced_genesis_provider);
+    });
+}
+
+#[test]
+fn startup_mode_wait_for_synced() {
+    CommandLineTest::new()
+        .flag("startup-mode", Some("wait-for-synced"))
+        .flag("startup-sync-timeout", Some("120"))
+        .flag("require-synced-genesis-provider", None)
+        .run()
+        .with_config(|config| {
+            assert_eq!(config.startup_mode, StartupMode::WaitForSynced);
+            assert_eq!(config.startup_sync_timeout, Some(Duration::from_secs(120)));
+            assert!(config.require_synced_genesis_provider);
+        });
+}
+
+#[test]
+fn startup_mode_fail_fast() {
+    CommandLineTest::new()
+        .flag("startup-mode", Some("fail-fast"))
+        .run()
+        .with_config(|config| assert_eq!(config.startup_mode, StartupMode::FailFast));
+}
+
+#[test]
+#[should_panic]
+fn startup_mode_invalid() {
+    CommandLineTest::new()
+        .flag("startup-mode", Some("eventually"))
+        .run();
+}
+
+#[test]
+fn duty_deadlines_block_proposal() {
+    CommandLineTest::new()
+        .flag("duty-deadlines", Some("block_proposal:3000:4000"))
+        .flag("skip-late-duties", Some("block_proposal"))
+        .run()
+        .with_config(|config| {
+            assert_eq!(
+                config.duty_deadlines.block_proposal,
+                DutyDeadline {
+                    sign_by: Some(Duration::from_millis(3000)),
+                    publish_by: Some(Duration::from_millis(4000)),
+                    skip_late: true,
+                }
+            );
+        });
+}
+
+#[test]
+#[should_panic]
+fn duty_deadlines_sign_after_publish() {
+    CommandLineTest::new()
+        .flag("duty-deadlines", Some("attestation:8000:6000"))
+        .run();
+}
+
+#[test]
+#[should_panic]
+fn duty_deadlines_unknown_duty() {
+    CommandLineTest::new()
+        .flag("duty-deadlines", Some("block:1000:2000"))
+        .run();
+}
+
 // Tests for Graffiti flags.
 #[test]
 fn graffiti_flag() {
@@ -400,6 +731,51 @@ fn metrics_allow_origin_all_flag() {
         .with_config(|config| assert_eq!(config.http_metrics.allow_origin, Some("*".to_string())));
 }
 #[test]
+fn metrics_auth_token_file_flag() {
+    CommandLineTest::new()
+        .flag("metrics", None)
+        .flag("metrics-auth-token-file", Some("/tmp/metrics-token"))
+        .run()
+        .with_config(|config| {
+            assert_eq!(
+                config.http_metrics.auth_token_path,
+                Some(PathBuf::from("/tmp/metrics-token"))
+            )
+        });
+}
+#[test]
+fn metrics_allowlist_and_denylist_flags() {
+    CommandLineTest::new()
+        .flag("metrics", None)
+        .flag("metrics-allowlist", Some("vc_*, process_cpu_seconds_total"))
+        .flag("metrics-denylist", Some("vc_beacon_nodes_available_count,"))
+        .run()
+        .with_config(|config| {
+            assert_eq!(
+                config.http_metrics.metric_allowlist,
+                Some(vec![
+                    "vc_*".to_string(),
+                    "process_cpu_seconds_total".to_string()
+                ])
+            );
+            assert_eq!(
+                config.http_metrics.metric_denylist,
+                vec!["vc_beacon_nodes_available_count".to_string()]
+            );
+        });
+}
+#[test]
+fn metrics_filter_default() {
+    CommandLineTest::new()
+        .flag("metrics", None)
+        .run()
+        .with_config(|config| {
+            assert_eq!(config.http_metrics.auth_token_path, None);
+            assert_eq!(config.http_metrics.metric_allowlist, None);
+            assert!(config.http_metrics.metric_denylist.is_empty());
+        });
+}
+#[test]
 pub fn malloc_tuning_flag() {
     CommandLineTest::new()
         .flag("disable-malloc-tuning", None)
@@ -540,6 +916,15 @@ fn broadcast_flag() {
                 vec![ApiTopic::Blocks, ApiTopic::Subscriptions],
             );
         });
+    CommandLineTest::new()
+        .flag("broadcast", Some("aggregates,subscriptions"))
+        .run()
+        .with_config(|config| {
+            assert_eq!(
+                config.broadcast_topics,
+                vec![ApiTopic::Aggregates, ApiTopic::Subscriptions],
+            );
+        });
     // Omitted "subscription" overrides default
     CommandLineTest::new()
         .flag("broadcast", Some("attestations"))
@@ -600,6 +985,21 @@ fn disable_latency_measurement_service() {
         });
 }
 
+#[test]
+fn web3_signer_health_checks_default() {
+    CommandLineTest::new()
+        .run()
+        .with_config(|config| assert!(config.enable_web3_signer_health_checks));
+}
+
+#[test]
+fn disable_web3_signer_health_checks() {
+    CommandLineTest::new()
+        .flag("disable-web3-signer-health-checks", None)
+        .run()
+        .with_config(|config| assert!(!config.enable_web3_signer_health_checks));
+}
+
 #[test]
 fn validator_registration_batch_size() {
     CommandLineTest::new().run().with_config(|config| {
@@ -661,6 +1061,35 @@ fn validator_web3_signer_keep_alive_override() {
         });
 }
 
+#[test]
+fn keystore_decryption_workers_default() {
+    CommandLineTest::new().run().with_config(|config| {
+        assert_eq!(
+            config.initialized_validators.keystore_decryption_workers,
+            default_keystore_decryption_workers()
+        );
+    });
+}
+
+#[test]
+fn keystore_decryption_workers_override() {
+    CommandLineTest::new()
+        .flag("keystore-decryption-workers", Some("4"))
+        .run()
+        .with_config(|config| {
+            assert_eq!(config.initialized_validators.keystore_decryption_workers, 4);
+        });
+}
+
+#[test]
+#[should_panic]
+fn keystore_decryption_workers_zero() {
+    CommandLineTest::new()
+        .flag("keystore-decryption-workers", Some("0"))
+        .run()
+        .with_config(|_| {});
+}
+
 #[test]
 fn validator_proposer_nodes_default_empty() {
     CommandLineTest::new().run().with_config(|config| {
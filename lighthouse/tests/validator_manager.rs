@@ -8,6 +8,7 @@ use std::str::FromStr;
 use tempfile::{tempdir, TempDir};
 use types::*;
 use validator_manager::{
+    audit_registrations::AuditRegistrationsConfig,
     create_validators::CreateConfig,
     delete_validators::DeleteConfig,
     import_validators::ImportConfig,
@@ -119,6 +120,12 @@ impl CommandLineTest<DeleteConfig> {
     }
 }
 
+impl CommandLineTest<AuditRegistrationsConfig> {
+    fn audit_registrations() -> Self {
+        Self::default().flag("audit-registrations", None)
+    }
+}
+
 #[test]
 pub fn validator_create_without_output_path() {
     CommandLineTest::validators_create().assert_failed();
@@ -302,6 +309,7 @@ pub fn validator_move_defaults() {
                 password_source: PasswordSource::Interactive {
                     stdin_inputs: cfg!(windows) || false,
                 },
+                plan_file: None,
             };
             assert_eq!(expected, config);
         });
@@ -322,6 +330,7 @@ pub fn validator_move_misc_flags_0() {
         .flag("--suggested-fee-recipient", Some(EXAMPLE_ETH1_ADDRESS))
         .flag("--gas-limit", Some("1337"))
         .flag("--stdin-inputs", None)
+        .flag("--plan-file", Some("./plan.json"))
         .assert_success(|config| {
             let expected = MoveConfig {
                 src_vc_url: SensitiveUrl::parse("http://localhost:1").unwrap(),
@@ -338,6 +347,7 @@ pub fn validator_move_misc_flags_0() {
                 fee_recipient: Some(Address::from_str(EXAMPLE_ETH1_ADDRESS).unwrap()),
                 gas_limit: Some(1337),
                 password_source: PasswordSource::Interactive { stdin_inputs: true },
+                plan_file: Some(PathBuf::from("./plan.json")),
             };
             assert_eq!(expected, config);
         });
@@ -370,6 +380,7 @@ pub fn validator_move_misc_flags_1() {
                 password_source: PasswordSource::Interactive {
                     stdin_inputs: cfg!(windows) || false,
                 },
+                plan_file: None,
             };
             assert_eq!(expected, config);
         });
@@ -402,6 +413,7 @@ pub fn validator_move_misc_flags_2() {
                 password_source: PasswordSource::Interactive {
                     stdin_inputs: cfg!(windows) || false,
                 },
+                plan_file: None,
             };
             assert_eq!(expected, config);
         });
@@ -430,6 +442,7 @@ pub fn validator_move_count() {
                 password_source: PasswordSource::Interactive {
                     stdin_inputs: cfg!(windows) || false,
                 },
+                plan_file: None,
             };
             assert_eq!(expected, config);
         });
@@ -468,3 +481,31 @@ pub fn validator_delete_defaults() {
             assert_eq!(expected, config);
         });
 }
+
+#[test]
+pub fn audit_registrations_defaults() {
+    CommandLineTest::audit_registrations()
+        .flag("--vc-token", Some("./token.json"))
+        .flag(
+            "--relay-url",
+            Some("http://relay-1.example,https://relay-2.example"),
+        )
+        .assert_success(|config| {
+            let expected = AuditRegistrationsConfig {
+                vc_url: SensitiveUrl::parse("http://localhost:5062").unwrap(),
+                vc_token_path: PathBuf::from("./token.json"),
+                relay_urls: vec![
+                    SensitiveUrl::parse("http://relay-1.example").unwrap(),
+                    SensitiveUrl::parse("https://relay-2.example").unwrap(),
+                ],
+            };
+            assert_eq!(expected, config);
+        });
+}
+
+#[test]
+pub fn audit_registrations_without_relay_url() {
+    CommandLineTest::audit_registrations()
+        .flag("--vc-token", Some("./token.json"))
+        .assert_failed();
+}
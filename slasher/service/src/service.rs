@@ -23,6 +23,9 @@ use tokio::sync::mpsc::UnboundedSender;
 use tokio::time::{interval_at, Duration, Instant};
 use types::{AttesterSlashing, Epoch, EthSpec, ProposerSlashing};
 
+/// The delay between a fast path request and prompting the processor to run.
+const FAST_PATH_DELAY: Duration = Duration::from_millis(500);
+
 pub struct SlasherService<T: BeaconChainTypes> {
     beacon_chain: Arc<BeaconChain<T>>,
     network_sender: UnboundedSender<NetworkMessage<T::EthSpec>>,
@@ -62,6 +65,7 @@ impl<T: BeaconChainTypes> SlasherService<T> {
         executor.spawn(
             Self::run_notifier(
                 beacon_chain.clone(),
+                slasher.clone(),
                 update_period,
                 slot_offset,
                 notif_sender,
@@ -79,8 +83,12 @@ impl<T: BeaconChainTypes> SlasherService<T> {
     }
 
     /// Run the async notifier which periodically prompts the processor to run.
+    ///
+    /// If the fast path is enabled the processor is also prompted shortly after a conflicting
+    /// attestation is observed on gossip.
     async fn run_notifier(
         beacon_chain: Arc<BeaconChain<T>>,
+        slasher: Arc<Slasher<T::EthSpec>>,
         update_period: u64,
         slot_offset: f64,
         notif_sender: SyncSender<Epoch>,
@@ -96,8 +104,18 @@ impl<T: BeaconChainTypes> SlasherService<T> {
             };
         let mut interval = interval_at(start_instant, Duration::from_secs(update_period));
 
+        let fast_path = slasher.config().fast_path;
+
         loop {
-            interval.tick().await;
+            tokio::select! {
+                _ = interval.tick() => {}
+                _ = slasher.fast_path_requested(), if fast_path => {
+                    // Wait briefly so that conflicting attestations arriving together are
+                    // processed in a single batch.
+                    tokio::time::sleep(FAST_PATH_DELAY).await;
+                    debug!(log, "Slasher fast path triggered");
+                }
+            }
             if let Some(current_slot) = beacon_chain.slot_clock.now() {
                 let current_epoch = current_slot.epoch(T::EthSpec::slots_per_epoch());
                 if let Err(TrySendError::Disconnected(_)) = notif_sender.try_send(current_epoch) {
@@ -147,6 +165,17 @@ impl<T: BeaconChainTypes> SlasherService<T> {
                 continue;
             };
 
+            // Convert chunks written by older versions to the current on-disk format, a batch at a
+            // time.
+            if let Err(e) = slasher.reformat_chunks() {
+                error!(
+                    log,
+                    "Error during slasher chunk reformat";
+                    "epoch" => current_epoch,
+                    "error" => ?e,
+                );
+            }
+
             // Provide slashings to the beacon chain, and optionally publish them.
             Self::process_slashings(&beacon_chain, &slasher, &network_sender);
 
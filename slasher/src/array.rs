@@ -1,4 +1,6 @@
-use crate::metrics::{self, SLASHER_COMPRESSION_RATIO, SLASHER_NUM_CHUNKS_UPDATED};
+use crate::metrics::{
+    self, SLASHER_COMPRESSION_RATIO, SLASHER_NUM_CHUNKS_REFORMATTED, SLASHER_NUM_CHUNKS_UPDATED,
+};
 use crate::{
     AttesterSlashingStatus, Config, Database, Error, IndexedAttesterRecord, RwTransaction,
     SlasherDB,
@@ -13,6 +15,22 @@ use types::{AttesterSlashing, Epoch, EthSpec, IndexedAttestation};
 
 pub const MAX_DISTANCE: u16 = u16::MAX;
 
+/// Prefix byte of chunks stored in the run-length encoded format.
+///
+/// Chunks in the legacy format are a zlib stream of the bincode-serialized chunk. A zlib stream
+/// always begins with `0x78`, so the format of a chunk on disk can be determined from its first
+/// byte.
+const RLE_CHUNK_PREFIX: u8 = 0x01;
+
+/// The format of a chunk stored on disk.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ChunkFormat {
+    /// zlib-compressed bincode of the full `u16` array.
+    Legacy,
+    /// zlib-compressed run-length encoding of the array, prefixed by `RLE_CHUNK_PREFIX`.
+    RunLength,
+}
+
 /// Terminology:
 ///
 /// Let
@@ -81,6 +99,79 @@ impl Chunk {
         Ok(())
     }
 
+    /// Encode the chunk for storage on disk in the run-length encoded format.
+    ///
+    /// Each run of identical distances is written as a LEB128 run length followed by the
+    /// little-endian distance. Validators which attest regularly have the same distance for most
+    /// epochs and inactive validators have the neutral element throughout, so runs are long and
+    /// the encoding is much smaller than the raw array, even before compression.
+    pub fn encode(&self) -> Result<Vec<u8>, Error> {
+        let mut rle = vec![];
+        let mut values = self.data.iter().peekable();
+        while let Some(&value) = values.next() {
+            let mut run_length = 1u64;
+            while values.next_if_eq(&&value).is_some() {
+                run_length += 1;
+            }
+            write_leb128(&mut rle, run_length);
+            rle.extend_from_slice(&value.to_le_bytes());
+        }
+
+        let mut encoder = ZlibEncoder::new(&rle[..], flate2::Compression::default());
+        let mut encoded = vec![RLE_CHUNK_PREFIX];
+        encoder.read_to_end(&mut encoded)?;
+        Ok(encoded)
+    }
+
+    /// Decode a chunk stored on disk in either format.
+    pub fn decode(bytes: &[u8], config: &Config) -> Result<(Self, ChunkFormat), Error> {
+        let expected_length = config.chunk_size * config.validator_chunk_size;
+
+        let Some(rle_bytes) = bytes.strip_prefix(&[RLE_CHUNK_PREFIX]) else {
+            let chunk: Chunk = bincode::deserialize_from(ZlibDecoder::new(bytes))?;
+            return Ok((chunk, ChunkFormat::Legacy));
+        };
+
+        let mut rle = vec![];
+        ZlibDecoder::new(rle_bytes).read_to_end(&mut rle)?;
+
+        let mut data = Vec::with_capacity(expected_length);
+        let mut remaining = &rle[..];
+        while !remaining.is_empty() {
+            let run_length = read_leb128(&mut remaining).ok_or(Error::ChunkCorrupt {
+                length: data.len(),
+                expected_length,
+            })?;
+            let (value_bytes, rest) =
+                remaining
+                    .split_first_chunk::<2>()
+                    .ok_or(Error::ChunkCorrupt {
+                        length: data.len(),
+                        expected_length,
+                    })?;
+            remaining = rest;
+
+            let new_length = usize::try_from(run_length)
+                .ok()
+                .and_then(|run_length| data.len().checked_add(run_length))
+                .filter(|new_length| *new_length <= expected_length)
+                .ok_or(Error::ChunkCorrupt {
+                    length: data.len(),
+                    expected_length,
+                })?;
+            data.resize(new_length, u16::from_le_bytes(*value_bytes));
+        }
+
+        if data.len() != expected_length {
+            return Err(Error::ChunkCorrupt {
+                length: data.len(),
+                expected_length,
+            });
+        }
+
+        Ok((Chunk { data }, ChunkFormat::RunLength))
+    }
+
     /// Compute the distance (difference) between two epochs.
     ///
     /// Error if the distance is greater than or equal to `MAX_DISTANCE`.
@@ -99,6 +190,32 @@ impl Chunk {
     }
 }
 
+/// Append `value` to `bytes` as an unsigned LEB128 integer.
+fn write_leb128(bytes: &mut Vec<u8>, mut value: u64) {
+    loop {
+        let byte = (value & 0x7f) as u8;
+        value >>= 7;
+        if value == 0 {
+            bytes.push(byte);
+            return;
+        }
+        bytes.push(byte | 0x80);
+    }
+}
+
+/// Read an unsigned LEB128 integer from the front of `bytes`, advancing it past the integer.
+fn read_leb128(bytes: &mut &[u8]) -> Option<u64> {
+    let mut value = 0u64;
+    for (i, byte) in bytes.iter().enumerate().take(10) {
+        value |= u64::from(byte & 0x7f) << (7 * i);
+        if byte & 0x80 == 0 {
+            *bytes = &bytes[i + 1..];
+            return Some(value);
+        }
+    }
+    None
+}
+
 #[derive(Debug, Serialize, Deserialize)]
 #[serde(transparent)]
 pub struct MinTargetChunk {
@@ -118,6 +235,10 @@ pub trait TargetArrayChunk: Sized + serde::Serialize + serde::de::DeserializeOwn
 
     fn chunk(&mut self) -> &mut Chunk;
 
+    fn as_chunk(&self) -> &Chunk;
+
+    fn from_chunk(chunk: Chunk) -> Self;
+
     fn neutral_element() -> u16;
 
     fn check_slashable<E: EthSpec>(
@@ -161,9 +282,9 @@ pub trait TargetArrayChunk: Sized + serde::Serialize + serde::de::DeserializeOwn
             return Ok(None);
         };
 
-        let chunk = bincode::deserialize_from(ZlibDecoder::new(chunk_bytes.borrow()))?;
+        let (chunk, _) = Chunk::decode(chunk_bytes.borrow(), config)?;
 
-        Ok(Some(chunk))
+        Ok(Some(Self::from_chunk(chunk)))
     }
 
     fn store<E: EthSpec>(
@@ -175,12 +296,10 @@ pub trait TargetArrayChunk: Sized + serde::Serialize + serde::de::DeserializeOwn
         config: &Config,
     ) -> Result<(), Error> {
         let disk_key = config.disk_key(validator_chunk_index, chunk_index);
-        let value = bincode::serialize(self)?;
-        let mut encoder = ZlibEncoder::new(&value[..], flate2::Compression::default());
-        let mut compressed_value = vec![];
-        encoder.read_to_end(&mut compressed_value)?;
+        let compressed_value = self.as_chunk().encode()?;
 
-        let compression_ratio = value.len() as f64 / compressed_value.len() as f64;
+        let uncompressed_size = self.as_chunk().data.len() * std::mem::size_of::<u16>();
+        let compression_ratio = uncompressed_size as f64 / compressed_value.len() as f64;
         metrics::set_float_gauge(&SLASHER_COMPRESSION_RATIO, compression_ratio);
 
         txn.put(
@@ -216,6 +335,14 @@ impl TargetArrayChunk for MinTargetChunk {
         &mut self.chunk
     }
 
+    fn as_chunk(&self) -> &Chunk {
+        &self.chunk
+    }
+
+    fn from_chunk(chunk: Chunk) -> Self {
+        Self { chunk }
+    }
+
     fn check_slashable<E: EthSpec>(
         &self,
         db: &SlasherDB<E>,
@@ -319,6 +446,14 @@ impl TargetArrayChunk for MaxTargetChunk {
         &mut self.chunk
     }
 
+    fn as_chunk(&self) -> &Chunk {
+        &self.chunk
+    }
+
+    fn from_chunk(chunk: Chunk) -> Self {
+        Self { chunk }
+    }
+
     fn check_slashable<E: EthSpec>(
         &self,
         db: &SlasherDB<E>,
@@ -623,3 +758,129 @@ pub fn update_array<E: EthSpec, T: TargetArrayChunk>(
 
     Ok(slashings)
 }
+
+/// Rewrite chunks stored in the legacy format using the run-length encoded format.
+///
+/// Up to `max_keys` disk keys are visited in both the min and max target databases, starting from
+/// `start_key`. Return the key from which to resume, or `None` if every chunk has been visited.
+pub fn reformat_chunks<E: EthSpec>(
+    db: &SlasherDB<E>,
+    txn: &mut RwTransaction<'_>,
+    start_key: u64,
+    max_keys: u64,
+    config: &Config,
+) -> Result<Option<u64>, Error> {
+    let Some(last_key) = std::cmp::max(
+        last_disk_key(txn, MinTargetChunk::select_db(db))?,
+        last_disk_key(txn, MaxTargetChunk::select_db(db))?,
+    ) else {
+        return Ok(None);
+    };
+    let end_key = start_key.saturating_add(max_keys).min(last_key + 1);
+
+    for disk_key in start_key..end_key {
+        reformat_chunk::<E, MinTargetChunk>(db, txn, disk_key, config)?;
+        reformat_chunk::<E, MaxTargetChunk>(db, txn, disk_key, config)?;
+    }
+
+    Ok((end_key <= last_key).then_some(end_key))
+}
+
+fn reformat_chunk<E: EthSpec, T: TargetArrayChunk>(
+    db: &SlasherDB<E>,
+    txn: &mut RwTransaction<'_>,
+    disk_key: u64,
+    config: &Config,
+) -> Result<(), Error> {
+    let key = disk_key.to_be_bytes();
+    let (chunk, format) = match txn.get(T::select_db(db), &key)? {
+        Some(chunk_bytes) => Chunk::decode(chunk_bytes.borrow(), config)?,
+        None => return Ok(()),
+    };
+    if format == ChunkFormat::Legacy {
+        txn.put(T::select_db(db), key, &chunk.encode()?)?;
+        metrics::inc_counter_vec(&SLASHER_NUM_CHUNKS_REFORMATTED, &[T::name()]);
+    }
+    Ok(())
+}
+
+fn last_disk_key(txn: &mut RwTransaction<'_>, db: &Database) -> Result<Option<u64>, Error> {
+    txn.cursor(db)?
+        .last_key()?
+        .map(|key| {
+            let bytes = key
+                .as_ref()
+                .try_into()
+                .map_err(|_| Error::ChunkKeyCorrupt { length: key.len() })?;
+            Ok(u64::from_be_bytes(bytes))
+        })
+        .transpose()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn config() -> Config {
+        let mut config = Config::new(std::path::PathBuf::new());
+        config.chunk_size = 4;
+        config.validator_chunk_size = 8;
+        config
+    }
+
+    #[test]
+    fn run_length_round_trip() {
+        let config = config();
+        let mut chunk = MinTargetChunk::empty(&config).chunk;
+        chunk.data[3] = 7;
+        chunk.data[4] = 7;
+        chunk.data[30] = 0;
+
+        let encoded = chunk.encode().unwrap();
+        assert_eq!(encoded[0], RLE_CHUNK_PREFIX);
+
+        let (decoded, format) = Chunk::decode(&encoded, &config).unwrap();
+        assert_eq!(format, ChunkFormat::RunLength);
+        assert_eq!(decoded.data, chunk.data);
+    }
+
+    #[test]
+    fn legacy_chunks_decode() {
+        let config = config();
+        let mut chunk = MaxTargetChunk::empty(&config).chunk;
+        chunk.data[5] = 300;
+
+        let value = bincode::serialize(&chunk).unwrap();
+        let mut legacy = vec![];
+        ZlibEncoder::new(&value[..], flate2::Compression::default())
+            .read_to_end(&mut legacy)
+            .unwrap();
+
+        let (decoded, format) = Chunk::decode(&legacy, &config).unwrap();
+        assert_eq!(format, ChunkFormat::Legacy);
+        assert_eq!(decoded.data, chunk.data);
+    }
+
+    #[test]
+    fn truncated_chunks_are_rejected() {
+        let config = config();
+        let chunk = Chunk {
+            data: vec![0; config.chunk_size * config.validator_chunk_size - 1],
+        };
+        assert!(matches!(
+            Chunk::decode(&chunk.encode().unwrap(), &config),
+            Err(Error::ChunkCorrupt { .. })
+        ));
+    }
+
+    #[test]
+    fn leb128_round_trip() {
+        for value in [0, 1, 127, 128, 16_383, 16_384, u64::MAX] {
+            let mut bytes = vec![];
+            write_leb128(&mut bytes, value);
+            let mut remaining = &bytes[..];
+            assert_eq!(read_leb128(&mut remaining), Some(value));
+            assert!(remaining.is_empty());
+        }
+    }
+}
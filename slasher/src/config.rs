@@ -14,6 +14,7 @@ pub const DEFAULT_SLOT_OFFSET: f64 = 10.5;
 pub const DEFAULT_MAX_DB_SIZE: usize = 512 * 1024; // 512 GiB
 pub const DEFAULT_ATTESTATION_ROOT_CACHE_SIZE: NonZeroUsize = new_non_zero_usize(100_000);
 pub const DEFAULT_BROADCAST: bool = false;
+pub const DEFAULT_FAST_PATH: bool = false;
 
 #[cfg(all(feature = "mdbx", not(any(feature = "lmdb", feature = "redb"))))]
 pub const DEFAULT_BACKEND: DatabaseBackend = DatabaseBackend::Mdbx;
@@ -48,6 +49,10 @@ pub struct Config {
     pub broadcast: bool,
     /// Database backend to use.
     pub backend: DatabaseBackend,
+    /// Whether to process attestations immediately when gossip verification observes a validator
+    /// attesting twice in the same epoch, rather than waiting for the next scheduled update.
+    #[serde(default)]
+    pub fast_path: bool,
 }
 
 /// Immutable configuration parameters which are stored on disk and checked for consistency.
@@ -92,6 +97,7 @@ impl Config {
             attestation_root_cache_size: DEFAULT_ATTESTATION_ROOT_CACHE_SIZE,
             broadcast: DEFAULT_BROADCAST,
             backend: DEFAULT_BACKEND,
+            fast_path: DEFAULT_FAST_PATH,
         }
     }
 
@@ -26,7 +26,7 @@ use types::{
 };
 
 /// Current database schema version, to check compatibility of on-disk DB with software.
-pub const CURRENT_SCHEMA_VERSION: u64 = 3;
+pub const CURRENT_SCHEMA_VERSION: u64 = 4;
 
 /// Metadata about the slashing database itself.
 const METADATA_DB: &str = "metadata";
@@ -59,6 +59,8 @@ const MAX_NUM_DBS: usize = 9;
 const METADATA_VERSION_KEY: &[u8] = &[0];
 /// Constant key under which the slasher configuration is stored in the `metadata_db`.
 const METADATA_CONFIG_KEY: &[u8] = &[1];
+/// Constant key under which the progress of the chunk reformat is stored in the `metadata_db`.
+const METADATA_CHUNK_REFORMAT_KEY: &[u8] = &[2];
 
 const ATTESTER_KEY_SIZE: usize = 7;
 const PROPOSER_KEY_SIZE: usize = 16;
@@ -384,6 +386,34 @@ impl<E: EthSpec> SlasherDB<E> {
         Ok(())
     }
 
+    /// Load the disk key from which to resume reformatting min-max chunks.
+    ///
+    /// Return `None` if every chunk has already been reformatted, or if the database was never
+    /// migrated from a schema which used the legacy chunk format.
+    pub fn load_chunk_reformat_progress(
+        &self,
+        txn: &mut RwTransaction<'_>,
+    ) -> Result<Option<u64>, Error> {
+        Ok(txn
+            .get(&self.databases.metadata_db, METADATA_CHUNK_REFORMAT_KEY)?
+            .map(bincode_deserialize)
+            .transpose()?
+            .flatten())
+    }
+
+    pub fn store_chunk_reformat_progress(
+        &self,
+        next_key: Option<u64>,
+        txn: &mut RwTransaction<'_>,
+    ) -> Result<(), Error> {
+        txn.put(
+            &self.databases.metadata_db,
+            METADATA_CHUNK_REFORMAT_KEY,
+            &bincode::serialize(&next_key)?,
+        )?;
+        Ok(())
+    }
+
     pub fn get_attester_max_target(
         &self,
         validator_index: u64,
@@ -860,10 +890,67 @@ impl<E: EthSpec> SlasherDB<E> {
 #[cfg(test)]
 mod test {
     use super::*;
+    use logging::test_logger;
+    use tempfile::tempdir;
     use types::{Checkpoint, ForkName, MainnetEthSpec, Unsigned};
 
     type E = MainnetEthSpec;
 
+    fn set_schema_version(db: &SlasherDB<E>, version: u64) {
+        let mut txn = db.begin_rw_txn().unwrap();
+        txn.put(
+            &db.databases.metadata_db,
+            METADATA_VERSION_KEY,
+            &bincode::serialize(&version).unwrap(),
+        )
+        .unwrap();
+        txn.commit().unwrap();
+    }
+
+    fn schema_version_and_reformat_progress(db: &SlasherDB<E>) -> (Option<u64>, Option<u64>) {
+        let mut txn = db.begin_rw_txn().unwrap();
+        let version = db.load_schema_version(&mut txn).unwrap();
+        let progress = db.load_chunk_reformat_progress(&mut txn).unwrap();
+        (version, progress)
+    }
+
+    #[test]
+    fn migrate_v3_to_v4_schedules_chunk_reformat() {
+        let tempdir = tempdir().unwrap();
+        let config = Arc::new(Config::new(tempdir.path().into()));
+        let spec = Arc::new(E::default_spec());
+        let db = SlasherDB::<E>::open(config, spec, test_logger()).unwrap();
+
+        // A freshly opened database is recorded at the current version.
+        let (version, _) = schema_version_and_reformat_progress(&db);
+        assert_eq!(version, Some(CURRENT_SCHEMA_VERSION));
+
+        // Re-running the migration at the current version doesn't restart the reformat.
+        let mut txn = db.begin_rw_txn().unwrap();
+        db.store_chunk_reformat_progress(None, &mut txn).unwrap();
+        txn.commit().unwrap();
+        let db = db.migrate().unwrap();
+        assert_eq!(
+            schema_version_and_reformat_progress(&db),
+            (Some(CURRENT_SCHEMA_VERSION), None)
+        );
+
+        // A v3 database is migrated, and every chunk is scheduled for reformatting.
+        set_schema_version(&db, 3);
+        let db = db.migrate().unwrap();
+        assert_eq!(
+            schema_version_and_reformat_progress(&db),
+            (Some(CURRENT_SCHEMA_VERSION), Some(0))
+        );
+
+        // Databases from the future are rejected.
+        set_schema_version(&db, CURRENT_SCHEMA_VERSION + 1);
+        assert!(matches!(
+            db.migrate(),
+            Err(Error::IncompatibleSchemaVersion { .. })
+        ));
+    }
+
     fn indexed_attestation_on_disk_roundtrip_test(
         spec: &ChainSpec,
         make_attestation: fn(
@@ -52,6 +52,13 @@ pub enum Error {
     AttesterKeyCorrupt {
         length: usize,
     },
+    ChunkCorrupt {
+        length: usize,
+        expected_length: usize,
+    },
+    ChunkKeyCorrupt {
+        length: usize,
+    },
     ProposerKeyCorrupt {
         length: usize,
     },
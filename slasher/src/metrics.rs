@@ -51,6 +51,13 @@ pub static SLASHER_NUM_CHUNKS_UPDATED: LazyLock<Result<IntCounterVec>> = LazyLoc
         &["array"],
     )
 });
+pub static SLASHER_NUM_CHUNKS_REFORMATTED: LazyLock<Result<IntCounterVec>> = LazyLock::new(|| {
+    try_create_int_counter_vec(
+        "slasher_num_chunks_reformatted",
+        "Number of min or max target chunks rewritten from the legacy on-disk format",
+        &["array"],
+    )
+});
 pub static SLASHER_COMPRESSION_RATIO: LazyLock<Result<Gauge>> = LazyLock::new(|| {
     try_create_float_gauge(
         "slasher_compression_ratio",
@@ -76,3 +83,9 @@ pub static SLASHER_ATTESTATION_ROOT_CACHE_SIZE: LazyLock<Result<IntGauge>> = Laz
         "Number of attestation data roots cached in memory",
     )
 });
+pub static SLASHER_FAST_PATH_REQUESTS: LazyLock<Result<IntCounter>> = LazyLock::new(|| {
+    try_create_int_counter(
+        "slasher_fast_path_requests_total",
+        "Number of conflicting gossip attestations which requested immediate processing",
+    )
+});
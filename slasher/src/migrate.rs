@@ -3,27 +3,44 @@ use types::EthSpec;
 
 impl<E: EthSpec> SlasherDB<E> {
     /// If the database exists, and has a schema, attempt to migrate it to the current version.
+    ///
+    /// The current schema version is recorded once any migration has completed, so that older
+    /// software refuses to open a database it can no longer read.
     pub fn migrate(self) -> Result<Self, Error> {
         let mut txn = self.begin_rw_txn()?;
         let schema_version = self.load_schema_version(&mut txn)?;
-        drop(txn);
 
-        if let Some(schema_version) = schema_version {
-            match (schema_version, CURRENT_SCHEMA_VERSION) {
-                // Schema v3 changed the underlying database from LMDB to MDBX. Unless the user did
-                // some manual hacking it should be impossible to read an MDBX schema version < 3.
-                (from, _) if from < 3 => Err(Error::IncompatibleSchemaVersion {
-                    database_schema_version: schema_version,
+        match (schema_version, CURRENT_SCHEMA_VERSION) {
+            // Schema v3 changed the underlying database from LMDB to MDBX. Unless the user did
+            // some manual hacking it should be impossible to read an MDBX schema version < 3.
+            (Some(from), _) if from < 3 => {
+                return Err(Error::IncompatibleSchemaVersion {
+                    database_schema_version: from,
                     software_schema_version: CURRENT_SCHEMA_VERSION,
-                }),
-                (x, y) if x == y => Ok(self),
-                (_, _) => Err(Error::IncompatibleSchemaVersion {
-                    database_schema_version: schema_version,
+                })
+            }
+            // Schema v4 run-length encodes the min-max target chunks. Chunks in the legacy format
+            // remain readable and are rewritten in the background by `Slasher::reformat_chunks`,
+            // starting from the first chunk.
+            //
+            // Databases without a recorded schema version may have been written by v3 software,
+            // so they are reformatted too. This is cheap for a new database, which has no chunks.
+            (Some(3) | None, 4) => {
+                self.store_chunk_reformat_progress(Some(0), &mut txn)?;
+            }
+            (Some(x), y) if x == y => {}
+            (Some(from), _) => {
+                return Err(Error::IncompatibleSchemaVersion {
+                    database_schema_version: from,
                     software_schema_version: CURRENT_SCHEMA_VERSION,
-                }),
+                })
             }
-        } else {
-            Ok(self)
+            (None, _) => {}
         }
+
+        self.store_schema_version(&mut txn)?;
+        txn.commit()?;
+
+        Ok(self)
     }
 }
@@ -12,11 +12,17 @@ use parking_lot::Mutex;
 use slog::{debug, error, info, Logger};
 use std::collections::HashSet;
 use std::sync::Arc;
+use tokio::sync::Notify;
 use types::{
     AttesterSlashing, ChainSpec, Epoch, EthSpec, IndexedAttestation, ProposerSlashing,
     SignedBeaconBlockHeader,
 };
 
+/// Number of disk keys to visit per call to `Slasher::reformat_chunks`.
+///
+/// Each key covers one chunk in each of the min and max target databases.
+const CHUNK_REFORMAT_BATCH_SIZE: u64 = 16_384;
+
 #[derive(Debug)]
 pub struct Slasher<E: EthSpec> {
     db: SlasherDB<E>,
@@ -25,6 +31,8 @@ pub struct Slasher<E: EthSpec> {
     attester_slashings: Mutex<HashSet<AttesterSlashing<E>>>,
     proposer_slashings: Mutex<HashSet<ProposerSlashing>>,
     config: Arc<Config>,
+    /// Notified when an attestation which is likely to be slashable is accepted.
+    fast_path_notify: Notify,
     log: Logger,
 }
 
@@ -57,6 +65,7 @@ impl<E: EthSpec> Slasher<E> {
             attester_slashings,
             proposer_slashings,
             config,
+            fast_path_notify: Notify::new(),
             log,
         })
     }
@@ -89,6 +98,26 @@ impl<E: EthSpec> Slasher<E> {
         self.attestation_queue.queue(attestation);
     }
 
+    /// Accept an attestation from a validator which has already been observed attesting to
+    /// different data in the same epoch.
+    ///
+    /// If the fast path is enabled, the slasher service is prompted to process the queued
+    /// attestations without waiting for the next scheduled update.
+    pub fn accept_conflicting_attestation(&self, attestation: IndexedAttestation<E>) {
+        self.accept_attestation(attestation);
+        if self.config.fast_path {
+            metrics::inc_counter(&metrics::SLASHER_FAST_PATH_REQUESTS);
+            self.fast_path_notify.notify_one();
+        }
+    }
+
+    /// Wait until processing is requested via `Self::accept_conflicting_attestation`.
+    ///
+    /// A request made whilst no task is waiting is stored, and completes the next wait immediately.
+    pub async fn fast_path_requested(&self) {
+        self.fast_path_notify.notified().await
+    }
+
     /// Accept a block from the network and queue it for processing.
     pub fn accept_block_header(&self, block_header: SignedBeaconBlockHeader) {
         self.block_queue.queue(block_header);
@@ -371,4 +400,37 @@ impl<E: EthSpec> Slasher<E> {
     pub fn prune_database(&self, current_epoch: Epoch) -> Result<(), Error> {
         self.db.prune(current_epoch)
     }
+
+    /// Rewrite a batch of min-max chunks stored in the legacy format.
+    ///
+    /// Databases created by older versions are converted incrementally so that the conversion
+    /// doesn't stall batch processing. Return `true` once every chunk has been converted.
+    pub fn reformat_chunks(&self) -> Result<bool, Error> {
+        let mut txn = self.db.begin_rw_txn()?;
+        let Some(start_key) = self.db.load_chunk_reformat_progress(&mut txn)? else {
+            return Ok(true);
+        };
+
+        let next_key = array::reformat_chunks(
+            &self.db,
+            &mut txn,
+            start_key,
+            CHUNK_REFORMAT_BATCH_SIZE,
+            &self.config,
+        )?;
+        self.db.store_chunk_reformat_progress(next_key, &mut txn)?;
+        txn.commit()?;
+
+        match next_key {
+            Some(next_key) => debug!(
+                self.log,
+                "Reformatted slasher chunks";
+                "start_key" => start_key,
+                "next_key" => next_key,
+            ),
+            None => info!(self.log, "Finished reformatting slasher chunks"),
+        }
+
+        Ok(next_key.is_none())
+    }
 }
@@ -12,6 +12,7 @@ use slasher::{
     Config, Slasher,
 };
 use std::collections::HashSet;
+use std::time::Duration;
 use tempfile::tempdir;
 use types::{AttesterSlashing, Epoch, IndexedAttestation};
 
@@ -32,6 +33,31 @@ fn double_vote_single_val() {
     }
 }
 
+#[tokio::test]
+async fn double_vote_fast_path() {
+    let tempdir = tempdir().unwrap();
+    let mut config = Config::new(tempdir.path().into());
+    config.fast_path = true;
+    let slasher = Slasher::open(config, chain_spec(), test_logger()).unwrap();
+
+    let v = vec![99];
+    let att1 = indexed_att(&v, 0, 1, 0);
+    let att2 = indexed_att(&v, 0, 1, 1);
+    slasher.accept_attestation(att1.clone());
+    slasher.accept_conflicting_attestation(att2.clone());
+
+    // The request is stored until the service waits for it.
+    tokio::time::timeout(Duration::from_secs(1), slasher.fast_path_requested())
+        .await
+        .expect("fast path should be requested");
+
+    slasher.process_queued(Epoch::new(1)).unwrap();
+    assert_eq!(
+        slasher.get_attester_slashings(),
+        hashset![att_slashing(&att1, &att2)]
+    );
+}
+
 #[test]
 fn double_vote_multi_vals() {
     let v = vec![0, 1, 2];
@@ -360,6 +360,7 @@ mod tests {
             let validator_store = ValidatorStore::<_, E>::new(
                 initialized_validators,
                 slashing_protection,
+                None,
                 Hash256::repeat_byte(42),
                 spec,
                 None,
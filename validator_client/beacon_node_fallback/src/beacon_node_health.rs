@@ -94,7 +94,8 @@ impl BeaconNodeSyncDistanceTiers {
 
 /// Execution Node health metrics.
 ///
-/// Currently only considers `el_offline`.
+/// Determined by the `el_offline` field of the `node/syncing` response. An execution node which is
+/// online but still syncing is reflected by `IsOptimistic` instead.
 #[derive(Copy, Clone, Debug, PartialEq, Eq, Deserialize, Serialize)]
 pub enum ExecutionEngineHealth {
     Healthy,
@@ -226,6 +227,16 @@ impl BeaconNodeHealth {
         self.health_tier
     }
 
+    /// Returns `true` if the node's execution engine is online and its head has been fully
+    /// validated by it.
+    ///
+    /// A node which fails this check may still appear to be synced, but is unsuitable for block
+    /// production since it cannot verify the execution payload of the parent block.
+    pub fn execution_validated(&self) -> bool {
+        self.optimistic_status == IsOptimistic::No
+            && self.execution_status == ExecutionEngineHealth::Healthy
+    }
+
     fn compute_health_tier(
         sync_distance: SyncDistance,
         optimistic_status: IsOptimistic,
@@ -392,6 +403,28 @@ mod tests {
         )
     }
 
+    #[test]
+    fn execution_validated() {
+        let distance_tiers = BeaconNodeSyncDistanceTiers::default();
+
+        for optimistic_status in [IsOptimistic::No, IsOptimistic::Yes] {
+            for ee_health in [Healthy, Unhealthy] {
+                let health = BeaconNodeHealth::from_status(
+                    0,
+                    Slot::new(0),
+                    Slot::new(0),
+                    optimistic_status,
+                    ee_health,
+                    &distance_tiers,
+                );
+                assert_eq!(
+                    health.execution_validated(),
+                    optimistic_status == IsOptimistic::No && ee_health == Healthy
+                );
+            }
+        }
+    }
+
     #[test]
     fn sync_tolerance_default() {
         let distance_tiers = BeaconNodeSyncDistanceTiers::default();
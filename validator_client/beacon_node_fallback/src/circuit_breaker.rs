@@ -0,0 +1,180 @@
+//! A circuit breaker which tracks the outcome of recent requests to the primary beacon node.
+//!
+//! When the primary beacon node is degraded, every duty fails and retries against it, and the
+//! resulting flood of requests and errors can make recovery harder. Once the breaker is open, the
+//! validator client skips non-critical duties (aggregation and sync committee contributions) until
+//! the cooldown has elapsed, while attestations and block proposals are still attempted.
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+use std::str::FromStr;
+use std::time::{Duration, Instant};
+
+/// The thresholds at which the circuit breaker opens.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CircuitBreakerConfig {
+    /// The number of failed requests within the window which opens the breaker.
+    pub failure_threshold: usize,
+    /// The number of most recent requests to the primary beacon node which are considered.
+    pub window: usize,
+    /// How long the breaker stays open before duties are attempted again.
+    pub cooldown: Duration,
+}
+
+impl FromStr for CircuitBreakerConfig {
+    type Err = String;
+
+    /// Parses a value of the form `FAILURES,WINDOW,COOLDOWN_SECONDS`, e.g. `8,10,24`.
+    fn from_str(s: &str) -> Result<Self, String> {
+        let values = s
+            .split(',')
+            .map(|s| {
+                s.trim()
+                    .parse::<u64>()
+                    .map_err(|e| format!("Invalid circuit breaker value: {e:?}"))
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        let [failure_threshold, window, cooldown] = values[..] else {
+            return Err("Invalid number of circuit breaker values, expected 3".to_string());
+        };
+
+        if failure_threshold == 0 || failure_threshold > window {
+            return Err(format!(
+                "Circuit breaker failure threshold must be between 1 and the window size ({window})"
+            ));
+        }
+
+        Ok(Self {
+            failure_threshold: failure_threshold as usize,
+            window: window as usize,
+            cooldown: Duration::from_secs(cooldown),
+        })
+    }
+}
+
+/// A change in the state of a `CircuitBreaker`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Transition {
+    Opened,
+    Closed,
+}
+
+#[derive(Debug)]
+pub struct CircuitBreaker {
+    config: CircuitBreakerConfig,
+    /// The outcome of recent requests, `true` for a failure.
+    outcomes: VecDeque<bool>,
+    open_until: Option<Instant>,
+}
+
+impl CircuitBreaker {
+    pub fn new(config: CircuitBreakerConfig) -> Self {
+        Self {
+            config,
+            outcomes: VecDeque::with_capacity(config.window),
+            open_until: None,
+        }
+    }
+
+    /// Record the outcome of a request to the primary beacon node.
+    ///
+    /// Requests completing while the breaker is open are ignored so that the breaker always stays
+    /// open for the full cooldown.
+    pub fn record(&mut self, failed: bool, now: Instant) -> Option<Transition> {
+        let closed = self.expire(now);
+        if self.open_until.is_some() {
+            return None;
+        }
+
+        if self.outcomes.len() == self.config.window {
+            self.outcomes.pop_front();
+        }
+        self.outcomes.push_back(failed);
+
+        let failures = self.outcomes.iter().filter(|failed| **failed).count();
+        if failures >= self.config.failure_threshold {
+            self.open_until = Some(now + self.config.cooldown);
+            self.outcomes.clear();
+            Some(Transition::Opened)
+        } else {
+            closed
+        }
+    }
+
+    /// Returns `true` if non-critical duties should currently be skipped.
+    pub fn is_open(&mut self, now: Instant) -> (bool, Option<Transition>) {
+        let transition = self.expire(now);
+        (self.open_until.is_some(), transition)
+    }
+
+    /// Close the breaker if its cooldown has elapsed.
+    fn expire(&mut self, now: Instant) -> Option<Transition> {
+        match self.open_until {
+            Some(open_until) if now >= open_until => {
+                self.open_until = None;
+                Some(Transition::Closed)
+            }
+            _ => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config() -> CircuitBreakerConfig {
+        CircuitBreakerConfig {
+            failure_threshold: 3,
+            window: 4,
+            cooldown: Duration::from_secs(12),
+        }
+    }
+
+    #[test]
+    fn opens_after_sustained_failures_and_closes_after_cooldown() {
+        let mut breaker = CircuitBreaker::new(config());
+        let now = Instant::now();
+
+        assert_eq!(breaker.record(true, now), None);
+        assert_eq!(breaker.record(false, now), None);
+        assert_eq!(breaker.record(true, now), None);
+        assert_eq!(breaker.is_open(now), (false, None));
+        assert_eq!(breaker.record(true, now), Some(Transition::Opened));
+        assert_eq!(breaker.is_open(now), (true, None));
+
+        // Outcomes are ignored while the breaker is open.
+        let later = now + Duration::from_secs(6);
+        assert_eq!(breaker.record(false, later), None);
+        assert_eq!(breaker.is_open(later), (true, None));
+
+        let after_cooldown = now + Duration::from_secs(12);
+        assert_eq!(
+            breaker.is_open(after_cooldown),
+            (false, Some(Transition::Closed))
+        );
+
+        // The window starts empty once the breaker has closed.
+        assert_eq!(breaker.record(true, after_cooldown), None);
+        assert_eq!(breaker.is_open(after_cooldown), (false, None));
+    }
+
+    #[test]
+    fn old_failures_leave_the_window() {
+        let mut breaker = CircuitBreaker::new(config());
+        let now = Instant::now();
+
+        for failed in [true, true, false, false, false, true] {
+            assert_eq!(breaker.record(failed, now), None);
+        }
+        assert_eq!(breaker.is_open(now), (false, None));
+    }
+
+    #[test]
+    fn parse_config() {
+        assert_eq!(CircuitBreakerConfig::from_str("3,4,12"), Ok(config()));
+        assert!(CircuitBreakerConfig::from_str("3,4").is_err());
+        assert!(CircuitBreakerConfig::from_str("5,4,12").is_err());
+        assert!(CircuitBreakerConfig::from_str("0,4,12").is_err());
+        assert!(CircuitBreakerConfig::from_str("a,4,12").is_err());
+    }
+}
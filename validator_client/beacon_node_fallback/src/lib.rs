@@ -3,15 +3,18 @@
 //! succeed.
 
 pub mod beacon_node_health;
+pub mod circuit_breaker;
 use beacon_node_health::{
     check_node_health, BeaconNodeHealth, BeaconNodeSyncDistanceTiers, ExecutionEngineHealth,
     IsOptimistic, SyncDistanceTier,
 };
+use circuit_breaker::{CircuitBreaker, CircuitBreakerConfig, Transition};
 use environment::RuntimeContext;
 use eth2::BeaconNodeHttpClient;
 use futures::future;
+use parking_lot::Mutex;
 use serde::{ser::SerializeStruct, Deserialize, Serialize, Serializer};
-use slog::{debug, error, warn, Logger};
+use slog::{debug, error, info, warn, Logger};
 use slot_clock::SlotClock;
 use std::cmp::Ordering;
 use std::fmt;
@@ -20,10 +23,13 @@ use std::future::Future;
 use std::marker::PhantomData;
 use std::sync::Arc;
 use std::time::{Duration, Instant};
-use strum::{EnumString, EnumVariantNames};
+use strum::{EnumString, EnumVariantNames, IntoStaticStr};
 use tokio::{sync::RwLock, time::sleep};
 use types::{ChainSpec, Config as ConfigSpec, EthSpec, Slot};
-use validator_metrics::{inc_counter_vec, ENDPOINT_ERRORS, ENDPOINT_REQUESTS};
+use validator_metrics::{
+    inc_counter_vec, set_gauge, AGGREGATION_CIRCUIT_BREAKER_OPEN, BROADCAST_SUBMISSIONS,
+    ENDPOINT_ERRORS, ENDPOINT_REQUESTS,
+};
 
 /// Message emitted when the VC detects the BN is using a different spec.
 const UPDATE_REQUIRED_LOG_HINT: &str = "this VC or the remote BN may need updating";
@@ -45,6 +51,8 @@ const FUTURE_SLOT_TOLERANCE: Slot = Slot::new(1);
 #[derive(Copy, Clone, Debug, Default, Serialize, Deserialize)]
 pub struct Config {
     pub sync_tolerances: BeaconNodeSyncDistanceTiers,
+    /// Skip non-critical duties when requests to the primary beacon node keep failing.
+    pub aggregation_circuit_breaker: Option<CircuitBreakerConfig>,
 }
 
 /// Indicates a measurement of latency between the VC and a BN.
@@ -128,6 +136,50 @@ impl<T> Errors<T> {
     }
 }
 
+impl<T: RequestFailureReason> Errors<T> {
+    /// Returns the `reason` label of the `vc_duty_failures_total` metric for each failed request.
+    ///
+    /// If no beacon node could be tried at all then a single `bn_unavailable` reason is returned.
+    pub fn failure_reasons(&self) -> Vec<&'static str> {
+        if self.0.is_empty() {
+            return vec![validator_metrics::FAILURE_BN_UNAVAILABLE];
+        }
+        self.0
+            .iter()
+            .filter_map(|(_, error)| error.request_failure())
+            .map(RequestFailureReason::failure_reason)
+            .collect()
+    }
+}
+
+/// An error of a request to a beacon node which can be recorded in the `vc_duty_failures_total`
+/// metric.
+pub trait RequestFailureReason {
+    /// Returns the `reason` label of the `vc_duty_failures_total` metric for this error.
+    fn failure_reason(&self) -> &'static str;
+}
+
+impl RequestFailureReason for eth2::Error {
+    fn failure_reason(&self) -> &'static str {
+        request_failure_reason(self)
+    }
+}
+
+/// Returns the `reason` label of the `vc_duty_failures_total` metric for a failed request to a
+/// beacon node.
+pub fn request_failure_reason(error: &eth2::Error) -> &'static str {
+    match (error, error.status()) {
+        (eth2::Error::HttpClient(e), _) if e.inner().is_timeout() => {
+            validator_metrics::FAILURE_TIMEOUT
+        }
+        (_, Some(status)) if status.is_client_error() => validator_metrics::FAILURE_HTTP_4XX,
+        (_, Some(status)) if status.is_server_error() => validator_metrics::FAILURE_HTTP_5XX,
+        // The request did not receive a response, e.g. the connection was refused.
+        (eth2::Error::HttpClient(_), None) => validator_metrics::FAILURE_BN_UNAVAILABLE,
+        _ => validator_metrics::FAILURE_OTHER,
+    }
+}
+
 /// Reasons why a candidate might not be ready.
 #[derive(Debug, Clone, Copy, PartialEq, Deserialize, Serialize)]
 pub enum CandidateError {
@@ -376,6 +428,7 @@ pub struct BeaconNodeFallback<T, E> {
     distance_tiers: BeaconNodeSyncDistanceTiers,
     slot_clock: Option<T>,
     broadcast_topics: Vec<ApiTopic>,
+    circuit_breaker: Option<Arc<Mutex<CircuitBreaker>>>,
     spec: Arc<ChainSpec>,
     log: Logger,
 }
@@ -394,6 +447,9 @@ impl<T: SlotClock, E: EthSpec> BeaconNodeFallback<T, E> {
             distance_tiers,
             slot_clock: None,
             broadcast_topics,
+            circuit_breaker: config
+                .aggregation_circuit_breaker
+                .map(|config| Arc::new(Mutex::new(CircuitBreaker::new(config)))),
             spec,
             log,
         }
@@ -408,6 +464,46 @@ impl<T: SlotClock, E: EthSpec> BeaconNodeFallback<T, E> {
         self.slot_clock = Some(slot_clock);
     }
 
+    /// Returns `true` if requests to the primary beacon node have failed persistently enough that
+    /// non-critical duties such as aggregation should be skipped.
+    ///
+    /// Always `false` unless the aggregation circuit breaker is configured.
+    pub fn aggregation_circuit_open(&self) -> bool {
+        let Some(breaker) = &self.circuit_breaker else {
+            return false;
+        };
+        let (open, transition) = breaker.lock().is_open(Instant::now());
+        self.on_circuit_transition(transition);
+        open
+    }
+
+    /// Record the outcome of a request to the primary (first tried) beacon node.
+    fn record_primary_outcome(&self, success: bool) {
+        if let Some(breaker) = &self.circuit_breaker {
+            let transition = breaker.lock().record(!success, Instant::now());
+            self.on_circuit_transition(transition);
+        }
+    }
+
+    fn on_circuit_transition(&self, transition: Option<Transition>) {
+        match transition {
+            Some(Transition::Opened) => {
+                warn!(
+                    self.log,
+                    "Skipping aggregation duties";
+                    "reason" => "sustained primary beacon node failures",
+                    "msg" => "attestations and proposals are still attempted",
+                );
+                set_gauge(&AGGREGATION_CIRCUIT_BREAKER_OPEN, 1);
+            }
+            Some(Transition::Closed) => {
+                info!(self.log, "Resuming aggregation duties");
+                set_gauge(&AGGREGATION_CIRCUIT_BREAKER_OPEN, 0);
+            }
+            None => {}
+        }
+    }
+
     /// The count of candidates, regardless of their state.
     pub async fn num_total(&self) -> usize {
         self.candidates.read().await.len()
@@ -574,8 +670,12 @@ impl<T: SlotClock, E: EthSpec> BeaconNodeFallback<T, E> {
         }
         drop(candidates);
 
-        for future in futures {
-            match future.await {
+        for (i, future) in futures.into_iter().enumerate() {
+            let result = future.await;
+            if i == 0 {
+                self.record_primary_outcome(result.is_ok());
+            }
+            match result {
                 Ok(val) => return Ok(val),
                 Err(e) => errors.push(e),
             }
@@ -607,6 +707,71 @@ impl<T: SlotClock, E: EthSpec> BeaconNodeFallback<T, E> {
         Err(Errors(errors))
     }
 
+    /// Run `func` against each candidate in `self` in the same manner as `first_success`, except
+    /// that candidates whose execution engine is offline or optimistic are tried last.
+    ///
+    /// This is intended for block production, where a node with a seemingly healthy consensus sync
+    /// may still be unable to produce a valid block if its execution engine is not fully synced.
+    pub async fn first_success_execution_validated<F, O, Err, R>(
+        &self,
+        func: F,
+    ) -> Result<O, Errors<Err>>
+    where
+        F: Fn(BeaconNodeHttpClient) -> R,
+        R: Future<Output = Result<O, Err>>,
+        Err: Debug,
+    {
+        let beacon_nodes = self.candidates_by_execution_status().await;
+        let mut errors = vec![];
+
+        // Try all candidates twice, mirroring `first_success`.
+        for pass in 0..2 {
+            for (i, beacon_node) in beacon_nodes.iter().enumerate() {
+                let result = Self::run_on_candidate(beacon_node.clone(), &func, &self.log).await;
+                if pass == 0 && i == 0 {
+                    self.record_primary_outcome(result.is_ok());
+                }
+                match result {
+                    Ok(val) => return Ok(val),
+                    Err(e) => errors.push(e),
+                }
+            }
+        }
+
+        Err(Errors(errors))
+    }
+
+    /// Returns the candidates in their current order, with those whose execution status is not
+    /// fully validated moved to the back of the list.
+    async fn candidates_by_execution_status(&self) -> Vec<BeaconNodeHttpClient> {
+        let candidates = self.candidates.read().await;
+        let mut validated = Vec::with_capacity(candidates.len());
+        let mut not_validated = vec![];
+
+        for candidate in candidates.iter() {
+            match candidate.health().await {
+                Ok(health) if health.execution_validated() => {
+                    validated.push(candidate.beacon_node.clone())
+                }
+                health => {
+                    if let Ok(health) = health {
+                        debug!(
+                            self.log,
+                            "Deprioritising beacon node with unvalidated execution";
+                            "endpoint" => %candidate.beacon_node,
+                            "optimistic" => ?health.optimistic_status,
+                            "execution_status" => ?health.execution_status,
+                        );
+                    }
+                    not_validated.push(candidate.beacon_node.clone())
+                }
+            }
+        }
+
+        validated.extend(not_validated);
+        validated
+    }
+
     /// Run the future `func` on `candidate` while reporting metrics.
     async fn run_on_candidate<F, R, Err, O>(
         candidate: BeaconNodeHttpClient,
@@ -665,6 +830,9 @@ impl<T: SlotClock, E: EthSpec> BeaconNodeFallback<T, E> {
         drop(candidates);
 
         let results = future::join_all(futures).await;
+        if let Some(result) = results.first() {
+            self.record_primary_outcome(result.is_ok());
+        }
 
         let errors: Vec<_> = results.into_iter().filter_map(|res| res.err()).collect();
 
@@ -677,6 +845,10 @@ impl<T: SlotClock, E: EthSpec> BeaconNodeFallback<T, E> {
 
     /// Call `func` on first beacon node that returns success or on all beacon nodes
     /// depending on the `topic` and configuration.
+    ///
+    /// When broadcasting, the outcome of the submission to each beacon node is recorded in the
+    /// `BROADCAST_SUBMISSIONS` metric. Beacon nodes are expected to deduplicate the messages they
+    /// receive from each other via gossip, so broadcasting never results in a duplicate on chain.
     pub async fn request<F, Err, R>(&self, topic: ApiTopic, func: F) -> Result<(), Errors<Err>>
     where
         F: Fn(BeaconNodeHttpClient) -> R,
@@ -684,7 +856,15 @@ impl<T: SlotClock, E: EthSpec> BeaconNodeFallback<T, E> {
         Err: Debug,
     {
         if self.broadcast_topics.contains(&topic) {
-            self.broadcast(func).await
+            let func = &func;
+            self.broadcast(|beacon_node| async move {
+                let endpoint = beacon_node.as_ref().to_string();
+                let result = func(beacon_node).await;
+                let outcome = if result.is_ok() { "success" } else { "error" };
+                inc_counter_vec(&BROADCAST_SUBMISSIONS, &[&endpoint, topic.into(), outcome]);
+                result
+            })
+            .await
         } else {
             self.first_success(func).await?;
             Ok(())
@@ -719,10 +899,22 @@ async fn sort_nodes_by_health<E: EthSpec>(nodes: &mut Vec<CandidateBeaconNode<E>
 }
 
 /// Serves as a cue for `BeaconNodeFallback` to tell which requests need to be broadcasted.
-#[derive(Clone, Copy, Debug, PartialEq, Deserialize, Serialize, EnumString, EnumVariantNames)]
+#[derive(
+    Clone,
+    Copy,
+    Debug,
+    PartialEq,
+    Deserialize,
+    Serialize,
+    EnumString,
+    EnumVariantNames,
+    IntoStaticStr,
+)]
 #[strum(serialize_all = "kebab-case")]
 pub enum ApiTopic {
     Attestations,
+    /// Aggregate attestations and sync committee contributions.
+    Aggregates,
     Blocks,
     Subscriptions,
     SyncCommittee,
@@ -731,7 +923,13 @@ pub enum ApiTopic {
 impl ApiTopic {
     pub fn all() -> Vec<ApiTopic> {
         use ApiTopic::*;
-        vec![Attestations, Blocks, Subscriptions, SyncCommittee]
+        vec![
+            Attestations,
+            Aggregates,
+            Blocks,
+            Subscriptions,
+            SyncCommittee,
+        ]
     }
 }
 
@@ -757,6 +955,40 @@ mod tests {
             .eq(all.into_iter()));
     }
 
+    #[test]
+    fn request_failure_reasons() {
+        let status_error =
+            |code| eth2::Error::StatusCode(eth2::StatusCode::from_u16(code).unwrap());
+        assert_eq!(
+            request_failure_reason(&status_error(404)),
+            validator_metrics::FAILURE_HTTP_4XX
+        );
+        assert_eq!(
+            request_failure_reason(&status_error(503)),
+            validator_metrics::FAILURE_HTTP_5XX
+        );
+        assert_eq!(
+            request_failure_reason(&eth2::Error::InvalidSignatureHeader),
+            validator_metrics::FAILURE_OTHER
+        );
+
+        let errors = Errors(vec![
+            ("0".to_string(), Error::RequestFailed(status_error(400))),
+            ("1".to_string(), Error::RequestFailed(status_error(500))),
+        ]);
+        assert_eq!(
+            errors.failure_reasons(),
+            vec![
+                validator_metrics::FAILURE_HTTP_4XX,
+                validator_metrics::FAILURE_HTTP_5XX
+            ]
+        );
+        assert_eq!(
+            Errors::<eth2::Error>(vec![]).failure_reasons(),
+            vec![validator_metrics::FAILURE_BN_UNAVAILABLE]
+        );
+    }
+
     #[tokio::test]
     async fn check_candidate_order() {
         // These fields is irrelvant for sorting. They are set to arbitrary values.
@@ -867,4 +1099,53 @@ mod tests {
 
         assert_eq!(candidates, expected_candidates);
     }
+
+    #[tokio::test]
+    async fn check_candidate_order_by_execution_status() {
+        let candidates = (0..3)
+            .map(|index| {
+                let beacon_node = BeaconNodeHttpClient::new(
+                    SensitiveUrl::parse(&format!("http://example_{index}.com")).unwrap(),
+                    Timeouts::set_all(Duration::from_secs(1)),
+                );
+                CandidateBeaconNode::<E>::new(beacon_node, index)
+            })
+            .collect::<Vec<_>>();
+
+        let health = |user_index, optimistic_status, execution_status| BeaconNodeHealth {
+            user_index,
+            head: Slot::new(99),
+            optimistic_status,
+            execution_status,
+            health_tier: BeaconNodeHealthTier::new(1, Slot::new(0), SyncDistanceTier::Synced),
+        };
+
+        // The first node is optimistic, the second has an offline execution engine and only the
+        // third is fully validated.
+        *candidates[0].health.write().await =
+            Ok(health(0, IsOptimistic::Yes, ExecutionEngineHealth::Healthy));
+        *candidates[1].health.write().await = Ok(health(
+            1,
+            IsOptimistic::No,
+            ExecutionEngineHealth::Unhealthy,
+        ));
+        *candidates[2].health.write().await =
+            Ok(health(2, IsOptimistic::No, ExecutionEngineHealth::Healthy));
+
+        let expected = vec![
+            candidates[2].beacon_node.clone(),
+            candidates[0].beacon_node.clone(),
+            candidates[1].beacon_node.clone(),
+        ];
+
+        let fallback = BeaconNodeFallback::<slot_clock::TestingSlotClock, E>::new(
+            candidates,
+            Config::default(),
+            vec![],
+            Arc::new(E::default_spec()),
+            logging::test_logger(),
+        );
+
+        assert_eq!(fallback.candidates_by_execution_status().await, expected);
+    }
 }
@@ -1,13 +1,23 @@
 use bls::PublicKey;
+use graffiti_file::{determine_graffiti, GraffitiFile};
+use slog::Logger;
 use slot_clock::SlotClock;
 use std::sync::Arc;
 use types::{graffiti::GraffitiString, EthSpec, Graffiti};
 use validator_store::ValidatorStore;
 
+/// Returns the graffiti which would be used in a block proposed by `validator_pubkey`.
+///
+/// This follows the same order of precedence as block proposal: the graffiti file, then the
+/// validator definition (which is where graffiti set via the API is stored), then the
+/// process-wide default. A graffiti set via the API is therefore not returned whilst the graffiti
+/// file contains an entry for the validator, or a default.
 pub fn get_graffiti<T: 'static + SlotClock + Clone, E: EthSpec>(
     validator_pubkey: PublicKey,
     validator_store: Arc<ValidatorStore<T, E>>,
+    graffiti_file: Option<GraffitiFile>,
     graffiti_flag: Option<Graffiti>,
+    log: &Logger,
 ) -> Result<Graffiti, warp::Rejection> {
     let initialized_validators_rw_lock = validator_store.initialized_validators();
     let initialized_validators = initialized_validators_rw_lock.read();
@@ -16,12 +26,17 @@ pub fn get_graffiti<T: 'static + SlotClock + Clone, E: EthSpec>(
             "The key was not found on the server".to_string(),
         )),
         Some(_) => {
-            let Some(graffiti) = initialized_validators.graffiti(&validator_pubkey.into()) else {
-                return graffiti_flag.ok_or(warp_utils::reject::custom_server_error(
-                    "No graffiti found, unable to return the process-wide default".to_string(),
-                ));
-            };
-            Ok(graffiti)
+            let validator_pubkey = validator_pubkey.into();
+            determine_graffiti(
+                &validator_pubkey,
+                log,
+                graffiti_file,
+                initialized_validators.graffiti(&validator_pubkey),
+                graffiti_flag,
+            )
+            .ok_or(warp_utils::reject::custom_server_error(
+                "No graffiti found, unable to return the process-wide default".to_string(),
+            ))
         }
     }
 }
@@ -3,6 +3,7 @@ mod create_signed_voluntary_exit;
 mod create_validator;
 mod graffiti;
 mod keystores;
+mod presigned_exit;
 mod remotekeys;
 mod tests;
 
@@ -12,6 +13,7 @@ use graffiti::{delete_graffiti, get_graffiti, set_graffiti};
 
 use create_signed_voluntary_exit::create_signed_voluntary_exit;
 use graffiti_file::{determine_graffiti, GraffitiFile};
+use presigned_exit::publish_presigned_exit;
 use validator_store::ValidatorStore;
 
 use account_utils::{
@@ -331,6 +333,31 @@ pub fn serve<T: 'static + SlotClock + Clone, E: EthSpec>(
             })
         });
 
+    // GET lighthouse/signers
+    let get_lighthouse_signers = warp::path("lighthouse")
+        .and(warp::path("signers"))
+        .and(warp::path::end())
+        .and(validator_store_filter.clone())
+        .then(|validator_store: Arc<ValidatorStore<T, E>>| {
+            blocking_json_task(move || {
+                let mut signers = validator_store
+                    .web3_signer_health()
+                    .into_iter()
+                    .map(|(url, health)| api_types::SignerHealthData {
+                        url,
+                        healthy: health.healthy,
+                        last_checked: health.last_checked,
+                        error: health.error,
+                        validators: health.validators,
+                        unavailable_validators: health.unavailable_validators,
+                    })
+                    .collect::<Vec<_>>();
+                signers.sort_by(|a, b| a.url.cmp(&b.url));
+
+                Ok(api_types::GenericResponse::from(signers))
+            })
+        });
+
     // GET lighthouse/validators/{validator_pubkey}
     let get_lighthouse_validators_pubkey = warp::path("lighthouse")
         .and(warp::path("validators"))
@@ -1058,6 +1085,40 @@ pub fn serve<T: 'static + SlotClock + Clone, E: EthSpec>(
         )
         .map(|reply| warp::reply::with_status(reply, warp::http::StatusCode::NO_CONTENT));
 
+    // POST lighthouse/validators/{pubkey}/presigned_exit
+    let post_validators_presigned_exit = warp::path("lighthouse")
+        .and(warp::path("validators"))
+        .and(warp::path::param::<PublicKey>())
+        .and(warp::path("presigned_exit"))
+        .and(warp::path::end())
+        .and(validator_store_filter.clone())
+        .and(block_service_filter.clone())
+        .and(log_filter.clone())
+        .and(task_executor_filter.clone())
+        .then(
+            |pubkey: PublicKey,
+             validator_store: Arc<ValidatorStore<T, E>>,
+             block_service: BlockService<T, E>,
+             log,
+             task_executor: TaskExecutor| {
+                blocking_json_task(move || {
+                    if let Some(handle) = task_executor.handle() {
+                        let signed_voluntary_exit = handle.block_on(publish_presigned_exit(
+                            pubkey,
+                            validator_store,
+                            block_service.beacon_nodes.clone(),
+                            log,
+                        ))?;
+                        Ok(signed_voluntary_exit)
+                    } else {
+                        Err(warp_utils::reject::custom_server_error(
+                            "Lighthouse shutting down".into(),
+                        ))
+                    }
+                })
+            },
+        );
+
     // POST /eth/v1/validator/{pubkey}/voluntary_exit
     let post_validators_voluntary_exits = eth_v1
         .and(warp::path("validator"))
@@ -1103,13 +1164,23 @@ pub fn serve<T: 'static + SlotClock + Clone, E: EthSpec>(
         .and(warp::path("graffiti"))
         .and(warp::path::end())
         .and(validator_store_filter.clone())
+        .and(graffiti_file_filter.clone())
         .and(graffiti_flag_filter)
+        .and(log_filter.clone())
         .then(
             |pubkey: PublicKey,
              validator_store: Arc<ValidatorStore<T, E>>,
-             graffiti_flag: Option<Graffiti>| {
+             graffiti_file: Option<GraffitiFile>,
+             graffiti_flag: Option<Graffiti>,
+             log| {
                 blocking_json_task(move || {
-                    let graffiti = get_graffiti(pubkey.clone(), validator_store, graffiti_flag)?;
+                    let graffiti = get_graffiti(
+                        pubkey.clone(),
+                        validator_store,
+                        graffiti_file,
+                        graffiti_flag,
+                        &log,
+                    )?;
                     Ok(GenericResponse::from(GetGraffitiResponse {
                         pubkey: pubkey.into(),
                         graffiti,
@@ -1301,6 +1372,7 @@ pub fn serve<T: 'static + SlotClock + Clone, E: EthSpec>(
                         .or(get_lighthouse_spec)
                         .or(get_lighthouse_validators)
                         .or(get_lighthouse_validators_pubkey)
+                        .or(get_lighthouse_signers)
                         .or(get_lighthouse_ui_health)
                         .or(get_lighthouse_ui_graffiti)
                         .or(get_lighthouse_ui_fallback_health)
@@ -1317,6 +1389,7 @@ pub fn serve<T: 'static + SlotClock + Clone, E: EthSpec>(
                         .or(post_validators_mnemonic)
                         .or(post_validators_web3signer)
                         .or(post_validators_voluntary_exits)
+                        .or(post_validators_presigned_exit)
                         .or(post_fee_recipient)
                         .or(post_gas_limit)
                         .or(post_std_keystores)
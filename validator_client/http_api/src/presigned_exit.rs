@@ -0,0 +1,114 @@
+use account_utils::validator_definitions::SigningDefinition;
+use beacon_node_fallback::BeaconNodeFallback;
+use bls::PublicKey;
+use eth2::types::GenericResponse;
+use slog::{info, Logger};
+use slot_clock::SlotClock;
+use std::sync::Arc;
+use types::{EthSpec, SignedVoluntaryExit};
+use validator_dir::{presigned_exit_path, EncryptedPresignedExit};
+use validator_store::ValidatorStore;
+
+/// Decrypts the presigned exit stored alongside the voting keystore of `pubkey` and publishes it
+/// to the beacon node.
+///
+/// The exit is decrypted using the voting keystore password from the validator definition, so
+/// validators whose password is not stored by the validator client are not supported.
+pub async fn publish_presigned_exit<T: 'static + SlotClock + Clone, E: EthSpec>(
+    pubkey: PublicKey,
+    validator_store: Arc<ValidatorStore<T, E>>,
+    beacon_nodes: Arc<BeaconNodeFallback<T, E>>,
+    log: Logger,
+) -> Result<GenericResponse<SignedVoluntaryExit>, warp::Rejection> {
+    let (voting_keystore_path, password) = {
+        let initialized_validators_rw_lock = validator_store.initialized_validators();
+        let initialized_validators = initialized_validators_rw_lock.read();
+        let def = initialized_validators
+            .validator_definitions()
+            .iter()
+            .find(|def| def.voting_public_key == pubkey)
+            .ok_or_else(|| {
+                warp_utils::reject::custom_not_found(format!(
+                    "{} is not managed by this validator client",
+                    pubkey.as_hex_string()
+                ))
+            })?;
+
+        let SigningDefinition::LocalKeystore {
+            voting_keystore_path,
+            ..
+        } = &def.signing_definition
+        else {
+            return Err(warp_utils::reject::custom_bad_request(format!(
+                "{} is not a local keystore, presigned exits are not supported",
+                pubkey.as_hex_string()
+            )));
+        };
+
+        let password = def
+            .signing_definition
+            .voting_keystore_password()
+            .map_err(|e| {
+                warp_utils::reject::custom_server_error(format!(
+                    "Unable to read keystore password: {:?}",
+                    e
+                ))
+            })?
+            .ok_or_else(|| {
+                warp_utils::reject::custom_bad_request(format!(
+                    "The keystore password for {} is not stored by this validator client",
+                    pubkey.as_hex_string()
+                ))
+            })?;
+
+        (voting_keystore_path.clone(), password)
+    };
+
+    let path = presigned_exit_path(&voting_keystore_path);
+    if !path.exists() {
+        return Err(warp_utils::reject::custom_not_found(format!(
+            "No presigned exit found for {}",
+            pubkey.as_hex_string()
+        )));
+    }
+    let encrypted_exit = EncryptedPresignedExit::from_json_file(&path).map_err(|e| {
+        warp_utils::reject::custom_server_error(format!("Unable to read presigned exit: {:?}", e))
+    })?;
+    if encrypted_exit.pubkey != pubkey.compress() {
+        return Err(warp_utils::reject::custom_server_error(format!(
+            "Presigned exit at {:?} is for a different validator: {:?}",
+            path, encrypted_exit.pubkey
+        )));
+    }
+    let signed_voluntary_exit = encrypted_exit.decrypt(password.as_ref()).map_err(|e| {
+        warp_utils::reject::custom_server_error(format!(
+            "Unable to decrypt presigned exit: {:?}",
+            e
+        ))
+    })?;
+
+    info!(
+        log,
+        "Publishing presigned voluntary exit";
+        "validator" => pubkey.as_hex_string(),
+        "validator_index" => signed_voluntary_exit.message.validator_index,
+        "epoch" => signed_voluntary_exit.message.epoch,
+    );
+
+    let signed_voluntary_exit_ref = &signed_voluntary_exit;
+    beacon_nodes
+        .first_success(|beacon_node| async move {
+            beacon_node
+                .post_beacon_pool_voluntary_exits(signed_voluntary_exit_ref)
+                .await
+        })
+        .await
+        .map_err(|e| {
+            warp_utils::reject::custom_server_error(format!(
+                "Failed to publish presigned exit: {}",
+                e
+            ))
+        })?;
+
+    Ok(GenericResponse::from(signed_voluntary_exit))
+}
@@ -3,7 +3,7 @@
 
 mod keystores;
 
-use doppelganger_service::DoppelgangerService;
+use doppelganger_service::{DoppelgangerService, DoppelgangerStatus};
 use initialized_validators::{Config as InitializedValidatorsConfig, InitializedValidators};
 
 use crate::{ApiSecret, Config as HttpConfig, Context};
@@ -18,6 +18,7 @@ use eth2::{
     Error as ApiError,
 };
 use eth2_keystore::KeystoreBuilder;
+use graffiti_file::GraffitiFile;
 use logging::test_logger;
 use parking_lot::RwLock;
 use sensitive_url::SensitiveUrl;
@@ -25,7 +26,7 @@ use slashing_protection::{SlashingDatabase, SLASHING_PROTECTION_FILENAME};
 use slot_clock::{SlotClock, TestingSlotClock};
 use std::future::Future;
 use std::marker::PhantomData;
-use std::net::{IpAddr, Ipv4Addr};
+use std::net::{IpAddr, Ipv4Addr, SocketAddr};
 use std::str::FromStr;
 use std::sync::Arc;
 use std::time::Duration;
@@ -33,6 +34,7 @@ use task_executor::test_utils::TestRuntime;
 use tempfile::{tempdir, TempDir};
 use types::graffiti::GraffitiString;
 use validator_store::{Config as ValidatorStoreConfig, ValidatorStore};
+use warp::{Filter, Reply};
 
 const PASSWORD_BYTES: &[u8] = &[42, 50, 37];
 pub const TEST_DEFAULT_FEE_RECIPIENT: Address = Address::repeat_byte(42);
@@ -45,7 +47,7 @@ struct ApiTester {
     validator_store: Arc<ValidatorStore<TestingSlotClock, E>>,
     url: SensitiveUrl,
     slot_clock: TestingSlotClock,
-    _validator_dir: TempDir,
+    validator_dir: TempDir,
     _secrets_dir: TempDir,
     _test_runtime: TestRuntime,
 }
@@ -58,11 +60,32 @@ impl ApiTester {
     }
 
     pub async fn new_with_config(config: ValidatorStoreConfig) -> Self {
+        Self::new_with_config_and_graffiti_file(config, None).await
+    }
+
+    /// Create a tester whose `--graffiti-file` contains only a `default` entry of
+    /// `default_file_graffiti`.
+    pub async fn new_with_graffiti_file(default_file_graffiti: &str) -> Self {
+        let mut config = ValidatorStoreConfig::default();
+        config.fee_recipient = Some(TEST_DEFAULT_FEE_RECIPIENT);
+        Self::new_with_config_and_graffiti_file(config, Some(default_file_graffiti)).await
+    }
+
+    async fn new_with_config_and_graffiti_file(
+        config: ValidatorStoreConfig,
+        default_file_graffiti: Option<&str>,
+    ) -> Self {
         let log = test_logger();
 
         let validator_dir = tempdir().unwrap();
         let secrets_dir = tempdir().unwrap();
 
+        let graffiti_file = default_file_graffiti.map(|graffiti| {
+            let path = validator_dir.path().join("graffiti.txt");
+            std::fs::write(&path, format!("default: {graffiti}\n")).unwrap();
+            GraffitiFile::new(path)
+        });
+
         let validator_defs = ValidatorDefinitions::open_or_create(validator_dir.path()).unwrap();
 
         let initialized_validators = InitializedValidators::from_definitions(
@@ -94,6 +117,7 @@ impl ApiTester {
         let validator_store = Arc::new(ValidatorStore::<_, E>::new(
             initialized_validators,
             slashing_protection,
+            None,
             Hash256::repeat_byte(42),
             spec.clone(),
             Some(Arc::new(DoppelgangerService::new(log.clone()))),
@@ -116,7 +140,7 @@ impl ApiTester {
             validator_dir: Some(validator_dir.path().into()),
             secrets_dir: Some(secrets_dir.path().into()),
             validator_store: Some(validator_store.clone()),
-            graffiti_file: None,
+            graffiti_file,
             graffiti_flag: Some(Graffiti::default()),
             spec: E::default_spec().into(),
             config: HttpConfig {
@@ -153,7 +177,7 @@ impl ApiTester {
             validator_store,
             url,
             slot_clock,
-            _validator_dir: validator_dir,
+            validator_dir,
             _secrets_dir: secrets_dir,
             _test_runtime: test_runtime,
         }
@@ -499,6 +523,120 @@ impl ApiTester {
         self
     }
 
+    pub async fn test_web3signer_health_probing(self) -> Self {
+        // Signers are assumed to be healthy until they have been probed.
+        assert!(self
+            .client
+            .get_lighthouse_signers()
+            .await
+            .unwrap()
+            .data
+            .is_empty());
+        let all_pubkeys: Vec<PublicKeyBytes> = self
+            .validator_store
+            .voting_pubkeys(DoppelgangerStatus::ignored);
+        assert_eq!(all_pubkeys.len(), self.vals_enabled());
+
+        // The signers do not exist, so probing marks them as unhealthy.
+        let signers = self.validator_store.web3_signer_validators();
+        assert!(!signers.is_empty());
+        for (url, validators) in signers {
+            self.validator_store
+                .probe_web3_signer(url.clone(), validators)
+                .await;
+            assert!(!self.validator_store.is_web3_signer_healthy(&url));
+        }
+
+        let signers = self.client.get_lighthouse_signers().await.unwrap().data;
+        assert!(signers
+            .iter()
+            .all(|signer| !signer.healthy && signer.error.is_some()));
+        let disabled = signers
+            .iter()
+            .flat_map(|signer| signer.validators.iter().copied())
+            .collect::<Vec<_>>();
+        let voting_pubkeys: Vec<PublicKeyBytes> = self
+            .validator_store
+            .voting_pubkeys(DoppelgangerStatus::ignored);
+        assert_eq!(voting_pubkeys.len(), all_pubkeys.len() - disabled.len());
+        assert!(voting_pubkeys
+            .iter()
+            .all(|pubkey| !disabled.contains(pubkey)));
+
+        self
+    }
+
+    pub async fn test_web3signer_health_probing_of_single_validator(self) -> Self {
+        let signing_keypair = Keypair::random();
+        let refused_keypair = Keypair::random();
+        let refused = PublicKeyBytes::from(&refused_keypair.pk);
+        let signer_url = serve_fake_web3signer(refused).await;
+
+        let request: Vec<_> = [signing_keypair.pk, refused_keypair.pk]
+            .into_iter()
+            .enumerate()
+            .map(|(i, voting_public_key)| Web3SignerValidatorRequest {
+                enable: true,
+                description: format!("{}", i),
+                graffiti: None,
+                suggested_fee_recipient: None,
+                gas_limit: None,
+                builder_proposals: None,
+                builder_boost_factor: None,
+                prefer_builder_proposals: None,
+                voting_public_key,
+                url: signer_url.clone(),
+                root_certificate_path: None,
+                request_timeout_ms: None,
+                client_identity_path: None,
+                client_identity_password: None,
+            })
+            .collect();
+        self.client
+            .post_lighthouse_validators_web3signer(&request)
+            .await
+            .unwrap();
+        let all_pubkeys: Vec<PublicKeyBytes> = self
+            .validator_store
+            .voting_pubkeys(DoppelgangerStatus::ignored);
+
+        // Each probe requests a signature for one more validator, until every validator has been
+        // probed.
+        let validators = self
+            .validator_store
+            .web3_signer_validators()
+            .remove(&signer_url)
+            .unwrap();
+        assert_eq!(validators.len(), 2);
+        for _ in 0..validators.len() {
+            self.validator_store
+                .probe_web3_signer(signer_url.clone(), validators.clone())
+                .await;
+        }
+
+        // The signer is up, so only the validator it refused to sign for is disabled.
+        let signers = self.client.get_lighthouse_signers().await.unwrap().data;
+        assert_eq!(signers.len(), 1);
+        assert!(signers[0].healthy);
+        assert!(signers[0].error.is_some());
+        assert_eq!(signers[0].unavailable_validators, vec![refused]);
+        assert!(!self.validator_store.is_web3_signer_healthy(&signer_url));
+        let voting_pubkeys: Vec<PublicKeyBytes> = self
+            .validator_store
+            .voting_pubkeys(DoppelgangerStatus::ignored);
+        assert_eq!(voting_pubkeys.len(), all_pubkeys.len() - 1);
+        assert!(!voting_pubkeys.contains(&refused));
+
+        // The refused validator is probed again, and stays disabled.
+        self.validator_store
+            .probe_web3_signer(signer_url.clone(), validators)
+            .await;
+        let signers = self.client.get_lighthouse_signers().await.unwrap().data;
+        assert_eq!(signers[0].unavailable_validators, vec![refused]);
+
+        self
+    }
+
     pub async fn test_sign_voluntary_exits(self, index: usize, maybe_epoch: Option<Epoch>) -> Self {
         let validator = &self.client.get_lighthouse_validators().await.unwrap().data[index];
         // manually setting validator index in `ValidatorStore`
@@ -780,6 +918,20 @@ impl ApiTester {
         self
     }
 
+    pub async fn assert_graffiti_persisted(self, index: usize, graffiti: &str) -> Self {
+        let validator = &self.client.get_lighthouse_validators().await.unwrap().data[index];
+        let graffiti_str = GraffitiString::from_str(graffiti).unwrap();
+        let definitions = ValidatorDefinitions::open(self.validator_dir.path()).unwrap();
+        let definition = definitions
+            .as_slice()
+            .iter()
+            .find(|def| def.voting_public_key.compress() == validator.voting_pubkey)
+            .unwrap();
+        assert_eq!(definition.graffiti, Some(graffiti_str));
+
+        self
+    }
+
     pub async fn test_delete_graffiti(self, index: usize) -> Self {
         let validator = &self.client.get_lighthouse_validators().await.unwrap().data[index];
         let resp = self.client.get_graffiti(&validator.voting_pubkey).await;
@@ -828,6 +980,28 @@ struct Web3SignerValidatorScenario {
     enabled: bool,
 }
 
+/// Serves a Web3Signer which passes its `upcheck` and signs for every validator except `refused`,
+/// returning its URL.
+async fn serve_fake_web3signer(refused: PublicKeyBytes) -> String {
+    let upcheck = warp::path("upcheck")
+        .and(warp::path::end())
+        .map(|| "OK".into_response());
+    let sign = warp::path!("api" / "v1" / "eth2" / "sign" / String)
+        .and(warp::post())
+        .map(move |pubkey: String| {
+            if pubkey == refused.as_hex_string() {
+                warp::http::StatusCode::INTERNAL_SERVER_ERROR.into_response()
+            } else {
+                format!(r#"{{"signature":"{}"}}"#, Signature::empty()).into_response()
+            }
+        });
+    let (listening_socket, server) =
+        warp::serve(upcheck.or(sign)).bind_ephemeral(SocketAddr::from((Ipv4Addr::LOCALHOST, 0)));
+    tokio::spawn(server);
+
+    format!("http://{}/", listening_socket)
+}
+
 #[tokio::test]
 async fn invalid_pubkey() {
     ApiTester::new()
@@ -851,6 +1025,8 @@ async fn routes_with_invalid_auth() {
         .await
         .test_with_invalid_auth(|client| async move { client.get_lighthouse_validators().await })
         .await
+        .test_with_invalid_auth(|client| async move { client.get_lighthouse_signers().await })
+        .await
         .test_with_invalid_auth(|client| async move {
             client
                 .get_lighthouse_validators_pubkey(&PublicKeyBytes::empty())
@@ -1310,10 +1486,36 @@ async fn validator_graffiti_api() {
         .await
         .test_get_graffiti(0, "Uncle Bill was here")
         .await
+        .assert_graffiti(0, "Uncle Bill was here")
+        .await
+        .assert_graffiti_persisted(0, "Uncle Bill was here")
+        .await
         .test_delete_graffiti(0)
         .await;
 }
 
+#[tokio::test]
+async fn validator_graffiti_api_with_graffiti_file() {
+    // The graffiti file takes precedence over graffiti set via the API, as it does when proposing.
+    ApiTester::new_with_graffiti_file("From the graffiti file")
+        .await
+        .create_hd_validators(HdValidatorScenario {
+            count: 1,
+            specify_mnemonic: false,
+            key_derivation_path_offset: 0,
+            disabled: vec![],
+        })
+        .await
+        .test_get_graffiti(0, "From the graffiti file")
+        .await
+        .test_set_graffiti(0, "Uncle Bill was here")
+        .await
+        .assert_graffiti_persisted(0, "Uncle Bill was here")
+        .await
+        .test_get_graffiti(0, "From the graffiti file")
+        .await;
+}
+
 #[tokio::test]
 async fn keystore_validator_creation() {
     ApiTester::new()
@@ -1343,6 +1545,27 @@ async fn keystore_validator_creation() {
         .assert_validators_count(2);
 }
 
+#[tokio::test]
+async fn web3signer_health_probing() {
+    ApiTester::new()
+        .await
+        .create_web3signer_validators(Web3SignerValidatorScenario {
+            count: 2,
+            enabled: true,
+        })
+        .await
+        .test_web3signer_health_probing()
+        .await;
+}
+
+#[tokio::test]
+async fn web3signer_health_probing_of_single_validator() {
+    ApiTester::new()
+        .await
+        .test_web3signer_health_probing_of_single_validator()
+        .await;
+}
+
 #[tokio::test]
 async fn web3signer_validator_creation() {
     ApiTester::new()
@@ -10,12 +10,14 @@ use slog::{crit, info, Logger};
 use slot_clock::{SlotClock, SystemTimeSlotClock};
 use std::future::Future;
 use std::net::{IpAddr, Ipv4Addr, SocketAddr};
+use std::path::PathBuf;
 use std::sync::Arc;
 use std::time::{SystemTime, UNIX_EPOCH};
 use types::EthSpec;
 use validator_services::duties_service::DutiesService;
 use validator_store::ValidatorStore;
 use warp::{http::Response, Filter};
+use warp_utils::metrics_access::{self, MetricFilter};
 
 #[derive(Debug)]
 pub enum Error {
@@ -59,6 +61,12 @@ pub struct Config {
     pub listen_port: u16,
     pub allow_origin: Option<String>,
     pub allocator_metrics_enabled: bool,
+    /// Path to a file containing a bearer token which must be supplied to access metrics.
+    pub auth_token_path: Option<PathBuf>,
+    /// If set, only metrics with names matching one of these patterns are served.
+    pub metric_allowlist: Option<Vec<String>>,
+    /// Metrics with names matching one of these patterns are never served.
+    pub metric_denylist: Vec<String>,
 }
 
 impl Default for Config {
@@ -69,6 +77,9 @@ impl Default for Config {
             listen_port: 5064,
             allow_origin: None,
             allocator_metrics_enabled: true,
+            auth_token_path: None,
+            metric_allowlist: None,
+            metric_denylist: vec![],
         }
     }
 }
@@ -99,7 +110,7 @@ pub fn serve<E: EthSpec>(
     let cors_builder = {
         let builder = warp::cors()
             .allow_method("GET")
-            .allow_headers(vec!["Content-Type"]);
+            .allow_headers(vec!["Content-Type", "Authorization"]);
 
         warp_utils::cors::set_builder_origins(
             builder,
@@ -116,28 +127,47 @@ pub fn serve<E: EthSpec>(
         ));
     }
 
+    let auth_token = config
+        .auth_token_path
+        .as_deref()
+        .map(metrics_access::load_auth_token)
+        .transpose()?;
+    let metric_filter = MetricFilter::new(
+        config.metric_allowlist.clone(),
+        config.metric_denylist.clone(),
+    );
+
     let inner_ctx = ctx.clone();
     let routes = warp::get()
         .and(warp::path("metrics"))
-        .map(move || inner_ctx.clone())
-        .and_then(|ctx: Arc<Context<E>>| async move {
-            Ok::<_, warp::Rejection>(
-                gather_prometheus_metrics(&ctx)
-                    .map(|body| {
-                        Response::builder()
-                            .status(200)
-                            .header("Content-Type", "text/plain")
-                            .body(body)
-                            .unwrap()
-                    })
-                    .unwrap_or_else(|e| {
-                        Response::builder()
-                            .status(500)
-                            .header("Content-Type", "text/plain")
-                            .body(format!("Unable to gather metrics: {:?}", e))
-                            .unwrap()
-                    }),
-            )
+        .and(warp::header::optional::<String>("Authorization"))
+        .and_then(move |authorization: Option<String>| {
+            let ctx = inner_ctx.clone();
+            let auth_token = auth_token.clone();
+            let metric_filter = metric_filter.clone();
+            async move {
+                if !metrics_access::is_authorized(auth_token.as_deref(), authorization.as_deref()) {
+                    return Ok::<_, warp::Rejection>(metrics_access::unauthorized_response());
+                }
+
+                Ok::<_, warp::Rejection>(
+                    gather_prometheus_metrics(&ctx)
+                        .map(|body| {
+                            Response::builder()
+                                .status(200)
+                                .header("Content-Type", "text/plain")
+                                .body(metric_filter.filter_text(&body))
+                                .unwrap()
+                        })
+                        .unwrap_or_else(|e| {
+                            Response::builder()
+                                .status(500)
+                                .header("Content-Type", "text/plain")
+                                .body(format!("Unable to gather metrics: {:?}", e))
+                                .unwrap()
+                        }),
+                )
+            }
         })
         // Add a `Server` header.
         .map(|reply| warp::reply::with_header(reply, "Server", &version_with_platform()))
@@ -239,6 +239,10 @@ impl KeyCache {
     pub fn get(&self, uuid: &Uuid) -> Option<Keypair> {
         self.pairs.get(uuid).cloned()
     }
+
+    pub fn contains(&self, uuid: &Uuid) -> bool {
+        self.pairs.contains_key(uuid)
+    }
 }
 
 #[derive(Debug)]
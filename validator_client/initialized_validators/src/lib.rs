@@ -20,7 +20,7 @@ use eth2_keystore::Keystore;
 use lockfile::{Lockfile, LockfileError};
 use metrics::set_gauge;
 use parking_lot::{MappedMutexGuard, Mutex, MutexGuard};
-use reqwest::{Certificate, Client, Error as ReqwestError, Identity};
+use reqwest::{Certificate, Client, ClientBuilder, Error as ReqwestError, Identity};
 use serde::{Deserialize, Serialize};
 use signing_method::SigningMethod;
 use slog::{debug, error, info, warn, Logger};
@@ -29,7 +29,8 @@ use std::fs::{self, File};
 use std::io::{self, Read};
 use std::path::{Path, PathBuf};
 use std::sync::Arc;
-use std::time::Duration;
+use std::time::{Duration, Instant};
+use tokio::task::JoinSet;
 use types::graffiti::GraffitiString;
 use types::{Address, Graffiti, Keypair, PublicKey, PublicKeyBytes};
 use url::{ParseError, Url};
@@ -48,11 +49,22 @@ const USE_STDIN: bool = false;
 
 pub const DEFAULT_WEB3SIGNER_KEEP_ALIVE: Option<Duration> = Some(Duration::from_secs(20));
 
+/// The minimum interval between progress logs whilst decrypting keystores.
+const KEYSTORE_DECRYPTION_PROGRESS_INTERVAL: Duration = Duration::from_secs(10);
+
+/// The default number of keystores to decrypt concurrently: one per available CPU.
+pub fn default_keystore_decryption_workers() -> usize {
+    std::thread::available_parallelism().map_or(1, Into::into)
+}
+
 // The configuration for initialised validators.
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct Config {
     pub web3_signer_keep_alive_timeout: Option<Duration>,
     pub web3_signer_max_idle_connections: Option<usize>,
+    /// The maximum number of keystores to decrypt concurrently at startup.
+    #[serde(default = "default_keystore_decryption_workers")]
+    pub keystore_decryption_workers: usize,
 }
 
 impl Default for Config {
@@ -60,6 +72,7 @@ impl Default for Config {
         Config {
             web3_signer_keep_alive_timeout: DEFAULT_WEB3SIGNER_KEEP_ALIVE,
             web3_signer_max_idle_connections: None,
+            keystore_decryption_workers: default_keystore_decryption_workers(),
         }
     }
 }
@@ -120,14 +133,14 @@ pub enum Error {
     SlotClock,
     /// The URL for the remote signer cannot be parsed.
     InvalidWeb3SignerUrl(String),
-    /// Unable to read the root certificate file for the remote signer.
-    InvalidWeb3SignerRootCertificateFile(io::Error),
-    InvalidWeb3SignerRootCertificate(ReqwestError),
-    /// Unable to read the client certificate for the remote signer.
-    MissingWeb3SignerClientIdentityCertificateFile,
-    MissingWeb3SignerClientIdentityPassword,
-    InvalidWeb3SignerClientIdentityCertificateFile(io::Error),
-    InvalidWeb3SignerClientIdentityCertificate(ReqwestError),
+    /// Unable to read the root certificate file for the remote signer or beacon node.
+    InvalidRootCertificateFile(io::Error),
+    InvalidRootCertificate(ReqwestError),
+    /// Unable to read the client certificate for the remote signer or beacon node.
+    MissingClientIdentityCertificateFile,
+    MissingClientIdentityPassword,
+    InvalidClientIdentityCertificateFile(io::Error),
+    InvalidClientIdentityCertificate(ReqwestError),
     UnableToBuildWeb3SignerClient(ReqwestError),
     /// Unable to apply an action to a validator.
     InvalidActionOnValidator,
@@ -316,6 +329,8 @@ impl InitializedValidator {
             SigningDefinition::Web3Signer(web3_signer) => {
                 let signing_url = build_web3_signer_url(&web3_signer.url, &def.voting_public_key)
                     .map_err(|e| Error::InvalidWeb3SignerUrl(e.to_string()))?;
+                let upcheck_url = build_web3_signer_upcheck_url(&web3_signer.url)
+                    .map_err(|e| Error::InvalidWeb3SignerUrl(e.to_string()))?;
 
                 let request_timeout = web3_signer
                     .request_timeout_ms
@@ -358,6 +373,7 @@ impl InitializedValidator {
 
                 SigningMethod::Web3Signer {
                     signing_url,
+                    upcheck_url,
                     http_client,
                     voting_public_key: def.voting_public_key,
                 }
@@ -390,10 +406,10 @@ impl InitializedValidator {
 pub fn load_pem_certificate<P: AsRef<Path>>(pem_path: P) -> Result<Certificate, Error> {
     let mut buf = Vec::new();
     File::open(&pem_path)
-        .map_err(Error::InvalidWeb3SignerRootCertificateFile)?
+        .map_err(Error::InvalidRootCertificateFile)?
         .read_to_end(&mut buf)
-        .map_err(Error::InvalidWeb3SignerRootCertificateFile)?;
-    Certificate::from_pem(&buf).map_err(Error::InvalidWeb3SignerRootCertificate)
+        .map_err(Error::InvalidRootCertificateFile)?;
+    Certificate::from_pem(&buf).map_err(Error::InvalidRootCertificate)
 }
 
 pub fn load_pkcs12_identity<P: AsRef<Path>>(
@@ -402,17 +418,20 @@ pub fn load_pkcs12_identity<P: AsRef<Path>>(
 ) -> Result<Identity, Error> {
     let mut buf = Vec::new();
     File::open(&pkcs12_path)
-        .map_err(Error::InvalidWeb3SignerClientIdentityCertificateFile)?
+        .map_err(Error::InvalidClientIdentityCertificateFile)?
         .read_to_end(&mut buf)
-        .map_err(Error::InvalidWeb3SignerClientIdentityCertificateFile)?;
-    Identity::from_pkcs12_der(&buf, password)
-        .map_err(Error::InvalidWeb3SignerClientIdentityCertificate)
+        .map_err(Error::InvalidClientIdentityCertificateFile)?;
+    Identity::from_pkcs12_der(&buf, password).map_err(Error::InvalidClientIdentityCertificate)
 }
 
 fn build_web3_signer_url(base_url: &str, voting_public_key: &PublicKey) -> Result<Url, ParseError> {
     Url::parse(base_url)?.join(&format!("api/v1/eth2/sign/{}", voting_public_key))
 }
 
+fn build_web3_signer_upcheck_url(base_url: &str) -> Result<Url, ParseError> {
+    Url::parse(base_url)?.join("upcheck")
+}
+
 fn build_web3_signer_client(
     root_certificate_path: Option<PathBuf>,
     client_identity_path: Option<PathBuf>,
@@ -426,29 +445,40 @@ fn build_web3_signer_client(
         .pool_idle_timeout(keep_alive_timeout)
         .pool_max_idle_per_host(max_idle_connections.unwrap_or(usize::MAX));
 
-    let builder = if let Some(path) = root_certificate_path {
-        let certificate = load_pem_certificate(path)?;
-        builder.add_root_certificate(certificate)
-    } else {
-        builder
-    };
+    add_tls_config(
+        builder,
+        root_certificate_path.as_deref(),
+        client_identity_path.as_deref(),
+        client_identity_password.as_deref(),
+    )?
+    .build()
+    .map_err(Error::UnableToBuildWeb3SignerClient)
+}
 
-    let builder = if let Some(path) = client_identity_path {
-        let identity = load_pkcs12_identity(
-            path,
-            &client_identity_password.ok_or(Error::MissingWeb3SignerClientIdentityPassword)?,
-        )?;
-        builder.identity(identity)
-    } else {
-        if client_identity_password.is_some() {
-            return Err(Error::MissingWeb3SignerClientIdentityCertificateFile);
+/// Configure `builder` to trust the CA certificate in `root_certificate_path`, in addition to the
+/// OS trust store, and to authenticate using the PKCS12 identity in `client_identity_path`.
+///
+/// The same settings are accepted for connections to Web3Signer and to beacon nodes.
+pub fn add_tls_config(
+    mut builder: ClientBuilder,
+    root_certificate_path: Option<&Path>,
+    client_identity_path: Option<&Path>,
+    client_identity_password: Option<&str>,
+) -> Result<ClientBuilder, Error> {
+    if let Some(path) = root_certificate_path {
+        builder = builder.add_root_certificate(load_pem_certificate(path)?);
+    }
+
+    match (client_identity_path, client_identity_password) {
+        (Some(path), Some(password)) => {
+            builder = builder.identity(load_pkcs12_identity(path, password)?);
         }
-        builder
-    };
+        (Some(_), None) => return Err(Error::MissingClientIdentityPassword),
+        (None, Some(_)) => return Err(Error::MissingClientIdentityCertificateFile),
+        (None, None) => {}
+    }
 
-    builder
-        .build()
-        .map_err(Error::UnableToBuildWeb3SignerClient)
+    Ok(builder)
 }
 
 /// Try to unlock `keystore` at `keystore_path` by prompting the user via `stdin`.
@@ -747,6 +777,23 @@ impl InitializedValidators {
         self.definitions.as_slice()
     }
 
+    /// Returns the enabled validators of each Web3Signer instance, keyed by the instance's URL.
+    pub fn web3_signer_validators(&self) -> HashMap<String, Vec<PublicKeyBytes>> {
+        let mut signers = HashMap::<_, Vec<_>>::new();
+        for def in self.definitions.as_slice() {
+            if let SigningDefinition::Web3Signer(web3_signer) = &def.signing_definition {
+                let pubkey = PublicKeyBytes::from(&def.voting_public_key);
+                if self.validators.contains_key(&pubkey) {
+                    signers
+                        .entry(web3_signer.url.clone())
+                        .or_default()
+                        .push(pubkey);
+                }
+            }
+        }
+        signers
+    }
+
     /// Indicates if the `voting_public_key` exists in self and if it is enabled.
     pub fn is_enabled(&self, voting_public_key: &PublicKey) -> Option<bool> {
         self.definitions
@@ -1202,6 +1249,133 @@ impl InitializedValidators {
         .map_err(Error::UnableToDecryptKeyCache)
     }
 
+    /// Decrypts the keystores of enabled local validators which are not yet initialized and are
+    /// missing from `key_cache`, using up to `Config::keystore_decryption_workers` threads.
+    ///
+    /// Keystores without a password in the definition or a password file are skipped. These are
+    /// handled by `InitializedValidator::from_definition`, which may prompt for a password.
+    ///
+    /// A keystore which fails to decrypt is reported in the same way as any other validator which
+    /// fails to initialize, and the error is returned.
+    async fn warm_key_cache(
+        &self,
+        key_cache: &mut KeyCache,
+        key_stores: &mut HashMap<PathBuf, Keystore>,
+    ) -> Result<(), Error> {
+        let mut queued_uuids = HashSet::new();
+        let mut pending = vec![];
+        for def in self.definitions.as_slice().iter().filter(|def| def.enabled) {
+            if self
+                .validators
+                .contains_key(&def.voting_public_key.compress())
+            {
+                continue;
+            }
+
+            if let SigningDefinition::LocalKeystore {
+                voting_keystore_path,
+                voting_keystore_password_path,
+                voting_keystore_password,
+            } = &def.signing_definition
+            {
+                use std::collections::hash_map::Entry::*;
+                let keystore = match key_stores.entry(voting_keystore_path.clone()) {
+                    Vacant(entry) => entry.insert(open_keystore(voting_keystore_path)?),
+                    Occupied(entry) => entry.into_mut(),
+                };
+
+                if key_cache.contains(keystore.uuid()) || !queued_uuids.insert(*keystore.uuid()) {
+                    continue;
+                }
+
+                let password = match (voting_keystore_password_path, voting_keystore_password) {
+                    (_, Some(password)) => password.as_ref().to_vec().into(),
+                    (Some(path), None) => {
+                        read_password(path).map_err(Error::UnableToReadVotingKeystorePassword)?
+                    }
+                    (None, None) => continue,
+                };
+
+                pending.push((keystore.clone(), password, def.voting_public_key.clone()));
+            }
+        }
+
+        if pending.is_empty() {
+            return Ok(());
+        }
+
+        let total = pending.len();
+        let workers = self.config.keystore_decryption_workers.max(1);
+        info!(
+            self.log,
+            "Decrypting validator keystores";
+            "count" => total,
+            "workers" => workers,
+        );
+
+        let mut pending = pending.into_iter();
+        let mut tasks = JoinSet::new();
+        let mut decrypted = 0;
+        let mut last_progress = Instant::now();
+        loop {
+            while tasks.len() < workers {
+                let Some((keystore, password, voting_public_key)) = pending.next() else {
+                    break;
+                };
+                // Decrypting a keystore can take several seconds, so keep it off the core
+                // executor.
+                tasks.spawn_blocking(move || {
+                    let keypair = keystore.decrypt_keypair(password.as_ref());
+                    (*keystore.uuid(), password, voting_public_key, keypair)
+                });
+            }
+
+            let Some(result) = tasks.join_next().await else {
+                break;
+            };
+            let (uuid, password, voting_public_key, keypair) = result.map_err(Error::TokioJoin)?;
+            let keypair =
+                keypair.map_err(|e| self.keystore_decryption_failed(&voting_public_key, e))?;
+            key_cache.add(keypair, &uuid, password);
+            decrypted += 1;
+
+            if last_progress.elapsed() >= KEYSTORE_DECRYPTION_PROGRESS_INTERVAL {
+                info!(
+                    self.log,
+                    "Decrypting validator keystores";
+                    "decrypted" => decrypted,
+                    "total" => total,
+                );
+                last_progress = Instant::now();
+            }
+        }
+
+        info!(
+            self.log,
+            "Decrypted validator keystores";
+            "count" => decrypted,
+        );
+        Ok(())
+    }
+
+    /// Log a keystore which failed to decrypt in the same way as `update_validators` logs any other
+    /// validator which fails to initialize, returning the error.
+    fn keystore_decryption_failed(
+        &self,
+        voting_public_key: &PublicKey,
+        e: eth2_keystore::Error,
+    ) -> Error {
+        let e = Error::UnableToDecryptKeystore(e);
+        error!(
+            self.log,
+            "Failed to initialize validator";
+            "error" => format!("{:?}", e),
+            "signing_method" => "local_keystore",
+            "validator" => format!("{:?}", voting_public_key)
+        );
+        e
+    }
+
     /// Scans `self.definitions` and attempts to initialize and validators which are not already
     /// initialized.
     ///
@@ -1244,6 +1418,10 @@ impl InitializedValidators {
             KeyCache::new()
         };
 
+        if has_local_definitions {
+            self.warm_key_cache(&mut key_cache, &mut key_stores).await?;
+        }
+
         let mut disabled_uuids = HashSet::new();
         for def in self.definitions.as_slice() {
             if def.enabled {
@@ -1456,3 +1634,115 @@ impl InitializedValidators {
         self.definitions.as_mut_slice()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use account_utils::validator_definitions::PasswordStorage;
+    use eth2_keystore::KeystoreBuilder;
+    use tempfile::tempdir;
+
+    /// Write a keystore for a random keypair to `dir`, returning a definition for it which uses
+    /// `definition_password` to decrypt it.
+    fn keystore_definition(
+        dir: &Path,
+        index: usize,
+        password: &str,
+        definition_password: &str,
+    ) -> ValidatorDefinition {
+        let keypair = Keypair::random();
+        let keystore = KeystoreBuilder::new(&keypair, password.as_bytes(), String::new())
+            .unwrap()
+            .build()
+            .unwrap();
+        let path = dir.join(format!("keystore_{index}.json"));
+        keystore
+            .to_json_writer(File::create(&path).unwrap())
+            .unwrap();
+
+        ValidatorDefinition::new_keystore_with_password(
+            path,
+            PasswordStorage::ValidatorDefinitions(definition_password.to_string().into()),
+            None,
+            None,
+            None,
+            None,
+            None,
+            None,
+        )
+        .unwrap()
+    }
+
+    fn config(workers: usize) -> Config {
+        Config {
+            keystore_decryption_workers: workers,
+            ..Config::default()
+        }
+    }
+
+    fn log() -> Logger {
+        Logger::root(slog::Discard, slog::o!())
+    }
+
+    #[tokio::test]
+    async fn decrypt_keystores_in_parallel() {
+        let dir = tempdir().unwrap();
+        let definitions = (0..4)
+            .map(|i| keystore_definition(dir.path(), i, "password", "password"))
+            .collect::<Vec<_>>();
+        let pubkeys = definitions
+            .iter()
+            .map(|def| def.voting_public_key.compress())
+            .collect::<Vec<_>>();
+
+        let validators = InitializedValidators::from_definitions(
+            definitions.into(),
+            dir.path().to_path_buf(),
+            config(4),
+            log(),
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(validators.num_enabled(), pubkeys.len());
+        for pubkey in &pubkeys {
+            assert!(validators.signing_method(pubkey).is_some());
+        }
+    }
+
+    #[tokio::test]
+    async fn decrypt_keystores_in_parallel_wrong_password() {
+        let dir = tempdir().unwrap();
+        let mut definitions = (0..3)
+            .map(|i| keystore_definition(dir.path(), i, "password", "password"))
+            .collect::<Vec<_>>();
+        definitions.push(keystore_definition(dir.path(), 3, "password", "wrong"));
+
+        let result = InitializedValidators::from_definitions(
+            definitions.into(),
+            dir.path().to_path_buf(),
+            config(4),
+            log(),
+        )
+        .await;
+
+        assert!(matches!(
+            result,
+            Err(Error::UnableToDecryptKeystore(
+                eth2_keystore::Error::InvalidPassword
+            ))
+        ));
+    }
+
+    #[test]
+    fn keystore_decryption_workers_defaults_when_absent() {
+        let config: Config = serde_json::from_str(
+            r#"{"web3_signer_keep_alive_timeout": null, "web3_signer_max_idle_connections": null}"#,
+        )
+        .unwrap();
+        assert_eq!(
+            config.keystore_decryption_workers,
+            default_keystore_decryption_workers()
+        );
+    }
+}
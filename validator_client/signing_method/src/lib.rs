@@ -25,6 +25,11 @@ pub enum Error {
         domain: Domain,
     },
     Web3SignerRequestFailed(String),
+    Web3SignerTimeout(String),
+    Web3SignerHttpStatus {
+        status: u16,
+        error: String,
+    },
     Web3SignerJsonParsingFailed(String),
     ShuttingDown,
     TokioJoin(String),
@@ -32,6 +37,40 @@ pub enum Error {
     GenesisForkVersionRequired,
 }
 
+impl Error {
+    /// Classify a failed request to a Web3Signer instance.
+    fn web3signer_request(e: reqwest::Error) -> Self {
+        if e.is_timeout() {
+            Error::Web3SignerTimeout(e.to_string())
+        } else if let Some(status) = e.status() {
+            Error::Web3SignerHttpStatus {
+                status: status.as_u16(),
+                error: e.to_string(),
+            }
+        } else {
+            Error::Web3SignerRequestFailed(e.to_string())
+        }
+    }
+
+    /// Returns the `reason` label of the `vc_duty_failures_total` metric for this error.
+    pub fn failure_reason(&self) -> &'static str {
+        match self {
+            Error::Web3SignerTimeout(_) => validator_metrics::FAILURE_TIMEOUT,
+            Error::Web3SignerHttpStatus { status, .. } if (400..500).contains(status) => {
+                validator_metrics::FAILURE_HTTP_4XX
+            }
+            Error::Web3SignerHttpStatus { .. } => validator_metrics::FAILURE_HTTP_5XX,
+            Error::InconsistentDomains { .. }
+            | Error::Web3SignerRequestFailed(_)
+            | Error::Web3SignerJsonParsingFailed(_)
+            | Error::ShuttingDown
+            | Error::TokioJoin(_)
+            | Error::MergeForkNotSupported
+            | Error::GenesisForkVersionRequired => validator_metrics::FAILURE_OTHER,
+        }
+    }
+}
+
 /// Enumerates all messages that can be signed by a validator.
 pub enum SignableMessage<'a, E: EthSpec, Payload: AbstractExecPayload<E> = FullPayload<E>> {
     RandaoReveal(Epoch),
@@ -89,6 +128,8 @@ pub enum SigningMethod {
     /// See: https://docs.web3signer.consensys.net/en/latest/
     Web3Signer {
         signing_url: Url,
+        /// The `upcheck` endpoint of the Web3Signer instance, used to probe its health.
+        upcheck_url: Url,
         http_client: Client,
         voting_public_key: PublicKey,
     },
@@ -130,6 +171,42 @@ impl SigningMethod {
         }
     }
 
+    /// Returns the `signer` label of metrics concerning this signing method.
+    ///
+    /// Web3Signer instances are identified by the origin of their URL so that the label neither
+    /// varies per validator nor exposes any credentials in the URL.
+    pub fn signer_label(&self) -> String {
+        match self {
+            SigningMethod::LocalKeystore { .. } => validator_metrics::LOCAL_KEYSTORE.to_string(),
+            SigningMethod::Web3Signer { signing_url, .. } => {
+                signing_url.origin().ascii_serialization()
+            }
+        }
+    }
+
+    /// Check that the Web3Signer instance backing this signing method is up.
+    ///
+    /// Always succeeds for local keystores.
+    pub async fn upcheck(&self) -> Result<(), Error> {
+        match self {
+            SigningMethod::LocalKeystore { .. } => Ok(()),
+            SigningMethod::Web3Signer {
+                upcheck_url,
+                http_client,
+                ..
+            } => {
+                http_client
+                    .get(upcheck_url.clone())
+                    .send()
+                    .await
+                    .map_err(Error::web3signer_request)?
+                    .error_for_status()
+                    .map_err(Error::web3signer_request)?;
+                Ok(())
+            }
+        }
+    }
+
     /// Return the signature of `signable_message`, with respect to the `signing_context`.
     pub async fn get_signature<E: EthSpec, Payload: AbstractExecPayload<E>>(
         &self,
@@ -250,9 +327,9 @@ impl SigningMethod {
                     .json(&request)
                     .send()
                     .await
-                    .map_err(|e| Error::Web3SignerRequestFailed(e.to_string()))?
+                    .map_err(Error::web3signer_request)?
                     .error_for_status()
-                    .map_err(|e| Error::Web3SignerRequestFailed(e.to_string()))?
+                    .map_err(Error::web3signer_request)?
                     .json()
                     .await
                     .map_err(|e| Error::Web3SignerJsonParsingFailed(e.to_string()))?;
@@ -0,0 +1,420 @@
+//! An in-memory implementation of the slashing protection rules, which can be compared against
+//! the `SlashingDatabase` to validate it (e.g., after restoring the database from a backup).
+use crate::interchange::Interchange;
+use crate::signed_attestation::{InvalidAttestation, SignedAttestation};
+use crate::signed_block::{InvalidBlock, SignedBlock};
+use crate::{InterchangeError, NotSafe, Safe, SigningRoot, SUPPORTED_INTERCHANGE_FORMAT_VERSION};
+use parking_lot::Mutex;
+use std::collections::{BTreeMap, HashMap};
+use types::{AttestationData, BeaconBlockHeader, Epoch, Hash256, PublicKeyBytes, SignedRoot, Slot};
+
+/// The blocks and attestations signed by a single validator.
+#[derive(Debug, Default)]
+struct ValidatorHistory {
+    blocks: BTreeMap<Slot, SigningRoot>,
+    /// Attestations keyed by target epoch.
+    attestations: BTreeMap<Epoch, SignedAttestation>,
+}
+
+impl ValidatorHistory {
+    /// Reconstructs the history that `SlashingDatabase::import_interchange_record` would produce,
+    /// i.e. a single block and attestation with null signing roots at the maximum slot and epochs.
+    fn from_interchange(
+        blocks: &[crate::interchange::SignedBlock],
+        attestations: &[crate::interchange::SignedAttestation],
+    ) -> Self {
+        let mut history = Self::default();
+        if let Some(max_slot) = blocks.iter().map(|block| block.slot).max() {
+            history.blocks.insert(max_slot, SigningRoot::default());
+        }
+        let max_source = attestations.iter().map(|att| att.source_epoch).max();
+        let max_target = attestations.iter().map(|att| att.target_epoch).max();
+        if let (Some(source_epoch), Some(target_epoch)) = (max_source, max_target) {
+            history.attestations.insert(
+                target_epoch,
+                SignedAttestation::new(source_epoch, target_epoch, SigningRoot::default()),
+            );
+        }
+        history
+    }
+
+    fn check_block(&self, slot: Slot, signing_root: SigningRoot) -> Result<Safe, NotSafe> {
+        if let Some(existing_root) = self.blocks.get(&slot) {
+            return if *existing_root == signing_root {
+                Ok(Safe::SameData)
+            } else {
+                Err(NotSafe::InvalidBlock(InvalidBlock::DoubleBlockProposal(
+                    SignedBlock::new(slot, *existing_root),
+                )))
+            };
+        }
+
+        if let Some(&min_slot) = self.blocks.keys().next() {
+            if slot <= min_slot {
+                return Err(NotSafe::InvalidBlock(
+                    InvalidBlock::SlotViolatesLowerBound {
+                        block_slot: slot,
+                        bound_slot: min_slot,
+                    },
+                ));
+            }
+        }
+
+        Ok(Safe::Valid)
+    }
+
+    fn check_attestation(
+        &self,
+        source_epoch: Epoch,
+        target_epoch: Epoch,
+        signing_root: SigningRoot,
+    ) -> Result<Safe, NotSafe> {
+        if source_epoch > target_epoch {
+            return Err(NotSafe::InvalidAttestation(
+                InvalidAttestation::SourceExceedsTarget,
+            ));
+        }
+
+        if let Some(existing) = self.attestations.get(&target_epoch) {
+            return if existing.signing_root == signing_root {
+                Ok(Safe::SameData)
+            } else {
+                Err(NotSafe::InvalidAttestation(InvalidAttestation::DoubleVote(
+                    existing.clone(),
+                )))
+            };
+        }
+
+        // As in the database, only the surrounding or surrounded attestation with the greatest
+        // target epoch is reported.
+        if let Some(prev) = self
+            .attestations
+            .range(target_epoch + 1..)
+            .rev()
+            .map(|(_, att)| att)
+            .find(|att| att.source_epoch < source_epoch)
+        {
+            return Err(NotSafe::InvalidAttestation(
+                InvalidAttestation::PrevSurroundsNew { prev: prev.clone() },
+            ));
+        }
+
+        if let Some(prev) = self
+            .attestations
+            .range(..target_epoch)
+            .rev()
+            .map(|(_, att)| att)
+            .find(|att| att.source_epoch > source_epoch)
+        {
+            return Err(NotSafe::InvalidAttestation(
+                InvalidAttestation::NewSurroundsPrev { prev: prev.clone() },
+            ));
+        }
+
+        if let Some(min_source) = self.attestations.values().map(|att| att.source_epoch).min() {
+            if source_epoch < min_source {
+                return Err(NotSafe::InvalidAttestation(
+                    InvalidAttestation::SourceLessThanLowerBound {
+                        source_epoch,
+                        bound_epoch: min_source,
+                    },
+                ));
+            }
+        }
+
+        if let Some(&min_target) = self.attestations.keys().next() {
+            if target_epoch <= min_target {
+                return Err(NotSafe::InvalidAttestation(
+                    InvalidAttestation::TargetLessThanOrEqLowerBound {
+                        target_epoch,
+                        bound_epoch: min_target,
+                    },
+                ));
+            }
+        }
+
+        Ok(Safe::Valid)
+    }
+}
+
+/// Applies the same rules as the `SlashingDatabase` to a history reconstructed from an
+/// interchange file, plus the messages signed since.
+///
+/// Validators which are absent from the interchange file are treated as having no history, rather
+/// than as unregistered.
+#[derive(Debug, Default)]
+pub struct InMemorySlashingProtection {
+    validators: Mutex<HashMap<PublicKeyBytes, ValidatorHistory>>,
+}
+
+impl InMemorySlashingProtection {
+    /// Reconstruct the slashing protection history described by `interchange`.
+    pub fn from_interchange(
+        interchange: Interchange,
+        genesis_validators_root: Hash256,
+    ) -> Result<Self, InterchangeError> {
+        let version = interchange.metadata.interchange_format_version;
+        if version != SUPPORTED_INTERCHANGE_FORMAT_VERSION {
+            return Err(InterchangeError::UnsupportedVersion(version));
+        }
+
+        if genesis_validators_root != interchange.metadata.genesis_validators_root {
+            return Err(InterchangeError::GenesisValidatorsMismatch {
+                client: genesis_validators_root,
+                interchange_file: interchange.metadata.genesis_validators_root,
+            });
+        }
+
+        let mut validators = HashMap::<_, ValidatorHistory>::new();
+        for record in interchange.data {
+            // Merge duplicate records for the same validator, as the database import does.
+            let mut blocks = record.signed_blocks;
+            let mut attestations = record.signed_attestations;
+            if let Some(existing) = validators.get(&record.pubkey) {
+                blocks.extend(existing.blocks.keys().map(|&slot| {
+                    crate::interchange::SignedBlock {
+                        slot,
+                        signing_root: None,
+                    }
+                }));
+                attestations.extend(existing.attestations.values().map(|att| {
+                    crate::interchange::SignedAttestation {
+                        source_epoch: att.source_epoch,
+                        target_epoch: att.target_epoch,
+                        signing_root: None,
+                    }
+                }));
+            }
+            validators.insert(
+                record.pubkey,
+                ValidatorHistory::from_interchange(&blocks, &attestations),
+            );
+        }
+
+        Ok(Self {
+            validators: Mutex::new(validators),
+        })
+    }
+
+    /// Check a block proposal for slash safety, recording it if `record` is `true` and it is
+    /// safe and not already known.
+    ///
+    /// `record` should be set to whether the proposal will actually be signed, so that the history
+    /// does not diverge from the messages that were really signed.
+    pub fn check_block_proposal(
+        &self,
+        validator_pubkey: &PublicKeyBytes,
+        block_header: &BeaconBlockHeader,
+        domain: Hash256,
+        record: bool,
+    ) -> Result<Safe, NotSafe> {
+        self.check_block_signing_root(
+            validator_pubkey,
+            block_header.slot,
+            block_header.signing_root(domain).into(),
+            record,
+        )
+    }
+
+    /// As for `check_block_proposal` but without requiring the whole `BeaconBlockHeader`.
+    pub fn check_block_signing_root(
+        &self,
+        validator_pubkey: &PublicKeyBytes,
+        slot: Slot,
+        signing_root: SigningRoot,
+        record: bool,
+    ) -> Result<Safe, NotSafe> {
+        let mut validators = self.validators.lock();
+        let history = validators.entry(*validator_pubkey).or_default();
+        let safe = history.check_block(slot, signing_root)?;
+        if record && safe == Safe::Valid {
+            history.blocks.insert(slot, signing_root);
+        }
+        Ok(safe)
+    }
+
+    /// Check an attestation for slash safety, recording it if `record` is `true` and it is safe
+    /// and not already known.
+    ///
+    /// See `check_block_proposal` for the meaning of `record`.
+    pub fn check_attestation(
+        &self,
+        validator_pubkey: &PublicKeyBytes,
+        attestation: &AttestationData,
+        domain: Hash256,
+        record: bool,
+    ) -> Result<Safe, NotSafe> {
+        self.check_attestation_signing_root(
+            validator_pubkey,
+            attestation.source.epoch,
+            attestation.target.epoch,
+            attestation.signing_root(domain).into(),
+            record,
+        )
+    }
+
+    /// As for `check_attestation` but without requiring the whole `AttestationData`.
+    pub fn check_attestation_signing_root(
+        &self,
+        validator_pubkey: &PublicKeyBytes,
+        att_source_epoch: Epoch,
+        att_target_epoch: Epoch,
+        att_signing_root: SigningRoot,
+        record: bool,
+    ) -> Result<Safe, NotSafe> {
+        let mut validators = self.validators.lock();
+        let history = validators.entry(*validator_pubkey).or_default();
+        let safe =
+            history.check_attestation(att_source_epoch, att_target_epoch, att_signing_root)?;
+        if record && safe == Safe::Valid {
+            history.attestations.insert(
+                att_target_epoch,
+                SignedAttestation::new(att_source_epoch, att_target_epoch, att_signing_root),
+            );
+        }
+        Ok(safe)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::interchange::{
+        InterchangeData, InterchangeMetadata, SignedAttestation as InterchangeAttestation,
+        SignedBlock as InterchangeBlock,
+    };
+    use crate::test_utils::{pubkey, DEFAULT_GENESIS_VALIDATORS_ROOT};
+    use crate::SlashingDatabase;
+    use tempfile::tempdir;
+
+    fn interchange() -> Interchange {
+        Interchange {
+            metadata: InterchangeMetadata {
+                interchange_format_version: SUPPORTED_INTERCHANGE_FORMAT_VERSION,
+                genesis_validators_root: DEFAULT_GENESIS_VALIDATORS_ROOT,
+            },
+            data: vec![InterchangeData {
+                pubkey: pubkey(0),
+                signed_blocks: vec![
+                    InterchangeBlock {
+                        slot: Slot::new(10),
+                        signing_root: Some(Hash256::repeat_byte(1)),
+                    },
+                    InterchangeBlock {
+                        slot: Slot::new(20),
+                        signing_root: Some(Hash256::repeat_byte(2)),
+                    },
+                ],
+                signed_attestations: vec![
+                    InterchangeAttestation {
+                        source_epoch: Epoch::new(2),
+                        target_epoch: Epoch::new(3),
+                        signing_root: Some(Hash256::repeat_byte(3)),
+                    },
+                    InterchangeAttestation {
+                        source_epoch: Epoch::new(5),
+                        target_epoch: Epoch::new(10),
+                        signing_root: Some(Hash256::repeat_byte(4)),
+                    },
+                ],
+            }],
+        }
+    }
+
+    fn root(byte: u8) -> SigningRoot {
+        Hash256::repeat_byte(byte).into()
+    }
+
+    /// The in-memory history must make the same decisions as a database populated from the same
+    /// interchange file.
+    ///
+    /// Decisions are compared by their `Debug` representation, since null signing roots are never
+    /// equal to each other.
+    #[test]
+    fn matches_database() {
+        let dir = tempdir().unwrap();
+        let db = SlashingDatabase::create(&dir.path().join("slashing_protection.sqlite")).unwrap();
+        db.import_interchange_info(interchange(), DEFAULT_GENESIS_VALIDATORS_ROOT)
+            .unwrap();
+        let in_memory = InMemorySlashingProtection::from_interchange(
+            interchange(),
+            DEFAULT_GENESIS_VALIDATORS_ROOT,
+        )
+        .unwrap();
+        let pk = pubkey(0);
+
+        for (slot, signing_root) in [(15, 5), (20, 2), (21, 6), (21, 6), (21, 7), (22, 8)] {
+            let slot = Slot::new(slot);
+            let expected = db.check_and_insert_block_signing_root(&pk, slot, root(signing_root));
+            let actual = in_memory.check_block_signing_root(
+                &pk,
+                slot,
+                root(signing_root),
+                expected == Ok(Safe::Valid),
+            );
+            assert_eq!(
+                format!("{:?}", actual),
+                format!("{:?}", expected),
+                "slot {}",
+                slot
+            );
+        }
+
+        for (source, target, signing_root) in [
+            (4, 9, 1),
+            (5, 10, 4),
+            (10, 11, 2),
+            (10, 11, 2),
+            (10, 11, 3),
+            (9, 12, 4),
+            (11, 13, 5),
+            (12, 12, 6),
+            (14, 13, 7),
+            (11, 15, 8),
+            (12, 14, 9),
+        ] {
+            let (source, target) = (Epoch::new(source), Epoch::new(target));
+            let expected = db.check_and_insert_attestation_signing_root(
+                &pk,
+                source,
+                target,
+                root(signing_root),
+            );
+            let actual = in_memory.check_attestation_signing_root(
+                &pk,
+                source,
+                target,
+                root(signing_root),
+                expected == Ok(Safe::Valid),
+            );
+            assert_eq!(
+                format!("{:?}", actual),
+                format!("{:?}", expected),
+                "source {} target {}",
+                source,
+                target
+            );
+        }
+    }
+
+    #[test]
+    fn unknown_validators_have_no_history() {
+        let in_memory = InMemorySlashingProtection::from_interchange(
+            interchange(),
+            DEFAULT_GENESIS_VALIDATORS_ROOT,
+        )
+        .unwrap();
+        assert_eq!(
+            in_memory.check_block_signing_root(&pubkey(1), Slot::new(1), root(1), true),
+            Ok(Safe::Valid)
+        );
+    }
+
+    #[test]
+    fn genesis_validators_root_mismatch() {
+        assert!(matches!(
+            InMemorySlashingProtection::from_interchange(interchange(), Hash256::repeat_byte(9)),
+            Err(InterchangeError::GenesisValidatorsMismatch { .. })
+        ));
+    }
+}
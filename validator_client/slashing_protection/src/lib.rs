@@ -1,6 +1,7 @@
 mod attestation_tests;
 mod block_tests;
 mod extra_interchange_tests;
+mod in_memory;
 pub mod interchange;
 pub mod interchange_test;
 mod parallel_tests;
@@ -10,6 +11,7 @@ mod signed_block;
 mod slashing_database;
 pub mod test_utils;
 
+pub use crate::in_memory::InMemorySlashingProtection;
 pub use crate::signed_attestation::{InvalidAttestation, SignedAttestation};
 pub use crate::signed_block::{InvalidBlock, SignedBlock};
 pub use crate::slashing_database::{
@@ -44,8 +44,8 @@ pub fn cli_app() -> Command {
                 .long("broadcast")
                 .value_name("API_TOPICS")
                 .help("Comma-separated list of beacon API topics to broadcast to all beacon nodes. \
-                       Possible values are: none, attestations, blocks, subscriptions, \
-                       sync-committee. Default (when flag is omitted) is to broadcast \
+                       Possible values are: none, attestations, aggregates, blocks, \
+                       subscriptions, sync-committee. Default (when flag is omitted) is to broadcast \
                        subscriptions only."
                 )
                 .action(ArgAction::Set)
@@ -93,6 +93,20 @@ pub fn cli_app() -> Command {
                 )
                 .display_order(0)
         )
+        .arg(
+            Arg::new("slashing-protection-comparison-file")
+                .long("slashing-protection-comparison-file")
+                .value_name("INTERCHANGE_FILE")
+                .help(
+                    "Path to an EIP-3076 slashing protection interchange file. If present, every \
+                     slashing protection decision is also checked against an in-memory history \
+                     reconstructed from this file, and any divergence is logged. The slashing \
+                     protection database remains authoritative. Useful for validating the \
+                     database after it has been restored from a backup."
+                )
+                .action(ArgAction::Set)
+                .display_order(0)
+        )
         .arg(
             Arg::new("disable-auto-discover")
             .long("disable-auto-discover")
@@ -104,6 +118,23 @@ pub fn cli_app() -> Command {
             )
             .display_order(0)
         )
+        .arg(
+            Arg::new("validator-definitions-layout")
+                .long("validator-definitions-layout")
+                .value_name("LAYOUT")
+                .help(
+                    "Converts the validator definitions to the given layout at startup. With \
+                    single-file all definitions are stored in validator_definitions.yml. With \
+                    per-validator-files each definition is stored in its own file in the \
+                    validators.d directory, so that changes to one validator do not rewrite the \
+                    definitions of all others. The layout is persisted, so this flag only needs \
+                    to be supplied once. Converting to per-validator-files makes the definitions \
+                    unreadable by older versions of Lighthouse."
+                )
+                .action(ArgAction::Set)
+                .value_parser(["single-file", "per-validator-files"])
+                .display_order(0)
+        )
         .arg(
             Arg::new("use-long-timeouts")
                 .long("use-long-timeouts")
@@ -125,6 +156,51 @@ pub fn cli_app() -> Command {
                         delimiter, and must not be part of the certificate path.")
                 .display_order(0)
         )
+        .arg(
+            Arg::new("beacon-nodes-tls-config")
+                .long("beacon-nodes-tls-config")
+                .value_name("PATH")
+                .action(ArgAction::Set)
+                .help("Path to a YAML file of TLS settings for individual beacon nodes (and/or \
+                        proposer nodes). Each entry has a `beacon_node` URL matching one given \
+                        to --beacon-nodes or --proposer-nodes, and optionally a \
+                        `root_certificate_path` to a PEM CA certificate to trust, and a \
+                        `client_identity_path` and `client_identity_password` for a PKCS12 client \
+                        certificate used for mutual TLS.")
+                .display_order(0)
+        )
+        .arg(
+            Arg::new("beacon-nodes-http2")
+                .long("beacon-nodes-http2")
+                .action(ArgAction::SetTrue)
+                .help_heading(FLAG_HEADER)
+                .help("If present, the validator client will use HTTP/2 to connect to beacon \
+                        nodes (and/or proposer nodes), multiplexing all requests over a single \
+                        long-lived connection. Every beacon node must support HTTP/2.")
+                .display_order(0)
+        )
+        .arg(
+            Arg::new("beacon-nodes-ssz-requests")
+                .long("beacon-nodes-ssz-requests")
+                .action(ArgAction::SetTrue)
+                .help_heading(FLAG_HEADER)
+                .help("If present, the validator client will send SSZ-encoded request bodies \
+                        when publishing attestations and aggregates and when requesting \
+                        attester and sync duties. If a beacon node rejects an SSZ request as an \
+                        unsupported media type it is retried as JSON, and JSON is used for that \
+                        beacon node from then on.")
+                .display_order(0)
+        )
+        .arg(
+            Arg::new("beacon-nodes-max-concurrent-requests")
+                .long("beacon-nodes-max-concurrent-requests")
+                .value_name("COUNT")
+                .action(ArgAction::Set)
+                .help("Maximum number of requests which may be awaiting a response from each \
+                        beacon node (and/or proposer node) at once. Further requests are queued \
+                        until an earlier request completes. Default is unlimited.")
+                .display_order(0)
+        )
         // This overwrites the graffiti configured in the beacon node.
         .arg(
             Arg::new("graffiti")
@@ -161,6 +237,110 @@ pub fn cli_app() -> Command {
                 .help_heading(FLAG_HEADER)
                 .display_order(0)
         )
+        .arg(
+            Arg::new("precompute-selection-proofs")
+                .long("precompute-selection-proofs")
+                .help("Compute attestation selection proofs for all known duties as soon as they \
+                       are downloaded, rather than shortly before they are required. This avoids \
+                       delaying aggregation duties when signing is slow (e.g. with a remote signer).")
+                .action(ArgAction::SetTrue)
+                .conflicts_with("distributed")
+                .help_heading(FLAG_HEADER)
+                .display_order(0)
+        )
+        .arg(
+            Arg::new("selection-proof-signing-concurrency")
+                .long("selection-proof-signing-concurrency")
+                .value_name("COUNT")
+                .help("Maximum number of attestation selection proofs to sign concurrently. \
+                       Increasing this value may speed up selection proof computation when using \
+                       a remote signer which can handle parallel requests.")
+                .action(ArgAction::Set)
+                .default_value("1")
+                .display_order(0)
+        )
+        .arg(
+            Arg::new("duty-deadlines")
+                .long("duty-deadlines")
+                .value_name("DEADLINES")
+                .help("Deadlines for signing and publishing time-sensitive duties, in \
+                       milliseconds from the start of the slot. A comma-separated list of \
+                       DUTY:SIGN_MS:PUBLISH_MS where DUTY is one of `attestation`, `aggregate`, \
+                       `sync_committee_message`, `sync_committee_contribution` or \
+                       `block_proposal`, e.g. `attestation:6000:8000`. Missed deadlines are \
+                       logged and counted in metrics.")
+                .action(ArgAction::Set)
+                .display_order(0)
+        )
+        .arg(
+            Arg::new("aggregation-circuit-breaker")
+                .long("aggregation-circuit-breaker")
+                .value_name("FAILURES,WINDOW,COOLDOWN")
+                .help("Skip aggregation and sync committee contribution duties while the \
+                       primary beacon node is failing. Takes a comma-separated list of 3 values: \
+                       if FAILURES of the last WINDOW requests to the primary beacon node fail, \
+                       these duties are skipped for COOLDOWN seconds, e.g. `8,10,24`. \
+                       Attestations and block proposals are always attempted. Disabled by default.")
+                .action(ArgAction::Set)
+                .display_order(0)
+        )
+        .arg(
+            Arg::new("startup-mode")
+                .long("startup-mode")
+                .value_name("MODE")
+                .help("Whether duties wait for a synced beacon node when the validator client \
+                       starts. With immediate, duties start as soon as genesis has occurred. \
+                       With wait-for-synced, duties start once a beacon node is synced, or once \
+                       --startup-sync-timeout has elapsed. With fail-fast, the validator client \
+                       exits if no beacon node is synced at startup.")
+                .action(ArgAction::Set)
+                .value_parser(["immediate", "wait-for-synced", "fail-fast"])
+                .default_value("immediate")
+                .display_order(0)
+        )
+        .arg(
+            Arg::new("startup-sync-timeout")
+                .long("startup-sync-timeout")
+                .value_name("SECONDS")
+                .help("The maximum number of seconds to wait for a synced beacon node with \
+                       --startup-mode wait-for-synced, after which duties are started regardless. \
+                       Waits indefinitely by default.")
+                .action(ArgAction::Set)
+                .display_order(0)
+        )
+        .arg(
+            Arg::new("require-synced-genesis-provider")
+                .long("require-synced-genesis-provider")
+                .help("If present, only fetch the genesis time and genesis validators root from \
+                       a beacon node which reports being synced, rather than from the first \
+                       beacon node which responds.")
+                .action(ArgAction::SetTrue)
+                .help_heading(FLAG_HEADER)
+                .display_order(0)
+        )
+        .arg(
+            Arg::new("skip-late-duties")
+                .long("skip-late-duties")
+                .value_name("DUTIES")
+                .help("A comma-separated list of duties which should not be signed or published \
+                       once the corresponding deadline from --duty-deadlines has passed. \
+                       Skipping a late `block_proposal` avoids publishing a block which is likely \
+                       to be orphaned.")
+                .requires("duty-deadlines")
+                .action(ArgAction::Set)
+                .display_order(0)
+        )
+        .arg(
+            Arg::new("keystore-decryption-workers")
+                .long("keystore-decryption-workers")
+                .value_name("COUNT")
+                .help("Maximum number of validator keystores to decrypt concurrently at startup. \
+                       Decrypted keys are saved to an encrypted key cache, so only keystores which \
+                       are missing from the cache need to be decrypted. Defaults to the number of \
+                       available CPUs.")
+                .action(ArgAction::Set)
+                .display_order(0)
+        )
         /* REST API related arguments */
         .arg(
             Arg::new("http")
@@ -288,6 +468,38 @@ pub fn cli_app() -> Command {
                 .action(ArgAction::Set)
                 .display_order(0)
         )
+        .arg(
+            Arg::new("metrics-auth-token-file")
+                .long("metrics-auth-token-file")
+                .value_name("PATH")
+                .requires("metrics")
+                .help("Path to a file containing a token which clients must supply as a bearer \
+                    token in the Authorization header to access the metrics server.")
+                .action(ArgAction::Set)
+                .display_order(0)
+        )
+        .arg(
+            Arg::new("metrics-allowlist")
+                .long("metrics-allowlist")
+                .value_name("NAMES")
+                .requires("metrics")
+                .help("Comma-separated list of metric names to serve. All other metrics are \
+                    omitted. A trailing * matches any metric name with the given prefix, \
+                    e.g. beacon_*.")
+                .action(ArgAction::Set)
+                .display_order(0)
+        )
+        .arg(
+            Arg::new("metrics-denylist")
+                .long("metrics-denylist")
+                .value_name("NAMES")
+                .requires("metrics")
+                .help("Comma-separated list of metric names to omit from the metrics server. \
+                    A trailing * matches any metric name with the given prefix. Takes precedence \
+                    over --metrics-allowlist.")
+                .action(ArgAction::Set)
+                .display_order(0)
+        )
         .arg(
             Arg::new("enable-high-validator-count-metrics")
                 .long("enable-high-validator-count-metrics")
@@ -381,6 +593,16 @@ pub fn cli_app() -> Command {
                 .help_heading(FLAG_HEADER)
                 .display_order(0)
         )
+        .arg(
+            Arg::new("disable-web3-signer-health-checks")
+                .long("disable-web3-signer-health-checks")
+                .help("Disables the service that periodically probes Web3Signer instances and \
+                    stops performing duties with the keys of an unavailable signer until it \
+                    recovers.")
+                .action(ArgAction::SetTrue)
+                .help_heading(FLAG_HEADER)
+                .display_order(0)
+        )
         .arg(
             Arg::new("validator-registration-batch-size")
                 .long("validator-registration-batch-size")
@@ -1,3 +1,4 @@
+use account_utils::validator_definitions::DefinitionsLayout;
 use beacon_node_fallback::{beacon_node_health::BeaconNodeSyncDistanceTiers, ApiTopic};
 use clap::ArgMatches;
 use clap_utils::{flags::DISABLE_MALLOC_TUNING_FLAG, parse_optional, parse_required};
@@ -19,10 +20,60 @@ use std::time::Duration;
 use types::{Address, GRAFFITI_BYTES_LEN};
 use validator_http_api;
 use validator_http_metrics;
+use validator_services::duty_deadlines::{DutyDeadlines, DutyKind};
 use validator_store::Config as ValidatorStoreConfig;
 
 pub const DEFAULT_BEACON_NODE: &str = "http://localhost:5052/";
 
+/// TLS settings for the connection to a single beacon node or proposer node.
+///
+/// The fields other than `beacon_node` have the same meaning as those of a Web3Signer validator
+/// definition.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct BeaconNodeTlsConfig {
+    /// The node these settings apply to, which must match an entry in `--beacon-nodes` or
+    /// `--proposer-nodes`.
+    pub beacon_node: SensitiveUrl,
+    /// Path to a PEM file of CA certificates to trust in addition to the OS trust store.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub root_certificate_path: Option<PathBuf>,
+    /// Path to a PKCS12 file containing the client certificate and key.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub client_identity_path: Option<PathBuf>,
+    /// Password for the PKCS12 file.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub client_identity_password: Option<String>,
+}
+
+/// Whether duties wait for a synced beacon node when the validator client starts.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum StartupMode {
+    /// Start duties as soon as genesis has occurred, regardless of beacon node sync status.
+    #[default]
+    Immediate,
+    /// Start duties once a beacon node is synced, or once `startup_sync_timeout` has elapsed.
+    WaitForSynced,
+    /// Exit if no beacon node is synced once genesis has occurred.
+    FailFast,
+}
+
+impl FromStr for StartupMode {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "immediate" => Ok(Self::Immediate),
+            "wait-for-synced" => Ok(Self::WaitForSynced),
+            "fail-fast" => Ok(Self::FailFast),
+            other => Err(format!(
+                "{} is not a valid startup mode, expected immediate, wait-for-synced or fail-fast",
+                other
+            )),
+        }
+    }
+}
+
 /// Stores the core configuration for this validator instance.
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct Config {
@@ -44,8 +95,12 @@ pub struct Config {
     pub allow_unsynced_beacon_node: bool,
     /// If true, don't scan the validators dir for new keystores.
     pub disable_auto_discover: bool,
+    /// If set, convert the validator definitions to this layout at startup.
+    pub validator_definitions_layout: Option<DefinitionsLayout>,
     /// If true, re-register existing validators in definitions.yml for slashing protection.
     pub init_slashing_protection: bool,
+    /// An interchange file used to cross-check slashing protection decisions.
+    pub slashing_protection_comparison_file: Option<PathBuf>,
     /// If true, use longer timeouts for requests made to the beacon node.
     pub use_long_timeouts: bool,
     /// Graffiti to be inserted everytime we create a block.
@@ -58,6 +113,13 @@ pub struct Config {
     pub http_metrics: validator_http_metrics::Config,
     /// Configuration for the Beacon Node fallback.
     pub beacon_node_fallback: beacon_node_fallback::Config,
+    /// Whether duties wait for a synced beacon node at startup.
+    pub startup_mode: StartupMode,
+    /// The maximum time to wait for a synced beacon node with `StartupMode::WaitForSynced`, after
+    /// which duties are started regardless. Waits indefinitely if `None`.
+    pub startup_sync_timeout: Option<Duration>,
+    /// If true, only fetch the genesis parameters from a beacon node which reports being synced.
+    pub require_synced_genesis_provider: bool,
     /// Configuration for sending metrics to a remote explorer endpoint.
     pub monitoring_api: Option<monitoring_api::Config>,
     /// If true, enable functionality that monitors the network for attestations or proposals from
@@ -73,14 +135,31 @@ pub struct Config {
     /// A list of custom certificates that the validator client will additionally use when
     /// connecting to a beacon node over SSL/TLS.
     pub beacon_nodes_tls_certs: Option<Vec<PathBuf>>,
+    /// TLS settings for connections to individual beacon nodes.
+    pub beacon_nodes_tls_config: Vec<BeaconNodeTlsConfig>,
+    /// Use HTTP/2 when connecting to beacon nodes, multiplexing requests over a single connection.
+    pub beacon_nodes_http2: bool,
+    /// Send SSZ request bodies to beacon nodes for the endpoints which accept them.
+    pub beacon_nodes_ssz_requests: bool,
+    /// The maximum number of requests which may be in-flight to each beacon node at once.
+    pub beacon_nodes_max_concurrent_requests: Option<usize>,
     /// Enables broadcasting of various requests (by topic) to all beacon nodes.
     pub broadcast_topics: Vec<ApiTopic>,
     /// Enables a service which attempts to measure latency between the VC and BNs.
     pub enable_latency_measurement_service: bool,
+    /// Enables a service which probes Web3Signer instances and excludes the validators of
+    /// unavailable signers from duties.
+    pub enable_web3_signer_health_checks: bool,
     /// Defines the number of validators per `validator/register_validator` request sent to the BN.
     pub validator_registration_batch_size: usize,
     /// Whether we are running with distributed network support.
     pub distributed: bool,
+    /// Compute attestation selection proofs as soon as duties are known.
+    pub precompute_selection_proofs: bool,
+    /// The maximum number of attestation selection proofs to sign concurrently.
+    pub selection_proof_signing_concurrency: usize,
+    /// Deadlines for signing and publishing each time-sensitive duty.
+    pub duty_deadlines: DutyDeadlines,
     /// Configuration for the initialized validators
     #[serde(flatten)]
     pub initialized_validators: InitializedValidatorsConfig,
@@ -108,22 +187,35 @@ impl Default for Config {
             proposer_nodes: Vec::new(),
             allow_unsynced_beacon_node: false,
             disable_auto_discover: false,
+            validator_definitions_layout: None,
             init_slashing_protection: false,
+            slashing_protection_comparison_file: None,
             use_long_timeouts: false,
             graffiti: None,
             graffiti_file: None,
             http_api: <_>::default(),
             http_metrics: <_>::default(),
             beacon_node_fallback: <_>::default(),
+            startup_mode: StartupMode::default(),
+            startup_sync_timeout: None,
+            require_synced_genesis_provider: false,
             monitoring_api: None,
             enable_doppelganger_protection: false,
             enable_high_validator_count_metrics: false,
             beacon_nodes_tls_certs: None,
+            beacon_nodes_tls_config: vec![],
+            beacon_nodes_http2: false,
+            beacon_nodes_ssz_requests: false,
+            beacon_nodes_max_concurrent_requests: None,
             builder_registration_timestamp_override: None,
             broadcast_topics: vec![ApiTopic::Subscriptions],
             enable_latency_measurement_service: true,
+            enable_web3_signer_health_checks: true,
             validator_registration_batch_size: 500,
             distributed: false,
+            precompute_selection_proofs: false,
+            selection_proof_signing_concurrency: 1,
+            duty_deadlines: DutyDeadlines::default(),
             initialized_validators: <_>::default(),
         }
     }
@@ -185,7 +277,11 @@ impl Config {
         }
 
         config.disable_auto_discover = cli_args.get_flag("disable-auto-discover");
+        config.validator_definitions_layout =
+            parse_optional(cli_args, "validator-definitions-layout")?;
         config.init_slashing_protection = cli_args.get_flag("init-slashing-protection");
+        config.slashing_protection_comparison_file =
+            parse_optional(cli_args, "slashing-protection-comparison-file")?;
         config.use_long_timeouts = cli_args.get_flag("use-long-timeouts");
 
         if let Some(graffiti_file_path) = cli_args.get_one::<String>("graffiti-file") {
@@ -226,10 +322,106 @@ impl Config {
             config.beacon_nodes_tls_certs = Some(tls_certs.split(',').map(PathBuf::from).collect());
         }
 
+        if let Some(path) = parse_optional::<PathBuf>(cli_args, "beacon-nodes-tls-config")? {
+            let file = fs::File::open(&path)
+                .map_err(|e| format!("Unable to open beacon node TLS config {:?}: {}", path, e))?;
+            let tls_config: Vec<BeaconNodeTlsConfig> = serde_yaml::from_reader(file)
+                .map_err(|e| format!("Unable to parse beacon node TLS config {:?}: {}", path, e))?;
+
+            let mut configured = Vec::with_capacity(tls_config.len());
+            for entry in &tls_config {
+                let node = &entry.beacon_node;
+                if !config
+                    .beacon_nodes
+                    .iter()
+                    .chain(&config.proposer_nodes)
+                    .any(|url| url == node)
+                {
+                    return Err(format!(
+                        "Beacon node TLS config for {} does not match any beacon or proposer node",
+                        node
+                    ));
+                }
+                if configured.contains(&node) {
+                    return Err(format!("Duplicate beacon node TLS config for {}", node));
+                }
+                configured.push(node);
+            }
+
+            config.beacon_nodes_tls_config = tls_config;
+        }
+
+        config.beacon_nodes_http2 = cli_args.get_flag("beacon-nodes-http2");
+        config.beacon_nodes_ssz_requests = cli_args.get_flag("beacon-nodes-ssz-requests");
+
+        if let Some(n) = parse_optional::<usize>(cli_args, "beacon-nodes-max-concurrent-requests")?
+        {
+            if n == 0 {
+                return Err(
+                    "--beacon-nodes-max-concurrent-requests must be a non-zero value".to_string(),
+                );
+            }
+            config.beacon_nodes_max_concurrent_requests = Some(n);
+        }
+
         if cli_args.get_flag("distributed") {
             config.distributed = true;
         }
 
+        config.precompute_selection_proofs = cli_args.get_flag("precompute-selection-proofs");
+
+        if let Some(n) = parse_optional::<usize>(cli_args, "selection-proof-signing-concurrency")? {
+            if n == 0 {
+                return Err(
+                    "--selection-proof-signing-concurrency must be a non-zero value".to_string(),
+                );
+            }
+            config.selection_proof_signing_concurrency = n;
+        }
+
+        if let Some(deadlines) = cli_args.get_one::<String>("duty-deadlines") {
+            for deadline in deadlines.split(',') {
+                let parts = deadline.trim().split(':').collect::<Vec<_>>();
+                let [duty, sign_by, publish_by] = parts.as_slice() else {
+                    return Err(format!(
+                        "Invalid duty deadline {}, expected DUTY:SIGN_MS:PUBLISH_MS",
+                        deadline
+                    ));
+                };
+                let parse_ms = |ms: &str| {
+                    ms.parse::<u64>()
+                        .map(Duration::from_millis)
+                        .map_err(|e| format!("Invalid duty deadline {}: {:?}", deadline, e))
+                };
+                let (sign_by, publish_by) = (parse_ms(sign_by)?, parse_ms(publish_by)?);
+                if sign_by > publish_by {
+                    return Err(format!(
+                        "Invalid duty deadline {}: signing deadline is after publishing deadline",
+                        deadline
+                    ));
+                }
+                let duty_deadline = config.duty_deadlines.get_mut(DutyKind::from_str(duty)?);
+                duty_deadline.sign_by = Some(sign_by);
+                duty_deadline.publish_by = Some(publish_by);
+            }
+        }
+
+        if let Some(duties) = cli_args.get_one::<String>("skip-late-duties") {
+            for duty in duties.split(',') {
+                config
+                    .duty_deadlines
+                    .get_mut(DutyKind::from_str(duty.trim())?)
+                    .skip_late = true;
+            }
+        }
+
+        if let Some(n) = parse_optional::<usize>(cli_args, "keystore-decryption-workers")? {
+            if n == 0 {
+                return Err("--keystore-decryption-workers must be a non-zero value".to_string());
+            }
+            config.initialized_validators.keystore_decryption_workers = n;
+        }
+
         if let Some(broadcast_topics) = cli_args.get_one::<String>("broadcast") {
             config.broadcast_topics = broadcast_topics
                 .split(',')
@@ -252,6 +444,20 @@ impl Config {
             config.beacon_node_fallback.sync_tolerances = BeaconNodeSyncDistanceTiers::default();
         }
 
+        config.beacon_node_fallback.aggregation_circuit_breaker =
+            parse_optional(cli_args, "aggregation-circuit-breaker")?;
+
+        /*
+         * Startup
+         */
+        if let Some(startup_mode) = parse_optional(cli_args, "startup-mode")? {
+            config.startup_mode = startup_mode;
+        }
+        config.startup_sync_timeout =
+            parse_optional(cli_args, "startup-sync-timeout")?.map(Duration::from_secs);
+        config.require_synced_genesis_provider =
+            cli_args.get_flag("require-synced-genesis-provider");
+
         /*
          * Web3 signer
          */
@@ -347,6 +553,28 @@ impl Config {
             config.http_metrics.allow_origin = Some(allow_origin.to_string());
         }
 
+        if let Some(path) = clap_utils::parse_optional(cli_args, "metrics-auth-token-file")? {
+            config.http_metrics.auth_token_path = Some(path);
+        }
+
+        if let Some(allowlist) = cli_args.get_one::<String>("metrics-allowlist") {
+            config.http_metrics.metric_allowlist = Some(
+                allowlist
+                    .split(',')
+                    .map(|name| name.trim().to_string())
+                    .filter(|name| !name.is_empty())
+                    .collect(),
+            );
+        }
+
+        if let Some(denylist) = cli_args.get_one::<String>("metrics-denylist") {
+            config.http_metrics.metric_denylist = denylist
+                .split(',')
+                .map(|name| name.trim().to_string())
+                .filter(|name| !name.is_empty())
+                .collect();
+        }
+
         if cli_args.get_flag(DISABLE_MALLOC_TUNING_FLAG) {
             config.http_metrics.allocator_metrics_enabled = false;
         }
@@ -402,6 +630,9 @@ impl Config {
         config.enable_latency_measurement_service =
             !cli_args.get_flag("disable-latency-measurement-service");
 
+        config.enable_web3_signer_health_checks =
+            !cli_args.get_flag("disable-web3-signer-health-checks");
+
         config.validator_registration_batch_size =
             parse_required(cli_args, "validator-registration-batch-size")?;
         if config.validator_registration_batch_size == 0 {
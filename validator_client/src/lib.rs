@@ -4,12 +4,15 @@ mod latency;
 mod notifier;
 
 pub use cli::cli_app;
-pub use config::Config;
+pub use config::{Config, StartupMode};
 use initialized_validators::InitializedValidators;
 use metrics::set_gauge;
 use monitoring_api::{MonitoringHttpClient, ProcessType};
 use sensitive_url::SensitiveUrl;
-use slashing_protection::{SlashingDatabase, SLASHING_PROTECTION_FILENAME};
+use slashing_protection::{
+    interchange::Interchange, InMemorySlashingProtection, SlashingDatabase,
+    SLASHING_PROTECTION_FILENAME,
+};
 
 use beacon_node_fallback::{
     start_fallback_updater_service, BeaconNodeFallback, CandidateBeaconNode,
@@ -33,7 +36,7 @@ use std::marker::PhantomData;
 use std::net::SocketAddr;
 use std::path::Path;
 use std::sync::Arc;
-use std::time::{SystemTime, UNIX_EPOCH};
+use std::time::{Instant, SystemTime, UNIX_EPOCH};
 use tokio::{
     sync::mpsc,
     time::{sleep, Duration},
@@ -45,6 +48,7 @@ use validator_services::{
     block_service::{BlockService, BlockServiceBuilder},
     duties_service::{self, DutiesService},
     preparation_service::{PreparationService, PreparationServiceBuilder},
+    signer_health_service,
     sync::SyncDutiesMap,
     sync_committee_service::SyncCommitteeService,
 };
@@ -71,6 +75,9 @@ const HTTP_GET_DEBUG_BEACON_STATE_QUOTIENT: u32 = 4;
 const HTTP_GET_DEPOSIT_SNAPSHOT_QUOTIENT: u32 = 4;
 const HTTP_GET_VALIDATOR_BLOCK_TIMEOUT_QUOTIENT: u32 = 4;
 
+/// The interval between HTTP/2 keep-alive pings sent to beacon nodes, as a fraction of a slot.
+const HTTP2_KEEP_ALIVE_QUOTIENT: u32 = 2;
+
 const DOPPELGANGER_SERVICE_NAME: &str = "doppelganger";
 
 #[derive(Clone)]
@@ -180,6 +187,21 @@ impl<E: EthSpec> ProductionValidatorClient<E> {
         let mut validator_defs = ValidatorDefinitions::open_or_create(&config.validator_dir)
             .map_err(|e| format!("Unable to open or create validator definitions: {:?}", e))?;
 
+        if let Some(layout) = config
+            .validator_definitions_layout
+            .filter(|layout| *layout != validator_defs.layout())
+        {
+            validator_defs.set_layout(layout);
+            validator_defs
+                .save(&config.validator_dir)
+                .map_err(|e| format!("Unable to convert validator definitions layout: {:?}", e))?;
+            info!(
+                log,
+                "Converted validator definitions layout";
+                "layout" => ?layout,
+            );
+        }
+
         if !config.disable_auto_discover {
             let new_validators = validator_defs
                 .discover_local_keystores(&config.validator_dir, &config.secrets_dir, &log)
@@ -295,6 +317,31 @@ impl<E: EthSpec> ProductionValidatorClient<E> {
                 }
             }
 
+            // Add the TLS settings specific to this node.
+            if let Some(tls_config) = config
+                .beacon_nodes_tls_config
+                .iter()
+                .find(|tls_config| tls_config.beacon_node == *url)
+            {
+                beacon_node_http_client_builder = initialized_validators::add_tls_config(
+                    beacon_node_http_client_builder,
+                    tls_config.root_certificate_path.as_deref(),
+                    tls_config.client_identity_path.as_deref(),
+                    tls_config.client_identity_password.as_deref(),
+                )
+                .map_err(|e| format!("Invalid TLS config for beacon node {}: {:?}", url, e))?;
+            }
+
+            if config.beacon_nodes_http2 {
+                beacon_node_http_client_builder = beacon_node_http_client_builder
+                    .http2_prior_knowledge()
+                    .http2_adaptive_window(true)
+                    // Keep the connection warm between slots so duties at the start of the next
+                    // slot don't pay for a new connection.
+                    .http2_keep_alive_interval(slot_duration / HTTP2_KEEP_ALIVE_QUOTIENT)
+                    .http2_keep_alive_while_idle(true);
+            }
+
             let beacon_node_http_client = beacon_node_http_client_builder
                 // Set default timeout to be the full slot duration.
                 .timeout(slot_duration)
@@ -328,11 +375,19 @@ impl<E: EthSpec> ProductionValidatorClient<E> {
                 Timeouts::set_all(slot_duration)
             };
 
-            Ok(BeaconNodeHttpClient::from_components(
+            let mut beacon_node = BeaconNodeHttpClient::from_components(
                 url.clone(),
                 beacon_node_http_client,
                 timeouts,
-            ))
+            );
+            if config.beacon_nodes_ssz_requests {
+                beacon_node = beacon_node.with_ssz_request_bodies();
+            }
+
+            Ok(match config.beacon_nodes_max_concurrent_requests {
+                Some(n) => beacon_node.with_max_concurrent_requests(n),
+                None => beacon_node,
+            })
         };
 
         let beacon_nodes: Vec<BeaconNodeHttpClient> = config
@@ -392,9 +447,13 @@ impl<E: EthSpec> ProductionValidatorClient<E> {
             log.clone(),
         );
 
+        // Proposals are never skipped, so the proposer nodes do not need a circuit breaker.
         let mut proposer_nodes: BeaconNodeFallback<_, E> = BeaconNodeFallback::new(
             proposer_candidates,
-            config.beacon_node_fallback,
+            beacon_node_fallback::Config {
+                aggregation_circuit_breaker: None,
+                ..config.beacon_node_fallback
+            },
             config.broadcast_topics.clone(),
             context.eth2_config.spec.clone(),
             log.clone(),
@@ -402,7 +461,12 @@ impl<E: EthSpec> ProductionValidatorClient<E> {
 
         // Perform some potentially long-running initialization tasks.
         let (genesis_time, genesis_validators_root) = tokio::select! {
-            tuple = init_from_beacon_node(&beacon_nodes, &proposer_nodes, &context) => tuple?,
+            tuple = init_from_beacon_node(
+                &beacon_nodes,
+                &proposer_nodes,
+                config.require_synced_genesis_provider,
+                &context,
+            ) => tuple?,
             () = context.executor.exit() => return Err("Shutting down".to_string())
         };
 
@@ -437,9 +501,43 @@ impl<E: EthSpec> ProductionValidatorClient<E> {
             None
         };
 
+        let slashing_protection_comparison = if let Some(path) =
+            &config.slashing_protection_comparison_file
+        {
+            let file = File::open(path).map_err(|e| {
+                format!(
+                    "Unable to open slashing protection comparison file {:?}: {:?}",
+                    path, e
+                )
+            })?;
+            let interchange = Interchange::from_json_reader(file).map_err(|e| {
+                format!(
+                    "Unable to parse slashing protection comparison file {:?}: {:?}",
+                    path, e
+                )
+            })?;
+            let comparison =
+                InMemorySlashingProtection::from_interchange(interchange, genesis_validators_root)
+                    .map_err(|e| {
+                        format!(
+                            "Invalid slashing protection comparison file {:?}: {:?}",
+                            path, e
+                        )
+                    })?;
+            info!(
+                log,
+                "Comparing slashing protection against interchange file";
+                "path" => ?path,
+            );
+            Some(comparison)
+        } else {
+            None
+        };
+
         let validator_store = Arc::new(ValidatorStore::new(
             validators,
             slashing_protection,
+            slashing_protection_comparison,
             genesis_validators_root,
             context.eth2_config.spec.clone(),
             doppelganger_service.clone(),
@@ -478,6 +576,8 @@ impl<E: EthSpec> ProductionValidatorClient<E> {
             context: duties_context,
             enable_high_validator_count_metrics: config.enable_high_validator_count_metrics,
             distributed: config.distributed,
+            precompute_selection_proofs: config.precompute_selection_proofs,
+            selection_proof_signing_concurrency: config.selection_proof_signing_concurrency,
         });
 
         // Update the metrics server.
@@ -492,7 +592,8 @@ impl<E: EthSpec> ProductionValidatorClient<E> {
             .beacon_nodes(beacon_nodes.clone())
             .runtime_context(context.service_context("block".into()))
             .graffiti(config.graffiti)
-            .graffiti_file(config.graffiti_file.clone());
+            .graffiti_file(config.graffiti_file.clone())
+            .duty_deadlines(config.duty_deadlines);
 
         // If we have proposer nodes, add them to the block service builder.
         if proposer_nodes_num > 0 {
@@ -507,6 +608,7 @@ impl<E: EthSpec> ProductionValidatorClient<E> {
             .validator_store(validator_store.clone())
             .beacon_nodes(beacon_nodes.clone())
             .runtime_context(context.service_context("attestation".into()))
+            .duty_deadlines(config.duty_deadlines)
             .build()?;
 
         let preparation_service = PreparationServiceBuilder::new()
@@ -524,6 +626,7 @@ impl<E: EthSpec> ProductionValidatorClient<E> {
             slot_clock.clone(),
             beacon_nodes.clone(),
             context.service_context("sync_committee".into()),
+            config.duty_deadlines,
         );
 
         Ok(Self {
@@ -590,6 +693,15 @@ impl<E: EthSpec> ProductionValidatorClient<E> {
         // Wait until genesis has occurred.
         wait_for_genesis(&self.beacon_nodes, self.genesis_time, &self.context).await?;
 
+        // Wait until a beacon node is synced, if required.
+        wait_for_synced_beacon_node(
+            &self.beacon_nodes,
+            self.config.startup_mode,
+            self.config.startup_sync_timeout,
+            &self.context,
+        )
+        .await?;
+
         duties_service::start_update_service(self.duties_service.clone(), block_service_tx);
 
         self.block_service
@@ -628,6 +740,14 @@ impl<E: EthSpec> ProductionValidatorClient<E> {
 
         spawn_notifier(self).map_err(|e| format!("Failed to start notifier: {}", e))?;
 
+        if self.config.enable_web3_signer_health_checks {
+            signer_health_service::start_update_service(
+                self.context.clone(),
+                self.validator_store.clone(),
+                self.duties_service.slot_clock.clone(),
+            );
+        }
+
         if self.config.enable_latency_measurement_service {
             latency::start_latency_service(
                 self.context.clone(),
@@ -640,9 +760,18 @@ impl<E: EthSpec> ProductionValidatorClient<E> {
     }
 }
 
+/// The reasons a beacon node may fail to provide the genesis parameters.
+#[derive(Debug)]
+enum GenesisProviderError {
+    Request(eth2::Error),
+    /// The node is syncing and `--require-synced-genesis-provider` is set.
+    Syncing,
+}
+
 async fn init_from_beacon_node<E: EthSpec>(
     beacon_nodes: &BeaconNodeFallback<SystemTimeSlotClock, E>,
     proposer_nodes: &BeaconNodeFallback<SystemTimeSlotClock, E>,
+    require_synced_genesis_provider: bool,
     context: &RuntimeContext<E>,
 ) -> Result<(u64, Hash256), String> {
     loop {
@@ -699,23 +828,49 @@ async fn init_from_beacon_node<E: EthSpec>(
 
     let genesis = loop {
         match beacon_nodes
-            .first_success(|node| async move { node.get_beacon_genesis().await })
+            .first_success(|node| async move {
+                if require_synced_genesis_provider {
+                    let syncing = node
+                        .get_node_syncing()
+                        .await
+                        .map_err(GenesisProviderError::Request)?
+                        .data;
+                    if syncing.is_syncing {
+                        return Err(GenesisProviderError::Syncing);
+                    }
+                }
+                node.get_beacon_genesis()
+                    .await
+                    .map_err(GenesisProviderError::Request)
+            })
             .await
         {
             Ok(genesis) => break genesis.data,
             Err(errors) => {
-                // Search for a 404 error which indicates that genesis has not yet
-                // occurred.
-                if errors
+                let failures = errors
                     .0
                     .iter()
                     .filter_map(|(_, e)| e.request_failure())
-                    .any(|e| e.status() == Some(StatusCode::NOT_FOUND))
-                {
+                    .collect::<Vec<_>>();
+                // Search for a 404 error which indicates that genesis has not yet
+                // occurred.
+                if failures.iter().any(|e| match e {
+                    GenesisProviderError::Request(e) => e.status() == Some(StatusCode::NOT_FOUND),
+                    GenesisProviderError::Syncing => false,
+                }) {
                     info!(
                         context.log(),
                         "Waiting for genesis";
                     );
+                } else if failures
+                    .iter()
+                    .any(|e| matches!(e, GenesisProviderError::Syncing))
+                {
+                    info!(
+                        context.log(),
+                        "Waiting for a synced beacon node to provide genesis";
+                        "info" => "--require-synced-genesis-provider is set",
+                    );
                 } else {
                     error!(
                         context.log(),
@@ -732,6 +887,63 @@ async fn init_from_beacon_node<E: EthSpec>(
     Ok((genesis.genesis_time, genesis.genesis_validators_root))
 }
 
+/// Wait until a beacon node is synced before duties are started, according to `startup_mode`.
+async fn wait_for_synced_beacon_node<E: EthSpec>(
+    beacon_nodes: &BeaconNodeFallback<SystemTimeSlotClock, E>,
+    startup_mode: StartupMode,
+    startup_sync_timeout: Option<Duration>,
+    context: &RuntimeContext<E>,
+) -> Result<(), String> {
+    if startup_mode == StartupMode::Immediate {
+        return Ok(());
+    }
+
+    let deadline = startup_sync_timeout.map(|timeout| Instant::now() + timeout);
+    loop {
+        beacon_nodes.update_all_candidates().await;
+        let (_, num_available, num_synced) = beacon_nodes.get_notifier_info().await;
+        let num_total = beacon_nodes.num_total().await;
+
+        if num_synced > 0 {
+            info!(
+                context.log(),
+                "Synced beacon node available, starting duties";
+                "synced" => num_synced,
+                "available" => num_available,
+                "total" => num_total,
+            );
+            return Ok(());
+        }
+
+        if startup_mode == StartupMode::FailFast {
+            return Err(format!(
+                "No synced beacon node at startup ({} of {} available). Use --startup-mode \
+                 wait-for-synced to wait for a beacon node to sync",
+                num_available, num_total
+            ));
+        }
+
+        if deadline.is_some_and(|deadline| Instant::now() >= deadline) {
+            warn!(
+                context.log(),
+                "Starting duties without a synced beacon node";
+                "info" => "--startup-sync-timeout has elapsed",
+                "available" => num_available,
+                "total" => num_total,
+            );
+            return Ok(());
+        }
+
+        info!(
+            context.log(),
+            "Waiting for a synced beacon node before starting duties";
+            "available" => num_available,
+            "total" => num_total,
+        );
+        sleep(RETRY_DELAY).await;
+    }
+}
+
 async fn wait_for_genesis<E: EthSpec>(
     beacon_nodes: &BeaconNodeFallback<SystemTimeSlotClock, E>,
     genesis_time: u64,
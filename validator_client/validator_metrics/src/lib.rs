@@ -32,6 +32,15 @@ pub const ATTESTATION_SELECTION_PROOFS: &str = "attestation_selection_proofs";
 pub const SUBSCRIPTIONS: &str = "subscriptions";
 pub const LOCAL_KEYSTORE: &str = "local_keystore";
 pub const WEB3SIGNER: &str = "web3signer";
+/// The `signer` label of duty failures which occurred before or after signing.
+pub const NO_SIGNER: &str = "none";
+pub const FAILURE_TIMEOUT: &str = "timeout";
+pub const FAILURE_HTTP_4XX: &str = "http_4xx";
+pub const FAILURE_HTTP_5XX: &str = "http_5xx";
+pub const FAILURE_SLASHING_PROTECTION: &str = "slashing_protection_refused";
+pub const FAILURE_DOPPELGANGER: &str = "doppelganger_protected";
+pub const FAILURE_BN_UNAVAILABLE: &str = "bn_unavailable";
+pub const FAILURE_OTHER: &str = "other";
 
 pub use metrics::*;
 
@@ -54,6 +63,12 @@ pub static TOTAL_VALIDATORS_COUNT: LazyLock<Result<IntGauge>> = LazyLock::new(||
     )
 });
 
+pub static WEB3SIGNER_UNHEALTHY_SIGNERS: LazyLock<Result<IntGauge>> = LazyLock::new(|| {
+    try_create_int_gauge(
+        "vc_web3signer_unhealthy_signers",
+        "Number of Web3Signer instances which failed their most recent health probe",
+    )
+});
 pub static SIGNED_BLOCKS_TOTAL: LazyLock<Result<IntCounterVec>> = LazyLock::new(|| {
     try_create_int_counter_vec(
         "vc_signed_beacon_blocks_total",
@@ -61,6 +76,14 @@ pub static SIGNED_BLOCKS_TOTAL: LazyLock<Result<IntCounterVec>> = LazyLock::new(
         &["status"],
     )
 });
+pub static SLASHING_PROTECTION_COMPARISON_DIVERGENCES_TOTAL: LazyLock<Result<IntCounterVec>> =
+    LazyLock::new(|| {
+        try_create_int_counter_vec(
+            "vc_slashing_protection_comparison_divergences_total",
+            "Total count of slashing protection decisions which differed from the comparison",
+            &["type"],
+        )
+    });
 pub static SIGNED_ATTESTATIONS_TOTAL: LazyLock<Result<IntCounterVec>> = LazyLock::new(|| {
     try_create_int_counter_vec(
         "vc_signed_attestations_total",
@@ -121,6 +144,51 @@ pub static SIGNED_VALIDATOR_REGISTRATIONS_TOTAL: LazyLock<Result<IntCounterVec>>
             &["status"],
         )
     });
+pub static DUTY_STAGE_SLOT_DELAY: LazyLock<Result<HistogramVec>> = LazyLock::new(|| {
+    try_create_histogram_vec_with_buckets(
+        "vc_duty_stage_slot_delay_seconds",
+        "Time from the start of the slot until each stage of a duty completed",
+        Ok(vec![
+            0.5, 1.0, 2.0, 3.0, 4.0, 5.0, 6.0, 7.0, 8.0, 9.0, 10.0, 12.0,
+        ]),
+        &["duty", "stage"],
+    )
+});
+pub static DUTY_DEADLINE_MISSED_TOTAL: LazyLock<Result<IntCounterVec>> = LazyLock::new(|| {
+    try_create_int_counter_vec(
+        "vc_duty_deadline_missed_total",
+        "Total count of duty stages which completed after their deadline",
+        &["duty", "stage"],
+    )
+});
+pub static DUTY_SKIPPED_LATE_TOTAL: LazyLock<Result<IntCounterVec>> = LazyLock::new(|| {
+    try_create_int_counter_vec(
+        "vc_duty_skipped_late_total",
+        "Total count of duty stages skipped because their deadline had passed",
+        &["duty", "stage"],
+    )
+});
+pub static AGGREGATION_CIRCUIT_BREAKER_OPEN: LazyLock<Result<IntGauge>> = LazyLock::new(|| {
+    try_create_int_gauge(
+        "vc_aggregation_circuit_breaker_open",
+        "Set to 1 while non-critical duties are skipped due to primary beacon node failures",
+    )
+});
+pub static DUTY_SKIPPED_CIRCUIT_BREAKER_TOTAL: LazyLock<Result<IntCounterVec>> =
+    LazyLock::new(|| {
+        try_create_int_counter_vec(
+            "vc_duty_skipped_circuit_breaker_total",
+            "Total count of duties skipped because the aggregation circuit breaker was open",
+            &["duty"],
+        )
+    });
+pub static DUTY_FAILURES_TOTAL: LazyLock<Result<IntCounterVec>> = LazyLock::new(|| {
+    try_create_int_counter_vec(
+        "vc_duty_failures_total",
+        "Total count of failed duties, by the signer involved and the reason for the failure",
+        &["duty", "signer", "reason"],
+    )
+});
 pub static DUTIES_SERVICE_TIMES: LazyLock<Result<HistogramVec>> = LazyLock::new(|| {
     try_create_histogram_vec(
         "vc_duties_service_task_times_seconds",
@@ -185,6 +253,13 @@ pub static ENDPOINT_REQUESTS: LazyLock<Result<IntCounterVec>> = LazyLock::new(||
         &["endpoint"],
     )
 });
+pub static BROADCAST_SUBMISSIONS: LazyLock<Result<IntCounterVec>> = LazyLock::new(|| {
+    try_create_int_counter_vec(
+        "vc_beacon_node_broadcast_submissions_total",
+        "The number of submissions broadcast to each beacon node, by API topic and outcome",
+        &["endpoint", "topic", "outcome"],
+    )
+});
 
 /*
  * Beacon node availability metrics
@@ -1,5 +1,9 @@
 use crate::duties_service::{DutiesService, DutyAndProof};
-use beacon_node_fallback::{ApiTopic, BeaconNodeFallback};
+use crate::duty_deadlines::{DutyDeadlines, DutyKind, DutyStage, DutyTimer};
+use crate::duty_failures::{
+    record_beacon_node_failures, record_signing_failure, skip_for_circuit_breaker,
+};
+use beacon_node_fallback::{ApiTopic, BeaconNodeFallback, RequestFailureReason};
 use environment::RuntimeContext;
 use futures::future::join_all;
 use slog::{crit, debug, error, info, trace, warn};
@@ -12,6 +16,25 @@ use tree_hash::TreeHash;
 use types::{Attestation, AttestationData, ChainSpec, CommitteeIndex, EthSpec, Slot};
 use validator_store::{Error as ValidatorStoreError, ValidatorStore};
 
+/// An error fetching an aggregate attestation from a beacon node.
+#[derive(Debug)]
+enum AggregateFetchError {
+    /// The request to the beacon node failed.
+    Request(eth2::Error),
+    /// The beacon node does not know an aggregate for the attestation data.
+    NotFound,
+}
+
+impl RequestFailureReason for AggregateFetchError {
+    fn failure_reason(&self) -> &'static str {
+        match self {
+            AggregateFetchError::Request(e) => e.failure_reason(),
+            // The beacon node answered the request with a 404.
+            AggregateFetchError::NotFound => validator_metrics::FAILURE_HTTP_4XX,
+        }
+    }
+}
+
 /// Builds an `AttestationService`.
 #[derive(Default)]
 pub struct AttestationServiceBuilder<T: SlotClock + 'static, E: EthSpec> {
@@ -20,6 +43,7 @@ pub struct AttestationServiceBuilder<T: SlotClock + 'static, E: EthSpec> {
     slot_clock: Option<T>,
     beacon_nodes: Option<Arc<BeaconNodeFallback<T, E>>>,
     context: Option<RuntimeContext<E>>,
+    duty_deadlines: DutyDeadlines,
 }
 
 impl<T: SlotClock + 'static, E: EthSpec> AttestationServiceBuilder<T, E> {
@@ -30,6 +54,7 @@ impl<T: SlotClock + 'static, E: EthSpec> AttestationServiceBuilder<T, E> {
             slot_clock: None,
             beacon_nodes: None,
             context: None,
+            duty_deadlines: DutyDeadlines::default(),
         }
     }
 
@@ -58,6 +83,11 @@ impl<T: SlotClock + 'static, E: EthSpec> AttestationServiceBuilder<T, E> {
         self
     }
 
+    pub fn duty_deadlines(mut self, duty_deadlines: DutyDeadlines) -> Self {
+        self.duty_deadlines = duty_deadlines;
+        self
+    }
+
     pub fn build(self) -> Result<AttestationService<T, E>, String> {
         Ok(AttestationService {
             inner: Arc::new(Inner {
@@ -76,6 +106,7 @@ impl<T: SlotClock + 'static, E: EthSpec> AttestationServiceBuilder<T, E> {
                 context: self
                     .context
                     .ok_or("Cannot build AttestationService without runtime_context")?,
+                duty_deadlines: self.duty_deadlines,
             }),
         })
     }
@@ -88,6 +119,7 @@ pub struct Inner<T, E: EthSpec> {
     slot_clock: T,
     beacon_nodes: Arc<BeaconNodeFallback<T, E>>,
     context: RuntimeContext<E>,
+    duty_deadlines: DutyDeadlines,
 }
 
 /// Attempts to produce attestations for all known validators 1/3rd of the way through each slot.
@@ -247,21 +279,35 @@ impl<T: SlotClock + 'static, E: EthSpec> AttestationService<T, E> {
             return Ok(());
         }
 
+        let mut attestation_timer = DutyTimer::new(
+            DutyKind::Attestation,
+            slot,
+            &self.duty_deadlines,
+            self.slot_clock.clone(),
+        );
+        attestation_timer.stage_complete(DutyStage::DutyFetch);
+
         // Step 1.
         //
         // Download, sign and publish an `Attestation` for each validator.
-        let attestation_opt = self
-            .produce_and_publish_attestations(slot, committee_index, &validator_duties)
-            .await
-            .map_err(move |e| {
-                crit!(
-                    log,
-                    "Error during attestation routine";
-                    "error" => format!("{:?}", e),
-                    "committee_index" => committee_index,
-                    "slot" => slot.as_u64(),
-                )
-            })?;
+        let attestation_result = self
+            .produce_and_publish_attestations(
+                slot,
+                committee_index,
+                &validator_duties,
+                &mut attestation_timer,
+            )
+            .await;
+        attestation_timer.log_timings(log);
+        let attestation_opt = attestation_result.map_err(move |e| {
+            crit!(
+                log,
+                "Error during attestation routine";
+                "error" => format!("{:?}", e),
+                "committee_index" => committee_index,
+                "slot" => slot.as_u64(),
+            )
+        })?;
 
         drop(attestations_timer);
 
@@ -281,16 +327,32 @@ impl<T: SlotClock + 'static, E: EthSpec> AttestationService<T, E> {
                 &[validator_metrics::AGGREGATES],
             );
 
+            let mut aggregate_timer = DutyTimer::new(
+                DutyKind::Aggregate,
+                slot,
+                &self.duty_deadlines,
+                self.slot_clock.clone(),
+            );
+            aggregate_timer.stage_complete(DutyStage::DutyFetch);
+
             // Then download, sign and publish a `SignedAggregateAndProof` for each
             // validator that is elected to aggregate for this `slot` and
             // `committee_index`.
-            self.produce_and_publish_aggregates(
-                &attestation_data,
-                committee_index,
-                &validator_duties,
-            )
-            .await
-            .map_err(move |e| {
+            let aggregate_result = self
+                .produce_and_publish_aggregates(
+                    &attestation_data,
+                    committee_index,
+                    &validator_duties,
+                    &mut aggregate_timer,
+                )
+                .await;
+            if validator_duties
+                .iter()
+                .any(|duty_and_proof| duty_and_proof.selection_proof.is_some())
+            {
+                aggregate_timer.log_timings(log);
+            }
+            aggregate_result.map_err(move |e| {
                 crit!(
                     log,
                     "Error during attestation routine";
@@ -321,6 +383,7 @@ impl<T: SlotClock + 'static, E: EthSpec> AttestationService<T, E> {
         slot: Slot,
         committee_index: CommitteeIndex,
         validator_duties: &[DutyAndProof],
+        timer: &mut DutyTimer<T>,
     ) -> Result<Option<AttestationData>, String> {
         let log = self.context.log();
 
@@ -344,11 +407,18 @@ impl<T: SlotClock + 'static, E: EthSpec> AttestationService<T, E> {
                 beacon_node
                     .get_validator_attestation_data(slot, committee_index)
                     .await
-                    .map_err(|e| format!("Failed to produce attestation data: {:?}", e))
                     .map(|result| result.data)
             })
             .await
-            .map_err(|e| e.to_string())?;
+            .map_err(|e| {
+                record_beacon_node_failures(DutyKind::Attestation, &e);
+                format!("Failed to produce attestation data: {}", e)
+            })?;
+        timer.stage_complete(DutyStage::DataFetch);
+
+        if timer.should_skip(DutyStage::Sign, log) {
+            return Ok(None);
+        }
 
         // Create futures to produce signed `Attestation` objects.
         let attestation_data_ref = &attestation_data;
@@ -418,6 +488,12 @@ impl<T: SlotClock + 'static, E: EthSpec> AttestationService<T, E> {
                     None
                 }
                 Err(e) => {
+                    record_signing_failure(
+                        &self.validator_store,
+                        DutyKind::Attestation,
+                        &duty.pubkey,
+                        &e,
+                    );
                     crit!(
                         log,
                         "Failed to sign attestation";
@@ -437,11 +513,18 @@ impl<T: SlotClock + 'static, E: EthSpec> AttestationService<T, E> {
             .into_iter()
             .flatten()
             .unzip();
+        timer.stage_complete(DutyStage::Sign);
 
         if attestations.is_empty() {
             warn!(log, "No attestations were published");
             return Ok(None);
         }
+
+        // The attestations are signed, so an aggregate may still be produced even if they are
+        // too late to publish.
+        if timer.should_skip(DutyStage::Publish, log) {
+            return Ok(Some(attestation_data));
+        }
         let fork_name = self
             .context
             .eth2_config
@@ -468,24 +551,30 @@ impl<T: SlotClock + 'static, E: EthSpec> AttestationService<T, E> {
             })
             .await
         {
-            Ok(()) => info!(
-                log,
-                "Successfully published attestations";
-                "count" => attestations.len(),
-                "validator_indices" => ?validator_indices,
-                "head_block" => ?attestation_data.beacon_block_root,
-                "committee_index" => attestation_data.index,
-                "slot" => attestation_data.slot.as_u64(),
-                "type" => "unaggregated",
-            ),
-            Err(e) => error!(
-                log,
-                "Unable to publish attestations";
-                "error" => %e,
-                "committee_index" => attestation_data.index,
-                "slot" => slot.as_u64(),
-                "type" => "unaggregated",
-            ),
+            Ok(()) => {
+                timer.stage_complete(DutyStage::Publish);
+                info!(
+                    log,
+                    "Successfully published attestations";
+                    "count" => attestations.len(),
+                    "validator_indices" => ?validator_indices,
+                    "head_block" => ?attestation_data.beacon_block_root,
+                    "committee_index" => attestation_data.index,
+                    "slot" => attestation_data.slot.as_u64(),
+                    "type" => "unaggregated",
+                )
+            }
+            Err(e) => {
+                record_beacon_node_failures(DutyKind::Attestation, &e);
+                error!(
+                    log,
+                    "Unable to publish attestations";
+                    "error" => %e,
+                    "committee_index" => attestation_data.index,
+                    "slot" => slot.as_u64(),
+                    "type" => "unaggregated",
+                )
+            }
         }
 
         Ok(Some(attestation_data))
@@ -509,6 +598,7 @@ impl<T: SlotClock + 'static, E: EthSpec> AttestationService<T, E> {
         attestation_data: &AttestationData,
         committee_index: CommitteeIndex,
         validator_duties: &[DutyAndProof],
+        timer: &mut DutyTimer<T>,
     ) -> Result<(), String> {
         let log = self.context.log();
 
@@ -520,6 +610,16 @@ impl<T: SlotClock + 'static, E: EthSpec> AttestationService<T, E> {
             return Ok(());
         }
 
+        if skip_for_circuit_breaker(&self.beacon_nodes, DutyKind::Aggregate) {
+            warn!(
+                log,
+                "Skipping aggregate due to beacon node failures";
+                "committee_index" => committee_index,
+                "slot" => attestation_data.slot.as_u64(),
+            );
+            return Ok(());
+        }
+
         let fork_name = self
             .context
             .eth2_config
@@ -541,10 +641,8 @@ impl<T: SlotClock + 'static, E: EthSpec> AttestationService<T, E> {
                             committee_index,
                         )
                         .await
-                        .map_err(|e| {
-                            format!("Failed to produce an aggregate attestation: {:?}", e)
-                        })?
-                        .ok_or_else(|| format!("No aggregate available for {:?}", attestation_data))
+                        .map_err(AggregateFetchError::Request)?
+                        .ok_or(AggregateFetchError::NotFound)
                         .map(|result| result.data)
                 } else {
                     beacon_node
@@ -553,15 +651,21 @@ impl<T: SlotClock + 'static, E: EthSpec> AttestationService<T, E> {
                             attestation_data.tree_hash_root(),
                         )
                         .await
-                        .map_err(|e| {
-                            format!("Failed to produce an aggregate attestation: {:?}", e)
-                        })?
-                        .ok_or_else(|| format!("No aggregate available for {:?}", attestation_data))
+                        .map_err(AggregateFetchError::Request)?
+                        .ok_or(AggregateFetchError::NotFound)
                         .map(|result| result.data)
                 }
             })
             .await
-            .map_err(|e| e.to_string())?;
+            .map_err(|e| {
+                record_beacon_node_failures(DutyKind::Aggregate, &e);
+                format!("Failed to produce an aggregate attestation: {}", e)
+            })?;
+        timer.stage_complete(DutyStage::DataFetch);
+
+        if timer.should_skip(DutyStage::Sign, log) {
+            return Ok(());
+        }
 
         // Create futures to produce the signed aggregated attestations.
         let signing_futures = validator_duties.iter().map(|duty_and_proof| async move {
@@ -595,6 +699,12 @@ impl<T: SlotClock + 'static, E: EthSpec> AttestationService<T, E> {
                     None
                 }
                 Err(e) => {
+                    record_signing_failure(
+                        &self.validator_store,
+                        DutyKind::Aggregate,
+                        &duty.pubkey,
+                        &e,
+                    );
                     crit!(
                         log,
                         "Failed to sign aggregate";
@@ -612,12 +722,13 @@ impl<T: SlotClock + 'static, E: EthSpec> AttestationService<T, E> {
             .into_iter()
             .flatten()
             .collect::<Vec<_>>();
+        timer.stage_complete(DutyStage::Sign);
 
-        if !signed_aggregate_and_proofs.is_empty() {
+        if !signed_aggregate_and_proofs.is_empty() && !timer.should_skip(DutyStage::Publish, log) {
             let signed_aggregate_and_proofs_slice = signed_aggregate_and_proofs.as_slice();
             match self
                 .beacon_nodes
-                .first_success(|beacon_node| async move {
+                .request(ApiTopic::Aggregates, |beacon_node| async move {
                     let _timer = validator_metrics::start_timer_vec(
                         &validator_metrics::ATTESTATION_SERVICE_TIMES,
                         &[validator_metrics::AGGREGATES_HTTP_POST],
@@ -640,6 +751,7 @@ impl<T: SlotClock + 'static, E: EthSpec> AttestationService<T, E> {
                 .await
             {
                 Ok(()) => {
+                    timer.stage_complete(DutyStage::Publish);
                     for signed_aggregate_and_proof in signed_aggregate_and_proofs {
                         let attestation = signed_aggregate_and_proof.message().aggregate();
                         info!(
@@ -655,6 +767,7 @@ impl<T: SlotClock + 'static, E: EthSpec> AttestationService<T, E> {
                     }
                 }
                 Err(e) => {
+                    record_beacon_node_failures(DutyKind::Aggregate, &e);
                     for signed_aggregate_and_proof in signed_aggregate_and_proofs {
                         let attestation = &signed_aggregate_and_proof.message().aggregate();
                         crit!(
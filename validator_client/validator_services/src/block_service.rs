@@ -1,3 +1,5 @@
+use crate::duty_deadlines::{DutyDeadlines, DutyKind, DutyStage, DutyTimer};
+use crate::duty_failures::{record_beacon_node_failure, record_signing_failure};
 use beacon_node_fallback::{ApiTopic, BeaconNodeFallback, Error as FallbackError, Errors};
 use bls::SignatureBytes;
 use environment::RuntimeContext;
@@ -52,6 +54,7 @@ pub struct BlockServiceBuilder<T, E: EthSpec> {
     context: Option<RuntimeContext<E>>,
     graffiti: Option<Graffiti>,
     graffiti_file: Option<GraffitiFile>,
+    duty_deadlines: DutyDeadlines,
 }
 
 impl<T: SlotClock + 'static, E: EthSpec> BlockServiceBuilder<T, E> {
@@ -64,6 +67,7 @@ impl<T: SlotClock + 'static, E: EthSpec> BlockServiceBuilder<T, E> {
             context: None,
             graffiti: None,
             graffiti_file: None,
+            duty_deadlines: DutyDeadlines::default(),
         }
     }
 
@@ -102,6 +106,11 @@ impl<T: SlotClock + 'static, E: EthSpec> BlockServiceBuilder<T, E> {
         self
     }
 
+    pub fn duty_deadlines(mut self, duty_deadlines: DutyDeadlines) -> Self {
+        self.duty_deadlines = duty_deadlines;
+        self
+    }
+
     pub fn build(self) -> Result<BlockService<T, E>, String> {
         Ok(BlockService {
             inner: Arc::new(Inner {
@@ -120,6 +129,7 @@ impl<T: SlotClock + 'static, E: EthSpec> BlockServiceBuilder<T, E> {
                 proposer_nodes: self.proposer_nodes,
                 graffiti: self.graffiti,
                 graffiti_file: self.graffiti_file,
+                duty_deadlines: self.duty_deadlines,
             }),
         })
     }
@@ -156,6 +166,8 @@ impl<T: SlotClock, E: EthSpec> ProposerFallback<T, E> {
     }
 
     // Try `func` on `self.beacon_nodes` first. If that doesn't work, try `self.proposer_nodes`.
+    //
+    // Within each set, nodes whose execution engine is offline or optimistic are tried last.
     pub async fn request_proposers_last<F, O, Err, R>(&self, func: F) -> Result<O, Errors<Err>>
     where
         F: Fn(BeaconNodeHttpClient) -> R + Clone,
@@ -163,7 +175,10 @@ impl<T: SlotClock, E: EthSpec> ProposerFallback<T, E> {
         Err: Debug,
     {
         // Try running `func` on the non-proposer beacon nodes.
-        let beacon_nodes_result = self.beacon_nodes.first_success(func.clone()).await;
+        let beacon_nodes_result = self
+            .beacon_nodes
+            .first_success_execution_validated(func.clone())
+            .await;
 
         match (beacon_nodes_result, &self.proposer_nodes) {
             // The non-proposer node call succeed, return the result.
@@ -171,7 +186,9 @@ impl<T: SlotClock, E: EthSpec> ProposerFallback<T, E> {
             // The non-proposer node call failed, but we don't have any proposer nodes. Return an error.
             (Err(e), None) => Err(e),
             // The non-proposer node call failed, try the same call on the proposer nodes.
-            (Err(_), Some(proposer_nodes)) => proposer_nodes.first_success(func).await,
+            (Err(_), Some(proposer_nodes)) => {
+                proposer_nodes.first_success_execution_validated(func).await
+            }
         }
     }
 }
@@ -185,6 +202,7 @@ pub struct Inner<T, E: EthSpec> {
     context: RuntimeContext<E>,
     graffiti: Option<Graffiti>,
     graffiti_file: Option<GraffitiFile>,
+    duty_deadlines: DutyDeadlines,
 }
 
 /// Attempts to produce attestations for any block producer(s) at the start of the epoch.
@@ -331,8 +349,16 @@ impl<T: SlotClock + 'static, E: EthSpec> BlockService<T, E> {
         graffiti: Option<Graffiti>,
         validator_pubkey: &PublicKeyBytes,
         unsigned_block: UnsignedBlock<E>,
+        timer: &mut DutyTimer<T>,
     ) -> Result<(), BlockError> {
         let log = self.context.log();
+
+        // A block which is published too late into the slot is likely to be orphaned and may cause
+        // a re-org, so it is better not to sign it at all.
+        if skip_late_proposal(timer, DutyStage::Sign, slot, validator_pubkey, log) {
+            return Ok(());
+        }
+
         let signing_timer = validator_metrics::start_timer(&validator_metrics::BLOCK_SIGNING_TIMES);
 
         let res = match unsigned_block {
@@ -366,15 +392,26 @@ impl<T: SlotClock + 'static, E: EthSpec> BlockService<T, E> {
                 return Ok(());
             }
             Err(e) => {
+                record_signing_failure(
+                    &self.validator_store,
+                    DutyKind::BlockProposal,
+                    validator_pubkey,
+                    &e,
+                );
                 return Err(BlockError::Recoverable(format!(
                     "Unable to sign block: {:?}",
                     e
-                )))
+                )));
             }
         };
 
         let signing_time_ms =
             Duration::from_secs_f64(signing_timer.map_or(0.0, |t| t.stop_and_record())).as_millis();
+        timer.stage_complete(DutyStage::Sign);
+
+        if skip_late_proposal(timer, DutyStage::Publish, slot, validator_pubkey, log) {
+            return Ok(());
+        }
 
         info!(
             log,
@@ -394,6 +431,7 @@ impl<T: SlotClock + 'static, E: EthSpec> BlockService<T, E> {
                     .await
             })
             .await?;
+        timer.stage_complete(DutyStage::Publish);
 
         info!(
             log,
@@ -438,10 +476,16 @@ impl<T: SlotClock + 'static, E: EthSpec> BlockService<T, E> {
                 return Ok(());
             }
             Err(e) => {
+                record_signing_failure(
+                    &self.validator_store,
+                    DutyKind::BlockProposal,
+                    &validator_pubkey,
+                    &e,
+                );
                 return Err(BlockError::Recoverable(format!(
                     "Unable to produce randao reveal signature: {:?}",
                     e
-                )))
+                )));
             }
         };
 
@@ -460,6 +504,12 @@ impl<T: SlotClock + 'static, E: EthSpec> BlockService<T, E> {
             beacon_nodes: self.beacon_nodes.clone(),
             proposer_nodes: self.proposer_nodes.clone(),
         };
+        let mut timer = DutyTimer::new(
+            DutyKind::BlockProposal,
+            slot,
+            &self.duty_deadlines,
+            self.slot_clock.as_ref().clone(),
+        );
 
         info!(
             log,
@@ -495,18 +545,21 @@ impl<T: SlotClock + 'static, E: EthSpec> BlockService<T, E> {
                 })
             })
             .await?;
+        timer.stage_complete(DutyStage::DataFetch);
 
-        self_ref
+        let result = self_ref
             .sign_and_publish_block(
                 proposer_fallback,
                 slot,
                 graffiti,
                 &validator_pubkey,
                 unsigned_block,
+                &mut timer,
             )
-            .await?;
+            .await;
+        timer.log_timings(log);
 
-        Ok(())
+        result
     }
 
     async fn publish_signed_block_contents(
@@ -559,6 +612,7 @@ impl<T: SlotClock + 'static, E: EthSpec> BlockService<T, E> {
             )
             .await
             .map_err(|e| {
+                record_beacon_node_failure(DutyKind::BlockProposal, &e);
                 BlockError::Recoverable(format!(
                     "Error from beacon node when producing block: {:?}",
                     e
@@ -662,6 +716,31 @@ impl<E: EthSpec> SignedBlock<E> {
     }
 }
 
+/// Returns `true` if the block proposal should be abandoned because `stage` would complete too
+/// late into the slot, logging the skipped proposal.
+fn skip_late_proposal<T: SlotClock>(
+    timer: &DutyTimer<T>,
+    stage: DutyStage,
+    slot: Slot,
+    validator_pubkey: &PublicKeyBytes,
+    log: &Logger,
+) -> bool {
+    if !timer.should_skip(stage, log) {
+        return false;
+    }
+
+    warn!(
+        log,
+        "Skipped block proposal";
+        "reason" => "too late in the slot",
+        "stage" => stage.as_str(),
+        "validator" => ?validator_pubkey,
+        "slot" => slot.as_u64(),
+        "info" => "a late block is likely to be orphaned, check the timings of the beacon node",
+    );
+    true
+}
+
 fn handle_block_post_error(err: eth2::Error, slot: Slot, log: &Logger) -> Result<(), BlockError> {
     // Handle non-200 success codes.
     if let Some(status) = err.status() {
@@ -683,7 +762,110 @@ fn handle_block_post_error(err: eth2::Error, slot: Slot, log: &Logger) -> Result
             return Ok(());
         }
     }
+    record_beacon_node_failure(DutyKind::BlockProposal, &err);
     Err(BlockError::Irrecoverable(format!(
         "Error from beacon node when publishing block: {err:?}",
     )))
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::duty_deadlines::DutyDeadline;
+    use slot_clock::ManualSlotClock;
+
+    fn skipped_proposals(stage: DutyStage) -> u64 {
+        validator_metrics::get_int_counter(
+            &validator_metrics::DUTY_SKIPPED_LATE_TOTAL,
+            &[DutyKind::BlockProposal.as_str(), stage.as_str()],
+        )
+        .map_or(0, |counter| counter.get())
+    }
+
+    #[test]
+    fn skips_proposals_past_their_deadline() {
+        let log = slog::Logger::root(slog::Discard, slog::o!());
+        let clock = ManualSlotClock::new(
+            Slot::new(0),
+            Duration::from_secs(0),
+            Duration::from_secs(12),
+        );
+        let mut deadlines = DutyDeadlines::default();
+        deadlines.block_proposal = DutyDeadline {
+            sign_by: Some(Duration::from_secs(3)),
+            publish_by: Some(Duration::from_secs(4)),
+            skip_late: true,
+        };
+        let slot = Slot::new(1);
+        let pubkey = PublicKeyBytes::empty();
+        let timer = DutyTimer::new(DutyKind::BlockProposal, slot, &deadlines, clock.clone());
+        let skipped_signing = skipped_proposals(DutyStage::Sign);
+        let skipped_publishing = skipped_proposals(DutyStage::Publish);
+
+        // Early in the slot the proposal goes ahead.
+        clock.set_current_time(Duration::from_secs(12 + 2));
+        assert!(!skip_late_proposal(
+            &timer,
+            DutyStage::Sign,
+            slot,
+            &pubkey,
+            &log
+        ));
+        assert!(!skip_late_proposal(
+            &timer,
+            DutyStage::Publish,
+            slot,
+            &pubkey,
+            &log
+        ));
+
+        // A block signed late is not published.
+        clock.set_current_time(Duration::from_secs(12 + 5));
+        assert!(skip_late_proposal(
+            &timer,
+            DutyStage::Publish,
+            slot,
+            &pubkey,
+            &log
+        ));
+        assert!(skip_late_proposal(
+            &timer,
+            DutyStage::Sign,
+            slot,
+            &pubkey,
+            &log
+        ));
+
+        assert_eq!(skipped_proposals(DutyStage::Sign), skipped_signing + 1);
+        assert_eq!(
+            skipped_proposals(DutyStage::Publish),
+            skipped_publishing + 1
+        );
+    }
+
+    #[test]
+    fn does_not_skip_proposals_by_default() {
+        let log = slog::Logger::root(slog::Discard, slog::o!());
+        let clock = ManualSlotClock::new(
+            Slot::new(0),
+            Duration::from_secs(0),
+            Duration::from_secs(12),
+        );
+        let slot = Slot::new(1);
+        let timer = DutyTimer::new(
+            DutyKind::BlockProposal,
+            slot,
+            &DutyDeadlines::default(),
+            clock.clone(),
+        );
+
+        clock.set_current_time(Duration::from_secs(12 + 11));
+        assert!(!skip_late_proposal(
+            &timer,
+            DutyStage::Sign,
+            slot,
+            &PublicKeyBytes::empty(),
+            &log
+        ));
+    }
+}
@@ -35,7 +35,8 @@ const HISTORICAL_DUTIES_EPOCHS: u64 = 2;
 
 /// Compute attestation selection proofs this many slots before they are required.
 ///
-/// At start-up selection proofs will be computed with less lookahead out of necessity.
+/// At start-up selection proofs will be computed with less lookahead out of necessity. This value
+/// is ignored if `DutiesService::precompute_selection_proofs` is set.
 const SELECTION_PROOF_SLOT_LOOKAHEAD: u64 = 8;
 
 /// The attestation selection proof lookahead for those running with the --distributed flag.
@@ -230,6 +231,11 @@ pub struct DutiesService<T, E: EthSpec> {
     pub enable_high_validator_count_metrics: bool,
     /// If this validator is running in distributed mode.
     pub distributed: bool,
+    /// If true, compute selection proofs for all known duties as soon as possible, rather than
+    /// `SELECTION_PROOF_SLOT_LOOKAHEAD` slots before they are required.
+    pub precompute_selection_proofs: bool,
+    /// The maximum number of selection proofs which may be signed concurrently.
+    pub selection_proof_signing_concurrency: usize,
 }
 
 impl<T: SlotClock + 'static, E: EthSpec> DutiesService<T, E> {
@@ -328,6 +334,18 @@ impl<T: SlotClock + 'static, E: EthSpec> DutiesService<T, E> {
             .collect()
     }
 
+    /// Returns the number of slots prior to a duty that its selection proof should be computed, or
+    /// `None` if selection proofs should be computed as soon as the duty is known.
+    fn selection_proof_lookahead(&self) -> Option<u64> {
+        if self.distributed {
+            Some(SELECTION_PROOF_SLOT_LOOKAHEAD_DVT)
+        } else if self.precompute_selection_proofs {
+            None
+        } else {
+            Some(SELECTION_PROOF_SLOT_LOOKAHEAD)
+        }
+    }
+
     /// Returns `true` if we should collect per validator metrics and `false` otherwise.
     pub fn per_validator_metrics(&self) -> bool {
         self.enable_high_validator_count_metrics
@@ -1041,10 +1059,30 @@ async fn post_validator_duties_attester<T: SlotClock + 'static, E: EthSpec>(
         .map_err(|e| Error::FailedToDownloadAttesters(e.to_string()))
 }
 
+/// Remove and return the duties from `duties_by_slot` whose selection proofs should be computed at
+/// `current_slot`, given the `selection_lookahead` returned by
+/// `DutiesService::selection_proof_lookahead`.
+fn take_selection_proof_batch<V>(
+    duties_by_slot: &mut BTreeMap<Slot, V>,
+    current_slot: Slot,
+    selection_lookahead: Option<u64>,
+) -> BTreeMap<Slot, V> {
+    if let Some(selection_lookahead) = selection_lookahead {
+        let lookahead_slot = current_slot + selection_lookahead;
+        let mut relevant_duties = duties_by_slot.split_off(&lookahead_slot);
+        std::mem::swap(&mut relevant_duties, duties_by_slot);
+        relevant_duties
+    } else {
+        std::mem::take(duties_by_slot)
+    }
+}
+
 /// Compute the attestation selection proofs for the `duties` and add them to the `attesters` map.
 ///
-/// Duties are computed in batches each slot. If a re-org is detected then the process will
-/// terminate early as it is assumed the selection proofs from `duties` are no longer relevant.
+/// Duties are computed in batches each slot, with up to `selection_proof_signing_concurrency`
+/// signatures in flight at once. When `precompute_selection_proofs` is set, all `duties` are
+/// computed in the first batch. If a re-org is detected then the process will terminate early as
+/// it is assumed the selection proofs from `duties` are no longer relevant.
 async fn fill_in_selection_proofs<T: SlotClock + 'static, E: EthSpec>(
     duties_service: Arc<DutiesService<T, E>>,
     duties: Vec<AttesterData>,
@@ -1072,16 +1110,10 @@ async fn fill_in_selection_proofs<T: SlotClock + 'static, E: EthSpec>(
                 continue;
             };
 
-            let selection_lookahead = if duties_service.distributed {
-                SELECTION_PROOF_SLOT_LOOKAHEAD_DVT
-            } else {
-                SELECTION_PROOF_SLOT_LOOKAHEAD
-            };
+            let selection_lookahead = duties_service.selection_proof_lookahead();
 
-            let lookahead_slot = current_slot + selection_lookahead;
-
-            let mut relevant_duties = duties_by_slot.split_off(&lookahead_slot);
-            std::mem::swap(&mut relevant_duties, &mut duties_by_slot);
+            let relevant_duties =
+                take_selection_proof_batch(&mut duties_by_slot, current_slot, selection_lookahead);
 
             let batch_size = relevant_duties.values().map(Vec::len).sum::<usize>();
 
@@ -1094,9 +1126,10 @@ async fn fill_in_selection_proofs<T: SlotClock + 'static, E: EthSpec>(
                 &[validator_metrics::ATTESTATION_SELECTION_PROOFS],
             );
 
-            // Sign selection proofs (serially).
+            // Sign selection proofs, limiting concurrency so that a remote signer isn't
+            // overwhelmed.
             let duty_and_proof_results = stream::iter(relevant_duties.into_values().flatten())
-                .then(|duty| async {
+                .map(|duty| async {
                     let opt_selection_proof = make_selection_proof(
                         &duty,
                         &duties_service.validator_store,
@@ -1105,6 +1138,7 @@ async fn fill_in_selection_proofs<T: SlotClock + 'static, E: EthSpec>(
                     .await?;
                     Ok((duty, opt_selection_proof))
                 })
+                .buffered(duties_service.selection_proof_signing_concurrency.max(1))
                 .collect::<Vec<_>>()
                 .await;
 
@@ -1185,7 +1219,7 @@ async fn fill_in_selection_proofs<T: SlotClock + 'static, E: EthSpec>(
                 log,
                 "Computed attestation selection proofs";
                 "batch_size" => batch_size,
-                "lookahead_slot" => lookahead_slot,
+                "lookahead_slots" => ?selection_lookahead,
                 "time_taken_ms" => time_taken_ms
             );
         } else {
@@ -1471,4 +1505,49 @@ mod test {
         assert_eq!(subscription_slots.slots.len(), 1);
         assert!(subscription_slots.should_send_subscription_at(current_slot + 1),);
     }
+
+    fn duties_by_slot(slots: impl IntoIterator<Item = u64>) -> BTreeMap<Slot, Vec<u64>> {
+        slots
+            .into_iter()
+            .map(|slot| (Slot::new(slot), vec![slot]))
+            .collect()
+    }
+
+    #[test]
+    fn selection_proof_batches_respect_lookahead() {
+        let mut duties = duties_by_slot(10..50);
+
+        // Only duties within the lookahead of the current slot are computed.
+        let batch = take_selection_proof_batch(
+            &mut duties,
+            Slot::new(10),
+            Some(SELECTION_PROOF_SLOT_LOOKAHEAD),
+        );
+        assert_eq!(
+            batch,
+            duties_by_slot(10..10 + SELECTION_PROOF_SLOT_LOOKAHEAD)
+        );
+        assert_eq!(
+            duties,
+            duties_by_slot(10 + SELECTION_PROOF_SLOT_LOOKAHEAD..50)
+        );
+
+        // The next slot's batch contains only the duties which have come within the lookahead.
+        let batch = take_selection_proof_batch(
+            &mut duties,
+            Slot::new(11),
+            Some(SELECTION_PROOF_SLOT_LOOKAHEAD),
+        );
+        assert_eq!(batch, duties_by_slot([10 + SELECTION_PROOF_SLOT_LOOKAHEAD]));
+    }
+
+    #[test]
+    fn selection_proof_batches_precompute_all_duties() {
+        let mut duties = duties_by_slot(10..50);
+
+        // Without a lookahead all known duties are computed in the first batch.
+        let batch = take_selection_proof_batch(&mut duties, Slot::new(10), None);
+        assert_eq!(batch, duties_by_slot(10..50));
+        assert!(duties.is_empty());
+    }
 }
@@ -0,0 +1,308 @@
+//! Deadline budgets and stage timings for time-sensitive validator duties.
+//!
+//! Each duty runs through the same stages: its duties are read, the data to sign is fetched from
+//! the beacon node, the data is signed and the result is published. A `DutyTimer` records how far
+//! into the slot each stage completed and checks the configured `DutyDeadline` before signing and
+//! publishing, optionally skipping stages which would be too late to be useful.
+use serde::{Deserialize, Serialize};
+use slog::{debug, warn, Logger};
+use slot_clock::SlotClock;
+use std::fmt;
+use std::str::FromStr;
+use std::time::Duration;
+use types::Slot;
+
+/// A time-sensitive validator duty.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DutyKind {
+    Attestation,
+    Aggregate,
+    SyncCommitteeMessage,
+    SyncCommitteeContribution,
+    BlockProposal,
+}
+
+impl DutyKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            DutyKind::Attestation => "attestation",
+            DutyKind::Aggregate => "aggregate",
+            DutyKind::SyncCommitteeMessage => "sync_committee_message",
+            DutyKind::SyncCommitteeContribution => "sync_committee_contribution",
+            DutyKind::BlockProposal => "block_proposal",
+        }
+    }
+}
+
+impl fmt::Display for DutyKind {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for DutyKind {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "attestation" => Ok(DutyKind::Attestation),
+            "aggregate" => Ok(DutyKind::Aggregate),
+            "sync_committee_message" => Ok(DutyKind::SyncCommitteeMessage),
+            "sync_committee_contribution" => Ok(DutyKind::SyncCommitteeContribution),
+            "block_proposal" => Ok(DutyKind::BlockProposal),
+            other => Err(format!("Unknown duty type: {}", other)),
+        }
+    }
+}
+
+/// A stage in the execution of a duty.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DutyStage {
+    DutyFetch,
+    DataFetch,
+    Sign,
+    Publish,
+}
+
+impl DutyStage {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            DutyStage::DutyFetch => "duty_fetch",
+            DutyStage::DataFetch => "data_fetch",
+            DutyStage::Sign => "sign",
+            DutyStage::Publish => "publish",
+        }
+    }
+}
+
+/// The deadlines for a single duty, as offsets from the start of the duty's slot.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub struct DutyDeadline {
+    /// Signing should be complete by this point in the slot.
+    pub sign_by: Option<Duration>,
+    /// Publishing should be complete by this point in the slot.
+    pub publish_by: Option<Duration>,
+    /// Skip signing or publishing if its deadline has already passed when the stage starts.
+    pub skip_late: bool,
+}
+
+impl DutyDeadline {
+    fn for_stage(&self, stage: DutyStage) -> Option<Duration> {
+        match stage {
+            DutyStage::Sign => self.sign_by,
+            DutyStage::Publish => self.publish_by,
+            DutyStage::DutyFetch | DutyStage::DataFetch => None,
+        }
+    }
+}
+
+/// The deadlines for every `DutyKind`. By default no deadlines are set and no duty is skipped.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub struct DutyDeadlines {
+    pub attestation: DutyDeadline,
+    pub aggregate: DutyDeadline,
+    pub sync_committee_message: DutyDeadline,
+    pub sync_committee_contribution: DutyDeadline,
+    pub block_proposal: DutyDeadline,
+}
+
+impl DutyDeadlines {
+    pub fn get(&self, kind: DutyKind) -> &DutyDeadline {
+        match kind {
+            DutyKind::Attestation => &self.attestation,
+            DutyKind::Aggregate => &self.aggregate,
+            DutyKind::SyncCommitteeMessage => &self.sync_committee_message,
+            DutyKind::SyncCommitteeContribution => &self.sync_committee_contribution,
+            DutyKind::BlockProposal => &self.block_proposal,
+        }
+    }
+
+    pub fn get_mut(&mut self, kind: DutyKind) -> &mut DutyDeadline {
+        match kind {
+            DutyKind::Attestation => &mut self.attestation,
+            DutyKind::Aggregate => &mut self.aggregate,
+            DutyKind::SyncCommitteeMessage => &mut self.sync_committee_message,
+            DutyKind::SyncCommitteeContribution => &mut self.sync_committee_contribution,
+            DutyKind::BlockProposal => &mut self.block_proposal,
+        }
+    }
+}
+
+/// Records the timing of each stage of a single duty against its deadlines.
+pub struct DutyTimer<T> {
+    kind: DutyKind,
+    slot: Slot,
+    deadline: DutyDeadline,
+    slot_clock: T,
+    stages: Vec<(DutyStage, Duration)>,
+}
+
+impl<T: SlotClock> DutyTimer<T> {
+    pub fn new(kind: DutyKind, slot: Slot, deadlines: &DutyDeadlines, slot_clock: T) -> Self {
+        Self {
+            kind,
+            slot,
+            deadline: *deadlines.get(kind),
+            slot_clock,
+            stages: Vec::with_capacity(4),
+        }
+    }
+
+    /// The time elapsed since the start of the duty's slot.
+    fn slot_delay(&self) -> Option<Duration> {
+        let slot_start = self.slot_clock.start_of(self.slot)?;
+        Some(self.slot_clock.now_duration()?.saturating_sub(slot_start))
+    }
+
+    /// Returns `true` if `stage` should be skipped because its deadline has already passed.
+    ///
+    /// Should be called just before starting `stage`.
+    pub fn should_skip(&self, stage: DutyStage, log: &Logger) -> bool {
+        let (Some(deadline), Some(delay)) = (self.deadline.for_stage(stage), self.slot_delay())
+        else {
+            return false;
+        };
+        if delay <= deadline || !self.deadline.skip_late {
+            return false;
+        }
+
+        let labels = &[self.kind.as_str(), stage.as_str()];
+        validator_metrics::inc_counter_vec(&validator_metrics::DUTY_SKIPPED_LATE_TOTAL, labels);
+        warn!(
+            log,
+            "Skipping late duty stage";
+            "duty" => %self.kind,
+            "stage" => stage.as_str(),
+            "slot" => self.slot,
+            "slot_delay_ms" => delay.as_millis(),
+            "deadline_ms" => deadline.as_millis(),
+        );
+        true
+    }
+
+    /// Record that `stage` has completed.
+    pub fn stage_complete(&mut self, stage: DutyStage) {
+        let Some(delay) = self.slot_delay() else {
+            return;
+        };
+        let labels = &[self.kind.as_str(), stage.as_str()];
+        validator_metrics::observe_timer_vec(
+            &validator_metrics::DUTY_STAGE_SLOT_DELAY,
+            labels,
+            delay,
+        );
+        if self
+            .deadline
+            .for_stage(stage)
+            .is_some_and(|deadline| delay > deadline)
+        {
+            validator_metrics::inc_counter_vec(
+                &validator_metrics::DUTY_DEADLINE_MISSED_TOTAL,
+                labels,
+            );
+        }
+        self.stages.push((stage, delay));
+    }
+
+    /// Log the time into the slot at which each completed stage finished.
+    pub fn log_timings(&self, log: &Logger) {
+        let stage_ms = |stage| {
+            self.stages
+                .iter()
+                .find(|(s, _)| *s == stage)
+                .map(|(_, delay)| delay.as_millis())
+        };
+        debug!(
+            log,
+            "Duty timings";
+            "duty" => %self.kind,
+            "slot" => self.slot,
+            "duty_fetch_ms" => stage_ms(DutyStage::DutyFetch),
+            "data_fetch_ms" => stage_ms(DutyStage::DataFetch),
+            "sign_ms" => stage_ms(DutyStage::Sign),
+            "publish_ms" => stage_ms(DutyStage::Publish),
+            "sign_by_ms" => self.deadline.sign_by.map(|d| d.as_millis()),
+            "publish_by_ms" => self.deadline.publish_by.map(|d| d.as_millis()),
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use slot_clock::{ManualSlotClock, SlotClock};
+
+    fn slot_clock() -> ManualSlotClock {
+        ManualSlotClock::new(
+            Slot::new(0),
+            Duration::from_secs(0),
+            Duration::from_secs(12),
+        )
+    }
+
+    fn logger() -> Logger {
+        slog::Logger::root(slog::Discard, slog::o!())
+    }
+
+    #[test]
+    fn skips_only_late_stages_when_enabled() {
+        let clock = slot_clock();
+        let mut deadlines = DutyDeadlines::default();
+        *deadlines.get_mut(DutyKind::Attestation) = DutyDeadline {
+            sign_by: Some(Duration::from_secs(6)),
+            publish_by: Some(Duration::from_secs(8)),
+            skip_late: true,
+        };
+        let timer = DutyTimer::new(
+            DutyKind::Attestation,
+            Slot::new(1),
+            &deadlines,
+            clock.clone(),
+        );
+
+        clock.set_current_time(Duration::from_secs(12 + 7));
+        assert!(timer.should_skip(DutyStage::Sign, &logger()));
+        assert!(!timer.should_skip(DutyStage::Publish, &logger()));
+        assert!(!timer.should_skip(DutyStage::DataFetch, &logger()));
+
+        // Other duties have no deadlines.
+        let timer = DutyTimer::new(DutyKind::Aggregate, Slot::new(1), &deadlines, clock.clone());
+        assert!(!timer.should_skip(DutyStage::Sign, &logger()));
+    }
+
+    #[test]
+    fn does_not_skip_without_skip_late() {
+        let clock = slot_clock();
+        let mut deadlines = DutyDeadlines::default();
+        deadlines.attestation.sign_by = Some(Duration::from_secs(1));
+        let mut timer = DutyTimer::new(
+            DutyKind::Attestation,
+            Slot::new(0),
+            &deadlines,
+            clock.clone(),
+        );
+
+        clock.set_current_time(Duration::from_secs(2));
+        assert!(!timer.should_skip(DutyStage::Sign, &logger()));
+        timer.stage_complete(DutyStage::Sign);
+        assert_eq!(
+            timer.stages,
+            vec![(DutyStage::Sign, Duration::from_secs(2))]
+        );
+    }
+
+    #[test]
+    fn duty_kind_round_trip() {
+        for kind in [
+            DutyKind::Attestation,
+            DutyKind::Aggregate,
+            DutyKind::SyncCommitteeMessage,
+            DutyKind::SyncCommitteeContribution,
+            DutyKind::BlockProposal,
+        ] {
+            assert_eq!(DutyKind::from_str(kind.as_str()), Ok(kind));
+        }
+        assert!(DutyKind::from_str("block").is_err());
+    }
+}
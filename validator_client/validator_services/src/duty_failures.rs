@@ -0,0 +1,155 @@
+//! Records failed validator duties in the `vc_duty_failures_total` metric.
+//!
+//! Failures are labelled by duty, by the signer involved and by reason, so that an operator can
+//! tell whether a remote signer, the slashing protection database or the beacon node is the layer
+//! which is failing. Each failed signature and each failed request to a beacon node is counted, so
+//! a duty which is retried on several beacon nodes may be counted more than once.
+//!
+//! Non-critical duties skipped while the primary beacon node is failing are recorded in
+//! `vc_duty_skipped_circuit_breaker_total`.
+use crate::duty_deadlines::DutyKind;
+use beacon_node_fallback::{
+    request_failure_reason, BeaconNodeFallback, Errors, RequestFailureReason,
+};
+use slot_clock::SlotClock;
+use types::{EthSpec, PublicKeyBytes};
+use validator_store::{Error as ValidatorStoreError, ValidatorStore};
+
+/// Record that `validator_pubkey` failed to sign the message of `duty`.
+pub fn record_signing_failure<T: SlotClock + 'static, E: EthSpec>(
+    validator_store: &ValidatorStore<T, E>,
+    duty: DutyKind,
+    validator_pubkey: &PublicKeyBytes,
+    error: &ValidatorStoreError,
+) {
+    let signer = validator_store.signer_label(validator_pubkey);
+    validator_metrics::inc_counter_vec(
+        &validator_metrics::DUTY_FAILURES_TOTAL,
+        &[duty.as_str(), &signer, error.failure_reason()],
+    );
+}
+
+/// Record the failed requests of a call to the beacon nodes for `duty`.
+pub fn record_beacon_node_failures<T: RequestFailureReason>(duty: DutyKind, errors: &Errors<T>) {
+    for reason in errors.failure_reasons() {
+        inc_beacon_node_failure(duty, reason);
+    }
+}
+
+/// Record a single failed request to a beacon node for `duty`.
+pub fn record_beacon_node_failure(duty: DutyKind, error: &eth2::Error) {
+    inc_beacon_node_failure(duty, request_failure_reason(error));
+}
+
+fn inc_beacon_node_failure(duty: DutyKind, reason: &str) {
+    validator_metrics::inc_counter_vec(
+        &validator_metrics::DUTY_FAILURES_TOTAL,
+        &[duty.as_str(), validator_metrics::NO_SIGNER, reason],
+    );
+}
+
+/// Returns `true` if the non-critical `duty` should be skipped because the aggregation circuit
+/// breaker of `beacon_nodes` is open, recording the skipped duty.
+pub fn skip_for_circuit_breaker<T: SlotClock, E: EthSpec>(
+    beacon_nodes: &BeaconNodeFallback<T, E>,
+    duty: DutyKind,
+) -> bool {
+    if !beacon_nodes.aggregation_circuit_open() {
+        return false;
+    }
+
+    validator_metrics::inc_counter_vec(
+        &validator_metrics::DUTY_SKIPPED_CIRCUIT_BREAKER_TOTAL,
+        &[duty.as_str()],
+    );
+    true
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use beacon_node_fallback::circuit_breaker::CircuitBreakerConfig;
+    use beacon_node_fallback::{CandidateBeaconNode, Config};
+    use eth2::{BeaconNodeHttpClient, SensitiveUrl, Timeouts};
+    use slot_clock::TestingSlotClock;
+    use std::sync::Arc;
+    use std::time::Duration;
+    use types::MainnetEthSpec;
+
+    type E = MainnetEthSpec;
+
+    fn skipped_duties(duty: DutyKind) -> u64 {
+        validator_metrics::get_int_counter(
+            &validator_metrics::DUTY_SKIPPED_CIRCUIT_BREAKER_TOTAL,
+            &[duty.as_str()],
+        )
+        .map_or(0, |counter| counter.get())
+    }
+
+    #[tokio::test]
+    async fn skips_aggregation_duties_while_the_primary_beacon_node_fails() {
+        let candidates = (0..2)
+            .map(|index| {
+                let beacon_node = BeaconNodeHttpClient::new(
+                    SensitiveUrl::parse(&format!("http://example_{index}.com")).unwrap(),
+                    Timeouts::set_all(Duration::from_secs(1)),
+                );
+                CandidateBeaconNode::<E>::new(beacon_node, index)
+            })
+            .collect::<Vec<_>>();
+        let primary = candidates[0].beacon_node.clone();
+        let config = Config {
+            aggregation_circuit_breaker: Some(CircuitBreakerConfig {
+                failure_threshold: 2,
+                window: 2,
+                cooldown: Duration::from_secs(60),
+            }),
+            ..Config::default()
+        };
+        let beacon_nodes = BeaconNodeFallback::<TestingSlotClock, E>::new(
+            candidates,
+            config,
+            vec![],
+            Arc::new(E::default_spec()),
+            slog::Logger::root(slog::Discard, slog::o!()),
+        );
+        let skipped_aggregates = skipped_duties(DutyKind::Aggregate);
+        let skipped_contributions = skipped_duties(DutyKind::SyncCommitteeContribution);
+
+        // Every request to the primary beacon node fails, and is answered by the secondary one.
+        let request = || {
+            beacon_nodes.first_success(|beacon_node| {
+                let failed = beacon_node == primary;
+                async move {
+                    if failed {
+                        Err("primary beacon node unavailable")
+                    } else {
+                        Ok(())
+                    }
+                }
+            })
+        };
+
+        assert!(request().await.is_ok());
+        assert!(!skip_for_circuit_breaker(
+            &beacon_nodes,
+            DutyKind::Aggregate
+        ));
+
+        // Sustained failures open the breaker, after which aggregation duties are skipped while
+        // other requests, such as those for attestations, are still made.
+        assert!(request().await.is_ok());
+        assert!(skip_for_circuit_breaker(&beacon_nodes, DutyKind::Aggregate));
+        assert!(skip_for_circuit_breaker(
+            &beacon_nodes,
+            DutyKind::SyncCommitteeContribution
+        ));
+        assert!(request().await.is_ok());
+
+        assert_eq!(skipped_duties(DutyKind::Aggregate), skipped_aggregates + 1);
+        assert_eq!(
+            skipped_duties(DutyKind::SyncCommitteeContribution),
+            skipped_contributions + 1
+        );
+    }
+}
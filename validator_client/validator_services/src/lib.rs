@@ -1,6 +1,9 @@
 pub mod attestation_service;
 pub mod block_service;
 pub mod duties_service;
+pub mod duty_deadlines;
+pub mod duty_failures;
 pub mod preparation_service;
+pub mod signer_health_service;
 pub mod sync;
 pub mod sync_committee_service;
@@ -0,0 +1,58 @@
+//! Periodically probes the Web3Signer instances used by the validator client.
+//!
+//! The validators of a signer which fails a probe are excluded from duties until the signer
+//! recovers, rather than producing a stream of signing errors for every duty.
+use environment::RuntimeContext;
+use futures::future::join_all;
+use slog::debug;
+use slot_clock::SlotClock;
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::Instant;
+use tokio::time::{sleep, Duration};
+use types::EthSpec;
+use validator_store::ValidatorStore;
+
+/// The interval at which unhealthy signers are probed, so that their validators are re-enabled
+/// promptly. Healthy signers are probed once per slot.
+const UNHEALTHY_PROBE_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Starts a service that probes each Web3Signer instance and records its health in the
+/// `validator_store`.
+pub fn start_update_service<T: SlotClock + 'static, E: EthSpec>(
+    context: RuntimeContext<E>,
+    validator_store: Arc<ValidatorStore<T, E>>,
+    slot_clock: T,
+) {
+    let log = context.log().clone();
+
+    let future = async move {
+        let mut last_probed = HashMap::<String, Instant>::new();
+
+        loop {
+            let signers = validator_store.web3_signer_validators();
+            last_probed.retain(|url, _| signers.contains_key(url));
+
+            let mut probes = vec![];
+            for (url, validators) in signers {
+                let due = !validator_store.is_web3_signer_healthy(&url)
+                    || last_probed.get(&url).map_or(true, |probed| {
+                        probed.elapsed() >= slot_clock.slot_duration()
+                    });
+                if due {
+                    last_probed.insert(url.clone(), Instant::now());
+                    probes.push(validator_store.probe_web3_signer(url, validators));
+                }
+            }
+
+            if !probes.is_empty() {
+                debug!(log, "Probing Web3Signer instances"; "count" => probes.len());
+                join_all(probes).await;
+            }
+
+            sleep(UNHEALTHY_PROBE_INTERVAL).await;
+        }
+    };
+
+    context.executor.spawn(future, "signer_health_service");
+}
@@ -1,4 +1,8 @@
 use crate::duties_service::DutiesService;
+use crate::duty_deadlines::{DutyDeadlines, DutyKind, DutyStage, DutyTimer};
+use crate::duty_failures::{
+    record_beacon_node_failures, record_signing_failure, skip_for_circuit_breaker,
+};
 use beacon_node_fallback::{ApiTopic, BeaconNodeFallback};
 use environment::RuntimeContext;
 use eth2::types::BlockId;
@@ -49,6 +53,7 @@ pub struct Inner<T: SlotClock + 'static, E: EthSpec> {
     ///
     /// This acts as a latch that fires once upon start-up, and then never again.
     first_subscription_done: AtomicBool,
+    duty_deadlines: DutyDeadlines,
 }
 
 impl<T: SlotClock + 'static, E: EthSpec> SyncCommitteeService<T, E> {
@@ -58,6 +63,7 @@ impl<T: SlotClock + 'static, E: EthSpec> SyncCommitteeService<T, E> {
         slot_clock: T,
         beacon_nodes: Arc<BeaconNodeFallback<T, E>>,
         context: RuntimeContext<E>,
+        duty_deadlines: DutyDeadlines,
     ) -> Self {
         Self {
             inner: Arc::new(Inner {
@@ -67,6 +73,7 @@ impl<T: SlotClock + 'static, E: EthSpec> SyncCommitteeService<T, E> {
                 beacon_nodes,
                 context,
                 first_subscription_done: AtomicBool::new(false),
+                duty_deadlines,
             }),
         }
     }
@@ -173,6 +180,14 @@ impl<T: SlotClock + 'static, E: EthSpec> SyncCommitteeService<T, E> {
             return Ok(());
         }
 
+        let mut message_timer = DutyTimer::new(
+            DutyKind::SyncCommitteeMessage,
+            slot,
+            &self.duty_deadlines,
+            self.slot_clock.clone(),
+        );
+        message_timer.stage_complete(DutyStage::DutyFetch);
+
         // Fetch `block_root` with non optimistic execution for `SyncCommitteeContribution`.
         let response = self
             .beacon_nodes
@@ -193,7 +208,10 @@ impl<T: SlotClock + 'static, E: EthSpec> SyncCommitteeService<T, E> {
             .await;
 
         let block_root = match response {
-            Ok(block) => block.data.root,
+            Ok(block) => {
+                message_timer.stage_complete(DutyStage::DataFetch);
+                block.data.root
+            }
             Err(errs) => {
                 warn!(
                     log,
@@ -212,7 +230,12 @@ impl<T: SlotClock + 'static, E: EthSpec> SyncCommitteeService<T, E> {
         self.inner.context.executor.spawn(
             async move {
                 service
-                    .publish_sync_committee_signatures(slot, block_root, validator_duties)
+                    .publish_sync_committee_signatures(
+                        slot,
+                        block_root,
+                        validator_duties,
+                        message_timer,
+                    )
                     .map(|_| ())
                     .await
             },
@@ -245,9 +268,35 @@ impl<T: SlotClock + 'static, E: EthSpec> SyncCommitteeService<T, E> {
         slot: Slot,
         beacon_block_root: Hash256,
         validator_duties: Vec<SyncDuty>,
+        mut timer: DutyTimer<T>,
+    ) -> Result<(), ()> {
+        let log = self.context.log();
+
+        let result = self
+            .sign_and_publish_sync_committee_signatures(
+                slot,
+                beacon_block_root,
+                validator_duties,
+                &mut timer,
+            )
+            .await;
+        timer.log_timings(log);
+        result
+    }
+
+    async fn sign_and_publish_sync_committee_signatures(
+        &self,
+        slot: Slot,
+        beacon_block_root: Hash256,
+        validator_duties: Vec<SyncDuty>,
+        timer: &mut DutyTimer<T>,
     ) -> Result<(), ()> {
         let log = self.context.log();
 
+        if timer.should_skip(DutyStage::Sign, log) {
+            return Ok(());
+        }
+
         // Create futures to produce sync committee signatures.
         let signature_futures = validator_duties.iter().map(|duty| async move {
             match self
@@ -274,6 +323,12 @@ impl<T: SlotClock + 'static, E: EthSpec> SyncCommitteeService<T, E> {
                     None
                 }
                 Err(e) => {
+                    record_signing_failure(
+                        &self.validator_store,
+                        DutyKind::SyncCommitteeMessage,
+                        &duty.pubkey,
+                        &e,
+                    );
                     crit!(
                         log,
                         "Failed to sign sync committee signature";
@@ -292,6 +347,11 @@ impl<T: SlotClock + 'static, E: EthSpec> SyncCommitteeService<T, E> {
             .into_iter()
             .flatten()
             .collect::<Vec<_>>();
+        timer.stage_complete(DutyStage::Sign);
+
+        if timer.should_skip(DutyStage::Publish, log) {
+            return Ok(());
+        }
 
         self.beacon_nodes
             .request(ApiTopic::SyncCommittee, |beacon_node| async move {
@@ -301,6 +361,7 @@ impl<T: SlotClock + 'static, E: EthSpec> SyncCommitteeService<T, E> {
             })
             .await
             .map_err(|e| {
+                record_beacon_node_failures(DutyKind::SyncCommitteeMessage, &e);
                 error!(
                     log,
                     "Unable to publish sync committee messages";
@@ -308,6 +369,7 @@ impl<T: SlotClock + 'static, E: EthSpec> SyncCommitteeService<T, E> {
                     "error" => %e,
                 );
             })?;
+        timer.stage_complete(DutyStage::Publish);
 
         info!(
             log,
@@ -359,6 +421,47 @@ impl<T: SlotClock + 'static, E: EthSpec> SyncCommitteeService<T, E> {
 
         let log = self.context.log();
 
+        let mut timer = DutyTimer::new(
+            DutyKind::SyncCommitteeContribution,
+            slot,
+            &self.duty_deadlines,
+            self.slot_clock.clone(),
+        );
+        timer.stage_complete(DutyStage::DutyFetch);
+
+        let result = self
+            .sign_and_publish_sync_committee_aggregate_for_subnet(
+                slot,
+                beacon_block_root,
+                subnet_id,
+                subnet_aggregators,
+                &mut timer,
+            )
+            .await;
+        timer.log_timings(log);
+        result
+    }
+
+    async fn sign_and_publish_sync_committee_aggregate_for_subnet(
+        &self,
+        slot: Slot,
+        beacon_block_root: Hash256,
+        subnet_id: SyncSubnetId,
+        subnet_aggregators: Vec<(u64, PublicKeyBytes, SyncSelectionProof)>,
+        timer: &mut DutyTimer<T>,
+    ) -> Result<(), ()> {
+        let log = self.context.log();
+
+        if skip_for_circuit_breaker(&self.beacon_nodes, DutyKind::SyncCommitteeContribution) {
+            warn!(
+                log,
+                "Skipping sync contribution due to beacon node failures";
+                "slot" => slot,
+                "subnet_id" => ?subnet_id,
+            );
+            return Ok(());
+        }
+
         let contribution = &self
             .beacon_nodes
             .first_success(|beacon_node| async move {
@@ -374,6 +477,7 @@ impl<T: SlotClock + 'static, E: EthSpec> SyncCommitteeService<T, E> {
             })
             .await
             .map_err(|e| {
+                record_beacon_node_failures(DutyKind::SyncCommitteeContribution, &e);
                 crit!(
                     log,
                     "Failed to produce sync contribution";
@@ -391,6 +495,11 @@ impl<T: SlotClock + 'static, E: EthSpec> SyncCommitteeService<T, E> {
                 );
             })?
             .data;
+        timer.stage_complete(DutyStage::DataFetch);
+
+        if timer.should_skip(DutyStage::Sign, log) {
+            return Ok(());
+        }
 
         // Create futures to produce signed contributions.
         let signature_futures = subnet_aggregators.into_iter().map(
@@ -418,6 +527,12 @@ impl<T: SlotClock + 'static, E: EthSpec> SyncCommitteeService<T, E> {
                         None
                     }
                     Err(e) => {
+                        record_signing_failure(
+                            &self.validator_store,
+                            DutyKind::SyncCommitteeContribution,
+                            &aggregator_pk,
+                            &e,
+                        );
                         crit!(
                             log,
                             "Unable to sign sync committee contribution";
@@ -436,16 +551,22 @@ impl<T: SlotClock + 'static, E: EthSpec> SyncCommitteeService<T, E> {
             .into_iter()
             .flatten()
             .collect::<Vec<_>>();
+        timer.stage_complete(DutyStage::Sign);
+
+        if timer.should_skip(DutyStage::Publish, log) {
+            return Ok(());
+        }
 
         // Publish to the beacon node.
         self.beacon_nodes
-            .first_success(|beacon_node| async move {
+            .request(ApiTopic::Aggregates, |beacon_node| async move {
                 beacon_node
                     .post_validator_contribution_and_proofs(signed_contributions)
                     .await
             })
             .await
             .map_err(|e| {
+                record_beacon_node_failures(DutyKind::SyncCommitteeContribution, &e);
                 error!(
                     log,
                     "Unable to publish signed contributions and proofs";
@@ -453,6 +574,7 @@ impl<T: SlotClock + 'static, E: EthSpec> SyncCommitteeService<T, E> {
                     "error" => %e,
                 );
             })?;
+        timer.stage_complete(DutyStage::Publish);
 
         info!(
             log,
@@ -5,13 +5,16 @@ use parking_lot::{Mutex, RwLock};
 use serde::{Deserialize, Serialize};
 use signing_method::{Error as SigningError, SignableMessage, SigningContext, SigningMethod};
 use slashing_protection::{
-    interchange::Interchange, InterchangeError, NotSafe, Safe, SlashingDatabase,
+    interchange::Interchange, InMemorySlashingProtection, InterchangeError, NotSafe, Safe,
+    SlashingDatabase,
 };
 use slog::{crit, error, info, warn, Logger};
 use slot_clock::SlotClock;
+use std::collections::{HashMap, HashSet};
 use std::marker::PhantomData;
 use std::path::Path;
 use std::sync::Arc;
+use std::time::{SystemTime, UNIX_EPOCH};
 use task_executor::TaskExecutor;
 use types::{
     attestation::Error as AttestationError, graffiti::GraffitiString, AbstractExecPayload, Address,
@@ -42,6 +45,22 @@ impl From<SigningError> for Error {
     }
 }
 
+impl Error {
+    /// Returns the `reason` label of the `vc_duty_failures_total` metric for this error.
+    pub fn failure_reason(&self) -> &'static str {
+        match self {
+            Error::Slashable(_) | Error::SameData => validator_metrics::FAILURE_SLASHING_PROTECTION,
+            Error::DoppelgangerProtected(_) => validator_metrics::FAILURE_DOPPELGANGER,
+            Error::UnableToSign(e) => e.failure_reason(),
+            Error::UnknownToDoppelgangerService(_)
+            | Error::UnknownPubkey(_)
+            | Error::GreaterThanCurrentSlot { .. }
+            | Error::GreaterThanCurrentEpoch { .. }
+            | Error::UnableToSignAttestation(_) => validator_metrics::FAILURE_OTHER,
+        }
+    }
+}
+
 #[derive(Debug, Default, Clone, Serialize, Deserialize)]
 pub struct Config {
     /// Fallback fee recipient address.
@@ -66,6 +85,37 @@ pub struct ProposalData {
     pub builder_proposals: bool,
 }
 
+/// The health of a Web3Signer instance, as observed by the most recent probe.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Web3SignerHealth {
+    /// Whether the signer passed the `upcheck` of the most recent probe. All validators of an
+    /// unhealthy signer are excluded from duties until the signer recovers.
+    pub healthy: bool,
+    /// The time of the most recent probe, in seconds since the UNIX epoch.
+    pub last_checked: u64,
+    /// The first error returned by the most recent probe, if any part of it failed.
+    pub error: Option<String>,
+    /// The enabled validators of the signer.
+    pub validators: Vec<PublicKeyBytes>,
+    /// The validators which the signer failed to sign for during the most recent probe, even
+    /// though it passed the `upcheck`. Only these validators are excluded from duties.
+    pub unavailable_validators: Vec<PublicKeyBytes>,
+    /// The validator which was probed for new failures in the most recent probe. The next probe
+    /// uses the following validator, so that every validator is probed in turn.
+    pub last_probed_validator: Option<PublicKeyBytes>,
+}
+
+impl Web3SignerHealth {
+    /// Returns the validators which should be excluded from duties.
+    fn excluded_validators(&self) -> &[PublicKeyBytes] {
+        if self.healthy {
+            &self.unavailable_validators
+        } else {
+            &self.validators
+        }
+    }
+}
+
 /// Number of epochs of slashing protection history to keep.
 ///
 /// This acts as a maximum safe-guard against clock drift.
@@ -79,6 +129,8 @@ pub const DEFAULT_GAS_LIMIT: u64 = 30_000_000;
 pub struct ValidatorStore<T, E: EthSpec> {
     validators: Arc<RwLock<InitializedValidators>>,
     slashing_protection: SlashingDatabase,
+    /// An in-memory history which every slashing protection decision is compared against.
+    slashing_protection_comparison: Option<InMemorySlashingProtection>,
     slashing_protection_last_prune: Arc<Mutex<Epoch>>,
     genesis_validators_root: Hash256,
     spec: Arc<ChainSpec>,
@@ -92,6 +144,8 @@ pub struct ValidatorStore<T, E: EthSpec> {
     prefer_builder_proposals: bool,
     builder_boost_factor: Option<u64>,
     task_executor: TaskExecutor,
+    /// The health of each Web3Signer instance, keyed by URL.
+    web3_signer_health: RwLock<HashMap<String, Web3SignerHealth>>,
     _phantom: PhantomData<E>,
 }
 
@@ -108,6 +162,7 @@ impl<T: SlotClock + 'static, E: EthSpec> ValidatorStore<T, E> {
     pub fn new(
         validators: InitializedValidators,
         slashing_protection: SlashingDatabase,
+        slashing_protection_comparison: Option<InMemorySlashingProtection>,
         genesis_validators_root: Hash256,
         spec: Arc<ChainSpec>,
         doppelganger_service: Option<Arc<DoppelgangerService>>,
@@ -119,6 +174,7 @@ impl<T: SlotClock + 'static, E: EthSpec> ValidatorStore<T, E> {
         Self {
             validators: Arc::new(RwLock::new(validators)),
             slashing_protection,
+            slashing_protection_comparison,
             slashing_protection_last_prune: Arc::new(Mutex::new(Epoch::new(0))),
             genesis_validators_root,
             spec,
@@ -132,6 +188,7 @@ impl<T: SlotClock + 'static, E: EthSpec> ValidatorStore<T, E> {
             prefer_builder_proposals: config.prefer_builder_proposals,
             builder_boost_factor: config.builder_boost_factor,
             task_executor,
+            web3_signer_health: <_>::default(),
             _phantom: PhantomData,
         }
     }
@@ -282,9 +339,11 @@ impl<T: SlotClock + 'static, E: EthSpec> ValidatorStore<T, E> {
             .iter_voting_pubkeys()
             .cloned()
             .collect::<Vec<_>>();
+        let unavailable = self.web3_signer_unavailable_pubkeys();
 
         pubkeys
             .into_iter()
+            .filter(|pubkey| !unavailable.contains(pubkey))
             .map(|pubkey| {
                 self.doppelganger_service
                     .as_ref()
@@ -296,6 +355,192 @@ impl<T: SlotClock + 'static, E: EthSpec> ValidatorStore<T, E> {
             .collect()
     }
 
+    /// Returns the validators which failed the most recent probe of their Web3Signer instance,
+    /// either because the instance is down or because it failed to sign for them.
+    fn web3_signer_unavailable_pubkeys(&self) -> HashSet<PublicKeyBytes> {
+        self.web3_signer_health
+            .read()
+            .values()
+            .flat_map(|health| health.excluded_validators().iter().copied())
+            .collect()
+    }
+
+    /// Returns the enabled validators of each Web3Signer instance, keyed by the instance's URL.
+    ///
+    /// Health records of signers which no longer have any enabled validators are removed.
+    pub fn web3_signer_validators(&self) -> HashMap<String, Vec<PublicKeyBytes>> {
+        let signers = self.validators.read().web3_signer_validators();
+        self.web3_signer_health
+            .write()
+            .retain(|url, _| signers.contains_key(url));
+        signers
+    }
+
+    /// Returns the health of each Web3Signer instance, keyed by URL.
+    pub fn web3_signer_health(&self) -> HashMap<String, Web3SignerHealth> {
+        self.web3_signer_health.read().clone()
+    }
+
+    /// Returns `false` if the most recent probe of the Web3Signer at `url` found it down, or
+    /// unable to sign for any of its validators.
+    pub fn is_web3_signer_healthy(&self, url: &str) -> bool {
+        self.web3_signer_health
+            .read()
+            .get(url)
+            .map_or(true, |health| {
+                health.healthy && health.unavailable_validators.is_empty()
+            })
+    }
+
+    /// Probe the Web3Signer at `url` by checking its `upcheck` endpoint and then signing a RANDAO
+    /// reveal (which is not slashable) for some of its `validators`.
+    ///
+    /// A signature is requested for each validator which failed the previous probe, so that it is
+    /// re-enabled promptly, and for one other validator in turn, so that new failures are
+    /// detected. If the
+    /// `upcheck` fails then all of the signer's validators are excluded from duties, otherwise only
+    /// the validators which it failed to sign for are excluded.
+    pub async fn probe_web3_signer(&self, url: String, validators: Vec<PublicKeyBytes>) {
+        let Some(upcheck_pubkey) = validators.first().copied() else {
+            return;
+        };
+
+        let (previously_unavailable, last_probed_validator) = self
+            .web3_signer_health
+            .read()
+            .get(&url)
+            .map(|health| {
+                (
+                    health.unavailable_validators.clone(),
+                    health.last_probed_validator,
+                )
+            })
+            .unwrap_or_default();
+
+        let upcheck = async {
+            self.doppelganger_bypassed_signing_method(upcheck_pubkey)?
+                .upcheck()
+                .await?;
+            Ok::<_, Error>(())
+        }
+        .await;
+
+        let mut error = upcheck.as_ref().err().map(|e| format!("{:?}", e));
+        let mut unavailable_validators = vec![];
+        let mut probed_validator = last_probed_validator;
+        if upcheck.is_ok() {
+            let (mut probe_pubkeys, available): (Vec<_>, Vec<_>) = validators
+                .iter()
+                .copied()
+                .partition(|pubkey| previously_unavailable.contains(pubkey));
+            if !available.is_empty() {
+                let next = last_probed_validator
+                    .and_then(|last| available.iter().position(|pubkey| *pubkey == last))
+                    .map_or(0, |index| (index + 1) % available.len());
+                probed_validator = Some(available[next]);
+                probe_pubkeys.push(available[next]);
+            }
+
+            for pubkey in probe_pubkeys {
+                if let Err(e) = self.probe_web3_signer_signature(pubkey).await {
+                    error.get_or_insert_with(|| format!("{:?}", e));
+                    unavailable_validators.push(pubkey);
+                }
+            }
+        }
+
+        let health = Web3SignerHealth {
+            healthy: upcheck.is_ok(),
+            last_checked: SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .map_or(0, |duration| duration.as_secs()),
+            error,
+            validators,
+            unavailable_validators,
+            last_probed_validator: probed_validator,
+        };
+
+        let previous = self
+            .web3_signer_health
+            .write()
+            .insert(url.clone(), health.clone());
+        let was_healthy = previous.as_ref().map_or(true, |previous| previous.healthy);
+
+        match (was_healthy, health.healthy) {
+            (true, false) => warn!(
+                self.log,
+                "Web3Signer unavailable, disabling its validators";
+                "url" => &url,
+                "validators" => health.validators.len(),
+                "error" => health.error.as_deref(),
+            ),
+            (false, true) => info!(
+                self.log,
+                "Web3Signer recovered, re-enabling its validators";
+                "url" => &url,
+                "validators" => health.validators.len(),
+            ),
+            _ => (),
+        }
+        if health.healthy {
+            for pubkey in &health.unavailable_validators {
+                if !previously_unavailable.contains(pubkey) {
+                    warn!(
+                        self.log,
+                        "Web3Signer failed to sign, disabling validator";
+                        "url" => &url,
+                        "validator" => ?pubkey,
+                        "error" => health.error.as_deref(),
+                    );
+                }
+            }
+            for pubkey in &previously_unavailable {
+                if !health.unavailable_validators.contains(pubkey) {
+                    info!(
+                        self.log,
+                        "Web3Signer signed successfully, re-enabling validator";
+                        "url" => &url,
+                        "validator" => ?pubkey,
+                    );
+                }
+            }
+        }
+
+        let unhealthy_signers = self
+            .web3_signer_health
+            .read()
+            .values()
+            .filter(|health| !health.healthy)
+            .count();
+        validator_metrics::set_gauge(
+            &validator_metrics::WEB3SIGNER_UNHEALTHY_SIGNERS,
+            unhealthy_signers as i64,
+        );
+    }
+
+    /// Request a RANDAO reveal signature for `validator_pubkey`, for use as a health probe.
+    async fn probe_web3_signer_signature(
+        &self,
+        validator_pubkey: PublicKeyBytes,
+    ) -> Result<(), Error> {
+        let signing_method = self.doppelganger_bypassed_signing_method(validator_pubkey)?;
+        let epoch = self
+            .slot_clock
+            .now()
+            .unwrap_or(self.spec.genesis_slot)
+            .epoch(E::slots_per_epoch());
+        let signing_context = self.signing_context(Domain::Randao, epoch);
+        signing_method
+            .get_signature::<E, BlindedPayload<E>>(
+                SignableMessage::RandaoReveal(epoch),
+                signing_context,
+                &self.spec,
+                &self.task_executor,
+            )
+            .await?;
+        Ok(())
+    }
+
     /// Returns doppelganger statuses for all enabled validators.
     #[allow(clippy::needless_collect)] // Collect is required to avoid holding a lock.
     pub fn doppelganger_statuses(&self) -> Vec<DoppelgangerStatus> {
@@ -339,6 +584,17 @@ impl<T: SlotClock + 'static, E: EthSpec> ValidatorStore<T, E> {
         self.validators.read().num_enabled()
     }
 
+    /// Returns the `signer` label of metrics concerning the signing method of `validator_pubkey`.
+    pub fn signer_label(&self, validator_pubkey: &PublicKeyBytes) -> String {
+        self.validators
+            .read()
+            .signing_method(validator_pubkey)
+            .map_or_else(
+                || validator_metrics::NO_SIGNER.to_string(),
+                |signing_method| signing_method.signer_label(),
+            )
+    }
+
     fn fork(&self, epoch: Epoch) -> Fork {
         self.spec.fork_at_epoch(epoch)
     }
@@ -602,11 +858,27 @@ impl<T: SlotClock + 'static, E: EthSpec> ValidatorStore<T, E> {
         let slashing_status = if signing_method
             .requires_local_slashing_protection(self.enable_web3signer_slashing_protection)
         {
-            self.slashing_protection.check_and_insert_block_proposal(
+            let block_header = block.block_header();
+            let slashing_status = self.slashing_protection.check_and_insert_block_proposal(
                 &validator_pubkey,
-                &block.block_header(),
+                &block_header,
                 domain_hash,
-            )
+            );
+            if let Some(comparison) = &self.slashing_protection_comparison {
+                let comparison_status = comparison.check_block_proposal(
+                    &validator_pubkey,
+                    &block_header,
+                    domain_hash,
+                    slashing_status == Ok(Safe::Valid),
+                );
+                self.compare_slashing_protection(
+                    validator_pubkey,
+                    validator_metrics::BEACON_BLOCK,
+                    &slashing_status,
+                    &comparison_status,
+                );
+            }
+            slashing_status
         } else {
             Ok(Safe::Valid)
         };
@@ -693,11 +965,26 @@ impl<T: SlotClock + 'static, E: EthSpec> ValidatorStore<T, E> {
         let slashing_status = if signing_method
             .requires_local_slashing_protection(self.enable_web3signer_slashing_protection)
         {
-            self.slashing_protection.check_and_insert_attestation(
+            let slashing_status = self.slashing_protection.check_and_insert_attestation(
                 &validator_pubkey,
                 attestation.data(),
                 domain_hash,
-            )
+            );
+            if let Some(comparison) = &self.slashing_protection_comparison {
+                let comparison_status = comparison.check_attestation(
+                    &validator_pubkey,
+                    attestation.data(),
+                    domain_hash,
+                    slashing_status == Ok(Safe::Valid),
+                );
+                self.compare_slashing_protection(
+                    validator_pubkey,
+                    validator_metrics::ATTESTATIONS,
+                    &slashing_status,
+                    &comparison_status,
+                );
+            }
+            slashing_status
         } else {
             Ok(Safe::Valid)
         };
@@ -764,6 +1051,48 @@ impl<T: SlotClock + 'static, E: EthSpec> ValidatorStore<T, E> {
         }
     }
 
+    /// Log any difference between the decision of the slashing protection database and the
+    /// decision of `self.slashing_protection_comparison`.
+    ///
+    /// The database decision is always the one acted upon. Decisions are considered to match if
+    /// both refuse to sign, even if they give different reasons.
+    fn compare_slashing_protection(
+        &self,
+        validator_pubkey: PublicKeyBytes,
+        message_type: &'static str,
+        database_status: &Result<Safe, NotSafe>,
+        comparison_status: &Result<Safe, NotSafe>,
+    ) {
+        if database_status.as_ref().ok() == comparison_status.as_ref().ok() {
+            return;
+        }
+
+        validator_metrics::inc_counter_vec(
+            &validator_metrics::SLASHING_PROTECTION_COMPARISON_DIVERGENCES_TOTAL,
+            &[message_type],
+        );
+        if database_status.is_ok() && comparison_status.is_err() {
+            crit!(
+                self.log,
+                "Slashing protection database permitted a message refused by the comparison";
+                "msg" => "the slashing protection database may be missing history",
+                "type" => message_type,
+                "validator" => ?validator_pubkey,
+                "database" => ?database_status,
+                "comparison" => ?comparison_status,
+            );
+        } else {
+            warn!(
+                self.log,
+                "Slashing protection decisions diverged";
+                "type" => message_type,
+                "validator" => ?validator_pubkey,
+                "database" => ?database_status,
+                "comparison" => ?comparison_status,
+            );
+        }
+    }
+
     pub async fn sign_voluntary_exit(
         &self,
         validator_pubkey: PublicKeyBytes,
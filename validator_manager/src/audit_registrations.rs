@@ -0,0 +1,275 @@
+use builder_client::BuilderHttpClient;
+use clap::{Arg, ArgAction, ArgMatches, Command};
+use eth2::SensitiveUrl;
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+use types::{Address, PublicKeyBytes, ValidatorRegistrationData};
+
+use crate::{common::vc_http_client, DumpConfig};
+
+pub const CMD: &str = "audit-registrations";
+pub const VC_URL_FLAG: &str = "vc-url";
+pub const VC_TOKEN_FLAG: &str = "vc-token";
+pub const RELAY_URL_FLAG: &str = "relay-url";
+
+pub fn cli_app() -> Command {
+    Command::new(CMD)
+        .about(
+            "Compares the registrations that relays hold for the validators in a validator \
+                client against the fee recipient and gas limit configured in the validator \
+                client. Exits with an error if any registration is missing or differs from \
+                the validator client.",
+        )
+        .arg(
+            Arg::new(VC_URL_FLAG)
+                .long(VC_URL_FLAG)
+                .value_name("HTTP_ADDRESS")
+                .help("A HTTP(S) address of a validator client using the keymanager-API.")
+                .default_value("http://localhost:5062")
+                .requires(VC_TOKEN_FLAG)
+                .action(ArgAction::Set)
+                .display_order(0),
+        )
+        .arg(
+            Arg::new(VC_TOKEN_FLAG)
+                .long(VC_TOKEN_FLAG)
+                .value_name("PATH")
+                .help("The file containing a token required by the validator client.")
+                .action(ArgAction::Set)
+                .display_order(0),
+        )
+        .arg(
+            Arg::new(RELAY_URL_FLAG)
+                .long(RELAY_URL_FLAG)
+                .value_name("HTTP_ADDRESS")
+                .help(
+                    "Comma-separated list of HTTP(S) addresses of relays implementing the \
+                    relay data API, which will be queried for the registrations of each \
+                    validator.",
+                )
+                .action(ArgAction::Set)
+                .required(true)
+                .display_order(0),
+        )
+}
+
+#[derive(Clone, PartialEq, Debug, Serialize, Deserialize)]
+pub struct AuditRegistrationsConfig {
+    pub vc_url: SensitiveUrl,
+    pub vc_token_path: PathBuf,
+    pub relay_urls: Vec<SensitiveUrl>,
+}
+
+impl AuditRegistrationsConfig {
+    fn from_cli(matches: &ArgMatches) -> Result<Self, String> {
+        let relay_urls = clap_utils::parse_required::<String>(matches, RELAY_URL_FLAG)?
+            .split(',')
+            .map(|url| {
+                SensitiveUrl::parse(url.trim())
+                    .map_err(|e| format!("Invalid relay URL {:?}: {:?}", url, e))
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(Self {
+            vc_url: clap_utils::parse_required(matches, VC_URL_FLAG)?,
+            vc_token_path: clap_utils::parse_required(matches, VC_TOKEN_FLAG)?,
+            relay_urls,
+        })
+    }
+}
+
+/// The registration held by a relay for a single validator, alongside the values configured in
+/// the validator client.
+///
+/// `registration` is an `Err` if the relay could not be queried for this validator, so that one
+/// unreachable relay does not prevent the rest of the validators from being audited.
+#[derive(Clone, PartialEq, Debug)]
+pub struct RegistrationAudit {
+    pub pubkey: PublicKeyBytes,
+    pub relay: String,
+    pub local_fee_recipient: Address,
+    pub local_gas_limit: u64,
+    pub registration: Result<Option<ValidatorRegistrationData>, String>,
+}
+
+impl RegistrationAudit {
+    /// Returns a description of each way in which the registration differs from the validator
+    /// client, which is empty if the registration is up to date.
+    pub fn mismatches(&self) -> Vec<String> {
+        let registration = match &self.registration {
+            Ok(Some(registration)) => registration,
+            Ok(None) => return vec!["not registered".to_string()],
+            Err(e) => return vec![format!("failed to query relay: {}", e)],
+        };
+
+        let mut mismatches = vec![];
+        if registration.fee_recipient != self.local_fee_recipient {
+            mismatches.push(format!(
+                "fee recipient is {:?}, expected {:?}",
+                registration.fee_recipient, self.local_fee_recipient
+            ));
+        }
+        if registration.gas_limit != self.local_gas_limit {
+            mismatches.push(format!(
+                "gas limit is {}, expected {}",
+                registration.gas_limit, self.local_gas_limit
+            ));
+        }
+        mismatches
+    }
+}
+
+pub async fn cli_run(matches: &ArgMatches, dump_config: DumpConfig) -> Result<(), String> {
+    let config = AuditRegistrationsConfig::from_cli(matches)?;
+    if dump_config.should_exit_early(&config)? {
+        Ok(())
+    } else {
+        let audits = run(config).await?;
+        let num_mismatched = audits
+            .iter()
+            .filter(|audit| !audit.mismatches().is_empty())
+            .count();
+        if num_mismatched == 0 {
+            Ok(())
+        } else {
+            Err(format!(
+                "{} of {} registrations are missing, out of date or could not be fetched",
+                num_mismatched,
+                audits.len()
+            ))
+        }
+    }
+}
+
+async fn run(config: AuditRegistrationsConfig) -> Result<Vec<RegistrationAudit>, String> {
+    let AuditRegistrationsConfig {
+        vc_url,
+        vc_token_path,
+        relay_urls,
+    } = config;
+
+    let (http_client, _) = vc_http_client(vc_url.clone(), &vc_token_path).await?;
+
+    // Only enabled validators are registered with relays by the validator client.
+    let pubkeys = http_client
+        .get_lighthouse_validators()
+        .await
+        .map_err(|e| format!("Failed to list validators on VC: {:?}", e))?
+        .data
+        .into_iter()
+        .filter(|validator| validator.enabled)
+        .map(|validator| validator.voting_pubkey)
+        .collect::<Vec<_>>();
+
+    let relays = relay_urls
+        .into_iter()
+        .map(|url| {
+            let relay = url.to_string();
+            BuilderHttpClient::new(url, None, None)
+                .map(|client| (relay, client))
+                .map_err(|e| format!("Failed to create relay client: {:?}", e))
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let mut audits = vec![];
+    for pubkey in &pubkeys {
+        let local_fee_recipient = http_client
+            .get_fee_recipient(pubkey)
+            .await
+            .map_err(|e| format!("Failed to get fee recipient for {:?}: {:?}", pubkey, e))?
+            .ethaddress;
+        let local_gas_limit = http_client
+            .get_gas_limit(pubkey)
+            .await
+            .map_err(|e| format!("Failed to get gas limit for {:?}: {:?}", pubkey, e))?
+            .gas_limit;
+
+        for (relay, relay_client) in &relays {
+            let registration = relay_client
+                .get_relay_validator_registration(pubkey)
+                .await
+                .map(|registration| {
+                    registration.map(|signed_registration| signed_registration.message)
+                })
+                .map_err(|e| format!("{:?}", e));
+
+            let audit = RegistrationAudit {
+                pubkey: *pubkey,
+                relay: relay.clone(),
+                local_fee_recipient,
+                local_gas_limit,
+                registration,
+            };
+
+            let mismatches = audit.mismatches();
+            if mismatches.is_empty() {
+                println!("{:?} {}: ok", pubkey, relay);
+            } else {
+                println!("{:?} {}: {}", pubkey, relay, mismatches.join(", "));
+            }
+            audits.push(audit);
+        }
+    }
+
+    eprintln!(
+        "Audited {} registrations of {} validators with {} relays",
+        audits.len(),
+        pubkeys.len(),
+        relays.len()
+    );
+
+    Ok(audits)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn audit(registration: Result<Option<(Address, u64)>, String>) -> RegistrationAudit {
+        RegistrationAudit {
+            pubkey: PublicKeyBytes::empty(),
+            relay: "http://relay.example".to_string(),
+            local_fee_recipient: Address::repeat_byte(1),
+            local_gas_limit: 36_000_000,
+            registration: registration.map(|registration| {
+                registration.map(|(fee_recipient, gas_limit)| ValidatorRegistrationData {
+                    fee_recipient,
+                    gas_limit,
+                    timestamp: 0,
+                    pubkey: PublicKeyBytes::empty(),
+                })
+            }),
+        }
+    }
+
+    #[test]
+    fn matching_registration() {
+        assert!(audit(Ok(Some((Address::repeat_byte(1), 36_000_000))))
+            .mismatches()
+            .is_empty());
+    }
+
+    #[test]
+    fn missing_registration() {
+        assert_eq!(
+            audit(Ok(None)).mismatches(),
+            vec!["not registered".to_string()]
+        );
+    }
+
+    #[test]
+    fn failed_relay_query() {
+        assert_eq!(
+            audit(Err("timeout".to_string())).mismatches(),
+            vec!["failed to query relay: timeout".to_string()]
+        );
+    }
+
+    #[test]
+    fn drifted_registration() {
+        let mismatches = audit(Ok(Some((Address::repeat_byte(2), 30_000_000)))).mismatches();
+        assert_eq!(mismatches.len(), 2);
+        assert!(mismatches[0].starts_with("fee recipient"));
+        assert!(mismatches[1].starts_with("gas limit"));
+    }
+}
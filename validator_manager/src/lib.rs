@@ -6,6 +6,7 @@ use serde::Serialize;
 use std::path::PathBuf;
 use types::EthSpec;
 
+pub mod audit_registrations;
 pub mod common;
 pub mod create_validators;
 pub mod delete_validators;
@@ -61,6 +62,7 @@ pub fn cli_app() -> Command {
         .subcommand(move_validators::cli_app())
         .subcommand(list_validators::cli_app())
         .subcommand(delete_validators::cli_app())
+        .subcommand(audit_registrations::cli_app())
 }
 
 /// Run the account manager, returning an error if the operation did not succeed.
@@ -94,6 +96,9 @@ pub fn run<E: EthSpec>(matches: &ArgMatches, env: Environment<E>) -> Result<(),
                     Some((delete_validators::CMD, matches)) => {
                         delete_validators::cli_run(matches, dump_config).await
                     }
+                    Some((audit_registrations::CMD, matches)) => {
+                        audit_registrations::cli_run(matches, dump_config).await
+                    }
                     Some(("", _)) => Err("No command supplied. See --help.".to_string()),
                     Some((unknown, _)) => Err(format!(
                         "{} is not a valid {} command. See --help.",
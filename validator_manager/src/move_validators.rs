@@ -1,12 +1,13 @@
 use super::common::*;
 use crate::DumpConfig;
-use account_utils::{read_password_from_user, ZeroizeString};
+use account_utils::{read_password_from_user, write_file_via_temporary, ZeroizeString};
 use clap::{Arg, ArgAction, ArgMatches, Command};
 use eth2::{
     lighthouse_vc::{
+        http_client::ValidatorClientHttpClient,
         std_types::{
             DeleteKeystoreStatus, DeleteKeystoresRequest, ImportKeystoreStatus, InterchangeJsonStr,
-            Status,
+            KeystoreJsonStr, SingleKeystoreResponse, Status,
         },
         types::{ExportKeystoresResponse, SingleExportKeystoresResponse},
     },
@@ -14,7 +15,8 @@ use eth2::{
 };
 use serde::{Deserialize, Serialize};
 use std::collections::{HashMap, HashSet};
-use std::path::PathBuf;
+use std::fs;
+use std::path::{Path, PathBuf};
 use std::str::FromStr;
 use std::time::Duration;
 use tokio::time::sleep;
@@ -34,6 +36,7 @@ pub const FEE_RECIPIENT_FLAG: &str = "suggested-fee-recipient";
 pub const BUILDER_PROPOSALS_FLAG: &str = "builder-proposals";
 pub const BUILDER_BOOST_FACTOR_FLAG: &str = "builder-boost-factor";
 pub const PREFER_BUILDER_PROPOSALS_FLAG: &str = "prefer-builder-proposals";
+pub const PLAN_FILE_FLAG: &str = "plan-file";
 
 const NO_VALIDATORS_MSG: &str = "No validators present on source validator client";
 
@@ -200,6 +203,90 @@ pub fn cli_app() -> Command {
                 .action(ArgAction::Set)
                 .display_order(0),
         )
+        .arg(
+            Arg::new(PLAN_FILE_FLAG)
+                .long(PLAN_FILE_FLAG)
+                .value_name("PATH")
+                .help(
+                    "A file which records the progress of the move, allowing an interrupted \
+                    move to be resumed by running this command again with the same file. If \
+                    the file already exists, the validators it lists are moved instead of those \
+                    selected by --validators or --count. Whilst a validator is between validator \
+                    clients the file contains its keystore and password, so it should be \
+                    deleted once the move has completed.",
+                )
+                .action(ArgAction::Set)
+                .display_order(0),
+        )
+}
+
+/// The progress of a single validator in a `MovePlan`.
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case", tag = "status")]
+pub enum MoveStatus {
+    /// The validator has not yet been removed from the source validator client.
+    Pending,
+    /// The validator has been removed from the source validator client, but not yet imported into
+    /// the destination validator client.
+    Exported {
+        validator: Box<ValidatorSpecification>,
+    },
+    /// The validator has been imported into the destination validator client.
+    Moved,
+    /// The validator was not moved since it is read-only on the source validator client.
+    Skipped,
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+pub struct PlannedMove {
+    pub pubkey: PublicKeyBytes,
+    pub status: MoveStatus,
+}
+
+/// A record of the validators being moved between two validator clients, which is persisted after
+/// each step so that an interrupted move can be resumed without losing any keystores.
+#[derive(Clone, Serialize, Deserialize)]
+pub struct MovePlan {
+    pub src_vc_url: SensitiveUrl,
+    pub dest_vc_url: SensitiveUrl,
+    pub validators: Vec<PlannedMove>,
+}
+
+impl MovePlan {
+    /// Read a plan from `path`, returning `None` if the file does not exist.
+    pub fn load(path: &Path) -> Result<Option<Self>, String> {
+        if !path.exists() {
+            return Ok(None);
+        }
+        let file =
+            fs::File::open(path).map_err(|e| format!("Unable to open {:?}: {:?}", path, e))?;
+        serde_json::from_reader(file)
+            .map(Some)
+            .map_err(|e| format!("Unable to parse plan file {:?}: {:?}", path, e))
+    }
+
+    /// Atomically write the plan to `path`.
+    pub fn save(&self, path: &Path) -> Result<(), String> {
+        let bytes = serde_json::to_vec(self)
+            .map_err(|e| format!("Unable to serialize move plan: {:?}", e))?;
+        write_file_via_temporary(path, &path.with_extension("tmp"), &bytes)
+            .map_err(|e| format!("Unable to write plan file {:?}: {:?}", path, e))
+    }
+
+    /// Update the status of the validator at `index`, persisting the plan to `path` if any.
+    fn set_status(
+        &mut self,
+        index: usize,
+        status: MoveStatus,
+        path: Option<&Path>,
+    ) -> Result<(), String> {
+        let planned_move = self
+            .validators
+            .get_mut(index)
+            .ok_or("Inconsistent move plan")?;
+        planned_move.status = status;
+        path.map_or(Ok(()), |path| self.save(path))
+    }
 }
 
 #[derive(Clone, PartialEq, Debug, Serialize, Deserialize)]
@@ -222,6 +309,7 @@ pub struct MoveConfig {
     pub fee_recipient: Option<Address>,
     pub gas_limit: Option<u64>,
     pub password_source: PasswordSource,
+    pub plan_file: Option<PathBuf>,
 }
 
 impl MoveConfig {
@@ -263,6 +351,7 @@ impl MoveConfig {
             password_source: PasswordSource::Interactive {
                 stdin_inputs: cfg!(windows) || matches.get_flag(STDIN_INPUTS_FLAG),
             },
+            plan_file: clap_utils::parse_optional(matches, PLAN_FILE_FLAG)?,
         })
     }
 }
@@ -289,7 +378,9 @@ async fn run<'a>(config: MoveConfig) -> Result<(), String> {
         mut password_source,
         builder_boost_factor,
         prefer_builder_proposals,
+        plan_file,
     } = config;
+    let plan_file = plan_file.as_deref();
 
     // Moving validators between the same VC is unlikely to be useful and probably indicates a user
     // error.
@@ -305,239 +396,157 @@ async fn run<'a>(config: MoveConfig) -> Result<(), String> {
     let (dest_http_client, _dest_keystores) =
         vc_http_client(dest_vc_url.clone(), &dest_vc_token_path).await?;
 
-    if src_keystores.is_empty() {
-        return Err(NO_VALIDATORS_MSG.to_string());
-    }
-
-    let pubkeys_to_move = match validators {
-        Validators::All => src_keystores.iter().map(|v| v.validating_pubkey).collect(),
-        Validators::Count(count) => {
-            let mut viable_pubkeys: Vec<_> = src_keystores
-                .iter()
-                .filter(|v| !v.readonly.unwrap_or(true))
-                .map(|v| v.validating_pubkey)
-                .collect();
-            viable_pubkeys.sort_unstable_by_key(PublicKeyBytes::serialize);
-            viable_pubkeys
-                .get(0..count)
-                .ok_or_else(|| {
-                    format!(
-                        "Cannot move {} keystores since source validator client only has {} \
-                        keystores which are able to be moved (not read-only).",
-                        count,
-                        viable_pubkeys.len()
-                    )
-                })?
-                .to_vec()
-        }
-        Validators::Specific(request_pubkeys) => {
-            let request_pubkeys_set: HashSet<_> = request_pubkeys.iter().collect();
-            let src_pubkeys_set: HashSet<_> =
-                src_keystores.iter().map(|v| &v.validating_pubkey).collect();
-            let difference = request_pubkeys_set
-                .difference(&src_pubkeys_set)
-                .collect::<Vec<_>>();
-            if !difference.is_empty() {
-                for pk in &difference {
-                    eprintln!("{:?} is not present on {:?}", pk, src_vc_url);
-                }
+    let mut plan = match plan_file.map(MovePlan::load).transpose()?.flatten() {
+        Some(plan) => {
+            if plan.src_vc_url != src_vc_url || plan.dest_vc_url != dest_vc_url {
                 return Err(format!(
-                    "{} validators not found on {:?}",
-                    difference.len(),
-                    src_vc_url
+                    "The plan file is for a move from {} to {}",
+                    plan.src_vc_url, plan.dest_vc_url
                 ));
             }
-            request_pubkeys
-        }
-    };
-
-    let src_keystores_map: HashMap<_, _> = src_keystores
-        .iter()
-        .map(|k| (k.validating_pubkey, k))
-        .collect();
-
-    let count = pubkeys_to_move.len();
-    for (i, &pubkey_to_move) in pubkeys_to_move.iter().enumerate() {
-        // Skip read-only validators rather than exiting. This makes it a bit easier to use the
-        // "all" flag.
-        if src_keystores_map
-            .get(&pubkey_to_move)
-            .ok_or("Inconsistent src keystore map")?
-            .readonly
-            .unwrap_or(true)
-        {
-            eprintln!("Skipping read-only validator {:?}", pubkey_to_move);
+            eprintln!(
+                "Resuming the move of {} validators from the plan file. The --{} and --{} flags \
+                are ignored.",
+                plan.validators.len(),
+                VALIDATORS_FLAG,
+                COUNT_FLAG
+            );
+            plan
         }
+        None => {
+            if src_keystores.is_empty() {
+                return Err(NO_VALIDATORS_MSG.to_string());
+            }
 
-        let request = DeleteKeystoresRequest {
-            pubkeys: vec![pubkey_to_move],
-        };
-        let deleted = match src_http_client.delete_lighthouse_keystores(&request).await {
-            Ok(deleted) => deleted,
-            Err(e) => {
-                match src_http_client.get_keystores().await {
-                    Ok(response) => {
-                        if response
-                            .data
-                            .iter()
-                            .any(|v| v.validating_pubkey == pubkey_to_move)
-                        {
-                            eprintln!(
-                                "There was an error removing a validator, however the validator \
-                            is still present on the source validator client. The recommended \
-                            solution is to run this command again."
-                            );
+            let pubkeys_to_move: Vec<PublicKeyBytes> = match validators {
+                Validators::All => src_keystores.iter().map(|v| v.validating_pubkey).collect(),
+                Validators::Count(count) => {
+                    let mut viable_pubkeys: Vec<_> = src_keystores
+                        .iter()
+                        .filter(|v| !v.readonly.unwrap_or(true))
+                        .map(|v| v.validating_pubkey)
+                        .collect();
+                    viable_pubkeys.sort_unstable_by_key(PublicKeyBytes::serialize);
+                    viable_pubkeys
+                        .get(0..count)
+                        .ok_or_else(|| {
+                            format!(
+                            "Cannot move {} keystores since source validator client only has {} \
+                            keystores which are able to be moved (not read-only).",
+                            count,
+                            viable_pubkeys.len()
+                        )
+                        })?
+                        .to_vec()
+                }
+                Validators::Specific(request_pubkeys) => {
+                    let request_pubkeys_set: HashSet<_> = request_pubkeys.iter().collect();
+                    let src_pubkeys_set: HashSet<_> =
+                        src_keystores.iter().map(|v| &v.validating_pubkey).collect();
+                    let difference = request_pubkeys_set
+                        .difference(&src_pubkeys_set)
+                        .collect::<Vec<_>>();
+                    if !difference.is_empty() {
+                        for pk in &difference {
+                            eprintln!("{:?} is not present on {:?}", pk, src_vc_url);
                         }
+                        return Err(format!(
+                            "{} validators not found on {:?}",
+                            difference.len(),
+                            src_vc_url
+                        ));
                     }
-                    Err(_) => {
-                        eprintln!(
-                            "There was an error removing a validator and it's unclear if \
-                            the validator was removed or not. Manual user intervention is \
-                            required."
-                        );
-                    }
-                };
+                    request_pubkeys
+                }
+            };
 
-                return Err(format!("Deleting {:?} failed with {:?}", pubkey_to_move, e));
+            MovePlan {
+                src_vc_url: src_vc_url.clone(),
+                dest_vc_url: dest_vc_url.clone(),
+                validators: pubkeys_to_move
+                    .into_iter()
+                    .map(|pubkey| PlannedMove {
+                        pubkey,
+                        status: MoveStatus::Pending,
+                    })
+                    .collect(),
             }
-        };
-
-        let ExportKeystoresResponse {
-            mut data,
-            slashing_protection,
-        } = deleted;
-
-        if data.len() != 1 {
-            return Err(format!(
-                "Too many deleted validators from VC: {}",
-                data.len()
-            ));
         }
+    };
+    if let Some(path) = plan_file {
+        plan.save(path)?;
+    }
 
-        let exported_validator = data
-            .pop()
-            .ok_or("VC responded with zero deleted validators")?;
-
-        let (voting_keystore, voting_keystore_password) = match exported_validator {
-            SingleExportKeystoresResponse {
-                status:
-                    Status {
-                        status: DeleteKeystoreStatus::Deleted,
-                        message: _,
-                    },
-                validating_keystore,
-                validating_keystore_password,
-            } => match (validating_keystore, validating_keystore_password) {
-                (Some(keystore), Some(password)) => (keystore, password),
-                (Some(keystore), None) => {
-                    eprintln!(
-                        "Validator {:?} requires a password, please provide it to continue \
-                            moving validators. \
-                            The dest VC will store this password on its filesystem and the password \
-                            will not be required next time the dest VC starts. \
-                            If the provided password is incorrect the user will \
-                            be asked to provide another password. \
-                            Failing to provide the correct password now will \
-                            result in the keystore being deleted from the src VC \
-                            without being transfered to the dest VC. \
-                            It is strongly recommend to provide a password now rather than exiting.",
-                        pubkey_to_move
-                    );
+    let src_keystores_map: HashMap<_, _> = src_keystores
+        .iter()
+        .map(|k| (k.validating_pubkey, k))
+        .collect();
 
-                    // Read the password from the user, retrying if the password is incorrect.
-                    loop {
-                        match password_source.read_password(&pubkey_to_move) {
-                            Ok(password) => {
-                                if let Err(e) = keystore.decrypt_keypair(password.as_ref()) {
-                                    eprintln!("Failed to decrypt keystore: {:?}", e);
-                                } else {
-                                    break (keystore, password);
-                                }
-                            }
-                            Err(e) => {
-                                eprintln!(
-                                    "Retrying after error: {:?}. If this error persists the user will need to \
-                                        manually recover their keystore for validator {:?} from the mnemonic."
-                                    ,
-                                    e, pubkey_to_move
-                                );
-                            }
-                        }
+    let count = plan.validators.len();
+    for i in 0..count {
+        let PlannedMove {
+            pubkey: pubkey_to_move,
+            status,
+        } = plan.validators[i].clone();
 
-                        // Add a sleep here to prevent spamming the console.
-                        sleep(Duration::from_secs(1)).await;
-                    }
-                }
-                (None, password_opt) => {
-                    eprintln!(
-                        "Validator {:?} was not moved since the validator client did \
-                            not return a keystore. It is likely that the \
-                            validator has been deleted from the source validator client \
-                            without being moved to the destination validator client. \
-                            This validator will most likely need to be manually recovered \
-                            from a mnemonic or backup.",
-                        pubkey_to_move
-                    );
-                    return Err(format!(
-                        "VC returned deleted but keystore not present (password {})",
-                        password_opt.is_some()
-                    ));
-                }
-            },
-            SingleExportKeystoresResponse {
-                status: Status { status, .. },
-                ..
-            } if matches!(
-                status,
-                DeleteKeystoreStatus::NotFound | DeleteKeystoreStatus::NotActive
-            ) =>
-            {
+        let validator_specification = match status {
+            MoveStatus::Moved | MoveStatus::Skipped => continue,
+            MoveStatus::Exported { validator } => {
                 eprintln!(
-                    "Validator {:?} was not moved since it was not found or not active. This scenario \
-                    is unexpected and might indicate that another process is also performing \
-                    an export from the source validator client. Exiting now for safety. \
-                    If there is definitely no other process exporting validators then it \
-                    may be safe to run this command again.",
+                    "Resuming the import of validator {:?}, which has already been removed \
+                    from the source validator client",
                     pubkey_to_move
                 );
-                return Err(format!(
-                    "VC indicated that a previously known validator was {:?}",
-                    status,
-                ));
+                *validator
             }
-            SingleExportKeystoresResponse {
-                status: Status { status, message },
-                ..
-            } => {
-                eprintln!(
-                    "Validator {:?} was not moved because the source validator client \
-                    indicated there was an error disabling it. Manual intervention is \
-                    required to recover from this scenario.",
-                    pubkey_to_move
-                );
-                return Err(format!(
-                    "VC returned status {:?} with message {:?}",
-                    status, message
-                ));
+            MoveStatus::Pending => {
+                let validator = export_validator(
+                    &src_http_client,
+                    &src_keystores_map,
+                    pubkey_to_move,
+                    &mut password_source,
+                )
+                .await?;
+                let Some((voting_keystore, voting_keystore_password, slashing_protection)) =
+                    validator
+                else {
+                    plan.set_status(i, MoveStatus::Skipped, plan_file)?;
+                    continue;
+                };
+
+                let validator_specification = ValidatorSpecification {
+                    voting_keystore,
+                    voting_keystore_password,
+                    slashing_protection: Some(InterchangeJsonStr(slashing_protection)),
+                    fee_recipient,
+                    gas_limit,
+                    builder_proposals,
+                    builder_boost_factor,
+                    prefer_builder_proposals,
+                    // Allow the VC to choose a default "enabled" state. Since "enabled" is not
+                    // part of the standard API, leaving this as `None` means we are not forced
+                    // to use the non-standard API.
+                    enabled: None,
+                };
+
+                // Record the exported keystore before importing it, so that it is not lost if
+                // the import is interrupted.
+                plan.set_status(
+                    i,
+                    MoveStatus::Exported {
+                        validator: Box::new(validator_specification.clone()),
+                    },
+                    plan_file,
+                )?;
+                validator_specification
             }
         };
 
-        let keystore_derivation_path = voting_keystore.0.path();
-
-        let validator_specification: ValidatorSpecification = ValidatorSpecification {
-            voting_keystore,
-            voting_keystore_password,
-            slashing_protection: Some(InterchangeJsonStr(slashing_protection)),
-            fee_recipient,
-            gas_limit,
-            builder_proposals,
-            builder_boost_factor,
-            prefer_builder_proposals,
-            // Allow the VC to choose a default "enabled" state. Since "enabled" is not part of
-            // the standard API, leaving this as `None` means we are not forced to use the
-            // non-standard API.
-            enabled: None,
-        };
+        // Never enable a validator on the destination VC whilst it may still be active on the
+        // source VC.
+        ensure_removed_from_source(&src_http_client, &pubkey_to_move).await?;
+
+        let keystore_derivation_path = validator_specification.voting_keystore.0.path();
 
         // We might as well just ignore validators that already exist on the destination machine,
         // there doesn't appear to be much harm just adding them again and removing them from the
@@ -640,6 +649,8 @@ async fn run<'a>(config: MoveConfig) -> Result<(), String> {
                 count
             );
         }
+
+        plan.set_status(i, MoveStatus::Moved, plan_file)?;
     }
 
     eprintln!("Done.");
@@ -647,6 +658,218 @@ async fn run<'a>(config: MoveConfig) -> Result<(), String> {
     Ok(())
 }
 
+/// Remove `pubkey_to_move` from the source VC, returning its keystore, password and slashing
+/// protection data.
+///
+/// Returns `None` if the validator is read-only and cannot be moved.
+async fn export_validator(
+    src_http_client: &ValidatorClientHttpClient,
+    src_keystores_map: &HashMap<PublicKeyBytes, &SingleKeystoreResponse>,
+    pubkey_to_move: PublicKeyBytes,
+    password_source: &mut PasswordSource,
+) -> Result<Option<(KeystoreJsonStr, ZeroizeString, String)>, String> {
+    // Skip read-only validators rather than exiting. This makes it a bit easier to use the
+    // "all" flag.
+    if src_keystores_map
+        .get(&pubkey_to_move)
+        .ok_or("Inconsistent src keystore map")?
+        .readonly
+        .unwrap_or(true)
+    {
+        eprintln!("Skipping read-only validator {:?}", pubkey_to_move);
+        return Ok(None);
+    }
+
+    let request = DeleteKeystoresRequest {
+        pubkeys: vec![pubkey_to_move],
+    };
+    let deleted = match src_http_client.delete_lighthouse_keystores(&request).await {
+        Ok(deleted) => deleted,
+        Err(e) => {
+            match src_http_client.get_keystores().await {
+                Ok(response) => {
+                    if response
+                        .data
+                        .iter()
+                        .any(|v| v.validating_pubkey == pubkey_to_move)
+                    {
+                        eprintln!(
+                            "There was an error removing a validator, however the validator \
+                            is still present on the source validator client. The recommended \
+                            solution is to run this command again."
+                        );
+                    }
+                }
+                Err(_) => {
+                    eprintln!(
+                        "There was an error removing a validator and it's unclear if \
+                        the validator was removed or not. Manual user intervention is \
+                        required."
+                    );
+                }
+            };
+
+            return Err(format!("Deleting {:?} failed with {:?}", pubkey_to_move, e));
+        }
+    };
+
+    let ExportKeystoresResponse {
+        mut data,
+        slashing_protection,
+    } = deleted;
+
+    if data.len() != 1 {
+        return Err(format!(
+            "Too many deleted validators from VC: {}",
+            data.len()
+        ));
+    }
+
+    let exported_validator = data
+        .pop()
+        .ok_or("VC responded with zero deleted validators")?;
+
+    let (voting_keystore, voting_keystore_password) = match exported_validator {
+        SingleExportKeystoresResponse {
+            status:
+                Status {
+                    status: DeleteKeystoreStatus::Deleted,
+                    message: _,
+                },
+            validating_keystore,
+            validating_keystore_password,
+        } => match (validating_keystore, validating_keystore_password) {
+            (Some(keystore), Some(password)) => (keystore, password),
+            (Some(keystore), None) => {
+                eprintln!(
+                    "Validator {:?} requires a password, please provide it to continue \
+                        moving validators. \
+                        The dest VC will store this password on its filesystem and the password \
+                        will not be required next time the dest VC starts. \
+                        If the provided password is incorrect the user will \
+                        be asked to provide another password. \
+                        Failing to provide the correct password now will \
+                        result in the keystore being deleted from the src VC \
+                        without being transfered to the dest VC. \
+                        It is strongly recommend to provide a password now rather than exiting.",
+                    pubkey_to_move
+                );
+
+                // Read the password from the user, retrying if the password is incorrect.
+                loop {
+                    match password_source.read_password(&pubkey_to_move) {
+                        Ok(password) => {
+                            if let Err(e) = keystore.decrypt_keypair(password.as_ref()) {
+                                eprintln!("Failed to decrypt keystore: {:?}", e);
+                            } else {
+                                break (keystore, password);
+                            }
+                        }
+                        Err(e) => {
+                            eprintln!(
+                                "Retrying after error: {:?}. If this error persists the user will need to \
+                                    manually recover their keystore for validator {:?} from the mnemonic."
+                                ,
+                                e, pubkey_to_move
+                            );
+                        }
+                    }
+
+                    // Add a sleep here to prevent spamming the console.
+                    sleep(Duration::from_secs(1)).await;
+                }
+            }
+            (None, password_opt) => {
+                eprintln!(
+                    "Validator {:?} was not moved since the validator client did \
+                        not return a keystore. It is likely that the \
+                        validator has been deleted from the source validator client \
+                        without being moved to the destination validator client. \
+                        This validator will most likely need to be manually recovered \
+                        from a mnemonic or backup.",
+                    pubkey_to_move
+                );
+                return Err(format!(
+                    "VC returned deleted but keystore not present (password {})",
+                    password_opt.is_some()
+                ));
+            }
+        },
+        SingleExportKeystoresResponse {
+            status: Status { status, .. },
+            ..
+        } if matches!(
+            status,
+            DeleteKeystoreStatus::NotFound | DeleteKeystoreStatus::NotActive
+        ) =>
+        {
+            eprintln!(
+                "Validator {:?} was not moved since it was not found or not active. This scenario \
+                    is unexpected and might indicate that another process is also performing \
+                    an export from the source validator client. Exiting now for safety. \
+                    If there is definitely no other process exporting validators then it \
+                    may be safe to run this command again.",
+                pubkey_to_move
+            );
+            return Err(format!(
+                "VC indicated that a previously known validator was {:?}",
+                status,
+            ));
+        }
+        SingleExportKeystoresResponse {
+            status: Status { status, message },
+            ..
+        } => {
+            eprintln!(
+                "Validator {:?} was not moved because the source validator client \
+                    indicated there was an error disabling it. Manual intervention is \
+                    required to recover from this scenario.",
+                pubkey_to_move
+            );
+            return Err(format!(
+                "VC returned status {:?} with message {:?}",
+                status, message
+            ));
+        }
+    };
+
+    Ok(Some((
+        voting_keystore,
+        voting_keystore_password,
+        slashing_protection,
+    )))
+}
+
+/// Returns an error if `pubkey` is still present on the source VC.
+///
+/// This prevents a validator from being enabled on the destination VC whilst it may still be
+/// signing on the source VC.
+async fn ensure_removed_from_source(
+    src_http_client: &ValidatorClientHttpClient,
+    pubkey: &PublicKeyBytes,
+) -> Result<(), String> {
+    let src_keystores = src_http_client
+        .get_keystores()
+        .await
+        .map_err(|e| {
+            format!(
+                "Unable to confirm that {:?} was removed from the source VC: {:?}",
+                pubkey, e
+            )
+        })?
+        .data;
+    if src_keystores.iter().any(|k| k.validating_pubkey == *pubkey) {
+        eprintln!(
+            "Validator {:?} is still present on the source validator client, so it will not \
+            be imported into the destination validator client. Remove it from the source \
+            validator client before running this command again.",
+            pubkey
+        );
+        return Err(format!("{:?} is still present on the source VC", pubkey));
+    }
+    Ok(())
+}
+
 async fn sleep_with_retry_message(pubkey: &PublicKeyBytes, path: Option<&str>) {
     let path = path.unwrap_or("<unspecified>");
     eprintln!(
@@ -672,6 +895,7 @@ mod test {
 
     const SRC_VC_TOKEN_FILE_NAME: &str = "src_vc_token.json";
     const DEST_VC_TOKEN_FILE_NAME: &str = "dest_vc_token.json";
+    const PLAN_FILE_NAME: &str = "move_plan.json";
 
     type MutatePasswordFn = Box<dyn Fn(&mut HashMap<PublicKeyBytes, Vec<String>>)>;
 
@@ -687,6 +911,7 @@ mod test {
         passwords: HashMap<PublicKeyBytes, Vec<String>>,
         use_password_files: bool,
         reuse_password_files: Option<usize>,
+        use_plan_file: bool,
     }
 
     impl TestBuilder {
@@ -704,9 +929,15 @@ mod test {
                 passwords: <_>::default(),
                 use_password_files: false,
                 reuse_password_files: None,
+                use_plan_file: false,
             }
         }
 
+        fn use_plan_file(mut self) -> Self {
+            self.use_plan_file = true;
+            self
+        }
+
         fn move_back_again(mut self) -> Self {
             self.move_back_again = true;
             self
@@ -801,10 +1032,25 @@ mod test {
                 fee_recipient: None,
                 gas_limit: None,
                 password_source: PasswordSource::Testing(self.passwords.clone()),
+                plan_file: self
+                    .use_plan_file
+                    .then(|| self.dir.path().join(PLAN_FILE_NAME)),
             };
 
             let result = run(move_config).await;
 
+            if result.is_ok() && self.use_plan_file {
+                let plan = MovePlan::load(&self.dir.path().join(PLAN_FILE_NAME))
+                    .unwrap()
+                    .expect("plan file should exist");
+                assert!(
+                    plan.validators
+                        .iter()
+                        .all(|v| matches!(v.status, MoveStatus::Moved)),
+                    "all validators should be recorded as moved"
+                );
+            }
+
             if result.is_ok() {
                 let src_vc_final_keystores = src_vc_client.get_keystores().await.unwrap().data;
                 let dest_vc_final_keystores = dest_vc_client.get_keystores().await.unwrap().data;
@@ -1290,4 +1536,125 @@ mod test {
             .await
             .assert_ok();
     }
+
+    #[tokio::test]
+    async fn three_validators_move_all_with_plan_file() {
+        TestBuilder::new()
+            .await
+            .use_plan_file()
+            .with_src_validators(3, 0)
+            .await
+            .run_test(|_| Validators::All)
+            .await
+            .assert_ok();
+    }
+
+    /// This test simulates resuming a move which was interrupted after a validator was removed
+    /// from the source VC but before it was imported into the destination VC.
+    #[tokio::test]
+    async fn resume_move_from_plan_file() {
+        let dir = tempdir().unwrap();
+        let http_config = ApiTester::default_http_config();
+        let import_test_result = ImportTestBuilder::new_with_http_config(http_config.clone())
+            .await
+            .create_validators(2, 0)
+            .await
+            .run_test()
+            .await;
+        assert!(import_test_result.result.is_ok());
+        let src_vc = import_test_result.vc;
+        let dest_vc = ApiTester::new_with_http_config(http_config).await;
+
+        let src_vc_token_path = dir.path().join(SRC_VC_TOKEN_FILE_NAME);
+        fs::write(&src_vc_token_path, &src_vc.api_token).unwrap();
+        let dest_vc_token_path = dir.path().join(DEST_VC_TOKEN_FILE_NAME);
+        fs::write(&dest_vc_token_path, &dest_vc.api_token).unwrap();
+        let (src_vc_client, src_vc_keystores) =
+            vc_http_client(src_vc.url.clone(), &src_vc_token_path)
+                .await
+                .unwrap();
+        let (dest_vc_client, _) = vc_http_client(dest_vc.url.clone(), &dest_vc_token_path)
+            .await
+            .unwrap();
+        let pubkeys: Vec<_> = src_vc_keystores
+            .iter()
+            .map(|k| k.validating_pubkey)
+            .collect();
+
+        // Remove the first validator from the source VC, as the interrupted move would have.
+        let mut exported = src_vc_client
+            .delete_lighthouse_keystores(&DeleteKeystoresRequest {
+                pubkeys: vec![pubkeys[0]],
+            })
+            .await
+            .unwrap();
+        let exported_validator = exported.data.pop().unwrap();
+        let plan_path = dir.path().join(PLAN_FILE_NAME);
+        MovePlan {
+            src_vc_url: src_vc.url.clone(),
+            dest_vc_url: dest_vc.url.clone(),
+            validators: vec![
+                PlannedMove {
+                    pubkey: pubkeys[0],
+                    status: MoveStatus::Exported {
+                        validator: Box::new(ValidatorSpecification {
+                            voting_keystore: exported_validator.validating_keystore.unwrap(),
+                            voting_keystore_password: exported_validator
+                                .validating_keystore_password
+                                .unwrap(),
+                            slashing_protection: Some(InterchangeJsonStr(
+                                exported.slashing_protection,
+                            )),
+                            fee_recipient: None,
+                            gas_limit: None,
+                            builder_proposals: None,
+                            builder_boost_factor: None,
+                            prefer_builder_proposals: None,
+                            enabled: None,
+                        }),
+                    },
+                },
+                PlannedMove {
+                    pubkey: pubkeys[1],
+                    status: MoveStatus::Pending,
+                },
+            ],
+        }
+        .save(&plan_path)
+        .unwrap();
+
+        let move_config = MoveConfig {
+            src_vc_url: src_vc.url.clone(),
+            src_vc_token_path,
+            dest_vc_url: dest_vc.url.clone(),
+            dest_vc_token_path,
+            // Ignored since the plan file exists.
+            validators: Validators::Count(1),
+            builder_proposals: None,
+            builder_boost_factor: None,
+            prefer_builder_proposals: None,
+            fee_recipient: None,
+            gas_limit: None,
+            password_source: PasswordSource::Testing(HashMap::new()),
+            plan_file: Some(plan_path.clone()),
+        };
+        run(move_config).await.unwrap();
+
+        assert!(src_vc_client.get_keystores().await.unwrap().data.is_empty());
+        let dest_pubkeys: HashSet<_> = dest_vc_client
+            .get_keystores()
+            .await
+            .unwrap()
+            .data
+            .into_iter()
+            .map(|k| k.validating_pubkey)
+            .collect();
+        assert_eq!(dest_pubkeys, pubkeys.into_iter().collect());
+
+        let plan = MovePlan::load(&plan_path).unwrap().unwrap();
+        assert!(plan
+            .validators
+            .iter()
+            .all(|v| matches!(v.status, MoveStatus::Moved)));
+    }
 }